@@ -12,8 +12,13 @@
 //! - `POST   /admin/users`            → mint user
 //! - `GET    /admin/users/{id}`       → user + linked passkeys + sessions + upstream
 //! - `PUT    /admin/users/{id}`       → update email / display_name / verified
-//! - `DELETE /admin/users/{id}`       → cascade delete via FKs
+//! - `DELETE /admin/users/{id}`       → cascade delete via FKs (org projections dropped first)
 //! - `POST   /admin/users/{id}/password-reset` → set new password (admin override)
+//! - `DELETE /admin/users/{id}/mfa`   → clear TOTP + recovery codes (lost authenticator)
+//! - `DELETE /admin/users/{id}/lockout` → lift a failed-login lockout on the account
+//!
+//! - `GET    /admin/lockouts`                → active account + IP lockouts
+//! - `DELETE /admin/lockouts/{scope}/{key}`  → lift one (`scope` = `account` | `ip`)
 //!
 //! - `GET    /admin/sessions?limit=&offset=&user_id=`
 //! - `DELETE /admin/sessions/{id}`
@@ -26,22 +31,32 @@
 //! - `POST   /admin/zanzibar/namespaces`           → define / replace schema
 //! - `GET    /admin/zanzibar/namespaces/{name}`
 //! - `GET    /admin/zanzibar/tuples`              → list (filter via query string)
-//! - `POST   /admin/zanzibar/tuples`              → write
-//! - `DELETE /admin/zanzibar/tuples`              → delete
-//! - `POST   /admin/zanzibar/check`               → permission check
+//! - `POST   /admin/zanzibar/tuples`              → write (returns its zookie; optional `caveat`, `expires_at`)
+//! - `DELETE /admin/zanzibar/tuples`              → delete (returns its zookie)
+//! - `POST   /admin/zanzibar/check`               → permission check, optionally at a zookie / with caveat context
 //! - `POST   /admin/zanzibar/expand`              → userset tree
+//! - `GET    /admin/zanzibar/watch`               → tuple change feed (SSE or long-poll)
+//!
+//! - `GET    /admin/audit?limit=&offset=&actor=&action=&since=&until=`
+//!   → `auth.audit` rows, newest first
+//!
+//! - `GET    /admin/api-keys`         → scoped API keys (never their secrets)
+//! - `POST   /admin/api-keys`         → `{name, owner, scopes, expires_at?}`; the token is returned once
+//! - `DELETE /admin/api-keys/{id}`    → revoke
 //!
-//! - `GET    /admin/audit?limit=&offset=&actor=&action=`
-//!   → empty response today (audit table is deferred per V1 schema notes)
+//! Every route also accepts a database API key holding `auth:read`
+//! (GET) or `auth:write` — except `/admin/api-keys`, which takes only
+//! the static admin bearer so a scoped key can't mint a broader one.
 
 use axum::Router;
 use axum::extract::{FromRef, Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::{IntoResponse, Json, Response};
 use axum::routing::{delete, get, post};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::api_keys::{Permission, Resource};
 use crate::ctx::AuthCtx;
 use crate::state::AdminApiKeys;
 use crate::store::User;
@@ -67,6 +82,10 @@ where
             "/admin/users/{id}/password-reset",
             post(password_reset_handler),
         )
+        .route("/admin/users/{id}/mfa", delete(mfa_reset_handler))
+        .route("/admin/users/{id}/lockout", delete(user_unlock_handler))
+        .route("/admin/lockouts", get(list_lockouts))
+        .route("/admin/lockouts/{scope}/{key}", delete(unlock_handler))
         .route("/admin/sessions", get(list_sessions))
         .route("/admin/sessions/{id}", delete(revoke_session))
         .route(
@@ -91,20 +110,34 @@ where
         )
         .route("/admin/zanzibar/check", post(zanzibar_check_handler))
         .route("/admin/zanzibar/expand", post(zanzibar_expand_handler))
+        .route("/admin/zanzibar/watch", get(zanzibar_watch_handler))
         .route("/admin/audit", get(audit_list))
+        .route(
+            "/admin/api-keys",
+            get(list_api_keys).post(create_api_key_handler),
+        )
+        .route("/admin/api-keys/{id}", delete(revoke_api_key_handler))
 }
 
-/// Admin-bearer-only gate shared by every admin handler. Per the
-/// decoupled-modules architecture (assay-engine HTTP boundary accepts
-/// only the operator admin api-key), this is a strict bearer check
-/// with no session/JWT/zanzibar resolution at request time. Per-user
-/// authentication is the upstream consumer's responsibility.
+/// Admin gate shared by every admin handler. Per the decoupled-modules
+/// architecture (assay-engine HTTP boundary accepts only operator
+/// credentials), this is a bearer check with no session/JWT/zanzibar
+/// resolution at request time: the static admin api-key, or a database
+/// API key holding `auth:read` (GET) / `auth:write` (anything else).
+/// Per-user authentication is the upstream consumer's responsibility.
 async fn require_admin(
+    method: &Method,
     headers: &HeaderMap,
-    _ctx: &AuthCtx,
+    ctx: &AuthCtx,
     keys: &AdminApiKeys,
 ) -> Result<(), Box<Response>> {
-    crate::gate::require_admin_bearer(headers, keys)
+    crate::gate::require_admin_or_api_key(
+        headers,
+        ctx,
+        keys,
+        Permission::new(Resource::Auth, method),
+    )
+    .await
 }
 
 // =====================================================================
@@ -132,10 +165,11 @@ pub struct ListUsersResponse {
 async fn list_users(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Query(q): Query<ListUsersQuery>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let limit = q.limit.unwrap_or(50).clamp(1, 500);
@@ -176,10 +210,11 @@ pub struct CreateUserBody {
 async fn create_user_handler(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<CreateUserBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let id = format!(
@@ -223,6 +258,10 @@ pub struct UserDetailResponse {
     pub passkeys: Vec<PasskeySummary>,
     pub sessions: Vec<crate::store::Session>,
     pub upstream: Vec<UpstreamLink>,
+    /// Second-factor enrollment; absent when MFA isn't configured.
+    #[cfg(feature = "auth-mfa")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mfa: Option<crate::mfa::MfaStatus>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -242,10 +281,11 @@ pub struct UpstreamLink {
 async fn get_user_detail(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let user = match ctx.users.get_user_by_id(&id).await {
@@ -282,6 +322,14 @@ async fn get_user_detail(
             .collect(),
         Err(e) => return server_error(&format!("list upstream: {e}")),
     };
+    #[cfg(feature = "auth-mfa")]
+    let mfa = match ctx.mfa.as_ref() {
+        Some(mfa) => match mfa.status(&id).await {
+            Ok(status) => Some(status),
+            Err(e) => return server_error(&format!("mfa status: {e}")),
+        },
+        None => None,
+    };
     (
         StatusCode::OK,
         Json(UserDetailResponse {
@@ -289,6 +337,8 @@ async fn get_user_detail(
             passkeys,
             sessions,
             upstream,
+            #[cfg(feature = "auth-mfa")]
+            mfa,
         }),
     )
         .into_response()
@@ -304,11 +354,12 @@ pub struct UpdateUserBody {
 async fn update_user_handler(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
     Json(body): Json<UpdateUserBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let mut user = match ctx.users.get_user_by_id(&id).await {
@@ -340,12 +391,21 @@ async fn update_user_handler(
 async fn delete_user_handler(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
+    // Org memberships cascade with the user row, so their projected
+    // tuples have to go first, while they can still be listed.
+    #[cfg(feature = "auth-orgs")]
+    if let Some(orgs) = ctx.orgs.as_ref()
+        && let Err(e) = orgs.forget_user(&id).await
+    {
+        return server_error(&format!("drop org memberships: {e}"));
+    }
     match ctx.users.delete_user(&id).await {
         Ok(true) => StatusCode::NO_CONTENT.into_response(),
         Ok(false) => (
@@ -368,11 +428,12 @@ pub struct PasswordResetBody {
 async fn password_reset_handler(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
     Json(body): Json<PasswordResetBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     if ctx
@@ -407,6 +468,154 @@ async fn password_reset_handler(
     }
 }
 
+/// Clear a user's TOTP enrollment and recovery codes — the path for a
+/// lost authenticator. Idempotent: 204 whether or not one existed.
+async fn mfa_reset_handler(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
+        return *r;
+    }
+    if ctx
+        .users
+        .get_user_by_id(&id)
+        .await
+        .unwrap_or(None)
+        .is_none()
+    {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "unknown user_id"})),
+        )
+            .into_response();
+    }
+    #[cfg(feature = "auth-mfa")]
+    {
+        let Some(mfa) = ctx.mfa.as_ref() else {
+            return svc_unavailable("mfa not configured");
+        };
+        match mfa.reset(&id).await {
+            Ok(_) => StatusCode::NO_CONTENT.into_response(),
+            Err(e) => server_error(&format!("reset mfa: {e}")),
+        }
+    }
+    #[cfg(not(feature = "auth-mfa"))]
+    {
+        svc_unavailable("auth-mfa feature not compiled in")
+    }
+}
+
+/// Lift a lockout on the user's login email. 204 when a lock was
+/// active, 404 when the account isn't locked.
+async fn user_unlock_handler(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
+        return *r;
+    }
+    let user = match ctx.users.get_user_by_id(&id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "unknown user_id"})),
+            )
+                .into_response();
+        }
+        Err(e) => return server_error(&format!("get user: {e}")),
+    };
+    let Some(email) = user.email else {
+        return not_locked();
+    };
+    #[cfg(feature = "auth-session")]
+    {
+        let key = crate::lockout::account_key(&email);
+        unlock(&ctx, crate::lockout::SCOPE_ACCOUNT, &key).await
+    }
+    #[cfg(not(feature = "auth-session"))]
+    {
+        let _ = email;
+        svc_unavailable("auth-session feature not compiled in")
+    }
+}
+
+async fn list_lockouts(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
+        return *r;
+    }
+    #[cfg(feature = "auth-session")]
+    {
+        let Some(lockout) = ctx.lockout.as_ref() else {
+            return svc_unavailable("lockout not configured");
+        };
+        match lockout.lockouts().await {
+            Ok(items) => (StatusCode::OK, Json(json!({ "items": items }))).into_response(),
+            Err(e) => server_error(&format!("list lockouts: {e}")),
+        }
+    }
+    #[cfg(not(feature = "auth-session"))]
+    {
+        svc_unavailable("auth-session feature not compiled in")
+    }
+}
+
+async fn unlock_handler(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path((scope, key)): Path<(String, String)>,
+) -> Response {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
+        return *r;
+    }
+    #[cfg(feature = "auth-session")]
+    {
+        if scope != crate::lockout::SCOPE_ACCOUNT && scope != crate::lockout::SCOPE_IP {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "scope must be `account` or `ip`"})),
+            )
+                .into_response();
+        }
+        unlock(&ctx, &scope, &key).await
+    }
+    #[cfg(not(feature = "auth-session"))]
+    {
+        let _ = (scope, key);
+        svc_unavailable("auth-session feature not compiled in")
+    }
+}
+
+#[cfg(feature = "auth-session")]
+async fn unlock(ctx: &AuthCtx, scope: &str, key: &str) -> Response {
+    let Some(lockout) = ctx.lockout.as_ref() else {
+        return svc_unavailable("lockout not configured");
+    };
+    match lockout.unlock(scope, key, "admin").await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => not_locked(),
+        Err(e) => server_error(&format!("unlock: {e}")),
+    }
+}
+
+fn not_locked() -> Response {
+    (StatusCode::NOT_FOUND, Json(json!({"error": "not locked"}))).into_response()
+}
+
 // =====================================================================
 //   /admin/sessions
 // =====================================================================
@@ -432,10 +641,11 @@ pub struct ListSessionsResponse {
 async fn list_sessions(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Query(q): Query<ListSessionsQuery>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let limit = q.limit.unwrap_or(50).clamp(1, 500);
@@ -464,10 +674,11 @@ async fn list_sessions(
 async fn revoke_session(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     match ctx.sessions.delete(&id).await {
@@ -489,10 +700,11 @@ pub struct RevokeAllResponse {
 async fn revoke_sessions_for_user(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(user_id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     match ctx.sessions.delete_for_user(&user_id).await {
@@ -514,9 +726,10 @@ pub struct BiscuitInfo {
 async fn biscuit_info(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let kid = ctx.biscuit.active_kid();
@@ -530,9 +743,10 @@ async fn biscuit_info(
 async fn jwks_proxy(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     // The OIDC provider's JWKS endpoint already enumerates the active
@@ -563,9 +777,10 @@ async fn jwks_proxy(
 async fn zanzibar_list_namespaces(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -588,10 +803,11 @@ async fn zanzibar_list_namespaces(
 async fn zanzibar_get_namespace(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(name): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -619,10 +835,11 @@ async fn zanzibar_get_namespace(
 async fn zanzibar_define_namespace(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(schema): Json<crate::zanzibar::NamespaceSchema>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -630,6 +847,13 @@ async fn zanzibar_define_namespace(
         let Some(store) = ctx.zanzibar.as_ref() else {
             return svc_unavailable("zanzibar not enabled");
         };
+        if let Some(e) = schema.caveats.values().find_map(|c| {
+            c.validate()
+                .err()
+                .map(|e| format!("caveat `{}`: {e}", c.name))
+        }) {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": e}))).into_response();
+        }
         return match store.define_namespace(&schema).await {
             Ok(()) => (
                 StatusCode::CREATED,
@@ -657,6 +881,22 @@ pub struct TupleBody {
     /// relation name for userset subjects. See `zanzibar::SubjectRef`.
     #[serde(default)]
     pub subject_rel: String,
+    /// Caveat the tuple is conditional on. Ignored by delete.
+    #[serde(default)]
+    pub caveat: Option<TupleCaveatBody>,
+    /// Unix seconds after which the grant lapses; must be in the
+    /// future. Ignored by delete.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TupleCaveatBody {
+    pub name: String,
+    /// Parameters bound at write time; they take precedence over the
+    /// context supplied at check time.
+    #[serde(default)]
+    pub context: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -680,10 +920,11 @@ pub struct ListTuplesResponse {
 async fn zanzibar_list_tuples(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Query(q): Query<ListTuplesQuery>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -725,10 +966,11 @@ async fn zanzibar_list_tuples(
 async fn zanzibar_write_tuple(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<TupleBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -737,8 +979,39 @@ async fn zanzibar_write_tuple(
             return svc_unavailable("zanzibar not enabled");
         };
         let tuple = body_to_tuple(body);
+        if let Some(at) = tuple.expires_at
+            && tuple.is_expired(crate::zanzibar::store::unix_now())
+        {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": format!("expires_at {at} is not in the future")})),
+            )
+                .into_response();
+        }
+        if let Some(caveat) = &tuple.caveat {
+            let ns = match store.get_namespace(&tuple.object_type).await {
+                Ok(ns) => ns,
+                Err(e) => return server_error(&format!("get namespace: {e}")),
+            };
+            let checked = match ns.as_ref().and_then(|ns| ns.caveats.get(&caveat.name)) {
+                Some(def) => def
+                    .check_context(&caveat.context)
+                    .map_err(|e| e.to_string()),
+                None => Err(format!(
+                    "caveat `{}` is not defined on namespace `{}`",
+                    caveat.name, tuple.object_type
+                )),
+            };
+            if let Err(e) = checked {
+                return (StatusCode::BAD_REQUEST, Json(json!({"error": e}))).into_response();
+            }
+        }
         return match store.write_tuple(&tuple).await {
-            Ok(()) => (StatusCode::CREATED, Json(json!({"ok": true}))).into_response(),
+            Ok(zookie) => (
+                StatusCode::CREATED,
+                Json(json!({"ok": true, "zookie": zookie})),
+            )
+                .into_response(),
             Err(e) => server_error(&format!("write tuple: {e}")),
         };
     }
@@ -769,6 +1042,8 @@ impl DeleteTupleQuery {
             subject_type: self.subject_type?,
             subject_id: self.subject_id?,
             subject_rel: self.subject_rel.unwrap_or_default(),
+            caveat: None,
+            expires_at: None,
         })
     }
 }
@@ -780,11 +1055,12 @@ impl DeleteTupleQuery {
 async fn zanzibar_delete_tuple(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Query(q): Query<DeleteTupleQuery>,
     body: axum::body::Bytes,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -818,8 +1094,10 @@ async fn zanzibar_delete_tuple(
         };
         let tuple = body_to_tuple(tuple_body);
         return match store.delete_tuple(&tuple).await {
-            Ok(true) => StatusCode::NO_CONTENT.into_response(),
-            Ok(false) => (
+            Ok(Some(zookie)) => {
+                (StatusCode::OK, Json(json!({"ok": true, "zookie": zookie}))).into_response()
+            }
+            Ok(None) => (
                 StatusCode::NOT_FOUND,
                 Json(json!({"error": "tuple not found"})),
             )
@@ -845,29 +1123,61 @@ pub struct CheckBody {
     /// relation name for userset subjects.
     #[serde(default)]
     pub subject_rel: String,
+    /// Zookie from an earlier write — evaluate against tuples at least
+    /// that fresh (read-your-writes).
+    #[serde(default)]
+    pub at_least_as_fresh: Option<String>,
+    /// Zookie to evaluate at exactly, ignoring later writes. Mutually
+    /// exclusive with `at_least_as_fresh`; omit both for `Minimum`.
+    #[serde(default)]
+    pub at_exact_snapshot: Option<String>,
+    /// Caveat parameters known at request time (client IP, current
+    /// time, …). Context bound on the tuple wins on conflict.
+    #[serde(default)]
+    pub context: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct CheckResponse {
     pub result: String,
     pub allowed: bool,
+    /// For `Conditional`: caveat parameters that would decide the check.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_context: Vec<String>,
 }
 
 async fn zanzibar_check_handler(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<CheckBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
     {
-        use crate::zanzibar::{CheckResult, Consistency, ObjectRef, SubjectRef};
+        use crate::zanzibar::{
+            CaveatError, CheckResult, Consistency, ConsistencyError, ObjectRef, SubjectRef,
+        };
         let Some(store) = ctx.zanzibar.as_ref() else {
             return svc_unavailable("zanzibar not enabled");
         };
+        let consistency = match (body.at_least_as_fresh, body.at_exact_snapshot) {
+            (None, None) => Consistency::Minimum,
+            (Some(z), None) => Consistency::AtLeastAsFresh(z),
+            (None, Some(z)) => Consistency::Exact(z),
+            (Some(_), Some(_)) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({
+                        "error": "at_least_as_fresh and at_exact_snapshot are mutually exclusive"
+                    })),
+                )
+                    .into_response();
+            }
+        };
         let resource = ObjectRef {
             object_type: body.resource_type,
             object_id: body.resource_id,
@@ -878,25 +1188,42 @@ async fn zanzibar_check_handler(
             subject_rel: body.subject_rel,
         };
         return match store
-            .check(&resource, &body.permission, &subject, Consistency::Minimum)
+            .check_with_context(
+                &resource,
+                &body.permission,
+                &subject,
+                consistency,
+                &body.context,
+            )
             .await
         {
             Ok(r) => {
                 let (label, allowed) = match &r {
                     CheckResult::Allowed { .. } => ("Allowed", true),
                     CheckResult::Denied => ("Denied", false),
+                    CheckResult::Conditional { .. } => ("Conditional", false),
                     CheckResult::DepthExceeded => ("DepthExceeded", false),
                     CheckResult::CycleDetected => ("CycleDetected", false),
                 };
+                let missing_context = match r {
+                    CheckResult::Conditional { missing_context } => missing_context,
+                    _ => Vec::new(),
+                };
                 (
                     StatusCode::OK,
                     Json(CheckResponse {
                         result: label.to_string(),
                         allowed,
+                        missing_context,
                     }),
                 )
                     .into_response()
             }
+            Err(e) if e.is::<ConsistencyError>() || e.is::<CaveatError>() => (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": e.to_string()})),
+            )
+                .into_response(),
             Err(e) => server_error(&format!("check: {e}")),
         };
     }
@@ -919,10 +1246,11 @@ pub struct ExpandBody {
 async fn zanzibar_expand_handler(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<ExpandBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     #[cfg(feature = "auth-zanzibar")]
@@ -948,6 +1276,176 @@ async fn zanzibar_expand_handler(
     }
 }
 
+/// How often an idle watch re-reads the changelog.
+#[cfg(feature = "auth-zanzibar")]
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Long-poll wait when the caller doesn't pass `timeout_secs`, and the
+/// most it may ask for.
+#[cfg(feature = "auth-zanzibar")]
+const WATCH_DEFAULT_TIMEOUT_SECS: u64 = 30;
+#[cfg(feature = "auth-zanzibar")]
+const WATCH_MAX_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct WatchQuery {
+    /// Zookie to resume after. Omitted → start at the current head.
+    /// SSE clients may send `Last-Event-ID` instead.
+    pub since: Option<String>,
+    pub object_type: Option<String>,
+    pub object_id: Option<String>,
+    pub relation: Option<String>,
+    pub subject_type: Option<String>,
+    pub subject_id: Option<String>,
+    pub limit: Option<i64>,
+    /// Long-poll only: how long to wait for a change before answering
+    /// with an empty batch. `0` answers immediately.
+    pub timeout_secs: Option<u64>,
+}
+
+// One endpoint, two transports. `Accept: text/event-stream` gets an
+// SSE stream with one `TOUCH` / `DELETE` event per change and the
+// change's zookie as the event id, so EventSource reconnects resume
+// via `Last-Event-ID`. Anything else is a long-poll answering
+// `{changes, zookie}` as soon as something matches (or on timeout);
+// the caller passes `zookie` back as `since`. A `since` older than the
+// GC horizon is a 400: the changes after it are gone, so the caller
+// must resync and watch from the head.
+async fn zanzibar_watch_handler(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Query(q): Query<WatchQuery>,
+) -> Response {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
+        return *r;
+    }
+    #[cfg(feature = "auth-zanzibar")]
+    {
+        use crate::zanzibar::{ConsistencyError, WatchFilter, decode_zookie};
+        let Some(store) = ctx.zanzibar.clone() else {
+            return svc_unavailable("zanzibar not enabled");
+        };
+        let since = q.since.clone().or_else(|| {
+            headers
+                .get("last-event-id")
+                .and_then(|h| h.to_str().ok())
+                .map(str::to_string)
+        });
+        if let Some(z) = since.as_deref()
+            && decode_zookie(z).is_none()
+        {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": format!("invalid zookie {z:?}")})),
+            )
+                .into_response();
+        }
+        let filter = WatchFilter {
+            object_type: q.object_type,
+            object_id: q.object_id,
+            relation: q.relation,
+            subject_type: q.subject_type,
+            subject_id: q.subject_id,
+            limit: q.limit,
+        };
+        let wants_sse = headers
+            .get(axum::http::header::ACCEPT)
+            .and_then(|h| h.to_str().ok())
+            .is_some_and(|a| a.contains("text/event-stream"));
+        if wants_sse {
+            return watch_sse(store, since, filter).into_response();
+        }
+
+        let timeout = std::time::Duration::from_secs(
+            q.timeout_secs
+                .unwrap_or(WATCH_DEFAULT_TIMEOUT_SECS)
+                .min(WATCH_MAX_TIMEOUT_SECS),
+        );
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut cursor = since;
+        loop {
+            let batch = match store.watch(cursor.as_deref(), &filter).await {
+                Ok(b) => b,
+                Err(e) if e.is::<ConsistencyError>() => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": e.to_string()})),
+                    )
+                        .into_response();
+                }
+                Err(e) => return server_error(&format!("watch: {e}")),
+            };
+            if !batch.changes.is_empty() || tokio::time::Instant::now() >= deadline {
+                return (StatusCode::OK, Json(batch)).into_response();
+            }
+            cursor = Some(batch.zookie);
+            tokio::time::sleep_until(
+                deadline.min(tokio::time::Instant::now() + WATCH_POLL_INTERVAL),
+            )
+            .await;
+        }
+    }
+    #[cfg(not(feature = "auth-zanzibar"))]
+    {
+        let _ = (ctx, q);
+        svc_unavailable("zanzibar not compiled in")
+    }
+}
+
+/// SSE transport for [`zanzibar_watch_handler`]: polls the changelog
+/// and emits each change as it lands. A store error ends the stream;
+/// the client reconnects with `Last-Event-ID` and picks up where it
+/// stopped.
+#[cfg(feature = "auth-zanzibar")]
+fn watch_sse(
+    store: std::sync::Arc<dyn crate::zanzibar::ZanzibarStore>,
+    since: Option<String>,
+    filter: crate::zanzibar::WatchFilter,
+) -> axum::response::sse::Sse<
+    impl futures_util::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+> {
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use std::collections::VecDeque;
+
+    let state = (
+        store,
+        since,
+        filter,
+        VecDeque::<crate::zanzibar::TupleChange>::new(),
+    );
+    let stream = futures_util::stream::unfold(
+        state,
+        |(store, mut cursor, filter, mut pending)| async move {
+            loop {
+                if let Some(change) = pending.pop_front() {
+                    let event = Event::default()
+                        .event(change.operation.as_str())
+                        .id(change.zookie.clone())
+                        .json_data(&change)
+                        .unwrap_or_default();
+                    return Some((Ok(event), (store, cursor, filter, pending)));
+                }
+                match store.watch(cursor.as_deref(), &filter).await {
+                    Ok(batch) => {
+                        if batch.changes.is_empty() {
+                            tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+                        }
+                        cursor = Some(batch.zookie);
+                        pending.extend(batch.changes);
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "zanzibar watch stream ended");
+                        return None;
+                    }
+                }
+            }
+        },
+    );
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 // =====================================================================
 //   /admin/audit
 // =====================================================================
@@ -970,41 +1468,203 @@ pub struct ListAuditQuery {
 
 #[derive(Clone, Debug, Serialize)]
 pub struct AuditResponse {
-    pub items: Vec<serde_json::Value>,
+    pub items: Vec<crate::audit::AuditEvent>,
     pub total: i64,
     pub limit: i64,
     pub offset: i64,
-    /// `false` until the `auth.audit` table is materialised (see
-    /// `crate::schema` notes — deferred to a later phase). The
-    /// dashboard renders an empty-state with this value to explain
-    /// the missing rows.
+    /// `false` when no audit store is wired (engine boot always wires
+    /// one; bare `AuthCtx`s don't). The dashboard renders an
+    /// empty-state with this value to explain the missing rows.
     pub enabled: bool,
 }
 
 async fn audit_list(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Query(q): Query<ListAuditQuery>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let limit = q.limit.unwrap_or(50).clamp(1, 500);
     let offset = q.offset.unwrap_or(0).max(0);
+    let Some(audit) = ctx.audit.as_ref() else {
+        return (
+            StatusCode::OK,
+            Json(AuditResponse {
+                items: Vec::new(),
+                total: 0,
+                limit,
+                offset,
+                enabled: false,
+            }),
+        )
+            .into_response();
+    };
+    let filter = crate::audit::AuditFilter {
+        actor: q.actor.filter(|s| !s.is_empty()),
+        action: q.action.filter(|s| !s.is_empty()),
+        since: q.since,
+        until: q.until,
+    };
+    let items = match audit.list(&filter, limit, offset).await {
+        Ok(v) => v,
+        Err(e) => return server_error(&format!("list audit: {e}")),
+    };
+    let total = match audit.count(&filter).await {
+        Ok(n) => n,
+        Err(e) => return server_error(&format!("count audit: {e}")),
+    };
     (
         StatusCode::OK,
         Json(AuditResponse {
-            items: Vec::new(),
-            total: 0,
+            items,
+            total,
             limit,
             offset,
-            enabled: false,
+            enabled: true,
+        }),
+    )
+        .into_response()
+}
+
+// =====================================================================
+//   /admin/api-keys
+// =====================================================================
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiKeysResponse {
+    pub items: Vec<crate::api_keys::ApiKey>,
+    /// `false` when no API-key store is wired; the dashboard explains
+    /// the empty list with it.
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CreatedApiKey {
+    pub key: crate::api_keys::ApiKey,
+    /// The plaintext token. Shown once — only its hash is stored.
+    pub token: String,
+}
+
+async fn list_api_keys(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(r) = crate::gate::require_admin_bearer(&headers, &keys) {
+        return *r;
+    }
+    let Some(store) = ctx.api_keys.as_ref() else {
+        return (
+            StatusCode::OK,
+            Json(ApiKeysResponse {
+                items: Vec::new(),
+                enabled: false,
+            }),
+        )
+            .into_response();
+    };
+    match store.list().await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(ApiKeysResponse {
+                items,
+                enabled: true,
+            }),
+        )
+            .into_response(),
+        Err(e) => server_error(&format!("list api keys: {e}")),
+    }
+}
+
+async fn create_api_key_handler(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    headers: HeaderMap,
+    Json(body): Json<crate::api_keys::NewApiKey>,
+) -> Response {
+    if let Err(r) = crate::gate::require_admin_bearer(&headers, &keys) {
+        return *r;
+    }
+    let Some(store) = ctx.api_keys.as_ref() else {
+        return svc_unavailable("api keys not configured");
+    };
+    let minted = match crate::api_keys::mint(body, now_secs()) {
+        Ok(m) => m,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "invalid_request", "error_description": e.to_string()})),
+            )
+                .into_response();
+        }
+    };
+    if let Err(e) = store.insert(&minted.key, &minted.secret_hash).await {
+        return server_error(&format!("create api key: {e}"));
+    }
+    crate::audit::record(
+        ctx.audit.as_ref(),
+        "admin",
+        "auth.api_key.create",
+        Some(&minted.key.id),
+        json!({
+            "name": minted.key.name,
+            "owner": minted.key.owner,
+            "scopes": minted.key.scopes,
+            "expires_at": minted.key.expires_at,
+        }),
+    )
+    .await;
+    (
+        StatusCode::CREATED,
+        Json(CreatedApiKey {
+            key: minted.key,
+            token: minted.token,
         }),
     )
         .into_response()
 }
 
+async fn revoke_api_key_handler(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(r) = crate::gate::require_admin_bearer(&headers, &keys) {
+        return *r;
+    }
+    let Some(store) = ctx.api_keys.as_ref() else {
+        return svc_unavailable("api keys not configured");
+    };
+    let key = match store.get(&id).await {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "unknown api key"})),
+            )
+                .into_response();
+        }
+        Err(e) => return server_error(&format!("get api key: {e}")),
+    };
+    if let Err(e) = store.delete(&id).await {
+        return server_error(&format!("revoke api key: {e}"));
+    }
+    crate::audit::record(
+        ctx.audit.as_ref(),
+        "admin",
+        "auth.api_key.revoke",
+        Some(&id),
+        json!({"name": key.name, "owner": key.owner}),
+    )
+    .await;
+    StatusCode::NO_CONTENT.into_response()
+}
+
 // =====================================================================
 //   helpers
 // =====================================================================
@@ -1018,6 +1678,11 @@ fn body_to_tuple(body: TupleBody) -> crate::zanzibar::Tuple {
         subject_type: body.subject_type,
         subject_id: body.subject_id,
         subject_rel: body.subject_rel,
+        caveat: body.caveat.map(|c| crate::zanzibar::TupleCaveat {
+            name: c.name,
+            context: c.context,
+        }),
+        expires_at: body.expires_at,
     }
 }
 
@@ -0,0 +1,925 @@
+//! Database-backed, scoped API keys — rows in `auth.api_keys`.
+//!
+//! The static `admin_api_keys` from `engine.toml` carry full power over
+//! every module. API keys are the narrower alternative for CI pipelines
+//! and service accounts: each has a name, an owner, an optional expiry,
+//! a last-used timestamp and a list of [`Scope`]s, and is accepted by
+//! the same module gates as the admin bearer (see
+//! [`crate::gate::require_api_key`]).
+//!
+//! Tokens look like `ak_<43 base64url chars>` and are shown exactly
+//! once, at creation. Only their SHA-256 is stored; the first eight
+//! characters after `ak_` are kept as a display prefix so operators can
+//! tell keys apart.
+//!
+//! Scope grammar — `write` implies `read`; `<glob>` is a pattern where
+//! `*` matches any run of characters, `/` included:
+//!
+//! - `*` — every module surface (but never API-key management)
+//! - `workflow:<access>` — every namespace
+//! - `workflow:namespace=<glob>:<access>`
+//! - `vault:<access>` — every vault surface
+//! - `vault:<surface>:<access>` — one surface (`kv`, `transit`, `pki`,
+//!   `ssh`, `collections`, `share`, `dynamic`, `sys`, or `*`)
+//! - `vault:<surface>:<access>:<glob>` — only paths (KV), key names
+//!   (transit) or role names (PKI, SSH) matching the glob, e.g.
+//!   `vault:kv:read:/apps/*`, `vault:ssh:write:nspawn-*`. A transit key
+//!   glob covers the crypto operations only, and a PKI or SSH role glob
+//!   issuing and signing only: managing keys and writing roles needs the
+//!   whole surface.
+//! - `auth:<access>` — auth admin (`/api/v1/engine/auth/admin/*`)
+//! - `engine:<access>` — engine-core admin (`/api/v1/engine/core/*`)
+//!
+//! A request is a `read` when its method is `GET`, `HEAD`, `OPTIONS` or
+//! `LIST` (the Vault CLI's listing verb) and a `write` otherwise.
+//!
+//! Keys are managed through `GET`/`POST /admin/api-keys` and
+//! `DELETE /admin/api-keys/{id}` (see [`crate::admin`]). Those routes
+//! accept only the static admin bearer so a scoped key can never mint
+//! a broader one.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Prefix every API key token starts with. Gates use it to tell API
+/// keys apart from static admin keys and JWTs without a lookup.
+pub const TOKEN_PREFIX: &str = "ak_";
+
+/// How long `last_used_at` may lag behind. Authentications inside this
+/// window skip the write so a busy pipeline doesn't update the row on
+/// every request.
+pub const LAST_USED_GRANULARITY_SECS: f64 = 60.0;
+
+/// Read or write. `Write` covers `Read`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    /// `GET`, `HEAD`, `OPTIONS` and `LIST` read; everything else writes.
+    pub fn for_method(method: &Method) -> Self {
+        if method == Method::GET
+            || method == Method::HEAD
+            || method == Method::OPTIONS
+            || method.as_str() == "LIST"
+        {
+            Access::Read
+        } else {
+            Access::Write
+        }
+    }
+
+    fn covers(self, needed: Access) -> bool {
+        self == Access::Write || needed == Access::Read
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Access::Read => "read",
+            Access::Write => "write",
+        }
+    }
+}
+
+impl FromStr for Access {
+    type Err = ScopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Access::Read),
+            "write" => Ok(Access::Write),
+            other => Err(ScopeError(format!(
+                "unknown access {other:?} (expected read or write)"
+            ))),
+        }
+    }
+}
+
+/// One parsed scope. See the module docs for the grammar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Scope {
+    All,
+    Workflow {
+        namespace: Option<String>,
+        access: Access,
+    },
+    Vault {
+        surface: Option<String>,
+        access: Access,
+        path: Option<String>,
+    },
+    Auth {
+        access: Access,
+    },
+    Engine {
+        access: Access,
+    },
+}
+
+/// Why a scope string was rejected.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("invalid scope: {0}")]
+pub struct ScopeError(pub String);
+
+impl FromStr for Scope {
+    type Err = ScopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "*" {
+            return Ok(Scope::All);
+        }
+        let parts: Vec<&str> = s.splitn(4, ':').collect();
+        match parts.as_slice() {
+            ["workflow", access] => Ok(Scope::Workflow {
+                namespace: None,
+                access: access.parse()?,
+            }),
+            ["workflow", selector, access] => {
+                let namespace = selector
+                    .strip_prefix("namespace=")
+                    .filter(|ns| !ns.is_empty())
+                    .ok_or_else(|| {
+                        ScopeError(format!(
+                            "{s:?}: expected workflow:namespace=<glob>:<access>"
+                        ))
+                    })?;
+                Ok(Scope::Workflow {
+                    namespace: Some(namespace.to_string()),
+                    access: access.parse()?,
+                })
+            }
+            ["vault", access] => Ok(Scope::Vault {
+                surface: None,
+                access: access.parse()?,
+                path: None,
+            }),
+            ["vault", surface, access] => Ok(Scope::Vault {
+                surface: Some(vault_surface(s, surface)?),
+                access: access.parse()?,
+                path: None,
+            }),
+            ["vault", surface, access, path] => {
+                let path = normalise_path(path);
+                if path.is_empty() {
+                    return Err(ScopeError(format!("{s:?}: empty path pattern")));
+                }
+                Ok(Scope::Vault {
+                    surface: Some(vault_surface(s, surface)?),
+                    access: access.parse()?,
+                    path: Some(path.to_string()),
+                })
+            }
+            ["auth", access] => Ok(Scope::Auth {
+                access: access.parse()?,
+            }),
+            ["engine", access] => Ok(Scope::Engine {
+                access: access.parse()?,
+            }),
+            _ => Err(ScopeError(format!(
+                "{s:?}: expected *, workflow:…, vault:…, auth:<access> or engine:<access>"
+            ))),
+        }
+    }
+}
+
+fn vault_surface(scope: &str, surface: &str) -> Result<String, ScopeError> {
+    if surface.is_empty() || surface.contains('/') {
+        return Err(ScopeError(format!(
+            "{scope:?}: bad vault surface {surface:?}"
+        )));
+    }
+    Ok(surface.to_string())
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::All => f.write_str("*"),
+            Scope::Workflow {
+                namespace: None,
+                access,
+            } => write!(f, "workflow:{}", access.as_str()),
+            Scope::Workflow {
+                namespace: Some(ns),
+                access,
+            } => write!(f, "workflow:namespace={ns}:{}", access.as_str()),
+            Scope::Vault {
+                surface: None,
+                access,
+                ..
+            } => write!(f, "vault:{}", access.as_str()),
+            Scope::Vault {
+                surface: Some(surface),
+                access,
+                path: None,
+            } => write!(f, "vault:{surface}:{}", access.as_str()),
+            Scope::Vault {
+                surface: Some(surface),
+                access,
+                path: Some(path),
+            } => write!(f, "vault:{surface}:{}:/{path}", access.as_str()),
+            Scope::Auth { access } => write!(f, "auth:{}", access.as_str()),
+            Scope::Engine { access } => write!(f, "engine:{}", access.as_str()),
+        }
+    }
+}
+
+impl Scope {
+    /// Whether this scope alone grants `permission`.
+    pub fn allows(&self, permission: &Permission<'_>) -> bool {
+        let needed = permission.access;
+        match (self, &permission.resource) {
+            (Scope::All, _) => true,
+            (Scope::Workflow { namespace, access }, Resource::Workflow { namespace: wanted }) => {
+                access.covers(needed)
+                    && match (namespace, wanted) {
+                        (None, _) => true,
+                        (Some(pattern), Some(ns)) => glob_match(pattern, ns),
+                        // The request didn't say which namespace it
+                        // touches — only an unrestricted scope may pass.
+                        (Some(_), None) => false,
+                    }
+            }
+            (
+                Scope::Vault {
+                    surface,
+                    access,
+                    path,
+                },
+                Resource::Vault {
+                    surface: wanted_surface,
+                    path: wanted_path,
+                },
+            ) => {
+                access.covers(needed)
+                    && surface
+                        .as_deref()
+                        .is_none_or(|s| s == "*" || s == *wanted_surface)
+                    && match (path, wanted_path) {
+                        (None, _) => true,
+                        (Some(pattern), Some(p)) => glob_match(pattern, normalise_path(p)),
+                        (Some(_), None) => false,
+                    }
+            }
+            (Scope::Auth { access }, Resource::Auth) => access.covers(needed),
+            (Scope::Engine { access }, Resource::Engine) => access.covers(needed),
+            _ => false,
+        }
+    }
+}
+
+/// What a request needs from an API key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resource<'a> {
+    /// A workflow API call. `namespace` is `None` when the request
+    /// doesn't name one.
+    Workflow { namespace: Option<&'a str> },
+    /// A vault call against `surface`, touching `path` (KV path or
+    /// transit key name) when it addresses one.
+    Vault {
+        surface: &'a str,
+        path: Option<&'a str>,
+    },
+    /// Auth admin.
+    Auth,
+    /// Engine-core admin.
+    Engine,
+}
+
+/// A [`Resource`] plus the [`Access`] the request needs on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Permission<'a> {
+    pub resource: Resource<'a>,
+    pub access: Access,
+}
+
+impl<'a> Permission<'a> {
+    pub fn new(resource: Resource<'a>, method: &Method) -> Self {
+        Self {
+            resource,
+            access: Access::for_method(method),
+        }
+    }
+}
+
+impl fmt::Display for Permission<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let access = self.access.as_str();
+        match self.resource {
+            Resource::Workflow { namespace: None } => write!(f, "workflow:{access}"),
+            Resource::Workflow {
+                namespace: Some(ns),
+            } => write!(f, "workflow:namespace={ns}:{access}"),
+            Resource::Vault {
+                surface,
+                path: None,
+            } => write!(f, "vault:{surface}:{access}"),
+            Resource::Vault {
+                surface,
+                path: Some(path),
+            } => write!(f, "vault:{surface}:{access}:/{}", normalise_path(path)),
+            Resource::Auth => write!(f, "auth:{access}"),
+            Resource::Engine => write!(f, "engine:{access}"),
+        }
+    }
+}
+
+/// Parse every scope, failing on the first bad one.
+pub fn parse_scopes<S: AsRef<str>>(scopes: &[S]) -> Result<Vec<Scope>, ScopeError> {
+    scopes.iter().map(|s| s.as_ref().parse()).collect()
+}
+
+fn normalise_path(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
+/// `*` matches any run of characters; everything else is literal.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let (p, v) = (pattern.as_bytes(), value.as_bytes());
+    let (mut pi, mut vi) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while vi < v.len() {
+        if pi < p.len() && p[pi] == b'*' {
+            star = Some((pi, vi));
+            pi += 1;
+        } else if pi < p.len() && p[pi] == v[vi] {
+            pi += 1;
+            vi += 1;
+        } else if let Some((sp, sv)) = star {
+            pi = sp + 1;
+            vi = sv + 1;
+            star = Some((sp, sv + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == b'*')
+}
+
+/// One row in `auth.api_keys`. The secret itself is never stored.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    /// Free-form owner — a user id, team or pipeline name.
+    pub owner: String,
+    /// First characters of the token, for telling keys apart.
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: f64,
+    pub expires_at: Option<f64>,
+    pub last_used_at: Option<f64>,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self, now: f64) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    /// Whether any of the key's scopes grants `permission`. Scopes that
+    /// no longer parse grant nothing.
+    pub fn allows(&self, permission: &Permission<'_>) -> bool {
+        self.scopes
+            .iter()
+            .filter_map(|s| s.parse::<Scope>().ok())
+            .any(|s| s.allows(permission))
+    }
+}
+
+/// Body of `POST /admin/api-keys`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NewApiKey {
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<String>,
+    /// Unix seconds; `None` never expires.
+    #[serde(default)]
+    pub expires_at: Option<f64>,
+}
+
+/// A freshly minted key and its one-time plaintext token.
+#[derive(Clone, Debug)]
+pub struct MintedApiKey {
+    pub key: ApiKey,
+    pub token: String,
+    pub secret_hash: String,
+}
+
+/// Validate `new` and mint a key for it. Scopes are normalised to their
+/// canonical spelling.
+pub fn mint(new: NewApiKey, now: f64) -> Result<MintedApiKey, ScopeError> {
+    if new.name.trim().is_empty() {
+        return Err(ScopeError("name is required".into()));
+    }
+    if new.scopes.is_empty() {
+        return Err(ScopeError("at least one scope is required".into()));
+    }
+    let scopes = parse_scopes(&new.scopes)?
+        .iter()
+        .map(Scope::to_string)
+        .collect();
+    let secret = data_encoding::BASE64URL_NOPAD.encode(&random_bytes::<32>());
+    let token = format!("{TOKEN_PREFIX}{secret}");
+    let key = ApiKey {
+        id: format!(
+            "key_{}",
+            data_encoding::BASE64URL_NOPAD.encode(&random_bytes::<12>())
+        ),
+        name: new.name.trim().to_string(),
+        owner: new.owner.trim().to_string(),
+        prefix: format!("{TOKEN_PREFIX}{}", &secret[..8]),
+        scopes,
+        created_at: now,
+        expires_at: new.expires_at,
+        last_used_at: None,
+    };
+    Ok(MintedApiKey {
+        secret_hash: token_hash(&token),
+        key,
+        token,
+    })
+}
+
+/// Look `token` up, reject it if expired, and bump `last_used_at`.
+/// `Ok(None)` for unknown or expired tokens.
+pub async fn authenticate(
+    store: &dyn ApiKeyStore,
+    token: &str,
+    now: f64,
+) -> anyhow::Result<Option<ApiKey>> {
+    let Some(key) = store.find_by_hash(&token_hash(token)).await? else {
+        return Ok(None);
+    };
+    if key.is_expired(now) {
+        return Ok(None);
+    }
+    if key
+        .last_used_at
+        .is_none_or(|at| now - at >= LAST_USED_GRANULARITY_SECS)
+    {
+        store.touch(&key.id, now).await?;
+    }
+    Ok(Some(key))
+}
+
+pub(crate) fn token_hash(token: &str) -> String {
+    data_encoding::HEXLOWER.encode(&Sha256::digest(token.as_bytes()))
+}
+
+pub(crate) fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    use rand::RngCore;
+    let mut buf = [0u8; N];
+    rand::rng().fill_bytes(&mut buf);
+    buf
+}
+
+#[async_trait::async_trait]
+pub trait ApiKeyStore: Send + Sync + 'static {
+    async fn insert(&self, key: &ApiKey, secret_hash: &str) -> anyhow::Result<()>;
+    async fn find_by_hash(&self, secret_hash: &str) -> anyhow::Result<Option<ApiKey>>;
+    async fn get(&self, id: &str) -> anyhow::Result<Option<ApiKey>>;
+    /// Every key, newest first.
+    async fn list(&self) -> anyhow::Result<Vec<ApiKey>>;
+    /// `true` when a key was deleted.
+    async fn delete(&self, id: &str) -> anyhow::Result<bool>;
+    async fn touch(&self, id: &str, now: f64) -> anyhow::Result<()>;
+    /// Move a key's expiry; `None` makes it never expire. `true` when
+    /// the key exists.
+    async fn set_expires_at(&self, id: &str, expires_at: Option<f64>) -> anyhow::Result<bool>;
+    /// Delete, in one statement, every key expired by `now` whose owner
+    /// starts with one of `owner_prefixes`. Returns how many went.
+    async fn delete_expired(&self, owner_prefixes: &[&str], now: f64) -> anyhow::Result<u64>;
+}
+
+/// `LIKE` pattern matching owners that start with `prefix` literally.
+fn owner_prefix_pattern(prefix: &str) -> String {
+    let escaped = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("{escaped}%")
+}
+
+type ApiKeyRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    f64,
+    Option<f64>,
+    Option<f64>,
+);
+
+fn key_from_row(
+    (id, name, owner, prefix, scopes, created_at, expires_at, last_used_at): ApiKeyRow,
+) -> ApiKey {
+    ApiKey {
+        id,
+        name,
+        owner,
+        prefix,
+        scopes: serde_json::from_str(&scopes).unwrap_or_default(),
+        created_at,
+        expires_at,
+        last_used_at,
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+#[derive(Clone)]
+pub struct PostgresApiKeyStore {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "backend-postgres")]
+impl PostgresApiKeyStore {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+const PG_SELECT: &str =
+    "SELECT id, name, owner, prefix, scopes::text, created_at, expires_at, last_used_at
+     FROM auth.api_keys";
+
+#[cfg(feature = "backend-postgres")]
+#[async_trait::async_trait]
+impl ApiKeyStore for PostgresApiKeyStore {
+    async fn insert(&self, key: &ApiKey, secret_hash: &str) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query(
+            "INSERT INTO auth.api_keys
+                 (id, name, owner, prefix, secret_hash, scopes, created_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6::jsonb, $7, $8)",
+        )
+        .bind(&key.id)
+        .bind(&key.name)
+        .bind(&key.owner)
+        .bind(&key.prefix)
+        .bind(secret_hash)
+        .bind(serde_json::to_string(&key.scopes)?)
+        .bind(key.created_at)
+        .bind(key.expires_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.api_keys insert")?;
+        Ok(())
+    }
+
+    async fn find_by_hash(&self, secret_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        use anyhow::Context;
+        let row: Option<ApiKeyRow> = sqlx::query_as(&format!("{PG_SELECT} WHERE secret_hash = $1"))
+            .bind(secret_hash)
+            .fetch_optional(&self.pool)
+            .await
+            .context("auth.api_keys find")?;
+        Ok(row.map(key_from_row))
+    }
+
+    async fn get(&self, id: &str) -> anyhow::Result<Option<ApiKey>> {
+        use anyhow::Context;
+        let row: Option<ApiKeyRow> = sqlx::query_as(&format!("{PG_SELECT} WHERE id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("auth.api_keys get")?;
+        Ok(row.map(key_from_row))
+    }
+
+    async fn list(&self) -> anyhow::Result<Vec<ApiKey>> {
+        use anyhow::Context;
+        let rows: Vec<ApiKeyRow> =
+            sqlx::query_as(&format!("{PG_SELECT} ORDER BY created_at DESC, id"))
+                .fetch_all(&self.pool)
+                .await
+                .context("auth.api_keys list")?;
+        Ok(rows.into_iter().map(key_from_row).collect())
+    }
+
+    async fn delete(&self, id: &str) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query("DELETE FROM auth.api_keys WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys delete")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch(&self, id: &str, now: f64) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("UPDATE auth.api_keys SET last_used_at = $2 WHERE id = $1")
+            .bind(id)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys touch")?;
+        Ok(())
+    }
+
+    async fn set_expires_at(&self, id: &str, expires_at: Option<f64>) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query("UPDATE auth.api_keys SET expires_at = $2 WHERE id = $1")
+            .bind(id)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys set_expires_at")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired(&self, owner_prefixes: &[&str], now: f64) -> anyhow::Result<u64> {
+        use anyhow::Context;
+        if owner_prefixes.is_empty() {
+            return Ok(0);
+        }
+        let owners = (2..owner_prefixes.len() + 2)
+            .map(|i| format!("owner LIKE ${i} ESCAPE '\\'"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let sql = format!("DELETE FROM auth.api_keys WHERE expires_at <= $1 AND ({owners})");
+        let mut query = sqlx::query(&sql).bind(now);
+        for prefix in owner_prefixes {
+            query = query.bind(owner_prefix_pattern(prefix));
+        }
+        let result = query
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys delete_expired")?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Clone)]
+pub struct SqliteApiKeyStore {
+    pool: sqlx::SqlitePool,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl SqliteApiKeyStore {
+    pub fn new(pool: sqlx::SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+const SQLITE_SELECT: &str =
+    "SELECT id, name, owner, prefix, scopes, created_at, expires_at, last_used_at
+     FROM auth.api_keys";
+
+#[cfg(feature = "backend-sqlite")]
+#[async_trait::async_trait]
+impl ApiKeyStore for SqliteApiKeyStore {
+    async fn insert(&self, key: &ApiKey, secret_hash: &str) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query(
+            "INSERT INTO auth.api_keys
+                 (id, name, owner, prefix, secret_hash, scopes, created_at, expires_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&key.id)
+        .bind(&key.name)
+        .bind(&key.owner)
+        .bind(&key.prefix)
+        .bind(secret_hash)
+        .bind(serde_json::to_string(&key.scopes)?)
+        .bind(key.created_at)
+        .bind(key.expires_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.api_keys insert")?;
+        Ok(())
+    }
+
+    async fn find_by_hash(&self, secret_hash: &str) -> anyhow::Result<Option<ApiKey>> {
+        use anyhow::Context;
+        let row: Option<ApiKeyRow> =
+            sqlx::query_as(&format!("{SQLITE_SELECT} WHERE secret_hash = ?"))
+                .bind(secret_hash)
+                .fetch_optional(&self.pool)
+                .await
+                .context("auth.api_keys find")?;
+        Ok(row.map(key_from_row))
+    }
+
+    async fn get(&self, id: &str) -> anyhow::Result<Option<ApiKey>> {
+        use anyhow::Context;
+        let row: Option<ApiKeyRow> = sqlx::query_as(&format!("{SQLITE_SELECT} WHERE id = ?"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("auth.api_keys get")?;
+        Ok(row.map(key_from_row))
+    }
+
+    async fn list(&self) -> anyhow::Result<Vec<ApiKey>> {
+        use anyhow::Context;
+        let rows: Vec<ApiKeyRow> =
+            sqlx::query_as(&format!("{SQLITE_SELECT} ORDER BY created_at DESC, id"))
+                .fetch_all(&self.pool)
+                .await
+                .context("auth.api_keys list")?;
+        Ok(rows.into_iter().map(key_from_row).collect())
+    }
+
+    async fn delete(&self, id: &str) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query("DELETE FROM auth.api_keys WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys delete")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch(&self, id: &str, now: f64) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("UPDATE auth.api_keys SET last_used_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys touch")?;
+        Ok(())
+    }
+
+    async fn set_expires_at(&self, id: &str, expires_at: Option<f64>) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query("UPDATE auth.api_keys SET expires_at = ? WHERE id = ?")
+            .bind(expires_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys set_expires_at")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired(&self, owner_prefixes: &[&str], now: f64) -> anyhow::Result<u64> {
+        use anyhow::Context;
+        if owner_prefixes.is_empty() {
+            return Ok(0);
+        }
+        let owners = vec!["owner LIKE ? ESCAPE '\\'"; owner_prefixes.len()].join(" OR ");
+        let sql = format!("DELETE FROM auth.api_keys WHERE expires_at <= ? AND ({owners})");
+        let mut query = sqlx::query(&sql).bind(now);
+        for prefix in owner_prefixes {
+            query = query.bind(owner_prefix_pattern(prefix));
+        }
+        let result = query
+            .execute(&self.pool)
+            .await
+            .context("auth.api_keys delete_expired")?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(s: &str) -> Scope {
+        s.parse().unwrap()
+    }
+
+    fn perm(resource: Resource<'_>, access: Access) -> Permission<'_> {
+        Permission { resource, access }
+    }
+
+    #[test]
+    fn scopes_round_trip() {
+        for s in [
+            "*",
+            "workflow:read",
+            "workflow:namespace=prod:write",
+            "vault:write",
+            "vault:kv:read",
+            "vault:kv:read:/apps/*",
+            "auth:read",
+            "engine:write",
+        ] {
+            assert_eq!(scope(s).to_string(), s);
+        }
+        // The leading slash on a path pattern is optional.
+        assert_eq!(
+            scope("vault:kv:read:apps/*").to_string(),
+            "vault:kv:read:/apps/*"
+        );
+    }
+
+    #[test]
+    fn bad_scopes_are_rejected() {
+        for s in [
+            "",
+            "workflow",
+            "workflow:delete",
+            "workflow:prod:write",
+            "workflow:namespace=:read",
+            "vault:kv:admin",
+            "vault:kv:read:/",
+            "billing:read",
+            "auth:kv:read",
+        ] {
+            assert!(s.parse::<Scope>().is_err(), "{s:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn write_implies_read() {
+        let s = scope("auth:write");
+        assert!(s.allows(&perm(Resource::Auth, Access::Read)));
+        assert!(s.allows(&perm(Resource::Auth, Access::Write)));
+        let s = scope("auth:read");
+        assert!(s.allows(&perm(Resource::Auth, Access::Read)));
+        assert!(!s.allows(&perm(Resource::Auth, Access::Write)));
+        assert!(!s.allows(&perm(Resource::Engine, Access::Read)));
+    }
+
+    #[test]
+    fn workflow_namespace_scopes() {
+        let s = scope("workflow:namespace=prod:write");
+        let ns = |n| Resource::Workflow { namespace: n };
+        assert!(s.allows(&perm(ns(Some("prod")), Access::Write)));
+        assert!(!s.allows(&perm(ns(Some("staging")), Access::Read)));
+        assert!(!s.allows(&perm(ns(None), Access::Read)));
+        let s = scope("workflow:namespace=team-*:read");
+        assert!(s.allows(&perm(ns(Some("team-a")), Access::Read)));
+        assert!(!s.allows(&perm(ns(Some("team-a")), Access::Write)));
+        let s = scope("workflow:read");
+        assert!(s.allows(&perm(ns(None), Access::Read)));
+    }
+
+    #[test]
+    fn vault_path_scopes() {
+        let s = scope("vault:kv:read:/apps/*");
+        let kv = |p| Resource::Vault {
+            surface: "kv",
+            path: p,
+        };
+        assert!(s.allows(&perm(kv(Some("apps/db")), Access::Read)));
+        assert!(s.allows(&perm(kv(Some("/apps/web/token")), Access::Read)));
+        assert!(!s.allows(&perm(kv(Some("apps/db")), Access::Write)));
+        assert!(!s.allows(&perm(kv(Some("infra/db")), Access::Read)));
+        assert!(!s.allows(&perm(kv(None), Access::Read)));
+        let transit = Resource::Vault {
+            surface: "transit",
+            path: Some("apps"),
+        };
+        assert!(!s.allows(&perm(transit, Access::Read)));
+        assert!(scope("vault:*:read").allows(&perm(transit, Access::Read)));
+        assert!(scope("vault:read").allows(&perm(transit, Access::Read)));
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("apps/*", "apps/"));
+        assert!(glob_match("a*c*e", "abcde"));
+        assert!(!glob_match("a*c*e", "abcd"));
+        assert!(glob_match("prod", "prod"));
+        assert!(!glob_match("prod", "prod2"));
+    }
+
+    #[test]
+    fn minting_normalises_scopes_and_hides_the_secret() {
+        let minted = mint(
+            NewApiKey {
+                name: " ci ".into(),
+                owner: "platform".into(),
+                scopes: vec!["vault:kv:read:apps/*".into()],
+                expires_at: None,
+            },
+            1.0,
+        )
+        .unwrap();
+        assert_eq!(minted.key.name, "ci");
+        assert_eq!(minted.key.scopes, vec!["vault:kv:read:/apps/*"]);
+        assert!(minted.token.starts_with(TOKEN_PREFIX));
+        assert!(minted.token.starts_with(&minted.key.prefix));
+        assert_eq!(minted.secret_hash, token_hash(&minted.token));
+        assert_ne!(minted.secret_hash, minted.token);
+        let no_scopes = NewApiKey {
+            name: "ci".into(),
+            owner: "platform".into(),
+            scopes: vec![],
+            expires_at: None,
+        };
+        assert!(mint(no_scopes, 1.0).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Append-only audit log — rows in `auth.audit`.
+//!
+//! Security-relevant actions (login lockouts, admin unlocks) append one
+//! [`AuditEvent`] each. The log is read back through
+//! `GET /admin/audit` (see [`crate::admin`]) and the dashboard's audit
+//! pane. Writers never fail the request they describe: an append error
+//! is logged and swallowed by [`record`].
+//!
+//! `actor` is who did it (`system` for automatic actions, `admin` for
+//! the operator bearer), `action` a dotted verb such as
+//! `auth.lockout`, `target` what it was done to, and `detail` free-form
+//! JSON context.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// One row in `auth.audit`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub created_at: f64,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub detail: serde_json::Value,
+}
+
+/// Filters for [`AuditStore::list`] / [`AuditStore::count`]. Unset
+/// fields match everything; `since` / `until` bound `created_at`
+/// inclusively.
+#[derive(Clone, Debug, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub since: Option<f64>,
+    pub until: Option<f64>,
+}
+
+#[async_trait::async_trait]
+pub trait AuditStore: Send + Sync + 'static {
+    /// Append one event stamped `created_at`.
+    async fn append(
+        &self,
+        actor: &str,
+        action: &str,
+        target: Option<&str>,
+        detail: &serde_json::Value,
+        created_at: f64,
+    ) -> anyhow::Result<()>;
+    /// Matching events, newest first.
+    async fn list(
+        &self,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<AuditEvent>>;
+    async fn count(&self, filter: &AuditFilter) -> anyhow::Result<i64>;
+}
+
+/// Append an event to the context's audit log, if one is wired. Errors
+/// are logged rather than returned so auditing never changes the
+/// outcome of the action being audited.
+pub async fn record(
+    audit: Option<&Arc<dyn AuditStore>>,
+    actor: &str,
+    action: &str,
+    target: Option<&str>,
+    detail: serde_json::Value,
+) {
+    let Some(audit) = audit else {
+        return;
+    };
+    if let Err(error) = audit
+        .append(actor, action, target, &detail, now_secs())
+        .await
+    {
+        tracing::error!(%error, action, "audit append failed");
+    }
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+type AuditRow = (i64, f64, String, String, Option<String>, String);
+
+fn event_from_row((id, created_at, actor, action, target, detail): AuditRow) -> AuditEvent {
+    AuditEvent {
+        id,
+        created_at,
+        actor,
+        action,
+        target,
+        detail: serde_json::from_str(&detail).unwrap_or(serde_json::Value::Null),
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+#[derive(Clone)]
+pub struct PostgresAuditStore {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "backend-postgres")]
+impl PostgresAuditStore {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+#[async_trait::async_trait]
+impl AuditStore for PostgresAuditStore {
+    async fn append(
+        &self,
+        actor: &str,
+        action: &str,
+        target: Option<&str>,
+        detail: &serde_json::Value,
+        created_at: f64,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query(
+            "INSERT INTO auth.audit (created_at, actor, action, target, detail)
+             VALUES ($1, $2, $3, $4, $5::jsonb)",
+        )
+        .bind(created_at)
+        .bind(actor)
+        .bind(action)
+        .bind(target)
+        .bind(detail.to_string())
+        .execute(&self.pool)
+        .await
+        .context("auth.audit append")?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<AuditEvent>> {
+        use anyhow::Context;
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            "SELECT id, created_at, actor, action, target, detail::text
+             FROM auth.audit
+             WHERE ($1::TEXT IS NULL OR actor = $1)
+               AND ($2::TEXT IS NULL OR action = $2)
+               AND ($3::DOUBLE PRECISION IS NULL OR created_at >= $3)
+               AND ($4::DOUBLE PRECISION IS NULL OR created_at <= $4)
+             ORDER BY created_at DESC, id DESC
+             LIMIT $5 OFFSET $6",
+        )
+        .bind(filter.actor.as_deref())
+        .bind(filter.action.as_deref())
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.audit list")?;
+        Ok(rows.into_iter().map(event_from_row).collect())
+    }
+
+    async fn count(&self, filter: &AuditFilter) -> anyhow::Result<i64> {
+        use anyhow::Context;
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM auth.audit
+             WHERE ($1::TEXT IS NULL OR actor = $1)
+               AND ($2::TEXT IS NULL OR action = $2)
+               AND ($3::DOUBLE PRECISION IS NULL OR created_at >= $3)
+               AND ($4::DOUBLE PRECISION IS NULL OR created_at <= $4)",
+        )
+        .bind(filter.actor.as_deref())
+        .bind(filter.action.as_deref())
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_one(&self.pool)
+        .await
+        .context("auth.audit count")?;
+        Ok(count)
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Clone)]
+pub struct SqliteAuditStore {
+    pool: sqlx::SqlitePool,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl SqliteAuditStore {
+    pub fn new(pool: sqlx::SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[async_trait::async_trait]
+impl AuditStore for SqliteAuditStore {
+    async fn append(
+        &self,
+        actor: &str,
+        action: &str,
+        target: Option<&str>,
+        detail: &serde_json::Value,
+        created_at: f64,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query(
+            "INSERT INTO auth.audit (created_at, actor, action, target, detail)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(created_at)
+        .bind(actor)
+        .bind(action)
+        .bind(target)
+        .bind(detail.to_string())
+        .execute(&self.pool)
+        .await
+        .context("auth.audit append")?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<AuditEvent>> {
+        use anyhow::Context;
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            "SELECT id, created_at, actor, action, target, detail
+             FROM auth.audit
+             WHERE (?1 IS NULL OR actor = ?1)
+               AND (?2 IS NULL OR action = ?2)
+               AND (?3 IS NULL OR created_at >= ?3)
+               AND (?4 IS NULL OR created_at <= ?4)
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?5 OFFSET ?6",
+        )
+        .bind(filter.actor.as_deref())
+        .bind(filter.action.as_deref())
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.audit list")?;
+        Ok(rows.into_iter().map(event_from_row).collect())
+    }
+
+    async fn count(&self, filter: &AuditFilter) -> anyhow::Result<i64> {
+        use anyhow::Context;
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM auth.audit
+             WHERE (?1 IS NULL OR actor = ?1)
+               AND (?2 IS NULL OR action = ?2)
+               AND (?3 IS NULL OR created_at >= ?3)
+               AND (?4 IS NULL OR created_at <= ?4)",
+        )
+        .bind(filter.actor.as_deref())
+        .bind(filter.action.as_deref())
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_one(&self.pool)
+        .await
+        .context("auth.audit count")?;
+        Ok(count)
+    }
+}
@@ -8,12 +8,20 @@
 
 use std::sync::Arc;
 
+use crate::api_keys::ApiKeyStore;
+use crate::audit::AuditStore;
 use crate::biscuit::BiscuitConfig;
+use crate::metrics::AuthMetrics;
 use crate::store::{SessionStore, UserStore};
 
+#[cfg(feature = "auth-session")]
+use crate::lockout::LoginThrottle;
 #[cfg(feature = "auth-recovery")]
 use crate::recovery::PasswordRecovery;
 
+#[cfg(feature = "auth-mfa")]
+use crate::mfa::MfaManager;
+
 #[cfg(feature = "auth-jwt")]
 use crate::external_jwt::ExternalJwtIssuer;
 #[cfg(feature = "auth-jwt")]
@@ -22,8 +30,12 @@ use crate::jwt::JwtConfig;
 use crate::oidc::OidcRegistry;
 #[cfg(feature = "auth-oidc-provider")]
 use crate::oidc_provider::OidcProviderConfig;
+#[cfg(feature = "auth-orgs")]
+use crate::orgs::OrgManager;
 #[cfg(feature = "auth-passkey")]
 use crate::passkey::PasskeyManager;
+#[cfg(feature = "auth-scim")]
+use crate::scim::ScimProvisioner;
 #[cfg(feature = "auth-zanzibar")]
 use crate::zanzibar::ZanzibarStore;
 
@@ -35,8 +47,25 @@ pub struct AuthCtx {
     pub users: Arc<dyn UserStore>,
     /// Session record store — opaque session id + CSRF token + expiry.
     pub sessions: Arc<dyn SessionStore>,
+    /// Append-only `auth.audit` log. `None` keeps `/admin/audit` empty
+    /// and drops events. See [`crate::audit`].
+    pub audit: Option<Arc<dyn AuditStore>>,
+    /// Scoped `auth.api_keys` accepted by the module gates next to the
+    /// static admin keys. `None` disables them. See [`crate::api_keys`].
+    pub api_keys: Option<Arc<dyn ApiKeyStore>>,
+    /// Login and token counters. Always present; shared by clones.
+    pub metrics: Arc<AuthMetrics>,
+    /// Failed-login counters and lockouts guarding `/login` and the
+    /// recovery endpoints. See [`crate::lockout::LoginThrottle`].
+    #[cfg(feature = "auth-session")]
+    pub lockout: Option<LoginThrottle>,
     #[cfg(feature = "auth-recovery")]
     pub recovery: Option<PasswordRecovery>,
+    /// TOTP + recovery-code second factor. When set, password logins
+    /// for enrolled users need a code before a session is minted. See
+    /// [`crate::mfa::MfaManager`].
+    #[cfg(feature = "auth-mfa")]
+    pub mfa: Option<MfaManager>,
     /// Biscuit capability-token issuer + verifier. Foundational
     /// (always present): wraps the active root keypair loaded from
     /// `auth.biscuit_root_keys` (or generated on first boot). Used for
@@ -86,6 +115,14 @@ pub struct AuthCtx {
     /// CTE walk, expand, lookup_*) lives behind it.
     #[cfg(feature = "auth-zanzibar")]
     pub zanzibar: Option<Arc<dyn ZanzibarStore>>,
+    /// Organizations + groups, projected into [`Self::zanzibar`]. See
+    /// [`crate::orgs::OrgManager`].
+    #[cfg(feature = "auth-orgs")]
+    pub orgs: Option<OrgManager>,
+    /// SCIM 2.0 provisioning — IdP-pushed users and groups, with its
+    /// own bearer tokens. See [`crate::scim::ScimProvisioner`].
+    #[cfg(feature = "auth-scim")]
+    pub scim: Option<ScimProvisioner>,
     /// Full OIDC provider — discovery, JWKS, /authorize, /token,
     /// /userinfo, /revoke, /introspect, federation. Optional because a
     /// deployment may use assay-engine purely as an OIDC client; engine
@@ -107,8 +144,15 @@ impl AuthCtx {
         Self {
             users,
             sessions,
+            audit: None,
+            api_keys: None,
+            metrics: Arc::default(),
+            #[cfg(feature = "auth-session")]
+            lockout: None,
             #[cfg(feature = "auth-recovery")]
             recovery: None,
+            #[cfg(feature = "auth-mfa")]
+            mfa: None,
             biscuit: BiscuitConfig::generate_ephemeral(),
             #[cfg(feature = "auth-jwt")]
             jwt: None,
@@ -120,17 +164,50 @@ impl AuthCtx {
             passkeys: None,
             #[cfg(feature = "auth-zanzibar")]
             zanzibar: None,
+            #[cfg(feature = "auth-orgs")]
+            orgs: None,
+            #[cfg(feature = "auth-scim")]
+            scim: None,
             #[cfg(feature = "auth-oidc-provider")]
             oidc_provider: None,
         }
     }
 
+    /// Replace the audit log. Engine boot wires the PG / SQLite
+    /// [`crate::audit::AuditStore`] once the V16 migration has run.
+    pub fn with_audit(mut self, audit: Arc<dyn AuditStore>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Accept database-backed API keys. Engine boot wires the PG /
+    /// SQLite [`ApiKeyStore`] once the V17 migration has run.
+    pub fn with_api_keys(mut self, api_keys: Arc<dyn ApiKeyStore>) -> Self {
+        self.api_keys = Some(api_keys);
+        self
+    }
+
+    /// Enable brute-force protection on password logins and recovery.
+    #[cfg(feature = "auth-session")]
+    pub fn with_lockout(mut self, lockout: LoginThrottle) -> Self {
+        self.lockout = Some(lockout);
+        self
+    }
+
     #[cfg(feature = "auth-recovery")]
     pub fn with_recovery(mut self, recovery: PasswordRecovery) -> Self {
         self.recovery = Some(recovery);
         self
     }
 
+    /// Replace the second-factor manager. Engine boot wires the PG /
+    /// SQLite [`crate::mfa::MfaStore`] once the V13 migration has run.
+    #[cfg(feature = "auth-mfa")]
+    pub fn with_mfa(mut self, mfa: MfaManager) -> Self {
+        self.mfa = Some(mfa);
+        self
+    }
+
     /// Replace the JWT configuration. Used by engine boot once the
     /// JWKS keys have been loaded from `auth.jwks_keys`.
     #[cfg(feature = "auth-jwt")]
@@ -193,6 +270,24 @@ impl AuthCtx {
         self
     }
 
+    /// Replace the organization manager. Engine boot builds it over the
+    /// PG / SQLite [`crate::orgs::OrgStore`] and the Zanzibar store it
+    /// projects into, once the V14 migration has run.
+    #[cfg(feature = "auth-orgs")]
+    pub fn with_orgs(mut self, orgs: OrgManager) -> Self {
+        self.orgs = Some(orgs);
+        self
+    }
+
+    /// Replace the SCIM provisioner. Engine boot builds it over the PG /
+    /// SQLite [`crate::scim::ScimStore`] once the V15 migration has run
+    /// and `[auth.scim]` is enabled.
+    #[cfg(feature = "auth-scim")]
+    pub fn with_scim(mut self, scim: ScimProvisioner) -> Self {
+        self.scim = Some(scim);
+        self
+    }
+
     /// Replace the OIDC provider configuration. Engine boot constructs
     /// the appropriate stores (PG / SQLite) after the V4 auth schema
     /// migration runs; see `crates/assay-engine/src/init.rs`.
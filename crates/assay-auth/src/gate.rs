@@ -8,6 +8,12 @@
 //! the engine using its own admin bearer. The engine itself does not
 //! resolve sessions or check zanzibar at request time.
 //!
+//! Database-backed API keys ([`crate::api_keys`]) are the scoped
+//! alternative to the admin bearer: [`require_api_key`] authenticates
+//! one and checks its scopes against the [`Permission`] a request
+//! needs, and [`require_admin_or_api_key`] folds that into the admin
+//! check for routes that gate per handler.
+//!
 //! [`extract_caller`] / [`require_role`] / [`require_role_for`] remain
 //! available for callers that still need full session+JWT+zanzibar
 //! resolution — primarily the admin-zanzibar endpoints used BY the
@@ -16,18 +22,20 @@
 //!
 //! Used by:
 //!
-//! - [`crate::admin`] — admin endpoints (`require_admin_bearer`)
+//! - [`crate::admin`] — admin endpoints (`require_admin_or_api_key`;
+//!   API-key management itself is `require_admin_bearer` only)
 //! - [`crate::oidc_provider::admin`] — OIDC provider admin
-//!   (`require_admin_bearer`)
+//!   (`require_admin_or_api_key`)
 //! - `assay_engine::engine_api` — engine-core admin
-//!   (`require_admin_bearer`)
-//! - `assay_engine::server` — module routers wrapped with
-//!   [`admin_bearer_layer`]-equivalent middleware
+//!   (`require_admin_or_api_key`)
+//! - `assay_engine::server` — module routers wrapped with middleware
+//!   calling `require_api_key` / `require_admin_or_jwt`
 
 use axum::http::{HeaderMap, StatusCode, header};
 use axum::response::{IntoResponse, Json, Response};
 use serde_json::json;
 
+use crate::api_keys::{ApiKey, Permission};
 use crate::ctx::AuthCtx;
 use crate::state::AdminApiKeys;
 
@@ -235,6 +243,63 @@ pub fn require_admin_bearer(headers: &HeaderMap, keys: &AdminApiKeys) -> Result<
     Err(unauthorized("admin bearer token required"))
 }
 
+/// The bearer token, when it has the shape of a database API key
+/// (`ak_…`). Lets gates skip the API-key lookup for admin keys and
+/// JWTs.
+pub fn presented_api_key(headers: &HeaderMap) -> Option<&str> {
+    bearer_token(headers).filter(|t| t.starts_with(crate::api_keys::TOKEN_PREFIX))
+}
+
+/// Authenticate a database-backed API key and check that its scopes
+/// cover every one of `permissions`.
+///
+/// Returns `Err(401)` when API keys aren't wired or the token is
+/// unknown or expired, `Err(403)` naming the first permission no scope
+/// grants, and `Err(500)` on a store error.
+pub async fn require_api_key(
+    token: &str,
+    ctx: &AuthCtx,
+    permissions: &[Permission<'_>],
+) -> Result<ApiKey, Box<Response>> {
+    let Some(store) = ctx.api_keys.as_ref() else {
+        return Err(unauthorized("api keys are not enabled"));
+    };
+    let now = crate::api_keys::now_secs();
+    let key = match crate::api_keys::authenticate(store.as_ref(), token, now).await {
+        Ok(Some(key)) => key,
+        Ok(None) => return Err(unauthorized("unknown or expired api key")),
+        Err(e) => return Err(internal(&format!("api key lookup: {e}"))),
+    };
+    if let Some(missing) = permissions.iter().find(|p| !key.allows(p)) {
+        return Err(forbidden(&format!(
+            "api key {:?} lacks scope {missing}",
+            key.name
+        )));
+    }
+    Ok(key)
+}
+
+/// [`require_admin_bearer`] for routes that also take scoped API keys:
+/// the static admin bearer passes outright, an `ak_…` token must hold a
+/// scope covering `permission`.
+pub async fn require_admin_or_api_key(
+    headers: &HeaderMap,
+    ctx: &AuthCtx,
+    keys: &AdminApiKeys,
+    permission: Permission<'_>,
+) -> Result<(), Box<Response>> {
+    if let Some(token) = bearer_token(headers)
+        && keys.enabled()
+        && keys.check(token)
+    {
+        return Ok(());
+    }
+    if let Some(token) = presented_api_key(headers) {
+        return require_api_key(token, ctx, &[permission]).await.map(|_| ());
+    }
+    Err(unauthorized("admin bearer token required"))
+}
+
 /// Resource-server check: accept either the operator admin api-key
 /// (service-to-service / break-glass) OR a JWT from a configured
 /// trusted issuer (per-user identity).
@@ -296,7 +361,7 @@ pub async fn require_admin_or_jwt(
 //   helpers
 // =====================================================================
 
-fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
     headers
         .get(header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
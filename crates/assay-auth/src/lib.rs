@@ -9,14 +9,21 @@
 //! | ---------------------- | ------------------------- | ----------------------------------------------------------- |
 //! | [`session`]            | Ory Kratos (sessions)     | Cookie + CSRF session manager (Argon2id-backed)             |
 //! | [`password`]           | Ory Kratos (passwords)    | Argon2id PHC strings, peppered hashing                      |
+//! | [`mfa`]                | Kratos (TOTP, lookup)     | TOTP second factor + hashed one-time recovery codes         |
+//! | [`lockout`]            | (Ory has nothing)         | Sliding-window brute-force counters, delays + lockouts      |
 //! | [`jwt`]                | Hydra (JWT)               | RS256 issue/verify with rotated JWKS                        |
 //! | [`oidc`]               | Kratos (federation)       | OIDC **client** — log in via Google/Apple/GitHub/upstream   |
 //! | [`oidc_provider`]      | Ory Hydra                 | Full OIDC **provider** — `/authorize`, `/token`, `/userinfo`, `/.well-known/*`, RFC 7009 revoke, RFC 7662 introspect, back-channel logout |
 //! | [`passkey`]            | Kratos (WebAuthn)         | `webauthn-rs`-backed passkey register + auth ceremonies     |
 //! | [`zanzibar`]           | Ory Keto / SpiceDB        | ReBAC tuples + recursive-CTE walk on PG18 + SQLite          |
+//! | [`orgs`]               | Kratos (organizations)    | Tenants + nested groups, projected into Zanzibar tuples     |
+//! | [`scim`]               | (Ory has nothing)         | SCIM 2.0 server — Okta / Entra push users + groups in       |
 //! | [`biscuit`]            | (Ory has nothing)         | Datalog-attenuable capability tokens — **always-on**        |
 //! | [`store`]              | —                         | `UserStore` / `SessionStore` traits + PG / SQLite backends  |
 //! | [`admin`]              | Ory Console (HTTP API)    | Cross-cutting admin endpoints (users, sessions, Zanzibar, …)|
+//! | [`audit`]              | —                         | Append-only `auth.audit` log (lockouts, unlocks)            |
+//! | [`api_keys`]           | —                         | Named, expiring, scoped API keys for the module gates       |
+//! | [`metrics`]            | —                         | Login + token counters for the engine's `/metrics`          |
 //!
 //! ## Why use `assay-auth` instead of Ory?
 //!
@@ -93,10 +100,13 @@
 pub mod error;
 
 pub mod admin;
+pub mod api_keys;
+pub mod audit;
 pub mod authz;
 pub mod biscuit;
 pub mod ctx;
 pub mod gate;
+pub mod metrics;
 pub mod router;
 pub mod schema;
 pub mod state;
@@ -105,12 +115,18 @@ pub mod store;
 #[cfg(feature = "auth-session")]
 pub mod session;
 
+#[cfg(feature = "auth-session")]
+pub mod lockout;
+
 #[cfg(feature = "auth-password")]
 pub mod password;
 
 #[cfg(feature = "auth-recovery")]
 pub mod recovery;
 
+#[cfg(feature = "auth-mfa")]
+pub mod mfa;
+
 #[cfg(feature = "auth-jwt")]
 pub mod jwt;
 
@@ -133,6 +149,12 @@ pub mod passkey;
 #[cfg(feature = "auth-zanzibar")]
 pub mod zanzibar;
 
+#[cfg(feature = "auth-orgs")]
+pub mod orgs;
+
+#[cfg(feature = "auth-scim")]
+pub mod scim;
+
 pub use ctx::AuthCtx;
 pub use error::{Error, Result};
 pub use gate::{Caller, CallerSource};
@@ -0,0 +1,815 @@
+//! Brute-force protection for password logins and recovery.
+//!
+//! Every failed attempt is counted twice — against the account (the
+//! normalised login email, known or not, so lockouts don't reveal which
+//! addresses exist) and against the client IP — in a sliding window of
+//! [`LockoutPolicy::window`]. Past [`LockoutPolicy::free_attempts`]
+//! each failure answers after a doubling delay; at the per-scope
+//! threshold the account or IP is locked for
+//! [`LockoutPolicy::lockout`] and every attempt gets `429` with a
+//! `Retry-After` until the lock expires or an operator clears it.
+//! Locking resets that key's counter, so an expired lock starts from a
+//! fresh budget. A login that issues a session clears the account
+//! counter (never the IP's); a right password that still owes a second
+//! factor clears nothing.
+//!
+//! Guarded endpoints: `POST /login`, `POST /login/mfa` (against the IP,
+//! and against the challenge's account once the `mfa_token` resolves —
+//! before the code is checked, so rotating IPs can't brute-force a
+//! TOTP code) and both `/password/recovery/*` endpoints (IP only, so
+//! nobody can lock a victim out by requesting resets for them).
+//!
+//! Lockouts and unlocks are appended to `auth.audit` (see
+//! [`crate::audit`]). Operators list and clear locks with
+//! `GET /admin/lockouts`, `DELETE /admin/lockouts/{scope}/{key}` and
+//! `DELETE /admin/users/{id}/lockout` (see [`crate::admin`]).
+//!
+//! The client IP is the TCP peer (`ConnectInfo<SocketAddr>`), or the
+//! last `X-Forwarded-For` entry when
+//! [`LockoutPolicy::trust_forwarded_for`] is set — the hop appended by
+//! the proxy directly in front of the engine. Earlier entries are
+//! client-supplied and never trusted.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::extract::ConnectInfo;
+use axum::http::{Extensions, HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Json, Response};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::AuthCtx;
+use crate::audit::AuditStore;
+
+/// Counter scope for the normalised login email.
+pub const SCOPE_ACCOUNT: &str = "account";
+/// Counter scope for the client IP.
+pub const SCOPE_IP: &str = "ip";
+
+/// Thresholds and delays. A zero threshold disables locking for that
+/// scope; counting and delays still apply.
+#[derive(Clone, Debug)]
+pub struct LockoutPolicy {
+    /// Sliding window failures are counted over.
+    pub window: Duration,
+    /// Failures per account within the window that lock it.
+    pub max_account_failures: u32,
+    /// Failures per client IP within the window that lock it.
+    pub max_ip_failures: u32,
+    /// How long a lock lasts.
+    pub lockout: Duration,
+    /// Failures answered without delay.
+    pub free_attempts: u32,
+    /// Delay after the first failure past `free_attempts`; doubles with
+    /// each further failure.
+    pub base_delay: Duration,
+    /// Ceiling for the progressive delay.
+    pub max_delay: Duration,
+    /// Take the client IP from the last `X-Forwarded-For` entry instead
+    /// of the peer.
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(15 * 60),
+            max_account_failures: 10,
+            max_ip_failures: 100,
+            lockout: Duration::from_secs(15 * 60),
+            free_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(4),
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+impl LockoutPolicy {
+    /// Delay owed after the `failures`-th failure in the window.
+    pub fn delay_for(&self, failures: i64) -> Duration {
+        let over = failures - i64::from(self.free_attempts);
+        if over <= 0 {
+            return Duration::ZERO;
+        }
+        let factor = 1u32 << (over - 1).min(16) as u32;
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    fn threshold(&self, scope: &str) -> i64 {
+        i64::from(match scope {
+            SCOPE_ACCOUNT => self.max_account_failures,
+            _ => self.max_ip_failures,
+        })
+    }
+}
+
+/// An active lock — row in `auth.lockouts`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Lockout {
+    pub scope: String,
+    pub key: String,
+    pub failures: i64,
+    pub locked_at: f64,
+    pub locked_until: f64,
+}
+
+#[async_trait::async_trait]
+pub trait LockoutStore: Send + Sync + 'static {
+    /// Count one failure for `(scope, key)` at `now` and return the
+    /// failures since `window_start`, this one included. Prunes rows
+    /// older than the window and expired locks on the way.
+    async fn record_failure(
+        &self,
+        scope: &str,
+        key: &str,
+        now: f64,
+        window_start: f64,
+    ) -> anyhow::Result<i64>;
+    async fn clear_failures(&self, scope: &str, key: &str) -> anyhow::Result<()>;
+    /// Insert or extend a lock and reset the key's failure counter.
+    async fn lock(&self, lockout: &Lockout) -> anyhow::Result<()>;
+    /// End of the active lock on `(scope, key)`, if any.
+    async fn locked_until(&self, scope: &str, key: &str, now: f64) -> anyhow::Result<Option<f64>>;
+    /// Drop the lock and counter for `(scope, key)`. `true` when a lock
+    /// was active.
+    async fn unlock(&self, scope: &str, key: &str, now: f64) -> anyhow::Result<bool>;
+    /// Active locks, soonest-expiring first.
+    async fn list_locks(&self, now: f64) -> anyhow::Result<Vec<Lockout>>;
+}
+
+/// What a failed attempt costs the caller.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FailureOutcome {
+    /// Progressive delay to serve before answering.
+    pub delay: Duration,
+    /// Set when this failure tripped a lock.
+    pub locked_for: Option<Duration>,
+}
+
+/// Per-account + per-IP failure counting over a [`LockoutStore`].
+#[derive(Clone)]
+pub struct LoginThrottle {
+    store: Arc<dyn LockoutStore>,
+    policy: LockoutPolicy,
+    audit: Option<Arc<dyn AuditStore>>,
+}
+
+impl LoginThrottle {
+    pub fn new(store: Arc<dyn LockoutStore>, policy: LockoutPolicy) -> Self {
+        Self {
+            store,
+            policy,
+            audit: None,
+        }
+    }
+
+    /// Append `auth.lockout` / `auth.unlock` events to `audit`.
+    pub fn with_audit(mut self, audit: Arc<dyn AuditStore>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    pub fn policy(&self) -> &LockoutPolicy {
+        &self.policy
+    }
+
+    /// Time left on the longest active lock covering either key.
+    pub async fn check(
+        &self,
+        account: Option<&str>,
+        ip: Option<&str>,
+    ) -> anyhow::Result<Option<Duration>> {
+        let now = now_secs();
+        let mut until: Option<f64> = None;
+        for (scope, key) in scoped(account, ip) {
+            if let Some(t) = self.store.locked_until(scope, key, now).await? {
+                until = Some(until.map_or(t, |u| u.max(t)));
+            }
+        }
+        Ok(until.map(|t| Duration::from_secs_f64((t - now).max(0.0))))
+    }
+
+    /// Count a failure against both keys, locking whichever crossed its
+    /// threshold.
+    pub async fn record_failure(
+        &self,
+        account: Option<&str>,
+        ip: Option<&str>,
+    ) -> anyhow::Result<FailureOutcome> {
+        let now = now_secs();
+        let window_start = now - self.policy.window.as_secs_f64();
+        let mut outcome = FailureOutcome::default();
+        let mut worst = 0;
+        for (scope, key) in scoped(account, ip) {
+            let failures = self
+                .store
+                .record_failure(scope, key, now, window_start)
+                .await?;
+            worst = worst.max(failures);
+            let threshold = self.policy.threshold(scope);
+            if threshold == 0 || failures < threshold {
+                continue;
+            }
+            let lockout = Lockout {
+                scope: scope.to_string(),
+                key: key.to_string(),
+                failures,
+                locked_at: now,
+                locked_until: now + self.policy.lockout.as_secs_f64(),
+            };
+            self.store.lock(&lockout).await?;
+            tracing::warn!(scope, key, failures, "login lockout");
+            crate::audit::record(
+                self.audit.as_ref(),
+                "system",
+                "auth.lockout",
+                Some(&format!("{scope}:{key}")),
+                json!({
+                    "scope": scope,
+                    "key": key,
+                    "failures": failures,
+                    "locked_until": lockout.locked_until,
+                    "ip": ip,
+                }),
+            )
+            .await;
+            outcome.locked_for = Some(self.policy.lockout);
+        }
+        outcome.delay = self.policy.delay_for(worst);
+        Ok(outcome)
+    }
+
+    /// Reset the account's counter after a successful login.
+    pub async fn record_success(&self, account: &str) -> anyhow::Result<()> {
+        self.store.clear_failures(SCOPE_ACCOUNT, account).await
+    }
+
+    /// Operator override: drop the lock and counter on `(scope, key)`.
+    pub async fn unlock(&self, scope: &str, key: &str, actor: &str) -> anyhow::Result<bool> {
+        let unlocked = self.store.unlock(scope, key, now_secs()).await?;
+        if unlocked {
+            crate::audit::record(
+                self.audit.as_ref(),
+                actor,
+                "auth.unlock",
+                Some(&format!("{scope}:{key}")),
+                json!({"scope": scope, "key": key}),
+            )
+            .await;
+        }
+        Ok(unlocked)
+    }
+
+    pub async fn lockouts(&self) -> anyhow::Result<Vec<Lockout>> {
+        self.store.list_locks(now_secs()).await
+    }
+
+    /// The caller's IP per [`LockoutPolicy::trust_forwarded_for`].
+    pub fn client_ip(&self, headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+        if self.policy.trust_forwarded_for
+            && let Some(hop) = headers
+                .get("x-forwarded-for")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit(',').next())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        {
+            return Some(hop.to_string());
+        }
+        extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    }
+}
+
+/// Counter key for a login email: trimmed and lowercased, so case
+/// variants share one budget.
+pub fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn scoped<'a>(account: Option<&'a str>, ip: Option<&'a str>) -> Vec<(&'static str, &'a str)> {
+    account
+        .map(|key| (SCOPE_ACCOUNT, key))
+        .into_iter()
+        .chain(ip.map(|key| (SCOPE_IP, key)))
+        .collect()
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+/// One guarded request. Handlers open it before checking credentials
+/// and close it with [`Self::failed`] or [`Self::succeeded`]; without a
+/// [`LoginThrottle`] on the context every step is a no-op.
+pub(crate) struct LoginAttempt<'a> {
+    throttle: Option<&'a LoginThrottle>,
+    account: Option<String>,
+    ip: Option<String>,
+}
+
+impl<'a> LoginAttempt<'a> {
+    /// Refuse up front when the account or IP is locked.
+    pub(crate) async fn begin(
+        ctx: &'a AuthCtx,
+        headers: &HeaderMap,
+        extensions: &Extensions,
+        account: Option<&str>,
+    ) -> Result<Self, Box<Response>> {
+        let Some(throttle) = ctx.lockout.as_ref() else {
+            return Ok(Self {
+                throttle: None,
+                account: None,
+                ip: None,
+            });
+        };
+        let attempt = Self {
+            throttle: Some(throttle),
+            account: account.map(account_key),
+            ip: throttle.client_ip(headers, extensions),
+        };
+        refuse_if_locked(throttle, attempt.account.as_deref(), attempt.ip.as_deref()).await?;
+        Ok(attempt)
+    }
+
+    /// Hold the attempt against `email`'s account too, once the handler
+    /// learns it — `/login/mfa` reads it from the challenge. Refuses if
+    /// that account is locked.
+    pub(crate) async fn with_account(mut self, email: Option<&str>) -> Result<Self, Box<Response>> {
+        let (Some(throttle), Some(email)) = (self.throttle, email) else {
+            return Ok(self);
+        };
+        let account = account_key(email);
+        refuse_if_locked(throttle, Some(&account), None).await?;
+        self.account = Some(account);
+        Ok(self)
+    }
+
+    /// Count the failure, then answer with `response` after the
+    /// progressive delay — or with `429` if this failure locked.
+    pub(crate) async fn failed(self, response: Response) -> Response {
+        let Some(throttle) = self.throttle else {
+            return response;
+        };
+        match throttle
+            .record_failure(self.account.as_deref(), self.ip.as_deref())
+            .await
+        {
+            Ok(FailureOutcome {
+                locked_for: Some(lockout),
+                ..
+            }) => too_many_attempts(lockout),
+            Ok(FailureOutcome { delay, .. }) => {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                response
+            }
+            Err(error) => {
+                tracing::error!(%error, "recording login failure failed");
+                response
+            }
+        }
+    }
+
+    pub(crate) async fn succeeded(self) {
+        let (Some(throttle), Some(account)) = (self.throttle, self.account.as_deref()) else {
+            return;
+        };
+        if let Err(error) = throttle.record_success(account).await {
+            tracing::error!(%error, "clearing login failures failed");
+        }
+    }
+}
+
+async fn refuse_if_locked(
+    throttle: &LoginThrottle,
+    account: Option<&str>,
+    ip: Option<&str>,
+) -> Result<(), Box<Response>> {
+    match throttle.check(account, ip).await {
+        Ok(None) => Ok(()),
+        Ok(Some(retry_after)) => Err(Box::new(too_many_attempts(retry_after))),
+        Err(error) => {
+            tracing::error!(%error, "lockout check failed");
+            Err(Box::new(
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "lockout check failed"})),
+                )
+                    .into_response(),
+            ))
+        }
+    }
+}
+
+fn too_many_attempts(retry_after: Duration) -> Response {
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({"error": "too_many_attempts", "retry_after": secs})),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, secs.into());
+    response
+}
+
+type LockoutRow = (String, String, i64, f64, f64);
+
+fn lockout_from_row((scope, key, failures, locked_at, locked_until): LockoutRow) -> Lockout {
+    Lockout {
+        scope,
+        key,
+        failures,
+        locked_at,
+        locked_until,
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+#[derive(Clone)]
+pub struct PostgresLockoutStore {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "backend-postgres")]
+impl PostgresLockoutStore {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+#[async_trait::async_trait]
+impl LockoutStore for PostgresLockoutStore {
+    async fn record_failure(
+        &self,
+        scope: &str,
+        key: &str,
+        now: f64,
+        window_start: f64,
+    ) -> anyhow::Result<i64> {
+        use anyhow::Context;
+        let mut tx = self.pool.begin().await.context("begin login failure")?;
+        sqlx::query("DELETE FROM auth.login_failures WHERE failed_at < $1")
+            .bind(window_start)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures prune")?;
+        sqlx::query("DELETE FROM auth.lockouts WHERE locked_until <= $1")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("auth.lockouts prune")?;
+        sqlx::query("INSERT INTO auth.login_failures (scope, key, failed_at) VALUES ($1, $2, $3)")
+            .bind(scope)
+            .bind(key)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures insert")?;
+        let (failures,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM auth.login_failures
+             WHERE scope = $1 AND key = $2 AND failed_at >= $3",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(window_start)
+        .fetch_one(&mut *tx)
+        .await
+        .context("auth.login_failures count")?;
+        tx.commit().await.context("commit login failure")?;
+        Ok(failures)
+    }
+
+    async fn clear_failures(&self, scope: &str, key: &str) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("DELETE FROM auth.login_failures WHERE scope = $1 AND key = $2")
+            .bind(scope)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .context("auth.login_failures clear")?;
+        Ok(())
+    }
+
+    async fn lock(&self, lockout: &Lockout) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let mut tx = self.pool.begin().await.context("begin lockout")?;
+        sqlx::query(
+            "INSERT INTO auth.lockouts (scope, key, failures, locked_at, locked_until)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (scope, key) DO UPDATE SET
+                 failures = EXCLUDED.failures,
+                 locked_at = EXCLUDED.locked_at,
+                 locked_until = EXCLUDED.locked_until",
+        )
+        .bind(&lockout.scope)
+        .bind(&lockout.key)
+        .bind(lockout.failures)
+        .bind(lockout.locked_at)
+        .bind(lockout.locked_until)
+        .execute(&mut *tx)
+        .await
+        .context("auth.lockouts upsert")?;
+        sqlx::query("DELETE FROM auth.login_failures WHERE scope = $1 AND key = $2")
+            .bind(&lockout.scope)
+            .bind(&lockout.key)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures reset")?;
+        tx.commit().await.context("commit lockout")?;
+        Ok(())
+    }
+
+    async fn locked_until(&self, scope: &str, key: &str, now: f64) -> anyhow::Result<Option<f64>> {
+        use anyhow::Context;
+        let row: Option<(f64,)> = sqlx::query_as(
+            "SELECT locked_until FROM auth.lockouts
+             WHERE scope = $1 AND key = $2 AND locked_until > $3",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.lockouts get")?;
+        Ok(row.map(|(until,)| until))
+    }
+
+    async fn unlock(&self, scope: &str, key: &str, now: f64) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let mut tx = self.pool.begin().await.context("begin unlock")?;
+        let result = sqlx::query(
+            "DELETE FROM auth.lockouts WHERE scope = $1 AND key = $2 AND locked_until > $3",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("auth.lockouts delete")?;
+        sqlx::query("DELETE FROM auth.login_failures WHERE scope = $1 AND key = $2")
+            .bind(scope)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures clear")?;
+        tx.commit().await.context("commit unlock")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_locks(&self, now: f64) -> anyhow::Result<Vec<Lockout>> {
+        use anyhow::Context;
+        let rows: Vec<LockoutRow> = sqlx::query_as(
+            "SELECT scope, key, failures, locked_at, locked_until FROM auth.lockouts
+             WHERE locked_until > $1 ORDER BY locked_until, scope, key",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.lockouts list")?;
+        Ok(rows.into_iter().map(lockout_from_row).collect())
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Clone)]
+pub struct SqliteLockoutStore {
+    pool: sqlx::SqlitePool,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl SqliteLockoutStore {
+    pub fn new(pool: sqlx::SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[async_trait::async_trait]
+impl LockoutStore for SqliteLockoutStore {
+    async fn record_failure(
+        &self,
+        scope: &str,
+        key: &str,
+        now: f64,
+        window_start: f64,
+    ) -> anyhow::Result<i64> {
+        use anyhow::Context;
+        let mut tx = self.pool.begin().await.context("begin login failure")?;
+        sqlx::query("DELETE FROM auth.login_failures WHERE failed_at < ?")
+            .bind(window_start)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures prune")?;
+        sqlx::query("DELETE FROM auth.lockouts WHERE locked_until <= ?")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("auth.lockouts prune")?;
+        sqlx::query("INSERT INTO auth.login_failures (scope, key, failed_at) VALUES (?, ?, ?)")
+            .bind(scope)
+            .bind(key)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures insert")?;
+        let (failures,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM auth.login_failures
+             WHERE scope = ? AND key = ? AND failed_at >= ?",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(window_start)
+        .fetch_one(&mut *tx)
+        .await
+        .context("auth.login_failures count")?;
+        tx.commit().await.context("commit login failure")?;
+        Ok(failures)
+    }
+
+    async fn clear_failures(&self, scope: &str, key: &str) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("DELETE FROM auth.login_failures WHERE scope = ? AND key = ?")
+            .bind(scope)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .context("auth.login_failures clear")?;
+        Ok(())
+    }
+
+    async fn lock(&self, lockout: &Lockout) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let mut tx = self.pool.begin().await.context("begin lockout")?;
+        sqlx::query(
+            "INSERT INTO auth.lockouts (scope, key, failures, locked_at, locked_until)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (scope, key) DO UPDATE SET
+                 failures = excluded.failures,
+                 locked_at = excluded.locked_at,
+                 locked_until = excluded.locked_until",
+        )
+        .bind(&lockout.scope)
+        .bind(&lockout.key)
+        .bind(lockout.failures)
+        .bind(lockout.locked_at)
+        .bind(lockout.locked_until)
+        .execute(&mut *tx)
+        .await
+        .context("auth.lockouts upsert")?;
+        sqlx::query("DELETE FROM auth.login_failures WHERE scope = ? AND key = ?")
+            .bind(&lockout.scope)
+            .bind(&lockout.key)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures reset")?;
+        tx.commit().await.context("commit lockout")?;
+        Ok(())
+    }
+
+    async fn locked_until(&self, scope: &str, key: &str, now: f64) -> anyhow::Result<Option<f64>> {
+        use anyhow::Context;
+        let row: Option<(f64,)> = sqlx::query_as(
+            "SELECT locked_until FROM auth.lockouts
+             WHERE scope = ? AND key = ? AND locked_until > ?",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.lockouts get")?;
+        Ok(row.map(|(until,)| until))
+    }
+
+    async fn unlock(&self, scope: &str, key: &str, now: f64) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let mut tx = self.pool.begin().await.context("begin unlock")?;
+        let result = sqlx::query(
+            "DELETE FROM auth.lockouts WHERE scope = ? AND key = ? AND locked_until > ?",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("auth.lockouts delete")?;
+        sqlx::query("DELETE FROM auth.login_failures WHERE scope = ? AND key = ?")
+            .bind(scope)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .context("auth.login_failures clear")?;
+        tx.commit().await.context("commit unlock")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_locks(&self, now: f64) -> anyhow::Result<Vec<Lockout>> {
+        use anyhow::Context;
+        let rows: Vec<LockoutRow> = sqlx::query_as(
+            "SELECT scope, key, failures, locked_at, locked_until FROM auth.lockouts
+             WHERE locked_until > ? ORDER BY locked_until, scope, key",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.lockouts list")?;
+        Ok(rows.into_iter().map(lockout_from_row).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_doubles_past_the_free_attempts_and_caps() {
+        let policy = LockoutPolicy::default();
+        assert_eq!(policy.delay_for(0), Duration::ZERO);
+        assert_eq!(policy.delay_for(3), Duration::ZERO);
+        assert_eq!(policy.delay_for(4), Duration::from_millis(250));
+        assert_eq!(policy.delay_for(5), Duration::from_millis(500));
+        assert_eq!(policy.delay_for(7), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(8), Duration::from_secs(4));
+        assert_eq!(policy.delay_for(1_000), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn account_keys_ignore_case_and_padding() {
+        assert_eq!(account_key("  Alice@Example.COM "), "alice@example.com");
+    }
+
+    #[test]
+    fn client_ip_prefers_the_peer_unless_forwarded_for_is_trusted() {
+        struct NoStore;
+        #[async_trait::async_trait]
+        impl LockoutStore for NoStore {
+            async fn record_failure(
+                &self,
+                _: &str,
+                _: &str,
+                _: f64,
+                _: f64,
+            ) -> anyhow::Result<i64> {
+                unreachable!()
+            }
+            async fn clear_failures(&self, _: &str, _: &str) -> anyhow::Result<()> {
+                unreachable!()
+            }
+            async fn lock(&self, _: &Lockout) -> anyhow::Result<()> {
+                unreachable!()
+            }
+            async fn locked_until(&self, _: &str, _: &str, _: f64) -> anyhow::Result<Option<f64>> {
+                unreachable!()
+            }
+            async fn unlock(&self, _: &str, _: &str, _: f64) -> anyhow::Result<bool> {
+                unreachable!()
+            }
+            async fn list_locks(&self, _: f64) -> anyhow::Result<Vec<Lockout>> {
+                unreachable!()
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            "198.51.100.9, 203.0.113.7".parse().unwrap(),
+        );
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+
+        let direct = LoginThrottle::new(Arc::new(NoStore), LockoutPolicy::default());
+        assert_eq!(
+            direct.client_ip(&headers, &extensions).as_deref(),
+            Some("10.0.0.1")
+        );
+        let proxied = LoginThrottle::new(
+            Arc::new(NoStore),
+            LockoutPolicy {
+                trust_forwarded_for: true,
+                ..LockoutPolicy::default()
+            },
+        );
+        assert_eq!(
+            proxied.client_ip(&headers, &extensions).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(
+            proxied
+                .client_ip(&HeaderMap::new(), &Extensions::new())
+                .as_deref(),
+            None
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Counters the auth handlers bump, rendered by the engine's
+//! `/metrics` next to every other module's instruments.
+
+use assay_domain::metrics::CounterVec;
+use axum::http::StatusCode;
+
+pub struct AuthMetrics {
+    /// Interactive login attempts by `method` (`password`, `mfa`,
+    /// `passkey`) and `outcome` (`success`, `failure`, `locked`).
+    pub logins: CounterVec,
+    /// Successful `/token` responses by grant type.
+    pub tokens_issued: CounterVec,
+}
+
+impl Default for AuthMetrics {
+    fn default() -> Self {
+        Self {
+            logins: CounterVec::new(&["method", "outcome"]),
+            tokens_issued: CounterVec::new(&["grant_type"]),
+        }
+    }
+}
+
+impl AuthMetrics {
+    /// Count a login from the status its handler answered with: 2xx
+    /// is a success, 401 a failure, 429 a lockout. Malformed requests
+    /// and server errors aren't attempts and aren't counted.
+    pub fn record_login(&self, method: &str, status: StatusCode) {
+        let outcome = match status {
+            s if s.is_success() => "success",
+            StatusCode::UNAUTHORIZED => "failure",
+            StatusCode::TOO_MANY_REQUESTS => "locked",
+            _ => return,
+        };
+        self.logins.inc(&[method, outcome]);
+    }
+}
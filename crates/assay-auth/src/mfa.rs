@@ -0,0 +1,1192 @@
+//! TOTP second factor + one-time recovery codes for password logins.
+//!
+//! A user enrolls by asking for a fresh secret (`otpauth://` URI the
+//! client renders as a QR code), then proves their authenticator works
+//! by confirming one code. Confirmation also mints
+//! [`RECOVERY_CODE_COUNT`] single-use recovery codes, returned once and
+//! stored only as SHA-256 hashes.
+//!
+//! Once enrolled, `POST /login` answers a correct password with an
+//! `mfa_token` instead of a session (see [`crate::session`]); the
+//! client finishes at `/login/mfa` with a TOTP or recovery code and the
+//! resulting session carries `amr = ["pwd", "otp", "mfa"]`, which the
+//! OIDC provider copies into its ID tokens.
+//!
+//! TOTP follows RFC 6238 with the authenticator-app defaults (SHA-1,
+//! 6 digits, 30 s period) and accepts one step of clock skew. An
+//! accepted step is recorded so the same code can't be replayed.
+//!
+//! Self-service endpoints (session cookie + `X-CSRF-Token` header on
+//! writes), mounted under `/api/v1/engine/auth`:
+//!
+//! - `GET  /mfa`                 → enrollment status
+//! - `POST /mfa/totp/enroll`     → new secret + provisioning URI
+//! - `POST /mfa/totp/confirm`    → activate; returns recovery codes
+//! - `POST /mfa/recovery-codes`  → regenerate (needs a current TOTP code)
+//!
+//! Operators clear a user's second factor with
+//! `DELETE /admin/users/{id}/mfa` (see [`crate::admin`]).
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore};
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::AuthCtx;
+
+/// TOTP time step in seconds.
+pub const TOTP_PERIOD_SECS: u64 = 30;
+/// Digits in a TOTP code.
+pub const TOTP_DIGITS: u32 = 6;
+/// Steps either side of "now" still accepted, for clock drift.
+const TOTP_SKEW_STEPS: i64 = 1;
+/// Raw secret length — 160 bits, the RFC 4226 recommendation.
+const SECRET_BYTES: usize = 20;
+/// Recovery codes minted per confirmation / regeneration.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+/// Recovery-code alphabet — lowercase without look-alikes (0/o, 1/l/i).
+const RECOVERY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+/// Lifetime of the password-verified, code-pending login challenge.
+const CHALLENGE_TTL_SECS: f64 = 300.0;
+/// Wrong codes tolerated per challenge before it's dead.
+pub const MAX_CHALLENGE_ATTEMPTS: i64 = 5;
+
+/// One user's TOTP enrollment — row in `auth.mfa_totp`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TotpEnrollment {
+    pub user_id: String,
+    /// Base32 (no padding) shared secret.
+    pub secret: String,
+    /// `None` until the user has confirmed a code.
+    pub confirmed_at: Option<f64>,
+    /// Last accepted time step; codes at or before it are replays.
+    pub last_used_step: Option<i64>,
+    pub created_at: f64,
+}
+
+#[async_trait::async_trait]
+pub trait MfaStore: Send + Sync + 'static {
+    async fn totp(&self, user_id: &str) -> anyhow::Result<Option<TotpEnrollment>>;
+
+    /// Stage an unconfirmed secret, replacing any earlier unconfirmed
+    /// one. Returns `false` when the user already has a confirmed
+    /// enrollment.
+    async fn stage_totp(&self, user_id: &str, secret: &str, now: f64) -> anyhow::Result<bool>;
+
+    /// Confirm the staged secret at `step` and replace the user's
+    /// recovery codes with `recovery_hashes`, atomically. Returns
+    /// `false` when there is nothing staged.
+    async fn confirm_totp(
+        &self,
+        user_id: &str,
+        step: i64,
+        recovery_hashes: &[String],
+        now: f64,
+    ) -> anyhow::Result<bool>;
+
+    /// Record `step` as used iff it's newer than the last accepted one.
+    /// Returns `false` for a replay (or a lost race with one).
+    async fn advance_totp_step(&self, user_id: &str, step: i64) -> anyhow::Result<bool>;
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: &str,
+        recovery_hashes: &[String],
+        now: f64,
+    ) -> anyhow::Result<()>;
+
+    /// Mark an unused recovery code used. Returns `false` when the hash
+    /// is unknown or already spent.
+    async fn redeem_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        now: f64,
+    ) -> anyhow::Result<bool>;
+
+    async fn remaining_recovery_codes(&self, user_id: &str) -> anyhow::Result<i64>;
+
+    /// Drop the enrollment, recovery codes and pending challenges.
+    /// Returns `true` when there was an enrollment to drop.
+    async fn reset(&self, user_id: &str) -> anyhow::Result<bool>;
+
+    async fn create_challenge(
+        &self,
+        token_hash: &str,
+        user_id: &str,
+        created_at: f64,
+        expires_at: f64,
+    ) -> anyhow::Result<()>;
+
+    /// Count one attempt against a live challenge and return its user.
+    /// `None` once the challenge is unknown, expired, or out of
+    /// attempts.
+    async fn attempt_challenge(
+        &self,
+        token_hash: &str,
+        now: f64,
+        max_attempts: i64,
+    ) -> anyhow::Result<Option<String>>;
+
+    async fn delete_challenge(&self, token_hash: &str) -> anyhow::Result<()>;
+}
+
+/// What the self-service status endpoint reports.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MfaStatus {
+    pub totp_enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+/// A freshly staged secret, returned to the user exactly once.
+#[derive(Clone, Debug, Serialize)]
+pub struct TotpProvisioning {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+/// Enrollment, login-challenge and reset operations over an
+/// [`MfaStore`]. Cheap to clone.
+#[derive(Clone)]
+pub struct MfaManager {
+    store: Arc<dyn MfaStore>,
+    issuer: String,
+}
+
+impl MfaManager {
+    /// `issuer` is the label authenticator apps show next to the
+    /// account (e.g. `"Assay"`).
+    pub fn new(store: Arc<dyn MfaStore>, issuer: impl Into<String>) -> Self {
+        Self {
+            store,
+            issuer: issuer.into(),
+        }
+    }
+
+    pub async fn status(&self, user_id: &str) -> anyhow::Result<MfaStatus> {
+        let totp_enabled = self.is_enrolled(user_id).await?;
+        let recovery_codes_remaining = if totp_enabled {
+            self.store.remaining_recovery_codes(user_id).await?
+        } else {
+            0
+        };
+        Ok(MfaStatus {
+            totp_enabled,
+            recovery_codes_remaining,
+        })
+    }
+
+    /// `true` once the user has a confirmed TOTP enrollment.
+    pub async fn is_enrolled(&self, user_id: &str) -> anyhow::Result<bool> {
+        Ok(self
+            .store
+            .totp(user_id)
+            .await?
+            .is_some_and(|t| t.confirmed_at.is_some()))
+    }
+
+    /// Stage a new secret for `user_id`. `account` labels the entry in
+    /// the authenticator (usually the email). `None` when the user is
+    /// already enrolled — an operator reset comes first.
+    pub async fn begin_enrollment(
+        &self,
+        user_id: &str,
+        account: &str,
+    ) -> anyhow::Result<Option<TotpProvisioning>> {
+        let secret = generate_secret();
+        if !self.store.stage_totp(user_id, &secret, now_secs()).await? {
+            return Ok(None);
+        }
+        let provisioning_uri = provisioning_uri(&self.issuer, account, &secret);
+        Ok(Some(TotpProvisioning {
+            secret,
+            provisioning_uri,
+        }))
+    }
+
+    /// Activate the staged secret with a code from the authenticator.
+    /// Returns the plaintext recovery codes, or `None` for a wrong code
+    /// or nothing staged.
+    pub async fn confirm_enrollment(
+        &self,
+        user_id: &str,
+        code: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        let Some(staged) = self.store.totp(user_id).await? else {
+            return Ok(None);
+        };
+        if staged.confirmed_at.is_some() {
+            return Ok(None);
+        }
+        let Some(step) = matching_step(&staged.secret, code, now_secs(), None) else {
+            return Ok(None);
+        };
+        let codes = generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|c| recovery_code_hash(c)).collect();
+        if !self
+            .store
+            .confirm_totp(user_id, step, &hashes, now_secs())
+            .await?
+        {
+            return Ok(None);
+        }
+        Ok(Some(codes))
+    }
+
+    /// Swap the user's recovery codes for a fresh set. Needs a current
+    /// TOTP code so a hijacked session alone can't mint them.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: &str,
+        code: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        if !self.verify_totp(user_id, code).await? {
+            return Ok(None);
+        }
+        let codes = generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|c| recovery_code_hash(c)).collect();
+        self.store
+            .replace_recovery_codes(user_id, &hashes, now_secs())
+            .await?;
+        Ok(Some(codes))
+    }
+
+    /// Operator reset — the user signs in with the password alone until
+    /// they enroll again.
+    pub async fn reset(&self, user_id: &str) -> anyhow::Result<bool> {
+        self.store.reset(user_id).await
+    }
+
+    /// Open a login challenge for a user whose password just verified.
+    /// The returned token is what `/login/mfa` expects back.
+    pub async fn start_challenge(&self, user_id: &str) -> anyhow::Result<String> {
+        let token = format!("mfa_{}", random_token());
+        let now = now_secs();
+        self.store
+            .create_challenge(&token_hash(&token), user_id, now, now + CHALLENGE_TTL_SECS)
+            .await?;
+        Ok(token)
+    }
+
+    /// Redeem a login challenge with a TOTP or recovery code. Returns
+    /// the user id on success; the challenge is single-use and dies
+    /// after [`MAX_CHALLENGE_ATTEMPTS`] wrong codes.
+    pub async fn complete_challenge(
+        &self,
+        token: &str,
+        code: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(user_id) = self.attempt_challenge(token).await? else {
+            return Ok(None);
+        };
+        Ok(self
+            .redeem_challenge(token, &user_id, code)
+            .await?
+            .then_some(user_id))
+    }
+
+    /// First half of [`Self::complete_challenge`]: count one attempt
+    /// and return the challenge's user before any code is checked, so
+    /// the caller can hold the attempt against that account. `None`
+    /// once the challenge is unknown, expired or out of attempts.
+    pub async fn attempt_challenge(&self, token: &str) -> anyhow::Result<Option<String>> {
+        self.store
+            .attempt_challenge(&token_hash(token), now_secs(), MAX_CHALLENGE_ATTEMPTS)
+            .await
+    }
+
+    /// Second half: check `code` for `user_id`, consuming the challenge
+    /// when it matches.
+    pub async fn redeem_challenge(
+        &self,
+        token: &str,
+        user_id: &str,
+        code: &str,
+    ) -> anyhow::Result<bool> {
+        let ok = self.verify_totp(user_id, code).await?
+            || self
+                .store
+                .redeem_recovery_code(user_id, &recovery_code_hash(code), now_secs())
+                .await?;
+        if ok {
+            self.store.delete_challenge(&token_hash(token)).await?;
+        }
+        Ok(ok)
+    }
+
+    async fn verify_totp(&self, user_id: &str, code: &str) -> anyhow::Result<bool> {
+        let Some(enrollment) = self.store.totp(user_id).await? else {
+            return Ok(false);
+        };
+        if enrollment.confirmed_at.is_none() {
+            return Ok(false);
+        }
+        let Some(step) = matching_step(
+            &enrollment.secret,
+            code,
+            now_secs(),
+            enrollment.last_used_step,
+        ) else {
+            return Ok(false);
+        };
+        self.store.advance_totp_step(user_id, step).await
+    }
+}
+
+/// Fresh base32 (no padding) TOTP secret.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    rand::rng().fill_bytes(&mut bytes);
+    data_encoding::BASE32_NOPAD.encode(&bytes)
+}
+
+/// `otpauth://totp/…` URI authenticator apps import (usually via QR).
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={secret}&issuer={}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD_SECS}",
+        uri_component(issuer),
+        uri_component(account),
+        uri_component(issuer),
+    )
+}
+
+/// RFC 6238 code for `step` (HOTP over the step counter).
+pub fn totp_at(secret: &[u8], step: i64) -> u32 {
+    let mut mac =
+        <Hmac<Sha1> as Mac>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(TOTP_DIGITS)
+}
+
+/// The time step `code` is valid for around `now`, newest first, or
+/// `None`. Steps at or before `last_used_step` never match.
+fn matching_step(secret: &str, code: &str, now: f64, last_used_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let key = data_encoding::BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let current = (now as i64).div_euclid(TOTP_PERIOD_SECS as i64);
+    (-TOTP_SKEW_STEPS..=TOTP_SKEW_STEPS)
+        .rev()
+        .map(|delta| current + delta)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| {
+            let expected = format!(
+                "{:0width$}",
+                totp_at(&key, *step),
+                width = TOTP_DIGITS as usize
+            );
+            bool::from(expected.as_bytes().ct_eq(code.as_bytes()))
+        })
+}
+
+/// Fresh recovery codes, formatted `xxxxx-xxxxx`.
+fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| RECOVERY_ALPHABET[rng.random_range(0..RECOVERY_ALPHABET.len())] as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// Hash of a recovery code as typed — case, dashes and spaces ignored.
+fn recovery_code_hash(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    token_hash(&normalized)
+}
+
+fn token_hash(token: &str) -> String {
+    data_encoding::HEXLOWER.encode(&Sha256::digest(token.as_bytes()))
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    data_encoding::BASE64URL_NOPAD.encode(&bytes)
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+fn uri_component(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+use axum::Router;
+use axum::extract::{FromRef, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::store::Session;
+
+/// Header carrying the session's CSRF token on state-changing calls.
+const CSRF_HEADER: &str = "x-csrf-token";
+
+pub fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    AuthCtx: FromRef<S>,
+{
+    Router::new()
+        .route("/mfa", get(status_get))
+        .route("/mfa/totp/enroll", post(enroll_post))
+        .route("/mfa/totp/confirm", post(confirm_post))
+        .route("/mfa/recovery-codes", post(recovery_codes_post))
+}
+
+#[derive(Deserialize)]
+struct CodeBody {
+    code: String,
+}
+
+async fn status_get(State(ctx): State<AuthCtx>, headers: HeaderMap) -> Response {
+    let (mfa, session) = match authenticated(&ctx, &headers, false).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    match mfa.status(&session.user_id).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(error) => server_error(error),
+    }
+}
+
+async fn enroll_post(State(ctx): State<AuthCtx>, headers: HeaderMap) -> Response {
+    let (mfa, session) = match authenticated(&ctx, &headers, true).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let account = match ctx.users.get_user_by_id(&session.user_id).await {
+        Ok(Some(user)) => user.email.unwrap_or(user.id),
+        Ok(None) => return unauthorized(),
+        Err(error) => return server_error(error),
+    };
+    match mfa.begin_enrollment(&session.user_id, &account).await {
+        Ok(Some(provisioning)) => (StatusCode::OK, Json(provisioning)).into_response(),
+        Ok(None) => (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "totp_already_enabled"})),
+        )
+            .into_response(),
+        Err(error) => server_error(error),
+    }
+}
+
+async fn confirm_post(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Json(body): Json<CodeBody>,
+) -> Response {
+    let (mfa, session) = match authenticated(&ctx, &headers, true).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    match mfa.confirm_enrollment(&session.user_id, &body.code).await {
+        Ok(Some(codes)) => recovery_codes(codes),
+        Ok(None) => invalid_code(),
+        Err(error) => server_error(error),
+    }
+}
+
+async fn recovery_codes_post(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Json(body): Json<CodeBody>,
+) -> Response {
+    let (mfa, session) = match authenticated(&ctx, &headers, true).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    match mfa
+        .regenerate_recovery_codes(&session.user_id, &body.code)
+        .await
+    {
+        Ok(Some(codes)) => recovery_codes(codes),
+        Ok(None) => invalid_code(),
+        Err(error) => server_error(error),
+    }
+}
+
+/// Resolve the MFA manager plus the caller's live session; writes also
+/// need the session's CSRF token echoed in [`CSRF_HEADER`].
+async fn authenticated<'a>(
+    ctx: &'a AuthCtx,
+    headers: &HeaderMap,
+    require_csrf: bool,
+) -> Result<(&'a MfaManager, Session), Response> {
+    let Some(mfa) = ctx.mfa.as_ref() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": "mfa_unavailable"})),
+        )
+            .into_response());
+    };
+    let Some(sid) = crate::session::parse_cookie(headers, crate::session::SESSION_COOKIE) else {
+        return Err(unauthorized());
+    };
+    let session = match crate::session::SessionManager::with_default_duration(ctx.sessions.clone())
+        .resolve(&sid)
+        .await
+    {
+        Ok(Some(session)) => session,
+        Ok(None) => return Err(unauthorized()),
+        Err(error) => return Err(server_error(error)),
+    };
+    if require_csrf {
+        let presented = headers
+            .get(CSRF_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        if !bool::from(presented.as_bytes().ct_eq(session.csrf_token.as_bytes())) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "csrf_mismatch"})),
+            )
+                .into_response());
+        }
+    }
+    Ok((mfa, session))
+}
+
+fn recovery_codes(codes: Vec<String>) -> Response {
+    (StatusCode::OK, Json(json!({"recovery_codes": codes}))).into_response()
+}
+
+fn invalid_code() -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "invalid_code"})),
+    )
+        .into_response()
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({"error": "no_session"})),
+    )
+        .into_response()
+}
+
+fn server_error(error: impl std::fmt::Display) -> Response {
+    tracing::error!(%error, "mfa request failed");
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
+
+#[cfg(feature = "backend-postgres")]
+#[derive(Clone)]
+pub struct PostgresMfaStore {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "backend-postgres")]
+impl PostgresMfaStore {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+#[async_trait::async_trait]
+impl MfaStore for PostgresMfaStore {
+    async fn totp(&self, user_id: &str) -> anyhow::Result<Option<TotpEnrollment>> {
+        use anyhow::Context;
+        let row: Option<(String, String, Option<f64>, Option<i64>, f64)> = sqlx::query_as(
+            "SELECT user_id, secret, confirmed_at, last_used_step, created_at
+             FROM auth.mfa_totp WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("load totp enrollment")?;
+        Ok(row.map(
+            |(user_id, secret, confirmed_at, last_used_step, created_at)| TotpEnrollment {
+                user_id,
+                secret,
+                confirmed_at,
+                last_used_step,
+                created_at,
+            },
+        ))
+    }
+
+    async fn stage_totp(&self, user_id: &str, secret: &str, now: f64) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query(
+            "INSERT INTO auth.mfa_totp (user_id, secret, created_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 secret = EXCLUDED.secret,
+                 last_used_step = NULL,
+                 created_at = EXCLUDED.created_at
+             WHERE mfa_totp.confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(secret)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("stage totp secret")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn confirm_totp(
+        &self,
+        user_id: &str,
+        step: i64,
+        recovery_hashes: &[String],
+        now: f64,
+    ) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let mut transaction = self.pool.begin().await.context("begin totp confirm")?;
+        let result = sqlx::query(
+            "UPDATE auth.mfa_totp SET confirmed_at = $2, last_used_step = $3
+             WHERE user_id = $1 AND confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(now)
+        .bind(step)
+        .execute(&mut *transaction)
+        .await
+        .context("confirm totp")?;
+        if result.rows_affected() == 0 {
+            transaction
+                .rollback()
+                .await
+                .context("rollback totp confirm")?;
+            return Ok(false);
+        }
+        replace_recovery_codes_pg(&mut transaction, user_id, recovery_hashes, now).await?;
+        transaction.commit().await.context("commit totp confirm")?;
+        Ok(true)
+    }
+
+    async fn advance_totp_step(&self, user_id: &str, step: i64) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query(
+            "UPDATE auth.mfa_totp SET last_used_step = $2
+             WHERE user_id = $1 AND confirmed_at IS NOT NULL
+               AND (last_used_step IS NULL OR last_used_step < $2)",
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await
+        .context("advance totp step")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: &str,
+        recovery_hashes: &[String],
+        now: f64,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .context("begin recovery code replace")?;
+        replace_recovery_codes_pg(&mut transaction, user_id, recovery_hashes, now).await?;
+        transaction
+            .commit()
+            .await
+            .context("commit recovery code replace")?;
+        Ok(())
+    }
+
+    async fn redeem_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        now: f64,
+    ) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query(
+            "UPDATE auth.mfa_recovery_codes SET used_at = $3
+             WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("redeem recovery code")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn remaining_recovery_codes(&self, user_id: &str) -> anyhow::Result<i64> {
+        use anyhow::Context;
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM auth.mfa_recovery_codes
+             WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("count recovery codes")
+    }
+
+    async fn reset(&self, user_id: &str) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let mut transaction = self.pool.begin().await.context("begin mfa reset")?;
+        let result = sqlx::query("DELETE FROM auth.mfa_totp WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("delete totp enrollment")?;
+        sqlx::query("DELETE FROM auth.mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("delete recovery codes")?;
+        sqlx::query("DELETE FROM auth.mfa_challenges WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("delete mfa challenges")?;
+        transaction.commit().await.context("commit mfa reset")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn create_challenge(
+        &self,
+        token_hash: &str,
+        user_id: &str,
+        created_at: f64,
+        expires_at: f64,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("DELETE FROM auth.mfa_challenges WHERE expires_at <= $1")
+            .bind(created_at)
+            .execute(&self.pool)
+            .await
+            .context("purge expired mfa challenges")?;
+        sqlx::query(
+            "INSERT INTO auth.mfa_challenges (token_hash, user_id, created_at, expires_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(created_at)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("create mfa challenge")?;
+        Ok(())
+    }
+
+    async fn attempt_challenge(
+        &self,
+        token_hash: &str,
+        now: f64,
+        max_attempts: i64,
+    ) -> anyhow::Result<Option<String>> {
+        use anyhow::Context;
+        sqlx::query_scalar(
+            "UPDATE auth.mfa_challenges SET attempts = attempts + 1
+             WHERE token_hash = $1 AND expires_at > $2 AND attempts < $3
+             RETURNING user_id",
+        )
+        .bind(token_hash)
+        .bind(now)
+        .bind(max_attempts as i32)
+        .fetch_optional(&self.pool)
+        .await
+        .context("attempt mfa challenge")
+    }
+
+    async fn delete_challenge(&self, token_hash: &str) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("DELETE FROM auth.mfa_challenges WHERE token_hash = $1")
+            .bind(token_hash)
+            .execute(&self.pool)
+            .await
+            .context("delete mfa challenge")?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+async fn replace_recovery_codes_pg(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: &str,
+    recovery_hashes: &[String],
+    now: f64,
+) -> anyhow::Result<()> {
+    use anyhow::Context;
+    sqlx::query("DELETE FROM auth.mfa_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut **transaction)
+        .await
+        .context("clear recovery codes")?;
+    for hash in recovery_hashes {
+        sqlx::query(
+            "INSERT INTO auth.mfa_recovery_codes (code_hash, user_id, created_at)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(hash)
+        .bind(user_id)
+        .bind(now)
+        .execute(&mut **transaction)
+        .await
+        .context("insert recovery code")?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Clone)]
+pub struct SqliteMfaStore {
+    pool: sqlx::SqlitePool,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl SqliteMfaStore {
+    pub fn new(pool: sqlx::SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[async_trait::async_trait]
+impl MfaStore for SqliteMfaStore {
+    async fn totp(&self, user_id: &str) -> anyhow::Result<Option<TotpEnrollment>> {
+        use anyhow::Context;
+        let row: Option<(String, String, Option<f64>, Option<i64>, f64)> = sqlx::query_as(
+            "SELECT user_id, secret, confirmed_at, last_used_step, created_at
+             FROM auth.mfa_totp WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("load totp enrollment")?;
+        Ok(row.map(
+            |(user_id, secret, confirmed_at, last_used_step, created_at)| TotpEnrollment {
+                user_id,
+                secret,
+                confirmed_at,
+                last_used_step,
+                created_at,
+            },
+        ))
+    }
+
+    async fn stage_totp(&self, user_id: &str, secret: &str, now: f64) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query(
+            "INSERT INTO auth.mfa_totp (user_id, secret, created_at)
+             VALUES (?, ?, ?)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 secret = excluded.secret,
+                 last_used_step = NULL,
+                 created_at = excluded.created_at
+             WHERE mfa_totp.confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(secret)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("stage totp secret")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn confirm_totp(
+        &self,
+        user_id: &str,
+        step: i64,
+        recovery_hashes: &[String],
+        now: f64,
+    ) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let mut transaction = self.pool.begin().await.context("begin totp confirm")?;
+        let result = sqlx::query(
+            "UPDATE auth.mfa_totp SET confirmed_at = ?, last_used_step = ?
+             WHERE user_id = ? AND confirmed_at IS NULL",
+        )
+        .bind(now)
+        .bind(step)
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await
+        .context("confirm totp")?;
+        if result.rows_affected() == 0 {
+            transaction
+                .rollback()
+                .await
+                .context("rollback totp confirm")?;
+            return Ok(false);
+        }
+        replace_recovery_codes_sqlite(&mut transaction, user_id, recovery_hashes, now).await?;
+        transaction.commit().await.context("commit totp confirm")?;
+        Ok(true)
+    }
+
+    async fn advance_totp_step(&self, user_id: &str, step: i64) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query(
+            "UPDATE auth.mfa_totp SET last_used_step = ?
+             WHERE user_id = ? AND confirmed_at IS NOT NULL
+               AND (last_used_step IS NULL OR last_used_step < ?)",
+        )
+        .bind(step)
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await
+        .context("advance totp step")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: &str,
+        recovery_hashes: &[String],
+        now: f64,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .context("begin recovery code replace")?;
+        replace_recovery_codes_sqlite(&mut transaction, user_id, recovery_hashes, now).await?;
+        transaction
+            .commit()
+            .await
+            .context("commit recovery code replace")?;
+        Ok(())
+    }
+
+    async fn redeem_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        now: f64,
+    ) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let result = sqlx::query(
+            "UPDATE auth.mfa_recovery_codes SET used_at = ?
+             WHERE user_id = ? AND code_hash = ? AND used_at IS NULL",
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await
+        .context("redeem recovery code")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn remaining_recovery_codes(&self, user_id: &str) -> anyhow::Result<i64> {
+        use anyhow::Context;
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM auth.mfa_recovery_codes
+             WHERE user_id = ? AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("count recovery codes")
+    }
+
+    async fn reset(&self, user_id: &str) -> anyhow::Result<bool> {
+        use anyhow::Context;
+        let mut transaction = self.pool.begin().await.context("begin mfa reset")?;
+        let result = sqlx::query("DELETE FROM auth.mfa_totp WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("delete totp enrollment")?;
+        sqlx::query("DELETE FROM auth.mfa_recovery_codes WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("delete recovery codes")?;
+        sqlx::query("DELETE FROM auth.mfa_challenges WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("delete mfa challenges")?;
+        transaction.commit().await.context("commit mfa reset")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn create_challenge(
+        &self,
+        token_hash: &str,
+        user_id: &str,
+        created_at: f64,
+        expires_at: f64,
+    ) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("DELETE FROM auth.mfa_challenges WHERE expires_at <= ?")
+            .bind(created_at)
+            .execute(&self.pool)
+            .await
+            .context("purge expired mfa challenges")?;
+        sqlx::query(
+            "INSERT INTO auth.mfa_challenges (token_hash, user_id, created_at, expires_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(created_at)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("create mfa challenge")?;
+        Ok(())
+    }
+
+    async fn attempt_challenge(
+        &self,
+        token_hash: &str,
+        now: f64,
+        max_attempts: i64,
+    ) -> anyhow::Result<Option<String>> {
+        use anyhow::Context;
+        sqlx::query_scalar(
+            "UPDATE auth.mfa_challenges SET attempts = attempts + 1
+             WHERE token_hash = ? AND expires_at > ? AND attempts < ?
+             RETURNING user_id",
+        )
+        .bind(token_hash)
+        .bind(now)
+        .bind(max_attempts)
+        .fetch_optional(&self.pool)
+        .await
+        .context("attempt mfa challenge")
+    }
+
+    async fn delete_challenge(&self, token_hash: &str) -> anyhow::Result<()> {
+        use anyhow::Context;
+        sqlx::query("DELETE FROM auth.mfa_challenges WHERE token_hash = ?")
+            .bind(token_hash)
+            .execute(&self.pool)
+            .await
+            .context("delete mfa challenge")?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+async fn replace_recovery_codes_sqlite(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    user_id: &str,
+    recovery_hashes: &[String],
+    now: f64,
+) -> anyhow::Result<()> {
+    use anyhow::Context;
+    sqlx::query("DELETE FROM auth.mfa_recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut **transaction)
+        .await
+        .context("clear recovery codes")?;
+    for hash in recovery_hashes {
+        sqlx::query(
+            "INSERT INTO auth.mfa_recovery_codes (code_hash, user_id, created_at)
+             VALUES (?, ?, ?)",
+        )
+        .bind(hash)
+        .bind(user_id)
+        .bind(now)
+        .execute(&mut **transaction)
+        .await
+        .context("insert recovery code")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 Appendix B SHA-1 vectors, truncated to six digits.
+    #[test]
+    fn totp_matches_rfc_6238_vectors() {
+        let key = b"12345678901234567890";
+        for (time, expected) in [
+            (59, 287_082),
+            (1_111_111_109, 81_804),
+            (1_234_567_890, 5_924),
+            (2_000_000_000, 279_037),
+        ] {
+            assert_eq!(totp_at(key, time / TOTP_PERIOD_SECS as i64), expected);
+        }
+    }
+
+    #[test]
+    fn matching_step_allows_one_step_of_skew_and_rejects_replays() {
+        let secret = data_encoding::BASE32_NOPAD.encode(b"12345678901234567890");
+        let now = 1_111_111_109.0;
+        let step = 1_111_111_109 / 30;
+        let previous = format!("{:06}", totp_at(b"12345678901234567890", step - 1));
+        assert_eq!(matching_step(&secret, &previous, now, None), Some(step - 1));
+        assert_eq!(matching_step(&secret, &previous, now, Some(step - 1)), None);
+        assert_eq!(matching_step(&secret, "081804", now, None), Some(step));
+        assert_eq!(matching_step(&secret, "81804", now, None), None);
+        assert_eq!(matching_step(&secret, "081804", now + 90.0, None), None);
+    }
+
+    #[test]
+    fn provisioning_uri_escapes_labels() {
+        let uri = provisioning_uri("Acme Ops", "ops@example.com", "JBSWY3DPEHPK3PXP");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Acme%20Ops:ops%40example.com?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Acme%20Ops&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn recovery_codes_hash_the_way_users_type_them() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        let code = &codes[0];
+        assert_eq!(code.len(), 11);
+        assert_eq!(
+            recovery_code_hash(code),
+            recovery_code_hash(&code.replace('-', " ").to_uppercase())
+        );
+    }
+}
@@ -27,11 +27,12 @@
 use std::collections::BTreeMap;
 
 use axum::extract::{Path, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::{IntoResponse, Json, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::api_keys::{Permission, Resource};
 use crate::ctx::AuthCtx;
 use crate::state::AdminApiKeys;
 
@@ -39,16 +40,24 @@ use super::auth_params;
 use super::issuer_validation;
 use super::types::{OidcClient, TokenAuthMethod, UpstreamProvider};
 
-/// Admin-bearer-only gate shared by every OIDC admin handler. Per
-/// the decoupled-modules architecture: the engine HTTP boundary
-/// accepts only the operator admin api-key. Upstream consumers
-/// (dashboard, BFF) intermediate per-user identity if needed.
+/// Admin gate shared by every OIDC admin handler. Per the
+/// decoupled-modules architecture: the engine HTTP boundary accepts
+/// only the operator admin api-key or an `auth:*`-scoped API key.
+/// Upstream consumers (dashboard, BFF) intermediate per-user identity
+/// if needed.
 pub(crate) async fn require_admin(
+    method: &Method,
     headers: &HeaderMap,
-    _ctx: &AuthCtx,
+    ctx: &AuthCtx,
     keys: &AdminApiKeys,
 ) -> Result<(), Box<Response>> {
-    crate::gate::require_admin_bearer(headers, keys)
+    crate::gate::require_admin_or_api_key(
+        headers,
+        ctx,
+        keys,
+        Permission::new(Resource::Auth, method),
+    )
+    .await
 }
 
 // =====================================================================
@@ -61,6 +70,9 @@ pub(crate) async fn require_admin(
 #[derive(Clone, Debug, Deserialize)]
 pub struct CreateClientBody {
     pub client_id: Option<String>,
+    /// May be empty for machine clients that only use
+    /// `client_credentials`.
+    #[serde(default)]
     pub redirect_uris: Vec<String>,
     pub name: String,
     pub logo_url: Option<String>,
@@ -77,6 +89,11 @@ pub struct CreateClientBody {
     #[serde(default = "default_true")]
     pub pkce_required: bool,
     pub backchannel_logout_uri: Option<String>,
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// Public JWK Set — required for `private_key_jwt` clients.
+    #[serde(default)]
+    pub jwks: Option<serde_json::Value>,
 }
 
 fn default_auth_method() -> String {
@@ -103,32 +120,59 @@ fn default_response_types() -> Vec<String> {
 #[derive(Clone, Debug, Serialize)]
 pub struct CreateClientResponse {
     pub client: OidcClient,
-    /// Plaintext bearer for confidential clients. `None` for `none`
-    /// (PKCE-only) clients.
+    /// Plaintext bearer for secret-based clients. `None` for `none`
+    /// (PKCE-only) and `private_key_jwt` clients.
     pub client_secret: Option<String>,
 }
 
+/// Shape checks shared by create + update: browser clients need valid
+/// redirect URIs, `private_key_jwt` needs a usable JWK Set, and
+/// `client_credentials` needs a client that can authenticate.
+fn validate_client(
+    redirect_uris: &[String],
+    grant_types: &[String],
+    auth_method: TokenAuthMethod,
+    jwks: Option<&serde_json::Value>,
+) -> Result<(), String> {
+    let grants = |g: &str| grant_types.iter().any(|t| t == g);
+    if grants("authorization_code") && redirect_uris.is_empty() {
+        return Err("redirect_uris must be non-empty".into());
+    }
+    for u in redirect_uris {
+        if url::Url::parse(u).is_err() {
+            return Err(format!("redirect_uri {u:?} is not a URL"));
+        }
+    }
+    if grants("client_credentials") && auth_method == TokenAuthMethod::None {
+        return Err("client_credentials requires an authenticating client".into());
+    }
+    if auth_method == TokenAuthMethod::PrivateKeyJwt {
+        let keys = jwks
+            .cloned()
+            .map(serde_json::from_value::<jsonwebtoken::jwk::JwkSet>)
+            .ok_or("private_key_jwt requires jwks")?
+            .map_err(|e| format!("jwks: {e}"))?;
+        if keys.keys.is_empty() {
+            return Err("jwks must contain at least one key".into());
+        }
+    }
+    Ok(())
+}
+
 pub async fn create_client(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<CreateClientBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
         Some(p) => p,
         None => return svc_unavailable("oidc_provider not enabled"),
     };
-    if body.redirect_uris.is_empty() {
-        return bad_request("redirect_uris must be non-empty");
-    }
-    for u in &body.redirect_uris {
-        if url::Url::parse(u).is_err() {
-            return bad_request(&format!("redirect_uri {u:?} is not a URL"));
-        }
-    }
     let auth_method = match TokenAuthMethod::parse(&body.token_endpoint_auth_method) {
         Some(m) => m,
         None => {
@@ -138,6 +182,14 @@ pub async fn create_client(
             ));
         }
     };
+    if let Err(e) = validate_client(
+        &body.redirect_uris,
+        &body.grant_types,
+        auth_method,
+        body.jwks.as_ref(),
+    ) {
+        return bad_request(&e);
+    }
     let client_id = body.client_id.clone().unwrap_or_else(|| {
         format!(
             "ocl_{}",
@@ -145,7 +197,7 @@ pub async fn create_client(
         )
     });
     let plaintext_secret = match auth_method {
-        TokenAuthMethod::None => None,
+        TokenAuthMethod::None | TokenAuthMethod::PrivateKeyJwt => None,
         _ => Some(format!(
             "ocs_{}",
             data_encoding::BASE64URL_NOPAD.encode(&random_bytes::<24>())
@@ -174,6 +226,8 @@ pub async fn create_client(
         response_types: body.response_types,
         pkce_required: body.pkce_required,
         backchannel_logout_uri: body.backchannel_logout_uri,
+        audiences: body.audiences,
+        jwks: body.jwks,
         created_at: now_secs(),
     };
     if let Err(e) = provider.clients.create(&client).await {
@@ -192,9 +246,10 @@ pub async fn create_client(
 pub async fn list_clients(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -210,10 +265,11 @@ pub async fn list_clients(
 pub async fn get_client(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -236,6 +292,7 @@ pub async fn get_client(
 /// want persisted.
 #[derive(Clone, Debug, Deserialize)]
 pub struct UpdateClientBody {
+    #[serde(default)]
     pub redirect_uris: Vec<String>,
     pub name: String,
     pub logo_url: Option<String>,
@@ -246,16 +303,21 @@ pub struct UpdateClientBody {
     pub response_types: Vec<String>,
     pub pkce_required: bool,
     pub backchannel_logout_uri: Option<String>,
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    #[serde(default)]
+    pub jwks: Option<serde_json::Value>,
 }
 
 pub async fn update_client(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
     Json(body): Json<UpdateClientBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -282,6 +344,14 @@ pub async fn update_client(
             ));
         }
     };
+    if let Err(e) = validate_client(
+        &body.redirect_uris,
+        &body.grant_types,
+        auth_method,
+        body.jwks.as_ref(),
+    ) {
+        return bad_request(&e);
+    }
     let updated = OidcClient {
         client_id: existing.client_id,
         client_secret_hash: existing.client_secret_hash,
@@ -295,6 +365,8 @@ pub async fn update_client(
         response_types: body.response_types,
         pkce_required: body.pkce_required,
         backchannel_logout_uri: body.backchannel_logout_uri,
+        audiences: body.audiences,
+        jwks: body.jwks,
         created_at: existing.created_at,
     };
     if let Err(e) = provider.clients.update(&updated).await {
@@ -306,10 +378,11 @@ pub async fn update_client(
 pub async fn delete_client(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -338,10 +411,11 @@ pub struct RotateSecretResponse {
 pub async fn rotate_client_secret(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -399,10 +473,11 @@ pub struct UpstreamBody {
 pub async fn upsert_upstream(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Json(body): Json<UpstreamBody>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -492,9 +567,10 @@ fn normalize_scopes(mut scopes: Vec<String>) -> Vec<String> {
 pub async fn list_upstream(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -510,10 +586,11 @@ pub async fn list_upstream(
 pub async fn get_upstream(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(slug): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -534,10 +611,11 @@ pub async fn get_upstream(
 pub async fn delete_upstream(
     State(ctx): State<AuthCtx>,
     State(keys): State<AdminApiKeys>,
+    method: Method,
     headers: HeaderMap,
     Path(slug): Path<String>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &ctx, &keys).await {
+    if let Err(r) = require_admin(&method, &headers, &ctx, &keys).await {
         return *r;
     }
     let provider = match ctx.oidc_provider.as_ref() {
@@ -44,6 +44,10 @@ pub struct AuthorizeRequest {
     pub code_challenge_method: Option<String>,
     pub prompt: Option<String>,
     pub max_age: Option<u32>,
+    /// Organization hint (id or slug). Scopes the login to one
+    /// organization the user must belong to; the tokens then carry its
+    /// id as `org_id`.
+    pub organization: Option<String>,
 }
 
 /// Validation outcome for an authorize request before any DB write.
@@ -150,6 +154,8 @@ pub fn build_code(user_id: &str, req: &AuthorizeRequest, scopes: Vec<String>) ->
         issued_at: now,
         expires_at: now + CODE_LIFETIME_SECS,
         consumed: false,
+        amr: Vec::new(),
+        org_id: None,
     }
 }
 
@@ -247,6 +253,7 @@ mod tests {
             code_challenge_method: Some("S256".to_string()),
             prompt: None,
             max_age: None,
+            organization: None,
         }
     }
 
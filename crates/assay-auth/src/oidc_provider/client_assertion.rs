@@ -0,0 +1,242 @@
+//! `private_key_jwt` client authentication — RFC 7523 §2.2 / OIDC
+//! Core §9.
+//!
+//! The client signs a short-lived JWT with its own private key and
+//! posts it as `client_assertion` alongside
+//! `client_assertion_type=urn:ietf:params:oauth:client-assertion-type:jwt-bearer`.
+//! We verify it against the public JWK Set registered on the client
+//! ([`OidcClient::jwks`]): `iss` and `sub` must both be the client id,
+//! `aud` must name this provider (issuer or token endpoint), and `exp`
+//! must be in the future. Symmetric (`HS*`) assertions are rejected —
+//! the registered keys are public, so an HMAC "signature" over one
+//! proves nothing.
+//!
+//! An assertion is single-use (OIDC Core §9): it must carry `jti` and
+//! `iat`, may live at most [`MAX_ASSERTION_LIFETIME_SECS`], and the
+//! caller records its `jti` per client until `exp`
+//! ([`OidcClientStore::record_assertion_jti`]) so a captured assertion
+//! can't be presented twice.
+//!
+//! [`OidcClientStore::record_assertion_jti`]: super::store::OidcClientStore::record_assertion_jti
+
+use jsonwebtoken::dangerous::insecure_decode;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::Deserialize;
+
+use super::types::OidcClient;
+
+/// The only `client_assertion_type` we accept.
+pub const JWT_BEARER_ASSERTION_TYPE: &str =
+    "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// Longest `exp - iat` accepted. Clients mint a fresh assertion per
+/// request, so anything longer only widens what a leak is worth.
+pub const MAX_ASSERTION_LIFETIME_SECS: i64 = 300;
+
+#[derive(Deserialize)]
+struct AssertionClaims {
+    sub: String,
+    #[serde(default)]
+    jti: Option<String>,
+    #[serde(default)]
+    iat: Option<i64>,
+    #[serde(default)]
+    exp: i64,
+}
+
+/// What the caller needs from a verified assertion to enforce
+/// single use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedAssertion {
+    pub jti: String,
+    /// The assertion's `exp`; its `jti` must be remembered until then.
+    pub expires_at: f64,
+}
+
+/// Unverified peek at the assertion's `sub` — the client id — so the
+/// caller can load the client whose keys verify it. Only parses the
+/// payload; [`verify`] does the real work.
+pub fn claimed_client_id(assertion: &str) -> Option<String> {
+    insecure_decode::<AssertionClaims>(assertion)
+        .ok()
+        .map(|d| d.claims.sub)
+        .filter(|s| !s.is_empty())
+}
+
+/// Verify `assertion` for `client`. `audiences` lists the values the
+/// `aud` claim may carry (the issuer and the token endpoint URL).
+/// Returns a human-readable reason on failure; callers surface it as
+/// `invalid_client`. Replay is the caller's to check, with the
+/// returned `jti`.
+pub fn verify(
+    assertion: &str,
+    client: &OidcClient,
+    audiences: &[String],
+) -> Result<VerifiedAssertion, String> {
+    let jwks = client
+        .jwks
+        .as_ref()
+        .ok_or_else(|| "client has no registered jwks".to_string())?;
+    let jwks: JwkSet =
+        serde_json::from_value(jwks.clone()).map_err(|e| format!("registered jwks: {e}"))?;
+    let header = decode_header(assertion).map_err(|e| format!("client_assertion header: {e}"))?;
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err(format!(
+            "client_assertion alg {:?} is not allowed",
+            header.alg
+        ));
+    }
+    let jwk = match header.kid.as_deref() {
+        Some(kid) => jwks.find(kid),
+        // No kid: only unambiguous when exactly one key is registered.
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(|| "no registered key matches the client_assertion".to_string())?;
+    let key = DecodingKey::from_jwk(jwk).map_err(|e| format!("registered jwk: {e}"))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[&client.client_id]);
+    validation.set_audience(audiences);
+    validation.set_required_spec_claims(&["exp", "iss", "sub", "aud"]);
+    let data = decode::<AssertionClaims>(assertion, &key, &validation)
+        .map_err(|e| format!("client_assertion: {e}"))?;
+    let claims = data.claims;
+    if claims.sub != client.client_id {
+        return Err("client_assertion sub does not match client_id".into());
+    }
+    let jti = claims
+        .jti
+        .filter(|j| !j.is_empty())
+        .ok_or_else(|| "client_assertion has no jti".to_string())?;
+    let iat = claims
+        .iat
+        .ok_or_else(|| "client_assertion has no iat".to_string())?;
+    let now = jsonwebtoken::get_current_timestamp() as i64;
+    if iat > now + validation.leeway as i64 {
+        return Err("client_assertion iat is in the future".into());
+    }
+    if claims.exp - iat > MAX_ASSERTION_LIFETIME_SECS {
+        return Err(format!(
+            "client_assertion lifetime exceeds {MAX_ASSERTION_LIFETIME_SECS}s"
+        ));
+    }
+    Ok(VerifiedAssertion {
+        jti,
+        expires_at: claims.exp as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use ed25519_dalek::pkcs8::EncodePrivateKey;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::json;
+
+    const AUD: &str = "https://idp.example.com/token";
+
+    fn keypair(kid: &str) -> (EncodingKey, serde_json::Value) {
+        let signing = SigningKey::generate(&mut rand_core_06::OsRng);
+        let pem = signing
+            .to_pkcs8_pem(ed25519_dalek::pkcs8::spki::der::pem::LineEnding::LF)
+            .unwrap();
+        let x = data_encoding::BASE64URL_NOPAD.encode(&signing.verifying_key().to_bytes());
+        (
+            EncodingKey::from_ed_pem(pem.as_bytes()).unwrap(),
+            json!({"kty": "OKP", "crv": "Ed25519", "alg": "EdDSA", "kid": kid, "x": x}),
+        )
+    }
+
+    fn client_with(jwk: serde_json::Value) -> OidcClient {
+        let mut c = OidcClient::new("svc", "Service", 0.0);
+        c.jwks = Some(json!({ "keys": [jwk] }));
+        c
+    }
+
+    fn assertion(key: &EncodingKey, kid: Option<&str>, claims: serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = kid.map(str::to_string);
+        encode(&header, &claims, key).unwrap()
+    }
+
+    fn claims(sub: &str, aud: &str, exp_in: i64) -> serde_json::Value {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        json!({"iss": sub, "sub": sub, "aud": aud, "iat": now, "exp": now + exp_in, "jti": "j1"})
+    }
+
+    #[test]
+    fn accepts_assertion_signed_by_registered_key() {
+        let (key, jwk) = keypair("k1");
+        let client = client_with(jwk);
+        let token = assertion(&key, Some("k1"), claims("svc", AUD, 60));
+        assert_eq!(claimed_client_id(&token).as_deref(), Some("svc"));
+        let verified = verify(&token, &client, &[AUD.to_string()]).unwrap();
+        assert_eq!(verified.jti, "j1");
+        // A single registered key also verifies a kid-less assertion.
+        let token = assertion(&key, None, claims("svc", AUD, 60));
+        assert!(verify(&token, &client, &[AUD.to_string()]).is_ok());
+    }
+
+    #[test]
+    fn rejects_long_lived_assertions_and_missing_jti_or_iat() {
+        let (key, jwk) = keypair("k1");
+        let client = client_with(jwk);
+        let auds = [AUD.to_string()];
+        let long_lived = claims("svc", AUD, 86_400);
+        let mut no_jti = claims("svc", AUD, 60);
+        no_jti.as_object_mut().unwrap().remove("jti");
+        let mut no_iat = claims("svc", AUD, 60);
+        no_iat.as_object_mut().unwrap().remove("iat");
+        let mut future_iat = claims("svc", AUD, 3_600);
+        future_iat["iat"] = json!(future_iat["exp"].as_i64().unwrap() - 60);
+        for claims in [long_lived, no_jti, no_iat, future_iat] {
+            let token = assertion(&key, Some("k1"), claims.clone());
+            assert!(verify(&token, &client, &auds).is_err(), "{claims}");
+        }
+        let token = assertion(&key, Some("k1"), claims("svc", AUD, 300));
+        assert!(verify(&token, &client, &auds).is_ok());
+    }
+
+    #[test]
+    fn rejects_foreign_key_audience_subject_and_expiry() {
+        let (key, jwk) = keypair("k1");
+        let (other, _) = keypair("k1");
+        let client = client_with(jwk);
+        let auds = [AUD.to_string()];
+        let cases = [
+            assertion(&other, Some("k1"), claims("svc", AUD, 60)),
+            assertion(&key, Some("k2"), claims("svc", AUD, 60)),
+            assertion(&key, Some("k1"), claims("svc", "https://elsewhere", 60)),
+            assertion(&key, Some("k1"), claims("intruder", AUD, 60)),
+            assertion(&key, Some("k1"), claims("svc", AUD, -600)),
+        ];
+        for token in cases {
+            assert!(verify(&token, &client, &auds).is_err(), "{token}");
+        }
+    }
+
+    #[test]
+    fn rejects_hmac_assertions_and_clients_without_jwks() {
+        let (key, jwk) = keypair("k1");
+        let hs = encode(
+            &Header::new(Algorithm::HS256),
+            &claims("svc", AUD, 60),
+            &EncodingKey::from_secret(b"guess"),
+        )
+        .unwrap();
+        assert!(verify(&hs, &client_with(jwk), &[AUD.to_string()]).is_err());
+
+        let token = assertion(&key, Some("k1"), claims("svc", AUD, 60));
+        let bare = OidcClient::new("svc", "Service", 0.0);
+        assert!(verify(&token, &bare, &[AUD.to_string()]).is_err());
+    }
+}
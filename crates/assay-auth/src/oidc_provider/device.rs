@@ -0,0 +1,443 @@
+//! Device Authorization Grant — RFC 8628.
+//!
+//! For input-constrained or headless clients (ops CLIs on a jump box):
+//!
+//! 1. The device POSTs `/device_authorization` and gets back a secret
+//!    `device_code`, a short `user_code`, and the `verification_uri`.
+//! 2. The user opens `/device` in any browser, signs in, types the
+//!    user code, and approves or denies the request on a page rendered
+//!    like the consent screen.
+//! 3. Meanwhile the device polls `/token` with
+//!    `grant_type=urn:ietf:params:oauth:grant-type:device_code` and gets
+//!    `authorization_pending` / `slow_down` until the user decides.
+//!
+//! This module carries the pure pieces — code minting, user-code
+//! normalisation, the poll state machine, and the askama pages; the
+//! handlers glue them to the [`super::store::OidcDeviceCodeStore`].
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use askama::Template;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::types::{DeviceCode, DeviceCodeStatus};
+
+/// `grant_type` value the device polls `/token` with.
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// How long the user has to approve a request — ten minutes.
+pub const DEVICE_CODE_LIFETIME_SECS: f64 = 600.0;
+/// Minimum seconds between polls handed to the device (RFC 8628 §3.2
+/// default).
+pub const DEFAULT_POLL_INTERVAL_SECS: i64 = 5;
+/// Added to the interval on every `slow_down` (RFC 8628 §3.5).
+pub const SLOW_DOWN_INCREMENT_SECS: i64 = 5;
+
+/// User-code alphabet: consonants only, so codes can't spell words and
+/// there's no 0/O or 1/I confusion (RFC 8628 §6.1).
+const USER_CODE_ALPHABET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+const USER_CODE_LEN: usize = 8;
+
+/// Form-encoded request body for `POST /device_authorization`. Client
+/// authentication fields mirror [`super::token::TokenRequest`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct DeviceAuthorizationRequest {
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
+    pub scope: Option<String>,
+}
+
+/// Successful `/device_authorization` response (RFC 8628 §3.2).
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: i64,
+    pub interval: i64,
+}
+
+impl DeviceAuthorizationResponse {
+    /// Build the response for a freshly minted `code`; the
+    /// verification page lives at `{issuer}/device`.
+    pub fn new(issuer: &str, code: &DeviceCode) -> Self {
+        let verification_uri = format!("{issuer}/device");
+        let user_code = format_user_code(&code.user_code);
+        Self {
+            device_code: code.device_code.clone(),
+            verification_uri_complete: format!("{verification_uri}?user_code={user_code}"),
+            verification_uri,
+            user_code,
+            expires_in: (code.expires_at - code.issued_at).round() as i64,
+            interval: code.interval,
+        }
+    }
+}
+
+/// Mint a pending [`DeviceCode`] for `client_id`. The stored user code
+/// is the normalised form (no dash); [`format_user_code`] adds it back
+/// for display.
+pub fn build_device_code(client_id: &str, scopes: Vec<String>) -> DeviceCode {
+    let now = now_secs();
+    DeviceCode {
+        device_code: format!("odc_{}", random_token()),
+        user_code: mint_user_code(),
+        client_id: client_id.to_string(),
+        scopes,
+        user_id: None,
+        status: DeviceCodeStatus::Pending,
+        interval: DEFAULT_POLL_INTERVAL_SECS,
+        last_polled_at: None,
+        issued_at: now,
+        expires_at: now + DEVICE_CODE_LIFETIME_SECS,
+    }
+}
+
+fn mint_user_code() -> String {
+    let mut buf = [0u8; USER_CODE_LEN];
+    rand::rng().fill_bytes(&mut buf);
+    buf.iter()
+        .map(|b| USER_CODE_ALPHABET[*b as usize % USER_CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// `BCDFGHJK` → `BCDF-GHJK`.
+pub fn format_user_code(code: &str) -> String {
+    if code.len() == USER_CODE_LEN {
+        format!("{}-{}", &code[..4], &code[4..])
+    } else {
+        code.to_string()
+    }
+}
+
+/// Normalise what the user typed: drop dashes and whitespace, upper-
+/// case the rest. Returns `None` when the result can't be a user code,
+/// so the page can complain without a store round-trip.
+pub fn normalize_user_code(input: &str) -> Option<String> {
+    let code: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    (code.len() == USER_CODE_LEN && code.bytes().all(|b| USER_CODE_ALPHABET.contains(&b)))
+        .then_some(code)
+}
+
+/// What a `/token` poll should answer for `code` at `now`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollOutcome {
+    /// Still waiting on the user; store `interval` with the poll.
+    Pending {
+        interval: i64,
+    },
+    /// Polled faster than `interval` allowed; store the bumped interval.
+    SlowDown {
+        interval: i64,
+    },
+    Approved,
+    Denied,
+    Expired,
+    /// Already redeemed for tokens.
+    Consumed,
+}
+
+/// The RFC 8628 §3.5 poll state machine. Expiry wins over every other
+/// state; an early poll on a pending request earns `slow_down`.
+pub fn poll_outcome(code: &DeviceCode, now: f64) -> PollOutcome {
+    if code.expires_at <= now {
+        return PollOutcome::Expired;
+    }
+    match code.status {
+        DeviceCodeStatus::Approved => PollOutcome::Approved,
+        DeviceCodeStatus::Denied => PollOutcome::Denied,
+        DeviceCodeStatus::Consumed => PollOutcome::Consumed,
+        DeviceCodeStatus::Pending => match code.last_polled_at {
+            Some(last) if now - last < code.interval as f64 => PollOutcome::SlowDown {
+                interval: code.interval + SLOW_DOWN_INCREMENT_SECS,
+            },
+            _ => PollOutcome::Pending {
+                interval: code.interval,
+            },
+        },
+    }
+}
+
+/// Query string for `GET /device` — `verification_uri_complete` carries
+/// the user code so the user only has to confirm.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct DeviceQuery {
+    pub user_code: Option<String>,
+}
+
+/// Parsed verification form submission.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct DeviceSubmission {
+    pub csrf_token: String,
+    pub user_code: String,
+    pub decision: String,
+}
+
+impl DeviceSubmission {
+    pub fn allowed(&self) -> bool {
+        self.decision == "allow"
+    }
+}
+
+/// Code-entry page — shown when `/device` is opened without a (valid)
+/// user code. Form actions are relative so the page works wherever the
+/// spec router is mounted.
+#[derive(Template)]
+#[template(
+    source = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Connect a device</title>
+  <style>
+    body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif;
+           max-width: 480px; margin: 4em auto; color: #222; }
+    h1 { font-size: 1.25rem; margin-bottom: 0.25em; }
+    .sub { color: #666; margin-bottom: 1.5em; }
+    .error { color: #b91c1c; margin-bottom: 1em; }
+    input { font-size: 1.25rem; letter-spacing: 0.15em; padding: 0.5em;
+            width: 12ch; text-transform: uppercase; }
+    button { padding: 0.75em 1.5em; border: none; border-radius: 6px;
+             cursor: pointer; font-size: 1rem; background: #2563eb; color: white; }
+  </style>
+</head>
+<body>
+  <h1>Connect a device</h1>
+  <div class="sub">Enter the code shown on your device. Issued by <strong>{{ issuer }}</strong></div>
+  {% if let Some(error) = error %}<div class="error">{{ error }}</div>{% endif %}
+  <form method="GET" action="device">
+    <input type="text" name="user_code" placeholder="XXXX-XXXX" autocomplete="off" autofocus />
+    <button type="submit">Continue</button>
+  </form>
+</body>
+</html>"#,
+    ext = "html",
+    escape = "html"
+)]
+pub struct DeviceEntryPage<'a> {
+    pub issuer: &'a str,
+    pub error: Option<&'a str>,
+}
+
+/// Approval page for one pending request — the device-flow twin of
+/// [`super::consent::ConsentPage`]. The user code is echoed so the user
+/// can check it against the device's screen.
+#[derive(Template)]
+#[template(
+    source = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Authorize {{ client_name }}</title>
+  <style>
+    body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif;
+           max-width: 480px; margin: 4em auto; color: #222; }
+    h1 { font-size: 1.25rem; margin-bottom: 0.25em; }
+    .sub { color: #666; margin-bottom: 1.5em; }
+    .code { font-family: ui-monospace, monospace; font-size: 1.5rem;
+            letter-spacing: 0.15em; margin-bottom: 1em; }
+    ul { background: #f7f7f7; border-radius: 6px; padding: 1em 2em; }
+    li { margin: 0.25em 0; }
+    form { display: inline-block; margin-right: 0.5em; }
+    button { padding: 0.75em 1.5em; border: none; border-radius: 6px;
+             cursor: pointer; font-size: 1rem; }
+    button.allow { background: #2563eb; color: white; }
+    button.deny  { background: #f3f4f6; color: #222; }
+  </style>
+</head>
+<body>
+  <h1>{{ client_name }} wants to access your account</h1>
+  <div class="sub">Issued by <strong>{{ issuer }}</strong>. Only continue if your device shows this code:</div>
+  <div class="code">{{ user_code }}</div>
+  <ul>
+    {% for scope in scopes %}
+    <li>{{ scope }}</li>
+    {% endfor %}
+  </ul>
+  <form method="POST" action="device">
+    <input type="hidden" name="csrf_token" value="{{ csrf_token }}" />
+    <input type="hidden" name="user_code" value="{{ user_code }}" />
+    <input type="hidden" name="decision" value="allow" />
+    <button class="allow" type="submit">Allow</button>
+  </form>
+  <form method="POST" action="device">
+    <input type="hidden" name="csrf_token" value="{{ csrf_token }}" />
+    <input type="hidden" name="user_code" value="{{ user_code }}" />
+    <input type="hidden" name="decision" value="deny" />
+    <button class="deny" type="submit">Deny</button>
+  </form>
+</body>
+</html>"#,
+    ext = "html",
+    escape = "html"
+)]
+pub struct DeviceVerifyPage<'a> {
+    pub client_name: &'a str,
+    pub issuer: &'a str,
+    pub user_code: &'a str,
+    pub scopes: &'a [String],
+    pub csrf_token: &'a str,
+}
+
+/// Closing page after Allow / Deny.
+#[derive(Template)]
+#[template(
+    source = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>{% if approved %}Device connected{% else %}Request denied{% endif %}</title>
+  <style>
+    body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif;
+           max-width: 480px; margin: 4em auto; color: #222; }
+    h1 { font-size: 1.25rem; margin-bottom: 0.25em; }
+    .sub { color: #666; }
+  </style>
+</head>
+<body>
+  {% if approved %}
+  <h1>Device connected</h1>
+  <div class="sub">{{ client_name }} can now finish signing in. You can close this window and return to your device.</div>
+  {% else %}
+  <h1>Request denied</h1>
+  <div class="sub">{{ client_name }} was not given access. You can close this window.</div>
+  {% endif %}
+</body>
+</html>"#,
+    ext = "html",
+    escape = "html"
+)]
+pub struct DeviceResultPage<'a> {
+    pub client_name: &'a str,
+    pub approved: bool,
+}
+
+/// Render any of the device pages to a UTF-8 HTML body, falling back
+/// to a sentinel like [`super::consent::ConsentPage::render_html`].
+pub fn render_html<T: Template>(page: &T) -> String {
+    page.render()
+        .unwrap_or_else(|_| "<!doctype html><body>device page render error</body>".to_string())
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn random_token() -> String {
+    let mut buf = [0u8; 32];
+    rand::rng().fill_bytes(&mut buf);
+    data_encoding::BASE64URL_NOPAD.encode(&buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minted_codes_are_pending_and_normalised() {
+        let c = build_device_code("cli", vec!["openid".to_string()]);
+        assert!(c.device_code.starts_with("odc_"));
+        assert_eq!(c.status, DeviceCodeStatus::Pending);
+        assert_eq!(c.interval, DEFAULT_POLL_INTERVAL_SECS);
+        assert_eq!(
+            normalize_user_code(&c.user_code).as_deref(),
+            Some(&*c.user_code)
+        );
+        let other = build_device_code("cli", vec![]);
+        assert_ne!(c.device_code, other.device_code);
+
+        let resp = DeviceAuthorizationResponse::new("https://idp.example.com", &c);
+        assert_eq!(resp.verification_uri, "https://idp.example.com/device");
+        assert_eq!(resp.user_code.len(), 9);
+        assert!(resp.verification_uri_complete.ends_with(&resp.user_code));
+        assert_eq!(resp.expires_in, DEVICE_CODE_LIFETIME_SECS as i64);
+    }
+
+    #[test]
+    fn user_code_normalisation_accepts_typing_variants() {
+        assert_eq!(
+            normalize_user_code("bcdf-ghjk").as_deref(),
+            Some("BCDFGHJK")
+        );
+        assert_eq!(
+            normalize_user_code(" BCDF GHJK ").as_deref(),
+            Some("BCDFGHJK")
+        );
+        assert_eq!(format_user_code("BCDFGHJK"), "BCDF-GHJK");
+        // Wrong length, vowels and digits are never issued.
+        assert_eq!(normalize_user_code("BCDF-GHJ"), None);
+        assert_eq!(normalize_user_code("ABCD-EFGH"), None);
+        assert_eq!(normalize_user_code("BCDF-GHJ1"), None);
+    }
+
+    #[test]
+    fn poll_outcome_walks_the_state_machine() {
+        let mut c = build_device_code("cli", vec![]);
+        let now = c.issued_at + 1.0;
+        assert_eq!(
+            poll_outcome(&c, now),
+            PollOutcome::Pending {
+                interval: DEFAULT_POLL_INTERVAL_SECS
+            }
+        );
+        c.last_polled_at = Some(now);
+        assert_eq!(
+            poll_outcome(&c, now + 1.0),
+            PollOutcome::SlowDown {
+                interval: DEFAULT_POLL_INTERVAL_SECS + SLOW_DOWN_INCREMENT_SECS
+            }
+        );
+        assert!(matches!(
+            poll_outcome(&c, now + DEFAULT_POLL_INTERVAL_SECS as f64),
+            PollOutcome::Pending { .. }
+        ));
+        c.status = DeviceCodeStatus::Approved;
+        assert_eq!(poll_outcome(&c, now), PollOutcome::Approved);
+        c.status = DeviceCodeStatus::Denied;
+        assert_eq!(poll_outcome(&c, now), PollOutcome::Denied);
+        c.status = DeviceCodeStatus::Consumed;
+        assert_eq!(poll_outcome(&c, now), PollOutcome::Consumed);
+        c.status = DeviceCodeStatus::Approved;
+        assert_eq!(poll_outcome(&c, c.expires_at), PollOutcome::Expired);
+    }
+
+    #[test]
+    fn pages_render_codes_scopes_and_relative_actions() {
+        let scopes = vec!["openid".to_string(), "offline_access".to_string()];
+        let html = render_html(&DeviceVerifyPage {
+            client_name: "ops CLI",
+            issuer: "https://idp.example.com",
+            user_code: "BCDF-GHJK",
+            scopes: &scopes,
+            csrf_token: "csrf_xyz",
+        });
+        assert!(html.contains("ops CLI"));
+        assert!(html.contains("BCDF-GHJK"));
+        assert!(html.contains("offline_access"));
+        assert!(html.contains("csrf_xyz"));
+        assert!(html.contains(r#"action="device""#));
+
+        let html = render_html(&DeviceEntryPage {
+            issuer: "https://idp.example.com",
+            error: Some("<unknown code>"),
+        });
+        assert!(html.contains("&lt;unknown code&gt;"));
+        assert!(html.contains(r#"name="user_code""#));
+
+        let html = render_html(&DeviceResultPage {
+            client_name: "ops CLI",
+            approved: false,
+        });
+        assert!(html.contains("Request denied"));
+    }
+}
@@ -3,7 +3,8 @@
 //! Per OpenID Connect Discovery 1.0 §4. We emit only the Core 1.0
 //! profile fields plus the few extension fields v0.2.0 callers actually
 //! need (`revocation_endpoint`, `end_session_endpoint`,
-//! `introspection_endpoint`).
+//! `introspection_endpoint`, and RFC 8628's
+//! `device_authorization_endpoint`).
 
 use axum::{Json, extract::State};
 use serde_json::{Value, json};
@@ -17,6 +18,7 @@ pub fn build_discovery(issuer: &str) -> Value {
         "issuer": issuer,
         "authorization_endpoint": format!("{issuer}/authorize"),
         "token_endpoint": format!("{issuer}/token"),
+        "device_authorization_endpoint": format!("{issuer}/device_authorization"),
         "userinfo_endpoint": format!("{issuer}/userinfo"),
         "jwks_uri": format!("{issuer}/.well-known/jwks.json"),
         "revocation_endpoint": format!("{issuer}/revoke"),
@@ -24,17 +26,31 @@ pub fn build_discovery(issuer: &str) -> Value {
         "end_session_endpoint": format!("{issuer}/logout"),
         "scopes_supported": ["openid", "email", "profile", "offline_access"],
         "response_types_supported": ["code"],
-        "grant_types_supported": ["authorization_code", "refresh_token"],
+        "grant_types_supported": [
+            "authorization_code",
+            "refresh_token",
+            "client_credentials",
+            "urn:ietf:params:oauth:grant-type:device_code"
+        ],
         "subject_types_supported": ["public"],
         "id_token_signing_alg_values_supported": ["EdDSA"],
         "token_endpoint_auth_methods_supported": [
             "client_secret_basic",
             "client_secret_post",
+            "private_key_jwt",
             "none"
         ],
+        "token_endpoint_auth_signing_alg_values_supported": [
+            "EdDSA", "ES256", "ES384", "RS256", "RS384", "RS512", "PS256", "PS384", "PS512"
+        ],
         "code_challenge_methods_supported": ["S256"],
         "claims_supported": [
-            "sub", "email", "email_verified", "name", "preferred_username", "sid"
+            "sub", "email", "email_verified", "name", "preferred_username", "sid", "amr", "acr",
+            "org_id"
+        ],
+        "acr_values_supported": [
+            super::token::ACR_SINGLE_FACTOR,
+            super::token::ACR_MULTI_FACTOR
         ],
     })
 }
@@ -83,6 +99,18 @@ mod tests {
             json!(["EdDSA"])
         );
         assert_eq!(doc["code_challenge_methods_supported"], json!(["S256"]));
+        assert!(
+            doc["grant_types_supported"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("client_credentials"))
+        );
+        assert!(
+            doc["token_endpoint_auth_methods_supported"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("private_key_jwt"))
+        );
     }
 
     #[test]
@@ -93,6 +121,16 @@ mod tests {
             doc["introspection_endpoint"],
             "https://idp.example.com/introspect"
         );
+        assert_eq!(
+            doc["device_authorization_endpoint"],
+            "https://idp.example.com/device_authorization"
+        );
+        assert!(
+            doc["grant_types_supported"]
+                .as_array()
+                .unwrap()
+                .contains(&json!(crate::oidc_provider::device::DEVICE_CODE_GRANT_TYPE))
+        );
         assert_eq!(
             doc["end_session_endpoint"],
             "https://idp.example.com/logout"
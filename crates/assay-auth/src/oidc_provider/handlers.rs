@@ -26,11 +26,16 @@ use serde_json::json;
 use crate::ctx::AuthCtx;
 
 use super::authorize::{self as authz, AuthorizeRequest, AuthorizeValidation};
+use super::client_assertion;
 use super::consent::{ConsentPage, ConsentSubmission, scopes_already_granted};
+use super::device::{
+    self, DeviceAuthorizationRequest, DeviceAuthorizationResponse, DeviceEntryPage, DeviceQuery,
+    DeviceResultPage, DeviceSubmission, DeviceVerifyPage, PollOutcome,
+};
 use super::introspect::{IntrospectRequest, IntrospectResponse};
 use super::revoke::RevokeRequest;
 use super::token::{self as tok, TokenErrorBody, TokenRequest, TokenResponse, errors};
-use super::types::{ConsentGrant, OidcSession};
+use super::types::{ConsentGrant, DeviceCode as DeviceCodeRow, OidcSession};
 use super::userinfo::{self, ProviderAccessTokenClaims};
 
 /// Cookie name carrying a transient "in-flight authorize request"
@@ -116,6 +121,13 @@ async fn authorize_post_validate(
         return Redirect::to(&authz::return_to_for(&original)).into_response();
     };
 
+    // An org-scoped login needs the user to belong to that org —
+    // refuse before asking for consent.
+    let org_id = match resolve_login_org(&ctx, &req, &session.user_id).await {
+        Ok(org_id) => org_id,
+        Err(response) => return response,
+    };
+
     // Authenticated. Decide whether consent is required.
     let provider = match ctx.oidc_provider.as_ref() {
         Some(p) => p,
@@ -161,22 +173,80 @@ async fn authorize_post_validate(
     }
 
     // No consent required — issue the code.
-    issue_authorization_code(&ctx, req, &session.user_id, scopes).await
+    issue_authorization_code(&ctx, req, &session, scopes, org_id).await
+}
+
+/// Resolve the `organization` authorize hint to the id of the org the
+/// login is scoped to (`Ok(None)` without a hint). A non-member gets
+/// the `access_denied` redirect; a hint on a deployment without
+/// organizations is an `invalid_request`.
+async fn resolve_login_org(
+    ctx: &AuthCtx,
+    req: &AuthorizeRequest,
+    user_id: &str,
+) -> Result<Option<String>, Response> {
+    let Some(hint) = req.organization.as_deref() else {
+        return Ok(None);
+    };
+    let deny = |error: &str, description: &str| {
+        Redirect::to(&authz::redirect_with_error(
+            &req.redirect_uri,
+            error,
+            description,
+            req.state.as_deref(),
+        ))
+        .into_response()
+    };
+    #[cfg(feature = "auth-orgs")]
+    if let Some(orgs) = ctx.orgs.as_ref() {
+        return match orgs.membership_for(hint, user_id).await {
+            Ok(Some(membership)) => Ok(Some(membership.org_id)),
+            Ok(None) => Err(deny(
+                "access_denied",
+                "user is not a member of the requested organization",
+            )),
+            Err(e) => Err(server_error_html(&format!("organization lookup: {e}"))),
+        };
+    }
+    #[cfg(not(feature = "auth-orgs"))]
+    let _ = (ctx, hint, user_id);
+    Err(deny("invalid_request", "organizations are not enabled"))
+}
+
+/// `true` while `user_id` still belongs to `org_id` — refresh grants
+/// re-check this so leaving an org ends its token chain.
+async fn org_membership_live(ctx: &AuthCtx, org_id: &str, user_id: &str) -> anyhow::Result<bool> {
+    #[cfg(feature = "auth-orgs")]
+    if let Some(orgs) = ctx.orgs.as_ref() {
+        return Ok(orgs
+            .store()
+            .get_membership(org_id, user_id)
+            .await?
+            .is_some());
+    }
+    #[cfg(not(feature = "auth-orgs"))]
+    let _ = (ctx, org_id, user_id);
+    Ok(false)
 }
 
 /// Common path: build + persist an [`AuthorizationCode`] row, then
-/// 302 the user back to the consumer's `redirect_uri`.
+/// 302 the user back to the consumer's `redirect_uri`. The code keeps
+/// the session's `amr` so the ID token can report how the user signed
+/// in, and the org the login was scoped to.
 async fn issue_authorization_code(
     ctx: &AuthCtx,
     req: AuthorizeRequest,
-    user_id: &str,
+    session: &crate::store::Session,
     scopes: Vec<String>,
+    org_id: Option<String>,
 ) -> Response {
     let provider = match ctx.oidc_provider.as_ref() {
         Some(p) => p,
         None => return server_misconfigured("oidc_provider is not enabled"),
     };
-    let code = authz::build_code(user_id, &req, scopes);
+    let mut code = authz::build_code(&session.user_id, &req, scopes);
+    code.amr = session.amr.clone();
+    code.org_id = org_id;
     if let Err(e) = provider.codes.create(&code).await {
         return server_error_html(&format!("persist authorization code: {e}"));
     }
@@ -211,6 +281,9 @@ fn rebuild_authorize_url(ctx: &AuthCtx, req: &AuthorizeRequest) -> String {
     if let Some(m) = &req.code_challenge_method {
         url.push_str(&format!("&code_challenge_method={}", url_encode(m)));
     }
+    if let Some(o) = &req.organization {
+        url.push_str(&format!("&organization={}", url_encode(o)));
+    }
     url
 }
 
@@ -299,15 +372,21 @@ pub async fn consent_post(
         return server_error_html(&format!("persist consent: {e}"));
     }
 
-    issue_authorization_code(&ctx, req, &session.user_id, scopes).await
+    // The resume cookie isn't signed, so re-check the org here rather
+    // than trusting what `/authorize` saw.
+    let org_id = match resolve_login_org(&ctx, &req, &session.user_id).await {
+        Ok(org_id) => org_id,
+        Err(response) => return response,
+    };
+    issue_authorization_code(&ctx, req, &session, scopes, org_id).await
 }
 
 // =====================================================================
 //   /token
 // =====================================================================
 
-/// `POST /token` — dispatch to `authorization_code` or `refresh_token`.
-/// `client_credentials` is reserved (returns `unsupported_grant_type`).
+/// `POST /token` — dispatch to `authorization_code`, `refresh_token`,
+/// `client_credentials` or the RFC 8628 device code grant.
 pub async fn token_post(
     State(ctx): State<AuthCtx>,
     headers: HeaderMap,
@@ -318,26 +397,42 @@ pub async fn token_post(
         None => return server_misconfigured("oidc_provider is not enabled"),
     };
 
-    // Authenticate the client (basic / post / none-PKCE).
+    // Authenticate the client (basic / post / private_key_jwt / none-PKCE).
     let client = match authenticate_client(&ctx, &headers, &req).await {
         Ok(c) => c,
         Err((status, body)) => return (status, Json(body)).into_response(),
     };
 
-    match req.grant_type.as_str() {
-        "authorization_code" => grant_authorization_code(&ctx, &client, &req).await,
-        "refresh_token" => grant_refresh(&ctx, &client, &req).await,
-        other => token_err(
-            StatusCode::BAD_REQUEST,
-            errors::UNSUPPORTED_GRANT_TYPE,
-            Some(format!("grant_type {other:?} is not supported")),
+    let (grant, response) = match req.grant_type.as_str() {
+        "authorization_code" => (
+            "authorization_code",
+            grant_authorization_code(&ctx, &client, &req).await,
+        ),
+        "refresh_token" => ("refresh_token", grant_refresh(&ctx, &client, &req).await),
+        "client_credentials" => (
+            "client_credentials",
+            grant_client_credentials(&ctx, &client, &req).await,
         ),
+        device::DEVICE_CODE_GRANT_TYPE => {
+            ("device_code", grant_device_code(&ctx, &client, &req).await)
+        }
+        other => {
+            return token_err(
+                StatusCode::BAD_REQUEST,
+                errors::UNSUPPORTED_GRANT_TYPE,
+                Some(format!("grant_type {other:?} is not supported")),
+            );
+        }
+    };
+    if response.status().is_success() {
+        ctx.metrics.tokens_issued.inc(&[grant]);
     }
+    response
 }
 
 /// Authenticate the client — supports `client_secret_basic`,
-/// `client_secret_post`, or PKCE-only `none`. Returns either the loaded
-/// client row or a wire-shaped error tuple.
+/// `client_secret_post`, `private_key_jwt`, or PKCE-only `none`.
+/// Returns either the loaded client row or a wire-shaped error tuple.
 async fn authenticate_client(
     ctx: &AuthCtx,
     headers: &HeaderMap,
@@ -368,6 +463,11 @@ async fn authenticate_client(
             Some((id.to_string(), secret.to_string()))
         });
 
+    let assertion = req.client_assertion.as_deref().filter(|a| !a.is_empty());
+    if let Some(assertion) = assertion {
+        return authenticate_assertion(provider, req, assertion).await;
+    }
+
     let (client_id, presented_secret) = match (basic, &req.client_id) {
         (Some((id, secret)), _) => (id, Some(secret)),
         (None, Some(id)) => (id.clone(), req.client_secret.clone()),
@@ -409,16 +509,76 @@ async fn authenticate_client(
             }
             Ok(client)
         }
-        super::types::TokenAuthMethod::PrivateKeyJwt => {
-            // Reserved for v0.2.0+; reject for now.
-            Err((
-                StatusCode::BAD_REQUEST,
-                err_body(
-                    errors::INVALID_CLIENT,
-                    Some("private_key_jwt not yet supported".into()),
-                ),
-            ))
-        }
+        super::types::TokenAuthMethod::PrivateKeyJwt => Err((
+            StatusCode::UNAUTHORIZED,
+            err_body(
+                errors::INVALID_CLIENT,
+                Some("client_assertion is required".into()),
+            ),
+        )),
+    }
+}
+
+/// `private_key_jwt` half of [`authenticate_client`]: the client named
+/// by `client_id` (or, failing that, the assertion's `sub`) must be
+/// registered for `private_key_jwt`, the assertion must verify
+/// against its JWK Set with this provider as audience, and its `jti`
+/// must not have been used before.
+async fn authenticate_assertion(
+    provider: &super::OidcProviderConfig,
+    req: &TokenRequest,
+    assertion: &str,
+) -> Result<super::types::OidcClient, (StatusCode, TokenErrorBody)> {
+    let invalid = |desc: String| {
+        (
+            StatusCode::UNAUTHORIZED,
+            err_body(errors::INVALID_CLIENT, Some(desc)),
+        )
+    };
+    if req.client_assertion_type.as_deref() != Some(client_assertion::JWT_BEARER_ASSERTION_TYPE) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            err_body(
+                errors::INVALID_REQUEST,
+                Some("unsupported client_assertion_type".into()),
+            ),
+        ));
+    }
+    let client_id = req
+        .client_id
+        .clone()
+        .or_else(|| client_assertion::claimed_client_id(assertion))
+        .ok_or_else(|| invalid("client_assertion has no sub".into()))?;
+    let client = match provider.clients.get(&client_id).await {
+        Ok(Some(c)) => c,
+        _ => return Err(invalid("unknown client".into())),
+    };
+    if client.token_endpoint_auth_method != super::types::TokenAuthMethod::PrivateKeyJwt {
+        return Err(invalid(
+            "client is not registered for private_key_jwt".into(),
+        ));
+    }
+    let audiences = [
+        provider.issuer.clone(),
+        format!("{}/token", provider.issuer),
+    ];
+    let verified = client_assertion::verify(assertion, &client, &audiences).map_err(invalid)?;
+    match provider
+        .clients
+        .record_assertion_jti(
+            &client.client_id,
+            &verified.jti,
+            verified.expires_at,
+            now_secs(),
+        )
+        .await
+    {
+        Ok(true) => Ok(client),
+        Ok(false) => Err(invalid("client_assertion jti has already been used".into())),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_body(errors::SERVER_ERROR, None),
+        )),
     }
 }
 
@@ -527,6 +687,8 @@ async fn grant_authorization_code(
         &consumed.user_id,
         &consumed.scopes,
         consumed.nonce.as_deref(),
+        &consumed.amr,
+        consumed.org_id.as_deref(),
     )
     .await
 }
@@ -597,6 +759,26 @@ async fn grant_refresh(
             Some("refresh_token client mismatch".into()),
         );
     }
+    if let Some(org_id) = row.org_id.as_deref() {
+        match org_membership_live(ctx, org_id, &row.user_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = provider.refresh.revoke(&hash).await;
+                return token_err(
+                    StatusCode::BAD_REQUEST,
+                    errors::INVALID_GRANT,
+                    Some("user is no longer a member of the organization".into()),
+                );
+            }
+            Err(e) => {
+                return token_err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    errors::SERVER_ERROR,
+                    Some(format!("organization lookup: {e}")),
+                );
+            }
+        }
+    }
     if let Err(e) = provider.refresh.revoke(&hash).await {
         return token_err(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -604,19 +786,232 @@ async fn grant_refresh(
             Some(format!("revoke old refresh: {e}")),
         );
     }
-    issue_token_pair(ctx, client, &row.user_id, &row.scopes, None).await
+    issue_token_pair(
+        ctx,
+        client,
+        &row.user_id,
+        &row.scopes,
+        None,
+        &[],
+        row.org_id.as_deref(),
+    )
+    .await
+}
+
+/// `client_credentials` grant (RFC 6749 §4.4) — mint an access token
+/// for the authenticated client itself. Requested scopes must all be
+/// registered on the client (none requested → all of them); the
+/// optional `audience` must be one of the client's registered
+/// audiences (none → the client id). No id_token, no refresh_token.
+async fn grant_client_credentials(
+    ctx: &AuthCtx,
+    client: &super::types::OidcClient,
+    req: &TokenRequest,
+) -> Response {
+    let provider = match ctx.oidc_provider.as_ref() {
+        Some(p) => p,
+        None => {
+            return token_err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                errors::SERVER_ERROR,
+                None,
+            );
+        }
+    };
+    if !client.allows_grant("client_credentials") {
+        return token_err(
+            StatusCode::BAD_REQUEST,
+            errors::UNAUTHORIZED_CLIENT,
+            Some("client is not registered for client_credentials".into()),
+        );
+    }
+    if client.token_endpoint_auth_method == super::types::TokenAuthMethod::None {
+        return token_err(
+            StatusCode::BAD_REQUEST,
+            errors::UNAUTHORIZED_CLIENT,
+            Some("public clients cannot use client_credentials".into()),
+        );
+    }
+    let scopes: Vec<String> = match req.scope.as_deref().map(str::split_whitespace) {
+        Some(requested) => requested.map(str::to_string).collect(),
+        None => client.default_scopes.clone(),
+    };
+    if let Some(denied) = scopes.iter().find(|s| !client.allows_scope(s)) {
+        return token_err(
+            StatusCode::BAD_REQUEST,
+            errors::INVALID_SCOPE,
+            Some(format!(
+                "scope {denied:?} is not registered for this client"
+            )),
+        );
+    }
+    let audience = match req.audience.as_deref().map(str::trim) {
+        None | Some("") => client.client_id.clone(),
+        Some(aud) if aud.split_whitespace().count() > 1 => {
+            return token_err(
+                StatusCode::BAD_REQUEST,
+                errors::INVALID_TARGET,
+                Some("request one audience per token".into()),
+            );
+        }
+        Some(aud) if !client.allows_audience(aud) => {
+            return token_err(
+                StatusCode::BAD_REQUEST,
+                errors::INVALID_TARGET,
+                Some(format!(
+                    "audience {aud:?} is not registered for this client"
+                )),
+            );
+        }
+        Some(aud) => aud.to_string(),
+    };
+
+    let Some(jwt) = ctx.jwt.as_ref() else {
+        return token_err(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            errors::SERVER_ERROR,
+            Some("jwt not configured".into()),
+        );
+    };
+    let claims = tok::build_client_credentials_claims(
+        &provider.issuer,
+        &client.client_id,
+        &audience,
+        &scopes,
+    );
+    let access_token = match jwt.issue(&claims) {
+        Ok(t) => t,
+        Err(e) => {
+            return token_err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                errors::SERVER_ERROR,
+                Some(format!("sign access_token: {e}")),
+            );
+        }
+    };
+    let response = TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: tok::ACCESS_TOKEN_LIFETIME_SECS as i64,
+        id_token: None,
+        refresh_token: None,
+        scope: scopes.join(" "),
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Device code grant (RFC 8628 §3.4) — the device polls until the user
+/// settles the request on `/device`. Early polls earn `slow_down` and a
+/// longer interval; an approved code is consumed exactly once.
+async fn grant_device_code(
+    ctx: &AuthCtx,
+    client: &super::types::OidcClient,
+    req: &TokenRequest,
+) -> Response {
+    let provider = match ctx.oidc_provider.as_ref() {
+        Some(p) => p,
+        None => {
+            return token_err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                errors::SERVER_ERROR,
+                None,
+            );
+        }
+    };
+    if !client.allows_grant(device::DEVICE_CODE_GRANT_TYPE) {
+        return token_err(
+            StatusCode::BAD_REQUEST,
+            errors::UNAUTHORIZED_CLIENT,
+            Some("client is not registered for the device code grant".into()),
+        );
+    }
+    let Some(device_code) = req.device_code.as_deref() else {
+        return token_err(
+            StatusCode::BAD_REQUEST,
+            errors::INVALID_REQUEST,
+            Some("device_code is required".into()),
+        );
+    };
+    let row = match provider.device_codes.get(device_code).await {
+        Ok(Some(r)) if r.client_id == client.client_id => r,
+        Ok(_) => {
+            return token_err(
+                StatusCode::BAD_REQUEST,
+                errors::INVALID_GRANT,
+                Some("device_code is unknown".into()),
+            );
+        }
+        Err(e) => {
+            return token_err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                errors::SERVER_ERROR,
+                Some(format!("device_code lookup: {e}")),
+            );
+        }
+    };
+
+    let now = now_secs();
+    let (code, desc) = match device::poll_outcome(&row, now) {
+        PollOutcome::Pending { interval } | PollOutcome::SlowDown { interval } => {
+            if let Err(e) = provider
+                .device_codes
+                .record_poll(device_code, now, interval)
+                .await
+            {
+                return token_err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    errors::SERVER_ERROR,
+                    Some(format!("record poll: {e}")),
+                );
+            }
+            if interval > row.interval {
+                (errors::SLOW_DOWN, format!("poll at most every {interval}s"))
+            } else {
+                (
+                    errors::AUTHORIZATION_PENDING,
+                    "the user has not approved this request yet".to_string(),
+                )
+            }
+        }
+        PollOutcome::Denied => (errors::ACCESS_DENIED, "the user denied this request".into()),
+        PollOutcome::Expired => (errors::EXPIRED_TOKEN, "device_code expired".into()),
+        PollOutcome::Consumed => (errors::INVALID_GRANT, "device_code already used".into()),
+        PollOutcome::Approved => {
+            return match provider.device_codes.consume(device_code).await {
+                Ok(Some(DeviceCodeRow {
+                    user_id: Some(user_id),
+                    scopes,
+                    ..
+                })) => issue_token_pair(ctx, client, &user_id, &scopes, None, &[], None).await,
+                Ok(_) => token_err(
+                    StatusCode::BAD_REQUEST,
+                    errors::INVALID_GRANT,
+                    Some("device_code already used".into()),
+                ),
+                Err(e) => token_err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    errors::SERVER_ERROR,
+                    Some(format!("consume device_code: {e}")),
+                ),
+            };
+        }
+    };
+    token_err(StatusCode::BAD_REQUEST, code, Some(desc))
 }
 
 /// Mint id_token + access_token + refresh_token (when `offline_access`
 /// or refresh-token grant in the client's allow-list) and record the
 /// SSO session row. Common path for both `authorization_code` and
-/// `refresh_token` grants.
+/// `refresh_token` grants. `org_id` (an org-scoped login) becomes a
+/// claim on both tokens and rides along on the refresh row.
 async fn issue_token_pair(
     ctx: &AuthCtx,
     client: &super::types::OidcClient,
     user_id: &str,
     scopes: &[String],
     nonce: Option<&str>,
+    amr: &[String],
+    org_id: Option<&str>,
 ) -> Response {
     let provider = match ctx.oidc_provider.as_ref() {
         Some(p) => p,
@@ -649,8 +1044,11 @@ async fn issue_token_pair(
         email_verified,
         display_name.as_deref(),
     );
-    let access_claims =
-        tok::build_access_token_claims(&provider.issuer, user_id, &client.client_id, &sid, scopes);
+    let id_claims = tok::with_org_claim(tok::with_authentication_claims(id_claims, amr), org_id);
+    let access_claims = tok::with_org_claim(
+        tok::build_access_token_claims(&provider.issuer, user_id, &client.client_id, &sid, scopes),
+        org_id,
+    );
 
     let jwt = match ctx.jwt.as_ref() {
         Some(j) => j,
@@ -689,7 +1087,8 @@ async fn issue_token_pair(
         client.allows_grant("refresh_token") || scopes.iter().any(|s| s == "offline_access");
     let refresh_token = if issue_refresh {
         let plaintext = tok::mint_refresh_token();
-        let row = tok::build_refresh_row(user_id, &client.client_id, scopes, &plaintext);
+        let mut row = tok::build_refresh_row(user_id, &client.client_id, scopes, &plaintext);
+        row.org_id = org_id.map(str::to_string);
         if let Err(e) = provider.refresh.create(&row).await {
             return token_err(
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -719,13 +1118,218 @@ async fn issue_token_pair(
         access_token,
         token_type: "Bearer",
         expires_in: tok::ACCESS_TOKEN_LIFETIME_SECS as i64,
-        id_token,
+        id_token: Some(id_token),
         refresh_token,
         scope: scopes.join(" "),
     };
     (StatusCode::OK, Json(response)).into_response()
 }
 
+// =====================================================================
+//   /device_authorization + /device
+// =====================================================================
+
+/// `POST /device_authorization` — RFC 8628 §3.1. Authenticates the
+/// client the same way `/token` does (public CLIs use `none`), mints a
+/// pending device code and returns the codes + verification URI.
+/// `scope` defaults to the client's registered scopes.
+pub async fn device_authorization_post(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Form(req): Form<DeviceAuthorizationRequest>,
+) -> Response {
+    let provider = match ctx.oidc_provider.as_ref() {
+        Some(p) => p,
+        None => return server_misconfigured("oidc_provider is not enabled"),
+    };
+    let synth = TokenRequest {
+        grant_type: device::DEVICE_CODE_GRANT_TYPE.to_string(),
+        client_id: req.client_id.clone(),
+        client_secret: req.client_secret.clone(),
+        client_assertion_type: req.client_assertion_type.clone(),
+        client_assertion: req.client_assertion.clone(),
+        ..Default::default()
+    };
+    let client = match authenticate_client(&ctx, &headers, &synth).await {
+        Ok(c) => c,
+        Err((status, body)) => return (status, Json(body)).into_response(),
+    };
+    if !client.allows_grant(device::DEVICE_CODE_GRANT_TYPE) {
+        return token_err(
+            StatusCode::BAD_REQUEST,
+            errors::UNAUTHORIZED_CLIENT,
+            Some("client is not registered for the device code grant".into()),
+        );
+    }
+    let scopes: Vec<String> = match req.scope.as_deref().map(str::split_whitespace) {
+        Some(requested) => requested.map(str::to_string).collect(),
+        None => client.default_scopes.clone(),
+    };
+    let code = device::build_device_code(&client.client_id, scopes);
+    if let Err(e) = provider.device_codes.create(&code).await {
+        return token_err(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            errors::SERVER_ERROR,
+            Some(format!("persist device_code: {e}")),
+        );
+    }
+    (
+        StatusCode::OK,
+        Json(DeviceAuthorizationResponse::new(&provider.issuer, &code)),
+    )
+        .into_response()
+}
+
+/// `GET /device` — the verification page. Signs the user in first
+/// (returning here afterwards), then either asks for the user code or,
+/// when `verification_uri_complete` carried one, shows the approval
+/// page for that request.
+pub async fn device_get(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Query(q): Query<DeviceQuery>,
+) -> Response {
+    let provider = match ctx.oidc_provider.as_ref() {
+        Some(p) => p,
+        None => return server_misconfigured("oidc_provider is not enabled"),
+    };
+    let Some(session) = live_session(&ctx, &headers).await else {
+        let mut here = format!("{}/device", provider.issuer);
+        if let Some(code) = q.user_code.as_deref().filter(|c| !c.is_empty()) {
+            here.push_str(&format!("?user_code={}", url_encode(code)));
+        }
+        return Redirect::to(&authz::return_to_for(&here)).into_response();
+    };
+    let Some(input) = q.user_code.as_deref().filter(|c| !c.trim().is_empty()) else {
+        return device_entry(provider, StatusCode::OK, None);
+    };
+    let row = match pending_device_code(provider, input).await {
+        Ok(row) => row,
+        Err(resp) => return resp,
+    };
+    let client_name = match provider.clients.get(&row.client_id).await {
+        Ok(Some(c)) => c.name,
+        _ => row.client_id.clone(),
+    };
+    let page = DeviceVerifyPage {
+        client_name: &client_name,
+        issuer: &provider.issuer,
+        user_code: &device::format_user_code(&row.user_code),
+        scopes: &row.scopes,
+        csrf_token: &session.csrf_token,
+    };
+    Html(device::render_html(&page)).into_response()
+}
+
+/// `POST /device` — the user clicked Allow / Deny. CSRF is anchored on
+/// the session like the consent POST; Allow also records the consent
+/// grant so a later `/authorize` for the same scopes skips the prompt.
+pub async fn device_post(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Form(submission): Form<DeviceSubmission>,
+) -> Response {
+    let provider = match ctx.oidc_provider.as_ref() {
+        Some(p) => p,
+        None => return server_misconfigured("oidc_provider is not enabled"),
+    };
+    let Some(session) = live_session(&ctx, &headers).await else {
+        return error_html(StatusCode::UNAUTHORIZED, "no active session");
+    };
+    if session.csrf_token != submission.csrf_token {
+        return error_html(StatusCode::FORBIDDEN, "csrf mismatch");
+    }
+    let row = match pending_device_code(provider, &submission.user_code).await {
+        Ok(row) => row,
+        Err(resp) => return resp,
+    };
+    let approved = submission.allowed();
+    match provider
+        .device_codes
+        .decide(&row.user_code, &session.user_id, approved)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return device_entry(
+                provider,
+                StatusCode::BAD_REQUEST,
+                Some("That code has already been used."),
+            );
+        }
+        Err(e) => return server_error_html(&format!("record device decision: {e}")),
+    }
+    if approved {
+        let grant = ConsentGrant {
+            user_id: session.user_id.clone(),
+            client_id: row.client_id.clone(),
+            scopes: row.scopes.clone(),
+            granted_at: now_secs(),
+        };
+        if let Err(e) = provider.consents.upsert(&grant).await {
+            tracing::warn!(?e, "failed to record device consent — continuing");
+        }
+    }
+    let client_name = match provider.clients.get(&row.client_id).await {
+        Ok(Some(c)) => c.name,
+        _ => row.client_id.clone(),
+    };
+    let page = DeviceResultPage {
+        client_name: &client_name,
+        approved,
+    };
+    Html(device::render_html(&page)).into_response()
+}
+
+/// Resolve the assay session cookie to a live session row.
+async fn live_session(ctx: &AuthCtx, headers: &HeaderMap) -> Option<crate::store::Session> {
+    let sid = parse_cookie(headers, crate::session::SESSION_COOKIE)?;
+    match ctx.sessions.get(&sid).await {
+        Ok(Some(s)) if s.expires_at > now_secs() => Some(s),
+        _ => None,
+    }
+}
+
+/// Look up the still-pending, unexpired request behind a typed user
+/// code, or the code-entry page explaining why there isn't one.
+async fn pending_device_code(
+    provider: &super::OidcProviderConfig,
+    input: &str,
+) -> Result<DeviceCodeRow, Response> {
+    let unknown = || {
+        device_entry(
+            provider,
+            StatusCode::BAD_REQUEST,
+            Some("That code is not valid or has expired."),
+        )
+    };
+    let Some(user_code) = device::normalize_user_code(input) else {
+        return Err(unknown());
+    };
+    match provider.device_codes.get_by_user_code(&user_code).await {
+        Ok(Some(row))
+            if row.status == super::types::DeviceCodeStatus::Pending
+                && row.expires_at > now_secs() =>
+        {
+            Ok(row)
+        }
+        Ok(_) => Err(unknown()),
+        Err(e) => Err(server_error_html(&format!("device_code lookup: {e}"))),
+    }
+}
+
+fn device_entry(
+    provider: &super::OidcProviderConfig,
+    status: StatusCode,
+    error: Option<&str>,
+) -> Response {
+    let page = DeviceEntryPage {
+        issuer: &provider.issuer,
+        error,
+    };
+    (status, Html(device::render_html(&page))).into_response()
+}
+
 // =====================================================================
 //   /userinfo
 // =====================================================================
@@ -780,7 +1384,10 @@ pub async fn userinfo_get(State(ctx): State<AuthCtx>, headers: HeaderMap) -> Res
                 .into_response();
         }
     };
-    let claims = userinfo::build_userinfo(&user, &data.claims.claims.scopes());
+    let mut claims = userinfo::build_userinfo(&user, &data.claims.claims.scopes());
+    if let Some(org_id) = &data.claims.claims.org_id {
+        claims["org_id"] = serde_json::Value::String(org_id.clone());
+    }
     (StatusCode::OK, Json(claims)).into_response()
 }
 
@@ -814,6 +1421,10 @@ pub async fn introspect_post(
     // helper does the parsing work.
     let synth = TokenRequest {
         grant_type: String::new(),
+        client_id: body.client_id.clone(),
+        client_secret: body.client_secret.clone(),
+        client_assertion_type: body.client_assertion_type.clone(),
+        client_assertion: body.client_assertion.clone(),
         ..Default::default()
     };
     if authenticate_client(&ctx, &headers, &synth).await.is_err() {
@@ -833,11 +1444,13 @@ pub async fn introspect_post(
     if let Ok(data) = jwt.verify_provider_token::<ProviderAccessTokenClaims>(&body.token)
         && data.claims.is_provider_access_token()
     {
+        // Machine tokens have no end user to name.
+        let username = (!data.claims.is_client_token()).then(|| data.claims.claims.sub.clone());
         let claims = data.claims.claims;
         let resp = IntrospectResponse {
             active: true,
             client_id: Some(claims.client_id.clone()),
-            username: Some(claims.sub.clone()),
+            username,
             scope: Some(claims.scope.clone()),
             exp: Some(claims.exp),
             sub: Some(claims.sub.clone()),
@@ -1130,6 +1743,22 @@ pub async fn upstream_callback(
         }
     };
 
+    // An IdP-deprovisioned account stays locked out, however it signs in.
+    #[cfg(feature = "auth-scim")]
+    match crate::scim::is_deactivated(&ctx, &user.id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            let mut response = error_html(StatusCode::FORBIDDEN, "account disabled");
+            append_clear_binding_cookie(&mut response, &provider.public_url);
+            return response;
+        }
+        Err(e) => {
+            let mut response = server_error_html(&format!("scim lookup: {e}"));
+            append_clear_binding_cookie(&mut response, &provider.public_url);
+            return response;
+        }
+    }
+
     // Mint an assay session.
     let mgr = crate::session::SessionManager::with_default_duration(ctx.sessions.clone());
     let session = match mgr.create(&user.id).await {
@@ -1433,6 +2062,7 @@ mod tests {
             code_challenge_method: Some("S256".into()),
             prompt: None,
             max_age: None,
+            organization: None,
         };
         let encoded = encode_resume(&req);
         let decoded = decode_resume(&encoded).unwrap();
@@ -13,6 +13,16 @@ pub struct IntrospectRequest {
     pub token: String,
     #[serde(default)]
     pub token_type_hint: Option<String>,
+    /// Form-body client authentication — `client_secret_post` or
+    /// `private_key_jwt`. HTTP Basic rides the `Authorization` header.
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub client_assertion_type: Option<String>,
+    #[serde(default)]
+    pub client_assertion: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
@@ -4,12 +4,16 @@
 //! (`store/`, `zanzibar/`):
 //!
 //! - [`types`] — POD records mirroring the V4 DDL (clients, codes,
-//!   refresh, sessions, consent, upstream state).
+//!   refresh, sessions, consent, upstream state) plus V12 device codes.
 //! - [`store`] — trait + PG/SQLite implementations for each row table.
 //! - [`discovery`] — `/.well-known/openid-configuration`.
 //! - [`jwks`] — `/.well-known/jwks.json`.
 //! - [`authorize`] — `/authorize` request validation + code minting.
-//! - [`token`] — `/token` (auth code + refresh) + JWT claim builders.
+//! - [`device`] — RFC 8628 device authorization: code minting + the
+//!   askama-rendered user-code verification page.
+//! - [`token`] — `/token` (auth code, refresh, client credentials,
+//!   device code) + JWT claim builders.
+//! - [`client_assertion`] — `private_key_jwt` client authentication.
 //! - [`userinfo`] — `/userinfo` claim filtering.
 //! - [`consent`] — askama-rendered consent screen.
 //! - [`revoke`] — RFC 7009 token revocation.
@@ -36,7 +40,9 @@ pub mod admin;
 pub mod auth_params;
 pub mod authorize;
 pub mod binding;
+pub mod client_assertion;
 pub mod consent;
+pub mod device;
 pub mod discovery;
 pub mod federation;
 pub mod handlers;
@@ -51,24 +57,25 @@ pub mod upstreams_public;
 pub mod userinfo;
 
 pub use store::{
-    OidcClientStore, OidcCodeStore, OidcConsentStore, OidcRefreshStore, OidcSessionStore,
-    OidcUpstreamStateStore, OidcUpstreamStore,
+    OidcClientStore, OidcCodeStore, OidcConsentStore, OidcDeviceCodeStore, OidcRefreshStore,
+    OidcSessionStore, OidcUpstreamStateStore, OidcUpstreamStore,
 };
 pub use types::{
-    AuthorizationCode, ConsentGrant, OidcClient, OidcSession, RefreshToken, TokenAuthMethod,
-    UpstreamLoginState, UpstreamProvider,
+    AuthorizationCode, ConsentGrant, DeviceCode, DeviceCodeStatus, OidcClient, OidcSession,
+    RefreshToken, TokenAuthMethod, UpstreamLoginState, UpstreamProvider,
 };
 
 #[cfg(feature = "backend-postgres")]
 pub use store::{
     PostgresOidcClientStore, PostgresOidcCodeStore, PostgresOidcConsentStore,
-    PostgresOidcRefreshStore, PostgresOidcSessionStore, PostgresOidcUpstreamStateStore,
-    PostgresOidcUpstreamStore,
+    PostgresOidcDeviceCodeStore, PostgresOidcRefreshStore, PostgresOidcSessionStore,
+    PostgresOidcUpstreamStateStore, PostgresOidcUpstreamStore,
 };
 #[cfg(feature = "backend-sqlite")]
 pub use store::{
-    SqliteOidcClientStore, SqliteOidcCodeStore, SqliteOidcConsentStore, SqliteOidcRefreshStore,
-    SqliteOidcSessionStore, SqliteOidcUpstreamStateStore, SqliteOidcUpstreamStore,
+    SqliteOidcClientStore, SqliteOidcCodeStore, SqliteOidcConsentStore, SqliteOidcDeviceCodeStore,
+    SqliteOidcRefreshStore, SqliteOidcSessionStore, SqliteOidcUpstreamStateStore,
+    SqliteOidcUpstreamStore,
 };
 
 /// Source the JWKS endpoint reads from. PG / SQLite back the V4 jwks
@@ -94,6 +101,7 @@ pub struct OidcProviderConfig {
     pub clients: Arc<dyn OidcClientStore>,
     pub upstream: Arc<dyn OidcUpstreamStore>,
     pub codes: Arc<dyn OidcCodeStore>,
+    pub device_codes: Arc<dyn OidcDeviceCodeStore>,
     pub refresh: Arc<dyn OidcRefreshStore>,
     pub sessions: Arc<dyn OidcSessionStore>,
     pub consents: Arc<dyn OidcConsentStore>,
@@ -115,7 +123,7 @@ impl OidcProviderConfig {
     ///
     /// Long argument list is the cost of being explicit about which
     /// store backs each persistence concern (clients, upstream IdPs,
-    /// auth codes, refresh tokens, sessions, consents, upstream-flow
+    /// auth codes, device codes, refresh tokens, sessions, consents, upstream-flow
     /// state). A builder/struct refactor was considered but rejected
     /// for now — the engine binary is the only caller and a one-shot
     /// `OidcProviderConfig::new(...)` reads cleanly there.
@@ -126,6 +134,7 @@ impl OidcProviderConfig {
         clients: Arc<dyn OidcClientStore>,
         upstream: Arc<dyn OidcUpstreamStore>,
         codes: Arc<dyn OidcCodeStore>,
+        device_codes: Arc<dyn OidcDeviceCodeStore>,
         refresh: Arc<dyn OidcRefreshStore>,
         sessions: Arc<dyn OidcSessionStore>,
         consents: Arc<dyn OidcConsentStore>,
@@ -137,6 +146,7 @@ impl OidcProviderConfig {
             clients,
             upstream,
             codes,
+            device_codes,
             refresh,
             sessions,
             consents,
@@ -240,6 +250,14 @@ where
             get(consent_preview).post(handlers::consent_post),
         )
         .route("/token", post(handlers::token_post))
+        .route(
+            "/device_authorization",
+            post(handlers::device_authorization_post),
+        )
+        .route(
+            "/device",
+            get(handlers::device_get).post(handlers::device_post),
+        )
         .route(
             "/userinfo",
             get(handlers::userinfo_get).post(handlers::userinfo_get),
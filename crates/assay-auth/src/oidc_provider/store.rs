@@ -8,6 +8,8 @@
 //! Plus the concrete row stores:
 //!
 //! - [`OidcCodeStore`] — issue / consume `auth.oidc_authorization_codes`.
+//! - [`OidcDeviceCodeStore`] — RFC 8628 device authorization requests in
+//!   `auth.oidc_device_codes`.
 //! - [`OidcRefreshStore`] — write / verify / revoke
 //!   `auth.oidc_refresh_tokens`.
 //! - [`OidcSessionStore`] — `auth.oidc_sessions` lookups for the SSO
@@ -26,8 +28,8 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 
 use super::types::{
-    AuthorizationCode, ConsentGrant, OidcClient, OidcSession, RefreshToken, TokenAuthMethod,
-    UpstreamLoginState, UpstreamProvider,
+    AuthorizationCode, ConsentGrant, DeviceCode, DeviceCodeStatus, OidcClient, OidcSession,
+    RefreshToken, TokenAuthMethod, UpstreamLoginState, UpstreamProvider,
 };
 
 #[async_trait]
@@ -39,6 +41,17 @@ pub trait OidcClientStore: Send + Sync + 'static {
     async fn delete(&self, client_id: &str) -> Result<bool>;
     /// Replace the client_secret_hash. Returns Ok(false) if no row matched.
     async fn rotate_secret_hash(&self, client_id: &str, new_hash: &str) -> Result<bool>;
+    /// Record the `jti` of a `private_key_jwt` assertion `client_id`
+    /// presented, kept until `expires_at`. Returns Ok(false) when the
+    /// pair is already recorded — a replay. Rows expired by `now` are
+    /// dropped first.
+    async fn record_assertion_jti(
+        &self,
+        client_id: &str,
+        jti: &str,
+        expires_at: f64,
+        now: f64,
+    ) -> Result<bool>;
 }
 
 #[async_trait]
@@ -59,6 +72,23 @@ pub trait OidcCodeStore: Send + Sync + 'static {
     async fn consume(&self, code: &str) -> Result<Option<AuthorizationCode>>;
 }
 
+#[async_trait]
+pub trait OidcDeviceCodeStore: Send + Sync + 'static {
+    async fn create(&self, code: &DeviceCode) -> Result<()>;
+    async fn get(&self, device_code: &str) -> Result<Option<DeviceCode>>;
+    async fn get_by_user_code(&self, user_code: &str) -> Result<Option<DeviceCode>>;
+    /// Stamp a `/token` poll and store the (possibly bumped) interval.
+    /// Returns Ok(false) if no row matched.
+    async fn record_poll(&self, device_code: &str, polled_at: f64, interval: i64) -> Result<bool>;
+    /// Settle a pending request: `approved` (bound to `user_id`) or
+    /// `denied`. Returns Ok(false) when the row is unknown or no longer
+    /// pending, so a second decision can't overwrite the first.
+    async fn decide(&self, user_code: &str, user_id: &str, approve: bool) -> Result<bool>;
+    /// Atomic consume — `approved` → `consumed`. Returns the consumed
+    /// row; `None` means it was not approved (or was already redeemed).
+    async fn consume(&self, device_code: &str) -> Result<Option<DeviceCode>>;
+}
+
 #[async_trait]
 pub trait OidcRefreshStore: Send + Sync + 'static {
     async fn create(&self, token: &RefreshToken) -> Result<()>;
@@ -127,6 +157,10 @@ mod pg {
             response_types: parse_json_array(&row.get::<String, _>("response_types")),
             pkce_required: row.get("pkce_required"),
             backchannel_logout_uri: row.get("backchannel_logout_uri"),
+            audiences: parse_json_array(&row.get::<String, _>("audiences")),
+            jwks: row
+                .get::<Option<String>, _>("jwks")
+                .and_then(|s| serde_json::from_str(&s).ok()),
             created_at: row.get("created_at"),
         }
     }
@@ -154,8 +188,8 @@ mod pg {
                     (client_id, client_secret_hash, redirect_uris, name, logo_url,
                      token_endpoint_auth_method, default_scopes, require_consent,
                      grant_types, response_types, pkce_required,
-                     backchannel_logout_uri, created_at)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+                     backchannel_logout_uri, audiences, jwks, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
             )
             .bind(&c.client_id)
             .bind(&c.client_secret_hash)
@@ -169,6 +203,8 @@ mod pg {
             .bind(encode_json_array(&c.response_types))
             .bind(c.pkce_required)
             .bind(&c.backchannel_logout_uri)
+            .bind(encode_json_array(&c.audiences))
+            .bind(c.jwks.as_ref().map(|v| v.to_string()))
             .bind(c.created_at)
             .execute(&self.pool)
             .await
@@ -206,7 +242,9 @@ mod pg {
                     grant_types = $9,
                     response_types = $10,
                     pkce_required = $11,
-                    backchannel_logout_uri = $12
+                    backchannel_logout_uri = $12,
+                    audiences = $13,
+                    jwks = $14
                  WHERE client_id = $1",
             )
             .bind(&c.client_id)
@@ -221,6 +259,8 @@ mod pg {
             .bind(encode_json_array(&c.response_types))
             .bind(c.pkce_required)
             .bind(&c.backchannel_logout_uri)
+            .bind(encode_json_array(&c.audiences))
+            .bind(c.jwks.as_ref().map(|v| v.to_string()))
             .execute(&self.pool)
             .await
             .context("auth.oidc_clients update")?;
@@ -247,6 +287,31 @@ mod pg {
             .context("auth.oidc_clients rotate_secret_hash")?;
             Ok(r.rows_affected() > 0)
         }
+
+        async fn record_assertion_jti(
+            &self,
+            client_id: &str,
+            jti: &str,
+            expires_at: f64,
+            now: f64,
+        ) -> Result<bool> {
+            sqlx::query("DELETE FROM auth.oidc_client_assertions WHERE expires_at < $1")
+                .bind(now)
+                .execute(&self.pool)
+                .await
+                .context("auth.oidc_client_assertions prune")?;
+            let r = sqlx::query(
+                "INSERT INTO auth.oidc_client_assertions (client_id, jti, expires_at)
+                 VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            )
+            .bind(client_id)
+            .bind(jti)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_client_assertions insert")?;
+            Ok(r.rows_affected() > 0)
+        }
     }
 
     fn map_upstream_row(row: sqlx::postgres::PgRow) -> UpstreamProvider {
@@ -364,6 +429,8 @@ mod pg {
             issued_at: row.get("issued_at"),
             expires_at: row.get("expires_at"),
             consumed: row.get("consumed"),
+            amr: parse_json_array(&row.get::<String, _>("amr")),
+            org_id: row.get("org_id"),
         }
     }
 
@@ -387,8 +454,8 @@ mod pg {
                 "INSERT INTO auth.oidc_authorization_codes
                     (code, client_id, user_id, redirect_uri, scopes,
                      code_challenge, code_challenge_method, nonce, state,
-                     issued_at, expires_at, consumed)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                     issued_at, expires_at, consumed, amr, org_id)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
             )
             .bind(&c.code)
             .bind(&c.client_id)
@@ -402,6 +469,8 @@ mod pg {
             .bind(c.issued_at)
             .bind(c.expires_at)
             .bind(c.consumed)
+            .bind(encode_json_array(&c.amr))
+            .bind(&c.org_id)
             .execute(&self.pool)
             .await
             .context("auth.oidc_authorization_codes insert")?;
@@ -426,6 +495,135 @@ mod pg {
         }
     }
 
+    fn map_device_row(row: sqlx::postgres::PgRow) -> DeviceCode {
+        DeviceCode {
+            device_code: row.get("device_code"),
+            user_code: row.get("user_code"),
+            client_id: row.get("client_id"),
+            scopes: parse_json_array(&row.get::<String, _>("scopes")),
+            user_id: row.get("user_id"),
+            status: DeviceCodeStatus::parse(&row.get::<String, _>("status"))
+                .unwrap_or(DeviceCodeStatus::Denied),
+            interval: row.get("interval_secs"),
+            last_polled_at: row.get("last_polled_at"),
+            issued_at: row.get("issued_at"),
+            expires_at: row.get("expires_at"),
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct PostgresOidcDeviceCodeStore {
+        pool: PgPool,
+    }
+    impl PostgresOidcDeviceCodeStore {
+        pub fn new(pool: PgPool) -> Self {
+            Self { pool }
+        }
+        pub fn into_dyn(self) -> Arc<dyn OidcDeviceCodeStore> {
+            Arc::new(self)
+        }
+    }
+
+    #[async_trait]
+    impl OidcDeviceCodeStore for PostgresOidcDeviceCodeStore {
+        async fn create(&self, c: &DeviceCode) -> Result<()> {
+            sqlx::query(
+                "INSERT INTO auth.oidc_device_codes
+                    (device_code, user_code, client_id, scopes, user_id, status,
+                     interval_secs, last_polled_at, issued_at, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(&c.device_code)
+            .bind(&c.user_code)
+            .bind(&c.client_id)
+            .bind(encode_json_array(&c.scopes))
+            .bind(&c.user_id)
+            .bind(c.status.as_str())
+            .bind(c.interval)
+            .bind(c.last_polled_at)
+            .bind(c.issued_at)
+            .bind(c.expires_at)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_device_codes insert")?;
+            Ok(())
+        }
+
+        async fn get(&self, device_code: &str) -> Result<Option<DeviceCode>> {
+            let row = sqlx::query("SELECT * FROM auth.oidc_device_codes WHERE device_code = $1")
+                .bind(device_code)
+                .fetch_optional(&self.pool)
+                .await
+                .context("auth.oidc_device_codes get")?;
+            Ok(row.map(map_device_row))
+        }
+
+        async fn get_by_user_code(&self, user_code: &str) -> Result<Option<DeviceCode>> {
+            let row = sqlx::query("SELECT * FROM auth.oidc_device_codes WHERE user_code = $1")
+                .bind(user_code)
+                .fetch_optional(&self.pool)
+                .await
+                .context("auth.oidc_device_codes get_by_user_code")?;
+            Ok(row.map(map_device_row))
+        }
+
+        async fn record_poll(
+            &self,
+            device_code: &str,
+            polled_at: f64,
+            interval: i64,
+        ) -> Result<bool> {
+            let r = sqlx::query(
+                "UPDATE auth.oidc_device_codes
+                    SET last_polled_at = $2, interval_secs = $3
+                    WHERE device_code = $1",
+            )
+            .bind(device_code)
+            .bind(polled_at)
+            .bind(interval)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_device_codes record_poll")?;
+            Ok(r.rows_affected() > 0)
+        }
+
+        async fn decide(&self, user_code: &str, user_id: &str, approve: bool) -> Result<bool> {
+            let status = if approve {
+                DeviceCodeStatus::Approved
+            } else {
+                DeviceCodeStatus::Denied
+            };
+            let r = sqlx::query(
+                "UPDATE auth.oidc_device_codes
+                    SET status = $2, user_id = $3
+                    WHERE user_code = $1 AND status = 'pending'",
+            )
+            .bind(user_code)
+            .bind(status.as_str())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_device_codes decide")?;
+            Ok(r.rows_affected() > 0)
+        }
+
+        async fn consume(&self, device_code: &str) -> Result<Option<DeviceCode>> {
+            // The `status = 'approved'` predicate is the single-use
+            // guarantee; RETURNING hands back the row in the same trip.
+            let row = sqlx::query(
+                "UPDATE auth.oidc_device_codes
+                    SET status = 'consumed'
+                    WHERE device_code = $1 AND status = 'approved'
+                    RETURNING *",
+            )
+            .bind(device_code)
+            .fetch_optional(&self.pool)
+            .await
+            .context("auth.oidc_device_codes consume")?;
+            Ok(row.map(map_device_row))
+        }
+    }
+
     fn map_refresh_row(row: sqlx::postgres::PgRow) -> RefreshToken {
         RefreshToken {
             token_hash: row.get("token_hash"),
@@ -435,6 +633,7 @@ mod pg {
             issued_at: row.get("issued_at"),
             expires_at: row.get("expires_at"),
             revoked: row.get("revoked"),
+            org_id: row.get("org_id"),
         }
     }
 
@@ -457,8 +656,8 @@ mod pg {
             sqlx::query(
                 "INSERT INTO auth.oidc_refresh_tokens
                     (token_hash, client_id, user_id, scopes,
-                     issued_at, expires_at, revoked)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                     issued_at, expires_at, revoked, org_id)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
             )
             .bind(&t.token_hash)
             .bind(&t.client_id)
@@ -467,6 +666,7 @@ mod pg {
             .bind(t.issued_at)
             .bind(t.expires_at)
             .bind(t.revoked)
+            .bind(&t.org_id)
             .execute(&self.pool)
             .await
             .context("auth.oidc_refresh_tokens insert")?;
@@ -717,8 +917,8 @@ mod pg {
 #[cfg(feature = "backend-postgres")]
 pub use pg::{
     PostgresOidcClientStore, PostgresOidcCodeStore, PostgresOidcConsentStore,
-    PostgresOidcRefreshStore, PostgresOidcSessionStore, PostgresOidcUpstreamStateStore,
-    PostgresOidcUpstreamStore,
+    PostgresOidcDeviceCodeStore, PostgresOidcRefreshStore, PostgresOidcSessionStore,
+    PostgresOidcUpstreamStateStore, PostgresOidcUpstreamStore,
 };
 
 // =====================================================================
@@ -759,6 +959,10 @@ mod sqlite_impl {
             response_types: parse_json_array(&row.get::<String, _>("response_types")),
             pkce_required: ub(row.get("pkce_required")),
             backchannel_logout_uri: row.get("backchannel_logout_uri"),
+            audiences: parse_json_array(&row.get::<String, _>("audiences")),
+            jwks: row
+                .get::<Option<String>, _>("jwks")
+                .and_then(|s| serde_json::from_str(&s).ok()),
             created_at: row.get("created_at"),
         }
     }
@@ -784,8 +988,8 @@ mod sqlite_impl {
                     (client_id, client_secret_hash, redirect_uris, name, logo_url,
                      token_endpoint_auth_method, default_scopes, require_consent,
                      grant_types, response_types, pkce_required,
-                     backchannel_logout_uri, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                     backchannel_logout_uri, audiences, jwks, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(&c.client_id)
             .bind(&c.client_secret_hash)
@@ -799,6 +1003,8 @@ mod sqlite_impl {
             .bind(encode_json_array(&c.response_types))
             .bind(b(c.pkce_required))
             .bind(&c.backchannel_logout_uri)
+            .bind(encode_json_array(&c.audiences))
+            .bind(c.jwks.as_ref().map(|v| v.to_string()))
             .bind(c.created_at)
             .execute(&self.pool)
             .await
@@ -836,7 +1042,9 @@ mod sqlite_impl {
                     grant_types = ?,
                     response_types = ?,
                     pkce_required = ?,
-                    backchannel_logout_uri = ?
+                    backchannel_logout_uri = ?,
+                    audiences = ?,
+                    jwks = ?
                  WHERE client_id = ?",
             )
             .bind(&c.client_secret_hash)
@@ -850,6 +1058,8 @@ mod sqlite_impl {
             .bind(encode_json_array(&c.response_types))
             .bind(b(c.pkce_required))
             .bind(&c.backchannel_logout_uri)
+            .bind(encode_json_array(&c.audiences))
+            .bind(c.jwks.as_ref().map(|v| v.to_string()))
             .bind(&c.client_id)
             .execute(&self.pool)
             .await
@@ -877,6 +1087,31 @@ mod sqlite_impl {
             .context("auth.oidc_clients rotate_secret_hash")?;
             Ok(r.rows_affected() > 0)
         }
+
+        async fn record_assertion_jti(
+            &self,
+            client_id: &str,
+            jti: &str,
+            expires_at: f64,
+            now: f64,
+        ) -> Result<bool> {
+            sqlx::query("DELETE FROM auth.oidc_client_assertions WHERE expires_at < ?")
+                .bind(now)
+                .execute(&self.pool)
+                .await
+                .context("auth.oidc_client_assertions prune")?;
+            let r = sqlx::query(
+                "INSERT OR IGNORE INTO auth.oidc_client_assertions (client_id, jti, expires_at)
+                 VALUES (?, ?, ?)",
+            )
+            .bind(client_id)
+            .bind(jti)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_client_assertions insert")?;
+            Ok(r.rows_affected() > 0)
+        }
     }
 
     fn map_upstream_row(row: sqlx::sqlite::SqliteRow) -> UpstreamProvider {
@@ -993,6 +1228,8 @@ mod sqlite_impl {
             issued_at: row.get("issued_at"),
             expires_at: row.get("expires_at"),
             consumed: ub(row.get("consumed")),
+            amr: parse_json_array(&row.get::<String, _>("amr")),
+            org_id: row.get("org_id"),
         }
     }
 
@@ -1016,8 +1253,8 @@ mod sqlite_impl {
                 "INSERT INTO auth.oidc_authorization_codes
                     (code, client_id, user_id, redirect_uri, scopes,
                      code_challenge, code_challenge_method, nonce, state,
-                     issued_at, expires_at, consumed)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                     issued_at, expires_at, consumed, amr, org_id)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(&c.code)
             .bind(&c.client_id)
@@ -1031,6 +1268,8 @@ mod sqlite_impl {
             .bind(c.issued_at)
             .bind(c.expires_at)
             .bind(b(c.consumed))
+            .bind(encode_json_array(&c.amr))
+            .bind(&c.org_id)
             .execute(&self.pool)
             .await
             .context("auth.oidc_authorization_codes insert")?;
@@ -1071,6 +1310,153 @@ mod sqlite_impl {
         }
     }
 
+    fn map_device_row(row: sqlx::sqlite::SqliteRow) -> DeviceCode {
+        DeviceCode {
+            device_code: row.get("device_code"),
+            user_code: row.get("user_code"),
+            client_id: row.get("client_id"),
+            scopes: parse_json_array(&row.get::<String, _>("scopes")),
+            user_id: row.get("user_id"),
+            status: DeviceCodeStatus::parse(&row.get::<String, _>("status"))
+                .unwrap_or(DeviceCodeStatus::Denied),
+            interval: row.get("interval_secs"),
+            last_polled_at: row.get("last_polled_at"),
+            issued_at: row.get("issued_at"),
+            expires_at: row.get("expires_at"),
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct SqliteOidcDeviceCodeStore {
+        pool: SqlitePool,
+    }
+    impl SqliteOidcDeviceCodeStore {
+        pub fn new(pool: SqlitePool) -> Self {
+            Self { pool }
+        }
+        pub fn into_dyn(self) -> Arc<dyn OidcDeviceCodeStore> {
+            Arc::new(self)
+        }
+    }
+
+    #[async_trait]
+    impl OidcDeviceCodeStore for SqliteOidcDeviceCodeStore {
+        async fn create(&self, c: &DeviceCode) -> Result<()> {
+            sqlx::query(
+                "INSERT INTO auth.oidc_device_codes
+                    (device_code, user_code, client_id, scopes, user_id, status,
+                     interval_secs, last_polled_at, issued_at, expires_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&c.device_code)
+            .bind(&c.user_code)
+            .bind(&c.client_id)
+            .bind(encode_json_array(&c.scopes))
+            .bind(&c.user_id)
+            .bind(c.status.as_str())
+            .bind(c.interval)
+            .bind(c.last_polled_at)
+            .bind(c.issued_at)
+            .bind(c.expires_at)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_device_codes insert")?;
+            Ok(())
+        }
+
+        async fn get(&self, device_code: &str) -> Result<Option<DeviceCode>> {
+            let row = sqlx::query("SELECT * FROM auth.oidc_device_codes WHERE device_code = ?")
+                .bind(device_code)
+                .fetch_optional(&self.pool)
+                .await
+                .context("auth.oidc_device_codes get")?;
+            Ok(row.map(map_device_row))
+        }
+
+        async fn get_by_user_code(&self, user_code: &str) -> Result<Option<DeviceCode>> {
+            let row = sqlx::query("SELECT * FROM auth.oidc_device_codes WHERE user_code = ?")
+                .bind(user_code)
+                .fetch_optional(&self.pool)
+                .await
+                .context("auth.oidc_device_codes get_by_user_code")?;
+            Ok(row.map(map_device_row))
+        }
+
+        async fn record_poll(
+            &self,
+            device_code: &str,
+            polled_at: f64,
+            interval: i64,
+        ) -> Result<bool> {
+            let r = sqlx::query(
+                "UPDATE auth.oidc_device_codes
+                    SET last_polled_at = ?, interval_secs = ?
+                    WHERE device_code = ?",
+            )
+            .bind(polled_at)
+            .bind(interval)
+            .bind(device_code)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_device_codes record_poll")?;
+            Ok(r.rows_affected() > 0)
+        }
+
+        async fn decide(&self, user_code: &str, user_id: &str, approve: bool) -> Result<bool> {
+            let status = if approve {
+                DeviceCodeStatus::Approved
+            } else {
+                DeviceCodeStatus::Denied
+            };
+            let r = sqlx::query(
+                "UPDATE auth.oidc_device_codes
+                    SET status = ?, user_id = ?
+                    WHERE user_code = ? AND status = 'pending'",
+            )
+            .bind(status.as_str())
+            .bind(user_id)
+            .bind(user_code)
+            .execute(&self.pool)
+            .await
+            .context("auth.oidc_device_codes decide")?;
+            Ok(r.rows_affected() > 0)
+        }
+
+        async fn consume(&self, device_code: &str) -> Result<Option<DeviceCode>> {
+            // Same load + conditional-update shape as the authorization
+            // code consume.
+            let mut tx = self.pool.begin().await.context("begin consume tx")?;
+            let row = sqlx::query(
+                "SELECT * FROM auth.oidc_device_codes
+                 WHERE device_code = ? AND status = 'approved'",
+            )
+            .bind(device_code)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("auth.oidc_device_codes consume select")?;
+            let Some(row) = row else {
+                tx.rollback().await.ok();
+                return Ok(None);
+            };
+            let result = sqlx::query(
+                "UPDATE auth.oidc_device_codes SET status = 'consumed' \
+                 WHERE device_code = ? AND status = 'approved'",
+            )
+            .bind(device_code)
+            .execute(&mut *tx)
+            .await
+            .context("auth.oidc_device_codes consume update")?;
+            if result.rows_affected() == 0 {
+                tx.rollback().await.ok();
+                return Ok(None);
+            }
+            tx.commit().await.context("commit consume tx")?;
+            let mut code = map_device_row(row);
+            code.status = DeviceCodeStatus::Consumed;
+            Ok(Some(code))
+        }
+    }
+
     fn map_refresh_row(row: sqlx::sqlite::SqliteRow) -> RefreshToken {
         RefreshToken {
             token_hash: row.get("token_hash"),
@@ -1080,6 +1466,7 @@ mod sqlite_impl {
             issued_at: row.get("issued_at"),
             expires_at: row.get("expires_at"),
             revoked: ub(row.get("revoked")),
+            org_id: row.get("org_id"),
         }
     }
 
@@ -1102,8 +1489,8 @@ mod sqlite_impl {
             sqlx::query(
                 "INSERT INTO auth.oidc_refresh_tokens
                     (token_hash, client_id, user_id, scopes,
-                     issued_at, expires_at, revoked)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                     issued_at, expires_at, revoked, org_id)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(&t.token_hash)
             .bind(&t.client_id)
@@ -1112,6 +1499,7 @@ mod sqlite_impl {
             .bind(t.issued_at)
             .bind(t.expires_at)
             .bind(b(t.revoked))
+            .bind(&t.org_id)
             .execute(&self.pool)
             .await
             .context("auth.oidc_refresh_tokens insert")?;
@@ -1369,6 +1757,7 @@ mod sqlite_impl {
 
 #[cfg(feature = "backend-sqlite")]
 pub use sqlite_impl::{
-    SqliteOidcClientStore, SqliteOidcCodeStore, SqliteOidcConsentStore, SqliteOidcRefreshStore,
-    SqliteOidcSessionStore, SqliteOidcUpstreamStateStore, SqliteOidcUpstreamStore,
+    SqliteOidcClientStore, SqliteOidcCodeStore, SqliteOidcConsentStore, SqliteOidcDeviceCodeStore,
+    SqliteOidcRefreshStore, SqliteOidcSessionStore, SqliteOidcUpstreamStateStore,
+    SqliteOidcUpstreamStore,
 };
@@ -1,10 +1,12 @@
 //! `/token` — OIDC token endpoint.
 //!
 //! Implements the `authorization_code` and `refresh_token` grants per
-//! OIDC Core §3.1.3 / §12. The `client_credentials` grant is part of
-//! the V1 surface (the trait shape leaves room) but the v0.2.0 plan
-//! defers the implementation; the handler returns
-//! `unsupported_grant_type` until phase 8 wires it.
+//! OIDC Core §3.1.3 / §12, plus `client_credentials` (RFC 6749 §4.4)
+//! for service-to-service calls: a confidential client trades its own
+//! credentials for an access token whose `sub` is the client itself,
+//! scoped to the client's registered scopes and audiences. No id_token
+//! or refresh_token accompanies a machine token. Devices redeem an
+//! approved RFC 8628 `device_code` here too (see [`super::device`]).
 //!
 //! Bearer tokens (id_token / access_token) are EdDSA-signed JWTs minted
 //! through the existing [`crate::jwt::JwtConfig`]. Refresh tokens are
@@ -25,6 +27,11 @@ pub const ACCESS_TOKEN_LIFETIME_SECS: f64 = 3600.0;
 /// Default refresh_token lifetime — 30 days.
 pub const REFRESH_TOKEN_LIFETIME_SECS: f64 = 60.0 * 60.0 * 24.0 * 30.0;
 
+/// `acr` for a login that passed a second factor (`amr` has `mfa`).
+pub const ACR_MULTI_FACTOR: &str = "urn:assay:acr:mfa";
+/// `acr` for any other recorded login.
+pub const ACR_SINGLE_FACTOR: &str = "urn:assay:acr:sfa";
+
 /// Form-encoded request body for `POST /token`.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct TokenRequest {
@@ -36,6 +43,15 @@ pub struct TokenRequest {
     pub code_verifier: Option<String>,
     pub refresh_token: Option<String>,
     pub scope: Option<String>,
+    /// `client_credentials` only — the resource server the token is
+    /// for. Must be one of the client's registered audiences.
+    pub audience: Option<String>,
+    /// `private_key_jwt` client authentication (RFC 7523 §2.2).
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
+    /// Device grant only — the `device_code` from
+    /// `/device_authorization`.
+    pub device_code: Option<String>,
 }
 
 /// Successful response body. `expires_in` is seconds-from-now matching
@@ -45,7 +61,9 @@ pub struct TokenResponse {
     pub access_token: String,
     pub token_type: &'static str,
     pub expires_in: i64,
-    pub id_token: String,
+    /// Absent for `client_credentials` — there is no end user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refresh_token: Option<String>,
     pub scope: String,
@@ -67,6 +85,13 @@ pub mod errors {
     pub const UNAUTHORIZED_CLIENT: &str = "unauthorized_client";
     pub const UNSUPPORTED_GRANT_TYPE: &str = "unsupported_grant_type";
     pub const INVALID_SCOPE: &str = "invalid_scope";
+    /// RFC 8707 §2 — the requested audience isn't allowed.
+    pub const INVALID_TARGET: &str = "invalid_target";
+    /// RFC 8628 §3.5 device-grant poll answers.
+    pub const AUTHORIZATION_PENDING: &str = "authorization_pending";
+    pub const SLOW_DOWN: &str = "slow_down";
+    pub const ACCESS_DENIED: &str = "access_denied";
+    pub const EXPIRED_TOKEN: &str = "expired_token";
     pub const SERVER_ERROR: &str = "server_error";
 }
 
@@ -132,6 +157,7 @@ pub fn build_refresh_row(
         issued_at: now,
         expires_at: now + REFRESH_TOKEN_LIFETIME_SECS,
         revoked: false,
+        org_id: None,
     }
 }
 
@@ -184,6 +210,36 @@ pub fn build_id_token_claims(
     claims
 }
 
+/// Add `amr` + `acr` to id_token claims for the session's recorded
+/// authentication methods. Codes minted before sessions recorded `amr`
+/// (and the refresh / device grants, which carry none) get neither
+/// claim rather than a guessed one.
+pub fn with_authentication_claims(
+    mut claims: serde_json::Value,
+    amr: &[String],
+) -> serde_json::Value {
+    if amr.is_empty() {
+        return claims;
+    }
+    let acr = if amr.iter().any(|m| m == "mfa") {
+        ACR_MULTI_FACTOR
+    } else {
+        ACR_SINGLE_FACTOR
+    };
+    claims["amr"] = serde_json::json!(amr);
+    claims["acr"] = serde_json::Value::String(acr.to_string());
+    claims
+}
+
+/// Add the `org_id` claim for an organization-scoped login. Used on
+/// both the id_token and the access_token.
+pub fn with_org_claim(mut claims: serde_json::Value, org_id: Option<&str>) -> serde_json::Value {
+    if let Some(org_id) = org_id {
+        claims["org_id"] = serde_json::Value::String(org_id.to_string());
+    }
+    claims
+}
+
 /// Build the JWT claim object for an access_token. Carries `client_id`
 /// and `scope` so resource servers can authorize without an extra
 /// lookup. Same `sid` as the id_token so revocation can fan out.
@@ -208,6 +264,30 @@ pub fn build_access_token_claims(
     })
 }
 
+/// Build the JWT claim object for a `client_credentials` access
+/// token. `sub` and `client_id` are both the client; `aud` is the
+/// requested resource server (or the client itself). Shares
+/// `token_use = "access"` with user tokens so `/introspect` treats
+/// both alike.
+pub fn build_client_credentials_claims(
+    issuer: &str,
+    client_id: &str,
+    audience: &str,
+    scopes: &[String],
+) -> serde_json::Value {
+    let now = now_secs();
+    serde_json::json!({
+        "iss": issuer,
+        "sub": client_id,
+        "aud": audience,
+        "client_id": client_id,
+        "iat": now as i64,
+        "exp": (now + ACCESS_TOKEN_LIFETIME_SECS) as i64,
+        "scope": scopes.join(" "),
+        "token_use": "access",
+    })
+}
+
 /// Mint a stable opaque session id for the SSO session row (`sid`
 /// claim).
 pub fn mint_sid() -> String {
@@ -227,6 +307,18 @@ fn now_secs() -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn authentication_claims_follow_amr() {
+        let base = serde_json::json!({"sub": "u"});
+        assert_eq!(with_authentication_claims(base.clone(), &[]), base);
+        let pwd = with_authentication_claims(base.clone(), &["pwd".to_string()]);
+        assert_eq!(pwd["amr"], serde_json::json!(["pwd"]));
+        assert_eq!(pwd["acr"], ACR_SINGLE_FACTOR);
+        let amr = ["pwd", "otp", "mfa"].map(str::to_string);
+        let mfa = with_authentication_claims(base, &amr);
+        assert_eq!(mfa["acr"], ACR_MULTI_FACTOR);
+    }
+
     #[test]
     fn pkce_s256_round_trip() {
         let verifier = "test-verifier-with-some-entropy-bytes";
@@ -347,6 +439,18 @@ mod tests {
         assert_eq!(v["token_use"], "access");
     }
 
+    #[test]
+    fn client_credentials_claims_name_the_client_as_subject() {
+        let scopes = vec!["reports:read".to_string()];
+        let v = build_client_credentials_claims("https://idp", "svc", "https://api", &scopes);
+        assert_eq!(v["sub"], "svc");
+        assert_eq!(v["client_id"], "svc");
+        assert_eq!(v["aud"], "https://api");
+        assert_eq!(v["scope"], "reports:read");
+        assert_eq!(v["token_use"], "access");
+        assert!(v.get("sid").is_none());
+    }
+
     #[test]
     fn mint_sid_starts_with_marker() {
         let s = mint_sid();
@@ -20,8 +20,8 @@ pub enum TokenAuthMethod {
     ClientSecretPost,
     /// No client authentication — public client; PKCE is mandatory.
     None,
-    /// JWT bearer assertion signed with the client's registered key.
-    /// Reserved for v0.2.0+; not exercised by the v0.2.0 test suite.
+    /// JWT bearer assertion (RFC 7523) signed with a key from the
+    /// client's registered [`OidcClient::jwks`].
     PrivateKeyJwt,
 }
 
@@ -64,6 +64,15 @@ pub struct OidcClient {
     pub response_types: Vec<String>,
     pub pkce_required: bool,
     pub backchannel_logout_uri: Option<String>,
+    /// Resource servers this client may name as the `audience` of a
+    /// `client_credentials` token. Tokens requested without one are
+    /// minted for the client itself.
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// Public JWK Set verifying `private_key_jwt` client assertions.
+    /// Required when `token_endpoint_auth_method` is `private_key_jwt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwks: Option<serde_json::Value>,
     pub created_at: f64,
 }
 
@@ -88,6 +97,8 @@ impl OidcClient {
             response_types: vec!["code".to_string()],
             pkce_required: true,
             backchannel_logout_uri: None,
+            audiences: Vec::new(),
+            jwks: None,
             created_at,
         }
     }
@@ -103,6 +114,17 @@ impl OidcClient {
     pub fn allows_grant(&self, grant: &str) -> bool {
         self.grant_types.iter().any(|g| g == grant)
     }
+
+    /// Whether `scope` is among the client's registered scopes. The
+    /// `client_credentials` grant only mints scopes from this list.
+    pub fn allows_scope(&self, scope: &str) -> bool {
+        self.default_scopes.iter().any(|s| s == scope)
+    }
+
+    /// Whether `audience` is in the client's registered audiences.
+    pub fn allows_audience(&self, audience: &str) -> bool {
+        self.audiences.iter().any(|a| a == audience)
+    }
 }
 
 /// Federated upstream provider — one row in `auth.upstream_providers`.
@@ -145,6 +167,67 @@ pub struct AuthorizationCode {
     pub issued_at: f64,
     pub expires_at: f64,
     pub consumed: bool,
+    /// `amr` of the session that authorized the code, replayed into
+    /// the ID token.
+    #[serde(default)]
+    pub amr: Vec<String>,
+    /// Organization the login was scoped to (`organization` authorize
+    /// parameter); becomes the tokens' `org_id` claim.
+    #[serde(default)]
+    pub org_id: Option<String>,
+}
+
+/// Lifecycle of a device authorization request (RFC 8628). A row starts
+/// `Pending`, the user flips it to `Approved` / `Denied` on the
+/// verification page, and a successful `/token` poll moves an approved
+/// row to `Consumed` so its device_code is single-use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceCodeStatus {
+    Pending,
+    Approved,
+    Denied,
+    Consumed,
+}
+
+impl DeviceCodeStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Denied => "denied",
+            Self::Consumed => "consumed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "denied" => Some(Self::Denied),
+            "consumed" => Some(Self::Consumed),
+            _ => None,
+        }
+    }
+}
+
+/// One device authorization request — row in
+/// `auth.oidc_device_codes`. `device_code` is the secret the device
+/// polls `/token` with; `user_code` is the short code the user types on
+/// the verification page. `user_id` is set once the user approves.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    pub user_id: Option<String>,
+    pub status: DeviceCodeStatus,
+    /// Minimum seconds between polls; bumped on every `slow_down`.
+    pub interval: i64,
+    pub last_polled_at: Option<f64>,
+    pub issued_at: f64,
+    pub expires_at: f64,
 }
 
 /// One issued refresh token — row in `auth.oidc_refresh_tokens`.
@@ -159,6 +242,10 @@ pub struct RefreshToken {
     pub issued_at: f64,
     pub expires_at: f64,
     pub revoked: bool,
+    /// Organization the originating login was scoped to; carried
+    /// across rotations so refreshed tokens keep their `org_id` claim.
+    #[serde(default)]
+    pub org_id: Option<String>,
 }
 
 /// One SSO session row — `auth.oidc_sessions`. The `sid` matches the
@@ -224,6 +311,19 @@ mod tests {
         assert_eq!(TokenAuthMethod::parse("garbage"), None);
     }
 
+    #[test]
+    fn device_code_status_round_trip() {
+        for s in [
+            DeviceCodeStatus::Pending,
+            DeviceCodeStatus::Approved,
+            DeviceCodeStatus::Denied,
+            DeviceCodeStatus::Consumed,
+        ] {
+            assert_eq!(DeviceCodeStatus::parse(s.as_str()), Some(s));
+        }
+        assert_eq!(DeviceCodeStatus::parse("expired"), None);
+    }
+
     #[test]
     fn oidc_client_new_defaults_to_confidential_pkce() {
         let c = OidcClient::new("c1", "App", 1.0);
@@ -237,6 +337,9 @@ mod tests {
         assert!(c.allows_grant("authorization_code"));
         assert!(c.allows_grant("refresh_token"));
         assert!(!c.allows_grant("client_credentials"));
+        assert!(c.allows_scope("openid"));
+        assert!(!c.allows_scope("admin"));
+        assert!(!c.allows_audience("https://api.example.com"));
     }
 
     #[test]
@@ -38,6 +38,9 @@ pub struct AccessTokenClaims {
     pub client_id: String,
     #[serde(default)]
     pub sid: String,
+    /// Organization the login was scoped to, echoed by `/userinfo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
 }
 
 impl AccessTokenClaims {
@@ -61,11 +64,18 @@ pub(crate) struct ProviderAccessTokenClaims {
 
 impl ProviderAccessTokenClaims {
     /// Whether these signed claims describe an access token minted for
-    /// the same client named by its audience.
+    /// the same client named by its audience — or, for a
+    /// `client_credentials` token, for the client as its own subject.
     pub fn is_provider_access_token(&self) -> bool {
         self.token_use == "access"
             && !self.claims.client_id.is_empty()
-            && self.claims.aud == self.claims.client_id
+            && (self.claims.aud == self.claims.client_id
+                || self.claims.sub == self.claims.client_id)
+    }
+
+    /// Whether this is a `client_credentials` token (no end user).
+    pub fn is_client_token(&self) -> bool {
+        self.claims.sub == self.claims.client_id
     }
 }
 
@@ -149,6 +159,7 @@ mod tests {
             scope: "openid email".into(),
             client_id: "c".into(),
             sid: "s".into(),
+            org_id: None,
         };
         assert_eq!(c.scopes(), vec!["openid".to_string(), "email".to_string()]);
     }
@@ -164,6 +175,7 @@ mod tests {
                 scope: "openid email".into(),
                 client_id: "agentkit-pages".into(),
                 sid: "s".into(),
+                org_id: None,
             },
             token_use: "access".into(),
         };
@@ -175,5 +187,12 @@ mod tests {
         claims.token_use = "access".into();
         claims.claims.aud = "other-client".into();
         assert!(!claims.is_provider_access_token());
+
+        // client_credentials: the client is its own subject, so any
+        // registered audience is fine.
+        claims.claims.sub = "agentkit-pages".into();
+        claims.claims.aud = "https://api.example.com".into();
+        assert!(claims.is_provider_access_token());
+        assert!(claims.is_client_token());
     }
 }
@@ -0,0 +1,631 @@
+//! Admin HTTP API for organizations and groups.
+//!
+//! Same admin api-key gate as [`crate::admin`]; mounted under
+//! `/api/v1/engine/auth/` by the engine. Wherever a path takes an
+//! organization, its slug works as well as its id.
+//!
+//! - `GET    /admin/orgs?limit=&offset=&search=`
+//! - `POST   /admin/orgs`                           → `{slug, display_name}`
+//! - `GET    /admin/orgs/{org}`                     → org + groups + member count
+//! - `PUT    /admin/orgs/{org}`                     → `{display_name}`
+//! - `DELETE /admin/orgs/{org}`                     → cascades memberships + groups
+//! - `GET    /admin/orgs/{org}/members?limit=&offset=`
+//! - `PUT    /admin/orgs/{org}/members/{user_id}`   → `{role}`; add or re-role
+//! - `DELETE /admin/orgs/{org}/members/{user_id}`   → also leaves the org's groups
+//! - `GET    /admin/orgs/{org}/groups`
+//! - `POST   /admin/orgs/{org}/groups`              → `{slug, display_name}`
+//! - `GET    /admin/groups/{id}`                    → group + direct members
+//! - `DELETE /admin/groups/{id}`
+//! - `PUT    /admin/groups/{id}/members/{type}/{member_id}` → `type` is `user` | `group`
+//! - `DELETE /admin/groups/{id}/members/{type}/{member_id}`
+//! - `GET    /admin/users/{id}/orgs`                → the user's memberships
+
+use axum::Router;
+use axum::extract::{FromRef, Path, Query, State};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, put};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{
+    Group, GroupMember, GroupMemberType, OrgError, OrgManager, OrgMembership, OrgRole, Organization,
+};
+use crate::api_keys::{Permission, Resource};
+use crate::ctx::AuthCtx;
+use crate::state::AdminApiKeys;
+
+pub fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    AuthCtx: FromRef<S>,
+    AdminApiKeys: FromRef<S>,
+{
+    Router::new()
+        .route("/admin/orgs", get(list_orgs).post(create_org))
+        .route(
+            "/admin/orgs/{org}",
+            get(get_org).put(update_org).delete(delete_org),
+        )
+        .route("/admin/orgs/{org}/members", get(list_members))
+        .route(
+            "/admin/orgs/{org}/members/{user_id}",
+            put(set_member).delete(remove_member),
+        )
+        .route(
+            "/admin/orgs/{org}/groups",
+            get(list_groups).post(create_group),
+        )
+        .route("/admin/groups/{id}", get(get_group).delete(delete_group))
+        .route(
+            "/admin/groups/{id}/members/{member_type}/{member_id}",
+            put(add_group_member).delete(remove_group_member),
+        )
+        .route("/admin/users/{id}/orgs", get(list_user_orgs))
+}
+
+// =====================================================================
+//   /admin/orgs
+// =====================================================================
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ListOrgsQuery {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ListOrgsResponse {
+    pub items: Vec<Organization>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+async fn list_orgs(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Query(q): Query<ListOrgsQuery>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    let offset = q.offset.unwrap_or(0).max(0);
+    let search = q.search.as_deref();
+    let items = match orgs.store().list_orgs(limit, offset, search).await {
+        Ok(v) => v,
+        Err(e) => return server_error(&format!("list orgs: {e}")),
+    };
+    let total = match orgs.store().count_orgs(search).await {
+        Ok(n) => n,
+        Err(e) => return server_error(&format!("count orgs: {e}")),
+    };
+    (
+        StatusCode::OK,
+        Json(ListOrgsResponse {
+            items,
+            total,
+            limit,
+            offset,
+        }),
+    )
+        .into_response()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateOrgBody {
+    pub slug: String,
+    /// Defaults to the slug.
+    pub display_name: Option<String>,
+}
+
+async fn create_org(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Json(body): Json<CreateOrgBody>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let display_name = body.display_name.as_deref().unwrap_or(&body.slug);
+    match orgs.create_org(&body.slug, display_name).await {
+        Ok(org) => (StatusCode::CREATED, Json(org)).into_response(),
+        Err(e) => org_error(e, "create org"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OrgDetailResponse {
+    pub organization: Organization,
+    pub groups: Vec<Group>,
+    pub member_count: i64,
+}
+
+async fn get_org(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(org): Path<String>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    let groups = match orgs.store().list_groups(&organization.id).await {
+        Ok(v) => v,
+        Err(e) => return server_error(&format!("list groups: {e}")),
+    };
+    let member_count = match orgs.store().count_memberships(&organization.id).await {
+        Ok(n) => n,
+        Err(e) => return server_error(&format!("count members: {e}")),
+    };
+    (
+        StatusCode::OK,
+        Json(OrgDetailResponse {
+            organization,
+            groups,
+            member_count,
+        }),
+    )
+        .into_response()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UpdateOrgBody {
+    pub display_name: String,
+}
+
+async fn update_org(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(org): Path<String>,
+    Json(body): Json<UpdateOrgBody>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let mut organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    match orgs
+        .store()
+        .update_org(&organization.id, &body.display_name)
+        .await
+    {
+        Ok(true) => {
+            organization.display_name = body.display_name;
+            (StatusCode::OK, Json(organization)).into_response()
+        }
+        Ok(false) => unknown_org(),
+        Err(e) => server_error(&format!("update org: {e}")),
+    }
+}
+
+async fn delete_org(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(org): Path<String>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    match orgs.delete_org(&organization.id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => unknown_org(),
+        Err(e) => server_error(&format!("delete org: {e}")),
+    }
+}
+
+// =====================================================================
+//   /admin/orgs/{org}/members
+// =====================================================================
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ListMembersQuery {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ListMembersResponse {
+    pub items: Vec<OrgMembership>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+async fn list_members(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(org): Path<String>,
+    Query(q): Query<ListMembersQuery>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    let offset = q.offset.unwrap_or(0).max(0);
+    let items = match orgs
+        .store()
+        .list_memberships(&organization.id, limit, offset)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => return server_error(&format!("list members: {e}")),
+    };
+    let total = match orgs.store().count_memberships(&organization.id).await {
+        Ok(n) => n,
+        Err(e) => return server_error(&format!("count members: {e}")),
+    };
+    (
+        StatusCode::OK,
+        Json(ListMembersResponse {
+            items,
+            total,
+            limit,
+            offset,
+        }),
+    )
+        .into_response()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SetMemberBody {
+    /// `owner` | `admin` | `member`; defaults to `member`.
+    pub role: Option<String>,
+}
+
+async fn set_member(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path((org, user_id)): Path<(String, String)>,
+    Json(body): Json<SetMemberBody>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let role = match body.role.as_deref() {
+        None => OrgRole::Member,
+        Some(r) => match OrgRole::parse(r) {
+            Some(role) => role,
+            None => return bad_request(&format!("unknown role {r:?}")),
+        },
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    match ctx.users.get_user_by_id(&user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "unknown user_id"})),
+            )
+                .into_response();
+        }
+        Err(e) => return server_error(&format!("get user: {e}")),
+    }
+    match orgs.set_membership(&organization.id, &user_id, role).await {
+        Ok(membership) => (StatusCode::OK, Json(membership)).into_response(),
+        Err(e) => org_error(e, "set membership"),
+    }
+}
+
+async fn remove_member(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path((org, user_id)): Path<(String, String)>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    match orgs.remove_membership(&organization.id, &user_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "not a member"})),
+        )
+            .into_response(),
+        Err(e) => server_error(&format!("remove membership: {e}")),
+    }
+}
+
+async fn list_user_orgs(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(user_id): Path<String>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    match orgs.store().list_user_memberships(&user_id).await {
+        Ok(items) => (StatusCode::OK, Json(json!({"items": items}))).into_response(),
+        Err(e) => server_error(&format!("list user orgs: {e}")),
+    }
+}
+
+// =====================================================================
+//   groups
+// =====================================================================
+
+async fn list_groups(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(org): Path<String>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    match orgs.store().list_groups(&organization.id).await {
+        Ok(items) => (StatusCode::OK, Json(json!({"items": items}))).into_response(),
+        Err(e) => server_error(&format!("list groups: {e}")),
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateGroupBody {
+    pub slug: String,
+    /// Defaults to the slug.
+    pub display_name: Option<String>,
+}
+
+async fn create_group(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(org): Path<String>,
+    Json(body): Json<CreateGroupBody>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let organization = match resolve(orgs, &org).await {
+        Ok(o) => o,
+        Err(r) => return r,
+    };
+    let display_name = body.display_name.as_deref().unwrap_or(&body.slug);
+    match orgs
+        .create_group(&organization.id, &body.slug, display_name)
+        .await
+    {
+        Ok(group) => (StatusCode::CREATED, Json(group)).into_response(),
+        Err(e) => org_error(e, "create group"),
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GroupDetailResponse {
+    pub group: Group,
+    pub members: Vec<GroupMember>,
+}
+
+async fn get_group(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let group = match orgs.store().get_group(&id).await {
+        Ok(Some(g)) => g,
+        Ok(None) => return unknown_group(),
+        Err(e) => return server_error(&format!("get group: {e}")),
+    };
+    match orgs.store().list_group_members(&id).await {
+        Ok(members) => {
+            (StatusCode::OK, Json(GroupDetailResponse { group, members })).into_response()
+        }
+        Err(e) => server_error(&format!("list group members: {e}")),
+    }
+}
+
+async fn delete_group(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    match orgs.delete_group(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => unknown_group(),
+        Err(e) => server_error(&format!("delete group: {e}")),
+    }
+}
+
+async fn add_group_member(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path((id, member_type, member_id)): Path<(String, String, String)>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let Some(member_type) = GroupMemberType::parse(&member_type) else {
+        return bad_request(&format!("unknown member type {member_type:?}"));
+    };
+    match orgs.add_group_member(&id, member_type, &member_id).await {
+        Ok(true) => StatusCode::CREATED.into_response(),
+        Ok(false) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => org_error(e, "add group member"),
+    }
+}
+
+async fn remove_group_member(
+    State(ctx): State<AuthCtx>,
+    State(keys): State<AdminApiKeys>,
+    method: Method,
+    headers: HeaderMap,
+    Path((id, member_type, member_id)): Path<(String, String, String)>,
+) -> Response {
+    let orgs = match gate(&method, &headers, &ctx, &keys).await {
+        Ok(orgs) => orgs,
+        Err(r) => return *r,
+    };
+    let Some(member_type) = GroupMemberType::parse(&member_type) else {
+        return bad_request(&format!("unknown member type {member_type:?}"));
+    };
+    match orgs.remove_group_member(&id, member_type, &member_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "not a member"})),
+        )
+            .into_response(),
+        Err(e) => server_error(&format!("remove group member: {e}")),
+    }
+}
+
+// =====================================================================
+//   helpers
+// =====================================================================
+
+/// Admin bearer (or `auth:*`-scoped API key) check, then the
+/// configured [`OrgManager`].
+async fn gate<'a>(
+    method: &Method,
+    headers: &HeaderMap,
+    ctx: &'a AuthCtx,
+    keys: &AdminApiKeys,
+) -> Result<&'a OrgManager, Box<Response>> {
+    crate::gate::require_admin_or_api_key(
+        headers,
+        ctx,
+        keys,
+        Permission::new(Resource::Auth, method),
+    )
+    .await?;
+    ctx.orgs.as_ref().ok_or_else(|| {
+        Box::new(
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "error": "service_unavailable",
+                    "error_description": "organizations not configured",
+                })),
+            )
+                .into_response(),
+        )
+    })
+}
+
+async fn resolve(orgs: &OrgManager, id_or_slug: &str) -> Result<Organization, Response> {
+    match orgs.resolve_org(id_or_slug).await {
+        Ok(Some(org)) => Ok(org),
+        Ok(None) => Err(unknown_org()),
+        Err(e) => Err(server_error(&format!("get org: {e}"))),
+    }
+}
+
+/// Map an [`OrgError`] to its 4xx; anything else is a 500.
+fn org_error(e: anyhow::Error, what: &str) -> Response {
+    let Some(org_error) = e.downcast_ref::<OrgError>() else {
+        return server_error(&format!("{what}: {e}"));
+    };
+    let status = match org_error {
+        OrgError::InvalidSlug | OrgError::NotAMember(_) | OrgError::CrossOrganization(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        OrgError::SlugTaken(_) | OrgError::Cycle(_) => StatusCode::CONFLICT,
+        OrgError::UnknownOrg(_) | OrgError::UnknownGroup(_) => StatusCode::NOT_FOUND,
+    };
+    (status, Json(json!({"error": org_error.to_string()}))).into_response()
+}
+
+fn unknown_org() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "unknown organization"})),
+    )
+        .into_response()
+}
+
+fn unknown_group() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "unknown group"})),
+    )
+        .into_response()
+}
+
+fn bad_request(msg: &str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({"error": msg}))).into_response()
+}
+
+fn server_error(msg: &str) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": "server_error", "error_description": msg})),
+    )
+        .into_response()
+}
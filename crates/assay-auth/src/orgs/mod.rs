@@ -0,0 +1,631 @@
+//! Organizations (tenants) and groups as first-class identity objects.
+//!
+//! Module shape:
+//!
+//! - this file — POD types ([`Organization`], [`OrgMembership`],
+//!   [`Group`], [`GroupMember`]), the [`OrgError`] validation errors,
+//!   and [`OrgManager`], which owns the Zanzibar projection.
+//! - [`store`] — the [`OrgStore`] async trait.
+//! - [`postgres`] / [`sqlite`] — backend implementations.
+//! - [`admin`] — the `/admin/orgs` + `/admin/groups` HTTP surface.
+//!
+//! The org store is the source of truth; Zanzibar holds a projection
+//! of it so policies can reference tenants without hand-written
+//! tuples. Every write through [`OrgManager`] mirrors itself into the
+//! tuple store:
+//!
+//! - membership → `organization:{org}#member@user:{uid}`, plus
+//!   `organization:{org}#owner@…` / `#admin@…` for those roles;
+//! - group → `group:{gid}#organization@organization:{org}`;
+//! - group member → `group:{gid}#member@user:{uid}` or, for a nested
+//!   group, `group:{gid}#member@group:{child}#member`.
+//!
+//! [`ZANZIBAR_SCHEMA`] defines the matching `organization` and `group`
+//! namespaces; [`OrgManager::ensure_namespaces`] installs them at boot
+//! unless the operator already defined their own.
+//!
+//! The projection is written after the row it mirrors, so a crash in
+//! between leaves the row without its tuple; re-applying the same
+//! membership (`PUT`) rewrites it. Writes are idempotent throughout.
+
+pub mod admin;
+#[cfg(feature = "backend-postgres")]
+pub mod postgres;
+#[cfg(feature = "backend-sqlite")]
+pub mod sqlite;
+pub mod store;
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::zanzibar::{ObjectRef, SubjectRef, Tuple, TupleFilter, ZanzibarStore, parse_schema};
+
+pub use store::OrgStore;
+
+#[cfg(feature = "backend-postgres")]
+pub use postgres::PostgresOrgStore;
+#[cfg(feature = "backend-sqlite")]
+pub use sqlite::SqliteOrgStore;
+
+/// Zanzibar namespace organizations project into.
+pub const ORG_NAMESPACE: &str = "organization";
+/// Zanzibar namespace groups project into.
+pub const GROUP_NAMESPACE: &str = "group";
+
+/// Schema for the projected namespaces. `manage` is what org owners
+/// and admins hold; `view` is every member. Groups inherit `manage`
+/// from their organization so org admins can administer them.
+pub const ZANZIBAR_SCHEMA: &str = r#"
+definition organization {
+    relation owner: user
+    relation admin: user
+    relation member: user
+    permission manage = owner + admin
+    permission view = owner + admin + member
+}
+
+definition group {
+    relation organization: organization
+    relation member: user | group#member
+    permission manage = organization->manage
+    permission view = member + organization->manage
+}
+"#;
+
+/// Longest accepted org / group slug.
+pub const MAX_SLUG_LEN: usize = 63;
+
+/// One organization — row in `auth.organizations`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Organization {
+    /// `org_…` identifier; what tokens carry in `org_id`.
+    pub id: String,
+    /// URL-safe unique handle (`[a-z0-9-]`), accepted wherever an id is.
+    pub slug: String,
+    pub display_name: String,
+    pub created_at: f64,
+}
+
+/// A member's role inside one organization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrgRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl OrgRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Admin => "admin",
+            Self::Member => "member",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "owner" => Some(Self::Owner),
+            "admin" => Some(Self::Admin),
+            "member" => Some(Self::Member),
+            _ => None,
+        }
+    }
+
+    /// Zanzibar relation this role holds on top of `member`, if any.
+    fn extra_relation(self) -> Option<&'static str> {
+        match self {
+            Self::Owner => Some("owner"),
+            Self::Admin => Some("admin"),
+            Self::Member => None,
+        }
+    }
+}
+
+/// One user's membership in an organization — row in
+/// `auth.org_memberships`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrgMembership {
+    pub org_id: String,
+    pub user_id: String,
+    pub role: OrgRole,
+    pub created_at: f64,
+}
+
+/// A group inside one organization — row in `auth.groups`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    /// `grp_…` identifier.
+    pub id: String,
+    pub org_id: String,
+    /// Unique within the organization.
+    pub slug: String,
+    pub display_name: String,
+    pub created_at: f64,
+}
+
+/// What a group member is: a user, or another group whose members
+/// all count as members of this one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupMemberType {
+    User,
+    Group,
+}
+
+impl GroupMemberType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Group => "group",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "user" => Some(Self::User),
+            "group" => Some(Self::Group),
+            _ => None,
+        }
+    }
+}
+
+/// One direct member of a group — row in `auth.group_members`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub group_id: String,
+    pub member_type: GroupMemberType,
+    pub member_id: String,
+    pub created_at: f64,
+}
+
+/// Requests [`OrgManager`] refuses. Carried inside `anyhow::Error` so
+/// the HTTP layer can downcast and answer 4xx instead of 500.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum OrgError {
+    #[error("slug must be 1-{MAX_SLUG_LEN} chars of [a-z0-9-]")]
+    InvalidSlug,
+    #[error("slug {0:?} is already taken")]
+    SlugTaken(String),
+    #[error("unknown organization {0:?}")]
+    UnknownOrg(String),
+    #[error("unknown group {0:?}")]
+    UnknownGroup(String),
+    #[error("user {0:?} is not a member of the organization")]
+    NotAMember(String),
+    #[error("group {0:?} belongs to a different organization")]
+    CrossOrganization(String),
+    #[error("adding group {0:?} would create a membership cycle")]
+    Cycle(String),
+}
+
+/// `true` for a slug made of 1..=[`MAX_SLUG_LEN`] `[a-z0-9-]` chars
+/// that doesn't start or end with `-`.
+pub fn valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= MAX_SLUG_LEN
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+/// Org / group CRUD over an [`OrgStore`], mirrored into a
+/// [`ZanzibarStore`]. Cheap to clone.
+#[derive(Clone)]
+pub struct OrgManager {
+    store: Arc<dyn OrgStore>,
+    zanzibar: Arc<dyn ZanzibarStore>,
+}
+
+impl OrgManager {
+    pub fn new(store: Arc<dyn OrgStore>, zanzibar: Arc<dyn ZanzibarStore>) -> Self {
+        Self { store, zanzibar }
+    }
+
+    /// Direct access to the backing store for read paths.
+    pub fn store(&self) -> &Arc<dyn OrgStore> {
+        &self.store
+    }
+
+    /// Define the `organization` / `group` namespaces from
+    /// [`ZANZIBAR_SCHEMA`], skipping any the operator already defined
+    /// so a customised schema survives restarts.
+    pub async fn ensure_namespaces(&self) -> anyhow::Result<()> {
+        for ns in parse_schema(ZANZIBAR_SCHEMA)? {
+            if self.zanzibar.get_namespace(&ns.name).await?.is_none() {
+                self.zanzibar.define_namespace(&ns).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------
+    //   organizations
+    // -----------------------------------------------------------------
+
+    pub async fn create_org(&self, slug: &str, display_name: &str) -> anyhow::Result<Organization> {
+        if !valid_slug(slug) {
+            return Err(OrgError::InvalidSlug.into());
+        }
+        let org = Organization {
+            id: mint_id("org"),
+            slug: slug.to_string(),
+            display_name: display_name.to_string(),
+            created_at: now_secs(),
+        };
+        if !self.store.create_org(&org).await? {
+            return Err(OrgError::SlugTaken(slug.to_string()).into());
+        }
+        Ok(org)
+    }
+
+    /// Look an organization up by id, falling back to slug — the
+    /// `organization` authorize parameter accepts either.
+    pub async fn resolve_org(&self, id_or_slug: &str) -> anyhow::Result<Option<Organization>> {
+        if let Some(org) = self.store.get_org(id_or_slug).await? {
+            return Ok(Some(org));
+        }
+        self.store.get_org_by_slug(id_or_slug).await
+    }
+
+    /// Delete an organization with its memberships and groups, and
+    /// drop every tuple projected from them.
+    pub async fn delete_org(&self, org_id: &str) -> anyhow::Result<bool> {
+        let groups = self.store.list_groups(org_id).await?;
+        if !self.store.delete_org(org_id).await? {
+            return Ok(false);
+        }
+        self.drop_object_tuples(ORG_NAMESPACE, org_id).await?;
+        for group in groups {
+            self.drop_object_tuples(GROUP_NAMESPACE, &group.id).await?;
+        }
+        Ok(true)
+    }
+
+    // -----------------------------------------------------------------
+    //   memberships
+    // -----------------------------------------------------------------
+
+    /// Add `user_id` to the organization, or change their role. The
+    /// caller checks the user exists; an unknown one fails on the
+    /// `auth.users` foreign key.
+    pub async fn set_membership(
+        &self,
+        org_id: &str,
+        user_id: &str,
+        role: OrgRole,
+    ) -> anyhow::Result<OrgMembership> {
+        if self.store.get_org(org_id).await?.is_none() {
+            return Err(OrgError::UnknownOrg(org_id.to_string()).into());
+        }
+        let membership = OrgMembership {
+            org_id: org_id.to_string(),
+            user_id: user_id.to_string(),
+            role,
+            created_at: now_secs(),
+        };
+        let previous = self.store.upsert_membership(&membership).await?;
+        if let Some(old) = previous.and_then(OrgRole::extra_relation)
+            && Some(old) != role.extra_relation()
+        {
+            self.zanzibar
+                .delete_tuple(&org_tuple(org_id, old, user_id))
+                .await?;
+        }
+        let mut tuples = vec![org_tuple(org_id, "member", user_id)];
+        if let Some(extra) = role.extra_relation() {
+            tuples.push(org_tuple(org_id, extra, user_id));
+        }
+        self.zanzibar.write_tuples(&tuples).await?;
+        Ok(membership)
+    }
+
+    /// Remove `user_id` from the organization and from every group in
+    /// it. Returns `false` when they weren't a member.
+    pub async fn remove_membership(&self, org_id: &str, user_id: &str) -> anyhow::Result<bool> {
+        let Some(removed) = self.store.remove_membership(org_id, user_id).await? else {
+            return Ok(false);
+        };
+        self.drop_membership_tuples(&removed).await?;
+        for group in self.store.list_groups(org_id).await? {
+            self.remove_group_member(&group.id, GroupMemberType::User, user_id)
+                .await?;
+        }
+        Ok(true)
+    }
+
+    /// The user's membership, when `org` (id or slug) exists and they
+    /// belong to it. Used by org-scoped logins.
+    pub async fn membership_for(
+        &self,
+        org: &str,
+        user_id: &str,
+    ) -> anyhow::Result<Option<OrgMembership>> {
+        let Some(org) = self.resolve_org(org).await? else {
+            return Ok(None);
+        };
+        self.store.get_membership(&org.id, user_id).await
+    }
+
+    /// Drop everything projected for a user that is being deleted. The
+    /// rows themselves go with the user (FK cascade for memberships;
+    /// group rows here since they aren't keyed on `auth.users`).
+    pub async fn forget_user(&self, user_id: &str) -> anyhow::Result<()> {
+        for membership in self.store.list_user_memberships(user_id).await? {
+            self.drop_membership_tuples(&membership).await?;
+        }
+        for group_id in self
+            .store
+            .list_member_groups(GroupMemberType::User, user_id)
+            .await?
+        {
+            self.remove_group_member(&group_id, GroupMemberType::User, user_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------
+    //   groups
+    // -----------------------------------------------------------------
+
+    pub async fn create_group(
+        &self,
+        org_id: &str,
+        slug: &str,
+        display_name: &str,
+    ) -> anyhow::Result<Group> {
+        if !valid_slug(slug) {
+            return Err(OrgError::InvalidSlug.into());
+        }
+        if self.store.get_org(org_id).await?.is_none() {
+            return Err(OrgError::UnknownOrg(org_id.to_string()).into());
+        }
+        let group = Group {
+            id: mint_id("grp"),
+            org_id: org_id.to_string(),
+            slug: slug.to_string(),
+            display_name: display_name.to_string(),
+            created_at: now_secs(),
+        };
+        if !self.store.create_group(&group).await? {
+            return Err(OrgError::SlugTaken(slug.to_string()).into());
+        }
+        self.zanzibar
+            .write_tuple(&Tuple::direct(
+                ObjectRef::new(GROUP_NAMESPACE, &group.id),
+                "organization",
+                SubjectRef::direct(ORG_NAMESPACE, org_id),
+            ))
+            .await?;
+        Ok(group)
+    }
+
+    /// Delete a group, its member rows, and its slots in parent groups.
+    pub async fn delete_group(&self, group_id: &str) -> anyhow::Result<bool> {
+        let parents = self
+            .store
+            .list_member_groups(GroupMemberType::Group, group_id)
+            .await?;
+        if !self.store.delete_group(group_id).await? {
+            return Ok(false);
+        }
+        self.drop_object_tuples(GROUP_NAMESPACE, group_id).await?;
+        for parent in parents {
+            self.zanzibar
+                .delete_tuple(&group_tuple(&parent, GroupMemberType::Group, group_id))
+                .await?;
+        }
+        Ok(true)
+    }
+
+    /// Add a user or a nested group to `group_id`. Users must belong to
+    /// the group's organization; nested groups must live in the same
+    /// organization and may not make the membership graph cyclic.
+    /// Returns `false` when the member was already present.
+    pub async fn add_group_member(
+        &self,
+        group_id: &str,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> anyhow::Result<bool> {
+        let Some(group) = self.store.get_group(group_id).await? else {
+            return Err(OrgError::UnknownGroup(group_id.to_string()).into());
+        };
+        match member_type {
+            GroupMemberType::User => {
+                if self
+                    .store
+                    .get_membership(&group.org_id, member_id)
+                    .await?
+                    .is_none()
+                {
+                    return Err(OrgError::NotAMember(member_id.to_string()).into());
+                }
+            }
+            GroupMemberType::Group => {
+                let Some(child) = self.store.get_group(member_id).await? else {
+                    return Err(OrgError::UnknownGroup(member_id.to_string()).into());
+                };
+                if child.org_id != group.org_id {
+                    return Err(OrgError::CrossOrganization(member_id.to_string()).into());
+                }
+                if self.reaches(member_id, group_id).await? {
+                    return Err(OrgError::Cycle(member_id.to_string()).into());
+                }
+            }
+        }
+        let member = GroupMember {
+            group_id: group_id.to_string(),
+            member_type,
+            member_id: member_id.to_string(),
+            created_at: now_secs(),
+        };
+        let added = self.store.add_group_member(&member).await?;
+        self.zanzibar
+            .write_tuple(&group_tuple(group_id, member_type, member_id))
+            .await?;
+        Ok(added)
+    }
+
+    pub async fn remove_group_member(
+        &self,
+        group_id: &str,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> anyhow::Result<bool> {
+        let removed = self
+            .store
+            .remove_group_member(group_id, member_type, member_id)
+            .await?;
+        if removed {
+            self.zanzibar
+                .delete_tuple(&group_tuple(group_id, member_type, member_id))
+                .await?;
+        }
+        Ok(removed)
+    }
+
+    /// `true` when `target` is `from` or one of the groups nested
+    /// (transitively) inside it.
+    async fn reaches(&self, from: &str, target: &str) -> anyhow::Result<bool> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([from.to_string()]);
+        while let Some(group_id) = queue.pop_front() {
+            if group_id == target {
+                return Ok(true);
+            }
+            if !seen.insert(group_id.clone()) {
+                continue;
+            }
+            for member in self.store.list_group_members(&group_id).await? {
+                if member.member_type == GroupMemberType::Group {
+                    queue.push_back(member.member_id);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    // -----------------------------------------------------------------
+    //   projection helpers
+    // -----------------------------------------------------------------
+
+    async fn drop_membership_tuples(&self, membership: &OrgMembership) -> anyhow::Result<()> {
+        let mut relations = vec!["member"];
+        relations.extend(membership.role.extra_relation());
+        for relation in relations {
+            self.zanzibar
+                .delete_tuple(&org_tuple(
+                    &membership.org_id,
+                    relation,
+                    &membership.user_id,
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Delete every tuple whose object is `{object_type}:{object_id}`.
+    async fn drop_object_tuples(&self, object_type: &str, object_id: &str) -> anyhow::Result<()> {
+        loop {
+            let tuples = self
+                .zanzibar
+                .list_tuples(&TupleFilter {
+                    object_type: Some(object_type.to_string()),
+                    object_id: Some(object_id.to_string()),
+                    limit: Some(1000),
+                    ..TupleFilter::default()
+                })
+                .await?;
+            let mut deleted = 0;
+            for tuple in &tuples {
+                if self.zanzibar.delete_tuple(tuple).await?.is_some() {
+                    deleted += 1;
+                }
+            }
+            if deleted == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn org_tuple(org_id: &str, relation: &str, user_id: &str) -> Tuple {
+    Tuple::direct(
+        ObjectRef::new(ORG_NAMESPACE, org_id),
+        relation,
+        SubjectRef::direct("user", user_id),
+    )
+}
+
+fn group_tuple(group_id: &str, member_type: GroupMemberType, member_id: &str) -> Tuple {
+    let subject = match member_type {
+        GroupMemberType::User => SubjectRef::direct("user", member_id),
+        GroupMemberType::Group => SubjectRef::userset(GROUP_NAMESPACE, member_id, "member"),
+    };
+    Tuple::direct(ObjectRef::new(GROUP_NAMESPACE, group_id), "member", subject)
+}
+
+fn mint_id(prefix: &str) -> String {
+    use rand::RngCore;
+    let mut buf = [0u8; 12];
+    rand::rng().fill_bytes(&mut buf);
+    format!("{prefix}_{}", data_encoding::BASE64URL_NOPAD.encode(&buf))
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_are_lowercase_dns_labels() {
+        assert!(valid_slug("acme"));
+        assert!(valid_slug("acme-corp-2"));
+        assert!(!valid_slug(""));
+        assert!(!valid_slug("Acme"));
+        assert!(!valid_slug("-acme"));
+        assert!(!valid_slug("acme-"));
+        assert!(!valid_slug("acme_corp"));
+        assert!(!valid_slug(&"a".repeat(MAX_SLUG_LEN + 1)));
+    }
+
+    #[test]
+    fn roles_round_trip() {
+        for role in [OrgRole::Owner, OrgRole::Admin, OrgRole::Member] {
+            assert_eq!(OrgRole::parse(role.as_str()), Some(role));
+        }
+        assert_eq!(OrgRole::parse("viewer"), None);
+    }
+
+    #[test]
+    fn schema_parses_both_namespaces() {
+        let names: Vec<String> = parse_schema(ZANZIBAR_SCHEMA)
+            .unwrap()
+            .into_iter()
+            .map(|ns| ns.name)
+            .collect();
+        assert_eq!(names, [ORG_NAMESPACE, GROUP_NAMESPACE]);
+    }
+
+    #[test]
+    fn nested_group_projects_as_userset() {
+        let t = group_tuple("grp_a", GroupMemberType::Group, "grp_b");
+        assert_eq!(t.subject_type, "group");
+        assert_eq!(t.subject_id, "grp_b");
+        assert_eq!(t.subject_rel, "member");
+    }
+}
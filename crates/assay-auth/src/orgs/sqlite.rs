@@ -0,0 +1,415 @@
+//! SQLite [`OrgStore`] implementation — same queries as the Postgres
+//! backend with `?` placeholders; the membership upsert reads the old
+//! role inside the write transaction instead of `FOR UPDATE`.
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+
+use super::store::OrgStore;
+use super::{Group, GroupMember, GroupMemberType, OrgMembership, OrgRole, Organization};
+
+/// SQLite-backed org store. Cheap to clone (the pool is `Arc`d).
+#[derive(Clone)]
+pub struct SqliteOrgStore {
+    pool: SqlitePool,
+}
+
+impl SqliteOrgStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+type OrgRow = (String, String, String, f64);
+type MembershipRow = (String, String, String, f64);
+type GroupRow = (String, String, String, String, f64);
+type GroupMemberRow = (String, String, String, f64);
+
+fn org_from_row((id, slug, display_name, created_at): OrgRow) -> Organization {
+    Organization {
+        id,
+        slug,
+        display_name,
+        created_at,
+    }
+}
+
+fn membership_from_row((org_id, user_id, role, created_at): MembershipRow) -> OrgMembership {
+    OrgMembership {
+        org_id,
+        user_id,
+        role: OrgRole::parse(&role).unwrap_or(OrgRole::Member),
+        created_at,
+    }
+}
+
+fn group_from_row((id, org_id, slug, display_name, created_at): GroupRow) -> Group {
+    Group {
+        id,
+        org_id,
+        slug,
+        display_name,
+        created_at,
+    }
+}
+
+fn member_from_row(
+    (group_id, member_type, member_id, created_at): GroupMemberRow,
+) -> Option<GroupMember> {
+    Some(GroupMember {
+        group_id,
+        member_type: GroupMemberType::parse(&member_type)?,
+        member_id,
+        created_at,
+    })
+}
+
+fn search_pattern(search: Option<&str>) -> Option<String> {
+    search.map(|needle| format!("%{}%", needle.to_lowercase()))
+}
+
+#[async_trait::async_trait]
+impl OrgStore for SqliteOrgStore {
+    async fn create_org(&self, org: &Organization) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO auth.organizations (id, slug, display_name, created_at)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT (slug) DO NOTHING",
+        )
+        .bind(&org.id)
+        .bind(&org.slug)
+        .bind(&org.display_name)
+        .bind(org.created_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.organizations insert")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn get_org(&self, id: &str) -> Result<Option<Organization>> {
+        let row: Option<OrgRow> = sqlx::query_as(
+            "SELECT id, slug, display_name, created_at
+             FROM auth.organizations WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.organizations get")?;
+        Ok(row.map(org_from_row))
+    }
+
+    async fn get_org_by_slug(&self, slug: &str) -> Result<Option<Organization>> {
+        let row: Option<OrgRow> = sqlx::query_as(
+            "SELECT id, slug, display_name, created_at
+             FROM auth.organizations WHERE slug = ?",
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.organizations get by slug")?;
+        Ok(row.map(org_from_row))
+    }
+
+    async fn list_orgs(
+        &self,
+        limit: i64,
+        offset: i64,
+        search: Option<&str>,
+    ) -> Result<Vec<Organization>> {
+        let pattern = search_pattern(search);
+        let rows: Vec<OrgRow> = sqlx::query_as(
+            "SELECT id, slug, display_name, created_at
+             FROM auth.organizations
+             WHERE ? IS NULL
+                OR slug LIKE ?
+                OR LOWER(display_name) LIKE ?
+             ORDER BY slug
+             LIMIT ? OFFSET ?",
+        )
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(limit.clamp(1, 500))
+        .bind(offset.max(0))
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.organizations list")?;
+        Ok(rows.into_iter().map(org_from_row).collect())
+    }
+
+    async fn count_orgs(&self, search: Option<&str>) -> Result<i64> {
+        let pattern = search_pattern(search);
+        let (n,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM auth.organizations
+             WHERE ? IS NULL
+                OR slug LIKE ?
+                OR LOWER(display_name) LIKE ?",
+        )
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&pattern)
+        .fetch_one(&self.pool)
+        .await
+        .context("auth.organizations count")?;
+        Ok(n)
+    }
+
+    async fn update_org(&self, id: &str, display_name: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE auth.organizations SET display_name = ? WHERE id = ?")
+            .bind(display_name)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.organizations update")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_org(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM auth.organizations WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.organizations delete")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn upsert_membership(&self, membership: &OrgMembership) -> Result<Option<OrgRole>> {
+        let mut transaction = self.pool.begin().await.context("begin membership upsert")?;
+        let previous: Option<(String,)> = sqlx::query_as(
+            "SELECT role FROM auth.org_memberships
+             WHERE org_id = ? AND user_id = ?",
+        )
+        .bind(&membership.org_id)
+        .bind(&membership.user_id)
+        .fetch_optional(&mut *transaction)
+        .await
+        .context("auth.org_memberships lock")?;
+        sqlx::query(
+            "INSERT INTO auth.org_memberships (org_id, user_id, role, created_at)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT (org_id, user_id) DO UPDATE SET role = excluded.role",
+        )
+        .bind(&membership.org_id)
+        .bind(&membership.user_id)
+        .bind(membership.role.as_str())
+        .bind(membership.created_at)
+        .execute(&mut *transaction)
+        .await
+        .context("auth.org_memberships upsert")?;
+        transaction
+            .commit()
+            .await
+            .context("commit membership upsert")?;
+        Ok(previous.and_then(|(role,)| OrgRole::parse(&role)))
+    }
+
+    async fn get_membership(&self, org_id: &str, user_id: &str) -> Result<Option<OrgMembership>> {
+        let row: Option<MembershipRow> = sqlx::query_as(
+            "SELECT org_id, user_id, role, created_at
+             FROM auth.org_memberships WHERE org_id = ? AND user_id = ?",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.org_memberships get")?;
+        Ok(row.map(membership_from_row))
+    }
+
+    async fn remove_membership(
+        &self,
+        org_id: &str,
+        user_id: &str,
+    ) -> Result<Option<OrgMembership>> {
+        let row: Option<MembershipRow> = sqlx::query_as(
+            "DELETE FROM auth.org_memberships WHERE org_id = ? AND user_id = ?
+             RETURNING org_id, user_id, role, created_at",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.org_memberships delete")?;
+        Ok(row.map(membership_from_row))
+    }
+
+    async fn list_memberships(
+        &self,
+        org_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrgMembership>> {
+        let rows: Vec<MembershipRow> = sqlx::query_as(
+            "SELECT org_id, user_id, role, created_at
+             FROM auth.org_memberships WHERE org_id = ?
+             ORDER BY created_at, user_id
+             LIMIT ? OFFSET ?",
+        )
+        .bind(org_id)
+        .bind(limit.clamp(1, 500))
+        .bind(offset.max(0))
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.org_memberships list")?;
+        Ok(rows.into_iter().map(membership_from_row).collect())
+    }
+
+    async fn count_memberships(&self, org_id: &str) -> Result<i64> {
+        let (n,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM auth.org_memberships WHERE org_id = ?")
+                .bind(org_id)
+                .fetch_one(&self.pool)
+                .await
+                .context("auth.org_memberships count")?;
+        Ok(n)
+    }
+
+    async fn list_user_memberships(&self, user_id: &str) -> Result<Vec<OrgMembership>> {
+        let rows: Vec<MembershipRow> = sqlx::query_as(
+            "SELECT org_id, user_id, role, created_at
+             FROM auth.org_memberships WHERE user_id = ?
+             ORDER BY created_at, org_id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.org_memberships list by user")?;
+        Ok(rows.into_iter().map(membership_from_row).collect())
+    }
+
+    async fn create_group(&self, group: &Group) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO auth.groups (id, org_id, slug, display_name, created_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (org_id, slug) DO NOTHING",
+        )
+        .bind(&group.id)
+        .bind(&group.org_id)
+        .bind(&group.slug)
+        .bind(&group.display_name)
+        .bind(group.created_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.groups insert")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn get_group(&self, id: &str) -> Result<Option<Group>> {
+        let row: Option<GroupRow> = sqlx::query_as(
+            "SELECT id, org_id, slug, display_name, created_at
+             FROM auth.groups WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.groups get")?;
+        Ok(row.map(group_from_row))
+    }
+
+    async fn list_groups(&self, org_id: &str) -> Result<Vec<Group>> {
+        let rows: Vec<GroupRow> = sqlx::query_as(
+            "SELECT id, org_id, slug, display_name, created_at
+             FROM auth.groups WHERE org_id = ? ORDER BY slug",
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.groups list")?;
+        Ok(rows.into_iter().map(group_from_row).collect())
+    }
+
+    async fn update_group(&self, id: &str, display_name: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE auth.groups SET display_name = ? WHERE id = ?")
+            .bind(display_name)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("auth.groups update")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_group(&self, id: &str) -> Result<bool> {
+        let mut transaction = self.pool.begin().await.context("begin group delete")?;
+        sqlx::query(
+            "DELETE FROM auth.group_members
+             WHERE member_type = 'group' AND member_id = ?",
+        )
+        .bind(id)
+        .execute(&mut *transaction)
+        .await
+        .context("auth.group_members delete nesting")?;
+        let result = sqlx::query("DELETE FROM auth.groups WHERE id = ?")
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .context("auth.groups delete")?;
+        transaction.commit().await.context("commit group delete")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn add_group_member(&self, member: &GroupMember) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO auth.group_members (group_id, member_type, member_id, created_at)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT (group_id, member_type, member_id) DO NOTHING",
+        )
+        .bind(&member.group_id)
+        .bind(member.member_type.as_str())
+        .bind(&member.member_id)
+        .bind(member.created_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.group_members insert")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn remove_group_member(
+        &self,
+        group_id: &str,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM auth.group_members
+             WHERE group_id = ? AND member_type = ? AND member_id = ?",
+        )
+        .bind(group_id)
+        .bind(member_type.as_str())
+        .bind(member_id)
+        .execute(&self.pool)
+        .await
+        .context("auth.group_members delete")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn list_group_members(&self, group_id: &str) -> Result<Vec<GroupMember>> {
+        let rows: Vec<GroupMemberRow> = sqlx::query_as(
+            "SELECT group_id, member_type, member_id, created_at
+             FROM auth.group_members WHERE group_id = ?
+             ORDER BY member_type, member_id",
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.group_members list")?;
+        Ok(rows.into_iter().filter_map(member_from_row).collect())
+    }
+
+    async fn list_member_groups(
+        &self,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT group_id FROM auth.group_members
+             WHERE member_type = ? AND member_id = ?
+             ORDER BY group_id",
+        )
+        .bind(member_type.as_str())
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.group_members list by member")?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+}
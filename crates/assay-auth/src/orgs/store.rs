@@ -0,0 +1,109 @@
+//! [`OrgStore`] trait — the storage seam for organizations and groups.
+//!
+//! Plain row CRUD; the Zanzibar projection and the membership / nesting
+//! rules live in [`super::OrgManager`]. Inserts that would collide on a
+//! slug return `Ok(false)` rather than an error so the manager can
+//! answer with [`super::OrgError::SlugTaken`].
+//!
+//! Implementations live in [`super::postgres`] and [`super::sqlite`].
+
+use super::{Group, GroupMember, GroupMemberType, OrgMembership, OrgRole, Organization};
+
+#[async_trait::async_trait]
+pub trait OrgStore: Send + Sync + 'static {
+    /// Insert an organization. `false` when the slug is taken.
+    async fn create_org(&self, org: &Organization) -> anyhow::Result<bool>;
+
+    async fn get_org(&self, id: &str) -> anyhow::Result<Option<Organization>>;
+
+    async fn get_org_by_slug(&self, slug: &str) -> anyhow::Result<Option<Organization>>;
+
+    /// Organizations ordered by slug. `search` matches a substring of
+    /// the slug or display name.
+    async fn list_orgs(
+        &self,
+        limit: i64,
+        offset: i64,
+        search: Option<&str>,
+    ) -> anyhow::Result<Vec<Organization>>;
+
+    async fn count_orgs(&self, search: Option<&str>) -> anyhow::Result<i64>;
+
+    /// Rename an organization (display name only — the slug and id are
+    /// what clients bind to). `false` when it doesn't exist.
+    async fn update_org(&self, id: &str, display_name: &str) -> anyhow::Result<bool>;
+
+    /// Delete an organization; memberships, groups and group members
+    /// cascade. `false` when it doesn't exist.
+    async fn delete_org(&self, id: &str) -> anyhow::Result<bool>;
+
+    /// Insert or re-role a membership. Returns the previous role, if
+    /// the user was already a member.
+    async fn upsert_membership(
+        &self,
+        membership: &OrgMembership,
+    ) -> anyhow::Result<Option<OrgRole>>;
+
+    async fn get_membership(
+        &self,
+        org_id: &str,
+        user_id: &str,
+    ) -> anyhow::Result<Option<OrgMembership>>;
+
+    /// Delete a membership and return it. `None` when there was none.
+    async fn remove_membership(
+        &self,
+        org_id: &str,
+        user_id: &str,
+    ) -> anyhow::Result<Option<OrgMembership>>;
+
+    /// Members of one organization, ordered by join time.
+    async fn list_memberships(
+        &self,
+        org_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<OrgMembership>>;
+
+    async fn count_memberships(&self, org_id: &str) -> anyhow::Result<i64>;
+
+    /// Every organization a user belongs to.
+    async fn list_user_memberships(&self, user_id: &str) -> anyhow::Result<Vec<OrgMembership>>;
+
+    /// Insert a group. `false` when the slug is taken in its org.
+    async fn create_group(&self, group: &Group) -> anyhow::Result<bool>;
+
+    async fn get_group(&self, id: &str) -> anyhow::Result<Option<Group>>;
+
+    /// Groups of one organization, ordered by slug.
+    async fn list_groups(&self, org_id: &str) -> anyhow::Result<Vec<Group>>;
+
+    /// Rename a group (display name only). `false` when it doesn't
+    /// exist.
+    async fn update_group(&self, id: &str, display_name: &str) -> anyhow::Result<bool>;
+
+    /// Delete a group with its member rows and every row nesting it in
+    /// another group. `false` when it doesn't exist.
+    async fn delete_group(&self, id: &str) -> anyhow::Result<bool>;
+
+    /// `false` when the member was already present.
+    async fn add_group_member(&self, member: &GroupMember) -> anyhow::Result<bool>;
+
+    /// `false` when there was no such member.
+    async fn remove_group_member(
+        &self,
+        group_id: &str,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> anyhow::Result<bool>;
+
+    /// Direct members of a group (users and nested groups).
+    async fn list_group_members(&self, group_id: &str) -> anyhow::Result<Vec<GroupMember>>;
+
+    /// Ids of the groups `member_id` is a direct member of.
+    async fn list_member_groups(
+        &self,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> anyhow::Result<Vec<String>>;
+}
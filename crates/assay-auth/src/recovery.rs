@@ -6,6 +6,7 @@ use sha2::{Digest, Sha256};
 use url::Url;
 
 use crate::AuthCtx;
+use crate::lockout::LoginAttempt;
 use crate::password::PasswordHasher;
 
 #[async_trait::async_trait]
@@ -182,7 +183,7 @@ impl PasswordRecovery {
 
 use axum::Router;
 use axum::extract::{FromRef, State};
-use axum::http::StatusCode;
+use axum::http::{Extensions, HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Json, Response};
 use axum::routing::post;
 use serde::Deserialize;
@@ -205,17 +206,27 @@ struct RequestRecoveryBody {
 
 async fn request_recovery(
     State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    extensions: Extensions,
     Json(body): Json<RequestRecoveryBody>,
 ) -> Response {
-    let Some(recovery) = ctx.recovery else {
+    let Some(recovery) = ctx.recovery.clone() else {
         return unavailable();
     };
+    let attempt = match LoginAttempt::begin(&ctx, &headers, &extensions, None).await {
+        Ok(attempt) => attempt,
+        Err(response) => return *response,
+    };
     tokio::spawn(async move {
         if let Err(error) = recovery.request(&body.email).await {
             tracing::error!(%error, "password recovery request failed");
         }
     });
-    (StatusCode::ACCEPTED, Json(json!({"status": "accepted"}))).into_response()
+    // Every request counts against the IP: the caller never learns
+    // whether an email went out, so there's no success to reset on.
+    attempt
+        .failed((StatusCode::ACCEPTED, Json(json!({"status": "accepted"}))).into_response())
+        .await
 }
 
 #[derive(Deserialize)]
@@ -226,17 +237,23 @@ struct CompleteRecoveryBody {
 
 async fn complete_recovery(
     State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    extensions: Extensions,
     Json(body): Json<CompleteRecoveryBody>,
 ) -> Response {
-    let Some(recovery) = ctx.recovery else {
+    let Some(recovery) = ctx.recovery.as_ref() else {
         return unavailable();
     };
+    let attempt = match LoginAttempt::begin(&ctx, &headers, &extensions, None).await {
+        Ok(attempt) => attempt,
+        Err(response) => return *response,
+    };
     if body.token.is_empty() || body.password.is_empty() || body.password.len() > 1024 {
         return invalid_token();
     }
     match recovery.complete(&body.token, &body.password).await {
         Ok(true) => StatusCode::NO_CONTENT.into_response(),
-        Ok(false) => invalid_token(),
+        Ok(false) => attempt.failed(invalid_token()).await,
         Err(error) => {
             tracing::error!(%error, "password recovery completion failed");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -5,10 +5,10 @@
 //! - [`oidc_spec_router`] — OIDC-spec endpoints that the wider OIDC
 //!   ecosystem expects under stable, well-known paths
 //!   (`/.well-known/*`, `/authorize`, `/token`, `/userinfo`, `/revoke`,
-//!   `/introspect`, `/logout`, `/oidc/upstream/*`). Mounted at `/auth`
-//!   by the engine.
+//!   `/introspect`, `/logout`, `/oidc/upstream/*`) plus the SCIM 2.0
+//!   server at `/scim/v2/*`. Mounted at `/auth` by the engine.
 //! - [`engine_auth_router`] — engine-internal auth surface (`/login`,
-//!   `/logout` (DELETE), `/whoami`, `/passkey/*`, `/admin/*`). Mounted
+//!   `/logout` (DELETE), `/whoami`, `/passkey/*`, `/mfa/*`, `/admin/*`). Mounted
 //!   under `/api/v1/engine/auth` by the engine — keeps operator-facing
 //!   APIs in one consistent namespace.
 //!
@@ -30,10 +30,11 @@ use crate::ctx::AuthCtx;
 /// OIDC-spec router — the public, well-known surface required by the
 /// OIDC + OAuth2 specs. Mounted at `/auth` by the engine binary so the
 /// canonical paths land at `/auth/.well-known/...`, `/auth/authorize`,
-/// `/auth/token`, etc.
+/// `/auth/token`, etc. SCIM provisioning rides along at
+/// `/auth/scim/v2/*`.
 ///
-/// Empty (returns a no-op router) when the `auth-oidc-provider`
-/// feature is off — the engine still mounts it; OIDC-less builds just
+/// Empty (returns a no-op router) when the `auth-oidc-provider` and
+/// `auth-scim` features are off — the engine still mounts it; OIDC-less builds just
 /// expose nothing under `/auth`.
 pub fn oidc_spec_router<S>() -> Router<S>
 where
@@ -44,6 +45,10 @@ where
     let r = Router::new();
     #[cfg(feature = "auth-oidc-provider")]
     let r = r.merge(crate::oidc_provider::spec_router::<S>());
+    // SCIM is a protocol surface for IdPs too, so it sits beside the
+    // OIDC endpoints rather than under the admin API.
+    #[cfg(feature = "auth-scim")]
+    let r = r.merge(crate::scim::router::<S>());
     let _ = ();
     r
 }
@@ -63,11 +68,15 @@ where
     let r = r.merge(crate::session::router::<S>());
     #[cfg(feature = "auth-recovery")]
     let r = r.merge(crate::recovery::router::<S>());
+    #[cfg(feature = "auth-mfa")]
+    let r = r.merge(crate::mfa::router::<S>());
     // Cross-cutting admin endpoints (users / sessions / zanzibar /
     // biscuit / jwks / audit). Always merged when the auth router is
     // built — the handlers themselves degrade gracefully (503) when
     // their underlying module isn't compiled in or wired up.
     let r = r.merge(crate::admin::router::<S>());
+    #[cfg(feature = "auth-orgs")]
+    let r = r.merge(crate::orgs::admin::router::<S>());
     // OIDC admin (clients + upstream) lives on the engine-internal
     // surface too — operator-only CRUD that's never called by the
     // OIDC spec flows.
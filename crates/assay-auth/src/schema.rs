@@ -27,8 +27,10 @@
 //! - `auth.passkeys` — WebAuthn credentials per user
 //! - `auth.sessions` — opaque session ids + CSRF tokens + expiry
 //! - `auth.jwks_keys` — rotated JWT signing keys (active + history)
-//! - `auth.audit` — append-only compliance log (deferred to a later
-//!   phase — see Phase 4 notes)
+//! - `auth.audit` — append-only compliance log (V16, written first by
+//!   login lockouts)
+//! - `auth.api_keys` — scoped, hashed API keys for module gates (V17)
+//! - `auth.zanzibar_gc` — the Zanzibar history horizon (V19)
 //!
 //! Auth does NOT write to `engine.events`; auth's real-time signal (if
 //! ever needed) goes through its own channel on `auth.audit`.
@@ -60,7 +62,35 @@ pub const MODULE_NAME: &str = "auth";
 ///               (cookie-bound CSRF binding token hash) — the
 ///               provider-agnostic federation hardening pack.
 /// V6: adds hashed, expiring, single-use password recovery tokens.
-pub const MIGRATION_VERSION: i32 = 6;
+/// V7: adds `auth.zanzibar_changelog`, the append-only tuple change
+///               feed behind the Zanzibar Watch API.
+/// V8: versions `auth.zanzibar_tuples` with `created_revision` /
+///               `deleted_revision` so checks can read an exact snapshot.
+/// V9: adds caveat columns to `auth.zanzibar_tuples` and
+///               `auth.zanzibar_changelog` for conditional tuples.
+/// V10: adds `expires_at` to `auth.zanzibar_tuples` and
+///               `auth.zanzibar_changelog` for time-bounded grants.
+/// V11: adds `audiences` and `jwks` to `auth.oidc_clients` for the
+///               `client_credentials` grant and `private_key_jwt`.
+/// V12: adds `auth.oidc_device_codes` for the RFC 8628 device
+///               authorization grant.
+/// V13: adds the TOTP second factor — `auth.mfa_totp`,
+///               `auth.mfa_recovery_codes`, `auth.mfa_challenges` — and
+///               an `amr` column on sessions + authorization codes.
+/// V14: adds organizations and groups — `auth.organizations`,
+///               `auth.org_memberships`, `auth.groups`,
+///               `auth.group_members` — and an `org_id` column on
+///               authorization codes + refresh tokens.
+/// V15: adds the SCIM sidecar tables `auth.scim_users` and
+///               `auth.scim_groups`.
+/// V16: adds login brute-force protection — `auth.login_failures`,
+///               `auth.lockouts` — and the `auth.audit` log.
+/// V17: adds `auth.api_keys` — database-backed, scoped API keys.
+/// V18: adds `auth.oidc_client_assertions`, the `jti` replay cache for
+///               `private_key_jwt` client assertions.
+/// V19: adds `auth.zanzibar_gc`, the horizon below which Zanzibar
+///               history has been compacted.
+pub const MIGRATION_VERSION: i32 = 19;
 
 /// Postgres DDL for the auth schema, version 1.
 ///
@@ -72,8 +102,8 @@ pub const MIGRATION_VERSION: i32 = 6;
 ///
 /// `auth.audit` is intentionally deferred — the table is part of plan
 /// 12c phase 4 task 4.6 step 1 but no caller writes to it yet, and
-/// shipping the DDL without a writer risks confusing operators.
-/// Phase 5/6 will add it alongside the first auditable action.
+/// shipping the DDL without a writer risks confusing operators. It
+/// lands in [`PG_DDL_V16`] alongside the first auditable action.
 pub const PG_DDL_V1: &str = r#"
 CREATE SCHEMA IF NOT EXISTS auth;
 
@@ -334,6 +364,325 @@ CREATE INDEX IF NOT EXISTS idx_auth_password_recovery_expires
     ON auth.password_recovery_tokens (expires_at);
 "#;
 
+/// Postgres DDL for the auth schema, version 7 — Zanzibar changelog.
+///
+/// One row per tuple that was actually inserted (`TOUCH`) or removed
+/// (`DELETE`). `revision` is the watch cursor: writers take a
+/// transaction-level advisory lock before appending, so revisions
+/// become visible in commit order and a reader that has seen revision
+/// N never later discovers a committed N-1.
+pub const PG_DDL_V7: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.zanzibar_changelog (
+    revision     BIGSERIAL PRIMARY KEY,
+    operation    TEXT NOT NULL,
+    object_type  TEXT NOT NULL,
+    object_id    TEXT NOT NULL,
+    relation     TEXT NOT NULL,
+    subject_type TEXT NOT NULL,
+    subject_id   TEXT NOT NULL,
+    subject_rel  TEXT NOT NULL DEFAULT '',
+    changed_at   DOUBLE PRECISION NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_auth_zanzibar_changelog_object
+    ON auth.zanzibar_changelog (object_type, revision);
+"#;
+
+/// Postgres DDL for the auth schema, version 8 — MVCC tuple rows.
+///
+/// A tuple row is live from `created_revision` (the changelog revision
+/// that wrote it) until `deleted_revision` (the one that removed it;
+/// NULL while live). Deletes stamp the row instead of removing it, so
+/// the primary key gives way to two unique indexes: one per row
+/// version, and one over the live rows that keeps re-writes idempotent.
+/// Rows that predate V8 get `created_revision = 0` — visible at every
+/// snapshot.
+pub const PG_DDL_V8: &str = r#"
+ALTER TABLE auth.zanzibar_tuples
+    ADD COLUMN IF NOT EXISTS created_revision BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE auth.zanzibar_tuples
+    ADD COLUMN IF NOT EXISTS deleted_revision BIGINT;
+ALTER TABLE auth.zanzibar_tuples DROP CONSTRAINT IF EXISTS zanzibar_tuples_pkey;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_auth_zanzibar_tuples_version
+    ON auth.zanzibar_tuples
+    (object_type, object_id, relation, subject_type, subject_id, subject_rel, created_revision);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_auth_zanzibar_tuples_live
+    ON auth.zanzibar_tuples
+    (object_type, object_id, relation, subject_type, subject_id, subject_rel)
+    WHERE deleted_revision IS NULL;
+"#;
+
+/// Postgres DDL for the auth schema, version 9 — caveated tuples.
+///
+/// `caveat_name` is `''` for an unconditional tuple (same convention as
+/// `subject_rel`); `caveat_context` holds the parameters fixed when the
+/// tuple was written. The changelog carries both so watchers see the
+/// condition a grant came with.
+pub const PG_DDL_V9: &str = r#"
+ALTER TABLE auth.zanzibar_tuples
+    ADD COLUMN IF NOT EXISTS caveat_name TEXT NOT NULL DEFAULT '';
+ALTER TABLE auth.zanzibar_tuples
+    ADD COLUMN IF NOT EXISTS caveat_context JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE auth.zanzibar_changelog
+    ADD COLUMN IF NOT EXISTS caveat_name TEXT NOT NULL DEFAULT '';
+ALTER TABLE auth.zanzibar_changelog
+    ADD COLUMN IF NOT EXISTS caveat_context JSONB NOT NULL DEFAULT '{}';
+"#;
+
+/// Postgres DDL for the auth schema, version 10 — expiring tuples.
+///
+/// `expires_at` is a unix epoch (NULL = never). Checks skip a tuple
+/// once it passes; the expiry sweeper then deletes it through the
+/// changelog like any other delete. The partial index keeps the
+/// sweeper's scan to the rows that can expire at all.
+pub const PG_DDL_V10: &str = r#"
+ALTER TABLE auth.zanzibar_tuples
+    ADD COLUMN IF NOT EXISTS expires_at BIGINT;
+ALTER TABLE auth.zanzibar_changelog
+    ADD COLUMN IF NOT EXISTS expires_at BIGINT;
+CREATE INDEX IF NOT EXISTS idx_auth_zanzibar_tuples_expiry
+    ON auth.zanzibar_tuples (expires_at)
+    WHERE expires_at IS NOT NULL AND deleted_revision IS NULL;
+"#;
+
+/// Postgres DDL for the auth schema, version 11 — machine clients.
+///
+/// `audiences` is the JSON array of resource servers a client may
+/// request `client_credentials` tokens for; `jwks` is the client's
+/// public JWK Set (JSON) verifying `private_key_jwt` assertions.
+pub const PG_DDL_V11: &str = r#"
+ALTER TABLE auth.oidc_clients
+    ADD COLUMN IF NOT EXISTS audiences TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE auth.oidc_clients
+    ADD COLUMN IF NOT EXISTS jwks TEXT;
+"#;
+
+/// Postgres DDL for the auth schema, version 12 — device authorization.
+///
+/// One row per `/device_authorization` request. `user_code` is unique
+/// so the verification page can look a request up by what the user
+/// typed; `status` walks `pending` → `approved` / `denied` →
+/// `consumed`.
+pub const PG_DDL_V12: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.oidc_device_codes (
+    device_code     TEXT PRIMARY KEY,
+    user_code       TEXT NOT NULL UNIQUE,
+    client_id       TEXT NOT NULL,
+    scopes          TEXT NOT NULL,
+    user_id         TEXT,
+    status          TEXT NOT NULL DEFAULT 'pending',
+    interval_secs   BIGINT NOT NULL,
+    last_polled_at  DOUBLE PRECISION,
+    issued_at       DOUBLE PRECISION NOT NULL,
+    expires_at      DOUBLE PRECISION NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_auth_oidc_device_codes_expires
+    ON auth.oidc_device_codes (expires_at);
+"#;
+
+/// Postgres DDL for the auth schema, version 13 — second factor.
+///
+/// `mfa_totp` holds one TOTP secret per user; `confirmed_at` stays NULL
+/// until the user proves the authenticator works, and `last_used_step`
+/// rejects replays of an accepted code. Recovery codes are stored as
+/// SHA-256 hashes. `mfa_challenges` bridges the password step and the
+/// code step of a login. `amr` (JSON array of RFC 8176 method names)
+/// follows a session into the codes it authorizes so ID tokens can
+/// report how the user signed in.
+pub const PG_DDL_V13: &str = r#"
+ALTER TABLE auth.sessions
+    ADD COLUMN IF NOT EXISTS amr TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE auth.oidc_authorization_codes
+    ADD COLUMN IF NOT EXISTS amr TEXT NOT NULL DEFAULT '[]';
+CREATE TABLE IF NOT EXISTS auth.mfa_totp (
+    user_id         TEXT PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    secret          TEXT NOT NULL,
+    confirmed_at    DOUBLE PRECISION,
+    last_used_step  BIGINT,
+    created_at      DOUBLE PRECISION NOT NULL
+);
+CREATE TABLE IF NOT EXISTS auth.mfa_recovery_codes (
+    code_hash   TEXT PRIMARY KEY,
+    user_id     TEXT NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    created_at  DOUBLE PRECISION NOT NULL,
+    used_at     DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS idx_auth_mfa_recovery_codes_user
+    ON auth.mfa_recovery_codes (user_id);
+CREATE TABLE IF NOT EXISTS auth.mfa_challenges (
+    token_hash  TEXT PRIMARY KEY,
+    user_id     TEXT NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    attempts    INTEGER NOT NULL DEFAULT 0,
+    created_at  DOUBLE PRECISION NOT NULL,
+    expires_at  DOUBLE PRECISION NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_auth_mfa_challenges_expires
+    ON auth.mfa_challenges (expires_at);
+"#;
+
+/// Postgres DDL for the auth schema, version 14 — organizations and
+/// groups.
+///
+/// A membership carries one role per (org, user). Groups belong to an
+/// organization; `group_members` rows are either users or other groups
+/// (`member_type` = `user` | `group`), which is how nesting is stored.
+/// `org_id` on codes and refresh tokens pins an org-scoped login so the
+/// tokens minted from them keep their `org_id` claim.
+pub const PG_DDL_V14: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.organizations (
+    id              TEXT PRIMARY KEY,
+    slug            TEXT NOT NULL UNIQUE,
+    display_name    TEXT NOT NULL,
+    created_at      DOUBLE PRECISION NOT NULL
+);
+CREATE TABLE IF NOT EXISTS auth.org_memberships (
+    org_id      TEXT NOT NULL REFERENCES auth.organizations(id) ON DELETE CASCADE,
+    user_id     TEXT NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    role        TEXT NOT NULL,
+    created_at  DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (org_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_auth_org_memberships_user
+    ON auth.org_memberships (user_id);
+CREATE TABLE IF NOT EXISTS auth.groups (
+    id              TEXT PRIMARY KEY,
+    org_id          TEXT NOT NULL REFERENCES auth.organizations(id) ON DELETE CASCADE,
+    slug            TEXT NOT NULL,
+    display_name    TEXT NOT NULL,
+    created_at      DOUBLE PRECISION NOT NULL,
+    UNIQUE (org_id, slug)
+);
+CREATE TABLE IF NOT EXISTS auth.group_members (
+    group_id     TEXT NOT NULL REFERENCES auth.groups(id) ON DELETE CASCADE,
+    member_type  TEXT NOT NULL,
+    member_id    TEXT NOT NULL,
+    created_at   DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (group_id, member_type, member_id)
+);
+CREATE INDEX IF NOT EXISTS idx_auth_group_members_member
+    ON auth.group_members (member_type, member_id);
+ALTER TABLE auth.oidc_authorization_codes
+    ADD COLUMN IF NOT EXISTS org_id TEXT;
+ALTER TABLE auth.oidc_refresh_tokens
+    ADD COLUMN IF NOT EXISTS org_id TEXT;
+"#;
+
+/// Postgres DDL for the auth schema, version 15 — SCIM provisioning.
+///
+/// Sidecar rows holding the SCIM attributes that have no home on
+/// `auth.users` / `auth.groups` (`userName`, `externalId`, name parts,
+/// `active`). Both cascade with the row they describe. `userName` is
+/// unique case-insensitively, as RFC 7643 §4.1.1 requires.
+pub const PG_DDL_V15: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.scim_users (
+    user_id      TEXT PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    user_name    TEXT NOT NULL,
+    external_id  TEXT,
+    given_name   TEXT,
+    family_name  TEXT,
+    active       BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at   DOUBLE PRECISION NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_auth_scim_users_user_name
+    ON auth.scim_users (LOWER(user_name));
+CREATE INDEX IF NOT EXISTS idx_auth_scim_users_external_id
+    ON auth.scim_users (external_id);
+CREATE TABLE IF NOT EXISTS auth.scim_groups (
+    group_id     TEXT PRIMARY KEY REFERENCES auth.groups(id) ON DELETE CASCADE,
+    external_id  TEXT,
+    updated_at   DOUBLE PRECISION NOT NULL
+);
+"#;
+
+/// Postgres DDL for the auth schema, version 16 — login lockouts and
+/// the audit log.
+///
+/// `auth.login_failures` holds one row per failed attempt, keyed by
+/// `scope` (`account` or `ip`) so the sliding window is a range count;
+/// rows older than the window are pruned as new failures arrive.
+/// `auth.lockouts` is the active-lock table an admin clears. Neither
+/// references `auth.users`: accounts are keyed by the normalised login
+/// email so unknown addresses are throttled exactly like known ones.
+pub const PG_DDL_V16: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.login_failures (
+    id          BIGSERIAL PRIMARY KEY,
+    scope       TEXT NOT NULL,
+    key         TEXT NOT NULL,
+    failed_at   DOUBLE PRECISION NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_auth_login_failures_key
+    ON auth.login_failures (scope, key, failed_at);
+CREATE INDEX IF NOT EXISTS idx_auth_login_failures_failed_at
+    ON auth.login_failures (failed_at);
+CREATE TABLE IF NOT EXISTS auth.lockouts (
+    scope         TEXT NOT NULL,
+    key           TEXT NOT NULL,
+    failures      BIGINT NOT NULL,
+    locked_at     DOUBLE PRECISION NOT NULL,
+    locked_until  DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (scope, key)
+);
+CREATE TABLE IF NOT EXISTS auth.audit (
+    id          BIGSERIAL PRIMARY KEY,
+    created_at  DOUBLE PRECISION NOT NULL,
+    actor       TEXT NOT NULL,
+    action      TEXT NOT NULL,
+    target      TEXT,
+    detail      JSONB NOT NULL DEFAULT '{}'::jsonb
+);
+CREATE INDEX IF NOT EXISTS idx_auth_audit_created
+    ON auth.audit (created_at);
+CREATE INDEX IF NOT EXISTS idx_auth_audit_action
+    ON auth.audit (action, created_at);
+"#;
+
+/// Postgres DDL for the auth schema, version 17 — scoped API keys (see
+/// [`crate::api_keys`]). Only the SHA-256 of each token is stored;
+/// `prefix` is the display-safe head of the token.
+pub const PG_DDL_V17: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.api_keys (
+    id            TEXT PRIMARY KEY,
+    name          TEXT NOT NULL,
+    owner         TEXT NOT NULL,
+    prefix        TEXT NOT NULL,
+    secret_hash   TEXT NOT NULL UNIQUE,
+    scopes        JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at    DOUBLE PRECISION NOT NULL,
+    expires_at    DOUBLE PRECISION,
+    last_used_at  DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS idx_auth_api_keys_owner
+    ON auth.api_keys (owner);
+"#;
+
+/// Postgres DDL for the auth schema, version 18 — `private_key_jwt`
+/// replay cache. One row per `(client_id, jti)` a client assertion has
+/// used, kept until the assertion's `exp`.
+pub const PG_DDL_V18: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.oidc_client_assertions (
+    client_id   TEXT NOT NULL,
+    jti         TEXT NOT NULL,
+    expires_at  DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (client_id, jti)
+);
+CREATE INDEX IF NOT EXISTS idx_auth_oidc_client_assertions_expires
+    ON auth.oidc_client_assertions (expires_at);
+"#;
+
+/// Postgres DDL for the auth schema, version 19 — Zanzibar history
+/// compaction. `auth.zanzibar_gc` holds one row: the oldest revision
+/// exact snapshots and watch cursors may still name. Tuple rows deleted
+/// and changelog rows written below it are gone; the partial index lets
+/// the compactor find the former without scanning live tuples.
+pub const PG_DDL_V19: &str = r#"
+CREATE TABLE IF NOT EXISTS auth.zanzibar_gc (
+    id       INTEGER PRIMARY KEY CHECK (id = 1),
+    horizon  BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_auth_zanzibar_tuples_deleted
+    ON auth.zanzibar_tuples (deleted_revision)
+    WHERE deleted_revision IS NOT NULL;
+"#;
+
 /// SQLite DDL for the auth schema, version 1.
 ///
 /// Caller must have ATTACHed `data/auth.db` AS `auth` before running
@@ -646,6 +995,431 @@ pub const SQLITE_DDL_V6: &[(&str, &str)] = &[
     ),
 ];
 
+/// SQLite DDL for the auth schema, version 7 — Zanzibar changelog.
+/// Mirrors [`PG_DDL_V7`]; `AUTOINCREMENT` keeps revisions from being
+/// reused if old rows are ever pruned. SQLite serialises writers, so
+/// revision order is commit order without extra locking.
+pub const SQLITE_DDL_V7: &[(&str, &str)] = &[
+    (
+        "zanzibar_changelog",
+        "CREATE TABLE IF NOT EXISTS auth.zanzibar_changelog (
+            revision     INTEGER PRIMARY KEY AUTOINCREMENT,
+            operation    TEXT NOT NULL,
+            object_type  TEXT NOT NULL,
+            object_id    TEXT NOT NULL,
+            relation     TEXT NOT NULL,
+            subject_type TEXT NOT NULL,
+            subject_id   TEXT NOT NULL,
+            subject_rel  TEXT NOT NULL DEFAULT '',
+            changed_at   REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_zanzibar_changelog_object",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_zanzibar_changelog_object \
+         ON zanzibar_changelog (object_type, revision)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 8 — MVCC tuple rows.
+///
+/// Mirrors [`PG_DDL_V8`]. SQLite can't drop a primary key in place, so
+/// the table is rebuilt under the versioned key. [`migrate_sqlite`]
+/// only runs this pack while `deleted_revision` is missing; the rebuild
+/// is not idempotent on its own.
+pub const SQLITE_DDL_V8: &[(&str, &str)] = &[
+    (
+        "zanzibar_tuples_v8",
+        "CREATE TABLE IF NOT EXISTS auth.zanzibar_tuples_v8 (
+            object_type      TEXT NOT NULL,
+            object_id        TEXT NOT NULL,
+            relation         TEXT NOT NULL,
+            subject_type     TEXT NOT NULL,
+            subject_id       TEXT NOT NULL,
+            subject_rel      TEXT NOT NULL DEFAULT '',
+            created_at       REAL NOT NULL,
+            created_revision INTEGER NOT NULL DEFAULT 0,
+            deleted_revision INTEGER,
+            PRIMARY KEY (object_type, object_id, relation, subject_type, subject_id,
+                         subject_rel, created_revision)
+        )",
+    ),
+    (
+        "zanzibar_tuples_v8 copy",
+        "INSERT INTO auth.zanzibar_tuples_v8
+            (object_type, object_id, relation, subject_type, subject_id, subject_rel, created_at)
+         SELECT object_type, object_id, relation, subject_type, subject_id, subject_rel, created_at
+         FROM auth.zanzibar_tuples",
+    ),
+    ("zanzibar_tuples drop", "DROP TABLE auth.zanzibar_tuples"),
+    (
+        "zanzibar_tuples_v8 rename",
+        "ALTER TABLE auth.zanzibar_tuples_v8 RENAME TO zanzibar_tuples",
+    ),
+    (
+        "idx_zanzibar_tuples_rev",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_zanzibar_tuples_rev \
+         ON zanzibar_tuples (subject_type, subject_id, relation)",
+    ),
+    (
+        "idx_zanzibar_tuples_live",
+        "CREATE UNIQUE INDEX IF NOT EXISTS auth.idx_auth_zanzibar_tuples_live \
+         ON zanzibar_tuples (object_type, object_id, relation, subject_type, subject_id, \
+         subject_rel) WHERE deleted_revision IS NULL",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 9 — caveated tuples.
+/// Mirrors [`PG_DDL_V9`]; like [`SQLITE_DDL_V5`], re-runs rely on the
+/// runner tolerating "duplicate column name".
+pub const SQLITE_DDL_V9: &[(&str, &str)] = &[
+    (
+        "zanzibar_tuples.caveat_name",
+        "ALTER TABLE auth.zanzibar_tuples ADD COLUMN caveat_name TEXT NOT NULL DEFAULT ''",
+    ),
+    (
+        "zanzibar_tuples.caveat_context",
+        "ALTER TABLE auth.zanzibar_tuples ADD COLUMN caveat_context TEXT NOT NULL DEFAULT '{}'",
+    ),
+    (
+        "zanzibar_changelog.caveat_name",
+        "ALTER TABLE auth.zanzibar_changelog ADD COLUMN caveat_name TEXT NOT NULL DEFAULT ''",
+    ),
+    (
+        "zanzibar_changelog.caveat_context",
+        "ALTER TABLE auth.zanzibar_changelog \
+         ADD COLUMN caveat_context TEXT NOT NULL DEFAULT '{}'",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 10 — expiring tuples.
+/// Mirrors [`PG_DDL_V10`]; [`migrate_sqlite`] adds the expiry index once
+/// the columns exist.
+pub const SQLITE_DDL_V10: &[(&str, &str)] = &[
+    (
+        "zanzibar_tuples.expires_at",
+        "ALTER TABLE auth.zanzibar_tuples ADD COLUMN expires_at INTEGER",
+    ),
+    (
+        "zanzibar_changelog.expires_at",
+        "ALTER TABLE auth.zanzibar_changelog ADD COLUMN expires_at INTEGER",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 11 — machine clients.
+/// Mirrors [`PG_DDL_V11`].
+pub const SQLITE_DDL_V11: &[(&str, &str)] = &[
+    (
+        "oidc_clients.audiences",
+        "ALTER TABLE auth.oidc_clients ADD COLUMN audiences TEXT NOT NULL DEFAULT '[]'",
+    ),
+    (
+        "oidc_clients.jwks",
+        "ALTER TABLE auth.oidc_clients ADD COLUMN jwks TEXT",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 12 — device authorization.
+/// Mirrors [`PG_DDL_V12`].
+pub const SQLITE_DDL_V12: &[(&str, &str)] = &[
+    (
+        "oidc_device_codes",
+        "CREATE TABLE IF NOT EXISTS auth.oidc_device_codes (
+            device_code     TEXT PRIMARY KEY,
+            user_code       TEXT NOT NULL UNIQUE,
+            client_id       TEXT NOT NULL,
+            scopes          TEXT NOT NULL,
+            user_id         TEXT,
+            status          TEXT NOT NULL DEFAULT 'pending',
+            interval_secs   INTEGER NOT NULL,
+            last_polled_at  REAL,
+            issued_at       REAL NOT NULL,
+            expires_at      REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_oidc_device_codes_expires",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_oidc_device_codes_expires \
+         ON oidc_device_codes (expires_at)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 13 — second-factor columns.
+/// Mirrors the `ALTER`s in [`PG_DDL_V13`]; the tables are in
+/// [`SQLITE_DDL_V13_TABLES`].
+pub const SQLITE_DDL_V13: &[(&str, &str)] = &[
+    (
+        "sessions.amr",
+        "ALTER TABLE auth.sessions ADD COLUMN amr TEXT NOT NULL DEFAULT '[]'",
+    ),
+    (
+        "oidc_authorization_codes.amr",
+        "ALTER TABLE auth.oidc_authorization_codes ADD COLUMN amr TEXT NOT NULL DEFAULT '[]'",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 13 — second-factor tables.
+/// Mirrors [`PG_DDL_V13`].
+pub const SQLITE_DDL_V13_TABLES: &[(&str, &str)] = &[
+    (
+        "mfa_totp",
+        "CREATE TABLE IF NOT EXISTS auth.mfa_totp (
+            user_id         TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+            secret          TEXT NOT NULL,
+            confirmed_at    REAL,
+            last_used_step  INTEGER,
+            created_at      REAL NOT NULL
+        )",
+    ),
+    (
+        "mfa_recovery_codes",
+        "CREATE TABLE IF NOT EXISTS auth.mfa_recovery_codes (
+            code_hash   TEXT PRIMARY KEY,
+            user_id     TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            created_at  REAL NOT NULL,
+            used_at     REAL
+        )",
+    ),
+    (
+        "idx_mfa_recovery_codes_user",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_mfa_recovery_codes_user \
+         ON mfa_recovery_codes (user_id)",
+    ),
+    (
+        "mfa_challenges",
+        "CREATE TABLE IF NOT EXISTS auth.mfa_challenges (
+            token_hash  TEXT PRIMARY KEY,
+            user_id     TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            attempts    INTEGER NOT NULL DEFAULT 0,
+            created_at  REAL NOT NULL,
+            expires_at  REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_mfa_challenges_expires",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_mfa_challenges_expires \
+         ON mfa_challenges (expires_at)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 14 — `org_id` columns.
+/// Mirrors the `ALTER`s in [`PG_DDL_V14`]; the tables are in
+/// [`SQLITE_DDL_V14_TABLES`].
+pub const SQLITE_DDL_V14: &[(&str, &str)] = &[
+    (
+        "oidc_authorization_codes.org_id",
+        "ALTER TABLE auth.oidc_authorization_codes ADD COLUMN org_id TEXT",
+    ),
+    (
+        "oidc_refresh_tokens.org_id",
+        "ALTER TABLE auth.oidc_refresh_tokens ADD COLUMN org_id TEXT",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 14 — organization and group
+/// tables. Mirrors [`PG_DDL_V14`].
+pub const SQLITE_DDL_V14_TABLES: &[(&str, &str)] = &[
+    (
+        "organizations",
+        "CREATE TABLE IF NOT EXISTS auth.organizations (
+            id              TEXT PRIMARY KEY,
+            slug            TEXT NOT NULL UNIQUE,
+            display_name    TEXT NOT NULL,
+            created_at      REAL NOT NULL
+        )",
+    ),
+    (
+        "org_memberships",
+        "CREATE TABLE IF NOT EXISTS auth.org_memberships (
+            org_id      TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+            user_id     TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            role        TEXT NOT NULL,
+            created_at  REAL NOT NULL,
+            PRIMARY KEY (org_id, user_id)
+        )",
+    ),
+    (
+        "idx_org_memberships_user",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_org_memberships_user \
+         ON org_memberships (user_id)",
+    ),
+    (
+        "groups",
+        "CREATE TABLE IF NOT EXISTS auth.groups (
+            id              TEXT PRIMARY KEY,
+            org_id          TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+            slug            TEXT NOT NULL,
+            display_name    TEXT NOT NULL,
+            created_at      REAL NOT NULL,
+            UNIQUE (org_id, slug)
+        )",
+    ),
+    (
+        "group_members",
+        "CREATE TABLE IF NOT EXISTS auth.group_members (
+            group_id     TEXT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+            member_type  TEXT NOT NULL,
+            member_id    TEXT NOT NULL,
+            created_at   REAL NOT NULL,
+            PRIMARY KEY (group_id, member_type, member_id)
+        )",
+    ),
+    (
+        "idx_group_members_member",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_group_members_member \
+         ON group_members (member_type, member_id)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 15 — SCIM sidecar tables.
+/// Mirrors [`PG_DDL_V15`].
+pub const SQLITE_DDL_V15: &[(&str, &str)] = &[
+    (
+        "scim_users",
+        "CREATE TABLE IF NOT EXISTS auth.scim_users (
+            user_id      TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+            user_name    TEXT NOT NULL,
+            external_id  TEXT,
+            given_name   TEXT,
+            family_name  TEXT,
+            active       INTEGER NOT NULL DEFAULT 1,
+            updated_at   REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_scim_users_user_name",
+        "CREATE UNIQUE INDEX IF NOT EXISTS auth.idx_auth_scim_users_user_name \
+         ON scim_users (LOWER(user_name))",
+    ),
+    (
+        "idx_scim_users_external_id",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_scim_users_external_id \
+         ON scim_users (external_id)",
+    ),
+    (
+        "scim_groups",
+        "CREATE TABLE IF NOT EXISTS auth.scim_groups (
+            group_id     TEXT PRIMARY KEY REFERENCES groups(id) ON DELETE CASCADE,
+            external_id  TEXT,
+            updated_at   REAL NOT NULL
+        )",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 16 — login lockouts and the
+/// audit log. Mirrors [`PG_DDL_V16`]; `detail` is JSON-encoded `TEXT`.
+pub const SQLITE_DDL_V16: &[(&str, &str)] = &[
+    (
+        "login_failures",
+        "CREATE TABLE IF NOT EXISTS auth.login_failures (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            scope       TEXT NOT NULL,
+            key         TEXT NOT NULL,
+            failed_at   REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_login_failures_key",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_login_failures_key \
+         ON login_failures (scope, key, failed_at)",
+    ),
+    (
+        "idx_login_failures_failed_at",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_login_failures_failed_at \
+         ON login_failures (failed_at)",
+    ),
+    (
+        "lockouts",
+        "CREATE TABLE IF NOT EXISTS auth.lockouts (
+            scope         TEXT NOT NULL,
+            key           TEXT NOT NULL,
+            failures      INTEGER NOT NULL,
+            locked_at     REAL NOT NULL,
+            locked_until  REAL NOT NULL,
+            PRIMARY KEY (scope, key)
+        )",
+    ),
+    (
+        "audit",
+        "CREATE TABLE IF NOT EXISTS auth.audit (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at  REAL NOT NULL,
+            actor       TEXT NOT NULL,
+            action      TEXT NOT NULL,
+            target      TEXT,
+            detail      TEXT NOT NULL DEFAULT '{}'
+        )",
+    ),
+    (
+        "idx_audit_created",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_audit_created ON audit (created_at)",
+    ),
+    (
+        "idx_audit_action",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_audit_action \
+         ON audit (action, created_at)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 17 — scoped API keys.
+/// Mirrors [`PG_DDL_V17`]; `scopes` is a JSON-encoded `TEXT` array.
+pub const SQLITE_DDL_V17: &[(&str, &str)] = &[
+    (
+        "api_keys",
+        "CREATE TABLE IF NOT EXISTS auth.api_keys (
+            id            TEXT PRIMARY KEY,
+            name          TEXT NOT NULL,
+            owner         TEXT NOT NULL,
+            prefix        TEXT NOT NULL,
+            secret_hash   TEXT NOT NULL UNIQUE,
+            scopes        TEXT NOT NULL DEFAULT '[]',
+            created_at    REAL NOT NULL,
+            expires_at    REAL,
+            last_used_at  REAL
+        )",
+    ),
+    (
+        "idx_api_keys_owner",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_api_keys_owner ON api_keys (owner)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 18 — `private_key_jwt`
+/// replay cache. Mirrors [`PG_DDL_V18`].
+pub const SQLITE_DDL_V18: &[(&str, &str)] = &[
+    (
+        "oidc_client_assertions",
+        "CREATE TABLE IF NOT EXISTS auth.oidc_client_assertions (
+            client_id   TEXT NOT NULL,
+            jti         TEXT NOT NULL,
+            expires_at  REAL NOT NULL,
+            PRIMARY KEY (client_id, jti)
+        )",
+    ),
+    (
+        "idx_oidc_client_assertions_expires",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_oidc_client_assertions_expires \
+         ON oidc_client_assertions (expires_at)",
+    ),
+];
+
+/// SQLite DDL for the auth schema, version 19 — Zanzibar history
+/// compaction. Mirrors [`PG_DDL_V19`].
+pub const SQLITE_DDL_V19: &[(&str, &str)] = &[
+    (
+        "zanzibar_gc",
+        "CREATE TABLE IF NOT EXISTS auth.zanzibar_gc (
+            id       INTEGER PRIMARY KEY CHECK (id = 1),
+            horizon  INTEGER NOT NULL
+        )",
+    ),
+    (
+        "idx_zanzibar_tuples_deleted",
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_zanzibar_tuples_deleted \
+         ON zanzibar_tuples (deleted_revision) WHERE deleted_revision IS NOT NULL",
+    ),
+];
+
 /// Postgres migration runner.
 ///
 /// Applies every DDL pack up to and including the current
@@ -657,7 +1431,9 @@ pub const SQLITE_DDL_V6: &[(&str, &str)] = &[
 pub async fn migrate_postgres(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     use anyhow::Context;
     for ddl in [
-        PG_DDL_V1, PG_DDL_V2, PG_DDL_V3, PG_DDL_V4, PG_DDL_V5, PG_DDL_V6,
+        PG_DDL_V1, PG_DDL_V2, PG_DDL_V3, PG_DDL_V4, PG_DDL_V5, PG_DDL_V6, PG_DDL_V7, PG_DDL_V8,
+        PG_DDL_V9, PG_DDL_V10, PG_DDL_V11, PG_DDL_V12, PG_DDL_V13, PG_DDL_V14, PG_DDL_V15,
+        PG_DDL_V16, PG_DDL_V17, PG_DDL_V18, PG_DDL_V19,
     ] {
         for stmt in split_pg_statements(ddl) {
             sqlx::query(&stmt)
@@ -696,19 +1472,58 @@ pub async fn migrate_sqlite(pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
                 .with_context(|| format!("auth sqlite migrate: {label}"))?;
         }
     }
-    for (label, stmt) in SQLITE_DDL_V5 {
-        if let Err(e) = sqlx::query(stmt).execute(pool).await {
-            // SQLite's `ALTER TABLE … ADD COLUMN` is not idempotent;
-            // tolerate the duplicate-column error so re-running the
-            // migration on an already-V5 DB is a no-op.
-            let msg = format!("{e}");
-            if msg.contains("duplicate column name") {
-                continue;
-            }
-            return Err(anyhow::anyhow!("auth sqlite migrate: {label}: {e}"));
+    add_sqlite_columns(pool, SQLITE_DDL_V5).await?;
+    for (label, stmt) in SQLITE_DDL_V6.iter().chain(SQLITE_DDL_V7) {
+        sqlx::query(stmt)
+            .execute(pool)
+            .await
+            .with_context(|| format!("auth sqlite migrate: {label}"))?;
+    }
+    let (versioned,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pragma_table_info('zanzibar_tuples', 'auth') \
+         WHERE name = 'deleted_revision'",
+    )
+    .fetch_one(pool)
+    .await
+    .context("auth sqlite migrate: inspect zanzibar_tuples")?;
+    if versioned == 0 {
+        let mut tx = pool
+            .begin()
+            .await
+            .context("auth sqlite migrate: begin V8")?;
+        for (label, stmt) in SQLITE_DDL_V8 {
+            sqlx::query(stmt)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("auth sqlite migrate: {label}"))?;
         }
+        tx.commit()
+            .await
+            .context("auth sqlite migrate: commit V8")?;
     }
-    for (label, stmt) in SQLITE_DDL_V6 {
+    add_sqlite_columns(pool, SQLITE_DDL_V9).await?;
+    add_sqlite_columns(pool, SQLITE_DDL_V10).await?;
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS auth.idx_auth_zanzibar_tuples_expiry \
+         ON zanzibar_tuples (expires_at) \
+         WHERE expires_at IS NOT NULL AND deleted_revision IS NULL",
+    )
+    .execute(pool)
+    .await
+    .context("auth sqlite migrate: idx_zanzibar_tuples_expiry")?;
+    add_sqlite_columns(pool, SQLITE_DDL_V11).await?;
+    add_sqlite_columns(pool, SQLITE_DDL_V13).await?;
+    add_sqlite_columns(pool, SQLITE_DDL_V14).await?;
+    for (label, stmt) in SQLITE_DDL_V12
+        .iter()
+        .chain(SQLITE_DDL_V13_TABLES)
+        .chain(SQLITE_DDL_V14_TABLES)
+        .chain(SQLITE_DDL_V15)
+        .chain(SQLITE_DDL_V16)
+        .chain(SQLITE_DDL_V17)
+        .chain(SQLITE_DDL_V18)
+        .chain(SQLITE_DDL_V19)
+    {
         sqlx::query(stmt)
             .execute(pool)
             .await
@@ -723,6 +1538,23 @@ pub async fn migrate_sqlite(pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Run a pack of `ALTER TABLE … ADD COLUMN` statements. SQLite's form
+/// is not idempotent; tolerate the duplicate-column error so re-running
+/// the migration on an already-migrated DB is a no-op.
+#[cfg(feature = "backend-sqlite")]
+async fn add_sqlite_columns(pool: &sqlx::SqlitePool, pack: &[(&str, &str)]) -> anyhow::Result<()> {
+    for (label, stmt) in pack {
+        if let Err(e) = sqlx::query(stmt).execute(pool).await {
+            let msg = format!("{e}");
+            if msg.contains("duplicate column name") {
+                continue;
+            }
+            return Err(anyhow::anyhow!("auth sqlite migrate: {label}: {e}"));
+        }
+    }
+    Ok(())
+}
+
 /// Split a PG DDL chunk into individual statements. Drops pure-comment
 /// lines first so a `--`-introduced semicolon doesn't fragment a real
 /// statement (mirrors the same trick `assay-workflow::store::postgres`
@@ -0,0 +1,542 @@
+//! RFC 7644 §3.4.2.2 filters and §3.5.2 PATCH paths.
+//!
+//! Filters are evaluated against the rendered JSON resource rather
+//! than pushed down to SQL — the provisioner only takes SQL shortcuts
+//! for the handful of `eq` lookups IdPs actually send (see
+//! [`Filter::eq_lookup`]). Attribute names compare case-insensitively
+//! and may carry a schema URN prefix
+//! (`urn:ietf:params:scim:schemas:core:2.0:User:userName`), which is
+//! dropped. String comparisons are case-insensitive too: every
+//! attribute this server exposes is `caseExact: false` apart from
+//! `id`, and ids never differ only by case.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use super::ScimError;
+
+/// `attr` or `attr.sub`, URN prefix stripped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttrPath {
+    pub attr: String,
+    pub sub: Option<String>,
+}
+
+impl AttrPath {
+    fn parse(raw: &str) -> Result<Self, ScimError> {
+        // The URN itself contains dots (`…:2.0:User`), so split off the
+        // prefix at the last colon before looking for a sub-attribute.
+        let bare = raw.rsplit_once(':').map_or(raw, |(_, name)| name);
+        let (attr, sub) = match bare.split_once('.') {
+            Some((attr, sub)) => (attr, Some(sub)),
+            None => (bare, None),
+        };
+        let valid = |s: &str| {
+            s.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '$')
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '$')
+        };
+        if !valid(attr) || sub.is_some_and(|s| !valid(s)) {
+            return Err(ScimError::InvalidPath(format!(
+                "invalid attribute path {raw:?}"
+            )));
+        }
+        Ok(Self {
+            attr: attr.to_string(),
+            sub: sub.map(str::to_string),
+        })
+    }
+
+    /// Whether this names `attr` (case-insensitively) with no sub-attribute.
+    pub fn is(&self, attr: &str) -> bool {
+        self.sub.is_none() && self.attr.eq_ignore_ascii_case(attr)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    fn parse(word: &str) -> Option<Self> {
+        Some(match word.to_ascii_lowercase().as_str() {
+            "eq" => Self::Eq,
+            "ne" => Self::Ne,
+            "co" => Self::Co,
+            "sw" => Self::Sw,
+            "ew" => Self::Ew,
+            "gt" => Self::Gt,
+            "ge" => Self::Ge,
+            "lt" => Self::Lt,
+            "le" => Self::Le,
+            _ => return None,
+        })
+    }
+}
+
+/// A parsed filter expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Present(AttrPath),
+    Compare(AttrPath, CompareOp, Value),
+    /// `emails[type eq "work"]` — some element of a multi-valued
+    /// attribute matches the inner filter.
+    ValuePath(String, Box<Filter>),
+}
+
+/// A PATCH `path`: `attr`, `attr.sub`, `attr[filter]` or
+/// `attr[filter].sub`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchPath {
+    pub attr: String,
+    pub filter: Option<Filter>,
+    pub sub: Option<String>,
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self, ScimError> {
+        let mut parser = Parser::new(input)?;
+        let filter = parser.or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(invalid_filter(input));
+        }
+        Ok(filter)
+    }
+
+    /// `Some((attr, value))` when the whole filter is a single
+    /// `attr eq "string"` — the shape IdPs use to look a resource up
+    /// before creating it, which the provisioner answers from an index.
+    pub fn eq_lookup(&self) -> Option<(&str, &str)> {
+        match self {
+            Filter::Compare(path, CompareOp::Eq, Value::String(value)) if path.sub.is_none() => {
+                Some((path.attr.as_str(), value.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluate against a resource (or, inside a value path, one
+    /// element of a multi-valued attribute).
+    pub fn matches(&self, resource: &Value) -> bool {
+        match self {
+            Filter::And(a, b) => a.matches(resource) && b.matches(resource),
+            Filter::Or(a, b) => a.matches(resource) || b.matches(resource),
+            Filter::Not(inner) => !inner.matches(resource),
+            Filter::Present(path) => values_at(resource, path).iter().any(|v| match v {
+                Value::Null => false,
+                Value::String(s) => !s.is_empty(),
+                Value::Array(items) => !items.is_empty(),
+                _ => true,
+            }),
+            Filter::Compare(path, op, expected) => values_at(resource, path)
+                .iter()
+                .any(|actual| compare(actual, *op, expected)),
+            Filter::ValuePath(attr, inner) => match get_ci(resource, attr) {
+                Some(Value::Array(items)) => items.iter().any(|item| inner.matches(item)),
+                Some(item @ Value::Object(_)) => inner.matches(item),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl PatchPath {
+    pub fn parse(input: &str) -> Result<Self, ScimError> {
+        let invalid = || ScimError::InvalidPath(format!("invalid path {input:?}"));
+        let Some(open) = input.find('[') else {
+            let path = AttrPath::parse(input.trim())?;
+            return Ok(Self {
+                attr: path.attr,
+                filter: None,
+                sub: path.sub,
+            });
+        };
+        let close = input.rfind(']').ok_or_else(invalid)?;
+        if close < open {
+            return Err(invalid());
+        }
+        let attr = AttrPath::parse(input[..open].trim())?;
+        if attr.sub.is_some() {
+            return Err(invalid());
+        }
+        let filter = Filter::parse(&input[open + 1..close])
+            .map_err(|_| ScimError::InvalidPath(format!("invalid filter in path {input:?}")))?;
+        let rest = input[close + 1..].trim();
+        let sub = match rest.strip_prefix('.') {
+            Some(sub) if !sub.is_empty() => Some(sub.to_string()),
+            Some(_) => return Err(invalid()),
+            None if rest.is_empty() => None,
+            None => return Err(invalid()),
+        };
+        Ok(Self {
+            attr: attr.attr,
+            filter: Some(filter),
+            sub,
+        })
+    }
+}
+
+/// Case-insensitive object key lookup.
+pub fn get_ci<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+/// Every value `path` reaches. Multi-valued attributes fan out; a
+/// multi-valued complex attribute without a sub-attribute compares on
+/// its `value` sub-attribute (RFC 7644 §3.4.2.2).
+fn values_at<'a>(resource: &'a Value, path: &AttrPath) -> Vec<&'a Value> {
+    let Some(top) = get_ci(resource, &path.attr) else {
+        return Vec::new();
+    };
+    let items: Vec<&Value> = match top {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+    items
+        .into_iter()
+        .filter_map(|item| match (&path.sub, item) {
+            (Some(sub), item) => get_ci(item, sub),
+            (None, Value::Object(_)) if top.is_array() => get_ci(item, "value"),
+            (None, item) => Some(item),
+        })
+        .collect()
+}
+
+fn compare(actual: &Value, op: CompareOp, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::String(a), Value::String(b)) => {
+            let (a, b) = (a.to_lowercase(), b.to_lowercase());
+            match op {
+                CompareOp::Co => a.contains(&b),
+                CompareOp::Sw => a.starts_with(&b),
+                CompareOp::Ew => a.ends_with(&b),
+                _ => ordered(a.cmp(&b), op),
+            }
+        }
+        (Value::Number(a), Value::Number(b)) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).is_some_and(|ord| ordered(ord, op)),
+            _ => false,
+        },
+        (Value::Bool(a), Value::Bool(b)) => match op {
+            CompareOp::Eq => a == b,
+            CompareOp::Ne => a != b,
+            _ => false,
+        },
+        (actual, Value::Null) => match op {
+            CompareOp::Eq => actual.is_null(),
+            CompareOp::Ne => !actual.is_null(),
+            _ => false,
+        },
+        _ => op == CompareOp::Ne,
+    }
+}
+
+fn ordered(ord: Ordering, op: CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => ord == Ordering::Equal,
+        CompareOp::Ne => ord != Ordering::Equal,
+        CompareOp::Gt => ord == Ordering::Greater,
+        CompareOp::Ge => ord != Ordering::Less,
+        CompareOp::Lt => ord == Ordering::Less,
+        CompareOp::Le => ord != Ordering::Greater,
+        CompareOp::Co | CompareOp::Sw | CompareOp::Ew => false,
+    }
+}
+
+fn invalid_filter(input: &str) -> ScimError {
+    ScimError::InvalidFilter(format!("invalid filter {input:?}"))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Literal(Value),
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Self, ScimError> {
+        Ok(Self {
+            input,
+            tokens: tokenize(input)?,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, ScimError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| invalid_filter(self.input))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn or(&mut self) -> Result<Filter, ScimError> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            self.pos += 1;
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter, ScimError> {
+        let mut left = self.unary()?;
+        while self.keyword("and") {
+            self.pos += 1;
+            left = Filter::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Filter, ScimError> {
+        if self.keyword("not") {
+            self.pos += 1;
+            return Ok(Filter::Not(Box::new(self.group()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            return self.group();
+        }
+        self.attr_expr()
+    }
+
+    fn group(&mut self) -> Result<Filter, ScimError> {
+        if self.next()? != Token::Open {
+            return Err(invalid_filter(self.input));
+        }
+        let inner = self.or()?;
+        if self.next()? != Token::Close {
+            return Err(invalid_filter(self.input));
+        }
+        Ok(inner)
+    }
+
+    fn attr_expr(&mut self) -> Result<Filter, ScimError> {
+        let Token::Word(raw) = self.next()? else {
+            return Err(invalid_filter(self.input));
+        };
+        let path = AttrPath::parse(&raw).map_err(|_| invalid_filter(self.input))?;
+        if self.peek() == Some(&Token::OpenBracket) {
+            self.pos += 1;
+            let inner = self.or()?;
+            if self.next()? != Token::CloseBracket || path.sub.is_some() {
+                return Err(invalid_filter(self.input));
+            }
+            return Ok(Filter::ValuePath(path.attr, Box::new(inner)));
+        }
+        let Token::Word(op) = self.next()? else {
+            return Err(invalid_filter(self.input));
+        };
+        if op.eq_ignore_ascii_case("pr") {
+            return Ok(Filter::Present(path));
+        }
+        let op = CompareOp::parse(&op).ok_or_else(|| invalid_filter(self.input))?;
+        let value = match self.next()? {
+            Token::Literal(value) => value,
+            Token::Word(word) => match word.to_ascii_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => serde_json::from_str::<serde_json::Number>(&word)
+                    .map(Value::Number)
+                    .map_err(|_| invalid_filter(self.input))?,
+            },
+            _ => return Err(invalid_filter(self.input)),
+        };
+        Ok(Filter::Compare(path, op, value))
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ScimError> {
+    let mut tokens = Vec::new();
+    let bytes = input.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\n' | b'\r' => i += 1,
+            b'(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            b')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            b'[' => {
+                tokens.push(Token::OpenBracket);
+                i += 1;
+            }
+            b']' => {
+                tokens.push(Token::CloseBracket);
+                i += 1;
+            }
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if i >= bytes.len() {
+                    return Err(invalid_filter(input));
+                }
+                i += 1;
+                let literal: String =
+                    serde_json::from_str(&input[start..i]).map_err(|_| invalid_filter(input))?;
+                tokens.push(Token::Literal(Value::String(literal)));
+            }
+            _ => {
+                let start = i;
+                while i < bytes.len() && !b" \t\n\r()[]\"".contains(&bytes[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Word(input[start..i].to_string()));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user() -> Value {
+        json!({
+            "id": "usr_1",
+            "userName": "Alice@Example.com",
+            "active": true,
+            "name": {"givenName": "Alice", "familyName": "Liddell"},
+            "emails": [
+                {"value": "alice@example.com", "type": "work", "primary": true},
+                {"value": "alice@home.example", "type": "home"}
+            ],
+            "meta": {"lastModified": "2026-10-01T00:00:00Z"}
+        })
+    }
+
+    fn check(filter: &str) -> bool {
+        Filter::parse(filter).unwrap().matches(&user())
+    }
+
+    #[test]
+    fn comparisons_are_case_insensitive() {
+        assert!(check(r#"userName eq "alice@example.com""#));
+        assert!(check(r#"USERNAME Eq "ALICE@EXAMPLE.COM""#));
+        assert!(check(
+            r#"urn:ietf:params:scim:schemas:core:2.0:User:userName sw "alice""#
+        ));
+        assert!(check(r#"name.familyName co "iddel""#));
+        assert!(!check(r#"userName ne "alice@example.com""#));
+        assert!(check(r#"meta.lastModified gt "2026-09-30T00:00:00Z""#));
+        assert!(check("active eq true"));
+        assert!(!check("active eq false"));
+    }
+
+    #[test]
+    fn multi_valued_attributes_fan_out() {
+        assert!(check(r#"emails eq "alice@home.example""#));
+        assert!(check(r#"emails.value ew "example.com""#));
+        assert!(check(
+            r#"emails[type eq "work" and value co "example.com"]"#
+        ));
+        assert!(!check(r#"emails[type eq "other"]"#));
+        assert!(check("emails pr"));
+        assert!(!check("externalId pr"));
+    }
+
+    #[test]
+    fn logical_operators_and_precedence() {
+        assert!(check(
+            r#"userName eq "nobody" or active eq true and name.givenName eq "alice""#
+        ));
+        assert!(!check(
+            r#"(userName eq "nobody" or active eq true) and name.givenName eq "bob""#
+        ));
+        assert!(check(r#"not (userName eq "nobody")"#));
+    }
+
+    #[test]
+    fn eq_lookup_recognises_single_equality() {
+        let filter = Filter::parse(r#"externalId eq "00u1""#).unwrap();
+        assert_eq!(filter.eq_lookup(), Some(("externalId", "00u1")));
+        let filter = Filter::parse(r#"externalId eq "a" and active eq true"#).unwrap();
+        assert_eq!(filter.eq_lookup(), None);
+    }
+
+    #[test]
+    fn malformed_filters_are_rejected() {
+        for bad in [
+            "userName",
+            r#"userName xx "a""#,
+            r#"userName eq "unterminated"#,
+            r#"(userName eq "a""#,
+            r#"userName eq "a" extra"#,
+            r#"emails[type eq "work""#,
+        ] {
+            assert!(
+                matches!(Filter::parse(bad), Err(ScimError::InvalidFilter(_))),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn patch_paths() {
+        assert_eq!(
+            PatchPath::parse("name.givenName").unwrap(),
+            PatchPath {
+                attr: "name".into(),
+                filter: None,
+                sub: Some("givenName".into())
+            }
+        );
+        let path = PatchPath::parse(r#"emails[type eq "work"].value"#).unwrap();
+        assert_eq!(path.attr, "emails");
+        assert_eq!(path.sub.as_deref(), Some("value"));
+        assert!(path.filter.unwrap().matches(&json!({"type": "work"})));
+        let path = PatchPath::parse(r#"members[value eq "usr_2"]"#).unwrap();
+        assert_eq!(path.attr, "members");
+        assert!(path.sub.is_none());
+        assert!(PatchPath::parse("members[value eq").is_err());
+        assert!(PatchPath::parse("bad attr").is_err());
+    }
+}
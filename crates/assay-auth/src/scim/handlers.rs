@@ -0,0 +1,1534 @@
+//! SCIM HTTP surface. Merged into the OIDC-spec router, so the engine
+//! serves it under `/auth/scim/v2/`.
+//!
+//! - `GET    /scim/v2/ServiceProviderConfig`
+//! - `GET    /scim/v2/ResourceTypes`
+//! - `GET    /scim/v2/Users?filter=&startIndex=&count=&attributes=&excludedAttributes=`
+//! - `POST   /scim/v2/Users`
+//! - `GET    /scim/v2/Users/{id}`
+//! - `PUT    /scim/v2/Users/{id}`        → replace; `active: false` deprovisions
+//! - `PATCH  /scim/v2/Users/{id}`        → `PatchOp`
+//! - `DELETE /scim/v2/Users/{id}`        → revokes sessions, then deletes
+//! - `GET    /scim/v2/Groups?…`, `POST /scim/v2/Groups`
+//! - `GET | PUT | PATCH | DELETE /scim/v2/Groups/{id}`
+//! - `POST   /scim/v2/Bulk`
+//!
+//! Every route but the two discovery documents needs
+//! `Authorization: Bearer <scim token>`. Bodies are parsed by hand
+//! rather than through axum's `Json` extractor, which would reject the
+//! `application/scim+json` content type IdPs send.
+//!
+//! Two leniencies IdPs rely on: a `PUT` without `emails` keeps the
+//! current address (clearing it would lock a password user out), and a
+//! group `PUT` without `members` leaves membership alone.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{FromRef, Path, Query, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use super::filter::{Filter, get_ci};
+use super::patch::{self, PatchOperation};
+use super::{
+    BULK_RESPONSE_SCHEMA, ERROR_SCHEMA, GROUP_SCHEMA, LIST_RESPONSE_SCHEMA, ScimError,
+    ScimGroupAttrs, ScimProvisioner, ScimUserAttrs, USER_SCHEMA,
+};
+use crate::ctx::AuthCtx;
+use crate::orgs::{
+    Group, GroupMemberType, MAX_SLUG_LEN, OrgError, OrgManager, OrgRole, Organization,
+};
+use crate::session::SessionManager;
+use crate::store::User;
+
+/// Most operations one `/Bulk` request may carry.
+pub const MAX_BULK_OPERATIONS: usize = 100;
+/// Largest accepted `/Bulk` body, in bytes.
+pub const MAX_BULK_PAYLOAD: usize = 1 << 20;
+/// Page size when the IdP sends no `count`.
+pub const DEFAULT_COUNT: i64 = 100;
+/// Largest honoured `count`.
+pub const MAX_COUNT: i64 = 200;
+
+/// Users fetched per round trip when a filter has to scan.
+const SCAN_PAGE: i64 = 500;
+
+pub fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    AuthCtx: FromRef<S>,
+{
+    Router::new()
+        .route(
+            "/scim/v2/ServiceProviderConfig",
+            get(service_provider_config),
+        )
+        .route("/scim/v2/ResourceTypes", get(resource_types))
+        .route("/scim/v2/Users", get(list_users).post(create_user))
+        .route(
+            "/scim/v2/Users/{id}",
+            get(get_user)
+                .put(replace_user)
+                .patch(patch_user)
+                .delete(delete_user),
+        )
+        .route("/scim/v2/Groups", get(list_groups).post(create_group))
+        .route(
+            "/scim/v2/Groups/{id}",
+            get(get_group)
+                .put(replace_group)
+                .patch(patch_group)
+                .delete(delete_group),
+        )
+        .route("/scim/v2/Bulk", post(bulk))
+}
+
+// =====================================================================
+//   handlers
+// =====================================================================
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuery {
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// 1-based.
+    #[serde(default)]
+    pub start_index: Option<i64>,
+    #[serde(default)]
+    pub count: Option<i64>,
+    #[serde(default)]
+    pub attributes: Option<String>,
+    #[serde(default)]
+    pub excluded_attributes: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceQuery {
+    #[serde(default)]
+    pub attributes: Option<String>,
+    #[serde(default)]
+    pub excluded_attributes: Option<String>,
+}
+
+async fn service_provider_config(State(ctx): State<AuthCtx>) -> Response {
+    let Some(scim) = ctx.scim.as_ref() else {
+        return not_configured();
+    };
+    let body = json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch": {"supported": true},
+        "bulk": {
+            "supported": true,
+            "maxOperations": MAX_BULK_OPERATIONS,
+            "maxPayloadSize": MAX_BULK_PAYLOAD,
+        },
+        "filter": {"supported": true, "maxResults": MAX_COUNT},
+        "changePassword": {"supported": true},
+        "sort": {"supported": false},
+        "etag": {"supported": false},
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "OAuth Bearer Token",
+            "description": "A SCIM bearer token configured on the server.",
+            "primary": true,
+        }],
+        "meta": {
+            "resourceType": "ServiceProviderConfig",
+            "location": format!("{}/ServiceProviderConfig", scim.base_url()),
+        },
+    });
+    scim_response(StatusCode::OK, body)
+}
+
+async fn resource_types(State(ctx): State<AuthCtx>) -> Response {
+    let Some(scim) = ctx.scim.as_ref() else {
+        return not_configured();
+    };
+    let resource_type = |name: &str, endpoint: &str, schema: &str| {
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id": name,
+            "name": name,
+            "endpoint": endpoint,
+            "schema": schema,
+            "meta": {
+                "resourceType": "ResourceType",
+                "location": format!("{}/ResourceTypes/{name}", scim.base_url()),
+            },
+        })
+    };
+    let mut resources = vec![resource_type("User", "/Users", USER_SCHEMA)];
+    if scim.organization().is_some() {
+        resources.push(resource_type("Group", "/Groups", GROUP_SCHEMA));
+    }
+    scim_response(StatusCode::OK, list_response(resources.len(), 1, resources))
+}
+
+async fn list_users(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Query(q): Query<ListQuery>,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    respond(Provisioning::new(&ctx, scim).list_users(&q).await)
+}
+
+async fn create_user(State(ctx): State<AuthCtx>, headers: HeaderMap, body: Bytes) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let p = Provisioning::new(&ctx, scim);
+    respond(async { p.create_user(parse_body(&body)?).await }.await)
+}
+
+async fn get_user(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(q): Query<ResourceQuery>,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let result = Provisioning::new(&ctx, scim).user_resource(&id, true).await;
+    respond(result.map(|mut user| {
+        project(
+            &mut user,
+            q.attributes.as_deref(),
+            q.excluded_attributes.as_deref(),
+        );
+        (StatusCode::OK, user)
+    }))
+}
+
+async fn replace_user(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let p = Provisioning::new(&ctx, scim);
+    respond(async { p.replace_user(&id, parse_body(&body)?).await }.await)
+}
+
+async fn patch_user(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let p = Provisioning::new(&ctx, scim);
+    respond(async { p.patch_user(&id, parse_body(&body)?).await }.await)
+}
+
+async fn delete_user(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    respond(Provisioning::new(&ctx, scim).delete_user(&id).await)
+}
+
+async fn list_groups(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Query(q): Query<ListQuery>,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    respond(Provisioning::new(&ctx, scim).list_groups(&q).await)
+}
+
+async fn create_group(State(ctx): State<AuthCtx>, headers: HeaderMap, body: Bytes) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let p = Provisioning::new(&ctx, scim);
+    respond(async { p.create_group(parse_body(&body)?).await }.await)
+}
+
+async fn get_group(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(q): Query<ResourceQuery>,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let with_members = !lists_attr(q.excluded_attributes.as_deref(), "members");
+    let result = Provisioning::new(&ctx, scim)
+        .group_resource(&id, with_members)
+        .await;
+    respond(result.map(|mut group| {
+        project(
+            &mut group,
+            q.attributes.as_deref(),
+            q.excluded_attributes.as_deref(),
+        );
+        (StatusCode::OK, group)
+    }))
+}
+
+async fn replace_group(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let p = Provisioning::new(&ctx, scim);
+    respond(async { p.replace_group(&id, parse_body(&body)?).await }.await)
+}
+
+async fn patch_group(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    let p = Provisioning::new(&ctx, scim);
+    respond(async { p.patch_group(&id, parse_body(&body)?).await }.await)
+}
+
+async fn delete_group(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    respond(Provisioning::new(&ctx, scim).delete_group(&id).await)
+}
+
+async fn bulk(State(ctx): State<AuthCtx>, headers: HeaderMap, body: Bytes) -> Response {
+    let scim = match gate(&headers, &ctx) {
+        Ok(scim) => scim,
+        Err(r) => return *r,
+    };
+    respond(Provisioning::new(&ctx, scim).bulk(&body).await)
+}
+
+// =====================================================================
+//   provisioning
+// =====================================================================
+
+/// What a user create / replace carries once parsed.
+struct UserInput {
+    user_name: String,
+    external_id: Option<String>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+    display_name: Option<String>,
+    email: Option<String>,
+    active: bool,
+    password: Option<String>,
+}
+
+/// What a group create / replace carries once parsed. `members` is
+/// `None` when the attribute was absent.
+struct GroupInput {
+    display_name: String,
+    external_id: Option<String>,
+    members: Option<Vec<MemberRef>>,
+}
+
+/// A `members` entry: the id, and its type when the IdP said.
+struct MemberRef {
+    id: String,
+    member_type: Option<GroupMemberType>,
+}
+
+#[derive(Deserialize)]
+struct BulkRequest {
+    #[serde(default, rename = "failOnErrors")]
+    fail_on_errors: Option<usize>,
+    #[serde(rename = "Operations", alias = "operations")]
+    operations: Vec<BulkOperation>,
+}
+
+#[derive(Deserialize)]
+struct BulkOperation {
+    method: String,
+    #[serde(default, rename = "bulkId")]
+    bulk_id: Option<String>,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
+/// One request's view of the provisioner: the auth context plus the
+/// SCIM config, so the operations can be shared between the
+/// per-resource routes and `/Bulk`.
+struct Provisioning<'a> {
+    ctx: &'a AuthCtx,
+    scim: &'a ScimProvisioner,
+}
+
+type Outcome = Result<(StatusCode, Value), ScimError>;
+
+impl<'a> Provisioning<'a> {
+    fn new(ctx: &'a AuthCtx, scim: &'a ScimProvisioner) -> Self {
+        Self { ctx, scim }
+    }
+
+    fn location(&self, kind: &str, id: &str) -> String {
+        format!("{}/{kind}/{id}", self.scim.base_url())
+    }
+
+    /// The organization provisioned users join and `/Groups` maps to.
+    async fn org(&self) -> Result<Option<(&'a OrgManager, Organization)>, ScimError> {
+        let (Some(name), Some(orgs)) = (self.scim.organization(), self.ctx.orgs.as_ref()) else {
+            return Ok(None);
+        };
+        match orgs.resolve_org(name).await? {
+            Some(org) => Ok(Some((orgs, org))),
+            None => Err(ScimError::Internal(format!(
+                "organization {name:?} does not exist"
+            ))),
+        }
+    }
+
+    async fn groups_org(&self) -> Result<(&'a OrgManager, Organization), ScimError> {
+        self.org().await?.ok_or(ScimError::GroupsDisabled)
+    }
+
+    // -----------------------------------------------------------------
+    //   users
+    // -----------------------------------------------------------------
+
+    fn render_user(&self, user: &User, attrs: Option<&ScimUserAttrs>) -> Value {
+        let user_name = attrs
+            .map(|a| a.user_name.clone())
+            .or_else(|| user.email.clone())
+            .unwrap_or_else(|| user.id.clone());
+        let mut resource = Map::new();
+        resource.insert("schemas".into(), json!([USER_SCHEMA]));
+        resource.insert("id".into(), json!(user.id));
+        if let Some(external_id) = attrs.and_then(|a| a.external_id.as_ref()) {
+            resource.insert("externalId".into(), json!(external_id));
+        }
+        resource.insert("userName".into(), json!(user_name));
+        let given = attrs.and_then(|a| a.given_name.as_deref());
+        let family = attrs.and_then(|a| a.family_name.as_deref());
+        if given.is_some() || family.is_some() {
+            let mut name = Map::new();
+            if let Some(given) = given {
+                name.insert("givenName".into(), json!(given));
+            }
+            if let Some(family) = family {
+                name.insert("familyName".into(), json!(family));
+            }
+            let formatted: Vec<&str> = given.into_iter().chain(family).collect();
+            name.insert("formatted".into(), json!(formatted.join(" ")));
+            resource.insert("name".into(), Value::Object(name));
+        }
+        if let Some(display_name) = &user.display_name {
+            resource.insert("displayName".into(), json!(display_name));
+        }
+        if let Some(email) = &user.email {
+            resource.insert(
+                "emails".into(),
+                json!([{"value": email, "type": "work", "primary": true}]),
+            );
+        }
+        resource.insert("active".into(), json!(attrs.is_none_or(|a| a.active)));
+        resource.insert(
+            "meta".into(),
+            json!({
+                "resourceType": "User",
+                "created": rfc3339(user.created_at),
+                "lastModified": rfc3339(attrs.map_or(user.created_at, |a| a.updated_at)),
+                "location": self.location("Users", &user.id),
+            }),
+        );
+        Value::Object(resource)
+    }
+
+    /// The user's direct groups in the bound organization, as the
+    /// read-only `groups` attribute.
+    async fn user_groups(&self, user_id: &str) -> Result<Option<Value>, ScimError> {
+        let Some((orgs, org)) = self.org().await? else {
+            return Ok(None);
+        };
+        let mut groups = Vec::new();
+        for group_id in orgs
+            .store()
+            .list_member_groups(GroupMemberType::User, user_id)
+            .await?
+        {
+            if let Some(group) = orgs.store().get_group(&group_id).await?
+                && group.org_id == org.id
+            {
+                groups.push(json!({
+                    "value": group.id,
+                    "display": group.display_name,
+                    "$ref": self.location("Groups", &group.id),
+                }));
+            }
+        }
+        Ok(Some(Value::Array(groups)))
+    }
+
+    async fn user_resource(&self, id: &str, with_groups: bool) -> Result<Value, ScimError> {
+        let Some(user) = self.ctx.users.get_user_by_id(id).await? else {
+            return Err(ScimError::NotFound(format!("user {id} not found")));
+        };
+        let attrs = self.scim.store().get_user(id).await?;
+        let mut resource = self.render_user(&user, attrs.as_ref());
+        if with_groups && let Some(groups) = self.user_groups(id).await? {
+            resource["groups"] = groups;
+        }
+        Ok(resource)
+    }
+
+    /// Render a page of users with one sidecar round trip.
+    async fn render_users(&self, users: &[User]) -> Result<Vec<Value>, ScimError> {
+        let ids: Vec<String> = users.iter().map(|u| u.id.clone()).collect();
+        let attrs: HashMap<String, ScimUserAttrs> = self
+            .scim
+            .store()
+            .list_users(&ids)
+            .await?
+            .into_iter()
+            .map(|a| (a.user_id.clone(), a))
+            .collect();
+        Ok(users
+            .iter()
+            .map(|u| self.render_user(u, attrs.get(&u.id)))
+            .collect())
+    }
+
+    async fn list_users(&self, q: &ListQuery) -> Outcome {
+        let filter = q.filter.as_deref().map(Filter::parse).transpose()?;
+        let users = self.ctx.users.as_ref();
+        // The lookups IdPs make before every create go to an index;
+        // anything else scans.
+        let indexed = match filter.as_ref().and_then(Filter::eq_lookup) {
+            Some((attr, value)) if attr.eq_ignore_ascii_case("id") => {
+                Some(users.get_user_by_id(value).await?.into_iter().collect())
+            }
+            Some((attr, value)) if attr.eq_ignore_ascii_case("userName") => {
+                let mut found = Vec::new();
+                if let Some(attrs) = self.scim.store().get_user_by_user_name(value).await? {
+                    found.extend(users.get_user_by_id(&attrs.user_id).await?);
+                }
+                // Users the IdP never provisioned answer to their email.
+                found.extend(users.get_user_by_email(value).await?);
+                Some(found)
+            }
+            Some((attr, value)) if attr.eq_ignore_ascii_case("externalId") => {
+                let mut found = Vec::new();
+                if let Some(attrs) = self.scim.store().get_user_by_external_id(value).await? {
+                    found.extend(users.get_user_by_id(&attrs.user_id).await?);
+                }
+                Some(found)
+            }
+            _ => None,
+        };
+        let matches = |resource: &Value| filter.as_ref().is_none_or(|f| f.matches(resource));
+        let mut resources: Vec<Value> = Vec::new();
+        match indexed {
+            Some(found) => {
+                let mut seen = HashSet::new();
+                let found: Vec<User> = found
+                    .into_iter()
+                    .filter(|u| seen.insert(u.id.clone()))
+                    .collect();
+                resources.extend(self.render_users(&found).await?.into_iter().filter(matches));
+            }
+            None => {
+                let mut offset = 0;
+                loop {
+                    let page = users.list_users(SCAN_PAGE, offset, None).await?;
+                    resources.extend(self.render_users(&page).await?.into_iter().filter(matches));
+                    if (page.len() as i64) < SCAN_PAGE {
+                        break;
+                    }
+                    offset += SCAN_PAGE;
+                }
+            }
+        }
+        let total = resources.len();
+        let (start, count) = page_bounds(q);
+        let mut page: Vec<Value> = resources.into_iter().skip(start - 1).take(count).collect();
+        let with_groups = !lists_attr(q.excluded_attributes.as_deref(), "groups");
+        for resource in &mut page {
+            if with_groups
+                && let Some(id) = resource["id"].as_str().map(str::to_string)
+                && let Some(groups) = self.user_groups(&id).await?
+            {
+                resource["groups"] = groups;
+            }
+            project(
+                resource,
+                q.attributes.as_deref(),
+                q.excluded_attributes.as_deref(),
+            );
+        }
+        Ok((StatusCode::OK, list_response(total, start, page)))
+    }
+
+    async fn create_user(&self, body: Value) -> Outcome {
+        let input = user_input(&body)?;
+        self.check_user_unique(&input, None).await?;
+        let id = format!(
+            "usr_{}",
+            data_encoding::BASE64URL_NOPAD.encode(&rand::random::<[u8; 12]>())
+        );
+        let now = now_secs();
+        let user = User {
+            id: id.clone(),
+            email: input.email.clone(),
+            // The IdP vouches for the addresses it pushes.
+            email_verified: input.email.is_some(),
+            display_name: input.display_name.clone(),
+            created_at: now,
+        };
+        self.ctx.users.create_user(&user).await?;
+        self.write_user_attrs(&id, &input, now).await?;
+        if let Some((orgs, org)) = self.org().await? {
+            orgs.set_membership(&org.id, &id, OrgRole::Member).await?;
+        }
+        let mut resource = self.user_resource(&id, false).await?;
+        resource["groups"] = json!([]);
+        Ok((StatusCode::CREATED, resource))
+    }
+
+    async fn replace_user(&self, id: &str, body: Value) -> Outcome {
+        let Some(mut user) = self.ctx.users.get_user_by_id(id).await? else {
+            return Err(ScimError::NotFound(format!("user {id} not found")));
+        };
+        check_id(&body, id)?;
+        let input = user_input(&body)?;
+        self.check_user_unique(&input, Some(id)).await?;
+        let previous = self.scim.store().get_user(id).await?;
+        if let Some(email) = &input.email
+            && user.email.as_ref() != Some(email)
+        {
+            user.email = Some(email.clone());
+            user.email_verified = true;
+        }
+        user.display_name = input.display_name.clone();
+        self.ctx.users.update_user(&user).await?;
+        self.write_user_attrs(id, &input, now_secs()).await?;
+        if previous.is_none_or(|p| p.active) && !input.active {
+            self.revoke(id).await?;
+        }
+        Ok((StatusCode::OK, self.user_resource(id, true).await?))
+    }
+
+    async fn patch_user(&self, id: &str, body: Value) -> Outcome {
+        let operations = patch_operations(&body)?;
+        let mut resource = self.user_resource(id, false).await?;
+        patch::apply(&mut resource, &operations)?;
+        self.replace_user(id, resource).await
+    }
+
+    async fn delete_user(&self, id: &str) -> Outcome {
+        if self.ctx.users.get_user_by_id(id).await?.is_none() {
+            return Err(ScimError::NotFound(format!("user {id} not found")));
+        }
+        self.revoke(id).await?;
+        if let Some(orgs) = self.ctx.orgs.as_ref() {
+            orgs.forget_user(id).await?;
+        }
+        self.ctx.users.delete_user(id).await?;
+        Ok((StatusCode::NO_CONTENT, Value::Null))
+    }
+
+    /// `userName` and email must not belong to anyone but `except`.
+    async fn check_user_unique(
+        &self,
+        input: &UserInput,
+        except: Option<&str>,
+    ) -> Result<(), ScimError> {
+        if let Some(other) = self
+            .scim
+            .store()
+            .get_user_by_user_name(&input.user_name)
+            .await?
+            && Some(other.user_id.as_str()) != except
+        {
+            return Err(ScimError::Uniqueness(format!(
+                "userName {:?} is already taken",
+                input.user_name
+            )));
+        }
+        if let Some(email) = &input.email
+            && let Some(other) = self.ctx.users.get_user_by_email(email).await?
+            && Some(other.id.as_str()) != except
+        {
+            return Err(ScimError::Uniqueness(format!(
+                "email {email:?} is already taken"
+            )));
+        }
+        Ok(())
+    }
+
+    async fn write_user_attrs(
+        &self,
+        id: &str,
+        input: &UserInput,
+        now: f64,
+    ) -> Result<(), ScimError> {
+        self.scim
+            .store()
+            .upsert_user(&ScimUserAttrs {
+                user_id: id.to_string(),
+                user_name: input.user_name.clone(),
+                external_id: input.external_id.clone(),
+                given_name: input.given_name.clone(),
+                family_name: input.family_name.clone(),
+                active: input.active,
+                updated_at: now,
+            })
+            .await?;
+        if let Some(password) = &input.password {
+            let hash = crate::password::PasswordHasher::default().hash(password)?;
+            self.ctx.users.set_password_hash(id, &hash).await?;
+        }
+        Ok(())
+    }
+
+    /// Deprovisioning: end every session and refresh token the user
+    /// holds so access stops now, not when the tokens expire.
+    async fn revoke(&self, user_id: &str) -> Result<(), ScimError> {
+        SessionManager::with_default_duration(self.ctx.sessions.clone())
+            .revoke_for_user(user_id)
+            .await?;
+        #[cfg(feature = "auth-oidc-provider")]
+        if let Some(provider) = self.ctx.oidc_provider.as_ref() {
+            provider.refresh.revoke_for_user(user_id).await?;
+        }
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------
+    //   groups
+    // -----------------------------------------------------------------
+
+    async fn render_group(
+        &self,
+        orgs: &OrgManager,
+        group: &Group,
+        attrs: Option<&ScimGroupAttrs>,
+        with_members: bool,
+    ) -> Result<Value, ScimError> {
+        let mut resource = Map::new();
+        resource.insert("schemas".into(), json!([GROUP_SCHEMA]));
+        resource.insert("id".into(), json!(group.id));
+        if let Some(external_id) = attrs.and_then(|a| a.external_id.as_ref()) {
+            resource.insert("externalId".into(), json!(external_id));
+        }
+        resource.insert("displayName".into(), json!(group.display_name));
+        if with_members {
+            let members: Vec<Value> = orgs
+                .store()
+                .list_group_members(&group.id)
+                .await?
+                .into_iter()
+                .map(|m| {
+                    let (kind, endpoint) = match m.member_type {
+                        GroupMemberType::User => ("User", "Users"),
+                        GroupMemberType::Group => ("Group", "Groups"),
+                    };
+                    json!({
+                        "value": m.member_id,
+                        "type": kind,
+                        "$ref": self.location(endpoint, &m.member_id),
+                    })
+                })
+                .collect();
+            resource.insert("members".into(), Value::Array(members));
+        }
+        resource.insert(
+            "meta".into(),
+            json!({
+                "resourceType": "Group",
+                "created": rfc3339(group.created_at),
+                "lastModified": rfc3339(attrs.map_or(group.created_at, |a| a.updated_at)),
+                "location": self.location("Groups", &group.id),
+            }),
+        );
+        Ok(Value::Object(resource))
+    }
+
+    /// A group of the bound organization. Groups of other orgs are
+    /// invisible to SCIM.
+    async fn find_group(
+        &self,
+        orgs: &OrgManager,
+        org: &Organization,
+        id: &str,
+    ) -> Result<Group, ScimError> {
+        match orgs.store().get_group(id).await? {
+            Some(group) if group.org_id == org.id => Ok(group),
+            _ => Err(ScimError::NotFound(format!("group {id} not found"))),
+        }
+    }
+
+    async fn group_resource(&self, id: &str, with_members: bool) -> Result<Value, ScimError> {
+        let (orgs, org) = self.groups_org().await?;
+        let group = self.find_group(orgs, &org, id).await?;
+        let attrs = self.scim.store().get_group(id).await?;
+        self.render_group(orgs, &group, attrs.as_ref(), with_members)
+            .await
+    }
+
+    async fn list_groups(&self, q: &ListQuery) -> Outcome {
+        let (orgs, org) = self.groups_org().await?;
+        let filter = q.filter.as_deref().map(Filter::parse).transpose()?;
+        let with_members =
+            filter.is_some() || !lists_attr(q.excluded_attributes.as_deref(), "members");
+        let groups = orgs.store().list_groups(&org.id).await?;
+        let ids: Vec<String> = groups.iter().map(|g| g.id.clone()).collect();
+        let attrs: HashMap<String, ScimGroupAttrs> = self
+            .scim
+            .store()
+            .list_groups(&ids)
+            .await?
+            .into_iter()
+            .map(|a| (a.group_id.clone(), a))
+            .collect();
+        let mut resources = Vec::new();
+        for group in &groups {
+            let resource = self
+                .render_group(orgs, group, attrs.get(&group.id), with_members)
+                .await?;
+            if filter.as_ref().is_none_or(|f| f.matches(&resource)) {
+                resources.push(resource);
+            }
+        }
+        let total = resources.len();
+        let (start, count) = page_bounds(q);
+        let mut page: Vec<Value> = resources.into_iter().skip(start - 1).take(count).collect();
+        for resource in &mut page {
+            project(
+                resource,
+                q.attributes.as_deref(),
+                q.excluded_attributes.as_deref(),
+            );
+        }
+        Ok((StatusCode::OK, list_response(total, start, page)))
+    }
+
+    async fn create_group(&self, body: Value) -> Outcome {
+        let (orgs, org) = self.groups_org().await?;
+        let input = group_input(&body)?;
+        let base = slugify(&input.display_name);
+        let mut attempt = 1;
+        let group = loop {
+            let slug = if attempt == 1 {
+                base.clone()
+            } else {
+                format!("{base}-{attempt}")
+            };
+            match orgs.create_group(&org.id, &slug, &input.display_name).await {
+                Ok(group) => break group,
+                Err(e)
+                    if attempt < 100
+                        && matches!(e.downcast_ref(), Some(OrgError::SlugTaken(_))) =>
+                {
+                    attempt += 1;
+                }
+                Err(e) => return Err(org_error(e)),
+            }
+        };
+        self.scim
+            .store()
+            .upsert_group(&ScimGroupAttrs {
+                group_id: group.id.clone(),
+                external_id: input.external_id.clone(),
+                updated_at: group.created_at,
+            })
+            .await?;
+        for member in input.members.unwrap_or_default() {
+            let (member_type, member_id) = self.resolve_member(orgs, &org, member).await?;
+            self.add_member(orgs, &org, &group.id, member_type, &member_id)
+                .await?;
+        }
+        Ok((
+            StatusCode::CREATED,
+            self.group_resource(&group.id, true).await?,
+        ))
+    }
+
+    async fn replace_group(&self, id: &str, body: Value) -> Outcome {
+        let (orgs, org) = self.groups_org().await?;
+        let group = self.find_group(orgs, &org, id).await?;
+        check_id(&body, id)?;
+        let input = group_input(&body)?;
+        if input.display_name != group.display_name {
+            orgs.store().update_group(id, &input.display_name).await?;
+        }
+        self.scim
+            .store()
+            .upsert_group(&ScimGroupAttrs {
+                group_id: id.to_string(),
+                external_id: input.external_id.clone(),
+                updated_at: now_secs(),
+            })
+            .await?;
+        if let Some(members) = input.members {
+            let mut wanted = HashSet::new();
+            for member in members {
+                wanted.insert(self.resolve_member(orgs, &org, member).await?);
+            }
+            let current: HashSet<(GroupMemberType, String)> = orgs
+                .store()
+                .list_group_members(id)
+                .await?
+                .into_iter()
+                .map(|m| (m.member_type, m.member_id))
+                .collect();
+            for (member_type, member_id) in current.difference(&wanted) {
+                orgs.remove_group_member(id, *member_type, member_id)
+                    .await?;
+            }
+            for (member_type, member_id) in wanted.difference(&current) {
+                self.add_member(orgs, &org, id, *member_type, member_id)
+                    .await?;
+            }
+        }
+        Ok((StatusCode::OK, self.group_resource(id, true).await?))
+    }
+
+    async fn patch_group(&self, id: &str, body: Value) -> Outcome {
+        let operations = patch_operations(&body)?;
+        let mut resource = self.group_resource(id, true).await?;
+        patch::apply(&mut resource, &operations)?;
+        if get_ci(&resource, "members").is_none() {
+            // `remove` of the whole attribute empties the group.
+            resource["members"] = json!([]);
+        }
+        self.replace_group(id, resource).await
+    }
+
+    async fn delete_group(&self, id: &str) -> Outcome {
+        let (orgs, org) = self.groups_org().await?;
+        self.find_group(orgs, &org, id).await?;
+        orgs.delete_group(id).await?;
+        Ok((StatusCode::NO_CONTENT, Value::Null))
+    }
+
+    /// Settle a member's type: as given, else a group of this
+    /// organization with that id, else a user.
+    async fn resolve_member(
+        &self,
+        orgs: &OrgManager,
+        org: &Organization,
+        member: MemberRef,
+    ) -> Result<(GroupMemberType, String), ScimError> {
+        let member_type = match member.member_type {
+            Some(t) => t,
+            None => match orgs.store().get_group(&member.id).await? {
+                Some(group) if group.org_id == org.id => GroupMemberType::Group,
+                _ => GroupMemberType::User,
+            },
+        };
+        Ok((member_type, member.id))
+    }
+
+    /// Add a group member, enrolling a user in the organization first
+    /// if they aren't in it yet.
+    async fn add_member(
+        &self,
+        orgs: &OrgManager,
+        org: &Organization,
+        group_id: &str,
+        member_type: GroupMemberType,
+        member_id: &str,
+    ) -> Result<(), ScimError> {
+        if member_type == GroupMemberType::User {
+            if self.ctx.users.get_user_by_id(member_id).await?.is_none() {
+                return Err(ScimError::InvalidValue(format!(
+                    "unknown user {member_id:?}"
+                )));
+            }
+            if orgs
+                .store()
+                .get_membership(&org.id, member_id)
+                .await?
+                .is_none()
+            {
+                orgs.set_membership(&org.id, member_id, OrgRole::Member)
+                    .await?;
+            }
+        }
+        orgs.add_group_member(group_id, member_type, member_id)
+            .await
+            .map_err(org_error)?;
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------
+    //   bulk
+    // -----------------------------------------------------------------
+
+    async fn bulk(&self, body: &[u8]) -> Outcome {
+        if body.len() > MAX_BULK_PAYLOAD {
+            return Err(ScimError::TooLarge(format!(
+                "bulk payload exceeds {MAX_BULK_PAYLOAD} bytes"
+            )));
+        }
+        let request: BulkRequest = serde_json::from_slice(body)
+            .map_err(|e| ScimError::InvalidSyntax(format!("invalid bulk request: {e}")))?;
+        if request.operations.len() > MAX_BULK_OPERATIONS {
+            return Err(ScimError::TooLarge(format!(
+                "bulk requests carry at most {MAX_BULK_OPERATIONS} operations"
+            )));
+        }
+        let mut ids: HashMap<String, String> = HashMap::new();
+        let mut errors = 0;
+        let mut results = Vec::new();
+        for operation in request.operations {
+            let mut result = Map::new();
+            result.insert("method".into(), json!(operation.method));
+            if let Some(bulk_id) = &operation.bulk_id {
+                result.insert("bulkId".into(), json!(bulk_id));
+            }
+            match self.bulk_operation(&operation, &ids).await {
+                Ok((status, resource)) => {
+                    if let Some(location) = resource["meta"]["location"].as_str() {
+                        result.insert("location".into(), json!(location));
+                    }
+                    if let (Some(bulk_id), Some(id)) = (&operation.bulk_id, resource["id"].as_str())
+                    {
+                        ids.insert(bulk_id.clone(), id.to_string());
+                    }
+                    result.insert("status".into(), json!(status.as_u16().to_string()));
+                }
+                Err(e) => {
+                    errors += 1;
+                    result.insert("status".into(), json!(e.status().as_u16().to_string()));
+                    result.insert("response".into(), error_body(&e));
+                }
+            }
+            results.push(Value::Object(result));
+            if request.fail_on_errors.is_some_and(|limit| errors >= limit) {
+                break;
+            }
+        }
+        Ok((
+            StatusCode::OK,
+            json!({"schemas": [BULK_RESPONSE_SCHEMA], "Operations": results}),
+        ))
+    }
+
+    async fn bulk_operation(
+        &self,
+        operation: &BulkOperation,
+        ids: &HashMap<String, String>,
+    ) -> Outcome {
+        let path = resolve_bulk_ids(Value::String(operation.path.clone()), ids)?;
+        let path = path.as_str().unwrap_or_default();
+        let data = || {
+            operation
+                .data
+                .clone()
+                .ok_or_else(|| ScimError::InvalidSyntax(format!("{} needs data", operation.method)))
+                .and_then(|data| resolve_bulk_ids(data, ids))
+        };
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let method = operation.method.to_ascii_uppercase();
+        match (method.as_str(), segments.as_slice()) {
+            ("POST", ["Users"]) => self.create_user(data()?).await,
+            ("POST", ["Groups"]) => self.create_group(data()?).await,
+            ("PUT", ["Users", id]) => self.replace_user(id, data()?).await,
+            ("PUT", ["Groups", id]) => self.replace_group(id, data()?).await,
+            ("PATCH", ["Users", id]) => self.patch_user(id, data()?).await,
+            ("PATCH", ["Groups", id]) => self.patch_group(id, data()?).await,
+            ("DELETE", ["Users", id]) => self.delete_user(id).await,
+            ("DELETE", ["Groups", id]) => self.delete_group(id).await,
+            _ => Err(ScimError::InvalidValue(format!(
+                "unsupported bulk operation {} {path}",
+                operation.method
+            ))),
+        }
+    }
+}
+
+// =====================================================================
+//   parsing
+// =====================================================================
+
+fn parse_body(body: &[u8]) -> Result<Value, ScimError> {
+    match serde_json::from_slice(body) {
+        Ok(value @ Value::Object(_)) => Ok(value),
+        Ok(_) => Err(ScimError::InvalidSyntax(
+            "body must be a JSON object".into(),
+        )),
+        Err(e) => Err(ScimError::InvalidSyntax(format!("invalid JSON: {e}"))),
+    }
+}
+
+fn str_attr(value: &Value, key: &str) -> Option<String> {
+    get_ci(value, key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn user_input(resource: &Value) -> Result<UserInput, ScimError> {
+    let user_name = str_attr(resource, "userName")
+        .ok_or_else(|| ScimError::InvalidValue("userName is required".into()))?;
+    let name = get_ci(resource, "name");
+    let active = match get_ci(resource, "active") {
+        None | Some(Value::Null) => true,
+        Some(Value::Bool(active)) => *active,
+        // Entra sends booleans as strings in PATCH values.
+        Some(Value::String(s)) if s.eq_ignore_ascii_case("true") => true,
+        Some(Value::String(s)) if s.eq_ignore_ascii_case("false") => false,
+        Some(other) => {
+            return Err(ScimError::InvalidValue(format!(
+                "active must be a boolean, got {other}"
+            )));
+        }
+    };
+    let email =
+        primary_email(resource)?.or_else(|| user_name.contains('@').then(|| user_name.clone()));
+    Ok(UserInput {
+        external_id: str_attr(resource, "externalId"),
+        given_name: name.and_then(|n| str_attr(n, "givenName")),
+        family_name: name.and_then(|n| str_attr(n, "familyName")),
+        display_name: str_attr(resource, "displayName")
+            .or_else(|| name.and_then(|n| str_attr(n, "formatted"))),
+        email,
+        active,
+        password: str_attr(resource, "password"),
+        user_name,
+    })
+}
+
+/// The `primary` email, else the first one.
+fn primary_email(resource: &Value) -> Result<Option<String>, ScimError> {
+    let emails = match get_ci(resource, "emails") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Array(emails)) => emails,
+        Some(_) => return Err(ScimError::InvalidValue("emails must be an array".into())),
+    };
+    let primary = emails
+        .iter()
+        .find(|e| get_ci(e, "primary").and_then(Value::as_bool) == Some(true))
+        .or_else(|| emails.first());
+    Ok(primary.and_then(|e| str_attr(e, "value")))
+}
+
+fn group_input(resource: &Value) -> Result<GroupInput, ScimError> {
+    let display_name = str_attr(resource, "displayName")
+        .ok_or_else(|| ScimError::InvalidValue("displayName is required".into()))?;
+    let members = match get_ci(resource, "members") {
+        None => None,
+        Some(Value::Null) => Some(Vec::new()),
+        Some(Value::Array(items)) => Some(
+            items
+                .iter()
+                .map(member_ref)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Some(_) => return Err(ScimError::InvalidValue("members must be an array".into())),
+    };
+    Ok(GroupInput {
+        display_name,
+        external_id: str_attr(resource, "externalId"),
+        members,
+    })
+}
+
+fn member_ref(item: &Value) -> Result<MemberRef, ScimError> {
+    let id = str_attr(item, "value")
+        .ok_or_else(|| ScimError::InvalidValue("members need a value".into()))?;
+    let member_type = match str_attr(item, "type") {
+        None => None,
+        Some(t) if t.eq_ignore_ascii_case("user") => Some(GroupMemberType::User),
+        Some(t) if t.eq_ignore_ascii_case("group") => Some(GroupMemberType::Group),
+        Some(t) => {
+            return Err(ScimError::InvalidValue(format!(
+                "unknown member type {t:?}"
+            )));
+        }
+    };
+    Ok(MemberRef { id, member_type })
+}
+
+fn patch_operations(body: &Value) -> Result<Vec<PatchOperation>, ScimError> {
+    let operations = get_ci(body, "Operations")
+        .cloned()
+        .ok_or_else(|| ScimError::InvalidSyntax("PatchOp needs Operations".into()))?;
+    serde_json::from_value(operations)
+        .map_err(|e| ScimError::InvalidSyntax(format!("invalid Operations: {e}")))
+}
+
+/// A body's `id`, when present, must name the resource it's sent to.
+fn check_id(body: &Value, id: &str) -> Result<(), ScimError> {
+    match get_ci(body, "id").and_then(Value::as_str) {
+        Some(other) if other != id => Err(ScimError::Mutability("id is immutable".into())),
+        _ => Ok(()),
+    }
+}
+
+/// Replace every `bulkId:<id>` string with the id the operation that
+/// declared it created.
+fn resolve_bulk_ids(value: Value, ids: &HashMap<String, String>) -> Result<Value, ScimError> {
+    Ok(match value {
+        Value::String(s) if s.contains("bulkId:") => {
+            let mut resolved = Vec::new();
+            for segment in s.split('/') {
+                match segment.strip_prefix("bulkId:") {
+                    Some(bulk_id) => match ids.get(bulk_id) {
+                        Some(id) => resolved.push(id.as_str()),
+                        None => {
+                            return Err(ScimError::InvalidValue(format!(
+                                "unresolved bulkId {bulk_id:?}"
+                            )));
+                        }
+                    },
+                    None => resolved.push(segment),
+                }
+            }
+            Value::String(resolved.join("/"))
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| resolve_bulk_ids(item, ids))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(k, v)| Ok((k, resolve_bulk_ids(v, ids)?)))
+                .collect::<Result<_, ScimError>>()?,
+        ),
+        other => other,
+    })
+}
+
+/// An org-scoped slug for an IdP group name: lowercase, runs of
+/// anything else collapsed to `-`, room left for a `-N` suffix.
+fn slugify(display_name: &str) -> String {
+    let mut slug = String::new();
+    for c in display_name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(MAX_SLUG_LEN - 4);
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "group".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn org_error(e: anyhow::Error) -> ScimError {
+    match e.downcast_ref::<OrgError>() {
+        Some(OrgError::SlugTaken(_)) => ScimError::Uniqueness(e.to_string()),
+        Some(_) => ScimError::InvalidValue(e.to_string()),
+        None => e.into(),
+    }
+}
+
+// =====================================================================
+//   listing + projection
+// =====================================================================
+
+/// `(startIndex, count)`, clamped to 1-based and `0..=MAX_COUNT`.
+fn page_bounds(q: &ListQuery) -> (usize, usize) {
+    let start = q.start_index.unwrap_or(1).max(1) as usize;
+    let count = q.count.unwrap_or(DEFAULT_COUNT).clamp(0, MAX_COUNT) as usize;
+    (start, count)
+}
+
+fn list_response(total: usize, start: usize, resources: Vec<Value>) -> Value {
+    json!({
+        "schemas": [LIST_RESPONSE_SCHEMA],
+        "totalResults": total,
+        "startIndex": start,
+        "itemsPerPage": resources.len(),
+        "Resources": resources,
+    })
+}
+
+/// `attr` / `attr.sub` entries of an `attributes` list, URN prefixes
+/// dropped.
+fn attr_list(list: &str) -> Vec<(String, Option<String>)> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|raw| {
+            let bare = raw.rsplit_once(':').map_or(raw, |(_, name)| name);
+            match bare.split_once('.') {
+                Some((attr, sub)) => (attr.to_string(), Some(sub.to_string())),
+                None => (bare.to_string(), None),
+            }
+        })
+        .collect()
+}
+
+/// Whether `list` names the whole of `attr`.
+fn lists_attr(list: Option<&str>, attr: &str) -> bool {
+    list.is_some_and(|list| {
+        attr_list(list)
+            .iter()
+            .any(|(a, sub)| sub.is_none() && a.eq_ignore_ascii_case(attr))
+    })
+}
+
+/// Apply `attributes` / `excludedAttributes` (RFC 7644 §3.9). `id` and
+/// `schemas` are always returned.
+fn project(resource: &mut Value, attributes: Option<&str>, excluded: Option<&str>) {
+    let Some(object) = resource.as_object_mut() else {
+        return;
+    };
+    let always = |key: &str| key == "id" || key == "schemas";
+    if let Some(list) = attributes {
+        let wanted = attr_list(list);
+        object.retain(|key, value| {
+            if always(key) {
+                return true;
+            }
+            let subs: Vec<&Option<String>> = wanted
+                .iter()
+                .filter(|(attr, _)| attr.eq_ignore_ascii_case(key))
+                .map(|(_, sub)| sub)
+                .collect();
+            if subs.is_empty() {
+                return false;
+            }
+            if subs.iter().any(|sub| sub.is_none()) {
+                return true;
+            }
+            let keep = |name: &str| {
+                subs.iter()
+                    .any(|sub| sub.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(name)))
+            };
+            match value {
+                Value::Object(inner) => inner.retain(|k, _| keep(k)),
+                Value::Array(items) => {
+                    for item in items.iter_mut() {
+                        if let Value::Object(inner) = item {
+                            inner.retain(|k, _| keep(k));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            true
+        });
+    }
+    if let Some(list) = excluded {
+        for (attr, sub) in attr_list(list) {
+            let Some(key) = object
+                .keys()
+                .find(|k| k.eq_ignore_ascii_case(&attr) && !always(k))
+                .cloned()
+            else {
+                continue;
+            };
+            match sub {
+                None => {
+                    object.remove(&key);
+                }
+                Some(sub) => {
+                    if let Some(Value::Object(inner)) = object.get_mut(&key) {
+                        inner.retain(|k, _| !k.eq_ignore_ascii_case(&sub));
+                    }
+                }
+            }
+        }
+    }
+}
+
+// =====================================================================
+//   responses
+// =====================================================================
+
+fn gate<'a>(headers: &HeaderMap, ctx: &'a AuthCtx) -> Result<&'a ScimProvisioner, Box<Response>> {
+    let Some(scim) = ctx.scim.as_ref() else {
+        return Err(Box::new(not_configured()));
+    };
+    if let Some(token) = crate::gate::bearer_token(headers)
+        && scim.check_token(token)
+    {
+        return Ok(scim);
+    }
+    let mut response = scim_response(
+        StatusCode::UNAUTHORIZED,
+        json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": "401",
+            "detail": "SCIM bearer token required",
+        }),
+    );
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    Err(Box::new(response))
+}
+
+fn not_configured() -> Response {
+    scim_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": "503",
+            "detail": "SCIM provisioning not configured",
+        }),
+    )
+}
+
+fn error_body(e: &ScimError) -> Value {
+    let mut body = json!({
+        "schemas": [ERROR_SCHEMA],
+        "status": e.status().as_u16().to_string(),
+        "detail": e.to_string(),
+    });
+    if let Some(scim_type) = e.scim_type() {
+        body["scimType"] = json!(scim_type);
+    }
+    body
+}
+
+fn scim_response(status: StatusCode, body: Value) -> Response {
+    let mut response = (status, body.to_string()).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/scim+json"),
+    );
+    response
+}
+
+fn respond(outcome: Outcome) -> Response {
+    match outcome {
+        Ok((StatusCode::NO_CONTENT, _)) => StatusCode::NO_CONTENT.into_response(),
+        Ok((status, body)) => {
+            let location = (status == StatusCode::CREATED)
+                .then(|| body["meta"]["location"].as_str())
+                .flatten()
+                .and_then(|l| HeaderValue::from_str(l).ok());
+            let mut response = scim_response(status, body);
+            if let Some(location) = location {
+                response.headers_mut().insert(header::LOCATION, location);
+            }
+            response
+        }
+        Err(e) => {
+            if let ScimError::Internal(detail) = &e {
+                tracing::error!(%detail, "scim request failed");
+            }
+            scim_response(e.status(), error_body(&e))
+        }
+    }
+}
+
+fn rfc3339(secs: f64) -> String {
+    chrono::DateTime::from_timestamp(secs.trunc() as i64, (secs.fract() * 1e9) as u32)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn now_secs() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_fit_the_org_rules() {
+        assert_eq!(slugify("Engineering"), "engineering");
+        assert_eq!(slugify("  Sales & Marketing (EU) "), "sales-marketing-eu");
+        assert_eq!(slugify("!!!"), "group");
+        let long = slugify(&"x".repeat(200));
+        assert!(crate::orgs::valid_slug(&format!("{long}-99")));
+    }
+
+    #[test]
+    fn projection_keeps_id_and_requested_attributes() {
+        let mut user = json!({
+            "schemas": [USER_SCHEMA],
+            "id": "usr_1",
+            "userName": "a@example.com",
+            "name": {"givenName": "A", "familyName": "B"},
+            "active": true,
+        });
+        project(&mut user, Some("userName,name.givenName"), None);
+        assert_eq!(
+            user,
+            json!({
+                "schemas": [USER_SCHEMA],
+                "id": "usr_1",
+                "userName": "a@example.com",
+                "name": {"givenName": "A"},
+            })
+        );
+        project(
+            &mut user,
+            None,
+            Some("urn:ietf:params:scim:schemas:core:2.0:User:name,id"),
+        );
+        assert_eq!(user.get("name"), None);
+        assert_eq!(user["id"], "usr_1");
+    }
+
+    #[test]
+    fn bulk_ids_resolve_in_paths_and_data() {
+        let ids = HashMap::from([("u1".to_string(), "usr_x".to_string())]);
+        assert_eq!(
+            resolve_bulk_ids(json!("/Users/bulkId:u1"), &ids).unwrap(),
+            json!("/Users/usr_x")
+        );
+        assert_eq!(
+            resolve_bulk_ids(json!({"members": [{"value": "bulkId:u1"}]}), &ids).unwrap(),
+            json!({"members": [{"value": "usr_x"}]})
+        );
+        assert!(resolve_bulk_ids(json!("bulkId:nope"), &ids).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! SCIM 2.0 provisioning (RFC 7643 / RFC 7644) for users and groups.
+//!
+//! Module shape:
+//!
+//! - this file — the sidecar attribute types ([`ScimUserAttrs`],
+//!   [`ScimGroupAttrs`]), [`ScimError`], and [`ScimProvisioner`], the
+//!   config [`crate::ctx::AuthCtx`] carries.
+//! - [`filter`] — filter expressions and PATCH paths.
+//! - [`patch`] — PATCH operations applied to a rendered resource.
+//! - [`store`] — the [`ScimStore`] async trait.
+//! - [`postgres`] / [`sqlite`] — backend implementations.
+//! - [`handlers`] — the `/scim/v2/*` HTTP surface.
+//!
+//! Users are the ordinary [`crate::store::UserStore`] rows; the SCIM
+//! store only adds what the protocol needs on top (`userName`,
+//! `externalId`, name parts, `active`). Groups are the
+//! [`crate::orgs`] groups of the one organization the provisioner is
+//! bound to, so IdP-pushed groups land in the same Zanzibar
+//! projection as hand-made ones. Without an organization only
+//! `/Users` is served.
+//!
+//! Deprovisioning is the point of SCIM for most operators: setting
+//! `active: false` (or deleting the user) revokes every session and
+//! OIDC refresh token the user holds, and [`is_deactivated`] keeps
+//! them from logging back in.
+//!
+//! The IdP authenticates with its own bearer tokens, separate from the
+//! admin api-keys — a SCIM token can provision users but not touch the
+//! rest of the admin surface.
+
+#[cfg(feature = "backend-postgres")]
+pub mod postgres;
+#[cfg(feature = "backend-sqlite")]
+pub mod sqlite;
+
+pub mod filter;
+pub mod handlers;
+pub mod patch;
+pub mod store;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+
+use crate::ctx::AuthCtx;
+use crate::state::AdminApiKeys;
+
+pub use handlers::router;
+pub use store::ScimStore;
+
+#[cfg(feature = "backend-postgres")]
+pub use postgres::PostgresScimStore;
+#[cfg(feature = "backend-sqlite")]
+pub use sqlite::SqliteScimStore;
+
+pub const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const PATCH_OP_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const BULK_REQUEST_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:BulkRequest";
+pub const BULK_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:BulkResponse";
+pub const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// SCIM attributes of one user — row in `auth.scim_users`. Users the
+/// IdP never touched have no row; they render with defaults (email as
+/// `userName`, `active: true`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScimUserAttrs {
+    pub user_id: String,
+    /// Unique case-insensitively.
+    pub user_name: String,
+    pub external_id: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub active: bool,
+    pub updated_at: f64,
+}
+
+/// SCIM attributes of one group — row in `auth.scim_groups`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScimGroupAttrs {
+    pub group_id: String,
+    pub external_id: Option<String>,
+    pub updated_at: f64,
+}
+
+/// A request the provisioner refuses, with the RFC 7644 §3.12
+/// `scimType` it answers with.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ScimError {
+    #[error("{0}")]
+    InvalidFilter(String),
+    #[error("{0}")]
+    InvalidSyntax(String),
+    #[error("{0}")]
+    InvalidPath(String),
+    #[error("{0}")]
+    InvalidValue(String),
+    #[error("{0}")]
+    NoTarget(String),
+    #[error("{0}")]
+    Mutability(String),
+    #[error("{0}")]
+    Uniqueness(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    TooLarge(String),
+    #[error("groups need an organization; set [auth.scim] organization")]
+    GroupsDisabled,
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ScimError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidFilter(_)
+            | Self::InvalidSyntax(_)
+            | Self::InvalidPath(_)
+            | Self::InvalidValue(_)
+            | Self::NoTarget(_)
+            | Self::Mutability(_) => StatusCode::BAD_REQUEST,
+            Self::Uniqueness(_) => StatusCode::CONFLICT,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::GroupsDisabled => StatusCode::NOT_IMPLEMENTED,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn scim_type(&self) -> Option<&'static str> {
+        match self {
+            Self::InvalidFilter(_) => Some("invalidFilter"),
+            Self::InvalidSyntax(_) => Some("invalidSyntax"),
+            Self::InvalidPath(_) => Some("invalidPath"),
+            Self::InvalidValue(_) => Some("invalidValue"),
+            Self::NoTarget(_) => Some("noTarget"),
+            Self::Mutability(_) => Some("mutability"),
+            Self::Uniqueness(_) => Some("uniqueness"),
+            Self::NotFound(_) | Self::TooLarge(_) | Self::GroupsDisabled | Self::Internal(_) => {
+                None
+            }
+        }
+    }
+}
+
+impl From<anyhow::Error> for ScimError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Internal(format!("{e:#}"))
+    }
+}
+
+impl From<crate::Error> for ScimError {
+    fn from(e: crate::Error) -> Self {
+        Self::Internal(e.to_string())
+    }
+}
+
+/// SCIM server configuration. Cheap to clone.
+#[derive(Clone)]
+pub struct ScimProvisioner {
+    store: Arc<dyn ScimStore>,
+    tokens: AdminApiKeys,
+    organization: Option<String>,
+    base_url: String,
+}
+
+impl ScimProvisioner {
+    /// `tokens` are the bearers the IdP presents (compared in constant
+    /// time, like admin keys). `base_url` is the public URL of the
+    /// `/scim/v2` root, used for `meta.location`.
+    pub fn new(
+        store: Arc<dyn ScimStore>,
+        tokens: AdminApiKeys,
+        base_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            store,
+            tokens,
+            organization: None,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Bind `/Groups` to an organization (id or slug). Provisioned
+    /// users also become members of it.
+    pub fn with_organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    pub fn store(&self) -> &Arc<dyn ScimStore> {
+        &self.store
+    }
+
+    pub fn organization(&self) -> Option<&str> {
+        self.organization.as_deref()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Whether `presented` is one of the configured bearers.
+    pub fn check_token(&self, presented: &str) -> bool {
+        self.tokens.enabled() && self.tokens.check(presented)
+    }
+}
+
+/// `true` when the IdP has set the user `active: false`. Login paths
+/// refuse to mint a session for such users; `false` whenever SCIM
+/// isn't configured.
+pub async fn is_deactivated(ctx: &AuthCtx, user_id: &str) -> anyhow::Result<bool> {
+    let Some(scim) = ctx.scim.as_ref() else {
+        return Ok(false);
+    };
+    Ok(scim
+        .store
+        .get_user(user_id)
+        .await?
+        .is_some_and(|attrs| !attrs.active))
+}
@@ -0,0 +1,311 @@
+//! RFC 7644 §3.5.2 PATCH, applied to a rendered resource.
+//!
+//! The provisioner renders the current resource, applies the
+//! operations here, and persists the result through the same path as
+//! a `PUT` — so PATCH needs no per-attribute write logic of its own.
+//!
+//! Lenient where the big IdPs deviate from the RFC: op names are
+//! case-insensitive (Entra sends `Replace`), and an `add`/`replace`
+//! whose value filter matches no element appends one built from an
+//! `attr eq "literal"` filter (`emails[type eq "work"].value` on a
+//! user with no work email).
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::ScimError;
+use super::filter::{CompareOp, Filter, PatchPath, get_ci};
+
+/// One entry of a `PatchOp` request's `Operations`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PatchOperation {
+    pub op: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Add,
+    Replace,
+    Remove,
+}
+
+/// Apply `operations` to `resource` in order.
+pub fn apply(resource: &mut Value, operations: &[PatchOperation]) -> Result<(), ScimError> {
+    for operation in operations {
+        let op = match operation.op.to_ascii_lowercase().as_str() {
+            "add" => Op::Add,
+            "replace" => Op::Replace,
+            "remove" => Op::Remove,
+            other => {
+                return Err(ScimError::InvalidSyntax(format!(
+                    "unknown PATCH op {other:?}"
+                )));
+            }
+        };
+        let path = operation
+            .path
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .map(PatchPath::parse)
+            .transpose()?;
+        match (op, path, operation.value.as_ref()) {
+            (Op::Remove, None, _) => {
+                return Err(ScimError::NoTarget("remove needs a path".to_string()));
+            }
+            (Op::Remove, Some(path), value) => remove(resource, &path, value),
+            (_, None, Some(Value::Object(values))) => {
+                for (key, value) in values {
+                    let path = PatchPath::parse(key)?;
+                    set(resource, &path, value.clone(), op)?;
+                }
+            }
+            (_, None, _) => {
+                return Err(ScimError::InvalidValue(
+                    "a PATCH op without a path needs an object value".to_string(),
+                ));
+            }
+            (_, Some(path), Some(value)) => set(resource, &path, value.clone(), op)?,
+            (_, Some(_), None) => {
+                return Err(ScimError::InvalidValue(format!(
+                    "{} needs a value",
+                    operation.op
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The object's key matching `name` case-insensitively, if any.
+fn key_ci(object: &Map<String, Value>, name: &str) -> Option<String> {
+    object
+        .keys()
+        .find(|k| k.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+fn object_mut(value: &mut Value) -> &mut Map<String, Value> {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    value.as_object_mut().expect("just made an object")
+}
+
+/// Slot for `name` in `object`, reusing an existing key's casing.
+fn slot<'a>(object: &'a mut Map<String, Value>, name: &str) -> &'a mut Value {
+    let key = key_ci(object, name).unwrap_or_else(|| name.to_string());
+    object.entry(key).or_insert(Value::Null)
+}
+
+fn set(resource: &mut Value, path: &PatchPath, value: Value, op: Op) -> Result<(), ScimError> {
+    let object = object_mut(resource);
+    let target = slot(object, &path.attr);
+    let Some(filter) = &path.filter else {
+        match &path.sub {
+            Some(sub) => {
+                *slot(object_mut(target), sub) = value;
+            }
+            None => match (op, target.as_array_mut(), value) {
+                // `add` on a multi-valued attribute appends; duplicates
+                // are dropped so a re-sent add is a no-op.
+                (Op::Add, Some(items), Value::Array(new)) => {
+                    for item in new {
+                        if !items.contains(&item) {
+                            items.push(item);
+                        }
+                    }
+                }
+                (Op::Add, Some(items), item) => {
+                    if !items.contains(&item) {
+                        items.push(item);
+                    }
+                }
+                (_, _, value) => *target = value,
+            },
+        }
+        return Ok(());
+    };
+    if !target.is_array() {
+        *target = Value::Array(Vec::new());
+    }
+    let items = target.as_array_mut().expect("just made an array");
+    let mut matched = false;
+    for item in items.iter_mut().filter(|item| filter.matches(item)) {
+        matched = true;
+        match &path.sub {
+            Some(sub) => *slot(object_mut(item), sub) = value.clone(),
+            None => *item = value.clone(),
+        }
+    }
+    if matched {
+        return Ok(());
+    }
+    let Filter::Compare(key, CompareOp::Eq, literal) = filter else {
+        return Err(ScimError::NoTarget(format!(
+            "no {} element matches the path filter",
+            path.attr
+        )));
+    };
+    let mut item = Map::new();
+    item.insert(key.attr.clone(), literal.clone());
+    match &path.sub {
+        Some(sub) => {
+            item.insert(sub.clone(), value);
+        }
+        None => match value {
+            Value::Object(fields) => item.extend(fields),
+            other => {
+                item.insert("value".to_string(), other);
+            }
+        },
+    }
+    items.push(Value::Object(item));
+    Ok(())
+}
+
+fn remove(resource: &mut Value, path: &PatchPath, value: Option<&Value>) {
+    let Some(object) = resource.as_object_mut() else {
+        return;
+    };
+    let Some(key) = key_ci(object, &path.attr) else {
+        return;
+    };
+    match (&path.filter, &path.sub) {
+        (None, None) => match (value, object.get_mut(&key)) {
+            // Entra removes members by value rather than by filter:
+            // `{"op":"remove","path":"members","value":[{"value":"…"}]}`.
+            (Some(Value::Array(gone)), Some(Value::Array(items))) => {
+                let ids: Vec<&Value> = gone.iter().filter_map(|g| get_ci(g, "value")).collect();
+                items.retain(|item| get_ci(item, "value").is_none_or(|v| !ids.contains(&v)));
+            }
+            _ => {
+                object.remove(&key);
+            }
+        },
+        (None, Some(sub)) => {
+            if let Some(Value::Object(inner)) = object.get_mut(&key)
+                && let Some(sub_key) = key_ci(inner, sub)
+            {
+                inner.remove(&sub_key);
+            }
+        }
+        (Some(filter), sub) => {
+            if let Some(Value::Array(items)) = object.get_mut(&key) {
+                match sub {
+                    None => items.retain(|item| !filter.matches(item)),
+                    Some(sub) => {
+                        for item in items.iter_mut().filter(|item| filter.matches(item)) {
+                            if let Some(inner) = item.as_object_mut()
+                                && let Some(sub_key) = key_ci(inner, sub)
+                            {
+                                inner.remove(&sub_key);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ops(value: Value) -> Vec<PatchOperation> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn pathless_replace_merges_attributes() {
+        // Okta's deactivation.
+        let mut user = json!({"userName": "a@example.com", "active": true});
+        apply(
+            &mut user,
+            &ops(json!([{"op": "replace", "value": {"active": false, "name.givenName": "A"}}])),
+        )
+        .unwrap();
+        assert_eq!(user["active"], false);
+        assert_eq!(user["name"]["givenName"], "A");
+    }
+
+    #[test]
+    fn entra_style_ops_are_accepted() {
+        let mut user = json!({"userName": "a@example.com", "Active": true, "emails": []});
+        apply(
+            &mut user,
+            &ops(json!([
+                {"op": "Replace", "path": "active", "value": "False"},
+                {"op": "Add", "path": "emails[type eq \"work\"].value", "value": "a@work.example"},
+            ])),
+        )
+        .unwrap();
+        assert_eq!(user["Active"], "False");
+        assert_eq!(
+            user["emails"],
+            json!([{"type": "work", "value": "a@work.example"}])
+        );
+        apply(
+            &mut user,
+            &ops(json!([{"op": "replace", "path": "emails[type eq \"work\"].value", "value": "b@work.example"}])),
+        )
+        .unwrap();
+        assert_eq!(user["emails"][0]["value"], "b@work.example");
+        assert_eq!(user["emails"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn member_add_and_remove() {
+        let mut group = json!({"displayName": "eng", "members": [{"value": "usr_1"}]});
+        apply(
+            &mut group,
+            &ops(json!([{"op": "add", "path": "members", "value": [{"value": "usr_1"}, {"value": "usr_2"}]}])),
+        )
+        .unwrap();
+        assert_eq!(group["members"].as_array().unwrap().len(), 2);
+        apply(
+            &mut group,
+            &ops(json!([{"op": "remove", "path": "members[value eq \"usr_1\"]"}])),
+        )
+        .unwrap();
+        assert_eq!(group["members"], json!([{"value": "usr_2"}]));
+        apply(
+            &mut group,
+            &ops(json!([{"op": "remove", "path": "members", "value": [{"value": "usr_2"}]}])),
+        )
+        .unwrap();
+        assert_eq!(group["members"], json!([]));
+        apply(
+            &mut group,
+            &ops(json!([{"op": "remove", "path": "members"}])),
+        )
+        .unwrap();
+        assert!(group.get("members").is_none());
+    }
+
+    #[test]
+    fn invalid_operations_are_rejected() {
+        let mut user = json!({});
+        let err = apply(
+            &mut user,
+            &ops(json!([{"op": "move", "path": "a", "value": 1}])),
+        );
+        assert!(matches!(err, Err(ScimError::InvalidSyntax(_))));
+        let err = apply(&mut user, &ops(json!([{"op": "remove"}])));
+        assert!(matches!(err, Err(ScimError::NoTarget(_))));
+        let err = apply(&mut user, &ops(json!([{"op": "replace", "value": "x"}])));
+        assert!(matches!(err, Err(ScimError::InvalidValue(_))));
+        let err = apply(
+            &mut user,
+            &ops(
+                json!([{"op": "replace", "path": "emails[type ne \"work\"].value", "value": "x"}]),
+            ),
+        );
+        assert!(matches!(err, Err(ScimError::NoTarget(_))));
+    }
+}
@@ -0,0 +1,188 @@
+//! SQLite [`ScimStore`] implementation — same queries as the Postgres
+//! backend with `?` placeholders; the batch lookup expands its `IN`
+//! list instead of binding an array.
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+
+use super::store::ScimStore;
+use super::{ScimGroupAttrs, ScimUserAttrs};
+
+/// SQLite-backed SCIM attribute store. Cheap to clone (the pool is
+/// `Arc`d).
+#[derive(Clone)]
+pub struct SqliteScimStore {
+    pool: SqlitePool,
+}
+
+impl SqliteScimStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+type UserRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    bool,
+    f64,
+);
+
+fn user_from_row(
+    (user_id, user_name, external_id, given_name, family_name, active, updated_at): UserRow,
+) -> ScimUserAttrs {
+    ScimUserAttrs {
+        user_id,
+        user_name,
+        external_id,
+        given_name,
+        family_name,
+        active,
+        updated_at,
+    }
+}
+
+fn group_from_row(
+    (group_id, external_id, updated_at): (String, Option<String>, f64),
+) -> ScimGroupAttrs {
+    ScimGroupAttrs {
+        group_id,
+        external_id,
+        updated_at,
+    }
+}
+
+const USER_COLUMNS: &str =
+    "user_id, user_name, external_id, given_name, family_name, active, updated_at";
+
+#[async_trait::async_trait]
+impl ScimStore for SqliteScimStore {
+    async fn get_user(&self, user_id: &str) -> Result<Option<ScimUserAttrs>> {
+        let row: Option<UserRow> = sqlx::query_as(&format!(
+            "SELECT {USER_COLUMNS} FROM auth.scim_users WHERE user_id = ?"
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.scim_users get")?;
+        Ok(row.map(user_from_row))
+    }
+
+    async fn get_user_by_user_name(&self, user_name: &str) -> Result<Option<ScimUserAttrs>> {
+        let row: Option<UserRow> = sqlx::query_as(&format!(
+            "SELECT {USER_COLUMNS} FROM auth.scim_users WHERE LOWER(user_name) = LOWER(?)"
+        ))
+        .bind(user_name)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.scim_users get by userName")?;
+        Ok(row.map(user_from_row))
+    }
+
+    async fn get_user_by_external_id(&self, external_id: &str) -> Result<Option<ScimUserAttrs>> {
+        let row: Option<UserRow> = sqlx::query_as(&format!(
+            "SELECT {USER_COLUMNS} FROM auth.scim_users WHERE external_id = ? LIMIT 1"
+        ))
+        .bind(external_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.scim_users get by externalId")?;
+        Ok(row.map(user_from_row))
+    }
+
+    async fn list_users(&self, user_ids: &[String]) -> Result<Vec<ScimUserAttrs>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; user_ids.len()].join(", ");
+        let sql =
+            format!("SELECT {USER_COLUMNS} FROM auth.scim_users WHERE user_id IN ({placeholders})");
+        let mut query = sqlx::query_as::<_, UserRow>(&sql);
+        for id in user_ids {
+            query = query.bind(id);
+        }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .context("auth.scim_users list")?;
+        Ok(rows.into_iter().map(user_from_row).collect())
+    }
+
+    async fn upsert_user(&self, attrs: &ScimUserAttrs) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO auth.scim_users
+                (user_id, user_name, external_id, given_name, family_name, active, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (user_id) DO UPDATE SET
+                user_name = excluded.user_name,
+                external_id = excluded.external_id,
+                given_name = excluded.given_name,
+                family_name = excluded.family_name,
+                active = excluded.active,
+                updated_at = excluded.updated_at",
+        )
+        .bind(&attrs.user_id)
+        .bind(&attrs.user_name)
+        .bind(&attrs.external_id)
+        .bind(&attrs.given_name)
+        .bind(&attrs.family_name)
+        .bind(attrs.active)
+        .bind(attrs.updated_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.scim_users upsert")?;
+        Ok(())
+    }
+
+    async fn get_group(&self, group_id: &str) -> Result<Option<ScimGroupAttrs>> {
+        let row: Option<(String, Option<String>, f64)> = sqlx::query_as(
+            "SELECT group_id, external_id, updated_at
+             FROM auth.scim_groups WHERE group_id = ?",
+        )
+        .bind(group_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("auth.scim_groups get")?;
+        Ok(row.map(group_from_row))
+    }
+
+    async fn list_groups(&self, group_ids: &[String]) -> Result<Vec<ScimGroupAttrs>> {
+        if group_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; group_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT group_id, external_id, updated_at
+             FROM auth.scim_groups WHERE group_id IN ({placeholders})"
+        );
+        let mut query = sqlx::query_as::<_, (String, Option<String>, f64)>(&sql);
+        for id in group_ids {
+            query = query.bind(id);
+        }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .context("auth.scim_groups list")?;
+        Ok(rows.into_iter().map(group_from_row).collect())
+    }
+
+    async fn upsert_group(&self, attrs: &ScimGroupAttrs) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO auth.scim_groups (group_id, external_id, updated_at)
+             VALUES (?, ?, ?)
+             ON CONFLICT (group_id) DO UPDATE SET
+                external_id = excluded.external_id,
+                updated_at = excluded.updated_at",
+        )
+        .bind(&attrs.group_id)
+        .bind(&attrs.external_id)
+        .bind(attrs.updated_at)
+        .execute(&self.pool)
+        .await
+        .context("auth.scim_groups upsert")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! [`ScimStore`] trait — the SCIM-only attributes kept beside the
+//! core user and group rows.
+//!
+//! Users and groups themselves live in [`crate::store::UserStore`] and
+//! [`crate::orgs::OrgStore`]; this sidecar only holds what SCIM adds
+//! (`userName`, `externalId`, name parts, `active`). Rows cascade with
+//! the user / group they describe.
+//!
+//! Implementations live in [`super::postgres`] and [`super::sqlite`].
+
+use super::{ScimGroupAttrs, ScimUserAttrs};
+
+#[async_trait::async_trait]
+pub trait ScimStore: Send + Sync + 'static {
+    async fn get_user(&self, user_id: &str) -> anyhow::Result<Option<ScimUserAttrs>>;
+
+    /// Case-insensitive `userName` lookup.
+    async fn get_user_by_user_name(&self, user_name: &str)
+    -> anyhow::Result<Option<ScimUserAttrs>>;
+
+    async fn get_user_by_external_id(
+        &self,
+        external_id: &str,
+    ) -> anyhow::Result<Option<ScimUserAttrs>>;
+
+    /// Attributes for each of `user_ids` that has any — one round trip
+    /// per listing page.
+    async fn list_users(&self, user_ids: &[String]) -> anyhow::Result<Vec<ScimUserAttrs>>;
+
+    /// Insert or replace a user's attributes. The caller checks
+    /// `userName` uniqueness first; a race loses on the unique index.
+    async fn upsert_user(&self, attrs: &ScimUserAttrs) -> anyhow::Result<()>;
+
+    async fn get_group(&self, group_id: &str) -> anyhow::Result<Option<ScimGroupAttrs>>;
+
+    /// Attributes for each of `group_ids` that has any.
+    async fn list_groups(&self, group_ids: &[String]) -> anyhow::Result<Vec<ScimGroupAttrs>>;
+
+    async fn upsert_group(&self, attrs: &ScimGroupAttrs) -> anyhow::Result<()>;
+}
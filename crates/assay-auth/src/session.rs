@@ -14,7 +14,11 @@
 //!
 //! Phase 8 adds a HTTP router under [`router`] that mounts the
 //! session-facing endpoints (`/login`, `/logout`, `/whoami`, passkey
-//! ceremony). The auth top-level router merges this in.
+//! ceremony). The auth top-level router merges this in. With
+//! `auth-mfa`, `/login` stops short of a session for users with TOTP
+//! enrolled and `/login/mfa` finishes the login with a code (see
+//! [`crate::mfa`]). Both are throttled per account and per IP when
+//! [`crate::lockout`] is wired.
 
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -39,6 +43,13 @@ pub const CSRF_COOKIE: &str = "assay_csrf";
 /// [`SessionManager::new`].
 pub const DEFAULT_SESSION_DURATION: Duration = Duration::from_secs(60 * 60 * 24 * 30);
 
+/// RFC 8176 `amr` value for a password login.
+pub const AMR_PASSWORD: &str = "pwd";
+/// RFC 8176 `amr` value for a one-time code (TOTP or recovery code).
+pub const AMR_OTP: &str = "otp";
+/// RFC 8176 `amr` value marking a login that passed more than one factor.
+pub const AMR_MFA: &str = "mfa";
+
 /// Owns the [`SessionStore`] and mints / resolves / revokes sessions.
 ///
 /// Cheap to clone — the underlying store is reference-counted.
@@ -69,6 +80,13 @@ impl SessionManager {
     /// (`id`) and the parallel CSRF token. Call sites set both cookies
     /// on the response (see [`cookie_for`] / [`csrf_cookie_for`]).
     pub async fn create(&self, user_id: &str) -> Result<Session> {
+        self.create_with_amr(user_id, Vec::new()).await
+    }
+
+    /// [`SessionManager::create`], recording how the user authenticated
+    /// (`amr`, e.g. `["pwd", "otp", "mfa"]`). The OIDC provider copies
+    /// it into the ID tokens this session authorizes.
+    pub async fn create_with_amr(&self, user_id: &str, amr: Vec<String>) -> Result<Session> {
         let id = format!("sess_{}", random_token());
         let csrf_token = format!("csrf_{}", random_token());
         let created_at = now_secs();
@@ -81,6 +99,7 @@ impl SessionManager {
             expires_at,
             ip_hash: None,
             user_agent_hash: None,
+            amr,
         };
         self.store.create(&session).await?;
         Ok(session)
@@ -123,6 +142,7 @@ impl SessionManager {
             expires_at: old.expires_at,
             ip_hash: old.ip_hash,
             user_agent_hash: old.user_agent_hash,
+            amr: old.amr,
         };
         self.store.create(&session).await?;
         Ok(Some(session))
@@ -217,13 +237,14 @@ fn random_token() -> String {
 
 use axum::Router;
 use axum::extract::{FromRef, State};
-use axum::http::{HeaderMap, StatusCode, header};
+use axum::http::{Extensions, HeaderMap, StatusCode, header};
 use axum::response::{IntoResponse, Json, Response};
 use axum::routing::{delete, get, post};
 use serde::Deserialize;
 use serde_json::json;
 
 use crate::ctx::AuthCtx;
+use crate::lockout::LoginAttempt;
 
 /// Build the session router. Generic over a parent state `S` from
 /// which `AuthCtx` is extractable via `axum::extract::FromRef`.
@@ -232,14 +253,17 @@ where
     S: Clone + Send + Sync + 'static,
     AuthCtx: FromRef<S>,
 {
-    Router::new()
+    let r = Router::new()
         .route("/login", post(login_post))
         .route("/session", delete(logout_delete))
         .route("/whoami", get(whoami_get))
         .route("/passkey/register/start", post(passkey_register_start))
         .route("/passkey/register/finish", post(passkey_register_finish))
         .route("/passkey/auth/start", post(passkey_auth_start))
-        .route("/passkey/auth/finish", post(passkey_auth_finish))
+        .route("/passkey/auth/finish", post(passkey_auth_finish));
+    #[cfg(feature = "auth-mfa")]
+    let r = r.route("/login/mfa", post(login_mfa_post));
+    r
 }
 
 #[derive(Deserialize)]
@@ -248,25 +272,152 @@ struct LoginBody {
     password: String,
 }
 
-async fn login_post(State(ctx): State<AuthCtx>, Json(body): Json<LoginBody>) -> Response {
-    let user = match ctx.users.get_user_by_email(&body.email).await {
-        Ok(Some(u)) => u,
-        _ => return unauthorized("invalid credentials"),
+async fn login_post(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    extensions: Extensions,
+    Json(body): Json<LoginBody>,
+) -> Response {
+    let response = password_login(&ctx, &headers, &extensions, &body).await;
+    ctx.metrics.record_login("password", response.status());
+    response
+}
+
+async fn password_login(
+    ctx: &AuthCtx,
+    headers: &HeaderMap,
+    extensions: &Extensions,
+    body: &LoginBody,
+) -> Response {
+    let attempt = match LoginAttempt::begin(ctx, headers, extensions, Some(&body.email)).await {
+        Ok(attempt) => attempt,
+        Err(response) => return *response,
     };
-    let stored = match ctx.users.get_password_hash(&user.id).await {
-        Ok(Some(h)) => h,
-        _ => return unauthorized("invalid credentials"),
+    let Some(user) = verify_password(ctx, body).await else {
+        return attempt.failed(unauthorized("invalid credentials")).await;
     };
+    // Enrolled second factor → no session yet; hand back a short-lived
+    // challenge the client redeems at `/login/mfa` with a code. The
+    // account counter stays put until that succeeds.
+    #[cfg(feature = "auth-mfa")]
+    if let Some(mfa) = ctx.mfa.as_ref() {
+        match mfa.is_enrolled(&user.id).await {
+            Ok(false) => {}
+            Ok(true) => {
+                return match mfa.start_challenge(&user.id).await {
+                    Ok(token) => (
+                        StatusCode::OK,
+                        Json(json!({
+                            "mfa_required": true,
+                            "mfa_token": token,
+                            "methods": ["totp", "recovery_code"],
+                        })),
+                    )
+                        .into_response(),
+                    Err(e) => server_error(&format!("start mfa challenge: {e}")),
+                };
+            }
+            Err(e) => return server_error(&format!("mfa lookup: {e}")),
+        }
+    }
+    let response = start_session(ctx, &user, vec![AMR_PASSWORD.to_string()]).await;
+    if response.status().is_success() {
+        attempt.succeeded().await;
+    }
+    response
+}
+
+/// The user behind `body` when the password matches. Unknown emails,
+/// password-less accounts and wrong passwords all come back `None`.
+async fn verify_password(ctx: &AuthCtx, body: &LoginBody) -> Option<crate::store::User> {
+    let user = ctx.users.get_user_by_email(&body.email).await.ok()??;
+    let stored = ctx.users.get_password_hash(&user.id).await.ok()??;
     let hasher = crate::password::PasswordHasher::default();
-    let ok = match hasher.verify(&body.password, &stored) {
-        Ok(b) => b,
-        Err(_) => return unauthorized("invalid credentials"),
+    match hasher.verify(&body.password, &stored) {
+        Ok(true) => Some(user),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "auth-mfa")]
+#[derive(Deserialize)]
+struct LoginMfaBody {
+    mfa_token: String,
+    code: String,
+}
+
+/// Second step of a password login for users with TOTP enrolled:
+/// redeem the `mfa_token` from `/login` with a TOTP or recovery code.
+#[cfg(feature = "auth-mfa")]
+async fn login_mfa_post(
+    State(ctx): State<AuthCtx>,
+    headers: HeaderMap,
+    extensions: Extensions,
+    Json(body): Json<LoginMfaBody>,
+) -> Response {
+    let response = mfa_login(&ctx, &headers, &extensions, &body).await;
+    ctx.metrics.record_login("mfa", response.status());
+    response
+}
+
+#[cfg(feature = "auth-mfa")]
+async fn mfa_login(
+    ctx: &AuthCtx,
+    headers: &HeaderMap,
+    extensions: &Extensions,
+    body: &LoginMfaBody,
+) -> Response {
+    let Some(mfa) = ctx.mfa.as_ref() else {
+        return svc_unavailable("mfa not configured");
+    };
+    let attempt = match LoginAttempt::begin(ctx, headers, extensions, None).await {
+        Ok(attempt) => attempt,
+        Err(response) => return *response,
+    };
+    let user_id = match mfa.attempt_challenge(&body.mfa_token).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return attempt.failed(unauthorized("invalid code")).await,
+        Err(e) => return server_error(&format!("verify mfa: {e}")),
+    };
+    let user = match ctx.users.get_user_by_id(&user_id).await {
+        Ok(Some(u)) => u,
+        _ => return unauthorized("user unknown"),
+    };
+    // The challenge names the account, so a wrong code counts against
+    // it as well as the IP.
+    let attempt = match attempt.with_account(user.email.as_deref()).await {
+        Ok(attempt) => attempt,
+        Err(response) => return *response,
     };
-    if !ok {
-        return unauthorized("invalid credentials");
+    match mfa
+        .redeem_challenge(&body.mfa_token, &user_id, &body.code)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return attempt.failed(unauthorized("invalid code")).await,
+        Err(e) => return server_error(&format!("verify mfa: {e}")),
+    }
+    let amr = [AMR_PASSWORD, AMR_OTP, AMR_MFA]
+        .map(str::to_string)
+        .to_vec();
+    let response = start_session(ctx, &user, amr).await;
+    if response.status().is_success() {
+        attempt.succeeded().await;
+    }
+    response
+}
+
+/// Mint the session and answer with both cookies — the tail shared by
+/// the single- and two-step password logins.
+async fn start_session(ctx: &AuthCtx, user: &crate::store::User, amr: Vec<String>) -> Response {
+    #[cfg(feature = "auth-scim")]
+    match crate::scim::is_deactivated(ctx, &user.id).await {
+        Ok(false) => {}
+        Ok(true) => return forbidden("account disabled"),
+        Err(e) => return server_error(&format!("scim lookup: {e}")),
     }
     let mgr = SessionManager::with_default_duration(ctx.sessions.clone());
-    let session = match mgr.create(&user.id).await {
+    let session = match mgr.create_with_amr(&user.id, amr).await {
         Ok(s) => s,
         Err(e) => return server_error(&format!("create session: {e}")),
     };
@@ -477,7 +628,7 @@ async fn passkey_auth_finish(
             Ok(r) => r,
             Err(e) => return bad_request(&format!("decode response: {e}")),
         };
-    match mgr.finish_authentication(&state, &response) {
+    let response = match mgr.finish_authentication(&state, &response) {
         Ok(result) => (
             StatusCode::OK,
             Json(json!({
@@ -488,10 +639,12 @@ async fn passkey_auth_finish(
         )
             .into_response(),
         Err(e) => unauthorized(&format!("finish_authentication: {e}")),
-    }
+    };
+    ctx.metrics.record_login("passkey", response.status());
+    response
 }
 
-fn parse_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+pub(crate) fn parse_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
     let raw = headers.get(header::COOKIE)?.to_str().ok()?;
     for kv in raw.split(';') {
         let kv = kv.trim();
@@ -507,6 +660,10 @@ fn parse_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
 fn unauthorized(msg: &str) -> Response {
     (StatusCode::UNAUTHORIZED, Json(json!({"error": msg}))).into_response()
 }
+#[cfg(feature = "auth-scim")]
+fn forbidden(msg: &str) -> Response {
+    (StatusCode::FORBIDDEN, Json(json!({"error": msg}))).into_response()
+}
 fn bad_request(msg: &str) -> Response {
     (StatusCode::BAD_REQUEST, Json(json!({"error": msg}))).into_response()
 }
@@ -646,6 +803,7 @@ mod tests {
             expires_at: now_secs() - 1.0,
             ip_hash: None,
             user_agent_hash: None,
+            amr: Vec::new(),
         };
         store.create(&expired).await.unwrap();
         let mgr = SessionManager::with_default_duration(store);
@@ -696,6 +854,7 @@ mod tests {
             expires_at: now_secs() + 3600.0,
             ip_hash: None,
             user_agent_hash: None,
+            amr: Vec::new(),
         };
         let url = Url::parse("https://app.example.com").unwrap();
         let cookie = cookie_for(&session, &url);
@@ -717,6 +876,7 @@ mod tests {
             expires_at: now_secs() + 3600.0,
             ip_hash: None,
             user_agent_hash: None,
+            amr: Vec::new(),
         };
         let cookie = csrf_cookie_for(&session);
         assert_eq!(cookie.name(), CSRF_COOKIE);
@@ -735,6 +895,7 @@ mod tests {
             expires_at: now_secs() + 3600.0,
             ip_hash: None,
             user_agent_hash: None,
+            amr: Vec::new(),
         };
         let url = Url::parse("http://localhost:3000").unwrap();
         let cookie = cookie_for(&session, &url);
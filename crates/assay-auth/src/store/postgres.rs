@@ -293,8 +293,8 @@ impl SessionStore for PostgresSessionStore {
     async fn create(&self, session: &Session) -> Result<()> {
         sqlx::query(
             "INSERT INTO auth.sessions
-                 (id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash)
-             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                 (id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
         )
         .bind(&session.id)
         .bind(&session.user_id)
@@ -303,6 +303,7 @@ impl SessionStore for PostgresSessionStore {
         .bind(session.expires_at)
         .bind(&session.ip_hash)
         .bind(&session.user_agent_hash)
+        .bind(serde_json::to_string(&session.amr).unwrap_or_else(|_| "[]".to_string()))
         .execute(&self.pool)
         .await
         .context("auth.sessions insert")?;
@@ -311,7 +312,7 @@ impl SessionStore for PostgresSessionStore {
 
     async fn get(&self, id: &str) -> Result<Option<Session>> {
         let row = sqlx::query(
-            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
              FROM auth.sessions WHERE id = $1",
         )
         .bind(id)
@@ -332,7 +333,7 @@ impl SessionStore for PostgresSessionStore {
 
     async fn list_for_user(&self, user_id: &str) -> Result<Vec<Session>> {
         let rows = sqlx::query(
-            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
              FROM auth.sessions WHERE user_id = $1 ORDER BY created_at DESC",
         )
         .bind(user_id)
@@ -370,7 +371,7 @@ impl SessionStore for PostgresSessionStore {
         let off = offset.max(0);
         let rows = if let Some(uid) = user_filter {
             sqlx::query(
-                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
                  FROM auth.sessions WHERE user_id = $1
                  ORDER BY created_at DESC
                  LIMIT $2 OFFSET $3",
@@ -383,7 +384,7 @@ impl SessionStore for PostgresSessionStore {
             .context("auth.sessions list_all (user filter)")?
         } else {
             sqlx::query(
-                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
                  FROM auth.sessions
                  ORDER BY created_at DESC
                  LIMIT $1 OFFSET $2",
@@ -433,6 +434,7 @@ fn map_session_row_pg(row: sqlx::postgres::PgRow) -> Session {
         expires_at: row.get("expires_at"),
         ip_hash: row.get("ip_hash"),
         user_agent_hash: row.get("user_agent_hash"),
+        amr: serde_json::from_str(&row.get::<String, _>("amr")).unwrap_or_default(),
     }
 }
 
@@ -295,8 +295,8 @@ impl SessionStore for SqliteSessionStore {
     async fn create(&self, session: &Session) -> Result<()> {
         sqlx::query(
             "INSERT INTO auth.sessions
-                 (id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+                 (id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&session.id)
         .bind(&session.user_id)
@@ -305,6 +305,7 @@ impl SessionStore for SqliteSessionStore {
         .bind(session.expires_at)
         .bind(&session.ip_hash)
         .bind(&session.user_agent_hash)
+        .bind(serde_json::to_string(&session.amr).unwrap_or_else(|_| "[]".to_string()))
         .execute(&self.pool)
         .await
         .context("auth.sessions insert")?;
@@ -313,7 +314,7 @@ impl SessionStore for SqliteSessionStore {
 
     async fn get(&self, id: &str) -> Result<Option<Session>> {
         let row = sqlx::query(
-            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
              FROM auth.sessions WHERE id = ?",
         )
         .bind(id)
@@ -334,7 +335,7 @@ impl SessionStore for SqliteSessionStore {
 
     async fn list_for_user(&self, user_id: &str) -> Result<Vec<Session>> {
         let rows = sqlx::query(
-            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+            "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
              FROM auth.sessions WHERE user_id = ? ORDER BY created_at DESC",
         )
         .bind(user_id)
@@ -372,7 +373,7 @@ impl SessionStore for SqliteSessionStore {
         let off = offset.max(0);
         let rows = if let Some(uid) = user_filter {
             sqlx::query(
-                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
                  FROM auth.sessions WHERE user_id = ?
                  ORDER BY created_at DESC
                  LIMIT ? OFFSET ?",
@@ -385,7 +386,7 @@ impl SessionStore for SqliteSessionStore {
             .context("auth.sessions list_all (user filter)")?
         } else {
             sqlx::query(
-                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash
+                "SELECT id, user_id, csrf_token, created_at, expires_at, ip_hash, user_agent_hash, amr
                  FROM auth.sessions
                  ORDER BY created_at DESC
                  LIMIT ? OFFSET ?",
@@ -436,6 +437,7 @@ fn map_session_row_sqlite(row: sqlx::sqlite::SqliteRow) -> Session {
         expires_at: row.get("expires_at"),
         ip_hash: row.get("ip_hash"),
         user_agent_hash: row.get("user_agent_hash"),
+        amr: serde_json::from_str(&row.get::<String, _>("amr")).unwrap_or_default(),
     }
 }
 
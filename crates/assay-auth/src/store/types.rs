@@ -42,4 +42,9 @@ pub struct Session {
     pub expires_at: f64,
     pub ip_hash: Option<String>,
     pub user_agent_hash: Option<String>,
+    /// RFC 8176 authentication method references for how the session
+    /// was established (`pwd`, `otp`, `mfa`, …). Empty when the login
+    /// path doesn't record one (federation, older rows).
+    #[serde(default)]
+    pub amr: Vec<String>,
 }
@@ -0,0 +1,1049 @@
+//! Caveats — the CEL-like predicates behind conditional relationships.
+//!
+//! A caveat is declared in the schema DSL alongside the definitions
+//! that use it, and a relation opts in per subject type with `with`:
+//!
+//! ```text
+//! caveat in_office(client_ip ipaddress, office_cidr string) {
+//!     client_ip.in_cidr(office_cidr)
+//! }
+//!
+//! definition document {
+//!     relation viewer: user | user with in_office
+//! }
+//! ```
+//!
+//! A tuple written with a [`super::TupleCaveat`] only grants while its
+//! predicate holds. Parameters come from two places: the context stored
+//! on the tuple when the grant is made (`office_cidr` above) and the
+//! context `check` is called with (`client_ip`). The stored context wins
+//! on conflicts, so a caller can't widen a grant by supplying its own
+//! CIDR. A predicate that needs a parameter neither side supplied
+//! leaves the check [`super::CheckResult::Conditional`], naming what is
+//! missing so the caller can retry with more context.
+//!
+//! The expression language is a small CEL subset:
+//!
+//! - literals: integers, doubles, `"strings"` / `'strings'`, `true`,
+//!   `false`, `null`, and lists `[a, b]`;
+//! - operators: `||`, `&&`, `!`, `==`, `!=`, `<`, `<=`, `>`, `>=`,
+//!   `in` (list membership / map key), `+ - * / %`, unary `-`, and
+//!   indexing `xs[0]` / `m["k"]`;
+//! - functions: `size(x)`, `int(x)`, `double(x)`, `string(x)`,
+//!   `timestamp("2026-01-01T00:00:00Z")`;
+//! - methods: `s.startsWith(p)`, `s.endsWith(p)`, `s.contains(p)`,
+//!   `x.size()`, `ip.in_cidr("10.0.0.0/8")`, and — on timestamps, in
+//!   UTC — `getHours()`, `getMinutes()`, `getDayOfWeek()` (0 = Sunday),
+//!   `getDate()`, `getMonth()` (0-based) and `getFullYear()`.
+//!
+//! Parameter types are `int`, `uint`, `double`, `bool`, `string`,
+//! `ipaddress`, `timestamp` (RFC 3339 string or epoch seconds),
+//! `list<T>`, `map<T>` and `any`. Context values are checked against
+//! the declared type before evaluation; a mismatch is a
+//! [`CaveatError`], not a silent deny.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::types::CaveatContext;
+
+/// A named predicate declared with `caveat NAME(params) { expr }`.
+/// Persisted on every [`super::NamespaceSchema`] whose relations
+/// reference it; the expression is kept as source and compiled on use.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaveatDef {
+    pub name: String,
+    /// Declared parameters, by name.
+    pub params: BTreeMap<String, CaveatParamType>,
+    pub expression: String,
+}
+
+impl CaveatDef {
+    /// Build a caveat, rejecting an expression that doesn't parse or
+    /// that references an undeclared parameter.
+    pub fn new(
+        name: impl Into<String>,
+        params: impl IntoIterator<Item = (String, CaveatParamType)>,
+        expression: impl Into<String>,
+    ) -> Result<Self, CaveatError> {
+        let def = Self {
+            name: name.into(),
+            params: params.into_iter().collect(),
+            expression: expression.into(),
+        };
+        def.compile()?;
+        Ok(def)
+    }
+
+    /// Re-check a definition that arrived without going through
+    /// [`CaveatDef::new`] (e.g. a deserialised schema).
+    pub fn validate(&self) -> Result<(), CaveatError> {
+        self.compile().map(|_| ())
+    }
+
+    /// Check a context bound at write time: every key must be a
+    /// declared parameter and convert to its declared type.
+    pub fn check_context(&self, context: &CaveatContext) -> Result<(), CaveatError> {
+        for (name, raw) in context {
+            let ty = self
+                .params
+                .get(name)
+                .ok_or_else(|| CaveatError::UnknownParameter(name.clone()))?;
+            ty.convert(raw)
+                .map_err(|e| CaveatError::Eval(format!("parameter `{name}` ({ty}): {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// Parse the expression and check every identifier is a declared
+    /// parameter.
+    fn compile(&self) -> Result<Expr, CaveatError> {
+        let expr = Parser::new(&self.expression)?.parse()?;
+        let mut idents = BTreeSet::new();
+        expr.idents(&mut idents);
+        if let Some(unknown) = idents.iter().find(|i| !self.params.contains_key(*i)) {
+            return Err(CaveatError::UnknownParameter(unknown.clone()));
+        }
+        Ok(expr)
+    }
+
+    /// Evaluate against the tuple's `stored` context merged over the
+    /// caller's `request` context.
+    pub fn evaluate(
+        &self,
+        stored: &CaveatContext,
+        request: &CaveatContext,
+    ) -> Result<CaveatOutcome, CaveatError> {
+        let expr = self.compile()?;
+        let mut idents = BTreeSet::new();
+        expr.idents(&mut idents);
+        let mut env = BTreeMap::new();
+        let mut missing = Vec::new();
+        for name in idents {
+            let Some(raw) = stored.get(&name).or_else(|| request.get(&name)) else {
+                missing.push(name);
+                continue;
+            };
+            let ty = &self.params[&name];
+            let value = ty
+                .convert(raw)
+                .map_err(|e| CaveatError::Eval(format!("parameter `{name}` ({ty}): {e}")))?;
+            env.insert(name, value);
+        }
+        if !missing.is_empty() {
+            return Ok(CaveatOutcome::MissingContext(missing));
+        }
+        match expr.eval(&env).map_err(CaveatError::Eval)? {
+            Value::Bool(true) => Ok(CaveatOutcome::Satisfied),
+            Value::Bool(false) => Ok(CaveatOutcome::Unsatisfied),
+            other => Err(CaveatError::Eval(format!(
+                "expression yields {}, not bool",
+                other.type_name()
+            ))),
+        }
+    }
+}
+
+/// Result of evaluating one caveat.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaveatOutcome {
+    Satisfied,
+    Unsatisfied,
+    /// Parameters neither the tuple nor the request supplied, sorted.
+    MissingContext(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum CaveatError {
+    #[error("caveat syntax error at offset {offset}: {message}")]
+    Syntax { offset: usize, message: String },
+    #[error("caveat references undeclared parameter `{0}`")]
+    UnknownParameter(String),
+    #[error("caveat evaluation failed: {0}")]
+    Eval(String),
+}
+
+/// Declared type of a caveat parameter. Serialises as its DSL
+/// spelling (`"int"`, `"list<string>"`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CaveatParamType {
+    Int,
+    Uint,
+    Double,
+    Bool,
+    String,
+    IpAddress,
+    Timestamp,
+    Any,
+    List(Box<CaveatParamType>),
+    Map(Box<CaveatParamType>),
+}
+
+impl CaveatParamType {
+    /// Parse the DSL spelling. Whitespace inside `list< T >` is ignored.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let generic = |prefix: &str| {
+            s.strip_prefix(prefix)
+                .and_then(|rest| rest.strip_suffix('>'))
+                .and_then(Self::parse)
+                .map(Box::new)
+        };
+        Some(match s.as_str() {
+            "int" => Self::Int,
+            "uint" => Self::Uint,
+            "double" => Self::Double,
+            "bool" => Self::Bool,
+            "string" => Self::String,
+            "ipaddress" => Self::IpAddress,
+            "timestamp" => Self::Timestamp,
+            "any" => Self::Any,
+            _ if s.starts_with("list<") => Self::List(generic("list<")?),
+            _ if s.starts_with("map<") => Self::Map(generic("map<")?),
+            _ => return None,
+        })
+    }
+
+    /// Check a JSON context value against this type.
+    fn convert(&self, v: &serde_json::Value) -> Result<Value, String> {
+        use serde_json::Value as J;
+        let mismatch = || format!("expected {self}, got {v}");
+        Ok(match (self, v) {
+            (Self::Int, J::Number(n)) => Value::Int(n.as_i64().ok_or_else(mismatch)?),
+            (Self::Uint, J::Number(n)) => {
+                let u = n.as_u64().ok_or_else(mismatch)?;
+                Value::Int(i64::try_from(u).map_err(|_| mismatch())?)
+            }
+            (Self::Double, J::Number(n)) => Value::Double(n.as_f64().ok_or_else(mismatch)?),
+            (Self::Bool, J::Bool(b)) => Value::Bool(*b),
+            (Self::String, J::String(s)) => Value::String(s.clone()),
+            (Self::IpAddress, J::String(s)) => {
+                Value::Ip(s.parse().map_err(|_| format!("invalid ip address {s:?}"))?)
+            }
+            (Self::Timestamp, J::String(s)) => Value::Timestamp(
+                DateTime::parse_from_rfc3339(s)
+                    .map_err(|_| format!("invalid RFC 3339 timestamp {s:?}"))?
+                    .with_timezone(&Utc),
+            ),
+            (Self::Timestamp, J::Number(n)) => {
+                let secs = n.as_i64().ok_or_else(mismatch)?;
+                Value::Timestamp(Utc.timestamp_opt(secs, 0).single().ok_or_else(mismatch)?)
+            }
+            (Self::List(inner), J::Array(items)) => Value::List(
+                items
+                    .iter()
+                    .map(|i| inner.convert(i))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (Self::Map(inner), J::Object(entries)) => Value::Map(
+                entries
+                    .iter()
+                    .map(|(k, v)| Ok((k.clone(), inner.convert(v)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            (Self::Any, v) => Value::from_json(v),
+            _ => return Err(mismatch()),
+        })
+    }
+}
+
+impl fmt::Display for CaveatParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int => f.write_str("int"),
+            Self::Uint => f.write_str("uint"),
+            Self::Double => f.write_str("double"),
+            Self::Bool => f.write_str("bool"),
+            Self::String => f.write_str("string"),
+            Self::IpAddress => f.write_str("ipaddress"),
+            Self::Timestamp => f.write_str("timestamp"),
+            Self::Any => f.write_str("any"),
+            Self::List(inner) => write!(f, "list<{inner}>"),
+            Self::Map(inner) => write!(f, "map<{inner}>"),
+        }
+    }
+}
+
+impl From<CaveatParamType> for String {
+    fn from(t: CaveatParamType) -> Self {
+        t.to_string()
+    }
+}
+
+impl TryFrom<String> for CaveatParamType {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s).ok_or_else(|| format!("unknown caveat parameter type {s:?}"))
+    }
+}
+
+// ---- runtime values ----
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+    Ip(IpAddr),
+    Timestamp(DateTime<Utc>),
+}
+
+impl Value {
+    fn from_json(v: &serde_json::Value) -> Self {
+        use serde_json::Value as J;
+        match v {
+            J::Null => Self::Null,
+            J::Bool(b) => Self::Bool(*b),
+            J::Number(n) => match n.as_i64() {
+                Some(i) => Self::Int(i),
+                None => Self::Double(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            J::String(s) => Self::String(s.clone()),
+            J::Array(items) => Self::List(items.iter().map(Self::from_json).collect()),
+            J::Object(entries) => Self::Map(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Self::from_json(v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "bool",
+            Self::Int(_) => "int",
+            Self::Double(_) => "double",
+            Self::String(_) => "string",
+            Self::List(_) => "list",
+            Self::Map(_) => "map",
+            Self::Ip(_) => "ipaddress",
+            Self::Timestamp(_) => "timestamp",
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(i) => Some(*i as f64),
+            Self::Double(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Equality with int/double promotion, as comparisons use.
+    fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Self::Int(_) | Self::Double(_), Self::Int(_) | Self::Double(_)) => {
+                self.as_f64() == other.as_f64()
+            }
+            _ => self == other,
+        }
+    }
+}
+
+// ---- syntax ----
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Lit(Value),
+    Ident(String),
+    List(Vec<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    Method(Box<Expr>, String, Vec<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BinOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Int(i64),
+    Double(f64),
+    Str(String),
+    Ident(String),
+    Punct(&'static str),
+}
+
+fn lex(src: &str) -> Result<Vec<(Tok, usize)>, CaveatError> {
+    const PUNCT: [&str; 20] = [
+        "&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "+", "-", "*", "/", "%", "(", ")", "[",
+        "]", ",", ".",
+    ];
+    let err = |offset, message: &str| CaveatError::Syntax {
+        offset,
+        message: message.to_string(),
+    };
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        if c == b'"' || c == b'\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match bytes.get(i) {
+                    None => return Err(err(start, "unterminated string")),
+                    Some(&q) if q == c => break,
+                    Some(b'\\') => {
+                        let escaped = match bytes.get(i + 1) {
+                            Some(b'n') => '\n',
+                            Some(b't') => '\t',
+                            Some(&e @ (b'\\' | b'"' | b'\'')) => e as char,
+                            _ => return Err(err(i, "unsupported escape")),
+                        };
+                        s.push(escaped);
+                        i += 2;
+                    }
+                    Some(_) => {
+                        // Copy one UTF-8 scalar.
+                        let ch = src[i..].chars().next().expect("in bounds");
+                        s.push(ch);
+                        i += ch.len_utf8();
+                    }
+                }
+            }
+            i += 1;
+            out.push((Tok::Str(s), start));
+            continue;
+        }
+        if c.is_ascii_digit() {
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            let is_double =
+                bytes.get(i) == Some(&b'.') && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit());
+            if is_double {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let d = src[start..i]
+                    .parse()
+                    .map_err(|_| err(start, "invalid number"))?;
+                out.push((Tok::Double(d), start));
+            } else {
+                let n = src[start..i]
+                    .parse()
+                    .map_err(|_| err(start, "integer out of range"))?;
+                out.push((Tok::Int(n), start));
+            }
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            out.push((Tok::Ident(src[start..i].to_string()), start));
+            continue;
+        }
+        let Some(p) = PUNCT.iter().find(|p| src[i..].starts_with(**p)) else {
+            return Err(err(start, "unexpected character"));
+        };
+        i += p.len();
+        out.push((Tok::Punct(p), start));
+    }
+    Ok(out)
+}
+
+struct Parser {
+    toks: Vec<(Tok, usize)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn new(src: &str) -> Result<Self, CaveatError> {
+        Ok(Self {
+            toks: lex(src)?,
+            pos: 0,
+            end: src.len(),
+        })
+    }
+
+    fn parse(mut self) -> Result<Expr, CaveatError> {
+        if self.toks.is_empty() {
+            return Err(self.err("empty expression"));
+        }
+        let expr = self.or()?;
+        if self.pos < self.toks.len() {
+            return Err(self.err("unexpected trailing input"));
+        }
+        Ok(expr)
+    }
+
+    fn err(&self, message: &str) -> CaveatError {
+        CaveatError::Syntax {
+            offset: self.toks.get(self.pos).map(|t| t.1).unwrap_or(self.end),
+            message: message.to_string(),
+        }
+    }
+
+    fn eat(&mut self, p: &str) -> bool {
+        if matches!(self.toks.get(self.pos), Some((Tok::Punct(q), _)) if *q == p) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, p: &str) -> Result<(), CaveatError> {
+        if self.eat(p) {
+            Ok(())
+        } else {
+            Err(self.err(&format!("expected `{p}`")))
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, CaveatError> {
+        let mut left = self.and()?;
+        while self.eat("||") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, CaveatError> {
+        let mut left = self.relation()?;
+        while self.eat("&&") {
+            left = Expr::And(Box::new(left), Box::new(self.relation()?));
+        }
+        Ok(left)
+    }
+
+    /// Comparisons don't chain — `a < b < c` is a syntax error, as in CEL.
+    fn relation(&mut self) -> Result<Expr, CaveatError> {
+        let left = self.additive()?;
+        let op = match self.toks.get(self.pos) {
+            Some((Tok::Punct("==" | "!=" | "<" | "<=" | ">" | ">="), _)) => {
+                let Tok::Punct(p) = self.toks[self.pos].0 else {
+                    unreachable!()
+                };
+                match p {
+                    "==" => BinOp::Eq,
+                    "!=" => BinOp::Ne,
+                    "<" => BinOp::Lt,
+                    "<=" => BinOp::Le,
+                    ">" => BinOp::Gt,
+                    _ => BinOp::Ge,
+                }
+            }
+            Some((Tok::Ident(kw), _)) if kw == "in" => BinOp::In,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, CaveatError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat("+") {
+                BinOp::Add
+            } else if self.eat("-") {
+                BinOp::Sub
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, CaveatError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat("*") {
+                BinOp::Mul
+            } else if self.eat("/") {
+                BinOp::Div
+            } else if self.eat("%") {
+                BinOp::Rem
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, CaveatError> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, CaveatError> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat(".") {
+                let name = self.ident()?;
+                self.expect("(")?;
+                let args = self.args(")")?;
+                expr = Expr::Method(Box::new(expr), name, args);
+            } else if self.eat("[") {
+                let index = self.or()?;
+                self.expect("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, CaveatError> {
+        match self.toks.get(self.pos) {
+            Some((Tok::Ident(name), _)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.err("expected identifier")),
+        }
+    }
+
+    /// Comma-separated expressions up to (and consuming) `close`.
+    fn args(&mut self, close: &str) -> Result<Vec<Expr>, CaveatError> {
+        let mut out = Vec::new();
+        if self.eat(close) {
+            return Ok(out);
+        }
+        loop {
+            out.push(self.or()?);
+            if self.eat(close) {
+                return Ok(out);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, CaveatError> {
+        let Some((tok, _)) = self.toks.get(self.pos).cloned() else {
+            return Err(self.err("unexpected end of expression"));
+        };
+        self.pos += 1;
+        Ok(match tok {
+            Tok::Int(n) => Expr::Lit(Value::Int(n)),
+            Tok::Double(d) => Expr::Lit(Value::Double(d)),
+            Tok::Str(s) => Expr::Lit(Value::String(s)),
+            Tok::Ident(name) => match name.as_str() {
+                "true" => Expr::Lit(Value::Bool(true)),
+                "false" => Expr::Lit(Value::Bool(false)),
+                "null" => Expr::Lit(Value::Null),
+                _ if self.eat("(") => Expr::Call(name, self.args(")")?),
+                _ => Expr::Ident(name),
+            },
+            Tok::Punct("(") => {
+                let inner = self.or()?;
+                self.expect(")")?;
+                inner
+            }
+            Tok::Punct("[") => Expr::List(self.args("]")?),
+            Tok::Punct(_) => {
+                self.pos -= 1;
+                return Err(self.err("expected an operand"));
+            }
+        })
+    }
+}
+
+// ---- evaluation ----
+
+impl Expr {
+    /// Every parameter the expression reads.
+    fn idents(&self, out: &mut BTreeSet<String>) {
+        match self {
+            Expr::Lit(_) => {}
+            Expr::Ident(name) => {
+                out.insert(name.clone());
+            }
+            Expr::List(items) | Expr::Call(_, items) => items.iter().for_each(|e| e.idents(out)),
+            Expr::Not(e) | Expr::Neg(e) => e.idents(out),
+            Expr::And(l, r) | Expr::Or(l, r) | Expr::Binary(_, l, r) | Expr::Index(l, r) => {
+                l.idents(out);
+                r.idents(out);
+            }
+            Expr::Method(recv, _, args) => {
+                recv.idents(out);
+                args.iter().for_each(|e| e.idents(out));
+            }
+        }
+    }
+
+    fn eval(&self, env: &BTreeMap<String, Value>) -> Result<Value, String> {
+        let bool_of = |e: &Expr| match e.eval(env)? {
+            Value::Bool(b) => Ok(b),
+            other => Err(format!("expected bool, got {}", other.type_name())),
+        };
+        Ok(match self {
+            Expr::Lit(v) => v.clone(),
+            Expr::Ident(name) => env
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("unbound parameter `{name}`"))?,
+            Expr::List(items) => Value::List(
+                items
+                    .iter()
+                    .map(|e| e.eval(env))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Not(e) => Value::Bool(!bool_of(e)?),
+            Expr::Neg(e) => match e.eval(env)? {
+                Value::Int(i) => Value::Int(i.checked_neg().ok_or("integer overflow")?),
+                Value::Double(d) => Value::Double(-d),
+                other => return Err(format!("cannot negate {}", other.type_name())),
+            },
+            Expr::And(l, r) => Value::Bool(bool_of(l)? && bool_of(r)?),
+            Expr::Or(l, r) => Value::Bool(bool_of(l)? || bool_of(r)?),
+            Expr::Binary(op, l, r) => binary(*op, l.eval(env)?, r.eval(env)?)?,
+            Expr::Index(target, index) => match (target.eval(env)?, index.eval(env)?) {
+                (Value::List(items), Value::Int(i)) => usize::try_from(i)
+                    .ok()
+                    .and_then(|i| items.get(i).cloned())
+                    .ok_or_else(|| format!("index {i} out of range"))?,
+                (Value::Map(entries), Value::String(k)) => entries
+                    .get(&k)
+                    .cloned()
+                    .ok_or_else(|| format!("no such key {k:?}"))?,
+                (t, i) => {
+                    return Err(format!(
+                        "cannot index {} with {}",
+                        t.type_name(),
+                        i.type_name()
+                    ));
+                }
+            },
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|e| e.eval(env))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call(name, args)?
+            }
+            Expr::Method(recv, name, args) => {
+                let mut all = vec![recv.eval(env)?];
+                for a in args {
+                    all.push(a.eval(env)?);
+                }
+                call(name, all)?
+            }
+        })
+    }
+}
+
+fn binary(op: BinOp, l: Value, r: Value) -> Result<Value, String> {
+    use std::cmp::Ordering;
+    let order = |l: &Value, r: &Value| -> Result<Ordering, String> {
+        match (l, r) {
+            (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+            (Value::Timestamp(a), Value::Timestamp(b)) => Ok(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Ok(a.cmp(b)),
+            _ => match (l.as_f64(), r.as_f64()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).ok_or_else(|| "NaN".to_string()),
+                _ => Err(format!(
+                    "cannot compare {} with {}",
+                    l.type_name(),
+                    r.type_name()
+                )),
+            },
+        }
+    };
+    let type_err = |l: &Value, r: &Value| {
+        format!(
+            "unsupported operands {} and {}",
+            l.type_name(),
+            r.type_name()
+        )
+    };
+    Ok(match op {
+        BinOp::Eq => Value::Bool(l.equals(&r)),
+        BinOp::Ne => Value::Bool(!l.equals(&r)),
+        BinOp::Lt => Value::Bool(order(&l, &r)?.is_lt()),
+        BinOp::Le => Value::Bool(order(&l, &r)?.is_le()),
+        BinOp::Gt => Value::Bool(order(&l, &r)?.is_gt()),
+        BinOp::Ge => Value::Bool(order(&l, &r)?.is_ge()),
+        BinOp::In => match (&l, &r) {
+            (_, Value::List(items)) => Value::Bool(items.iter().any(|i| i.equals(&l))),
+            (Value::String(k), Value::Map(entries)) => Value::Bool(entries.contains_key(k)),
+            _ => return Err(type_err(&l, &r)),
+        },
+        BinOp::Add => match (l, r) {
+            (Value::Int(a), Value::Int(b)) => {
+                Value::Int(a.checked_add(b).ok_or("integer overflow")?)
+            }
+            (Value::String(a), Value::String(b)) => Value::String(a + &b),
+            (Value::List(mut a), Value::List(b)) => {
+                a.extend(b);
+                Value::List(a)
+            }
+            (l, r) => arith(&l, &r, |a, b| a + b).ok_or_else(|| type_err(&l, &r))?,
+        },
+        BinOp::Sub => match (&l, &r) {
+            (Value::Int(a), Value::Int(b)) => {
+                Value::Int(a.checked_sub(*b).ok_or("integer overflow")?)
+            }
+            _ => arith(&l, &r, |a, b| a - b).ok_or_else(|| type_err(&l, &r))?,
+        },
+        BinOp::Mul => match (&l, &r) {
+            (Value::Int(a), Value::Int(b)) => {
+                Value::Int(a.checked_mul(*b).ok_or("integer overflow")?)
+            }
+            _ => arith(&l, &r, |a, b| a * b).ok_or_else(|| type_err(&l, &r))?,
+        },
+        BinOp::Div | BinOp::Rem => match (&l, &r) {
+            (Value::Int(_), Value::Int(0)) => return Err("division by zero".into()),
+            (Value::Int(a), Value::Int(b)) if op == BinOp::Div => Value::Int(a.wrapping_div(*b)),
+            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_rem(*b)),
+            _ if op == BinOp::Div => arith(&l, &r, |a, b| a / b).ok_or_else(|| type_err(&l, &r))?,
+            _ => arith(&l, &r, |a, b| a % b).ok_or_else(|| type_err(&l, &r))?,
+        },
+    })
+}
+
+/// Numeric op with int → double promotion; `None` for non-numbers.
+fn arith(l: &Value, r: &Value, f: impl Fn(f64, f64) -> f64) -> Option<Value> {
+    Some(Value::Double(f(l.as_f64()?, r.as_f64()?)))
+}
+
+/// Functions and methods — a method call passes its receiver as the
+/// first argument.
+fn call(name: &str, args: Vec<Value>) -> Result<Value, String> {
+    let bad = |args: &[Value]| {
+        let types: Vec<&str> = args.iter().map(Value::type_name).collect();
+        format!("no overload {name}({})", types.join(", "))
+    };
+    Ok(match (name, args.as_slice()) {
+        ("size", [Value::String(s)]) => Value::Int(s.chars().count() as i64),
+        ("size", [Value::List(items)]) => Value::Int(items.len() as i64),
+        ("size", [Value::Map(entries)]) => Value::Int(entries.len() as i64),
+        ("int", [Value::Int(i)]) => Value::Int(*i),
+        ("int", [Value::Double(d)]) => Value::Int(*d as i64),
+        ("int", [Value::String(s)]) => Value::Int(
+            s.parse()
+                .map_err(|_| format!("int({s:?}): not an integer"))?,
+        ),
+        ("int", [Value::Timestamp(t)]) => Value::Int(t.timestamp()),
+        ("double", [v @ (Value::Int(_) | Value::Double(_))]) => {
+            Value::Double(v.as_f64().expect("numeric"))
+        }
+        ("double", [Value::String(s)]) => Value::Double(
+            s.parse()
+                .map_err(|_| format!("double({s:?}): not a number"))?,
+        ),
+        ("string", [Value::String(s)]) => Value::String(s.clone()),
+        ("string", [Value::Int(i)]) => Value::String(i.to_string()),
+        ("string", [Value::Double(d)]) => Value::String(d.to_string()),
+        ("string", [Value::Bool(b)]) => Value::String(b.to_string()),
+        ("string", [Value::Ip(ip)]) => Value::String(ip.to_string()),
+        ("string", [Value::Timestamp(t)]) => Value::String(t.to_rfc3339()),
+        ("timestamp", [Value::String(s)]) => Value::Timestamp(
+            DateTime::parse_from_rfc3339(s)
+                .map_err(|_| format!("timestamp({s:?}): not RFC 3339"))?
+                .with_timezone(&Utc),
+        ),
+        ("startsWith", [Value::String(s), Value::String(p)]) => {
+            Value::Bool(s.starts_with(p.as_str()))
+        }
+        ("endsWith", [Value::String(s), Value::String(p)]) => Value::Bool(s.ends_with(p.as_str())),
+        ("contains", [Value::String(s), Value::String(p)]) => Value::Bool(s.contains(p.as_str())),
+        ("in_cidr", [Value::Ip(ip), Value::String(cidr)]) => Value::Bool(in_cidr(*ip, cidr)?),
+        ("getHours", [Value::Timestamp(t)]) => Value::Int(i64::from(t.hour())),
+        ("getMinutes", [Value::Timestamp(t)]) => Value::Int(i64::from(t.minute())),
+        ("getDayOfWeek", [Value::Timestamp(t)]) => {
+            Value::Int(i64::from(t.weekday().num_days_from_sunday()))
+        }
+        ("getDate", [Value::Timestamp(t)]) => Value::Int(i64::from(t.day())),
+        ("getMonth", [Value::Timestamp(t)]) => Value::Int(i64::from(t.month0())),
+        ("getFullYear", [Value::Timestamp(t)]) => Value::Int(i64::from(t.year())),
+        _ => return Err(bad(&args)),
+    })
+}
+
+/// `true` iff `ip` lies inside `cidr`. Addresses of the other family
+/// are simply outside the range.
+fn in_cidr(ip: IpAddr, cidr: &str) -> Result<bool, String> {
+    let invalid = || format!("invalid CIDR {cidr:?}");
+    let (net, prefix) = cidr.split_once('/').ok_or_else(invalid)?;
+    let net: IpAddr = net.parse().map_err(|_| invalid())?;
+    let prefix: u32 = prefix.parse().map_err(|_| invalid())?;
+    Ok(match (ip, net) {
+        (IpAddr::V4(ip), IpAddr::V4(net)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(net) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(net)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(net) & mask
+        }
+        (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+            return Err(invalid());
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ctx(v: serde_json::Value) -> CaveatContext {
+        v.as_object().cloned().expect("object")
+    }
+
+    fn def(params: &[(&str, &str)], expr: &str) -> CaveatDef {
+        CaveatDef::new(
+            "c",
+            params
+                .iter()
+                .map(|(n, t)| (n.to_string(), CaveatParamType::parse(t).expect("type"))),
+            expr,
+        )
+        .expect("valid caveat")
+    }
+
+    #[test]
+    fn ip_range_caveat() {
+        let c = def(
+            &[("client_ip", "ipaddress"), ("cidr", "string")],
+            "client_ip.in_cidr(cidr)",
+        );
+        let stored = ctx(json!({"cidr": "10.0.0.0/8"}));
+        let eval = |ip: &str| c.evaluate(&stored, &ctx(json!({ "client_ip": ip })));
+        assert_eq!(eval("10.1.2.3").unwrap(), CaveatOutcome::Satisfied);
+        assert_eq!(eval("192.168.0.1").unwrap(), CaveatOutcome::Unsatisfied);
+        assert_eq!(eval("::1").unwrap(), CaveatOutcome::Unsatisfied);
+        assert!(matches!(eval("nope"), Err(CaveatError::Eval(_))));
+        // The stored context wins — a caller can't widen its own range.
+        let widened = ctx(json!({"client_ip": "192.168.0.1", "cidr": "0.0.0.0/0"}));
+        assert_eq!(
+            c.evaluate(&stored, &widened).unwrap(),
+            CaveatOutcome::Unsatisfied
+        );
+    }
+
+    #[test]
+    fn business_hours_caveat() {
+        let c = def(
+            &[("now", "timestamp")],
+            "now.getDayOfWeek() >= 1 && now.getDayOfWeek() <= 5 \
+             && now.getHours() >= 9 && now.getHours() < 17",
+        );
+        let at = |ts: &str| c.evaluate(&ctx(json!({})), &ctx(json!({ "now": ts })));
+        // 2026-10-19 is a Monday.
+        assert_eq!(
+            at("2026-10-19T10:30:00Z").unwrap(),
+            CaveatOutcome::Satisfied
+        );
+        assert_eq!(
+            at("2026-10-19T18:00:00Z").unwrap(),
+            CaveatOutcome::Unsatisfied
+        );
+        assert_eq!(
+            at("2026-10-18T10:30:00Z").unwrap(),
+            CaveatOutcome::Unsatisfied
+        );
+        assert_eq!(
+            c.evaluate(&ctx(json!({})), &ctx(json!({}))).unwrap(),
+            CaveatOutcome::MissingContext(vec!["now".into()])
+        );
+    }
+
+    #[test]
+    fn operators_and_literals() {
+        let c = def(
+            &[
+                ("n", "int"),
+                ("tags", "list<string>"),
+                ("limits", "map<double>"),
+            ],
+            "(n * 2 + 1) % 5 == 2 && 'ops' in tags && size(tags) == 2 \
+             && limits['cpu'] > 0.5 && !(n > 10) && tags[0].startsWith(\"o\")",
+        );
+        let request = ctx(json!({"n": 3, "tags": ["ops", "dev"], "limits": {"cpu": 1.5}}));
+        assert_eq!(
+            c.evaluate(&ctx(json!({})), &request).unwrap(),
+            CaveatOutcome::Satisfied
+        );
+        let wrong_type = ctx(json!({"n": "3", "tags": [], "limits": {}}));
+        assert!(c.evaluate(&ctx(json!({})), &wrong_type).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_definitions() {
+        let params = || vec![("a".to_string(), CaveatParamType::Int)];
+        assert_eq!(
+            CaveatDef::new("c", params(), "a > b"),
+            Err(CaveatError::UnknownParameter("b".into()))
+        );
+        assert!(matches!(
+            CaveatDef::new("c", params(), "a >"),
+            Err(CaveatError::Syntax { .. })
+        ));
+        assert!(matches!(
+            CaveatDef::new("c", params(), "a < 1 < 2"),
+            Err(CaveatError::Syntax { .. })
+        ));
+        let non_bool = CaveatDef::new("c", params(), "a + 1").unwrap();
+        assert!(
+            non_bool
+                .evaluate(&ctx(json!({"a": 1})), &ctx(json!({})))
+                .is_err()
+        );
+        assert!(non_bool.check_context(&ctx(json!({"a": 1}))).is_ok());
+        assert_eq!(
+            non_bool.check_context(&ctx(json!({"z": 1}))),
+            Err(CaveatError::UnknownParameter("z".into()))
+        );
+        assert!(non_bool.check_context(&ctx(json!({"a": "x"}))).is_err());
+    }
+
+    #[test]
+    fn param_types_round_trip_through_serde() {
+        let t = CaveatParamType::parse("map< list<ipaddress> >").unwrap();
+        assert_eq!(t.to_string(), "map<list<ipaddress>>");
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, "\"map<list<ipaddress>>\"");
+        assert_eq!(serde_json::from_str::<CaveatParamType>(&json).unwrap(), t);
+        assert!(CaveatParamType::parse("list<nope>").is_none());
+    }
+}
@@ -41,16 +41,29 @@
 //! by [`resolve`] returning `None`. Three-valued logic is fail-closed: a
 //! branch that can't be resolved (depth/cycle) never grants; only a
 //! definitive `Allowed` does.
+//!
+//! Caveats add one more indeterminate verdict. The leaf CTE reports
+//! which caveats sit on each path that reached the subject, and an
+//! arrow hop reports the caveat on the tupleset tuple; the evaluator
+//! runs them against the request context (a path grants only if every
+//! caveat on it holds). A caveat missing parameters yields
+//! [`Verdict::Conditional`], which propagates like the other
+//! indeterminate verdicts and surfaces as
+//! [`CheckResult::Conditional`] with the missing parameter names.
 
+use super::caveat::{CaveatDef, CaveatOutcome};
 use super::resolve::resolve;
 use super::types::{
-    CheckResult, MAX_DEPTH, NamespaceSchema, ObjectRef, PermissionExpr, RelationDef, RelationKind,
-    SubjectRef,
+    CaveatContext, CheckResult, MAX_DEPTH, NamespaceSchema, ObjectRef, PermissionExpr, RelationDef,
+    RelationKind, SubjectRef, TupleCaveat,
 };
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::future::Future;
 use std::pin::Pin;
 
+use serde::Deserialize;
+
 /// Verdict of one evaluation node. A superset of the boolean
 /// allow/deny: the two indeterminate variants carry *why* a branch
 /// could not be resolved so the algebra can fail closed and the final
@@ -59,23 +72,61 @@ use std::pin::Pin;
 pub enum Verdict {
     Allowed,
     Denied,
+    /// Hinges on caveats the request context can't decide.
+    Conditional,
     DepthExceeded,
     CycleDetected,
 }
 
 impl Verdict {
-    fn into_result(self) -> CheckResult {
+    fn into_result(self, missing_context: BTreeSet<String>) -> CheckResult {
         match self {
             Verdict::Allowed => CheckResult::Allowed {
                 resolved_via: Vec::new(),
             },
             Verdict::Denied => CheckResult::Denied,
+            Verdict::Conditional => CheckResult::Conditional {
+                missing_context: missing_context.into_iter().collect(),
+            },
             Verdict::DepthExceeded => CheckResult::DepthExceeded,
             Verdict::CycleDetected => CheckResult::CycleDetected,
         }
     }
 }
 
+/// One caveat met on a leaf path: the caveat `name`, the namespace
+/// declaring it (the caveated tuple's object type), and the context
+/// stored on that tuple. Deserialised from the JSON the leaf CTEs build.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PathCaveat {
+    pub object_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub context: CaveatContext,
+}
+
+/// What a leaf check found. `verdict` only counts uncaveated paths;
+/// when it isn't `Allowed`, `caveated_paths` lists the caveats along
+/// each path that did reach the subject, for the evaluator to decide.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeafOutcome {
+    pub verdict: Verdict,
+    pub caveated_paths: Vec<Vec<PathCaveat>>,
+}
+
+impl From<Verdict> for LeafOutcome {
+    fn from(verdict: Verdict) -> Self {
+        Self {
+            verdict,
+            caveated_paths: Vec::new(),
+        }
+    }
+}
+
+/// Target object of an arrow hop, with the caveat on the tuple that
+/// points at it.
+pub type ArrowTarget = (ObjectRef, Option<TupleCaveat>);
+
 /// The storage primitives the evaluator composes: the single-relation
 /// leaf check, the arrow-target listing, and the schema fetch an arrow
 /// hop needs. Implemented once per backend over its own pool + SQL
@@ -85,24 +136,26 @@ pub trait LeafCheck: Sync {
     /// along any relation in `relations`, following userset hops? This
     /// is the existing recursive CTE, lifted to take the resolved
     /// relation set as input. Returns the CTE's own three-valued
-    /// verdict (incl. its internal depth/cycle bound on userset chains).
+    /// verdict (incl. its internal depth/cycle bound on userset chains)
+    /// plus the caveats on any caveated path that reached the subject.
     fn check_relation_set<'a>(
         &'a self,
         object: &'a ObjectRef,
         relations: &'a [String],
         subject: &'a SubjectRef,
-    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Verdict>> + Send + 'a>>;
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<LeafOutcome>> + Send + 'a>>;
 
     /// List the *object* subjects of `(object, relation)` tuples — the
     /// left edge of a `relation->permission` arrow. Only object
     /// references (`subject_rel == ""`) participate in an arrow hop; a
     /// userset subject on the tupleset relation is skipped (it has no
-    /// object to re-evaluate the target permission against).
+    /// object to re-evaluate the target permission against). Each
+    /// target comes with the caveat on its tuple, if any.
     fn arrow_targets<'a>(
         &'a self,
         object: &'a ObjectRef,
         relation: &'a str,
-    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<ObjectRef>>> + Send + 'a>>;
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<ArrowTarget>>> + Send + 'a>>;
 
     /// Fetch a namespace schema by type name. Used to re-resolve an
     /// arrow's target permission against the target object's definition.
@@ -118,25 +171,43 @@ pub trait LeafCheck: Sync {
 ///
 /// `entry_schema` is `object`'s namespace (already fetched by the
 /// caller's `check`). Arrow hops fetch the target object's schema on
-/// demand via [`LeafCheck::schema_for`].
+/// demand via [`LeafCheck::schema_for`]. `context` is the request's
+/// caveat context; tuple contexts take precedence over it.
 pub async fn evaluate<B: LeafCheck>(
     backend: &B,
     entry_schema: &NamespaceSchema,
     object: &ObjectRef,
     permission: &str,
     subject: &SubjectRef,
+    context: &CaveatContext,
 ) -> anyhow::Result<CheckResult> {
-    let mut frames: Vec<(String, String, String)> = Vec::new();
+    let mut state = EvalState {
+        frames: Vec::new(),
+        context: context.clone(),
+        caveats: BTreeMap::new(),
+        missing: BTreeSet::new(),
+    };
     let v = eval_name(
         backend,
         entry_schema,
         object,
         permission,
         subject,
-        &mut frames,
+        &mut state,
     )
     .await?;
-    Ok(v.into_result())
+    Ok(v.into_result(state.missing))
+}
+
+/// Per-check evaluation state threaded through the recursion.
+struct EvalState {
+    /// `(object_type, object_id, name)` stack for the depth/cycle bound.
+    frames: Vec<(String, String, String)>,
+    context: CaveatContext,
+    /// Caveat definitions already fetched, keyed by (namespace, name).
+    caveats: BTreeMap<(String, String), Option<CaveatDef>>,
+    /// Parameters some caveat needed but no context supplied.
+    missing: BTreeSet<String>,
 }
 
 /// Evaluate a relation- or permission-*name* on `object`. This is where
@@ -151,7 +222,7 @@ fn eval_name<'a, B: LeafCheck>(
     object: &'a ObjectRef,
     name: &'a str,
     subject: &'a SubjectRef,
-    frames: &'a mut Vec<(String, String, String)>,
+    state: &'a mut EvalState,
 ) -> Pin<Box<dyn Future<Output = anyhow::Result<Verdict>> + Send + 'a>> {
     Box::pin(async move {
         let frame = (
@@ -166,15 +237,15 @@ fn eval_name<'a, B: LeafCheck>(
         // and contributes no rows). `CycleDetected` is reserved for a
         // malformed *schema* (a permission expression that references
         // itself), surfaced by `resolve` returning `None` below.
-        if frames.contains(&frame) {
+        if state.frames.contains(&frame) {
             return Ok(Verdict::Denied);
         }
-        if frames.len() >= MAX_DEPTH as usize {
+        if state.frames.len() >= MAX_DEPTH as usize {
             return Ok(Verdict::DepthExceeded);
         }
-        frames.push(frame);
-        let out = eval_name_inner(backend, schema, object, name, subject, frames).await;
-        frames.pop();
+        state.frames.push(frame);
+        let out = eval_name_inner(backend, schema, object, name, subject, state).await;
+        state.frames.pop();
         out
     })
 }
@@ -185,7 +256,7 @@ async fn eval_name_inner<B: LeafCheck>(
     object: &ObjectRef,
     name: &str,
     subject: &SubjectRef,
-    frames: &mut Vec<(String, String, String)>,
+    state: &mut EvalState,
 ) -> anyhow::Result<Verdict> {
     match schema.definitions.get(name) {
         // A real relation, or an undefined name treated leniently as
@@ -196,9 +267,7 @@ async fn eval_name_inner<B: LeafCheck>(
             ..
         }) => {
             let relations = [name.to_string()];
-            backend
-                .check_relation_set(object, &relations, subject)
-                .await
+            eval_leaf(backend, object, &relations, subject, state).await
         }
         Some(RelationDef {
             kind: RelationKind::Permission(expr),
@@ -219,16 +288,84 @@ async fn eval_name_inner<B: LeafCheck>(
                     return Ok(Verdict::Denied);
                 }
                 let relations: Vec<String> = resolved.union_relations.into_iter().collect();
-                return backend
-                    .check_relation_set(object, &relations, subject)
-                    .await;
+                return eval_leaf(backend, object, &relations, subject, state).await;
             }
             // Otherwise walk the expression structurally.
-            eval_expr(backend, schema, object, expr, subject, frames).await
+            eval_expr(backend, schema, object, expr, subject, state).await
         }
     }
 }
 
+/// Run the leaf CTE and, when no uncaveated path grants, decide the
+/// caveated paths: a path grants iff every caveat on it holds, and any
+/// granting path grants the leaf.
+async fn eval_leaf<B: LeafCheck>(
+    backend: &B,
+    object: &ObjectRef,
+    relations: &[String],
+    subject: &SubjectRef,
+    state: &mut EvalState,
+) -> anyhow::Result<Verdict> {
+    let outcome = backend
+        .check_relation_set(object, relations, subject)
+        .await?;
+    let mut acc = outcome.verdict;
+    if acc == Verdict::Allowed {
+        return Ok(acc);
+    }
+    for path in &outcome.caveated_paths {
+        let mut v = Verdict::Allowed;
+        for c in path {
+            let cv = eval_caveat(backend, &c.object_type, &c.name, &c.context, state).await?;
+            v = combine_intersect(v, cv);
+            if v == Verdict::Denied {
+                break;
+            }
+        }
+        if v == Verdict::Allowed {
+            return Ok(v);
+        }
+        acc = combine_union(acc, v);
+    }
+    Ok(acc)
+}
+
+/// Decide one caveat from a tuple on `object_type`, looking its
+/// definition up in that namespace. A caveat the namespace no longer
+/// declares can't be evaluated and fails closed.
+async fn eval_caveat<B: LeafCheck>(
+    backend: &B,
+    object_type: &str,
+    name: &str,
+    stored: &CaveatContext,
+    state: &mut EvalState,
+) -> anyhow::Result<Verdict> {
+    let key = (object_type.to_string(), name.to_string());
+    if !state.caveats.contains_key(&key) {
+        let def = backend
+            .schema_for(object_type)
+            .await?
+            .and_then(|mut s| s.caveats.remove(name));
+        state.caveats.insert(key.clone(), def);
+    }
+    let Some(def) = &state.caveats[&key] else {
+        tracing::warn!(
+            object_type,
+            caveat = name,
+            "zanzibar: tuple references an undefined caveat — denying"
+        );
+        return Ok(Verdict::Denied);
+    };
+    Ok(match def.evaluate(stored, &state.context)? {
+        CaveatOutcome::Satisfied => Verdict::Allowed,
+        CaveatOutcome::Unsatisfied => Verdict::Denied,
+        CaveatOutcome::MissingContext(names) => {
+            state.missing.extend(names);
+            Verdict::Conditional
+        }
+    })
+}
+
 /// Recursively evaluate a [`PermissionExpr`] node against `(object,
 /// subject)` within `schema` (the namespace `object` belongs to).
 fn eval_expr<'a, B: LeafCheck>(
@@ -237,41 +374,41 @@ fn eval_expr<'a, B: LeafCheck>(
     object: &'a ObjectRef,
     expr: &'a PermissionExpr,
     subject: &'a SubjectRef,
-    frames: &'a mut Vec<(String, String, String)>,
+    state: &'a mut EvalState,
 ) -> Pin<Box<dyn Future<Output = anyhow::Result<Verdict>> + Send + 'a>> {
     Box::pin(async move {
         match expr {
             PermissionExpr::Direct { relation } => {
-                eval_name(backend, schema, object, relation, subject, frames).await
+                eval_name(backend, schema, object, relation, subject, state).await
             }
             PermissionExpr::Union { left, right } => {
-                let l = eval_expr(backend, schema, object, left, subject, frames).await?;
+                let l = eval_expr(backend, schema, object, left, subject, state).await?;
                 if l == Verdict::Allowed {
                     return Ok(Verdict::Allowed);
                 }
-                let r = eval_expr(backend, schema, object, right, subject, frames).await?;
+                let r = eval_expr(backend, schema, object, right, subject, state).await?;
                 Ok(combine_union(l, r))
             }
             PermissionExpr::Intersect { left, right } => {
-                let l = eval_expr(backend, schema, object, left, subject, frames).await?;
+                let l = eval_expr(backend, schema, object, left, subject, state).await?;
                 if l == Verdict::Denied {
                     return Ok(Verdict::Denied);
                 }
-                let r = eval_expr(backend, schema, object, right, subject, frames).await?;
+                let r = eval_expr(backend, schema, object, right, subject, state).await?;
                 Ok(combine_intersect(l, r))
             }
             PermissionExpr::Exclude { left, right } => {
-                let l = eval_expr(backend, schema, object, left, subject, frames).await?;
+                let l = eval_expr(backend, schema, object, left, subject, state).await?;
                 if l == Verdict::Denied {
                     return Ok(Verdict::Denied);
                 }
-                let r = eval_expr(backend, schema, object, right, subject, frames).await?;
+                let r = eval_expr(backend, schema, object, right, subject, state).await?;
                 Ok(combine_exclude(l, r))
             }
             PermissionExpr::TuplesetArrow {
                 tupleset,
                 permission,
-            } => eval_arrow(backend, object, tupleset, permission, subject, frames).await,
+            } => eval_arrow(backend, object, tupleset, permission, subject, state).await,
         }
     })
 }
@@ -280,22 +417,35 @@ fn eval_expr<'a, B: LeafCheck>(
 /// `(object, tupleset)`, re-resolve and evaluate `permission` on `o`
 /// against `o`'s own namespace. Any [`Verdict::Allowed`] wins;
 /// otherwise the strongest indeterminate signal is reported (fail
-/// closed). A missing target namespace is a clean deny for that hop.
+/// closed). A missing target namespace is a clean deny for that hop,
+/// and so is a caveat on the tupleset tuple that doesn't hold.
 async fn eval_arrow<B: LeafCheck>(
     backend: &B,
     object: &ObjectRef,
     tupleset: &str,
     permission: &str,
     subject: &SubjectRef,
-    frames: &mut Vec<(String, String, String)>,
+    state: &mut EvalState,
 ) -> anyhow::Result<Verdict> {
     let targets = backend.arrow_targets(object, tupleset).await?;
     let mut acc = Verdict::Denied;
-    for target in &targets {
+    for (target, caveat) in &targets {
         let Some(target_schema) = backend.schema_for(&target.object_type).await? else {
             continue;
         };
-        let v = eval_name(backend, &target_schema, target, permission, subject, frames).await?;
+        let v = eval_name(backend, &target_schema, target, permission, subject, state).await?;
+        // Only consult the hop's caveat when the target could grant, so
+        // a conditional result asks for context that actually matters.
+        if v == Verdict::Denied {
+            continue;
+        }
+        let hop = match caveat {
+            Some(c) => {
+                eval_caveat(backend, &object.object_type, &c.name, &c.context, state).await?
+            }
+            None => Verdict::Allowed,
+        };
+        let v = combine_intersect(hop, v);
         if v == Verdict::Allowed {
             return Ok(Verdict::Allowed);
         }
@@ -306,10 +456,12 @@ async fn eval_arrow<B: LeafCheck>(
 
 /// `a OR b` — a definitive grant on either side wins regardless of the
 /// other being indeterminate. Otherwise the strongest "couldn't
-/// determine" signal survives so the caller can surface depth/cycle.
+/// determine" signal survives so the caller can surface it; a
+/// conditional side ranks first, since more context could still grant.
 fn combine_union(a: Verdict, b: Verdict) -> Verdict {
     match (a, b) {
         (Verdict::Allowed, _) | (_, Verdict::Allowed) => Verdict::Allowed,
+        (Verdict::Conditional, _) | (_, Verdict::Conditional) => Verdict::Conditional,
         (Verdict::DepthExceeded, _) | (_, Verdict::DepthExceeded) => Verdict::DepthExceeded,
         (Verdict::CycleDetected, _) | (_, Verdict::CycleDetected) => Verdict::CycleDetected,
         _ => Verdict::Denied,
@@ -318,13 +470,15 @@ fn combine_union(a: Verdict, b: Verdict) -> Verdict {
 
 /// `a AND b` — a definitive deny on either side wins. Both `Allowed`
 /// grants; otherwise an indeterminate side blocks the grant (fail
-/// closed) and is propagated.
+/// closed) and is propagated, depth/cycle ahead of conditional — more
+/// context can't fix those.
 fn combine_intersect(a: Verdict, b: Verdict) -> Verdict {
     match (a, b) {
         (Verdict::Denied, _) | (_, Verdict::Denied) => Verdict::Denied,
         (Verdict::Allowed, Verdict::Allowed) => Verdict::Allowed,
         (Verdict::DepthExceeded, _) | (_, Verdict::DepthExceeded) => Verdict::DepthExceeded,
         (Verdict::CycleDetected, _) | (_, Verdict::CycleDetected) => Verdict::CycleDetected,
+        _ => Verdict::Conditional,
     }
 }
 
@@ -341,8 +495,11 @@ fn combine_exclude(left: Verdict, right: Verdict) -> Verdict {
         (Verdict::DepthExceeded, _) | (_, Verdict::DepthExceeded) => Verdict::DepthExceeded,
         (Verdict::CycleDetected, _) | (_, Verdict::CycleDetected) => Verdict::CycleDetected,
         // left == Denied is handled by the caller's short-circuit; the
-        // remaining (Denied, Denied) is a deny.
-        _ => Verdict::Denied,
+        // remaining (Denied, _) is a deny.
+        (Verdict::Denied, _) => Verdict::Denied,
+        // Conditional on either side with nothing stronger: the answer
+        // depends on the missing context.
+        _ => Verdict::Conditional,
     }
 }
 
@@ -350,16 +507,17 @@ fn combine_exclude(left: Verdict, right: Verdict) -> Verdict {
 mod tests {
     use super::*;
     use crate::zanzibar::types::{RelationDef, TypeRef};
-    use std::collections::{BTreeMap, BTreeSet};
     use std::sync::Mutex;
 
+    /// `((object_type, object_id, relation), subject, caveat)`.
+    type FakeTuple = ((String, String, String), SubjectRef, Option<TupleCaveat>);
+
     /// In-memory [`LeafCheck`] over a tuple set, mirroring the SQL
     /// backends without a database. The leaf check follows userset hops
     /// transitively (the CTE's job) with its own depth/cycle bound so
     /// the evaluator's composition can be unit-tested in isolation.
     struct FakeBackend {
-        /// (object_type, object_id, relation) -> subjects.
-        tuples: Vec<((String, String, String), SubjectRef)>,
+        tuples: Vec<FakeTuple>,
         schemas: BTreeMap<String, NamespaceSchema>,
         /// Records each relation-set leaf check for fast-path assertions.
         leaf_calls: Mutex<Vec<Vec<String>>>,
@@ -374,20 +532,35 @@ mod tests {
             }
         }
 
-        fn tuple(mut self, ot: &str, oid: &str, rel: &str, subject: SubjectRef) -> Self {
+        fn tuple(self, ot: &str, oid: &str, rel: &str, subject: SubjectRef) -> Self {
+            self.caveated(ot, oid, rel, subject, None)
+        }
+
+        fn caveated(
+            mut self,
+            ot: &str,
+            oid: &str,
+            rel: &str,
+            subject: SubjectRef,
+            caveat: Option<TupleCaveat>,
+        ) -> Self {
             self.tuples
-                .push(((ot.into(), oid.into(), rel.into()), subject));
+                .push(((ot.into(), oid.into(), rel.into()), subject, caveat));
             self
         }
 
-        /// Direct subjects of (object, relation).
-        fn direct_subjects(&self, object: &ObjectRef, relation: &str) -> Vec<SubjectRef> {
+        /// Direct subjects of (object, relation), with their caveats.
+        fn direct_subjects(
+            &self,
+            object: &ObjectRef,
+            relation: &str,
+        ) -> Vec<(SubjectRef, Option<TupleCaveat>)> {
             self.tuples
                 .iter()
-                .filter(|((ot, oid, rel), _)| {
+                .filter(|((ot, oid, rel), _, _)| {
                     ot == &object.object_type && oid == &object.object_id && rel == relation
                 })
-                .map(|(_, s)| s.clone())
+                .map(|(_, s, c)| (s.clone(), c.clone()))
                 .collect()
         }
     }
@@ -398,29 +571,45 @@ mod tests {
             object: &'a ObjectRef,
             relations: &'a [String],
             subject: &'a SubjectRef,
-        ) -> Pin<Box<dyn Future<Output = anyhow::Result<Verdict>> + Send + 'a>> {
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<LeafOutcome>> + Send + 'a>> {
             self.leaf_calls.lock().unwrap().push(relations.to_vec());
             Box::pin(async move {
-                // BFS over userset hops, bounded like the real CTE.
+                // BFS over userset hops, bounded like the real CTE. Each
+                // frontier entry carries the caveats met on its path.
                 let mut seen: BTreeSet<(String, String, String)> = BTreeSet::new();
-                let mut frontier: Vec<(ObjectRef, String)> = relations
+                let mut frontier: Vec<(ObjectRef, String, Vec<PathCaveat>)> = relations
                     .iter()
-                    .map(|r| (object.clone(), r.clone()))
+                    .map(|r| (object.clone(), r.clone(), Vec::new()))
                     .collect();
+                let mut caveated_paths = Vec::new();
                 let mut depth = 0u32;
                 while !frontier.is_empty() {
                     depth += 1;
                     if depth > MAX_DEPTH {
-                        return Ok(Verdict::DepthExceeded);
+                        return Ok(LeafOutcome {
+                            verdict: Verdict::DepthExceeded,
+                            caveated_paths,
+                        });
                     }
                     let mut next = Vec::new();
-                    for (obj, rel) in frontier.drain(..) {
-                        for s in self.direct_subjects(&obj, &rel) {
+                    for (obj, rel, path) in frontier.drain(..) {
+                        for (s, caveat) in self.direct_subjects(&obj, &rel) {
+                            let mut path = path.clone();
+                            if let Some(c) = caveat {
+                                path.push(PathCaveat {
+                                    object_type: obj.object_type.clone(),
+                                    name: c.name,
+                                    context: c.context,
+                                });
+                            }
                             if s.subject_rel.is_empty() {
                                 if s.subject_type == subject.subject_type
                                     && s.subject_id == subject.subject_id
                                 {
-                                    return Ok(Verdict::Allowed);
+                                    if path.is_empty() {
+                                        return Ok(Verdict::Allowed.into());
+                                    }
+                                    caveated_paths.push(path);
                                 }
                             } else {
                                 let key = (
@@ -432,6 +621,7 @@ mod tests {
                                     next.push((
                                         ObjectRef::new(s.subject_type, s.subject_id),
                                         s.subject_rel,
+                                        path,
                                     ));
                                 }
                             }
@@ -439,7 +629,10 @@ mod tests {
                     }
                     frontier = next;
                 }
-                Ok(Verdict::Denied)
+                Ok(LeafOutcome {
+                    verdict: Verdict::Denied,
+                    caveated_paths,
+                })
             })
         }
 
@@ -447,13 +640,13 @@ mod tests {
             &'a self,
             object: &'a ObjectRef,
             relation: &'a str,
-        ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<ObjectRef>>> + Send + 'a>> {
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<ArrowTarget>>> + Send + 'a>> {
             Box::pin(async move {
                 Ok(self
                     .direct_subjects(object, relation)
                     .into_iter()
-                    .filter(|s| s.subject_rel.is_empty())
-                    .map(|s| ObjectRef::new(s.subject_type, s.subject_id))
+                    .filter(|(s, _)| s.subject_rel.is_empty())
+                    .map(|(s, c)| (ObjectRef::new(s.subject_type, s.subject_id), c))
                     .collect())
             })
         }
@@ -497,10 +690,21 @@ mod tests {
         oid: &str,
         perm: &str,
         sid: &str,
+    ) -> CheckResult {
+        check_with(backend, schema, oid, perm, sid, &CaveatContext::new()).await
+    }
+
+    async fn check_with(
+        backend: &FakeBackend,
+        schema: &NamespaceSchema,
+        oid: &str,
+        perm: &str,
+        sid: &str,
+        context: &CaveatContext,
     ) -> CheckResult {
         let object = ObjectRef::new(&schema.name, oid);
         let subject = SubjectRef::direct("user", sid);
-        evaluate(backend, schema, &object, perm, &subject)
+        evaluate(backend, schema, &object, perm, &subject, context)
             .await
             .unwrap()
     }
@@ -703,4 +907,156 @@ mod tests {
             CheckResult::CycleDetected
         );
     }
+
+    /// `doc` with `viewer: user | user with on_network`, where the
+    /// caveat checks the request IP against a CIDR stored on the tuple,
+    /// and `parent: folder with on_network` for caveated arrow hops.
+    fn caveat_schemas() -> (NamespaceSchema, NamespaceSchema) {
+        use crate::zanzibar::caveat::{CaveatDef, CaveatParamType};
+        let on_network = CaveatDef::new(
+            "on_network",
+            [
+                ("ip".to_string(), CaveatParamType::IpAddress),
+                ("cidr".to_string(), CaveatParamType::String),
+            ],
+            "ip.in_cidr(cidr)",
+        )
+        .unwrap();
+        let doc = NamespaceSchema::new("doc")
+            .with_caveat(on_network.clone())
+            .with_relation(
+                "viewer",
+                RelationDef::relation(
+                    "viewer",
+                    vec![
+                        TypeRef::direct("user"),
+                        TypeRef::direct("user").with_caveat("on_network"),
+                    ],
+                ),
+            )
+            .with_relation(
+                "parent",
+                RelationDef::relation(
+                    "parent",
+                    vec![TypeRef::direct("folder").with_caveat("on_network")],
+                ),
+            )
+            .with_relation(
+                "view",
+                RelationDef::permission(
+                    "view",
+                    PermissionExpr::union(
+                        PermissionExpr::direct("viewer"),
+                        PermissionExpr::arrow("parent", "read"),
+                    ),
+                ),
+            );
+        let folder = NamespaceSchema::new("folder").with_relation(
+            "read",
+            RelationDef::relation("read", vec![TypeRef::direct("user")]),
+        );
+        (doc, folder)
+    }
+
+    fn ctx(v: serde_json::Value) -> CaveatContext {
+        v.as_object().cloned().unwrap()
+    }
+
+    fn office() -> Option<TupleCaveat> {
+        Some(TupleCaveat {
+            name: "on_network".into(),
+            context: ctx(serde_json::json!({"cidr": "10.0.0.0/8"})),
+        })
+    }
+
+    #[tokio::test]
+    async fn caveated_leaf_is_three_valued() {
+        let (doc, folder) = caveat_schemas();
+        let backend = FakeBackend::new(vec![doc.clone(), folder]).caveated(
+            "doc",
+            "x",
+            "viewer",
+            SubjectRef::direct("user", "alice"),
+            office(),
+        );
+        let inside = ctx(serde_json::json!({"ip": "10.1.2.3"}));
+        let outside = ctx(serde_json::json!({"ip": "192.168.1.1"}));
+        assert!(
+            check_with(&backend, &doc, "x", "view", "alice", &inside)
+                .await
+                .is_allowed()
+        );
+        assert_eq!(
+            check_with(&backend, &doc, "x", "view", "alice", &outside).await,
+            CheckResult::Denied
+        );
+        assert_eq!(
+            check(&backend, &doc, "x", "view", "alice").await,
+            CheckResult::Conditional {
+                missing_context: vec!["ip".into()]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn uncaveated_path_wins_over_caveated() {
+        let (doc, folder) = caveat_schemas();
+        let backend = FakeBackend::new(vec![doc.clone(), folder])
+            .caveated(
+                "doc",
+                "x",
+                "viewer",
+                SubjectRef::direct("user", "alice"),
+                office(),
+            )
+            .tuple("doc", "x", "viewer", SubjectRef::direct("user", "alice"));
+        assert!(
+            check(&backend, &doc, "x", "view", "alice")
+                .await
+                .is_allowed()
+        );
+    }
+
+    #[tokio::test]
+    async fn caveated_arrow_hop() {
+        let (doc, folder) = caveat_schemas();
+        let backend = FakeBackend::new(vec![doc.clone(), folder])
+            .caveated(
+                "doc",
+                "x",
+                "parent",
+                SubjectRef::direct("folder", "f"),
+                office(),
+            )
+            .tuple("folder", "f", "read", SubjectRef::direct("user", "bob"));
+        let inside = ctx(serde_json::json!({"ip": "10.9.9.9"}));
+        assert!(
+            check_with(&backend, &doc, "x", "view", "bob", &inside)
+                .await
+                .is_allowed()
+        );
+        assert!(matches!(
+            check(&backend, &doc, "x", "view", "bob").await,
+            CheckResult::Conditional { .. }
+        ));
+        // The caveat gates the hop, not the grant behind it.
+        assert_eq!(
+            check_with(&backend, &doc, "x", "view", "carol", &inside).await,
+            CheckResult::Denied
+        );
+    }
+
+    #[test]
+    fn conditional_composes_fail_closed() {
+        use Verdict::*;
+        assert_eq!(combine_union(Conditional, Denied), Conditional);
+        assert_eq!(combine_union(Conditional, Allowed), Allowed);
+        assert_eq!(combine_union(Conditional, DepthExceeded), Conditional);
+        assert_eq!(combine_intersect(Conditional, Allowed), Conditional);
+        assert_eq!(combine_intersect(Conditional, Denied), Denied);
+        assert_eq!(combine_intersect(Conditional, DepthExceeded), DepthExceeded);
+        assert_eq!(combine_exclude(Allowed, Conditional), Conditional);
+        assert_eq!(combine_exclude(Conditional, Denied), Conditional);
+        assert_eq!(combine_exclude(Conditional, Allowed), Denied);
+    }
 }
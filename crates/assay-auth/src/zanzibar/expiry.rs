@@ -0,0 +1,77 @@
+//! Background sweep of expired Zanzibar tuples.
+//!
+//! Reads never see a tuple past its `expires_at` — every store filters
+//! them out — so this loop only reclaims the rows. Each expired tuple
+//! is deleted through the changelog, so Watch subscribers see a
+//! `DELETE`, and every batch that removed something publishes one
+//! [`EXPIRED_EVENT_KIND`] event on the engine event bus. Idempotent
+//! across nodes: a tuple another node already swept is skipped.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use assay_domain::events::{EngineEventBus, NewEvent, Subsystem};
+use serde_json::json;
+
+use super::store::{ZanzibarStore, unix_now};
+
+/// `engine_events.kind` of the event a sweep publishes.
+pub const EXPIRED_EVENT_KIND: &str = "zanzibar.tuples_expired";
+
+/// Tuples aren't namespaced; their events go to the default namespace.
+const EVENT_NAMESPACE: &str = "main";
+
+/// Tuples expired per transaction (and per event).
+const BATCH: i64 = 500;
+
+/// Run the sweep loop forever. Callers spawn this as a tokio task
+/// alongside the engine's other housekeeping loops.
+pub async fn run_expiry_sweeper(
+    store: Arc<dyn ZanzibarStore>,
+    bus: Arc<dyn EngineEventBus>,
+    cadence: Duration,
+) {
+    let mut tick = tokio::time::interval(cadence);
+    loop {
+        tick.tick().await;
+        match sweep_expired(store.as_ref(), bus.as_ref(), unix_now()).await {
+            Ok(n) if n > 0 => tracing::info!(expired = n, "zanzibar expiry sweep"),
+            Ok(_) => tracing::debug!("zanzibar expiry sweep: nothing expired"),
+            Err(e) => tracing::warn!(?e, "zanzibar expiry sweep failed; will retry next tick"),
+        }
+    }
+}
+
+/// Delete every tuple expired at `now` (unix seconds), publishing one
+/// event per batch with the removed tuples and the zookie of the last
+/// delete. Returns how many tuples were removed.
+pub async fn sweep_expired(
+    store: &dyn ZanzibarStore,
+    bus: &dyn EngineEventBus,
+    now: i64,
+) -> anyhow::Result<usize> {
+    let mut total = 0;
+    loop {
+        let changes = store.expire_tuples(now, BATCH).await?;
+        let Some(last) = changes.last() else {
+            break;
+        };
+        let tuples: Vec<_> = changes.iter().map(|c| &c.tuple).collect();
+        bus.publish_committed(NewEvent {
+            namespace: EVENT_NAMESPACE,
+            subsystem: Subsystem::Auth,
+            kind: EXPIRED_EVENT_KIND,
+            payload: json!({
+                "count": changes.len(),
+                "zookie": last.zookie,
+                "tuples": tuples,
+            }),
+        })
+        .await?;
+        total += changes.len();
+        if (changes.len() as i64) < BATCH {
+            break;
+        }
+    }
+    Ok(total)
+}
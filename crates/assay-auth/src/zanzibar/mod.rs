@@ -3,12 +3,20 @@
 //! Module shape:
 //!
 //! - [`types`] — POD types: [`Tuple`], [`ObjectRef`], [`SubjectRef`],
-//!   [`NamespaceSchema`], [`PermissionExpr`], [`Consistency`],
-//!   [`CheckResult`], [`UsersetTree`].
+//!   [`NamespaceSchema`], [`PermissionExpr`], [`Consistency`] (and its
+//!   [`ConsistencyError`]),
+//!   [`CheckResult`], [`UsersetTree`], the Watch change feed
+//!   ([`TupleChange`], [`WatchFilter`], [`WatchBatch`]), and
+//!   [`Compaction`].
 //! - [`schema`] — SpiceDB-compatible DSL parser.
+//! - [`caveat`] — the CEL-subset predicates behind conditional tuples
+//!   ([`CaveatDef`], [`CaveatError`]).
 //! - [`resolve`] — permission-expression → seed relation set
 //!   computation, shared by both backend impls.
 //! - [`store`] — the [`ZanzibarStore`] async trait.
+//! - [`expiry`] — background sweep deleting tuples past `expires_at`.
+//! - [`gc`] — background compaction of MVCC history below the GC
+//!   horizon.
 //! - [`postgres`] / [`sqlite`] — recursive-CTE-backed implementations.
 //!
 //! Why a directory module: phase 6 is the largest single auth module
@@ -17,7 +25,10 @@
 //! callers expect. Compile-time `auth-zanzibar` feature still gates
 //! the entire tree.
 
+pub mod caveat;
 pub mod eval;
+pub mod expiry;
+pub mod gc;
 #[cfg(feature = "backend-postgres")]
 pub mod postgres;
 pub mod resolve;
@@ -27,11 +38,14 @@ pub mod sqlite;
 pub mod store;
 pub mod types;
 
+pub use caveat::{CaveatDef, CaveatError, CaveatOutcome, CaveatParamType};
 pub use schema::{ParseError, parse_schema};
 pub use store::ZanzibarStore;
 pub use types::{
-    CheckResult, Consistency, MAX_DEPTH, NamespaceSchema, ObjectRef, PermissionExpr, RelationDef,
-    RelationKind, SubjectRef, TreeOp, Tuple, TupleFilter, TypeRef, UsersetTree,
+    CaveatContext, CheckResult, Compaction, Consistency, ConsistencyError, MAX_DEPTH,
+    NamespaceSchema, ObjectRef, PermissionExpr, RelationDef, RelationKind, SubjectRef, TreeOp,
+    Tuple, TupleCaveat, TupleChange, TupleFilter, TupleOperation, TypeRef, UsersetTree, WatchBatch,
+    WatchFilter, decode_zookie, encode_zookie,
 };
 
 #[cfg(feature = "backend-postgres")]
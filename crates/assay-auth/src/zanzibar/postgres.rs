@@ -22,18 +22,25 @@
 //!   100-namespace order-of-magnitude any v0.2.0 deployment will
 //!   actually have, parsing on every check is fine — the schema
 //!   cache is a phase-9 follow-up.
+//! - Expired tuples (`expires_at <= now`, with `now` bound from Rust)
+//!   are filtered in every read path until the sweeper stamps them
+//!   deleted; `idx_auth_zanzibar_tuples_expiry` keeps that sweep cheap.
 
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use sqlx::{PgPool, Row};
 
-use super::eval::{LeafCheck, Verdict, evaluate};
+use super::eval::{ArrowTarget, LeafCheck, LeafOutcome, PathCaveat, Verdict, evaluate};
 use super::resolve::resolve;
-use super::store::ZanzibarStore;
+use super::store::{
+    ZanzibarStore, caveat_columns, caveat_from_columns, ensure_retained, snapshot_revision,
+    unix_now,
+};
 use super::types::{
-    CheckResult, Consistency, MAX_DEPTH, NamespaceSchema, ObjectRef, SubjectRef, TreeOp, Tuple,
-    TupleFilter, UsersetTree,
+    CaveatContext, CheckResult, Compaction, Consistency, MAX_DEPTH, NamespaceSchema, ObjectRef,
+    SubjectRef, TreeOp, Tuple, TupleChange, TupleFilter, TupleOperation, UsersetTree, WatchBatch,
+    WatchFilter, decode_zookie, encode_zookie,
 };
 use std::future::Future;
 use std::pin::Pin;
@@ -54,6 +61,22 @@ impl PostgresZanzibarStore {
     pub fn into_dyn(self) -> Arc<dyn ZanzibarStore> {
         Arc::new(self)
     }
+
+    /// Latest committed changelog revision (0 before the first write).
+    async fn head(&self) -> Result<i64> {
+        head_revision(&self.pool).await
+    }
+
+    /// Oldest revision `Exact` reads and watch cursors may name (0
+    /// before the first compaction).
+    async fn horizon(&self) -> Result<i64> {
+        let (horizon,): (i64,) =
+            sqlx::query_as("SELECT COALESCE(MAX(horizon), 0) FROM auth.zanzibar_gc")
+                .fetch_one(&self.pool)
+                .await
+                .context("auth.zanzibar_gc horizon")?;
+        Ok(horizon)
+    }
 }
 
 #[async_trait::async_trait]
@@ -103,38 +126,32 @@ impl ZanzibarStore for PostgresZanzibarStore {
             .collect()
     }
 
-    async fn write_tuple(&self, t: &Tuple) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO auth.zanzibar_tuples
-                (object_type, object_id, relation,
-                 subject_type, subject_id, subject_rel, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, EXTRACT(EPOCH FROM NOW()))
-             ON CONFLICT DO NOTHING",
-        )
-        .bind(&t.object_type)
-        .bind(&t.object_id)
-        .bind(&t.relation)
-        .bind(&t.subject_type)
-        .bind(&t.subject_id)
-        .bind(&t.subject_rel)
-        .execute(&self.pool)
-        .await
-        .context("auth.zanzibar_tuples insert")?;
-        Ok(())
+    async fn write_tuple(&self, t: &Tuple) -> Result<String> {
+        self.write_tuples(std::slice::from_ref(t)).await
     }
 
-    async fn write_tuples(&self, tuples: &[Tuple]) -> Result<()> {
+    async fn write_tuples(&self, tuples: &[Tuple]) -> Result<String> {
         if tuples.is_empty() {
-            return Ok(());
+            return Ok(encode_zookie(self.head().await?));
         }
         let mut tx = self.pool.begin().await.context("begin tuples txn")?;
+        lock_changelog(&mut tx).await?;
         for t in tuples {
+            // Already live with the same caveat and expiry → nothing
+            // recorded, nothing to insert.
+            let Some(revision) = record_change(&mut tx, TupleOperation::Touch, t).await? else {
+                continue;
+            };
+            // Live under a different caveat or expiry → that version
+            // ends here.
+            stamp_deleted(&mut tx, t, revision).await?;
+            let (caveat_name, caveat_context) = caveat_columns(t);
             sqlx::query(
                 "INSERT INTO auth.zanzibar_tuples
                     (object_type, object_id, relation,
-                     subject_type, subject_id, subject_rel, created_at)
-                 VALUES ($1, $2, $3, $4, $5, $6, EXTRACT(EPOCH FROM NOW()))
-                 ON CONFLICT DO NOTHING",
+                     subject_type, subject_id, subject_rel, created_at, created_revision,
+                     caveat_name, caveat_context, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, EXTRACT(EPOCH FROM NOW()), $7, $8, $9, $10)",
             )
             .bind(&t.object_type)
             .bind(&t.object_id)
@@ -142,45 +159,45 @@ impl ZanzibarStore for PostgresZanzibarStore {
             .bind(&t.subject_type)
             .bind(&t.subject_id)
             .bind(&t.subject_rel)
+            .bind(revision)
+            .bind(caveat_name)
+            .bind(caveat_context)
+            .bind(t.expires_at)
             .execute(&mut *tx)
             .await
             .context("auth.zanzibar_tuples batch insert")?;
         }
+        // Under the changelog lock the head is this batch's last
+        // revision, or — for an all no-op batch — the revision the
+        // tuples were already visible from.
+        let head = head_revision(&mut *tx).await?;
         tx.commit().await.context("commit tuples txn")?;
-        Ok(())
+        Ok(encode_zookie(head))
     }
 
-    async fn delete_tuple(&self, t: &Tuple) -> Result<bool> {
-        // subject_rel is NOT NULL ('' for direct), so plain equality
-        // suffices — no IS NOT DISTINCT FROM dance.
-        let res = sqlx::query(
-            "DELETE FROM auth.zanzibar_tuples
-             WHERE object_type = $1 AND object_id = $2 AND relation = $3
-               AND subject_type = $4 AND subject_id = $5
-               AND subject_rel = $6",
-        )
-        .bind(&t.object_type)
-        .bind(&t.object_id)
-        .bind(&t.relation)
-        .bind(&t.subject_type)
-        .bind(&t.subject_id)
-        .bind(&t.subject_rel)
-        .execute(&self.pool)
-        .await
-        .context("auth.zanzibar_tuples delete")?;
-        Ok(res.rows_affected() > 0)
+    async fn delete_tuple(&self, t: &Tuple) -> Result<Option<String>> {
+        let mut tx = self.pool.begin().await.context("begin delete txn")?;
+        lock_changelog(&mut tx).await?;
+        let Some(revision) = record_change(&mut tx, TupleOperation::Delete, t).await? else {
+            return Ok(None);
+        };
+        stamp_deleted(&mut tx, t, revision).await?;
+        tx.commit().await.context("commit delete txn")?;
+        Ok(Some(encode_zookie(revision)))
     }
 
     async fn list_tuples(&self, filter: &TupleFilter) -> Result<Vec<Tuple>> {
-        let rows: Vec<(String, String, String, String, String, String)> = sqlx::query_as(
+        let rows: Vec<TupleRow> = sqlx::query_as(
             "SELECT object_type, object_id, relation,
-                    subject_type, subject_id, subject_rel
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at
              FROM auth.zanzibar_tuples
              WHERE ($1::text IS NULL OR object_type  = $1)
                AND ($2::text IS NULL OR object_id    = $2)
                AND ($3::text IS NULL OR relation     = $3)
                AND ($4::text IS NULL OR subject_type = $4)
                AND ($5::text IS NULL OR subject_id   = $5)
+               AND deleted_revision IS NULL
              ORDER BY object_type, object_id, relation, subject_type, subject_id
              LIMIT $6 OFFSET $7",
         )
@@ -194,26 +211,18 @@ impl ZanzibarStore for PostgresZanzibarStore {
         .fetch_all(&self.pool)
         .await
         .context("auth.zanzibar_tuples list")?;
-        Ok(rows
-            .into_iter()
-            .map(|(ot, oid, rel, st, sid, srel)| Tuple {
-                object_type: ot,
-                object_id: oid,
-                relation: rel,
-                subject_type: st,
-                subject_id: sid,
-                subject_rel: srel,
-            })
-            .collect())
+        rows.into_iter().map(tuple_from_row).collect()
     }
 
-    async fn check(
+    async fn check_with_context(
         &self,
         resource: &ObjectRef,
         permission: &str,
         subject: &SubjectRef,
-        _consistency: Consistency,
+        consistency: Consistency,
+        context: &CaveatContext,
     ) -> Result<CheckResult> {
+        let at = snapshot_revision(&consistency, || self.head(), || self.horizon()).await?;
         // No namespace defined → deny (the safe default). The full
         // permission algebra (union / intersect / exclude / arrow) is
         // composed by the backend-agnostic evaluator over the two
@@ -223,7 +232,12 @@ impl ZanzibarStore for PostgresZanzibarStore {
         let Some(schema) = self.get_namespace(&resource.object_type).await? else {
             return Ok(CheckResult::Denied);
         };
-        evaluate(self, &schema, resource, permission, subject).await
+        let snapshot = Snapshot {
+            store: self,
+            at,
+            now: unix_now(),
+        };
+        evaluate(&snapshot, &schema, resource, permission, subject, context).await
     }
 
     async fn expand(
@@ -271,10 +285,13 @@ impl ZanzibarStore for PostgresZanzibarStore {
         let rows = sqlx::query(
             "SELECT DISTINCT object_type, object_id
              FROM auth.zanzibar_tuples
-             WHERE object_type = $1 AND relation = ANY($2)",
+             WHERE object_type = $1 AND relation = ANY($2)
+               AND deleted_revision IS NULL
+               AND (expires_at IS NULL OR expires_at > $3)",
         )
         .bind(resource_type)
         .bind(&relation_list)
+        .bind(unix_now())
         .fetch_all(&self.pool)
         .await
         .context("auth.zanzibar_tuples candidate resources")?;
@@ -320,6 +337,8 @@ impl ZanzibarStore for PostgresZanzibarStore {
                        ARRAY[t.subject_type || ':' || t.subject_id]
                 FROM auth.zanzibar_tuples t
                 WHERE t.object_type = $1 AND t.object_id = $2 AND t.relation = ANY($3)
+                  AND t.deleted_revision IS NULL AND t.caveat_name = ''
+                  AND (t.expires_at IS NULL OR t.expires_at > $6)
                 UNION ALL
                 SELECT t.subject_type, t.subject_id, t.subject_rel, w.depth + 1,
                        w.path || (t.subject_type || ':' || t.subject_id)
@@ -330,6 +349,8 @@ impl ZanzibarStore for PostgresZanzibarStore {
                  AND w.subject_rel <> ''
                  AND t.relation = w.subject_rel
                 WHERE w.depth < $4
+                  AND t.deleted_revision IS NULL AND t.caveat_name = ''
+                  AND (t.expires_at IS NULL OR t.expires_at > $6)
                   AND NOT (t.subject_type || ':' || t.subject_id) = ANY(w.path)
             )
             SELECT DISTINCT subject_type, subject_id
@@ -342,6 +363,7 @@ impl ZanzibarStore for PostgresZanzibarStore {
         .bind(&relation_list)
         .bind(MAX_DEPTH as i32)
         .bind(subject_type)
+        .bind(unix_now())
         .fetch_all(&self.pool)
         .await
         .context("auth.zanzibar lookup_subjects CTE")?;
@@ -355,40 +377,395 @@ impl ZanzibarStore for PostgresZanzibarStore {
             })
             .collect())
     }
+
+    async fn watch(&self, since: Option<&str>, filter: &WatchFilter) -> Result<WatchBatch> {
+        let limit = filter.effective_limit();
+        let head = self.head().await?;
+        let Some(zookie) = since else {
+            return Ok(WatchBatch::new(Vec::new(), head, limit));
+        };
+        let since = decode_zookie(zookie).with_context(|| format!("invalid zookie {zookie:?}"))?;
+        ensure_retained(zookie, since, self.horizon().await?)?;
+        let rows: Vec<ChangelogRow> = sqlx::query_as(
+            "SELECT revision, operation, object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at, changed_at
+             FROM auth.zanzibar_changelog
+             WHERE revision > $1 AND revision <= $2
+               AND ($3::text IS NULL OR object_type  = $3)
+               AND ($4::text IS NULL OR object_id    = $4)
+               AND ($5::text IS NULL OR relation     = $5)
+               AND ($6::text IS NULL OR subject_type = $6)
+               AND ($7::text IS NULL OR subject_id   = $7)
+             ORDER BY revision
+             LIMIT $8",
+        )
+        .bind(since)
+        .bind(head)
+        .bind(&filter.object_type)
+        .bind(&filter.object_id)
+        .bind(&filter.relation)
+        .bind(&filter.subject_type)
+        .bind(&filter.subject_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.zanzibar_changelog watch")?;
+        let changes = rows
+            .into_iter()
+            .map(change_from_row)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(WatchBatch::new(changes, head.max(since), limit))
+    }
+
+    async fn expire_tuples(&self, now: i64, limit: i64) -> Result<Vec<TupleChange>> {
+        let mut tx = self.pool.begin().await.context("begin expiry txn")?;
+        lock_changelog(&mut tx).await?;
+        let rows: Vec<TupleRow> = sqlx::query_as(
+            "SELECT object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at
+             FROM auth.zanzibar_tuples
+             WHERE expires_at <= $1 AND deleted_revision IS NULL
+             ORDER BY expires_at
+             LIMIT $2",
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await
+        .context("auth.zanzibar_tuples expired")?;
+        let mut revisions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let t = tuple_from_row(row)?;
+            let Some(revision) = record_change(&mut tx, TupleOperation::Delete, &t).await? else {
+                continue;
+            };
+            stamp_deleted(&mut tx, &t, revision).await?;
+            revisions.push(revision);
+        }
+        let rows: Vec<ChangelogRow> = sqlx::query_as(
+            "SELECT revision, operation, object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at, changed_at
+             FROM auth.zanzibar_changelog
+             WHERE revision = ANY($1)
+             ORDER BY revision",
+        )
+        .bind(&revisions)
+        .fetch_all(&mut *tx)
+        .await
+        .context("auth.zanzibar_changelog expired")?;
+        tx.commit().await.context("commit expiry txn")?;
+        rows.into_iter().map(change_from_row).collect()
+    }
+
+    async fn compact_history(&self, cutoff: f64) -> Result<Compaction> {
+        let mut tx = self.pool.begin().await.context("begin compaction txn")?;
+        // Every zookie handed out since `cutoff` is at least the
+        // revision just before the first change since then — or the
+        // head, when nothing changed.
+        let (horizon,): (i64,) = sqlx::query_as(
+            "INSERT INTO auth.zanzibar_gc (id, horizon)
+             SELECT 1, COALESCE(
+                 (SELECT MIN(revision) - 1 FROM auth.zanzibar_changelog WHERE changed_at >= $1),
+                 (SELECT COALESCE(MAX(revision), 0) FROM auth.zanzibar_changelog)
+             )
+             ON CONFLICT (id) DO UPDATE SET horizon = GREATEST(auth.zanzibar_gc.horizon, EXCLUDED.horizon)
+             RETURNING horizon",
+        )
+        .bind(cutoff)
+        .fetch_one(&mut *tx)
+        .await
+        .context("auth.zanzibar_gc advance")?;
+        let tuples = sqlx::query("DELETE FROM auth.zanzibar_tuples WHERE deleted_revision < $1")
+            .bind(horizon)
+            .execute(&mut *tx)
+            .await
+            .context("auth.zanzibar_tuples compact")?
+            .rows_affected();
+        let changes = sqlx::query("DELETE FROM auth.zanzibar_changelog WHERE revision < $1")
+            .bind(horizon)
+            .execute(&mut *tx)
+            .await
+            .context("auth.zanzibar_changelog compact")?
+            .rows_affected();
+        tx.commit().await.context("commit compaction txn")?;
+        Ok(Compaction {
+            horizon,
+            tuples,
+            changes,
+        })
+    }
+}
+
+/// Advisory-lock key serialising changelog writers (ASCII "zanzwtch").
+/// Held until commit, so revisions become visible in the order they
+/// were assigned — a watcher never skips a revision that commits late.
+const CHANGELOG_LOCK_KEY: i64 = 0x7a61_6e7a_7774_6368;
+
+async fn lock_changelog(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(CHANGELOG_LOCK_KEY)
+        .execute(&mut **tx)
+        .await
+        .context("auth.zanzibar_changelog lock")?;
+    Ok(())
+}
+
+async fn head_revision<'e, E: sqlx::PgExecutor<'e>>(executor: E) -> Result<i64> {
+    let (head,): (i64,) =
+        sqlx::query_as("SELECT COALESCE(MAX(revision), 0) FROM auth.zanzibar_changelog")
+            .fetch_one(executor)
+            .await
+            .context("auth.zanzibar_changelog head")?;
+    Ok(head)
+}
+
+/// Append one changelog entry inside the caller's transaction, so the
+/// tuple change and its record commit together. Records only real
+/// changes — a `TOUCH` of a tuple already live with the same caveat and
+/// expiry or
+/// a `DELETE` of a missing one appends nothing and returns `None`;
+/// otherwise returns the revision the caller stamps onto the tuple
+/// rows. A `DELETE` records the caveat and expiry the removed tuple
+/// carried.
+async fn record_change(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    op: TupleOperation,
+    t: &Tuple,
+) -> Result<Option<i64>> {
+    let sql = match op {
+        TupleOperation::Touch => {
+            "INSERT INTO auth.zanzibar_changelog
+                (operation, object_type, object_id, relation,
+                 subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                 expires_at, changed_at)
+             SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, EXTRACT(EPOCH FROM NOW())
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM auth.zanzibar_tuples
+                 WHERE object_type = $2 AND object_id = $3 AND relation = $4
+                   AND subject_type = $5 AND subject_id = $6 AND subject_rel = $7
+                   AND caveat_name = $8 AND caveat_context = $9
+                   AND expires_at IS NOT DISTINCT FROM $10
+                   AND deleted_revision IS NULL
+             )
+             RETURNING revision"
+        }
+        TupleOperation::Delete => {
+            "INSERT INTO auth.zanzibar_changelog
+                (operation, object_type, object_id, relation,
+                 subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                 expires_at, changed_at)
+             SELECT $1, object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at, EXTRACT(EPOCH FROM NOW())
+             FROM auth.zanzibar_tuples
+             WHERE object_type = $2 AND object_id = $3 AND relation = $4
+               AND subject_type = $5 AND subject_id = $6 AND subject_rel = $7
+               AND deleted_revision IS NULL
+             RETURNING revision"
+        }
+    };
+    let mut query = sqlx::query_as(sql)
+        .bind(op.as_str())
+        .bind(&t.object_type)
+        .bind(&t.object_id)
+        .bind(&t.relation)
+        .bind(&t.subject_type)
+        .bind(&t.subject_id)
+        .bind(&t.subject_rel);
+    if op == TupleOperation::Touch {
+        let (caveat_name, caveat_context) = caveat_columns(t);
+        query = query
+            .bind(caveat_name)
+            .bind(caveat_context)
+            .bind(t.expires_at);
+    }
+    let row: Option<(i64,)> = query
+        .fetch_optional(&mut **tx)
+        .await
+        .context("auth.zanzibar_changelog insert")?;
+    Ok(row.map(|(revision,)| revision))
+}
+
+/// End the live version of `t` (whatever its caveat) at `revision`.
+/// Rows are stamped rather than removed, so an `Exact` read at an
+/// earlier revision still sees the tuple. subject_rel is NOT NULL
+/// ('' for direct), so plain equality suffices — no IS NOT DISTINCT
+/// FROM dance.
+async fn stamp_deleted(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    t: &Tuple,
+    revision: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE auth.zanzibar_tuples SET deleted_revision = $7
+         WHERE object_type = $1 AND object_id = $2 AND relation = $3
+           AND subject_type = $4 AND subject_id = $5
+           AND subject_rel = $6 AND deleted_revision IS NULL",
+    )
+    .bind(&t.object_type)
+    .bind(&t.object_id)
+    .bind(&t.relation)
+    .bind(&t.subject_type)
+    .bind(&t.subject_id)
+    .bind(&t.subject_rel)
+    .bind(revision)
+    .execute(&mut **tx)
+    .await
+    .context("auth.zanzibar_tuples delete")?;
+    Ok(())
 }
 
-impl LeafCheck for PostgresZanzibarStore {
+type TupleRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    serde_json::Value,
+    Option<i64>,
+);
+
+fn tuple_from_row(
+    (
+        object_type,
+        object_id,
+        relation,
+        subject_type,
+        subject_id,
+        subject_rel,
+        caveat_name,
+        caveat_context,
+        expires_at,
+    ): TupleRow,
+) -> Result<Tuple> {
+    Ok(Tuple {
+        object_type,
+        object_id,
+        relation,
+        subject_type,
+        subject_id,
+        subject_rel,
+        caveat: caveat_from_columns(caveat_name, caveat_context)?,
+        expires_at,
+    })
+}
+
+type ChangelogRow = (
+    i64,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    serde_json::Value,
+    Option<i64>,
+    f64,
+);
+
+fn change_from_row(
+    (
+        revision,
+        op,
+        object_type,
+        object_id,
+        relation,
+        subject_type,
+        subject_id,
+        subject_rel,
+        caveat_name,
+        caveat_context,
+        expires_at,
+        changed_at,
+    ): ChangelogRow,
+) -> Result<TupleChange> {
+    let operation = TupleOperation::parse(&op)
+        .with_context(|| format!("auth.zanzibar_changelog: unknown operation {op:?}"))?;
+    Ok(TupleChange {
+        zookie: encode_zookie(revision),
+        operation,
+        tuple: Tuple {
+            object_type,
+            object_id,
+            relation,
+            subject_type,
+            subject_id,
+            subject_rel,
+            caveat: caveat_from_columns(caveat_name, caveat_context)?,
+            expires_at,
+        },
+        changed_at,
+    })
+}
+
+/// The store pinned to one revision for `check`: a row is visible iff
+/// it was created at or before `at` and not deleted by then.
+/// [`super::store::LATEST_REVISION`] selects exactly the live rows.
+/// Expiry is judged against the wall clock (`now`), whatever the
+/// revision — an expired grant never comes back via an old zookie.
+struct Snapshot<'a> {
+    store: &'a PostgresZanzibarStore,
+    at: i64,
+    now: i64,
+}
+
+impl LeafCheck for Snapshot<'_> {
     fn check_relation_set<'a>(
         &'a self,
         object: &'a ObjectRef,
         relations: &'a [String],
         subject: &'a SubjectRef,
-    ) -> Pin<Box<dyn Future<Output = Result<Verdict>> + Send + 'a>> {
+    ) -> Pin<Box<dyn Future<Output = Result<LeafOutcome>> + Send + 'a>> {
         Box::pin(async move {
             if relations.is_empty() {
-                return Ok(Verdict::Denied);
+                return Ok(Verdict::Denied.into());
             }
             // subject_rel is NOT NULL — '' for direct subjects (the
             // terminal kind the leaf answers) and a relation name for
             // usersets the CTE walks through. Cycle guard via the in-CTE
             // `path` array — if the next subject is already on the path,
-            // the join produces zero rows.
-            let row: Option<(i32,)> = sqlx::query_as(
+            // the join produces zero rows. `caveats` collects the caveat
+            // of every tuple along the path; the verdict only counts
+            // uncaveated paths, and the caveated ones come back
+            // separately for the evaluator to decide.
+            let row: Option<(i32, serde_json::Value)> = sqlx::query_as(
                 r#"
-                WITH RECURSIVE walk(subject_type, subject_id, subject_rel, depth, path) AS (
+                WITH RECURSIVE walk(subject_type, subject_id, subject_rel, depth, path, caveats) AS (
                     SELECT t.subject_type,
                            t.subject_id,
                            t.subject_rel,
                            1 AS depth,
-                           ARRAY[t.subject_type || ':' || t.subject_id] AS path
+                           ARRAY[t.subject_type || ':' || t.subject_id] AS path,
+                           CASE WHEN t.caveat_name = '' THEN '[]'::jsonb
+                                ELSE jsonb_build_array(jsonb_build_object(
+                                    'object_type', t.object_type,
+                                    'name', t.caveat_name,
+                                    'context', t.caveat_context))
+                           END AS caveats
                     FROM auth.zanzibar_tuples t
                     WHERE t.object_type = $1 AND t.object_id = $2 AND t.relation = ANY($3)
+                      AND t.created_revision <= $7
+                      AND (t.deleted_revision IS NULL OR t.deleted_revision > $7)
+                      AND (t.expires_at IS NULL OR t.expires_at > $8)
                     UNION ALL
                     SELECT t.subject_type,
                            t.subject_id,
                            t.subject_rel,
                            w.depth + 1,
-                           w.path || (t.subject_type || ':' || t.subject_id)
+                           w.path || (t.subject_type || ':' || t.subject_id),
+                           CASE WHEN t.caveat_name = '' THEN w.caveats
+                                ELSE w.caveats || jsonb_build_array(jsonb_build_object(
+                                    'object_type', t.object_type,
+                                    'name', t.caveat_name,
+                                    'context', t.caveat_context))
+                           END
                     FROM auth.zanzibar_tuples t
                     JOIN walk w
                       ON t.object_type = w.subject_type
@@ -396,16 +773,25 @@ impl LeafCheck for PostgresZanzibarStore {
                      AND w.subject_rel <> ''
                      AND t.relation = w.subject_rel
                     WHERE w.depth < $4
+                      AND t.created_revision <= $7
+                      AND (t.deleted_revision IS NULL OR t.deleted_revision > $7)
+                      AND (t.expires_at IS NULL OR t.expires_at > $8)
                       AND NOT (t.subject_type || ':' || t.subject_id) = ANY(w.path)
                 )
                 SELECT CASE
                     WHEN EXISTS (
                         SELECT 1 FROM walk
                         WHERE subject_type = $5 AND subject_id = $6 AND subject_rel = ''
+                          AND caveats = '[]'::jsonb
                     ) THEN 1
                     WHEN EXISTS (SELECT 1 FROM walk WHERE depth >= $4) THEN 2
                     ELSE 0
-                END AS verdict
+                END AS verdict,
+                (
+                    SELECT COALESCE(jsonb_agg(DISTINCT caveats), '[]'::jsonb) FROM walk
+                    WHERE subject_type = $5 AND subject_id = $6 AND subject_rel = ''
+                      AND caveats <> '[]'::jsonb
+                ) AS caveated_paths
                 "#,
             )
             .bind(&object.object_type)
@@ -414,14 +800,25 @@ impl LeafCheck for PostgresZanzibarStore {
             .bind(MAX_DEPTH as i32)
             .bind(&subject.subject_type)
             .bind(&subject.subject_id)
-            .fetch_optional(&self.pool)
+            .bind(self.at)
+            .bind(self.now)
+            .fetch_optional(&self.store.pool)
             .await
             .context("auth.zanzibar check CTE")?;
 
-            Ok(match row.map(|(v,)| v).unwrap_or(0) {
+            let Some((verdict, paths)) = row else {
+                return Ok(Verdict::Denied.into());
+            };
+            let verdict = match verdict {
                 1 => Verdict::Allowed,
                 2 => Verdict::DepthExceeded,
                 _ => Verdict::Denied,
+            };
+            let caveated_paths: Vec<Vec<PathCaveat>> =
+                serde_json::from_value(paths).context("auth.zanzibar check CTE caveats")?;
+            Ok(LeafOutcome {
+                verdict,
+                caveated_paths,
             })
         })
     }
@@ -430,32 +827,39 @@ impl LeafCheck for PostgresZanzibarStore {
         &'a self,
         object: &'a ObjectRef,
         relation: &'a str,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<ObjectRef>>> + Send + 'a>> {
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ArrowTarget>>> + Send + 'a>> {
         Box::pin(async move {
             // Only object-reference subjects (`subject_rel = ''`) are
             // the left edge of an arrow hop — a userset subject on the
             // tupleset relation has no object to re-evaluate against.
             let rows = sqlx::query(
-                "SELECT subject_type, subject_id
+                "SELECT subject_type, subject_id, caveat_name, caveat_context
                  FROM auth.zanzibar_tuples
                  WHERE object_type = $1 AND object_id = $2 AND relation = $3
-                   AND subject_rel = ''",
+                   AND subject_rel = ''
+                   AND created_revision <= $4
+                   AND (deleted_revision IS NULL OR deleted_revision > $4)
+                   AND (expires_at IS NULL OR expires_at > $5)",
             )
             .bind(&object.object_type)
             .bind(&object.object_id)
             .bind(relation)
-            .fetch_all(&self.pool)
+            .bind(self.at)
+            .bind(self.now)
+            .fetch_all(&self.store.pool)
             .await
             .context("auth.zanzibar arrow targets")?;
-            Ok(rows
-                .into_iter()
+            rows.into_iter()
                 .map(|row| {
-                    ObjectRef::new(
+                    let target = ObjectRef::new(
                         row.get::<String, _>("subject_type"),
                         row.get::<String, _>("subject_id"),
-                    )
+                    );
+                    let caveat =
+                        caveat_from_columns(row.get("caveat_name"), row.get("caveat_context"))?;
+                    Ok((target, caveat))
                 })
-                .collect())
+                .collect()
         })
     }
 
@@ -463,7 +867,7 @@ impl LeafCheck for PostgresZanzibarStore {
         &'a self,
         object_type: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<Option<NamespaceSchema>>> + Send + 'a>> {
-        Box::pin(async move { self.get_namespace(object_type).await })
+        Box::pin(async move { self.store.get_namespace(object_type).await })
     }
 }
 
@@ -491,11 +895,14 @@ fn expand_pg<'a>(
         let rows = sqlx::query(
             "SELECT subject_type, subject_id, subject_rel
              FROM auth.zanzibar_tuples
-             WHERE object_type = $1 AND object_id = $2 AND relation = $3",
+             WHERE object_type = $1 AND object_id = $2 AND relation = $3
+               AND deleted_revision IS NULL
+               AND (expires_at IS NULL OR expires_at > $4)",
         )
         .bind(&resource.object_type)
         .bind(&resource.object_id)
         .bind(relation)
+        .bind(unix_now())
         .fetch_all(&store.pool)
         .await
         .context("auth.zanzibar_tuples expand fetch")?;
@@ -26,6 +26,13 @@
 //!   - `name` — direct subject (`user`)
 //!   - `name#relation` — userset (`group#member`)
 //!   - `name:*` — wildcard subject (`user:*`)
+//!
+//!   and any of them may be followed by `with CAVEAT` for conditional
+//!   tuples (`user with in_office`).
+//! - `caveat NAME(PARAM TYPE, …) { EXPR }` blocks at top level,
+//!   alongside the definitions. The expression is a CEL subset — see
+//!   [`super::caveat`] for the grammar and parameter types. It is
+//!   compiled here, so a typo fails schema load rather than a check.
 //! - `permission NAME = EXPR` lines, with EXPR built from:
 //!   - `name` — direct relation reference
 //!   - `+` — union (left-associative)
@@ -37,15 +44,19 @@
 //!   `#` is reserved for userset references like `group#member`, so
 //!   it can't double as a comment marker.)
 //!
-//! Returns one [`NamespaceSchema`] per definition block. The caller
+//! Returns one [`NamespaceSchema`] per definition block, each carrying
+//! the caveats its relations reference (an unknown one is a parse
+//! error). The caller
 //! (`define_namespace` in the store impls) persists each independently
 //! so a partial schema update on one namespace doesn't drop another.
 
 use std::collections::BTreeMap;
 
+use super::caveat::{CaveatDef, CaveatParamType};
 use super::types::{NamespaceSchema, PermissionExpr, RelationDef, TypeRef};
 
-/// Convenience entry point — parse one or more `definition` blocks.
+/// Convenience entry point — parse one or more `definition` (and
+/// `caveat`) blocks.
 /// Returns each namespace as an independent [`NamespaceSchema`].
 pub fn parse_schema(input: &str) -> Result<Vec<NamespaceSchema>, ParseError> {
     Parser::new(input).parse_top()
@@ -85,18 +96,23 @@ enum Token {
 }
 
 /// Token + its source location, kept paired so error messages can
-/// pinpoint the offending construct.
+/// pinpoint the offending construct. `offset` is the byte offset, used
+/// to slice caveat parameter lists and bodies out of the source.
 #[derive(Clone, Debug)]
 struct LocTok {
     tok: Token,
     line: usize,
     col: usize,
+    offset: usize,
 }
 
 struct Parser<'a> {
     tokens: Vec<LocTok>,
     pos: usize,
-    _src: &'a str,
+    src: &'a str,
+    /// `with CAVEAT` references seen in the current definition block,
+    /// with their location, resolved once every caveat is known.
+    caveat_refs: Vec<(String, usize, usize)>,
 }
 
 impl<'a> Parser<'a> {
@@ -105,24 +121,111 @@ impl<'a> Parser<'a> {
         Self {
             tokens,
             pos: 0,
-            _src: src,
+            src,
+            caveat_refs: Vec::new(),
         }
     }
 
     fn parse_top(&mut self) -> Result<Vec<NamespaceSchema>, ParseError> {
         let mut out = Vec::new();
+        let mut caveats: BTreeMap<String, CaveatDef> = BTreeMap::new();
         while !self.at_end() {
+            if self.peek() == Some(&Token::Ident("caveat".into())) {
+                self.pos += 1;
+                let def = self.parse_caveat()?;
+                if caveats.contains_key(&def.name) {
+                    return Err(self.err_back(format!("duplicate caveat `{}`", def.name)));
+                }
+                caveats.insert(def.name.clone(), def);
+                continue;
+            }
             let ns = self.parse_definition()?;
-            out.push(ns);
+            out.push((ns, std::mem::take(&mut self.caveat_refs)));
         }
-        Ok(out)
+        // Caveats may be declared after the definitions using them, so
+        // references resolve only now.
+        out.into_iter()
+            .map(|(mut ns, refs)| {
+                for (name, line, col) in refs {
+                    let Some(def) = caveats.get(&name) else {
+                        return Err(ParseError {
+                            line,
+                            col,
+                            message: format!("unknown caveat `{name}`"),
+                        });
+                    };
+                    ns.caveats.insert(name, def.clone());
+                }
+                Ok(ns)
+            })
+            .collect()
+    }
+
+    /// `caveat NAME ( PARAM TYPE, … ) { EXPR }` — the `caveat` keyword
+    /// is already consumed. Parameter list and body are sliced verbatim
+    /// from the source (the expression has its own grammar) and
+    /// compiled by [`CaveatDef::new`].
+    fn parse_caveat(&mut self) -> Result<CaveatDef, ParseError> {
+        let kw = self.pos - 1;
+        let name = self.expect_ident()?;
+        let params_src = self.raw_group(Token::LParen, Token::RParen)?;
+        let body_src = self.raw_group(Token::LBrace, Token::RBrace)?;
+        let mut params = BTreeMap::new();
+        for param in params_src.split(',').map(str::trim) {
+            if param.is_empty() {
+                continue;
+            }
+            let Some((pname, ty)) = param.split_once(char::is_whitespace) else {
+                return Err(self.err_at(
+                    kw,
+                    format!("caveat `{name}`: parameter `{param}` has no type"),
+                ));
+            };
+            let Some(ty) = CaveatParamType::parse(ty) else {
+                return Err(self.err_at(
+                    kw,
+                    format!("caveat `{name}`: unknown parameter type `{}`", ty.trim()),
+                ));
+            };
+            if params.insert(pname.to_string(), ty).is_some() {
+                return Err(self.err_at(
+                    kw,
+                    format!("caveat `{name}`: duplicate parameter `{pname}`"),
+                ));
+            }
+        }
+        CaveatDef::new(name.clone(), params, body_src.trim())
+            .map_err(|e| self.err_at(kw, format!("caveat `{name}`: {e}")))
+    }
+
+    /// Consume a balanced `open … close` group and return the source
+    /// text between the delimiters.
+    fn raw_group(&mut self, open: Token, close: Token) -> Result<&'a str, ParseError> {
+        let start = match self.tokens.get(self.pos) {
+            Some(lt) if lt.tok == open => lt.offset + 1,
+            _ => return Err(self.err_here(format!("expected token {open:?}"))),
+        };
+        self.pos += 1;
+        let mut depth = 1usize;
+        while let Some(lt) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            if lt.tok == open {
+                depth += 1;
+            } else if lt.tok == close {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(&self.src[start..lt.offset]);
+                }
+            }
+        }
+        Err(self.err_here(format!("expected token {close:?}, got end of input")))
     }
 
     /// `definition NAME { … relation/permission lines … }`
     fn parse_definition(&mut self) -> Result<NamespaceSchema, ParseError> {
         let kw = self.expect_ident()?;
         if kw != "definition" {
-            return Err(self.err_back(format!("expected `definition`, got `{kw}`")));
+            return Err(self.err_back(format!("expected `definition` or `caveat`, got `{kw}`")));
         }
         let name = self.expect_ident()?;
         self.expect(Token::LBrace)?;
@@ -149,7 +252,11 @@ impl<'a> Parser<'a> {
             }
         }
         self.expect(Token::RBrace)?;
-        Ok(NamespaceSchema { name, definitions })
+        Ok(NamespaceSchema {
+            name,
+            definitions,
+            caveats: BTreeMap::new(),
+        })
     }
 
     /// `relation NAME : TYPE [ | TYPE … ]`
@@ -164,20 +271,32 @@ impl<'a> Parser<'a> {
     }
 
     /// One TYPE on the right-hand side of a `relation` line.
-    /// `user`, `user:*`, or `group#member`.
+    /// `user`, `user:*`, or `group#member`, optionally followed by
+    /// `with CAVEAT`.
     fn parse_type_ref(&mut self) -> Result<TypeRef, ParseError> {
         let ty = self.expect_ident()?;
-        if self.consume(&Token::Hash) {
+        let type_ref = if self.consume(&Token::Hash) {
             let rel = self.expect_ident()?;
-            return Ok(TypeRef::userset(ty, rel));
-        }
-        if self.consume(&Token::Colon) {
+            TypeRef::userset(ty, rel)
+        } else if self.consume(&Token::Colon) {
             // Only `:*` is allowed here — anything else is a relation
             // tuple, not a type ref.
             self.expect(Token::Star)?;
-            return Ok(TypeRef::wildcard(ty));
+            TypeRef::wildcard(ty)
+        } else {
+            TypeRef::direct(ty)
+        };
+        if self.peek() != Some(&Token::Ident("with".into())) {
+            return Ok(type_ref);
         }
-        Ok(TypeRef::direct(ty))
+        self.pos += 1;
+        let caveat = self.expect_ident()?;
+        let (line, col) = {
+            let lt = &self.tokens[self.pos - 1];
+            (lt.line, lt.col)
+        };
+        self.caveat_refs.push((caveat.clone(), line, col));
+        Ok(type_ref.with_caveat(caveat))
     }
 
     /// `permission NAME = EXPR`
@@ -291,12 +410,8 @@ impl<'a> Parser<'a> {
         ParseError { line, col, message }
     }
 
-    /// Variant of [`Self::err_here`] that points at the *previous* token —
-    /// used by error sites that have already advanced past the offender
-    /// (`expect_ident` returning `Ok` then realising the keyword was
-    /// wrong).
-    fn err_back(&self, message: String) -> ParseError {
-        let idx = self.pos.saturating_sub(1);
+    /// Error pointing at the token at index `idx`.
+    fn err_at(&self, idx: usize, message: String) -> ParseError {
         let (line, col) = self
             .tokens
             .get(idx)
@@ -304,6 +419,14 @@ impl<'a> Parser<'a> {
             .unwrap_or((0, 0));
         ParseError { line, col, message }
     }
+
+    /// Variant of [`Self::err_here`] that points at the *previous* token —
+    /// used by error sites that have already advanced past the offender
+    /// (`expect_ident` returning `Ok` then realising the keyword was
+    /// wrong).
+    fn err_back(&self, message: String) -> ParseError {
+        self.err_at(self.pos.saturating_sub(1), message)
+    }
 }
 
 /// Lexer — emits one [`LocTok`] per recognised piece of syntax. Tracks
@@ -339,12 +462,35 @@ fn lex(src: &str) -> Vec<LocTok> {
             }
             continue;
         }
+        // String literals only occur in caveat expressions, which the
+        // parser slices from the source — skip them whole so a quoted
+        // `}` or `//` can't end the block or start a comment.
+        if c == b'"' || c == b'\'' {
+            i += 1;
+            col += 1;
+            while i < bytes.len() && bytes[i] != c {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                    col += 1;
+                }
+                if bytes.get(i) == Some(&b'\n') {
+                    line += 1;
+                    col = 0;
+                }
+                i += 1;
+                col += 1;
+            }
+            i += 1;
+            col += 1;
+            continue;
+        }
         // Multi-char tokens first.
         if c == b'-' && i + 1 < bytes.len() && bytes[i + 1] == b'>' {
             out.push(LocTok {
                 tok: Token::Arrow,
                 line,
                 col,
+                offset: i,
             });
             i += 2;
             col += 2;
@@ -367,7 +513,12 @@ fn lex(src: &str) -> Vec<LocTok> {
             _ => None,
         };
         if let Some(t) = single {
-            out.push(LocTok { tok: t, line, col });
+            out.push(LocTok {
+                tok: t,
+                line,
+                col,
+                offset: i,
+            });
             i += 1;
             col += 1;
             continue;
@@ -387,6 +538,7 @@ fn lex(src: &str) -> Vec<LocTok> {
                 tok: Token::Ident(ident),
                 line,
                 col: start_col,
+                offset: start,
             });
             continue;
         }
@@ -556,4 +708,79 @@ mod tests {
         let nss = parse_schema(src).expect("parse with comments");
         assert_eq!(nss.len(), 2);
     }
+
+    #[test]
+    fn parses_caveats() {
+        let src = r#"
+            definition user {}
+
+            definition document {
+                relation viewer: user | user with in_office | group#member with in_office
+                relation parent: folder with business_hours
+            }
+
+            // Declared after use; quoted braces don't end the block.
+            caveat in_office(client_ip ipaddress, office_cidr string) {
+                client_ip.in_cidr(office_cidr) && office_cidr != "}"
+            }
+
+            caveat business_hours(now timestamp, days list<int>) {
+                now.getDayOfWeek() in days
+                    && now.getHours() >= 9 && now.getHours() < 17
+            }
+        "#;
+        let nss = parse_schema(src).expect("parse");
+        assert_eq!(nss.len(), 2);
+        assert!(nss[0].caveats.is_empty());
+
+        let doc = &nss[1];
+        assert_eq!(
+            doc.caveats.keys().collect::<Vec<_>>(),
+            ["business_hours", "in_office"]
+        );
+        let in_office = &doc.caveats["in_office"];
+        assert_eq!(in_office.params["client_ip"], CaveatParamType::IpAddress);
+        assert!(in_office.expression.starts_with("client_ip.in_cidr"));
+        assert_eq!(
+            doc.caveats["business_hours"].params["days"],
+            CaveatParamType::List(Box::new(CaveatParamType::Int))
+        );
+        match &doc.definitions["viewer"].kind {
+            RelationKind::Direct(types) => {
+                assert_eq!(types[0].caveat, None);
+                assert_eq!(types[1].caveat.as_deref(), Some("in_office"));
+                assert_eq!(types[2].relation.as_deref(), Some("member"));
+                assert_eq!(types[2].caveat.as_deref(), Some("in_office"));
+            }
+            _ => panic!("expected direct"),
+        }
+
+        // Round-trips through the persisted JSON form.
+        let json = serde_json::to_value(doc).unwrap();
+        assert_eq!(
+            json["caveats"]["business_hours"]["params"]["days"],
+            "list<int>"
+        );
+        let back: NamespaceSchema = serde_json::from_value(json).unwrap();
+        assert_eq!(&back, doc);
+    }
+
+    #[test]
+    fn rejects_bad_caveats() {
+        let err = parse_schema("definition d {\n relation v: user with nope\n}").unwrap_err();
+        assert_eq!((err.line, err.col), (2, 24));
+        assert!(err.message.contains("unknown caveat `nope`"), "{err}");
+
+        let err = parse_schema("caveat c(a int) { a > b }").unwrap_err();
+        assert!(err.message.contains("undeclared parameter `b`"), "{err}");
+
+        let err = parse_schema("caveat c(a integer) { a > 1 }").unwrap_err();
+        assert!(err.message.contains("unknown parameter type"), "{err}");
+
+        let err = parse_schema("caveat c(a int) { a > }").unwrap_err();
+        assert!(err.message.contains("syntax error"), "{err}");
+
+        let err = parse_schema("caveat c(a int) { a > 1 } caveat c(a int) { a > 2 }").unwrap_err();
+        assert!(err.message.contains("duplicate caveat"), "{err}");
+    }
 }
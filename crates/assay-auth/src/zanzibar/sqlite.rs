@@ -15,18 +15,26 @@
 //!   `json_array(...)` + `json_array_length` for the depth limit, and
 //!   `instr(json_path, key)` for the membership check — matches the
 //!   PG `path || ROW(...) ANY` semantic in spirit.
+//! - The check CTE's per-path caveat list is built with `json_array` /
+//!   `json_insert(…, '$[#]', …)` where PG uses `jsonb` concatenation.
+//! - A tuple's `expires_at` is compared with `IS` in the re-write guard
+//!   (NULL-safe), and against a Rust-bound `now` in the read paths.
 
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use sqlx::{Row, SqlitePool};
 
-use super::eval::{LeafCheck, Verdict, evaluate};
+use super::eval::{ArrowTarget, LeafCheck, LeafOutcome, PathCaveat, Verdict, evaluate};
 use super::resolve::resolve;
-use super::store::ZanzibarStore;
+use super::store::{
+    ZanzibarStore, caveat_columns, caveat_from_columns, ensure_retained, snapshot_revision,
+    unix_now,
+};
 use super::types::{
-    CheckResult, Consistency, MAX_DEPTH, NamespaceSchema, ObjectRef, SubjectRef, TreeOp, Tuple,
-    TupleFilter, UsersetTree,
+    CaveatContext, CheckResult, Compaction, Consistency, MAX_DEPTH, NamespaceSchema, ObjectRef,
+    SubjectRef, TreeOp, Tuple, TupleCaveat, TupleChange, TupleFilter, TupleOperation, UsersetTree,
+    WatchBatch, WatchFilter, decode_zookie, encode_zookie,
 };
 use std::future::Future;
 use std::pin::Pin;
@@ -46,6 +54,22 @@ impl SqliteZanzibarStore {
     pub fn into_dyn(self) -> Arc<dyn ZanzibarStore> {
         Arc::new(self)
     }
+
+    /// Latest committed changelog revision (0 before the first write).
+    async fn head(&self) -> Result<i64> {
+        head_revision(&self.pool).await
+    }
+
+    /// Oldest revision `Exact` reads and watch cursors may name (0
+    /// before the first compaction).
+    async fn horizon(&self) -> Result<i64> {
+        let (horizon,): (i64,) =
+            sqlx::query_as("SELECT COALESCE(MAX(horizon), 0) FROM auth.zanzibar_gc")
+                .fetch_one(&self.pool)
+                .await
+                .context("auth.zanzibar_gc horizon")?;
+        Ok(horizon)
+    }
 }
 
 fn now_secs() -> f64 {
@@ -104,41 +128,33 @@ impl ZanzibarStore for SqliteZanzibarStore {
             .collect()
     }
 
-    async fn write_tuple(&self, t: &Tuple) -> Result<()> {
-        let now = now_secs();
-        sqlx::query(
-            "INSERT INTO auth.zanzibar_tuples
-                (object_type, object_id, relation,
-                 subject_type, subject_id, subject_rel, created_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)
-             ON CONFLICT DO NOTHING",
-        )
-        .bind(&t.object_type)
-        .bind(&t.object_id)
-        .bind(&t.relation)
-        .bind(&t.subject_type)
-        .bind(&t.subject_id)
-        .bind(&t.subject_rel)
-        .bind(now)
-        .execute(&self.pool)
-        .await
-        .context("auth.zanzibar_tuples insert")?;
-        Ok(())
+    async fn write_tuple(&self, t: &Tuple) -> Result<String> {
+        self.write_tuples(std::slice::from_ref(t)).await
     }
 
-    async fn write_tuples(&self, tuples: &[Tuple]) -> Result<()> {
+    async fn write_tuples(&self, tuples: &[Tuple]) -> Result<String> {
         if tuples.is_empty() {
-            return Ok(());
+            return Ok(encode_zookie(self.head().await?));
         }
         let mut tx = self.pool.begin().await.context("begin tuples txn")?;
         for t in tuples {
             let now = now_secs();
+            // Already live with the same caveat and expiry → nothing
+            // recorded, nothing to insert.
+            let Some(revision) = record_change(&mut tx, TupleOperation::Touch, t, now).await?
+            else {
+                continue;
+            };
+            // Live under a different caveat or expiry → that version
+            // ends here.
+            stamp_deleted(&mut tx, t, revision).await?;
+            let (caveat_name, caveat_context) = caveat_columns(t);
             sqlx::query(
                 "INSERT INTO auth.zanzibar_tuples
                     (object_type, object_id, relation,
-                     subject_type, subject_id, subject_rel, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)
-                 ON CONFLICT DO NOTHING",
+                     subject_type, subject_id, subject_rel, created_at, created_revision,
+                     caveat_name, caveat_context, expires_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(&t.object_type)
             .bind(&t.object_id)
@@ -147,34 +163,31 @@ impl ZanzibarStore for SqliteZanzibarStore {
             .bind(&t.subject_id)
             .bind(&t.subject_rel)
             .bind(now)
+            .bind(revision)
+            .bind(caveat_name)
+            .bind(caveat_context.to_string())
+            .bind(t.expires_at)
             .execute(&mut *tx)
             .await
             .context("auth.zanzibar_tuples batch insert")?;
         }
+        // The transaction holds SQLite's write lock, so the head is this
+        // batch's last revision, or — for an all no-op batch — the
+        // revision the tuples were already visible from.
+        let head = head_revision(&mut *tx).await?;
         tx.commit().await.context("commit tuples txn")?;
-        Ok(())
+        Ok(encode_zookie(head))
     }
 
-    async fn delete_tuple(&self, t: &Tuple) -> Result<bool> {
-        // subject_rel is NOT NULL ('' for direct), so plain equality
-        // works on both backends — no `IS` / `IS NOT DISTINCT FROM`
-        // dance needed.
-        let res = sqlx::query(
-            "DELETE FROM auth.zanzibar_tuples
-             WHERE object_type = ? AND object_id = ? AND relation = ?
-               AND subject_type = ? AND subject_id = ?
-               AND subject_rel = ?",
-        )
-        .bind(&t.object_type)
-        .bind(&t.object_id)
-        .bind(&t.relation)
-        .bind(&t.subject_type)
-        .bind(&t.subject_id)
-        .bind(&t.subject_rel)
-        .execute(&self.pool)
-        .await
-        .context("auth.zanzibar_tuples delete")?;
-        Ok(res.rows_affected() > 0)
+    async fn delete_tuple(&self, t: &Tuple) -> Result<Option<String>> {
+        let mut tx = self.pool.begin().await.context("begin delete txn")?;
+        let Some(revision) = record_change(&mut tx, TupleOperation::Delete, t, now_secs()).await?
+        else {
+            return Ok(None);
+        };
+        stamp_deleted(&mut tx, t, revision).await?;
+        tx.commit().await.context("commit delete txn")?;
+        Ok(Some(encode_zookie(revision)))
     }
 
     async fn list_tuples(&self, filter: &TupleFilter) -> Result<Vec<Tuple>> {
@@ -182,15 +195,17 @@ impl ZanzibarStore for SqliteZanzibarStore {
         // single static SQL string covers every filter combination.
         // sqlx doesn't reuse `?N`-style placeholders across the two
         // backends, so we bind the same Option twice per field.
-        let rows: Vec<(String, String, String, String, String, String)> = sqlx::query_as(
+        let rows: Vec<TupleRow> = sqlx::query_as(
             "SELECT object_type, object_id, relation,
-                    subject_type, subject_id, subject_rel
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at
              FROM auth.zanzibar_tuples
              WHERE (? IS NULL OR object_type  = ?)
                AND (? IS NULL OR object_id    = ?)
                AND (? IS NULL OR relation     = ?)
                AND (? IS NULL OR subject_type = ?)
                AND (? IS NULL OR subject_id   = ?)
+               AND deleted_revision IS NULL
              ORDER BY object_type, object_id, relation, subject_type, subject_id
              LIMIT ? OFFSET ?",
         )
@@ -209,26 +224,18 @@ impl ZanzibarStore for SqliteZanzibarStore {
         .fetch_all(&self.pool)
         .await
         .context("auth.zanzibar_tuples list")?;
-        Ok(rows
-            .into_iter()
-            .map(|(ot, oid, rel, st, sid, srel)| Tuple {
-                object_type: ot,
-                object_id: oid,
-                relation: rel,
-                subject_type: st,
-                subject_id: sid,
-                subject_rel: srel,
-            })
-            .collect())
+        rows.into_iter().map(tuple_from_row).collect()
     }
 
-    async fn check(
+    async fn check_with_context(
         &self,
         resource: &ObjectRef,
         permission: &str,
         subject: &SubjectRef,
-        _consistency: Consistency,
+        consistency: Consistency,
+        context: &CaveatContext,
     ) -> Result<CheckResult> {
+        let at = snapshot_revision(&consistency, || self.head(), || self.horizon()).await?;
         // No namespace defined → deny (the safe default). The full
         // permission algebra (union / intersect / exclude / arrow) is
         // composed by the backend-agnostic evaluator over the two
@@ -238,7 +245,12 @@ impl ZanzibarStore for SqliteZanzibarStore {
         let Some(schema) = self.get_namespace(&resource.object_type).await? else {
             return Ok(CheckResult::Denied);
         };
-        evaluate(self, &schema, resource, permission, subject).await
+        let snapshot = Snapshot {
+            store: self,
+            at,
+            now: unix_now(),
+        };
+        evaluate(&snapshot, &schema, resource, permission, subject, context).await
     }
 
     async fn expand(
@@ -276,10 +288,13 @@ impl ZanzibarStore for SqliteZanzibarStore {
             "SELECT DISTINCT object_type, object_id
              FROM auth.zanzibar_tuples
              WHERE object_type = ?
-               AND relation IN (SELECT value FROM json_each(?))",
+               AND relation IN (SELECT value FROM json_each(?))
+               AND deleted_revision IS NULL
+               AND (expires_at IS NULL OR expires_at > ?)",
         )
         .bind(resource_type)
         .bind(relation_json)
+        .bind(unix_now())
         .fetch_all(&self.pool)
         .await
         .context("auth.zanzibar_tuples candidate resources")?;
@@ -327,6 +342,8 @@ impl ZanzibarStore for SqliteZanzibarStore {
                 WHERE t.object_type = ?1
                   AND t.object_id   = ?2
                   AND t.relation IN (SELECT value FROM json_each(?3))
+                  AND t.deleted_revision IS NULL AND t.caveat_name = ''
+                  AND (t.expires_at IS NULL OR t.expires_at > ?6)
                 UNION ALL
                 SELECT t.subject_type, t.subject_id, t.subject_rel, w.depth + 1,
                        w.path || t.subject_type || ':' || t.subject_id || '|'
@@ -337,6 +354,8 @@ impl ZanzibarStore for SqliteZanzibarStore {
                  AND w.subject_rel <> ''
                  AND t.relation = w.subject_rel
                 WHERE w.depth < ?4
+                  AND t.deleted_revision IS NULL AND t.caveat_name = ''
+                  AND (t.expires_at IS NULL OR t.expires_at > ?6)
                   AND instr(w.path, '|' || t.subject_type || ':' || t.subject_id || '|') = 0
             )
             SELECT DISTINCT subject_type, subject_id
@@ -349,6 +368,7 @@ impl ZanzibarStore for SqliteZanzibarStore {
         .bind(relation_json)
         .bind(MAX_DEPTH as i64)
         .bind(subject_type)
+        .bind(unix_now())
         .fetch_all(&self.pool)
         .await
         .context("auth.zanzibar lookup_subjects CTE")?;
@@ -362,18 +382,363 @@ impl ZanzibarStore for SqliteZanzibarStore {
             })
             .collect())
     }
+
+    async fn watch(&self, since: Option<&str>, filter: &WatchFilter) -> Result<WatchBatch> {
+        let limit = filter.effective_limit();
+        let head = self.head().await?;
+        let Some(zookie) = since else {
+            return Ok(WatchBatch::new(Vec::new(), head, limit));
+        };
+        let since = decode_zookie(zookie).with_context(|| format!("invalid zookie {zookie:?}"))?;
+        ensure_retained(zookie, since, self.horizon().await?)?;
+        // Same `(? IS NULL OR col = ?)` idiom as `list_tuples`.
+        let rows: Vec<ChangelogRow> = sqlx::query_as(
+            "SELECT revision, operation, object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at, changed_at
+             FROM auth.zanzibar_changelog
+             WHERE revision > ? AND revision <= ?
+               AND (? IS NULL OR object_type  = ?)
+               AND (? IS NULL OR object_id    = ?)
+               AND (? IS NULL OR relation     = ?)
+               AND (? IS NULL OR subject_type = ?)
+               AND (? IS NULL OR subject_id   = ?)
+             ORDER BY revision
+             LIMIT ?",
+        )
+        .bind(since)
+        .bind(head)
+        .bind(&filter.object_type)
+        .bind(&filter.object_type)
+        .bind(&filter.object_id)
+        .bind(&filter.object_id)
+        .bind(&filter.relation)
+        .bind(&filter.relation)
+        .bind(&filter.subject_type)
+        .bind(&filter.subject_type)
+        .bind(&filter.subject_id)
+        .bind(&filter.subject_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("auth.zanzibar_changelog watch")?;
+        let changes = rows
+            .into_iter()
+            .map(change_from_row)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(WatchBatch::new(changes, head.max(since), limit))
+    }
+
+    async fn expire_tuples(&self, now: i64, limit: i64) -> Result<Vec<TupleChange>> {
+        let mut tx = self.pool.begin().await.context("begin expiry txn")?;
+        let rows: Vec<TupleRow> = sqlx::query_as(
+            "SELECT object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at
+             FROM auth.zanzibar_tuples
+             WHERE expires_at <= ? AND deleted_revision IS NULL
+             ORDER BY expires_at
+             LIMIT ?",
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await
+        .context("auth.zanzibar_tuples expired")?;
+        let mut revisions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let t = tuple_from_row(row)?;
+            let Some(revision) =
+                record_change(&mut tx, TupleOperation::Delete, &t, now_secs()).await?
+            else {
+                continue;
+            };
+            stamp_deleted(&mut tx, &t, revision).await?;
+            revisions.push(revision);
+        }
+        let (Some(first), Some(last)) = (revisions.first(), revisions.last()) else {
+            tx.commit().await.context("commit expiry txn")?;
+            return Ok(Vec::new());
+        };
+        // The write lock held since the first insert keeps other
+        // writers out, so this batch's revisions are contiguous.
+        let rows: Vec<ChangelogRow> = sqlx::query_as(
+            "SELECT revision, operation, object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, caveat_name, caveat_context,
+                    expires_at, changed_at
+             FROM auth.zanzibar_changelog
+             WHERE revision BETWEEN ? AND ?
+             ORDER BY revision",
+        )
+        .bind(first)
+        .bind(last)
+        .fetch_all(&mut *tx)
+        .await
+        .context("auth.zanzibar_changelog expired")?;
+        tx.commit().await.context("commit expiry txn")?;
+        rows.into_iter().map(change_from_row).collect()
+    }
+
+    async fn compact_history(&self, cutoff: f64) -> Result<Compaction> {
+        let mut tx = self.pool.begin().await.context("begin compaction txn")?;
+        // Every zookie handed out since `cutoff` is at least the
+        // revision just before the first change since then — or the
+        // head, when nothing changed.
+        // `WHERE true` keeps SQLite from reading `ON CONFLICT` as a
+        // join constraint of the `SELECT`.
+        let (horizon,): (i64,) = sqlx::query_as(
+            "INSERT INTO auth.zanzibar_gc (id, horizon)
+             SELECT 1, COALESCE(
+                 (SELECT MIN(revision) - 1 FROM auth.zanzibar_changelog WHERE changed_at >= ?),
+                 (SELECT COALESCE(MAX(revision), 0) FROM auth.zanzibar_changelog)
+             )
+             WHERE true
+             ON CONFLICT (id) DO UPDATE SET horizon = MAX(horizon, excluded.horizon)
+             RETURNING horizon",
+        )
+        .bind(cutoff)
+        .fetch_one(&mut *tx)
+        .await
+        .context("auth.zanzibar_gc advance")?;
+        let tuples = sqlx::query("DELETE FROM auth.zanzibar_tuples WHERE deleted_revision < ?")
+            .bind(horizon)
+            .execute(&mut *tx)
+            .await
+            .context("auth.zanzibar_tuples compact")?
+            .rows_affected();
+        let changes = sqlx::query("DELETE FROM auth.zanzibar_changelog WHERE revision < ?")
+            .bind(horizon)
+            .execute(&mut *tx)
+            .await
+            .context("auth.zanzibar_changelog compact")?
+            .rows_affected();
+        tx.commit().await.context("commit compaction txn")?;
+        Ok(Compaction {
+            horizon,
+            tuples,
+            changes,
+        })
+    }
 }
 
-impl LeafCheck for SqliteZanzibarStore {
+async fn head_revision<'e, E: sqlx::SqliteExecutor<'e>>(executor: E) -> Result<i64> {
+    let (head,): (i64,) =
+        sqlx::query_as("SELECT COALESCE(MAX(revision), 0) FROM auth.zanzibar_changelog")
+            .fetch_one(executor)
+            .await
+            .context("auth.zanzibar_changelog head")?;
+    Ok(head)
+}
+
+/// Append one changelog entry inside the caller's transaction, so the
+/// tuple change and its record commit together. Records only real
+/// changes — a `TOUCH` of a tuple already live with the same caveat and
+/// expiry or
+/// a `DELETE` of a missing one appends nothing and returns `None`;
+/// otherwise returns the revision the caller stamps onto the tuple
+/// rows. A `DELETE` records the caveat and expiry the removed tuple
+/// carried.
+async fn record_change(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    op: TupleOperation,
+    t: &Tuple,
+    now: f64,
+) -> Result<Option<i64>> {
+    let sql = match op {
+        TupleOperation::Touch => {
+            "INSERT INTO auth.zanzibar_changelog
+                (operation, object_type, object_id, relation,
+                 subject_type, subject_id, subject_rel, changed_at, caveat_name, caveat_context,
+                 expires_at)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM auth.zanzibar_tuples
+                 WHERE object_type = ?2 AND object_id = ?3 AND relation = ?4
+                   AND subject_type = ?5 AND subject_id = ?6 AND subject_rel = ?7
+                   AND caveat_name = ?9 AND caveat_context = ?10
+                   AND expires_at IS ?11
+                   AND deleted_revision IS NULL
+             )
+             RETURNING revision"
+        }
+        TupleOperation::Delete => {
+            "INSERT INTO auth.zanzibar_changelog
+                (operation, object_type, object_id, relation,
+                 subject_type, subject_id, subject_rel, changed_at, caveat_name, caveat_context,
+                 expires_at)
+             SELECT ?1, object_type, object_id, relation,
+                    subject_type, subject_id, subject_rel, ?8, caveat_name, caveat_context,
+                    expires_at
+             FROM auth.zanzibar_tuples
+             WHERE object_type = ?2 AND object_id = ?3 AND relation = ?4
+               AND subject_type = ?5 AND subject_id = ?6 AND subject_rel = ?7
+               AND deleted_revision IS NULL
+             RETURNING revision"
+        }
+    };
+    let mut query = sqlx::query_as(sql)
+        .bind(op.as_str())
+        .bind(&t.object_type)
+        .bind(&t.object_id)
+        .bind(&t.relation)
+        .bind(&t.subject_type)
+        .bind(&t.subject_id)
+        .bind(&t.subject_rel)
+        .bind(now);
+    if op == TupleOperation::Touch {
+        let (caveat_name, caveat_context) = caveat_columns(t);
+        query = query
+            .bind(caveat_name)
+            .bind(caveat_context.to_string())
+            .bind(t.expires_at);
+    }
+    let row: Option<(i64,)> = query
+        .fetch_optional(&mut **tx)
+        .await
+        .context("auth.zanzibar_changelog insert")?;
+    Ok(row.map(|(revision,)| revision))
+}
+
+/// End the live version of `t` (whatever its caveat) at `revision`.
+/// Rows are stamped rather than removed, so an `Exact` read at an
+/// earlier revision still sees the tuple. subject_rel is NOT NULL
+/// ('' for direct), so plain equality works on both backends — no
+/// `IS` / `IS NOT DISTINCT FROM` dance needed.
+async fn stamp_deleted(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    t: &Tuple,
+    revision: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE auth.zanzibar_tuples SET deleted_revision = ?
+         WHERE object_type = ? AND object_id = ? AND relation = ?
+           AND subject_type = ? AND subject_id = ?
+           AND subject_rel = ? AND deleted_revision IS NULL",
+    )
+    .bind(revision)
+    .bind(&t.object_type)
+    .bind(&t.object_id)
+    .bind(&t.relation)
+    .bind(&t.subject_type)
+    .bind(&t.subject_id)
+    .bind(&t.subject_rel)
+    .execute(&mut **tx)
+    .await
+    .context("auth.zanzibar_tuples delete")?;
+    Ok(())
+}
+
+/// [`caveat_from_columns`] for SQLite's TEXT-encoded `caveat_context`.
+fn caveat_from_text(name: String, context: &str) -> Result<Option<TupleCaveat>> {
+    let context = serde_json::from_str(context).context("zanzibar decode caveat_context")?;
+    caveat_from_columns(name, context)
+}
+
+type TupleRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<i64>,
+);
+
+fn tuple_from_row(
+    (
+        object_type,
+        object_id,
+        relation,
+        subject_type,
+        subject_id,
+        subject_rel,
+        caveat_name,
+        caveat_context,
+        expires_at,
+    ): TupleRow,
+) -> Result<Tuple> {
+    Ok(Tuple {
+        object_type,
+        object_id,
+        relation,
+        subject_type,
+        subject_id,
+        subject_rel,
+        caveat: caveat_from_text(caveat_name, &caveat_context)?,
+        expires_at,
+    })
+}
+
+type ChangelogRow = (
+    i64,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<i64>,
+    f64,
+);
+
+fn change_from_row(
+    (
+        revision,
+        op,
+        object_type,
+        object_id,
+        relation,
+        subject_type,
+        subject_id,
+        subject_rel,
+        caveat_name,
+        caveat_context,
+        expires_at,
+        changed_at,
+    ): ChangelogRow,
+) -> Result<TupleChange> {
+    let operation = TupleOperation::parse(&op)
+        .with_context(|| format!("auth.zanzibar_changelog: unknown operation {op:?}"))?;
+    Ok(TupleChange {
+        zookie: encode_zookie(revision),
+        operation,
+        tuple: Tuple {
+            object_type,
+            object_id,
+            relation,
+            subject_type,
+            subject_id,
+            subject_rel,
+            caveat: caveat_from_text(caveat_name, &caveat_context)?,
+            expires_at,
+        },
+        changed_at,
+    })
+}
+
+/// The store pinned to one revision for `check` — same visibility and
+/// expiry rules as the PG backend's snapshot.
+struct Snapshot<'a> {
+    store: &'a SqliteZanzibarStore,
+    at: i64,
+    now: i64,
+}
+
+impl LeafCheck for Snapshot<'_> {
     fn check_relation_set<'a>(
         &'a self,
         object: &'a ObjectRef,
         relations: &'a [String],
         subject: &'a SubjectRef,
-    ) -> Pin<Box<dyn Future<Output = Result<Verdict>> + Send + 'a>> {
+    ) -> Pin<Box<dyn Future<Output = Result<LeafOutcome>> + Send + 'a>> {
         Box::pin(async move {
             if relations.is_empty() {
-                return Ok(Verdict::Denied);
+                return Ok(Verdict::Denied.into());
             }
             // SQLite has no array type — encode the relation set as a
             // JSON array consumed via `json_each(?)`. Same shape as PG's
@@ -384,25 +749,41 @@ impl LeafCheck for SqliteZanzibarStore {
             // Cycle-guard via a JSON-encoded path string. Each hop
             // appends `<type>:<id>` and the join refuses to add a node
             // already on the path. SQLite recursive CTEs allow this with
-            // ordinary `||` string concat.
-            let row: Option<(i64,)> = sqlx::query_as(
+            // ordinary `||` string concat. `caveats` collects each
+            // path's caveats, as in the PG backend.
+            let row: Option<(i64, String)> = sqlx::query_as(
                 r#"
-                WITH RECURSIVE walk(subject_type, subject_id, subject_rel, depth, path) AS (
+                WITH RECURSIVE walk(subject_type, subject_id, subject_rel, depth, path, caveats) AS (
                     SELECT t.subject_type,
                            t.subject_id,
                            t.subject_rel,
                            1 AS depth,
-                           '|' || t.subject_type || ':' || t.subject_id || '|' AS path
+                           '|' || t.subject_type || ':' || t.subject_id || '|' AS path,
+                           CASE WHEN t.caveat_name = '' THEN '[]'
+                                ELSE json_array(json_object(
+                                    'object_type', t.object_type,
+                                    'name', t.caveat_name,
+                                    'context', json(t.caveat_context)))
+                           END AS caveats
                     FROM auth.zanzibar_tuples t
                     WHERE t.object_type = ?1
                       AND t.object_id = ?2
                       AND t.relation IN (SELECT value FROM json_each(?3))
+                      AND t.created_revision <= ?7
+                      AND (t.deleted_revision IS NULL OR t.deleted_revision > ?7)
+                      AND (t.expires_at IS NULL OR t.expires_at > ?8)
                     UNION ALL
                     SELECT t.subject_type,
                            t.subject_id,
                            t.subject_rel,
                            w.depth + 1,
-                           w.path || t.subject_type || ':' || t.subject_id || '|'
+                           w.path || t.subject_type || ':' || t.subject_id || '|',
+                           CASE WHEN t.caveat_name = '' THEN w.caveats
+                                ELSE json_insert(w.caveats, '$[#]', json_object(
+                                    'object_type', t.object_type,
+                                    'name', t.caveat_name,
+                                    'context', json(t.caveat_context)))
+                           END
                     FROM auth.zanzibar_tuples t
                     JOIN walk w
                       ON t.object_type = w.subject_type
@@ -410,16 +791,27 @@ impl LeafCheck for SqliteZanzibarStore {
                      AND w.subject_rel <> ''
                      AND t.relation = w.subject_rel
                     WHERE w.depth < ?4
+                      AND t.created_revision <= ?7
+                      AND (t.deleted_revision IS NULL OR t.deleted_revision > ?7)
+                      AND (t.expires_at IS NULL OR t.expires_at > ?8)
                       AND instr(w.path, '|' || t.subject_type || ':' || t.subject_id || '|') = 0
                 )
                 SELECT CASE
                     WHEN EXISTS (
                         SELECT 1 FROM walk
                         WHERE subject_type = ?5 AND subject_id = ?6 AND subject_rel = ''
+                          AND caveats = '[]'
                     ) THEN 1
                     WHEN EXISTS (SELECT 1 FROM walk WHERE depth >= ?4) THEN 2
                     ELSE 0
-                END AS verdict
+                END AS verdict,
+                (
+                    SELECT json_group_array(json(caveats)) FROM (
+                        SELECT DISTINCT caveats FROM walk
+                        WHERE subject_type = ?5 AND subject_id = ?6 AND subject_rel = ''
+                          AND caveats <> '[]'
+                    )
+                ) AS caveated_paths
                 "#,
             )
             .bind(&object.object_type)
@@ -428,14 +820,25 @@ impl LeafCheck for SqliteZanzibarStore {
             .bind(MAX_DEPTH as i64)
             .bind(&subject.subject_type)
             .bind(&subject.subject_id)
-            .fetch_optional(&self.pool)
+            .bind(self.at)
+            .bind(self.now)
+            .fetch_optional(&self.store.pool)
             .await
             .context("auth.zanzibar check CTE")?;
 
-            Ok(match row.map(|(v,)| v).unwrap_or(0) {
+            let Some((verdict, paths)) = row else {
+                return Ok(Verdict::Denied.into());
+            };
+            let verdict = match verdict {
                 1 => Verdict::Allowed,
                 2 => Verdict::DepthExceeded,
                 _ => Verdict::Denied,
+            };
+            let caveated_paths: Vec<Vec<PathCaveat>> =
+                serde_json::from_str(&paths).context("auth.zanzibar check CTE caveats")?;
+            Ok(LeafOutcome {
+                verdict,
+                caveated_paths,
             })
         })
     }
@@ -444,32 +847,42 @@ impl LeafCheck for SqliteZanzibarStore {
         &'a self,
         object: &'a ObjectRef,
         relation: &'a str,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<ObjectRef>>> + Send + 'a>> {
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ArrowTarget>>> + Send + 'a>> {
         Box::pin(async move {
             // Only object-reference subjects (`subject_rel = ''`) are
             // the left edge of an arrow hop — a userset subject on the
             // tupleset relation has no object to re-evaluate against.
             let rows = sqlx::query(
-                "SELECT subject_type, subject_id
+                "SELECT subject_type, subject_id, caveat_name, caveat_context
                  FROM auth.zanzibar_tuples
                  WHERE object_type = ? AND object_id = ? AND relation = ?
-                   AND subject_rel = ''",
+                   AND subject_rel = ''
+                   AND created_revision <= ?
+                   AND (deleted_revision IS NULL OR deleted_revision > ?)
+                   AND (expires_at IS NULL OR expires_at > ?)",
             )
             .bind(&object.object_type)
             .bind(&object.object_id)
             .bind(relation)
-            .fetch_all(&self.pool)
+            .bind(self.at)
+            .bind(self.at)
+            .bind(self.now)
+            .fetch_all(&self.store.pool)
             .await
             .context("auth.zanzibar arrow targets")?;
-            Ok(rows
-                .into_iter()
+            rows.into_iter()
                 .map(|row| {
-                    ObjectRef::new(
+                    let target = ObjectRef::new(
                         row.get::<String, _>("subject_type"),
                         row.get::<String, _>("subject_id"),
-                    )
+                    );
+                    let caveat = caveat_from_text(
+                        row.get("caveat_name"),
+                        row.get::<&str, _>("caveat_context"),
+                    )?;
+                    Ok((target, caveat))
                 })
-                .collect())
+                .collect()
         })
     }
 
@@ -477,7 +890,7 @@ impl LeafCheck for SqliteZanzibarStore {
         &'a self,
         object_type: &'a str,
     ) -> Pin<Box<dyn Future<Output = Result<Option<NamespaceSchema>>> + Send + 'a>> {
-        Box::pin(async move { self.get_namespace(object_type).await })
+        Box::pin(async move { self.store.get_namespace(object_type).await })
     }
 }
 
@@ -505,11 +918,14 @@ fn expand_sqlite<'a>(
         let rows = sqlx::query(
             "SELECT subject_type, subject_id, subject_rel
              FROM auth.zanzibar_tuples
-             WHERE object_type = ? AND object_id = ? AND relation = ?",
+             WHERE object_type = ? AND object_id = ? AND relation = ?
+               AND deleted_revision IS NULL
+               AND (expires_at IS NULL OR expires_at > ?)",
         )
         .bind(&resource.object_type)
         .bind(&resource.object_id)
         .bind(relation)
+        .bind(unix_now())
         .fetch_all(&store.pool)
         .await
         .context("auth.zanzibar_tuples expand fetch")?;
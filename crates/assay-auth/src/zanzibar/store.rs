@@ -9,22 +9,39 @@
 //!   CRUD.
 //! - `write_tuple` / `write_tuples` / `delete_tuple` — relation-tuple
 //!   CRUD. Batch writes are atomic so a multi-tuple admin operation
-//!   never half-applies.
+//!   never half-applies. Each returns the zookie of the write, which a
+//!   later `check` passes back to read its own writes.
 //! - `check` — the hot path. Returns
 //!   [`super::types::CheckResult::Allowed`] iff `subject` has
 //!   `permission` on `object` per the namespace's resolved relation
-//!   set.
+//!   set. `check_with_context` also takes the request context that
+//!   caveated tuples are evaluated against.
 //! - `expand` — diagnostic / admin tooling counterpart that returns
 //!   the userset rewrite tree.
 //! - `lookup_resources` / `lookup_subjects` — forward / reverse
 //!   listings used by UI surfaces ("show every doc Alice can view",
 //!   "show every viewer of doc X").
+//! - `watch` — the change feed. Every tuple write/delete that changes
+//!   state appends to `auth.zanzibar_changelog` in the same
+//!   transaction; callers page through it by zookie to invalidate
+//!   permission caches.
+//! - `expire_tuples` — deletes tuples whose `expires_at` has passed.
+//!   Reads already ignore them; this just reclaims the rows and tells
+//!   watchers. Driven by [`super::expiry`].
+//! - `compact_history` — advances the GC horizon and drops the deleted
+//!   tuple rows and changelog entries below it, so MVCC history doesn't
+//!   grow forever. `Exact` reads and `watch` cursors below the horizon
+//!   fail with [`ConsistencyError::Compacted`]. Driven by [`super::gc`].
 //!
 //! Implementations live in [`super::postgres`] and [`super::sqlite`].
 
+use std::future::Future;
+use std::time::{Duration, Instant};
+
 use super::types::{
-    CheckResult, Consistency, NamespaceSchema, ObjectRef, SubjectRef, Tuple, TupleFilter,
-    UsersetTree,
+    CaveatContext, CheckResult, Compaction, Consistency, ConsistencyError, NamespaceSchema,
+    ObjectRef, SubjectRef, Tuple, TupleCaveat, TupleChange, TupleFilter, UsersetTree, WatchBatch,
+    WatchFilter, decode_zookie,
 };
 
 /// Async, object-safe Zanzibar storage trait. See module docs.
@@ -43,17 +60,20 @@ pub trait ZanzibarStore: Send + Sync + 'static {
     /// List every namespace, ordered by name. Used by admin UI.
     async fn list_namespaces(&self) -> anyhow::Result<Vec<NamespaceSchema>>;
 
-    /// Insert one tuple. Idempotent on the composite PK — re-writing
-    /// the same tuple is a no-op (returns Ok(()) without erroring).
-    async fn write_tuple(&self, tuple: &Tuple) -> anyhow::Result<()>;
+    /// Insert one tuple and return the zookie it is visible from.
+    /// Idempotent — re-writing a live tuple is a no-op that returns the
+    /// current head zookie. Re-writing it with a different caveat
+    /// replaces the live tuple.
+    async fn write_tuple(&self, tuple: &Tuple) -> anyhow::Result<String>;
 
     /// Atomic batch write. Either every tuple is persisted or none are.
     /// Used by the admin "import schema + seed tuples" workflows.
-    async fn write_tuples(&self, tuples: &[Tuple]) -> anyhow::Result<()>;
+    /// Returns the zookie every tuple in the batch is visible from.
+    async fn write_tuples(&self, tuples: &[Tuple]) -> anyhow::Result<String>;
 
-    /// Delete one tuple by exact match. Returns `Ok(true)` iff a row
-    /// was removed.
-    async fn delete_tuple(&self, tuple: &Tuple) -> anyhow::Result<bool>;
+    /// Delete one tuple by exact match. Returns the zookie of the
+    /// deletion, or `Ok(None)` if no live tuple matched.
+    async fn delete_tuple(&self, tuple: &Tuple) -> anyhow::Result<Option<String>>;
 
     /// List tuples matching `filter`, ordered by
     /// (object_type, object_id, relation, subject_type, subject_id).
@@ -64,16 +84,44 @@ pub trait ZanzibarStore: Send + Sync + 'static {
     /// every relation that resolves to `permission` per the namespace
     /// schema, looking for `subject`.
     ///
-    /// `consistency` is captured today but treated uniformly as
-    /// `Minimum` (any committed snapshot) — full snapshot enforcement
-    /// is phase 9 work. The argument is preserved on the trait so
-    /// that addition is a non-breaking change.
+    /// `consistency` picks the snapshot: `Minimum` and `AtLeastAsFresh`
+    /// read the current tuples (the latter once the head has reached
+    /// its zookie), `Exact` reads the tuples live at that revision.
+    /// Unsatisfiable zookies fail with [`ConsistencyError`].
+    ///
+    /// Equivalent to [`Self::check_with_context`] with an empty
+    /// context, so a permission that depends on a caveated tuple comes
+    /// back [`CheckResult::Conditional`].
     async fn check(
         &self,
         resource: &ObjectRef,
         permission: &str,
         subject: &SubjectRef,
         consistency: Consistency,
+    ) -> anyhow::Result<CheckResult> {
+        self.check_with_context(
+            resource,
+            permission,
+            subject,
+            consistency,
+            &CaveatContext::new(),
+        )
+        .await
+    }
+
+    /// [`Self::check`] with a request `context` for caveated tuples.
+    /// Parameters stored on a tuple take precedence over `context`;
+    /// a caveat whose parameters neither supplies leaves the result
+    /// [`CheckResult::Conditional`]. Ill-typed context values or a
+    /// failing caveat expression error with
+    /// [`super::caveat::CaveatError`].
+    async fn check_with_context(
+        &self,
+        resource: &ObjectRef,
+        permission: &str,
+        subject: &SubjectRef,
+        consistency: Consistency,
+        context: &CaveatContext,
     ) -> anyhow::Result<CheckResult>;
 
     /// Userset-rewrite expansion — returns the tree of subjects that
@@ -89,7 +137,8 @@ pub trait ZanzibarStore: Send + Sync + 'static {
 
     /// Forward index — find every `(resource_type, *)` where `subject`
     /// has `permission`. Used to populate UI lists like "every
-    /// document Alice can view".
+    /// document Alice can view". Only unconditional grants are listed —
+    /// there is no request context to decide caveats with.
     async fn lookup_resources(
         &self,
         resource_type: &str,
@@ -99,11 +148,146 @@ pub trait ZanzibarStore: Send + Sync + 'static {
 
     /// Reverse index — find every subject of type `subject_type` that
     /// has `permission` on `resource`. Used to populate UI lists like
-    /// "every viewer of doc X".
+    /// "every viewer of doc X". Like `lookup_resources`, caveated
+    /// tuples don't count.
     async fn lookup_subjects(
         &self,
         subject_type: &str,
         resource: &ObjectRef,
         permission: &str,
     ) -> anyhow::Result<Vec<SubjectRef>>;
+
+    /// Tuple changes committed after `since`, oldest first, narrowed
+    /// by `filter`. `since = None` starts at the current head: no
+    /// changes, just the zookie to watch from. Errors on a zookie this
+    /// store didn't issue, and with [`ConsistencyError::Compacted`] on
+    /// one below the GC horizon.
+    async fn watch(&self, since: Option<&str>, filter: &WatchFilter) -> anyhow::Result<WatchBatch>;
+
+    /// Delete up to `limit` live tuples that expired at or before `now`
+    /// (unix seconds), soonest-expired first. Each is recorded as a
+    /// `DELETE` in the changelog; the recorded changes are returned,
+    /// oldest first. Safe to run concurrently on several nodes — a
+    /// tuple another sweep already removed is skipped.
+    async fn expire_tuples(&self, now: i64, limit: i64) -> anyhow::Result<Vec<TupleChange>>;
+
+    /// Advance the GC horizon to the oldest revision still needed by a
+    /// zookie handed out at or after `cutoff` (unix seconds), then
+    /// delete tuple rows with `deleted_revision` below it and changelog
+    /// rows below it. The horizon never moves backwards or past the
+    /// head. Returns the horizon in effect and what was removed.
+    async fn compact_history(&self, cutoff: f64) -> anyhow::Result<Compaction>;
+}
+
+/// Current time in unix seconds — the clock `expires_at` is read
+/// against.
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// `(caveat_name, caveat_context)` column values for `t` — `''` and an
+/// empty object for an unconditional tuple.
+pub(crate) fn caveat_columns(t: &Tuple) -> (&str, serde_json::Value) {
+    match &t.caveat {
+        Some(c) => (&c.name, serde_json::Value::Object(c.context.clone())),
+        None => ("", serde_json::Value::Object(CaveatContext::new())),
+    }
+}
+
+/// Inverse of [`caveat_columns`].
+pub(crate) fn caveat_from_columns(
+    name: String,
+    context: serde_json::Value,
+) -> anyhow::Result<Option<TupleCaveat>> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    let serde_json::Value::Object(context) = context else {
+        anyhow::bail!("zanzibar: caveat_context of caveat {name:?} is not a JSON object");
+    };
+    Ok(Some(TupleCaveat { name, context }))
+}
+
+/// Revision a snapshot read at "the current tuples" binds — every live
+/// row has `created_revision <= LATEST_REVISION` and no
+/// `deleted_revision`.
+pub(crate) const LATEST_REVISION: i64 = i64::MAX;
+
+/// How long `AtLeastAsFresh` waits for the head to reach its zookie.
+/// Both backends read from the primary, so a lagging head only happens
+/// for a zookie minted elsewhere; the wait covers replica-style lag
+/// without hanging a check indefinitely.
+const FRESHNESS_WAIT: Duration = Duration::from_secs(2);
+const FRESHNESS_POLL: Duration = Duration::from_millis(50);
+
+/// Resolve `consistency` to the revision a check reads at, given
+/// probes for the store's current head revision and GC horizon.
+/// Returns [`LATEST_REVISION`] for reads of the current tuples.
+pub(crate) async fn snapshot_revision<F, Fut, G, Gut>(
+    consistency: &Consistency,
+    head: F,
+    horizon: G,
+) -> anyhow::Result<i64>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<i64>>,
+    G: FnOnce() -> Gut,
+    Gut: Future<Output = anyhow::Result<i64>>,
+{
+    let decode = |zookie: &str| {
+        decode_zookie(zookie).ok_or_else(|| ConsistencyError::InvalidZookie(zookie.to_string()))
+    };
+    match consistency {
+        Consistency::Minimum => Ok(LATEST_REVISION),
+        Consistency::AtLeastAsFresh(zookie) => {
+            let wanted = decode(zookie)?;
+            let deadline = Instant::now() + FRESHNESS_WAIT;
+            loop {
+                let current = head().await?;
+                if current >= wanted {
+                    return Ok(LATEST_REVISION);
+                }
+                if Instant::now() >= deadline {
+                    return Err(ConsistencyError::AheadOfHead {
+                        zookie: zookie.clone(),
+                        head: current,
+                    }
+                    .into());
+                }
+                tokio::time::sleep(FRESHNESS_POLL).await;
+            }
+        }
+        Consistency::Exact(zookie) => {
+            let wanted = decode(zookie)?;
+            let current = head().await?;
+            if wanted > current {
+                return Err(ConsistencyError::AheadOfHead {
+                    zookie: zookie.clone(),
+                    head: current,
+                }
+                .into());
+            }
+            ensure_retained(zookie, wanted, horizon().await?)?;
+            Ok(wanted)
+        }
+    }
+}
+
+/// Refuse a zookie naming `revision` when it's below the GC horizon:
+/// the rows a snapshot or watch from there would read may be gone.
+pub(crate) fn ensure_retained(
+    zookie: &str,
+    revision: i64,
+    horizon: i64,
+) -> Result<(), ConsistencyError> {
+    if revision < horizon {
+        return Err(ConsistencyError::Compacted {
+            zookie: zookie.to_string(),
+            horizon,
+        });
+    }
+    Ok(())
 }
@@ -23,6 +23,8 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::caveat::CaveatDef;
+
 /// `<type>:<id>` reference to a protected resource (the *object* side
 /// of a relation tuple). Field name is `object_type`/`object_id` to
 /// match the column names in `auth.zanzibar_tuples` 1:1 — keeps SQL
@@ -145,6 +147,17 @@ impl SubjectRef {
 /// string for a direct subject (e.g. `user:alice`) and the relation
 /// name for a userset subject (e.g. `family:smith#member`); see
 /// [`SubjectRef`] for the rationale.
+///
+/// `caveat` makes the tuple conditional — it only grants while the
+/// named caveat holds (see [`super::caveat`]). It isn't part of the
+/// tuple's identity: re-writing the same tuple with a different caveat
+/// replaces it, and a delete removes it whatever its caveat.
+///
+/// `expires_at` (unix seconds) bounds the grant in time: once it
+/// passes, checks, expands and lookups ignore the tuple, and the
+/// expiry sweeper deletes it. Like the caveat, it's not part of the
+/// identity — re-writing with a new expiry extends (or ends) the
+/// grant.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Tuple {
     pub object_type: String,
@@ -154,6 +167,24 @@ pub struct Tuple {
     pub subject_id: String,
     #[serde(default)]
     pub subject_rel: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caveat: Option<TupleCaveat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// Parameter values for a caveat, keyed by parameter name.
+pub type CaveatContext = serde_json::Map<String, serde_json::Value>;
+
+/// The caveat attached to a conditional [`Tuple`]: the caveat's name
+/// (declared in the object's namespace) plus the parameters fixed at
+/// write time. Parameters left out here must come from the `check`
+/// request's context.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TupleCaveat {
+    pub name: String,
+    #[serde(default)]
+    pub context: CaveatContext,
 }
 
 /// Filter for listing tuples. Every field is optional; an empty filter
@@ -196,9 +227,32 @@ impl Tuple {
             subject_type: s.subject_type,
             subject_id: s.subject_id,
             subject_rel: s.subject_rel,
+            caveat: None,
+            expires_at: None,
         }
     }
 
+    /// Make the tuple conditional on caveat `name`, with `context`
+    /// fixed at write time.
+    pub fn with_caveat(mut self, name: impl Into<String>, context: CaveatContext) -> Self {
+        self.caveat = Some(TupleCaveat {
+            name: name.into(),
+            context,
+        });
+        self
+    }
+
+    /// Grant only until `expires_at` (unix seconds).
+    pub fn expiring_at(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Whether the grant has lapsed at `now` (unix seconds).
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
     pub fn object(&self) -> ObjectRef {
         ObjectRef::new(self.object_type.clone(), self.object_id.clone())
     }
@@ -212,6 +266,110 @@ impl Tuple {
     }
 }
 
+/// Kind of change recorded in the tuple changelog. Named after the
+/// SpiceDB Watch operations: `Touch` is a tuple that was written,
+/// `Delete` one that was removed. Re-writing an existing tuple or
+/// deleting a missing one changes nothing and is not recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TupleOperation {
+    Touch,
+    Delete,
+}
+
+impl TupleOperation {
+    /// Value stored in `auth.zanzibar_changelog.operation`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Touch => "TOUCH",
+            Self::Delete => "DELETE",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "TOUCH" => Some(Self::Touch),
+            "DELETE" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// One changelog entry. `zookie` is the revision of this change;
+/// passing it back to `watch` resumes right after it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TupleChange {
+    pub zookie: String,
+    pub operation: TupleOperation,
+    pub tuple: Tuple,
+    pub changed_at: f64,
+}
+
+/// Which changes a `watch` call returns. Every field is optional; an
+/// empty filter matches every change. `limit` defaults to 100 and is
+/// clamped to 1000.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WatchFilter {
+    pub object_type: Option<String>,
+    pub object_id: Option<String>,
+    pub relation: Option<String>,
+    pub subject_type: Option<String>,
+    pub subject_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl WatchFilter {
+    /// Effective limit: caller-supplied (clamped to 1..=1000) or 100.
+    pub fn effective_limit(&self) -> i64 {
+        self.limit.map(|n| n.clamp(1, 1000)).unwrap_or(100)
+    }
+}
+
+/// Result of one `watch` call: the matching changes after the
+/// requested zookie, oldest first, and the zookie to resume from.
+/// The resume zookie advances past non-matching changes too, so an
+/// idle filtered watcher doesn't rescan them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WatchBatch {
+    pub changes: Vec<TupleChange>,
+    pub zookie: String,
+}
+
+impl WatchBatch {
+    /// Assemble a batch from changes read with revision in
+    /// `(since, head]`. A full page resumes after its last change;
+    /// anything shorter means the range was exhausted, so the
+    /// watcher resumes from `head`.
+    pub fn new(changes: Vec<TupleChange>, head: i64, limit: i64) -> Self {
+        let zookie = match changes.last() {
+            Some(last) if changes.len() as i64 >= limit => last.zookie.clone(),
+            _ => encode_zookie(head),
+        };
+        Self { changes, zookie }
+    }
+}
+
+/// Outcome of one history compaction: the GC horizon now in effect
+/// and how many rows fell below it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Compaction {
+    pub horizon: i64,
+    pub tuples: u64,
+    pub changes: u64,
+}
+
+/// Render a changelog revision as a zookie. Callers treat zookies as
+/// opaque; the decimal form just keeps them readable in logs.
+pub fn encode_zookie(revision: i64) -> String {
+    revision.to_string()
+}
+
+/// Inverse of [`encode_zookie`]. `None` for anything that isn't a
+/// zookie this store issued.
+pub fn decode_zookie(zookie: &str) -> Option<i64> {
+    zookie.parse::<i64>().ok().filter(|r| *r >= 0)
+}
+
 /// Read-consistency mode for `check`-style queries. Closely matches
 /// the Zanzibar paper terminology and the SpiceDB API surface.
 ///
@@ -224,11 +382,14 @@ impl Tuple {
 ///   cache-friendly batched checks where every check should see the
 ///   same world.
 ///
-/// In v0.2.0 zookies are opaque transaction-id strings; the Postgres
-/// backend serialises `pg_current_wal_lsn()` and the SQLite backend
-/// uses a monotonic counter. The current check implementation is
-/// `Consistency::Minimum` only (the other modes pass through to the
-/// same code path); full snapshot enforcement is future work.
+/// Zookies are changelog revisions (see [`encode_zookie`]); every
+/// tuple write returns one. Tuple rows carry the revision that created
+/// and deleted them, so `Exact` reads the rows live at that revision
+/// rather than the current ones. A zookie past the store's head fails
+/// with [`ConsistencyError`] — `AtLeastAsFresh` first waits briefly for
+/// the head to catch up. So does an `Exact` zookie below the store's
+/// GC horizon: the history it names has been compacted away (see
+/// [`super::gc`]).
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub enum Consistency {
     #[default]
@@ -237,11 +398,27 @@ pub enum Consistency {
     Exact(String),
 }
 
+/// A [`Consistency`] requirement the store can't honour. Distinct from
+/// storage failures so the HTTP layer can answer 400 rather than 500.
+#[derive(Debug, thiserror::Error)]
+pub enum ConsistencyError {
+    #[error("invalid zookie {0:?}")]
+    InvalidZookie(String),
+    #[error("zookie {zookie} is ahead of the store head ({head})")]
+    AheadOfHead { zookie: String, head: i64 },
+    #[error("zookie {zookie} is older than the GC horizon ({horizon})")]
+    Compacted { zookie: String, horizon: i64 },
+}
+
 /// Result of a `check` call. `Allowed` carries the (best-effort) tuple
 /// path that resolved the permission so callers can show "why?" in a
 /// debug UI; the path may be empty if the storage layer chose to skip
 /// it for performance.
 ///
+/// `Conditional` means the permission hinges on caveats whose
+/// parameters the request didn't supply; `missing_context` names them
+/// so the caller can retry with more context. It never grants.
+///
 /// `DepthExceeded` and `CycleDetected` are *not* errors in the
 /// `Result` sense — they're a deliberate denial signal. A buggy schema
 /// shouldn't crash the request; it should deny the access and let the
@@ -250,6 +427,7 @@ pub enum Consistency {
 pub enum CheckResult {
     Allowed { resolved_via: Vec<Tuple> },
     Denied,
+    Conditional { missing_context: Vec<String> },
     DepthExceeded,
     CycleDetected,
 }
@@ -313,6 +491,12 @@ pub struct NamespaceSchema {
     /// JSON serialisation stable across runs (matters for diff-friendly
     /// `auth.zanzibar_namespaces.schema_json` history).
     pub definitions: BTreeMap<String, RelationDef>,
+    /// Caveats the relations here reference (`user with in_office`),
+    /// keyed by name. The DSL declares caveats once at top level; the
+    /// parser copies each into every namespace that uses it, so a check
+    /// only ever needs the object's own namespace.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub caveats: BTreeMap<String, CaveatDef>,
 }
 
 impl NamespaceSchema {
@@ -320,6 +504,7 @@ impl NamespaceSchema {
         Self {
             name: name.into(),
             definitions: BTreeMap::new(),
+            caveats: BTreeMap::new(),
         }
     }
 
@@ -327,6 +512,11 @@ impl NamespaceSchema {
         self.definitions.insert(name.into(), def);
         self
     }
+
+    pub fn with_caveat(mut self, def: CaveatDef) -> Self {
+        self.caveats.insert(def.name.clone(), def);
+        self
+    }
 }
 
 /// A single line in a SpiceDB schema — `relation owner: user`,
@@ -373,6 +563,7 @@ pub enum RelationKind {
 /// A type reference on the right-hand side of a `relation` line.
 /// `user` is `TypeRef::direct("user")`; `family#member` is
 /// `TypeRef::userset("family", "member")`; `user:*` is the wildcard form.
+/// Any of them may carry a caveat — `user with in_office`.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TypeRef {
     pub object_type: String,
@@ -386,6 +577,9 @@ pub struct TypeRef {
     /// JSON callers (the common case — wildcards are an escape hatch).
     #[serde(default)]
     pub wildcard: bool,
+    /// Caveat tuples of this type may carry — `user with in_office`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caveat: Option<String>,
 }
 
 impl TypeRef {
@@ -394,6 +588,7 @@ impl TypeRef {
             object_type: ty.into(),
             relation: None,
             wildcard: false,
+            caveat: None,
         }
     }
 
@@ -402,6 +597,7 @@ impl TypeRef {
             object_type: ty.into(),
             relation: Some(relation.into()),
             wildcard: false,
+            caveat: None,
         }
     }
 
@@ -410,8 +606,14 @@ impl TypeRef {
             object_type: ty.into(),
             relation: None,
             wildcard: true,
+            caveat: None,
         }
     }
+
+    pub fn with_caveat(mut self, caveat: impl Into<String>) -> Self {
+        self.caveat = Some(caveat.into());
+        self
+    }
 }
 
 /// Algebraic permission expression — the right-hand side of a
@@ -531,7 +733,64 @@ mod tests {
             .is_allowed()
         );
         assert!(!CheckResult::Denied.is_allowed());
+        assert!(
+            !CheckResult::Conditional {
+                missing_context: vec!["client_ip".into()]
+            }
+            .is_allowed()
+        );
         assert!(!CheckResult::DepthExceeded.is_allowed());
         assert!(!CheckResult::CycleDetected.is_allowed());
     }
+
+    #[test]
+    fn tuple_expiry_is_inclusive() {
+        let t = Tuple::direct(
+            ObjectRef::new("document", "x"),
+            "viewer",
+            SubjectRef::direct("user", "alice"),
+        );
+        assert!(!t.is_expired(i64::MAX));
+        let t = t.expiring_at(100);
+        assert!(!t.is_expired(99));
+        assert!(t.is_expired(100));
+        // Permanent tuples serialize without the field.
+        let json = serde_json::to_value(&t).unwrap();
+        assert_eq!(json["expires_at"], 100);
+        let permanent = Tuple {
+            expires_at: None,
+            ..t
+        };
+        assert!(
+            serde_json::to_value(&permanent)
+                .unwrap()
+                .get("expires_at")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn watch_batch_resume_zookie() {
+        let change = |rev: i64| TupleChange {
+            zookie: encode_zookie(rev),
+            operation: TupleOperation::Touch,
+            tuple: Tuple::direct(
+                ObjectRef::new("document", "x"),
+                "owner",
+                SubjectRef::direct("user", "alice"),
+            ),
+            changed_at: 0.0,
+        };
+        // Short page: caught up to head even though the last match was older.
+        let batch = WatchBatch::new(vec![change(3)], 7, 2);
+        assert_eq!(batch.zookie, "7");
+        // Full page: resume right after the last change returned.
+        let batch = WatchBatch::new(vec![change(3), change(5)], 7, 2);
+        assert_eq!(batch.zookie, "5");
+        assert_eq!(WatchBatch::new(vec![], 0, 2).zookie, "0");
+
+        assert_eq!(decode_zookie(&encode_zookie(42)), Some(42));
+        assert_eq!(decode_zookie("-1"), None);
+        assert_eq!(decode_zookie("r42"), None);
+    }
 }
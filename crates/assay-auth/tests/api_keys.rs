@@ -0,0 +1,404 @@
+//! Integration tests for database-backed API keys.
+//!
+//! - **SQLite** — always-on. Store contract plus the HTTP flow: minting
+//!   and revoking over `/admin/api-keys`, scoped access to the auth
+//!   admin surface, expiry.
+//! - **Postgres** — store contract only, gated on
+//!   `ASSAY_TEST_DATABASE_URL`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use assay_auth::api_keys::{self, ApiKeyStore, NewApiKey};
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
+
+/// Backend-neutral API key store contract. `owner` must be unique to
+/// the run so shared Postgres databases don't see earlier rows.
+async fn exercise_api_key_store(store: &dyn ApiKeyStore, owner: &str) {
+    let t = now();
+    let minted = api_keys::mint(
+        NewApiKey {
+            name: "deploy".into(),
+            owner: owner.into(),
+            scopes: vec!["vault:kv:read:/apps/*".into(), "workflow:write".into()],
+            expires_at: Some(t + 3600.0),
+        },
+        t,
+    )
+    .unwrap();
+    store
+        .insert(&minted.key, &minted.secret_hash)
+        .await
+        .unwrap();
+
+    let found = api_keys::authenticate(store, &minted.token, t)
+        .await
+        .unwrap()
+        .expect("minted token authenticates");
+    assert_eq!(found.id, minted.key.id);
+    assert_eq!(found.scopes, minted.key.scopes);
+    assert_eq!(found.expires_at, minted.key.expires_at);
+    let touched = store.get(&minted.key.id).await.unwrap().unwrap();
+    assert_eq!(touched.last_used_at, Some(t));
+
+    // Within the granularity window the row isn't rewritten.
+    api_keys::authenticate(store, &minted.token, t + 1.0)
+        .await
+        .unwrap()
+        .unwrap();
+    let untouched = store.get(&minted.key.id).await.unwrap().unwrap();
+    assert_eq!(untouched.last_used_at, Some(t));
+
+    // Expired and unknown tokens don't authenticate.
+    assert!(
+        api_keys::authenticate(store, &minted.token, t + 7200.0)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        api_keys::authenticate(store, "ak_not-a-real-token", t)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Moving the expiry brings an expired key back, and can drop it.
+    assert!(
+        store
+            .set_expires_at(&minted.key.id, Some(t + 10_800.0))
+            .await
+            .unwrap()
+    );
+    assert!(
+        api_keys::authenticate(store, &minted.token, t + 7200.0)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(store.set_expires_at(&minted.key.id, None).await.unwrap());
+    let unbounded = store.get(&minted.key.id).await.unwrap().unwrap();
+    assert_eq!(unbounded.expires_at, None);
+    assert!(!store.set_expires_at("no-such-key", None).await.unwrap());
+
+    assert!(
+        store
+            .list()
+            .await
+            .unwrap()
+            .iter()
+            .any(|k| k.id == minted.key.id && k.owner == owner)
+    );
+    // Expired keys under the named owner prefixes go in one sweep;
+    // live, unbounded and differently-owned ones stay.
+    let mut swept = Vec::new();
+    for (suffix, expires_at) in [
+        (":expired", Some(t - 1.0)),
+        (":live", Some(t + 3600.0)),
+        (":unbounded", None),
+        ("_other:expired", Some(t - 1.0)),
+    ] {
+        let key = api_keys::mint(
+            NewApiKey {
+                name: "login".into(),
+                owner: format!("{owner}{suffix}"),
+                scopes: vec!["workflow:read".into()],
+                expires_at,
+            },
+            t - 10.0,
+        )
+        .unwrap();
+        store.insert(&key.key, &key.secret_hash).await.unwrap();
+        swept.push(key.key.id);
+    }
+    let prefix = format!("{owner}:");
+    assert_eq!(store.delete_expired(&[&prefix], t).await.unwrap(), 1);
+    assert_eq!(store.delete_expired(&[&prefix], t).await.unwrap(), 0);
+    assert!(store.get(&swept[0]).await.unwrap().is_none());
+    for id in &swept[1..] {
+        assert!(store.get(id).await.unwrap().is_some());
+        assert!(store.delete(id).await.unwrap());
+    }
+
+    assert!(store.delete(&minted.key.id).await.unwrap());
+    assert!(!store.delete(&minted.key.id).await.unwrap());
+    assert!(store.get(&minted.key.id).await.unwrap().is_none());
+    assert!(
+        api_keys::authenticate(store, &minted.token, t)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+mod sqlite_store {
+    use super::*;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use assay_auth::AuthCtx;
+    use assay_auth::api_keys::SqliteApiKeyStore;
+    use assay_auth::audit::{AuditFilter, AuditStore, SqliteAuditStore};
+    use assay_auth::state::{AdminApiKeys, AuthCtxWithAdmin};
+    use assay_auth::store::{SqliteSessionStore, SqliteUserStore};
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode, header};
+    use serde_json::{Value, json};
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use tower::ServiceExt;
+
+    const ADMIN_KEY: &str = "admin-key";
+
+    async fn setup() -> SqlitePool {
+        let suffix = format!("{}_{}_api_keys", std::process::id(), uuid::Uuid::new_v4());
+        let engine_uri = format!("file:assay_eng_{suffix}?mode=memory&cache=shared");
+        let auth_uri = format!("file:assay_auth_{suffix}?mode=memory&cache=shared");
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .after_connect(move |connection, _meta| {
+                let engine_uri = engine_uri.clone();
+                let auth_uri = auth_uri.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{engine_uri}' AS engine").as_str())
+                        .await?;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{auth_uri}' AS auth").as_str())
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assay_auth::schema::migrate_sqlite(&pool).await.unwrap();
+        pool
+    }
+
+    fn app(pool: &SqlitePool) -> (Router, Arc<SqliteAuditStore>) {
+        let audit = Arc::new(SqliteAuditStore::new(pool.clone()));
+        let ctx = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool.clone())),
+        )
+        .with_audit(audit.clone())
+        .with_api_keys(Arc::new(SqliteApiKeyStore::new(pool.clone())));
+        let app = assay_auth::router::router::<AuthCtxWithAdmin>().with_state(
+            AuthCtxWithAdmin::new(ctx).with_admin_keys(AdminApiKeys::from_keys([ADMIN_KEY])),
+        );
+        (app, audit)
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        bearer: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {bearer}"));
+        let req = match body {
+            Some(body) => req
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => req.body(Body::empty()),
+        }
+        .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), 64 * 1024).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, json)
+    }
+
+    async fn create(app: &Router, name: &str, scopes: Value, expires_at: Option<f64>) -> Value {
+        let (status, body) = call(
+            app,
+            "POST",
+            "/admin/api-keys",
+            ADMIN_KEY,
+            Some(json!({
+                "name": name,
+                "owner": "platform",
+                "scopes": scopes,
+                "expires_at": expires_at,
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        body
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let pool = setup().await;
+        exercise_api_key_store(&SqliteApiKeyStore::new(pool), "platform").await;
+    }
+
+    #[tokio::test]
+    async fn admins_mint_list_and_revoke_keys() {
+        let pool = setup().await;
+        let (app, audit) = app(&pool);
+
+        let created = create(&app, "ci", json!(["workflow:namespace=prod:write"]), None).await;
+        let token = created["token"].as_str().unwrap();
+        let id = created["key"]["id"].as_str().unwrap();
+        assert!(token.starts_with("ak_"));
+        assert!(token.starts_with(created["key"]["prefix"].as_str().unwrap()));
+
+        let (status, list) = call(&app, "GET", "/admin/api-keys", ADMIN_KEY, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(list["enabled"], json!(true));
+        let items = list["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], json!(id));
+        assert_eq!(items[0]["scopes"], json!(["workflow:namespace=prod:write"]));
+        // The listing never carries a secret.
+        assert!(!list.to_string().contains(token));
+
+        let (status, body) = call(
+            &app,
+            "POST",
+            "/admin/api-keys",
+            ADMIN_KEY,
+            Some(json!({"name": "bad", "owner": "x", "scopes": ["vault:kv:delete"]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], json!("invalid_request"));
+
+        let (status, _) = call(
+            &app,
+            "DELETE",
+            &format!("/admin/api-keys/{id}"),
+            ADMIN_KEY,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(
+            &app,
+            "DELETE",
+            &format!("/admin/api-keys/{id}"),
+            ADMIN_KEY,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let actions: Vec<String> = audit
+            .list(&AuditFilter::default(), 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.action)
+            .collect();
+        assert!(actions.contains(&"auth.api_key.create".to_string()));
+        assert!(actions.contains(&"auth.api_key.revoke".to_string()));
+    }
+
+    #[tokio::test]
+    async fn scoped_keys_reach_only_what_they_name() {
+        let pool = setup().await;
+        let (app, _) = app(&pool);
+
+        let reader = create(&app, "reader", json!(["auth:read"]), None).await;
+        let reader = reader["token"].as_str().unwrap();
+        let (status, _) = call(&app, "GET", "/admin/users", reader, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = call(
+            &app,
+            "POST",
+            "/admin/users",
+            reader,
+            Some(json!({"email": "new@example.com"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body.to_string().contains("auth:write"), "{body}");
+
+        // Keys can't manage keys, whatever their scopes.
+        let root = create(&app, "root", json!(["*"]), None).await;
+        let root = root["token"].as_str().unwrap();
+        let (status, _) = call(&app, "GET", "/admin/users", root, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "GET", "/admin/api-keys", root, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let vault_only = create(&app, "vault", json!(["vault:write"]), None).await;
+        let vault_only = vault_only["token"].as_str().unwrap();
+        let (status, _) = call(&app, "GET", "/admin/users", vault_only, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let expired = create(&app, "old", json!(["auth:read"]), Some(now() - 60.0)).await;
+        let expired = expired["token"].as_str().unwrap();
+        let (status, _) = call(&app, "GET", "/admin/users", expired, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(&app, "GET", "/admin/users", "ak_forged", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod postgres_store {
+    use super::*;
+    use assay_auth::api_keys::PostgresApiKeyStore;
+
+    async fn setup() -> Option<sqlx::PgPool> {
+        let url = std::env::var("ASSAY_TEST_DATABASE_URL").ok()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        let pool = sqlx::PgPool::connect(&url).await.ok()?;
+        sqlx::query("CREATE SCHEMA IF NOT EXISTS engine")
+            .execute(&pool)
+            .await
+            .ok()?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .ok()?;
+        assay_auth::schema::migrate_postgres(&pool).await.ok()?;
+        Some(pool)
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let Some(pool) = setup().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        let owner = format!("owner-{}", uuid::Uuid::new_v4());
+        exercise_api_key_store(&PostgresApiKeyStore::new(pool), &owner).await;
+    }
+}
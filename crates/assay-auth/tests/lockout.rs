@@ -0,0 +1,750 @@
+//! Integration tests for login brute-force protection and the audit
+//! log it writes to.
+//!
+//! - **SQLite** — always-on. Store contracts plus the HTTP flow:
+//!   account lockout, admin unlock, success resetting the counter,
+//!   per-IP lockout across accounts, recovery-token guessing, and
+//!   second-factor guessing counted against the account.
+//! - **Postgres** — store contracts only, gated on
+//!   `ASSAY_TEST_DATABASE_URL`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use assay_auth::audit::{AuditFilter, AuditStore};
+use assay_auth::lockout::{Lockout, LockoutStore};
+use serde_json::json;
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
+
+/// Backend-neutral lockout store contract. `key` must be unique to the
+/// run so shared Postgres databases don't see earlier rows.
+async fn exercise_lockout_store(store: &dyn LockoutStore, key: &str) {
+    let t = now();
+    assert_eq!(
+        store
+            .record_failure("account", key, t - 100.0, t - 200.0)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        store
+            .record_failure("account", key, t - 10.0, t - 200.0)
+            .await
+            .unwrap(),
+        2
+    );
+    // The window moved past the first failure.
+    assert_eq!(
+        store
+            .record_failure("account", key, t, t - 50.0)
+            .await
+            .unwrap(),
+        2
+    );
+    // Scopes don't share counters.
+    assert_eq!(
+        store.record_failure("ip", key, t, t - 50.0).await.unwrap(),
+        1
+    );
+    store.clear_failures("ip", key).await.unwrap();
+    assert_eq!(
+        store.record_failure("ip", key, t, t - 50.0).await.unwrap(),
+        1
+    );
+
+    assert!(
+        store
+            .locked_until("account", key, t)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let lockout = Lockout {
+        scope: "account".into(),
+        key: key.into(),
+        failures: 2,
+        locked_at: t,
+        locked_until: t + 60.0,
+    };
+    store.lock(&lockout).await.unwrap();
+    assert_eq!(
+        store.locked_until("account", key, t).await.unwrap(),
+        Some(t + 60.0)
+    );
+    assert!(
+        store
+            .locked_until("account", key, t + 61.0)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    // Locking resets the counter.
+    assert_eq!(
+        store
+            .record_failure("account", key, t + 1.0, t - 50.0)
+            .await
+            .unwrap(),
+        1
+    );
+    let listed = store.list_locks(t).await.unwrap();
+    assert!(listed.contains(&lockout));
+    assert!(!store.list_locks(t + 61.0).await.unwrap().contains(&lockout));
+
+    assert!(store.unlock("account", key, t).await.unwrap());
+    assert!(!store.unlock("account", key, t).await.unwrap());
+    assert!(
+        store
+            .locked_until("account", key, t)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        store
+            .record_failure("account", key, t + 2.0, t - 50.0)
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+/// Backend-neutral audit store contract. `actor` must be unique to the
+/// run.
+async fn exercise_audit_store(store: &dyn AuditStore, actor: &str) {
+    store
+        .append(
+            actor,
+            "auth.lockout",
+            Some("account:a"),
+            &json!({"n": 1}),
+            10.0,
+        )
+        .await
+        .unwrap();
+    store
+        .append(actor, "auth.unlock", None, &json!({}), 20.0)
+        .await
+        .unwrap();
+    store
+        .append(
+            actor,
+            "auth.lockout",
+            Some("ip:1.2.3.4"),
+            &json!({"n": 2}),
+            30.0,
+        )
+        .await
+        .unwrap();
+
+    let mine = AuditFilter {
+        actor: Some(actor.to_string()),
+        ..AuditFilter::default()
+    };
+    let all = store.list(&mine, 10, 0).await.unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(store.count(&mine).await.unwrap(), 3);
+    // Newest first.
+    assert_eq!(all[0].created_at, 30.0);
+    assert_eq!(all[0].target.as_deref(), Some("ip:1.2.3.4"));
+    assert_eq!(all[0].detail, json!({"n": 2}));
+    assert_eq!(all[1].target, None);
+
+    let lockouts = AuditFilter {
+        action: Some("auth.lockout".into()),
+        ..mine.clone()
+    };
+    assert_eq!(store.count(&lockouts).await.unwrap(), 2);
+    let window = AuditFilter {
+        since: Some(15.0),
+        until: Some(25.0),
+        ..mine.clone()
+    };
+    let windowed = store.list(&window, 10, 0).await.unwrap();
+    assert_eq!(windowed.len(), 1);
+    assert_eq!(windowed[0].action, "auth.unlock");
+
+    let page = store.list(&mine, 1, 1).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].created_at, 20.0);
+}
+
+#[cfg(feature = "backend-sqlite")]
+mod sqlite_store {
+    use super::*;
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use assay_auth::AuthCtx;
+    use assay_auth::audit::SqliteAuditStore;
+    use assay_auth::lockout::{LockoutPolicy, LoginThrottle, SqliteLockoutStore};
+    use assay_auth::password::PasswordHasher;
+    use assay_auth::recovery::{PasswordRecovery, RecoveryMailer, SqliteRecoveryStore};
+    use assay_auth::state::{AdminApiKeys, AuthCtxWithAdmin};
+    use assay_auth::store::{SqliteSessionStore, SqliteUserStore};
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode, header};
+    use serde_json::Value;
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use tower::ServiceExt;
+
+    const ADMIN_KEY: &str = "admin-key";
+
+    async fn setup() -> SqlitePool {
+        let suffix = format!("{}_{}_lockout", std::process::id(), uuid::Uuid::new_v4());
+        let engine_uri = format!("file:assay_eng_{suffix}?mode=memory&cache=shared");
+        let auth_uri = format!("file:assay_auth_{suffix}?mode=memory&cache=shared");
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .after_connect(move |connection, _meta| {
+                let engine_uri = engine_uri.clone();
+                let auth_uri = auth_uri.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{engine_uri}' AS engine").as_str())
+                        .await?;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{auth_uri}' AS auth").as_str())
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assay_auth::schema::migrate_sqlite(&pool).await.unwrap();
+        pool
+    }
+
+    async fn insert_user(pool: &SqlitePool, id: &str, email: &str, password: &str) {
+        let hash = PasswordHasher::default().hash(password).unwrap();
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES (?, ?, 1, NULL, ?, 1)",
+        )
+        .bind(id)
+        .bind(email)
+        .bind(hash)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    struct NoMail;
+
+    #[async_trait::async_trait]
+    impl RecoveryMailer for NoMail {
+        async fn send(&self, _recipient: &str, _reset_url: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn app(pool: &SqlitePool) -> (Router, Arc<SqliteAuditStore>) {
+        let audit = Arc::new(SqliteAuditStore::new(pool.clone()));
+        let policy = LockoutPolicy {
+            max_account_failures: 3,
+            max_ip_failures: 5,
+            free_attempts: 1,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            trust_forwarded_for: true,
+            ..LockoutPolicy::default()
+        };
+        let throttle = LoginThrottle::new(Arc::new(SqliteLockoutStore::new(pool.clone())), policy)
+            .with_audit(audit.clone());
+        let recovery = PasswordRecovery::new(
+            Arc::new(SqliteRecoveryStore::new(pool.clone())),
+            Arc::new(NoMail),
+            url::Url::parse("https://auth.example.com/recover").unwrap(),
+            Duration::from_secs(900),
+            Duration::from_secs(60),
+        );
+        let ctx = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool.clone())),
+        )
+        .with_audit(audit.clone())
+        .with_lockout(throttle)
+        .with_recovery(recovery);
+        #[cfg(feature = "auth-mfa")]
+        let ctx = ctx.with_mfa(mfa(pool));
+        let app = assay_auth::router::router::<AuthCtxWithAdmin>().with_state(
+            AuthCtxWithAdmin::new(ctx).with_admin_keys(AdminApiKeys::from_keys([ADMIN_KEY])),
+        );
+        (app, audit)
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        ip: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Option<String>, Value) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("x-forwarded-for", format!("198.51.100.1, {ip}"))
+            .header(header::AUTHORIZATION, format!("Bearer {ADMIN_KEY}"));
+        let req = match body {
+            Some(body) => req
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => req.body(Body::empty()),
+        }
+        .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let bytes = to_bytes(response.into_body(), 64 * 1024).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, retry_after, json)
+    }
+
+    fn login(email: &str, password: &str) -> Option<Value> {
+        Some(json!({"email": email, "password": password}))
+    }
+
+    #[cfg(feature = "auth-mfa")]
+    fn mfa(pool: &SqlitePool) -> assay_auth::mfa::MfaManager {
+        use assay_auth::mfa::{MfaManager, SqliteMfaStore};
+        MfaManager::new(Arc::new(SqliteMfaStore::new(pool.clone())), "Assay Test")
+    }
+
+    /// Enroll `user_id` in TOTP and return a code it never accepts.
+    #[cfg(feature = "auth-mfa")]
+    async fn enroll_totp(pool: &SqlitePool, user_id: &str, email: &str) -> &'static str {
+        use assay_auth::mfa::{TOTP_PERIOD_SECS, totp_at};
+        let mfa = mfa(pool);
+        let secret = mfa
+            .begin_enrollment(user_id, email)
+            .await
+            .unwrap()
+            .unwrap()
+            .secret;
+        let key = data_encoding::BASE32_NOPAD
+            .decode(secret.as_bytes())
+            .unwrap();
+        let step = now() as i64 / TOTP_PERIOD_SECS as i64;
+        let code = format!("{:06}", totp_at(&key, step));
+        mfa.confirm_enrollment(user_id, &code)
+            .await
+            .unwrap()
+            .unwrap();
+        // Neither a TOTP code nor a recovery code looks like this.
+        "not-a-code"
+    }
+
+    #[cfg(feature = "auth-mfa")]
+    async fn mfa_token(app: &Router, ip: &str, email: &str, password: &str) -> String {
+        let (status, _, body) = call(app, "POST", "/login", ip, login(email, password)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["mfa_required"], true);
+        body["mfa_token"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn store_contracts() {
+        let pool = setup().await;
+        exercise_lockout_store(&SqliteLockoutStore::new(pool.clone()), "alice@example.com").await;
+        exercise_audit_store(&SqliteAuditStore::new(pool), "tester").await;
+    }
+
+    #[tokio::test]
+    async fn repeated_failures_lock_the_account_until_an_admin_unlocks_it() {
+        let pool = setup().await;
+        insert_user(&pool, "user-alice", "alice@example.com", "correct horse").await;
+        let (app, audit) = app(&pool);
+
+        for _ in 0..2 {
+            let (status, _, _) = call(
+                &app,
+                "POST",
+                "/login",
+                "203.0.113.1",
+                login("alice@example.com", "wrong"),
+            )
+            .await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+        // The third failure trips the lock.
+        let (status, retry_after, body) = call(
+            &app,
+            "POST",
+            "/login",
+            "203.0.113.2",
+            login("alice@example.com", "wrong"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"], "too_many_attempts");
+        assert!(retry_after.unwrap().parse::<u64>().unwrap() > 800);
+
+        // Right password, different case, different IP — still locked.
+        let (status, retry_after, _) = call(
+            &app,
+            "POST",
+            "/login",
+            "203.0.113.3",
+            login("ALICE@example.com", "correct horse"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(retry_after.is_some());
+
+        let events = audit
+            .list(
+                &AuditFilter {
+                    action: Some("auth.lockout".into()),
+                    ..AuditFilter::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].actor, "system");
+        assert_eq!(
+            events[0].target.as_deref(),
+            Some("account:alice@example.com")
+        );
+        assert_eq!(events[0].detail["ip"], "203.0.113.2");
+
+        let (status, _, body) = call(&app, "GET", "/admin/lockouts", "10.0.0.1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let items = body["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["scope"], "account");
+        assert_eq!(items[0]["key"], "alice@example.com");
+
+        let (status, _, _) = call(
+            &app,
+            "DELETE",
+            "/admin/users/user-alice/lockout",
+            "10.0.0.1",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _, _) = call(
+            &app,
+            "DELETE",
+            "/admin/users/user-alice/lockout",
+            "10.0.0.1",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, body) = call(
+            &app,
+            "POST",
+            "/login",
+            "203.0.113.4",
+            login("alice@example.com", "correct horse"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["user_id"], "user-alice");
+
+        let (status, _, body) = call(
+            &app,
+            "GET",
+            "/admin/audit?action=auth.unlock",
+            "10.0.0.1",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["enabled"], true);
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["items"][0]["actor"], "admin");
+        assert_eq!(body["items"][0]["target"], "account:alice@example.com");
+    }
+
+    #[tokio::test]
+    async fn a_successful_login_resets_the_account_counter() {
+        let pool = setup().await;
+        insert_user(&pool, "user-bob", "bob@example.com", "hunter22").await;
+        let (app, _) = app(&pool);
+
+        for round in 0..2 {
+            for _ in 0..2 {
+                let (status, _, _) = call(
+                    &app,
+                    "POST",
+                    "/login",
+                    &format!("203.0.113.{}", 10 + round),
+                    login("bob@example.com", "nope"),
+                )
+                .await;
+                assert_eq!(status, StatusCode::UNAUTHORIZED);
+            }
+            let (status, _, _) = call(
+                &app,
+                "POST",
+                "/login",
+                "203.0.113.20",
+                login("bob@example.com", "hunter22"),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+    }
+
+    #[cfg(feature = "auth-mfa")]
+    #[tokio::test]
+    async fn guessing_mfa_codes_from_rotating_ips_locks_the_account() {
+        let pool = setup().await;
+        insert_user(&pool, "user-dave", "dave@example.com", "pa55word").await;
+        let wrong = enroll_totp(&pool, "user-dave", "dave@example.com").await;
+        let (app, _) = app(&pool);
+
+        let token = mfa_token(&app, "203.0.113.30", "dave@example.com", "pa55word").await;
+        let mut statuses = Vec::new();
+        for n in 0..3 {
+            let (status, _, _) = call(
+                &app,
+                "POST",
+                "/login/mfa",
+                &format!("203.0.113.{}", 31 + n),
+                Some(json!({"mfa_token": token, "code": wrong})),
+            )
+            .await;
+            statuses.push(status);
+        }
+        assert_eq!(statuses[..2], [StatusCode::UNAUTHORIZED; 2]);
+        assert_eq!(statuses[2], StatusCode::TOO_MANY_REQUESTS);
+
+        // The password alone no longer even yields a challenge.
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/login",
+            "203.0.113.40",
+            login("dave@example.com", "pa55word"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[cfg(feature = "auth-mfa")]
+    #[tokio::test]
+    async fn a_right_password_owing_a_second_factor_keeps_the_account_counter() {
+        let pool = setup().await;
+        insert_user(&pool, "user-erin", "erin@example.com", "pa55word").await;
+        let wrong = enroll_totp(&pool, "user-erin", "erin@example.com").await;
+        let (app, _) = app(&pool);
+
+        for _ in 0..2 {
+            let (status, _, _) = call(
+                &app,
+                "POST",
+                "/login",
+                "203.0.113.50",
+                login("erin@example.com", "nope"),
+            )
+            .await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+        // The right password doesn't clear the two failures, so one
+        // wrong code is the third.
+        let token = mfa_token(&app, "203.0.113.51", "erin@example.com", "pa55word").await;
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/login/mfa",
+            "203.0.113.52",
+            Some(json!({"mfa_token": token, "code": wrong})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn one_ip_spraying_many_accounts_gets_locked_out() {
+        let pool = setup().await;
+        insert_user(&pool, "user-carol", "carol@example.com", "s3cret").await;
+        let (app, _) = app(&pool);
+        let attacker = "192.0.2.66";
+
+        let mut statuses = Vec::new();
+        for n in 0..5 {
+            let (status, _, _) = call(
+                &app,
+                "POST",
+                "/login",
+                attacker,
+                login(&format!("victim{n}@example.com"), "guess"),
+            )
+            .await;
+            statuses.push(status);
+        }
+        assert_eq!(statuses[..4], [StatusCode::UNAUTHORIZED; 4]);
+        assert_eq!(statuses[4], StatusCode::TOO_MANY_REQUESTS);
+
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/login",
+            attacker,
+            login("carol@example.com", "s3cret"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        // Only the client's own hop counts — the spoofable leading
+        // X-Forwarded-For entry is the same for every request here.
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/login",
+            "192.0.2.67",
+            login("carol@example.com", "s3cret"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _, _) =
+            call(&app, "DELETE", "/admin/lockouts/bogus/x", "10.0.0.1", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _, _) = call(
+            &app,
+            "DELETE",
+            &format!("/admin/lockouts/ip/{attacker}"),
+            "10.0.0.1",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/login",
+            attacker,
+            login("carol@example.com", "s3cret"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn guessing_recovery_tokens_locks_the_ip() {
+        let pool = setup().await;
+        let (app, _) = app(&pool);
+        let guesser = "192.0.2.99";
+
+        for _ in 0..4 {
+            let (status, _, body) = call(
+                &app,
+                "POST",
+                "/password/recovery/complete",
+                guesser,
+                Some(json!({"token": "guess", "password": "new-password"})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], "invalid_or_expired_token");
+        }
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/password/recovery/complete",
+            guesser,
+            Some(json!({"token": "guess", "password": "new-password"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/password/recovery/request",
+            guesser,
+            Some(json!({"email": "anyone@example.com"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        let (status, _, _) = call(
+            &app,
+            "POST",
+            "/password/recovery/request",
+            "192.0.2.100",
+            Some(json!({"email": "anyone@example.com"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+mod postgres_store {
+    use super::*;
+    use assay_auth::audit::PostgresAuditStore;
+    use assay_auth::lockout::PostgresLockoutStore;
+
+    async fn setup() -> Option<sqlx::PgPool> {
+        let url = std::env::var("ASSAY_TEST_DATABASE_URL").ok()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        let pool = sqlx::PgPool::connect(&url).await.ok()?;
+        sqlx::query("CREATE SCHEMA IF NOT EXISTS engine")
+            .execute(&pool)
+            .await
+            .ok()?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .ok()?;
+        assay_auth::schema::migrate_postgres(&pool).await.ok()?;
+        Some(pool)
+    }
+
+    #[tokio::test]
+    async fn store_contracts() {
+        let Some(pool) = setup().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        let run = uuid::Uuid::new_v4();
+        exercise_lockout_store(
+            &PostgresLockoutStore::new(pool.clone()),
+            &format!("{run}@example.com"),
+        )
+        .await;
+        exercise_audit_store(&PostgresAuditStore::new(pool), &format!("tester-{run}")).await;
+    }
+}
@@ -0,0 +1,452 @@
+//! Integration tests for the TOTP + recovery-code second factor.
+//!
+//! - **SQLite** — always-on. Store contract plus the full HTTP flow:
+//!   enroll, confirm, two-step login, recovery-code single use, reset.
+//! - **Postgres** — store contract only, gated on
+//!   `ASSAY_TEST_DATABASE_URL`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use assay_auth::mfa::{MfaStore, TOTP_PERIOD_SECS, totp_at};
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
+
+fn current_code(secret: &str) -> String {
+    let key = data_encoding::BASE32_NOPAD
+        .decode(secret.as_bytes())
+        .unwrap();
+    let step = now() as i64 / TOTP_PERIOD_SECS as i64;
+    format!("{:06}", totp_at(&key, step))
+}
+
+/// Backend-neutral store contract. `user_id` must already exist.
+async fn exercise_store(store: &dyn MfaStore, user_id: &str) {
+    assert!(store.totp(user_id).await.unwrap().is_none());
+    assert!(store.stage_totp(user_id, "FIRST", 10.0).await.unwrap());
+    // Restaging an unconfirmed secret replaces it.
+    assert!(store.stage_totp(user_id, "SECOND", 11.0).await.unwrap());
+    let staged = store.totp(user_id).await.unwrap().unwrap();
+    assert_eq!(staged.secret, "SECOND");
+    assert!(staged.confirmed_at.is_none());
+
+    let hashes = vec!["h1".to_string(), "h2".to_string()];
+    assert!(
+        store
+            .confirm_totp(user_id, 100, &hashes, 12.0)
+            .await
+            .unwrap()
+    );
+    let confirmed = store.totp(user_id).await.unwrap().unwrap();
+    assert_eq!(confirmed.confirmed_at, Some(12.0));
+    assert_eq!(confirmed.last_used_step, Some(100));
+    // A confirmed enrollment can't be overwritten by a new stage.
+    assert!(!store.stage_totp(user_id, "THIRD", 13.0).await.unwrap());
+
+    assert!(!store.advance_totp_step(user_id, 100).await.unwrap());
+    assert!(store.advance_totp_step(user_id, 101).await.unwrap());
+
+    assert_eq!(store.remaining_recovery_codes(user_id).await.unwrap(), 2);
+    assert!(
+        store
+            .redeem_recovery_code(user_id, "h1", 14.0)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !store
+            .redeem_recovery_code(user_id, "h1", 15.0)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !store
+            .redeem_recovery_code(user_id, "nope", 15.0)
+            .await
+            .unwrap()
+    );
+    assert_eq!(store.remaining_recovery_codes(user_id).await.unwrap(), 1);
+    store
+        .replace_recovery_codes(user_id, &["h3".to_string()], 16.0)
+        .await
+        .unwrap();
+    assert!(
+        !store
+            .redeem_recovery_code(user_id, "h2", 17.0)
+            .await
+            .unwrap()
+    );
+    assert_eq!(store.remaining_recovery_codes(user_id).await.unwrap(), 1);
+
+    store
+        .create_challenge("ch1", user_id, 20.0, 320.0)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        assert_eq!(
+            store.attempt_challenge("ch1", 21.0, 2).await.unwrap(),
+            Some(user_id.to_string())
+        );
+    }
+    assert!(
+        store
+            .attempt_challenge("ch1", 22.0, 2)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    store
+        .create_challenge("ch2", user_id, 20.0, 320.0)
+        .await
+        .unwrap();
+    assert!(
+        store
+            .attempt_challenge("ch2", 400.0, 5)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    store
+        .create_challenge("ch3", user_id, 20.0, 320.0)
+        .await
+        .unwrap();
+    store.delete_challenge("ch3").await.unwrap();
+    assert!(
+        store
+            .attempt_challenge("ch3", 21.0, 5)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    assert!(store.reset(user_id).await.unwrap());
+    assert!(!store.reset(user_id).await.unwrap());
+    assert!(store.totp(user_id).await.unwrap().is_none());
+    assert_eq!(store.remaining_recovery_codes(user_id).await.unwrap(), 0);
+}
+
+#[cfg(feature = "backend-sqlite")]
+mod sqlite_store {
+    use super::*;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use assay_auth::AuthCtx;
+    use assay_auth::mfa::{MfaManager, RECOVERY_CODE_COUNT, SqliteMfaStore};
+    use assay_auth::session::SESSION_COOKIE;
+    use assay_auth::store::{SessionStore, SqliteSessionStore, SqliteUserStore};
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::extract::FromRef;
+    use axum::http::{Request, StatusCode, header};
+    use serde_json::Value;
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use tower::ServiceExt;
+
+    #[derive(Clone)]
+    struct TestState {
+        auth: AuthCtx,
+    }
+
+    impl FromRef<TestState> for AuthCtx {
+        fn from_ref(state: &TestState) -> Self {
+            state.auth.clone()
+        }
+    }
+
+    async fn setup() -> SqlitePool {
+        let suffix = format!("{}_{}_mfa", std::process::id(), uuid::Uuid::new_v4());
+        let engine_uri = format!("file:assay_eng_{suffix}?mode=memory&cache=shared");
+        let auth_uri = format!("file:assay_auth_{suffix}?mode=memory&cache=shared");
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .after_connect(move |connection, _meta| {
+                let engine_uri = engine_uri.clone();
+                let auth_uri = auth_uri.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{engine_uri}' AS engine").as_str())
+                        .await?;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{auth_uri}' AS auth").as_str())
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assay_auth::schema::migrate_sqlite(&pool).await.unwrap();
+        pool
+    }
+
+    async fn insert_user(pool: &SqlitePool, id: &str, email: &str, password_hash: &str) {
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES (?, ?, 1, NULL, ?, 1)",
+        )
+        .bind(id)
+        .bind(email)
+        .bind(password_hash)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        session: Option<(&str, &str)>,
+        body: Option<Value>,
+    ) -> (StatusCode, Option<String>, Value) {
+        let mut req = Request::builder().method(method).uri(uri);
+        if let Some((sid, csrf)) = session {
+            req = req
+                .header(header::COOKIE, format!("{SESSION_COOKIE}={sid}"))
+                .header("x-csrf-token", csrf);
+        }
+        let req = match body {
+            Some(body) => req
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => req.body(Body::empty()),
+        }
+        .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let sid = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .find_map(|v| v.strip_prefix(&format!("{SESSION_COOKIE}=")))
+            .and_then(|v| v.split(';').next())
+            .map(str::to_string);
+        let bytes = to_bytes(response.into_body(), 64 * 1024).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, sid, json)
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let pool = setup().await;
+        insert_user(&pool, "user-store", "store@example.com", "x").await;
+        exercise_store(&SqliteMfaStore::new(pool), "user-store").await;
+    }
+
+    #[tokio::test]
+    async fn enrolled_password_logins_need_a_second_factor() {
+        let pool = setup().await;
+        let hash = assay_auth::password::PasswordHasher::default()
+            .hash("correct horse")
+            .unwrap();
+        insert_user(&pool, "user-mfa", "ops@example.com", &hash).await;
+        let sessions = Arc::new(SqliteSessionStore::new(pool.clone()));
+        let mfa = MfaManager::new(Arc::new(SqliteMfaStore::new(pool.clone())), "Assay Test");
+        let auth = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            sessions.clone(),
+        )
+        .with_mfa(mfa.clone());
+        let app = assay_auth::session::router::<TestState>()
+            .merge(assay_auth::mfa::router::<TestState>())
+            .with_state(TestState { auth });
+        let login = serde_json::json!({"email": "ops@example.com", "password": "correct horse"});
+
+        // Not enrolled yet: a password alone signs in.
+        let (status, sid, body) = call(&app, "POST", "/login", None, Some(login.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let sid = sid.expect("session cookie");
+        let csrf = body["csrf_token"].as_str().unwrap().to_string();
+        let session = sessions.get(&sid).await.unwrap().unwrap();
+        assert_eq!(session.amr, vec!["pwd"]);
+        let cookie = Some((sid.as_str(), csrf.as_str()));
+
+        let (status, _, _) =
+            call(&app, "POST", "/mfa/totp/enroll", Some((&sid, "bad")), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _, provisioning) = call(&app, "POST", "/mfa/totp/enroll", cookie, None).await;
+        assert_eq!(status, StatusCode::OK, "{provisioning}");
+        let secret = provisioning["secret"].as_str().unwrap();
+        let uri = provisioning["provisioning_uri"].as_str().unwrap();
+        assert!(
+            uri.starts_with("otpauth://totp/Assay%20Test:ops%40example.com?"),
+            "{uri}"
+        );
+        assert!(uri.contains(&format!("secret={secret}")), "{uri}");
+
+        let wrong = serde_json::json!({"code": "000000"});
+        let (status, _, _) = call(&app, "POST", "/mfa/totp/confirm", cookie, Some(wrong)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let code = serde_json::json!({"code": current_code(secret)});
+        let (status, _, confirmed) =
+            call(&app, "POST", "/mfa/totp/confirm", cookie, Some(code)).await;
+        assert_eq!(status, StatusCode::OK, "{confirmed}");
+        let recovery: Vec<String> =
+            serde_json::from_value(confirmed["recovery_codes"].clone()).unwrap();
+        assert_eq!(recovery.len(), RECOVERY_CODE_COUNT);
+        let stored: Vec<String> =
+            sqlx::query_scalar("SELECT code_hash FROM auth.mfa_recovery_codes")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert!(stored.iter().all(|hash| !recovery.contains(hash)));
+        let (status, _, _) = call(&app, "POST", "/mfa/totp/enroll", cookie, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // Enrolled: the password now yields a challenge, not a session.
+        let (status, sid, challenge) =
+            call(&app, "POST", "/login", None, Some(login.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(sid.is_none());
+        assert_eq!(challenge["mfa_required"], true);
+        let token = challenge["mfa_token"].as_str().unwrap().to_string();
+
+        // The confirmation already spent the current TOTP step.
+        let replay = serde_json::json!({"mfa_token": token, "code": current_code(secret)});
+        let (status, _, _) = call(&app, "POST", "/login/mfa", None, Some(replay)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let typed = recovery[0].to_uppercase().replace('-', " ");
+        let redeem = serde_json::json!({"mfa_token": token, "code": typed});
+        let (status, sid, body) =
+            call(&app, "POST", "/login/mfa", None, Some(redeem.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let session = sessions.get(&sid.unwrap()).await.unwrap().unwrap();
+        assert_eq!(session.amr, vec!["pwd", "otp", "mfa"]);
+        // The challenge is single-use…
+        let (status, _, _) = call(&app, "POST", "/login/mfa", None, Some(redeem)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        // …and so is the recovery code.
+        let (_, _, challenge) = call(&app, "POST", "/login", None, Some(login.clone())).await;
+        let reuse = serde_json::json!({"mfa_token": challenge["mfa_token"], "code": recovery[0]});
+        let (status, _, _) = call(&app, "POST", "/login/mfa", None, Some(reuse)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _, status_body) = call(&app, "GET", "/mfa", cookie, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(status_body["totp_enabled"], true);
+        assert_eq!(
+            status_body["recovery_codes_remaining"],
+            (RECOVERY_CODE_COUNT - 1) as i64
+        );
+
+        // Operator reset drops back to password-only logins.
+        assert!(mfa.reset("user-mfa").await.unwrap());
+        let (status, sid, _) = call(&app, "POST", "/login", None, Some(login)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(sid.is_some());
+    }
+
+    #[tokio::test]
+    async fn challenges_die_after_too_many_wrong_codes() {
+        let pool = setup().await;
+        insert_user(&pool, "user-lock", "lock@example.com", "x").await;
+        let store = Arc::new(SqliteMfaStore::new(pool));
+        let mfa = MfaManager::new(store.clone(), "Assay");
+        store
+            .stage_totp("user-lock", "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", 1.0)
+            .await
+            .unwrap();
+        store.confirm_totp("user-lock", 0, &[], 1.0).await.unwrap();
+        let token = mfa.start_challenge("user-lock").await.unwrap();
+        for _ in 0..assay_auth::mfa::MAX_CHALLENGE_ATTEMPTS {
+            assert!(
+                mfa.complete_challenge(&token, "000000")
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        let code = current_code("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert!(
+            mfa.complete_challenge(&token, &code)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let fresh = mfa.start_challenge("user-lock").await.unwrap();
+        assert_eq!(
+            mfa.complete_challenge(&fresh, &code)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("user-lock")
+        );
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+mod postgres_store {
+    use super::*;
+    use assay_auth::mfa::PostgresMfaStore;
+
+    async fn setup() -> Option<sqlx::PgPool> {
+        let url = std::env::var("ASSAY_TEST_DATABASE_URL").ok()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        let pool = sqlx::PgPool::connect(&url).await.ok()?;
+        sqlx::query("CREATE SCHEMA IF NOT EXISTS engine")
+            .execute(&pool)
+            .await
+            .ok()?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .ok()?;
+        assay_auth::schema::migrate_postgres(&pool).await.ok()?;
+        Some(pool)
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let Some(pool) = setup().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        let user_id = format!("user-mfa-{}", uuid::Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES ($1, NULL, FALSE, NULL, NULL, 1)",
+        )
+        .bind(&user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        exercise_store(&PostgresMfaStore::new(pool.clone()), &user_id).await;
+        sqlx::query("DELETE FROM auth.users WHERE id = $1")
+            .bind(&user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
@@ -19,6 +19,11 @@
 //! 7. Consent grant upsert + replay.
 //! 8. Upstream-state take is single-use.
 //! 9. id_token claim builder respects scopes.
+//! 10. `client_credentials` over `/token` + `/introspect` (SQLite).
+//! 11. Device-code store contract (both backends) + the RFC 8628 flow
+//!     over `/device_authorization`, `/device` and `/token` (SQLite).
+//! 12. `private_key_jwt` assertions are single-use: the `jti` replay
+//!     cache (both backends) and a replay over `/token` (SQLite).
 //!
 //! The full HTTP-handler coverage (round-trip /authorize → /token →
 //! /userinfo via reqwest against a live axum app) lands in phase 8 once
@@ -115,6 +120,7 @@ fn authorize_request_validation_full_round_trip() {
         code_challenge_method: Some("S256".to_string()),
         prompt: None,
         max_age: None,
+        organization: None,
     };
     match validate(&req, &client) {
         AuthorizeValidation::Ok { scopes } => {
@@ -175,6 +181,71 @@ fn mint_refresh_token_is_unique_and_hashable() {
     assert_eq!(h, hash_refresh_token(&a));
 }
 
+/// Device-code store contract shared by both backends: lookup by user
+/// code, the first decision wins, and consume is single-use.
+async fn device_code_store_contract(store: &dyn assay_auth::oidc_provider::OidcDeviceCodeStore) {
+    use assay_auth::oidc_provider::device::build_device_code;
+    use assay_auth::oidc_provider::types::DeviceCodeStatus;
+
+    let code = build_device_code("cli", vec!["openid".to_string()]);
+    store.create(&code).await.expect("create");
+    let loaded = store
+        .get_by_user_code(&code.user_code)
+        .await
+        .expect("get_by_user_code")
+        .expect("present");
+    assert_eq!(loaded, code);
+
+    // Not approved yet — nothing to consume.
+    assert!(store.consume(&code.device_code).await.unwrap().is_none());
+    assert!(store.record_poll(&code.device_code, 5.0, 10).await.unwrap());
+    let polled = store.get(&code.device_code).await.unwrap().unwrap();
+    assert_eq!((polled.last_polled_at, polled.interval), (Some(5.0), 10));
+
+    assert!(store.decide(&code.user_code, "u1", true).await.unwrap());
+    assert!(!store.decide(&code.user_code, "u2", false).await.unwrap());
+    let consumed = store
+        .consume(&code.device_code)
+        .await
+        .expect("consume")
+        .expect("approved");
+    assert_eq!(consumed.user_id.as_deref(), Some("u1"));
+    assert_eq!(consumed.status, DeviceCodeStatus::Consumed);
+    assert!(store.consume(&code.device_code).await.unwrap().is_none());
+    let after = store.get(&code.device_code).await.unwrap().unwrap();
+    assert_eq!(after, consumed);
+}
+
+/// Client-assertion `jti` cache shared by both backends: a `jti` is
+/// accepted once per client until its expiry passes.
+async fn assertion_jti_store_contract(store: &dyn assay_auth::oidc_provider::OidcClientStore) {
+    assert!(
+        store
+            .record_assertion_jti("svc", "j1", 100.0, 50.0)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !store
+            .record_assertion_jti("svc", "j1", 100.0, 60.0)
+            .await
+            .unwrap()
+    );
+    assert!(
+        store
+            .record_assertion_jti("other", "j1", 100.0, 60.0)
+            .await
+            .unwrap()
+    );
+    // Pruned once expired; the assertion's own `exp` rejects it by then.
+    assert!(
+        store
+            .record_assertion_jti("svc", "j1", 200.0, 150.0)
+            .await
+            .unwrap()
+    );
+}
+
 // =====================================================================
 //   SQLITE — exercises the full V4 migration + every store
 // =====================================================================
@@ -185,8 +256,8 @@ mod sqlite_tests {
     use assay_auth::oidc_provider::store::{
         OidcClientStore, OidcCodeStore, OidcConsentStore, OidcRefreshStore, OidcSessionStore,
         OidcUpstreamStateStore, OidcUpstreamStore, SqliteOidcClientStore, SqliteOidcCodeStore,
-        SqliteOidcConsentStore, SqliteOidcRefreshStore, SqliteOidcSessionStore,
-        SqliteOidcUpstreamStateStore, SqliteOidcUpstreamStore,
+        SqliteOidcConsentStore, SqliteOidcDeviceCodeStore, SqliteOidcRefreshStore,
+        SqliteOidcSessionStore, SqliteOidcUpstreamStateStore, SqliteOidcUpstreamStore,
     };
     use assay_auth::oidc_provider::types::AuthorizationCode;
     use sqlx::SqlitePool;
@@ -247,6 +318,12 @@ mod sqlite_tests {
         pool
     }
 
+    #[tokio::test]
+    async fn client_store_rejects_replayed_assertion_jti() {
+        let pool = setup_sqlite().await;
+        assertion_jti_store_contract(&SqliteOidcClientStore::new(pool)).await;
+    }
+
     #[tokio::test]
     async fn client_store_create_get_list_update_delete() {
         let pool = setup_sqlite().await;
@@ -336,6 +413,8 @@ mod sqlite_tests {
             issued_at: 1.0,
             expires_at: 61.0,
             consumed: false,
+            amr: vec!["pwd".to_string(), "otp".to_string()],
+            org_id: Some("org_acme".to_string()),
         };
         store.create(&code).await.expect("create");
         let consumed = store
@@ -344,10 +423,18 @@ mod sqlite_tests {
             .expect("consume")
             .expect("present");
         assert_eq!(consumed.code, "oac_abc");
+        assert_eq!(consumed.amr, vec!["pwd", "otp"]);
+        assert_eq!(consumed.org_id.as_deref(), Some("org_acme"));
         // Second consume returns None — single-use.
         assert!(store.consume("oac_abc").await.expect("consume2").is_none());
     }
 
+    #[tokio::test]
+    async fn device_code_store_decide_and_consume() {
+        let pool = setup_sqlite().await;
+        device_code_store_contract(&SqliteOidcDeviceCodeStore::new(pool)).await;
+    }
+
     #[tokio::test]
     async fn refresh_store_revoke_and_revoke_for_user() {
         let pool = setup_sqlite().await;
@@ -362,6 +449,7 @@ mod sqlite_tests {
                 issued_at: 1.0,
                 expires_at: 1000.0,
                 revoked: false,
+                org_id: None,
             };
             store.create(&row).await.expect("create");
         }
@@ -460,6 +548,542 @@ mod sqlite_tests {
         // Second take returns None — single-use.
         assert!(store.take("state_abc").await.expect("take2").is_none());
     }
+
+    const ISSUER: &str = "https://idp.example.com";
+    const FORM: &str = "application/x-www-form-urlencoded";
+
+    /// The spec router over a provider backed by `pool`, signing with
+    /// an ephemeral key.
+    fn provider_app(pool: SqlitePool) -> axum::Router {
+        use assay_auth::ctx::AuthCtx;
+        use assay_auth::oidc_provider::spec_router;
+        spec_router::<AuthCtx>().with_state(provider_ctx(pool))
+    }
+
+    fn provider_ctx(pool: SqlitePool) -> assay_auth::ctx::AuthCtx {
+        use assay_auth::ctx::AuthCtx;
+        use assay_auth::jwt::{JwtConfig, generate_ephemeral_ed25519};
+        use assay_auth::oidc_provider::OidcProviderConfig;
+        use assay_auth::store::sqlite::{SqliteSessionStore, SqliteUserStore};
+        use std::sync::Arc;
+
+        let provider = OidcProviderConfig::new(
+            ISSUER,
+            url::Url::parse(ISSUER).unwrap(),
+            Arc::new(SqliteOidcClientStore::new(pool.clone())),
+            Arc::new(SqliteOidcUpstreamStore::new(pool.clone())),
+            Arc::new(SqliteOidcCodeStore::new(pool.clone())),
+            Arc::new(SqliteOidcDeviceCodeStore::new(pool.clone())),
+            Arc::new(SqliteOidcRefreshStore::new(pool.clone())),
+            Arc::new(SqliteOidcSessionStore::new(pool.clone())),
+            Arc::new(SqliteOidcConsentStore::new(pool.clone())),
+            Arc::new(SqliteOidcUpstreamStateStore::new(pool.clone())),
+        );
+        let jwt = JwtConfig::new(ISSUER.to_string(), vec![]);
+        jwt.set_active(generate_ephemeral_ed25519("k1").unwrap(), Vec::new());
+        AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool)),
+        )
+        .with_jwt(jwt)
+        .with_oidc_provider(provider)
+    }
+
+    /// Drive one request through `app`; returns status + body text.
+    async fn send(
+        app: &axum::Router,
+        req: axum::http::Request<axum::body::Body>,
+    ) -> (axum::http::StatusCode, String) {
+        use tower::ServiceExt;
+        let resp = app.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    /// [`send`] for the JSON endpoints.
+    async fn send_json(
+        app: &axum::Router,
+        req: axum::http::Request<axum::body::Body>,
+    ) -> (axum::http::StatusCode, serde_json::Value) {
+        let (status, body) = send(app, req).await;
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    fn form_post(
+        uri: &str,
+        headers: &[(&str, &str)],
+        form: &str,
+    ) -> axum::http::Request<axum::body::Body> {
+        let mut req = axum::http::Request::post(uri).header("content-type", FORM);
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        req.body(axum::body::Body::from(form.to_string())).unwrap()
+    }
+
+    /// `client_credentials` end to end: a confidential client trades
+    /// its secret for a machine token limited to its registered scopes
+    /// and audiences, and `/introspect` reports the token as active.
+    #[tokio::test]
+    async fn client_credentials_grant_mints_introspectable_token() {
+        use assay_auth::ctx::AuthCtx;
+        use assay_auth::jwt::{JwtConfig, generate_ephemeral_ed25519};
+        use assay_auth::oidc_provider::{OidcProviderConfig, spec_router};
+        use assay_auth::store::sqlite::{SqliteSessionStore, SqliteUserStore};
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        let issuer = "https://idp.example.com";
+        let pool = setup_sqlite().await;
+        let clients = SqliteOidcClientStore::new(pool.clone());
+        let mut client = OidcClient::new("svc", "Billing worker", 1.0);
+        client.grant_types = vec!["client_credentials".to_string()];
+        client.default_scopes = vec!["orders:read".to_string(), "orders:write".to_string()];
+        client.audiences = vec!["orders-api".to_string()];
+        client.client_secret_hash = Some(
+            assay_auth::password::PasswordHasher::default()
+                .hash("s3cret")
+                .unwrap(),
+        );
+        clients.create(&client).await.unwrap();
+        let loaded = clients.get("svc").await.unwrap().unwrap();
+        assert_eq!(loaded.audiences, vec!["orders-api".to_string()]);
+        assert!(loaded.jwks.is_none());
+
+        let provider = OidcProviderConfig::new(
+            issuer,
+            url::Url::parse(issuer).unwrap(),
+            Arc::new(clients),
+            Arc::new(SqliteOidcUpstreamStore::new(pool.clone())),
+            Arc::new(SqliteOidcCodeStore::new(pool.clone())),
+            Arc::new(SqliteOidcDeviceCodeStore::new(pool.clone())),
+            Arc::new(SqliteOidcRefreshStore::new(pool.clone())),
+            Arc::new(SqliteOidcSessionStore::new(pool.clone())),
+            Arc::new(SqliteOidcConsentStore::new(pool.clone())),
+            Arc::new(SqliteOidcUpstreamStateStore::new(pool.clone())),
+        );
+        let jwt = JwtConfig::new(issuer.to_string(), vec![]);
+        jwt.set_active(generate_ephemeral_ed25519("k1").unwrap(), Vec::new());
+        let ctx = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool)),
+        )
+        .with_jwt(jwt)
+        .with_oidc_provider(provider);
+        let app = spec_router::<AuthCtx>().with_state(ctx);
+
+        let basic = format!("Basic {}", data_encoding::BASE64.encode(b"svc:s3cret"));
+        let post = |uri: &str, auth: Option<&str>, form: &str| {
+            let mut req =
+                Request::post(uri).header("content-type", "application/x-www-form-urlencoded");
+            if let Some(auth) = auth {
+                req = req.header("authorization", auth);
+            }
+            req.body(Body::from(form.to_string())).unwrap()
+        };
+        let call = |req: Request<Body>| {
+            let app = app.clone();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                let status = resp.status();
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        // client_secret_basic, narrowed scope, registered audience.
+        let (status, body) = call(post(
+            "/token",
+            Some(&basic),
+            "grant_type=client_credentials&scope=orders:read&audience=orders-api",
+        ))
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["scope"], "orders:read");
+        assert!(body.get("refresh_token").is_none());
+        assert!(body.get("id_token").is_none());
+        let token = body["access_token"].as_str().unwrap().to_string();
+
+        // client_secret_post falls back to the registered scopes.
+        let (status, body) = call(post(
+            "/token",
+            None,
+            "grant_type=client_credentials&client_id=svc&client_secret=s3cret",
+        ))
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["scope"], "orders:read orders:write");
+
+        for (form, error) in [
+            ("grant_type=client_credentials&scope=admin", "invalid_scope"),
+            (
+                "grant_type=client_credentials&audience=payments-api",
+                "invalid_target",
+            ),
+        ] {
+            let (status, body) = call(post("/token", Some(&basic), form)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{form}");
+            assert_eq!(body["error"], error, "{form}");
+        }
+        let bad = format!("Basic {}", data_encoding::BASE64.encode(b"svc:wrong"));
+        let (status, body) =
+            call(post("/token", Some(&bad), "grant_type=client_credentials")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "invalid_client");
+
+        let (status, body) =
+            call(post("/introspect", Some(&basic), &format!("token={token}"))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["active"], true);
+        assert_eq!(body["client_id"], "svc");
+        assert_eq!(body["sub"], "svc");
+        assert_eq!(body["aud"], "orders-api");
+        assert_eq!(body["scope"], "orders:read");
+        assert!(body.get("username").is_none());
+    }
+
+    /// `private_key_jwt` over `/token`: an assertion redeems once, a
+    /// replay of it is refused, and so is a fresh one that lives too
+    /// long.
+    #[tokio::test]
+    async fn private_key_jwt_assertion_is_single_use() {
+        use assay_auth::oidc_provider::client_assertion::JWT_BEARER_ASSERTION_TYPE;
+        use axum::http::StatusCode;
+        use ed25519_dalek::pkcs8::EncodePrivateKey;
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+        let signing = ed25519_dalek::SigningKey::generate(&mut rand_core_06::OsRng);
+        let pem = signing
+            .to_pkcs8_pem(ed25519_dalek::pkcs8::spki::der::pem::LineEnding::LF)
+            .unwrap();
+        let key = EncodingKey::from_ed_pem(pem.as_bytes()).unwrap();
+        let x = data_encoding::BASE64URL_NOPAD.encode(&signing.verifying_key().to_bytes());
+
+        let pool = setup_sqlite().await;
+        let mut client = OidcClient::new("svc", "Billing worker", 1.0);
+        client.token_endpoint_auth_method = TokenAuthMethod::PrivateKeyJwt;
+        client.grant_types = vec!["client_credentials".to_string()];
+        client.default_scopes = vec!["orders:read".to_string()];
+        client.jwks = Some(serde_json::json!({"keys": [
+            {"kty": "OKP", "crv": "Ed25519", "alg": "EdDSA", "kid": "k1", "x": x}
+        ]}));
+        SqliteOidcClientStore::new(pool.clone())
+            .create(&client)
+            .await
+            .unwrap();
+        let app = provider_app(pool);
+
+        let now = jsonwebtoken::get_current_timestamp();
+        let sign = |jti: &str, lifetime: u64| {
+            let mut header = Header::new(Algorithm::EdDSA);
+            header.kid = Some("k1".to_string());
+            let claims = serde_json::json!({
+                "iss": "svc", "sub": "svc", "aud": format!("{ISSUER}/token"),
+                "jti": jti, "iat": now, "exp": now + lifetime,
+            });
+            jsonwebtoken::encode(&header, &claims, &key).unwrap()
+        };
+        let token = |assertion: String| {
+            form_post(
+                "/token",
+                &[],
+                &format!(
+                    "grant_type=client_credentials&client_assertion_type={}&client_assertion={assertion}",
+                    JWT_BEARER_ASSERTION_TYPE.replace(':', "%3A"),
+                ),
+            )
+        };
+
+        let assertion = sign("a1", 60);
+        let (status, body) = send_json(&app, token(assertion.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["scope"], "orders:read");
+
+        let (status, body) = send_json(&app, token(assertion)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{body}");
+        assert_eq!(body["error"], "invalid_client");
+
+        let (status, body) = send_json(&app, token(sign("a2", 86_400))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{body}");
+        assert_eq!(body["error"], "invalid_client");
+
+        let (status, _) = send_json(&app, token(sign("a3", 60))).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    /// RFC 8628 end to end: the CLI starts a device login, polls into
+    /// `authorization_pending` then `slow_down`, the signed-in user
+    /// approves on `/device`, and the next poll redeems the code once.
+    #[tokio::test]
+    async fn device_grant_polls_until_user_approves() {
+        use assay_auth::oidc_provider::device::DEVICE_CODE_GRANT_TYPE;
+        use assay_auth::store::sqlite::{SqliteSessionStore, SqliteUserStore};
+        use assay_auth::store::{Session, SessionStore, UserStore};
+        use axum::http::{Request, StatusCode};
+
+        let pool = setup_sqlite().await;
+        let mut cli = OidcClient::new("ops-cli", "ops CLI", 1.0);
+        cli.token_endpoint_auth_method = TokenAuthMethod::None;
+        cli.grant_types = vec![
+            DEVICE_CODE_GRANT_TYPE.to_string(),
+            "refresh_token".to_string(),
+        ];
+        cli.default_scopes = vec!["openid".to_string(), "email".to_string()];
+        let clients = SqliteOidcClientStore::new(pool.clone());
+        clients.create(&cli).await.unwrap();
+        clients
+            .create(&OidcClient::new("web", "Web app", 1.0))
+            .await
+            .unwrap();
+        SqliteUserStore::new(pool.clone())
+            .create_user(&User {
+                id: "usr_alice".to_string(),
+                email: Some("alice@example.com".to_string()),
+                email_verified: true,
+                display_name: None,
+                created_at: 1.0,
+            })
+            .await
+            .unwrap();
+        SqliteSessionStore::new(pool.clone())
+            .create(&Session {
+                id: "sess_alice".to_string(),
+                user_id: "usr_alice".to_string(),
+                csrf_token: "csrf_alice".to_string(),
+                created_at: 1.0,
+                expires_at: 4_000_000_000.0,
+                ip_hash: None,
+                user_agent_hash: None,
+                amr: vec!["pwd".to_string()],
+            })
+            .await
+            .unwrap();
+        let app = provider_app(pool);
+        let cookie = ("cookie", "assay_session=sess_alice");
+
+        let (status, body) = send_json(
+            &app,
+            form_post("/device_authorization", &[], "client_id=web"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "unauthorized_client");
+
+        let (status, start) = send_json(
+            &app,
+            form_post("/device_authorization", &[], "client_id=ops-cli"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{start}");
+        assert_eq!(start["verification_uri"], format!("{ISSUER}/device"));
+        let device_code = start["device_code"].as_str().unwrap().to_string();
+        let user_code = start["user_code"].as_str().unwrap().to_string();
+        let poll = format!(
+            "grant_type={}&client_id=ops-cli&device_code={device_code}",
+            DEVICE_CODE_GRANT_TYPE.replace(':', "%3A")
+        );
+
+        for expected in ["authorization_pending", "slow_down"] {
+            let (status, body) = send_json(&app, form_post("/token", &[], &poll)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], expected);
+        }
+
+        // Signed out → login, coming back to the same code.
+        let uri = format!("/device?user_code={user_code}");
+        let (status, _) = send(&app, Request::get(&uri).body(Default::default()).unwrap()).await;
+        assert_eq!(status, StatusCode::SEE_OTHER);
+
+        let typed = format!("/device?user_code={}", user_code.to_lowercase());
+        let req = Request::get(&typed)
+            .header(cookie.0, cookie.1)
+            .body(Default::default())
+            .unwrap();
+        let (status, html) = send(&app, req).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            html.contains(&user_code) && html.contains("ops CLI"),
+            "{html}"
+        );
+
+        let decide = |csrf: &str| {
+            form_post(
+                "/device",
+                &[cookie],
+                &format!("csrf_token={csrf}&user_code={user_code}&decision=allow"),
+            )
+        };
+        let (status, _) = send(&app, decide("forged")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, html) = send(&app, decide("csrf_alice")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(html.contains("Device connected"), "{html}");
+        let (status, _) = send(&app, decide("csrf_alice")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, tokens) = send_json(&app, form_post("/token", &[], &poll)).await;
+        assert_eq!(status, StatusCode::OK, "{tokens}");
+        assert_eq!(tokens["scope"], "openid email");
+        assert!(tokens["id_token"].is_string());
+        assert!(tokens["refresh_token"].is_string());
+        let (status, body) = send_json(&app, form_post("/token", &[], &poll)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+    }
+
+    /// Org-scoped login: the `organization` hint is honoured only for
+    /// members, lands in the ID/access tokens as `org_id`, and leaving
+    /// the org ends the refresh chain.
+    #[cfg(feature = "auth-orgs")]
+    #[tokio::test]
+    async fn organization_hint_scopes_the_login() {
+        use assay_auth::oidc_provider::spec_router;
+        use assay_auth::orgs::{OrgManager, OrgRole, SqliteOrgStore};
+        use assay_auth::store::sqlite::{SqliteSessionStore, SqliteUserStore};
+        use assay_auth::store::{Session, SessionStore, UserStore};
+        use assay_auth::zanzibar::SqliteZanzibarStore;
+        use axum::http::{Request, StatusCode, header};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        const CALLBACK: &str = "https://app.example.com/cb";
+        let pool = setup_sqlite().await;
+        let mut client = OidcClient::new("app", "App", 1.0);
+        client.redirect_uris = vec![CALLBACK.to_string()];
+        client.require_consent = false;
+        client.pkce_required = false;
+        client.client_secret_hash = Some(
+            assay_auth::password::PasswordHasher::default()
+                .hash("s3cret")
+                .unwrap(),
+        );
+        SqliteOidcClientStore::new(pool.clone())
+            .create(&client)
+            .await
+            .unwrap();
+        for name in ["alice", "bob"] {
+            SqliteUserStore::new(pool.clone())
+                .create_user(&User {
+                    id: format!("usr_{name}"),
+                    email: Some(format!("{name}@example.com")),
+                    email_verified: true,
+                    display_name: None,
+                    created_at: 1.0,
+                })
+                .await
+                .unwrap();
+            SqliteSessionStore::new(pool.clone())
+                .create(&Session {
+                    id: format!("sess_{name}"),
+                    user_id: format!("usr_{name}"),
+                    csrf_token: format!("csrf_{name}"),
+                    created_at: 1.0,
+                    expires_at: 4_000_000_000.0,
+                    ip_hash: None,
+                    user_agent_hash: None,
+                    amr: vec!["pwd".to_string()],
+                })
+                .await
+                .unwrap();
+        }
+        let orgs = OrgManager::new(
+            Arc::new(SqliteOrgStore::new(pool.clone())),
+            Arc::new(SqliteZanzibarStore::new(pool.clone())),
+        );
+        orgs.ensure_namespaces().await.unwrap();
+        let acme = orgs.create_org("acme", "Acme").await.unwrap();
+        orgs.set_membership(&acme.id, "usr_alice", OrgRole::Member)
+            .await
+            .unwrap();
+        let app = spec_router::<assay_auth::ctx::AuthCtx>()
+            .with_state(provider_ctx(pool).with_orgs(orgs.clone()));
+
+        let authorize = |user: &str| {
+            Request::get(format!(
+                "/authorize?response_type=code&client_id=app&scope=openid\
+                 &redirect_uri={}&state=xyz&organization=acme",
+                CALLBACK.replace(':', "%3A").replace('/', "%2F")
+            ))
+            .header("cookie", format!("assay_session=sess_{user}"))
+            .body(axum::body::Body::empty())
+            .unwrap()
+        };
+        let location = |resp: &axum::response::Response| {
+            let raw = resp.headers()[header::LOCATION].to_str().unwrap();
+            url::Url::parse(raw).unwrap()
+        };
+        let param = |url: &url::Url, key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+        };
+
+        let denied = app.clone().oneshot(authorize("bob")).await.unwrap();
+        assert!(denied.status().is_redirection());
+        let denied = location(&denied);
+        assert_eq!(param(&denied, "error").as_deref(), Some("access_denied"));
+        assert_eq!(param(&denied, "state").as_deref(), Some("xyz"));
+
+        let granted = app.clone().oneshot(authorize("alice")).await.unwrap();
+        assert!(granted.status().is_redirection());
+        let code = param(&location(&granted), "code").expect("code issued");
+
+        let basic = format!("Basic {}", data_encoding::BASE64.encode(b"app:s3cret"));
+        let (status, tokens) = send_json(
+            &app,
+            form_post(
+                "/token",
+                &[("authorization", &basic)],
+                &format!(
+                    "grant_type=authorization_code&code={code}&redirect_uri={}",
+                    CALLBACK.replace(':', "%3A").replace('/', "%2F")
+                ),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{tokens}");
+        let claims = |jwt: &str| -> serde_json::Value {
+            let payload = jwt.split('.').nth(1).unwrap();
+            serde_json::from_slice(
+                &data_encoding::BASE64URL_NOPAD
+                    .decode(payload.as_bytes())
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            claims(tokens["id_token"].as_str().unwrap())["org_id"],
+            acme.id
+        );
+        assert_eq!(
+            claims(tokens["access_token"].as_str().unwrap())["org_id"],
+            acme.id
+        );
+
+        // Leaving the org ends the refresh chain.
+        assert!(orgs.remove_membership(&acme.id, "usr_alice").await.unwrap());
+        let refresh = format!(
+            "grant_type=refresh_token&refresh_token={}",
+            tokens["refresh_token"].as_str().unwrap()
+        );
+        let (status, body) = send_json(
+            &app,
+            form_post("/token", &[("authorization", &basic)], &refresh),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+    }
 }
 
 // =====================================================================
@@ -471,8 +1095,8 @@ mod pg_tests {
     use super::*;
     use assay_auth::oidc_provider::store::{
         OidcClientStore, OidcCodeStore, OidcRefreshStore, OidcUpstreamStateStore,
-        PostgresOidcClientStore, PostgresOidcCodeStore, PostgresOidcRefreshStore,
-        PostgresOidcUpstreamStateStore,
+        PostgresOidcClientStore, PostgresOidcCodeStore, PostgresOidcDeviceCodeStore,
+        PostgresOidcRefreshStore, PostgresOidcUpstreamStateStore,
     };
     use assay_auth::oidc_provider::types::AuthorizationCode;
     use sqlx::PgPool;
@@ -521,9 +1145,13 @@ mod pg_tests {
         let store = PostgresOidcClientStore::new(pool);
         let mut client = OidcClient::new("c_pg", "PG App", 1.0);
         client.redirect_uris = vec!["https://app.example.com/cb".to_string()];
+        client.audiences = vec!["orders-api".to_string()];
+        client.jwks = Some(serde_json::json!({"keys": []}));
         store.create(&client).await.expect("create");
         let loaded = store.get("c_pg").await.expect("get").expect("present");
         assert_eq!(loaded.client_id, "c_pg");
+        assert_eq!(loaded.audiences, client.audiences);
+        assert_eq!(loaded.jwks, client.jwks);
         assert_eq!(
             loaded.token_endpoint_auth_method,
             TokenAuthMethod::ClientSecretBasic
@@ -532,6 +1160,15 @@ mod pg_tests {
         assert!(store.delete("c_pg").await.expect("delete"));
     }
 
+    #[tokio::test]
+    async fn pg_client_store_rejects_replayed_assertion_jti() {
+        let Some(pool) = setup_pg().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        assertion_jti_store_contract(&PostgresOidcClientStore::new(pool)).await;
+    }
+
     #[tokio::test]
     async fn pg_code_consume_is_single_use() {
         let Some(pool) = setup_pg().await else {
@@ -552,6 +1189,8 @@ mod pg_tests {
             issued_at: 1.0,
             expires_at: 61.0,
             consumed: false,
+            amr: Vec::new(),
+            org_id: None,
         };
         store.create(&code).await.expect("create");
         let consumed = store
@@ -563,6 +1202,15 @@ mod pg_tests {
         assert!(store.consume("oac_pg").await.expect("consume2").is_none());
     }
 
+    #[tokio::test]
+    async fn pg_device_code_decide_and_consume() {
+        let Some(pool) = setup_pg().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        device_code_store_contract(&PostgresOidcDeviceCodeStore::new(pool)).await;
+    }
+
     #[tokio::test]
     async fn pg_refresh_revoke_for_user() {
         let Some(pool) = setup_pg().await else {
@@ -580,6 +1228,7 @@ mod pg_tests {
                 issued_at: 1.0,
                 expires_at: 1000.0,
                 revoked: false,
+                org_id: None,
             };
             store.create(&row).await.expect("create");
         }
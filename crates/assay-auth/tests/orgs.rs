@@ -0,0 +1,592 @@
+//! Integration tests for organizations and groups.
+//!
+//! - **SQLite** — always-on. Store contract, the Zanzibar projection
+//!   (membership, roles, nested groups, removal) and the admin HTTP
+//!   surface.
+//! - **Postgres** — store contract only, gated on
+//!   `ASSAY_TEST_DATABASE_URL`.
+
+#![cfg(feature = "auth-orgs")]
+
+use assay_auth::orgs::store::OrgStore;
+use assay_auth::orgs::{Group, GroupMember, GroupMemberType, OrgMembership, OrgRole, Organization};
+
+fn org(id: &str, slug: &str) -> Organization {
+    Organization {
+        id: id.to_string(),
+        slug: slug.to_string(),
+        display_name: slug.to_uppercase(),
+        created_at: 1.0,
+    }
+}
+
+fn group(id: &str, org_id: &str, slug: &str) -> Group {
+    Group {
+        id: id.to_string(),
+        org_id: org_id.to_string(),
+        slug: slug.to_string(),
+        display_name: slug.to_string(),
+        created_at: 1.0,
+    }
+}
+
+fn member(group_id: &str, member_type: GroupMemberType, member_id: &str) -> GroupMember {
+    GroupMember {
+        group_id: group_id.to_string(),
+        member_type,
+        member_id: member_id.to_string(),
+        created_at: 1.0,
+    }
+}
+
+/// Backend-neutral store contract. `user_id` must already exist; ids
+/// are prefixed with `tag` so runs against a shared database don't
+/// collide.
+async fn exercise_store(store: &dyn OrgStore, user_id: &str, tag: &str) {
+    let org_id = format!("org_{tag}");
+    let slug = format!("acme-{tag}");
+    assert!(store.create_org(&org(&org_id, &slug)).await.unwrap());
+    assert!(
+        !store
+            .create_org(&org(&format!("org_{tag}_dup"), &slug))
+            .await
+            .unwrap(),
+        "slugs are unique"
+    );
+    assert_eq!(
+        store.get_org_by_slug(&slug).await.unwrap().unwrap().id,
+        org_id
+    );
+    let found = store.list_orgs(10, 0, Some(&slug)).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(store.count_orgs(Some(&slug)).await.unwrap(), 1);
+    assert!(store.update_org(&org_id, "Renamed").await.unwrap());
+    assert_eq!(
+        store.get_org(&org_id).await.unwrap().unwrap().display_name,
+        "Renamed"
+    );
+    assert!(!store.update_org("org_missing", "x").await.unwrap());
+
+    let membership = OrgMembership {
+        org_id: org_id.clone(),
+        user_id: user_id.to_string(),
+        role: OrgRole::Member,
+        created_at: 2.0,
+    };
+    assert_eq!(store.upsert_membership(&membership).await.unwrap(), None);
+    let promoted = OrgMembership {
+        role: OrgRole::Admin,
+        ..membership.clone()
+    };
+    assert_eq!(
+        store.upsert_membership(&promoted).await.unwrap(),
+        Some(OrgRole::Member)
+    );
+    let stored = store
+        .get_membership(&org_id, user_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.role, OrgRole::Admin);
+    assert_eq!(stored.created_at, 2.0, "re-roling keeps the join time");
+    assert_eq!(store.count_memberships(&org_id).await.unwrap(), 1);
+    assert_eq!(
+        store.list_memberships(&org_id, 10, 0).await.unwrap().len(),
+        1
+    );
+    assert_eq!(
+        store.list_user_memberships(user_id).await.unwrap()[0].org_id,
+        org_id
+    );
+
+    let parent = format!("grp_{tag}_parent");
+    let child = format!("grp_{tag}_child");
+    assert!(
+        store
+            .create_group(&group(&parent, &org_id, "eng"))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !store
+            .create_group(&group(&format!("grp_{tag}_dup"), &org_id, "eng"))
+            .await
+            .unwrap(),
+        "group slugs are unique per org"
+    );
+    assert!(
+        store
+            .create_group(&group(&child, &org_id, "sre"))
+            .await
+            .unwrap()
+    );
+    assert_eq!(store.list_groups(&org_id).await.unwrap().len(), 2);
+    assert!(
+        store
+            .update_group(&child, "Site Reliability")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        store.get_group(&child).await.unwrap().unwrap().display_name,
+        "Site Reliability"
+    );
+    assert!(!store.update_group("grp_missing", "x").await.unwrap());
+
+    assert!(
+        store
+            .add_group_member(&member(&parent, GroupMemberType::Group, &child))
+            .await
+            .unwrap()
+    );
+    assert!(
+        store
+            .add_group_member(&member(&child, GroupMemberType::User, user_id))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !store
+            .add_group_member(&member(&child, GroupMemberType::User, user_id))
+            .await
+            .unwrap()
+    );
+    assert_eq!(store.list_group_members(&parent).await.unwrap().len(), 1);
+    assert_eq!(
+        store
+            .list_member_groups(GroupMemberType::Group, &child)
+            .await
+            .unwrap(),
+        vec![parent.clone()]
+    );
+
+    // Deleting the child also removes its slot in the parent.
+    assert!(store.delete_group(&child).await.unwrap());
+    assert!(!store.delete_group(&child).await.unwrap());
+    assert!(store.list_group_members(&parent).await.unwrap().is_empty());
+    assert!(
+        store
+            .list_member_groups(GroupMemberType::User, user_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let removed = store.remove_membership(&org_id, user_id).await.unwrap();
+    assert_eq!(removed.map(|m| m.role), Some(OrgRole::Admin));
+    assert!(
+        store
+            .remove_membership(&org_id, user_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    assert!(store.delete_org(&org_id).await.unwrap());
+    assert!(!store.delete_org(&org_id).await.unwrap());
+    assert!(
+        store.get_group(&parent).await.unwrap().is_none(),
+        "groups cascade"
+    );
+}
+
+#[cfg(feature = "backend-sqlite")]
+mod sqlite_store {
+    use super::*;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use assay_auth::AuthCtx;
+    use assay_auth::orgs::{OrgError, OrgManager, SqliteOrgStore};
+    use assay_auth::state::{AdminApiKeys, AuthCtxWithAdmin};
+    use assay_auth::store::{SqliteSessionStore, SqliteUserStore};
+    use assay_auth::zanzibar::{
+        Consistency, ObjectRef, SqliteZanzibarStore, SubjectRef, ZanzibarStore,
+    };
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode, header};
+    use serde_json::{Value, json};
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use tower::ServiceExt;
+
+    async fn setup() -> SqlitePool {
+        let suffix = format!("{}_{}_orgs", std::process::id(), uuid::Uuid::new_v4());
+        let engine_uri = format!("file:assay_eng_{suffix}?mode=memory&cache=shared");
+        let auth_uri = format!("file:assay_auth_{suffix}?mode=memory&cache=shared");
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .after_connect(move |connection, _meta| {
+                let engine_uri = engine_uri.clone();
+                let auth_uri = auth_uri.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{engine_uri}' AS engine").as_str())
+                        .await?;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{auth_uri}' AS auth").as_str())
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assay_auth::schema::migrate_sqlite(&pool).await.unwrap();
+        pool
+    }
+
+    async fn insert_user(pool: &SqlitePool, id: &str) {
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES (?, NULL, 0, NULL, NULL, 1)",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn manager(pool: &SqlitePool) -> (OrgManager, Arc<SqliteZanzibarStore>) {
+        let zanzibar = Arc::new(SqliteZanzibarStore::new(pool.clone()));
+        let orgs = OrgManager::new(
+            Arc::new(SqliteOrgStore::new(pool.clone())),
+            zanzibar.clone(),
+        );
+        orgs.ensure_namespaces().await.unwrap();
+        (orgs, zanzibar)
+    }
+
+    async fn allowed(
+        zanzibar: &SqliteZanzibarStore,
+        object_type: &str,
+        object_id: &str,
+        permission: &str,
+        user_id: &str,
+    ) -> bool {
+        zanzibar
+            .check(
+                &ObjectRef::new(object_type, object_id),
+                permission,
+                &SubjectRef::direct("user", user_id),
+                Consistency::Minimum,
+            )
+            .await
+            .unwrap()
+            .is_allowed()
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let pool = setup().await;
+        insert_user(&pool, "user-store").await;
+        exercise_store(&SqliteOrgStore::new(pool), "user-store", "sqlite").await;
+    }
+
+    #[tokio::test]
+    async fn memberships_and_nested_groups_project_into_zanzibar() {
+        let pool = setup().await;
+        for id in ["alice", "bob"] {
+            insert_user(&pool, id).await;
+        }
+        let (orgs, zanzibar) = manager(&pool).await;
+        let acme = orgs.create_org("acme", "Acme").await.unwrap();
+        let err = orgs.create_org("acme", "Again").await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OrgError>(),
+            Some(&OrgError::SlugTaken("acme".into()))
+        );
+
+        orgs.set_membership(&acme.id, "alice", OrgRole::Owner)
+            .await
+            .unwrap();
+        orgs.set_membership(&acme.id, "bob", OrgRole::Member)
+            .await
+            .unwrap();
+        assert!(allowed(&zanzibar, "organization", &acme.id, "manage", "alice").await);
+        assert!(allowed(&zanzibar, "organization", &acme.id, "view", "bob").await);
+        assert!(!allowed(&zanzibar, "organization", &acme.id, "manage", "bob").await);
+
+        // Re-roling swaps the role tuple rather than stacking it.
+        orgs.set_membership(&acme.id, "alice", OrgRole::Member)
+            .await
+            .unwrap();
+        assert!(!allowed(&zanzibar, "organization", &acme.id, "manage", "alice").await);
+        orgs.set_membership(&acme.id, "alice", OrgRole::Admin)
+            .await
+            .unwrap();
+
+        let eng = orgs
+            .create_group(&acme.id, "eng", "Engineering")
+            .await
+            .unwrap();
+        let sre = orgs.create_group(&acme.id, "sre", "SRE").await.unwrap();
+        orgs.add_group_member(&eng.id, GroupMemberType::Group, &sre.id)
+            .await
+            .unwrap();
+        orgs.add_group_member(&sre.id, GroupMemberType::User, "bob")
+            .await
+            .unwrap();
+        // bob reaches `eng` through the nested `sre` group; org admins
+        // can view (and manage) every group of their org.
+        assert!(allowed(&zanzibar, "group", &eng.id, "view", "bob").await);
+        assert!(!allowed(&zanzibar, "group", &eng.id, "manage", "bob").await);
+        assert!(allowed(&zanzibar, "group", &eng.id, "manage", "alice").await);
+
+        let err = orgs
+            .add_group_member(&sre.id, GroupMemberType::Group, &eng.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(OrgError::Cycle(_))));
+
+        let other = orgs.create_org("other", "Other").await.unwrap();
+        let stranger = orgs.create_group(&other.id, "ops", "Ops").await.unwrap();
+        let err = orgs
+            .add_group_member(&eng.id, GroupMemberType::Group, &stranger.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(OrgError::CrossOrganization(_))
+        ));
+        let err = orgs
+            .add_group_member(&stranger.id, GroupMemberType::User, "bob")
+            .await
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(OrgError::NotAMember(_))));
+
+        // Leaving the org drops the group memberships inside it too.
+        assert!(orgs.remove_membership(&acme.id, "bob").await.unwrap());
+        assert!(!allowed(&zanzibar, "organization", &acme.id, "view", "bob").await);
+        assert!(!allowed(&zanzibar, "group", &eng.id, "view", "bob").await);
+        assert!(
+            orgs.store()
+                .list_group_members(&sre.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(orgs.delete_org(&acme.id).await.unwrap());
+        assert!(!allowed(&zanzibar, "organization", &acme.id, "view", "alice").await);
+        assert!(!allowed(&zanzibar, "group", &eng.id, "manage", "alice").await);
+    }
+
+    #[tokio::test]
+    async fn operator_defined_namespaces_are_kept() {
+        let pool = setup().await;
+        let zanzibar = SqliteZanzibarStore::new(pool.clone());
+        let custom =
+            assay_auth::zanzibar::parse_schema("definition organization { relation member: user }")
+                .unwrap();
+        zanzibar.define_namespace(&custom[0]).await.unwrap();
+        manager(&pool).await;
+        let ns = zanzibar
+            .get_namespace("organization")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ns, custom[0]);
+        assert!(zanzibar.get_namespace("group").await.unwrap().is_some());
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, "Bearer admin-key");
+        let req = match body {
+            Some(body) => req
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => req.body(Body::empty()),
+        }
+        .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), 64 * 1024).await.unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn admin_routes_manage_orgs_members_and_groups() {
+        let pool = setup().await;
+        insert_user(&pool, "alice").await;
+        let (orgs, _) = manager(&pool).await;
+        let auth = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool.clone())),
+        );
+        let state = AuthCtxWithAdmin {
+            auth: auth.clone(),
+            admin: AdminApiKeys::from_keys(["admin-key"]),
+        };
+        let unconfigured =
+            assay_auth::orgs::admin::router::<AuthCtxWithAdmin>().with_state(state.clone());
+        let (status, _) = call(&unconfigured, "GET", "/admin/orgs", None).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        let app =
+            assay_auth::orgs::admin::router::<AuthCtxWithAdmin>().with_state(AuthCtxWithAdmin {
+                auth: auth.with_orgs(orgs),
+                ..state
+            });
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/admin/orgs",
+            Some(json!({"slug": "Not A Slug"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, created) = call(
+            &app,
+            "POST",
+            "/admin/orgs",
+            Some(json!({"slug": "acme", "display_name": "Acme"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{created}");
+        let org_id = created["id"].as_str().unwrap().to_string();
+        let (status, _) = call(&app, "POST", "/admin/orgs", Some(json!({"slug": "acme"}))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = call(
+            &app,
+            "PUT",
+            "/admin/orgs/acme/members/ghost",
+            Some(json!({})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, membership) = call(
+            &app,
+            "PUT",
+            "/admin/orgs/acme/members/alice",
+            Some(json!({"role": "owner"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{membership}");
+        assert_eq!(membership["role"], "owner");
+
+        let (status, grp) = call(
+            &app,
+            "POST",
+            "/admin/orgs/acme/groups",
+            Some(json!({"slug": "eng"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{grp}");
+        let group_id = grp["id"].as_str().unwrap().to_string();
+        let member_uri = format!("/admin/groups/{group_id}/members/user/alice");
+        let (status, _) = call(&app, "PUT", &member_uri, None).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = call(&app, "PUT", &member_uri, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(
+            &app,
+            "PUT",
+            &format!("/admin/groups/{group_id}/members/robot/x"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, detail) = call(&app, "GET", &format!("/admin/orgs/{org_id}"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(detail["member_count"], 1);
+        assert_eq!(detail["groups"][0]["slug"], "eng");
+        let (_, group) = call(&app, "GET", &format!("/admin/groups/{group_id}"), None).await;
+        assert_eq!(group["members"][0]["member_id"], "alice");
+        let (_, mine) = call(&app, "GET", "/admin/users/alice/orgs", None).await;
+        assert_eq!(mine["items"][0]["org_id"], org_id);
+
+        let (status, _) = call(&app, "DELETE", "/admin/orgs/acme/members/alice", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, group) = call(&app, "GET", &format!("/admin/groups/{group_id}"), None).await;
+        assert_eq!(group["members"], json!([]));
+        let (status, _) = call(&app, "DELETE", "/admin/orgs/acme", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "GET", "/admin/orgs/acme", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+mod postgres_store {
+    use super::*;
+    use assay_auth::orgs::PostgresOrgStore;
+
+    async fn setup() -> Option<sqlx::PgPool> {
+        let url = std::env::var("ASSAY_TEST_DATABASE_URL").ok()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        let pool = sqlx::PgPool::connect(&url).await.ok()?;
+        sqlx::query("CREATE SCHEMA IF NOT EXISTS engine")
+            .execute(&pool)
+            .await
+            .ok()?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .ok()?;
+        assay_auth::schema::migrate_postgres(&pool).await.ok()?;
+        Some(pool)
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let Some(pool) = setup().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        let tag = uuid::Uuid::new_v4().simple().to_string();
+        let user_id = format!("user-orgs-{tag}");
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES ($1, NULL, FALSE, NULL, NULL, 1)",
+        )
+        .bind(&user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        exercise_store(&PostgresOrgStore::new(pool.clone()), &user_id, &tag).await;
+        sqlx::query("DELETE FROM auth.users WHERE id = $1")
+            .bind(&user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,753 @@
+//! Integration tests for the SCIM 2.0 provisioner.
+//!
+//! - **SQLite** — always-on. The HTTP tests play the IdP: an
+//!   Okta-style user lifecycle (lookup, create, deactivate, delete), the
+//!   Entra-style PATCH dialect with groups, and `/Bulk`. Plus the
+//!   store contract.
+//! - **Postgres** — store contract only, gated on
+//!   `ASSAY_TEST_DATABASE_URL`.
+
+#![cfg(feature = "auth-scim")]
+
+use assay_auth::scim::store::ScimStore;
+use assay_auth::scim::{ScimGroupAttrs, ScimUserAttrs};
+
+fn user_attrs(user_id: &str, user_name: &str, external_id: &str) -> ScimUserAttrs {
+    ScimUserAttrs {
+        user_id: user_id.to_string(),
+        user_name: user_name.to_string(),
+        external_id: Some(external_id.to_string()),
+        given_name: Some("Ada".to_string()),
+        family_name: None,
+        active: true,
+        updated_at: 1.0,
+    }
+}
+
+/// Backend-neutral store contract. `user_id` and `group_id` must
+/// already exist; names are suffixed with `tag` so runs against a
+/// shared database don't collide.
+async fn exercise_store(store: &dyn ScimStore, user_id: &str, group_id: &str, tag: &str) {
+    let user_name = format!("Ada-{tag}@Example.com");
+    let external_id = format!("ext-{tag}");
+    assert!(store.get_user(user_id).await.unwrap().is_none());
+    store
+        .upsert_user(&user_attrs(user_id, &user_name, &external_id))
+        .await
+        .unwrap();
+    let found = store
+        .get_user_by_user_name(&user_name.to_lowercase())
+        .await
+        .unwrap()
+        .expect("userName lookup is case-insensitive");
+    assert_eq!(found.user_id, user_id);
+    assert_eq!(found.given_name.as_deref(), Some("Ada"));
+    assert_eq!(
+        store
+            .get_user_by_external_id(&external_id)
+            .await
+            .unwrap()
+            .map(|a| a.user_id),
+        Some(user_id.to_string())
+    );
+
+    let deactivated = ScimUserAttrs {
+        active: false,
+        updated_at: 2.0,
+        ..user_attrs(user_id, &user_name, &external_id)
+    };
+    store.upsert_user(&deactivated).await.unwrap();
+    assert_eq!(store.get_user(user_id).await.unwrap(), Some(deactivated));
+    let listed = store
+        .list_users(&[user_id.to_string(), "usr_missing".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(store.list_users(&[]).await.unwrap().is_empty());
+
+    let group = ScimGroupAttrs {
+        group_id: group_id.to_string(),
+        external_id: Some(format!("grp-ext-{tag}")),
+        updated_at: 3.0,
+    };
+    store.upsert_group(&group).await.unwrap();
+    assert_eq!(
+        store.get_group(group_id).await.unwrap(),
+        Some(group.clone())
+    );
+    let renamed = ScimGroupAttrs {
+        external_id: None,
+        ..group
+    };
+    store.upsert_group(&renamed).await.unwrap();
+    assert_eq!(
+        store.list_groups(&[group_id.to_string()]).await.unwrap(),
+        vec![renamed]
+    );
+}
+
+#[cfg(feature = "backend-sqlite")]
+mod sqlite_store {
+    use super::*;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use assay_auth::AuthCtx;
+    use assay_auth::orgs::{GroupMemberType, OrgManager, SqliteOrgStore};
+    use assay_auth::scim::{ScimProvisioner, SqliteScimStore};
+    use assay_auth::state::AdminApiKeys;
+    use assay_auth::store::{SqliteSessionStore, SqliteUserStore};
+    use assay_auth::zanzibar::SqliteZanzibarStore;
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{HeaderMap, Request, StatusCode, header};
+    use serde_json::{Value, json};
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "scim-token";
+    const BASE: &str = "https://id.example.com/auth/scim/v2";
+
+    async fn setup() -> SqlitePool {
+        let suffix = format!("{}_{}_scim", std::process::id(), uuid::Uuid::new_v4());
+        let engine_uri = format!("file:assay_eng_{suffix}?mode=memory&cache=shared");
+        let auth_uri = format!("file:assay_auth_{suffix}?mode=memory&cache=shared");
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .after_connect(move |connection, _meta| {
+                let engine_uri = engine_uri.clone();
+                let auth_uri = auth_uri.clone();
+                Box::pin(async move {
+                    use sqlx::Executor;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{engine_uri}' AS engine").as_str())
+                        .await?;
+                    connection
+                        .execute(format!("ATTACH DATABASE '{auth_uri}' AS auth").as_str())
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assay_auth::schema::migrate_sqlite(&pool).await.unwrap();
+        pool
+    }
+
+    async fn orgs(pool: &SqlitePool) -> OrgManager {
+        let orgs = OrgManager::new(
+            Arc::new(SqliteOrgStore::new(pool.clone())),
+            Arc::new(SqliteZanzibarStore::new(pool.clone())),
+        );
+        orgs.ensure_namespaces().await.unwrap();
+        orgs
+    }
+
+    /// The SCIM surface plus the password login, over one context.
+    /// `organization` binds `/Groups`.
+    async fn app(pool: &SqlitePool, organization: Option<&str>) -> (Router, AuthCtx) {
+        let mut scim = ScimProvisioner::new(
+            Arc::new(SqliteScimStore::new(pool.clone())),
+            AdminApiKeys::from_keys([TOKEN]),
+            BASE,
+        );
+        if let Some(organization) = organization {
+            scim = scim.with_organization(organization);
+        }
+        let ctx = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool.clone())),
+        )
+        .with_orgs(orgs(pool).await)
+        .with_scim(scim);
+        let app = Router::new()
+            .merge(assay_auth::scim::router::<AuthCtx>())
+            .merge(assay_auth::session::router::<AuthCtx>())
+            .with_state(ctx.clone());
+        (app, ctx)
+    }
+
+    /// One IdP request. Bodies go out as `application/scim+json`, the
+    /// way Okta and Entra send them.
+    async fn scim(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, HeaderMap, Value) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {TOKEN}"));
+        let req = match body {
+            Some(body) => req
+                .header(header::CONTENT_TYPE, "application/scim+json")
+                .body(Body::from(body.to_string())),
+            None => req.body(Body::empty()),
+        }
+        .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = to_bytes(response.into_body(), 1 << 20).await.unwrap();
+        (
+            status,
+            headers,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    async fn login(app: &Router, email: &str, password: &str) -> StatusCode {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/login")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                json!({"email": email, "password": password}).to_string(),
+            ))
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap().status()
+    }
+
+    fn patch(operations: Value) -> Value {
+        json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": operations,
+        })
+    }
+
+    fn member_ids(group: &Value) -> Vec<String> {
+        let mut ids: Vec<String> = group["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["value"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES ('user-store', NULL, 0, NULL, NULL, 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let orgs = orgs(&pool).await;
+        let org = orgs.create_org("store", "Store").await.unwrap();
+        let group = orgs.create_group(&org.id, "eng", "Eng").await.unwrap();
+        exercise_store(
+            &SqliteScimStore::new(pool),
+            "user-store",
+            &group.id,
+            "sqlite",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn okta_style_user_lifecycle() {
+        let pool = setup().await;
+        let (app, ctx) = app(&pool, None).await;
+
+        let req = Request::builder()
+            .uri("/scim/v2/Users")
+            .header(header::AUTHORIZATION, "Bearer admin-key")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+        let (status, headers, config) =
+            scim(&app, "GET", "/scim/v2/ServiceProviderConfig", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[header::CONTENT_TYPE], "application/scim+json");
+        assert_eq!(config["patch"]["supported"], true);
+        assert_eq!(config["bulk"]["maxOperations"], 100);
+        assert_eq!(
+            config["authenticationSchemes"][0]["type"],
+            "oauthbearertoken"
+        );
+
+        // Okta looks the user up before creating them.
+        let lookup =
+            "/scim/v2/Users?filter=userName%20eq%20%22ada%40example.com%22&startIndex=1&count=100";
+        let (status, _, found) = scim(&app, "GET", lookup, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(found["totalResults"], 0);
+
+        let new_user = json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+            "userName": "ada@example.com",
+            "externalId": "00u1okta",
+            "name": {"givenName": "Ada", "familyName": "Lovelace"},
+            "emails": [{"primary": true, "value": "ada@example.com", "type": "work"}],
+            "displayName": "Ada Lovelace",
+            "active": true,
+            "password": "correct horse battery",
+        });
+        let (status, headers, ada) =
+            scim(&app, "POST", "/scim/v2/Users", Some(new_user.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = ada["id"].as_str().unwrap().to_string();
+        assert!(id.starts_with("usr_"));
+        assert_eq!(
+            headers[header::LOCATION],
+            format!("{BASE}/Users/{id}").as_str()
+        );
+        assert_eq!(ada["name"]["formatted"], "Ada Lovelace");
+        assert_eq!(ada["meta"]["resourceType"], "User");
+
+        let (status, _, err) = scim(&app, "POST", "/scim/v2/Users", Some(new_user)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(err["scimType"], "uniqueness");
+        assert_eq!(err["status"], "409");
+
+        let (_, _, found) = scim(&app, "GET", lookup, None).await;
+        assert_eq!(found["totalResults"], 1);
+        assert_eq!(found["Resources"][0]["id"], id.as_str());
+        let (_, _, found) = scim(
+            &app,
+            "GET",
+            "/scim/v2/Users?filter=name.givenName%20sw%20%22ad%22%20and%20active%20eq%20true&attributes=userName",
+            None,
+        )
+        .await;
+        assert_eq!(found["totalResults"], 1);
+        assert_eq!(
+            found["Resources"][0],
+            json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                "id": id,
+                "userName": "ada@example.com",
+            })
+        );
+        let (status, _, err) = scim(&app, "GET", "/scim/v2/Users?filter=userName%20eq", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(err["scimType"], "invalidFilter");
+
+        // The provisioned password works, then deactivation ends the
+        // session and blocks the next login.
+        assert_eq!(
+            login(&app, "ada@example.com", "correct horse battery").await,
+            StatusCode::OK
+        );
+        assert_eq!(ctx.sessions.list_for_user(&id).await.unwrap().len(), 1);
+        let deactivate = patch(json!([{"op": "replace", "value": {"active": false}}]));
+        let (status, _, ada) = scim(
+            &app,
+            "PATCH",
+            &format!("/scim/v2/Users/{id}"),
+            Some(deactivate),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ada["active"], false);
+        assert!(ctx.sessions.list_for_user(&id).await.unwrap().is_empty());
+        assert_eq!(
+            login(&app, "ada@example.com", "correct horse battery").await,
+            StatusCode::FORBIDDEN
+        );
+        assert!(assay_auth::scim::is_deactivated(&ctx, &id).await.unwrap());
+
+        // Okta re-activates with a full PUT.
+        let (status, _, ada) = scim(
+            &app,
+            "PUT",
+            &format!("/scim/v2/Users/{id}"),
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                "id": id,
+                "userName": "ada@example.com",
+                "name": {"givenName": "Augusta", "familyName": "Lovelace"},
+                "active": true,
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ada["name"]["givenName"], "Augusta");
+        assert_eq!(ada["emails"][0]["value"], "ada@example.com");
+        assert_eq!(
+            login(&app, "ada@example.com", "correct horse battery").await,
+            StatusCode::OK
+        );
+        let (status, _, err) = scim(
+            &app,
+            "PUT",
+            &format!("/scim/v2/Users/{id}"),
+            Some(json!({"id": "usr_other", "userName": "ada@example.com"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(err["scimType"], "mutability");
+
+        let (status, _, _) = scim(&app, "DELETE", &format!("/scim/v2/Users/{id}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(ctx.sessions.list_for_user(&id).await.unwrap().is_empty());
+        let (status, _, err) = scim(&app, "GET", &format!("/scim/v2/Users/{id}"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            err["schemas"][0],
+            "urn:ietf:params:scim:api:messages:2.0:Error"
+        );
+
+        let (status, _, err) = scim(&app, "GET", "/scim/v2/Groups", None).await;
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED, "no organization bound");
+        assert_eq!(err["status"], "501");
+    }
+
+    #[tokio::test]
+    async fn entra_style_patches_and_groups() {
+        let pool = setup().await;
+        let (app, ctx) = app(&pool, Some("acme")).await;
+        let orgs = ctx.orgs.clone().unwrap();
+        let acme = orgs.create_org("acme", "Acme").await.unwrap();
+
+        let mut ids = Vec::new();
+        for name in ["grace", "linus"] {
+            let (status, _, user) = scim(
+                &app,
+                "POST",
+                "/scim/v2/Users",
+                Some(json!({
+                    "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                    "userName": format!("{name}@acme.test"),
+                    "active": true,
+                    "emails": [{"type": "work", "primary": true, "value": format!("{name}@acme.test")}],
+                })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+            ids.push(user["id"].as_str().unwrap().to_string());
+        }
+        let (grace, linus) = (ids[0].clone(), ids[1].clone());
+        assert!(
+            orgs.store()
+                .get_membership(&acme.id, &grace)
+                .await
+                .unwrap()
+                .is_some(),
+            "provisioned users join the bound organization"
+        );
+
+        // Entra's dialect: capitalised ops, string booleans, filtered
+        // value paths.
+        let (status, _, user) = scim(
+            &app,
+            "PATCH",
+            &format!("/scim/v2/Users/{grace}"),
+            Some(patch(json!([
+                {"op": "Replace", "path": "displayName", "value": "Grace Hopper"},
+                {"op": "Replace", "path": "emails[type eq \"work\"].value", "value": "grace@navy.test"},
+                {"op": "Add", "path": "name.familyName", "value": "Hopper"},
+                {"op": "Replace", "path": "active", "value": "True"},
+            ]))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{user}");
+        assert_eq!(user["displayName"], "Grace Hopper");
+        assert_eq!(user["emails"][0]["value"], "grace@navy.test");
+        assert_eq!(user["name"]["familyName"], "Hopper");
+        assert_eq!(user["active"], true);
+        let (status, _, err) = scim(
+            &app,
+            "PATCH",
+            &format!("/scim/v2/Users/{grace}"),
+            Some(patch(
+                json!([{"op": "Replace", "path": "emails[type eq", "value": "x"}]),
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(err["scimType"], "invalidPath");
+
+        let (status, _, eng) = scim(
+            &app,
+            "POST",
+            "/scim/v2/Groups",
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
+                "displayName": "Engineering",
+                "externalId": "entra-eng",
+                "members": [{"value": grace}],
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let eng_id = eng["id"].as_str().unwrap().to_string();
+        assert_eq!(member_ids(&eng), vec![grace.clone()]);
+        assert_eq!(eng["members"][0]["type"], "User");
+        let (status, _, twin) = scim(
+            &app,
+            "POST",
+            "/scim/v2/Groups",
+            Some(json!({"displayName": "Engineering"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let twin_id = twin["id"].as_str().unwrap();
+        assert_eq!(
+            orgs.store().get_group(twin_id).await.unwrap().unwrap().slug,
+            "engineering-2"
+        );
+
+        let (_, _, user) = scim(&app, "GET", &format!("/scim/v2/Users/{grace}"), None).await;
+        assert_eq!(user["groups"][0]["value"], eng_id.as_str());
+        assert_eq!(user["groups"][0]["display"], "Engineering");
+
+        // Add linus, drop grace, nest the twin group.
+        let (status, _, eng) = scim(
+            &app,
+            "PATCH",
+            &format!("/scim/v2/Groups/{eng_id}"),
+            Some(patch(json!([
+                {"op": "Add", "path": "members", "value": [{"value": linus}, {"value": twin_id}]},
+                {"op": "Remove", "path": format!("members[value eq \"{grace}\"]")},
+            ]))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{eng}");
+        let mut expected = vec![linus.clone(), twin_id.to_string()];
+        expected.sort();
+        assert_eq!(member_ids(&eng), expected);
+        let nested = orgs.store().list_group_members(&eng_id).await.unwrap();
+        assert!(
+            nested
+                .iter()
+                .any(|m| m.member_type == GroupMemberType::Group && m.member_id == twin_id)
+        );
+
+        // Entra removes by value, and renames with a pathless replace.
+        let (status, _, eng) = scim(
+            &app,
+            "PATCH",
+            &format!("/scim/v2/Groups/{eng_id}"),
+            Some(patch(json!([
+                {"op": "Remove", "path": "members", "value": [{"value": twin_id}]},
+                {"op": "Replace", "value": {"displayName": "Platform"}},
+            ]))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(member_ids(&eng), vec![linus.clone()]);
+        assert_eq!(eng["displayName"], "Platform");
+        assert_eq!(eng["externalId"], "entra-eng");
+
+        let (_, _, found) = scim(
+            &app,
+            "GET",
+            "/scim/v2/Groups?filter=displayName%20eq%20%22platform%22&excludedAttributes=members",
+            None,
+        )
+        .await;
+        assert_eq!(found["totalResults"], 1);
+        assert!(found["Resources"][0].get("members").is_none());
+        let (_, _, found) = scim(
+            &app,
+            "GET",
+            &format!("/scim/v2/Groups?filter=members%5Bvalue%20eq%20%22{linus}%22%5D"),
+            None,
+        )
+        .await;
+        assert_eq!(found["totalResults"], 1);
+
+        // PUT replaces membership wholesale.
+        let (status, _, eng) = scim(
+            &app,
+            "PUT",
+            &format!("/scim/v2/Groups/{eng_id}"),
+            Some(json!({
+                "displayName": "Platform",
+                "members": [{"value": grace, "type": "User"}],
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(member_ids(&eng), vec![grace.clone()]);
+        let (status, _, err) = scim(
+            &app,
+            "PATCH",
+            &format!("/scim/v2/Groups/{eng_id}"),
+            Some(patch(
+                json!([{"op": "add", "path": "members", "value": [{"value": "usr_nobody"}]}]),
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(err["scimType"], "invalidValue");
+
+        let (status, _, _) = scim(&app, "DELETE", &format!("/scim/v2/Groups/{eng_id}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _, _) = scim(&app, "GET", &format!("/scim/v2/Groups/{eng_id}"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(orgs.store().get_group(&eng_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn bulk_resolves_bulk_ids_and_stops_on_errors() {
+        let pool = setup().await;
+        let (app, ctx) = app(&pool, Some("acme")).await;
+        ctx.orgs
+            .as_ref()
+            .unwrap()
+            .create_org("acme", "Acme")
+            .await
+            .unwrap();
+
+        let (status, _, result) = scim(
+            &app,
+            "POST",
+            "/scim/v2/Bulk",
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:BulkRequest"],
+                "Operations": [
+                    {"method": "POST", "path": "/Users", "bulkId": "u1",
+                     "data": {"userName": "kay@example.com"}},
+                    {"method": "POST", "path": "/Groups", "bulkId": "g1",
+                     "data": {"displayName": "Ops", "members": [{"value": "bulkId:u1"}]}},
+                    {"method": "PATCH", "path": "/Users/bulkId:u1",
+                     "data": {"Operations": [{"op": "replace", "path": "displayName", "value": "Kay"}]}},
+                    {"method": "DELETE", "path": "/Users/usr_missing"},
+                ],
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let ops = result["Operations"].as_array().unwrap();
+        let statuses: Vec<&str> = ops.iter().map(|o| o["status"].as_str().unwrap()).collect();
+        assert_eq!(statuses, ["201", "201", "200", "404"]);
+        assert_eq!(ops[0]["bulkId"], "u1");
+        let user_location = ops[0]["location"].as_str().unwrap();
+        let user_id = user_location.rsplit('/').next().unwrap();
+        let (_, _, user) = scim(&app, "GET", &format!("/scim/v2/Users/{user_id}"), None).await;
+        assert_eq!(user["displayName"], "Kay");
+        assert_eq!(user["groups"][0]["display"], "Ops");
+        assert_eq!(ops[3]["response"]["status"], "404");
+
+        let (_, _, result) = scim(
+            &app,
+            "POST",
+            "/scim/v2/Bulk",
+            Some(json!({
+                "failOnErrors": 1,
+                "Operations": [
+                    {"method": "POST", "path": "/Users", "data": {}},
+                    {"method": "POST", "path": "/Users", "data": {"userName": "never@example.com"}},
+                ],
+            })),
+        )
+        .await;
+        let ops = result["Operations"].as_array().unwrap();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0]["status"], "400");
+        assert_eq!(ops[0]["response"]["scimType"], "invalidValue");
+
+        let too_many: Vec<Value> = (0..101)
+            .map(|_| json!({"method": "DELETE", "path": "/Users/x"}))
+            .collect();
+        let (status, _, _) = scim(
+            &app,
+            "POST",
+            "/scim/v2/Bulk",
+            Some(json!({"Operations": too_many})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
+
+#[cfg(feature = "backend-postgres")]
+mod postgres_store {
+    use super::*;
+    use std::sync::Arc;
+
+    use assay_auth::orgs::{OrgManager, PostgresOrgStore};
+    use assay_auth::scim::PostgresScimStore;
+    use assay_auth::zanzibar::PostgresZanzibarStore;
+
+    async fn setup() -> Option<sqlx::PgPool> {
+        let url = std::env::var("ASSAY_TEST_DATABASE_URL").ok()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        let pool = sqlx::PgPool::connect(&url).await.ok()?;
+        sqlx::query("CREATE SCHEMA IF NOT EXISTS engine")
+            .execute(&pool)
+            .await
+            .ok()?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS engine.migrations (
+                module TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                PRIMARY KEY (module, version)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .ok()?;
+        assay_auth::schema::migrate_postgres(&pool).await.ok()?;
+        Some(pool)
+    }
+
+    #[tokio::test]
+    async fn store_contract() {
+        let Some(pool) = setup().await else {
+            eprintln!("skipping (ASSAY_TEST_DATABASE_URL not set)");
+            return;
+        };
+        let tag = uuid::Uuid::new_v4().simple().to_string();
+        let user_id = format!("user-scim-{tag}");
+        sqlx::query(
+            "INSERT INTO auth.users
+             (id, email, email_verified, display_name, password_hash, created_at)
+             VALUES ($1, NULL, FALSE, NULL, NULL, 1)",
+        )
+        .bind(&user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let orgs = OrgManager::new(
+            Arc::new(PostgresOrgStore::new(pool.clone())),
+            Arc::new(PostgresZanzibarStore::new(pool.clone())),
+        );
+        let org = orgs
+            .create_org(&format!("scim-{tag}"), "SCIM")
+            .await
+            .unwrap();
+        let group = orgs.create_group(&org.id, "eng", "Eng").await.unwrap();
+        exercise_store(
+            &PostgresScimStore::new(pool.clone()),
+            &user_id,
+            &group.id,
+            &tag,
+        )
+        .await;
+        orgs.delete_org(&org.id).await.unwrap();
+        sqlx::query("DELETE FROM auth.users WHERE id = $1")
+            .bind(&user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
@@ -19,6 +19,15 @@
 //! 5. Reverse `lookup_subjects` walks transitively.
 //! 6. Forward `lookup_resources` filters per subject.
 //! 7. Schema parser snapshot — the canonical plan-12c example.
+//! 8. Watch: writes/deletes surface as TOUCH/DELETE changes after a
+//!    zookie, filtered and paged.
+//! 9. Consistency: `Exact` reads the tuples live at a write's zookie,
+//!    `AtLeastAsFresh` reads current tuples, future zookies are refused.
+//! 10. Expiry: tuples past `expires_at` drop out of every read and
+//!     the sweep deletes them through the changelog.
+//! 11. History GC: compaction drops deleted tuple rows and changelog
+//!     entries below the horizon; `Exact` zookies and watch cursors
+//!     older than it are refused.
 //!
 //! Test ergonomics: a small `setup_sqlite` helper builds a pool with
 //! the auth db ATTACHed in shared-cache memory mode (matches what
@@ -32,8 +41,9 @@
 use std::collections::BTreeSet;
 
 use assay_auth::zanzibar::{
-    CheckResult, Consistency, NamespaceSchema, ObjectRef, PermissionExpr, RelationDef,
-    RelationKind, SubjectRef, Tuple, TypeRef, ZanzibarStore, parse_schema,
+    CaveatContext, CaveatError, CheckResult, Consistency, ConsistencyError, NamespaceSchema,
+    ObjectRef, PermissionExpr, RelationDef, RelationKind, SubjectRef, Tuple, TupleOperation,
+    TypeRef, WatchFilter, ZanzibarStore, parse_schema,
 };
 
 // ---------- Sqlite-only test setup ----------
@@ -42,9 +52,12 @@ use assay_auth::zanzibar::{
 mod sqlite_tests {
     use super::*;
     use assay_auth::zanzibar::SqliteZanzibarStore;
+    use assay_auth::zanzibar::expiry::{EXPIRED_EVENT_KIND, sweep_expired};
+    use assay_domain::events::{CursorGoneError, EngineEventBus, Event, EventFilter, NewEvent};
     use sqlx::SqlitePool;
     use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
     use std::str::FromStr;
+    use std::sync::Arc;
     use std::sync::atomic::{AtomicU64, Ordering};
 
     static SEQ: AtomicU64 = AtomicU64::new(0);
@@ -55,6 +68,10 @@ mod sqlite_tests {
     /// (auth's migrate fn writes to it), then runs the auth migration
     /// up to V3 — yielding `auth.zanzibar_*` tables ready for tests.
     pub async fn setup_sqlite() -> SqliteZanzibarStore {
+        SqliteZanzibarStore::new(setup_sqlite_pool().await)
+    }
+
+    async fn setup_sqlite_pool() -> SqlitePool {
         let suffix = format!(
             "{}_{}",
             std::process::id(),
@@ -102,7 +119,7 @@ mod sqlite_tests {
             .await
             .expect("run auth migration");
 
-        SqliteZanzibarStore::new(pool)
+        pool
     }
 
     #[tokio::test]
@@ -658,8 +675,20 @@ mod sqlite_tests {
             SubjectRef::direct("user", "alice"),
         );
         store.write_tuple(&t).await.expect("write");
-        assert!(store.delete_tuple(&t).await.expect("delete first"));
-        assert!(!store.delete_tuple(&t).await.expect("delete twice"));
+        assert!(
+            store
+                .delete_tuple(&t)
+                .await
+                .expect("delete first")
+                .is_some()
+        );
+        assert!(
+            store
+                .delete_tuple(&t)
+                .await
+                .expect("delete twice")
+                .is_none()
+        );
     }
 
     /// Re-defining a namespace overwrites the previous schema.
@@ -680,6 +709,209 @@ mod sqlite_tests {
         assert_eq!(fetched, ns);
     }
 
+    #[tokio::test]
+    async fn watch_streams_changes_since_zookie() {
+        let store = setup_sqlite().await;
+        assert_watch_semantics(&store).await;
+    }
+
+    #[tokio::test]
+    async fn consistency_modes_read_snapshots() {
+        // Re-running the migration must leave the rebuilt, versioned
+        // tuple table alone.
+        let pool = setup_sqlite_pool().await;
+        assay_auth::schema::migrate_sqlite(&pool)
+            .await
+            .expect("re-run auth migration");
+        assert_consistency_semantics(&SqliteZanzibarStore::new(pool)).await;
+    }
+
+    #[tokio::test]
+    async fn caveats_make_checks_conditional() {
+        let pool = setup_sqlite_pool().await;
+        assay_auth::schema::migrate_sqlite(&pool)
+            .await
+            .expect("re-run auth migration");
+        assert_caveat_semantics(&SqliteZanzibarStore::new(pool)).await;
+    }
+
+    #[tokio::test]
+    async fn expired_tuples_are_ignored_and_swept() {
+        let pool = setup_sqlite_pool().await;
+        assay_auth::schema::migrate_sqlite(&pool)
+            .await
+            .expect("re-run auth migration");
+        assert_expiry_semantics(&SqliteZanzibarStore::new(pool)).await;
+    }
+
+    #[tokio::test]
+    async fn history_gc_compacts_below_horizon() {
+        assert_gc_semantics(&setup_sqlite().await).await;
+    }
+
+    /// Records published events; the sweep only ever publishes.
+    #[derive(Default)]
+    struct RecordingBus {
+        events: std::sync::Mutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EngineEventBus for RecordingBus {
+        async fn publish_committed(&self, ev: NewEvent<'_>) -> anyhow::Result<i64> {
+            let mut events = self.events.lock().unwrap();
+            events.push((ev.kind.to_string(), ev.payload));
+            Ok(events.len() as i64)
+        }
+
+        async fn read_since(
+            &self,
+            _namespace: &str,
+            _after: Option<i64>,
+            _filter: &EventFilter,
+            _limit: u32,
+        ) -> Result<Vec<Event>, CursorGoneError> {
+            Ok(Vec::new())
+        }
+
+        fn subscribe(&self, _namespace: &str) -> tokio::sync::broadcast::Receiver<Arc<Event>> {
+            tokio::sync::broadcast::channel(1).1
+        }
+
+        async fn prune(&self, _before_ts: f64) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+
+        async fn oldest_id(&self, _namespace: &str) -> anyhow::Result<Option<i64>> {
+            Ok(None)
+        }
+    }
+
+    /// One sweep deletes every expired tuple and publishes one
+    /// `zanzibar.tuples_expired` event naming them; an idle sweep
+    /// publishes nothing.
+    #[tokio::test]
+    async fn sweep_publishes_expired_event() {
+        let store = setup_sqlite().await;
+        let doc = ObjectRef::new("document", "s");
+        let expired = Tuple::direct(doc.clone(), "viewer", SubjectRef::direct("user", "bob"))
+            .expiring_at(1_000);
+        let kept =
+            Tuple::direct(doc, "viewer", SubjectRef::direct("user", "alice")).expiring_at(5_000);
+        store
+            .write_tuples(&[expired.clone(), kept.clone()])
+            .await
+            .expect("write");
+
+        let bus = RecordingBus::default();
+        assert_eq!(sweep_expired(&store, &bus, 2_000).await.expect("sweep"), 1);
+        assert_eq!(sweep_expired(&store, &bus, 2_000).await.expect("idle"), 0);
+
+        let events = bus.events.lock().unwrap().clone();
+        assert_eq!(events.len(), 1);
+        let (kind, payload) = &events[0];
+        assert_eq!(kind, EXPIRED_EVENT_KIND);
+        assert_eq!(payload["count"], 1);
+        assert_eq!(
+            payload["tuples"],
+            serde_json::json!([serde_json::to_value(&expired).unwrap()])
+        );
+        assert!(payload["zookie"].is_string());
+        assert_eq!(
+            store.list_tuples(&Default::default()).await.expect("list"),
+            vec![kept]
+        );
+    }
+
+    #[tokio::test]
+    async fn watch_rejects_foreign_zookie() {
+        let store = setup_sqlite().await;
+        assert!(
+            store
+                .watch(Some("not-a-zookie"), &WatchFilter::default())
+                .await
+                .is_err()
+        );
+    }
+
+    /// `GET /admin/zanzibar/watch` end to end: bad zookies are a 400,
+    /// the long-poll answers with the pending change, and the SSE
+    /// transport emits it as a `TOUCH` event carrying its zookie.
+    #[tokio::test]
+    async fn watch_endpoint_long_polls_and_streams() {
+        use assay_auth::ctx::AuthCtx;
+        use assay_auth::state::{AdminApiKeys, AuthCtxWithAdmin};
+        use assay_auth::store::sqlite::{SqliteSessionStore, SqliteUserStore};
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use futures_util::StreamExt;
+        use tower::ServiceExt;
+
+        let pool = setup_sqlite_pool().await;
+        let store = SqliteZanzibarStore::new(pool.clone());
+        let ctx = AuthCtx::new(
+            Arc::new(SqliteUserStore::new(pool.clone())),
+            Arc::new(SqliteSessionStore::new(pool)),
+        )
+        .with_zanzibar(store.clone().into_dyn());
+        let app = assay_auth::router::router::<AuthCtxWithAdmin>().with_state(
+            AuthCtxWithAdmin::new(ctx).with_admin_keys(AdminApiKeys::from_keys(["admin-key"])),
+        );
+        let get = |uri: &str, accept: &str| {
+            Request::get(uri)
+                .header("authorization", "Bearer admin-key")
+                .header("accept", accept)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(get("/admin/zanzibar/watch?since=nope", "application/json"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let head = store
+            .watch(None, &WatchFilter::default())
+            .await
+            .unwrap()
+            .zookie;
+        let tuple = Tuple::direct(
+            ObjectRef::new("document", "http"),
+            "owner",
+            SubjectRef::direct("user", "alice"),
+        );
+        store.write_tuple(&tuple).await.unwrap();
+
+        let resp = app
+            .clone()
+            .oneshot(get(
+                &format!("/admin/zanzibar/watch?since={head}&timeout_secs=0"),
+                "application/json",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let batch: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(batch["changes"][0]["operation"], "TOUCH");
+        assert_eq!(batch["changes"][0]["tuple"]["object_id"], "http");
+        let zookie = batch["zookie"].as_str().unwrap().to_string();
+
+        let mut req = get("/admin/zanzibar/watch", "text/event-stream");
+        req.headers_mut()
+            .insert("last-event-id", head.parse().unwrap());
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let mut body = resp.into_body().into_data_stream();
+        let frame = body.next().await.unwrap().unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(frame.contains("event: TOUCH"), "frame: {frame}");
+        assert!(frame.contains(&format!("id: {zookie}")), "frame: {frame}");
+    }
+
     /// Helper — defines the canonical `document` namespace shared by
     /// most round-trip tests. `view = owner + viewer`, `edit = owner`.
     async fn seed_doc_namespace(store: &SqliteZanzibarStore) {
@@ -713,6 +945,639 @@ mod sqlite_tests {
     }
 }
 
+/// Backend-agnostic Watch scenario shared by the SQLite and Postgres
+/// suites.
+async fn assert_watch_semantics(store: &dyn ZanzibarStore) {
+    let start = store
+        .watch(None, &WatchFilter::default())
+        .await
+        .expect("head");
+    assert!(start.changes.is_empty());
+
+    let doc = ObjectRef::new("document", "w");
+    let alice = Tuple::direct(doc.clone(), "owner", SubjectRef::direct("user", "alice"));
+    let bob = Tuple::direct(doc.clone(), "viewer", SubjectRef::direct("user", "bob"));
+    let folder = Tuple::direct(
+        ObjectRef::new("folder", "f"),
+        "viewer",
+        SubjectRef::direct("user", "bob"),
+    );
+    store
+        .write_tuples(&[alice.clone(), bob.clone()])
+        .await
+        .expect("write");
+    // Re-writing an existing tuple and deleting a missing one change
+    // nothing, so neither shows up in the feed.
+    store.write_tuple(&alice).await.expect("rewrite");
+    assert!(
+        store
+            .delete_tuple(&folder)
+            .await
+            .expect("delete missing")
+            .is_none()
+    );
+    store.write_tuple(&folder).await.expect("write folder");
+    assert!(store.delete_tuple(&alice).await.expect("delete").is_some());
+
+    let all = store
+        .watch(Some(&start.zookie), &WatchFilter::default())
+        .await
+        .expect("watch");
+    let seen: Vec<(TupleOperation, Tuple)> = all
+        .changes
+        .iter()
+        .map(|c| (c.operation, c.tuple.clone()))
+        .collect();
+    assert_eq!(
+        seen,
+        vec![
+            (TupleOperation::Touch, alice.clone()),
+            (TupleOperation::Touch, bob.clone()),
+            (TupleOperation::Touch, folder.clone()),
+            (TupleOperation::Delete, alice.clone()),
+        ]
+    );
+    assert_eq!(all.zookie, all.changes.last().unwrap().zookie);
+
+    // Caught up: resuming from the returned zookie yields nothing.
+    let idle = store
+        .watch(Some(&all.zookie), &WatchFilter::default())
+        .await
+        .expect("idle");
+    assert!(idle.changes.is_empty());
+    assert_eq!(idle.zookie, all.zookie);
+
+    // Filtered: only folder changes, but the cursor still reaches head.
+    let folders = store
+        .watch(
+            Some(&start.zookie),
+            &WatchFilter {
+                object_type: Some("folder".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("filtered");
+    assert_eq!(folders.changes.len(), 1);
+    assert_eq!(folders.changes[0].tuple, folder);
+    assert_eq!(folders.zookie, all.zookie);
+
+    // Paged: a full page resumes right after its last change.
+    let page = WatchFilter {
+        limit: Some(3),
+        ..Default::default()
+    };
+    let first = store
+        .watch(Some(&start.zookie), &page)
+        .await
+        .expect("page 1");
+    assert_eq!(first.changes.len(), 3);
+    let rest = store
+        .watch(Some(&first.zookie), &page)
+        .await
+        .expect("page 2");
+    assert_eq!(rest.changes.len(), 1);
+    assert_eq!(rest.changes[0].operation, TupleOperation::Delete);
+}
+
+/// Backend-agnostic expiry scenario shared by the SQLite and Postgres
+/// suites: a tuple past its `expires_at` is invisible to every read,
+/// rewriting it moves the deadline, and `expire_tuples` deletes it
+/// through the changelog.
+async fn assert_expiry_semantics(store: &dyn ZanzibarStore) {
+    let src = r#"
+        definition user {}
+
+        definition document {
+            relation viewer: user
+            permission view = viewer
+        }
+    "#;
+    for ns in parse_schema(src).expect("parse") {
+        store.define_namespace(&ns).await.expect("define");
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let doc = ObjectRef::new("document", "x");
+    let alice = SubjectRef::direct("user", "alice");
+    let bob = SubjectRef::direct("user", "bob");
+    let carol = SubjectRef::direct("user", "carol");
+    let stale = Tuple::direct(doc.clone(), "viewer", bob.clone()).expiring_at(now - 10);
+    let live = Tuple::direct(doc.clone(), "viewer", alice.clone()).expiring_at(now + 3600);
+    let forever = Tuple::direct(doc.clone(), "viewer", carol.clone());
+    store
+        .write_tuples(&[stale.clone(), live.clone(), forever.clone()])
+        .await
+        .expect("write");
+
+    let view = |subject: SubjectRef| {
+        let doc = doc.clone();
+        async move {
+            store
+                .check(&doc, "view", &subject, Consistency::Minimum)
+                .await
+                .expect("check")
+        }
+    };
+    assert_eq!(view(bob.clone()).await, CheckResult::Denied);
+    assert!(view(alice.clone()).await.is_allowed());
+    assert!(view(carol.clone()).await.is_allowed());
+
+    let subjects: BTreeSet<String> = store
+        .lookup_subjects("user", &doc, "view")
+        .await
+        .expect("lookup_subjects")
+        .into_iter()
+        .map(|s| s.subject_id)
+        .collect();
+    assert_eq!(
+        subjects,
+        BTreeSet::from(["alice".to_string(), "carol".to_string()])
+    );
+    assert!(
+        store
+            .lookup_resources("document", "view", &bob)
+            .await
+            .expect("lookup_resources")
+            .is_empty()
+    );
+    let tree = store.expand(&doc, "viewer", 8).await.expect("expand");
+    let leaves = serde_json::to_string(&tree).unwrap();
+    assert!(leaves.contains("alice") && leaves.contains("carol"));
+    assert!(!leaves.contains("bob"), "expired tuple in expand: {leaves}");
+
+    // Expired-but-unswept tuples still list, carrying their deadline.
+    let filter = assay_auth::zanzibar::TupleFilter {
+        object_type: Some("document".into()),
+        ..Default::default()
+    };
+    let listed = store.list_tuples(&filter).await.expect("list");
+    assert_eq!(listed.len(), 3);
+    assert!(listed.contains(&stale));
+
+    // Rewriting moves the deadline: alice's grant lapses, bob's returns.
+    let lapsed = live.clone().expiring_at(now - 1);
+    store.write_tuple(&lapsed).await.expect("lapse alice");
+    assert_eq!(view(alice.clone()).await, CheckResult::Denied);
+    let renewed = stale.clone().expiring_at(now + 60);
+    store.write_tuple(&renewed).await.expect("renew bob");
+    assert!(view(bob.clone()).await.is_allowed());
+    store.write_tuple(&stale).await.expect("expire bob again");
+
+    let before = store
+        .watch(None, &WatchFilter::default())
+        .await
+        .expect("head");
+    // Soonest-expired first, `limit` at a time.
+    let first = store.expire_tuples(now, 1).await.expect("expire");
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].operation, TupleOperation::Delete);
+    assert_eq!(first[0].tuple, stale);
+    let rest = store.expire_tuples(now, 10).await.expect("expire rest");
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].tuple, lapsed);
+    assert!(
+        store
+            .expire_tuples(now, 10)
+            .await
+            .expect("nothing left")
+            .is_empty()
+    );
+
+    let feed = store
+        .watch(Some(&before.zookie), &WatchFilter::default())
+        .await
+        .expect("watch");
+    let seen: Vec<(TupleOperation, Tuple)> = feed
+        .changes
+        .iter()
+        .map(|c| (c.operation, c.tuple.clone()))
+        .collect();
+    assert_eq!(
+        seen,
+        vec![
+            (TupleOperation::Delete, stale.clone()),
+            (TupleOperation::Delete, lapsed.clone()),
+        ]
+    );
+    assert_eq!(feed.zookie, rest[0].zookie);
+    assert_eq!(
+        store.list_tuples(&filter).await.expect("list after"),
+        vec![forever]
+    );
+}
+
+/// Backend-agnostic consistency scenario shared by the SQLite and
+/// Postgres suites: a tuple written, deleted, and re-written is visible
+/// at exactly the zookies where it was live.
+async fn assert_consistency_semantics(store: &dyn ZanzibarStore) {
+    let ns = NamespaceSchema::new("document")
+        .with_relation(
+            "owner",
+            RelationDef::relation("owner", vec![TypeRef::direct("user")]),
+        )
+        .with_relation(
+            "view",
+            RelationDef::permission("view", PermissionExpr::direct("owner")),
+        );
+    store.define_namespace(&ns).await.expect("define");
+    let doc = ObjectRef::new("document", "c");
+    let alice = SubjectRef::direct("user", "alice");
+    let owner = Tuple::direct(doc.clone(), "owner", alice.clone());
+    let view = |consistency: Consistency| {
+        let doc = doc.clone();
+        let alice = alice.clone();
+        async move {
+            store
+                .check(&doc, "view", &alice, consistency)
+                .await
+                .map(|r| r.is_allowed())
+        }
+    };
+
+    let before = store.write_tuples(&[]).await.expect("head");
+    let written = store.write_tuple(&owner).await.expect("write");
+    assert_ne!(written, before);
+    // A no-op re-write hands back a zookie the tuple is visible from.
+    assert_eq!(store.write_tuple(&owner).await.expect("rewrite"), written);
+    let deleted = store
+        .delete_tuple(&owner)
+        .await
+        .expect("delete")
+        .expect("was live");
+    let rewritten = store.write_tuple(&owner).await.expect("write again");
+
+    assert!(!view(Consistency::Exact(before.clone())).await.unwrap());
+    assert!(view(Consistency::Exact(written.clone())).await.unwrap());
+    assert!(!view(Consistency::Exact(deleted.clone())).await.unwrap());
+    assert!(view(Consistency::Exact(rewritten.clone())).await.unwrap());
+    assert!(
+        view(Consistency::AtLeastAsFresh(written.clone()))
+            .await
+            .unwrap()
+    );
+    assert!(view(Consistency::Minimum).await.unwrap());
+
+    // After a final delete, fresh reads deny while the old snapshot
+    // still grants; deleted rows never leak into listings.
+    let gone = store
+        .delete_tuple(&owner)
+        .await
+        .expect("delete")
+        .expect("was live");
+    assert!(
+        !view(Consistency::AtLeastAsFresh(gone.clone()))
+            .await
+            .unwrap()
+    );
+    assert!(view(Consistency::Exact(rewritten.clone())).await.unwrap());
+    let listed = store
+        .list_tuples(&assay_auth::zanzibar::TupleFilter {
+            object_type: Some("document".into()),
+            object_id: Some("c".into()),
+            ..Default::default()
+        })
+        .await
+        .expect("list");
+    assert!(listed.is_empty());
+
+    let ahead = (gone.parse::<i64>().unwrap() + 100).to_string();
+    for consistency in [
+        Consistency::Exact(ahead.clone()),
+        Consistency::AtLeastAsFresh(ahead.clone()),
+    ] {
+        let err = view(consistency).await.expect_err("ahead of head");
+        assert!(matches!(
+            err.downcast_ref::<ConsistencyError>(),
+            Some(ConsistencyError::AheadOfHead { .. })
+        ));
+    }
+    let err = view(Consistency::Exact("nope".into()))
+        .await
+        .expect_err("invalid");
+    assert!(matches!(
+        err.downcast_ref::<ConsistencyError>(),
+        Some(ConsistencyError::InvalidZookie(_))
+    ));
+}
+
+/// Backend-agnostic history-GC scenario shared by the SQLite and
+/// Postgres suites, run against a fresh store: compaction removes the
+/// history below the horizon, and zookies that would read it are
+/// refused instead of answering from compacted state.
+async fn assert_gc_semantics(store: &dyn ZanzibarStore) {
+    use assay_auth::zanzibar::gc::compact_history;
+    use std::time::Duration;
+
+    let ns = NamespaceSchema::new("document")
+        .with_relation(
+            "owner",
+            RelationDef::relation("owner", vec![TypeRef::direct("user")]),
+        )
+        .with_relation(
+            "view",
+            RelationDef::permission("view", PermissionExpr::direct("owner")),
+        );
+    store.define_namespace(&ns).await.expect("define");
+    let doc = ObjectRef::new("document", "g");
+    let alice = SubjectRef::direct("user", "alice");
+    let bob = SubjectRef::direct("user", "bob");
+    let view = |subject: &SubjectRef, consistency: Consistency| {
+        let doc = doc.clone();
+        let subject = subject.clone();
+        async move {
+            store
+                .check(&doc, "view", &subject, consistency)
+                .await
+                .map(|r| r.is_allowed())
+        }
+    };
+    let compacted = |err: anyhow::Error| {
+        matches!(
+            err.downcast_ref::<ConsistencyError>(),
+            Some(ConsistencyError::Compacted { .. })
+        )
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let day = Duration::from_secs(86_400);
+
+    let before = store.write_tuples(&[]).await.expect("head");
+    let alice_owner = Tuple::direct(doc.clone(), "owner", alice.clone());
+    let written = store.write_tuple(&alice_owner).await.expect("write");
+    store
+        .delete_tuple(&alice_owner)
+        .await
+        .expect("delete")
+        .expect("was live");
+    let kept = store
+        .write_tuple(&Tuple::direct(doc.clone(), "owner", bob.clone()))
+        .await
+        .expect("write bob");
+
+    // Everything happened inside the window: nothing is compacted and
+    // the oldest zookie still reads its snapshot.
+    let c = compact_history(store, now, day).await.expect("gc");
+    assert_eq!((c.tuples, c.changes), (0, 0));
+    assert_eq!(c.horizon.to_string(), before);
+    assert!(
+        view(&alice, Consistency::Exact(written.clone()))
+            .await
+            .unwrap()
+    );
+
+    // A window ending after the last write compacts up to the head:
+    // alice's deleted row and the two changes before bob's write go.
+    let c = compact_history(store, now + 60, Duration::ZERO)
+        .await
+        .expect("gc");
+    assert_eq!(c.horizon.to_string(), kept);
+    assert_eq!((c.tuples, c.changes), (1, 2));
+
+    for zookie in [&before, &written] {
+        let err = view(&alice, Consistency::Exact(zookie.clone()))
+            .await
+            .expect_err("compacted snapshot");
+        assert!(compacted(err));
+        let err = store
+            .watch(Some(zookie), &WatchFilter::default())
+            .await
+            .expect_err("compacted cursor");
+        assert!(compacted(err));
+    }
+    // The horizon itself and current reads are untouched; the live
+    // tuple written before it survives.
+    assert!(view(&bob, Consistency::Exact(kept.clone())).await.unwrap());
+    assert!(
+        !view(&alice, Consistency::Exact(kept.clone()))
+            .await
+            .unwrap()
+    );
+    assert!(view(&bob, Consistency::Minimum).await.unwrap());
+    let idle = store
+        .watch(Some(&kept), &WatchFilter::default())
+        .await
+        .expect("watch from horizon");
+    assert!(idle.changes.is_empty());
+
+    // A wider window never moves the horizon back.
+    let c = compact_history(store, now, day).await.expect("gc");
+    assert_eq!(c.horizon.to_string(), kept);
+    assert_eq!((c.tuples, c.changes), (0, 0));
+    let later = store.write_tuple(&alice_owner).await.expect("write again");
+    let batch = store
+        .watch(Some(&kept), &WatchFilter::default())
+        .await
+        .expect("watch");
+    assert_eq!(batch.zookie, later);
+    assert_eq!(batch.changes.len(), 1);
+}
+
+/// Backend-agnostic caveat scenario shared by the SQLite and Postgres
+/// suites: caveated grants are allowed, denied, or conditional on the
+/// request context, and never show up in lookups.
+async fn assert_caveat_semantics(store: &dyn ZanzibarStore) {
+    let src = r#"
+        definition user {}
+
+        definition folder {
+            relation viewer: user
+        }
+
+        definition document {
+            relation owner: user
+            relation viewer: user | user with in_office
+            relation parent: folder with business_hours
+            permission view = owner + viewer + parent->viewer
+        }
+
+        caveat in_office(client_ip ipaddress, office_cidr string) {
+            client_ip.in_cidr(office_cidr)
+        }
+
+        caveat business_hours(now timestamp) {
+            now.getHours() >= 9 && now.getHours() < 17
+        }
+    "#;
+    for ns in parse_schema(src).expect("parse") {
+        store.define_namespace(&ns).await.expect("define");
+    }
+    let ctx = |v: serde_json::Value| -> CaveatContext {
+        serde_json::from_value(v).expect("context object")
+    };
+    let doc = ObjectRef::new("document", "cv");
+    let folder = ObjectRef::new("folder", "f");
+    let alice = SubjectRef::direct("user", "alice");
+    let bob = SubjectRef::direct("user", "bob");
+    let carol = SubjectRef::direct("user", "carol");
+    let bob_viewer = Tuple::direct(doc.clone(), "viewer", bob.clone()).with_caveat(
+        "in_office",
+        ctx(serde_json::json!({"office_cidr": "10.0.0.0/8"})),
+    );
+
+    let start = store.write_tuples(&[]).await.expect("head");
+    store
+        .write_tuples(&[
+            Tuple::direct(doc.clone(), "owner", alice.clone()),
+            bob_viewer.clone(),
+            Tuple::direct(doc.clone(), "parent", SubjectRef::direct("folder", "f"))
+                .with_caveat("business_hours", CaveatContext::new()),
+            Tuple::direct(folder.clone(), "viewer", carol.clone()),
+        ])
+        .await
+        .expect("write");
+
+    let view = |subject: SubjectRef, context: serde_json::Value| {
+        let doc = doc.clone();
+        let context = ctx(context);
+        async move {
+            store
+                .check_with_context(&doc, "view", &subject, Consistency::Minimum, &context)
+                .await
+        }
+    };
+    let conditional = |missing: &[&str]| CheckResult::Conditional {
+        missing_context: missing.iter().map(|m| m.to_string()).collect(),
+    };
+
+    assert!(
+        view(alice.clone(), serde_json::json!({}))
+            .await
+            .unwrap()
+            .is_allowed()
+    );
+    assert_eq!(
+        view(bob.clone(), serde_json::json!({})).await.unwrap(),
+        conditional(&["client_ip"])
+    );
+    assert!(
+        view(bob.clone(), serde_json::json!({"client_ip": "10.1.2.3"}))
+            .await
+            .unwrap()
+            .is_allowed()
+    );
+    assert_eq!(
+        view(bob.clone(), serde_json::json!({"client_ip": "192.168.0.1"}))
+            .await
+            .unwrap(),
+        CheckResult::Denied
+    );
+    let err = view(bob.clone(), serde_json::json!({"client_ip": "nope"}))
+        .await
+        .expect_err("bad ip");
+    assert!(err.is::<CaveatError>(), "{err:?}");
+
+    // The caveat sits on the arrow hop, not on carol's folder tuple.
+    assert_eq!(
+        view(carol.clone(), serde_json::json!({})).await.unwrap(),
+        conditional(&["now"])
+    );
+    assert!(
+        view(
+            carol.clone(),
+            serde_json::json!({"now": "2026-10-19T10:30:00Z"})
+        )
+        .await
+        .unwrap()
+        .is_allowed()
+    );
+    assert_eq!(
+        view(
+            carol.clone(),
+            serde_json::json!({"now": "2026-10-19T20:00:00Z"})
+        )
+        .await
+        .unwrap(),
+        CheckResult::Denied
+    );
+
+    // Lookups have no context to decide caveats with.
+    assert_eq!(
+        store
+            .lookup_subjects("user", &doc, "view")
+            .await
+            .expect("lookup subjects"),
+        vec![alice.clone()]
+    );
+    assert!(
+        store
+            .lookup_resources("document", "view", &bob)
+            .await
+            .expect("lookup resources")
+            .is_empty()
+    );
+
+    // Same caveat again is a no-op; a different one replaces the tuple.
+    let written = store.write_tuple(&bob_viewer).await.expect("rewrite");
+    assert_eq!(
+        store.write_tuple(&bob_viewer).await.expect("again"),
+        written
+    );
+    let moved = Tuple::direct(doc.clone(), "viewer", bob.clone()).with_caveat(
+        "in_office",
+        ctx(serde_json::json!({"office_cidr": "192.168.0.0/16"})),
+    );
+    store.write_tuple(&moved).await.expect("replace");
+    assert!(
+        view(bob.clone(), serde_json::json!({"client_ip": "192.168.0.1"}))
+            .await
+            .unwrap()
+            .is_allowed()
+    );
+    let listed = store
+        .list_tuples(&assay_auth::zanzibar::TupleFilter {
+            object_type: Some("document".into()),
+            relation: Some("viewer".into()),
+            ..Default::default()
+        })
+        .await
+        .expect("list");
+    assert_eq!(listed, vec![moved.clone()]);
+
+    let changes: Vec<(TupleOperation, Tuple)> = store
+        .watch(
+            Some(&start),
+            &WatchFilter {
+                relation: Some("viewer".into()),
+                subject_id: Some("bob".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("watch")
+        .changes
+        .into_iter()
+        .map(|c| (c.operation, c.tuple))
+        .collect();
+    // A replacement is a single TOUCH carrying the new caveat.
+    assert_eq!(
+        changes,
+        vec![
+            (TupleOperation::Touch, bob_viewer.clone()),
+            (TupleOperation::Touch, moved.clone()),
+        ]
+    );
+
+    // Delete ignores the caveat.
+    assert!(
+        store
+            .delete_tuple(&Tuple::direct(doc.clone(), "viewer", bob.clone()))
+            .await
+            .expect("delete")
+            .is_some()
+    );
+    assert_eq!(
+        view(bob.clone(), serde_json::json!({"client_ip": "192.168.0.1"}))
+            .await
+            .unwrap(),
+        CheckResult::Denied
+    );
+}
+
 // ---------- Postgres tests (gated on env) ----------
 
 #[cfg(feature = "backend-postgres")]
@@ -720,12 +1585,19 @@ mod postgres_tests {
     use super::*;
     use assay_auth::zanzibar::PostgresZanzibarStore;
     use sqlx::PgPool;
+    use tokio::sync::{Mutex, MutexGuard};
+
+    /// Every PG test drops and recreates the `auth` schema, so they
+    /// take turns.
+    static PG_LOCK: Mutex<()> = Mutex::const_new(());
 
     /// Skip helper — returns `Some(PgPool)` if `ASSAY_TEST_DATABASE_URL`
     /// is set and the engine + auth schemas migrate cleanly. Returns
     /// `None` to skip the test on dev machines without docker/PG.
-    async fn maybe_setup_pg() -> Option<PostgresZanzibarStore> {
+    /// The guard keeps other PG tests off the schema until dropped.
+    async fn maybe_setup_pg() -> Option<(MutexGuard<'static, ()>, PostgresZanzibarStore)> {
         let url = std::env::var("ASSAY_TEST_DATABASE_URL").ok()?;
+        let guard = PG_LOCK.lock().await;
         let pool = PgPool::connect(&url).await.ok()?;
         // Minimal `engine` schema scaffold (the auth migrate writes
         // into `engine.migrations`).
@@ -749,12 +1621,12 @@ mod postgres_tests {
             .await
             .ok()?;
         assay_auth::schema::migrate_postgres(&pool).await.ok()?;
-        Some(PostgresZanzibarStore::new(pool))
+        Some((guard, PostgresZanzibarStore::new(pool)))
     }
 
     #[tokio::test]
     async fn pg_roundtrip_check() {
-        let Some(store) = maybe_setup_pg().await else {
+        let Some((_guard, store)) = maybe_setup_pg().await else {
             eprintln!("ASSAY_TEST_DATABASE_URL unset — skipping pg_roundtrip_check");
             return;
         };
@@ -801,6 +1673,61 @@ mod postgres_tests {
             CheckResult::Denied
         );
     }
+
+    #[tokio::test]
+    async fn pg_watch_streams_changes_since_zookie() {
+        let Some((_guard, store)) = maybe_setup_pg().await else {
+            eprintln!(
+                "ASSAY_TEST_DATABASE_URL unset — skipping pg_watch_streams_changes_since_zookie"
+            );
+            return;
+        };
+        assert_watch_semantics(&store).await;
+    }
+
+    #[tokio::test]
+    async fn pg_consistency_modes_read_snapshots() {
+        let Some((_guard, store)) = maybe_setup_pg().await else {
+            eprintln!(
+                "ASSAY_TEST_DATABASE_URL unset — skipping pg_consistency_modes_read_snapshots"
+            );
+            return;
+        };
+        assert_consistency_semantics(&store).await;
+    }
+
+    #[tokio::test]
+    async fn pg_caveats_make_checks_conditional() {
+        let Some((_guard, store)) = maybe_setup_pg().await else {
+            eprintln!(
+                "ASSAY_TEST_DATABASE_URL unset — skipping pg_caveats_make_checks_conditional"
+            );
+            return;
+        };
+        assert_caveat_semantics(&store).await;
+    }
+
+    #[tokio::test]
+    async fn pg_expired_tuples_are_ignored_and_swept() {
+        let Some((_guard, store)) = maybe_setup_pg().await else {
+            eprintln!(
+                "ASSAY_TEST_DATABASE_URL unset — skipping pg_expired_tuples_are_ignored_and_swept"
+            );
+            return;
+        };
+        assert_expiry_semantics(&store).await;
+    }
+
+    #[tokio::test]
+    async fn pg_history_gc_compacts_below_horizon() {
+        let Some((_guard, store)) = maybe_setup_pg().await else {
+            eprintln!(
+                "ASSAY_TEST_DATABASE_URL unset — skipping pg_history_gc_compacts_below_horizon"
+            );
+            return;
+        };
+        assert_gc_semantics(&store).await;
+    }
 }
 
 // ---------- Schema parser snapshot (no backend needed) ----------
@@ -219,10 +219,10 @@ fn validate_scalar_condition(authored: &Authored<'_>) -> Result<PolicyCondition,
 
 fn check_scalar_value(operator: ConditionOperator, value: &str) -> Result<(), String> {
     match operator {
-        ConditionOperator::NumericLessThan | ConditionOperator::NumericGreaterThan => {
-            if value.trim().is_empty() || parse_number(value).is_none() {
-                return Err(format!("\"{value}\" is not a number"));
-            }
+        ConditionOperator::NumericLessThan | ConditionOperator::NumericGreaterThan
+            if value.trim().is_empty() || parse_number(value).is_none() =>
+        {
+            return Err(format!("\"{value}\" is not a number"));
         }
         ConditionOperator::DateLessThan | ConditionOperator::DateGreaterThan => {
             if parse_instant(value).is_none() {
@@ -92,10 +92,12 @@
   const views = {
     users: typeof AssayAuthUsers !== 'undefined' ? AssayAuthUsers : null,
     sessions: typeof AssayAuthSessions !== 'undefined' ? AssayAuthSessions : null,
+    orgs: typeof AssayAuthOrgs !== 'undefined' ? AssayAuthOrgs : null,
     'oidc-clients': typeof AssayAuthOidcClients !== 'undefined' ? AssayAuthOidcClients : null,
     'oidc-upstream': typeof AssayAuthOidcUpstream !== 'undefined' ? AssayAuthOidcUpstream : null,
     zanzibar: typeof AssayAuthZanzibar !== 'undefined' ? AssayAuthZanzibar : null,
     keys: typeof AssayAuthKeys !== 'undefined' ? AssayAuthKeys : null,
+    'api-keys': typeof AssayAuthApiKeys !== 'undefined' ? AssayAuthApiKeys : null,
     audit: typeof AssayAuthAudit !== 'undefined' ? AssayAuthAudit : null,
   };
 
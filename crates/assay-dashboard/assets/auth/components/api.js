@@ -72,6 +72,7 @@
     resetPassword: function (id, password) {
       return call('POST', '/admin/users/' + encodeURIComponent(id) + '/password-reset', { password: password });
     },
+    unlockUser: function (id) { return call('DELETE', '/admin/users/' + encodeURIComponent(id) + '/lockout'); },
 
     // Sessions
     listSessions: function (params) { return call('GET', '/admin/sessions' + qs(params)); },
@@ -80,6 +81,33 @@
       return call('DELETE', '/admin/sessions/by-user/' + encodeURIComponent(uid));
     },
 
+    // Organizations + groups
+    listOrgs: function (params) { return call('GET', '/admin/orgs' + qs(params)); },
+    createOrg: function (body) { return call('POST', '/admin/orgs', body); },
+    getOrg: function (id) { return call('GET', '/admin/orgs/' + encodeURIComponent(id)); },
+    updateOrg: function (id, body) { return call('PUT', '/admin/orgs/' + encodeURIComponent(id), body); },
+    deleteOrg: function (id) { return call('DELETE', '/admin/orgs/' + encodeURIComponent(id)); },
+    listOrgMembers: function (id, params) {
+      return call('GET', '/admin/orgs/' + encodeURIComponent(id) + '/members' + qs(params));
+    },
+    setOrgMember: function (id, userId, role) {
+      return call('PUT', '/admin/orgs/' + encodeURIComponent(id) + '/members/' + encodeURIComponent(userId), { role: role });
+    },
+    removeOrgMember: function (id, userId) {
+      return call('DELETE', '/admin/orgs/' + encodeURIComponent(id) + '/members/' + encodeURIComponent(userId));
+    },
+    createGroup: function (orgId, body) {
+      return call('POST', '/admin/orgs/' + encodeURIComponent(orgId) + '/groups', body);
+    },
+    getGroup: function (id) { return call('GET', '/admin/groups/' + encodeURIComponent(id)); },
+    deleteGroup: function (id) { return call('DELETE', '/admin/groups/' + encodeURIComponent(id)); },
+    addGroupMember: function (id, type, memberId) {
+      return call('PUT', '/admin/groups/' + encodeURIComponent(id) + '/members/' + encodeURIComponent(type) + '/' + encodeURIComponent(memberId));
+    },
+    removeGroupMember: function (id, type, memberId) {
+      return call('DELETE', '/admin/groups/' + encodeURIComponent(id) + '/members/' + encodeURIComponent(type) + '/' + encodeURIComponent(memberId));
+    },
+
     // OIDC clients
     listOidcClients: function () { return call('GET', '/admin/oidc/clients'); },
     createOidcClient: function (body) { return call('POST', '/admin/oidc/clients', body); },
@@ -106,6 +134,9 @@
     defineZanzibarNamespace: function (body) {
       return call('POST', '/admin/zanzibar/namespaces', body);
     },
+    listZanzibarTuples: function (params) {
+      return call('GET', '/admin/zanzibar/tuples' + qs(params));
+    },
     writeZanzibarTuple: function (body) { return call('POST', '/admin/zanzibar/tuples', body); },
     deleteZanzibarTuple: function (body) {
       // DELETE with body is non-standard but supported here for tuple deletion
@@ -135,5 +166,10 @@
 
     // Audit
     audit: function (params) { return call('GET', '/admin/audit' + qs(params)); },
+
+    // API keys — managed with the static admin key only
+    listApiKeys: function () { return call('GET', '/admin/api-keys'); },
+    createApiKey: function (body) { return call('POST', '/admin/api-keys', body); },
+    revokeApiKey: function (id) { return call('DELETE', '/admin/api-keys/' + encodeURIComponent(id)); },
   };
 })();
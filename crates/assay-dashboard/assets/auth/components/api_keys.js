@@ -0,0 +1,136 @@
+/* API Keys pane — scoped, expiring keys over /admin/api-keys with show-token-once. */
+
+var AssayAuthApiKeys = (function () {
+  'use strict';
+
+  let ctx = null;
+  let container = null;
+
+  function render(el, c) {
+    ctx = c;
+    container = el;
+    container.innerHTML =
+      '<div class="auth-toolbar">' +
+        '<h2 class="section-title">API Keys</h2>' +
+        '<button type="button" class="btn btn-primary" id="ak-new">New key</button>' +
+      '</div>' +
+      '<div id="ak-wrap"><div class="auth-empty">Loading…</div></div>';
+
+    document.getElementById('ak-new').addEventListener('click', openCreate);
+
+    container.addEventListener('click', function (e) {
+      const action = e.target.closest('[data-action]');
+      if (!action) return;
+      if (action.dataset.action === 'revoke') return revoke(action.dataset.id, action.dataset.name);
+    });
+
+    load();
+  }
+
+  async function load() {
+    const wrap = container.querySelector('#ak-wrap');
+    try {
+      const res = await ctx.api.listApiKeys();
+      if (!res.enabled) {
+        wrap.innerHTML = '<div class="auth-empty">API keys are not enabled on this engine.</div>';
+        return;
+      }
+      renderTable(wrap, res.items || []);
+    } catch (err) {
+      wrap.innerHTML = '<div class="auth-empty">Error: ' + ctx.escapeHtml(err.message) + '</div>';
+    }
+  }
+
+  function renderTable(wrap, items) {
+    if (!items.length) {
+      wrap.innerHTML = '<div class="auth-empty">No API keys. Mint one per pipeline or service instead of sharing the admin key.</div>';
+      return;
+    }
+    const now = Date.now() / 1000;
+    let html = '<table class="data-table"><thead><tr>' +
+      '<th>Name</th><th>Owner</th><th>Prefix</th><th>Scopes</th><th>Expires</th><th>Last used</th><th></th>' +
+      '</tr></thead><tbody>';
+    items.forEach(function (k) {
+      const expired = k.expires_at && k.expires_at <= now;
+      html += '<tr>' +
+        '<td>' + ctx.escapeHtml(k.name) + '</td>' +
+        '<td>' + ctx.escapeHtml(k.owner) + '</td>' +
+        '<td class="auth-mono">' + ctx.escapeHtml(k.prefix) + '…</td>' +
+        '<td class="auth-mono">' + (k.scopes || []).map(function (s) { return '<div>' + ctx.escapeHtml(s) + '</div>'; }).join('') + '</td>' +
+        '<td>' + (k.expires_at ? ctx.escapeHtml(new Date(k.expires_at * 1000).toLocaleString()) + (expired ? ' (expired)' : '') : 'never') + '</td>' +
+        '<td>' + (k.last_used_at ? ctx.escapeHtml(ctx.formatTime(k.last_used_at)) : '—') + '</td>' +
+        '<td><button class="btn btn-small btn-danger" data-action="revoke" data-id="' + ctx.escapeHtml(k.id) +
+          '" data-name="' + ctx.escapeHtml(k.name) + '">Revoke</button></td>' +
+      '</tr>';
+    });
+    html += '</tbody></table>';
+    wrap.innerHTML = html;
+  }
+
+  function openCreate() {
+    const wrap = container.querySelector('#ak-wrap');
+    wrap.innerHTML = '<h3>New API key</h3>' +
+      '<div class="auth-form">' +
+        '<label for="ak-name">Name</label><input type="text" id="ak-name" placeholder="deploy-pipeline" />' +
+        '<label for="ak-owner">Owner</label><input type="text" id="ak-owner" placeholder="platform-team" />' +
+        '<label for="ak-scopes">Scopes (one per line)</label>' +
+        '<textarea id="ak-scopes" placeholder="workflow:namespace=prod:write&#10;vault:kv:read:/apps/*"></textarea>' +
+        '<label for="ak-expires">Expires (optional)</label><input type="datetime-local" id="ak-expires" />' +
+        '<div class="auth-form-actions">' +
+          '<button type="button" class="btn btn-primary" id="ak-create">Create</button>' +
+          '<button type="button" class="btn" id="ak-cancel">Cancel</button>' +
+        '</div>' +
+      '</div>' +
+      '<p class="auth-empty">Scopes: <code>*</code>, <code>workflow:&lt;read|write&gt;</code>, ' +
+        '<code>workflow:namespace=&lt;glob&gt;:&lt;access&gt;</code>, <code>vault:&lt;access&gt;</code>, ' +
+        '<code>vault:&lt;surface&gt;:&lt;access&gt;[:&lt;path glob&gt;]</code>, <code>auth:&lt;access&gt;</code>, ' +
+        '<code>engine:&lt;access&gt;</code>. <code>write</code> implies <code>read</code>.</p>';
+    document.getElementById('ak-cancel').addEventListener('click', load);
+    document.getElementById('ak-create').addEventListener('click', async function () {
+      const expires = document.getElementById('ak-expires').value;
+      const body = {
+        name: document.getElementById('ak-name').value,
+        owner: document.getElementById('ak-owner').value,
+        scopes: document.getElementById('ak-scopes').value.split('\n').map(function (s) { return s.trim(); }).filter(Boolean),
+        expires_at: expires ? new Date(expires).getTime() / 1000 : null,
+      };
+      try {
+        const result = await ctx.api.createApiKey(body);
+        renderTokenOnce(result);
+      } catch (err) {
+        ctx.toast('Create failed: ' + err.message, 'error');
+      }
+    });
+  }
+
+  function renderTokenOnce(result) {
+    const wrap = container.querySelector('#ak-wrap');
+    wrap.innerHTML = '<button class="btn btn-small" id="ak-back">&larr; Back</button>' +
+      '<h3>Key ' + ctx.escapeHtml(result.key.name) + ' created</h3>' +
+      '<div class="auth-secret-once">' +
+        '<strong>Capture this token now — it will not be shown again.</strong>' +
+        '<span class="auth-mono">' + ctx.escapeHtml(result.token) + '</span>' +
+        '<button type="button" class="btn btn-small" id="ak-copy">Copy</button>' +
+      '</div>' +
+      '<p>Send it as <span class="auth-mono">Authorization: Bearer &lt;token&gt;</span>.</p>';
+    document.getElementById('ak-back').addEventListener('click', load);
+    document.getElementById('ak-copy').addEventListener('click', function () {
+      navigator.clipboard.writeText(result.token).then(function () { ctx.toast('Copied', 'info'); });
+    });
+  }
+
+  async function revoke(id, name) {
+    if (!confirm('Revoke API key ' + name + '? Callers using it start failing immediately.')) return;
+    try {
+      await ctx.api.revokeApiKey(id);
+      ctx.toast('Revoked', 'info');
+      load();
+    } catch (err) { ctx.toast('Revoke failed: ' + err.message, 'error'); }
+  }
+
+  if (typeof window !== 'undefined') {
+    window.AssayAuthApiKeys = { render: render };
+  }
+
+  return { render: render };
+})();
@@ -1,8 +1,7 @@
 /* Audit log pane — paginated viewer with actor/action/time filters.
  *
- * The auth.audit table is currently deferred (V1 schema notes), so the
- * server returns an empty list with `enabled: false`. This pane renders
- * the filter shape today so the UI is ready when the table lands. */
+ * Rows come from auth.audit (lockouts, unlocks). A server built without
+ * an audit store answers `enabled: false` and the pane says so. */
 
 var AssayAuthAudit = (function () {
   'use strict';
@@ -54,8 +53,7 @@ var AssayAuthAudit = (function () {
       });
       if (data && data.enabled === false) {
         wrap.innerHTML = '<div class="auth-empty">' +
-          '<p>The <code>auth.audit</code> table is materialised but no rows are written yet.</p>' +
-          '<p>Audit emission lands in a follow-up phase; this pane will populate automatically once it does.</p>' +
+          '<p>This server has no <code>auth.audit</code> store wired, so no events are recorded.</p>' +
         '</div>';
         return;
       }
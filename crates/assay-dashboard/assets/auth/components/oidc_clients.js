@@ -76,10 +76,13 @@ var AssayAuthOidcClients = (function () {
         '<label for="nc-method">Auth method</label><select id="nc-method">' +
           '<option value="client_secret_basic" selected>client_secret_basic</option>' +
           '<option value="client_secret_post">client_secret_post</option>' +
+          '<option value="private_key_jwt">private_key_jwt</option>' +
           '<option value="none">none (PKCE-only)</option>' +
         '</select>' +
         '<label for="nc-scopes">Default scopes (space-sep)</label><input type="text" id="nc-scopes" value="openid email profile" />' +
         '<label for="nc-grants">Grant types (comma-sep)</label><input type="text" id="nc-grants" value="authorization_code,refresh_token" />' +
+        '<label for="nc-audiences">Audiences (space-sep, client_credentials)</label><input type="text" id="nc-audiences" placeholder="orders-api" />' +
+        '<label for="nc-jwks">JWK Set (private_key_jwt)</label><textarea id="nc-jwks" placeholder="{&quot;keys&quot;: [...]}"></textarea>' +
         '<label for="nc-resp">Response types (comma-sep)</label><input type="text" id="nc-resp" value="code" />' +
         '<label for="nc-pkce">PKCE required</label><input type="checkbox" id="nc-pkce" checked />' +
         '<label for="nc-consent">Require consent</label><input type="checkbox" id="nc-consent" checked />' +
@@ -90,6 +93,14 @@ var AssayAuthOidcClients = (function () {
       '</div>';
     document.getElementById('nc-cancel').addEventListener('click', load);
     document.getElementById('nc-create').addEventListener('click', async function () {
+      const jwksText = document.getElementById('nc-jwks').value.trim();
+      let jwks;
+      try {
+        jwks = jwksText ? JSON.parse(jwksText) : undefined;
+      } catch (err) {
+        ctx.toast('JWK Set is not valid JSON: ' + err.message, 'error');
+        return;
+      }
       const body = {
         name: document.getElementById('nc-name').value,
         redirect_uris: document.getElementById('nc-redirect').value.split('\n').map(function (s) { return s.trim(); }).filter(Boolean),
@@ -97,6 +108,8 @@ var AssayAuthOidcClients = (function () {
         default_scopes: document.getElementById('nc-scopes').value.split(/\s+/).filter(Boolean),
         grant_types: document.getElementById('nc-grants').value.split(',').map(function (s) { return s.trim(); }).filter(Boolean),
         response_types: document.getElementById('nc-resp').value.split(',').map(function (s) { return s.trim(); }).filter(Boolean),
+        audiences: document.getElementById('nc-audiences').value.split(/\s+/).filter(Boolean),
+        jwks: jwks,
         pkce_required: document.getElementById('nc-pkce').checked,
         require_consent: document.getElementById('nc-consent').checked,
       };
@@ -143,12 +156,14 @@ var AssayAuthOidcClients = (function () {
         '<h3>' + ctx.escapeHtml(c.name) + '</h3>' +
         '<dl class="auth-form">' +
           '<dt>Client ID</dt><dd class="auth-mono">' + ctx.escapeHtml(c.client_id) + '</dd>' +
-          '<dt>Has secret</dt><dd>' + (c.client_secret_hash ? 'yes (Argon2id PHC)' : 'no (PKCE-only)') + '</dd>' +
+          '<dt>Has secret</dt><dd>' + (c.client_secret_hash ? 'yes (Argon2id PHC)' : (c.token_endpoint_auth_method === 'private_key_jwt' ? 'no (private_key_jwt)' : 'no (PKCE-only)')) + '</dd>' +
           '<dt>Redirect URIs</dt><dd>' + (c.redirect_uris || []).map(function (u) { return '<div class="auth-mono">' + ctx.escapeHtml(u) + '</div>'; }).join('') + '</dd>' +
           '<dt>Auth method</dt><dd>' + ctx.escapeHtml(c.token_endpoint_auth_method) + '</dd>' +
           '<dt>Default scopes</dt><dd>' + ctx.escapeHtml((c.default_scopes || []).join(' ')) + '</dd>' +
           '<dt>Grant types</dt><dd>' + ctx.escapeHtml((c.grant_types || []).join(', ')) + '</dd>' +
           '<dt>Response types</dt><dd>' + ctx.escapeHtml((c.response_types || []).join(', ')) + '</dd>' +
+          '<dt>Audiences</dt><dd class="auth-mono">' + ctx.escapeHtml((c.audiences || []).join(' ') || '—') + '</dd>' +
+          '<dt>JWK Set</dt><dd>' + (c.jwks ? ((c.jwks.keys || []).length + ' key(s)') : '—') + '</dd>' +
           '<dt>PKCE required</dt><dd>' + (c.pkce_required ? '✓' : '—') + '</dd>' +
           '<dt>Require consent</dt><dd>' + (c.require_consent ? '✓' : '—') + '</dd>' +
           '<dt>Backchannel logout</dt><dd class="auth-mono">' + ctx.escapeHtml(c.backchannel_logout_uri || '—') + '</dd>' +
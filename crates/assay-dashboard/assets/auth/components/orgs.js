@@ -0,0 +1,307 @@
+/* Organizations pane — list / create / delete orgs, manage members (with roles) and nested groups. */
+
+var AssayAuthOrgs = (function () {
+  'use strict';
+
+  const ROLES = ['member', 'admin', 'owner'];
+
+  let ctx = null;
+  let container = null;
+  let state = { search: '', limit: 50, offset: 0 };
+
+  function render(el, c) {
+    ctx = c;
+    container = el;
+    container.innerHTML =
+      '<div class="auth-toolbar">' +
+        '<h2 class="section-title">Organizations</h2>' +
+        '<input type="search" class="auth-search" id="orgs-search" placeholder="Search by slug/name…" value="' + ctx.escapeHtml(state.search) + '" />' +
+        '<button type="button" class="btn btn-primary" id="orgs-new">New organization</button>' +
+      '</div>' +
+      '<div id="orgs-wrap"><div class="auth-empty">Loading…</div></div>';
+
+    document.getElementById('orgs-search').addEventListener('input', function (e) {
+      state.search = e.target.value.trim();
+      state.offset = 0;
+      load();
+    });
+    document.getElementById('orgs-new').addEventListener('click', openCreate);
+
+    container.addEventListener('click', function (e) {
+      const row = e.target.closest('tr[data-org-id]');
+      if (!row) return;
+      const action = e.target.closest('[data-action]');
+      const id = row.dataset.orgId;
+      if (action && action.dataset.action === 'delete') {
+        e.stopPropagation();
+        return deleteOrg(id);
+      }
+      showDetail(id);
+    });
+
+    load();
+  }
+
+  async function load() {
+    const wrap = container.querySelector('#orgs-wrap');
+    try {
+      const data = await ctx.api.listOrgs({
+        limit: state.limit,
+        offset: state.offset,
+        search: state.search || undefined,
+      });
+      renderTable(wrap, data);
+    } catch (err) {
+      wrap.innerHTML = '<div class="auth-empty">Error: ' + ctx.escapeHtml(err.message) + '</div>';
+    }
+  }
+
+  function renderTable(wrap, data) {
+    const items = (data && data.items) || [];
+    if (!items.length) {
+      wrap.innerHTML = '<div class="auth-empty">No organizations.</div>';
+      return;
+    }
+    let html = '<table class="data-table"><thead><tr>' +
+      '<th>ID</th><th>Slug</th><th>Name</th><th>Created</th><th></th>' +
+      '</tr></thead><tbody>';
+    for (let i = 0; i < items.length; i++) {
+      const o = items[i];
+      html += '<tr class="clickable-row" data-org-id="' + ctx.escapeHtml(o.id) + '">' +
+        '<td class="auth-mono">' + ctx.escapeHtml(ctx.truncate(o.id, 22)) + '</td>' +
+        '<td class="auth-mono">' + ctx.escapeHtml(o.slug) + '</td>' +
+        '<td>' + ctx.escapeHtml(o.display_name) + '</td>' +
+        '<td>' + ctx.formatTime(o.created_at) + '</td>' +
+        '<td><button class="btn btn-small btn-danger" data-action="delete">Delete</button></td>' +
+      '</tr>';
+    }
+    html += '</tbody></table>';
+    if (data.total > items.length) {
+      html += '<div class="auth-toolbar" style="margin-top:12px;">' +
+        '<span>' + (state.offset + 1) + '-' + (state.offset + items.length) + ' of ' + data.total + '</span>' +
+        '<button class="btn btn-small" id="orgs-prev"' + (state.offset === 0 ? ' disabled' : '') + '>Prev</button>' +
+        '<button class="btn btn-small" id="orgs-next"' + (state.offset + items.length >= data.total ? ' disabled' : '') + '>Next</button>' +
+      '</div>';
+    }
+    wrap.innerHTML = html;
+    const prev = document.getElementById('orgs-prev');
+    const next = document.getElementById('orgs-next');
+    if (prev) prev.addEventListener('click', function () {
+      state.offset = Math.max(0, state.offset - state.limit); load();
+    });
+    if (next) next.addEventListener('click', function () {
+      state.offset += state.limit; load();
+    });
+  }
+
+  function openCreate() {
+    const wrap = container.querySelector('#orgs-wrap');
+    wrap.innerHTML = '<h3>New organization</h3>' +
+      '<div class="auth-form">' +
+        '<label for="no-slug">Slug</label><input type="text" id="no-slug" placeholder="acme" />' +
+        '<label for="no-name">Display name</label><input type="text" id="no-name" />' +
+        '<div class="auth-form-actions">' +
+          '<button type="button" class="btn btn-primary" id="no-create">Create</button>' +
+          '<button type="button" class="btn" id="no-cancel">Cancel</button>' +
+        '</div>' +
+      '</div>';
+    document.getElementById('no-cancel').addEventListener('click', load);
+    document.getElementById('no-create').addEventListener('click', async function () {
+      try {
+        const org = await ctx.api.createOrg({
+          slug: document.getElementById('no-slug').value.trim(),
+          display_name: document.getElementById('no-name').value.trim() || null,
+        });
+        ctx.toast('Organization created', 'info');
+        showDetail(org.id);
+      } catch (err) { ctx.toast('Create failed: ' + err.message, 'error'); }
+    });
+  }
+
+  async function deleteOrg(id) {
+    if (!confirm('Delete organization ' + id + '? Memberships and groups cascade.')) return;
+    try {
+      await ctx.api.deleteOrg(id);
+      ctx.toast('Deleted', 'info');
+      load();
+    } catch (err) { ctx.toast('Delete failed: ' + err.message, 'error'); }
+  }
+
+  function roleSelect(id, current) {
+    let html = '<select id="' + id + '">';
+    ROLES.forEach(function (r) {
+      html += '<option value="' + r + '"' + (r === current ? ' selected' : '') + '>' + r + '</option>';
+    });
+    return html + '</select>';
+  }
+
+  async function showDetail(id) {
+    const wrap = container.querySelector('#orgs-wrap');
+    wrap.innerHTML = '<div class="auth-empty">Loading organization…</div>';
+    try {
+      const d = await ctx.api.getOrg(id);
+      const members = await ctx.api.listOrgMembers(id, { limit: 500 });
+      const org = d.organization;
+      let html = '<button class="btn btn-small" id="orgs-back">&larr; Back</button>' +
+        '<h3>' + ctx.escapeHtml(org.display_name) + ' <span class="auth-mono">(' + ctx.escapeHtml(org.slug) + ')</span></h3>' +
+        '<div class="auth-form">' +
+          '<label for="od-name">Display name</label><input type="text" id="od-name" value="' + ctx.escapeHtml(org.display_name) + '" />' +
+          '<div class="auth-form-actions">' +
+            '<button type="button" class="btn btn-primary" id="od-save">Save</button>' +
+          '</div>' +
+        '</div>';
+
+      html += '<div class="auth-pane-section"><h3>Members (' + d.member_count + ')</h3>' +
+        '<div class="auth-toolbar">' +
+          '<input type="text" class="auth-search" id="od-member-user" placeholder="user_id" />' +
+          roleSelect('od-member-role', 'member') +
+          '<button type="button" class="btn btn-small" id="od-member-add">Add / set role</button>' +
+        '</div>';
+      if (!members.items.length) {
+        html += '<p class="auth-empty">No members.</p>';
+      } else {
+        html += '<table class="data-table"><thead><tr><th>User</th><th>Role</th><th>Joined</th><th></th></tr></thead><tbody>';
+        members.items.forEach(function (m) {
+          html += '<tr><td class="auth-mono">' + ctx.escapeHtml(m.user_id) + '</td>' +
+            '<td>' + ctx.escapeHtml(m.role) + '</td>' +
+            '<td>' + ctx.formatTime(m.created_at) + '</td>' +
+            '<td><button class="btn btn-small btn-danger" data-remove-member="' + ctx.escapeHtml(m.user_id) + '">Remove</button></td></tr>';
+        });
+        html += '</tbody></table>';
+      }
+      html += '</div>';
+
+      html += '<div class="auth-pane-section"><h3>Groups (' + d.groups.length + ')</h3>' +
+        '<div class="auth-toolbar">' +
+          '<input type="text" class="auth-search" id="od-group-slug" placeholder="slug" />' +
+          '<input type="text" class="auth-search" id="od-group-name" placeholder="Display name" />' +
+          '<button type="button" class="btn btn-small" id="od-group-add">New group</button>' +
+        '</div>';
+      if (!d.groups.length) {
+        html += '<p class="auth-empty">No groups.</p>';
+      } else {
+        html += '<table class="data-table"><thead><tr><th>ID</th><th>Slug</th><th>Name</th><th></th></tr></thead><tbody>';
+        d.groups.forEach(function (g) {
+          html += '<tr><td class="auth-mono">' + ctx.escapeHtml(g.id) + '</td>' +
+            '<td class="auth-mono">' + ctx.escapeHtml(g.slug) + '</td>' +
+            '<td>' + ctx.escapeHtml(g.display_name) + '</td>' +
+            '<td><button class="btn btn-small" data-open-group="' + ctx.escapeHtml(g.id) + '">Members</button> ' +
+            '<button class="btn btn-small btn-danger" data-delete-group="' + ctx.escapeHtml(g.id) + '">Delete</button></td></tr>';
+        });
+        html += '</tbody></table>';
+      }
+      html += '</div>';
+
+      wrap.innerHTML = html;
+      document.getElementById('orgs-back').addEventListener('click', load);
+      document.getElementById('od-save').addEventListener('click', async function () {
+        try {
+          await ctx.api.updateOrg(id, { display_name: document.getElementById('od-name').value.trim() });
+          ctx.toast('Saved', 'info');
+          showDetail(id);
+        } catch (err) { ctx.toast('Save failed: ' + err.message, 'error'); }
+      });
+      document.getElementById('od-member-add').addEventListener('click', async function () {
+        const userId = document.getElementById('od-member-user').value.trim();
+        if (!userId) return;
+        try {
+          await ctx.api.setOrgMember(id, userId, document.getElementById('od-member-role').value);
+          ctx.toast('Membership saved', 'info');
+          showDetail(id);
+        } catch (err) { ctx.toast('Save failed: ' + err.message, 'error'); }
+      });
+      document.getElementById('od-group-add').addEventListener('click', async function () {
+        try {
+          await ctx.api.createGroup(id, {
+            slug: document.getElementById('od-group-slug').value.trim(),
+            display_name: document.getElementById('od-group-name').value.trim() || null,
+          });
+          ctx.toast('Group created', 'info');
+          showDetail(id);
+        } catch (err) { ctx.toast('Create failed: ' + err.message, 'error'); }
+      });
+      wrap.querySelectorAll('button[data-remove-member]').forEach(function (btn) {
+        btn.addEventListener('click', async function () {
+          const userId = btn.dataset.removeMember;
+          if (!confirm('Remove ' + userId + ' from this organization and its groups?')) return;
+          try {
+            await ctx.api.removeOrgMember(id, userId);
+            ctx.toast('Member removed', 'info');
+            showDetail(id);
+          } catch (err) { ctx.toast('Remove failed: ' + err.message, 'error'); }
+        });
+      });
+      wrap.querySelectorAll('button[data-open-group]').forEach(function (btn) {
+        btn.addEventListener('click', function () { showGroup(id, btn.dataset.openGroup); });
+      });
+      wrap.querySelectorAll('button[data-delete-group]').forEach(function (btn) {
+        btn.addEventListener('click', async function () {
+          if (!confirm('Delete group ' + btn.dataset.deleteGroup + '?')) return;
+          try {
+            await ctx.api.deleteGroup(btn.dataset.deleteGroup);
+            ctx.toast('Group deleted', 'info');
+            showDetail(id);
+          } catch (err) { ctx.toast('Delete failed: ' + err.message, 'error'); }
+        });
+      });
+    } catch (err) {
+      wrap.innerHTML = '<div class="auth-empty">Error: ' + ctx.escapeHtml(err.message) + '</div>';
+    }
+  }
+
+  async function showGroup(orgId, groupId) {
+    const wrap = container.querySelector('#orgs-wrap');
+    wrap.innerHTML = '<div class="auth-empty">Loading group…</div>';
+    try {
+      const d = await ctx.api.getGroup(groupId);
+      let html = '<button class="btn btn-small" id="grp-back">&larr; Back</button>' +
+        '<h3>' + ctx.escapeHtml(d.group.display_name) + ' <span class="auth-mono">(' + ctx.escapeHtml(d.group.slug) + ')</span></h3>' +
+        '<div class="auth-toolbar">' +
+          '<select id="grp-member-type"><option value="user">user</option><option value="group">group</option></select>' +
+          '<input type="text" class="auth-search" id="grp-member-id" placeholder="user_id or group id" />' +
+          '<button type="button" class="btn btn-small" id="grp-member-add">Add member</button>' +
+        '</div>';
+      if (!d.members.length) {
+        html += '<p class="auth-empty">No members.</p>';
+      } else {
+        html += '<table class="data-table"><thead><tr><th>Type</th><th>Member</th><th>Added</th><th></th></tr></thead><tbody>';
+        d.members.forEach(function (m) {
+          html += '<tr><td>' + ctx.escapeHtml(m.member_type) + '</td>' +
+            '<td class="auth-mono">' + ctx.escapeHtml(m.member_id) + '</td>' +
+            '<td>' + ctx.formatTime(m.created_at) + '</td>' +
+            '<td><button class="btn btn-small btn-danger" data-type="' + ctx.escapeHtml(m.member_type) +
+            '" data-member="' + ctx.escapeHtml(m.member_id) + '">Remove</button></td></tr>';
+        });
+        html += '</tbody></table>';
+      }
+      wrap.innerHTML = html;
+      document.getElementById('grp-back').addEventListener('click', function () { showDetail(orgId); });
+      document.getElementById('grp-member-add').addEventListener('click', async function () {
+        const memberId = document.getElementById('grp-member-id').value.trim();
+        if (!memberId) return;
+        try {
+          await ctx.api.addGroupMember(groupId, document.getElementById('grp-member-type').value, memberId);
+          ctx.toast('Member added', 'info');
+          showGroup(orgId, groupId);
+        } catch (err) { ctx.toast('Add failed: ' + err.message, 'error'); }
+      });
+      wrap.querySelectorAll('button[data-member]').forEach(function (btn) {
+        btn.addEventListener('click', async function () {
+          try {
+            await ctx.api.removeGroupMember(groupId, btn.dataset.type, btn.dataset.member);
+            ctx.toast('Member removed', 'info');
+            showGroup(orgId, groupId);
+          } catch (err) { ctx.toast('Remove failed: ' + err.message, 'error'); }
+        });
+      });
+    } catch (err) {
+      wrap.innerHTML = '<div class="auth-empty">Error: ' + ctx.escapeHtml(err.message) + '</div>';
+    }
+  }
+
+  if (typeof window !== 'undefined') {
+    window.AssayAuthOrgs = { render: render };
+  }
+
+  return { render: render };
+})();
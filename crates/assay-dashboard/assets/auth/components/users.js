@@ -144,6 +144,7 @@ var AssayAuthUsers = (function () {
           '<label for="ud-verified">Email verified</label><input type="checkbox" id="ud-verified"' + (d.user.email_verified ? ' checked' : '') + ' />' +
           '<div class="auth-form-actions">' +
             '<button type="button" class="btn btn-primary" id="ud-save">Save</button>' +
+            '<button type="button" class="btn" id="ud-unlock">Unlock login</button>' +
             '<button type="button" class="btn btn-danger" id="ud-delete">Delete</button>' +
           '</div>' +
         '</div>' +
@@ -202,6 +203,12 @@ var AssayAuthUsers = (function () {
           showDetail(id);
         } catch (err) { ctx.toast('Save failed: ' + err.message, 'error'); }
       });
+      document.getElementById('ud-unlock').addEventListener('click', async function () {
+        try {
+          await ctx.api.unlockUser(id);
+          ctx.toast('Login unlocked', 'info');
+        } catch (err) { ctx.toast('Unlock failed: ' + err.message, 'error'); }
+      });
       document.getElementById('ud-delete').addEventListener('click', function () { doDelete(id); });
       const revokeAll = document.getElementById('ud-revoke-all');
       if (revokeAll) revokeAll.addEventListener('click', async function () {
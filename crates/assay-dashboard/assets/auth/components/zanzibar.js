@@ -19,7 +19,7 @@ var AssayAuthZanzibar = (function () {
         '<button type="button" class="btn btn-small" data-tab="define">Define namespace</button>' +
         '<button type="button" class="btn btn-small" data-tab="check">Check</button>' +
         '<button type="button" class="btn btn-small" data-tab="expand">Expand</button>' +
-        '<button type="button" class="btn btn-small" data-tab="tuple">Write tuple</button>' +
+        '<button type="button" class="btn btn-small" data-tab="tuple">Tuples</button>' +
         '<button type="button" class="btn btn-small" data-tab="bootstrap">Bootstrap admin</button>' +
       '</div>' +
       '<div id="zb-wrap"></div>';
@@ -199,9 +199,56 @@ var AssayAuthZanzibar = (function () {
     });
   }
 
+  // Remaining lifetime of an expiring tuple (`expires_at` is unix
+  // seconds). Expired-but-unswept tuples still list until the sweeper
+  // removes them; checks already ignore them.
+  function formatLifetime(expiresAt) {
+    if (expiresAt == null) return '—';
+    let left = expiresAt - Math.floor(Date.now() / 1000);
+    if (left <= 0) return '<span class="auth-status-denied">expired</span>';
+    const d = Math.floor(left / 86400); left %= 86400;
+    const h = Math.floor(left / 3600); left %= 3600;
+    const m = Math.floor(left / 60);
+    const s = left % 60;
+    let out;
+    if (d) out = d + 'd ' + h + 'h';
+    else if (h) out = h + 'h ' + m + 'm';
+    else if (m) out = m + 'm ' + s + 's';
+    else out = s + 's';
+    return '<span title="' + ctx.escapeHtml(new Date(expiresAt * 1000).toLocaleString()) + '">in ' + out + '</span>';
+  }
+
+  async function renderTupleList() {
+    const list = container.querySelector('#zt-list');
+    if (!list) return;
+    list.innerHTML = '<div class="auth-empty">Loading tuples…</div>';
+    try {
+      const data = await ctx.api.listZanzibarTuples({ limit: 200 });
+      const items = (data && data.items) || [];
+      if (!items.length) {
+        list.innerHTML = '<div class="auth-empty">No tuples written.</div>';
+        return;
+      }
+      let html = '<table class="data-table"><thead><tr><th>Object</th><th>Relation</th><th>Subject</th><th>Expires</th></tr></thead><tbody>';
+      items.forEach(function (t) {
+        const subject = t.subject_type + ':' + t.subject_id + (t.subject_rel ? '#' + t.subject_rel : '');
+        html += '<tr><td class="auth-mono">' + ctx.escapeHtml(t.object_type + ':' + t.object_id) +
+          '</td><td class="auth-mono">' + ctx.escapeHtml(t.relation) +
+          '</td><td class="auth-mono">' + ctx.escapeHtml(subject) +
+          '</td><td>' + formatLifetime(t.expires_at) + '</td></tr>';
+      });
+      html += '</tbody></table>';
+      list.innerHTML = html;
+    } catch (err) {
+      list.innerHTML = '<div class="auth-empty">Error: ' + ctx.escapeHtml(err.message) + '</div>';
+    }
+  }
+
   function renderTupleForm() {
     const wrap = container.querySelector('#zb-wrap');
-    wrap.innerHTML = '<h3>Write / delete tuple</h3>' +
+    wrap.innerHTML = '<h3>Tuples</h3>' +
+      '<div id="zt-list"></div>' +
+      '<h3>Write / delete tuple</h3>' +
       '<div class="auth-form">' +
         '<label for="zt-ot">Object type</label><input type="text" id="zt-ot" />' +
         '<label for="zt-oid">Object id</label><input type="text" id="zt-oid" />' +
@@ -209,6 +256,7 @@ var AssayAuthZanzibar = (function () {
         '<label for="zt-st">Subject type</label><input type="text" id="zt-st" />' +
         '<label for="zt-sid">Subject id</label><input type="text" id="zt-sid" />' +
         '<label for="zt-srel">Subject relation (optional)</label><input type="text" id="zt-srel" />' +
+        '<label for="zt-ttl">Lifetime in seconds (optional; write only)</label><input type="number" min="1" id="zt-ttl" />' +
         '<div class="auth-form-actions">' +
           '<button type="button" class="btn btn-primary" id="zt-write">Write</button>' +
           '<button type="button" class="btn btn-danger" id="zt-delete">Delete</button>' +
@@ -226,9 +274,13 @@ var AssayAuthZanzibar = (function () {
       };
     }
     document.getElementById('zt-write').addEventListener('click', async function () {
+      const tuple = body();
+      const ttl = parseInt(document.getElementById('zt-ttl').value, 10);
+      if (ttl > 0) tuple.expires_at = Math.floor(Date.now() / 1000) + ttl;
       try {
-        await ctx.api.writeZanzibarTuple(body());
+        await ctx.api.writeZanzibarTuple(tuple);
         document.getElementById('zt-result').innerHTML = '<p class="auth-status-allowed">Tuple written.</p>';
+        renderTupleList();
       } catch (err) {
         document.getElementById('zt-result').innerHTML = '<p class="auth-status-denied">Error: ' + ctx.escapeHtml(err.message) + '</p>';
       }
@@ -237,10 +289,12 @@ var AssayAuthZanzibar = (function () {
       try {
         await ctx.api.deleteZanzibarTuple(body());
         document.getElementById('zt-result').innerHTML = '<p class="auth-status-allowed">Tuple deleted.</p>';
+        renderTupleList();
       } catch (err) {
         document.getElementById('zt-result').innerHTML = '<p class="auth-status-denied">Error: ' + ctx.escapeHtml(err.message) + '</p>';
       }
     });
+    renderTupleList();
   }
 
   const BOOTSTRAP_TUPLES = [
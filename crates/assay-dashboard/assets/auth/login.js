@@ -1,7 +1,10 @@
 /* Assay Auth — login landing controller.
  *
  * Submits first-party email/password credentials to the engine session
- * endpoint. Enabled upstream IdPs are rendered as optional alternatives.
+ * endpoint. Accounts with TOTP enrolled get an `mfa_token` back instead
+ * of a session; the page then swaps to a code form that redeems it at
+ * /login/mfa with an authenticator or recovery code. Enabled upstream
+ * IdPs are rendered as optional alternatives.
  *
  * Provider icons come from /auth/icons.svg (a single sprite shipped
  * with the auth dashboard). The button references the right symbol by
@@ -29,6 +32,11 @@
   const passwordInput = document.getElementById('password');
   const passwordError = document.getElementById('password-error');
   const passwordSubmit = document.getElementById('password-submit');
+  const mfaForm = document.getElementById('mfa-login');
+  const mfaInput = document.getElementById('mfa-code');
+  const mfaError = document.getElementById('mfa-error');
+  const mfaSubmit = document.getElementById('mfa-submit');
+  let mfaToken = null;
 
   function safeReturnTo(raw) {
     try {
@@ -69,6 +77,12 @@
       })
     }).then(function (response) {
       if (!response.ok) throw new Error('invalid credentials');
+      return response.json();
+    }).then(function (body) {
+      if (body && body.mfa_required) {
+        showMfaStep(body.mfa_token);
+        return;
+      }
       window.location.assign(returnTo);
     }).catch(function () {
       showPasswordError('Email or password is incorrect.');
@@ -79,7 +93,39 @@
     });
   }
 
+  // Password accepted, second factor pending. The token is only held in
+  // memory; a reload starts the login over.
+  function showMfaStep(token) {
+    mfaToken = token;
+    passwordForm.hidden = true;
+    mfaForm.hidden = false;
+    mfaError.textContent = '';
+    mfaInput.value = '';
+    mfaInput.focus();
+  }
+
+  function submitMfaLogin(event) {
+    event.preventDefault();
+    mfaError.textContent = '';
+    mfaSubmit.disabled = true;
+    fetch('/api/v1/engine/auth/login/mfa', {
+      method: 'POST',
+      credentials: 'same-origin',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ mfa_token: mfaToken, code: mfaInput.value })
+    }).then(function (response) {
+      if (!response.ok) throw new Error('invalid code');
+      window.location.assign(returnTo);
+    }).catch(function () {
+      mfaError.textContent = 'That code did not work. Try again, or sign in again if it keeps failing.';
+      mfaInput.value = '';
+      mfaInput.focus();
+      mfaSubmit.disabled = false;
+    });
+  }
+
   if (passwordForm) passwordForm.addEventListener('submit', submitPasswordLogin);
+  if (mfaForm) mfaForm.addEventListener('submit', submitMfaLogin);
 
   // Reveal control. Additive and self-contained — it only ever flips the
   // input's type, so a browser that never runs this block still has a
@@ -1,6 +1,6 @@
 /* Assay Workflow Dashboard — workflow action handlers (v0.12.0)
  *
- * Signal / Cancel / Terminate / Continue-as-new — one entry point per
+ * Signal / Cancel / Terminate / Continue-as-new / Reset — one entry point per
  * action. Each opens an AssayModal for input/confirmation, posts to
  * the engine, surfaces a toast on success/failure, and refreshes the
  * affected views. Callers (workflow row icons, detail-panel keyboard
@@ -268,6 +268,72 @@ var AssayActions = (function () {
     });
   }
 
+  function resetWorkflow(id, eventSeq) {
+    // The reset point field takes either an event seq or one of the
+    // engine's named points. Opened from the Events tab it defaults to
+    // the selected event; from the row icon, to the last workflow task.
+    AssayModal.form({
+      title: 'Reset — ' + id,
+      submitLabel: 'Reset workflow',
+      description:
+        'Starts a NEW run whose history is this run\'s events up to the ' +
+        'reset point, then terminates this run if it is still going. Signals ' +
+        'received after the reset point are re-applied to the new run.',
+      fields: [
+        {
+          name: 'reset_point',
+          label: 'Reset point',
+          type: 'text',
+          required: true,
+          value: eventSeq != null ? String(eventSeq) : 'last_workflow_task',
+          hint: 'An event seq, last_workflow_task or first_failed_activity.',
+        },
+        {
+          name: 'reason',
+          label: 'Reason',
+          type: 'textarea',
+          required: true,
+          placeholder: 'What went wrong, and what changed since?',
+        },
+        {
+          name: 'workflow_id',
+          label: 'New workflow ID (optional)',
+          type: 'text',
+          placeholder: 'Defaults to ' + String(id || '').replace(/-reset-\d+$/, '') + '-reset-<unix secs>',
+        },
+      ],
+      onSubmit: async function (values) {
+        var point = String(values.reset_point).trim();
+        var body = { reason: String(values.reason).trim() };
+        if (/^\d+$/.test(point)) body.event_seq = parseInt(point, 10);
+        else body.reset_type = point;
+        if (values.workflow_id && String(values.workflow_id).trim()) {
+          body.workflow_id = String(values.workflow_id).trim();
+        }
+        try {
+          var result = await ctx.apiFetch(
+            '/workflows/' + encodeURIComponent(id) + '/reset',
+            {
+              method: 'POST',
+              headers: { 'Content-Type': 'application/json' },
+              body: JSON.stringify(body),
+            }
+          );
+          var newId = result && result.workflow_id;
+          ctx.toast('Reset to #' + (result && result.reset_to_seq) + ': ' + (newId || 'unknown'), 'success');
+          if (newId && typeof window !== 'undefined' && window.AssayWorkflows
+              && window.AssayWorkflows.setExpandedId) {
+            window.AssayWorkflows.setExpandedId(newId);
+          }
+          reopenDetail(id);
+          refreshList();
+        } catch (err) {
+          ctx.toast('Reset failed: ' + (err && err.message), 'error');
+        }
+      },
+    });
+  }
+
   return {
     init: init,
     signal: signal,
@@ -275,5 +341,6 @@ var AssayActions = (function () {
     terminate: terminate,
     retryFailedActivity: retryFailedActivity,
     continueAsNew: continueAsNew,
+    resetWorkflow: resetWorkflow,
   };
 })();
@@ -238,6 +238,13 @@ var AssayDetail = (function () {
                 '<span class="event-detail-meta">#' + e.seq + ' — ' +
                   ctx.formatExactTime(e.timestamp) + ' (' + ctx.formatTime(e.timestamp) + ')' +
                 '</span>' +
+                // Children are reset through their parent, so the
+                // engine refuses them; don't offer the button there.
+                (wf.parent_id ? '' :
+                  '<button type="button" class="event-reset-btn" data-workflow-id="' +
+                    ctx.escapeHtml(wf.id) + '" data-seq="' + e.seq + '"' +
+                    ' title="Start a new run from this run\'s history up to #' + e.seq + '">' +
+                    'Reset to here</button>') +
               '</div>' +
               (e.payload
                 ? '<div class="json-viewer">' + ctx.escapeHtml(ctx.formatJson(e.payload)) + '</div>'
@@ -476,6 +483,17 @@ var AssayDetail = (function () {
       return;
     }
 
+    // "Reset to here" in the Events tab — opens the reset modal with
+    // the selected event's seq prefilled.
+    var resetBtn = e.target.closest('.event-reset-btn');
+    if (resetBtn) {
+      e.preventDefault();
+      if (ctx && ctx.actions) {
+        ctx.actions.resetWorkflow(resetBtn.dataset.workflowId, parseInt(resetBtn.dataset.seq, 10));
+      }
+      return;
+    }
+
     // Event list item — master-detail pattern. Clicking a row on
     // the left selects that event and shows its payload on the right.
     var evtItem = e.target.closest('.event-list-item');
@@ -8,6 +8,7 @@ var AssayWorkflows = (function () {
   let currentFilter = '';
   let searchTerm = '';
   let searchAttrs = '';
+  let visibilityQuery = '';
   let ctx = null;
   let container = null;
 
@@ -102,6 +103,7 @@ var AssayWorkflows = (function () {
     currentFilter = '';
     searchTerm = '';
     searchAttrs = '';
+    visibilityQuery = '';
 
     el.innerHTML =
       '<div class="toolbar">' +
@@ -126,6 +128,10 @@ var AssayWorkflows = (function () {
         '<input type="text" class="search-input" id="wf-search-attrs" placeholder=\'Filter by search attributes, e.g. {"env":"prod","tenant":"acme"}\' style="flex:1;">' +
         '<span class="inline-form-hint">Matches workflows whose search_attributes contain every listed key at the given value.</span>' +
       '</div>' +
+      '<div class="toolbar-advanced" id="wf-query-row" hidden>' +
+        '<input type="text" class="search-input" id="wf-query" placeholder="Query, e.g. WorkflowType = \'deploy\' AND search.progress > 0.5" style="flex:1;">' +
+        '<span class="inline-form-hint">Visibility query; replaces the search attributes filter. Registered attributes are queried as search.&lt;name&gt;.</span>' +
+      '</div>' +
       '<div id="wf-start-form-wrap"></div>' +
       '<div id="wf-table-wrap"></div>' +
       '<div id="wf-pagination" class="pagination"></div>';
@@ -146,18 +152,23 @@ var AssayWorkflows = (function () {
     // installs where workflows carry tenant / env / project tags.
     el.querySelector('#wf-advanced-toggle').addEventListener('click', function () {
       var row = el.querySelector('#wf-advanced-row');
+      var queryRow = el.querySelector('#wf-query-row');
       var btn = el.querySelector('#wf-advanced-toggle');
       var hidden = row.hasAttribute('hidden');
       if (hidden) {
         row.removeAttribute('hidden');
+        queryRow.removeAttribute('hidden');
         btn.classList.add('active');
       } else {
         row.setAttribute('hidden', '');
+        queryRow.setAttribute('hidden', '');
         btn.classList.remove('active');
         // Clearing the search when collapsing keeps "what the user
         // sees filtered by" consistent with "what's on-screen".
         searchAttrs = '';
+        visibilityQuery = '';
         el.querySelector('#wf-search-attrs').value = '';
+        el.querySelector('#wf-query').value = '';
         loadWorkflows();
       }
     });
@@ -181,6 +192,17 @@ var AssayWorkflows = (function () {
       }, 300);
     });
 
+    let queryTimer = null;
+    el.querySelector('#wf-query').addEventListener('input', function (e) {
+      const val = e.target.value.trim();
+      clearTimeout(queryTimer);
+      queryTimer = setTimeout(function () {
+        visibilityQuery = val;
+        currentOffset = 0;
+        loadWorkflows();
+      }, 300);
+    });
+
     el.querySelector('#wf-start-toggle').addEventListener('click', toggleStartForm);
 
     el.querySelector('#wf-table-wrap').addEventListener('click', function (e) {
@@ -201,6 +223,7 @@ var AssayWorkflows = (function () {
         else if (act === 'terminate') ctx.actions.terminate(id);
         else if (act === 'retry') ctx.actions.retryFailedActivity(id);
         else if (act === 'continue') ctx.actions.continueAsNew(id);
+        else if (act === 'reset') ctx.actions.resetWorkflow(id);
         return;
       }
 
@@ -244,7 +267,16 @@ var AssayWorkflows = (function () {
   async function loadWorkflows() {
     var wrap = container.querySelector('#wf-table-wrap');
     var params = '?limit=' + PAGE_SIZE + '&offset=' + currentOffset;
-    if (currentFilter) params += '&status=' + currentFilter;
+    if (visibilityQuery) {
+      // The server rejects `query` combined with the other filters, so
+      // fold the status dropdown into the query instead.
+      var q = currentFilter
+        ? '(' + visibilityQuery + ") AND status = '" + currentFilter + "'"
+        : visibilityQuery;
+      params += '&query=' + encodeURIComponent(q);
+    } else if (currentFilter) {
+      params += '&status=' + currentFilter;
+    }
     // `searchTerm` matches id OR workflow_type, case-insensitive
     // substring. The server's `?type=` param only matches workflow_type
     // EXACTLY, which is why typing "demo" wouldn't match a
@@ -252,7 +284,7 @@ var AssayWorkflows = (function () {
     // side against the current page's results. Large-list users still
     // paginate normally; if search coverage across ALL runs is needed,
     // that's a separate backend feature (substring LIKE in the store).
-    if (searchAttrs) {
+    if (searchAttrs && !visibilityQuery) {
       // Validate JSON client-side so bad input doesn't silently vanish.
       try {
         JSON.parse(searchAttrs);
@@ -368,6 +400,16 @@ var AssayWorkflows = (function () {
               '</svg>' +
             '</button>';
         }
+        // Reset forks from an earlier point of this run's history. Not
+        // offered on children — their parent's replay drives them.
+        if (!wf.parent_id) {
+          html +=
+            '<button class="row-action-btn" data-action="reset" data-id="' + idAttr + '" title="Reset to an earlier point of history" aria-label="Reset workflow">' +
+              '<svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">' +
+                '<path d="M3 12a9 9 0 1 0 3-6.7"/><polyline points="3 4 3 10 9 10"/><line x1="12" y1="8" x2="12" y2="12"/><line x1="12" y1="12" x2="15" y2="14"/>' +
+              '</svg>' +
+            '</button>';
+        }
         html +=
           '<button class="row-action-btn" data-action="continue" data-id="' + idAttr + '" title="Start a new run (continue-as-new)" aria-label="Start a new run">' +
             '<svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">' +
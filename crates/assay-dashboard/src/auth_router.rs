@@ -14,10 +14,11 @@ use axum::response::IntoResponse;
 use axum::routing::get;
 
 use crate::assets::{
-    AUTH_API_JS, AUTH_APP_JS, AUTH_AUDIT_JS, AUTH_ICONS_SVG, AUTH_INDEX_HTML, AUTH_KEYS_JS,
-    AUTH_LANDING_HTML, AUTH_LOGIN_CSS, AUTH_LOGIN_HTML, AUTH_LOGIN_JS, AUTH_OIDC_CLIENTS_JS,
-    AUTH_OIDC_UPSTREAM_JS, AUTH_RECOVERY_HTML, AUTH_RECOVERY_JS, AUTH_SESSIONS_JS, AUTH_STYLE_CSS,
-    AUTH_USERS_JS, AUTH_ZANZIBAR_JS, FAVICON_SVG,
+    AUTH_API_JS, AUTH_API_KEYS_JS, AUTH_APP_JS, AUTH_AUDIT_JS, AUTH_ICONS_SVG, AUTH_INDEX_HTML,
+    AUTH_KEYS_JS, AUTH_LANDING_HTML, AUTH_LOGIN_CSS, AUTH_LOGIN_HTML, AUTH_LOGIN_JS,
+    AUTH_OIDC_CLIENTS_JS, AUTH_OIDC_UPSTREAM_JS, AUTH_ORGS_JS, AUTH_RECOVERY_HTML,
+    AUTH_RECOVERY_JS, AUTH_SESSIONS_JS, AUTH_STYLE_CSS, AUTH_USERS_JS, AUTH_ZANZIBAR_JS,
+    FAVICON_SVG,
 };
 
 /// Build the auth-console asset router. Stateless `Router<()>` ready
@@ -41,10 +42,12 @@ pub fn console_router() -> Router<()> {
         .route("/auth/components/api.js", get(api_js))
         .route("/auth/components/users.js", get(users_js))
         .route("/auth/components/sessions.js", get(sessions_js))
+        .route("/auth/components/orgs.js", get(orgs_js))
         .route("/auth/components/oidc_clients.js", get(oidc_clients_js))
         .route("/auth/components/oidc_upstream.js", get(oidc_upstream_js))
         .route("/auth/components/zanzibar.js", get(zanzibar_js))
         .route("/auth/components/keys.js", get(keys_js))
+        .route("/auth/components/api_keys.js", get(api_keys_js))
         .route("/auth/components/audit.js", get(audit_js))
 }
 
@@ -116,6 +119,9 @@ async fn users_js() -> impl IntoResponse {
 async fn sessions_js() -> impl IntoResponse {
     asset("application/javascript", AUTH_SESSIONS_JS)
 }
+async fn orgs_js() -> impl IntoResponse {
+    asset("application/javascript", AUTH_ORGS_JS)
+}
 async fn oidc_clients_js() -> impl IntoResponse {
     asset("application/javascript", AUTH_OIDC_CLIENTS_JS)
 }
@@ -128,6 +134,9 @@ async fn zanzibar_js() -> impl IntoResponse {
 async fn keys_js() -> impl IntoResponse {
     asset("application/javascript", AUTH_KEYS_JS)
 }
+async fn api_keys_js() -> impl IntoResponse {
+    asset("application/javascript", AUTH_API_KEYS_JS)
+}
 async fn audit_js() -> impl IntoResponse {
     asset("application/javascript", AUTH_AUDIT_JS)
 }
@@ -212,11 +221,11 @@ mod tests {
     use tower::ServiceExt;
 
     use crate::assets::{
-        AUTH_LANDING_HTML, AUTH_LOGIN_CSS, AUTH_LOGIN_HTML, AUTH_LOGIN_JS, AUTH_RECOVERY_HTML,
-        AUTH_RECOVERY_JS,
+        AUTH_APP_JS, AUTH_INDEX_HTML, AUTH_LANDING_HTML, AUTH_LOGIN_CSS, AUTH_LOGIN_HTML,
+        AUTH_LOGIN_JS, AUTH_RECOVERY_HTML, AUTH_RECOVERY_JS,
     };
 
-    use super::public_router;
+    use super::{console_router, public_router};
 
     #[test]
     fn public_landing_exposes_only_account_entry_points() {
@@ -244,6 +253,53 @@ mod tests {
         assert_eq!(console.status(), StatusCode::NOT_FOUND);
     }
 
+    // The organizations pane is a console asset like every other pane:
+    // linked from the shell, registered as a view, never public.
+    #[tokio::test]
+    async fn console_serves_the_organizations_pane() {
+        let pane = console_router()
+            .oneshot(
+                Request::get("/auth/components/orgs.js")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(pane.status(), StatusCode::OK);
+        let body = to_bytes(pane.into_body(), 64 * 1024).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("var AssayAuthOrgs"));
+
+        let public = public_router()
+            .oneshot(
+                Request::get("/auth/components/orgs.js")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(public.status(), StatusCode::NOT_FOUND);
+        assert!(AUTH_INDEX_HTML.contains("data-view=\"orgs\""));
+        assert!(AUTH_INDEX_HTML.contains("/auth/components/orgs.js"));
+        assert!(AUTH_APP_JS.contains("orgs: typeof AssayAuthOrgs"));
+    }
+
+    #[tokio::test]
+    async fn console_serves_the_api_keys_pane() {
+        let pane = console_router()
+            .oneshot(
+                Request::get("/auth/components/api_keys.js")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(pane.status(), StatusCode::OK);
+        let body = to_bytes(pane.into_body(), 64 * 1024).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("var AssayAuthApiKeys"));
+        assert!(AUTH_INDEX_HTML.contains("data-view=\"api-keys\""));
+        assert!(AUTH_APP_JS.contains("'api-keys': typeof AssayAuthApiKeys"));
+    }
+
     #[test]
     fn login_page_keeps_first_party_password_auth_available() {
         assert!(AUTH_LOGIN_HTML.contains("<form id=\"password-login\""));
@@ -269,6 +325,17 @@ mod tests {
         assert!(AUTH_LOGIN_JS.contains("window.location.assign(returnTo)"));
     }
 
+    // An enrolled account answers the password with `mfa_required`; the
+    // page must offer the code step rather than treating it as signed in.
+    #[test]
+    fn password_login_hands_off_to_the_second_factor_step() {
+        assert!(AUTH_LOGIN_HTML.contains("<form id=\"mfa-login\""));
+        assert!(AUTH_LOGIN_HTML.contains("autocomplete=\"one-time-code\""));
+        assert!(AUTH_LOGIN_JS.contains("body.mfa_required"));
+        assert!(AUTH_LOGIN_JS.contains("fetch('/api/v1/engine/auth/login/mfa'"));
+        assert!(AUTH_LOGIN_JS.contains("mfa_token: mfaToken"));
+    }
+
     #[test]
     fn login_page_links_to_password_recovery() {
         assert!(AUTH_LOGIN_HTML.contains("href=\"/auth/recovery\""));
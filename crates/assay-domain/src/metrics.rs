@@ -0,0 +1,297 @@
+//! In-process metric instruments and the Prometheus text encoder.
+//!
+//! Modules own their instruments ([`CounterVec`], [`HistogramVec`]) and
+//! bump them on the hot path; `assay-engine` renders every module's
+//! instruments, plus gauges it reads at scrape time, into one
+//! exposition at `/metrics`. Label values must come from small, bounded
+//! sets — route templates, queue names, grant types — never ids or raw
+//! paths.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, MutexGuard};
+
+/// `Content-Type` of the text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Default latency buckets, in seconds (5 ms … 10 s).
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Monotonic counters keyed by label values.
+#[derive(Debug)]
+pub struct CounterVec {
+    labels: &'static [&'static str],
+    values: Mutex<BTreeMap<Vec<String>, u64>>,
+}
+
+impl CounterVec {
+    pub fn new(labels: &'static [&'static str]) -> Self {
+        Self {
+            labels,
+            values: Mutex::default(),
+        }
+    }
+
+    /// Add one to the series named by `values` (one per label).
+    pub fn inc(&self, values: &[&str]) {
+        self.inc_by(values, 1);
+    }
+
+    pub fn inc_by(&self, values: &[&str], n: u64) {
+        debug_assert_eq!(values.len(), self.labels.len());
+        *lock(&self.values).entry(owned(values)).or_default() += n;
+    }
+
+    /// Current value of one series; `0` when it was never bumped.
+    pub fn get(&self, values: &[&str]) -> u64 {
+        lock(&self.values).get(&owned(values)).copied().unwrap_or(0)
+    }
+
+    pub fn snapshot(&self) -> Vec<(Vec<String>, u64)> {
+        lock(&self.values)
+            .iter()
+            .map(|(k, v)| (k.clone(), *v))
+            .collect()
+    }
+}
+
+/// One histogram series. `counts[i]` holds the observations that fell
+/// in bucket `i` only (not cumulative); the last slot is `+Inf`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HistogramData {
+    pub counts: Vec<u64>,
+    pub sum: f64,
+    pub count: u64,
+}
+
+/// Histograms keyed by label values, sharing one bucket layout.
+#[derive(Debug)]
+pub struct HistogramVec {
+    labels: &'static [&'static str],
+    buckets: &'static [f64],
+    values: Mutex<BTreeMap<Vec<String>, HistogramData>>,
+}
+
+impl HistogramVec {
+    /// `buckets` are upper bounds in ascending order; `+Inf` is implied.
+    pub fn new(labels: &'static [&'static str], buckets: &'static [f64]) -> Self {
+        Self {
+            labels,
+            buckets,
+            values: Mutex::default(),
+        }
+    }
+
+    pub fn observe(&self, values: &[&str], value: f64) {
+        debug_assert_eq!(values.len(), self.labels.len());
+        let slot = self
+            .buckets
+            .iter()
+            .position(|upper| value <= *upper)
+            .unwrap_or(self.buckets.len());
+        let mut series = lock(&self.values);
+        let data = series
+            .entry(owned(values))
+            .or_insert_with(|| HistogramData {
+                counts: vec![0; self.buckets.len() + 1],
+                ..HistogramData::default()
+            });
+        data.counts[slot] += 1;
+        data.sum += value;
+        data.count += 1;
+    }
+
+    pub fn snapshot(&self) -> Vec<(Vec<String>, HistogramData)> {
+        lock(&self.values)
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+/// Builds one text exposition. Each call writes a complete metric
+/// family — `# HELP`, `# TYPE` and its samples.
+#[derive(Debug, Default)]
+pub struct Encoder {
+    out: String,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn counter(&mut self, name: &str, help: &str, counter: &CounterVec) {
+        self.header(name, help, "counter");
+        for (values, n) in counter.snapshot() {
+            self.sample(name, counter.labels, &values, None, n as f64);
+        }
+    }
+
+    pub fn histogram(&mut self, name: &str, help: &str, histogram: &HistogramVec) {
+        self.header(name, help, "histogram");
+        let bucket_name = format!("{name}_bucket");
+        for (values, data) in histogram.snapshot() {
+            let mut cumulative = 0;
+            for (i, n) in data.counts.iter().enumerate() {
+                cumulative += n;
+                let le = histogram
+                    .buckets
+                    .get(i)
+                    .map_or_else(|| "+Inf".to_string(), |upper| format_value(*upper));
+                self.sample(
+                    &bucket_name,
+                    histogram.labels,
+                    &values,
+                    Some(&le),
+                    cumulative as f64,
+                );
+            }
+            self.sample(
+                &format!("{name}_sum"),
+                histogram.labels,
+                &values,
+                None,
+                data.sum,
+            );
+            self.sample(
+                &format!("{name}_count"),
+                histogram.labels,
+                &values,
+                None,
+                data.count as f64,
+            );
+        }
+    }
+
+    /// A gauge family read at scrape time. Each sample carries one
+    /// value per entry in `labels`.
+    pub fn gauge<I>(&mut self, name: &str, help: &str, labels: &[&str], samples: I)
+    where
+        I: IntoIterator<Item = (Vec<String>, f64)>,
+    {
+        self.header(name, help, "gauge");
+        for (values, value) in samples {
+            self.sample(name, labels, &values, None, value);
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn header(&mut self, name: &str, help: &str, kind: &str) {
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+    }
+
+    fn sample(
+        &mut self,
+        name: &str,
+        labels: &[&str],
+        values: &[String],
+        le: Option<&str>,
+        value: f64,
+    ) {
+        self.out.push_str(name);
+        let pairs = labels
+            .iter()
+            .copied()
+            .zip(values.iter().map(String::as_str))
+            .chain(le.map(|le| ("le", le)));
+        let mut open = false;
+        for (label, value) in pairs {
+            self.out.push(if open { ',' } else { '{' });
+            open = true;
+            let _ = write!(self.out, "{label}=\"{}\"", escape_label(value));
+        }
+        if open {
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn owned(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| (*v).to_string()).collect()
+}
+
+/// A poisoned lock only means another thread panicked mid-increment;
+/// the counts are still usable.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_render_one_sample_per_series() {
+        let c = CounterVec::new(&["method", "status"]);
+        c.inc(&["GET", "200"]);
+        c.inc(&["GET", "200"]);
+        c.inc_by(&["POST", "500"], 3);
+        assert_eq!(c.get(&["GET", "200"]), 2);
+        assert_eq!(c.get(&["PUT", "200"]), 0);
+
+        let mut enc = Encoder::new();
+        enc.counter("requests_total", "Requests.", &c);
+        assert_eq!(
+            enc.finish(),
+            "# HELP requests_total Requests.\n\
+             # TYPE requests_total counter\n\
+             requests_total{method=\"GET\",status=\"200\"} 2\n\
+             requests_total{method=\"POST\",status=\"500\"} 3\n"
+        );
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let h = HistogramVec::new(&["queue"], &[0.1, 1.0]);
+        h.observe(&["q"], 0.05);
+        h.observe(&["q"], 0.5);
+        h.observe(&["q"], 7.0);
+
+        let mut enc = Encoder::new();
+        enc.histogram("lat_seconds", "Latency.", &h);
+        let text = enc.finish();
+        assert!(text.contains("lat_seconds_bucket{queue=\"q\",le=\"0.1\"} 1\n"));
+        assert!(text.contains("lat_seconds_bucket{queue=\"q\",le=\"1\"} 2\n"));
+        assert!(text.contains("lat_seconds_bucket{queue=\"q\",le=\"+Inf\"} 3\n"));
+        assert!(text.contains("lat_seconds_sum{queue=\"q\"} 7.55\n"));
+        assert!(text.contains("lat_seconds_count{queue=\"q\"} 3\n"));
+    }
+
+    #[test]
+    fn gauges_escape_label_values() {
+        let mut enc = Encoder::new();
+        enc.gauge(
+            "up",
+            "Up.",
+            &["name"],
+            [(vec!["a\"b\\c\nd".to_string()], 1.0)],
+        );
+        enc.gauge("sealed", "Sealed.", &[], [(vec![], 0.0)]);
+        let text = enc.finish();
+        assert!(text.contains("up{name=\"a\\\"b\\\\c\\nd\"} 1\n"));
+        assert!(text.contains("sealed 0\n"));
+    }
+}
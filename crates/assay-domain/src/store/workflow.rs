@@ -32,6 +32,26 @@ pub trait WorkflowStore: Send + Sync + 'static {
         workflow: &WorkflowRecord,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
 
+    /// Create `workflow`, settling a clash with an existing workflow of the
+    /// same id in the same transaction, so concurrent starts of one id
+    /// create at most one run:
+    ///
+    /// - the existing run carries the same `request_id` → `Duplicate`;
+    /// - it hasn't closed → `Running`;
+    /// - it closed and `policy` allows replacing it → the closed run is
+    ///   moved, with its history and children links, to
+    ///   `retired_workflow_id(id, run_id)` and `workflow` takes the id;
+    /// - otherwise → `Rejected`.
+    ///
+    /// `Running` is returned whatever the policy; terminating the old run
+    /// for `TerminateIfRunning` is the caller's job.
+    fn start_workflow_run(
+        &self,
+        workflow: &WorkflowRecord,
+        policy: IdReusePolicy,
+        request_id: Option<&str>,
+    ) -> impl Future<Output = anyhow::Result<StartRunOutcome>> + Send;
+
     fn get_workflow(
         &self,
         id: &str,
@@ -47,6 +67,40 @@ pub trait WorkflowStore: Send + Sync + 'static {
         offset: i64,
     ) -> impl Future<Output = anyhow::Result<Vec<WorkflowRecord>>> + Send;
 
+    /// List workflows matching a visibility query (all of the namespace
+    /// when `None`), newest first.
+    fn query_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+        limit: i64,
+        offset: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<WorkflowRecord>>> + Send;
+
+    fn count_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> impl Future<Output = anyhow::Result<i64>> + Send;
+
+    fn count_workflows_by_status(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> impl Future<Output = anyhow::Result<Vec<WorkflowStatusCount>>> + Send;
+
+    /// Register a typed search attribute and index it. Returns false if
+    /// the namespace already has an attribute with that name.
+    fn create_search_attribute(
+        &self,
+        def: &SearchAttributeDef,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn list_search_attributes(
+        &self,
+        namespace: &str,
+    ) -> impl Future<Output = anyhow::Result<Vec<SearchAttributeDef>>> + Send;
+
     /// List workflows in terminal states whose `completed_at` is older than
     /// `cutoff` and which haven't been archived yet. Used by the optional
     /// S3 archival background task to batch candidates.
@@ -228,6 +282,20 @@ pub trait WorkflowStore: Send + Sync + 'static {
         async { Ok(RetryFailedActivityResult::Unsupported) }
     }
 
+    /// Fork `source_id` into `new_run` atomically: inserts the new run as
+    /// dispatchable, copies events `1..=reset_to_seq` from the source,
+    /// optionally re-appends the source's later `SignalReceived` events,
+    /// and appends a `WorkflowReset` event to both runs carrying `reason`.
+    /// Ending the source run is left to the caller.
+    fn reset_workflow(
+        &self,
+        source_id: &str,
+        new_run: &WorkflowRecord,
+        reset_to_seq: i32,
+        reapply_signals: bool,
+        reason: &str,
+    ) -> impl Future<Output = anyhow::Result<ResetWorkflowResult>> + Send;
+
     fn complete_activity(
         &self,
         id: i64,
@@ -236,11 +304,30 @@ pub trait WorkflowStore: Send + Sync + 'static {
         failed: bool,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
 
+    /// Record a heartbeat. Returns true when the worker should stop: a
+    /// cancel was requested for the activity or its workflow has closed.
     fn heartbeat_activity(
         &self,
         id: i64,
         details: Option<&str>,
-    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Cancel activity `seq` of a workflow: a PENDING row becomes
+    /// CANCELLED, a RUNNING one is flagged so its next heartbeat reports
+    /// the request.
+    fn request_activity_cancel(
+        &self,
+        workflow_id: &str,
+        seq: i32,
+    ) -> impl Future<Output = anyhow::Result<CancelActivityResult>> + Send;
+
+    /// If a cancel was requested for this RUNNING activity, mark it
+    /// CANCELLED and return true. Called when its worker gives up so the
+    /// failure isn't retried.
+    fn acknowledge_activity_cancel(
+        &self,
+        id: i64,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 
     fn get_timed_out_activities(
         &self,
@@ -251,8 +338,8 @@ pub trait WorkflowStore: Send + Sync + 'static {
 
     /// Mark all PENDING activities of a workflow as CANCELLED so workers
     /// that haven't claimed them yet won't pick them up. Returns the
-    /// number of rows affected. Does NOT touch RUNNING activities — those
-    /// will see the cancellation when they next heartbeat or complete.
+    /// number of rows affected. RUNNING activities are only flagged —
+    /// their workers see the cancellation on the next heartbeat.
     fn cancel_pending_activities(
         &self,
         workflow_id: &str,
@@ -285,6 +372,12 @@ pub trait WorkflowStore: Send + Sync + 'static {
         now: f64,
     ) -> impl Future<Output = anyhow::Result<Vec<WorkflowTimer>>> + Send;
 
+    /// Count unfired timers, and those of them already due at `now`.
+    fn get_timer_backlog(
+        &self,
+        now: f64,
+    ) -> impl Future<Output = anyhow::Result<TimerBacklog>> + Send;
+
     // ── Signals ─────────────────────────────────────────────
 
     fn send_signal(
@@ -298,6 +391,90 @@ pub trait WorkflowStore: Send + Sync + 'static {
         name: &str,
     ) -> impl Future<Output = anyhow::Result<Vec<WorkflowSignal>>> + Send;
 
+    // ── Updates ─────────────────────────────────────────────
+
+    /// Insert a `REQUESTED` update. Returns false (and writes nothing)
+    /// when an update with the same id already exists, so a client that
+    /// retries with the same `update_id` attaches to the original.
+    fn create_update(
+        &self,
+        update: &WorkflowUpdate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn get_update(
+        &self,
+        update_id: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<WorkflowUpdate>>> + Send;
+
+    /// Updates still waiting for a workflow task to validate them, in
+    /// arrival order. Delivered to the worker with the task's history.
+    fn list_pending_updates(
+        &self,
+        workflow_id: &str,
+    ) -> impl Future<Output = anyhow::Result<Vec<WorkflowUpdate>>> + Send;
+
+    /// Move one of `workflow_id`'s updates from `from_status` to
+    /// `to_status`, recording the outcome. Returns false when the row is
+    /// missing, belongs to another workflow, or is no longer in
+    /// `from_status`; the caller tells those apart with `get_update`.
+    fn transition_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        from_status: &str,
+        to_status: &str,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    // ── Batch jobs ─────────────────────────────────────────
+
+    fn create_batch_job(&self, job: &BatchJob) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    fn get_batch_job(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<BatchJob>>> + Send;
+
+    /// Newest first.
+    fn list_batch_jobs(
+        &self,
+        namespace: &str,
+        limit: i64,
+        offset: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<BatchJob>>> + Send;
+
+    /// Record a job's progress. Counts are written even after the job
+    /// was cancelled (the item in flight still ran); returns false once
+    /// the job has left `RUNNING`, which tells the runner to stop.
+    fn update_batch_job_progress(
+        &self,
+        id: &str,
+        total: i64,
+        processed: i64,
+        succeeded: i64,
+        failed: i64,
+        last_error: Option<&str>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Move a `RUNNING` job to a final status. Returns false if it had
+    /// already left `RUNNING`.
+    fn finish_batch_job(
+        &self,
+        id: &str,
+        status: &str,
+        error: Option<&str>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Mark `FAILED`, with `error`, every `RUNNING` job whose row was
+    /// last written before `stale_before` — its runner is gone. Returns
+    /// how many were failed.
+    fn fail_stale_batch_jobs(
+        &self,
+        stale_before: f64,
+        error: &str,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
     // ── Schedules ───────────────────────────────────────────
 
     fn create_schedule(
@@ -316,15 +493,18 @@ pub trait WorkflowStore: Send + Sync + 'static {
         namespace: &str,
     ) -> impl Future<Output = anyhow::Result<Vec<WorkflowSchedule>>> + Send;
 
+    /// Record a scheduler pass. `workflow_id` is the schedule's latest
+    /// run; `None` keeps the one already stored.
     fn update_schedule_last_run(
         &self,
         namespace: &str,
         name: &str,
         last_run_at: f64,
         next_run_at: f64,
-        workflow_id: &str,
+        workflow_id: Option<&str>,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
 
+    /// Delete a schedule together with its recorded actions.
     fn delete_schedule(
         &self,
         namespace: &str,
@@ -348,9 +528,8 @@ pub trait WorkflowStore: Send + Sync + 'static {
     /// Flip a schedule's `paused` flag. Returns the updated record, or
     /// `None` if the schedule doesn't exist.
     ///
-    /// A paused schedule is skipped by the scheduler; resuming it
-    /// doesn't backfill missed fires — the next fire is whatever the
-    /// cron expression says, starting from now.
+    /// A paused schedule is skipped by the scheduler; on resume, the
+    /// fires missed meanwhile are handled by its catch-up policy.
     fn set_schedule_paused(
         &self,
         namespace: &str,
@@ -358,6 +537,39 @@ pub trait WorkflowStore: Send + Sync + 'static {
         paused: bool,
     ) -> impl Future<Output = anyhow::Result<Option<WorkflowSchedule>>> + Send;
 
+    /// Record an action taken for a schedule fire and return its id.
+    /// Settled (non-`buffered`) actions beyond the newest `keep` for the
+    /// schedule are pruned in the same call; buffered ones are never
+    /// pruned, they are still owed a run.
+    fn record_schedule_action(
+        &self,
+        action: &ScheduleAction,
+        keep: i64,
+    ) -> impl Future<Output = anyhow::Result<i64>> + Send;
+
+    /// A schedule's recorded actions, newest first.
+    fn list_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+        limit: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<ScheduleAction>>> + Send;
+
+    /// Buffered actions still waiting for a run, oldest fire first.
+    fn list_buffered_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+    ) -> impl Future<Output = anyhow::Result<Vec<ScheduleAction>>> + Send;
+
+    /// Turn a buffered action into a `started` one once its run exists.
+    fn resolve_buffered_schedule_action(
+        &self,
+        id: i64,
+        workflow_id: &str,
+        taken_at: f64,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
     // ── Workers ─────────────────────────────────────────────
 
     fn register_worker(
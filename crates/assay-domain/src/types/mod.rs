@@ -1,5 +1,7 @@
 //! Shared type definitions re-exported at the crate root.
 
+pub mod visibility;
 pub mod workflow;
 
+pub use visibility::*;
 pub use workflow::*;
@@ -0,0 +1,163 @@
+//! Visibility queries: the parsed form of the `?query=` language on
+//! `GET /workflows`. The parser lives in `assay-workflow`; stores compile
+//! this tree to their own SQL dialect.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Value type of a registered search attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchAttributeType {
+    /// Exact-match string.
+    Keyword,
+    Int,
+    Double,
+    Bool,
+    /// RFC 3339 UTC timestamp string (`2026-10-01T00:00:00Z`).
+    Datetime,
+}
+
+impl std::fmt::Display for SearchAttributeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Keyword => "keyword",
+            Self::Int => "int",
+            Self::Double => "double",
+            Self::Bool => "bool",
+            Self::Datetime => "datetime",
+        })
+    }
+}
+
+impl std::str::FromStr for SearchAttributeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keyword" => Ok(Self::Keyword),
+            "int" => Ok(Self::Int),
+            "double" => Ok(Self::Double),
+            "bool" => Ok(Self::Bool),
+            "datetime" => Ok(Self::Datetime),
+            _ => Err(format!(
+                "unknown search attribute type {s:?} (expected keyword, int, double, bool or datetime)"
+            )),
+        }
+    }
+}
+
+/// A search attribute registered on a namespace. Registration makes
+/// `search.<name>` usable in queries and indexes the attribute.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchAttributeDef {
+    pub namespace: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attr_type: SearchAttributeType,
+    pub created_at: f64,
+}
+
+/// Workflow columns a query can filter on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkflowField {
+    WorkflowId,
+    RunId,
+    WorkflowType,
+    Status,
+    TaskQueue,
+    ParentId,
+    CreatedAt,
+    UpdatedAt,
+    CompletedAt,
+}
+
+impl WorkflowField {
+    /// Column name in `workflow.workflows`.
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::WorkflowId => "id",
+            Self::RunId => "run_id",
+            Self::WorkflowType => "workflow_type",
+            Self::Status => "status",
+            Self::TaskQueue => "task_queue",
+            Self::ParentId => "parent_id",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::CompletedAt => "completed_at",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryField {
+    Workflow(WorkflowField),
+    /// `search.<name>`; the name is a registered attribute, so it is a
+    /// plain identifier and safe to inline as a JSON path.
+    SearchAttribute {
+        name: String,
+        attr_type: SearchAttributeType,
+    },
+}
+
+/// A literal, already coerced to the field's type (timestamps on the
+/// time columns are epoch seconds).
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn sql(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "<>",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
+/// Parsed, type-checked visibility query.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VisibilityQuery {
+    And(Vec<VisibilityQuery>),
+    Or(Vec<VisibilityQuery>),
+    Not(Box<VisibilityQuery>),
+    Compare {
+        field: QueryField,
+        op: CompareOp,
+        value: QueryValue,
+    },
+    In {
+        field: QueryField,
+        values: Vec<QueryValue>,
+        negated: bool,
+    },
+    IsNull {
+        field: QueryField,
+        negated: bool,
+    },
+}
+
+/// One row of a `group by status` count.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct WorkflowStatusCount {
+    pub status: String,
+    pub count: i64,
+}
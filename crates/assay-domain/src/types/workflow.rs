@@ -113,6 +113,12 @@ pub enum EventType {
     ChildWorkflowStarted,
     ChildWorkflowCompleted,
     SideEffectRecorded,
+    UpdateAccepted,
+    UpdateCompleted,
+    UpdateRejected,
+    VersionMarkerRecorded,
+    WorkflowTaskFailed,
+    WorkflowReset,
 }
 
 impl fmt::Display for EventType {
@@ -170,6 +176,102 @@ impl FromStr for OverlapPolicy {
     }
 }
 
+// ── Catch-up Policy ─────────────────────────────────────────
+
+/// What the scheduler does with fires it missed (engine down, schedule
+/// paused, long poll gap) that are still inside the catch-up window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchupPolicy {
+    /// Drop missed fires; only a fire that is on time starts a run.
+    Skip,
+    /// Start one run for the most recent missed fire.
+    One,
+    /// Start a run for every missed fire, oldest first.
+    All,
+}
+
+impl fmt::Display for CatchupPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::One => write!(f, "one"),
+            Self::All => write!(f, "all"),
+        }
+    }
+}
+
+impl FromStr for CatchupPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "one" => Ok(Self::One),
+            "all" => Ok(Self::All),
+            _ => Err(format!("unknown catch-up policy: {s}")),
+        }
+    }
+}
+
+// ── ID Reuse Policy ─────────────────────────────────────────
+
+/// What starting a workflow does when a workflow with the same id
+/// already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdReusePolicy {
+    /// Start a new run if the existing one has closed, whatever its outcome.
+    AllowDuplicate,
+    /// Start a new run only if the existing one failed, was cancelled or
+    /// timed out.
+    AllowDuplicateFailedOnly,
+    /// Never reuse an id.
+    #[default]
+    RejectDuplicate,
+    /// Terminate the existing run if it is still going, then start a new one.
+    TerminateIfRunning,
+}
+
+impl fmt::Display for IdReusePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllowDuplicate => write!(f, "allow_duplicate"),
+            Self::AllowDuplicateFailedOnly => write!(f, "allow_duplicate_failed_only"),
+            Self::RejectDuplicate => write!(f, "reject_duplicate"),
+            Self::TerminateIfRunning => write!(f, "terminate_if_running"),
+        }
+    }
+}
+
+impl FromStr for IdReusePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow_duplicate" => Ok(Self::AllowDuplicate),
+            "allow_duplicate_failed_only" => Ok(Self::AllowDuplicateFailedOnly),
+            "reject_duplicate" => Ok(Self::RejectDuplicate),
+            "terminate_if_running" => Ok(Self::TerminateIfRunning),
+            _ => Err(format!("unknown id reuse policy: {s}")),
+        }
+    }
+}
+
+impl IdReusePolicy {
+    /// Whether a closed run in `status` may be replaced by a new one.
+    pub fn allows_replacing(self, status: WorkflowStatus) -> bool {
+        match self {
+            Self::AllowDuplicate | Self::TerminateIfRunning => status.is_terminal(),
+            Self::AllowDuplicateFailedOnly => matches!(
+                status,
+                WorkflowStatus::Failed | WorkflowStatus::Cancelled | WorkflowStatus::TimedOut
+            ),
+            Self::RejectDuplicate => false,
+        }
+    }
+}
+
 // ── Records ─────────────────────────────────────────────────
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
@@ -248,6 +350,50 @@ pub struct WorkflowActivity {
     pub last_heartbeat: Option<f64>,
 }
 
+/// Outcome of a workflow's `CancelActivity` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CancelActivityResult {
+    /// No worker had claimed it yet; it is now CANCELLED.
+    Cancelled,
+    /// It is running; the worker is told on its next heartbeat.
+    CancelRequested,
+    /// Already closed, or a cancel was already requested.
+    Unchanged,
+    NotFound,
+}
+
+/// Outcome of `WorkflowStore::start_workflow_run`.
+#[derive(Clone, Debug)]
+pub enum StartRunOutcome {
+    /// The run was created. `replaced_run_id` names the closed run that
+    /// held the id before; its row and history now live under
+    /// `retired_workflow_id(id, run_id)`.
+    Started { replaced_run_id: Option<String> },
+    /// The existing run was started with the same `request_id`; nothing
+    /// was written.
+    Duplicate(WorkflowRecord),
+    /// The id belongs to a run that hasn't closed.
+    Running(WorkflowRecord),
+    /// The id belongs to a closed run the policy doesn't allow replacing.
+    Rejected(WorkflowRecord),
+}
+
+/// Result of starting a workflow through the engine.
+#[derive(Clone, Debug)]
+pub enum StartWorkflowResult {
+    /// A new run was created.
+    Started(WorkflowRecord),
+    /// A retry of the request that started this run.
+    Existing(WorkflowRecord),
+    /// The id is taken and the reuse policy doesn't allow starting.
+    Conflict(String),
+}
+
+/// Id a closed run is kept under once a new run takes over its id.
+pub fn retired_workflow_id(workflow_id: &str, run_id: &str) -> String {
+    format!("{workflow_id}@{run_id}")
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct RetriedActivity {
     pub activity: WorkflowActivity,
@@ -265,6 +411,31 @@ pub enum RetryFailedActivityResult {
     Unsupported,
 }
 
+/// A run forked from an earlier point of another run's history by
+/// `POST /workflows/{id}/reset`.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResetWorkflow {
+    pub workflow: WorkflowRecord,
+    /// Events `1..=reset_to_seq` copied from the source run.
+    pub events_copied: u64,
+    /// `SignalReceived` events after the reset point re-appended to the
+    /// new run, so signals the source run had already received aren't lost.
+    pub signals_reapplied: u64,
+}
+
+#[derive(Clone, Debug)]
+pub enum ResetWorkflowResult {
+    Reset(Box<ResetWorkflow>),
+    NotFound,
+    Archived,
+    /// `reset_to_seq` falls outside the source run's history.
+    InvalidEventSeq {
+        event_count: i32,
+    },
+    /// The id chosen for the new run is taken.
+    AlreadyExists,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct WorkflowTimer {
     pub id: Option<i64>,
@@ -284,6 +455,25 @@ pub struct WorkflowSignal {
     pub received_at: f64,
 }
 
+/// A synchronous, result-returning request against a running workflow.
+/// Created by `POST /workflows/{id}/update/{name}`; the caller blocks
+/// until a worker's workflow task validates and applies it (status
+/// `COMPLETED` or `REJECTED`). `result` and `error` are JSON / text set
+/// by the worker's `CompleteUpdate` / `RejectUpdate` command.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct WorkflowUpdate {
+    pub id: String,
+    pub workflow_id: String,
+    pub name: String,
+    pub args: Option<String>,
+    /// `REQUESTED` → `ACCEPTED` → `COMPLETED`, or `REQUESTED` → `REJECTED`.
+    pub status: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub requested_at: f64,
+    pub completed_at: Option<f64>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct WorkflowSchedule {
     pub name: String,
@@ -301,7 +491,50 @@ pub struct WorkflowSchedule {
     pub last_run_at: Option<f64>,
     pub next_run_at: Option<f64>,
     pub last_workflow_id: Option<String>,
+    /// Fires older than this many seconds are dropped rather than caught
+    /// up when the scheduler gets to them late.
+    pub catchup_window_secs: i64,
+    /// `skip`, `one` or `all` — see `CatchupPolicy`.
+    pub catchup_policy: String,
+    /// Upper bound, in seconds, of a deterministic per-fire delay added to
+    /// each start so many schedules on the same cron don't fire at once.
+    pub jitter_secs: i64,
     pub created_at: f64,
+    /// Most recent actions taken for this schedule, newest first. Filled
+    /// in when a schedule is read through the API; empty on the rows the
+    /// scheduler itself loads.
+    #[serde(default)]
+    pub recent_actions: Vec<ScheduleAction>,
+}
+
+/// One thing the scheduler (or a backfill) did for a schedule fire.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleAction {
+    pub id: Option<i64>,
+    pub namespace: String,
+    pub schedule_name: String,
+    /// Nominal fire time from the cron expression, before jitter.
+    pub fire_time: f64,
+    /// When the action was taken.
+    pub taken_at: f64,
+    /// `started`, `skipped`, or `buffered` (waiting for the previous run
+    /// to finish under the `queue` overlap policy).
+    pub action: String,
+    pub workflow_id: Option<String>,
+    /// Why a fire was skipped or buffered.
+    pub reason: Option<String>,
+    /// True for actions requested through `POST /schedules/{name}/backfill`.
+    pub backfill: bool,
+}
+
+/// Outcome of a schedule backfill.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleBackfillResult {
+    pub started: usize,
+    pub skipped: usize,
+    pub buffered: usize,
+    /// One entry per fire in the requested range, oldest first.
+    pub actions: Vec<ScheduleAction>,
 }
 
 /// Partial update to a `WorkflowSchedule`. Only fields set to `Some` are
@@ -314,6 +547,38 @@ pub struct SchedulePatch {
     pub input: Option<serde_json::Value>,
     pub task_queue: Option<String>,
     pub overlap_policy: Option<String>,
+    pub catchup_window_secs: Option<i64>,
+    pub catchup_policy: Option<String>,
+    pub jitter_secs: Option<i64>,
+}
+
+/// A background operation applied to every workflow matching a filter.
+/// Created by `POST /batch`; the engine node that accepted it works
+/// through the targets at `max_per_second` and records progress here.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchJob {
+    pub id: String,
+    pub namespace: String,
+    /// `signal`, `cancel`, `terminate`, `reset` or
+    /// `upsert_search_attributes`.
+    pub operation: String,
+    /// JSON filter the targets were selected by.
+    pub filter: String,
+    /// JSON operation arguments (signal name + payload, reason, ...).
+    pub params: Option<String>,
+    pub max_per_second: f64,
+    /// `RUNNING` → `COMPLETED`, `CANCELLED` or `FAILED`.
+    pub status: String,
+    /// Matching workflows; 0 until the targets have been selected.
+    pub total: i64,
+    pub processed: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    /// Most recent per-workflow failure (or why the job failed).
+    pub last_error: Option<String>,
+    pub created_at: f64,
+    pub updated_at: f64,
+    pub completed_at: Option<f64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
@@ -361,10 +626,21 @@ pub struct NamespaceStats {
     pub workers: i64,
 }
 
+/// Unfired timers. `overdue` counts the ones already past `fire_at` —
+/// a growing value means the timer poller is falling behind.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct TimerBacklog {
+    pub pending: i64,
+    pub overdue: i64,
+}
+
 /// Task queue statistics.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct QueueStats {
     pub queue: String,
+    /// Workflows waiting for a worker to claim their next workflow task.
+    #[serde(default)]
+    pub pending_workflow_tasks: i64,
     pub pending_activities: i64,
     pub running_activities: i64,
     pub workers: i64,
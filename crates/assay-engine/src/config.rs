@@ -31,6 +31,8 @@ pub struct EngineConfig {
     pub dashboard: DashboardConfig,
     #[serde(default)]
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
     /// TTL in seconds for the engine_events outbox. Rows older than this
     /// are pruned hourly by the cleanup loop. Default 3 days.
     #[serde(default = "default_engine_events_ttl_secs")]
@@ -171,7 +173,13 @@ pub struct AuthConfig {
     #[serde(default)]
     pub recovery: AuthRecoveryConfig,
     #[serde(default)]
+    pub mfa: AuthMfaConfig,
+    #[serde(default)]
     pub oidc_provider: AuthOidcProviderConfig,
+    #[serde(default)]
+    pub zanzibar: AuthZanzibarConfig,
+    #[serde(default)]
+    pub scim: AuthScimConfig,
     /// Admin API keys — comma-separated bearer tokens that grant access
     /// to `/admin/*` routes. Operators rotate these via the engine
     /// config. Per-token, no expiry; for fancier admin auth (Zanzibar
@@ -238,6 +246,89 @@ pub struct AuthSessionConfig {
     /// Default session lifetime in seconds. `None` ⇒ uses the
     /// `assay_auth::session::DEFAULT_SESSION_DURATION` (30 days).
     pub ttl_seconds: Option<u64>,
+    /// Brute-force protection for password logins and recovery.
+    #[serde(default)]
+    pub lockout: AuthLockoutConfig,
+}
+
+/// Failed-login counting under `[auth.session.lockout]`. On by default;
+/// a zero `max_*_failures` turns off locking for that scope while
+/// keeping the progressive delay.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AuthLockoutConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Sliding window failures are counted over.
+    #[serde(default = "default_lockout_window_seconds")]
+    pub window_seconds: u64,
+    /// Failures per login email within the window before it locks.
+    #[serde(default = "default_max_account_failures")]
+    pub max_account_failures: u32,
+    /// Failures per client IP within the window before it locks.
+    #[serde(default = "default_max_ip_failures")]
+    pub max_ip_failures: u32,
+    /// How long a lock lasts unless an admin lifts it first.
+    #[serde(default = "default_lockout_seconds")]
+    pub lockout_seconds: u64,
+    /// Failures answered without delay before the backoff starts.
+    #[serde(default = "default_free_attempts")]
+    pub free_attempts: u32,
+    /// First backoff step; doubles per further failure.
+    #[serde(default = "default_base_delay_ms")]
+    pub base_delay_ms: u64,
+    /// Backoff ceiling.
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Key IP counters on the last `X-Forwarded-For` entry (the one the
+    /// fronting proxy appends) instead of the TCP peer. Enable only
+    /// behind exactly one such proxy.
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for AuthLockoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_seconds: default_lockout_window_seconds(),
+            max_account_failures: default_max_account_failures(),
+            max_ip_failures: default_max_ip_failures(),
+            lockout_seconds: default_lockout_seconds(),
+            free_attempts: default_free_attempts(),
+            base_delay_ms: default_base_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+fn default_lockout_window_seconds() -> u64 {
+    15 * 60
+}
+
+fn default_max_account_failures() -> u32 {
+    10
+}
+
+fn default_max_ip_failures() -> u32 {
+    100
+}
+
+fn default_lockout_seconds() -> u64 {
+    15 * 60
+}
+
+fn default_free_attempts() -> u32 {
+    3
+}
+
+fn default_base_delay_ms() -> u64 {
+    250
+}
+
+fn default_max_delay_ms() -> u64 {
+    4_000
 }
 
 /// WebAuthn / passkey module knobs.
@@ -252,6 +343,15 @@ pub struct AuthPasskeyConfig {
     pub rp_name: Option<String>,
 }
 
+/// TOTP second-factor knobs. Enrollment itself is per user.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AuthMfaConfig {
+    /// Issuer label authenticator apps show next to the account.
+    /// Defaults to `auth.passkey.rp_name`, then `"Assay"`.
+    pub totp_issuer: Option<String>,
+}
+
 /// Self-service password-recovery deployment knobs.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[non_exhaustive]
@@ -288,6 +388,65 @@ fn default_recovery_cooldown_seconds() -> u64 {
     60
 }
 
+/// SCIM 2.0 provisioning endpoint, served at `/auth/scim/v2`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AuthScimConfig {
+    /// Mount the SCIM endpoint. Boot fails when it's enabled without a
+    /// bearer token.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Bearer tokens the IdP presents. Separate from `admin_api_keys`
+    /// so a SCIM token can't reach the rest of the admin API.
+    #[serde(default)]
+    pub bearer_tokens: Vec<String>,
+    /// Organization (id or slug) provisioned users join. `/Groups`
+    /// manages its groups; unset, only `/Users` is served.
+    pub organization: Option<String>,
+}
+
+/// Zanzibar housekeeping knobs.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AuthZanzibarConfig {
+    /// How often the sweeper deletes tuples past their `expires_at`.
+    /// Checks ignore expired tuples regardless; this only bounds how
+    /// long they linger in listings. Defaults to 60 seconds.
+    #[serde(default = "default_zanzibar_expiry_sweep_seconds")]
+    pub expiry_sweep_seconds: u64,
+    /// How much tuple history to keep. Exact-snapshot checks and watch
+    /// cursors older than this fail with a consistency error once the
+    /// GC has compacted past them. Defaults to 24 hours.
+    #[serde(default = "default_zanzibar_gc_window_seconds")]
+    pub gc_window_seconds: u64,
+    /// How often the GC compacts history older than the window.
+    /// Defaults to 10 minutes.
+    #[serde(default = "default_zanzibar_gc_interval_seconds")]
+    pub gc_interval_seconds: u64,
+}
+
+impl Default for AuthZanzibarConfig {
+    fn default() -> Self {
+        Self {
+            expiry_sweep_seconds: default_zanzibar_expiry_sweep_seconds(),
+            gc_window_seconds: default_zanzibar_gc_window_seconds(),
+            gc_interval_seconds: default_zanzibar_gc_interval_seconds(),
+        }
+    }
+}
+
+fn default_zanzibar_expiry_sweep_seconds() -> u64 {
+    60
+}
+
+fn default_zanzibar_gc_window_seconds() -> u64 {
+    24 * 3600
+}
+
+fn default_zanzibar_gc_interval_seconds() -> u64 {
+    600
+}
+
 /// SMTP settings used only by password recovery.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[non_exhaustive]
@@ -339,7 +498,8 @@ pub struct VaultConfig {
     pub hashicorp_compat: HashicorpCompatConfig,
 }
 
-/// Vault / OpenBao KV2 read facade at `/v1/*`. Off unless an operator asks
+/// Vault / OpenBao facade at `/v1/*`: KV2, transit and the AppRole /
+/// Kubernetes logins. Off unless an operator asks
 /// for it: serving a second dialect of the secret store at the engine root is
 /// a deliberate act, not a default.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -351,6 +511,9 @@ pub struct HashicorpCompatConfig {
     /// their existing paths.
     #[serde(default = "default_vault_compat_mount")]
     pub mount: String,
+    /// Where transit `encrypt` / `decrypt` answer; Vault's own default.
+    #[serde(default = "default_vault_compat_transit_mount")]
+    pub transit_mount: String,
 }
 
 impl Default for HashicorpCompatConfig {
@@ -358,6 +521,7 @@ impl Default for HashicorpCompatConfig {
         Self {
             enabled: false,
             mount: default_vault_compat_mount(),
+            transit_mount: default_vault_compat_transit_mount(),
         }
     }
 }
@@ -366,6 +530,10 @@ fn default_vault_compat_mount() -> String {
     "secrets".to_string()
 }
 
+fn default_vault_compat_transit_mount() -> String {
+    "transit".to_string()
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[non_exhaustive]
 pub struct DashboardConfig {
@@ -404,6 +572,27 @@ impl DashboardConfig {
     }
 }
 
+/// Prometheus exposition at `GET /metrics`. On by default behind the
+/// admin bearer (or an API key scoped `engine:read`); `public = true`
+/// drops the check for scrapers on a trusted network.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct MetricsConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub public: bool,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            public: false,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[non_exhaustive]
 pub struct LoggingConfig {
@@ -668,6 +857,7 @@ data_dir = ":memory:"
 
         assert!(!cfg.vault.hashicorp_compat.enabled);
         assert_eq!(cfg.vault.hashicorp_compat.mount, "secrets");
+        assert_eq!(cfg.vault.hashicorp_compat.transit_mount, "transit");
     }
 
     #[test]
@@ -677,11 +867,24 @@ data_dir = ":memory:"
 [vault.hashicorp_compat]
 enabled = true
 mount = "kv"
+transit_mount = "crypto"
 "#,
         );
 
         assert!(cfg.vault.hashicorp_compat.enabled);
         assert_eq!(cfg.vault.hashicorp_compat.mount, "kv");
+        assert_eq!(cfg.vault.hashicorp_compat.transit_mount, "crypto");
+    }
+
+    #[test]
+    fn metrics_are_on_but_gated_by_default() {
+        let cfg = minimal_config_with("");
+        assert!(cfg.metrics.enabled);
+        assert!(!cfg.metrics.public);
+
+        let cfg = minimal_config_with("\n[metrics]\npublic = true\n");
+        assert!(cfg.metrics.enabled);
+        assert!(cfg.metrics.public);
     }
 
     #[test]
@@ -743,6 +946,50 @@ starttls = true
         assert!(smtp.starttls);
     }
 
+    #[test]
+    fn login_lockout_is_on_by_default_and_tunable() {
+        let cfg = minimal_config_with("");
+        let lockout = &cfg.auth.session.lockout;
+        assert!(lockout.enabled);
+        assert_eq!(lockout.max_account_failures, 10);
+        assert_eq!(lockout.window_seconds, 900);
+        assert!(!lockout.trust_forwarded_for);
+
+        let cfg = minimal_config_with(
+            r#"
+[auth.session.lockout]
+max_account_failures = 5
+lockout_seconds = 60
+trust_forwarded_for = true
+"#,
+        );
+        let lockout = &cfg.auth.session.lockout;
+        assert!(lockout.enabled);
+        assert_eq!(lockout.max_account_failures, 5);
+        assert_eq!(lockout.max_ip_failures, 100);
+        assert_eq!(lockout.lockout_seconds, 60);
+        assert!(lockout.trust_forwarded_for);
+    }
+
+    #[test]
+    fn scim_is_off_until_configured() {
+        let cfg = minimal_config_with("");
+        assert!(!cfg.auth.scim.enabled);
+        assert!(cfg.auth.scim.bearer_tokens.is_empty());
+
+        let cfg = minimal_config_with(
+            r#"
+[auth.scim]
+enabled = true
+bearer_tokens = ["okta-token"]
+organization = "acme"
+"#,
+        );
+        assert!(cfg.auth.scim.enabled);
+        assert_eq!(cfg.auth.scim.bearer_tokens, ["okta-token"]);
+        assert_eq!(cfg.auth.scim.organization.as_deref(), Some("acme"));
+    }
+
     #[test]
     fn flagship_host_and_dashboard_boundaries_deserialize() {
         let cfg: EngineConfig = toml::from_str(
@@ -87,6 +87,7 @@ pub struct EmbeddedEngine {
 ///
 /// Marked `#[non_exhaustive]` so future backends (e.g., MySQL) can
 /// be added without breaking exhaustive matches.
+#[derive(Clone)]
 #[non_exhaustive]
 pub enum EmbeddedPool {
     #[cfg(feature = "backend-postgres")]
@@ -235,6 +236,28 @@ async fn compose<S: WorkflowStore + Clone + 'static>(
         cfg.engine_events_ttl_secs,
     ));
 
+    // Reclaim expired Zanzibar tuples. Reads already ignore them, so
+    // a late tick only delays the delete + its engine event.
+    #[cfg(feature = "auth-zanzibar")]
+    if let Some(zanzibar) = auth_ctx.as_ref().and_then(|a| a.zanzibar.clone()) {
+        tokio::spawn(assay_auth::zanzibar::expiry::run_expiry_sweeper(
+            zanzibar,
+            Arc::clone(&bus),
+            std::time::Duration::from_secs(cfg.auth.zanzibar.expiry_sweep_seconds.max(1)),
+        ));
+    }
+
+    // Compact Zanzibar history older than the configured window, so
+    // deleted tuple rows and the changelog don't grow forever.
+    #[cfg(feature = "auth-zanzibar")]
+    if let Some(zanzibar) = auth_ctx.as_ref().and_then(|a| a.zanzibar.clone()) {
+        tokio::spawn(assay_auth::zanzibar::gc::run_history_gc(
+            zanzibar,
+            std::time::Duration::from_secs(cfg.auth.zanzibar.gc_window_seconds),
+            std::time::Duration::from_secs(cfg.auth.zanzibar.gc_interval_seconds.max(1)),
+        ));
+    }
+
     let whitelabel = Arc::new(WhitelabelConfig::from_env());
     let asset_version = env!("CARGO_PKG_VERSION").to_string();
     let dashboard_ctx = Arc::new(DashboardCtx::new(whitelabel, asset_version));
@@ -243,6 +266,19 @@ async fn compose<S: WorkflowStore + Clone + 'static>(
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs_f64();
+    // Vault-compat logins mint scoped API keys, so the facade can only
+    // hand out tokens when the api-key store is wired.
+    #[cfg(all(feature = "vault", feature = "vault-hashicorp-compat"))]
+    let vault_ctx = match auth_ctx.as_ref().and_then(|a| a.api_keys.clone()) {
+        Some(keys) => vault_ctx.map(|vault| {
+            vault.with_token_issuer(crate::vault_tokens::ApiKeyTokenIssuer::new(
+                keys,
+                crate::state::AdminApiKeys(Arc::clone(&admin_api_keys)),
+                started_at,
+            ))
+        }),
+        None => vault_ctx,
+    };
     let engine_config = Arc::new(cfg);
 
     let state = EngineState {
@@ -257,6 +293,8 @@ async fn compose<S: WorkflowStore + Clone + 'static>(
         engine_version: env!("CARGO_PKG_VERSION"),
         started_at,
         engine_config,
+        metrics: Arc::default(),
+        pool: pool.clone(),
     };
 
     let router = crate::server::build_app(state);
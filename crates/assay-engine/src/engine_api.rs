@@ -16,8 +16,10 @@
 //!
 //! Admin-gated endpoints reuse the same `Authorization: Bearer ...`
 //! check the auth admin router uses (compared in constant-ish time
-//! against `EngineState.admin_api_keys`). When `admin_api_keys` is
-//! empty every admin endpoint returns 401 — locking the surface
+//! against `EngineState.admin_api_keys`), which also accepts a
+//! database API key scoped `engine:read` (GET) or `engine:write` (see
+//! `assay_auth::api_keys`). With no admin keys configured and no API
+//! keys minted every admin endpoint returns 401 — locking the surface
 //! entirely. `info`, `health`, and `active-modules` are always public
 //! so dashboards can render the header bar + cross-nav before an
 //! operator has supplied credentials.
@@ -29,12 +31,13 @@
 
 use axum::Router;
 use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, StatusCode, header};
+use axum::http::{HeaderMap, Method, StatusCode, header};
 use axum::response::{IntoResponse, Json, Response};
 use axum::routing::{get, post};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
+use assay_auth::api_keys::{Permission, Resource};
 use assay_workflow::WorkflowStore;
 
 use crate::config::{BackendConfig, EngineConfig};
@@ -167,9 +170,10 @@ pub struct ListModulesResponse {
 
 async fn list_modules<S: WorkflowStore + Clone + 'static>(
     State(s): State<EngineState<S>>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &s).await {
+    if let Err(r) = require_admin(&method, &headers, &s).await {
         return *r;
     }
     let items = match list_module_records(&s.engine_config).await {
@@ -206,11 +210,12 @@ pub struct ToggleResponse {
 
 async fn toggle_module<S: WorkflowStore + Clone + 'static>(
     State(s): State<EngineState<S>>,
+    method: Method,
     headers: HeaderMap,
     Path(name): Path<String>,
     body: Option<Json<ToggleBody>>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &s).await {
+    if let Err(r) = require_admin(&method, &headers, &s).await {
         return *r;
     }
     // Look up the current module row so we know what to flip to.
@@ -280,10 +285,11 @@ pub struct PageQuery {
 
 async fn list_instances<S: WorkflowStore + Clone + 'static>(
     State(s): State<EngineState<S>>,
+    method: Method,
     headers: HeaderMap,
     Query(_q): Query<PageQuery>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &s).await {
+    if let Err(r) = require_admin(&method, &headers, &s).await {
         return *r;
     }
     let items = match list_instance_records(&s.engine_config).await {
@@ -346,10 +352,11 @@ pub struct ListAuditResponse {
 
 async fn list_audit<S: WorkflowStore + Clone + 'static>(
     State(s): State<EngineState<S>>,
+    method: Method,
     headers: HeaderMap,
     Query(q): Query<AuditQuery>,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &s).await {
+    if let Err(r) = require_admin(&method, &headers, &s).await {
         return *r;
     }
     let limit = q.limit.unwrap_or(50).clamp(1, 500);
@@ -396,9 +403,10 @@ async fn list_audit<S: WorkflowStore + Clone + 'static>(
 
 async fn get_config<S: WorkflowStore + Clone + 'static>(
     State(s): State<EngineState<S>>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(r) = require_admin(&headers, &s).await {
+    if let Err(r) = require_admin(&method, &headers, &s).await {
         return *r;
     }
     let mut value = match serde_json::to_value(&*s.engine_config) {
@@ -470,18 +478,26 @@ fn redact_secrets(v: &mut Value) {
 //   helpers — admin auth
 // =====================================================================
 
-/// Engine-core admin gate. Strict admin-bearer-only check; no
+/// Engine-core admin gate. Admin bearer, or — when the auth module is
+/// up — an API key scoped `engine:read` (GET) / `engine:write`; no
 /// session/JWT/zanzibar at the engine boundary per the decoupled-
 /// modules architecture.
-async fn require_admin<S: WorkflowStore + Clone + 'static>(
+pub(crate) async fn require_admin<S: WorkflowStore + Clone + 'static>(
+    method: &Method,
     headers: &HeaderMap,
     state: &EngineState<S>,
 ) -> Result<(), Box<Response>> {
     let keys = crate::state::AdminApiKeys(std::sync::Arc::clone(&state.admin_api_keys));
-    assay_auth::gate::require_admin_bearer(headers, &keys)
+    match state.auth.as_ref() {
+        Some(auth) => {
+            let permission = Permission::new(Resource::Engine, method);
+            assay_auth::gate::require_admin_or_api_key(headers, auth, &keys, permission).await
+        }
+        None => assay_auth::gate::require_admin_bearer(headers, &keys),
+    }
 }
 
-fn bearer_token(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<String> {
     let raw = headers
         .get(header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())?;
@@ -494,7 +510,7 @@ fn bearer_token(headers: &HeaderMap) -> Option<String> {
 /// audit log doesn't store the token itself. Truncated last 6 chars
 /// labelled `admin:****abcdef` — same shape used elsewhere for keyed
 /// admin actions (see auth admin.rs `audit` calls).
-fn short_actor(token: String) -> String {
+pub(crate) fn short_actor(token: String) -> String {
     let t = token.trim();
     if t.len() <= 6 {
         return format!("admin:****{t}");
@@ -43,8 +43,11 @@ pub mod config;
 pub mod embedded;
 pub mod engine_api;
 pub mod init;
+pub mod metrics;
 pub mod server;
 pub mod state;
+#[cfg(all(feature = "vault", feature = "vault-hashicorp-compat"))]
+mod vault_tokens;
 
 pub use assay_auth as auth;
 pub use assay_dashboard as dashboard;
@@ -53,7 +56,8 @@ pub use assay_workflow as workflow;
 
 pub use config::{
     AuthConfig, AuthOidcProviderConfig, AuthPasskeyConfig, AuthRecoveryConfig, AuthSessionConfig,
-    AuthSmtpConfig, BackendConfig, DashboardConfig, EngineConfig, ServerConfig,
+    AuthSmtpConfig, AuthZanzibarConfig, BackendConfig, DashboardConfig, EngineConfig,
+    MetricsConfig, ServerConfig,
 };
 pub use state::{AdminApiKeys, EngineState};
 
@@ -93,6 +97,14 @@ async fn build_vault_ctx_pg(
         .with_transit(assay_vault::store::postgres::PgTransitStore::new(
             pool.clone(),
         ));
+    #[cfg(feature = "vault-pki")]
+    {
+        ctx = ctx.with_pki(assay_vault::store::postgres::PgPkiStore::new(pool.clone()));
+    }
+    #[cfg(feature = "vault-ssh")]
+    {
+        ctx = ctx.with_ssh(assay_vault::store::postgres::PgSshStore::new(pool.clone()));
+    }
     #[cfg(feature = "vault-sealing-shamir")]
     {
         ctx = ctx.with_seal_store(assay_vault::store::postgres::PgSealStore::new(pool.clone()));
@@ -135,6 +147,12 @@ async fn build_vault_ctx_pg(
         let svc = assay_vault::dynamic::DynamicCredsService::new(registry, leases);
         ctx = ctx.with_dynamic(svc);
     }
+    #[cfg(feature = "vault-hashicorp-compat")]
+    {
+        ctx = ctx.with_compat_auth(assay_vault::store::postgres::PgCompatAuthStore::new(
+            pool.clone(),
+        ));
+    }
     Ok(Some(ctx))
 }
 
@@ -156,6 +174,18 @@ async fn build_vault_ctx_sqlite(
         .with_transit(assay_vault::store::sqlite::SqliteTransitStore::new(
             pool.clone(),
         ));
+    #[cfg(feature = "vault-pki")]
+    {
+        ctx = ctx.with_pki(assay_vault::store::sqlite::SqlitePkiStore::new(
+            pool.clone(),
+        ));
+    }
+    #[cfg(feature = "vault-ssh")]
+    {
+        ctx = ctx.with_ssh(assay_vault::store::sqlite::SqliteSshStore::new(
+            pool.clone(),
+        ));
+    }
     #[cfg(feature = "vault-sealing-shamir")]
     {
         ctx = ctx.with_seal_store(assay_vault::store::sqlite::SqliteSealStore::new(
@@ -198,6 +228,12 @@ async fn build_vault_ctx_sqlite(
         let svc = assay_vault::dynamic::DynamicCredsService::new(registry, leases);
         ctx = ctx.with_dynamic(svc);
     }
+    #[cfg(feature = "vault-hashicorp-compat")]
+    {
+        ctx = ctx.with_compat_auth(assay_vault::store::sqlite::SqliteCompatAuthStore::new(
+            pool.clone(),
+        ));
+    }
     Ok(Some(ctx))
 }
 
@@ -211,6 +247,19 @@ async fn build_auth_ctx_pg(
     let sessions = PostgresSessionStore::new(pool.clone()).into_dyn();
     let mut ctx = assay_auth::AuthCtx::new(users.clone(), sessions);
 
+    let audit: Arc<dyn assay_auth::audit::AuditStore> =
+        Arc::new(assay_auth::audit::PostgresAuditStore::new(pool.clone()));
+    ctx = ctx.with_audit(audit.clone());
+    ctx = ctx.with_api_keys(Arc::new(assay_auth::api_keys::PostgresApiKeyStore::new(
+        pool.clone(),
+    )));
+    #[cfg(feature = "auth-session")]
+    if let Some(policy) = lockout_policy(cfg) {
+        let store = Arc::new(assay_auth::lockout::PostgresLockoutStore::new(pool.clone()));
+        ctx = ctx
+            .with_lockout(assay_auth::lockout::LoginThrottle::new(store, policy).with_audit(audit));
+    }
+
     #[cfg(feature = "auth-recovery")]
     if let Some(options) = recovery_options(cfg)? {
         let store = Arc::new(assay_auth::recovery::PostgresRecoveryStore::new(
@@ -226,6 +275,12 @@ async fn build_auth_ctx_pg(
         ));
     }
 
+    #[cfg(feature = "auth-mfa")]
+    {
+        let store = Arc::new(assay_auth::mfa::PostgresMfaStore::new(pool.clone()));
+        ctx = ctx.with_mfa(assay_auth::mfa::MfaManager::new(store, totp_issuer(cfg)));
+    }
+
     let biscuit = assay_auth::biscuit::load_or_init_postgres(pool)
         .await
         .map_err(|e| anyhow::anyhow!("biscuit root key (pg): {e}"))?;
@@ -271,9 +326,29 @@ async fn build_auth_ctx_pg(
         let zanzibar: Arc<dyn assay_auth::zanzibar::ZanzibarStore> = Arc::new(
             assay_auth::zanzibar::PostgresZanzibarStore::new(pool.clone()),
         );
+        #[cfg(feature = "auth-orgs")]
+        {
+            let store = Arc::new(assay_auth::orgs::PostgresOrgStore::new(pool.clone()));
+            let orgs = assay_auth::orgs::OrgManager::new(store, zanzibar.clone());
+            orgs.ensure_namespaces()
+                .await
+                .map_err(|e| anyhow::anyhow!("org zanzibar namespaces (pg): {e}"))?;
+            ctx = ctx.with_orgs(orgs);
+        }
         ctx = ctx.with_zanzibar(zanzibar);
     }
 
+    #[cfg(feature = "auth-scim")]
+    if let Some(scim) = scim_provisioner(
+        cfg,
+        &ctx,
+        Arc::new(assay_auth::scim::PostgresScimStore::new(pool.clone())),
+    )
+    .await?
+    {
+        ctx = ctx.with_scim(scim);
+    }
+
     #[cfg(feature = "auth-oidc-provider")]
     if cfg.auth.oidc_provider.enabled {
         let issuer = oidc_issuer(cfg);
@@ -284,6 +359,7 @@ async fn build_auth_ctx_pg(
             assay_auth::oidc_provider::PostgresOidcClientStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::PostgresOidcUpstreamStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::PostgresOidcCodeStore::new(pool.clone()).into_dyn(),
+            assay_auth::oidc_provider::PostgresOidcDeviceCodeStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::PostgresOidcRefreshStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::PostgresOidcSessionStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::PostgresOidcConsentStore::new(pool.clone()).into_dyn(),
@@ -327,6 +403,19 @@ async fn build_auth_ctx_sqlite(
     let sessions = SqliteSessionStore::new(pool.clone()).into_dyn();
     let mut ctx = assay_auth::AuthCtx::new(users.clone(), sessions);
 
+    let audit: Arc<dyn assay_auth::audit::AuditStore> =
+        Arc::new(assay_auth::audit::SqliteAuditStore::new(pool.clone()));
+    ctx = ctx.with_audit(audit.clone());
+    ctx = ctx.with_api_keys(Arc::new(assay_auth::api_keys::SqliteApiKeyStore::new(
+        pool.clone(),
+    )));
+    #[cfg(feature = "auth-session")]
+    if let Some(policy) = lockout_policy(cfg) {
+        let store = Arc::new(assay_auth::lockout::SqliteLockoutStore::new(pool.clone()));
+        ctx = ctx
+            .with_lockout(assay_auth::lockout::LoginThrottle::new(store, policy).with_audit(audit));
+    }
+
     #[cfg(feature = "auth-recovery")]
     if let Some(options) = recovery_options(cfg)? {
         let store = Arc::new(assay_auth::recovery::SqliteRecoveryStore::new(pool.clone()));
@@ -340,6 +429,12 @@ async fn build_auth_ctx_sqlite(
         ));
     }
 
+    #[cfg(feature = "auth-mfa")]
+    {
+        let store = Arc::new(assay_auth::mfa::SqliteMfaStore::new(pool.clone()));
+        ctx = ctx.with_mfa(assay_auth::mfa::MfaManager::new(store, totp_issuer(cfg)));
+    }
+
     let biscuit = assay_auth::biscuit::load_or_init_sqlite(pool)
         .await
         .map_err(|e| anyhow::anyhow!("biscuit root key (sqlite): {e}"))?;
@@ -384,9 +479,29 @@ async fn build_auth_ctx_sqlite(
     {
         let zanzibar: Arc<dyn assay_auth::zanzibar::ZanzibarStore> =
             Arc::new(assay_auth::zanzibar::SqliteZanzibarStore::new(pool.clone()));
+        #[cfg(feature = "auth-orgs")]
+        {
+            let store = Arc::new(assay_auth::orgs::SqliteOrgStore::new(pool.clone()));
+            let orgs = assay_auth::orgs::OrgManager::new(store, zanzibar.clone());
+            orgs.ensure_namespaces()
+                .await
+                .map_err(|e| anyhow::anyhow!("org zanzibar namespaces (sqlite): {e}"))?;
+            ctx = ctx.with_orgs(orgs);
+        }
         ctx = ctx.with_zanzibar(zanzibar);
     }
 
+    #[cfg(feature = "auth-scim")]
+    if let Some(scim) = scim_provisioner(
+        cfg,
+        &ctx,
+        Arc::new(assay_auth::scim::SqliteScimStore::new(pool.clone())),
+    )
+    .await?
+    {
+        ctx = ctx.with_scim(scim);
+    }
+
     #[cfg(feature = "auth-oidc-provider")]
     if cfg.auth.oidc_provider.enabled {
         let issuer = oidc_issuer(cfg);
@@ -397,6 +512,7 @@ async fn build_auth_ctx_sqlite(
             assay_auth::oidc_provider::SqliteOidcClientStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::SqliteOidcUpstreamStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::SqliteOidcCodeStore::new(pool.clone()).into_dyn(),
+            assay_auth::oidc_provider::SqliteOidcDeviceCodeStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::SqliteOidcRefreshStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::SqliteOidcSessionStore::new(pool.clone()).into_dyn(),
             assay_auth::oidc_provider::SqliteOidcConsentStore::new(pool.clone()).into_dyn(),
@@ -498,6 +614,75 @@ fn parse_auth_public_url(cfg: &EngineConfig) -> anyhow::Result<url::Url> {
     url::Url::parse(public_url).map_err(|e| anyhow::anyhow!("auth.public_url {public_url:?}: {e}"))
 }
 
+/// `[auth.session.lockout]` as a policy, or `None` when disabled.
+#[cfg(feature = "auth-session")]
+fn lockout_policy(cfg: &EngineConfig) -> Option<assay_auth::lockout::LockoutPolicy> {
+    use std::time::Duration;
+    let lockout = &cfg.auth.session.lockout;
+    lockout.enabled.then(|| assay_auth::lockout::LockoutPolicy {
+        window: Duration::from_secs(lockout.window_seconds),
+        max_account_failures: lockout.max_account_failures,
+        max_ip_failures: lockout.max_ip_failures,
+        lockout: Duration::from_secs(lockout.lockout_seconds),
+        free_attempts: lockout.free_attempts,
+        base_delay: Duration::from_millis(lockout.base_delay_ms),
+        max_delay: Duration::from_millis(lockout.max_delay_ms),
+        trust_forwarded_for: lockout.trust_forwarded_for,
+    })
+}
+
+#[cfg(feature = "auth-mfa")]
+fn totp_issuer(cfg: &EngineConfig) -> String {
+    cfg.auth
+        .mfa
+        .totp_issuer
+        .clone()
+        .or_else(|| cfg.auth.passkey.rp_name.clone())
+        .unwrap_or_else(|| "Assay".to_string())
+}
+
+/// Build the SCIM provisioner from `[auth.scim]`, or `None` when it's
+/// disabled. Runs after the org manager is wired so a misspelt
+/// `organization` is reported at boot rather than on the first
+/// `/Groups` call.
+#[cfg(feature = "auth-scim")]
+async fn scim_provisioner(
+    cfg: &EngineConfig,
+    ctx: &assay_auth::AuthCtx,
+    store: Arc<dyn assay_auth::scim::ScimStore>,
+) -> anyhow::Result<Option<assay_auth::scim::ScimProvisioner>> {
+    let scim = &cfg.auth.scim;
+    if !scim.enabled {
+        return Ok(None);
+    }
+    if scim.bearer_tokens.is_empty() {
+        anyhow::bail!("auth.scim.bearer_tokens is required when SCIM is enabled");
+    }
+    let base_url = format!(
+        "{}/auth/scim/v2",
+        auth_public_url(cfg).trim_end_matches('/')
+    );
+    let mut provisioner = assay_auth::scim::ScimProvisioner::new(
+        store,
+        assay_auth::state::AdminApiKeys::from_keys(scim.bearer_tokens.iter().cloned()),
+        base_url,
+    );
+    if let Some(organization) = &scim.organization {
+        let known = match ctx.orgs.as_ref() {
+            Some(orgs) => orgs.resolve_org(organization).await?.is_some(),
+            None => false,
+        };
+        if !known {
+            tracing::warn!(
+                organization,
+                "auth.scim.organization does not exist yet; /Groups fails until it is created"
+            );
+        }
+        provisioner = provisioner.with_organization(organization.clone());
+    }
+    Ok(Some(provisioner))
+}
+
 struct RecoveryOptions {
     smtp: assay_auth::recovery::SmtpRecoverySettings,
     recovery_url: url::Url,
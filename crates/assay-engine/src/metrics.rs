@@ -0,0 +1,309 @@
+//! Prometheus exposition at `GET /metrics`.
+//!
+//! Two kinds of series end up in one scrape:
+//!
+//! - **Instruments** bumped as things happen — HTTP requests per route
+//!   (here, via [`track_http`]), logins and token issuance
+//!   (`assay_auth::metrics`), workflow-task dispatch latency
+//!   (`assay_workflow::metrics`).
+//! - **Gauges** read at scrape time — workflow queue depth, timer
+//!   backlog and schedule lag from the workflow store, vault seal state
+//!   and lease counts, DB pool connections.
+//!
+//! A store error while reading a gauge drops that family from the
+//! scrape (and logs) rather than failing the whole exposition.
+//!
+//! The endpoint takes the same admin gate as the engine-core API — the
+//! admin bearer or an API key scoped `engine:read` — unless
+//! `[metrics] public = true`.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use assay_domain::metrics::{CONTENT_TYPE, CounterVec, Encoder, HistogramVec, LATENCY_BUCKETS};
+use assay_workflow::{WorkflowCtx, WorkflowStore};
+use axum::Router;
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{HeaderMap, Method, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+
+use crate::embedded::EmbeddedPool;
+use crate::state::EngineState;
+
+/// HTTP instruments, shared by every request through [`track_http`].
+pub struct EngineMetrics {
+    pub http_requests: CounterVec,
+    pub http_request_duration: HistogramVec,
+}
+
+impl Default for EngineMetrics {
+    fn default() -> Self {
+        Self {
+            http_requests: CounterVec::new(&["method", "route", "status"]),
+            http_request_duration: HistogramVec::new(&["method", "route"], LATENCY_BUCKETS),
+        }
+    }
+}
+
+pub fn router<S>() -> Router<EngineState<S>>
+where
+    S: WorkflowStore + Clone + 'static,
+{
+    Router::new().route("/metrics", get(metrics_handler::<S>))
+}
+
+/// Route-layer middleware counting and timing each request. Labelled
+/// by the matched route template, never the raw path, so ids in URLs
+/// don't explode the series count.
+pub async fn track_http(
+    State(metrics): State<Arc<EngineMetrics>>,
+    route: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().as_str().to_owned();
+    let route = route.map_or_else(|| "unmatched".to_owned(), |r| r.as_str().to_owned());
+    let started = Instant::now();
+    let response = next.run(request).await;
+    metrics
+        .http_request_duration
+        .observe(&[&method, &route], started.elapsed().as_secs_f64());
+    metrics
+        .http_requests
+        .inc(&[&method, &route, response.status().as_str()]);
+    response
+}
+
+async fn metrics_handler<S: WorkflowStore + Clone + 'static>(
+    State(state): State<EngineState<S>>,
+    method: Method,
+    headers: HeaderMap,
+) -> Response {
+    if !state.engine_config.metrics.public
+        && let Err(r) = crate::engine_api::require_admin(&method, &headers, &state).await
+    {
+        return *r;
+    }
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], render(&state).await).into_response()
+}
+
+/// Render the full exposition for `state`.
+pub async fn render<S: WorkflowStore + Clone + 'static>(state: &EngineState<S>) -> String {
+    let mut enc = Encoder::new();
+    enc.gauge(
+        "assay_engine_info",
+        "Engine build and instance; always 1.",
+        &["version", "instance_id"],
+        [(
+            vec![
+                state.engine_version.to_string(),
+                state.instance_id.to_string(),
+            ],
+            1.0,
+        )],
+    );
+    enc.counter(
+        "assay_http_requests_total",
+        "HTTP requests by method, matched route and status.",
+        &state.metrics.http_requests,
+    );
+    enc.histogram(
+        "assay_http_request_duration_seconds",
+        "HTTP request latency by method and matched route.",
+        &state.metrics.http_request_duration,
+    );
+
+    render_workflow(&mut enc, &state.workflow).await;
+
+    if let Some(auth) = state.auth.as_ref() {
+        enc.counter(
+            "assay_auth_logins_total",
+            "Interactive login attempts by method and outcome.",
+            &auth.metrics.logins,
+        );
+        enc.counter(
+            "assay_auth_tokens_issued_total",
+            "Tokens issued at the OIDC token endpoint by grant type.",
+            &auth.metrics.tokens_issued,
+        );
+    }
+
+    #[cfg(feature = "vault")]
+    if let Some(vault) = state.vault.as_ref() {
+        render_vault(&mut enc, vault).await;
+    }
+
+    render_pool(&mut enc, &state.pool);
+    enc.finish()
+}
+
+async fn render_workflow<S: WorkflowStore>(enc: &mut Encoder, workflow: &WorkflowCtx<S>) {
+    enc.histogram(
+        "assay_workflow_task_dispatch_latency_seconds",
+        "Time from a workflow task becoming dispatchable to a worker claiming it.",
+        &workflow.metrics().dispatch_latency,
+    );
+
+    let namespaces = match workflow.list_namespaces().await {
+        Ok(namespaces) => namespaces,
+        Err(error) => {
+            tracing::warn!(%error, "metrics: list namespaces failed");
+            Vec::new()
+        }
+    };
+    let now = now_secs();
+    let mut pending_tasks = Vec::new();
+    let mut pending_activities = Vec::new();
+    let mut running_activities = Vec::new();
+    let mut workers = Vec::new();
+    let mut schedule_lag = Vec::new();
+    for ns in &namespaces {
+        match workflow.get_queue_stats(&ns.name).await {
+            Ok(stats) => {
+                for q in stats {
+                    let labels = vec![ns.name.clone(), q.queue];
+                    pending_tasks.push((labels.clone(), q.pending_workflow_tasks as f64));
+                    pending_activities.push((labels.clone(), q.pending_activities as f64));
+                    running_activities.push((labels.clone(), q.running_activities as f64));
+                    workers.push((labels, q.workers as f64));
+                }
+            }
+            Err(error) => {
+                tracing::warn!(%error, namespace = %ns.name, "metrics: queue stats failed")
+            }
+        }
+        match workflow.list_schedules(&ns.name).await {
+            Ok(schedules) => {
+                for s in schedules.into_iter().filter(|s| !s.paused) {
+                    let lag = s.next_run_at.map_or(0.0, |next| (now - next).max(0.0));
+                    schedule_lag.push((vec![ns.name.clone(), s.name], lag));
+                }
+            }
+            Err(error) => {
+                tracing::warn!(%error, namespace = %ns.name, "metrics: list schedules failed")
+            }
+        }
+    }
+
+    let queue = &["namespace", "queue"];
+    enc.gauge(
+        "assay_workflow_queue_pending_workflow_tasks",
+        "Workflows waiting for a worker to claim their next workflow task.",
+        queue,
+        pending_tasks,
+    );
+    enc.gauge(
+        "assay_workflow_queue_pending_activities",
+        "Activities waiting for a worker.",
+        queue,
+        pending_activities,
+    );
+    enc.gauge(
+        "assay_workflow_queue_running_activities",
+        "Activities claimed by a worker and not yet finished.",
+        queue,
+        running_activities,
+    );
+    enc.gauge(
+        "assay_workflow_queue_workers",
+        "Registered workers polling the queue.",
+        queue,
+        workers,
+    );
+    enc.gauge(
+        "assay_workflow_schedule_lag_seconds",
+        "How far an unpaused schedule's next run is overdue; 0 when on time.",
+        &["namespace", "schedule"],
+        schedule_lag,
+    );
+
+    match workflow.get_timer_backlog().await {
+        Ok(backlog) => {
+            enc.gauge(
+                "assay_workflow_timers_pending",
+                "Timers not yet fired.",
+                &[],
+                [(vec![], backlog.pending as f64)],
+            );
+            enc.gauge(
+                "assay_workflow_timers_overdue",
+                "Unfired timers already past their fire time.",
+                &[],
+                [(vec![], backlog.overdue as f64)],
+            );
+        }
+        Err(error) => tracing::warn!(%error, "metrics: timer backlog failed"),
+    }
+}
+
+#[cfg(feature = "vault")]
+async fn render_vault(enc: &mut Encoder, vault: &assay_vault::VaultCtx) {
+    let sealed = vault.seal_state.status().sealed;
+    enc.gauge(
+        "assay_vault_sealed",
+        "1 while the vault is sealed.",
+        &[],
+        [(vec![], if sealed { 1.0 } else { 0.0 })],
+    );
+
+    #[cfg(feature = "vault-dynamic-postgres")]
+    if let Some(dynamic) = vault.dynamic.as_ref() {
+        match dynamic.leases().list_leases(None).await {
+            Ok(leases) => {
+                let now = now_secs();
+                let mut counts = std::collections::BTreeMap::<(String, &str), f64>::new();
+                for lease in leases {
+                    let state = match lease.revoked_at {
+                        Some(_) => "revoked",
+                        None if lease.expires_at <= now => "expired",
+                        None => "active",
+                    };
+                    *counts.entry((lease.provider, state)).or_default() += 1.0;
+                }
+                enc.gauge(
+                    "assay_vault_leases",
+                    "Dynamic-credential leases by provider and state (active, expired awaiting revocation, revoked).",
+                    &["provider", "state"],
+                    counts
+                        .into_iter()
+                        .map(|((provider, state), n)| (vec![provider, state.to_string()], n)),
+                );
+            }
+            Err(error) => tracing::warn!(%error, "metrics: list leases failed"),
+        }
+    }
+}
+
+fn render_pool(enc: &mut Encoder, pool: &EmbeddedPool) {
+    let (size, idle, max) = match pool {
+        #[cfg(feature = "backend-postgres")]
+        EmbeddedPool::Postgres(p) => (p.size(), p.num_idle(), p.options().get_max_connections()),
+        #[cfg(feature = "backend-sqlite")]
+        EmbeddedPool::Sqlite(p) => (p.size(), p.num_idle(), p.options().get_max_connections()),
+    };
+    let idle = idle as f64;
+    enc.gauge(
+        "assay_db_pool_connections",
+        "Open database connections by state.",
+        &["state"],
+        [
+            (vec!["idle".to_string()], idle),
+            (vec!["active".to_string()], (size as f64 - idle).max(0.0)),
+        ],
+    );
+    enc.gauge(
+        "assay_db_pool_max_connections",
+        "Configured database pool size.",
+        &[],
+        [(vec![], max as f64)],
+    );
+}
+
+fn now_secs() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
@@ -8,6 +8,7 @@
 //! - `/api/v1/vault/*`           vault module (admin-bearer-gated)
 //! - `/v1/*`                     Vault/OpenBao KV2 read facade (opt-in)
 //! - `/healthz`                  redirect to `/api/v1/engine/core/health`
+//! - `/metrics`                  Prometheus exposition (admin-gated unless `[metrics] public`)
 //!
 //! Per the decoupled-modules architecture: each module accepts ONLY
 //! an admin bearer token at its HTTP boundary. Per-user authentication
@@ -17,12 +18,23 @@
 //! engine using its own admin bearer. The engine itself does not
 //! resolve sessions or check zanzibar at request time.
 //!
-//! Share-redeem (`GET /api/v1/vault/share/{token}`) is the one route
-//! that bypasses admin bearer — the biscuit token in the URL is its
-//! own auth, verified inside the handler.
+//! Two kinds of vault route bypass the admin bearer: share-redeem
+//! (`GET /api/v1/vault/share/{token}`), where the biscuit token in the
+//! URL is its own auth, verified inside the handler; and the PKI
+//! publication routes (CA certificate, CRL, OCSP), which TLS clients
+//! fetch without credentials.
+//!
+//! Narrower than the admin bearer: database API keys (`ak_…`, see
+//! `assay_auth::api_keys`) pass the same gates when one of their
+//! scopes covers the request. The gate works out what a request needs
+//! from its module, method and path — plus, for workflow calls, the
+//! namespace named in the `namespace` / `ns` query parameter, the JSON
+//! body's `namespace`, or the addressed workflow's record. A workflow
+//! call that names no namespace needs an unrestricted `workflow:` scope.
 
+use assay_auth::api_keys::{Permission, Resource};
 use axum::Router;
-use axum::http::{HeaderMap, StatusCode, header};
+use axum::http::{HeaderMap, Method, StatusCode, header};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::get;
 use std::sync::Arc;
@@ -50,7 +62,7 @@ pub fn build_app<S: WorkflowStore + Clone + 'static>(state: EngineState<S>) -> R
     // We supply admin_bearer_middleware as the gate; the workflow
     // crate applies it to only the authed portion of the router so
     // /health, /version, /openapi.json, /docs stay public for probes.
-    let state_for_workflow = state.clone();
+    let state_for_workflow = (state.clone(), GatedModule::Workflow);
     let workflow_router = assay_workflow::api::router(Arc::clone(&state.workflow), |r| {
         r.layer(axum::middleware::from_fn_with_state(
             state_for_workflow,
@@ -74,6 +86,12 @@ pub fn build_app<S: WorkflowStore + Clone + 'static>(state: EngineState<S>) -> R
 
     let mut app = workflow_router.merge(healthz).merge(engine_api_router);
 
+    // Prometheus scrape target; see `metrics.rs` for what it covers.
+    let metrics_enabled = state.engine_config.metrics.enabled;
+    if metrics_enabled {
+        app = app.merge(crate::metrics::router::<S>().with_state(state.clone()));
+    }
+
     // Built-in operator SPAs. The engine ships its own browser UI —
     // auth console, vault console, workflow dashboard, engine console —
     // so a stand-alone deployment (no sysops in front) is usable from
@@ -138,9 +156,10 @@ pub fn build_app<S: WorkflowStore + Clone + 'static>(state: EngineState<S>) -> R
     if state.vault.is_some() {
         // Vault router requires a non-optional gate closure
         // (typechecked). We supply admin_bearer_middleware, which has
-        // the share-redeem path bypass built in so /share/{token}
-        // stays public (biscuit verifies in the handler).
-        let state_for_vault = state.clone();
+        // the public-path bypass built in so /share/{token} (biscuit
+        // verifies in the handler) and the PKI CA / CRL / OCSP routes
+        // stay public.
+        let state_for_vault = (state.clone(), GatedModule::Vault);
         let vault = assay_vault::router::vault_router::<EngineState<S>, _>(|r| {
             r.layer(axum::middleware::from_fn_with_state(
                 state_for_vault,
@@ -190,6 +209,15 @@ pub fn build_app<S: WorkflowStore + Clone + 'static>(state: EngineState<S>) -> R
         app = app.merge(root);
     }
 
+    // Per-route HTTP counters. A route layer (not a plain layer) so the
+    // middleware sees `MatchedPath`; unmatched requests aren't counted.
+    if metrics_enabled {
+        app = app.route_layer(axum::middleware::from_fn_with_state(
+            Arc::clone(&state.metrics),
+            crate::metrics::track_http,
+        ));
+    }
+
     if !state.engine_config.server.allowed_hosts.is_empty() {
         app = app.layer(axum::middleware::from_fn_with_state(
             state,
@@ -200,15 +228,18 @@ pub fn build_app<S: WorkflowStore + Clone + 'static>(state: EngineState<S>) -> R
     app
 }
 
-/// Vault / OpenBao KV2 read facade, mounted at the router root because Vault
-/// clients (ESO, ansible, curl) hardcode `/v1/…` and cannot be told to use a
-/// prefix. It carries the same admin-bearer gate every other module surface
-/// does, translating `X-Vault-Token` into that bearer on the way in.
+/// Vault / OpenBao facade (KV2, transit, AppRole and Kubernetes logins),
+/// mounted at the router root because Vault clients (ESO, Terraform,
+/// vault-agent, curl) hardcode `/v1/…` and cannot be told to use a
+/// prefix. Everything but `sys/health`, the logins and `auth/token/*-self`
+/// carries the same admin-bearer gate every other module surface does,
+/// translating `X-Vault-Token` into that bearer on the way in.
 #[cfg(all(feature = "vault", feature = "vault-hashicorp-compat"))]
 fn hashicorp_compat_router<S: WorkflowStore + Clone + 'static>(state: &EngineState<S>) -> Router {
     let compat = &state.engine_config.vault.hashicorp_compat;
-    let mount = assay_vault::hashicorp_compat::Mount::new(&compat.mount);
-    let state_for_gate = state.clone();
+    let mount = assay_vault::hashicorp_compat::Mount::new(&compat.mount)
+        .with_transit(&compat.transit_mount);
+    let state_for_gate = (state.clone(), GatedModule::HashicorpCompat);
     assay_vault::hashicorp_compat::router::<EngineState<S>, _>(mount, |r| {
         r.layer(axum::middleware::from_fn_with_state(
             state_for_gate,
@@ -267,44 +298,437 @@ fn request_host(headers: &HeaderMap) -> Option<String> {
 }
 
 /// Resource-server middleware applied to every engine module router.
-/// Accepts EITHER the operator admin api-key (service-to-service /
-/// break-glass) OR a JWT from a configured trusted issuer (per-user
-/// resource-server pattern). No session, no zanzibar — policy lives
-/// upstream.
+/// Accepts the operator admin api-key (service-to-service /
+/// break-glass), a database API key whose scopes cover the request
+/// (see [`api_key_permissions`]), OR a JWT from a configured trusted
+/// issuer (per-user resource-server pattern). No session, no zanzibar —
+/// policy lives upstream.
 ///
-/// The one bypass: vault share-redeem (`GET /share/{token}` —
-/// path-relative because this middleware runs INSIDE the nested
-/// `/api/v1/vault` router, after axum has stripped the prefix).
-/// `/share/revoke` is excluded — it's an admin operation that mints
-/// or revokes tokens. The biscuit token in the share-redeem URL is
-/// its own authentication.
+/// The bypasses are the public vault routes — see [`vault_public_path`].
+/// Vault requests that pass are tagged with the caller (see
+/// [`with_vault_actor`]).
 async fn admin_bearer_middleware<S: WorkflowStore + Clone + 'static>(
-    axum::extract::State(state): axum::extract::State<EngineState<S>>,
+    axum::extract::State((state, module)): axum::extract::State<(EngineState<S>, GatedModule)>,
     request: axum::extract::Request,
     next: axum::middleware::Next,
 ) -> axum::response::Response {
-    let path = request.uri().path();
-    if (path.starts_with("/share/") && path != "/share/revoke")
-        || (path.starts_with("/api/v1/vault/share/") && path != "/api/v1/vault/share/revoke")
-    {
+    if module == GatedModule::Vault && vault_public_path(request.uri().path()) {
         return next.run(request).await;
     }
     let keys = crate::state::AdminApiKeys(Arc::clone(&state.admin_api_keys));
+    // API keys are told apart by their `ak_` prefix, so admin keys and
+    // JWTs never pay for the lookup (or the body buffering below).
+    if let Some(auth) = state.auth.as_ref()
+        && let Some(token) = assay_auth::gate::presented_api_key(request.headers())
+        && !keys.check(token)
+    {
+        let token = token.to_owned();
+        let (request, needed) = match api_key_needs(&state, module, request).await {
+            Ok(resolved) => resolved,
+            Err(r) => return *r,
+        };
+        let permissions = api_key_permissions(&needed, request.method());
+        let key = match assay_auth::gate::require_api_key(&token, auth, &permissions).await {
+            Ok(key) => key,
+            Err(r) => return *r,
+        };
+        let request = with_vault_actor(request, module, format!("api_key:{}", key.id));
+        return next.run(request).await;
+    }
     // If auth is configured, accept admin bearer OR trusted JWT.
     // If auth is not configured at all (no AuthCtx), only the admin
     // bearer path is available — fall back to the strict check.
     let outcome = match state.auth.as_ref() {
-        Some(auth) => assay_auth::gate::require_admin_or_jwt(request.headers(), auth, &keys)
-            .await
-            .map(|_| ()),
-        None => assay_auth::gate::require_admin_bearer(request.headers(), &keys),
+        Some(auth) => assay_auth::gate::require_admin_or_jwt(request.headers(), auth, &keys).await,
+        None => assay_auth::gate::require_admin_bearer(request.headers(), &keys).map(|_| None),
     };
-    if let Err(r) = outcome {
-        return *r;
-    }
+    let subject = match outcome {
+        Ok(subject) => subject,
+        Err(r) => return *r,
+    };
+    // `None` is the admin key: name it the way the engine audit log does.
+    let actor = subject.unwrap_or_else(|| {
+        crate::engine_api::bearer_token(request.headers())
+            .map(crate::engine_api::short_actor)
+            .unwrap_or_default()
+    });
+    let request = with_vault_actor(request, module, actor);
     next.run(request).await
 }
 
+/// Attach the authenticated caller to a vault request as an
+/// [`assay_vault::audit::Actor`], so handlers that keep an issuance
+/// record (the SSH CA) can say who asked.
+#[cfg(feature = "vault")]
+fn with_vault_actor(
+    mut request: axum::extract::Request,
+    module: GatedModule,
+    actor: String,
+) -> axum::extract::Request {
+    if module == GatedModule::Vault {
+        request
+            .extensions_mut()
+            .insert(assay_vault::audit::Actor(actor));
+    }
+    request
+}
+
+#[cfg(not(feature = "vault"))]
+fn with_vault_actor(
+    request: axum::extract::Request,
+    _module: GatedModule,
+    _actor: String,
+) -> axum::extract::Request {
+    request
+}
+
+/// Which module router a gate instance guards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GatedModule {
+    Workflow,
+    Vault,
+    HashicorpCompat,
+}
+
+/// What a request needs from an API key, owned so the workflow gate
+/// can consume the request body to find it.
+#[derive(Debug, PartialEq, Eq)]
+enum ApiKeyNeed {
+    /// Every namespace the request names; empty when it names none.
+    Workflow { namespaces: Vec<String> },
+    Vault {
+        surface: String,
+        path: Option<String>,
+    },
+}
+
+/// Largest JSON body the workflow gate buffers to read `namespace`
+/// from — axum's default `Json` limit, so nothing the handlers would
+/// accept is refused here.
+const GATE_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+async fn api_key_needs<S: WorkflowStore + Clone + 'static>(
+    state: &EngineState<S>,
+    module: GatedModule,
+    request: axum::extract::Request,
+) -> Result<(axum::extract::Request, ApiKeyNeed), Box<Response>> {
+    match module {
+        GatedModule::Vault => {
+            let need = vault_need(request.uri().path());
+            Ok((request, need))
+        }
+        GatedModule::HashicorpCompat => {
+            let need = hashicorp_need(request.method(), request.uri());
+            Ok((request, need))
+        }
+        GatedModule::Workflow => workflow_need(state, request).await,
+    }
+}
+
+/// Where a workflow route's handler reads the namespace it acts on.
+/// The gate looks in exactly that place and nowhere else: a `namespace`
+/// the handler never reads says nothing about what it will touch.
+#[derive(Debug, PartialEq, Eq)]
+enum NamespaceSource {
+    /// Query parameters with this name.
+    Query(&'static str),
+    /// The JSON body's `namespace`.
+    Body,
+    /// `/namespaces/{name}` itself.
+    Path(String),
+    /// The stored workflow with this id.
+    Workflow(String),
+    /// The stored activity (task) with this id, through its workflow.
+    Activity(String),
+    /// The stored batch job with this id.
+    Batch(String),
+    /// Not namespaced: only an unrestricted workflow scope passes.
+    None,
+}
+
+/// Map a workflow router path (with or without the
+/// `/api/v1/engine/workflow` prefix) to its [`NamespaceSource`].
+fn namespace_source(method: &Method, path: &str) -> NamespaceSource {
+    let rest = path
+        .strip_prefix("/api/v1/engine/workflow")
+        .unwrap_or(path)
+        .trim_start_matches('/');
+    let mut segments = rest.split('/').filter(|s| !s.is_empty());
+    let collection = segments.next().unwrap_or_default();
+    let item = segments.next();
+    let listing = |method: &Method| {
+        if method == Method::POST {
+            NamespaceSource::Body
+        } else {
+            NamespaceSource::Query("namespace")
+        }
+    };
+    match (collection, item) {
+        ("namespaces", Some(name)) => NamespaceSource::Path(decode(name)),
+        ("workflows" | "batch" | "schedules", None) => listing(method),
+        ("workflows" | "workflow-tasks", Some(id)) if id != "poll" => {
+            NamespaceSource::Workflow(decode(id))
+        }
+        ("activities" | "tasks", Some(id)) if id != "poll" => NamespaceSource::Activity(decode(id)),
+        ("batch", Some(id)) => NamespaceSource::Batch(decode(id)),
+        ("schedules", Some(_)) | ("workers" | "queues", None) | ("visibility", Some("count")) => {
+            NamespaceSource::Query("namespace")
+        }
+        ("workers", Some("register")) => NamespaceSource::Body,
+        ("events", Some("stream")) => NamespaceSource::Query("ns"),
+        _ => NamespaceSource::None,
+    }
+}
+
+/// Resolve the namespace a workflow call acts on from its
+/// [`NamespaceSource`]. Several `namespace` query values each need
+/// covering; a record that doesn't exist resolves to none, so only an
+/// unrestricted key reaches the handler's 404.
+async fn workflow_need<S: WorkflowStore + Clone + 'static>(
+    state: &EngineState<S>,
+    request: axum::extract::Request,
+) -> Result<(axum::extract::Request, ApiKeyNeed), Box<Response>> {
+    let lookup_failed = |what: &str, e: anyhow::Error| {
+        Box::new(
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({
+                    "error": "server_error",
+                    "error_description": format!("resolve {what} namespace: {e}"),
+                })),
+            )
+                .into_response(),
+        )
+    };
+    let workflow_namespace = |id: String| async move {
+        match state.workflow.get_workflow(&id).await {
+            Ok(record) => Ok(record.map(|r| r.namespace)),
+            Err(e) => Err(lookup_failed("workflow", e)),
+        }
+    };
+    let mut namespaces = Vec::new();
+    let mut request = request;
+    match namespace_source(request.method(), request.uri().path()) {
+        NamespaceSource::Query(key) => {
+            namespaces = query_namespaces(request.uri().query(), key);
+        }
+        NamespaceSource::Path(name) => namespaces.push(name),
+        NamespaceSource::Workflow(id) => namespaces.extend(workflow_namespace(id).await?),
+        NamespaceSource::Activity(id) => {
+            let activity = match id.parse::<i64>() {
+                Ok(id) => state
+                    .workflow
+                    .get_activity(id)
+                    .await
+                    .map_err(|e| lookup_failed("activity", e))?,
+                Err(_) => None,
+            };
+            if let Some(activity) = activity {
+                namespaces.extend(workflow_namespace(activity.workflow_id).await?);
+            }
+        }
+        NamespaceSource::Batch(id) => {
+            let job = state
+                .workflow
+                .get_batch_job(&id)
+                .await
+                .map_err(|e| lookup_failed("batch job", e))?;
+            namespaces.extend(job.map(|job| job.namespace));
+        }
+        NamespaceSource::Body => {
+            let is_json = request
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|ct| ct.starts_with("application/json"));
+            if is_json {
+                let (parts, body) = request.into_parts();
+                let bytes = match axum::body::to_bytes(body, GATE_BODY_LIMIT).await {
+                    Ok(bytes) => bytes,
+                    Err(_) => return Err(Box::new(StatusCode::PAYLOAD_TOO_LARGE.into_response())),
+                };
+                if let Ok(body) = serde_json::from_slice::<serde_json::Value>(&bytes)
+                    && let Some(ns) = body.get("namespace").and_then(|v| v.as_str())
+                {
+                    namespaces.push(ns.to_string());
+                }
+                request = axum::extract::Request::from_parts(parts, axum::body::Body::from(bytes));
+            }
+        }
+        NamespaceSource::None => {}
+    }
+    namespaces.sort();
+    namespaces.dedup();
+    Ok((request, ApiKeyNeed::Workflow { namespaces }))
+}
+
+fn query_namespaces(query: Option<&str>, key: &str) -> Vec<String> {
+    url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .filter(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+        .collect()
+}
+
+/// Vault routes that carry their own auth or none at all. Checked with
+/// or without the `/api/v1/vault` prefix, because this middleware runs
+/// INSIDE the nested vault router, after axum has stripped it.
+///
+/// - Share-redeem (`GET /share/{token}`): the biscuit token in the URL
+///   is its own authentication. `/share/revoke` is excluded — it's an
+///   admin operation that mints or revokes tokens.
+/// - PKI publication: the CA certificate, the CRL and the OCSP
+///   responder, which relying parties fetch from the URLs stamped into
+///   issued certificates.
+/// - The SSH CA public key, which hosts fetch into `TrustedUserCAKeys`
+///   and clients into `@cert-authority` lines.
+fn vault_public_path(path: &str) -> bool {
+    let rest = path.strip_prefix("/api/v1/vault").unwrap_or(path);
+    if let Some(share) = rest.strip_prefix("/share/") {
+        return share != "revoke";
+    }
+    matches!(
+        rest,
+        "/pki/ca" | "/pki/ca/pem" | "/pki/crl" | "/pki/crl/pem" | "/pki/ocsp" | "/ssh/public_key"
+    ) || rest.starts_with("/pki/ocsp/")
+}
+
+/// Map a vault router path (with or without the `/api/v1/vault`
+/// prefix) to its surface, plus the KV path, transit key, or PKI or SSH
+/// role it touches.
+/// KV listings get a trailing `/` so `vault:kv:read:/apps/*` can list
+/// `apps`.
+fn vault_need(path: &str) -> ApiKeyNeed {
+    let rest = path
+        .strip_prefix("/api/v1/vault")
+        .unwrap_or(path)
+        .trim_start_matches('/');
+    let (head, tail) = rest.split_once('/').unwrap_or((rest, ""));
+    let (surface, path) = match head {
+        "kv" | "kv-meta" | "kv-destroy" | "kv-undelete" => ("kv", Some(decode(tail))),
+        "kv-list" => (
+            "kv",
+            Some(format!("{}/", decode(tail).trim_end_matches('/'))),
+        ),
+        "transit" => {
+            // Only the crypto operations are scoped by key name;
+            // `keys/{name}` (create, config, rotate, delete) needs the
+            // whole surface, or a key meant to encrypt could also
+            // rotate or delete the key it encrypts with.
+            let mut segments = tail.split('/');
+            let key = match (segments.next(), segments.next()) {
+                (
+                    Some("encrypt" | "decrypt" | "rewrap" | "hmac" | "sign" | "verify"),
+                    Some(name),
+                ) if !name.is_empty() => Some(decode(name)),
+                // `datakey/{plaintext|wrapped}/{name}`
+                (Some("datakey"), Some(_)) => {
+                    segments.next().filter(|name| !name.is_empty()).map(decode)
+                }
+                _ => None,
+            };
+            ("transit", key)
+        }
+        "pki" => {
+            // Only issuance is scoped by role, as for SSH below.
+            let role = match tail.split_once('/') {
+                Some(("issue" | "sign", name)) if !name.is_empty() => Some(decode(name)),
+                _ => None,
+            };
+            ("pki", role)
+        }
+        "ssh" => {
+            // Only signing is scoped by role: a key that could write
+            // `roles/{name}` could widen the role it signs with.
+            let role = match tail.split_once('/') {
+                Some(("sign", name)) if !name.is_empty() => Some(decode(name)),
+                _ => None,
+            };
+            ("ssh", role)
+        }
+        "me" | "collections" | "items" | "folders" => ("collections", None),
+        other => (other, None),
+    };
+    ApiKeyNeed::Vault {
+        surface: surface.to_string(),
+        path,
+    }
+}
+
+/// Map a Vault-dialect request to the native surface it reaches:
+/// `/v1/{mount}/data|metadata|delete|undelete|destroy/{path}` to KV,
+/// `/v1/{mount}/encrypt|decrypt/{key}` to transit, and `/v1/sys/…` plus
+/// the auth-method administration under `/v1/auth/…` to `sys`. A LIST
+/// (verb or `?list=true`) is a listing, so it gets the same trailing `/`
+/// as `kv-list`.
+fn hashicorp_need(method: &Method, uri: &axum::http::Uri) -> ApiKeyNeed {
+    let rest = uri.path().strip_prefix("/v1/").unwrap_or(uri.path());
+    let mut segments = rest.splitn(3, '/');
+    let (mount, kind, tail) = (segments.next(), segments.next(), segments.next());
+    if matches!(mount, Some("sys" | "auth")) {
+        return ApiKeyNeed::Vault {
+            surface: "sys".to_string(),
+            path: None,
+        };
+    }
+    let tail = decode(tail.unwrap_or_default());
+    if matches!(kind, Some("encrypt" | "decrypt")) {
+        return ApiKeyNeed::Vault {
+            surface: "transit".to_string(),
+            path: Some(tail).filter(|key| !key.is_empty()),
+        };
+    }
+    let listing = method.as_str() == "LIST"
+        || url::form_urlencoded::parse(uri.query().unwrap_or_default().as_bytes())
+            .any(|(k, v)| k == "list" && v == "true");
+    let path = match kind {
+        Some("metadata") if listing => Some(format!("{}/", tail.trim_end_matches('/'))),
+        Some("data" | "metadata" | "delete" | "undelete" | "destroy") => Some(tail),
+        _ => None,
+    };
+    ApiKeyNeed::Vault {
+        surface: "kv".to_string(),
+        path,
+    }
+}
+
+fn decode(segment: &str) -> String {
+    percent_encoding::percent_decode_str(segment)
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+/// The permissions an API key must hold for `need` — one per workflow
+/// namespace, so a key scoped to `prod` can't reach `staging` by naming
+/// both.
+fn api_key_permissions<'a>(need: &'a ApiKeyNeed, method: &Method) -> Vec<Permission<'a>> {
+    match need {
+        ApiKeyNeed::Workflow { namespaces } if namespaces.is_empty() => {
+            vec![Permission::new(
+                Resource::Workflow { namespace: None },
+                method,
+            )]
+        }
+        ApiKeyNeed::Workflow { namespaces } => namespaces
+            .iter()
+            .map(|ns| {
+                Permission::new(
+                    Resource::Workflow {
+                        namespace: Some(ns),
+                    },
+                    method,
+                )
+            })
+            .collect(),
+        ApiKeyNeed::Vault { surface, path } => vec![Permission::new(
+            Resource::Vault {
+                surface,
+                path: path.as_deref(),
+            },
+            method,
+        )],
+    }
+}
+
 /// Bind a TCP listener on `bind_addr` and serve the composed app.
 ///
 /// Convenience wrapper that composes [`EngineState`] into an
@@ -331,7 +755,12 @@ pub async fn bind_and_serve(bind_addr: &str, app: axum::Router) -> anyhow::Resul
         .map_err(|e| anyhow::anyhow!("bind {bind_addr}: {e}"))?;
     let actual = listener.local_addr()?;
     info!(target: "assay-engine", %actual, "listening");
-    axum::serve(listener, app).await?;
+    // Peer addresses feed the auth module's per-IP login throttling.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
 
@@ -403,3 +832,271 @@ mod host_boundary_tests {
         assert!(host_is_allowed(&HeaderMap::new(), &[]));
     }
 }
+
+#[cfg(test)]
+mod api_key_need_tests {
+    use axum::http::{Method, Uri};
+
+    use super::{
+        ApiKeyNeed, NamespaceSource, api_key_permissions, hashicorp_need, namespace_source,
+        query_namespaces, vault_need, vault_public_path,
+    };
+
+    fn vault(surface: &str, path: Option<&str>) -> ApiKeyNeed {
+        ApiKeyNeed::Vault {
+            surface: surface.to_string(),
+            path: path.map(str::to_string),
+        }
+    }
+
+    /// Whether a key holding `scopes` passes the vault gate for
+    /// `method path`.
+    fn key_allows(scopes: &[&str], method: Method, path: &str) -> bool {
+        let scopes = assay_auth::api_keys::parse_scopes(scopes).unwrap();
+        let need = vault_need(path);
+        api_key_permissions(&need, &method)
+            .iter()
+            .all(|p| scopes.iter().any(|s| s.allows(p)))
+    }
+
+    #[test]
+    fn vault_paths_map_to_surfaces() {
+        assert_eq!(vault_need("/kv/apps/db"), vault("kv", Some("apps/db")));
+        assert_eq!(
+            vault_need("/api/v1/vault/kv-meta/apps/db"),
+            vault("kv", Some("apps/db"))
+        );
+        assert_eq!(vault_need("/kv-list/apps"), vault("kv", Some("apps/")));
+        assert_eq!(vault_need("/kv-list"), vault("kv", Some("/")));
+        assert_eq!(
+            vault_need("/kv/apps%2F..%2Finfra"),
+            vault("kv", Some("apps/../infra"))
+        );
+        assert_eq!(
+            vault_need("/transit/encrypt/payments"),
+            vault("transit", Some("payments"))
+        );
+        assert_eq!(
+            vault_need("/transit/keys/payments/rotate"),
+            vault("transit", None)
+        );
+        assert_eq!(
+            vault_need("/transit/sign/release"),
+            vault("transit", Some("release"))
+        );
+        assert_eq!(
+            vault_need("/transit/datakey/wrapped/payments"),
+            vault("transit", Some("payments"))
+        );
+        assert_eq!(vault_need("/transit/keys"), vault("transit", None));
+        assert_eq!(vault_need("/pki/issue/web"), vault("pki", Some("web")));
+        assert_eq!(
+            vault_need("/api/v1/vault/pki/roles/web"),
+            vault("pki", None)
+        );
+        assert_eq!(vault_need("/pki/roles"), vault("pki", None));
+        assert_eq!(vault_need("/pki/root/generate"), vault("pki", None));
+        assert_eq!(vault_need("/ssh/sign/ops"), vault("ssh", Some("ops")));
+        assert_eq!(vault_need("/ssh/roles/ops"), vault("ssh", None));
+        assert_eq!(vault_need("/ssh/ca/generate"), vault("ssh", None));
+        assert_eq!(vault_need("/folders/f1"), vault("collections", None));
+        assert_eq!(vault_need("/sys/seal"), vault("sys", None));
+    }
+
+    #[test]
+    fn ssh_role_scopes_sign_but_never_write_roles() {
+        let scoped = ["vault:ssh:write:ops*"];
+        assert!(key_allows(&scoped, Method::POST, "/ssh/sign/ops"));
+        assert!(key_allows(&scoped, Method::POST, "/ssh/sign/ops-eu"));
+        assert!(!key_allows(&scoped, Method::POST, "/ssh/sign/admin"));
+        assert!(!key_allows(&scoped, Method::POST, "/ssh/roles/ops"));
+        assert!(!key_allows(&scoped, Method::DELETE, "/ssh/roles/ops"));
+        assert!(key_allows(
+            &["vault:ssh:write"],
+            Method::POST,
+            "/ssh/roles/ops"
+        ));
+    }
+
+    #[test]
+    fn transit_key_scopes_use_but_never_manage_keys() {
+        let scoped = ["vault:transit:write:payments"];
+        for op in ["encrypt", "decrypt", "rewrap", "hmac", "sign", "verify"] {
+            assert!(key_allows(
+                &scoped,
+                Method::POST,
+                &format!("/transit/{op}/payments")
+            ));
+        }
+        assert!(key_allows(
+            &scoped,
+            Method::POST,
+            "/transit/datakey/wrapped/payments"
+        ));
+        assert!(!key_allows(
+            &scoped,
+            Method::POST,
+            "/transit/encrypt/billing"
+        ));
+        for path in [
+            "/transit/keys/payments",
+            "/transit/keys/payments/rotate",
+            "/transit/keys/payments/config",
+        ] {
+            assert!(!key_allows(&scoped, Method::POST, path), "{path}");
+        }
+        assert!(!key_allows(
+            &scoped,
+            Method::DELETE,
+            "/transit/keys/payments"
+        ));
+        assert!(key_allows(
+            &["vault:transit:write"],
+            Method::POST,
+            "/transit/keys/payments/rotate"
+        ));
+    }
+
+    #[test]
+    fn pki_role_scopes_issue_but_never_write_roles() {
+        let scoped = ["vault:pki:write:web"];
+        assert!(key_allows(&scoped, Method::POST, "/pki/issue/web"));
+        assert!(key_allows(&scoped, Method::POST, "/pki/sign/web"));
+        assert!(!key_allows(&scoped, Method::POST, "/pki/issue/db"));
+        assert!(!key_allows(&scoped, Method::POST, "/pki/roles/web"));
+        assert!(!key_allows(&scoped, Method::DELETE, "/pki/roles/web"));
+        assert!(key_allows(
+            &["vault:pki:write"],
+            Method::POST,
+            "/pki/roles/web"
+        ));
+    }
+
+    #[test]
+    fn only_share_redeem_and_ca_publication_are_public() {
+        for path in [
+            "/share/abc",
+            "/api/v1/vault/share/abc",
+            "/pki/ca",
+            "/pki/crl/pem",
+            "/api/v1/vault/pki/ocsp",
+            "/pki/ocsp/MEMwQTA_",
+            "/api/v1/vault/ssh/public_key",
+        ] {
+            assert!(vault_public_path(path), "{path}");
+        }
+        for path in [
+            "/share/revoke",
+            "/api/v1/vault/share/revoke",
+            "/pki/issue/web",
+            "/pki/root/generate",
+            "/pki/certs",
+            "/pki/cafoo",
+            "/ssh/sign/ops",
+            "/ssh/certs",
+        ] {
+            assert!(!vault_public_path(path), "{path}");
+        }
+    }
+
+    #[test]
+    fn hashicorp_paths_map_to_kv() {
+        let uri = |s: &str| s.parse::<Uri>().unwrap();
+        assert_eq!(
+            hashicorp_need(&Method::GET, &uri("/v1/secrets/data/apps/db")),
+            vault("kv", Some("apps/db"))
+        );
+        let list = Method::from_bytes(b"LIST").unwrap();
+        assert_eq!(
+            hashicorp_need(&list, &uri("/v1/secrets/metadata/apps")),
+            vault("kv", Some("apps/"))
+        );
+        assert_eq!(
+            hashicorp_need(&Method::GET, &uri("/v1/secrets/metadata/apps?list=true")),
+            vault("kv", Some("apps/"))
+        );
+        assert_eq!(
+            hashicorp_need(&Method::GET, &uri("/v1/sys/health")),
+            vault("sys", None)
+        );
+        assert_eq!(
+            hashicorp_need(&Method::POST, &uri("/v1/secrets/destroy/apps/db")),
+            vault("kv", Some("apps/db"))
+        );
+    }
+
+    #[test]
+    fn hashicorp_transit_and_auth_paths_map_to_their_surfaces() {
+        let uri = |s: &str| s.parse::<Uri>().unwrap();
+        assert_eq!(
+            hashicorp_need(&Method::POST, &uri("/v1/transit/encrypt/payments")),
+            vault("transit", Some("payments"))
+        );
+        assert_eq!(
+            hashicorp_need(&Method::PUT, &uri("/v1/crypto/decrypt/payments")),
+            vault("transit", Some("payments"))
+        );
+        assert_eq!(
+            hashicorp_need(&Method::POST, &uri("/v1/auth/approle/role/ci/secret-id")),
+            vault("sys", None)
+        );
+    }
+
+    #[test]
+    fn workflow_namespaces_come_only_from_the_bound_query_name() {
+        let query = Some("namespace=prod&limit=5&ns=staging&namespace=dev");
+        assert_eq!(query_namespaces(query, "namespace"), vec!["prod", "dev"]);
+        assert_eq!(query_namespaces(query, "ns"), vec!["staging"]);
+        assert!(query_namespaces(None, "namespace").is_empty());
+    }
+
+    #[test]
+    fn workflow_routes_read_the_namespace_where_their_handler_does() {
+        use NamespaceSource::*;
+        let source = |method: &Method, path: &str| {
+            namespace_source(method, &format!("/api/v1/engine/workflow{path}"))
+        };
+        let cases = [
+            (Method::GET, "/workflows", Query("namespace")),
+            (Method::POST, "/workflows", Body),
+            (
+                Method::POST,
+                "/workflows/wf-1/signal/go",
+                Workflow("wf-1".into()),
+            ),
+            (
+                Method::POST,
+                "/workflows/wf%201/activities",
+                Workflow("wf 1".into()),
+            ),
+            (
+                Method::POST,
+                "/workflow-tasks/wf-1/commands",
+                Workflow("wf-1".into()),
+            ),
+            (Method::POST, "/workflow-tasks/poll", None),
+            (Method::POST, "/tasks/7/complete", Activity("7".into())),
+            (Method::POST, "/tasks/7/heartbeat", Activity("7".into())),
+            (Method::POST, "/tasks/poll", None),
+            (Method::GET, "/activities/7", Activity("7".into())),
+            (Method::GET, "/batch", Query("namespace")),
+            (Method::POST, "/batch", Body),
+            (Method::POST, "/batch/b-1/cancel", Batch("b-1".into())),
+            (Method::GET, "/schedules", Query("namespace")),
+            (Method::POST, "/schedules", Body),
+            (Method::PATCH, "/schedules/nightly", Query("namespace")),
+            (Method::POST, "/schedules/nightly/pause", Query("namespace")),
+            (Method::GET, "/namespaces/prod", Path("prod".into())),
+            (Method::GET, "/namespaces", None),
+            (Method::POST, "/workers/register", Body),
+            (Method::POST, "/workers/heartbeat", None),
+            (Method::GET, "/workers", Query("namespace")),
+            (Method::GET, "/queues", Query("namespace")),
+            (Method::GET, "/visibility/count", Query("namespace")),
+            (Method::GET, "/events/stream", Query("ns")),
+        ];
+        for (method, path, want) in cases {
+            assert_eq!(source(&method, path), want, "{method} {path}");
+        }
+    }
+}
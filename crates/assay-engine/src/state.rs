@@ -19,6 +19,8 @@ use assay_workflow::{WorkflowCtx, WorkflowStore};
 pub use assay_auth::state::AdminApiKeys;
 
 use crate::config::EngineConfig;
+use crate::embedded::EmbeddedPool;
+use crate::metrics::EngineMetrics;
 
 #[derive(Clone)]
 pub struct EngineState<S: WorkflowStore> {
@@ -63,6 +65,12 @@ pub struct EngineState<S: WorkflowStore> {
     /// shows the operator exactly what the running engine is using.
     /// `Arc` so cloning state per-request stays cheap.
     pub engine_config: Arc<EngineConfig>,
+    /// HTTP instruments bumped by `metrics::track_http` and rendered,
+    /// with every module's own, at `/metrics`.
+    pub metrics: Arc<EngineMetrics>,
+    /// The pool every module store shares — `/metrics` reports its
+    /// connection counts.
+    pub pool: EmbeddedPool,
 }
 
 impl<S: WorkflowStore> axum::extract::FromRef<EngineState<S>> for AuthCtx {
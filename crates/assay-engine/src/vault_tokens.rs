@@ -0,0 +1,138 @@
+//! The Vault-compat facade's [`TokenIssuer`]: a token an AppRole or
+//! Kubernetes login hands out is an assay API key.
+//!
+//! The key's scopes are the role's `token_policies`, its owner is
+//! `{method}:{role}` and its expiry is the token's TTL, so the module
+//! gates check it exactly like a key minted over `/admin/api-keys`, and
+//! operators see (and can revoke) every outstanding login there. The
+//! static admin keys resolve too, as Vault's `root` policy, so a client
+//! configured with one can still call `auth/token/lookup-self`.
+
+use std::sync::Arc;
+
+use assay_auth::api_keys::{self, ApiKeyStore, NewApiKey, TOKEN_PREFIX};
+use assay_auth::state::AdminApiKeys;
+use assay_vault::error::{Result, VaultError};
+use assay_vault::hashicorp_compat::{IssuedToken, TokenGrant, TokenInfo, TokenIssuer};
+
+pub(crate) struct ApiKeyTokenIssuer {
+    keys: Arc<dyn ApiKeyStore>,
+    admin: AdminApiKeys,
+    /// Reported as the admin keys' creation time: the engine's start.
+    started_at: f64,
+}
+
+impl ApiKeyTokenIssuer {
+    pub(crate) fn new(keys: Arc<dyn ApiKeyStore>, admin: AdminApiKeys, started_at: f64) -> Self {
+        Self {
+            keys,
+            admin,
+            started_at,
+        }
+    }
+
+    /// Drop keys earlier logins minted that have since expired. Nothing
+    /// else would: a vault-agent re-logging in every hour leaves one
+    /// dead row behind each time.
+    async fn sweep_expired(&self, now: f64) -> Result<()> {
+        self.keys
+            .delete_expired(LOGIN_OWNER_PREFIXES, now)
+            .await
+            .map_err(VaultError::Backend)?;
+        Ok(())
+    }
+}
+
+/// What a login's keys' owner starts with: `approle:{role}` /
+/// `kubernetes:{role}`.
+const LOGIN_OWNER_PREFIXES: &[&str] = &["approle:", "kubernetes:"];
+
+fn now_secs() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[async_trait::async_trait]
+impl TokenIssuer for ApiKeyTokenIssuer {
+    fn check_policies(&self, policies: &[String]) -> Result<()> {
+        api_keys::parse_scopes(policies)
+            .map(|_| ())
+            .map_err(|e| VaultError::Invalid(e.to_string()))
+    }
+
+    async fn issue(&self, grant: TokenGrant) -> Result<IssuedToken> {
+        let now = now_secs();
+        self.sweep_expired(now).await?;
+        let minted = api_keys::mint(
+            NewApiKey {
+                name: grant.display_name,
+                owner: grant.owner,
+                scopes: grant.policies,
+                expires_at: Some(grant.expires_at),
+            },
+            now,
+        )
+        .map_err(|e| VaultError::Invalid(e.to_string()))?;
+        self.keys
+            .insert(&minted.key, &minted.secret_hash)
+            .await
+            .map_err(VaultError::Backend)?;
+        let key = minted.key;
+        Ok(IssuedToken {
+            token: minted.token,
+            info: TokenInfo {
+                accessor: key.id,
+                display_name: key.name,
+                owner: key.owner,
+                policies: key.scopes,
+                created_at: key.created_at,
+                expires_at: key.expires_at,
+            },
+        })
+    }
+
+    async fn lookup(&self, token: &str) -> Result<Option<TokenInfo>> {
+        if self.admin.check(token) {
+            return Ok(Some(TokenInfo {
+                accessor: String::new(),
+                display_name: "root".into(),
+                owner: "admin".into(),
+                policies: vec!["root".into()],
+                created_at: self.started_at,
+                expires_at: None,
+            }));
+        }
+        if !token.starts_with(TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let key = api_keys::authenticate(self.keys.as_ref(), token, now_secs())
+            .await
+            .map_err(VaultError::Backend)?;
+        Ok(key.map(|key| TokenInfo {
+            accessor: key.id,
+            display_name: key.name,
+            owner: key.owner,
+            policies: key.scopes,
+            created_at: key.created_at,
+            expires_at: key.expires_at,
+        }))
+    }
+
+    async fn extend(&self, accessor: &str, expires_at: f64) -> Result<()> {
+        match self.keys.set_expires_at(accessor, Some(expires_at)).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(VaultError::NotFound),
+            Err(e) => Err(VaultError::Backend(e)),
+        }
+    }
+
+    async fn revoke(&self, accessor: &str) -> Result<()> {
+        self.keys
+            .delete(accessor)
+            .await
+            .map(|_| ())
+            .map_err(VaultError::Backend)
+    }
+}
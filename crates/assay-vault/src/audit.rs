@@ -7,6 +7,12 @@
 //! - [`WebhookSink`] — POSTs JSON to a configured URL. Reuses `reqwest`.
 //! - [`SinkRegistry`] — owns a Vec<Box<dyn Sink>>; `dispatch` fans an
 //!   event out to every sink whose filter matches.
+//! - [`Actor`] — the caller's identity, which the embedder's gate
+//!   attaches to each request for handlers to stamp onto events.
+//!
+//! The event types and registry are always compiled, so services can
+//! emit events in any build; the sink implementations sit behind
+//! `vault-audit-forwarding`.
 //!
 //! syslog and S3 sinks are reserved for the next phase-2 commit;
 //! syslog wants the `syslog` crate, S3 wants the `aws-sigv4` minimal
@@ -60,6 +66,13 @@ impl AuditEvent {
     }
 }
 
+/// Who is making a request, as the embedder's gate authenticated them
+/// (e.g. `admin:****abc123`, `api_key:<id>`, a JWT subject). The gate
+/// inserts it as a request extension; handlers that emit audit events
+/// read it with `Option<Extension<Actor>>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Actor(pub String);
+
 /// A forwarding sink. Implementations ship the event to wherever the
 /// operator wired them — webhook, syslog, S3, …
 #[async_trait]
@@ -12,7 +12,8 @@
 //!    previous active KEK: unwrap the wrapped_dek with the old KEK,
 //!    re-wrap with the new KEK, UPDATE in place. Skip destroyed rows
 //!    (their wrapped_dek is intentionally empty).
-//! 4. Same for `vault.transit_versions`.
+//! 4. Same for `vault.transit_versions` and the CA keys in
+//!    `vault.pki_ca` and `vault.ssh_ca`.
 //! 5. Mark the old kek_metadata row `rotated_at = now`.
 //! 6. Replace the in-memory KekHandle so subsequent ops use the new
 //!    one. Old KEK stays in memory until the next reboot — any new
@@ -43,6 +44,8 @@ pub struct RotationReport {
     pub new_kid: String,
     pub kv_rewrapped: u64,
     pub transit_rewrapped: u64,
+    pub pki_rewrapped: u64,
+    pub ssh_rewrapped: u64,
 }
 
 /// Rotate the KEK on a Postgres-backed vault.
@@ -73,6 +76,8 @@ pub async fn rotate_postgres(
 
     let kv_rewrapped = rewrap_kv_postgres(pool, &old_kek, &new_kek).await?;
     let transit_rewrapped = rewrap_transit_postgres(pool, &old_kek, &new_kek).await?;
+    let pki_rewrapped = rewrap_ca_postgres(pool, "pki_ca", &old_kek, &new_kek).await?;
+    let ssh_rewrapped = rewrap_ca_postgres(pool, "ssh_ca", &old_kek, &new_kek).await?;
 
     sqlx::query(
         "UPDATE vault.kek_metadata
@@ -92,6 +97,8 @@ pub async fn rotate_postgres(
         new_kid,
         kv_rewrapped,
         transit_rewrapped,
+        pki_rewrapped,
+        ssh_rewrapped,
     })
 }
 
@@ -177,6 +184,37 @@ async fn rewrap_transit_postgres(
     Ok(count)
 }
 
+/// Re-wrap the sealed key of a single-row CA table (`pki_ca`, `ssh_ca`).
+#[cfg(feature = "backend-postgres")]
+async fn rewrap_ca_postgres(
+    pool: &sqlx::PgPool,
+    table: &str,
+    old: &KekHandle,
+    new: &KekHandle,
+) -> Result<u64> {
+    let row: Option<Vec<u8>> = sqlx::query_scalar(&format!(
+        "SELECT key_wrapped FROM vault.{table} WHERE kek_kid = $1"
+    ))
+    .bind(old.kid())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| VaultError::Backend(anyhow::anyhow!("{table} rewrap select: {e}")))?;
+    let Some(key_wrapped) = row else {
+        return Ok(0);
+    };
+    let dek = old.unwrap_dek(&WrappedDek::from_bytes(key_wrapped))?;
+    let rewrapped = new.wrap_dek(&dek)?;
+    sqlx::query(&format!(
+        "UPDATE vault.{table} SET key_wrapped = $1, kek_kid = $2 WHERE id = 1"
+    ))
+    .bind(rewrapped.as_bytes())
+    .bind(new.kid())
+    .execute(pool)
+    .await
+    .map_err(|e| VaultError::Backend(anyhow::anyhow!("{table} rewrap update: {e}")))?;
+    Ok(1)
+}
+
 /// SQLite mirror.
 #[cfg(feature = "backend-sqlite")]
 pub async fn rotate_sqlite(
@@ -208,6 +246,8 @@ pub async fn rotate_sqlite(
 
     let kv_rewrapped = rewrap_kv_sqlite(pool, &old_kek, &new_kek).await?;
     let transit_rewrapped = rewrap_transit_sqlite(pool, &old_kek, &new_kek).await?;
+    let pki_rewrapped = rewrap_ca_sqlite(pool, "pki_ca", &old_kek, &new_kek).await?;
+    let ssh_rewrapped = rewrap_ca_sqlite(pool, "ssh_ca", &old_kek, &new_kek).await?;
 
     seal_state.set_unsealed(new_kid.clone(), new_kek);
 
@@ -216,6 +256,8 @@ pub async fn rotate_sqlite(
         new_kid,
         kv_rewrapped,
         transit_rewrapped,
+        pki_rewrapped,
+        ssh_rewrapped,
     })
 }
 
@@ -305,6 +347,36 @@ async fn rewrap_transit_sqlite(
     Ok(count)
 }
 
+#[cfg(feature = "backend-sqlite")]
+async fn rewrap_ca_sqlite(
+    pool: &sqlx::SqlitePool,
+    table: &str,
+    old: &KekHandle,
+    new: &KekHandle,
+) -> Result<u64> {
+    let row: Option<Vec<u8>> = sqlx::query_scalar(&format!(
+        "SELECT key_wrapped FROM vault.{table} WHERE kek_kid = ?"
+    ))
+    .bind(old.kid())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| VaultError::Backend(anyhow::anyhow!("{table} rewrap select: {e}")))?;
+    let Some(key_wrapped) = row else {
+        return Ok(0);
+    };
+    let dek = old.unwrap_dek(&WrappedDek::from_bytes(key_wrapped))?;
+    let rewrapped = new.wrap_dek(&dek)?;
+    sqlx::query(&format!(
+        "UPDATE vault.{table} SET key_wrapped = ?, kek_kid = ? WHERE id = 1"
+    ))
+    .bind(rewrapped.as_bytes())
+    .bind(new.kid())
+    .execute(pool)
+    .await
+    .map_err(|e| VaultError::Backend(anyhow::anyhow!("{table} rewrap update: {e}")))?;
+    Ok(1)
+}
+
 fn mint_kid(key: &[u8; KEY_LEN]) -> String {
     crate::crypto::kek::mint_kid(key)
 }
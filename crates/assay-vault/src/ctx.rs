@@ -5,7 +5,7 @@
 //! Phase 1 wires the master KEK + KV + transit services as type-erased
 //! `Arc<dyn …>` handles. Subsequent phases add:
 //!
-//! - Phase 2: `sealing: SealingState`, `audit_forwarders: Vec<…>`
+//! - Phase 2: `sealing: SealingState`, `audit: SinkRegistry`
 //! - Phase 3: `collections: Arc<dyn CollectionStore>`,
 //!   `personal_vaults: Arc<dyn PersonalVaultStore>`
 //! - Phase 4: `biscuit_root: BiscuitRoot`, `share_revocations: …`
@@ -13,12 +13,17 @@
 
 use std::sync::Arc;
 
+use crate::audit::SinkRegistry;
 use crate::crypto::KekHandle;
 use crate::crypto::seal_state::SealState;
 use crate::crypto::sealing::SealingMethod;
 
 #[cfg(feature = "vault-kv")]
 use crate::kv::{KvService, KvStore};
+#[cfg(feature = "vault-pki")]
+use crate::pki::{PkiService, PkiStore};
+#[cfg(feature = "vault-ssh")]
+use crate::ssh::{SshService, SshStore};
 #[cfg(feature = "vault-transit")]
 use crate::transit::{TransitService, TransitStore};
 
@@ -46,10 +51,19 @@ pub struct VaultCtx {
     /// startup. None when no sealing backend is wired (tests + slim
     /// builds without sharks).
     pub seal_store: Option<DynSealStore>,
+    /// Audit sinks services forward events to. Empty unless the
+    /// embedder wires some with [`Self::with_audit`].
+    pub audit: SinkRegistry,
     #[cfg(feature = "vault-kv")]
     pub kv: Option<KvService<DynKvStore>>,
     #[cfg(feature = "vault-transit")]
     pub transit: Option<TransitService<DynTransitStore>>,
+    /// Internal certificate authority — roles, issuance, CRL + OCSP.
+    #[cfg(feature = "vault-pki")]
+    pub pki: Option<PkiService<DynPkiStore>>,
+    /// SSH CA — signs user and host certificates under roles.
+    #[cfg(feature = "vault-ssh")]
+    pub ssh: Option<SshService<DynSshStore>>,
     /// Personal-vault store (Phase 3). Holds 1-per-user X25519 pubkey
     /// rows; the assay-auth user-create hook calls ensure_vault.
     #[cfg(feature = "vault-collections")]
@@ -75,6 +89,13 @@ pub struct VaultCtx {
         feature = "vault-dynamic-kubernetes",
     ))]
     pub dynamic: Option<crate::dynamic::DynamicCredsService>,
+    /// AppRole / Kubernetes roles for the Vault-compat facade's logins.
+    #[cfg(feature = "vault-hashicorp-compat")]
+    pub compat_auth: Option<DynCompatAuthStore>,
+    /// Mints the tokens those logins hand out. Embedder-supplied: the
+    /// engine issues assay API keys.
+    #[cfg(feature = "vault-hashicorp-compat")]
+    pub token_issuer: Option<Arc<dyn crate::hashicorp_compat::TokenIssuer>>,
 }
 
 impl Default for VaultCtx {
@@ -86,10 +107,15 @@ impl Default for VaultCtx {
             kek,
             seal_state,
             seal_store: None,
+            audit: SinkRegistry::new(),
             #[cfg(feature = "vault-kv")]
             kv: None,
             #[cfg(feature = "vault-transit")]
             transit: None,
+            #[cfg(feature = "vault-pki")]
+            pki: None,
+            #[cfg(feature = "vault-ssh")]
+            ssh: None,
             #[cfg(feature = "vault-collections")]
             personal_vaults: None,
             #[cfg(feature = "vault-collections")]
@@ -107,6 +133,10 @@ impl Default for VaultCtx {
                 feature = "vault-dynamic-kubernetes",
             ))]
             dynamic: None,
+            #[cfg(feature = "vault-hashicorp-compat")]
+            compat_auth: None,
+            #[cfg(feature = "vault-hashicorp-compat")]
+            token_issuer: None,
         }
     }
 }
@@ -161,6 +191,32 @@ impl VaultCtx {
         self
     }
 
+    #[cfg(feature = "vault-pki")]
+    pub fn with_pki<S: PkiStore + 'static>(mut self, store: S) -> Self {
+        let store: DynPkiStore = Arc::new(store);
+        self.pki = Some(PkiService::new(store, self.seal_state.clone()));
+        self
+    }
+
+    #[cfg(feature = "vault-ssh")]
+    pub fn with_ssh<S: SshStore + 'static>(mut self, store: S) -> Self {
+        let store: DynSshStore = Arc::new(store);
+        self.ssh =
+            Some(SshService::new(store, self.seal_state.clone()).with_audit(self.audit.clone()));
+        self
+    }
+
+    /// Forward audit events to `registry`. Services wired before or
+    /// after this call both pick it up.
+    pub fn with_audit(mut self, registry: SinkRegistry) -> Self {
+        #[cfg(feature = "vault-ssh")]
+        {
+            self.ssh = self.ssh.map(|ssh| ssh.with_audit(registry.clone()));
+        }
+        self.audit = registry;
+        self
+    }
+
     /// Wire a backend-pluggable [`crate::crypto::sealing::SealStore`].
     /// Engine boot calls this with PgSealStore / SqliteSealStore.
     pub fn with_seal_store<S: crate::crypto::sealing::SealStore + 'static>(
@@ -217,6 +273,24 @@ impl VaultCtx {
         self.dynamic = Some(service);
         self
     }
+
+    #[cfg(feature = "vault-hashicorp-compat")]
+    pub fn with_compat_auth<S: crate::hashicorp_compat::CompatAuthStore + 'static>(
+        mut self,
+        store: S,
+    ) -> Self {
+        self.compat_auth = Some(Arc::new(store));
+        self
+    }
+
+    #[cfg(feature = "vault-hashicorp-compat")]
+    pub fn with_token_issuer<I: crate::hashicorp_compat::TokenIssuer + 'static>(
+        mut self,
+        issuer: I,
+    ) -> Self {
+        self.token_issuer = Some(Arc::new(issuer));
+        self
+    }
 }
 
 /// Trait-object alias for the seal store — same shape as DynKvStore /
@@ -231,6 +305,8 @@ pub type DynCollectionStore = Arc<dyn crate::collections::CollectionStore>;
 pub type DynItemStore = Arc<dyn crate::items::ItemStore>;
 #[cfg(feature = "vault-collections")]
 pub type DynFolderStore = Arc<dyn crate::items::FolderStore>;
+#[cfg(feature = "vault-hashicorp-compat")]
+pub type DynCompatAuthStore = Arc<dyn crate::hashicorp_compat::CompatAuthStore>;
 
 /// Type alias for the `KvService` carried in [`VaultCtx`]. The
 /// trait-object indirection lets the engine choose PG or SQLite at
@@ -242,12 +318,19 @@ pub type DynKvStore = Arc<dyn KvStore>;
 #[cfg(feature = "vault-transit")]
 pub type DynTransitStore = Arc<dyn TransitStore>;
 
+/// Trait-object alias for PKI — same rationale.
+#[cfg(feature = "vault-pki")]
+pub type DynPkiStore = Arc<dyn PkiStore>;
+#[cfg(feature = "vault-ssh")]
+pub type DynSshStore = Arc<dyn SshStore>;
+
 // Forward `KvStore` / `TransitStore` through the `Arc<dyn …>` so the
 // blanket service can use them. async_trait emits these for free for
 // `Arc<dyn Trait>` because the trait is `dyn`-compatible.
 #[cfg(feature = "vault-kv")]
 #[async_trait::async_trait]
 impl KvStore for DynKvStore {
+    #[allow(clippy::too_many_arguments)]
     async fn put_row(
         &self,
         path: &str,
@@ -256,9 +339,18 @@ impl KvStore for DynKvStore {
         wrapped_dek: &[u8],
         kek_kid: &str,
         custom_md: &serde_json::Value,
+        cas: Option<i64>,
     ) -> crate::error::Result<i64> {
         (**self)
-            .put_row(path, ciphertext, nonce, wrapped_dek, kek_kid, custom_md)
+            .put_row(
+                path,
+                ciphertext,
+                nonce,
+                wrapped_dek,
+                kek_kid,
+                custom_md,
+                cas,
+            )
             .await
     }
     async fn get_row(
@@ -277,6 +369,16 @@ impl KvStore for DynKvStore {
     async fn read_meta(&self, path: &str) -> crate::error::Result<Option<crate::kv::KvMeta>> {
         (**self).read_meta(path).await
     }
+    async fn put_meta(
+        &self,
+        path: &str,
+        update: &crate::kv::KvMetaUpdate,
+    ) -> crate::error::Result<crate::kv::KvMeta> {
+        (**self).put_meta(path, update).await
+    }
+    async fn list_versions(&self, path: &str) -> crate::error::Result<Vec<crate::kv::KvVersion>> {
+        (**self).list_versions(path).await
+    }
     async fn soft_delete(
         &self,
         path: &str,
@@ -291,6 +393,9 @@ impl KvStore for DynKvStore {
     async fn undelete(&self, path: &str, version: i64) -> crate::error::Result<bool> {
         (**self).undelete(path, version).await
     }
+    async fn delete_path(&self, path: &str) -> crate::error::Result<bool> {
+        (**self).delete_path(path).await
+    }
 }
 
 #[cfg(feature = "vault-transit")]
@@ -302,9 +407,18 @@ impl TransitStore for DynTransitStore {
         algo: &str,
         version_wrapped: &[u8],
         kek_kid: &str,
+        private_key: Option<&[u8]>,
+        public_key: Option<&str>,
     ) -> crate::error::Result<()> {
         (**self)
-            .create_key(name, algo, version_wrapped, kek_kid)
+            .create_key(
+                name,
+                algo,
+                version_wrapped,
+                kek_kid,
+                private_key,
+                public_key,
+            )
             .await
     }
     async fn get_key(
@@ -331,10 +445,117 @@ impl TransitStore for DynTransitStore {
         name: &str,
         version_wrapped: &[u8],
         kek_kid: &str,
+        private_key: Option<&[u8]>,
+        public_key: Option<&str>,
     ) -> crate::error::Result<i64> {
-        (**self).rotate(name, version_wrapped, kek_kid).await
+        (**self)
+            .rotate(name, version_wrapped, kek_kid, private_key, public_key)
+            .await
+    }
+    async fn list_versions(
+        &self,
+        name: &str,
+    ) -> crate::error::Result<Vec<crate::transit::TransitVersion>> {
+        (**self).list_versions(name).await
+    }
+    async fn set_min_decryption_version(
+        &self,
+        name: &str,
+        version: i64,
+    ) -> crate::error::Result<()> {
+        (**self).set_min_decryption_version(name, version).await
     }
     async fn list_keys(&self) -> crate::error::Result<Vec<crate::transit::TransitKey>> {
         (**self).list_keys().await
     }
 }
+
+#[cfg(feature = "vault-pki")]
+#[async_trait::async_trait]
+impl PkiStore for DynPkiStore {
+    async fn get_ca(&self) -> crate::error::Result<Option<crate::pki::PkiCa>> {
+        (**self).get_ca().await
+    }
+    async fn create_ca(&self, ca: &crate::pki::PkiCa) -> crate::error::Result<()> {
+        (**self).create_ca(ca).await
+    }
+    async fn set_ca_certificate(
+        &self,
+        certificate: &str,
+        ca_chain: &str,
+    ) -> crate::error::Result<()> {
+        (**self).set_ca_certificate(certificate, ca_chain).await
+    }
+    async fn delete_ca(&self) -> crate::error::Result<bool> {
+        (**self).delete_ca().await
+    }
+    async fn set_urls(&self, urls: &crate::pki::PkiUrls) -> crate::error::Result<()> {
+        (**self).set_urls(urls).await
+    }
+    async fn put_role(&self, role: &crate::pki::PkiRole) -> crate::error::Result<()> {
+        (**self).put_role(role).await
+    }
+    async fn get_role(&self, name: &str) -> crate::error::Result<Option<crate::pki::PkiRole>> {
+        (**self).get_role(name).await
+    }
+    async fn list_roles(&self) -> crate::error::Result<Vec<crate::pki::PkiRole>> {
+        (**self).list_roles().await
+    }
+    async fn delete_role(&self, name: &str) -> crate::error::Result<bool> {
+        (**self).delete_role(name).await
+    }
+    async fn insert_cert(&self, cert: &crate::pki::PkiCert) -> crate::error::Result<()> {
+        (**self).insert_cert(cert).await
+    }
+    async fn get_cert(
+        &self,
+        serial_number: &str,
+    ) -> crate::error::Result<Option<crate::pki::PkiCert>> {
+        (**self).get_cert(serial_number).await
+    }
+    async fn list_certs(&self) -> crate::error::Result<Vec<crate::pki::PkiCert>> {
+        (**self).list_certs().await
+    }
+    async fn list_revoked(&self) -> crate::error::Result<Vec<crate::pki::PkiCert>> {
+        (**self).list_revoked().await
+    }
+    async fn revoke_cert(
+        &self,
+        serial_number: &str,
+        revoked_at: f64,
+    ) -> crate::error::Result<bool> {
+        (**self).revoke_cert(serial_number, revoked_at).await
+    }
+}
+
+#[cfg(feature = "vault-ssh")]
+#[async_trait::async_trait]
+impl SshStore for DynSshStore {
+    async fn get_ca(&self) -> crate::error::Result<Option<crate::ssh::SshCa>> {
+        (**self).get_ca().await
+    }
+    async fn create_ca(&self, ca: &crate::ssh::SshCa) -> crate::error::Result<()> {
+        (**self).create_ca(ca).await
+    }
+    async fn delete_ca(&self) -> crate::error::Result<bool> {
+        (**self).delete_ca().await
+    }
+    async fn put_role(&self, role: &crate::ssh::SshRole) -> crate::error::Result<()> {
+        (**self).put_role(role).await
+    }
+    async fn get_role(&self, name: &str) -> crate::error::Result<Option<crate::ssh::SshRole>> {
+        (**self).get_role(name).await
+    }
+    async fn list_roles(&self) -> crate::error::Result<Vec<crate::ssh::SshRole>> {
+        (**self).list_roles().await
+    }
+    async fn delete_role(&self, name: &str) -> crate::error::Result<bool> {
+        (**self).delete_role(name).await
+    }
+    async fn insert_cert(&self, cert: &crate::ssh::SshCert) -> crate::error::Result<()> {
+        (**self).insert_cert(cert).await
+    }
+    async fn list_certs(&self) -> crate::error::Result<Vec<crate::ssh::SshCert>> {
+        (**self).list_certs().await
+    }
+}
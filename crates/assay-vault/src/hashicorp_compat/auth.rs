@@ -0,0 +1,529 @@
+//! Auth methods — AppRole and Kubernetes logins, and the token self-service
+//! routes Vault agents and providers call once they hold a token.
+//!
+//! ```text
+//! POST   /v1/auth/approle/login                   role_id + secret_id → token
+//! POST   /v1/auth/kubernetes/login                role + service-account JWT → token
+//! GET    /v1/auth/token/lookup-self               describe the presented token
+//! POST   /v1/auth/token/renew-self                extend it, up to the role's max TTL
+//! POST   /v1/auth/token/revoke-self               revoke it
+//!
+//! POST   /v1/auth/approle/role/{name}             create / update a role     ┐
+//! GET    /v1/auth/approle/role/{name}             read it                    │
+//! DELETE /v1/auth/approle/role/{name}             delete it + its secret IDs │
+//! LIST   /v1/auth/approle/role                    role names                 │ behind
+//! GET    /v1/auth/approle/role/{name}/role-id     its role_id (POST sets it) │ the gate
+//! POST   /v1/auth/approle/role/{name}/secret-id   mint a secret_id           │
+//! POST   /v1/auth/kubernetes/config               how logins are verified    │
+//! …      /v1/auth/kubernetes/role[/{name}]        as for AppRole roles       ┘
+//! ```
+//!
+//! The facade keeps no token store. A login proves who the caller is, then
+//! asks the embedder's [`TokenIssuer`] for a token that carries the role's
+//! `token_policies` and expires after its `token_ttl`. In the engine that
+//! token is an assay API key whose scopes are those policies
+//! (`vault:kv:read:/apps/*`), so a Vault token is still an assay token and
+//! the gate in front of the KV and transit routes is still the one place it
+//! is checked. Logins sit outside the gate because the caller has no token
+//! yet; the `token/*-self` routes do too, and resolve the token they are
+//! handed through the issuer instead.
+//!
+//! TTLs follow Vault: `0` means the 768h system default, and renewal never
+//! takes a token past `token_max_ttl` from when it was issued.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{FromRef, Path, Query, State};
+use axum::http::{Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, get};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::ctx::{DynCompatAuthStore, VaultCtx};
+use crate::error::{Result, VaultError};
+use crate::hashicorp_compat::{Params, envelope, errors, not_found, vault_error};
+
+mod approle;
+mod kubernetes;
+mod token;
+
+/// Vault's default (and default maximum) token lifetime, `768h`.
+pub const DEFAULT_TOKEN_TTL: i64 = 768 * 3600;
+
+/// Role and method-config storage for the facade's auth methods.
+#[async_trait]
+pub trait CompatAuthStore: Send + Sync {
+    /// Insert or replace the role `(role.method, role.name)`.
+    async fn put_role(&self, role: &AuthRole) -> Result<()>;
+    async fn get_role(&self, method: &str, name: &str) -> Result<Option<AuthRole>>;
+    /// The AppRole role whose `role_id` is `role_id`.
+    async fn find_role_id(&self, role_id: &str) -> Result<Option<AuthRole>>;
+    /// Role names under `method`, sorted.
+    async fn list_roles(&self, method: &str) -> Result<Vec<String>>;
+    /// Deletes the role and every secret ID minted for it.
+    async fn delete_role(&self, method: &str, name: &str) -> Result<bool>;
+    async fn put_config(&self, method: &str, config: &Value) -> Result<()>;
+    async fn get_config(&self, method: &str) -> Result<Option<Value>>;
+    async fn insert_secret_id(&self, secret: &SecretId) -> Result<()>;
+    /// Spend one use of the secret ID hashing to `secret_hash` minted for
+    /// `role`, deleting it when that was its last. `None` when it is unknown
+    /// or expired.
+    async fn use_secret_id(
+        &self,
+        role: &str,
+        secret_hash: &str,
+        now: f64,
+    ) -> Result<Option<SecretId>>;
+}
+
+/// A role as stored. `config` is the method's own role document.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct AuthRole {
+    /// `approle` or `kubernetes`.
+    pub method: String,
+    pub name: String,
+    /// AppRole only: the public half of the login credential.
+    pub role_id: Option<String>,
+    pub config: Value,
+}
+
+/// An AppRole secret ID. Only its SHA-256 is stored.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct SecretId {
+    pub accessor: String,
+    pub role: String,
+    pub secret_hash: String,
+    pub metadata: Value,
+    /// Logins left; `None` is unlimited.
+    pub uses_left: Option<i64>,
+    pub created_at: f64,
+    pub expires_at: Option<f64>,
+}
+
+/// Mints and resolves the tokens logins hand out. The engine implements it
+/// over assay API keys; see the module docs.
+#[async_trait]
+pub trait TokenIssuer: Send + Sync {
+    /// Refuse policies a token could not carry. Role writes call it, so a
+    /// typo fails when the role is saved rather than at the first login.
+    fn check_policies(&self, policies: &[String]) -> Result<()>;
+    async fn issue(&self, grant: TokenGrant) -> Result<IssuedToken>;
+    /// The live token `token`; `None` for one that is unknown or expired.
+    async fn lookup(&self, token: &str) -> Result<Option<TokenInfo>>;
+    /// Move the expiry of the token `accessor` names.
+    async fn extend(&self, accessor: &str, expires_at: f64) -> Result<()>;
+    async fn revoke(&self, accessor: &str) -> Result<()>;
+}
+
+/// What a login asks the issuer for.
+#[derive(Clone, Debug)]
+pub struct TokenGrant {
+    /// Vault's `display_name`: `approle`, `kubernetes-{namespace}-{name}`.
+    pub display_name: String,
+    /// `{method}:{role}` — how `renew-self` finds the role again.
+    pub owner: String,
+    pub policies: Vec<String>,
+    pub expires_at: f64,
+}
+
+/// A token as the issuer knows it.
+#[derive(Clone, Debug)]
+pub struct TokenInfo {
+    /// Names the token without being it; what `extend` and `revoke` take.
+    pub accessor: String,
+    pub display_name: String,
+    pub owner: String,
+    pub policies: Vec<String>,
+    pub created_at: f64,
+    /// `None` for a token that never expires.
+    pub expires_at: Option<f64>,
+}
+
+/// A freshly minted token and the one copy of its secret.
+#[derive(Clone, Debug)]
+pub struct IssuedToken {
+    pub token: String,
+    pub info: TokenInfo,
+}
+
+/// Logins and the token self-service routes — outside the gate.
+pub(super) fn public_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    approle::login_router::<S>()
+        .merge(kubernetes::login_router::<S>())
+        .merge(token::router::<S>())
+}
+
+/// Role and method-config administration — behind the gate.
+pub(super) fn admin_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    role_routes::<S, approle::AppRole>()
+        .merge(approle::admin_router::<S>())
+        .merge(role_routes::<S, kubernetes::KubernetesRole>())
+        .merge(kubernetes::admin_router::<S>())
+}
+
+/// The token half of a role: what its logins' tokens may do and how long
+/// they live.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, serde::Deserialize)]
+#[serde(default)]
+pub(super) struct TokenParams {
+    pub token_policies: Vec<String>,
+    pub token_ttl: i64,
+    pub token_max_ttl: i64,
+}
+
+impl TokenParams {
+    /// Vault still accepts the pre-`token_` spellings, and older
+    /// Terraform configurations send them.
+    fn apply(&mut self, params: &Params) -> Result<()> {
+        if let Some(policies) = params.strings_any(&["token_policies", "policies"])? {
+            self.token_policies = policies;
+        }
+        if let Some(ttl) = params.duration_any(&["token_ttl", "ttl"])? {
+            self.token_ttl = ttl;
+        }
+        if let Some(max) = params.duration_any(&["token_max_ttl", "max_ttl"])? {
+            self.token_max_ttl = max;
+        }
+        if self.token_max_ttl > 0 && self.token_ttl > self.token_max_ttl {
+            return Err(VaultError::Invalid(
+                "token_ttl cannot be greater than token_max_ttl".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn max_ttl(&self) -> i64 {
+        if self.token_max_ttl > 0 {
+            self.token_max_ttl
+        } else {
+            DEFAULT_TOKEN_TTL
+        }
+    }
+
+    pub fn ttl(&self) -> i64 {
+        let ttl = if self.token_ttl > 0 {
+            self.token_ttl
+        } else {
+            DEFAULT_TOKEN_TTL
+        };
+        ttl.min(self.max_ttl())
+    }
+
+    fn describe(&self, out: &mut serde_json::Map<String, Value>) {
+        out.insert("token_policies".into(), json!(self.token_policies));
+        out.insert("policies".into(), json!(self.token_policies));
+        out.insert("token_ttl".into(), json!(self.token_ttl));
+        out.insert("token_max_ttl".into(), json!(self.token_max_ttl));
+        out.insert("token_type".into(), json!("service"));
+    }
+}
+
+/// A method's role document.
+pub(super) trait RoleConfig: Default + Serialize + DeserializeOwned + Send {
+    const METHOD: &'static str;
+    /// Apply a role write's fields on top of `self`.
+    fn apply(&mut self, params: &Params) -> Result<()>;
+    /// The role as `GET …/role/{name}` reports it.
+    fn describe(&self) -> serde_json::Map<String, Value>;
+    fn token(&self) -> &TokenParams;
+    /// AppRole gives every role a `role_id`; Kubernetes roles have none.
+    fn mints_role_id() -> bool {
+        false
+    }
+}
+
+fn role_routes<S, R>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+    R: RoleConfig + 'static,
+{
+    let base = format!("/v1/auth/{}/role", R::METHOD);
+    Router::new().route(&base, any(list_roles::<S, R>)).route(
+        &format!("{base}/{{name}}"),
+        get(read_role::<S, R>)
+            .post(write_role::<S, R>)
+            .put(write_role::<S, R>)
+            .delete(delete_role::<S, R>),
+    )
+}
+
+#[derive(serde::Deserialize)]
+struct ListQuery {
+    list: Option<String>,
+}
+
+async fn list_roles<S, R>(
+    method: Method,
+    State(vault): State<VaultCtx>,
+    Query(q): Query<ListQuery>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+    R: RoleConfig,
+{
+    let wants_list = method.as_str() == "LIST"
+        || (method == Method::GET && q.list.as_deref().is_some_and(|v| v == "true"));
+    if !wants_list {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    match store.list_roles(R::METHOD).await {
+        Ok(names) if names.is_empty() => not_found(),
+        Ok(names) => axum::Json(envelope(json!({ "keys": names }))).into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn read_role<S, R>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+    R: RoleConfig,
+{
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    match load_role::<R>(store, &name).await {
+        Ok(Some((_, role))) => axum::Json(envelope(Value::Object(role.describe()))).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn write_role<S, R>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+    R: RoleConfig,
+{
+    let (store, issuer) = match store_and_issuer(&vault) {
+        Ok(pair) => pair,
+        Err(response) => return *response,
+    };
+    let result = async {
+        let params = Params::from_body(&body)?;
+        let existing = load_role::<R>(store, &name).await?;
+        let (stored, mut role) = match existing {
+            Some((stored, role)) => (Some(stored), role),
+            None => (None, R::default()),
+        };
+        role.apply(&params)?;
+        if role.token().token_policies.is_empty() {
+            return Err(VaultError::Invalid(
+                "token_policies is required: they are the scopes the role's tokens carry".into(),
+            ));
+        }
+        issuer.check_policies(&role.token().token_policies)?;
+        let role_id = if R::mints_role_id() {
+            Some(match params.string("role_id")? {
+                Some(id) if !id.trim().is_empty() => id.trim().to_string(),
+                _ => stored
+                    .and_then(|r| r.role_id)
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            })
+        } else {
+            None
+        };
+        let config = serde_json::to_value(&role)
+            .map_err(|e| VaultError::Backend(anyhow::anyhow!("encode role: {e}")))?;
+        store
+            .put_role(&AuthRole {
+                method: R::METHOD.to_string(),
+                name: name.clone(),
+                role_id,
+                config,
+            })
+            .await
+    }
+    .await;
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn delete_role<S, R>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+    R: RoleConfig,
+{
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    // Vault answers 204 whether or not the role existed.
+    match store.delete_role(R::METHOD, &name).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn load_role<R: RoleConfig>(
+    store: &DynCompatAuthStore,
+    name: &str,
+) -> Result<Option<(AuthRole, R)>> {
+    let Some(stored) = store.get_role(R::METHOD, name).await? else {
+        return Ok(None);
+    };
+    let role = decode_role(&stored)?;
+    Ok(Some((stored, role)))
+}
+
+fn decode_role<R: DeserializeOwned>(stored: &AuthRole) -> Result<R> {
+    serde_json::from_value(stored.config.clone()).map_err(|e| {
+        VaultError::Backend(anyhow::anyhow!(
+            "decode {} role {}: {e}",
+            stored.method,
+            stored.name
+        ))
+    })
+}
+
+/// Ask the issuer for a token on behalf of `role` and answer in Vault's
+/// login shape.
+async fn issue(
+    issuer: &Arc<dyn TokenIssuer>,
+    method: &str,
+    role: &str,
+    params: &TokenParams,
+    display_name: String,
+    metadata: Value,
+) -> Response {
+    let ttl = params.ttl();
+    let grant = TokenGrant {
+        display_name,
+        owner: format!("{method}:{role}"),
+        policies: params.token_policies.clone(),
+        expires_at: now_secs() + ttl as f64,
+    };
+    match issuer.issue(grant).await {
+        Ok(issued) => {
+            axum::Json(login_envelope(&issued.token, &issued.info, ttl, metadata)).into_response()
+        }
+        Err(e) => vault_error(e),
+    }
+}
+
+/// The `auth` block Vault returns from a login or a renewal; `data` is null.
+fn login_envelope(token: &str, info: &TokenInfo, lease: i64, metadata: Value) -> Value {
+    let mut body = envelope(Value::Null);
+    body["auth"] = json!({
+        "client_token": token,
+        "accessor": info.accessor,
+        "policies": info.policies,
+        "token_policies": info.policies,
+        "metadata": metadata,
+        "lease_duration": lease,
+        "renewable": token_source(&info.owner).is_some(),
+        "entity_id": "",
+        "token_type": "service",
+        "orphan": true,
+        "mfa_requirement": null,
+        "num_uses": 0,
+    });
+    body
+}
+
+/// `(method, role)` for a token a login here issued; `None` for any other
+/// token the issuer knows (the admin key, an API key made by hand).
+fn token_source(owner: &str) -> Option<(&str, &str)> {
+    let (method, role) = owner.split_once(':')?;
+    matches!(method, "approle" | "kubernetes")
+        .then_some((method, role))
+        .filter(|(_, role)| !role.is_empty())
+}
+
+/// The `metadata` Vault attaches to a token from each method.
+fn token_metadata(method: &str, role: &str) -> Value {
+    match method {
+        "approle" => json!({ "role_name": role }),
+        _ => json!({ "role": role }),
+    }
+}
+
+fn store(vault: &VaultCtx) -> std::result::Result<&DynCompatAuthStore, Box<Response>> {
+    vault.compat_auth.as_ref().ok_or_else(|| {
+        Box::new(errors(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &["vault auth methods not configured"],
+        ))
+    })
+}
+
+fn issuer(vault: &VaultCtx) -> std::result::Result<&Arc<dyn TokenIssuer>, Box<Response>> {
+    vault.token_issuer.as_ref().ok_or_else(|| {
+        Box::new(errors(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &["token issuance not configured"],
+        ))
+    })
+}
+
+type StoreAndIssuer<'a> = (&'a DynCompatAuthStore, &'a Arc<dyn TokenIssuer>);
+
+fn store_and_issuer(vault: &VaultCtx) -> std::result::Result<StoreAndIssuer<'_>, Box<Response>> {
+    Ok((store(vault)?, issuer(vault)?))
+}
+
+fn now_secs() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_ttls_fall_back_to_the_vault_default() {
+        let params = TokenParams::default();
+        assert_eq!(params.ttl(), DEFAULT_TOKEN_TTL);
+        assert_eq!(params.max_ttl(), DEFAULT_TOKEN_TTL);
+    }
+
+    #[test]
+    fn the_ttl_never_exceeds_the_max_ttl() {
+        let params = TokenParams {
+            token_max_ttl: 600,
+            ..TokenParams::default()
+        };
+        assert_eq!(params.ttl(), 600);
+    }
+
+    #[test]
+    fn only_tokens_a_login_issued_have_a_source() {
+        assert_eq!(token_source("approle:ci"), Some(("approle", "ci")));
+        assert_eq!(token_source("kubernetes:web"), Some(("kubernetes", "web")));
+        assert_eq!(token_source("approle:"), None);
+        assert_eq!(token_source("platform-team"), None);
+        assert_eq!(token_source("pipeline:deploy"), None);
+    }
+}
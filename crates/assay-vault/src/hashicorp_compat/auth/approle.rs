@@ -0,0 +1,290 @@
+//! AppRole: a `role_id` names the role and a `secret_id` minted for it
+//! proves the caller may log in as it. Secret IDs are shown once, stored as
+//! their SHA-256, and spent one use per login.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{FromRef, Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use super::{
+    AuthRole, RoleConfig, SecretId, TokenParams, issue, load_role, now_secs, store,
+    store_and_issuer, token_metadata,
+};
+use crate::ctx::VaultCtx;
+use crate::error::{Result, VaultError};
+use crate::hashicorp_compat::{Params, envelope, errors, not_found, vault_error};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(in crate::hashicorp_compat) struct AppRole {
+    /// Seconds a minted secret ID lives; `0` never expires.
+    secret_id_ttl: i64,
+    /// Logins a minted secret ID allows; `0` is unlimited.
+    secret_id_num_uses: i64,
+    #[serde(flatten)]
+    token: TokenParams,
+}
+
+impl RoleConfig for AppRole {
+    const METHOD: &'static str = "approle";
+
+    fn apply(&mut self, params: &Params) -> Result<()> {
+        if params.bool("bind_secret_id")? == Some(false) {
+            return Err(VaultError::Invalid(
+                "bind_secret_id cannot be disabled: a secret ID is the only login constraint \
+                 assay supports"
+                    .into(),
+            ));
+        }
+        if let Some(ttl) = params.duration("secret_id_ttl")? {
+            self.secret_id_ttl = ttl;
+        }
+        if let Some(uses) = params.int("secret_id_num_uses")? {
+            if uses < 0 {
+                return Err(VaultError::Invalid(
+                    "secret_id_num_uses cannot be negative".into(),
+                ));
+            }
+            self.secret_id_num_uses = uses;
+        }
+        self.token.apply(params)
+    }
+
+    fn describe(&self) -> serde_json::Map<String, Value> {
+        let mut out = serde_json::Map::new();
+        out.insert("bind_secret_id".into(), json!(true));
+        out.insert("secret_id_ttl".into(), json!(self.secret_id_ttl));
+        out.insert("secret_id_num_uses".into(), json!(self.secret_id_num_uses));
+        self.token.describe(&mut out);
+        out
+    }
+
+    fn token(&self) -> &TokenParams {
+        &self.token
+    }
+
+    fn mints_role_id() -> bool {
+        true
+    }
+}
+
+pub(super) fn login_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new().route("/v1/auth/approle/login", post(login::<S>).put(login::<S>))
+}
+
+pub(super) fn admin_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new()
+        .route(
+            "/v1/auth/approle/role/{name}/role-id",
+            get(read_role_id::<S>)
+                .post(write_role_id::<S>)
+                .put(write_role_id::<S>),
+        )
+        .route(
+            "/v1/auth/approle/role/{name}/secret-id",
+            post(mint_secret_id::<S>).put(mint_secret_id::<S>),
+        )
+}
+
+async fn read_role_id<S>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    match store.get_role(AppRole::METHOD, &name).await {
+        Ok(Some(role)) => axum::Json(envelope(json!({ "role_id": role.role_id }))).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn write_role_id<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    let result = async {
+        let role_id = Params::from_body(&body)?
+            .string("role_id")?
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| VaultError::Invalid("missing role_id".into()))?;
+        let mut role = store
+            .get_role(AppRole::METHOD, &name)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        role.role_id = Some(role_id);
+        store.put_role(&role).await
+    }
+    .await;
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn mint_secret_id<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    let result = async {
+        let params = Params::from_body(&body)?;
+        let (_, role) = load_role::<AppRole>(store, &name)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        // A request may tighten the role's limits, never loosen them.
+        let ttl = tighter(role.secret_id_ttl, params.duration("ttl")?);
+        let uses = tighter(role.secret_id_num_uses, params.int("num_uses")?);
+        let metadata = match params.value("metadata") {
+            None => json!({}),
+            Some(Value::Object(map)) => Value::Object(map.clone()),
+            // The CLI sends metadata as a JSON-encoded string.
+            Some(Value::String(text)) => serde_json::from_str::<Value>(text)
+                .ok()
+                .filter(Value::is_object)
+                .ok_or_else(|| VaultError::Invalid("metadata must be a JSON object".into()))?,
+            Some(_) => return Err(VaultError::Invalid("metadata must be a JSON object".into())),
+        };
+        let secret = uuid::Uuid::new_v4().to_string();
+        let now = now_secs();
+        let record = SecretId {
+            accessor: uuid::Uuid::new_v4().to_string(),
+            role: name.clone(),
+            secret_hash: secret_hash(&secret),
+            metadata,
+            uses_left: (uses > 0).then_some(uses),
+            created_at: now,
+            expires_at: (ttl > 0).then_some(now + ttl as f64),
+        };
+        store.insert_secret_id(&record).await?;
+        Ok(json!({
+            "secret_id": secret,
+            "secret_id_accessor": record.accessor,
+            "secret_id_ttl": ttl,
+            "secret_id_num_uses": uses,
+        }))
+    }
+    .await;
+    match result {
+        Ok(data) => axum::Json(envelope(data)).into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn login<S>(State(vault): State<VaultCtx>, body: Bytes) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let (store, issuer) = match store_and_issuer(&vault) {
+        Ok(pair) => pair,
+        Err(response) => return *response,
+    };
+    let params = match Params::from_body(&body) {
+        Ok(params) => params,
+        Err(e) => return vault_error(e),
+    };
+    let (role_id, secret_id) = match (params.string("role_id"), params.string("secret_id")) {
+        (Ok(Some(role_id)), Ok(Some(secret_id))) => (role_id, secret_id),
+        (Ok(None), _) => return errors(StatusCode::BAD_REQUEST, &["missing role_id"]),
+        (Ok(Some(_)), Ok(None)) => {
+            return errors(StatusCode::BAD_REQUEST, &["missing secret_id"]);
+        }
+        (Err(e), _) | (_, Err(e)) => return vault_error(e),
+    };
+    let role: Option<(AuthRole, AppRole)> = match store.find_role_id(role_id.trim()).await {
+        Ok(Some(stored)) => match super::decode_role(&stored) {
+            Ok(role) => Some((stored, role)),
+            Err(e) => return vault_error(e),
+        },
+        Ok(None) => None,
+        Err(e) => return vault_error(e),
+    };
+    let Some((stored, role)) = role else {
+        return invalid_credentials();
+    };
+    match store
+        .use_secret_id(&stored.name, &secret_hash(secret_id.trim()), now_secs())
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return invalid_credentials(),
+        Err(e) => return vault_error(e),
+    }
+    issue(
+        issuer,
+        AppRole::METHOD,
+        &stored.name,
+        &role.token,
+        AppRole::METHOD.to_string(),
+        token_metadata(AppRole::METHOD, &stored.name),
+    )
+    .await
+}
+
+/// One answer for an unknown role_id and a wrong secret_id, as Vault gives,
+/// so a caller can't probe which role IDs exist.
+fn invalid_credentials() -> Response {
+    errors(StatusCode::BAD_REQUEST, &["invalid role or secret ID"])
+}
+
+fn secret_hash(secret: &str) -> String {
+    data_encoding::HEXLOWER.encode(&Sha256::digest(secret.as_bytes()))
+}
+
+/// The smaller of two limits where `0` means none.
+fn tighter(role: i64, requested: Option<i64>) -> i64 {
+    match requested.filter(|&r| r > 0) {
+        Some(r) if role == 0 || r < role => r,
+        _ => role,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_request_can_only_tighten_the_role_limit() {
+        assert_eq!(tighter(0, None), 0);
+        assert_eq!(tighter(0, Some(60)), 60);
+        assert_eq!(tighter(600, Some(60)), 60);
+        assert_eq!(tighter(600, Some(6000)), 600);
+        assert_eq!(tighter(600, Some(0)), 600);
+    }
+}
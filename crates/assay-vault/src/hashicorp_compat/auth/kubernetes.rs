@@ -0,0 +1,531 @@
+//! Kubernetes: a pod logs in with its service-account JWT and gets a token
+//! when the account's name and namespace are bound to the role it names.
+//!
+//! The JWT is verified one of two ways, chosen by `auth/kubernetes/config`:
+//!
+//! - **Locally**, when `jwks_url` or `pem_keys` is set: the signature is
+//!   checked against the cluster's service-account signing keys (the API
+//!   server publishes them at `/openid/v1/jwks`), plus `iss` when `issuer`
+//!   is set and `exp` when the token carries one.
+//! - **TokenReview**, otherwise: the JWT is posted to
+//!   `{kubernetes_host}/apis/authentication.k8s.io/v1/tokenreviews`, with
+//!   `token_reviewer_jwt` as the bearer (or the JWT itself when unset), and
+//!   the API server says whose it is. This is what catches a deleted pod's
+//!   token, which local verification cannot.
+
+use std::collections::HashSet;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{FromRef, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::{RoleConfig, TokenParams, issue, load_role, store, store_and_issuer, token_metadata};
+use crate::ctx::VaultCtx;
+use crate::error::{Result, VaultError};
+use crate::hashicorp_compat::{Params, envelope, errors, not_found, vault_error};
+
+/// How long a TokenReview or JWKS fetch may take before the login fails.
+const HTTP_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(in crate::hashicorp_compat) struct KubernetesRole {
+    /// Service-account names that may log in; `*` allows any.
+    bound_service_account_names: Vec<String>,
+    /// Namespaces they may log in from; `*` allows any.
+    bound_service_account_namespaces: Vec<String>,
+    /// `aud` the JWT must carry; empty skips the check.
+    audience: String,
+    #[serde(flatten)]
+    token: TokenParams,
+}
+
+impl RoleConfig for KubernetesRole {
+    const METHOD: &'static str = "kubernetes";
+
+    fn apply(&mut self, params: &Params) -> Result<()> {
+        if let Some(names) = params.strings("bound_service_account_names")? {
+            self.bound_service_account_names = names;
+        }
+        if let Some(namespaces) = params.strings("bound_service_account_namespaces")? {
+            self.bound_service_account_namespaces = namespaces;
+        }
+        if let Some(audience) = params.string("audience")? {
+            self.audience = audience;
+        }
+        if self.bound_service_account_names.is_empty() {
+            return Err(VaultError::Invalid(
+                "\"bound_service_account_names\" can not be empty".into(),
+            ));
+        }
+        if self.bound_service_account_namespaces.is_empty() {
+            return Err(VaultError::Invalid(
+                "\"bound_service_account_namespaces\" can not be empty".into(),
+            ));
+        }
+        self.token.apply(params)
+    }
+
+    fn describe(&self) -> serde_json::Map<String, Value> {
+        let mut out = serde_json::Map::new();
+        out.insert(
+            "bound_service_account_names".into(),
+            json!(self.bound_service_account_names),
+        );
+        out.insert(
+            "bound_service_account_namespaces".into(),
+            json!(self.bound_service_account_namespaces),
+        );
+        out.insert("audience".into(), json!(self.audience));
+        self.token.describe(&mut out);
+        out
+    }
+
+    fn token(&self) -> &TokenParams {
+        &self.token
+    }
+}
+
+/// `auth/kubernetes/config`. See the module docs for how the fields choose
+/// between local verification and TokenReview.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct KubernetesConfig {
+    kubernetes_host: String,
+    /// PEM bundle the API server's (and the JWKS endpoint's) TLS
+    /// certificate chains to.
+    kubernetes_ca_cert: String,
+    token_reviewer_jwt: String,
+    /// Service-account signing public keys, PEM.
+    pem_keys: Vec<String>,
+    jwks_url: String,
+    /// Expected `iss`; empty skips the check.
+    issuer: String,
+}
+
+impl KubernetesConfig {
+    fn apply(&mut self, params: &Params) -> Result<()> {
+        for (field, slot) in [
+            ("kubernetes_host", &mut self.kubernetes_host),
+            ("kubernetes_ca_cert", &mut self.kubernetes_ca_cert),
+            ("token_reviewer_jwt", &mut self.token_reviewer_jwt),
+            ("jwks_url", &mut self.jwks_url),
+            ("issuer", &mut self.issuer),
+        ] {
+            if let Some(value) = params.string(field)? {
+                *slot = value.trim().to_string();
+            }
+        }
+        if let Some(keys) = params.strings("pem_keys")? {
+            self.pem_keys = keys;
+        }
+        if self.kubernetes_host.is_empty() && !self.verifies_locally() {
+            return Err(VaultError::Invalid(
+                "one of kubernetes_host, jwks_url or pem_keys is required".into(),
+            ));
+        }
+        if !self.kubernetes_ca_cert.is_empty()
+            && reqwest::Certificate::from_pem_bundle(self.kubernetes_ca_cert.as_bytes()).is_err()
+        {
+            return Err(VaultError::Invalid(
+                "kubernetes_ca_cert is not a PEM certificate bundle".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn verifies_locally(&self) -> bool {
+        !self.jwks_url.is_empty() || !self.pem_keys.is_empty()
+    }
+
+    fn http_client(&self) -> std::result::Result<reqwest::Client, String> {
+        let mut builder =
+            reqwest::Client::builder().timeout(std::time::Duration::from_secs(HTTP_TIMEOUT_SECS));
+        if !self.kubernetes_ca_cert.is_empty() {
+            let certs = reqwest::Certificate::from_pem_bundle(self.kubernetes_ca_cert.as_bytes())
+                .map_err(|e| format!("kubernetes_ca_cert: {e}"))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        builder.build().map_err(|e| format!("http client: {e}"))
+    }
+}
+
+pub(super) fn login_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new().route(
+        "/v1/auth/kubernetes/login",
+        post(login::<S>).put(login::<S>),
+    )
+}
+
+pub(super) fn admin_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new().route(
+        "/v1/auth/kubernetes/config",
+        get(read_config::<S>)
+            .post(write_config::<S>)
+            .put(write_config::<S>),
+    )
+}
+
+async fn load_config(vault: &VaultCtx) -> std::result::Result<KubernetesConfig, Box<Response>> {
+    let store = store(vault)?;
+    match store.get_config(KubernetesRole::METHOD).await {
+        Ok(Some(config)) => serde_json::from_value(config).map_err(|e| {
+            Box::new(vault_error(VaultError::Backend(anyhow::anyhow!(
+                "decode kubernetes config: {e}"
+            ))))
+        }),
+        Ok(None) => Err(Box::new(errors(
+            StatusCode::BAD_REQUEST,
+            &["could not load backend configuration"],
+        ))),
+        Err(e) => Err(Box::new(vault_error(e))),
+    }
+}
+
+async fn read_config<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let config = match load_config(&vault).await {
+        Ok(config) => config,
+        Err(response) if response.status() == StatusCode::BAD_REQUEST => return not_found(),
+        Err(response) => return *response,
+    };
+    // The reviewer JWT is a credential; Vault never reads it back either.
+    axum::Json(envelope(json!({
+        "kubernetes_host": config.kubernetes_host,
+        "kubernetes_ca_cert": config.kubernetes_ca_cert,
+        "pem_keys": config.pem_keys,
+        "jwks_url": config.jwks_url,
+        "issuer": config.issuer,
+        "disable_local_ca_jwt": true,
+    })))
+    .into_response()
+}
+
+async fn write_config<S>(State(vault): State<VaultCtx>, body: Bytes) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    let result = async {
+        let params = Params::from_body(&body)?;
+        let mut config: KubernetesConfig = match store.get_config(KubernetesRole::METHOD).await? {
+            Some(stored) => serde_json::from_value(stored).unwrap_or_default(),
+            None => KubernetesConfig::default(),
+        };
+        config.apply(&params)?;
+        let value = serde_json::to_value(&config)
+            .map_err(|e| VaultError::Backend(anyhow::anyhow!("encode kubernetes config: {e}")))?;
+        store.put_config(KubernetesRole::METHOD, &value).await
+    }
+    .await;
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+async fn login<S>(State(vault): State<VaultCtx>, body: Bytes) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let (store, issuer) = match store_and_issuer(&vault) {
+        Ok(pair) => pair,
+        Err(response) => return *response,
+    };
+    let params = match Params::from_body(&body) {
+        Ok(params) => params,
+        Err(e) => return vault_error(e),
+    };
+    let (role_name, jwt) = match (params.string("role"), params.string("jwt")) {
+        (Ok(Some(role)), Ok(Some(jwt))) => (role, jwt),
+        (Ok(None), _) => return errors(StatusCode::BAD_REQUEST, &["missing role"]),
+        (Ok(Some(_)), Ok(None)) => return errors(StatusCode::BAD_REQUEST, &["missing jwt"]),
+        (Err(e), _) | (_, Err(e)) => return vault_error(e),
+    };
+    let role = match load_role::<KubernetesRole>(store, &role_name).await {
+        Ok(Some((_, role))) => role,
+        Ok(None) => {
+            let message = format!("invalid role name \"{role_name}\"");
+            return errors(StatusCode::BAD_REQUEST, &[message.as_str()]);
+        }
+        Err(e) => return vault_error(e),
+    };
+    let config = match load_config(&vault).await {
+        Ok(config) => config,
+        Err(response) => return *response,
+    };
+    let account = match verify(&config, &role, jwt.trim()).await {
+        Ok(account) => account,
+        Err(reason) => {
+            tracing::info!(role = %role_name, %reason, "kubernetes login refused");
+            return errors(StatusCode::FORBIDDEN, &["permission denied"]);
+        }
+    };
+    // Every 403 leaves the facade as "permission denied", so which binding
+    // failed goes to the log only.
+    let refusal = if !bound(&role.bound_service_account_names, &account.name) {
+        Some("service account name not authorized")
+    } else if !bound(&role.bound_service_account_namespaces, &account.namespace) {
+        Some("namespace not authorized")
+    } else {
+        None
+    };
+    if let Some(reason) = refusal {
+        tracing::info!(
+            role = %role_name,
+            namespace = %account.namespace,
+            name = %account.name,
+            reason,
+            "kubernetes login refused"
+        );
+        return errors(StatusCode::FORBIDDEN, &["permission denied"]);
+    }
+    let mut metadata = token_metadata(KubernetesRole::METHOD, &role_name);
+    metadata["service_account_name"] = json!(account.name);
+    metadata["service_account_namespace"] = json!(account.namespace);
+    metadata["service_account_uid"] = json!(account.uid);
+    issue(
+        issuer,
+        KubernetesRole::METHOD,
+        &role_name,
+        &role.token,
+        format!("kubernetes-{}-{}", account.namespace, account.name),
+        metadata,
+    )
+    .await
+}
+
+/// Who a verified JWT belongs to.
+#[derive(Debug, PartialEq, Eq)]
+struct ServiceAccount {
+    namespace: String,
+    name: String,
+    uid: String,
+}
+
+/// Verify `jwt` as `config` says to. The error is a reason for the log; the
+/// caller only ever sees "permission denied".
+async fn verify(
+    config: &KubernetesConfig,
+    role: &KubernetesRole,
+    jwt: &str,
+) -> std::result::Result<ServiceAccount, String> {
+    if config.verifies_locally() {
+        verify_locally(config, role, jwt).await
+    } else {
+        review(config, role, jwt).await
+    }
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(rename = "kubernetes.io", default)]
+    kubernetes: Value,
+}
+
+async fn verify_locally(
+    config: &KubernetesConfig,
+    role: &KubernetesRole,
+    jwt: &str,
+) -> std::result::Result<ServiceAccount, String> {
+    let header = jsonwebtoken::decode_header(jwt).map_err(|e| format!("jwt header: {e}"))?;
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err("service-account tokens are never HMAC-signed".into());
+    }
+    let keys = if config.jwks_url.is_empty() {
+        pem_decoding_keys(&config.pem_keys)
+    } else {
+        fetch_jwks(config, header.kid.as_deref()).await?
+    };
+    if keys.is_empty() {
+        return Err("no configured key matches the token".into());
+    }
+    let mut validation = Validation::new(header.alg);
+    // Pre-1.24 secret-based tokens carry no `exp`; it is checked when present.
+    validation.required_spec_claims = HashSet::new();
+    if !config.issuer.is_empty() {
+        validation.set_issuer(&[&config.issuer]);
+    }
+    if role.audience.is_empty() {
+        validation.validate_aud = false;
+    } else {
+        validation.set_audience(&[&role.audience]);
+    }
+    let mut last_error = String::new();
+    for key in &keys {
+        match jsonwebtoken::decode::<Claims>(jwt, key, &validation) {
+            Ok(data) => {
+                let uid = data.claims.kubernetes["serviceaccount"]["uid"]
+                    .as_str()
+                    .unwrap_or_default();
+                return service_account(&data.claims.sub, uid);
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(format!("jwt verification: {last_error}"))
+}
+
+fn pem_decoding_keys(pems: &[String]) -> Vec<DecodingKey> {
+    pems.iter()
+        .filter_map(|pem| {
+            let pem = pem.as_bytes();
+            DecodingKey::from_rsa_pem(pem)
+                .or_else(|_| DecodingKey::from_ec_pem(pem))
+                .or_else(|_| DecodingKey::from_ed_pem(pem))
+                .ok()
+        })
+        .collect()
+}
+
+/// The JWKS keys the token's `kid` names, or every key when it names none.
+async fn fetch_jwks(
+    config: &KubernetesConfig,
+    kid: Option<&str>,
+) -> std::result::Result<Vec<DecodingKey>, String> {
+    let set: JwkSet = config
+        .http_client()?
+        .get(&config.jwks_url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| format!("jwks fetch: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("jwks decode: {e}"))?;
+    Ok(set
+        .keys
+        .iter()
+        .filter(|jwk| kid.is_none() || jwk.common.key_id.as_deref() == kid)
+        .filter_map(|jwk| DecodingKey::from_jwk(jwk).ok())
+        .collect())
+}
+
+async fn review(
+    config: &KubernetesConfig,
+    role: &KubernetesRole,
+    jwt: &str,
+) -> std::result::Result<ServiceAccount, String> {
+    let url = format!(
+        "{}/apis/authentication.k8s.io/v1/tokenreviews",
+        config.kubernetes_host.trim_end_matches('/')
+    );
+    let reviewer = if config.token_reviewer_jwt.is_empty() {
+        jwt
+    } else {
+        &config.token_reviewer_jwt
+    };
+    let mut spec = json!({ "token": jwt });
+    if !role.audience.is_empty() {
+        spec["audiences"] = json!([role.audience]);
+    }
+    let review: Value = config
+        .http_client()?
+        .post(&url)
+        .bearer_auth(reviewer)
+        .json(&json!({
+            "apiVersion": "authentication.k8s.io/v1",
+            "kind": "TokenReview",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| format!("token review: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("token review decode: {e}"))?;
+    let status = &review["status"];
+    if status["authenticated"] != json!(true) {
+        let reason = status["error"].as_str().unwrap_or("not authenticated");
+        return Err(format!("token review: {reason}"));
+    }
+    if !role.audience.is_empty()
+        && !status["audiences"]
+            .as_array()
+            .is_some_and(|auds| auds.iter().any(|a| a == role.audience.as_str()))
+    {
+        return Err("token review: audience not accepted".into());
+    }
+    let username = status["user"]["username"].as_str().unwrap_or_default();
+    let uid = status["user"]["uid"].as_str().unwrap_or_default();
+    service_account(username, uid)
+}
+
+/// `system:serviceaccount:{namespace}:{name}` — the `sub` of every
+/// service-account token and the username TokenReview reports.
+fn service_account(subject: &str, uid: &str) -> std::result::Result<ServiceAccount, String> {
+    match subject
+        .strip_prefix("system:serviceaccount:")
+        .and_then(|rest| rest.split_once(':'))
+    {
+        Some((namespace, name)) if !namespace.is_empty() && !name.is_empty() => {
+            Ok(ServiceAccount {
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+                uid: uid.to_string(),
+            })
+        }
+        _ => Err(format!("{subject:?} is not a service account")),
+    }
+}
+
+fn bound(allowed: &[String], value: &str) -> bool {
+    allowed.iter().any(|a| a == "*" || a == value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_subject_names_the_namespace_and_the_account() {
+        assert_eq!(
+            service_account("system:serviceaccount:apps:web", "u-1"),
+            Ok(ServiceAccount {
+                namespace: "apps".into(),
+                name: "web".into(),
+                uid: "u-1".into(),
+            })
+        );
+        assert!(service_account("system:node:worker-1", "").is_err());
+        assert!(service_account("system:serviceaccount:apps", "").is_err());
+    }
+
+    #[test]
+    fn a_star_binds_any_value() {
+        let names = vec!["web".to_string()];
+        assert!(bound(&names, "web"));
+        assert!(!bound(&names, "worker"));
+        assert!(bound(&["*".to_string()], "worker"));
+    }
+}
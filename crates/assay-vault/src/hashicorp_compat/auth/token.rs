@@ -0,0 +1,183 @@
+//! `auth/token/{lookup,renew,revoke}-self` — what a token holder may ask
+//! about its own token. Outside the gate: each handler resolves the
+//! presented token through the [`TokenIssuer`](super::TokenIssuer), which
+//! refuses one it doesn't know.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{FromRef, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde_json::json;
+
+use super::{
+    TokenInfo, TokenIssuer, TokenParams, decode_role, issuer, login_envelope, now_secs, store,
+    token_metadata, token_source,
+};
+use crate::ctx::VaultCtx;
+use crate::hashicorp_compat::{Params, envelope, errors, rfc3339, vault_error};
+
+pub(super) fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new()
+        .route(
+            "/v1/auth/token/lookup-self",
+            get(lookup_self::<S>).post(lookup_self::<S>),
+        )
+        .route(
+            "/v1/auth/token/renew-self",
+            post(renew_self::<S>).put(renew_self::<S>),
+        )
+        .route(
+            "/v1/auth/token/revoke-self",
+            post(revoke_self::<S>).put(revoke_self::<S>),
+        )
+}
+
+/// The presented token and what the issuer knows about it. `X-Vault-Token`
+/// has already been copied into `Authorization` by the facade.
+async fn presented<'a>(
+    vault: &'a VaultCtx,
+    headers: &HeaderMap,
+) -> Result<(&'a std::sync::Arc<dyn TokenIssuer>, String, TokenInfo), Box<Response>> {
+    let issuer = issuer(vault)?;
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| Box::new(StatusCode::FORBIDDEN.into_response()))?;
+    match issuer.lookup(token).await {
+        Ok(Some(info)) => Ok((issuer, token.to_string(), info)),
+        Ok(None) => Err(Box::new(StatusCode::FORBIDDEN.into_response())),
+        Err(e) => Err(Box::new(vault_error(e))),
+    }
+}
+
+async fn lookup_self<S>(State(vault): State<VaultCtx>, headers: HeaderMap) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let (_, token, info) = match presented(&vault, &headers).await {
+        Ok(found) => found,
+        Err(response) => return *response,
+    };
+    let source = token_source(&info.owner);
+    let (path, meta) = match source {
+        Some((method, role)) => (format!("auth/{method}/login"), token_metadata(method, role)),
+        None => ("auth/token/create".to_string(), json!(null)),
+    };
+    let remaining = info
+        .expires_at
+        .map(|at| (at - now_secs()).max(0.0) as i64)
+        .unwrap_or(0);
+    let lifetime = info
+        .expires_at
+        .map(|at| (at - info.created_at).max(0.0) as i64)
+        .unwrap_or(0);
+    axum::Json(envelope(json!({
+        "accessor": info.accessor,
+        "creation_time": info.created_at as i64,
+        "creation_ttl": lifetime,
+        "display_name": info.display_name,
+        "entity_id": "",
+        "expire_time": info.expires_at.map(rfc3339),
+        "explicit_max_ttl": 0,
+        "id": token,
+        "issue_time": rfc3339(info.created_at),
+        "meta": meta,
+        "num_uses": 0,
+        "orphan": true,
+        "path": path,
+        "policies": info.policies,
+        "renewable": source.is_some(),
+        "ttl": remaining,
+        "type": "service",
+    })))
+    .into_response()
+}
+
+/// Extend the token by `increment` (default: its role's `token_ttl`), but
+/// never past `token_max_ttl` after it was issued. The role is read afresh,
+/// so tightening a role's max TTL applies to tokens already out.
+async fn renew_self<S>(State(vault): State<VaultCtx>, headers: HeaderMap, body: Bytes) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let (issuer, token, mut info) = match presented(&vault, &headers).await {
+        Ok(found) => found,
+        Err(response) => return *response,
+    };
+    let owner = info.owner.clone();
+    let Some((method, role_name)) = token_source(&owner) else {
+        return errors(StatusCode::BAD_REQUEST, &["lease is not renewable"]);
+    };
+    let store = match store(&vault) {
+        Ok(store) => store,
+        Err(response) => return *response,
+    };
+    let increment = match Params::from_body(&body).and_then(|p| p.duration("increment")) {
+        Ok(increment) => increment,
+        Err(e) => return vault_error(e),
+    };
+    let params: TokenParams = match store.get_role(method, role_name).await {
+        Ok(Some(role)) => match decode_role(&role) {
+            Ok(params) => params,
+            Err(e) => return vault_error(e),
+        },
+        Ok(None) => {
+            return errors(
+                StatusCode::BAD_REQUEST,
+                &["the role that issued this token no longer exists"],
+            );
+        }
+        Err(e) => return vault_error(e),
+    };
+    let now = now_secs();
+    let wanted = increment.filter(|&i| i > 0).unwrap_or_else(|| params.ttl());
+    let cap = info.created_at + params.max_ttl() as f64;
+    let expires_at = (now + wanted as f64).min(cap);
+    if expires_at <= now {
+        return errors(
+            StatusCode::BAD_REQUEST,
+            &["token has reached its max TTL and cannot be renewed"],
+        );
+    }
+    if let Err(e) = issuer.extend(&info.accessor, expires_at).await {
+        return vault_error(e);
+    }
+    let lease = (expires_at - now) as i64;
+    info.expires_at = Some(expires_at);
+    let metadata = token_metadata(method, role_name);
+    axum::Json(login_envelope(&token, &info, lease, metadata)).into_response()
+}
+
+async fn revoke_self<S>(State(vault): State<VaultCtx>, headers: HeaderMap) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let (issuer, _, info) = match presented(&vault, &headers).await {
+        Ok(found) => found,
+        Err(response) => return *response,
+    };
+    // Only what a login issued is revocable here; the admin key lives in
+    // engine.toml and a hand-made API key belongs to whoever made it.
+    if token_source(&info.owner).is_none() {
+        return errors(
+            StatusCode::BAD_REQUEST,
+            &["this token was not issued by an auth method and cannot revoke itself"],
+        );
+    }
+    match issuer.revoke(&info.accessor).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_error(e),
+    }
+}
@@ -1,20 +1,24 @@
-//! KV v2 read handlers — `data` reads, `metadata` reads, and LIST.
+//! KV v2 handlers — `data` reads and writes, version `delete` / `undelete` /
+//! `destroy`, `metadata` reads, writes and deletes, and LIST.
 
 use std::collections::BTreeSet;
 
 use axum::Router;
+use axum::body::Bytes;
 use axum::extract::{Extension, FromRef, Path, Query, State};
 use axum::http::{Method, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{any, get};
+use axum::routing::{any, get, post};
 use serde::Deserialize;
 use serde_json::{Value, json};
 
 use crate::ctx::{DynKvStore, VaultCtx};
+use crate::error::{Result, VaultError};
 use crate::hashicorp_compat::{
-    Mount, envelope, errors, kv_unconfigured, normalize_path, not_found, rfc3339, vault_error,
+    Mount, Params, envelope, errors, kv_unconfigured, normalize_path, not_found, rfc3339,
+    vault_error,
 };
-use crate::kv::{KvMeta, KvRead, KvService};
+use crate::kv::{KvMeta, KvMetaUpdate, KvRead, KvService, KvVersion};
 
 pub(super) fn router<S>() -> Router<S>
 where
@@ -22,7 +26,25 @@ where
     VaultCtx: FromRef<S>,
 {
     Router::new()
-        .route("/v1/{mount}/data/{*path}", get(read_data::<S>))
+        .route(
+            "/v1/{mount}/data/{*path}",
+            get(read_data::<S>)
+                .post(write_data::<S>)
+                .put(write_data::<S>)
+                .delete(delete_latest::<S>),
+        )
+        .route(
+            "/v1/{mount}/delete/{*path}",
+            post(versions_op::<S, Delete>).put(versions_op::<S, Delete>),
+        )
+        .route(
+            "/v1/{mount}/undelete/{*path}",
+            post(versions_op::<S, Undelete>).put(versions_op::<S, Undelete>),
+        )
+        .route(
+            "/v1/{mount}/destroy/{*path}",
+            post(versions_op::<S, Destroy>).put(versions_op::<S, Destroy>),
+        )
         .route("/v1/{mount}/metadata/{*path}", any(metadata::<S>))
         .route("/v1/{mount}/metadata", any(metadata_root::<S>))
 }
@@ -68,7 +90,7 @@ where
 
     // A soft-deleted version answers 404 while still describing itself, so a
     // caller can tell "deleted at T" from "never existed".
-    if read.deleted_at.is_some() {
+    if read.is_deleted() {
         let body = envelope(json!({ "data": Value::Null, "metadata": metadata }));
         return (StatusCode::NOT_FOUND, axum::Json(body)).into_response();
     }
@@ -85,18 +107,169 @@ where
     axum::Json(envelope(json!({ "data": payload, "metadata": metadata }))).into_response()
 }
 
+/// `{"data": {…}, "options": {"cas": N}}` → a new version, answered with
+/// that version's metadata the way Vault does.
+async fn write_data<S>(
+    State(vault): State<VaultCtx>,
+    Extension(mount): Extension<Mount>,
+    Path((requested_mount, path)): Path<(String, String)>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let kv = match resolve(&vault, &requested_mount, &mount) {
+        Ok(kv) => kv,
+        Err(response) => return *response,
+    };
+    let path = normalize_path(&path);
+    let result = async {
+        let params = Params::from_body(&body)?;
+        let data = match params.value("data") {
+            Some(data @ Value::Object(_)) => data.to_string(),
+            Some(_) => return Err(VaultError::Invalid("data must be a JSON object".into())),
+            None => return Err(VaultError::Invalid("no data provided".into())),
+        };
+        let cas = match params.value("options") {
+            None => None,
+            Some(Value::Object(options)) => Params(options.clone()).int("cas")?,
+            Some(_) => return Err(VaultError::Invalid("options must be a JSON object".into())),
+        };
+        let version = kv.put_cas(path, data.as_bytes(), json!({}), cas).await?;
+        let created = kv
+            .versions(path)
+            .await?
+            .into_iter()
+            .find(|v| v.version == version)
+            .ok_or(VaultError::NotFound)?;
+        Ok(json!({
+            "created_time": rfc3339(created.created_at),
+            "custom_metadata": custom_metadata(kv, path).await,
+            "deletion_time": created.deleted_at.map(rfc3339).unwrap_or_default(),
+            "destroyed": false,
+            "version": version,
+        }))
+    }
+    .await;
+    match result {
+        Ok(data) => axum::Json(envelope(data)).into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+/// `DELETE …/data/{path}` soft-deletes the latest version. Vault answers
+/// 204 whether or not there was anything to delete, and so does this.
+async fn delete_latest<S>(
+    State(vault): State<VaultCtx>,
+    Extension(mount): Extension<Mount>,
+    Path((requested_mount, path)): Path<(String, String)>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let kv = match resolve(&vault, &requested_mount, &mount) {
+        Ok(kv) => kv,
+        Err(response) => return *response,
+    };
+    let path = normalize_path(&path);
+    let result = match kv.read_meta(path).await {
+        Ok(meta) => kv.soft_delete(path, meta.latest_version).await,
+        Err(e) => Err(e),
+    };
+    no_content_unless_failed(result)
+}
+
+/// What `…/{delete,undelete,destroy}/{path}` does to each listed version.
+trait VersionsOp {
+    async fn apply(kv: &KvService<DynKvStore>, path: &str, version: i64) -> Result<()>;
+}
+
+struct Delete;
+struct Undelete;
+struct Destroy;
+
+impl VersionsOp for Delete {
+    async fn apply(kv: &KvService<DynKvStore>, path: &str, version: i64) -> Result<()> {
+        kv.soft_delete(path, version).await
+    }
+}
+
+impl VersionsOp for Undelete {
+    async fn apply(kv: &KvService<DynKvStore>, path: &str, version: i64) -> Result<()> {
+        kv.undelete(path, version).await
+    }
+}
+
+impl VersionsOp for Destroy {
+    async fn apply(kv: &KvService<DynKvStore>, path: &str, version: i64) -> Result<()> {
+        kv.destroy(path, version).await
+    }
+}
+
+/// `{"versions": [1, 2]}`. A version already in the requested state, or
+/// never there, is skipped rather than failing the rest.
+async fn versions_op<S, Op: VersionsOp>(
+    State(vault): State<VaultCtx>,
+    Extension(mount): Extension<Mount>,
+    Path((requested_mount, path)): Path<(String, String)>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let kv = match resolve(&vault, &requested_mount, &mount) {
+        Ok(kv) => kv,
+        Err(response) => return *response,
+    };
+    let path = normalize_path(&path);
+    let result = async {
+        let versions = Params::from_body(&body)?
+            .strings("versions")?
+            .unwrap_or_default()
+            .iter()
+            .map(|v| {
+                v.parse::<i64>()
+                    .map_err(|_| VaultError::Invalid(format!("invalid version {v:?}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if versions.is_empty() {
+            return Err(VaultError::Invalid("no version number provided".into()));
+        }
+        for version in versions {
+            match Op::apply(kv, path, version).await {
+                Ok(()) | Err(VaultError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+    .await;
+    no_content_unless_failed(result)
+}
+
+fn no_content_unless_failed(result: Result<()>) -> Response {
+    match result {
+        Ok(()) | Err(VaultError::NotFound) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
 async fn metadata<S>(
     method: Method,
     State(vault): State<VaultCtx>,
     Extension(mount): Extension<Mount>,
     Path((requested_mount, path)): Path<(String, String)>,
     Query(q): Query<ListQuery>,
+    body: Bytes,
 ) -> Response
 where
     S: Clone + Send + Sync + 'static,
     VaultCtx: FromRef<S>,
 {
-    metadata_impl(method, vault, requested_mount, mount, path, q).await
+    metadata_impl(method, vault, requested_mount, mount, path, q, body).await
 }
 
 async fn metadata_root<S>(
@@ -110,9 +283,26 @@ where
     S: Clone + Send + Sync + 'static,
     VaultCtx: FromRef<S>,
 {
-    metadata_impl(method, vault, requested_mount, mount, String::new(), q).await
+    metadata_impl(
+        method,
+        vault,
+        requested_mount,
+        mount,
+        String::new(),
+        q,
+        Bytes::new(),
+    )
+    .await
+}
+
+enum MetadataOp {
+    List,
+    Read,
+    Write,
+    Delete,
 }
 
+/// The mount root only lists; there is no metadata to write or delete on it.
 async fn metadata_impl(
     method: Method,
     vault: VaultCtx,
@@ -120,26 +310,61 @@ async fn metadata_impl(
     mount: Mount,
     path: String,
     q: ListQuery,
+    body: Bytes,
 ) -> Response {
-    let is_list = method.as_str() == "LIST" || (method == Method::GET && q.wants_list());
-    if !is_list && method != Method::GET {
+    let path = normalize_path(&path);
+    let op = if method.as_str() == "LIST" || (method == Method::GET && q.wants_list()) {
+        MetadataOp::List
+    } else if method == Method::GET {
+        MetadataOp::Read
+    } else if path.is_empty() {
         return StatusCode::METHOD_NOT_ALLOWED.into_response();
-    }
+    } else if method == Method::POST || method == Method::PUT {
+        MetadataOp::Write
+    } else if method == Method::DELETE {
+        MetadataOp::Delete
+    } else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
     let kv = match resolve(&vault, &requested_mount, &mount) {
         Ok(kv) => kv,
         Err(response) => return *response,
     };
-    let path = normalize_path(&path);
-    if is_list {
-        list_children(kv, path).await
-    } else {
-        read_metadata(kv, path).await
+    match op {
+        MetadataOp::List => list_children(kv, path).await,
+        MetadataOp::Read => read_metadata(kv, path).await,
+        MetadataOp::Write => write_metadata(kv, path, &body).await,
+        MetadataOp::Delete => no_content_unless_failed(kv.delete_path(path).await),
     }
 }
 
 async fn read_metadata(kv: &KvService<DynKvStore>, path: &str) -> Response {
-    match kv.read_meta(path).await {
-        Ok(meta) => axum::Json(envelope(path_metadata(&meta))).into_response(),
+    let meta = match kv.read_meta(path).await {
+        Ok(meta) => meta,
+        Err(e) => return vault_error(e),
+    };
+    match kv.versions(path).await {
+        Ok(versions) => axum::Json(envelope(path_metadata(&meta, &versions))).into_response(),
+        Err(e) => vault_error(e),
+    }
+}
+
+/// Fields left out keep their stored value; `custom_metadata` replaces the
+/// stored object rather than merging into it, as in Vault.
+async fn write_metadata(kv: &KvService<DynKvStore>, path: &str, body: &[u8]) -> Response {
+    let result = async {
+        let params = Params::from_body(body)?;
+        let update = KvMetaUpdate {
+            cas_required: params.bool("cas_required")?,
+            max_versions: params.int("max_versions")?,
+            delete_version_after: params.duration("delete_version_after")?,
+            custom_md: params.value("custom_metadata").cloned(),
+        };
+        kv.put_meta(path, &update).await
+    }
+    .await;
+    match result {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
         Err(e) => vault_error(e),
     }
 }
@@ -199,30 +424,50 @@ fn version_metadata(read: &KvRead, custom_metadata: Value) -> Value {
     })
 }
 
-/// assay records version history at the path level, not per version, so
-/// `versions` describes the current version alone. `max_versions: 0` and
-/// `cas_required: false` are Vault's own defaults for a mount with neither
-/// configured, which is exactly this one.
-fn path_metadata(meta: &KvMeta) -> Value {
+/// The path's policy and every version still stored. Vault reports
+/// `oldest_version: 0` until something has been pruned, then the first
+/// version it kept.
+fn path_metadata(meta: &KvMeta, versions: &[KvVersion]) -> Value {
+    let oldest = versions
+        .first()
+        .map(|v| v.version)
+        .filter(|&v| v > 1)
+        .unwrap_or(0);
+    let versions: serde_json::Map<String, Value> = versions
+        .iter()
+        .map(|v| {
+            let entry = json!({
+                "created_time": rfc3339(v.created_at),
+                "deletion_time": v.deleted_at.map(rfc3339).unwrap_or_default(),
+                "destroyed": v.destroyed,
+            });
+            (v.version.to_string(), entry)
+        })
+        .collect();
     json!({
-        "cas_required": false,
+        "cas_required": meta.cas_required,
         "created_time": rfc3339(meta.created_at),
         "current_version": meta.latest_version,
         "custom_metadata": custom_md_or_null(meta.custom_md.clone()),
-        "delete_version_after": "0s",
-        "max_versions": 0,
-        "oldest_version": 0,
+        "delete_version_after": go_duration(meta.delete_version_after),
+        "max_versions": meta.max_versions,
+        "oldest_version": oldest,
         "updated_time": rfc3339(meta.updated_at),
-        "versions": {
-            meta.latest_version.to_string(): {
-                "created_time": rfc3339(meta.updated_at),
-                "deletion_time": "",
-                "destroyed": false,
-            }
-        },
+        "versions": versions,
     })
 }
 
+/// Seconds in Go's `time.Duration` notation, which is how Vault renders
+/// `delete_version_after`: `0s`, `45s`, `1m30s`, `1h0m0s`.
+fn go_duration(secs: i64) -> String {
+    let (h, m, s) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    match (h, m) {
+        (0, 0) => format!("{s}s"),
+        (0, _) => format!("{m}m{s}s"),
+        _ => format!("{h}h{m}m{s}s"),
+    }
+}
+
 async fn custom_metadata(kv: &KvService<DynKvStore>, path: &str) -> Value {
     match kv.read_meta(path).await {
         Ok(meta) => custom_md_or_null(meta.custom_md),
@@ -243,7 +488,7 @@ fn resolve<'a>(
     vault: &'a VaultCtx,
     requested: &str,
     mount: &Mount,
-) -> Result<&'a KvService<DynKvStore>, Box<Response>> {
+) -> std::result::Result<&'a KvService<DynKvStore>, Box<Response>> {
     if requested != mount.name() {
         return Err(Box::new(not_found()));
     }
@@ -262,6 +507,9 @@ mod tests {
             path: path.to_string(),
             latest_version: 1,
             custom_md: json!({}),
+            cas_required: false,
+            max_versions: 0,
+            delete_version_after: 0,
             created_at: 0.0,
             updated_at: 0.0,
         }
@@ -316,6 +564,15 @@ mod tests {
         assert_eq!(custom_md_or_null(json!({"owner": "sre"}))["owner"], "sre");
     }
 
+    #[test]
+    fn delete_version_after_reads_as_a_go_duration() {
+        assert_eq!(go_duration(0), "0s");
+        assert_eq!(go_duration(45), "45s");
+        assert_eq!(go_duration(90), "1m30s");
+        assert_eq!(go_duration(3600), "1h0m0s");
+        assert_eq!(go_duration(90_061), "25h1m1s");
+    }
+
     #[test]
     fn only_an_explicit_list_flag_turns_a_get_into_a_listing() {
         let q = |v: Option<&str>| ListQuery {
@@ -1,30 +1,43 @@
-//! HashiCorp Vault / OpenBao **read-path** compatibility facade.
+//! HashiCorp Vault / OpenBao compatibility facade.
 //!
-//! Serves the Vault dialect — `X-Vault-Token` auth, `/v1/{mount}/data/{path}`
-//! KV2 reads, Vault's response envelope — on top of [`crate::kv`], so ESO's
-//! vault provider, ansible's `community.hashi_vault`, `vault kv get` and curl
-//! reach assay-vault by repointing a URL. Full mapping, config, and consumer
-//! examples: `docs/vault-hashicorp-compat.md`.
+//! Serves the Vault dialect — `X-Vault-Token` auth, `/v1/{mount}/…` paths,
+//! Vault's response envelope — on top of [`crate::kv`] and
+//! [`crate::transit`], so ESO's vault provider, ansible's
+//! `community.hashi_vault`, Terraform's vault provider, vault-agent,
+//! `vault kv get` and curl reach assay-vault by repointing a URL. Full
+//! mapping, config, and consumer examples: `docs/vault-hashicorp-compat.md`.
 //!
 //! ```text
-//! GET  /v1/{mount}/data/{path}          latest version (?version=N for a specific one)
-//! GET  /v1/{mount}/metadata/{path}      path-level metadata
-//! LIST /v1/{mount}/metadata/{prefix}    immediate children (also GET …?list=true)
-//! GET  /v1/sys/health                   liveness + seal state (unauthenticated)
+//! GET    /v1/{mount}/data/{path}              latest version (?version=N for a specific one)
+//! POST   /v1/{mount}/data/{path}              write a version (options.cas for check-and-set)
+//! DELETE /v1/{mount}/data/{path}              soft-delete the latest version
+//! POST   /v1/{mount}/delete/{path}            soft-delete `versions`
+//! POST   /v1/{mount}/undelete/{path}          restore soft-deleted `versions`
+//! POST   /v1/{mount}/destroy/{path}           destroy `versions` for good
+//! GET    /v1/{mount}/metadata/{path}          path-level metadata
+//! POST   /v1/{mount}/metadata/{path}          max_versions, cas_required, delete_version_after, custom_metadata
+//! DELETE /v1/{mount}/metadata/{path}          the path and every version
+//! LIST   /v1/{mount}/metadata/{prefix}        immediate children (also GET …?list=true)
+//! POST   /v1/{transit}/encrypt/{key}          base64 plaintext → vault:vN: ciphertext
+//! POST   /v1/{transit}/decrypt/{key}          and back
+//! …      /v1/auth/{approle,kubernetes,token}/… logins and token self-service, see `auth`
+//! GET    /v1/sys/health                       liveness + seal state (unauthenticated)
 //! ```
 //!
-//! Read-only by construction: no writes, no `sys/*` beyond health, no token
-//! issuance, no auth mounts. Any other method on a facade route answers 405.
+//! No `sys/*` beyond health, no policies, no mounts: any other method on a
+//! facade route answers 405, any other path 404.
 //!
-//! The mount is a label, not a namespace — it names the one logical KV2 mount
-//! this engine exposes and is stripped before the lookup, leaving the assay KV
-//! path verbatim. Any other mount is a 404, so a typo cannot read a different
-//! secret than the caller named.
+//! A mount is a label, not a namespace — [`Mount`] names the one logical
+//! KV2 mount and the one transit mount this engine exposes, and the label
+//! is stripped before the lookup, leaving the assay KV path or transit key
+//! name verbatim. Any other mount is a 404, so a typo cannot reach a
+//! different secret than the caller named.
 //!
 //! A Vault token IS an assay token: [`router`] takes the same mandatory `gate`
 //! closure [`crate::router::vault_router`] does and pre-translates
-//! `X-Vault-Token` into the bearer that gate already checks, so there is no
-//! second token store and one enforcement point.
+//! `X-Vault-Token` into the bearer that gate already checks, so there is one
+//! enforcement point. The tokens AppRole and Kubernetes logins hand out are
+//! assay tokens too, minted by the embedder's [`TokenIssuer`].
 //!
 //! Response field sets are copied from the KV2 spec
 //! (<https://developer.hashicorp.com/vault/api-docs/secret/kv/kv-v2>).
@@ -41,46 +54,82 @@ use axum::response::{IntoResponse, Response};
 use serde_json::{Value, json};
 
 use crate::ctx::VaultCtx;
+use crate::error::{Result, VaultError};
 
+mod auth;
 mod kv2;
 mod sys;
+#[cfg(feature = "vault-transit")]
+mod transit;
+
+pub use auth::{
+    AuthRole, CompatAuthStore, DEFAULT_TOKEN_TTL, IssuedToken, SecretId, TokenGrant, TokenInfo,
+    TokenIssuer,
+};
 
 /// Mount name served when the embedder doesn't configure one.
 pub const DEFAULT_MOUNT: &str = "secrets";
 
+/// Transit mount name served when the embedder doesn't configure one.
+pub const DEFAULT_TRANSIT_MOUNT: &str = "transit";
+
 const VAULT_TOKEN_HEADER: &str = "x-vault-token";
 
-/// The single logical KV2 mount this facade answers for.
+/// The logical mounts this facade answers for: one KV2, one transit.
 #[derive(Clone, Debug)]
-pub struct Mount(Arc<str>);
+pub struct Mount {
+    kv: Arc<str>,
+    transit: Arc<str>,
+}
 
 impl Mount {
+    /// `name` is the KV2 mount; the transit mount keeps its default.
     pub fn new(name: impl AsRef<str>) -> Self {
-        let name = name.as_ref().trim().trim_matches('/');
-        if name.is_empty() {
-            return Self::default();
+        Self {
+            kv: label(name.as_ref(), DEFAULT_MOUNT),
+            ..Self::default()
         }
-        Self(Arc::from(name))
     }
 
+    pub fn with_transit(mut self, name: impl AsRef<str>) -> Self {
+        self.transit = label(name.as_ref(), DEFAULT_TRANSIT_MOUNT);
+        self
+    }
+
+    /// The KV2 mount.
     pub fn name(&self) -> &str {
-        &self.0
+        &self.kv
+    }
+
+    pub fn transit(&self) -> &str {
+        &self.transit
     }
 }
 
 impl Default for Mount {
     fn default() -> Self {
-        Self(Arc::from(DEFAULT_MOUNT))
+        Self {
+            kv: Arc::from(DEFAULT_MOUNT),
+            transit: Arc::from(DEFAULT_TRANSIT_MOUNT),
+        }
     }
 }
 
+fn label(name: &str, default: &str) -> Arc<str> {
+    let name = name.trim().trim_matches('/');
+    Arc::from(if name.is_empty() { default } else { name })
+}
+
 /// Compose the compat facade, generic over a parent state from which
 /// [`VaultCtx`] is extractable.
 ///
 /// `gate` is the wire-boundary auth layer and is **non-optional** — an
-/// unauthenticated facade is a compile error. `GET /v1/sys/health` sits
-/// outside it because Vault's health endpoint is unauthenticated and k8s
-/// probes plus ESO store validation hit it without a token.
+/// unauthenticated facade is a compile error. Three groups sit outside it:
+/// `GET /v1/sys/health`, because Vault's health endpoint is unauthenticated
+/// and k8s probes plus ESO store validation hit it without a token; the
+/// AppRole and Kubernetes logins, whose callers have no token yet; and
+/// `auth/token/*-self`, which resolve the presented token through the
+/// [`TokenIssuer`] themselves.
 ///
 /// Mount at the router root: Vault clients hardcode `/v1/…` and cannot be
 /// told to use a prefix.
@@ -90,9 +139,12 @@ where
     VaultCtx: FromRef<S>,
     F: FnOnce(Router<S>) -> Router<S>,
 {
-    let gated = gate(kv2::router::<S>());
-    gated
+    let guarded = kv2::router::<S>().merge(auth::admin_router::<S>());
+    #[cfg(feature = "vault-transit")]
+    let guarded = guarded.merge(transit::router::<S>());
+    gate(guarded)
         .merge(sys::router::<S>())
+        .merge(auth::public_router::<S>())
         .layer(axum::Extension(mount))
         .layer(axum::middleware::from_fn(vault_error_envelope))
         .layer(axum::middleware::from_fn(adopt_vault_token))
@@ -136,8 +188,8 @@ pub(crate) fn not_found() -> Response {
     errors(StatusCode::NOT_FOUND, &[])
 }
 
-/// The wrapper every Vault read response carries; only `data` varies across
-/// the endpoints served here.
+/// The wrapper every Vault response body carries; `data` (and `auth`, for a
+/// login) is what varies across the endpoints served here.
 pub(crate) fn envelope(data: Value) -> Value {
     json!({
         "request_id": uuid::Uuid::new_v4().to_string(),
@@ -160,13 +212,14 @@ pub(crate) fn rfc3339(secs: f64) -> String {
         .unwrap_or_default()
 }
 
-pub(crate) fn vault_error(e: crate::error::VaultError) -> Response {
-    use crate::error::VaultError as E;
+pub(crate) fn vault_error(e: VaultError) -> Response {
+    use VaultError as E;
     match e {
         E::NotFound => not_found(),
         E::Sealed => errors(StatusCode::SERVICE_UNAVAILABLE, &["Vault is sealed"]),
         E::Forbidden => errors(StatusCode::FORBIDDEN, &["permission denied"]),
-        E::Invalid(msg) => errors(StatusCode::BAD_REQUEST, &[msg.as_str()]),
+        // Vault reports a failed check-and-set as a plain 400.
+        E::Invalid(msg) | E::Conflict(msg) => errors(StatusCode::BAD_REQUEST, &[msg.as_str()]),
         other => errors(
             StatusCode::INTERNAL_SERVER_ERROR,
             &[other.to_string().as_str()],
@@ -189,6 +242,152 @@ pub(crate) fn normalize_path(path: &str) -> &str {
     path.trim_matches('/')
 }
 
+/// A write's JSON body. Vault's clients disagree on types — the CLI sends
+/// every `key=value` as a string, Terraform sends numbers, lists as arrays
+/// and TTLs as either — so each getter accepts every spelling Vault does.
+/// A `null` field reads as absent.
+pub(crate) struct Params(serde_json::Map<String, Value>);
+
+impl Params {
+    /// An empty body is an empty request, as Vault treats it.
+    pub(crate) fn from_body(body: &[u8]) -> Result<Self> {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Self(serde_json::Map::new()));
+        }
+        match serde_json::from_slice(body) {
+            Ok(Value::Object(map)) => Ok(Self(map)),
+            Ok(Value::Null) => Ok(Self(serde_json::Map::new())),
+            _ => Err(VaultError::Invalid(
+                "request body must be a JSON object".into(),
+            )),
+        }
+    }
+
+    pub(crate) fn value(&self, key: &str) -> Option<&Value> {
+        self.0.get(key).filter(|v| !v.is_null())
+    }
+
+    pub(crate) fn string(&self, key: &str) -> Result<Option<String>> {
+        match self.value(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(Value::Number(n)) => Ok(Some(n.to_string())),
+            Some(Value::Bool(b)) => Ok(Some(b.to_string())),
+            Some(_) => Err(VaultError::Invalid(format!("{key} must be a string"))),
+        }
+    }
+
+    /// An array of strings, or one comma-separated string.
+    pub(crate) fn strings(&self, key: &str) -> Result<Option<Vec<String>>> {
+        let invalid = || VaultError::Invalid(format!("{key} must be a list of strings"));
+        let items: Vec<String> = match self.value(key) {
+            None => return Ok(None),
+            Some(Value::String(s)) => s.split(',').map(str::to_string).collect(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) => Ok(s.clone()),
+                    Value::Number(n) => Ok(n.to_string()),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(invalid()),
+        };
+        Ok(Some(
+            items
+                .into_iter()
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+        ))
+    }
+
+    /// [`Self::strings`] under the first of `keys` that is present.
+    pub(crate) fn strings_any(&self, keys: &[&str]) -> Result<Option<Vec<String>>> {
+        match keys.iter().find(|key| self.value(key).is_some()) {
+            Some(key) => self.strings(key),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn bool(&self, key: &str) -> Result<Option<bool>> {
+        match self.value(key) {
+            None => Ok(None),
+            Some(Value::Bool(b)) => Ok(Some(*b)),
+            Some(Value::String(s)) => match s.trim() {
+                "true" | "1" => Ok(Some(true)),
+                "false" | "0" => Ok(Some(false)),
+                _ => Err(VaultError::Invalid(format!("{key} must be a boolean"))),
+            },
+            Some(_) => Err(VaultError::Invalid(format!("{key} must be a boolean"))),
+        }
+    }
+
+    pub(crate) fn int(&self, key: &str) -> Result<Option<i64>> {
+        let invalid = || VaultError::Invalid(format!("{key} must be an integer"));
+        match self.value(key) {
+            None => Ok(None),
+            Some(Value::Number(n)) => n.as_i64().map(Some).ok_or_else(invalid),
+            Some(Value::String(s)) => s.trim().parse().map(Some).map_err(|_| invalid()),
+            Some(_) => Err(invalid()),
+        }
+    }
+
+    /// Seconds, from a number or a duration string (`90`, `90s`, `1h30m`).
+    pub(crate) fn duration(&self, key: &str) -> Result<Option<i64>> {
+        let invalid = || VaultError::Invalid(format!("{key} must be a duration"));
+        match self.value(key) {
+            None => Ok(None),
+            Some(Value::Number(n)) => n
+                .as_i64()
+                .filter(|&secs| secs >= 0)
+                .map(Some)
+                .ok_or_else(invalid),
+            Some(Value::String(s)) => parse_duration(s).map(Some).ok_or_else(invalid),
+            Some(_) => Err(invalid()),
+        }
+    }
+
+    /// [`Self::duration`] under the first of `keys` that is present.
+    pub(crate) fn duration_any(&self, keys: &[&str]) -> Result<Option<i64>> {
+        match keys.iter().find(|key| self.value(key).is_some()) {
+            Some(key) => self.duration(key),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Whole seconds from the duration strings Vault accepts: bare seconds, or
+/// Go-style `{n}{unit}` runs over `s`, `m`, `h` and Vault's extra `d`.
+fn parse_duration(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = text.parse::<i64>() {
+        return (secs >= 0).then_some(secs);
+    }
+    let mut total: i64 = 0;
+    let mut digits = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return None,
+        };
+        let n: i64 = digits.parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        digits.clear();
+    }
+    digits.is_empty().then_some(total)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +402,39 @@ mod tests {
     #[test]
     fn mount_ignores_slashes_a_config_file_may_carry() {
         assert_eq!(Mount::new("/kv/").name(), "kv");
+        assert_eq!(Mount::default().with_transit("/crypt/").transit(), "crypt");
+        assert_eq!(Mount::new("kv").with_transit(" ").transit(), "transit");
+    }
+
+    #[test]
+    fn durations_parse_the_way_vault_parses_them() {
+        assert_eq!(parse_duration("90"), Some(90));
+        assert_eq!(parse_duration("90s"), Some(90));
+        assert_eq!(parse_duration("1h30m"), Some(5400));
+        assert_eq!(parse_duration("768h"), Some(DEFAULT_TOKEN_TTL));
+        assert_eq!(parse_duration("7d"), Some(7 * 86_400));
+        assert_eq!(parse_duration("-5"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("1h5"), None);
+    }
+
+    #[test]
+    fn params_accept_the_stringly_typed_fields_the_cli_sends() {
+        let params = Params::from_body(
+            br#"{"policies":"a, b,","ttl":"1h","num_uses":"3","bind":"true","n":null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            params.strings_any(&["token_policies", "policies"]).unwrap(),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(params.duration("ttl").unwrap(), Some(3600));
+        assert_eq!(params.int("num_uses").unwrap(), Some(3));
+        assert_eq!(params.bool("bind").unwrap(), Some(true));
+        assert_eq!(params.string("n").unwrap(), None);
+        assert!(Params::from_body(b"").unwrap().value("x").is_none());
+        assert!(Params::from_body(b"[1]").is_err());
     }
 
     #[test]
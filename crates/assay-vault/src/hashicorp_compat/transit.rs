@@ -0,0 +1,174 @@
+//! Transit `encrypt` / `decrypt` in the Vault dialect: base64 plaintext in,
+//! `vault:vN:` ciphertext out. [`crate::transit`] already writes that
+//! ciphertext shape, so this is request and response plumbing only.
+//!
+//! Like Vault, encrypting under a key that doesn't exist creates it first
+//! (`type` may name its key type; Vault's `aes256-gcm96` maps to assay's
+//! default). Decryption never creates anything.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{Extension, FromRef, Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use data_encoding::BASE64;
+use serde_json::json;
+
+use crate::ctx::{DynTransitStore, VaultCtx};
+use crate::error::VaultError;
+use crate::hashicorp_compat::{Mount, Params, envelope, errors, not_found, vault_error};
+use crate::transit::TransitService;
+
+pub(super) fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new()
+        .route(
+            "/v1/{mount}/encrypt/{name}",
+            post(encrypt::<S>).put(encrypt::<S>),
+        )
+        .route(
+            "/v1/{mount}/decrypt/{name}",
+            post(decrypt::<S>).put(decrypt::<S>),
+        )
+}
+
+async fn encrypt<S>(
+    State(vault): State<VaultCtx>,
+    Extension(mount): Extension<Mount>,
+    Path((requested_mount, name)): Path<(String, String)>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let transit = match resolve(&vault, &requested_mount, &mount) {
+        Ok(transit) => transit,
+        Err(response) => return *response,
+    };
+    let result = async {
+        let params = Params::from_body(&body)?;
+        refuse_unsupported(&params)?;
+        let plaintext = params
+            .string("plaintext")?
+            .ok_or_else(|| VaultError::Invalid("missing plaintext to encrypt".into()))?;
+        let plaintext = BASE64
+            .decode(plaintext.as_bytes())
+            .map_err(|_| VaultError::Invalid("failed to base64-decode plaintext".into()))?;
+        match transit.encrypt(&name, &plaintext).await {
+            Err(VaultError::NotFound) => {
+                let key_type = params.string("type")?.filter(|t| t != "aes256-gcm96");
+                transit.create_key(&name, key_type.as_deref()).await?;
+                transit.encrypt(&name, &plaintext).await
+            }
+            other => other,
+        }
+    }
+    .await;
+    match result {
+        Ok(ciphertext) => axum::Json(envelope(json!({
+            "ciphertext": ciphertext,
+            "key_version": key_version(&ciphertext),
+        })))
+        .into_response(),
+        Err(e) => transit_error(e),
+    }
+}
+
+async fn decrypt<S>(
+    State(vault): State<VaultCtx>,
+    Extension(mount): Extension<Mount>,
+    Path((requested_mount, name)): Path<(String, String)>,
+    body: Bytes,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let transit = match resolve(&vault, &requested_mount, &mount) {
+        Ok(transit) => transit,
+        Err(response) => return *response,
+    };
+    let result = async {
+        let params = Params::from_body(&body)?;
+        refuse_unsupported(&params)?;
+        let ciphertext = params
+            .string("ciphertext")?
+            .ok_or_else(|| VaultError::Invalid("missing ciphertext to decrypt".into()))?;
+        transit.decrypt(&name, ciphertext.trim()).await
+    }
+    .await;
+    match result {
+        Ok(plaintext) => {
+            axum::Json(envelope(json!({ "plaintext": BASE64.encode(&plaintext) }))).into_response()
+        }
+        Err(e) => transit_error(e),
+    }
+}
+
+/// assay transit keys are not derived and the facade encrypts one value
+/// per call; saying so beats silently ignoring a field that changes the
+/// meaning of the request.
+fn refuse_unsupported(params: &Params) -> Result<(), VaultError> {
+    if params.value("batch_input").is_some() {
+        return Err(VaultError::Invalid("batch_input is not supported".into()));
+    }
+    if params.value("context").is_some() {
+        return Err(VaultError::Invalid(
+            "key derivation (context) is not supported".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// `N` from `vault:vN:…`.
+fn key_version(ciphertext: &str) -> i64 {
+    ciphertext
+        .strip_prefix("vault:v")
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(version, _)| version.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Vault reports transit failures as 400s, a missing key included.
+fn transit_error(e: VaultError) -> Response {
+    match e {
+        VaultError::NotFound => errors(StatusCode::BAD_REQUEST, &["encryption key not found"]),
+        VaultError::Crypto(msg) | VaultError::Invalid(msg) => {
+            errors(StatusCode::BAD_REQUEST, &[msg.as_str()])
+        }
+        other => vault_error(other),
+    }
+}
+
+fn resolve<'a>(
+    vault: &'a VaultCtx,
+    requested: &str,
+    mount: &Mount,
+) -> Result<&'a TransitService<DynTransitStore>, Box<Response>> {
+    if requested != mount.transit() {
+        return Err(Box::new(not_found()));
+    }
+    vault.transit.as_ref().ok_or_else(|| {
+        Box::new(errors(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &["vault transit surface not configured"],
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_key_version_is_read_off_the_ciphertext_prefix() {
+        assert_eq!(key_version("vault:v3:AAAA"), 3);
+        assert_eq!(key_version("vault:v12:AAAA"), 12);
+        assert_eq!(key_version("garbage"), 0);
+    }
+}
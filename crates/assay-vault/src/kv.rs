@@ -3,6 +3,23 @@
 //! Plan 17 §S1. Path-tree storage with version history, soft-delete,
 //! hard-destroy, undelete, and arbitrary JSON metadata per path.
 //!
+//! ## Check-and-set and retention
+//!
+//! Each path carries a policy in its metadata, set through
+//! [`KvService::put_meta`]:
+//!
+//! - `cas_required` — every write must name the version it expects to
+//!   replace ([`KvService::put_cas`]); `cas = 0` means "only if the path
+//!   has no versions yet". A mismatch is [`VaultError::Conflict`], so two
+//!   writers racing on the same path can't clobber each other silently.
+//! - `max_versions` — a write that pushes the history past this many
+//!   versions prunes the oldest ones in the same transaction. `0` keeps
+//!   everything.
+//! - `delete_version_after` — seconds after which a newly written
+//!   version reads as soft-deleted. `0` means never. The deadline is
+//!   stamped into the version's `deleted_at` at write time, so
+//!   [`KvService::undelete`] cancels it like any other deletion.
+//!
 //! ## Layering
 //!
 //! - [`KvStore`] is a trait of pure IO methods. The PG / SQLite impls
@@ -48,19 +65,58 @@ pub struct KvMeta {
     pub path: String,
     pub latest_version: i64,
     pub custom_md: Value,
+    /// Writes must carry a `cas` matching `latest_version`.
+    #[serde(default)]
+    pub cas_required: bool,
+    /// Versions kept per path; `0` keeps every version.
+    #[serde(default)]
+    pub max_versions: i64,
+    /// Seconds after which a new version is soft-deleted; `0` = never.
+    #[serde(default)]
+    pub delete_version_after: i64,
     pub created_at: f64,
     pub updated_at: f64,
 }
 
+/// A metadata write. `None` leaves the field as it is; a path with no
+/// metadata yet starts from the defaults (no CAS, unlimited versions,
+/// no automatic deletion, empty custom metadata). `custom_md` replaces
+/// the stored object outright rather than merging into it.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct KvMetaUpdate {
+    pub cas_required: Option<bool>,
+    pub max_versions: Option<i64>,
+    pub delete_version_after: Option<i64>,
+    pub custom_md: Option<Value>,
+}
+
+/// One version's lifecycle state, without its ciphertext.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct KvVersion {
+    pub version: i64,
+    pub created_at: f64,
+    pub deleted_at: Option<f64>,
+    pub destroyed: bool,
+}
+
 /// Pure-IO trait — no crypto. Implementations live in `store::postgres`
-/// / `store::sqlite`. The PUT path is one transaction: bump
-/// `kv_meta.latest_version` and INSERT into `vault.kv` with that
-/// version.
+/// / `store::sqlite`. The PUT path is one transaction: check the path's
+/// CAS policy, bump `kv_meta.latest_version`, INSERT into `vault.kv`
+/// with that version, and prune past `max_versions`.
 #[async_trait]
 pub trait KvStore: Send + Sync + 'static {
     /// Atomically allocate the next version for `path`, INSERT the row,
     /// and merge `custom_md` into the path's metadata. Returns the
     /// allocated version.
+    ///
+    /// `cas`, when set, must equal the path's current `latest_version`
+    /// (`0` for a path with no versions) or the write fails with
+    /// [`VaultError::Conflict`]; a path with `cas_required` refuses a
+    /// write without one as [`VaultError::Invalid`]. The new row's
+    /// `deleted_at` is scheduled from `delete_version_after`, and
+    /// versions older than the newest `max_versions` are deleted.
+    #[allow(clippy::too_many_arguments)]
     async fn put_row(
         &self,
         path: &str,
@@ -69,6 +125,7 @@ pub trait KvStore: Send + Sync + 'static {
         wrapped_dek: &[u8],
         kek_kid: &str,
         custom_md: &Value,
+        cas: Option<i64>,
     ) -> Result<i64>;
 
     /// Fetch a specific version. Returns None if the row never existed
@@ -88,8 +145,19 @@ pub trait KvStore: Send + Sync + 'static {
     /// Read one path's metadata.
     async fn read_meta(&self, path: &str) -> Result<Option<KvMeta>>;
 
+    /// Create or update one path's metadata. A new path starts at
+    /// `latest_version = 0`, so policy can be set before the first
+    /// write. Returns the stored metadata.
+    async fn put_meta(&self, path: &str, update: &KvMetaUpdate) -> Result<KvMeta>;
+
+    /// Every version still stored for `path`, oldest first. Pruned
+    /// versions are gone; destroyed ones are listed with `destroyed`.
+    async fn list_versions(&self, path: &str) -> Result<Vec<KvVersion>>;
+
     /// Soft-delete: set `deleted_at`. Idempotent. Returns whether a row
-    /// was modified (false = already soft-deleted or hard-destroyed).
+    /// was modified (false = already soft-deleted or hard-destroyed). A
+    /// version whose scheduled deletion is still in the future counts
+    /// as live and is deleted now.
     async fn soft_delete(&self, path: &str, version: i64, deleted_at: f64) -> Result<bool>;
 
     /// Hard-destroy: zero out the ciphertext + wrapped_dek bytes and
@@ -101,6 +169,10 @@ pub trait KvStore: Send + Sync + 'static {
     /// Reverse a soft-delete. Errors if the version was hard-destroyed
     /// — destroyed material can't be undone.
     async fn undelete(&self, path: &str, version: i64) -> Result<bool>;
+
+    /// Delete the path outright: every version row and its metadata, in
+    /// one transaction. Returns whether the path existed.
+    async fn delete_path(&self, path: &str) -> Result<bool>;
 }
 
 /// Decrypted read result.
@@ -114,6 +186,15 @@ pub struct KvRead {
     pub created_at: f64,
 }
 
+impl KvRead {
+    /// Whether this version reads as deleted now. A `deleted_at` in the
+    /// future is a scheduled deletion (`delete_version_after`) that
+    /// hasn't come due yet.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some_and(|t| t <= unix_now())
+    }
+}
+
 /// High-level KV API. Wraps a store + the live sealing state. Cheap to
 /// clone — store impls are `Arc<dyn KvStore>` underneath; `SealState`
 /// is itself an `Arc`-shared inner. Every crypto op fetches the active
@@ -149,8 +230,25 @@ impl<S: KvStore> KvService<S> {
     /// Encrypt and store a new version of `path`. Returns the allocated
     /// version. `custom_md` is merged into the path-level metadata; pass
     /// `Value::Null` or `serde_json::json!({})` to leave it untouched.
+    /// Fails on a path with `cas_required` — use [`Self::put_cas`].
     pub async fn put(&self, path: &str, plaintext: &[u8], custom_md: Value) -> Result<i64> {
+        self.put_cas(path, plaintext, custom_md, None).await
+    }
+
+    /// [`Self::put`] with check-and-set: `Some(n)` writes only while the
+    /// path's latest version is `n` (`0` = the path has no versions),
+    /// and fails with [`VaultError::Conflict`] otherwise.
+    pub async fn put_cas(
+        &self,
+        path: &str,
+        plaintext: &[u8],
+        custom_md: Value,
+        cas: Option<i64>,
+    ) -> Result<i64> {
         validate_path(path)?;
+        if cas.is_some_and(|v| v < 0) {
+            return Err(VaultError::Invalid("cas must be >= 0".into()));
+        }
         let kek = self.seal_state.require_unsealed()?;
         let dek = random_dek();
         let nonce = random_nonce();
@@ -166,6 +264,7 @@ impl<S: KvStore> KvService<S> {
                 wrapped.as_bytes(),
                 kek.kid(),
                 &custom_md,
+                cas,
             )
             .await?;
         Ok(version)
@@ -229,6 +328,31 @@ impl<S: KvStore> KvService<S> {
             .ok_or(VaultError::NotFound)
     }
 
+    /// Set a path's CAS and retention policy and/or replace its custom
+    /// metadata. Lowering `max_versions` takes effect on the next write,
+    /// which prunes down to the new limit.
+    pub async fn put_meta(&self, path: &str, update: &KvMetaUpdate) -> Result<KvMeta> {
+        validate_path(path)?;
+        if update.max_versions.is_some_and(|v| v < 0) {
+            return Err(VaultError::Invalid("max_versions must be >= 0".into()));
+        }
+        if update.delete_version_after.is_some_and(|v| v < 0) {
+            return Err(VaultError::Invalid(
+                "delete_version_after must be >= 0".into(),
+            ));
+        }
+        if update.custom_md.as_ref().is_some_and(|md| !md.is_object()) {
+            return Err(VaultError::Invalid("custom_md must be an object".into()));
+        }
+        self.store.put_meta(path, update).await
+    }
+
+    /// Lifecycle state of every stored version of `path`, oldest first.
+    pub async fn versions(&self, path: &str) -> Result<Vec<KvVersion>> {
+        validate_path(path)?;
+        self.store.list_versions(path).await
+    }
+
     pub async fn soft_delete(&self, path: &str, version: i64) -> Result<()> {
         validate_path(path)?;
         let now = unix_now();
@@ -256,6 +380,35 @@ impl<S: KvStore> KvService<S> {
         }
         Ok(())
     }
+
+    /// Remove `path` with every version and its metadata — unlike
+    /// [`Self::destroy`], nothing is left to say it ever existed.
+    pub async fn delete_path(&self, path: &str) -> Result<()> {
+        validate_path(path)?;
+        if !self.store.delete_path(path).await? {
+            return Err(VaultError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+/// The check-and-set rule both stores apply inside the write
+/// transaction, against the path's `latest_version` (`0` for a path
+/// with no metadata yet).
+pub(crate) fn check_cas(latest_version: i64, cas_required: bool, cas: Option<i64>) -> Result<()> {
+    match cas {
+        None if cas_required => Err(VaultError::Invalid(
+            "check-and-set parameter required for this path".into(),
+        )),
+        Some(expected) if expected != latest_version => Err(cas_mismatch(latest_version)),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn cas_mismatch(latest_version: i64) -> VaultError {
+    VaultError::Conflict(format!(
+        "check-and-set parameter did not match the current version ({latest_version})"
+    ))
 }
 
 /// AAD bound into the AEAD for every KV row. Path becomes part of the
@@ -306,6 +459,25 @@ mod tests {
         assert!(a.starts_with(b"vault.kv:"));
     }
 
+    #[test]
+    fn cas_matches_the_latest_version_and_zero_means_absent() {
+        assert!(check_cas(0, false, Some(0)).is_ok());
+        assert!(check_cas(3, false, Some(3)).is_ok());
+        assert!(check_cas(3, false, None).is_ok());
+        assert!(matches!(
+            check_cas(3, false, Some(0)),
+            Err(VaultError::Conflict(_))
+        ));
+        assert!(matches!(
+            check_cas(0, false, Some(1)),
+            Err(VaultError::Conflict(_))
+        ));
+        assert!(matches!(
+            check_cas(3, true, None),
+            Err(VaultError::Invalid(_))
+        ));
+    }
+
     #[test]
     fn validate_path_rejects_obvious_garbage() {
         assert!(validate_path("").is_err());
@@ -13,15 +13,17 @@
 //! | [`ctx::VaultCtx`]        | —        | Composed state — engine plugs it into [`assay_engine`]      |
 //! | [`error::VaultError`]    | —        | Top-level error → HTTP / Lua mapping                        |
 //! | `kv` (Phase 1)           | S1       | KV v2 — versioned, server-decryptable ops secrets           |
-//! | `transit` (Phase 1)      | S2       | Encrypt / decrypt without exposing key material             |
+//! | `transit` (Phase 1)      | S2       | Encrypt / sign / HMAC without exposing key material         |
+//! | `pki`                    | —        | Internal CA — roles, issuance, CRL + OCSP                   |
+//! | `ssh`                    | —        | SSH CA — signs user + host certificates under roles         |
 //! | `dynamic` (Phase 5)      | S3       | Short-lived service credentials (PG / AWS / GCP / K8s)      |
 //! | `collections` (Phase 3)  | S4       | Bitwarden-aligned shared collections + items + folders      |
 //! | `personal_vault` (P3)    | S4       | Per-user personal vault (auto-created on signup)            |
 //! | `share` (Phase 4)        | S5       | Biscuit-attenuated share links, server-revocable            |
 //! | `bitwarden_compat` (P7)  | S6       | BW-protocol shim — stock BW clients work as front-ends      |
-//! | `hashicorp_compat`       | —        | Vault / OpenBao KV2 read facade — ESO + ansible + curl      |
+//! | `hashicorp_compat`       | —        | Vault / OpenBao facade — KV2, transit, AppRole + k8s logins |
 //! | `sealing` (Phase 2)      | S7       | Master KEK protection (Shamir / cloud KMS / HSM)            |
-//! | `audit` (Phase 2)        | S8       | Forward audit events to syslog / S3 / webhook               |
+//! | [`audit`] (Phase 2)      | S8       | Audit events; forwarding to syslog / S3 / webhook           |
 //! | `ha` (Phase 6)           | S9       | Leader-lease tightening for sub-10s failover                |
 //!
 //! ## Storage
@@ -63,8 +65,6 @@ pub mod bitwarden_compat;
 #[cfg(feature = "vault-hashicorp-compat")]
 pub mod hashicorp_compat;
 
-#[cfg(feature = "vault-collections")]
-#[cfg(feature = "vault-audit-forwarding")]
 pub mod audit;
 
 #[cfg(feature = "vault-kv")]
@@ -73,6 +73,12 @@ pub mod kv;
 #[cfg(feature = "vault-transit")]
 pub mod transit;
 
+#[cfg(feature = "vault-pki")]
+pub mod pki;
+
+#[cfg(feature = "vault-ssh")]
+pub mod ssh;
+
 #[cfg(feature = "vault-collections")]
 pub mod personal_vault;
 
@@ -102,10 +108,17 @@ pub use crypto::KekHandle;
 pub use ctx::VaultCtx;
 pub use error::{Result, VaultError};
 #[cfg(feature = "vault-kv")]
-pub use kv::{KvMeta, KvRead, KvRow, KvService, KvStore};
+pub use kv::{KvMeta, KvMetaUpdate, KvRead, KvRow, KvService, KvStore, KvVersion};
+#[cfg(feature = "vault-pki")]
+pub use pki::{CertRequest, IssuedCert, PkiCert, PkiRole, PkiService, PkiStore, PkiUrls};
 pub use schema::{MIGRATION_VERSION, MODULE_NAME};
+#[cfg(feature = "vault-ssh")]
+pub use ssh::{SignRequest, SignedKey, SshCert, SshRole, SshService, SshStore};
 #[cfg(feature = "vault-transit")]
-pub use transit::{TransitKey, TransitService, TransitStore, TransitVersion};
+pub use transit::{
+    DataKey, KeyType, SignatureAlgorithm, TransitKey, TransitKeyInfo, TransitPublicKey,
+    TransitService, TransitStore, TransitVersion,
+};
 
 /// Stable module name registered in `engine.modules` and used as the
 /// schema/attach name on both backends. Engine boot inserts a row with
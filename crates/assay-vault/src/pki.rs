@@ -0,0 +1,986 @@
+//! PKI — an internal certificate authority. Vault-equivalent `pki`
+//! secrets engine.
+//!
+//! The vault holds one CA, either a self-signed root or an
+//! intermediate whose certificate a parent CA signed (possibly another
+//! assay's root via [`PkiService::sign_intermediate`]). Operators
+//! define roles constraining which names, key types and lifetimes a
+//! caller may ask for; clients then either have the vault generate a
+//! key pair ([`PkiService::issue`] — the private key is returned once
+//! and never stored) or submit a CSR ([`PkiService::sign`]).
+//!
+//! Revocation is published two ways, both signed by the CA key: a CRL
+//! rebuilt on request and an OCSP responder. Together with the CA
+//! certificate they are the only PKI reads that need no credentials.
+//!
+//! ## Key custody
+//!
+//! The CA private key is sealed the same way transit keys are: a fresh
+//! DEK wrapped by the master KEK (`key_wrapped`), the PKCS#8 key AEAD-
+//! sealed under that DEK (`private_key`). KEK rotation rewraps the DEK
+//! without touching the key. Signing — issuance, CRLs, OCSP — fails
+//! with [`VaultError::Sealed`] while the vault is sealed; the CA
+//! certificate stays readable.
+//!
+//! ## Layering
+//!
+//! Mirrors transit: [`PkiStore`] is pure IO, [`PkiService`] does the
+//! crypto. Certificates are built with a small inline DER writer
+//! (`pki::der`) and transit's signing primitives.
+
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::aead::{NONCE_LEN, decrypt, encrypt, random_dek, random_nonce};
+use crate::crypto::kek::{KekHandle, WrappedDek};
+use crate::crypto::seal_state::SealState;
+use crate::error::{Result, VaultError};
+use crate::transit::{KeyType, keys};
+
+mod der;
+mod x509;
+
+pub use x509::PkiUrls;
+
+use x509::{CertStatus, CertTemplate, CrlTemplate, OcspTemplate, Profile, Signer};
+
+/// Default lifetime of a generated root.
+const ROOT_TTL_SECS: u64 = 10 * 365 * 86_400;
+/// Default lifetime of an intermediate signed by this CA.
+const INTERMEDIATE_TTL_SECS: u64 = 5 * 365 * 86_400;
+/// How long a CRL is valid before relying parties should refetch it.
+const CRL_TTL_SECS: i64 = 72 * 3600;
+/// How long an OCSP answer may be cached.
+const OCSP_TTL_SECS: i64 = 12 * 3600;
+/// Backdating of `notBefore`, so a relying party whose clock runs a
+/// little behind still accepts a fresh certificate.
+const CLOCK_SKEW_SECS: i64 = 30;
+
+/// The vault's CA as stored.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PkiCa {
+    /// `root` or `intermediate`.
+    pub kind: String,
+    pub key_type: String,
+    /// PEM; `None` while a generated intermediate waits for its parent
+    /// to sign the CSR.
+    pub certificate: Option<String>,
+    /// PEM certificates above this CA, nearest first. Empty for a root.
+    pub ca_chain: String,
+    /// `nonce || AEAD(DEK, PKCS#8)`.
+    pub private_key: Vec<u8>,
+    pub key_wrapped: Vec<u8>,
+    pub kek_kid: String,
+    /// Number of the next CRL; bumped on every revocation.
+    pub crl_number: i64,
+    pub urls: PkiUrls,
+    pub created_at: f64,
+}
+
+/// What a role lets callers ask for.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct PkiRole {
+    #[serde(skip_deserializing)]
+    pub name: String,
+    /// Domains names may be drawn from (see `allow_bare_domains` and
+    /// `allow_subdomains`).
+    pub allowed_domains: Vec<String>,
+    /// Allow a name equal to an allowed domain.
+    pub allow_bare_domains: bool,
+    /// Allow names under an allowed domain, wildcards included.
+    pub allow_subdomains: bool,
+    /// Allow `localhost` whatever the domain list says.
+    pub allow_localhost: bool,
+    /// Allow any name at all — for client-certificate identities.
+    pub allow_any_name: bool,
+    pub allow_ip_sans: bool,
+    /// `serverAuth` extended key usage.
+    pub server_flag: bool,
+    /// `clientAuth` extended key usage.
+    pub client_flag: bool,
+    /// Key type `issue` generates. `sign` accepts any CSR key type.
+    pub key_type: String,
+    pub ttl_secs: u64,
+    pub max_ttl_secs: u64,
+}
+
+impl Default for PkiRole {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            allowed_domains: Vec::new(),
+            allow_bare_domains: false,
+            allow_subdomains: false,
+            allow_localhost: false,
+            allow_any_name: false,
+            allow_ip_sans: false,
+            server_flag: true,
+            client_flag: true,
+            key_type: KeyType::EcdsaP256.as_str().to_string(),
+            ttl_secs: 86_400,
+            max_ttl_secs: 30 * 86_400,
+        }
+    }
+}
+
+/// One certificate this CA issued.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct PkiCert {
+    pub serial_number: String,
+    /// Issuing role; empty for intermediates signed by this CA.
+    pub role: String,
+    pub common_name: String,
+    pub certificate: String,
+    pub not_after: f64,
+    pub revoked_at: Option<f64>,
+    pub created_at: f64,
+}
+
+/// What a caller asks `issue` / `sign` for.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct CertRequest {
+    pub common_name: Option<String>,
+    /// Extra DNS names. For `sign`, replaces the CSR's when non-empty.
+    pub alt_names: Vec<String>,
+    pub ip_sans: Vec<String>,
+    pub ttl_secs: Option<u64>,
+}
+
+/// A freshly signed certificate and the chain to present with it.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct IssuedCert {
+    pub serial_number: String,
+    pub certificate: String,
+    pub issuing_ca: String,
+    /// `issuing_ca` followed by its own chain.
+    pub ca_chain: Vec<String>,
+    /// Unix seconds.
+    pub expiration: i64,
+    /// PKCS#8 PEM — only for `issue`, which generated the key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key_type: Option<String>,
+}
+
+/// Pure-IO trait. The PG / SQLite impls never see key material in the
+/// clear.
+#[async_trait]
+pub trait PkiStore: Send + Sync + 'static {
+    async fn get_ca(&self) -> Result<Option<PkiCa>>;
+    /// Store the CA. [`VaultError::Conflict`] when one already exists.
+    async fn create_ca(&self, ca: &PkiCa) -> Result<()>;
+    /// Install the certificate for a pending intermediate.
+    async fn set_ca_certificate(&self, certificate: &str, ca_chain: &str) -> Result<()>;
+    /// Remove the CA and every certificate record. Roles stay.
+    async fn delete_ca(&self) -> Result<bool>;
+    async fn set_urls(&self, urls: &PkiUrls) -> Result<()>;
+
+    /// Insert or replace a role.
+    async fn put_role(&self, role: &PkiRole) -> Result<()>;
+    async fn get_role(&self, name: &str) -> Result<Option<PkiRole>>;
+    async fn list_roles(&self) -> Result<Vec<PkiRole>>;
+    async fn delete_role(&self, name: &str) -> Result<bool>;
+
+    async fn insert_cert(&self, cert: &PkiCert) -> Result<()>;
+    async fn get_cert(&self, serial_number: &str) -> Result<Option<PkiCert>>;
+    async fn list_certs(&self) -> Result<Vec<PkiCert>>;
+    async fn list_revoked(&self) -> Result<Vec<PkiCert>>;
+    /// Mark a certificate revoked and bump the CA's CRL number in one
+    /// transaction. `false` when the serial is unknown or already
+    /// revoked.
+    async fn revoke_cert(&self, serial_number: &str, revoked_at: f64) -> Result<bool>;
+}
+
+/// High-level PKI API. Cheap to clone.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct PkiService<S: PkiStore> {
+    store: S,
+    seal_state: SealState,
+}
+
+impl<S: PkiStore> PkiService<S> {
+    pub fn new(store: S, seal_state: SealState) -> Self {
+        Self { store, seal_state }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Generate a key and a self-signed root certificate for it.
+    /// Returns the certificate PEM.
+    pub async fn generate_root(
+        &self,
+        common_name: &str,
+        key_type: Option<&str>,
+        ttl_secs: Option<u64>,
+    ) -> Result<String> {
+        let kek = self.seal_state.require_unsealed()?;
+        self.require_no_ca().await?;
+        let key_type = ca_key_type(key_type)?;
+        let pair = generate(key_type).await?;
+        let spki = der::pem_decode("PUBLIC KEY", &pair.public_pem)?;
+        let name = x509::name_with_common_name(common_name);
+        let now = Utc::now();
+        let signer = Signer {
+            key_type,
+            private_der: pair.private_der.clone(),
+        };
+        let cert = x509::build_certificate(
+            &signer,
+            &CertTemplate {
+                serial: &random_serial(),
+                issuer: &name,
+                subject: &name,
+                spki: &spki,
+                not_before: now - Duration::seconds(CLOCK_SKEW_SECS),
+                not_after: now + secs(ttl_secs.unwrap_or(ROOT_TTL_SECS))?,
+                profile: Profile::Ca,
+                dns_names: &[],
+                ip_addresses: &[],
+                authority_key_id: None,
+                urls: &PkiUrls::default(),
+            },
+        )?;
+        let certificate = der::pem_encode("CERTIFICATE", &cert);
+        let ca = seal_ca(
+            &kek,
+            "root",
+            key_type,
+            Some(&certificate),
+            "",
+            &pair.private_der,
+        )?;
+        self.store.create_ca(&ca).await?;
+        Ok(certificate)
+    }
+
+    /// Generate a key for an intermediate CA and return a CSR for the
+    /// parent to sign. Finish with [`Self::set_signed_intermediate`].
+    pub async fn generate_intermediate(
+        &self,
+        common_name: &str,
+        key_type: Option<&str>,
+    ) -> Result<String> {
+        let kek = self.seal_state.require_unsealed()?;
+        self.require_no_ca().await?;
+        let key_type = ca_key_type(key_type)?;
+        let pair = generate(key_type).await?;
+        let spki = der::pem_decode("PUBLIC KEY", &pair.public_pem)?;
+        let signer = Signer {
+            key_type,
+            private_der: pair.private_der.clone(),
+        };
+        let csr = x509::build_csr(&signer, &x509::name_with_common_name(common_name), &spki)?;
+        let ca = seal_ca(&kek, "intermediate", key_type, None, "", &pair.private_der)?;
+        self.store.create_ca(&ca).await?;
+        Ok(der::pem_encode("CERTIFICATE REQUEST", &csr))
+    }
+
+    /// Install the certificate a parent CA issued for the pending
+    /// intermediate. `bundle` is the certificate PEM, optionally
+    /// followed by the parent's chain.
+    pub async fn set_signed_intermediate(&self, bundle: &str) -> Result<()> {
+        let ca = self.ca().await?;
+        if ca.certificate.is_some() {
+            return Err(VaultError::Conflict(
+                "the CA already has a certificate".into(),
+            ));
+        }
+        let mut certs = certificates(bundle)?.into_iter();
+        let cert = certs
+            .next()
+            .ok_or_else(|| VaultError::Invalid("no CERTIFICATE PEM block".into()))?;
+        let info = x509::parse_certificate(&cert)?;
+        if self.ca_spki(&ca)? != info.spki {
+            return Err(VaultError::Invalid(
+                "certificate is not for the pending intermediate's key".into(),
+            ));
+        }
+        let chain: String = certs
+            .map(|der| der::pem_encode("CERTIFICATE", &der))
+            .collect();
+        self.store
+            .set_ca_certificate(&der::pem_encode("CERTIFICATE", &cert), &chain)
+            .await
+    }
+
+    /// Import an existing CA: a PEM bundle holding a PKCS#8 private key
+    /// and its certificate, optionally followed by the certificates
+    /// above it.
+    pub async fn import_ca(&self, bundle: &str) -> Result<()> {
+        let kek = self.seal_state.require_unsealed()?;
+        self.require_no_ca().await?;
+        let private_der = der::pem_decode("PRIVATE KEY", bundle)?;
+        let (key_type, public_pem) = keys::inspect_private(&private_der)?;
+        let spki = der::pem_decode("PUBLIC KEY", &public_pem)?;
+        let mut cert = None;
+        let mut chain = String::new();
+        for der_bytes in certificates(bundle)? {
+            if cert.is_none() && x509::parse_certificate(&der_bytes)?.spki == spki {
+                cert = Some(der_bytes);
+            } else {
+                chain.push_str(&der::pem_encode("CERTIFICATE", &der_bytes));
+            }
+        }
+        let cert = cert.ok_or_else(|| {
+            VaultError::Invalid("no certificate in the bundle matches the private key".into())
+        })?;
+        let kind = if chain.is_empty() {
+            "root"
+        } else {
+            "intermediate"
+        };
+        let certificate = der::pem_encode("CERTIFICATE", &cert);
+        let ca = seal_ca(
+            &kek,
+            kind,
+            key_type,
+            Some(&certificate),
+            &chain,
+            &private_der,
+        )?;
+        self.store.create_ca(&ca).await
+    }
+
+    /// Drop the CA and its issuance records, so a new one can be
+    /// generated or imported.
+    pub async fn delete_ca(&self) -> Result<()> {
+        if !self.store.delete_ca().await? {
+            return Err(VaultError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The CA certificate as PEM. Readable while sealed.
+    pub async fn ca_pem(&self) -> Result<String> {
+        self.ca()
+            .await?
+            .certificate
+            .ok_or_else(pending_intermediate)
+    }
+
+    pub async fn ca_der(&self) -> Result<Vec<u8>> {
+        der::pem_decode("CERTIFICATE", &self.ca_pem().await?)
+    }
+
+    pub async fn urls(&self) -> Result<PkiUrls> {
+        Ok(self.ca().await?.urls)
+    }
+
+    /// Set the URLs stamped into certificates issued from now on.
+    pub async fn set_urls(&self, urls: &PkiUrls) -> Result<()> {
+        self.ca().await?;
+        self.store.set_urls(urls).await
+    }
+
+    /// Sign an intermediate CA's CSR with this CA. The CSR's subject is
+    /// kept; `common_name` overrides it when given.
+    pub async fn sign_intermediate(
+        &self,
+        csr_pem: &str,
+        common_name: Option<&str>,
+        ttl_secs: Option<u64>,
+    ) -> Result<IssuedCert> {
+        let csr = x509::parse_csr(&der::pem_decode("CERTIFICATE REQUEST", csr_pem)?)?;
+        let subject = match common_name {
+            Some(cn) => x509::name_with_common_name(cn),
+            None => csr.subject.clone(),
+        };
+        let common_name = x509::common_name(&subject)?.unwrap_or_default();
+        self.sign_certificate(
+            "",
+            &common_name,
+            &subject,
+            &csr.spki,
+            ttl_secs.unwrap_or(INTERMEDIATE_TTL_SECS),
+            Profile::Ca,
+            &[],
+            &[],
+        )
+        .await
+    }
+
+    /// Create or replace a role.
+    pub async fn put_role(&self, name: &str, mut role: PkiRole) -> Result<()> {
+        validate_role_name(name)?;
+        KeyType::parse(&role.key_type).and_then(leaf_key_type)?;
+        if role.ttl_secs == 0 || role.max_ttl_secs < role.ttl_secs {
+            return Err(VaultError::Invalid(
+                "role needs 0 < ttl_secs <= max_ttl_secs".into(),
+            ));
+        }
+        role.name = name.to_string();
+        self.store.put_role(&role).await
+    }
+
+    pub async fn get_role(&self, name: &str) -> Result<PkiRole> {
+        self.store.get_role(name).await?.ok_or(VaultError::NotFound)
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<PkiRole>> {
+        self.store.list_roles().await
+    }
+
+    pub async fn delete_role(&self, name: &str) -> Result<()> {
+        if !self.store.delete_role(name).await? {
+            return Err(VaultError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Generate a key pair of the role's key type and issue a
+    /// certificate for it. The private key is in the result and
+    /// nowhere else.
+    pub async fn issue(&self, role: &str, request: &CertRequest) -> Result<IssuedCert> {
+        let role = self.get_role(role).await?;
+        let common_name = request
+            .common_name
+            .as_deref()
+            .ok_or_else(|| VaultError::Invalid("common_name is required".into()))?;
+        let key_type = KeyType::parse(&role.key_type)?;
+        let (dns_names, ip_addresses) =
+            check_names(&role, common_name, &request.alt_names, &request.ip_sans)?;
+        let ttl = role_ttl(&role, request.ttl_secs)?;
+        self.seal_state.require_unsealed()?;
+        let pair = generate(key_type).await?;
+        let spki = der::pem_decode("PUBLIC KEY", &pair.public_pem)?;
+        let mut issued = self
+            .sign_certificate(
+                &role.name,
+                common_name,
+                &x509::name_with_common_name(common_name),
+                &spki,
+                ttl,
+                leaf_profile(&role, key_type),
+                &dns_names,
+                &ip_addresses,
+            )
+            .await?;
+        issued.private_key = Some(der::pem_encode("PRIVATE KEY", &pair.private_der));
+        issued.private_key_type = Some(key_type.as_str().to_string());
+        Ok(issued)
+    }
+
+    /// Issue a certificate for the key in a CSR. The CSR's signature is
+    /// checked (proof of possession); its common name and DNS / IP
+    /// names are used unless `request` names its own, and either way
+    /// must pass the role.
+    pub async fn sign(
+        &self,
+        role: &str,
+        csr_pem: &str,
+        request: &CertRequest,
+    ) -> Result<IssuedCert> {
+        let role = self.get_role(role).await?;
+        let csr = x509::parse_csr(&der::pem_decode("CERTIFICATE REQUEST", csr_pem)?)?;
+        let common_name = request
+            .common_name
+            .clone()
+            .or(csr.common_name.clone())
+            .ok_or_else(|| VaultError::Invalid("common_name is required".into()))?;
+        let alt_names = if request.alt_names.is_empty() {
+            csr.dns_names.clone()
+        } else {
+            request.alt_names.clone()
+        };
+        let ip_sans = if request.ip_sans.is_empty() {
+            csr.ip_addresses.iter().map(IpAddr::to_string).collect()
+        } else {
+            request.ip_sans.clone()
+        };
+        let (dns_names, ip_addresses) = check_names(&role, &common_name, &alt_names, &ip_sans)?;
+        let ttl = role_ttl(&role, request.ttl_secs)?;
+        let key_type = x509::spki_key_type(&csr.spki)?;
+        self.sign_certificate(
+            &role.name,
+            &common_name,
+            &x509::name_with_common_name(&common_name),
+            &csr.spki,
+            ttl,
+            leaf_profile(&role, key_type),
+            &dns_names,
+            &ip_addresses,
+        )
+        .await
+    }
+
+    /// Revoke a certificate. Returns the revocation time; revoking an
+    /// already-revoked certificate returns the original time.
+    pub async fn revoke(&self, serial_number: &str) -> Result<f64> {
+        let serial_number = canonical_serial(serial_number)?;
+        let cert = self
+            .store
+            .get_cert(&serial_number)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        if let Some(at) = cert.revoked_at {
+            return Ok(at);
+        }
+        let now = Utc::now().timestamp() as f64;
+        self.store.revoke_cert(&serial_number, now).await?;
+        Ok(now)
+    }
+
+    pub async fn read_cert(&self, serial_number: &str) -> Result<PkiCert> {
+        self.store
+            .get_cert(&canonical_serial(serial_number)?)
+            .await?
+            .ok_or(VaultError::NotFound)
+    }
+
+    pub async fn list_certs(&self) -> Result<Vec<PkiCert>> {
+        self.store.list_certs().await
+    }
+
+    /// A freshly signed CRL (DER) listing every revoked certificate
+    /// that hasn't expired.
+    pub async fn crl(&self) -> Result<Vec<u8>> {
+        let ca = self.ca().await?;
+        let cert = ca.certificate.as_deref().ok_or_else(pending_intermediate)?;
+        let info = x509::parse_certificate(&der::pem_decode("CERTIFICATE", cert)?)?;
+        let signer = self.signer(&ca)?;
+        let now = Utc::now();
+        let mut revoked = Vec::new();
+        for c in self.store.list_revoked().await? {
+            if c.not_after < now.timestamp() as f64 {
+                continue;
+            }
+            let at =
+                DateTime::from_timestamp(c.revoked_at.unwrap_or_default() as i64, 0).unwrap_or(now);
+            revoked.push((x509::parse_serial_hex(&c.serial_number)?, at));
+        }
+        x509::build_crl(
+            &signer,
+            &CrlTemplate {
+                issuer: &info.subject,
+                authority_key_id: &x509::key_id(&info.spki)?,
+                crl_number: ca.crl_number.max(1) as u64,
+                this_update: now,
+                next_update: now + Duration::seconds(CRL_TTL_SECS),
+                revoked: &revoked,
+            },
+        )
+    }
+
+    pub async fn crl_pem(&self) -> Result<String> {
+        Ok(der::pem_encode("X509 CRL", &self.crl().await?))
+    }
+
+    /// Answer a DER OCSP request. Problems with the request or the
+    /// vault's state come back as OCSP error responses, as RFC 6960
+    /// requires, so this never fails.
+    pub async fn ocsp(&self, request: &[u8]) -> Vec<u8> {
+        let Ok(request) = x509::parse_ocsp_request(request) else {
+            return x509::ocsp_error(1);
+        };
+        match self.ocsp_response(&request).await {
+            Ok(response) => response,
+            Err(VaultError::NotFound) => x509::ocsp_error(6),
+            Err(e) => {
+                tracing::warn!(error = %e, "pki: OCSP response failed");
+                x509::ocsp_error(2)
+            }
+        }
+    }
+
+    async fn ocsp_response(&self, request: &x509::OcspRequest) -> Result<Vec<u8>> {
+        let ca = self.ca().await?;
+        let cert = ca.certificate.as_deref().ok_or(VaultError::NotFound)?;
+        let info = x509::parse_certificate(&der::pem_decode("CERTIFICATE", cert)?)?;
+        let signer = self.signer(&ca)?;
+        let mut responses = Vec::with_capacity(request.cert_ids.len());
+        for id in &request.cert_ids {
+            let status = if !id.names_issuer(&info.subject, &info.spki)? {
+                CertStatus::Unknown
+            } else {
+                match self.store.get_cert(&x509::serial_hex(&id.serial)).await? {
+                    None => CertStatus::Unknown,
+                    Some(PkiCert {
+                        revoked_at: Some(at),
+                        ..
+                    }) => CertStatus::Revoked(
+                        DateTime::from_timestamp(at as i64, 0).unwrap_or_else(Utc::now),
+                    ),
+                    Some(_) => CertStatus::Good,
+                }
+            };
+            responses.push((id, status));
+        }
+        let now = Utc::now();
+        x509::build_ocsp_response(
+            &signer,
+            &OcspTemplate {
+                responder_key_hash: &x509::key_id(&info.spki)?,
+                produced_at: now,
+                next_update: now + Duration::seconds(OCSP_TTL_SECS),
+                responses: &responses,
+                nonce: request.nonce.as_deref(),
+            },
+        )
+    }
+
+    /// Sign a certificate with the CA and record it.
+    #[allow(clippy::too_many_arguments)]
+    async fn sign_certificate(
+        &self,
+        role: &str,
+        common_name: &str,
+        subject: &[u8],
+        spki: &[u8],
+        ttl_secs: u64,
+        profile: Profile,
+        dns_names: &[String],
+        ip_addresses: &[IpAddr],
+    ) -> Result<IssuedCert> {
+        let ca = self.ca().await?;
+        let ca_cert = ca.certificate.as_deref().ok_or_else(pending_intermediate)?;
+        let ca_info = x509::parse_certificate(&der::pem_decode("CERTIFICATE", ca_cert)?)?;
+        let signer = self.signer(&ca)?;
+        let now = Utc::now();
+        // Nothing outlives the CA that vouches for it.
+        let not_after = (now + secs(ttl_secs)?).min(ca_info.not_after);
+        let serial = random_serial();
+        let cert = x509::build_certificate(
+            &signer,
+            &CertTemplate {
+                serial: &serial,
+                issuer: &ca_info.subject,
+                subject,
+                spki,
+                not_before: now - Duration::seconds(CLOCK_SKEW_SECS),
+                not_after,
+                profile,
+                dns_names,
+                ip_addresses,
+                authority_key_id: Some(&x509::key_id(&ca_info.spki)?),
+                urls: &ca.urls,
+            },
+        )?;
+        let certificate = der::pem_encode("CERTIFICATE", &cert);
+        let serial_number = x509::serial_hex(&serial);
+        self.store
+            .insert_cert(&PkiCert {
+                serial_number: serial_number.clone(),
+                role: role.to_string(),
+                common_name: common_name.to_string(),
+                certificate: certificate.clone(),
+                not_after: not_after.timestamp() as f64,
+                revoked_at: None,
+                created_at: now.timestamp() as f64,
+            })
+            .await?;
+        let mut ca_chain = vec![ca_cert.to_string()];
+        ca_chain.extend(
+            certificates(&ca.ca_chain)?
+                .into_iter()
+                .map(|der_bytes| der::pem_encode("CERTIFICATE", &der_bytes)),
+        );
+        Ok(IssuedCert {
+            serial_number,
+            certificate,
+            issuing_ca: ca_cert.to_string(),
+            ca_chain,
+            expiration: not_after.timestamp(),
+            private_key: None,
+            private_key_type: None,
+        })
+    }
+
+    async fn ca(&self) -> Result<PkiCa> {
+        self.store.get_ca().await?.ok_or(VaultError::NotFound)
+    }
+
+    async fn require_no_ca(&self) -> Result<()> {
+        if self.store.get_ca().await?.is_some() {
+            return Err(VaultError::Conflict(
+                "a CA is already configured; delete it first".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Unseal the CA private key for one signing call.
+    fn signer(&self, ca: &PkiCa) -> Result<Signer> {
+        let kek = self.seal_state.require_unsealed()?;
+        if ca.kek_kid != kek.kid() {
+            return Err(VaultError::Crypto(format!(
+                "PKI CA key wrapped with KEK {} but the active KEK is {}",
+                ca.kek_kid,
+                kek.kid()
+            )));
+        }
+        let dek = kek.unwrap_dek(&WrappedDek::from_bytes(ca.key_wrapped.clone()))?;
+        if ca.private_key.len() < NONCE_LEN {
+            return Err(VaultError::Crypto("sealed CA key truncated".into()));
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&ca.private_key[..NONCE_LEN]);
+        let private_der = decrypt(&dek, &nonce, PRIVATE_AAD, &ca.private_key[NONCE_LEN..])?;
+        Ok(Signer {
+            key_type: KeyType::parse(&ca.key_type)?,
+            private_der,
+        })
+    }
+
+    fn ca_spki(&self, ca: &PkiCa) -> Result<Vec<u8>> {
+        let signer = self.signer(ca)?;
+        let (_, public_pem) = keys::inspect_private(&signer.private_der)?;
+        der::pem_decode("PUBLIC KEY", &public_pem)
+    }
+}
+
+/// AAD for the sealed CA key.
+const PRIVATE_AAD: &[u8] = b"vault.pki.ca";
+
+fn seal_ca(
+    kek: &KekHandle,
+    kind: &str,
+    key_type: KeyType,
+    certificate: Option<&str>,
+    ca_chain: &str,
+    private_der: &[u8],
+) -> Result<PkiCa> {
+    let dek = random_dek();
+    let key_wrapped = kek.wrap_dek(&dek)?;
+    let nonce = random_nonce();
+    let sealed = encrypt(&dek, &nonce, PRIVATE_AAD, private_der)?;
+    let mut private_key = Vec::with_capacity(NONCE_LEN + sealed.len());
+    private_key.extend_from_slice(&nonce);
+    private_key.extend_from_slice(&sealed);
+    Ok(PkiCa {
+        kind: kind.to_string(),
+        key_type: key_type.as_str().to_string(),
+        certificate: certificate.map(str::to_string),
+        ca_chain: ca_chain.to_string(),
+        private_key,
+        key_wrapped: key_wrapped.into_bytes(),
+        kek_kid: kek.kid().to_string(),
+        crl_number: 1,
+        urls: PkiUrls::default(),
+        created_at: Utc::now().timestamp() as f64,
+    })
+}
+
+async fn generate(key_type: KeyType) -> Result<keys::KeyPair> {
+    tokio::task::spawn_blocking(move || keys::generate(key_type))
+        .await
+        .map_err(|e| VaultError::Crypto(format!("pki keygen task: {e}")))?
+}
+
+fn ca_key_type(key_type: Option<&str>) -> Result<KeyType> {
+    leaf_key_type(KeyType::parse(key_type.unwrap_or("ecdsa-p256"))?)
+}
+
+fn leaf_key_type(key_type: KeyType) -> Result<KeyType> {
+    if !key_type.supports_signing() {
+        return Err(VaultError::Invalid(format!(
+            "{} is not a certificate key type; expected ed25519, ecdsa-p256, rsa-2048 or rsa-4096",
+            key_type.as_str()
+        )));
+    }
+    Ok(key_type)
+}
+
+fn leaf_profile(role: &PkiRole, key_type: KeyType) -> Profile {
+    Profile::Leaf {
+        server: role.server_flag,
+        client: role.client_flag,
+        key_encipherment: matches!(key_type, KeyType::Rsa2048 | KeyType::Rsa4096),
+    }
+}
+
+fn role_ttl(role: &PkiRole, requested: Option<u64>) -> Result<u64> {
+    match requested {
+        None => Ok(role.ttl_secs),
+        Some(0) => Err(VaultError::Invalid("ttl_secs must be positive".into())),
+        Some(ttl) if ttl > role.max_ttl_secs => Err(VaultError::Invalid(format!(
+            "ttl_secs {ttl} exceeds role '{}' max_ttl_secs {}",
+            role.name, role.max_ttl_secs
+        ))),
+        Some(ttl) => Ok(ttl),
+    }
+}
+
+/// Check the common name and every SAN against the role. The common
+/// name joins the SANs (as a DNS name, or an IP when it parses as one)
+/// because TLS clients match on SANs only.
+fn check_names(
+    role: &PkiRole,
+    common_name: &str,
+    alt_names: &[String],
+    ip_sans: &[String],
+) -> Result<(Vec<String>, Vec<IpAddr>)> {
+    let mut dns_names = Vec::new();
+    let mut ip_addresses = Vec::new();
+    let cn_ip = common_name.parse::<IpAddr>().ok();
+    let names = std::iter::once(common_name)
+        .filter(|_| cn_ip.is_none())
+        .chain(alt_names.iter().map(String::as_str));
+    for name in names {
+        if !name_allowed(role, name) {
+            return Err(VaultError::Forbidden);
+        }
+        if !dns_names.iter().any(|n| n == name) {
+            dns_names.push(name.to_string());
+        }
+    }
+    let ips = ip_sans
+        .iter()
+        .map(|ip| {
+            ip.parse::<IpAddr>()
+                .map_err(|_| VaultError::Invalid(format!("'{ip}' is not an IP address")))
+        })
+        .chain(cn_ip.map(Ok));
+    for ip in ips {
+        let ip = ip?;
+        if !role.allow_ip_sans {
+            return Err(VaultError::Forbidden);
+        }
+        if !ip_addresses.contains(&ip) {
+            ip_addresses.push(ip);
+        }
+    }
+    Ok((dns_names, ip_addresses))
+}
+
+fn name_allowed(role: &PkiRole, name: &str) -> bool {
+    if role.allow_any_name {
+        return true;
+    }
+    if name.is_empty() || name.chars().any(|c| c.is_whitespace()) {
+        return false;
+    }
+    let name = name.to_ascii_lowercase();
+    if role.allow_localhost && name == "localhost" {
+        return true;
+    }
+    let (wildcard, base) = match name.strip_prefix("*.") {
+        Some(rest) => (true, rest),
+        None => (false, name.as_str()),
+    };
+    role.allowed_domains.iter().any(|domain| {
+        let domain = domain.to_ascii_lowercase();
+        let bare = base == domain;
+        let sub = base.ends_with(&format!(".{domain}"));
+        if wildcard {
+            role.allow_subdomains && (bare || sub)
+        } else {
+            (role.allow_bare_domains && bare) || (role.allow_subdomains && sub)
+        }
+    })
+}
+
+/// 16 random bytes, high bit clear so the INTEGER needs no sign byte
+/// and the hex form round-trips exactly.
+fn random_serial() -> Vec<u8> {
+    let mut serial = vec![0u8; 16];
+    rand::rng().fill_bytes(&mut serial);
+    serial[0] = (serial[0] & 0x7f) | 0x01;
+    serial
+}
+
+fn canonical_serial(serial_number: &str) -> Result<String> {
+    Ok(x509::serial_hex(&x509::parse_serial_hex(serial_number)?))
+}
+
+fn certificates(bundle: &str) -> Result<Vec<Vec<u8>>> {
+    Ok(der::pem_blocks(bundle)?
+        .into_iter()
+        .filter(|(label, _)| label == "CERTIFICATE")
+        .map(|(_, der_bytes)| der_bytes)
+        .collect())
+}
+
+fn secs(n: u64) -> Result<Duration> {
+    i64::try_from(n)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .ok_or_else(|| VaultError::Invalid(format!("ttl {n}s out of range")))
+}
+
+fn pending_intermediate() -> VaultError {
+    VaultError::Invalid("the intermediate CA is waiting for its signed certificate".into())
+}
+
+fn validate_role_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > 128
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(VaultError::Invalid(
+            "PKI role name: 1-128 chars of [A-Za-z0-9._-]".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(domains: &[&str]) -> PkiRole {
+        PkiRole {
+            allowed_domains: domains.iter().map(|d| d.to_string()).collect(),
+            ..PkiRole::default()
+        }
+    }
+
+    #[test]
+    fn names_follow_the_role_flags() {
+        let mut r = role(&["svc.internal"]);
+        assert!(!name_allowed(&r, "svc.internal"));
+        assert!(!name_allowed(&r, "api.svc.internal"));
+        r.allow_bare_domains = true;
+        assert!(name_allowed(&r, "svc.internal"));
+        assert!(!name_allowed(&r, "api.svc.internal"));
+        r.allow_subdomains = true;
+        assert!(name_allowed(&r, "API.svc.internal"));
+        assert!(name_allowed(&r, "*.svc.internal"));
+        assert!(!name_allowed(&r, "evilsvc.internal"));
+        assert!(!name_allowed(&r, "localhost"));
+        r.allow_localhost = true;
+        assert!(name_allowed(&r, "localhost"));
+    }
+
+    #[test]
+    fn common_name_joins_the_sans() {
+        let mut r = role(&["svc.internal"]);
+        r.allow_subdomains = true;
+        let (dns, ips) =
+            check_names(&r, "api.svc.internal", &["db.svc.internal".into()], &[]).unwrap();
+        assert_eq!(dns, vec!["api.svc.internal", "db.svc.internal"]);
+        assert!(ips.is_empty());
+        assert!(matches!(
+            check_names(&r, "api.svc.internal", &[], &["10.0.0.1".into()]),
+            Err(VaultError::Forbidden)
+        ));
+        r.allow_ip_sans = true;
+        let (_, ips) = check_names(&r, "api.svc.internal", &[], &["10.0.0.1".into()]).unwrap();
+        assert_eq!(ips, vec!["10.0.0.1".parse::<IpAddr>().unwrap()]);
+    }
+
+    #[test]
+    fn serials_round_trip_through_hex() {
+        let serial = random_serial();
+        assert!(serial[0] & 0x80 == 0);
+        let hex = x509::serial_hex(&serial);
+        assert_eq!(x509::parse_serial_hex(&hex).unwrap(), serial);
+        assert_eq!(canonical_serial(&hex.to_uppercase()).unwrap(), hex);
+    }
+}
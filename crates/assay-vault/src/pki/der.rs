@@ -0,0 +1,454 @@
+//! Just enough DER for X.509 — a TLV writer for the structures PKI
+//! builds (certificates, CSRs, CRLs, OCSP responses) and a cursor that
+//! walks the ones it reads, handing back raw slices so signed portions
+//! can be verified byte-for-byte.
+//!
+//! Written inline rather than pulling an ASN.1 framework: every
+//! structure here is a fixed shape from RFC 5280 / 2986 / 6960, and
+//! only single-byte tags and definite lengths occur in DER.
+
+use chrono::{DateTime, Datelike, Utc};
+
+use crate::error::{Result, VaultError};
+
+pub(crate) const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const NULL: u8 = 0x05;
+pub(crate) const OID: u8 = 0x06;
+pub(crate) const ENUMERATED: u8 = 0x0a;
+pub(crate) const UTF8_STRING: u8 = 0x0c;
+pub(crate) const PRINTABLE_STRING: u8 = 0x13;
+pub(crate) const IA5_STRING: u8 = 0x16;
+pub(crate) const UTC_TIME: u8 = 0x17;
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+
+/// Context-specific tag `[n]`, primitive (IMPLICIT over a primitive).
+pub(crate) const fn context(n: u8) -> u8 {
+    0x80 | n
+}
+
+/// Context-specific tag `[n]`, constructed (EXPLICIT, or IMPLICIT over
+/// a SEQUENCE / SET).
+pub(crate) const fn context_constructed(n: u8) -> u8 {
+    0xa0 | n
+}
+
+// ── writer ───────────────────────────────────────────────────────────
+
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 6);
+    out.push(tag);
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// A constructed value over already-encoded children.
+pub(crate) fn constructed<I, T>(tag: u8, children: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut content = Vec::new();
+    for child in children {
+        content.extend_from_slice(child.as_ref());
+    }
+    tlv(tag, &content)
+}
+
+pub(crate) fn sequence<I, T>(children: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    constructed(SEQUENCE, children)
+}
+
+/// A non-negative INTEGER from big-endian magnitude bytes.
+pub(crate) fn unsigned_integer(magnitude: &[u8]) -> Vec<u8> {
+    let trimmed = match magnitude.iter().position(|b| *b != 0) {
+        Some(i) => &magnitude[i..],
+        None => &[0u8][..],
+    };
+    if trimmed[0] & 0x80 != 0 {
+        let mut padded = Vec::with_capacity(trimmed.len() + 1);
+        padded.push(0);
+        padded.extend_from_slice(trimmed);
+        tlv(INTEGER, &padded)
+    } else {
+        tlv(INTEGER, trimmed)
+    }
+}
+
+pub(crate) fn small_integer(n: u64) -> Vec<u8> {
+    unsigned_integer(&n.to_be_bytes())
+}
+
+pub(crate) fn boolean(v: bool) -> Vec<u8> {
+    tlv(BOOLEAN, &[if v { 0xff } else { 0x00 }])
+}
+
+pub(crate) fn null() -> Vec<u8> {
+    tlv(NULL, &[])
+}
+
+pub(crate) fn octet_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(OCTET_STRING, bytes)
+}
+
+/// BIT STRING with no unused bits — signatures and public keys.
+pub(crate) fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    let mut content = Vec::with_capacity(bytes.len() + 1);
+    content.push(0);
+    content.extend_from_slice(bytes);
+    tlv(BIT_STRING, &content)
+}
+
+/// A named-bit BIT STRING (`KeyUsage`) of at most eight bits, with the
+/// trailing zero bits DER requires dropped.
+pub(crate) fn named_bits(bits: u8) -> Vec<u8> {
+    if bits == 0 {
+        return tlv(BIT_STRING, &[0]);
+    }
+    tlv(BIT_STRING, &[bits.trailing_zeros() as u8, bits])
+}
+
+pub(crate) fn oid(arcs: &[u64]) -> Vec<u8> {
+    let mut content = Vec::new();
+    push_base128(&mut content, arcs[0] * 40 + arcs[1]);
+    for arc in &arcs[2..] {
+        push_base128(&mut content, *arc);
+    }
+    tlv(OID, &content)
+}
+
+fn push_base128(out: &mut Vec<u8>, mut n: u64) {
+    let mut groups = vec![(n & 0x7f) as u8];
+    n >>= 7;
+    while n > 0 {
+        groups.push(0x80 | (n & 0x7f) as u8);
+        n >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+pub(crate) fn utf8_string(s: &str) -> Vec<u8> {
+    tlv(UTF8_STRING, s.as_bytes())
+}
+
+/// RFC 5280 §4.1.2.5: UTCTime through 2049, GeneralizedTime after.
+pub(crate) fn time(t: DateTime<Utc>) -> Vec<u8> {
+    if t.year() < 2050 {
+        tlv(UTC_TIME, t.format("%y%m%d%H%M%SZ").to_string().as_bytes())
+    } else {
+        generalized_time(t)
+    }
+}
+
+pub(crate) fn generalized_time(t: DateTime<Utc>) -> Vec<u8> {
+    tlv(
+        GENERALIZED_TIME,
+        t.format("%Y%m%d%H%M%SZ").to_string().as_bytes(),
+    )
+}
+
+// ── reader ───────────────────────────────────────────────────────────
+
+/// One decoded element: its tag, its content, and the whole encoding.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    pub value: &'a [u8],
+    pub raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// Walk the children of a constructed value.
+    pub fn children(&self) -> Reader<'a> {
+        Reader::new(self.value)
+    }
+
+    pub fn expect(self, tag: u8) -> Result<Self> {
+        if self.tag != tag {
+            return Err(malformed(format!(
+                "expected tag {tag:#04x}, found {:#04x}",
+                self.tag
+            )));
+        }
+        Ok(self)
+    }
+
+    /// The content of a BIT STRING with no unused bits.
+    pub fn bit_string_bytes(&self) -> Result<&'a [u8]> {
+        match self.expect(BIT_STRING)?.value.split_first() {
+            Some((0, bytes)) => Ok(bytes),
+            _ => Err(malformed("bit string with unused bits")),
+        }
+    }
+
+    /// Decode an OBJECT IDENTIFIER into its arcs.
+    pub fn oid_arcs(&self) -> Result<Vec<u64>> {
+        let content = self.expect(OID)?.value;
+        let mut arcs = Vec::new();
+        let mut n: u64 = 0;
+        for (i, b) in content.iter().enumerate() {
+            if n > u64::MAX >> 7 {
+                return Err(malformed("object identifier arc overflows"));
+            }
+            n = (n << 7) | u64::from(b & 0x7f);
+            if b & 0x80 == 0 {
+                if arcs.is_empty() {
+                    let first = (n / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(n - first * 40);
+                } else {
+                    arcs.push(n);
+                }
+                n = 0;
+            } else if i == content.len() - 1 {
+                return Err(malformed("truncated object identifier"));
+            }
+        }
+        if arcs.is_empty() {
+            return Err(malformed("empty object identifier"));
+        }
+        Ok(arcs)
+    }
+
+    /// A string of any of the types a `DirectoryString` may use.
+    pub fn text(&self) -> Result<String> {
+        match self.tag {
+            UTF8_STRING | PRINTABLE_STRING | IA5_STRING => String::from_utf8(self.value.to_vec())
+                .map_err(|_| malformed("string is not valid UTF-8")),
+            other => Err(malformed(format!("unsupported string tag {other:#04x}"))),
+        }
+    }
+}
+
+/// Cursor over a run of sibling elements.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Reader<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { rest: bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.rest.first().copied()
+    }
+
+    pub fn next(&mut self) -> Result<Tlv<'a>> {
+        let bytes = self.rest;
+        let (&tag, after_tag) = bytes
+            .split_first()
+            .ok_or_else(|| malformed("unexpected end of input"))?;
+        if tag & 0x1f == 0x1f {
+            return Err(malformed("multi-byte tags are not supported"));
+        }
+        let (&first, after_len) = after_tag
+            .split_first()
+            .ok_or_else(|| malformed("missing length"))?;
+        let (len, header) = if first < 0x80 {
+            (first as usize, 2)
+        } else {
+            let n = (first & 0x7f) as usize;
+            if n == 0 || n > 4 || after_len.len() < n {
+                return Err(malformed("unsupported length encoding"));
+            }
+            let len = after_len[..n]
+                .iter()
+                .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+            (len, 2 + n)
+        };
+        let end = header
+            .checked_add(len)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| malformed("length runs past the end of input"))?;
+        self.rest = &bytes[end..];
+        Ok(Tlv {
+            tag,
+            value: &bytes[header..end],
+            raw: &bytes[..end],
+        })
+    }
+
+    /// Read the next element, requiring `tag`.
+    pub fn expect(&mut self, tag: u8) -> Result<Tlv<'a>> {
+        self.next()?.expect(tag)
+    }
+
+    /// Read the next element only when its tag is `tag`.
+    pub fn optional(&mut self, tag: u8) -> Result<Option<Tlv<'a>>> {
+        if self.peek_tag() == Some(tag) {
+            self.next().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Parse exactly one element spanning all of `bytes`.
+pub(crate) fn parse(bytes: &[u8]) -> Result<Tlv<'_>> {
+    let mut reader = Reader::new(bytes);
+    let tlv = reader.next()?;
+    if !reader.is_empty() {
+        return Err(malformed("trailing bytes after the outer element"));
+    }
+    Ok(tlv)
+}
+
+fn malformed(what: impl std::fmt::Display) -> VaultError {
+    VaultError::Invalid(format!("malformed DER: {what}"))
+}
+
+// ── PEM ──────────────────────────────────────────────────────────────
+
+pub(crate) fn pem_encode(label: &str, der: &[u8]) -> String {
+    let b64 = data_encoding::BASE64.encode(der);
+    let mut out = format!("-----BEGIN {label}-----\n");
+    for line in b64.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {label}-----\n"));
+    out
+}
+
+/// Every PEM block in `text`, in order, as `(label, der)`.
+pub(crate) fn pem_blocks(text: &str) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        let Some(label) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|l| l.strip_suffix("-----"))
+        else {
+            continue;
+        };
+        let end = format!("-----END {label}-----");
+        let mut body = String::new();
+        loop {
+            match lines.next() {
+                Some(l) if l == end => break,
+                Some(l) => body.push_str(l),
+                None => {
+                    return Err(VaultError::Invalid(format!(
+                        "PEM block {label} not terminated"
+                    )));
+                }
+            }
+        }
+        let der = data_encoding::BASE64
+            .decode(body.as_bytes())
+            .map_err(|e| VaultError::Invalid(format!("PEM block {label}: {e}")))?;
+        blocks.push((label.to_string(), der));
+    }
+    Ok(blocks)
+}
+
+/// The single `label` block in `text`.
+pub(crate) fn pem_decode(label: &str, text: &str) -> Result<Vec<u8>> {
+    let mut matching = pem_blocks(text)?.into_iter().filter(|(l, _)| l == label);
+    match (matching.next(), matching.next()) {
+        (Some((_, der)), None) => Ok(der),
+        (None, _) => Err(VaultError::Invalid(format!("no {label} PEM block"))),
+        (Some(_), Some(_)) => Err(VaultError::Invalid(format!(
+            "more than one {label} PEM block"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lengths_use_the_short_and_long_forms() {
+        assert_eq!(tlv(OCTET_STRING, &[1, 2]), vec![0x04, 0x02, 1, 2]);
+        let long = tlv(OCTET_STRING, &[0; 300]);
+        assert_eq!(&long[..4], &[0x04, 0x82, 0x01, 0x2c]);
+        let parsed = parse(&long).unwrap();
+        assert_eq!(parsed.value.len(), 300);
+    }
+
+    #[test]
+    fn integers_are_minimal_and_positive() {
+        assert_eq!(small_integer(0), vec![0x02, 0x01, 0x00]);
+        assert_eq!(small_integer(127), vec![0x02, 0x01, 0x7f]);
+        assert_eq!(small_integer(128), vec![0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(
+            unsigned_integer(&[0, 0, 1, 0]),
+            vec![0x02, 0x02, 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn oids_round_trip() {
+        // sha256WithRSAEncryption, from RFC 4055.
+        let enc = oid(&[1, 2, 840, 113549, 1, 1, 11]);
+        assert_eq!(
+            enc,
+            vec![
+                0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b
+            ]
+        );
+        assert_eq!(
+            parse(&enc).unwrap().oid_arcs().unwrap(),
+            vec![1, 2, 840, 113549, 1, 1, 11]
+        );
+    }
+
+    #[test]
+    fn key_usage_bits_drop_trailing_zeros() {
+        // digitalSignature | keyCertSign | cRLSign
+        assert_eq!(named_bits(0x86), vec![0x03, 0x02, 0x01, 0x86]);
+        // digitalSignature only
+        assert_eq!(named_bits(0x80), vec![0x03, 0x02, 0x07, 0x80]);
+    }
+
+    #[test]
+    fn times_switch_to_generalized_after_2049() {
+        let t = DateTime::parse_from_rfc3339("2030-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(time(t), tlv(UTC_TIME, b"300102030405Z"));
+        let t = DateTime::parse_from_rfc3339("2050-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(time(t), tlv(GENERALIZED_TIME, b"20500102030405Z"));
+    }
+
+    #[test]
+    fn pem_round_trip() {
+        let der = vec![7u8; 100];
+        let pem = pem_encode("CERTIFICATE", &der);
+        assert!(pem.lines().all(|l| l.len() <= 64));
+        assert_eq!(pem_decode("CERTIFICATE", &pem).unwrap(), der);
+        assert!(pem_decode("PRIVATE KEY", &pem).is_err());
+    }
+
+    #[test]
+    fn reader_rejects_overlong_lengths() {
+        assert!(parse(&[0x04, 0x05, 1, 2]).is_err());
+        assert!(parse(&[0x04, 0x01, 1, 2]).is_err());
+    }
+}
@@ -0,0 +1,676 @@
+//! X.509 structures PKI reads and writes: certificates (RFC 5280),
+//! CSRs (RFC 2986), CRLs (RFC 5280 §5) and OCSP (RFC 6960).
+//!
+//! Signing reuses transit's key primitives: ECDSA P-256 and RSA sign
+//! over SHA-256 (RSA with PKCS#1 v1.5 padding, what every X.509
+//! verifier expects), ed25519 over the message itself.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use super::der::{self, Tlv};
+use crate::error::{Result, VaultError};
+use crate::transit::{KeyType, SignatureAlgorithm, keys};
+
+mod oid {
+    pub const COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+    pub const RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113549, 1, 1, 1];
+    pub const SHA256_WITH_RSA: &[u64] = &[1, 2, 840, 113549, 1, 1, 11];
+    pub const EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+    pub const PRIME256V1: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+    pub const ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+    pub const ED25519: &[u64] = &[1, 3, 101, 112];
+    pub const EXTENSION_REQUEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 14];
+    pub const SUBJECT_KEY_ID: &[u64] = &[2, 5, 29, 14];
+    pub const KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+    pub const SUBJECT_ALT_NAME: &[u64] = &[2, 5, 29, 17];
+    pub const BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+    pub const CRL_NUMBER: &[u64] = &[2, 5, 29, 20];
+    pub const CRL_DISTRIBUTION_POINTS: &[u64] = &[2, 5, 29, 31];
+    pub const AUTHORITY_KEY_ID: &[u64] = &[2, 5, 29, 35];
+    pub const EXT_KEY_USAGE: &[u64] = &[2, 5, 29, 37];
+    pub const AUTHORITY_INFO_ACCESS: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 1, 1];
+    pub const SERVER_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 1];
+    pub const CLIENT_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 2];
+    pub const OCSP: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 1];
+    pub const OCSP_BASIC: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 1, 1];
+    pub const OCSP_NONCE: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 1, 2];
+    pub const CA_ISSUERS: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 2];
+    pub const SHA1: &[u64] = &[1, 3, 14, 3, 2, 26];
+    pub const SHA256: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 1];
+}
+
+// KeyUsage bits, numbered from the most significant bit.
+const DIGITAL_SIGNATURE: u8 = 0x80;
+const KEY_ENCIPHERMENT: u8 = 0x20;
+const KEY_CERT_SIGN: u8 = 0x04;
+const CRL_SIGN: u8 = 0x02;
+
+/// A CA private key, unsealed for the length of one signing call.
+pub(crate) struct Signer {
+    pub key_type: KeyType,
+    pub private_der: Vec<u8>,
+}
+
+impl Signer {
+    fn algorithm(&self) -> Vec<u8> {
+        match self.key_type {
+            KeyType::EcdsaP256 => der::sequence([der::oid(oid::ECDSA_WITH_SHA256)]),
+            KeyType::Ed25519 => der::sequence([der::oid(oid::ED25519)]),
+            _ => der::sequence([der::oid(oid::SHA256_WITH_RSA), der::null()]),
+        }
+    }
+
+    /// `SEQUENCE { tbs, algorithm, BIT STRING signature }` — the outer
+    /// shape certificates, CSRs, CRLs and basic OCSP responses share.
+    fn sign(&self, tbs: Vec<u8>) -> Result<Vec<u8>> {
+        let signature = keys::sign(
+            self.key_type,
+            &self.private_der,
+            &tbs,
+            SignatureAlgorithm::Pkcs1v15,
+        )?;
+        Ok(der::sequence([
+            tbs,
+            self.algorithm(),
+            der::bit_string(&signature),
+        ]))
+    }
+}
+
+/// Where relying parties find the CA certificate, CRL and OCSP
+/// responder. Each becomes an extension on issued certificates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PkiUrls {
+    #[serde(default)]
+    pub issuing_certificates: Vec<String>,
+    #[serde(default)]
+    pub crl_distribution_points: Vec<String>,
+    #[serde(default)]
+    pub ocsp_servers: Vec<String>,
+}
+
+/// What a certificate is for.
+pub(crate) enum Profile {
+    Ca,
+    Leaf {
+        server: bool,
+        client: bool,
+        key_encipherment: bool,
+    },
+}
+
+/// Everything a new certificate needs besides the signer. Names and
+/// the public key travel as raw DER so a CSR's or issuer's encoding is
+/// copied byte-for-byte.
+pub(crate) struct CertTemplate<'a> {
+    pub serial: &'a [u8],
+    pub issuer: &'a [u8],
+    pub subject: &'a [u8],
+    pub spki: &'a [u8],
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub profile: Profile,
+    pub dns_names: &'a [String],
+    pub ip_addresses: &'a [IpAddr],
+    /// Key id of the issuing CA; `None` for a self-signed root.
+    pub authority_key_id: Option<&'a [u8]>,
+    pub urls: &'a PkiUrls,
+}
+
+pub(crate) fn build_certificate(signer: &Signer, t: &CertTemplate<'_>) -> Result<Vec<u8>> {
+    let subject_key_id = key_id(t.spki)?;
+    let mut extensions = Vec::new();
+    match t.profile {
+        Profile::Ca => {
+            extensions.push(extension(
+                oid::BASIC_CONSTRAINTS,
+                true,
+                &der::sequence([der::boolean(true)]),
+            ));
+            extensions.push(extension(
+                oid::KEY_USAGE,
+                true,
+                &der::named_bits(DIGITAL_SIGNATURE | KEY_CERT_SIGN | CRL_SIGN),
+            ));
+        }
+        Profile::Leaf {
+            server,
+            client,
+            key_encipherment,
+        } => {
+            extensions.push(extension(
+                oid::BASIC_CONSTRAINTS,
+                true,
+                &der::sequence::<_, Vec<u8>>([]),
+            ));
+            let usage = if key_encipherment {
+                DIGITAL_SIGNATURE | KEY_ENCIPHERMENT
+            } else {
+                DIGITAL_SIGNATURE
+            };
+            extensions.push(extension(oid::KEY_USAGE, true, &der::named_bits(usage)));
+            let mut purposes = Vec::new();
+            if server {
+                purposes.push(der::oid(oid::SERVER_AUTH));
+            }
+            if client {
+                purposes.push(der::oid(oid::CLIENT_AUTH));
+            }
+            if !purposes.is_empty() {
+                extensions.push(extension(
+                    oid::EXT_KEY_USAGE,
+                    false,
+                    &der::sequence(purposes),
+                ));
+            }
+        }
+    }
+    extensions.push(extension(
+        oid::SUBJECT_KEY_ID,
+        false,
+        &der::octet_string(&subject_key_id),
+    ));
+    let authority_key_id = t.authority_key_id.unwrap_or(&subject_key_id);
+    extensions.push(extension(
+        oid::AUTHORITY_KEY_ID,
+        false,
+        &der::sequence([der::tlv(der::context(0), authority_key_id)]),
+    ));
+    if !t.dns_names.is_empty() || !t.ip_addresses.is_empty() {
+        let names = t
+            .dns_names
+            .iter()
+            .map(|n| der::tlv(der::context(2), n.as_bytes()))
+            .chain(t.ip_addresses.iter().map(|ip| {
+                let octets = match ip {
+                    IpAddr::V4(v4) => v4.octets().to_vec(),
+                    IpAddr::V6(v6) => v6.octets().to_vec(),
+                };
+                der::tlv(der::context(7), &octets)
+            }));
+        extensions.push(extension(
+            oid::SUBJECT_ALT_NAME,
+            false,
+            &der::sequence(names),
+        ));
+    }
+    if !t.urls.crl_distribution_points.is_empty() {
+        let points = t.urls.crl_distribution_points.iter().map(|url| {
+            // DistributionPoint { distributionPoint [0] { fullName [0] { URI } } }
+            let uri = der::tlv(der::context(6), url.as_bytes());
+            let full_name = der::tlv(der::context_constructed(0), &uri);
+            der::sequence([der::tlv(der::context_constructed(0), &full_name)])
+        });
+        extensions.push(extension(
+            oid::CRL_DISTRIBUTION_POINTS,
+            false,
+            &der::sequence(points),
+        ));
+    }
+    if !t.urls.ocsp_servers.is_empty() || !t.urls.issuing_certificates.is_empty() {
+        let access = t
+            .urls
+            .ocsp_servers
+            .iter()
+            .map(|url| (oid::OCSP, url))
+            .chain(
+                t.urls
+                    .issuing_certificates
+                    .iter()
+                    .map(|url| (oid::CA_ISSUERS, url)),
+            )
+            .map(|(method, url)| {
+                der::sequence([der::oid(method), der::tlv(der::context(6), url.as_bytes())])
+            });
+        extensions.push(extension(
+            oid::AUTHORITY_INFO_ACCESS,
+            false,
+            &der::sequence(access),
+        ));
+    }
+
+    let tbs = der::sequence([
+        der::tlv(der::context_constructed(0), &der::small_integer(2)),
+        der::unsigned_integer(t.serial),
+        signer.algorithm(),
+        t.issuer.to_vec(),
+        der::sequence([der::time(t.not_before), der::time(t.not_after)]),
+        t.subject.to_vec(),
+        t.spki.to_vec(),
+        der::tlv(der::context_constructed(3), &der::sequence(extensions)),
+    ]);
+    signer.sign(tbs)
+}
+
+fn extension(id: &[u64], critical: bool, value: &[u8]) -> Vec<u8> {
+    let mut parts = vec![der::oid(id)];
+    if critical {
+        parts.push(der::boolean(true));
+    }
+    parts.push(der::octet_string(value));
+    der::sequence(parts)
+}
+
+/// A `Name` holding only a common name.
+pub(crate) fn name_with_common_name(common_name: &str) -> Vec<u8> {
+    der::sequence([der::constructed(
+        der::SET,
+        [der::sequence([
+            der::oid(oid::COMMON_NAME),
+            der::utf8_string(common_name),
+        ])],
+    )])
+}
+
+/// RFC 5280 §4.2.1.2 method 1: SHA-1 over the subjectPublicKey bits.
+pub(crate) fn key_id(spki: &[u8]) -> Result<Vec<u8>> {
+    Ok(Sha1::digest(subject_public_key(spki)?).to_vec())
+}
+
+fn subject_public_key(spki: &[u8]) -> Result<&[u8]> {
+    let mut fields = der::parse(spki)?.expect(der::SEQUENCE)?.children();
+    fields.expect(der::SEQUENCE)?;
+    fields.next()?.bit_string_bytes()
+}
+
+/// The transit key type of an SPKI. Any RSA modulus reads as
+/// `rsa-2048` — verification doesn't care about the size.
+pub(crate) fn spki_key_type(spki: &[u8]) -> Result<KeyType> {
+    let mut fields = der::parse(spki)?.expect(der::SEQUENCE)?.children();
+    let mut algorithm = fields.expect(der::SEQUENCE)?.children();
+    let id = algorithm.next()?.oid_arcs()?;
+    if id == oid::RSA_ENCRYPTION {
+        Ok(KeyType::Rsa2048)
+    } else if id == oid::ED25519 {
+        Ok(KeyType::Ed25519)
+    } else if id == oid::EC_PUBLIC_KEY && algorithm.next()?.oid_arcs()? == oid::PRIME256V1 {
+        Ok(KeyType::EcdsaP256)
+    } else {
+        Err(VaultError::Invalid(
+            "unsupported public key; expected RSA, ECDSA P-256 or ed25519".into(),
+        ))
+    }
+}
+
+/// Check `signature` (made with `algorithm`) over `tbs` against `spki`.
+fn verify_signed(spki: &[u8], tbs: &[u8], algorithm: Tlv<'_>, signature: &[u8]) -> Result<bool> {
+    let key_type = spki_key_type(spki)?;
+    let id = algorithm.children().next()?.oid_arcs()?;
+    let expected: &[u64] = match key_type {
+        KeyType::EcdsaP256 => oid::ECDSA_WITH_SHA256,
+        KeyType::Ed25519 => oid::ED25519,
+        _ => oid::SHA256_WITH_RSA,
+    };
+    if id != expected {
+        return Err(VaultError::Invalid(format!(
+            "unsupported signature algorithm for a {} key",
+            key_type.as_str()
+        )));
+    }
+    let public_pem = der::pem_encode("PUBLIC KEY", spki);
+    keys::verify(
+        key_type,
+        &public_pem,
+        tbs,
+        signature,
+        SignatureAlgorithm::Pkcs1v15,
+    )
+}
+
+/// The common name in a raw `Name`, if it has one.
+pub(crate) fn common_name(name: &[u8]) -> Result<Option<String>> {
+    let mut rdns = der::parse(name)?.expect(der::SEQUENCE)?.children();
+    while !rdns.is_empty() {
+        let mut attributes = rdns.expect(der::SET)?.children();
+        while !attributes.is_empty() {
+            let mut pair = attributes.expect(der::SEQUENCE)?.children();
+            if pair.next()?.oid_arcs()? == oid::COMMON_NAME {
+                return pair.next()?.text().map(Some);
+            }
+        }
+    }
+    Ok(None)
+}
+
+// ── CSRs ─────────────────────────────────────────────────────────────
+
+/// A parsed, signature-checked certification request.
+#[derive(Debug)]
+pub(crate) struct Csr {
+    pub subject: Vec<u8>,
+    pub common_name: Option<String>,
+    pub spki: Vec<u8>,
+    pub dns_names: Vec<String>,
+    pub ip_addresses: Vec<IpAddr>,
+}
+
+pub(crate) fn parse_csr(der_bytes: &[u8]) -> Result<Csr> {
+    let mut outer = der::parse(der_bytes)?.expect(der::SEQUENCE)?.children();
+    let info = outer.expect(der::SEQUENCE)?;
+    let algorithm = outer.expect(der::SEQUENCE)?;
+    let signature = outer.next()?.bit_string_bytes()?;
+
+    let mut fields = info.children();
+    fields.expect(der::INTEGER)?;
+    let subject = fields.expect(der::SEQUENCE)?.raw.to_vec();
+    let spki = fields.expect(der::SEQUENCE)?.raw.to_vec();
+    if !verify_signed(&spki, info.raw, algorithm, signature)? {
+        return Err(VaultError::Invalid("CSR signature does not verify".into()));
+    }
+
+    let mut csr = Csr {
+        common_name: common_name(&subject)?,
+        subject,
+        spki,
+        dns_names: Vec::new(),
+        ip_addresses: Vec::new(),
+    };
+    let Some(attributes) = fields.optional(der::context_constructed(0))? else {
+        return Ok(csr);
+    };
+    let mut attributes = attributes.children();
+    while !attributes.is_empty() {
+        let mut attribute = attributes.expect(der::SEQUENCE)?.children();
+        if attribute.next()?.oid_arcs()? != oid::EXTENSION_REQUEST {
+            continue;
+        }
+        let mut values = attribute.expect(der::SET)?.children();
+        let mut extensions = values.expect(der::SEQUENCE)?.children();
+        while !extensions.is_empty() {
+            let mut ext = extensions.expect(der::SEQUENCE)?.children();
+            let id = ext.next()?.oid_arcs()?;
+            ext.optional(der::BOOLEAN)?;
+            let value = ext.expect(der::OCTET_STRING)?;
+            if id == oid::SUBJECT_ALT_NAME {
+                read_alt_names(value.value, &mut csr)?;
+            }
+        }
+    }
+    Ok(csr)
+}
+
+fn read_alt_names(value: &[u8], csr: &mut Csr) -> Result<()> {
+    let mut names = der::parse(value)?.expect(der::SEQUENCE)?.children();
+    while !names.is_empty() {
+        let name = names.next()?;
+        match name.tag {
+            t if t == der::context(2) => csr.dns_names.push(
+                String::from_utf8(name.value.to_vec())
+                    .map_err(|_| VaultError::Invalid("CSR DNS name is not ASCII".into()))?,
+            ),
+            t if t == der::context(7) => {
+                let ip = match name.value.len() {
+                    4 => IpAddr::from(<[u8; 4]>::try_from(name.value).unwrap_or_default()),
+                    16 => IpAddr::from(<[u8; 16]>::try_from(name.value).unwrap_or_default()),
+                    _ => return Err(VaultError::Invalid("CSR IP address is malformed".into())),
+                };
+                csr.ip_addresses.push(ip);
+            }
+            _ => {
+                return Err(VaultError::Invalid(
+                    "CSR subject alt names may only be DNS names and IP addresses".into(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A CSR for `subject`'s key, as an intermediate hands its parent.
+pub(crate) fn build_csr(signer: &Signer, subject: &[u8], spki: &[u8]) -> Result<Vec<u8>> {
+    let info = der::sequence([
+        der::small_integer(0),
+        subject.to_vec(),
+        spki.to_vec(),
+        der::tlv(der::context_constructed(0), &[]),
+    ]);
+    signer.sign(info)
+}
+
+// ── certificates ─────────────────────────────────────────────────────
+
+/// The fields PKI reads back from a certificate.
+#[derive(Debug)]
+pub(crate) struct CertInfo {
+    pub subject: Vec<u8>,
+    pub spki: Vec<u8>,
+    pub not_after: DateTime<Utc>,
+}
+
+pub(crate) fn parse_certificate(der_bytes: &[u8]) -> Result<CertInfo> {
+    let mut outer = der::parse(der_bytes)?.expect(der::SEQUENCE)?.children();
+    let mut tbs = outer.expect(der::SEQUENCE)?.children();
+    tbs.optional(der::context_constructed(0))?;
+    tbs.expect(der::INTEGER)?;
+    tbs.expect(der::SEQUENCE)?;
+    tbs.expect(der::SEQUENCE)?;
+    let mut validity = tbs.expect(der::SEQUENCE)?.children();
+    validity.next()?;
+    let not_after = parse_time(validity.next()?)?;
+    let subject = tbs.expect(der::SEQUENCE)?.raw.to_vec();
+    let spki = tbs.expect(der::SEQUENCE)?.raw.to_vec();
+    Ok(CertInfo {
+        subject,
+        spki,
+        not_after,
+    })
+}
+
+fn parse_time(t: Tlv<'_>) -> Result<DateTime<Utc>> {
+    let text = std::str::from_utf8(t.value).unwrap_or_default();
+    let parsed = match t.tag {
+        der::UTC_TIME => NaiveDateTime::parse_from_str(text, "%y%m%d%H%M%SZ"),
+        der::GENERALIZED_TIME => NaiveDateTime::parse_from_str(text, "%Y%m%d%H%M%SZ"),
+        _ => {
+            return Err(VaultError::Invalid(
+                "certificate validity is not a time".into(),
+            ));
+        }
+    };
+    parsed
+        .map(|t| t.and_utc())
+        .map_err(|e| VaultError::Invalid(format!("certificate validity: {e}")))
+}
+
+/// An INTEGER's content without its sign-padding zero.
+fn magnitude(integer: &[u8]) -> &[u8] {
+    match integer {
+        [0, rest @ ..] if !rest.is_empty() => rest,
+        other => other,
+    }
+}
+
+/// `aa:bb:cc…` — the serial-number form Vault prints.
+pub(crate) fn serial_hex(serial: &[u8]) -> String {
+    serial
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub(crate) fn parse_serial_hex(serial: &str) -> Result<Vec<u8>> {
+    let digits: String = serial.chars().filter(|c| *c != ':' && *c != '-').collect();
+    data_encoding::HEXLOWER_PERMISSIVE
+        .decode(digits.as_bytes())
+        .map_err(|_| VaultError::Invalid(format!("'{serial}' is not a hex serial number")))
+}
+
+// ── CRLs ─────────────────────────────────────────────────────────────
+
+pub(crate) struct CrlTemplate<'a> {
+    pub issuer: &'a [u8],
+    pub authority_key_id: &'a [u8],
+    pub crl_number: u64,
+    pub this_update: DateTime<Utc>,
+    pub next_update: DateTime<Utc>,
+    /// `(serial, revoked_at)` for every revoked, unexpired certificate.
+    pub revoked: &'a [(Vec<u8>, DateTime<Utc>)],
+}
+
+pub(crate) fn build_crl(signer: &Signer, t: &CrlTemplate<'_>) -> Result<Vec<u8>> {
+    let mut fields = vec![
+        der::small_integer(1),
+        signer.algorithm(),
+        t.issuer.to_vec(),
+        der::time(t.this_update),
+        der::time(t.next_update),
+    ];
+    if !t.revoked.is_empty() {
+        fields.push(der::sequence(t.revoked.iter().map(|(serial, at)| {
+            der::sequence([der::unsigned_integer(serial), der::time(*at)])
+        })));
+    }
+    let extensions = der::sequence([
+        extension(
+            oid::AUTHORITY_KEY_ID,
+            false,
+            &der::sequence([der::tlv(der::context(0), t.authority_key_id)]),
+        ),
+        extension(oid::CRL_NUMBER, false, &der::small_integer(t.crl_number)),
+    ]);
+    fields.push(der::tlv(der::context_constructed(0), &extensions));
+    signer.sign(der::sequence(fields))
+}
+
+// ── OCSP ─────────────────────────────────────────────────────────────
+
+/// One `CertID` from a request.
+#[derive(Debug)]
+pub(crate) struct CertId {
+    pub raw: Vec<u8>,
+    pub hash_algorithm: Vec<u64>,
+    pub issuer_name_hash: Vec<u8>,
+    pub issuer_key_hash: Vec<u8>,
+    pub serial: Vec<u8>,
+}
+
+impl CertId {
+    /// Whether this id names the CA with `subject` and `spki`. Only
+    /// SHA-1 and SHA-256 ids can match.
+    pub fn names_issuer(&self, subject: &[u8], spki: &[u8]) -> Result<bool> {
+        let key = subject_public_key(spki)?;
+        let (name_hash, key_hash) = if self.hash_algorithm == oid::SHA1 {
+            (Sha1::digest(subject).to_vec(), Sha1::digest(key).to_vec())
+        } else if self.hash_algorithm == oid::SHA256 {
+            (
+                Sha256::digest(subject).to_vec(),
+                Sha256::digest(key).to_vec(),
+            )
+        } else {
+            return Ok(false);
+        };
+        Ok(name_hash == self.issuer_name_hash && key_hash == self.issuer_key_hash)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct OcspRequest {
+    pub cert_ids: Vec<CertId>,
+    /// The request's nonce extension, echoed into the response.
+    pub nonce: Option<Vec<u8>>,
+}
+
+pub(crate) fn parse_ocsp_request(der_bytes: &[u8]) -> Result<OcspRequest> {
+    let mut outer = der::parse(der_bytes)?.expect(der::SEQUENCE)?.children();
+    let mut tbs = outer.expect(der::SEQUENCE)?.children();
+    tbs.optional(der::context_constructed(0))?;
+    tbs.optional(der::context_constructed(1))?;
+    let mut requests = tbs.expect(der::SEQUENCE)?.children();
+    let mut cert_ids = Vec::new();
+    while !requests.is_empty() {
+        let mut request = requests.expect(der::SEQUENCE)?.children();
+        let id = request.expect(der::SEQUENCE)?;
+        let mut fields = id.children();
+        let hash_algorithm = fields
+            .expect(der::SEQUENCE)?
+            .children()
+            .next()?
+            .oid_arcs()?;
+        cert_ids.push(CertId {
+            raw: id.raw.to_vec(),
+            hash_algorithm,
+            issuer_name_hash: fields.expect(der::OCTET_STRING)?.value.to_vec(),
+            issuer_key_hash: fields.expect(der::OCTET_STRING)?.value.to_vec(),
+            serial: magnitude(fields.expect(der::INTEGER)?.value).to_vec(),
+        });
+    }
+    let mut nonce = None;
+    if let Some(extensions) = tbs.optional(der::context_constructed(2))? {
+        let mut list = extensions.children().expect(der::SEQUENCE)?.children();
+        while !list.is_empty() {
+            let ext = list.expect(der::SEQUENCE)?;
+            if ext.children().next()?.oid_arcs()? == oid::OCSP_NONCE {
+                nonce = Some(ext.raw.to_vec());
+            }
+        }
+    }
+    Ok(OcspRequest { cert_ids, nonce })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CertStatus {
+    Good,
+    Revoked(DateTime<Utc>),
+    Unknown,
+}
+
+pub(crate) struct OcspTemplate<'a> {
+    /// SHA-1 of the responder's (the CA's) public key bits.
+    pub responder_key_hash: &'a [u8],
+    pub produced_at: DateTime<Utc>,
+    pub next_update: DateTime<Utc>,
+    pub responses: &'a [(&'a CertId, CertStatus)],
+    pub nonce: Option<&'a [u8]>,
+}
+
+pub(crate) fn build_ocsp_response(signer: &Signer, t: &OcspTemplate<'_>) -> Result<Vec<u8>> {
+    let responses = t.responses.iter().map(|(id, status)| {
+        let status = match status {
+            CertStatus::Good => der::tlv(der::context(0), &[]),
+            CertStatus::Revoked(at) => {
+                der::tlv(der::context_constructed(1), &der::generalized_time(*at))
+            }
+            CertStatus::Unknown => der::tlv(der::context(2), &[]),
+        };
+        der::sequence([
+            id.raw.clone(),
+            status,
+            der::generalized_time(t.produced_at),
+            der::tlv(
+                der::context_constructed(0),
+                &der::generalized_time(t.next_update),
+            ),
+        ])
+    });
+    let mut data = vec![
+        der::tlv(
+            der::context_constructed(2),
+            &der::octet_string(t.responder_key_hash),
+        ),
+        der::generalized_time(t.produced_at),
+        der::sequence(responses),
+    ];
+    if let Some(nonce) = t.nonce {
+        data.push(der::tlv(
+            der::context_constructed(1),
+            &der::sequence([nonce]),
+        ));
+    }
+    let basic = signer.sign(der::sequence(data))?;
+    let bytes = der::sequence([der::oid(oid::OCSP_BASIC), der::octet_string(&basic)]);
+    Ok(der::sequence([
+        der::tlv(der::ENUMERATED, &[0]),
+        der::tlv(der::context_constructed(0), &bytes),
+    ]))
+}
+
+/// An OCSP response carrying only an error status (RFC 6960 §4.2.1):
+/// 1 malformedRequest, 2 internalError, 6 unauthorized.
+pub(crate) fn ocsp_error(status: u8) -> Vec<u8> {
+    der::sequence([der::tlv(der::ENUMERATED, &[status])])
+}
@@ -16,7 +16,10 @@
 //! Share-redeem (`GET /share/{token}`) verifies the biscuit token in
 //! the handler itself. Callers must either include a path-prefix
 //! bypass in their gate (the engine does this) OR accept that
-//! share-redeem also requires bearer auth in their deployment.
+//! share-redeem also requires bearer auth in their deployment. The
+//! same holds for the PKI publication routes (CA certificate, CRL,
+//! OCSP), which TLS clients fetch without credentials, and the SSH CA
+//! public key that hosts pull into `TrustedUserCAKeys`.
 
 use axum::Router;
 use axum::extract::FromRef;
@@ -36,8 +39,12 @@ mod collections;
 mod dynamic;
 #[cfg(feature = "vault-kv")]
 mod kv;
+#[cfg(feature = "vault-pki")]
+mod pki;
 #[cfg(feature = "vault-share")]
 mod share;
+#[cfg(feature = "vault-ssh")]
+mod ssh;
 mod sys;
 #[cfg(feature = "vault-transit")]
 mod transit;
@@ -73,6 +80,14 @@ where
     {
         r = r.merge(transit::router::<S>());
     }
+    #[cfg(feature = "vault-pki")]
+    {
+        r = r.merge(pki::router::<S>());
+    }
+    #[cfg(feature = "vault-ssh")]
+    {
+        r = r.merge(ssh::router::<S>());
+    }
     #[cfg(feature = "vault-collections")]
     {
         r = r.merge(collections::router::<S>());
@@ -3,9 +3,12 @@
 //! Wire shape:
 //!
 //! ```text
-//! PUT    /api/v1/vault/kv/*path                    body: { data, custom_md? }
+//! PUT    /api/v1/vault/kv/*path                    body: { data, custom_md?, cas? }
 //! GET    /api/v1/vault/kv/*path?version=N
 //! GET    /api/v1/vault/kv-list/*prefix             list under a prefix
+//! GET    /api/v1/vault/kv-meta/*path               path metadata
+//! POST   /api/v1/vault/kv-meta/*path               body: { cas_required?, max_versions?,
+//!                                                          delete_version_after?, custom_md? }
 //! DELETE /api/v1/vault/kv/*path?version=N          soft-delete
 //! POST   /api/v1/vault/kv/*path/destroy?version=N  hard-destroy
 //! POST   /api/v1/vault/kv/*path/undelete?version=N
@@ -18,6 +21,9 @@
 //! handled below — or a sibling `/kv-list/` prefix for LIST so the
 //! routing tree stays unambiguous. Everything else uses the
 //! plan-locked shape.
+//!
+//! A PUT whose `cas` doesn't match the path's latest version answers
+//! 409; a PUT without `cas` on a path with `cas_required` answers 400.
 
 use axum::Router;
 use axum::extract::{FromRef, Path, Query, State};
@@ -29,6 +35,7 @@ use serde_json::Value;
 
 use crate::ctx::{DynKvStore, VaultCtx};
 use crate::error::VaultError;
+use crate::kv::KvMetaUpdate;
 use crate::router::vault_err_to_response;
 
 pub fn router<S>() -> Router<S>
@@ -43,7 +50,7 @@ where
         )
         .route("/kv-list/{*prefix}", get(list_kv::<S>))
         .route("/kv-list", get(list_kv_root::<S>))
-        .route("/kv-meta/{*path}", get(meta_kv::<S>))
+        .route("/kv-meta/{*path}", get(meta_kv::<S>).post(put_meta_kv::<S>))
         .route("/kv-destroy/{*path}", post(destroy_kv::<S>))
         .route("/kv-undelete/{*path}", post(undelete_kv::<S>))
 }
@@ -56,6 +63,10 @@ struct PutBody {
     data: String,
     #[serde(default = "empty_obj")]
     custom_md: Value,
+    /// Check-and-set: write only while the latest version is this one
+    /// (`0` = the path has no versions yet).
+    #[serde(default)]
+    cas: Option<i64>,
 }
 
 fn empty_obj() -> Value {
@@ -95,7 +106,10 @@ where
         Some(k) => k,
         None => return service_unavailable("kv"),
     };
-    match kv.put(&path, body.data.as_bytes(), body.custom_md).await {
+    match kv
+        .put_cas(&path, body.data.as_bytes(), body.custom_md, body.cas)
+        .await
+    {
         Ok(version) => (
             StatusCode::CREATED,
             axum::Json(PutResponse { path, version }),
@@ -264,6 +278,25 @@ where
     }
 }
 
+async fn put_meta_kv<S>(
+    State(vault): State<VaultCtx>,
+    Path(path): Path<String>,
+    axum::Json(body): axum::Json<KvMetaUpdate>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let kv = match vault.kv.as_ref() {
+        Some(k) => k,
+        None => return service_unavailable("kv"),
+    };
+    match kv.put_meta(&path, &body).await {
+        Ok(m) => axum::Json(m).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
 fn service_unavailable(surface: &'static str) -> Response {
     (
         StatusCode::SERVICE_UNAVAILABLE,
@@ -0,0 +1,529 @@
+//! PKI HTTP handlers.
+//!
+//! ```text
+//! POST   /api/v1/vault/pki/root/generate              body: { common_name, key_type?, ttl_secs? } -> { certificate }
+//! DELETE /api/v1/vault/pki/root                       drop the CA + issuance records
+//! POST   /api/v1/vault/pki/root/sign-intermediate     body: { csr, common_name?, ttl_secs? } -> issued cert
+//! POST   /api/v1/vault/pki/intermediate/generate      body: { common_name, key_type? } -> { csr }
+//! POST   /api/v1/vault/pki/intermediate/set-signed    body: { certificate } -> 204
+//! POST   /api/v1/vault/pki/config/ca                  body: { pem_bundle } -> 204 (import key + cert)
+//! GET    /api/v1/vault/pki/config/urls                -> { issuing_certificates, crl_distribution_points, ocsp_servers }
+//! POST   /api/v1/vault/pki/config/urls                body: same -> 204
+//! POST   /api/v1/vault/pki/roles/{name}               body: role -> 204
+//! GET    /api/v1/vault/pki/roles/{name}               -> role
+//! DELETE /api/v1/vault/pki/roles/{name}               -> 204
+//! GET    /api/v1/vault/pki/roles                      -> { roles }
+//! POST   /api/v1/vault/pki/issue/{role}               body: { common_name, alt_names?, ip_sans?, ttl_secs? } -> issued cert + private key
+//! POST   /api/v1/vault/pki/sign/{role}                body: { csr, common_name?, alt_names?, ip_sans?, ttl_secs? } -> issued cert
+//! POST   /api/v1/vault/pki/revoke                     body: { serial_number } -> { revocation_time }
+//! GET    /api/v1/vault/pki/certs                      -> { certs }
+//! GET    /api/v1/vault/pki/cert/{serial}              -> cert record
+//!
+//! GET    /api/v1/vault/pki/ca                         DER (application/pkix-cert)
+//! GET    /api/v1/vault/pki/ca/pem                     PEM
+//! GET    /api/v1/vault/pki/crl                        DER (application/pkix-crl)
+//! GET    /api/v1/vault/pki/crl/pem                    PEM
+//! POST   /api/v1/vault/pki/ocsp                       application/ocsp-request -> application/ocsp-response
+//! GET    /api/v1/vault/pki/ocsp/{base64 request}      RFC 6960 §A.1 GET form
+//! ```
+//!
+//! The last block is what TLS stacks fetch on their own, without
+//! credentials; the engine's gate lets those paths through. Everything
+//! else is behind the normal vault gate.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{FromRef, Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::Deserialize;
+
+use crate::ctx::VaultCtx;
+use crate::error::VaultError;
+use crate::pki::{CertRequest, PkiRole, PkiUrls};
+use crate::router::vault_err_to_response;
+
+pub fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new()
+        .route("/pki/root/generate", post(generate_root::<S>))
+        .route("/pki/root", axum::routing::delete(delete_ca::<S>))
+        .route("/pki/root/sign-intermediate", post(sign_intermediate::<S>))
+        .route(
+            "/pki/intermediate/generate",
+            post(generate_intermediate::<S>),
+        )
+        .route("/pki/intermediate/set-signed", post(set_signed::<S>))
+        .route("/pki/config/ca", post(import_ca::<S>))
+        .route(
+            "/pki/config/urls",
+            get(read_urls::<S>).post(write_urls::<S>),
+        )
+        .route(
+            "/pki/roles/{name}",
+            post(put_role::<S>)
+                .get(read_role::<S>)
+                .delete(delete_role::<S>),
+        )
+        .route("/pki/roles", get(list_roles::<S>))
+        .route("/pki/issue/{role}", post(issue::<S>))
+        .route("/pki/sign/{role}", post(sign::<S>))
+        .route("/pki/revoke", post(revoke::<S>))
+        .route("/pki/certs", get(list_certs::<S>))
+        .route("/pki/cert/{serial}", get(read_cert::<S>))
+        .route("/pki/ca", get(ca_der::<S>))
+        .route("/pki/ca/pem", get(ca_pem::<S>))
+        .route("/pki/crl", get(crl_der::<S>))
+        .route("/pki/crl/pem", get(crl_pem::<S>))
+        .route("/pki/ocsp", post(ocsp_post::<S>))
+        .route("/pki/ocsp/{*request}", get(ocsp_get::<S>))
+}
+
+#[derive(Deserialize)]
+struct GenerateBody {
+    common_name: String,
+    key_type: Option<String>,
+    ttl_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SignIntermediateBody {
+    csr: String,
+    common_name: Option<String>,
+    ttl_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SetSignedBody {
+    certificate: String,
+}
+
+#[derive(Deserialize)]
+struct ImportBody {
+    pem_bundle: String,
+}
+
+#[derive(Deserialize)]
+struct SignBody {
+    csr: String,
+    #[serde(flatten)]
+    request: CertRequest,
+}
+
+#[derive(Deserialize)]
+struct RevokeBody {
+    serial_number: String,
+}
+
+async fn generate_root<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<GenerateBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc
+        .generate_root(&body.common_name, body.key_type.as_deref(), body.ttl_secs)
+        .await
+    {
+        Ok(certificate) => (
+            StatusCode::CREATED,
+            axum::Json(serde_json::json!({ "certificate": certificate })),
+        )
+            .into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn delete_ca<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.delete_ca().await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn sign_intermediate<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<SignIntermediateBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc
+        .sign_intermediate(&body.csr, body.common_name.as_deref(), body.ttl_secs)
+        .await
+    {
+        Ok(issued) => axum::Json(issued).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn generate_intermediate<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<GenerateBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc
+        .generate_intermediate(&body.common_name, body.key_type.as_deref())
+        .await
+    {
+        Ok(csr) => (
+            StatusCode::CREATED,
+            axum::Json(serde_json::json!({ "csr": csr })),
+        )
+            .into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn set_signed<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<SetSignedBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.set_signed_intermediate(&body.certificate).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn import_ca<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<ImportBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.import_ca(&body.pem_bundle).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn read_urls<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.urls().await {
+        Ok(urls) => axum::Json(urls).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn write_urls<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(urls): axum::Json<PkiUrls>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.set_urls(&urls).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn put_role<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(role): axum::Json<PkiRole>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.put_role(&name, role).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn read_role<S>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.get_role(&name).await {
+        Ok(role) => axum::Json(role).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn delete_role<S>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.delete_role(&name).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn list_roles<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.list_roles().await {
+        Ok(roles) => axum::Json(serde_json::json!({ "roles": roles })).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn issue<S>(
+    State(vault): State<VaultCtx>,
+    Path(role): Path<String>,
+    axum::Json(request): axum::Json<CertRequest>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.issue(&role, &request).await {
+        Ok(issued) => axum::Json(issued).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn sign<S>(
+    State(vault): State<VaultCtx>,
+    Path(role): Path<String>,
+    axum::Json(body): axum::Json<SignBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.sign(&role, &body.csr, &body.request).await {
+        Ok(issued) => axum::Json(issued).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn revoke<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<RevokeBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.revoke(&body.serial_number).await {
+        Ok(at) => axum::Json(serde_json::json!({ "revocation_time": at as i64 })).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn list_certs<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.list_certs().await {
+        Ok(certs) => axum::Json(serde_json::json!({ "certs": certs })).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn read_cert<S>(State(vault): State<VaultCtx>, Path(serial): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.read_cert(&serial).await {
+        Ok(cert) => axum::Json(cert).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn ca_der<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.ca_der().await {
+        Ok(der) => ([(header::CONTENT_TYPE, "application/pkix-cert")], der).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn ca_pem<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.ca_pem().await {
+        Ok(pem) => ([(header::CONTENT_TYPE, "application/x-pem-file")], pem).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn crl_der<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.crl().await {
+        Ok(der) => ([(header::CONTENT_TYPE, "application/pkix-crl")], der).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn crl_pem<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match svc.crl_pem().await {
+        Ok(pem) => ([(header::CONTENT_TYPE, "application/x-pem-file")], pem).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn ocsp_post<S>(State(vault): State<VaultCtx>, body: Bytes) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    ocsp_response(svc.ocsp(&body).await)
+}
+
+async fn ocsp_get<S>(State(vault): State<VaultCtx>, Path(request): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.pki.as_ref() {
+        Some(p) => p,
+        None => return service_unavailable("pki"),
+    };
+    match data_encoding::BASE64.decode(request.as_bytes()) {
+        Ok(der) => ocsp_response(svc.ocsp(&der).await),
+        Err(_) => vault_err_to_response(VaultError::Invalid(
+            "OCSP GET request is not valid base64".into(),
+        )),
+    }
+}
+
+fn ocsp_response(der: Vec<u8>) -> Response {
+    ([(header::CONTENT_TYPE, "application/ocsp-response")], der).into_response()
+}
+
+fn service_unavailable(surface: &'static str) -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        axum::Json(serde_json::json!({
+            "error": "service_unavailable",
+            "error_description": format!("vault {surface} surface not configured"),
+        })),
+    )
+        .into_response()
+}
@@ -0,0 +1,250 @@
+//! SSH CA HTTP handlers.
+//!
+//! ```text
+//! POST   /api/v1/vault/ssh/ca/generate     body: { key_type? } -> { public_key }
+//! POST   /api/v1/vault/ssh/ca/import       body: { private_key } (PKCS#8 PEM) -> { public_key }
+//! DELETE /api/v1/vault/ssh/ca              drop the CA key (roles + records stay)
+//! GET    /api/v1/vault/ssh/public_key      CA public key line (text/plain)
+//! POST   /api/v1/vault/ssh/roles/{name}    body: role -> 204
+//! GET    /api/v1/vault/ssh/roles/{name}    -> role
+//! DELETE /api/v1/vault/ssh/roles/{name}    -> 204
+//! GET    /api/v1/vault/ssh/roles           -> { roles }
+//! POST   /api/v1/vault/ssh/sign/{role}     body: { public_key, valid_principals?, ttl_secs?, key_id?, extensions?, critical_options? } -> signed key
+//! GET    /api/v1/vault/ssh/certs           -> { certs } (issuance records)
+//! ```
+//!
+//! `public_key` is what hosts fetch into `TrustedUserCAKeys`, so the
+//! engine's gate lets it through without credentials. Everything else
+//! is behind the normal vault gate.
+
+use axum::Router;
+use axum::extract::{Extension, FromRef, Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::Deserialize;
+
+use crate::audit::Actor;
+use crate::ctx::VaultCtx;
+use crate::router::vault_err_to_response;
+use crate::ssh::{SignRequest, SshRole};
+
+pub fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    Router::new()
+        .route("/ssh/ca/generate", post(generate_ca::<S>))
+        .route("/ssh/ca/import", post(import_ca::<S>))
+        .route("/ssh/ca", axum::routing::delete(delete_ca::<S>))
+        .route("/ssh/public_key", get(public_key::<S>))
+        .route(
+            "/ssh/roles/{name}",
+            post(put_role::<S>)
+                .get(read_role::<S>)
+                .delete(delete_role::<S>),
+        )
+        .route("/ssh/roles", get(list_roles::<S>))
+        .route("/ssh/sign/{role}", post(sign::<S>))
+        .route("/ssh/certs", get(list_certs::<S>))
+}
+
+#[derive(Deserialize)]
+struct GenerateBody {
+    key_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ImportBody {
+    private_key: String,
+}
+
+async fn generate_ca<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<GenerateBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.generate_ca(body.key_type.as_deref()).await {
+        Ok(public_key) => created(public_key),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn import_ca<S>(
+    State(vault): State<VaultCtx>,
+    axum::Json(body): axum::Json<ImportBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.import_ca(&body.private_key).await {
+        Ok(public_key) => created(public_key),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn delete_ca<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.delete_ca().await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn public_key<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.public_key().await {
+        Ok(line) => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            format!("{line}\n"),
+        )
+            .into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn put_role<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(role): axum::Json<SshRole>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.put_role(&name, role).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn read_role<S>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.get_role(&name).await {
+        Ok(role) => axum::Json(role).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn delete_role<S>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.delete_role(&name).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn list_roles<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.list_roles().await {
+        Ok(roles) => axum::Json(serde_json::json!({ "roles": roles })).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn sign<S>(
+    State(vault): State<VaultCtx>,
+    Path(role): Path<String>,
+    actor: Option<Extension<Actor>>,
+    axum::Json(request): axum::Json<SignRequest>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    let actor = actor.map(|Extension(Actor(who))| who);
+    match svc.sign(&role, &request, actor.as_deref()).await {
+        Ok(signed) => axum::Json(signed).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn list_certs<S>(State(vault): State<VaultCtx>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.ssh.as_ref() {
+        Some(s) => s,
+        None => return service_unavailable(),
+    };
+    match svc.list_certs().await {
+        Ok(certs) => axum::Json(serde_json::json!({ "certs": certs })).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+fn created(public_key: String) -> Response {
+    (
+        StatusCode::CREATED,
+        axum::Json(serde_json::json!({ "public_key": public_key })),
+    )
+        .into_response()
+}
+
+fn service_unavailable() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        axum::Json(serde_json::json!({
+            "error": "service_unavailable",
+            "error_description": "vault ssh surface not configured",
+        })),
+    )
+        .into_response()
+}
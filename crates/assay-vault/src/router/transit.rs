@@ -5,11 +5,23 @@
 //!
 //! ```text
 //! POST /api/v1/vault/transit/keys/{name}             body: { algo? } -> 201
+//! GET  /api/v1/vault/transit/keys/{name}             -> metadata + per-version public keys
 //! GET  /api/v1/vault/transit/keys                                   list
 //! POST /api/v1/vault/transit/keys/{name}/rotate                     -> { version }
+//! POST /api/v1/vault/transit/keys/{name}/config      body: { min_decryption_version } -> metadata
 //! POST /api/v1/vault/transit/encrypt/{name}          body: { plaintext_b64 } -> { ciphertext }
 //! POST /api/v1/vault/transit/decrypt/{name}          body: { ciphertext } -> { plaintext_b64 }
+//! POST /api/v1/vault/transit/rewrap/{name}           body: { ciphertext } -> { ciphertext }
+//! POST /api/v1/vault/transit/datakey/{plaintext|wrapped}/{name}
+//!                                                    body: { bits? } -> { ciphertext, plaintext_b64? }
+//! POST /api/v1/vault/transit/hmac/{name}             body: { input_b64, algorithm? } -> { hmac }
+//! POST /api/v1/vault/transit/sign/{name}             body: { input_b64, signature_algorithm? } -> { signature }
+//! POST /api/v1/vault/transit/verify/{name}           body: { input_b64, signature | hmac, … } -> { valid }
 //! ```
+//!
+//! `algo` is one of `aes256-gcm-siv` (default), `ed25519`, `ecdsa-p256`,
+//! `rsa-2048`, `rsa-4096`. `datakey/wrapped` omits the plaintext key so
+//! a caller that only stores it never sees it.
 
 use axum::Router;
 use axum::extract::{FromRef, Path, State};
@@ -21,6 +33,7 @@ use serde::{Deserialize, Serialize};
 use crate::ctx::VaultCtx;
 use crate::error::VaultError;
 use crate::router::vault_err_to_response;
+use crate::transit::SignatureAlgorithm;
 
 pub fn router<S>() -> Router<S>
 where
@@ -28,11 +41,20 @@ where
     VaultCtx: FromRef<S>,
 {
     Router::new()
-        .route("/transit/keys/{name}", post(create_key::<S>))
+        .route(
+            "/transit/keys/{name}",
+            post(create_key::<S>).get(read_key::<S>),
+        )
         .route("/transit/keys", get(list_keys::<S>))
         .route("/transit/keys/{name}/rotate", post(rotate::<S>))
+        .route("/transit/keys/{name}/config", post(configure::<S>))
         .route("/transit/encrypt/{name}", post(encrypt::<S>))
         .route("/transit/decrypt/{name}", post(decrypt::<S>))
+        .route("/transit/rewrap/{name}", post(rewrap::<S>))
+        .route("/transit/datakey/{kind}/{name}", post(datakey::<S>))
+        .route("/transit/hmac/{name}", post(hmac::<S>))
+        .route("/transit/sign/{name}", post(sign::<S>))
+        .route("/transit/verify/{name}", post(verify::<S>))
 }
 
 #[derive(Deserialize, Default)]
@@ -66,6 +88,61 @@ struct RotateResponse {
     version: i64,
 }
 
+#[derive(Deserialize)]
+struct ConfigBody {
+    min_decryption_version: i64,
+}
+
+#[derive(Deserialize, Default)]
+struct DatakeyBody {
+    bits: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct DatakeyResponse {
+    ciphertext: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plaintext_b64: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HmacBody {
+    input_b64: String,
+    algorithm: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HmacResponse {
+    hmac: String,
+}
+
+#[derive(Deserialize)]
+struct SignBody {
+    input_b64: String,
+    signature_algorithm: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SignResponse {
+    signature: String,
+}
+
+/// Exactly one of `signature` / `hmac`. `signature_algorithm` applies
+/// to signatures, `algorithm` to HMACs.
+#[derive(Deserialize)]
+struct VerifyBody {
+    input_b64: String,
+    signature: Option<String>,
+    hmac: Option<String>,
+    signature_algorithm: Option<String>,
+    algorithm: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyResponse {
+    valid: bool,
+}
+
 async fn create_key<S>(
     State(vault): State<VaultCtx>,
     Path(name): Path<String>,
@@ -129,13 +206,9 @@ where
         Some(t) => t,
         None => return service_unavailable("transit"),
     };
-    let plaintext = match data_encoding::BASE64.decode(body.plaintext_b64.as_bytes()) {
+    let plaintext = match decode_b64("plaintext_b64", &body.plaintext_b64) {
         Ok(b) => b,
-        Err(_) => {
-            return vault_err_to_response(VaultError::Invalid(
-                "plaintext_b64 is not valid base64".into(),
-            ));
-        }
+        Err(r) => return *r,
     };
     match svc.encrypt(&name, &plaintext).await {
         Ok(ciphertext) => axum::Json(EncryptResponse { ciphertext }).into_response(),
@@ -165,6 +238,191 @@ where
     }
 }
 
+async fn read_key<S>(State(vault): State<VaultCtx>, Path(name): Path<String>) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    match svc.read_key(&name).await {
+        Ok(info) => axum::Json(info).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn configure<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(body): axum::Json<ConfigBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    match svc
+        .set_min_decryption_version(&name, body.min_decryption_version)
+        .await
+    {
+        Ok(key) => axum::Json(key).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn rewrap<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(body): axum::Json<DecryptBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    match svc.rewrap(&name, &body.ciphertext).await {
+        Ok(ciphertext) => axum::Json(EncryptResponse { ciphertext }).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn datakey<S>(
+    State(vault): State<VaultCtx>,
+    Path((kind, name)): Path<(String, String)>,
+    body: Option<axum::Json<DatakeyBody>>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    let include_plaintext = match kind.as_str() {
+        "plaintext" => true,
+        "wrapped" => false,
+        _ => {
+            return vault_err_to_response(VaultError::Invalid(format!(
+                "datakey kind must be plaintext or wrapped; got '{kind}'"
+            )));
+        }
+    };
+    let bits = body.and_then(|b| b.0.bits);
+    match svc.datakey(&name, bits).await {
+        Ok(key) => axum::Json(DatakeyResponse {
+            ciphertext: key.ciphertext,
+            plaintext_b64: include_plaintext.then(|| data_encoding::BASE64.encode(&key.plaintext)),
+        })
+        .into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn hmac<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(body): axum::Json<HmacBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    let input = match decode_b64("input_b64", &body.input_b64) {
+        Ok(b) => b,
+        Err(r) => return *r,
+    };
+    match svc.hmac(&name, &input, body.algorithm.as_deref()).await {
+        Ok(hmac) => axum::Json(HmacResponse { hmac }).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn sign<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(body): axum::Json<SignBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    let input = match decode_b64("input_b64", &body.input_b64) {
+        Ok(b) => b,
+        Err(r) => return *r,
+    };
+    let algorithm = match SignatureAlgorithm::parse(body.signature_algorithm.as_deref()) {
+        Ok(a) => a,
+        Err(e) => return vault_err_to_response(e),
+    };
+    match svc.sign(&name, &input, algorithm).await {
+        Ok(signature) => axum::Json(SignResponse { signature }).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+async fn verify<S>(
+    State(vault): State<VaultCtx>,
+    Path(name): Path<String>,
+    axum::Json(body): axum::Json<VerifyBody>,
+) -> Response
+where
+    S: Clone + Send + Sync + 'static,
+    VaultCtx: FromRef<S>,
+{
+    let svc = match vault.transit.as_ref() {
+        Some(t) => t,
+        None => return service_unavailable("transit"),
+    };
+    let input = match decode_b64("input_b64", &body.input_b64) {
+        Ok(b) => b,
+        Err(r) => return *r,
+    };
+    let result = match (&body.signature, &body.hmac) {
+        (Some(signature), None) => {
+            match SignatureAlgorithm::parse(body.signature_algorithm.as_deref()) {
+                Ok(algorithm) => svc.verify(&name, &input, signature, algorithm).await,
+                Err(e) => Err(e),
+            }
+        }
+        (None, Some(hmac)) => {
+            svc.verify_hmac(&name, &input, hmac, body.algorithm.as_deref())
+                .await
+        }
+        _ => Err(VaultError::Invalid(
+            "exactly one of signature or hmac is required".into(),
+        )),
+    };
+    match result {
+        Ok(valid) => axum::Json(VerifyResponse { valid }).into_response(),
+        Err(e) => vault_err_to_response(e),
+    }
+}
+
+fn decode_b64(field: &str, value: &str) -> Result<Vec<u8>, Box<Response>> {
+    data_encoding::BASE64.decode(value.as_bytes()).map_err(|_| {
+        Box::new(vault_err_to_response(VaultError::Invalid(format!(
+            "{field} is not valid base64"
+        ))))
+    })
+}
+
 fn service_unavailable(surface: &'static str) -> Response {
     (
         StatusCode::SERVICE_UNAVAILABLE,
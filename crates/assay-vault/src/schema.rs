@@ -19,7 +19,7 @@
 //!
 //! Tables created (per plan 17 § "Schema — tables in `vault.*`"):
 //!
-//! - `vault.kv_meta`           — KV path metadata (latest version, custom md, deletion state)
+//! - `vault.kv_meta`           — KV path metadata (latest version, custom md, retention policy)
 //! - `vault.kv`                — versioned KV blobs (ciphertext, wrapped DEK, kek_kid)
 //! - `vault.transit_keys`      — transit master keys (name + algo + latest version)
 //! - `vault.transit_versions`  — per-key version material (rotated)
@@ -33,6 +33,15 @@
 //! - `vault.kek_metadata`      — sealing method, kid, unseal state
 //! - `vault.unseal_shares`     — Shamir SSS shares for KEK init unseal
 //! - `vault.audit_sinks`       — forwarding configs (syslog/S3/webhook)
+//! - `vault.pki_ca`            — the PKI CA: certificate, sealed key, CRL number (V3)
+//! - `vault.pki_roles`         — PKI issuance roles (V3)
+//! - `vault.pki_certs`         — certificates the CA issued, revocation state (V3)
+//! - `vault.ssh_ca`            — the SSH CA: public key, sealed private key (V4)
+//! - `vault.ssh_roles`         — SSH signing roles (V4)
+//! - `vault.ssh_certs`         — SSH certificates the CA signed, and for whom (V4)
+//! - `vault.compat_auth_roles` — Vault-compat AppRole + Kubernetes login roles (V6)
+//! - `vault.compat_auth_config`— Vault-compat auth method config (V6)
+//! - `vault.compat_approle_secret_ids` — AppRole secret IDs, hashed (V6)
 //!
 //! Schema is intentionally still loose in Phase 0: enough to apply
 //! cleanly and let the smoke test exercise an insert/read round-trip.
@@ -50,7 +59,16 @@ pub const MODULE_NAME: &str = "vault";
 /// V1: full plan-17 table set — see module-level docs for the list.
 ///     Phase 0 ships with V1 only; subsequent phases bump as new
 ///     storage shapes land.
-pub const MIGRATION_VERSION: i32 = 1;
+/// V2: transit signing keys — `min_decryption_version` on
+///     `vault.transit_keys`, encrypted private key + public key PEM on
+///     `vault.transit_versions`.
+/// V3: PKI — `vault.pki_ca`, `vault.pki_roles`, `vault.pki_certs`.
+/// V4: SSH CA — `vault.ssh_ca`, `vault.ssh_roles`, `vault.ssh_certs`.
+/// V5: KV retention — `cas_required`, `max_versions`,
+///     `delete_version_after` on `vault.kv_meta`.
+/// V6: Vault-compat auth methods — `vault.compat_auth_roles`,
+///     `vault.compat_auth_config`, `vault.compat_approle_secret_ids`.
+pub const MIGRATION_VERSION: i32 = 6;
 
 /// Postgres DDL for the vault schema, version 1.
 ///
@@ -282,6 +300,151 @@ CREATE TABLE IF NOT EXISTS vault.audit_sinks (
 );
 "#;
 
+/// Postgres DDL for the vault schema, version 2 — transit signing keys.
+///
+/// `key_wrapped` stays a KEK-wrapped 32-byte DEK for every key type, so
+/// KEK rotation rewraps transit versions without knowing their type.
+/// Asymmetric versions additionally carry `private_key` (PKCS#8 DER
+/// sealed under that DEK) and `public_key` (SPKI PEM, readable while
+/// sealed). Both are NULL for `aes256-gcm-siv` versions.
+pub const PG_DDL_V2: &str = r#"
+ALTER TABLE vault.transit_keys
+    ADD COLUMN IF NOT EXISTS min_decryption_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE vault.transit_versions
+    ADD COLUMN IF NOT EXISTS private_key BYTEA;
+ALTER TABLE vault.transit_versions
+    ADD COLUMN IF NOT EXISTS public_key TEXT;
+"#;
+
+/// Postgres DDL for the vault schema, version 3 — the PKI CA.
+///
+/// `pki_ca` holds at most one row. Its private key is sealed like a
+/// transit version's: `key_wrapped` is a KEK-wrapped DEK, `private_key`
+/// the PKCS#8 key sealed under it. `certificate_pem` is NULL while a
+/// generated intermediate waits for its signed certificate.
+pub const PG_DDL_V3: &str = r#"
+CREATE TABLE IF NOT EXISTS vault.pki_ca (
+    id              INTEGER PRIMARY KEY CHECK (id = 1),
+    kind            TEXT NOT NULL,
+    key_type        TEXT NOT NULL,
+    certificate_pem TEXT,
+    ca_chain        TEXT NOT NULL DEFAULT '',
+    private_key     BYTEA NOT NULL,
+    key_wrapped     BYTEA NOT NULL,
+    kek_kid         TEXT NOT NULL,
+    crl_number      BIGINT NOT NULL DEFAULT 1,
+    urls            JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at      DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+
+CREATE TABLE IF NOT EXISTS vault.pki_roles (
+    name        TEXT PRIMARY KEY,
+    config      JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW()),
+    updated_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+
+CREATE TABLE IF NOT EXISTS vault.pki_certs (
+    serial          TEXT PRIMARY KEY,
+    role            TEXT NOT NULL DEFAULT '',
+    common_name     TEXT NOT NULL,
+    certificate_pem TEXT NOT NULL,
+    not_after       DOUBLE PRECISION NOT NULL,
+    revoked_at      DOUBLE PRECISION,
+    created_at      DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+CREATE INDEX IF NOT EXISTS idx_vault_pki_certs_revoked
+    ON vault.pki_certs (revoked_at)
+    WHERE revoked_at IS NOT NULL;
+"#;
+
+/// Postgres DDL for the vault schema, version 4 — the SSH CA.
+///
+/// `ssh_ca` holds at most one row, its key sealed like the PKI CA's.
+/// `ssh_certs` is the issuance log: SSH certificates can't be fetched
+/// back from the CA, so it keeps what was signed and who asked.
+pub const PG_DDL_V4: &str = r#"
+CREATE TABLE IF NOT EXISTS vault.ssh_ca (
+    id          INTEGER PRIMARY KEY CHECK (id = 1),
+    key_type    TEXT NOT NULL,
+    public_key  TEXT NOT NULL,
+    private_key BYTEA NOT NULL,
+    key_wrapped BYTEA NOT NULL,
+    kek_kid     TEXT NOT NULL,
+    created_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+
+CREATE TABLE IF NOT EXISTS vault.ssh_roles (
+    name        TEXT PRIMARY KEY,
+    config      JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW()),
+    updated_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+
+CREATE TABLE IF NOT EXISTS vault.ssh_certs (
+    serial       TEXT PRIMARY KEY,
+    role         TEXT NOT NULL,
+    cert_type    TEXT NOT NULL,
+    key_id       TEXT NOT NULL,
+    principals   JSONB NOT NULL DEFAULT '[]'::jsonb,
+    fingerprint  TEXT NOT NULL,
+    valid_before DOUBLE PRECISION NOT NULL,
+    actor        TEXT,
+    created_at   DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+CREATE INDEX IF NOT EXISTS idx_vault_ssh_certs_created
+    ON vault.ssh_certs (created_at);
+"#;
+
+/// Postgres DDL for the vault schema, version 5 — KV v2 retention.
+///
+/// Per-path policy on `kv_meta`. `max_versions = 0` keeps every
+/// version; `delete_version_after` is in seconds, `0` meaning never.
+pub const PG_DDL_V5: &str = r#"
+ALTER TABLE vault.kv_meta
+    ADD COLUMN IF NOT EXISTS cas_required BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE vault.kv_meta
+    ADD COLUMN IF NOT EXISTS max_versions BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE vault.kv_meta
+    ADD COLUMN IF NOT EXISTS delete_version_after BIGINT NOT NULL DEFAULT 0;
+"#;
+
+/// Postgres DDL for the vault schema, version 6 — the Vault-compat
+/// facade's auth methods.
+///
+/// Roles of both methods share one table keyed by `(method, name)`;
+/// only AppRole roles carry a `role_id`. Secret IDs are stored as
+/// their SHA-256 and go with their role.
+pub const PG_DDL_V6: &str = r#"
+CREATE TABLE IF NOT EXISTS vault.compat_auth_roles (
+    method      TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    role_id     TEXT UNIQUE,
+    config      JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW()),
+    updated_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW()),
+    PRIMARY KEY (method, name)
+);
+
+CREATE TABLE IF NOT EXISTS vault.compat_auth_config (
+    method      TEXT PRIMARY KEY,
+    config      JSONB NOT NULL DEFAULT '{}'::jsonb,
+    updated_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
+);
+
+CREATE TABLE IF NOT EXISTS vault.compat_approle_secret_ids (
+    accessor    TEXT PRIMARY KEY,
+    role        TEXT NOT NULL,
+    secret_hash TEXT NOT NULL UNIQUE,
+    metadata    JSONB NOT NULL DEFAULT '{}'::jsonb,
+    uses_left   BIGINT,
+    created_at  DOUBLE PRECISION NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW()),
+    expires_at  DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS idx_vault_compat_approle_secret_ids_role
+    ON vault.compat_approle_secret_ids (role);
+"#;
+
 /// SQLite DDL for the vault schema, version 1.
 ///
 /// Caller must have ATTACHed `data/vault.db` AS `vault` before running
@@ -512,6 +675,173 @@ pub const SQLITE_DDL_V1: &[(&str, &str)] = &[
     ),
 ];
 
+/// Mirrors [`PG_DDL_V2`]. SQLite has no `ADD COLUMN IF NOT EXISTS`;
+/// the runner tolerates "duplicate column name" so re-runs are no-ops.
+pub const SQLITE_DDL_V2: &[(&str, &str)] = &[
+    (
+        "transit_keys.min_decryption_version",
+        "ALTER TABLE vault.transit_keys \
+         ADD COLUMN min_decryption_version INTEGER NOT NULL DEFAULT 1",
+    ),
+    (
+        "transit_versions.private_key",
+        "ALTER TABLE vault.transit_versions ADD COLUMN private_key BLOB",
+    ),
+    (
+        "transit_versions.public_key",
+        "ALTER TABLE vault.transit_versions ADD COLUMN public_key TEXT",
+    ),
+];
+
+/// Mirrors [`PG_DDL_V3`].
+pub const SQLITE_DDL_V3: &[(&str, &str)] = &[
+    (
+        "pki_ca",
+        "CREATE TABLE IF NOT EXISTS vault.pki_ca (
+            id              INTEGER PRIMARY KEY CHECK (id = 1),
+            kind            TEXT NOT NULL,
+            key_type        TEXT NOT NULL,
+            certificate_pem TEXT,
+            ca_chain        TEXT NOT NULL DEFAULT '',
+            private_key     BLOB NOT NULL,
+            key_wrapped     BLOB NOT NULL,
+            kek_kid         TEXT NOT NULL,
+            crl_number      INTEGER NOT NULL DEFAULT 1,
+            urls            TEXT NOT NULL DEFAULT '{}',
+            created_at      REAL NOT NULL
+        )",
+    ),
+    (
+        "pki_roles",
+        "CREATE TABLE IF NOT EXISTS vault.pki_roles (
+            name        TEXT PRIMARY KEY,
+            config      TEXT NOT NULL DEFAULT '{}',
+            created_at  REAL NOT NULL,
+            updated_at  REAL NOT NULL
+        )",
+    ),
+    (
+        "pki_certs",
+        "CREATE TABLE IF NOT EXISTS vault.pki_certs (
+            serial          TEXT PRIMARY KEY,
+            role            TEXT NOT NULL DEFAULT '',
+            common_name     TEXT NOT NULL,
+            certificate_pem TEXT NOT NULL,
+            not_after       REAL NOT NULL,
+            revoked_at      REAL,
+            created_at      REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_vault_pki_certs_revoked",
+        "CREATE INDEX IF NOT EXISTS vault.idx_vault_pki_certs_revoked \
+         ON pki_certs (revoked_at) WHERE revoked_at IS NOT NULL",
+    ),
+];
+
+/// Mirrors [`PG_DDL_V4`].
+pub const SQLITE_DDL_V4: &[(&str, &str)] = &[
+    (
+        "ssh_ca",
+        "CREATE TABLE IF NOT EXISTS vault.ssh_ca (
+            id          INTEGER PRIMARY KEY CHECK (id = 1),
+            key_type    TEXT NOT NULL,
+            public_key  TEXT NOT NULL,
+            private_key BLOB NOT NULL,
+            key_wrapped BLOB NOT NULL,
+            kek_kid     TEXT NOT NULL,
+            created_at  REAL NOT NULL
+        )",
+    ),
+    (
+        "ssh_roles",
+        "CREATE TABLE IF NOT EXISTS vault.ssh_roles (
+            name        TEXT PRIMARY KEY,
+            config      TEXT NOT NULL DEFAULT '{}',
+            created_at  REAL NOT NULL,
+            updated_at  REAL NOT NULL
+        )",
+    ),
+    (
+        "ssh_certs",
+        "CREATE TABLE IF NOT EXISTS vault.ssh_certs (
+            serial       TEXT PRIMARY KEY,
+            role         TEXT NOT NULL,
+            cert_type    TEXT NOT NULL,
+            key_id       TEXT NOT NULL,
+            principals   TEXT NOT NULL DEFAULT '[]',
+            fingerprint  TEXT NOT NULL,
+            valid_before REAL NOT NULL,
+            actor        TEXT,
+            created_at   REAL NOT NULL
+        )",
+    ),
+    (
+        "idx_vault_ssh_certs_created",
+        "CREATE INDEX IF NOT EXISTS vault.idx_vault_ssh_certs_created \
+         ON ssh_certs (created_at)",
+    ),
+];
+
+/// Mirrors [`PG_DDL_V5`]; applied by the same tolerant runner as
+/// [`SQLITE_DDL_V2`].
+pub const SQLITE_DDL_V5: &[(&str, &str)] = &[
+    (
+        "kv_meta.cas_required",
+        "ALTER TABLE vault.kv_meta ADD COLUMN cas_required INTEGER NOT NULL DEFAULT 0",
+    ),
+    (
+        "kv_meta.max_versions",
+        "ALTER TABLE vault.kv_meta ADD COLUMN max_versions INTEGER NOT NULL DEFAULT 0",
+    ),
+    (
+        "kv_meta.delete_version_after",
+        "ALTER TABLE vault.kv_meta \
+         ADD COLUMN delete_version_after INTEGER NOT NULL DEFAULT 0",
+    ),
+];
+
+/// Mirrors [`PG_DDL_V6`].
+pub const SQLITE_DDL_V6: &[(&str, &str)] = &[
+    (
+        "compat_auth_roles",
+        "CREATE TABLE IF NOT EXISTS vault.compat_auth_roles (
+            method      TEXT NOT NULL,
+            name        TEXT NOT NULL,
+            role_id     TEXT UNIQUE,
+            config      TEXT NOT NULL DEFAULT '{}',
+            created_at  REAL NOT NULL,
+            updated_at  REAL NOT NULL,
+            PRIMARY KEY (method, name)
+        )",
+    ),
+    (
+        "compat_auth_config",
+        "CREATE TABLE IF NOT EXISTS vault.compat_auth_config (
+            method      TEXT PRIMARY KEY,
+            config      TEXT NOT NULL DEFAULT '{}',
+            updated_at  REAL NOT NULL
+        )",
+    ),
+    (
+        "compat_approle_secret_ids",
+        "CREATE TABLE IF NOT EXISTS vault.compat_approle_secret_ids (
+            accessor    TEXT PRIMARY KEY,
+            role        TEXT NOT NULL,
+            secret_hash TEXT NOT NULL UNIQUE,
+            metadata    TEXT NOT NULL DEFAULT '{}',
+            uses_left   INTEGER,
+            created_at  REAL NOT NULL,
+            expires_at  REAL
+        )",
+    ),
+    (
+        "idx_vault_compat_approle_secret_ids_role",
+        "CREATE INDEX IF NOT EXISTS vault.idx_vault_compat_approle_secret_ids_role \
+         ON compat_approle_secret_ids (role)",
+    ),
+];
+
 /// Postgres migration runner.
 ///
 /// Applies every DDL pack up to and including [`MIGRATION_VERSION`],
@@ -522,7 +852,9 @@ pub const SQLITE_DDL_V1: &[(&str, &str)] = &[
 #[cfg(feature = "backend-postgres")]
 pub async fn migrate_postgres(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     use anyhow::Context;
-    for ddl in [PG_DDL_V1] {
+    for ddl in [
+        PG_DDL_V1, PG_DDL_V2, PG_DDL_V3, PG_DDL_V4, PG_DDL_V5, PG_DDL_V6,
+    ] {
         for stmt in split_pg_statements(ddl) {
             sqlx::query(&stmt)
                 .execute(pool)
@@ -558,6 +890,20 @@ pub async fn migrate_sqlite(pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
                 .with_context(|| format!("vault sqlite migrate: {label}"))?;
         }
     }
+    add_columns_sqlite(pool, SQLITE_DDL_V2).await?;
+    for (label, stmt) in SQLITE_DDL_V3.iter().chain(SQLITE_DDL_V4) {
+        sqlx::query(stmt)
+            .execute(pool)
+            .await
+            .with_context(|| format!("vault sqlite migrate: {label}"))?;
+    }
+    add_columns_sqlite(pool, SQLITE_DDL_V5).await?;
+    for (label, stmt) in SQLITE_DDL_V6 {
+        sqlx::query(stmt)
+            .execute(pool)
+            .await
+            .with_context(|| format!("vault sqlite migrate: {label}"))?;
+    }
     sqlx::query("INSERT OR IGNORE INTO engine.migrations (module, version) VALUES (?, ?)")
         .bind(MODULE_NAME)
         .bind(MIGRATION_VERSION)
@@ -567,6 +913,22 @@ pub async fn migrate_sqlite(pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Run a pack of `ALTER TABLE … ADD COLUMN` statements. SQLite has no
+/// `IF NOT EXISTS` for columns, so "duplicate column name" means the
+/// column is already there and the statement is skipped.
+#[cfg(feature = "backend-sqlite")]
+async fn add_columns_sqlite(pool: &sqlx::SqlitePool, pack: &[(&str, &str)]) -> anyhow::Result<()> {
+    for (label, stmt) in pack {
+        if let Err(e) = sqlx::query(stmt).execute(pool).await {
+            if e.to_string().contains("duplicate column name") {
+                continue;
+            }
+            return Err(anyhow::anyhow!("vault sqlite migrate: {label}: {e}"));
+        }
+    }
+    Ok(())
+}
+
 /// Split a PG DDL chunk into individual statements. Drops pure-comment
 /// lines first so a `--`-introduced semicolon doesn't fragment a real
 /// statement (mirrors the same trick `assay-auth::schema` uses).
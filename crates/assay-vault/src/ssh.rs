@@ -0,0 +1,656 @@
+//! SSH CA — signs OpenSSH user and host certificates. Vault-equivalent
+//! `ssh` secrets engine in its signed-certificate mode.
+//!
+//! The vault holds one CA key. Hosts trust it once
+//! (`TrustedUserCAKeys` for users, `@cert-authority` in
+//! `known_hosts` for hosts) and from then on accept any certificate it
+//! signed, so scripts and people log in with short-lived certificates
+//! instead of long-lived keys copied into `authorized_keys`.
+//!
+//! Roles decide what a caller may ask for: user or host certificates,
+//! which principals (login names for users, host names for hosts),
+//! which extensions and critical options, and how long the certificate
+//! lives. Every signature is recorded in `vault.ssh_certs` and
+//! dispatched to the audit sinks as `vault.ssh.sign`.
+//!
+//! ## Key custody
+//!
+//! Same as the PKI CA: the PKCS#8 key is AEAD-sealed under a DEK that
+//! the master KEK wraps, KEK rotation rewraps the DEK, and signing fails
+//! with [`VaultError::Sealed`] while the vault is sealed. The CA public
+//! key stays readable.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{AuditEvent, SinkRegistry};
+use crate::crypto::aead::{NONCE_LEN, decrypt, encrypt, random_dek, random_nonce};
+use crate::crypto::kek::{KekHandle, WrappedDek};
+use crate::crypto::seal_state::SealState;
+use crate::error::{Result, VaultError};
+use crate::transit::{KeyType, keys};
+
+mod wire;
+
+use wire::{CertTemplate, CertType, PublicKey};
+
+/// Backdating of `valid_after`, so a host whose clock runs a little
+/// behind still accepts a fresh certificate.
+const CLOCK_SKEW_SECS: u64 = 30;
+
+/// The vault's SSH CA as stored.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct SshCa {
+    pub key_type: String,
+    /// `authorized_keys`-style line, no comment.
+    pub public_key: String,
+    /// `nonce || AEAD(DEK, PKCS#8)`.
+    pub private_key: Vec<u8>,
+    pub key_wrapped: Vec<u8>,
+    pub kek_kid: String,
+    pub created_at: f64,
+}
+
+/// What a role lets callers ask for.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct SshRole {
+    #[serde(skip_deserializing)]
+    pub name: String,
+    /// `user` or `host`.
+    pub cert_type: String,
+    /// Principals a user certificate may carry; `*` allows any.
+    pub allowed_users: Vec<String>,
+    /// Principal a user certificate gets when the request names none.
+    /// Always allowed.
+    pub default_user: String,
+    /// Domains host-certificate principals may be drawn from.
+    pub allowed_domains: Vec<String>,
+    /// Allow host names under an allowed domain, not just the domain.
+    pub allow_subdomains: bool,
+    /// Extensions a request may ask for; `*` allows any.
+    pub allowed_extensions: Vec<String>,
+    /// Extensions granted when the request names none. Defaults to
+    /// `permit-pty` for user roles; host roles drop it.
+    pub default_extensions: BTreeMap<String, String>,
+    /// Critical options a request may ask for; `*` allows any.
+    pub allowed_critical_options: Vec<String>,
+    /// Critical options applied when the request names none.
+    pub default_critical_options: BTreeMap<String, String>,
+    /// Let requests choose the certificate's key id. Otherwise it is
+    /// `assay-<role>-<key fingerprint>`.
+    pub allow_user_key_ids: bool,
+    pub ttl_secs: u64,
+    pub max_ttl_secs: u64,
+}
+
+impl Default for SshRole {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            cert_type: "user".to_string(),
+            allowed_users: Vec::new(),
+            default_user: String::new(),
+            allowed_domains: Vec::new(),
+            allow_subdomains: false,
+            allowed_extensions: Vec::new(),
+            default_extensions: BTreeMap::from([("permit-pty".to_string(), String::new())]),
+            allowed_critical_options: Vec::new(),
+            default_critical_options: BTreeMap::new(),
+            allow_user_key_ids: false,
+            ttl_secs: 3600,
+            max_ttl_secs: 86_400,
+        }
+    }
+}
+
+/// One certificate the CA signed.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct SshCert {
+    pub serial_number: String,
+    pub role: String,
+    /// `user` or `host`.
+    pub cert_type: String,
+    pub key_id: String,
+    pub principals: Vec<String>,
+    /// SHA-256 fingerprint of the signed public key.
+    pub fingerprint: String,
+    pub valid_before: f64,
+    /// Who asked for it, as the engine's gate identified them.
+    pub actor: Option<String>,
+    pub created_at: f64,
+}
+
+/// What a caller asks `sign` for.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct SignRequest {
+    /// `authorized_keys`-style public key to certify.
+    pub public_key: String,
+    /// Falls back to the role's `default_user` for user certificates.
+    pub valid_principals: Vec<String>,
+    pub ttl_secs: Option<u64>,
+    /// Needs the role's `allow_user_key_ids`.
+    pub key_id: Option<String>,
+    /// Layered over the role's `default_extensions`.
+    pub extensions: Option<BTreeMap<String, String>>,
+    /// Layered over the role's `default_critical_options`: a request can
+    /// add or (where allowed) re-value options but never drop a default,
+    /// so it cannot strip a role's `force-command`.
+    pub critical_options: Option<BTreeMap<String, String>>,
+}
+
+impl SignRequest {
+    pub fn new(public_key: impl Into<String>) -> Self {
+        Self {
+            public_key: public_key.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn principals<I, P>(mut self, principals: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        self.valid_principals = principals.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn ttl_secs(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = Some(ttl_secs);
+        self
+    }
+}
+
+/// A freshly signed certificate.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct SignedKey {
+    pub serial_number: String,
+    /// `<type>-cert-v01@openssh.com <base64>` — save as `id_*-cert.pub`.
+    pub signed_key: String,
+    pub key_id: String,
+    pub valid_principals: Vec<String>,
+    /// Unix seconds.
+    pub valid_after: i64,
+    pub valid_before: i64,
+}
+
+/// Pure-IO trait. The PG / SQLite impls never see key material in the
+/// clear.
+#[async_trait]
+pub trait SshStore: Send + Sync + 'static {
+    async fn get_ca(&self) -> Result<Option<SshCa>>;
+    /// Store the CA. [`VaultError::Conflict`] when one already exists.
+    async fn create_ca(&self, ca: &SshCa) -> Result<()>;
+    /// Remove the CA. Roles and issuance records stay.
+    async fn delete_ca(&self) -> Result<bool>;
+
+    /// Insert or replace a role.
+    async fn put_role(&self, role: &SshRole) -> Result<()>;
+    async fn get_role(&self, name: &str) -> Result<Option<SshRole>>;
+    async fn list_roles(&self) -> Result<Vec<SshRole>>;
+    async fn delete_role(&self, name: &str) -> Result<bool>;
+
+    async fn insert_cert(&self, cert: &SshCert) -> Result<()>;
+    async fn list_certs(&self) -> Result<Vec<SshCert>>;
+}
+
+/// High-level SSH CA API. Cheap to clone.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct SshService<S: SshStore> {
+    store: S,
+    seal_state: SealState,
+    audit: SinkRegistry,
+}
+
+impl<S: SshStore> SshService<S> {
+    pub fn new(store: S, seal_state: SealState) -> Self {
+        Self {
+            store,
+            seal_state,
+            audit: SinkRegistry::new(),
+        }
+    }
+
+    /// Forward `vault.ssh.sign` events to these sinks.
+    pub fn with_audit(mut self, audit: SinkRegistry) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Generate the CA key (ed25519 unless asked otherwise). Returns the
+    /// public key line.
+    pub async fn generate_ca(&self, key_type: Option<&str>) -> Result<String> {
+        let kek = self.seal_state.require_unsealed()?;
+        self.require_no_ca().await?;
+        let key_type = ca_key_type(KeyType::parse(key_type.unwrap_or("ed25519"))?)?;
+        let pair = tokio::task::spawn_blocking(move || keys::generate(key_type))
+            .await
+            .map_err(|e| VaultError::Crypto(format!("ssh keygen task: {e}")))??;
+        self.store_ca(&kek, key_type, &pair.private_der).await
+    }
+
+    /// Install an existing CA key from a PKCS#8 PEM (`BEGIN PRIVATE
+    /// KEY`). Convert an OpenSSH-format key first with
+    /// `ssh-keygen -p -m PKCS8 -f <key>`. Returns the public key line.
+    pub async fn import_ca(&self, private_key_pem: &str) -> Result<String> {
+        let kek = self.seal_state.require_unsealed()?;
+        self.require_no_ca().await?;
+        let private_der = pkcs8_der(private_key_pem)?;
+        let (key_type, _) = keys::inspect_private(&private_der)?;
+        self.store_ca(&kek, ca_key_type(key_type)?, &private_der)
+            .await
+    }
+
+    pub async fn delete_ca(&self) -> Result<()> {
+        if !self.store.delete_ca().await? {
+            return Err(VaultError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The CA public key line, for `TrustedUserCAKeys` and
+    /// `@cert-authority` entries.
+    pub async fn public_key(&self) -> Result<String> {
+        Ok(self.ca().await?.public_key)
+    }
+
+    pub async fn put_role(&self, name: &str, mut role: SshRole) -> Result<()> {
+        validate_role_name(name)?;
+        match role.cert_type.as_str() {
+            "user" => {}
+            "host" => {
+                if role.default_extensions == SshRole::default().default_extensions {
+                    role.default_extensions.clear();
+                }
+                if !role.default_extensions.is_empty()
+                    || !role.allowed_extensions.is_empty()
+                    || !role.default_critical_options.is_empty()
+                    || !role.allowed_critical_options.is_empty()
+                {
+                    return Err(VaultError::Invalid(
+                        "host certificates carry no extensions or critical options".into(),
+                    ));
+                }
+            }
+            other => {
+                return Err(VaultError::Invalid(format!(
+                    "cert_type '{other}'; expected user or host"
+                )));
+            }
+        }
+        if role.ttl_secs == 0 || role.max_ttl_secs < role.ttl_secs {
+            return Err(VaultError::Invalid(
+                "role needs 0 < ttl_secs <= max_ttl_secs".into(),
+            ));
+        }
+        role.name = name.to_string();
+        self.store.put_role(&role).await
+    }
+
+    pub async fn get_role(&self, name: &str) -> Result<SshRole> {
+        self.store.get_role(name).await?.ok_or(VaultError::NotFound)
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<SshRole>> {
+        self.store.list_roles().await
+    }
+
+    pub async fn delete_role(&self, name: &str) -> Result<()> {
+        if !self.store.delete_role(name).await? {
+            return Err(VaultError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Sign a public key under a role. `actor` names the caller for the
+    /// issuance record and audit event.
+    pub async fn sign(
+        &self,
+        role: &str,
+        request: &SignRequest,
+        actor: Option<&str>,
+    ) -> Result<SignedKey> {
+        let role = self.get_role(role).await?;
+        let key = PublicKey::parse(&request.public_key)?;
+        let cert_type = if role.cert_type == "host" {
+            CertType::Host
+        } else {
+            CertType::User
+        };
+        let principals = check_principals(&role, cert_type, &request.valid_principals)?;
+        let extensions = check_options(
+            request.extensions.as_ref(),
+            &role.default_extensions,
+            &role.allowed_extensions,
+        )?;
+        let critical_options = check_options(
+            request.critical_options.as_ref(),
+            &role.default_critical_options,
+            &role.allowed_critical_options,
+        )?;
+        let ttl = role_ttl(&role, request.ttl_secs)?;
+        let fingerprint = key.fingerprint();
+        let key_id = match &request.key_id {
+            Some(_) if !role.allow_user_key_ids => return Err(VaultError::Forbidden),
+            Some(key_id) => key_id.clone(),
+            None => format!("assay-{}-{fingerprint}", role.name),
+        };
+
+        let ca = self.ca().await?;
+        let (key_type, private_der) = self.unseal(&ca)?;
+        let ca_key = PublicKey::parse(&ca.public_key)?;
+        let now = Utc::now().timestamp() as u64;
+        let valid_after = now.saturating_sub(CLOCK_SKEW_SECS);
+        let valid_before = now + ttl;
+        let serial = rand::rng().next_u64();
+        let signed_key = wire::build_certificate(
+            &ca_key,
+            key_type,
+            &private_der,
+            &CertTemplate {
+                key: &key,
+                serial,
+                cert_type,
+                key_id: &key_id,
+                principals: &principals,
+                valid_after,
+                valid_before,
+                critical_options: &critical_options,
+                extensions: &extensions,
+            },
+        )?;
+
+        let record = SshCert {
+            serial_number: format!("{serial:016x}"),
+            role: role.name.clone(),
+            cert_type: role.cert_type.clone(),
+            key_id: key_id.clone(),
+            principals: principals.clone(),
+            fingerprint,
+            valid_before: valid_before as f64,
+            actor: actor.map(str::to_string),
+            created_at: now as f64,
+        };
+        self.store.insert_cert(&record).await?;
+        self.audit.dispatch(&issuance_event(&record)).await;
+
+        Ok(SignedKey {
+            serial_number: record.serial_number,
+            signed_key,
+            key_id,
+            valid_principals: principals,
+            valid_after: valid_after as i64,
+            valid_before: valid_before as i64,
+        })
+    }
+
+    pub async fn list_certs(&self) -> Result<Vec<SshCert>> {
+        self.store.list_certs().await
+    }
+
+    async fn ca(&self) -> Result<SshCa> {
+        self.store.get_ca().await?.ok_or(VaultError::NotFound)
+    }
+
+    async fn require_no_ca(&self) -> Result<()> {
+        if self.store.get_ca().await?.is_some() {
+            return Err(VaultError::Conflict(
+                "an SSH CA is already configured; delete it first".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn store_ca(
+        &self,
+        kek: &KekHandle,
+        key_type: KeyType,
+        private_der: &[u8],
+    ) -> Result<String> {
+        let public_key = PublicKey::from_private(key_type, private_der)?.to_line();
+        let dek = random_dek();
+        let key_wrapped = kek.wrap_dek(&dek)?;
+        let nonce = random_nonce();
+        let sealed = encrypt(&dek, &nonce, PRIVATE_AAD, private_der)?;
+        let mut private_key = Vec::with_capacity(NONCE_LEN + sealed.len());
+        private_key.extend_from_slice(&nonce);
+        private_key.extend_from_slice(&sealed);
+        self.store
+            .create_ca(&SshCa {
+                key_type: key_type.as_str().to_string(),
+                public_key: public_key.clone(),
+                private_key,
+                key_wrapped: key_wrapped.into_bytes(),
+                kek_kid: kek.kid().to_string(),
+                created_at: Utc::now().timestamp() as f64,
+            })
+            .await?;
+        Ok(public_key)
+    }
+
+    /// Unseal the CA private key for one signing call.
+    fn unseal(&self, ca: &SshCa) -> Result<(KeyType, Vec<u8>)> {
+        let kek = self.seal_state.require_unsealed()?;
+        if ca.kek_kid != kek.kid() {
+            return Err(VaultError::Crypto(format!(
+                "SSH CA key wrapped with KEK {} but the active KEK is {}",
+                ca.kek_kid,
+                kek.kid()
+            )));
+        }
+        let dek = kek.unwrap_dek(&WrappedDek::from_bytes(ca.key_wrapped.clone()))?;
+        if ca.private_key.len() < NONCE_LEN {
+            return Err(VaultError::Crypto("sealed SSH CA key truncated".into()));
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&ca.private_key[..NONCE_LEN]);
+        let private_der = decrypt(&dek, &nonce, PRIVATE_AAD, &ca.private_key[NONCE_LEN..])?;
+        Ok((KeyType::parse(&ca.key_type)?, private_der))
+    }
+}
+
+/// AAD for the sealed CA key.
+const PRIVATE_AAD: &[u8] = b"vault.ssh.ca";
+
+fn issuance_event(cert: &SshCert) -> AuditEvent {
+    let event = AuditEvent::now("vault.ssh.sign")
+        .field("serial_number", cert.serial_number.clone().into())
+        .field("role", cert.role.clone().into())
+        .field("cert_type", cert.cert_type.clone().into())
+        .field("key_id", cert.key_id.clone().into())
+        .field("principals", cert.principals.clone().into())
+        .field("fingerprint", cert.fingerprint.clone().into())
+        .field("valid_before", cert.valid_before.into());
+    match &cert.actor {
+        Some(actor) => event.actor(actor.clone()),
+        None => event,
+    }
+}
+
+fn ca_key_type(key_type: KeyType) -> Result<KeyType> {
+    if !key_type.supports_signing() {
+        return Err(VaultError::Invalid(format!(
+            "{} is not an SSH CA key type; expected ed25519, ecdsa-p256, rsa-2048 or rsa-4096",
+            key_type.as_str()
+        )));
+    }
+    Ok(key_type)
+}
+
+fn pkcs8_der(pem: &str) -> Result<Vec<u8>> {
+    match rsa::pkcs8::SecretDocument::from_pem(pem.trim()) {
+        Ok(("PRIVATE KEY", doc)) => Ok(doc.as_bytes().to_vec()),
+        _ => Err(VaultError::Invalid(
+            "private_key must be a PKCS#8 PEM (BEGIN PRIVATE KEY)".into(),
+        )),
+    }
+}
+
+fn check_principals(
+    role: &SshRole,
+    cert_type: CertType,
+    requested: &[String],
+) -> Result<Vec<String>> {
+    let principals = if requested.is_empty() {
+        if cert_type == CertType::User && !role.default_user.is_empty() {
+            vec![role.default_user.clone()]
+        } else {
+            return Err(VaultError::Invalid("valid_principals is required".into()));
+        }
+    } else {
+        requested.to_vec()
+    };
+    for principal in &principals {
+        let allowed = match cert_type {
+            CertType::User => {
+                *principal == role.default_user
+                    || role
+                        .allowed_users
+                        .iter()
+                        .any(|u| u == "*" || u == principal)
+            }
+            CertType::Host => host_allowed(role, principal),
+        };
+        if principal.is_empty() || !allowed {
+            return Err(VaultError::Forbidden);
+        }
+    }
+    Ok(principals)
+}
+
+fn host_allowed(role: &SshRole, host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    role.allowed_domains.iter().any(|domain| {
+        let domain = domain.to_ascii_lowercase();
+        host == domain || (role.allow_subdomains && host.ends_with(&format!(".{domain}")))
+    })
+}
+
+/// The role's defaults with the request's options (each must be
+/// allowed) layered on top. Defaults are never dropped.
+fn check_options(
+    requested: Option<&BTreeMap<String, String>>,
+    defaults: &BTreeMap<String, String>,
+    allowed: &[String],
+) -> Result<BTreeMap<String, String>> {
+    let mut options = defaults.clone();
+    for (name, value) in requested.into_iter().flatten() {
+        if !allowed.iter().any(|a| a == "*" || a == name) {
+            return Err(VaultError::Forbidden);
+        }
+        options.insert(name.clone(), value.clone());
+    }
+    Ok(options)
+}
+
+fn role_ttl(role: &SshRole, requested: Option<u64>) -> Result<u64> {
+    match requested {
+        None => Ok(role.ttl_secs),
+        Some(0) => Err(VaultError::Invalid("ttl_secs must be positive".into())),
+        Some(ttl) if ttl > role.max_ttl_secs => Err(VaultError::Invalid(format!(
+            "ttl_secs {ttl} exceeds role '{}' max_ttl_secs {}",
+            role.name, role.max_ttl_secs
+        ))),
+        Some(ttl) => Ok(ttl),
+    }
+}
+
+fn validate_role_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > 128
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(VaultError::Invalid(
+            "SSH role name: 1-128 chars of [A-Za-z0-9._-]".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_role() -> SshRole {
+        SshRole {
+            name: "ops".into(),
+            allowed_users: vec!["deploy".into(), "root".into()],
+            default_user: "deploy".into(),
+            allowed_extensions: vec!["permit-pty".into(), "permit-agent-forwarding".into()],
+            ..SshRole::default()
+        }
+    }
+
+    #[test]
+    fn user_principals_follow_the_role() {
+        let role = user_role();
+        assert_eq!(
+            check_principals(&role, CertType::User, &[]).unwrap(),
+            ["deploy"]
+        );
+        assert!(check_principals(&role, CertType::User, &["root".into()]).is_ok());
+        assert!(matches!(
+            check_principals(&role, CertType::User, &["admin".into()]),
+            Err(VaultError::Forbidden)
+        ));
+    }
+
+    #[test]
+    fn host_principals_need_an_allowed_domain() {
+        let role = SshRole {
+            cert_type: "host".into(),
+            allowed_domains: vec!["nodes.internal".into()],
+            allow_subdomains: true,
+            ..SshRole::default()
+        };
+        assert!(check_principals(&role, CertType::Host, &[]).is_err());
+        assert!(check_principals(&role, CertType::Host, &["web1.nodes.internal".into()]).is_ok());
+        assert!(check_principals(&role, CertType::Host, &["web1.evil.internal".into()]).is_err());
+    }
+
+    #[test]
+    fn requested_extensions_layer_over_the_defaults() {
+        let role = user_role();
+        let defaults =
+            check_options(None, &role.default_extensions, &role.allowed_extensions).unwrap();
+        assert!(defaults.contains_key("permit-pty"));
+        let requested = BTreeMap::from([("permit-agent-forwarding".to_string(), String::new())]);
+        let picked = check_options(
+            Some(&requested),
+            &role.default_extensions,
+            &role.allowed_extensions,
+        )
+        .unwrap();
+        assert_eq!(
+            picked.keys().collect::<Vec<_>>(),
+            ["permit-agent-forwarding", "permit-pty"]
+        );
+        let x11 = BTreeMap::from([("permit-X11-forwarding".to_string(), String::new())]);
+        assert!(
+            check_options(
+                Some(&x11),
+                &role.default_extensions,
+                &role.allowed_extensions
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! SSH wire encoding (RFC 4251 §5) and the OpenSSH certificate format
+//! (`PROTOCOL.certkeys` in the OpenSSH tree).
+//!
+//! Only what the CA needs: parse an `authorized_keys`-style public key,
+//! derive the CA's own public key from its PKCS#8 private key, sign, and
+//! lay out a `*-cert-v01@openssh.com` blob.
+
+use std::collections::BTreeMap;
+
+use data_encoding::{BASE64, BASE64_NOPAD};
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::traits::PublicKeyParts;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, VaultError};
+use crate::transit::{KeyType, SignatureAlgorithm, keys};
+
+/// Smallest RSA modulus accepted for a key to be signed.
+const MIN_RSA_BITS: usize = 2048;
+
+#[derive(Default)]
+pub(crate) struct Writer(Vec<u8>);
+
+impl Writer {
+    pub fn u32(&mut self, n: u32) -> &mut Self {
+        self.0.extend_from_slice(&n.to_be_bytes());
+        self
+    }
+
+    pub fn u64(&mut self, n: u64) -> &mut Self {
+        self.0.extend_from_slice(&n.to_be_bytes());
+        self
+    }
+
+    pub fn string(&mut self, bytes: &[u8]) -> &mut Self {
+        self.u32(bytes.len() as u32);
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    /// Unsigned big-endian magnitude as an `mpint`: leading zeros
+    /// stripped, one zero byte added back when the top bit is set.
+    pub fn mpint(&mut self, magnitude: &[u8]) -> &mut Self {
+        let start = magnitude
+            .iter()
+            .position(|&b| b != 0)
+            .unwrap_or(magnitude.len());
+        let magnitude = &magnitude[start..];
+        if magnitude.first().is_some_and(|&b| b & 0x80 != 0) {
+            self.u32(magnitude.len() as u32 + 1);
+            self.0.push(0);
+            self.0.extend_from_slice(magnitude);
+            self
+        } else {
+            self.string(magnitude)
+        }
+    }
+
+    pub fn raw(&mut self, bytes: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self(buf)
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn string(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(malformed());
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+}
+
+/// An SSH public key: its algorithm name and wire-format blob.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PublicKey {
+    pub algorithm: String,
+    pub blob: Vec<u8>,
+}
+
+impl PublicKey {
+    /// Parse `<algorithm> <base64 blob> [comment]`, checking the blob
+    /// is a well-formed key of a type the CA will sign.
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut parts = line.split_whitespace();
+        let (Some(algorithm), Some(encoded)) = (parts.next(), parts.next()) else {
+            return Err(VaultError::Invalid(
+                "public_key must be '<type> <base64> [comment]'".into(),
+            ));
+        };
+        let blob = BASE64
+            .decode(encoded.as_bytes())
+            .map_err(|_| VaultError::Invalid("public_key is not valid base64".into()))?;
+        let mut r = Reader::new(&blob);
+        if r.string()? != algorithm.as_bytes() {
+            return Err(VaultError::Invalid(
+                "public_key type does not match its blob".into(),
+            ));
+        }
+        match algorithm {
+            "ssh-ed25519" => {
+                if r.string()?.len() != 32 {
+                    return Err(malformed());
+                }
+            }
+            "ecdsa-sha2-nistp256" | "ecdsa-sha2-nistp384" | "ecdsa-sha2-nistp521" => {
+                let curve = &algorithm["ecdsa-sha2-".len()..];
+                let point_len = match curve {
+                    "nistp256" => 65,
+                    "nistp384" => 97,
+                    _ => 133,
+                };
+                let point = (r.string()? == curve.as_bytes())
+                    .then(|| r.string())
+                    .transpose()?;
+                if point.is_none_or(|p| p.len() != point_len || p[0] != 0x04) {
+                    return Err(malformed());
+                }
+            }
+            "ssh-rsa" => {
+                let _e = r.string()?;
+                let n = r.string()?;
+                let bits = n.iter().skip_while(|&&b| b == 0).count() * 8;
+                if bits < MIN_RSA_BITS {
+                    return Err(VaultError::Invalid(format!(
+                        "RSA keys must be at least {MIN_RSA_BITS} bits"
+                    )));
+                }
+            }
+            other if other.contains("-cert-") => {
+                return Err(VaultError::Invalid(
+                    "public_key is already a certificate".into(),
+                ));
+            }
+            other => {
+                return Err(VaultError::Invalid(format!(
+                    "unsupported public key type '{other}'; expected ssh-ed25519, \
+                     ecdsa-sha2-nistp256/384/521 or ssh-rsa"
+                )));
+            }
+        }
+        if !r.is_empty() {
+            return Err(malformed());
+        }
+        Ok(Self {
+            algorithm: algorithm.to_string(),
+            blob,
+        })
+    }
+
+    /// The public half of a PKCS#8 private key of `key_type`.
+    pub fn from_private(key_type: KeyType, private_der: &[u8]) -> Result<Self> {
+        let decode_err = |e: &dyn std::fmt::Display| VaultError::Crypto(format!("ssh CA key: {e}"));
+        let mut w = Writer::default();
+        let algorithm = match key_type {
+            KeyType::Ed25519 => {
+                let key = ed25519_dalek::SigningKey::from_pkcs8_der(private_der)
+                    .map_err(|e| decode_err(&e))?;
+                w.string(b"ssh-ed25519")
+                    .string(key.verifying_key().as_bytes());
+                "ssh-ed25519"
+            }
+            KeyType::EcdsaP256 => {
+                let key = p256::ecdsa::SigningKey::from_pkcs8_der(private_der)
+                    .map_err(|e| decode_err(&e))?;
+                let point = key.verifying_key().to_encoded_point(false);
+                w.string(b"ecdsa-sha2-nistp256")
+                    .string(b"nistp256")
+                    .string(point.as_bytes());
+                "ecdsa-sha2-nistp256"
+            }
+            KeyType::Rsa2048 | KeyType::Rsa4096 => {
+                let key =
+                    rsa::RsaPrivateKey::from_pkcs8_der(private_der).map_err(|e| decode_err(&e))?;
+                w.string(b"ssh-rsa")
+                    .mpint(&key.e().to_bytes_be())
+                    .mpint(&key.n().to_bytes_be());
+                "ssh-rsa"
+            }
+            other => {
+                return Err(VaultError::Invalid(format!(
+                    "{} cannot sign SSH certificates",
+                    other.as_str()
+                )));
+            }
+        };
+        Ok(Self {
+            algorithm: algorithm.to_string(),
+            blob: w.into_inner(),
+        })
+    }
+
+    /// `ssh-keygen -l` style fingerprint: `SHA256:<unpadded base64>`.
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "SHA256:{}",
+            BASE64_NOPAD.encode(&Sha256::digest(&self.blob))
+        )
+    }
+
+    /// The `authorized_keys` / `TrustedUserCAKeys` line, no comment.
+    pub fn to_line(&self) -> String {
+        format!("{} {}", self.algorithm, BASE64.encode(&self.blob))
+    }
+
+    /// The key's fields after the leading algorithm string.
+    fn fields(&self) -> &[u8] {
+        &self.blob[4 + self.algorithm.len()..]
+    }
+}
+
+/// Sign `data` with the CA key, returning an SSH signature blob
+/// (`string algorithm, string signature`).
+pub(crate) fn sign(key_type: KeyType, private_der: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let raw = keys::sign(key_type, private_der, data, SignatureAlgorithm::Pkcs1v15)?;
+    let mut w = Writer::default();
+    match key_type {
+        KeyType::Ed25519 => {
+            w.string(b"ssh-ed25519").string(&raw);
+        }
+        KeyType::EcdsaP256 => {
+            let sig = p256::ecdsa::Signature::from_der(&raw)
+                .map_err(|e| VaultError::Crypto(format!("ecdsa signature: {e}")))?;
+            let mut inner = Writer::default();
+            inner.mpint(&sig.r().to_bytes()).mpint(&sig.s().to_bytes());
+            w.string(b"ecdsa-sha2-nistp256").string(&inner.into_inner());
+        }
+        // transit's PKCS#1 v1.5 signatures are over SHA-256.
+        _ => {
+            w.string(b"rsa-sha2-256").string(&raw);
+        }
+    }
+    Ok(w.into_inner())
+}
+
+/// `SSH_CERT_TYPE_USER` / `SSH_CERT_TYPE_HOST`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CertType {
+    User = 1,
+    Host = 2,
+}
+
+pub(crate) struct CertTemplate<'a> {
+    pub key: &'a PublicKey,
+    pub serial: u64,
+    pub cert_type: CertType,
+    pub key_id: &'a str,
+    pub principals: &'a [String],
+    pub valid_after: u64,
+    pub valid_before: u64,
+    pub critical_options: &'a BTreeMap<String, String>,
+    pub extensions: &'a BTreeMap<String, String>,
+}
+
+/// Build and sign a certificate; returns it as an `authorized_keys`
+/// style line (`<type>-cert-v01@openssh.com <base64>`).
+pub(crate) fn build_certificate(
+    ca: &PublicKey,
+    key_type: KeyType,
+    private_der: &[u8],
+    t: &CertTemplate<'_>,
+) -> Result<String> {
+    let algorithm = format!("{}-cert-v01@openssh.com", t.key.algorithm);
+    let mut nonce = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::rng(), &mut nonce);
+    let mut principals = Writer::default();
+    for p in t.principals {
+        principals.string(p.as_bytes());
+    }
+    let mut w = Writer::default();
+    w.string(algorithm.as_bytes())
+        .string(&nonce)
+        .raw(t.key.fields())
+        .u64(t.serial)
+        .u32(t.cert_type as u32)
+        .string(t.key_id.as_bytes())
+        .string(&principals.into_inner())
+        .u64(t.valid_after)
+        .u64(t.valid_before)
+        .string(&options(t.critical_options))
+        .string(&options(t.extensions))
+        .string(b"")
+        .string(&ca.blob);
+    let signature = sign(key_type, private_der, &w.0)?;
+    w.string(&signature);
+    Ok(format!("{algorithm} {}", BASE64.encode(&w.into_inner())))
+}
+
+/// Critical options and extensions: name-sorted `(name, data)` pairs,
+/// where data is empty for flags and otherwise a string holding the
+/// value.
+fn options(options: &BTreeMap<String, String>) -> Vec<u8> {
+    let mut w = Writer::default();
+    for (name, value) in options {
+        w.string(name.as_bytes());
+        if value.is_empty() {
+            w.string(b"");
+        } else {
+            let mut inner = Writer::default();
+            inner.string(value.as_bytes());
+            w.string(&inner.into_inner());
+        }
+    }
+    w.into_inner()
+}
+
+fn malformed() -> VaultError {
+    VaultError::Invalid("malformed SSH public key".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mpints_follow_rfc_4251() {
+        let encode = |m: &[u8]| {
+            let mut w = Writer::default();
+            w.mpint(m);
+            w.into_inner()
+        };
+        assert_eq!(encode(&[]), [0, 0, 0, 0]);
+        assert_eq!(encode(&[0, 0, 0x12]), [0, 0, 0, 1, 0x12]);
+        assert_eq!(encode(&[0x80]), [0, 0, 0, 2, 0, 0x80]);
+    }
+
+    #[test]
+    fn generated_ca_keys_round_trip_through_the_line_form() {
+        for key_type in [KeyType::Ed25519, KeyType::EcdsaP256, KeyType::Rsa2048] {
+            let pair = keys::generate(key_type).unwrap();
+            let key = PublicKey::from_private(key_type, &pair.private_der).unwrap();
+            assert_eq!(PublicKey::parse(&key.to_line()).unwrap(), key);
+            assert!(key.fingerprint().starts_with("SHA256:"));
+        }
+    }
+
+    #[test]
+    fn parse_rejects_mismatched_and_certificate_keys() {
+        let pair = keys::generate(KeyType::Ed25519).unwrap();
+        let key = PublicKey::from_private(KeyType::Ed25519, &pair.private_der).unwrap();
+        let line = key.to_line();
+        let blob = line.split(' ').nth(1).unwrap();
+        assert!(PublicKey::parse(&format!("ssh-rsa {blob}")).is_err());
+        assert!(PublicKey::parse(&format!("{line} laptop")).is_ok());
+        assert!(PublicKey::parse("ssh-ed25519").is_err());
+        assert!(PublicKey::parse("ssh-ed25519-cert-v01@openssh.com AAAA").is_err());
+    }
+}
@@ -17,7 +17,7 @@ pub async fn migrate(pool: &PgPool) -> Result<()> {
 mod kv {
     use super::*;
     use crate::error::{Result as VaultResult, VaultError};
-    use crate::kv::{KvMeta, KvRow, KvStore};
+    use crate::kv::{KvMeta, KvMetaUpdate, KvRow, KvStore, KvVersion, cas_mismatch, check_cas};
     use serde_json::Value;
 
     /// Postgres-backed KV store. Cheap to clone — wraps a `sqlx::PgPool`.
@@ -36,6 +36,26 @@ mod kv {
         move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
     }
 
+    type MetaRow = (String, i64, Value, bool, i64, i64, f64, f64);
+
+    const META_COLUMNS: &str = "path, latest_version, custom_md, cas_required, max_versions, \
+                                delete_version_after, created_at, updated_at";
+
+    fn meta_from_row(row: MetaRow) -> KvMeta {
+        let (path, latest_version, custom_md, cas_required, max_versions, delete_after, ca, ua) =
+            row;
+        KvMeta {
+            path,
+            latest_version,
+            custom_md,
+            cas_required,
+            max_versions,
+            delete_version_after: delete_after,
+            created_at: ca,
+            updated_at: ua,
+        }
+    }
+
     #[async_trait]
     impl KvStore for PgKvStore {
         async fn put_row(
@@ -46,13 +66,31 @@ mod kv {
             wrapped_dek: &[u8],
             kek_kid: &str,
             custom_md: &Value,
+            cas: Option<i64>,
         ) -> VaultResult<i64> {
             let mut tx = self.pool.begin().await.map_err(map_err("kv put begin"))?;
 
+            // Lock the path's meta row for the rest of the transaction so
+            // the CAS check and the bump below see the same version.
+            let existing: Option<(i64, bool, i64, i64)> = sqlx::query_as(
+                "SELECT latest_version, cas_required, max_versions, delete_version_after
+                   FROM vault.kv_meta
+                  WHERE path = $1
+                    FOR UPDATE",
+            )
+            .bind(path)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(map_err("kv put read existing meta"))?;
+            let (latest, cas_required, max_versions, delete_after) = existing.unwrap_or_default();
+            check_cas(latest, cas_required, cas)?;
+
             // UPSERT meta — bump latest_version atomically and merge
             // custom_md. `custom_md = '{}'` keeps the existing row's md
-            // so a vanilla PUT with no metadata is non-destructive.
-            let new_version: i64 = sqlx::query_scalar(
+            // so a vanilla PUT with no metadata is non-destructive. The
+            // WHERE re-asserts CAS for a path another writer created
+            // after the read above; no row back means that writer won.
+            let new_version: Option<i64> = sqlx::query_scalar(
                 "INSERT INTO vault.kv_meta (path, latest_version, custom_md, created_at, updated_at)
                  VALUES ($1, 1, $2, EXTRACT(EPOCH FROM NOW()), EXTRACT(EPOCH FROM NOW()))
                  ON CONFLICT (path) DO UPDATE
@@ -62,18 +100,22 @@ mod kv {
                                      ELSE vault.kv_meta.custom_md || $2::jsonb
                                    END,
                        updated_at = EXTRACT(EPOCH FROM NOW())
+                 WHERE $3::bigint IS NULL OR vault.kv_meta.latest_version = $3
                  RETURNING latest_version",
             )
             .bind(path)
             .bind(custom_md)
-            .fetch_one(&mut *tx)
+            .bind(cas)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(map_err("kv put upsert meta"))?;
+            let new_version = new_version.ok_or_else(|| cas_mismatch(latest))?;
 
             sqlx::query(
                 "INSERT INTO vault.kv
-                    (path, version, ciphertext, nonce, wrapped_dek, kek_kid)
-                 VALUES ($1, $2, $3, $4, $5, $6)",
+                    (path, version, ciphertext, nonce, wrapped_dek, kek_kid, deleted_at)
+                 VALUES ($1, $2, $3, $4, $5, $6,
+                         CASE WHEN $7::bigint > 0 THEN EXTRACT(EPOCH FROM NOW()) + $7::bigint END)",
             )
             .bind(path)
             .bind(new_version)
@@ -81,10 +123,20 @@ mod kv {
             .bind(nonce)
             .bind(wrapped_dek)
             .bind(kek_kid)
+            .bind(delete_after)
             .execute(&mut *tx)
             .await
             .map_err(map_err("kv put insert row"))?;
 
+            if max_versions > 0 {
+                sqlx::query("DELETE FROM vault.kv WHERE path = $1 AND version <= $2")
+                    .bind(path)
+                    .bind(new_version - max_versions)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("kv put prune"))?;
+            }
+
             tx.commit().await.map_err(map_err("kv put commit"))?;
             Ok(new_version)
         }
@@ -143,47 +195,78 @@ mod kv {
 
         async fn list_meta(&self, prefix: &str) -> VaultResult<Vec<KvMeta>> {
             let pattern = format!("{}%", prefix.replace('%', "\\%").replace('_', "\\_"));
-            let rows: Vec<(String, i64, Value, f64, f64)> = sqlx::query_as(
-                "SELECT path, latest_version, custom_md, created_at, updated_at
+            let rows: Vec<MetaRow> = sqlx::query_as(&format!(
+                "SELECT {META_COLUMNS}
                    FROM vault.kv_meta
                   WHERE path LIKE $1 ESCAPE '\\'
-                  ORDER BY path",
-            )
+                  ORDER BY path"
+            ))
             .bind(pattern)
             .fetch_all(&self.pool)
             .await
             .map_err(map_err("kv list_meta"))?;
-            Ok(rows
-                .into_iter()
-                .map(
-                    |(path, latest_version, custom_md, created_at, updated_at)| KvMeta {
-                        path,
-                        latest_version,
-                        custom_md,
-                        created_at,
-                        updated_at,
-                    },
-                )
-                .collect())
+            Ok(rows.into_iter().map(meta_from_row).collect())
         }
 
         async fn read_meta(&self, path: &str) -> VaultResult<Option<KvMeta>> {
-            let row: Option<(i64, Value, f64, f64)> = sqlx::query_as(
-                "SELECT latest_version, custom_md, created_at, updated_at
-                   FROM vault.kv_meta
-                  WHERE path = $1",
-            )
+            let row: Option<MetaRow> = sqlx::query_as(&format!(
+                "SELECT {META_COLUMNS} FROM vault.kv_meta WHERE path = $1"
+            ))
             .bind(path)
             .fetch_optional(&self.pool)
             .await
             .map_err(map_err("kv read_meta"))?;
-            Ok(row.map(|(lv, md, ca, ua)| KvMeta {
-                path: path.to_string(),
-                latest_version: lv,
-                custom_md: md,
-                created_at: ca,
-                updated_at: ua,
-            }))
+            Ok(row.map(meta_from_row))
+        }
+
+        async fn put_meta(&self, path: &str, update: &KvMetaUpdate) -> VaultResult<KvMeta> {
+            let row: MetaRow = sqlx::query_as(&format!(
+                "INSERT INTO vault.kv_meta
+                    (path, latest_version, custom_md, cas_required, max_versions,
+                     delete_version_after, created_at, updated_at)
+                 VALUES ($1, 0, COALESCE($2::jsonb, '{{}}'::jsonb), COALESCE($3::boolean, FALSE),
+                         COALESCE($4::bigint, 0), COALESCE($5::bigint, 0),
+                         EXTRACT(EPOCH FROM NOW()), EXTRACT(EPOCH FROM NOW()))
+                 ON CONFLICT (path) DO UPDATE
+                   SET custom_md = COALESCE($2::jsonb, vault.kv_meta.custom_md),
+                       cas_required = COALESCE($3::boolean, vault.kv_meta.cas_required),
+                       max_versions = COALESCE($4::bigint, vault.kv_meta.max_versions),
+                       delete_version_after =
+                           COALESCE($5::bigint, vault.kv_meta.delete_version_after),
+                       updated_at = EXTRACT(EPOCH FROM NOW())
+                 RETURNING {META_COLUMNS}"
+            ))
+            .bind(path)
+            .bind(update.custom_md.as_ref())
+            .bind(update.cas_required)
+            .bind(update.max_versions)
+            .bind(update.delete_version_after)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(map_err("kv put_meta"))?;
+            Ok(meta_from_row(row))
+        }
+
+        async fn list_versions(&self, path: &str) -> VaultResult<Vec<KvVersion>> {
+            let rows: Vec<(i64, f64, Option<f64>, bool)> = sqlx::query_as(
+                "SELECT version, created_at, deleted_at, destroyed
+                   FROM vault.kv
+                  WHERE path = $1
+                  ORDER BY version",
+            )
+            .bind(path)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("kv list_versions"))?;
+            Ok(rows
+                .into_iter()
+                .map(|(version, created_at, deleted_at, destroyed)| KvVersion {
+                    version,
+                    created_at,
+                    deleted_at,
+                    destroyed,
+                })
+                .collect())
         }
 
         async fn soft_delete(
@@ -198,7 +281,7 @@ mod kv {
                   WHERE path = $1
                     AND version = $2
                     AND destroyed = FALSE
-                    AND deleted_at IS NULL",
+                    AND (deleted_at IS NULL OR deleted_at > $3)",
             )
             .bind(path)
             .bind(version)
@@ -244,6 +327,29 @@ mod kv {
             .rows_affected();
             Ok(n > 0)
         }
+
+        async fn delete_path(&self, path: &str) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("kv delete_path begin"))?;
+            sqlx::query("DELETE FROM vault.kv WHERE path = $1")
+                .bind(path)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("kv delete_path versions"))?;
+            let n = sqlx::query("DELETE FROM vault.kv_meta WHERE path = $1")
+                .bind(path)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("kv delete_path meta"))?
+                .rows_affected();
+            tx.commit()
+                .await
+                .map_err(map_err("kv delete_path commit"))?;
+            Ok(n > 0)
+        }
     }
 }
 
@@ -272,6 +378,23 @@ mod transit {
         move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
     }
 
+    type VersionRow = (i64, Vec<u8>, String, Option<Vec<u8>>, Option<String>, f64);
+
+    fn version_from_row(
+        name: &str,
+        (version, kw, kk, pk, pub_pem, ca): VersionRow,
+    ) -> TransitVersion {
+        TransitVersion {
+            name: name.to_string(),
+            version,
+            key_wrapped: kw,
+            kek_kid: kk,
+            private_key: pk,
+            public_key: pub_pem,
+            created_at: ca,
+        }
+    }
+
     #[async_trait]
     impl TransitStore for PgTransitStore {
         async fn create_key(
@@ -280,6 +403,8 @@ mod transit {
             algo: &str,
             version_wrapped: &[u8],
             kek_kid: &str,
+            private_key: Option<&[u8]>,
+            public_key: Option<&str>,
         ) -> VaultResult<()> {
             let mut tx = self
                 .pool
@@ -307,12 +432,15 @@ mod transit {
             res.map_err(map_err("transit create insert key"))?;
 
             sqlx::query(
-                "INSERT INTO vault.transit_versions (name, version, key_wrapped, kek_kid)
-                 VALUES ($1, 1, $2, $3)",
+                "INSERT INTO vault.transit_versions
+                    (name, version, key_wrapped, kek_kid, private_key, public_key)
+                 VALUES ($1, 1, $2, $3, $4, $5)",
             )
             .bind(name)
             .bind(version_wrapped)
             .bind(kek_kid)
+            .bind(private_key)
+            .bind(public_key)
             .execute(&mut *tx)
             .await
             .map_err(map_err("transit create insert version"))?;
@@ -324,17 +452,20 @@ mod transit {
         }
 
         async fn get_key(&self, name: &str) -> VaultResult<Option<TransitKey>> {
-            let row: Option<(String, i64, f64)> = sqlx::query_as(
-                "SELECT algo, latest_ver, created_at FROM vault.transit_keys WHERE name = $1",
+            let row: Option<(String, i64, i64, f64)> = sqlx::query_as(
+                "SELECT algo, latest_ver, min_decryption_version, created_at
+                   FROM vault.transit_keys
+                  WHERE name = $1",
             )
             .bind(name)
             .fetch_optional(&self.pool)
             .await
             .map_err(map_err("transit get_key"))?;
-            Ok(row.map(|(algo, lv, ca)| TransitKey {
+            Ok(row.map(|(algo, lv, mdv, ca)| TransitKey {
                 name: name.to_string(),
                 algo,
                 latest_ver: lv,
+                min_decryption_version: mdv,
                 created_at: ca,
             }))
         }
@@ -344,8 +475,8 @@ mod transit {
             name: &str,
             version: i64,
         ) -> VaultResult<Option<TransitVersion>> {
-            let row: Option<(Vec<u8>, String, f64)> = sqlx::query_as(
-                "SELECT key_wrapped, kek_kid, created_at
+            let row: Option<VersionRow> = sqlx::query_as(
+                "SELECT version, key_wrapped, kek_kid, private_key, public_key, created_at
                    FROM vault.transit_versions
                   WHERE name = $1 AND version = $2",
             )
@@ -354,13 +485,7 @@ mod transit {
             .fetch_optional(&self.pool)
             .await
             .map_err(map_err("transit get_version"))?;
-            Ok(row.map(|(kw, kk, ca)| TransitVersion {
-                name: name.to_string(),
-                version,
-                key_wrapped: kw,
-                kek_kid: kk,
-                created_at: ca,
-            }))
+            Ok(row.map(|r| version_from_row(name, r)))
         }
 
         async fn get_latest_version(&self, name: &str) -> VaultResult<Option<TransitVersion>> {
@@ -384,6 +509,8 @@ mod transit {
             name: &str,
             version_wrapped: &[u8],
             kek_kid: &str,
+            private_key: Option<&[u8]>,
+            public_key: Option<&str>,
         ) -> VaultResult<i64> {
             let mut tx = self
                 .pool
@@ -402,13 +529,16 @@ mod transit {
             .map_err(map_err("transit rotate bump"))?
             .ok_or(VaultError::NotFound)?;
             sqlx::query(
-                "INSERT INTO vault.transit_versions (name, version, key_wrapped, kek_kid)
-                 VALUES ($1, $2, $3, $4)",
+                "INSERT INTO vault.transit_versions
+                    (name, version, key_wrapped, kek_kid, private_key, public_key)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
             )
             .bind(name)
             .bind(new_ver)
             .bind(version_wrapped)
             .bind(kek_kid)
+            .bind(private_key)
+            .bind(public_key)
             .execute(&mut *tx)
             .await
             .map_err(map_err("transit rotate insert version"))?;
@@ -418,9 +548,41 @@ mod transit {
             Ok(new_ver)
         }
 
+        async fn list_versions(&self, name: &str) -> VaultResult<Vec<TransitVersion>> {
+            let rows: Vec<VersionRow> = sqlx::query_as(
+                "SELECT version, key_wrapped, kek_kid, private_key, public_key, created_at
+                   FROM vault.transit_versions
+                  WHERE name = $1
+                  ORDER BY version",
+            )
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("transit list_versions"))?;
+            Ok(rows
+                .into_iter()
+                .map(|r| version_from_row(name, r))
+                .collect())
+        }
+
+        async fn set_min_decryption_version(&self, name: &str, version: i64) -> VaultResult<()> {
+            let res = sqlx::query(
+                "UPDATE vault.transit_keys SET min_decryption_version = $1 WHERE name = $2",
+            )
+            .bind(version)
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("transit set_min_decryption_version"))?;
+            if res.rows_affected() == 0 {
+                return Err(VaultError::NotFound);
+            }
+            Ok(())
+        }
+
         async fn list_keys(&self) -> VaultResult<Vec<TransitKey>> {
-            let rows: Vec<(String, String, i64, f64)> = sqlx::query_as(
-                "SELECT name, algo, latest_ver, created_at
+            let rows: Vec<(String, String, i64, i64, f64)> = sqlx::query_as(
+                "SELECT name, algo, latest_ver, min_decryption_version, created_at
                    FROM vault.transit_keys
                   ORDER BY name",
             )
@@ -429,10 +591,11 @@ mod transit {
             .map_err(map_err("transit list_keys"))?;
             Ok(rows
                 .into_iter()
-                .map(|(n, a, lv, ca)| TransitKey {
+                .map(|(n, a, lv, mdv, ca)| TransitKey {
                     name: n,
                     algo: a,
                     latest_ver: lv,
+                    min_decryption_version: mdv,
                     created_at: ca,
                 })
                 .collect())
@@ -443,6 +606,509 @@ mod transit {
 #[cfg(feature = "vault-transit")]
 pub use transit::PgTransitStore;
 
+#[cfg(feature = "vault-pki")]
+mod pki {
+    use super::*;
+    use crate::error::{Result as VaultResult, VaultError};
+    use crate::pki::{PkiCa, PkiCert, PkiRole, PkiStore, PkiUrls};
+    use serde_json::Value;
+
+    /// Postgres-backed PKI store.
+    #[derive(Clone)]
+    pub struct PgPkiStore {
+        pool: PgPool,
+    }
+
+    impl PgPkiStore {
+        pub fn new(pool: PgPool) -> Self {
+            Self { pool }
+        }
+    }
+
+    fn map_err(ctx: &'static str) -> impl FnOnce(sqlx::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    fn json_err(ctx: &'static str) -> impl FnOnce(serde_json::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    type CertRow = (String, String, String, String, f64, Option<f64>, f64);
+
+    fn cert_from_row((serial, role, cn, pem, not_after, revoked_at, ca): CertRow) -> PkiCert {
+        PkiCert {
+            serial_number: serial,
+            role,
+            common_name: cn,
+            certificate: pem,
+            not_after,
+            revoked_at,
+            created_at: ca,
+        }
+    }
+
+    fn role_from_row(name: String, config: Value) -> VaultResult<PkiRole> {
+        let mut role: PkiRole =
+            serde_json::from_value(config).map_err(json_err("pki role decode"))?;
+        role.name = name;
+        Ok(role)
+    }
+
+    const CERT_COLUMNS: &str =
+        "serial, role, common_name, certificate_pem, not_after, revoked_at, created_at";
+
+    #[async_trait]
+    impl PkiStore for PgPkiStore {
+        async fn get_ca(&self) -> VaultResult<Option<PkiCa>> {
+            #[allow(clippy::type_complexity)]
+            let row: Option<(
+                String,
+                String,
+                Option<String>,
+                String,
+                Vec<u8>,
+                Vec<u8>,
+                String,
+                i64,
+                Value,
+                f64,
+            )> = sqlx::query_as(
+                "SELECT kind, key_type, certificate_pem, ca_chain, private_key, key_wrapped,
+                        kek_kid, crl_number, urls, created_at
+                   FROM vault.pki_ca
+                  WHERE id = 1",
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("pki get_ca"))?;
+            row.map(|(kind, kt, cert, chain, pk, kw, kk, crl, urls, ca)| {
+                Ok(PkiCa {
+                    kind,
+                    key_type: kt,
+                    certificate: cert,
+                    ca_chain: chain,
+                    private_key: pk,
+                    key_wrapped: kw,
+                    kek_kid: kk,
+                    crl_number: crl,
+                    urls: serde_json::from_value(urls).map_err(json_err("pki urls decode"))?,
+                    created_at: ca,
+                })
+            })
+            .transpose()
+        }
+
+        async fn create_ca(&self, ca: &PkiCa) -> VaultResult<()> {
+            let urls = serde_json::to_value(&ca.urls).map_err(json_err("pki urls encode"))?;
+            let res = sqlx::query(
+                "INSERT INTO vault.pki_ca
+                    (id, kind, key_type, certificate_pem, ca_chain, private_key, key_wrapped,
+                     kek_kid, crl_number, urls, created_at)
+                 VALUES (1, $1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(&ca.kind)
+            .bind(&ca.key_type)
+            .bind(ca.certificate.as_deref())
+            .bind(&ca.ca_chain)
+            .bind(&ca.private_key)
+            .bind(&ca.key_wrapped)
+            .bind(&ca.kek_kid)
+            .bind(ca.crl_number)
+            .bind(urls)
+            .bind(ca.created_at)
+            .execute(&self.pool)
+            .await;
+            if let Err(sqlx::Error::Database(dberr)) = &res
+                && dberr.code().as_deref() == Some("23505")
+            {
+                return Err(VaultError::Conflict("a PKI CA already exists".into()));
+            }
+            res.map_err(map_err("pki create_ca"))?;
+            Ok(())
+        }
+
+        async fn set_ca_certificate(&self, certificate: &str, ca_chain: &str) -> VaultResult<()> {
+            let res = sqlx::query(
+                "UPDATE vault.pki_ca
+                    SET certificate_pem = $1, ca_chain = $2
+                  WHERE id = 1 AND certificate_pem IS NULL",
+            )
+            .bind(certificate)
+            .bind(ca_chain)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("pki set_ca_certificate"))?;
+            if res.rows_affected() == 0 {
+                return Err(VaultError::Conflict(
+                    "no pending intermediate to install a certificate for".into(),
+                ));
+            }
+            Ok(())
+        }
+
+        async fn delete_ca(&self) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("pki delete_ca begin"))?;
+            sqlx::query("DELETE FROM vault.pki_certs")
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("pki delete_ca certs"))?;
+            let res = sqlx::query("DELETE FROM vault.pki_ca WHERE id = 1")
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("pki delete_ca"))?;
+            tx.commit().await.map_err(map_err("pki delete_ca commit"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn set_urls(&self, urls: &PkiUrls) -> VaultResult<()> {
+            let urls = serde_json::to_value(urls).map_err(json_err("pki urls encode"))?;
+            sqlx::query("UPDATE vault.pki_ca SET urls = $1 WHERE id = 1")
+                .bind(urls)
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("pki set_urls"))?;
+            Ok(())
+        }
+
+        async fn put_role(&self, role: &PkiRole) -> VaultResult<()> {
+            let config = serde_json::to_value(role).map_err(json_err("pki role encode"))?;
+            sqlx::query(
+                "INSERT INTO vault.pki_roles (name, config)
+                 VALUES ($1, $2)
+                 ON CONFLICT (name) DO UPDATE
+                    SET config = EXCLUDED.config,
+                        updated_at = EXTRACT(EPOCH FROM NOW())",
+            )
+            .bind(&role.name)
+            .bind(config)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("pki put_role"))?;
+            Ok(())
+        }
+
+        async fn get_role(&self, name: &str) -> VaultResult<Option<PkiRole>> {
+            let row: Option<Value> =
+                sqlx::query_scalar("SELECT config FROM vault.pki_roles WHERE name = $1")
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(map_err("pki get_role"))?;
+            row.map(|config| role_from_row(name.to_string(), config))
+                .transpose()
+        }
+
+        async fn list_roles(&self) -> VaultResult<Vec<PkiRole>> {
+            let rows: Vec<(String, Value)> =
+                sqlx::query_as("SELECT name, config FROM vault.pki_roles ORDER BY name")
+                    .fetch_all(&self.pool)
+                    .await
+                    .map_err(map_err("pki list_roles"))?;
+            rows.into_iter()
+                .map(|(name, config)| role_from_row(name, config))
+                .collect()
+        }
+
+        async fn delete_role(&self, name: &str) -> VaultResult<bool> {
+            let res = sqlx::query("DELETE FROM vault.pki_roles WHERE name = $1")
+                .bind(name)
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("pki delete_role"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn insert_cert(&self, cert: &PkiCert) -> VaultResult<()> {
+            sqlx::query(
+                "INSERT INTO vault.pki_certs
+                    (serial, role, common_name, certificate_pem, not_after, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&cert.serial_number)
+            .bind(&cert.role)
+            .bind(&cert.common_name)
+            .bind(&cert.certificate)
+            .bind(cert.not_after)
+            .bind(cert.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("pki insert_cert"))?;
+            Ok(())
+        }
+
+        async fn get_cert(&self, serial_number: &str) -> VaultResult<Option<PkiCert>> {
+            let row: Option<CertRow> = sqlx::query_as(&format!(
+                "SELECT {CERT_COLUMNS} FROM vault.pki_certs WHERE serial = $1"
+            ))
+            .bind(serial_number)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("pki get_cert"))?;
+            Ok(row.map(cert_from_row))
+        }
+
+        async fn list_certs(&self) -> VaultResult<Vec<PkiCert>> {
+            let rows: Vec<CertRow> = sqlx::query_as(&format!(
+                "SELECT {CERT_COLUMNS} FROM vault.pki_certs ORDER BY created_at, serial"
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("pki list_certs"))?;
+            Ok(rows.into_iter().map(cert_from_row).collect())
+        }
+
+        async fn list_revoked(&self) -> VaultResult<Vec<PkiCert>> {
+            let rows: Vec<CertRow> = sqlx::query_as(&format!(
+                "SELECT {CERT_COLUMNS} FROM vault.pki_certs
+                  WHERE revoked_at IS NOT NULL
+                  ORDER BY revoked_at, serial"
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("pki list_revoked"))?;
+            Ok(rows.into_iter().map(cert_from_row).collect())
+        }
+
+        async fn revoke_cert(&self, serial_number: &str, revoked_at: f64) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("pki revoke begin"))?;
+            let res = sqlx::query(
+                "UPDATE vault.pki_certs SET revoked_at = $2
+                  WHERE serial = $1 AND revoked_at IS NULL",
+            )
+            .bind(serial_number)
+            .bind(revoked_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err("pki revoke"))?;
+            if res.rows_affected() == 0 {
+                return Ok(false);
+            }
+            sqlx::query("UPDATE vault.pki_ca SET crl_number = crl_number + 1 WHERE id = 1")
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("pki revoke crl_number"))?;
+            tx.commit().await.map_err(map_err("pki revoke commit"))?;
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(feature = "vault-pki")]
+pub use pki::PgPkiStore;
+
+#[cfg(feature = "vault-ssh")]
+mod ssh {
+    use super::*;
+    use crate::error::{Result as VaultResult, VaultError};
+    use crate::ssh::{SshCa, SshCert, SshRole, SshStore};
+    use serde_json::Value;
+
+    /// Postgres-backed SSH CA store.
+    #[derive(Clone)]
+    pub struct PgSshStore {
+        pool: PgPool,
+    }
+
+    impl PgSshStore {
+        pub fn new(pool: PgPool) -> Self {
+            Self { pool }
+        }
+    }
+
+    fn map_err(ctx: &'static str) -> impl FnOnce(sqlx::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    fn json_err(ctx: &'static str) -> impl FnOnce(serde_json::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    type CertRow = (
+        String,
+        String,
+        String,
+        String,
+        Value,
+        String,
+        f64,
+        Option<String>,
+        f64,
+    );
+
+    fn cert_from_row(
+        (serial, role, cert_type, key_id, principals, fp, vb, actor, ca): CertRow,
+    ) -> VaultResult<SshCert> {
+        Ok(SshCert {
+            serial_number: serial,
+            role,
+            cert_type,
+            key_id,
+            principals: serde_json::from_value(principals)
+                .map_err(json_err("ssh principals decode"))?,
+            fingerprint: fp,
+            valid_before: vb,
+            actor,
+            created_at: ca,
+        })
+    }
+
+    fn role_from_row(name: String, config: Value) -> VaultResult<SshRole> {
+        let mut role: SshRole =
+            serde_json::from_value(config).map_err(json_err("ssh role decode"))?;
+        role.name = name;
+        Ok(role)
+    }
+
+    #[async_trait]
+    impl SshStore for PgSshStore {
+        async fn get_ca(&self) -> VaultResult<Option<SshCa>> {
+            let row: Option<(String, String, Vec<u8>, Vec<u8>, String, f64)> = sqlx::query_as(
+                "SELECT key_type, public_key, private_key, key_wrapped, kek_kid, created_at
+                   FROM vault.ssh_ca
+                  WHERE id = 1",
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("ssh get_ca"))?;
+            Ok(row.map(|(kt, public_key, pk, kw, kk, ca)| SshCa {
+                key_type: kt,
+                public_key,
+                private_key: pk,
+                key_wrapped: kw,
+                kek_kid: kk,
+                created_at: ca,
+            }))
+        }
+
+        async fn create_ca(&self, ca: &SshCa) -> VaultResult<()> {
+            let res = sqlx::query(
+                "INSERT INTO vault.ssh_ca
+                    (id, key_type, public_key, private_key, key_wrapped, kek_kid, created_at)
+                 VALUES (1, $1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&ca.key_type)
+            .bind(&ca.public_key)
+            .bind(&ca.private_key)
+            .bind(&ca.key_wrapped)
+            .bind(&ca.kek_kid)
+            .bind(ca.created_at)
+            .execute(&self.pool)
+            .await;
+            if let Err(sqlx::Error::Database(dberr)) = &res
+                && dberr.code().as_deref() == Some("23505")
+            {
+                return Err(VaultError::Conflict("an SSH CA already exists".into()));
+            }
+            res.map_err(map_err("ssh create_ca"))?;
+            Ok(())
+        }
+
+        async fn delete_ca(&self) -> VaultResult<bool> {
+            let res = sqlx::query("DELETE FROM vault.ssh_ca WHERE id = 1")
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("ssh delete_ca"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn put_role(&self, role: &SshRole) -> VaultResult<()> {
+            let config = serde_json::to_value(role).map_err(json_err("ssh role encode"))?;
+            sqlx::query(
+                "INSERT INTO vault.ssh_roles (name, config)
+                 VALUES ($1, $2)
+                 ON CONFLICT (name) DO UPDATE
+                    SET config = EXCLUDED.config,
+                        updated_at = EXTRACT(EPOCH FROM NOW())",
+            )
+            .bind(&role.name)
+            .bind(config)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("ssh put_role"))?;
+            Ok(())
+        }
+
+        async fn get_role(&self, name: &str) -> VaultResult<Option<SshRole>> {
+            let row: Option<Value> =
+                sqlx::query_scalar("SELECT config FROM vault.ssh_roles WHERE name = $1")
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(map_err("ssh get_role"))?;
+            row.map(|config| role_from_row(name.to_string(), config))
+                .transpose()
+        }
+
+        async fn list_roles(&self) -> VaultResult<Vec<SshRole>> {
+            let rows: Vec<(String, Value)> =
+                sqlx::query_as("SELECT name, config FROM vault.ssh_roles ORDER BY name")
+                    .fetch_all(&self.pool)
+                    .await
+                    .map_err(map_err("ssh list_roles"))?;
+            rows.into_iter()
+                .map(|(name, config)| role_from_row(name, config))
+                .collect()
+        }
+
+        async fn delete_role(&self, name: &str) -> VaultResult<bool> {
+            let res = sqlx::query("DELETE FROM vault.ssh_roles WHERE name = $1")
+                .bind(name)
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("ssh delete_role"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn insert_cert(&self, cert: &SshCert) -> VaultResult<()> {
+            let principals = serde_json::to_value(&cert.principals)
+                .map_err(json_err("ssh principals encode"))?;
+            sqlx::query(
+                "INSERT INTO vault.ssh_certs
+                    (serial, role, cert_type, key_id, principals, fingerprint, valid_before,
+                     actor, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .bind(&cert.serial_number)
+            .bind(&cert.role)
+            .bind(&cert.cert_type)
+            .bind(&cert.key_id)
+            .bind(principals)
+            .bind(&cert.fingerprint)
+            .bind(cert.valid_before)
+            .bind(cert.actor.as_deref())
+            .bind(cert.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("ssh insert_cert"))?;
+            Ok(())
+        }
+
+        async fn list_certs(&self) -> VaultResult<Vec<SshCert>> {
+            let rows: Vec<CertRow> = sqlx::query_as(
+                "SELECT serial, role, cert_type, key_id, principals, fingerprint, valid_before,
+                        actor, created_at
+                   FROM vault.ssh_certs
+                  ORDER BY created_at, serial",
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("ssh list_certs"))?;
+            rows.into_iter().map(cert_from_row).collect()
+        }
+    }
+}
+
+#[cfg(feature = "vault-ssh")]
+pub use ssh::PgSshStore;
+
 #[cfg(feature = "vault-sealing-shamir")]
 mod sealing {
     use super::*;
@@ -1383,3 +2049,245 @@ mod dynamic {
     feature = "vault-dynamic-kubernetes",
 ))]
 pub use dynamic::PgLeaseStore;
+
+#[cfg(feature = "vault-hashicorp-compat")]
+mod compat_auth {
+    use super::*;
+    use crate::error::{Result as VaultResult, VaultError};
+    use crate::hashicorp_compat::{AuthRole, CompatAuthStore, SecretId};
+    use serde_json::Value;
+
+    /// Postgres-backed store for the Vault-compat facade's AppRole and
+    /// Kubernetes roles, method config, and AppRole secret IDs.
+    #[derive(Clone)]
+    pub struct PgCompatAuthStore {
+        pool: PgPool,
+    }
+
+    impl PgCompatAuthStore {
+        pub fn new(pool: PgPool) -> Self {
+            Self { pool }
+        }
+    }
+
+    fn map_err(ctx: &'static str) -> impl FnOnce(sqlx::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    type RoleRow = (String, String, Option<String>, Value);
+
+    fn role_from_row((method, name, role_id, config): RoleRow) -> VaultResult<AuthRole> {
+        Ok(AuthRole {
+            method,
+            name,
+            role_id,
+            config,
+        })
+    }
+
+    type SecretRow = (String, String, String, Value, Option<i64>, f64, Option<f64>);
+
+    fn secret_from_row(
+        (accessor, role, secret_hash, metadata, uses_left, created_at, expires_at): SecretRow,
+    ) -> VaultResult<SecretId> {
+        Ok(SecretId {
+            accessor,
+            role,
+            secret_hash,
+            metadata,
+            uses_left,
+            created_at,
+            expires_at,
+        })
+    }
+
+    const SECRET_COLUMNS: &str =
+        "accessor, role, secret_hash, metadata, uses_left, created_at, expires_at";
+
+    #[async_trait]
+    impl CompatAuthStore for PgCompatAuthStore {
+        async fn put_role(&self, role: &AuthRole) -> VaultResult<()> {
+            let res = sqlx::query(
+                "INSERT INTO vault.compat_auth_roles (method, name, role_id, config)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (method, name) DO UPDATE
+                    SET role_id = EXCLUDED.role_id,
+                        config = EXCLUDED.config,
+                        updated_at = EXTRACT(EPOCH FROM NOW())",
+            )
+            .bind(&role.method)
+            .bind(&role.name)
+            .bind(role.role_id.as_deref())
+            .bind(&role.config)
+            .execute(&self.pool)
+            .await;
+            if let Err(sqlx::Error::Database(dberr)) = &res
+                && dberr.code().as_deref() == Some("23505")
+            {
+                return Err(VaultError::Conflict(
+                    "role_id is already in use by another role".into(),
+                ));
+            }
+            res.map_err(map_err("compat put_role"))?;
+            Ok(())
+        }
+
+        async fn get_role(&self, method: &str, name: &str) -> VaultResult<Option<AuthRole>> {
+            let row: Option<RoleRow> = sqlx::query_as(
+                "SELECT method, name, role_id, config
+                   FROM vault.compat_auth_roles
+                  WHERE method = $1 AND name = $2",
+            )
+            .bind(method)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("compat get_role"))?;
+            row.map(role_from_row).transpose()
+        }
+
+        async fn find_role_id(&self, role_id: &str) -> VaultResult<Option<AuthRole>> {
+            let row: Option<RoleRow> = sqlx::query_as(
+                "SELECT method, name, role_id, config
+                   FROM vault.compat_auth_roles
+                  WHERE method = 'approle' AND role_id = $1",
+            )
+            .bind(role_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("compat find_role_id"))?;
+            row.map(role_from_row).transpose()
+        }
+
+        async fn list_roles(&self, method: &str) -> VaultResult<Vec<String>> {
+            sqlx::query_scalar(
+                "SELECT name FROM vault.compat_auth_roles WHERE method = $1 ORDER BY name",
+            )
+            .bind(method)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("compat list_roles"))
+        }
+
+        async fn delete_role(&self, method: &str, name: &str) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("compat delete_role begin"))?;
+            let n =
+                sqlx::query("DELETE FROM vault.compat_auth_roles WHERE method = $1 AND name = $2")
+                    .bind(method)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("compat delete_role"))?
+                    .rows_affected();
+            if method == "approle" {
+                sqlx::query("DELETE FROM vault.compat_approle_secret_ids WHERE role = $1")
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("compat delete_role secret_ids"))?;
+            }
+            tx.commit()
+                .await
+                .map_err(map_err("compat delete_role commit"))?;
+            Ok(n > 0)
+        }
+
+        async fn put_config(&self, method: &str, config: &serde_json::Value) -> VaultResult<()> {
+            sqlx::query(
+                "INSERT INTO vault.compat_auth_config (method, config)
+                 VALUES ($1, $2)
+                 ON CONFLICT (method) DO UPDATE
+                    SET config = EXCLUDED.config,
+                        updated_at = EXTRACT(EPOCH FROM NOW())",
+            )
+            .bind(method)
+            .bind(config)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("compat put_config"))?;
+            Ok(())
+        }
+
+        async fn get_config(&self, method: &str) -> VaultResult<Option<serde_json::Value>> {
+            let row: Option<Value> =
+                sqlx::query_scalar("SELECT config FROM vault.compat_auth_config WHERE method = $1")
+                    .bind(method)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(map_err("compat get_config"))?;
+            Ok(row)
+        }
+
+        async fn insert_secret_id(&self, secret: &SecretId) -> VaultResult<()> {
+            sqlx::query(&format!(
+                "INSERT INTO vault.compat_approle_secret_ids ({SECRET_COLUMNS})
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)"
+            ))
+            .bind(&secret.accessor)
+            .bind(&secret.role)
+            .bind(&secret.secret_hash)
+            .bind(&secret.metadata)
+            .bind(secret.uses_left)
+            .bind(secret.created_at)
+            .bind(secret.expires_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("compat insert_secret_id"))?;
+            Ok(())
+        }
+
+        /// One transaction: sweep expired secret IDs, spend a use of this
+        /// one, and drop it if that was its last.
+        async fn use_secret_id(
+            &self,
+            role: &str,
+            secret_hash: &str,
+            now: f64,
+        ) -> VaultResult<Option<SecretId>> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("compat use_secret_id begin"))?;
+            sqlx::query(
+                "DELETE FROM vault.compat_approle_secret_ids
+                  WHERE expires_at IS NOT NULL AND expires_at <= $1",
+            )
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err("compat use_secret_id sweep"))?;
+            let row: Option<SecretRow> = sqlx::query_as(&format!(
+                "UPDATE vault.compat_approle_secret_ids
+                    SET uses_left = uses_left - 1
+                  WHERE role = $1
+                    AND secret_hash = $2
+                    AND (uses_left IS NULL OR uses_left > 0)
+                 RETURNING {SECRET_COLUMNS}"
+            ))
+            .bind(role)
+            .bind(secret_hash)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(map_err("compat use_secret_id"))?;
+            if row.as_ref().is_some_and(|r| r.4 == Some(0)) {
+                sqlx::query("DELETE FROM vault.compat_approle_secret_ids WHERE secret_hash = $1")
+                    .bind(secret_hash)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("compat use_secret_id spent"))?;
+            }
+            tx.commit()
+                .await
+                .map_err(map_err("compat use_secret_id commit"))?;
+            row.map(secret_from_row).transpose()
+        }
+    }
+}
+
+#[cfg(feature = "vault-hashicorp-compat")]
+pub use compat_auth::PgCompatAuthStore;
@@ -20,7 +20,7 @@ pub async fn migrate(pool: &SqlitePool) -> Result<()> {
 mod kv {
     use super::*;
     use crate::error::{Result as VaultResult, VaultError};
-    use crate::kv::{KvMeta, KvRow, KvStore};
+    use crate::kv::{KvMeta, KvMetaUpdate, KvRow, KvStore, KvVersion, cas_mismatch, check_cas};
     use serde_json::Value;
 
     /// SQLite-backed KV store.
@@ -50,6 +50,25 @@ mod kv {
         serde_json::from_str(s).unwrap_or_else(|_| Value::Object(Default::default()))
     }
 
+    type MetaRow = (String, i64, String, i64, i64, i64, f64, f64);
+
+    const META_COLUMNS: &str = "path, latest_version, custom_md, cas_required, max_versions, \
+                                delete_version_after, created_at, updated_at";
+
+    fn meta_from_row(row: MetaRow) -> KvMeta {
+        let (path, latest_version, md_str, cas_required, max_versions, delete_after, ca, ua) = row;
+        KvMeta {
+            path,
+            latest_version,
+            custom_md: parse_md(&md_str),
+            cas_required: cas_required != 0,
+            max_versions,
+            delete_version_after: delete_after,
+            created_at: ca,
+            updated_at: ua,
+        }
+    }
+
     #[async_trait]
     impl KvStore for SqliteKvStore {
         async fn put_row(
@@ -60,53 +79,68 @@ mod kv {
             wrapped_dek: &[u8],
             kek_kid: &str,
             custom_md: &Value,
+            cas: Option<i64>,
         ) -> VaultResult<i64> {
             let mut tx = self.pool.begin().await.map_err(map_err("kv put begin"))?;
             let now = unix_now();
             let md_str = serde_json::to_string(custom_md).unwrap_or_else(|_| "{}".to_string());
 
             // SQLite lacks JSONB merge, so do the merge in two steps.
-            // Read the current md (if any), merge, UPSERT the bumped row,
-            // RETURNING the new latest_version.
-            let existing_md: Option<(String,)> =
-                sqlx::query_as("SELECT custom_md FROM vault.kv_meta WHERE path = ?")
-                    .bind(path)
-                    .fetch_optional(&mut *tx)
-                    .await
-                    .map_err(map_err("kv put read existing meta"))?;
+            // Read the current md and policy (if any), check CAS, merge,
+            // UPSERT the bumped row, RETURNING the new latest_version.
+            let existing: Option<(String, i64, i64, i64, i64)> = sqlx::query_as(
+                "SELECT custom_md, latest_version, cas_required, max_versions, delete_version_after
+                   FROM vault.kv_meta
+                  WHERE path = ?",
+            )
+            .bind(path)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(map_err("kv put read existing meta"))?;
+
+            let (latest, cas_required, max_versions, delete_after) = existing
+                .as_ref()
+                .map(|(_, lv, cr, mv, dva)| (*lv, *cr != 0, *mv, *dva))
+                .unwrap_or_default();
+            check_cas(latest, cas_required, cas)?;
 
-            let merged_md = match existing_md {
-                Some((s,))
+            let merged_md = match existing.map(|(md, ..)| md) {
+                Some(s)
                     if !custom_md.is_object()
                         || custom_md.as_object().map(|o| o.is_empty()).unwrap_or(true) =>
                 {
                     s
                 }
-                Some((s,)) => merge_json(&s, &md_str),
+                Some(s) => merge_json(&s, &md_str),
                 None => md_str,
             };
 
-            let new_version: i64 = sqlx::query_scalar(
+            // The WHERE on the update re-asserts CAS against the row it
+            // is about to bump; no row back means another writer won.
+            let new_version: Option<i64> = sqlx::query_scalar(
                 "INSERT INTO vault.kv_meta (path, latest_version, custom_md, created_at, updated_at)
-                 VALUES (?, 1, ?, ?, ?)
+                 VALUES (?1, 1, ?2, ?3, ?3)
                  ON CONFLICT (path) DO UPDATE
                    SET latest_version = latest_version + 1,
                        custom_md = excluded.custom_md,
                        updated_at = excluded.updated_at
+                 WHERE ?4 IS NULL OR latest_version = ?4
                  RETURNING latest_version",
             )
             .bind(path)
             .bind(merged_md)
             .bind(now)
-            .bind(now)
-            .fetch_one(&mut *tx)
+            .bind(cas)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(map_err("kv put upsert meta"))?;
+            let new_version = new_version.ok_or_else(|| cas_mismatch(latest))?;
 
+            let scheduled_delete = (delete_after > 0).then_some(now + delete_after as f64);
             sqlx::query(
                 "INSERT INTO vault.kv
-                    (path, version, ciphertext, nonce, wrapped_dek, kek_kid, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    (path, version, ciphertext, nonce, wrapped_dek, kek_kid, deleted_at, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(path)
             .bind(new_version)
@@ -114,11 +148,21 @@ mod kv {
             .bind(nonce)
             .bind(wrapped_dek)
             .bind(kek_kid)
+            .bind(scheduled_delete)
             .bind(now)
             .execute(&mut *tx)
             .await
             .map_err(map_err("kv put insert row"))?;
 
+            if max_versions > 0 {
+                sqlx::query("DELETE FROM vault.kv WHERE path = ? AND version <= ?")
+                    .bind(path)
+                    .bind(new_version - max_versions)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("kv put prune"))?;
+            }
+
             tx.commit().await.map_err(map_err("kv put commit"))?;
             Ok(new_version)
         }
@@ -185,45 +229,82 @@ mod kv {
                     .replace('%', "\\%")
                     .replace('_', "\\_")
             );
-            let rows: Vec<(String, i64, String, f64, f64)> = sqlx::query_as(
-                "SELECT path, latest_version, custom_md, created_at, updated_at
+            let rows: Vec<MetaRow> = sqlx::query_as(&format!(
+                "SELECT {META_COLUMNS}
                    FROM vault.kv_meta
                   WHERE path LIKE ? ESCAPE '\\'
-                  ORDER BY path",
-            )
+                  ORDER BY path"
+            ))
             .bind(pattern)
             .fetch_all(&self.pool)
             .await
             .map_err(map_err("kv list_meta"))?;
-            Ok(rows
-                .into_iter()
-                .map(|(path, latest_version, md_str, ca, ua)| KvMeta {
-                    path,
-                    latest_version,
-                    custom_md: parse_md(&md_str),
-                    created_at: ca,
-                    updated_at: ua,
-                })
-                .collect())
+            Ok(rows.into_iter().map(meta_from_row).collect())
         }
 
         async fn read_meta(&self, path: &str) -> VaultResult<Option<KvMeta>> {
-            let row: Option<(i64, String, f64, f64)> = sqlx::query_as(
-                "SELECT latest_version, custom_md, created_at, updated_at
-                   FROM vault.kv_meta
-                  WHERE path = ?",
-            )
+            let row: Option<MetaRow> = sqlx::query_as(&format!(
+                "SELECT {META_COLUMNS} FROM vault.kv_meta WHERE path = ?"
+            ))
             .bind(path)
             .fetch_optional(&self.pool)
             .await
             .map_err(map_err("kv read_meta"))?;
-            Ok(row.map(|(lv, md_str, ca, ua)| KvMeta {
-                path: path.to_string(),
-                latest_version: lv,
-                custom_md: parse_md(&md_str),
-                created_at: ca,
-                updated_at: ua,
-            }))
+            Ok(row.map(meta_from_row))
+        }
+
+        async fn put_meta(&self, path: &str, update: &KvMetaUpdate) -> VaultResult<KvMeta> {
+            let now = unix_now();
+            let md_str = update
+                .custom_md
+                .as_ref()
+                .map(|md| serde_json::to_string(md).unwrap_or_else(|_| "{}".to_string()));
+            let row: MetaRow = sqlx::query_as(&format!(
+                "INSERT INTO vault.kv_meta
+                    (path, latest_version, custom_md, cas_required, max_versions,
+                     delete_version_after, created_at, updated_at)
+                 VALUES (?1, 0, COALESCE(?2, '{{}}'), COALESCE(?3, 0), COALESCE(?4, 0),
+                         COALESCE(?5, 0), ?6, ?6)
+                 ON CONFLICT (path) DO UPDATE
+                   SET custom_md = COALESCE(?2, custom_md),
+                       cas_required = COALESCE(?3, cas_required),
+                       max_versions = COALESCE(?4, max_versions),
+                       delete_version_after = COALESCE(?5, delete_version_after),
+                       updated_at = ?6
+                 RETURNING {META_COLUMNS}"
+            ))
+            .bind(path)
+            .bind(md_str)
+            .bind(update.cas_required)
+            .bind(update.max_versions)
+            .bind(update.delete_version_after)
+            .bind(now)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(map_err("kv put_meta"))?;
+            Ok(meta_from_row(row))
+        }
+
+        async fn list_versions(&self, path: &str) -> VaultResult<Vec<KvVersion>> {
+            let rows: Vec<(i64, f64, Option<f64>, i64)> = sqlx::query_as(
+                "SELECT version, created_at, deleted_at, destroyed
+                   FROM vault.kv
+                  WHERE path = ?
+                  ORDER BY version",
+            )
+            .bind(path)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("kv list_versions"))?;
+            Ok(rows
+                .into_iter()
+                .map(|(version, created_at, deleted_at, destroyed)| KvVersion {
+                    version,
+                    created_at,
+                    deleted_at,
+                    destroyed: destroyed != 0,
+                })
+                .collect())
         }
 
         async fn soft_delete(
@@ -234,11 +315,11 @@ mod kv {
         ) -> VaultResult<bool> {
             let n = sqlx::query(
                 "UPDATE vault.kv
-                    SET deleted_at = ?
-                  WHERE path = ?
-                    AND version = ?
+                    SET deleted_at = ?1
+                  WHERE path = ?2
+                    AND version = ?3
                     AND destroyed = 0
-                    AND deleted_at IS NULL",
+                    AND (deleted_at IS NULL OR deleted_at > ?1)",
             )
             .bind(deleted_at)
             .bind(path)
@@ -284,6 +365,29 @@ mod kv {
             .rows_affected();
             Ok(n > 0)
         }
+
+        async fn delete_path(&self, path: &str) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("kv delete_path begin"))?;
+            sqlx::query("DELETE FROM vault.kv WHERE path = ?1")
+                .bind(path)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("kv delete_path versions"))?;
+            let n = sqlx::query("DELETE FROM vault.kv_meta WHERE path = ?1")
+                .bind(path)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("kv delete_path meta"))?
+                .rows_affected();
+            tx.commit()
+                .await
+                .map_err(map_err("kv delete_path commit"))?;
+            Ok(n > 0)
+        }
     }
 
     /// Shallow object-merge of two JSON strings — overlay's keys win.
@@ -329,6 +433,23 @@ mod transit {
         move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
     }
 
+    type VersionRow = (i64, Vec<u8>, String, Option<Vec<u8>>, Option<String>, f64);
+
+    fn version_from_row(
+        name: &str,
+        (version, kw, kk, pk, pub_pem, ca): VersionRow,
+    ) -> TransitVersion {
+        TransitVersion {
+            name: name.to_string(),
+            version,
+            key_wrapped: kw,
+            kek_kid: kk,
+            private_key: pk,
+            public_key: pub_pem,
+            created_at: ca,
+        }
+    }
+
     fn unix_now() -> f64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -344,6 +465,8 @@ mod transit {
             algo: &str,
             version_wrapped: &[u8],
             kek_kid: &str,
+            private_key: Option<&[u8]>,
+            public_key: Option<&str>,
         ) -> VaultResult<()> {
             let mut tx = self
                 .pool
@@ -371,12 +494,15 @@ mod transit {
             res.map_err(map_err("transit create insert key"))?;
 
             sqlx::query(
-                "INSERT INTO vault.transit_versions (name, version, key_wrapped, kek_kid, created_at)
-                 VALUES (?, 1, ?, ?, ?)",
+                "INSERT INTO vault.transit_versions
+                    (name, version, key_wrapped, kek_kid, private_key, public_key, created_at)
+                 VALUES (?, 1, ?, ?, ?, ?, ?)",
             )
             .bind(name)
             .bind(version_wrapped)
             .bind(kek_kid)
+            .bind(private_key)
+            .bind(public_key)
             .bind(now)
             .execute(&mut *tx)
             .await
@@ -389,17 +515,20 @@ mod transit {
         }
 
         async fn get_key(&self, name: &str) -> VaultResult<Option<TransitKey>> {
-            let row: Option<(String, i64, f64)> = sqlx::query_as(
-                "SELECT algo, latest_ver, created_at FROM vault.transit_keys WHERE name = ?",
+            let row: Option<(String, i64, i64, f64)> = sqlx::query_as(
+                "SELECT algo, latest_ver, min_decryption_version, created_at
+                   FROM vault.transit_keys
+                  WHERE name = ?",
             )
             .bind(name)
             .fetch_optional(&self.pool)
             .await
             .map_err(map_err("transit get_key"))?;
-            Ok(row.map(|(algo, lv, ca)| TransitKey {
+            Ok(row.map(|(algo, lv, mdv, ca)| TransitKey {
                 name: name.to_string(),
                 algo,
                 latest_ver: lv,
+                min_decryption_version: mdv,
                 created_at: ca,
             }))
         }
@@ -409,8 +538,8 @@ mod transit {
             name: &str,
             version: i64,
         ) -> VaultResult<Option<TransitVersion>> {
-            let row: Option<(Vec<u8>, String, f64)> = sqlx::query_as(
-                "SELECT key_wrapped, kek_kid, created_at
+            let row: Option<VersionRow> = sqlx::query_as(
+                "SELECT version, key_wrapped, kek_kid, private_key, public_key, created_at
                    FROM vault.transit_versions
                   WHERE name = ? AND version = ?",
             )
@@ -419,13 +548,7 @@ mod transit {
             .fetch_optional(&self.pool)
             .await
             .map_err(map_err("transit get_version"))?;
-            Ok(row.map(|(kw, kk, ca)| TransitVersion {
-                name: name.to_string(),
-                version,
-                key_wrapped: kw,
-                kek_kid: kk,
-                created_at: ca,
-            }))
+            Ok(row.map(|r| version_from_row(name, r)))
         }
 
         async fn get_latest_version(&self, name: &str) -> VaultResult<Option<TransitVersion>> {
@@ -446,6 +569,8 @@ mod transit {
             name: &str,
             version_wrapped: &[u8],
             kek_kid: &str,
+            private_key: Option<&[u8]>,
+            public_key: Option<&str>,
         ) -> VaultResult<i64> {
             let mut tx = self
                 .pool
@@ -465,13 +590,16 @@ mod transit {
             let new_ver = new_ver.ok_or(VaultError::NotFound)?;
             let now = unix_now();
             sqlx::query(
-                "INSERT INTO vault.transit_versions (name, version, key_wrapped, kek_kid, created_at)
-                 VALUES (?, ?, ?, ?, ?)",
+                "INSERT INTO vault.transit_versions
+                    (name, version, key_wrapped, kek_kid, private_key, public_key, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(name)
             .bind(new_ver)
             .bind(version_wrapped)
             .bind(kek_kid)
+            .bind(private_key)
+            .bind(public_key)
             .bind(now)
             .execute(&mut *tx)
             .await
@@ -482,9 +610,41 @@ mod transit {
             Ok(new_ver)
         }
 
+        async fn list_versions(&self, name: &str) -> VaultResult<Vec<TransitVersion>> {
+            let rows: Vec<VersionRow> = sqlx::query_as(
+                "SELECT version, key_wrapped, kek_kid, private_key, public_key, created_at
+                   FROM vault.transit_versions
+                  WHERE name = ?
+                  ORDER BY version",
+            )
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("transit list_versions"))?;
+            Ok(rows
+                .into_iter()
+                .map(|r| version_from_row(name, r))
+                .collect())
+        }
+
+        async fn set_min_decryption_version(&self, name: &str, version: i64) -> VaultResult<()> {
+            let res = sqlx::query(
+                "UPDATE vault.transit_keys SET min_decryption_version = ? WHERE name = ?",
+            )
+            .bind(version)
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("transit set_min_decryption_version"))?;
+            if res.rows_affected() == 0 {
+                return Err(VaultError::NotFound);
+            }
+            Ok(())
+        }
+
         async fn list_keys(&self) -> VaultResult<Vec<TransitKey>> {
-            let rows: Vec<(String, String, i64, f64)> = sqlx::query_as(
-                "SELECT name, algo, latest_ver, created_at
+            let rows: Vec<(String, String, i64, i64, f64)> = sqlx::query_as(
+                "SELECT name, algo, latest_ver, min_decryption_version, created_at
                    FROM vault.transit_keys
                   ORDER BY name",
             )
@@ -493,10 +653,11 @@ mod transit {
             .map_err(map_err("transit list_keys"))?;
             Ok(rows
                 .into_iter()
-                .map(|(n, a, lv, ca)| TransitKey {
+                .map(|(n, a, lv, mdv, ca)| TransitKey {
                     name: n,
                     algo: a,
                     latest_ver: lv,
+                    min_decryption_version: mdv,
                     created_at: ca,
                 })
                 .collect())
@@ -507,6 +668,527 @@ mod transit {
 #[cfg(feature = "vault-transit")]
 pub use transit::SqliteTransitStore;
 
+#[cfg(feature = "vault-pki")]
+mod pki {
+    use super::*;
+    use crate::error::{Result as VaultResult, VaultError};
+    use crate::pki::{PkiCa, PkiCert, PkiRole, PkiStore, PkiUrls};
+
+    /// SQLite-backed PKI store.
+    #[derive(Clone)]
+    pub struct SqlitePkiStore {
+        pool: SqlitePool,
+    }
+
+    impl SqlitePkiStore {
+        pub fn new(pool: SqlitePool) -> Self {
+            Self { pool }
+        }
+    }
+
+    fn map_err(ctx: &'static str) -> impl FnOnce(sqlx::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    fn json_err(ctx: &'static str) -> impl FnOnce(serde_json::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    type CertRow = (String, String, String, String, f64, Option<f64>, f64);
+
+    fn cert_from_row((serial, role, cn, pem, not_after, revoked_at, ca): CertRow) -> PkiCert {
+        PkiCert {
+            serial_number: serial,
+            role,
+            common_name: cn,
+            certificate: pem,
+            not_after,
+            revoked_at,
+            created_at: ca,
+        }
+    }
+
+    fn role_from_row(name: String, config: String) -> VaultResult<PkiRole> {
+        let mut role: PkiRole =
+            serde_json::from_str(&config).map_err(json_err("pki role decode"))?;
+        role.name = name;
+        Ok(role)
+    }
+
+    fn unix_now() -> f64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+    }
+
+    const CERT_COLUMNS: &str =
+        "serial, role, common_name, certificate_pem, not_after, revoked_at, created_at";
+
+    #[async_trait]
+    impl PkiStore for SqlitePkiStore {
+        async fn get_ca(&self) -> VaultResult<Option<PkiCa>> {
+            #[allow(clippy::type_complexity)]
+            let row: Option<(
+                String,
+                String,
+                Option<String>,
+                String,
+                Vec<u8>,
+                Vec<u8>,
+                String,
+                i64,
+                String,
+                f64,
+            )> = sqlx::query_as(
+                "SELECT kind, key_type, certificate_pem, ca_chain, private_key, key_wrapped,
+                        kek_kid, crl_number, urls, created_at
+                   FROM vault.pki_ca
+                  WHERE id = 1",
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("pki get_ca"))?;
+            row.map(|(kind, kt, cert, chain, pk, kw, kk, crl, urls, ca)| {
+                Ok(PkiCa {
+                    kind,
+                    key_type: kt,
+                    certificate: cert,
+                    ca_chain: chain,
+                    private_key: pk,
+                    key_wrapped: kw,
+                    kek_kid: kk,
+                    crl_number: crl,
+                    urls: serde_json::from_str(&urls).map_err(json_err("pki urls decode"))?,
+                    created_at: ca,
+                })
+            })
+            .transpose()
+        }
+
+        async fn create_ca(&self, ca: &PkiCa) -> VaultResult<()> {
+            let urls = serde_json::to_string(&ca.urls).map_err(json_err("pki urls encode"))?;
+            let res = sqlx::query(
+                "INSERT INTO vault.pki_ca
+                    (id, kind, key_type, certificate_pem, ca_chain, private_key, key_wrapped,
+                     kek_kid, crl_number, urls, created_at)
+                 VALUES (1, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&ca.kind)
+            .bind(&ca.key_type)
+            .bind(ca.certificate.as_deref())
+            .bind(&ca.ca_chain)
+            .bind(&ca.private_key)
+            .bind(&ca.key_wrapped)
+            .bind(&ca.kek_kid)
+            .bind(ca.crl_number)
+            .bind(urls)
+            .bind(ca.created_at)
+            .execute(&self.pool)
+            .await;
+            if let Err(sqlx::Error::Database(dberr)) = &res
+                && dberr.message().contains("UNIQUE")
+            {
+                return Err(VaultError::Conflict("a PKI CA already exists".into()));
+            }
+            res.map_err(map_err("pki create_ca"))?;
+            Ok(())
+        }
+
+        async fn set_ca_certificate(&self, certificate: &str, ca_chain: &str) -> VaultResult<()> {
+            let res = sqlx::query(
+                "UPDATE vault.pki_ca
+                    SET certificate_pem = ?, ca_chain = ?
+                  WHERE id = 1 AND certificate_pem IS NULL",
+            )
+            .bind(certificate)
+            .bind(ca_chain)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("pki set_ca_certificate"))?;
+            if res.rows_affected() == 0 {
+                return Err(VaultError::Conflict(
+                    "no pending intermediate to install a certificate for".into(),
+                ));
+            }
+            Ok(())
+        }
+
+        async fn delete_ca(&self) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("pki delete_ca begin"))?;
+            sqlx::query("DELETE FROM vault.pki_certs")
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("pki delete_ca certs"))?;
+            let res = sqlx::query("DELETE FROM vault.pki_ca WHERE id = 1")
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("pki delete_ca"))?;
+            tx.commit().await.map_err(map_err("pki delete_ca commit"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn set_urls(&self, urls: &PkiUrls) -> VaultResult<()> {
+            let urls = serde_json::to_string(urls).map_err(json_err("pki urls encode"))?;
+            sqlx::query("UPDATE vault.pki_ca SET urls = ? WHERE id = 1")
+                .bind(urls)
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("pki set_urls"))?;
+            Ok(())
+        }
+
+        async fn put_role(&self, role: &PkiRole) -> VaultResult<()> {
+            let config = serde_json::to_string(role).map_err(json_err("pki role encode"))?;
+            let now = unix_now();
+            sqlx::query(
+                "INSERT INTO vault.pki_roles (name, config, created_at, updated_at)
+                 VALUES (?, ?, ?, ?)
+                 ON CONFLICT (name) DO UPDATE
+                    SET config = excluded.config,
+                        updated_at = excluded.updated_at",
+            )
+            .bind(&role.name)
+            .bind(config)
+            .bind(now)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("pki put_role"))?;
+            Ok(())
+        }
+
+        async fn get_role(&self, name: &str) -> VaultResult<Option<PkiRole>> {
+            let row: Option<String> =
+                sqlx::query_scalar("SELECT config FROM vault.pki_roles WHERE name = ?")
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(map_err("pki get_role"))?;
+            row.map(|config| role_from_row(name.to_string(), config))
+                .transpose()
+        }
+
+        async fn list_roles(&self) -> VaultResult<Vec<PkiRole>> {
+            let rows: Vec<(String, String)> =
+                sqlx::query_as("SELECT name, config FROM vault.pki_roles ORDER BY name")
+                    .fetch_all(&self.pool)
+                    .await
+                    .map_err(map_err("pki list_roles"))?;
+            rows.into_iter()
+                .map(|(name, config)| role_from_row(name, config))
+                .collect()
+        }
+
+        async fn delete_role(&self, name: &str) -> VaultResult<bool> {
+            let res = sqlx::query("DELETE FROM vault.pki_roles WHERE name = ?")
+                .bind(name)
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("pki delete_role"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn insert_cert(&self, cert: &PkiCert) -> VaultResult<()> {
+            sqlx::query(
+                "INSERT INTO vault.pki_certs
+                    (serial, role, common_name, certificate_pem, not_after, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&cert.serial_number)
+            .bind(&cert.role)
+            .bind(&cert.common_name)
+            .bind(&cert.certificate)
+            .bind(cert.not_after)
+            .bind(cert.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("pki insert_cert"))?;
+            Ok(())
+        }
+
+        async fn get_cert(&self, serial_number: &str) -> VaultResult<Option<PkiCert>> {
+            let row: Option<CertRow> = sqlx::query_as(&format!(
+                "SELECT {CERT_COLUMNS} FROM vault.pki_certs WHERE serial = ?"
+            ))
+            .bind(serial_number)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("pki get_cert"))?;
+            Ok(row.map(cert_from_row))
+        }
+
+        async fn list_certs(&self) -> VaultResult<Vec<PkiCert>> {
+            let rows: Vec<CertRow> = sqlx::query_as(&format!(
+                "SELECT {CERT_COLUMNS} FROM vault.pki_certs ORDER BY created_at, serial"
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("pki list_certs"))?;
+            Ok(rows.into_iter().map(cert_from_row).collect())
+        }
+
+        async fn list_revoked(&self) -> VaultResult<Vec<PkiCert>> {
+            let rows: Vec<CertRow> = sqlx::query_as(&format!(
+                "SELECT {CERT_COLUMNS} FROM vault.pki_certs
+                  WHERE revoked_at IS NOT NULL
+                  ORDER BY revoked_at, serial"
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("pki list_revoked"))?;
+            Ok(rows.into_iter().map(cert_from_row).collect())
+        }
+
+        async fn revoke_cert(&self, serial_number: &str, revoked_at: f64) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("pki revoke begin"))?;
+            let res = sqlx::query(
+                "UPDATE vault.pki_certs SET revoked_at = ?
+                  WHERE serial = ? AND revoked_at IS NULL",
+            )
+            .bind(revoked_at)
+            .bind(serial_number)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err("pki revoke"))?;
+            if res.rows_affected() == 0 {
+                return Ok(false);
+            }
+            sqlx::query("UPDATE vault.pki_ca SET crl_number = crl_number + 1 WHERE id = 1")
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err("pki revoke crl_number"))?;
+            tx.commit().await.map_err(map_err("pki revoke commit"))?;
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(feature = "vault-pki")]
+pub use pki::SqlitePkiStore;
+
+#[cfg(feature = "vault-ssh")]
+mod ssh {
+    use super::*;
+    use crate::error::{Result as VaultResult, VaultError};
+    use crate::ssh::{SshCa, SshCert, SshRole, SshStore};
+
+    /// SQLite-backed SSH CA store.
+    #[derive(Clone)]
+    pub struct SqliteSshStore {
+        pool: SqlitePool,
+    }
+
+    impl SqliteSshStore {
+        pub fn new(pool: SqlitePool) -> Self {
+            Self { pool }
+        }
+    }
+
+    fn map_err(ctx: &'static str) -> impl FnOnce(sqlx::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    fn json_err(ctx: &'static str) -> impl FnOnce(serde_json::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    type CertRow = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        f64,
+        Option<String>,
+        f64,
+    );
+
+    fn cert_from_row(
+        (serial, role, cert_type, key_id, principals, fp, vb, actor, ca): CertRow,
+    ) -> VaultResult<SshCert> {
+        Ok(SshCert {
+            serial_number: serial,
+            role,
+            cert_type,
+            key_id,
+            principals: serde_json::from_str(&principals)
+                .map_err(json_err("ssh principals decode"))?,
+            fingerprint: fp,
+            valid_before: vb,
+            actor,
+            created_at: ca,
+        })
+    }
+
+    fn role_from_row(name: String, config: String) -> VaultResult<SshRole> {
+        let mut role: SshRole =
+            serde_json::from_str(&config).map_err(json_err("ssh role decode"))?;
+        role.name = name;
+        Ok(role)
+    }
+
+    fn unix_now() -> f64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+    }
+
+    #[async_trait]
+    impl SshStore for SqliteSshStore {
+        async fn get_ca(&self) -> VaultResult<Option<SshCa>> {
+            let row: Option<(String, String, Vec<u8>, Vec<u8>, String, f64)> = sqlx::query_as(
+                "SELECT key_type, public_key, private_key, key_wrapped, kek_kid, created_at
+                   FROM vault.ssh_ca
+                  WHERE id = 1",
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("ssh get_ca"))?;
+            Ok(row.map(|(kt, public_key, pk, kw, kk, ca)| SshCa {
+                key_type: kt,
+                public_key,
+                private_key: pk,
+                key_wrapped: kw,
+                kek_kid: kk,
+                created_at: ca,
+            }))
+        }
+
+        async fn create_ca(&self, ca: &SshCa) -> VaultResult<()> {
+            let res = sqlx::query(
+                "INSERT INTO vault.ssh_ca
+                    (id, key_type, public_key, private_key, key_wrapped, kek_kid, created_at)
+                 VALUES (1, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&ca.key_type)
+            .bind(&ca.public_key)
+            .bind(&ca.private_key)
+            .bind(&ca.key_wrapped)
+            .bind(&ca.kek_kid)
+            .bind(ca.created_at)
+            .execute(&self.pool)
+            .await;
+            if let Err(sqlx::Error::Database(dberr)) = &res
+                && dberr.message().contains("UNIQUE")
+            {
+                return Err(VaultError::Conflict("an SSH CA already exists".into()));
+            }
+            res.map_err(map_err("ssh create_ca"))?;
+            Ok(())
+        }
+
+        async fn delete_ca(&self) -> VaultResult<bool> {
+            let res = sqlx::query("DELETE FROM vault.ssh_ca WHERE id = 1")
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("ssh delete_ca"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn put_role(&self, role: &SshRole) -> VaultResult<()> {
+            let config = serde_json::to_string(role).map_err(json_err("ssh role encode"))?;
+            let now = unix_now();
+            sqlx::query(
+                "INSERT INTO vault.ssh_roles (name, config, created_at, updated_at)
+                 VALUES (?, ?, ?, ?)
+                 ON CONFLICT (name) DO UPDATE
+                    SET config = excluded.config,
+                        updated_at = excluded.updated_at",
+            )
+            .bind(&role.name)
+            .bind(config)
+            .bind(now)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("ssh put_role"))?;
+            Ok(())
+        }
+
+        async fn get_role(&self, name: &str) -> VaultResult<Option<SshRole>> {
+            let row: Option<String> =
+                sqlx::query_scalar("SELECT config FROM vault.ssh_roles WHERE name = ?")
+                    .bind(name)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(map_err("ssh get_role"))?;
+            row.map(|config| role_from_row(name.to_string(), config))
+                .transpose()
+        }
+
+        async fn list_roles(&self) -> VaultResult<Vec<SshRole>> {
+            let rows: Vec<(String, String)> =
+                sqlx::query_as("SELECT name, config FROM vault.ssh_roles ORDER BY name")
+                    .fetch_all(&self.pool)
+                    .await
+                    .map_err(map_err("ssh list_roles"))?;
+            rows.into_iter()
+                .map(|(name, config)| role_from_row(name, config))
+                .collect()
+        }
+
+        async fn delete_role(&self, name: &str) -> VaultResult<bool> {
+            let res = sqlx::query("DELETE FROM vault.ssh_roles WHERE name = ?")
+                .bind(name)
+                .execute(&self.pool)
+                .await
+                .map_err(map_err("ssh delete_role"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        async fn insert_cert(&self, cert: &SshCert) -> VaultResult<()> {
+            let principals = serde_json::to_string(&cert.principals)
+                .map_err(json_err("ssh principals encode"))?;
+            sqlx::query(
+                "INSERT INTO vault.ssh_certs
+                    (serial, role, cert_type, key_id, principals, fingerprint, valid_before,
+                     actor, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&cert.serial_number)
+            .bind(&cert.role)
+            .bind(&cert.cert_type)
+            .bind(&cert.key_id)
+            .bind(principals)
+            .bind(&cert.fingerprint)
+            .bind(cert.valid_before)
+            .bind(cert.actor.as_deref())
+            .bind(cert.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("ssh insert_cert"))?;
+            Ok(())
+        }
+
+        async fn list_certs(&self) -> VaultResult<Vec<SshCert>> {
+            let rows: Vec<CertRow> = sqlx::query_as(
+                "SELECT serial, role, cert_type, key_id, principals, fingerprint, valid_before,
+                        actor, created_at
+                   FROM vault.ssh_certs
+                  ORDER BY created_at, serial",
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("ssh list_certs"))?;
+            rows.into_iter().map(cert_from_row).collect()
+        }
+    }
+}
+
+#[cfg(feature = "vault-ssh")]
+pub use ssh::SqliteSshStore;
+
 #[cfg(feature = "vault-sealing-shamir")]
 mod sealing {
     use super::*;
@@ -1485,3 +2167,273 @@ mod dynamic {
     feature = "vault-dynamic-kubernetes",
 ))]
 pub use dynamic::SqliteLeaseStore;
+
+#[cfg(feature = "vault-hashicorp-compat")]
+mod compat_auth {
+    use super::*;
+    use crate::error::{Result as VaultResult, VaultError};
+    use crate::hashicorp_compat::{AuthRole, CompatAuthStore, SecretId};
+
+    /// SQLite-backed store for the Vault-compat facade's AppRole and
+    /// Kubernetes roles, method config, and AppRole secret IDs.
+    #[derive(Clone)]
+    pub struct SqliteCompatAuthStore {
+        pool: SqlitePool,
+    }
+
+    impl SqliteCompatAuthStore {
+        pub fn new(pool: SqlitePool) -> Self {
+            Self { pool }
+        }
+    }
+
+    fn map_err(ctx: &'static str) -> impl FnOnce(sqlx::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    fn json_err(ctx: &'static str) -> impl FnOnce(serde_json::Error) -> VaultError {
+        move |e| VaultError::Backend(anyhow::anyhow!("{ctx}: {e}"))
+    }
+
+    fn unix_now() -> f64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+    }
+
+    type RoleRow = (String, String, Option<String>, String);
+
+    fn role_from_row((method, name, role_id, config): RoleRow) -> VaultResult<AuthRole> {
+        Ok(AuthRole {
+            method,
+            name,
+            role_id,
+            config: serde_json::from_str(&config).map_err(json_err("compat role decode"))?,
+        })
+    }
+
+    type SecretRow = (
+        String,
+        String,
+        String,
+        String,
+        Option<i64>,
+        f64,
+        Option<f64>,
+    );
+
+    fn secret_from_row(
+        (accessor, role, secret_hash, metadata, uses_left, created_at, expires_at): SecretRow,
+    ) -> VaultResult<SecretId> {
+        Ok(SecretId {
+            accessor,
+            role,
+            secret_hash,
+            metadata: serde_json::from_str(&metadata)
+                .map_err(json_err("compat secret_id decode"))?,
+            uses_left,
+            created_at,
+            expires_at,
+        })
+    }
+
+    const SECRET_COLUMNS: &str =
+        "accessor, role, secret_hash, metadata, uses_left, created_at, expires_at";
+
+    #[async_trait]
+    impl CompatAuthStore for SqliteCompatAuthStore {
+        async fn put_role(&self, role: &AuthRole) -> VaultResult<()> {
+            let config = role.config.to_string();
+            let res = sqlx::query(
+                "INSERT INTO vault.compat_auth_roles
+                    (method, name, role_id, config, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                 ON CONFLICT (method, name) DO UPDATE
+                    SET role_id = excluded.role_id,
+                        config = excluded.config,
+                        updated_at = excluded.updated_at",
+            )
+            .bind(&role.method)
+            .bind(&role.name)
+            .bind(role.role_id.as_deref())
+            .bind(config)
+            .bind(unix_now())
+            .execute(&self.pool)
+            .await;
+            if let Err(sqlx::Error::Database(dberr)) = &res
+                && dberr.message().contains("UNIQUE")
+            {
+                return Err(VaultError::Conflict(
+                    "role_id is already in use by another role".into(),
+                ));
+            }
+            res.map_err(map_err("compat put_role"))?;
+            Ok(())
+        }
+
+        async fn get_role(&self, method: &str, name: &str) -> VaultResult<Option<AuthRole>> {
+            let row: Option<RoleRow> = sqlx::query_as(
+                "SELECT method, name, role_id, config
+                   FROM vault.compat_auth_roles
+                  WHERE method = ?1 AND name = ?2",
+            )
+            .bind(method)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("compat get_role"))?;
+            row.map(role_from_row).transpose()
+        }
+
+        async fn find_role_id(&self, role_id: &str) -> VaultResult<Option<AuthRole>> {
+            let row: Option<RoleRow> = sqlx::query_as(
+                "SELECT method, name, role_id, config
+                   FROM vault.compat_auth_roles
+                  WHERE method = 'approle' AND role_id = ?1",
+            )
+            .bind(role_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(map_err("compat find_role_id"))?;
+            row.map(role_from_row).transpose()
+        }
+
+        async fn list_roles(&self, method: &str) -> VaultResult<Vec<String>> {
+            sqlx::query_scalar(
+                "SELECT name FROM vault.compat_auth_roles WHERE method = ?1 ORDER BY name",
+            )
+            .bind(method)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_err("compat list_roles"))
+        }
+
+        async fn delete_role(&self, method: &str, name: &str) -> VaultResult<bool> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("compat delete_role begin"))?;
+            let n =
+                sqlx::query("DELETE FROM vault.compat_auth_roles WHERE method = ?1 AND name = ?2")
+                    .bind(method)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("compat delete_role"))?
+                    .rows_affected();
+            if method == "approle" {
+                sqlx::query("DELETE FROM vault.compat_approle_secret_ids WHERE role = ?1")
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("compat delete_role secret_ids"))?;
+            }
+            tx.commit()
+                .await
+                .map_err(map_err("compat delete_role commit"))?;
+            Ok(n > 0)
+        }
+
+        async fn put_config(&self, method: &str, config: &serde_json::Value) -> VaultResult<()> {
+            let config = config.to_string();
+            sqlx::query(
+                "INSERT INTO vault.compat_auth_config (method, config, updated_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT (method) DO UPDATE
+                    SET config = excluded.config,
+                        updated_at = excluded.updated_at",
+            )
+            .bind(method)
+            .bind(config)
+            .bind(unix_now())
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("compat put_config"))?;
+            Ok(())
+        }
+
+        async fn get_config(&self, method: &str) -> VaultResult<Option<serde_json::Value>> {
+            let row: Option<String> =
+                sqlx::query_scalar("SELECT config FROM vault.compat_auth_config WHERE method = ?1")
+                    .bind(method)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(map_err("compat get_config"))?;
+            row.map(|config| {
+                serde_json::from_str(&config).map_err(json_err("compat config decode"))
+            })
+            .transpose()
+        }
+
+        async fn insert_secret_id(&self, secret: &SecretId) -> VaultResult<()> {
+            let metadata = secret.metadata.to_string();
+            sqlx::query(&format!(
+                "INSERT INTO vault.compat_approle_secret_ids ({SECRET_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            ))
+            .bind(&secret.accessor)
+            .bind(&secret.role)
+            .bind(&secret.secret_hash)
+            .bind(metadata)
+            .bind(secret.uses_left)
+            .bind(secret.created_at)
+            .bind(secret.expires_at)
+            .execute(&self.pool)
+            .await
+            .map_err(map_err("compat insert_secret_id"))?;
+            Ok(())
+        }
+
+        /// One transaction: sweep expired secret IDs, spend a use of this
+        /// one, and drop it if that was its last.
+        async fn use_secret_id(
+            &self,
+            role: &str,
+            secret_hash: &str,
+            now: f64,
+        ) -> VaultResult<Option<SecretId>> {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(map_err("compat use_secret_id begin"))?;
+            sqlx::query(
+                "DELETE FROM vault.compat_approle_secret_ids
+                  WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+            )
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err("compat use_secret_id sweep"))?;
+            let row: Option<SecretRow> = sqlx::query_as(&format!(
+                "UPDATE vault.compat_approle_secret_ids
+                    SET uses_left = uses_left - 1
+                  WHERE role = ?1
+                    AND secret_hash = ?2
+                    AND (uses_left IS NULL OR uses_left > 0)
+                 RETURNING {SECRET_COLUMNS}"
+            ))
+            .bind(role)
+            .bind(secret_hash)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(map_err("compat use_secret_id"))?;
+            if row.as_ref().is_some_and(|r| r.4 == Some(0)) {
+                sqlx::query("DELETE FROM vault.compat_approle_secret_ids WHERE secret_hash = ?1")
+                    .bind(secret_hash)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(map_err("compat use_secret_id spent"))?;
+            }
+            tx.commit()
+                .await
+                .map_err(map_err("compat use_secret_id commit"))?;
+            row.map(secret_from_row).transpose()
+        }
+    }
+}
+
+#[cfg(feature = "vault-hashicorp-compat")]
+pub use compat_auth::SqliteCompatAuthStore;
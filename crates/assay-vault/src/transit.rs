@@ -1,11 +1,22 @@
-//! Transit — encrypt / decrypt without exposing key material to the
-//! caller. Plan 17 §S2 / Vault-equivalent.
+//! Transit — encrypt / decrypt / sign without exposing key material to
+//! the caller. Plan 17 §S2 / Vault-equivalent.
 //!
 //! Operators register a named key; clients call `encrypt` / `decrypt`
-//! with the name and the server holds the underlying material. Rotating
-//! a key bumps its version: subsequent `encrypt` calls use the new
-//! version, but ciphertexts stamped with the old version remain
-//! decryptable until an explicit re-wrap.
+//! (or `sign` / `verify`) with the name and the server holds the
+//! underlying material. Rotating a key bumps its version: subsequent
+//! `encrypt` / `sign` calls use the new version, but ciphertexts and
+//! signatures stamped with an old version stay usable until the key's
+//! `min_decryption_version` moves past it. [`TransitService::rewrap`]
+//! moves a ciphertext to the latest version without handing the
+//! plaintext back.
+//!
+//! ## Key types
+//!
+//! See [`KeyType`]. `aes256-gcm-siv` (the default) encrypts, decrypts
+//! and mints data keys for envelope encryption; `ed25519`,
+//! `ecdsa-p256`, `rsa-2048` and `rsa-4096` sign and verify, and their
+//! public keys are readable — sealed or not — through
+//! [`TransitService::read_key`]. Every type can HMAC.
 //!
 //! ## Layering
 //!
@@ -18,15 +29,23 @@
 //! Ciphertexts are returned as ASCII strings: `vault:v{version}:{b64}`,
 //! where the base64 payload is `nonce(12) || ciphertext(variable)` —
 //! same shape Vault uses, easy to grep for in logs. Decrypt parses the
-//! prefix to know which key version to fetch.
+//! prefix to know which key version to fetch. Signatures and HMACs use
+//! the same `vault:v{version}:{b64}` shape over the raw tag.
 
 use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Sha512};
 
 use crate::crypto::aead::{NONCE_LEN, decrypt, encrypt, random_dek, random_nonce};
-use crate::crypto::kek::WrappedDek;
+use crate::crypto::kek::{KekHandle, WrappedDek};
 use crate::error::{Result, VaultError};
 
+pub(crate) mod keys;
+
+pub use keys::{KeyType, SignatureAlgorithm};
+
 /// Per-key metadata.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -34,10 +53,14 @@ pub struct TransitKey {
     pub name: String,
     pub algo: String,
     pub latest_ver: i64,
+    /// Oldest version `decrypt`, `rewrap` and `verify` still accept.
+    pub min_decryption_version: i64,
     pub created_at: f64,
 }
 
-/// One version of a transit key — DEK wrapped by the master KEK.
+/// One version of a transit key — DEK wrapped by the master KEK. For
+/// asymmetric key types `private_key` is `nonce || AEAD(DEK, PKCS#8)`
+/// and `public_key` the SPKI PEM; both are `None` for `aes256-gcm-siv`.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct TransitVersion {
@@ -45,21 +68,54 @@ pub struct TransitVersion {
     pub version: i64,
     pub key_wrapped: Vec<u8>,
     pub kek_kid: String,
+    pub private_key: Option<Vec<u8>>,
+    pub public_key: Option<String>,
+    pub created_at: f64,
+}
+
+/// Key metadata plus the exportable half of every version.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct TransitKeyInfo {
+    #[serde(flatten)]
+    pub key: TransitKey,
+    pub versions: Vec<TransitPublicKey>,
+}
+
+/// The public side of one key version. `public_key` is `None` for
+/// `aes256-gcm-siv` keys, which have nothing to export.
+#[derive(Clone, Debug, Serialize)]
+#[non_exhaustive]
+pub struct TransitPublicKey {
+    pub version: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
     pub created_at: f64,
 }
 
+/// A fresh data key for envelope encryption: `plaintext` to use and
+/// discard, `ciphertext` (wrapped under the transit key) to store.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct DataKey {
+    pub ciphertext: String,
+    pub plaintext: Vec<u8>,
+}
+
 /// Pure-IO trait. The PG / SQLite impls take and return raw wrapped-key
 /// blobs; they don't unwrap.
 #[async_trait]
 pub trait TransitStore: Send + Sync + 'static {
-    /// Insert a fresh key + its first version. `algo` is informational
-    /// today — Phase 1 only ships AES-256-GCM-SIV.
+    /// Insert a fresh key + its first version. `private_key` /
+    /// `public_key` are `None` for `aes256-gcm-siv`.
     async fn create_key(
         &self,
         name: &str,
         algo: &str,
         version_wrapped: &[u8],
         kek_kid: &str,
+        private_key: Option<&[u8]>,
+        public_key: Option<&str>,
     ) -> Result<()>;
 
     /// Read key metadata (returns None if the key doesn't exist).
@@ -73,7 +129,22 @@ pub trait TransitStore: Send + Sync + 'static {
 
     /// Append a new version, bumping `latest_ver`. Returns the new
     /// version number.
-    async fn rotate(&self, name: &str, version_wrapped: &[u8], kek_kid: &str) -> Result<i64>;
+    async fn rotate(
+        &self,
+        name: &str,
+        version_wrapped: &[u8],
+        kek_kid: &str,
+        private_key: Option<&[u8]>,
+        public_key: Option<&str>,
+    ) -> Result<i64>;
+
+    /// Every version of `name`, oldest first. Empty if the key doesn't
+    /// exist.
+    async fn list_versions(&self, name: &str) -> Result<Vec<TransitVersion>>;
+
+    /// Set `min_decryption_version`. [`VaultError::NotFound`] if the key
+    /// doesn't exist; range checks are the service's job.
+    async fn set_min_decryption_version(&self, name: &str, version: i64) -> Result<()>;
 
     /// List every transit key. Used by admin / dashboard.
     async fn list_keys(&self) -> Result<Vec<TransitKey>>;
@@ -100,16 +171,21 @@ impl<S: TransitStore> TransitService<S> {
     }
 
     /// Create a new transit key. Errors if the name already exists.
-    /// `algo` is reserved for forward compatibility — Phase 1 always
-    /// uses AES-256-GCM-SIV regardless of the value.
+    /// `algo` is a [`KeyType`] name; `None` means `aes256-gcm-siv`.
     pub async fn create_key(&self, name: &str, algo: Option<&str>) -> Result<()> {
         validate_name(name)?;
+        let key_type = KeyType::parse(algo.unwrap_or("aes256-gcm-siv"))?;
         let kek = self.seal_state.require_unsealed()?;
-        let dek = random_dek();
-        let wrapped = kek.wrap_dek(&dek)?;
-        let algo = algo.unwrap_or("aes256-gcm-siv");
+        let v = new_version(&kek, name, key_type).await?;
         self.store
-            .create_key(name, algo, wrapped.as_bytes(), kek.kid())
+            .create_key(
+                name,
+                key_type.as_str(),
+                v.key_wrapped.as_bytes(),
+                kek.kid(),
+                v.private_key.as_deref(),
+                v.public_key.as_deref(),
+            )
             .await?;
         Ok(())
     }
@@ -119,6 +195,7 @@ impl<S: TransitStore> TransitService<S> {
     pub async fn encrypt(&self, name: &str, plaintext: &[u8]) -> Result<String> {
         validate_name(name)?;
         let kek = self.seal_state.require_unsealed()?;
+        self.encryption_key(name).await?;
         let v = self
             .store
             .get_latest_version(name)
@@ -133,11 +210,14 @@ impl<S: TransitStore> TransitService<S> {
 
     /// Decrypt a wire-format ciphertext. Reads the version off the
     /// prefix, fetches that key version (which may be older than the
-    /// current latest), and runs AEAD-decrypt.
+    /// current latest, but not older than `min_decryption_version`),
+    /// and runs AEAD-decrypt.
     pub async fn decrypt(&self, name: &str, envelope: &str) -> Result<Vec<u8>> {
         validate_name(name)?;
         let kek = self.seal_state.require_unsealed()?;
+        let key = self.encryption_key(name).await?;
         let parts = parse_envelope(envelope)?;
+        check_min_version(&key, parts.version)?;
         let v = self
             .store
             .get_version(name, parts.version)
@@ -148,21 +228,274 @@ impl<S: TransitStore> TransitService<S> {
         decrypt(&dek, &parts.nonce, &aad, &parts.ciphertext)
     }
 
+    /// Re-encrypt a ciphertext under the latest version of `name`. The
+    /// plaintext never leaves the service, so clients can migrate
+    /// stored ciphertexts ahead of raising `min_decryption_version`.
+    pub async fn rewrap(&self, name: &str, envelope: &str) -> Result<String> {
+        let plaintext = self.decrypt(name, envelope).await?;
+        self.encrypt(name, &plaintext).await
+    }
+
+    /// Mint a random `bits`-bit data key and wrap it under `name`.
+    /// `bits` is 128, 256 (the default) or 512.
+    pub async fn datakey(&self, name: &str, bits: Option<u32>) -> Result<DataKey> {
+        let len = match bits.unwrap_or(256) {
+            128 => 16,
+            256 => 32,
+            512 => 64,
+            other => {
+                return Err(VaultError::Invalid(format!(
+                    "datakey bits must be 128, 256 or 512; got {other}"
+                )));
+            }
+        };
+        let mut plaintext = vec![0u8; len];
+        rand::rng().fill_bytes(&mut plaintext);
+        let ciphertext = self.encrypt(name, &plaintext).await?;
+        Ok(DataKey {
+            ciphertext,
+            plaintext,
+        })
+    }
+
+    /// HMAC `input` with the latest version of `name`. `algorithm` is
+    /// `sha2-256` (the default) or `sha2-512`. Returns
+    /// `vault:vN:b64(tag)`.
+    pub async fn hmac(&self, name: &str, input: &[u8], algorithm: Option<&str>) -> Result<String> {
+        validate_name(name)?;
+        let algorithm = HmacAlgorithm::parse(algorithm)?;
+        let kek = self.seal_state.require_unsealed()?;
+        let v = self
+            .store
+            .get_latest_version(name)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        let key = hmac_key(&unwrap_version(&kek, &v)?);
+        Ok(encode_versioned(v.version, &algorithm.tag(&key, input)))
+    }
+
+    /// Check a `vault:vN:` HMAC over `input`. Versions below
+    /// `min_decryption_version` are rejected rather than reported as a
+    /// mismatch.
+    pub async fn verify_hmac(
+        &self,
+        name: &str,
+        input: &[u8],
+        hmac: &str,
+        algorithm: Option<&str>,
+    ) -> Result<bool> {
+        validate_name(name)?;
+        let algorithm = HmacAlgorithm::parse(algorithm)?;
+        let kek = self.seal_state.require_unsealed()?;
+        let key = self.key(name).await?;
+        let (version, tag) = split_versioned(hmac)?;
+        check_min_version(&key, version)?;
+        let v = self
+            .store
+            .get_version(name, version)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        let hkey = hmac_key(&unwrap_version(&kek, &v)?);
+        Ok(algorithm.verify(&hkey, input, &tag))
+    }
+
+    /// Sign `input` with the latest version of an asymmetric key.
+    /// Returns `vault:vN:b64(signature)`; see [`SignatureAlgorithm`]
+    /// for RSA padding.
+    pub async fn sign(
+        &self,
+        name: &str,
+        input: &[u8],
+        algorithm: SignatureAlgorithm,
+    ) -> Result<String> {
+        validate_name(name)?;
+        let kek = self.seal_state.require_unsealed()?;
+        let key_type = self.signing_key(name).await?.1;
+        let v = self
+            .store
+            .get_latest_version(name)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        let dek = unwrap_version(&kek, &v)?;
+        let sealed = v
+            .private_key
+            .as_deref()
+            .ok_or_else(|| missing_material(&v))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(VaultError::Crypto("sealed private key truncated".into()));
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&sealed[..NONCE_LEN]);
+        let private_der = decrypt(&dek, &nonce, &private_aad(name), &sealed[NONCE_LEN..])?;
+        let signature = keys::sign(key_type, &private_der, input, algorithm)?;
+        Ok(encode_versioned(v.version, &signature))
+    }
+
+    /// Verify a `vault:vN:` signature over `input` with that version's
+    /// public key.
+    pub async fn verify(
+        &self,
+        name: &str,
+        input: &[u8],
+        signature: &str,
+        algorithm: SignatureAlgorithm,
+    ) -> Result<bool> {
+        validate_name(name)?;
+        self.seal_state.require_unsealed()?;
+        let (key, key_type) = self.signing_key(name).await?;
+        let (version, signature) = split_versioned(signature)?;
+        check_min_version(&key, version)?;
+        let v = self
+            .store
+            .get_version(name, version)
+            .await?
+            .ok_or(VaultError::NotFound)?;
+        let public_pem = v
+            .public_key
+            .as_deref()
+            .ok_or_else(|| missing_material(&v))?;
+        keys::verify(key_type, public_pem, input, &signature, algorithm)
+    }
+
     /// Append a new version to `name`. Returns the new version number.
     pub async fn rotate(&self, name: &str) -> Result<i64> {
         validate_name(name)?;
         let kek = self.seal_state.require_unsealed()?;
-        let dek = random_dek();
-        let wrapped = kek.wrap_dek(&dek)?;
-        self.store.rotate(name, wrapped.as_bytes(), kek.kid()).await
+        let key = self.key(name).await?;
+        let v = new_version(&kek, name, KeyType::parse(&key.algo)?).await?;
+        self.store
+            .rotate(
+                name,
+                v.key_wrapped.as_bytes(),
+                kek.kid(),
+                v.private_key.as_deref(),
+                v.public_key.as_deref(),
+            )
+            .await
+    }
+
+    /// Raise (or lower) the oldest version `decrypt`, `rewrap` and
+    /// `verify` accept. Must lie in `1..=latest_ver`.
+    pub async fn set_min_decryption_version(&self, name: &str, version: i64) -> Result<TransitKey> {
+        validate_name(name)?;
+        let mut key = self.key(name).await?;
+        if !(1..=key.latest_ver).contains(&version) {
+            return Err(VaultError::Invalid(format!(
+                "min_decryption_version must be between 1 and {}; got {version}",
+                key.latest_ver
+            )));
+        }
+        self.store.set_min_decryption_version(name, version).await?;
+        key.min_decryption_version = version;
+        Ok(key)
+    }
+
+    /// Key metadata with every version's public key. Public halves are
+    /// stored in the clear, so this works while the vault is sealed.
+    pub async fn read_key(&self, name: &str) -> Result<TransitKeyInfo> {
+        validate_name(name)?;
+        let key = self.key(name).await?;
+        let versions = self
+            .store
+            .list_versions(name)
+            .await?
+            .into_iter()
+            .map(|v| TransitPublicKey {
+                version: v.version,
+                public_key: v.public_key,
+                created_at: v.created_at,
+            })
+            .collect();
+        Ok(TransitKeyInfo { key, versions })
     }
 
     pub async fn list_keys(&self) -> Result<Vec<TransitKey>> {
         self.store.list_keys().await
     }
+
+    async fn key(&self, name: &str) -> Result<TransitKey> {
+        self.store.get_key(name).await?.ok_or(VaultError::NotFound)
+    }
+
+    async fn encryption_key(&self, name: &str) -> Result<TransitKey> {
+        let key = self.key(name).await?;
+        if !KeyType::parse(&key.algo)?.supports_encryption() {
+            return Err(VaultError::Invalid(format!(
+                "transit key '{name}' is {}; only aes256-gcm-siv keys encrypt",
+                key.algo
+            )));
+        }
+        Ok(key)
+    }
+
+    async fn signing_key(&self, name: &str) -> Result<(TransitKey, KeyType)> {
+        let key = self.key(name).await?;
+        let key_type = KeyType::parse(&key.algo)?;
+        if !key_type.supports_signing() {
+            return Err(VaultError::Invalid(format!(
+                "transit key '{name}' is {}; only asymmetric keys sign",
+                key.algo
+            )));
+        }
+        Ok((key, key_type))
+    }
+}
+
+/// Material for one new key version, ready for the store.
+struct NewVersion {
+    key_wrapped: WrappedDek,
+    private_key: Option<Vec<u8>>,
+    public_key: Option<String>,
+}
+
+/// Every version gets a fresh KEK-wrapped DEK — the AEAD key for
+/// `aes256-gcm-siv`, the seal over the private key for asymmetric types,
+/// and the HMAC key's root for both. Keeping `key_wrapped` a plain DEK
+/// means KEK rotation rewraps transit versions without knowing their
+/// type.
+async fn new_version(kek: &KekHandle, name: &str, key_type: KeyType) -> Result<NewVersion> {
+    let dek = random_dek();
+    let key_wrapped = kek.wrap_dek(&dek)?;
+    if !key_type.supports_signing() {
+        return Ok(NewVersion {
+            key_wrapped,
+            private_key: None,
+            public_key: None,
+        });
+    }
+    let pair = tokio::task::spawn_blocking(move || keys::generate(key_type))
+        .await
+        .map_err(|e| VaultError::Crypto(format!("transit keygen task: {e}")))??;
+    let nonce = random_nonce();
+    let sealed = encrypt(&dek, &nonce, &private_aad(name), &pair.private_der)?;
+    let mut private_key = Vec::with_capacity(NONCE_LEN + sealed.len());
+    private_key.extend_from_slice(&nonce);
+    private_key.extend_from_slice(&sealed);
+    Ok(NewVersion {
+        key_wrapped,
+        private_key: Some(private_key),
+        public_key: Some(pair.public_pem),
+    })
+}
+
+fn check_min_version(key: &TransitKey, version: i64) -> Result<()> {
+    if version < key.min_decryption_version {
+        return Err(VaultError::Invalid(format!(
+            "version {version} of transit key '{}' is below min_decryption_version {}",
+            key.name, key.min_decryption_version
+        )));
+    }
+    Ok(())
+}
+
+fn missing_material(v: &TransitVersion) -> VaultError {
+    VaultError::Crypto(format!(
+        "transit version {}/v{} has no key pair",
+        v.name, v.version
+    ))
 }
 
-fn unwrap_version(kek: &crate::crypto::kek::KekHandle, v: &TransitVersion) -> Result<[u8; 32]> {
+fn unwrap_version(kek: &KekHandle, v: &TransitVersion) -> Result<[u8; 32]> {
     if v.kek_kid != kek.kid() {
         return Err(VaultError::Crypto(format!(
             "transit version {name}/v{ver} encrypted with KEK {kid} but service active KEK is {active}",
@@ -188,6 +521,69 @@ fn aad_for(name: &str, version: i64) -> Vec<u8> {
     buf
 }
 
+/// AAD for a version's sealed private key. Binding the name is enough:
+/// each version's DEK differs, so a private key moved between versions
+/// of the same key fails to authenticate anyway.
+fn private_aad(name: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(name.len() + 24);
+    buf.extend_from_slice(b"vault.transit.private:");
+    buf.extend_from_slice(name.as_bytes());
+    buf
+}
+
+/// Per-version HMAC key, derived from the DEK so it never doubles as
+/// the AEAD key.
+fn hmac_key(dek: &[u8; 32]) -> Vec<u8> {
+    HmacAlgorithm::Sha256.tag(dek, b"vault.transit.hmac")
+}
+
+#[derive(Clone, Copy)]
+enum HmacAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    fn parse(s: Option<&str>) -> Result<Self> {
+        match s {
+            None | Some("sha2-256") => Ok(Self::Sha256),
+            Some("sha2-512") => Ok(Self::Sha512),
+            Some(other) => Err(VaultError::Invalid(format!(
+                "unknown hmac algorithm '{other}'; expected sha2-256 or sha2-512"
+            ))),
+        }
+    }
+
+    fn tag(self, key: &[u8], input: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha256 => mac::<Hmac<Sha256>>(key, input)
+                .finalize()
+                .into_bytes()
+                .to_vec(),
+            Self::Sha512 => mac::<Hmac<Sha512>>(key, input)
+                .finalize()
+                .into_bytes()
+                .to_vec(),
+        }
+    }
+
+    /// Constant-time comparison against `tag`.
+    fn verify(self, key: &[u8], input: &[u8], tag: &[u8]) -> bool {
+        match self {
+            Self::Sha256 => mac::<Hmac<Sha256>>(key, input).verify_slice(tag).is_ok(),
+            Self::Sha512 => mac::<Hmac<Sha512>>(key, input).verify_slice(tag).is_ok(),
+        }
+    }
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(key: &[u8], input: &[u8]) -> M {
+    // HMAC takes keys of any length; `new_from_slice` cannot fail.
+    let mut m =
+        <M as hmac::digest::KeyInit>::new_from_slice(key).expect("hmac accepts any key length");
+    m.update(input);
+    m
+}
+
 fn validate_name(name: &str) -> Result<()> {
     if name.is_empty() {
         return Err(VaultError::Invalid("transit key name is empty".into()));
@@ -216,11 +612,32 @@ fn encode_envelope(version: i64, nonce: &[u8; NONCE_LEN], ct: &[u8]) -> String {
     let mut buf = Vec::with_capacity(NONCE_LEN + ct.len());
     buf.extend_from_slice(nonce);
     buf.extend_from_slice(ct);
-    let b64 = data_encoding::BASE64.encode(&buf);
-    format!("vault:v{version}:{b64}")
+    encode_versioned(version, &buf)
 }
 
 fn parse_envelope(s: &str) -> Result<EnvelopeParts> {
+    let (version, raw) = split_versioned(s)?;
+    if raw.len() < NONCE_LEN {
+        return Err(VaultError::Invalid("envelope shorter than nonce".into()));
+    }
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&raw[..NONCE_LEN]);
+    let ciphertext = raw[NONCE_LEN..].to_vec();
+    Ok(EnvelopeParts {
+        version,
+        nonce,
+        ciphertext,
+    })
+}
+
+/// `vault:v{version}:{b64}` — the shared shape of ciphertexts,
+/// signatures and HMACs.
+fn encode_versioned(version: i64, bytes: &[u8]) -> String {
+    let b64 = data_encoding::BASE64.encode(bytes);
+    format!("vault:v{version}:{b64}")
+}
+
+fn split_versioned(s: &str) -> Result<(i64, Vec<u8>)> {
     let rest = s
         .strip_prefix("vault:v")
         .ok_or_else(|| VaultError::Invalid("missing vault:v prefix".into()))?;
@@ -233,17 +650,7 @@ fn parse_envelope(s: &str) -> Result<EnvelopeParts> {
     let raw = data_encoding::BASE64
         .decode(b64.as_bytes())
         .map_err(|_| VaultError::Invalid("bad base64 in envelope".into()))?;
-    if raw.len() < NONCE_LEN {
-        return Err(VaultError::Invalid("envelope shorter than nonce".into()));
-    }
-    let mut nonce = [0u8; NONCE_LEN];
-    nonce.copy_from_slice(&raw[..NONCE_LEN]);
-    let ciphertext = raw[NONCE_LEN..].to_vec();
-    Ok(EnvelopeParts {
-        version,
-        nonce,
-        ciphertext,
-    })
+    Ok((version, raw))
 }
 
 #[cfg(test)]
@@ -281,6 +688,16 @@ mod tests {
         assert!(validate_name("nope!").is_err());
     }
 
+    #[test]
+    fn hmac_key_is_not_the_dek() {
+        let dek = [7u8; 32];
+        assert_ne!(hmac_key(&dek), dek.to_vec());
+        let tag = HmacAlgorithm::Sha512.tag(&hmac_key(&dek), b"msg");
+        assert_eq!(tag.len(), 64);
+        assert!(HmacAlgorithm::Sha512.verify(&hmac_key(&dek), b"msg", &tag));
+        assert!(!HmacAlgorithm::Sha256.verify(&hmac_key(&dek), b"msg", &tag));
+    }
+
     #[test]
     fn aad_distinguishes_versions() {
         assert_ne!(aad_for("k", 1), aad_for("k", 2));
@@ -0,0 +1,325 @@
+//! Transit key types and the per-type primitives behind sign / verify.
+//!
+//! `aes256-gcm-siv` keys encrypt and decrypt; the asymmetric types
+//! sign and verify. Every type can HMAC — the HMAC key is derived from
+//! the version's DEK (see `TransitService::hmac`), so it needs nothing
+//! from this module.
+//!
+//! Asymmetric private keys travel as PKCS#8 DER (sealed under the
+//! version DEK before they reach the store); public keys as SPKI PEM,
+//! the shape `openssl` and every language's crypto library read.
+//! ECDSA and RSA hash the input with SHA-256; ed25519 signs the input
+//! itself.
+
+use rsa::pkcs8::{
+    DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding,
+    SecretDocument,
+};
+use rsa::signature::{RandomizedSigner, SignatureEncoding, Signer, Verifier};
+use sha2::Sha256;
+
+use crate::error::{Result, VaultError};
+
+/// What a transit key is for. Stored as the key row's `algo` string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyType {
+    Aes256GcmSiv,
+    Ed25519,
+    EcdsaP256,
+    Rsa2048,
+    Rsa4096,
+}
+
+impl KeyType {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "aes256-gcm-siv" => Self::Aes256GcmSiv,
+            "ed25519" => Self::Ed25519,
+            "ecdsa-p256" => Self::EcdsaP256,
+            "rsa-2048" => Self::Rsa2048,
+            "rsa-4096" => Self::Rsa4096,
+            other => {
+                return Err(VaultError::Invalid(format!(
+                    "unknown transit key type '{other}'; expected one of \
+                     aes256-gcm-siv, ed25519, ecdsa-p256, rsa-2048, rsa-4096"
+                )));
+            }
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Aes256GcmSiv => "aes256-gcm-siv",
+            Self::Ed25519 => "ed25519",
+            Self::EcdsaP256 => "ecdsa-p256",
+            Self::Rsa2048 => "rsa-2048",
+            Self::Rsa4096 => "rsa-4096",
+        }
+    }
+
+    pub fn supports_encryption(self) -> bool {
+        matches!(self, Self::Aes256GcmSiv)
+    }
+
+    pub fn supports_signing(self) -> bool {
+        !self.supports_encryption()
+    }
+
+    fn is_rsa(self) -> bool {
+        matches!(self, Self::Rsa2048 | Self::Rsa4096)
+    }
+}
+
+/// RSA padding for sign / verify. Ignored by the other key types.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    #[default]
+    Pss,
+    Pkcs1v15,
+}
+
+impl SignatureAlgorithm {
+    pub fn parse(s: Option<&str>) -> Result<Self> {
+        match s {
+            None | Some("pss") => Ok(Self::Pss),
+            Some("pkcs1v15") => Ok(Self::Pkcs1v15),
+            Some(other) => Err(VaultError::Invalid(format!(
+                "unknown signature_algorithm '{other}'; expected pss or pkcs1v15"
+            ))),
+        }
+    }
+}
+
+/// A freshly generated asymmetric key: PKCS#8 DER private half, SPKI
+/// PEM public half.
+pub(crate) struct KeyPair {
+    pub private_der: Vec<u8>,
+    pub public_pem: String,
+}
+
+/// Generate a key pair for `key_type`. CPU-bound — RSA-4096 takes
+/// seconds — so callers run it off the async runtime.
+pub(crate) fn generate(key_type: KeyType) -> Result<KeyPair> {
+    let mut rng = rand_core_06::OsRng;
+    match key_type {
+        KeyType::Aes256GcmSiv => Err(VaultError::Invalid(
+            "aes256-gcm-siv keys have no key pair".into(),
+        )),
+        KeyType::Ed25519 => {
+            let key = ed25519_dalek::SigningKey::generate(&mut rng);
+            pair(
+                key.to_pkcs8_der(),
+                key.verifying_key().to_public_key_pem(LINE_ENDING),
+            )
+        }
+        KeyType::EcdsaP256 => {
+            let key = p256::ecdsa::SigningKey::random(&mut rng);
+            pair(
+                key.to_pkcs8_der(),
+                key.verifying_key().to_public_key_pem(LINE_ENDING),
+            )
+        }
+        KeyType::Rsa2048 | KeyType::Rsa4096 => {
+            let bits = if key_type == KeyType::Rsa2048 {
+                2048
+            } else {
+                4096
+            };
+            let key = rsa::RsaPrivateKey::new(&mut rng, bits)
+                .map_err(|e| VaultError::Crypto(format!("rsa keygen: {e}")))?;
+            pair(
+                key.to_pkcs8_der(),
+                key.to_public_key().to_public_key_pem(LINE_ENDING),
+            )
+        }
+    }
+}
+
+/// Identify an imported PKCS#8 private key and derive its SPKI PEM
+/// public half.
+#[cfg(any(feature = "vault-pki", feature = "vault-ssh"))]
+pub(crate) fn inspect_private(private_der: &[u8]) -> Result<(KeyType, String)> {
+    let encoded = |key_type, public: std::result::Result<String, _>| {
+        public
+            .map(|pem| (key_type, pem))
+            .map_err(|e: rsa::pkcs8::spki::Error| {
+                VaultError::Crypto(format!("encode public key: {e}"))
+            })
+    };
+    if let Ok(key) = p256::ecdsa::SigningKey::from_pkcs8_der(private_der) {
+        return encoded(
+            KeyType::EcdsaP256,
+            key.verifying_key().to_public_key_pem(LINE_ENDING),
+        );
+    }
+    if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_der(private_der) {
+        return encoded(
+            KeyType::Ed25519,
+            key.verifying_key().to_public_key_pem(LINE_ENDING),
+        );
+    }
+    if let Ok(key) = rsa::RsaPrivateKey::from_pkcs8_der(private_der) {
+        let key_type = match rsa::traits::PublicKeyParts::size(&key) * 8 {
+            2048 => KeyType::Rsa2048,
+            4096 => KeyType::Rsa4096,
+            bits => {
+                return Err(VaultError::Invalid(format!(
+                    "unsupported RSA key size {bits}; expected 2048 or 4096"
+                )));
+            }
+        };
+        return encoded(key_type, key.to_public_key().to_public_key_pem(LINE_ENDING));
+    }
+    Err(VaultError::Invalid(
+        "unsupported private key; expected PKCS#8 ed25519, ECDSA P-256 or RSA".into(),
+    ))
+}
+
+/// Sign `input` with a PKCS#8 private key of `key_type`.
+pub(crate) fn sign(
+    key_type: KeyType,
+    private_der: &[u8],
+    input: &[u8],
+    algorithm: SignatureAlgorithm,
+) -> Result<Vec<u8>> {
+    match key_type {
+        KeyType::Ed25519 => {
+            let key = ed25519_dalek::SigningKey::from_pkcs8_der(private_der).map_err(decode_err)?;
+            Ok(key.sign(input).to_vec())
+        }
+        KeyType::EcdsaP256 => {
+            let key = p256::ecdsa::SigningKey::from_pkcs8_der(private_der).map_err(decode_err)?;
+            let sig: p256::ecdsa::Signature = key.sign(input);
+            Ok(sig.to_der().to_vec())
+        }
+        t if t.is_rsa() => {
+            let key = rsa::RsaPrivateKey::from_pkcs8_der(private_der).map_err(decode_err)?;
+            Ok(match algorithm {
+                SignatureAlgorithm::Pss => rsa::pss::BlindedSigningKey::<Sha256>::new(key)
+                    .sign_with_rng(&mut rand_core_06::OsRng, input)
+                    .to_vec(),
+                SignatureAlgorithm::Pkcs1v15 => rsa::pkcs1v15::SigningKey::<Sha256>::new(key)
+                    .sign(input)
+                    .to_vec(),
+            })
+        }
+        other => Err(not_signing(other)),
+    }
+}
+
+/// Check `signature` over `input` against an SPKI PEM public key. A
+/// malformed signature is `Ok(false)`, not an error.
+pub(crate) fn verify(
+    key_type: KeyType,
+    public_pem: &str,
+    input: &[u8],
+    signature: &[u8],
+    algorithm: SignatureAlgorithm,
+) -> Result<bool> {
+    match key_type {
+        KeyType::Ed25519 => {
+            let key =
+                ed25519_dalek::VerifyingKey::from_public_key_pem(public_pem).map_err(decode_err)?;
+            Ok(ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|sig| key.verify_strict(input, &sig).is_ok()))
+        }
+        KeyType::EcdsaP256 => {
+            let key =
+                p256::ecdsa::VerifyingKey::from_public_key_pem(public_pem).map_err(decode_err)?;
+            Ok(p256::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|sig| key.verify(input, &sig).is_ok()))
+        }
+        t if t.is_rsa() => {
+            let key = rsa::RsaPublicKey::from_public_key_pem(public_pem).map_err(decode_err)?;
+            Ok(match algorithm {
+                SignatureAlgorithm::Pss => {
+                    rsa::pss::Signature::try_from(signature).is_ok_and(|sig| {
+                        rsa::pss::VerifyingKey::<Sha256>::new(key)
+                            .verify(input, &sig)
+                            .is_ok()
+                    })
+                }
+                SignatureAlgorithm::Pkcs1v15 => rsa::pkcs1v15::Signature::try_from(signature)
+                    .is_ok_and(|sig| {
+                        rsa::pkcs1v15::VerifyingKey::<Sha256>::new(key)
+                            .verify(input, &sig)
+                            .is_ok()
+                    }),
+            })
+        }
+        other => Err(not_signing(other)),
+    }
+}
+
+const LINE_ENDING: LineEnding = LineEnding::LF;
+
+fn pair<E1, E2>(
+    private: std::result::Result<SecretDocument, E1>,
+    public: std::result::Result<String, E2>,
+) -> Result<KeyPair>
+where
+    E1: std::fmt::Display,
+    E2: std::fmt::Display,
+{
+    let private_der = private
+        .map_err(|e| VaultError::Crypto(format!("encode private key: {e}")))?
+        .as_bytes()
+        .to_vec();
+    let public_pem = public.map_err(|e| VaultError::Crypto(format!("encode public key: {e}")))?;
+    Ok(KeyPair {
+        private_der,
+        public_pem,
+    })
+}
+
+fn decode_err(e: impl std::fmt::Display) -> VaultError {
+    VaultError::Crypto(format!("decode transit key: {e}"))
+}
+
+fn not_signing(key_type: KeyType) -> VaultError {
+    VaultError::Invalid(format!(
+        "transit key type {} does not support signing",
+        key_type.as_str()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_type_round_trips_its_name() {
+        for t in [
+            KeyType::Aes256GcmSiv,
+            KeyType::Ed25519,
+            KeyType::EcdsaP256,
+            KeyType::Rsa2048,
+            KeyType::Rsa4096,
+        ] {
+            assert_eq!(KeyType::parse(t.as_str()).unwrap(), t);
+        }
+        assert!(KeyType::parse("des").is_err());
+    }
+
+    #[test]
+    fn sign_verify_round_trip_per_type() {
+        for t in [KeyType::Ed25519, KeyType::EcdsaP256, KeyType::Rsa2048] {
+            let pair = generate(t).unwrap();
+            assert!(pair.public_pem.starts_with("-----BEGIN PUBLIC KEY-----"));
+            for alg in [SignatureAlgorithm::Pss, SignatureAlgorithm::Pkcs1v15] {
+                let sig = sign(t, &pair.private_der, b"artifact", alg).unwrap();
+                assert!(verify(t, &pair.public_pem, b"artifact", &sig, alg).unwrap());
+                assert!(!verify(t, &pair.public_pem, b"tampered", &sig, alg).unwrap());
+                assert!(!verify(t, &pair.public_pem, b"artifact", b"junk", alg).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn aes_keys_cannot_sign() {
+        assert!(matches!(
+            sign(KeyType::Aes256GcmSiv, &[], b"x", SignatureAlgorithm::Pss),
+            Err(VaultError::Invalid(_))
+        ));
+    }
+}
@@ -1,14 +1,15 @@
-//! HTTP-level tests for the Vault / OpenBao KV2 read facade.
+//! HTTP-level tests for the Vault / OpenBao facade's KV2 and transit routes.
 //!
-//! Every assertion is about the wire: an ESO `ExternalSecret` or an ansible
-//! `vault_kv2_get` lookup only ever sees status codes and JSON shapes, so a
-//! service-level test would not tell us whether those consumers work.
+//! Every assertion is about the wire: an ESO `ExternalSecret`, an ansible
+//! `vault_kv2_get` lookup or a Terraform `vault_kv_secret_v2` only ever sees
+//! status codes and JSON shapes, so a service-level test would not tell us
+//! whether those consumers work.
 
 #![cfg(all(feature = "backend-sqlite", feature = "vault-hashicorp-compat"))]
 
 use assay_vault::hashicorp_compat::{Mount, router};
 use assay_vault::store::sqlite::SqliteKvStore;
-use assay_vault::{KekHandle, VaultCtx};
+use assay_vault::{KekHandle, KvMetaUpdate, VaultCtx};
 use axum::Router;
 use axum::body::Body;
 use axum::extract::Request;
@@ -143,6 +144,16 @@ fn request(method: &str, uri: &str) -> Request {
         .unwrap()
 }
 
+fn with_body(method: &str, uri: &str, body: Value) -> Request {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("X-Vault-Token", TOKEN)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
 fn anonymous(method: &str, uri: &str) -> Request {
     Request::builder()
         .method(method)
@@ -423,6 +434,43 @@ async fn metadata_reports_the_current_version_and_custom_metadata() {
     assert_eq!(body["data"]["versions"]["1"]["destroyed"], false);
 }
 
+#[tokio::test]
+async fn metadata_reports_the_path_policy_and_every_kept_version() {
+    let ctx = ctx().await;
+    let kv = ctx.kv.clone().unwrap();
+    kv.put_meta(
+        "apps/billing",
+        &KvMetaUpdate {
+            cas_required: Some(true),
+            max_versions: Some(2),
+            delete_version_after: Some(5400),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    for cas in 0..3 {
+        kv.put_cas("apps/billing", br#"{"k":"v"}"#, json!({}), Some(cas))
+            .await
+            .unwrap();
+    }
+    let response = app_for(ctx, "secrets")
+        .oneshot(request("GET", "/v1/secrets/metadata/apps/billing"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["cas_required"], true);
+    assert_eq!(data["max_versions"], 2);
+    assert_eq!(data["delete_version_after"], "1h30m0s");
+    assert_eq!(data["current_version"], 3);
+    assert_eq!(data["oldest_version"], 2);
+    let versions = data["versions"].as_object().unwrap();
+    assert_eq!(versions.keys().collect::<Vec<_>>(), ["2", "3"]);
+    assert_ne!(versions["3"]["deletion_time"], "", "deletion is scheduled");
+}
+
 #[tokio::test]
 async fn metadata_for_a_missing_path_is_a_404() {
     let app = seeded_app().await;
@@ -480,42 +528,375 @@ async fn a_sealed_engine_refuses_reads_rather_than_serving_stale_plaintext() {
 }
 
 #[tokio::test]
-async fn every_write_verb_is_refused_on_the_read_facade() {
+async fn a_write_creates_a_version_and_answers_with_its_metadata() {
+    let app = seeded_app().await;
+    let response = app
+        .clone()
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/data/platform/postgres",
+            json!({"data": {"username": "assay", "password": "rotated"}}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["version"], 2);
+    assert_eq!(data["destroyed"], false);
+    assert_eq!(data["custom_metadata"]["owner"], "sre", "a write keeps it");
+
+    let response = app
+        .oneshot(request("GET", "/v1/secrets/data/platform/postgres"))
+        .await
+        .unwrap();
+    assert_eq!(
+        json_body(response).await["data"]["data"]["password"],
+        "rotated"
+    );
+}
+
+#[tokio::test]
+async fn put_writes_the_same_way_post_does() {
+    let app = seeded_app().await;
+    let response = app
+        .clone()
+        .oneshot(with_body(
+            "PUT",
+            "/v1/secrets/data/apps/new",
+            json!({"data": {"k": "v"}}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await["data"]["version"], 1);
+}
+
+#[tokio::test]
+async fn a_write_without_an_object_under_data_is_a_400() {
     let app = seeded_app().await;
-    for method in ["PUT", "POST", "DELETE", "PATCH"] {
+    for body in [json!({}), json!({"data": "flat"}), json!(["data"])] {
         let response = app
             .clone()
-            .oneshot(request(method, "/v1/secrets/data/platform/postgres"))
+            .oneshot(with_body("POST", "/v1/secrets/data/apps/new", body.clone()))
             .await
             .unwrap();
-        assert_eq!(
-            response.status(),
-            StatusCode::METHOD_NOT_ALLOWED,
-            "{method} on a data path"
-        );
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{body}");
+    }
+}
+
+#[tokio::test]
+async fn check_and_set_refuses_a_write_against_a_stale_version() {
+    let app = seeded_app().await;
+    let stale = app
+        .clone()
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/data/platform/postgres",
+            json!({"data": {"k": "v"}, "options": {"cas": 0}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(stale.status(), StatusCode::BAD_REQUEST);
+    assert!(json_body(stale).await["errors"][0].is_string());
+
+    let current = app
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/data/platform/postgres",
+            json!({"data": {"k": "v"}, "options": {"cas": 1}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(current.status(), StatusCode::OK);
+    assert_eq!(json_body(current).await["data"]["version"], 2);
+}
+
+#[tokio::test]
+async fn deleting_the_data_path_soft_deletes_the_latest_version() {
+    let app = seeded_app().await;
+    let response = app
+        .clone()
+        .oneshot(request("DELETE", "/v1/secrets/data/platform/postgres"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let read = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/data/platform/postgres"))
+        .await
+        .unwrap();
+    assert_eq!(read.status(), StatusCode::NOT_FOUND);
+
+    let undelete = app
+        .clone()
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/undelete/platform/postgres",
+            json!({"versions": [1]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(undelete.status(), StatusCode::NO_CONTENT);
+
+    let read = app
+        .oneshot(request("GET", "/v1/secrets/data/platform/postgres"))
+        .await
+        .unwrap();
+    assert_eq!(read.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn destroyed_versions_are_gone_for_good() {
+    let app = seeded_app().await;
+    let response = app
+        .clone()
+        .oneshot(with_body(
+            "PUT",
+            "/v1/secrets/destroy/platform/postgres",
+            json!({"versions": "1"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let undelete = app
+        .clone()
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/undelete/platform/postgres",
+            json!({"versions": [1]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(undelete.status(), StatusCode::NO_CONTENT);
+
+    let metadata = app
+        .oneshot(request("GET", "/v1/secrets/metadata/platform/postgres"))
+        .await
+        .unwrap();
+    assert_eq!(
+        json_body(metadata).await["data"]["versions"]["1"]["destroyed"],
+        true
+    );
+}
+
+#[tokio::test]
+async fn a_version_operation_without_versions_is_a_400() {
+    let app = seeded_app().await;
+    let response = app
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/delete/platform/postgres",
+            json!({"versions": []}),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        json_body(response).await,
+        json!({"errors": ["no version number provided"]})
+    );
+}
+
+#[tokio::test]
+async fn a_metadata_write_sets_the_path_policy() {
+    let app = seeded_app().await;
+    let response = app
+        .clone()
+        .oneshot(with_body(
+            "POST",
+            "/v1/secrets/metadata/platform/postgres",
+            json!({
+                "max_versions": "3",
+                "cas_required": true,
+                "delete_version_after": "1h",
+                "custom_metadata": {"owner": "platform"},
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .oneshot(request("GET", "/v1/secrets/metadata/platform/postgres"))
+        .await
+        .unwrap();
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["max_versions"], 3);
+    assert_eq!(data["cas_required"], true);
+    assert_eq!(data["delete_version_after"], "1h0m0s");
+    assert_eq!(data["custom_metadata"]["owner"], "platform");
+}
+
+#[tokio::test]
+async fn deleting_the_metadata_removes_the_path_and_every_version() {
+    let app = seeded_app().await;
+    let response = app
+        .clone()
+        .oneshot(request("DELETE", "/v1/secrets/metadata/platform/postgres"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    for path in [
+        "/v1/secrets/data/platform/postgres",
+        "/v1/secrets/metadata/platform/postgres",
+    ] {
+        let response = app.clone().oneshot(request("GET", path)).await.unwrap();
+        assert_vault_not_found(response).await;
+    }
+    let listing = app
+        .oneshot(request("LIST", "/v1/secrets/metadata/platform"))
+        .await
+        .unwrap();
+    assert_eq!(
+        json_body(listing).await["data"]["keys"],
+        json!(["immich/", "redis"])
+    );
+}
+
+#[tokio::test]
+async fn patch_is_still_refused() {
+    let app = seeded_app().await;
+    for path in [
+        "/v1/secrets/data/platform/postgres",
+        "/v1/secrets/metadata/platform/postgres",
+    ] {
+        let response = app
+            .clone()
+            .oneshot(with_body("PATCH", path, json!({"data": {"k": "v"}})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{path}");
         assert_eq!(
             json_body(response).await,
             json!({"errors": ["unsupported operation"]}),
-            "{method} on a data path"
+            "{path}"
         );
     }
 }
 
 #[tokio::test]
-async fn writes_to_the_metadata_path_are_refused_as_well() {
+async fn writes_need_the_token_as_well() {
     let app = seeded_app().await;
-    for method in ["PUT", "POST", "DELETE"] {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/secrets/data/platform/postgres")
+                .body(Body::from(json!({"data": {"k": "v"}}).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_permission_denied(response).await;
+}
+
+#[cfg(feature = "vault-transit")]
+mod transit {
+    use super::*;
+    use assay_vault::store::sqlite::SqliteTransitStore;
+
+    async fn transit_app(mount: Mount) -> Router {
+        let pool = boot_pool().await;
+        let ctx = VaultCtx::new()
+            .with_kek(KekHandle::generate_ephemeral())
+            .with_transit(SqliteTransitStore::new(pool));
+        router::<VaultCtx, _>(mount, |r| {
+            r.layer(axum::middleware::from_fn(require_bearer))
+        })
+        .with_state(ctx)
+    }
+
+    #[tokio::test]
+    async fn encrypt_creates_the_key_and_decrypt_round_trips() {
+        let app = transit_app(Mount::new("secrets")).await;
         let response = app
             .clone()
-            .oneshot(request(method, "/v1/secrets/metadata/platform/postgres"))
+            .oneshot(with_body(
+                "POST",
+                "/v1/transit/encrypt/payments",
+                json!({"plaintext": "aGVsbG8gd29ybGQ="}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let data = json_body(response).await["data"].clone();
+        let ciphertext = data["ciphertext"].as_str().unwrap().to_string();
+        assert!(ciphertext.starts_with("vault:v1:"), "{ciphertext}");
+        assert_eq!(data["key_version"], 1);
+
+        let response = app
+            .oneshot(with_body(
+                "PUT",
+                "/v1/transit/decrypt/payments",
+                json!({"ciphertext": ciphertext}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await["data"]["plaintext"],
+            "aGVsbG8gd29ybGQ="
+        );
+    }
+
+    #[tokio::test]
+    async fn decrypting_under_an_unknown_key_is_a_400_not_a_new_key() {
+        let app = transit_app(Mount::new("secrets")).await;
+        let response = app
+            .oneshot(with_body(
+                "POST",
+                "/v1/transit/decrypt/nope",
+                json!({"ciphertext": "vault:v1:AAAA"}),
+            ))
             .await
             .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
         assert_eq!(
-            response.status(),
-            StatusCode::METHOD_NOT_ALLOWED,
-            "{method} on a metadata path"
+            json_body(response).await,
+            json!({"errors": ["encryption key not found"]})
         );
     }
+
+    #[tokio::test]
+    async fn plaintext_that_is_not_base64_is_a_400() {
+        let app = transit_app(Mount::new("secrets")).await;
+        let response = app
+            .oneshot(with_body(
+                "POST",
+                "/v1/transit/encrypt/payments",
+                json!({"plaintext": "not base64!"}),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn the_transit_mount_is_the_configured_one() {
+        let app = transit_app(Mount::new("secrets").with_transit("crypto")).await;
+        let body = json!({"plaintext": "aGk="});
+        let default = app
+            .clone()
+            .oneshot(with_body("POST", "/v1/transit/encrypt/k", body.clone()))
+            .await
+            .unwrap();
+        assert_vault_not_found(default).await;
+
+        let configured = app
+            .oneshot(with_body("POST", "/v1/crypto/encrypt/k", body))
+            .await
+            .unwrap();
+        assert_eq!(configured.status(), StatusCode::OK);
+    }
 }
 
 #[tokio::test]
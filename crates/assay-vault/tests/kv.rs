@@ -9,7 +9,7 @@
 use assay_vault::crypto::seal_state::SealState;
 use assay_vault::crypto::sealing::SealingMethod;
 use assay_vault::store::sqlite::SqliteKvStore;
-use assay_vault::{KekHandle, KvService};
+use assay_vault::{KekHandle, KvMetaUpdate, KvService, VaultError};
 use serde_json::json;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{Executor, SqlitePool};
@@ -249,3 +249,226 @@ async fn aad_binds_path_so_relocated_ciphertext_fails() {
         "AAD bind must reject ciphertext relocated to a different path; got {res:?}"
     );
 }
+
+#[tokio::test]
+async fn cas_write_only_lands_on_the_version_it_names() {
+    let svc = service(boot_pool().await);
+    assert_eq!(
+        svc.put_cas("k", b"v1", json!({}), Some(0)).await.unwrap(),
+        1
+    );
+    assert_eq!(
+        svc.put_cas("k", b"v2", json!({}), Some(1)).await.unwrap(),
+        2
+    );
+
+    // A writer still holding v1 loses, and so does a "create only" write.
+    for stale in [1, 0, 3] {
+        assert!(matches!(
+            svc.put_cas("k", b"clobber", json!({}), Some(stale)).await,
+            Err(VaultError::Conflict(_))
+        ));
+    }
+    let latest = svc.get("k", None).await.unwrap();
+    assert_eq!(latest.version, 2);
+    assert_eq!(latest.plaintext, b"v2");
+
+    // Writes without cas are untouched on a path that doesn't require it.
+    assert_eq!(svc.put("k", b"v3", json!({})).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn cas_required_refuses_blind_writes() {
+    let svc = service(boot_pool().await);
+    // Policy can be set before the path has any versions.
+    let meta = svc
+        .put_meta(
+            "guarded",
+            &KvMetaUpdate {
+                cas_required: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(meta.latest_version, 0);
+    assert!(meta.cas_required);
+
+    assert!(matches!(
+        svc.put("guarded", b"v1", json!({})).await,
+        Err(VaultError::Invalid(_))
+    ));
+    assert_eq!(
+        svc.put_cas("guarded", b"v1", json!({}), Some(0))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(matches!(
+        svc.put_cas("guarded", b"v2", json!({}), Some(0)).await,
+        Err(VaultError::Conflict(_))
+    ));
+}
+
+#[tokio::test]
+async fn max_versions_prunes_the_oldest_versions_on_write() {
+    let svc = service(boot_pool().await);
+    for v in 1..=4 {
+        svc.put("k", format!("v{v}").as_bytes(), json!({}))
+            .await
+            .unwrap();
+    }
+    svc.put_meta(
+        "k",
+        &KvMetaUpdate {
+            max_versions: Some(2),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    // Lowering the limit doesn't prune by itself…
+    assert_eq!(svc.versions("k").await.unwrap().len(), 4);
+
+    // …the next write does, down to the limit.
+    svc.put("k", b"v5", json!({})).await.unwrap();
+    let kept: Vec<i64> = svc
+        .versions("k")
+        .await
+        .unwrap()
+        .iter()
+        .map(|v| v.version)
+        .collect();
+    assert_eq!(kept, vec![4, 5]);
+    assert!(matches!(
+        svc.get("k", Some(3)).await,
+        Err(VaultError::NotFound)
+    ));
+    assert_eq!(svc.get("k", Some(4)).await.unwrap().plaintext, b"v4");
+    assert_eq!(svc.read_meta("k").await.unwrap().latest_version, 5);
+}
+
+#[tokio::test]
+async fn delete_version_after_schedules_each_new_version_for_deletion() {
+    let svc = service(boot_pool().await);
+    svc.put_meta(
+        "later",
+        &KvMetaUpdate {
+            delete_version_after: Some(3600),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    svc.put("later", b"v1", json!({})).await.unwrap();
+    let read = svc.get("later", None).await.unwrap();
+    let due = read.deleted_at.expect("deletion scheduled");
+    assert!((due - read.created_at - 3600.0).abs() < 5.0);
+    assert!(!read.is_deleted(), "not due for an hour");
+
+    // A scheduled version can still be deleted right away.
+    svc.soft_delete("later", 1).await.unwrap();
+    assert!(svc.get("later", Some(1)).await.unwrap().is_deleted());
+
+    svc.put_meta(
+        "soon",
+        &KvMetaUpdate {
+            delete_version_after: Some(1),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    svc.put("soon", b"v1", json!({})).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    assert!(svc.get("soon", None).await.unwrap().is_deleted());
+}
+
+#[tokio::test]
+async fn metadata_updates_leave_unnamed_fields_alone() {
+    let svc = service(boot_pool().await);
+    svc.put("k", b"v1", json!({"owner": "alice", "rotate": "monthly"}))
+        .await
+        .unwrap();
+    svc.put_meta(
+        "k",
+        &KvMetaUpdate {
+            cas_required: Some(true),
+            max_versions: Some(10),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let meta = svc
+        .put_meta(
+            "k",
+            &KvMetaUpdate {
+                custom_md: Some(json!({"owner": "bob"})),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(meta.cas_required);
+    assert_eq!(meta.max_versions, 10);
+    assert_eq!(meta.delete_version_after, 0);
+    assert_eq!(meta.latest_version, 1);
+    // custom_md is replaced, not merged, on a metadata write.
+    assert_eq!(meta.custom_md, json!({"owner": "bob"}));
+
+    for bad in [
+        KvMetaUpdate {
+            max_versions: Some(-1),
+            ..Default::default()
+        },
+        KvMetaUpdate {
+            delete_version_after: Some(-1),
+            ..Default::default()
+        },
+        KvMetaUpdate {
+            custom_md: Some(json!("owner")),
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(
+            svc.put_meta("k", &bad).await,
+            Err(VaultError::Invalid(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn delete_path_leaves_nothing_behind() {
+    let svc = service(boot_pool().await);
+    svc.put("k", b"v1", json!({"owner": "alice"}))
+        .await
+        .unwrap();
+    svc.put("k", b"v2", json!({})).await.unwrap();
+    svc.put("kept", b"v", json!({})).await.unwrap();
+
+    svc.delete_path("k").await.unwrap();
+
+    assert!(matches!(
+        svc.get("k", Some(1)).await,
+        Err(VaultError::NotFound)
+    ));
+    assert!(matches!(
+        svc.read_meta("k").await,
+        Err(VaultError::NotFound)
+    ));
+    let paths: Vec<String> = svc
+        .list("")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.path)
+        .collect();
+    assert_eq!(paths, ["kept"]);
+    // A fresh write starts the path's history over.
+    assert_eq!(svc.put("k", b"again", json!({})).await.unwrap(), 1);
+    assert!(matches!(
+        svc.delete_path("missing").await,
+        Err(VaultError::NotFound)
+    ));
+}
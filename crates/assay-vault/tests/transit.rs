@@ -1,12 +1,12 @@
-//! Transit integration tests — full encrypt / decrypt / rotate cycle
-//! against the SQLite store.
+//! Transit integration tests — encrypt / decrypt / rotate, signing,
+//! HMAC, rewrap and data keys against the SQLite store.
 
 #![cfg(all(feature = "backend-sqlite", feature = "vault-transit"))]
 
 use assay_vault::crypto::seal_state::SealState;
 use assay_vault::crypto::sealing::SealingMethod;
 use assay_vault::store::sqlite::SqliteTransitStore;
-use assay_vault::{KekHandle, TransitService};
+use assay_vault::{KekHandle, SignatureAlgorithm, TransitService};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{Executor, SqlitePool};
 use std::str::FromStr;
@@ -174,3 +174,187 @@ async fn decrypt_unknown_version_is_not_found() {
         Err(assay_vault::VaultError::NotFound)
     ));
 }
+
+#[tokio::test]
+async fn unknown_key_type_is_invalid() {
+    let svc = service(boot_pool().await);
+    assert!(matches!(
+        svc.create_key("k", Some("des")).await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn sign_verify_round_trip_and_public_key_export() {
+    let svc = service(boot_pool().await);
+    for (name, algo) in [("ed", "ed25519"), ("ec", "ecdsa-p256"), ("rs", "rsa-2048")] {
+        svc.create_key(name, Some(algo)).await.unwrap();
+        let sig = svc
+            .sign(name, b"release.tar.gz", SignatureAlgorithm::default())
+            .await
+            .unwrap();
+        assert!(sig.starts_with("vault:v1:"), "{algo}: {sig}");
+        assert!(
+            svc.verify(name, b"release.tar.gz", &sig, SignatureAlgorithm::default())
+                .await
+                .unwrap()
+        );
+        assert!(
+            !svc.verify(
+                name,
+                b"tampered.tar.gz",
+                &sig,
+                SignatureAlgorithm::default()
+            )
+            .await
+            .unwrap()
+        );
+
+        let info = svc.read_key(name).await.unwrap();
+        assert_eq!(info.key.algo, algo);
+        let pem = info.versions[0].public_key.as_deref().unwrap();
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"), "{algo}");
+    }
+
+    // Signing keys don't encrypt; encryption keys don't sign.
+    assert!(matches!(
+        svc.encrypt("ed", b"x").await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+    svc.create_key("aes", None).await.unwrap();
+    assert!(matches!(
+        svc.sign("aes", b"x", SignatureAlgorithm::default()).await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+    assert!(
+        svc.read_key("aes").await.unwrap().versions[0]
+            .public_key
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn rotated_signing_key_keeps_old_signatures_verifiable() {
+    let svc = service(boot_pool().await);
+    svc.create_key("release", Some("ed25519")).await.unwrap();
+    let old = svc
+        .sign("release", b"v1.0", SignatureAlgorithm::default())
+        .await
+        .unwrap();
+    svc.rotate("release").await.unwrap();
+    let new = svc
+        .sign("release", b"v1.0", SignatureAlgorithm::default())
+        .await
+        .unwrap();
+    assert!(new.starts_with("vault:v2:"));
+    assert!(
+        svc.verify("release", b"v1.0", &old, SignatureAlgorithm::default())
+            .await
+            .unwrap()
+    );
+
+    let info = svc.read_key("release").await.unwrap();
+    assert_eq!(info.versions.len(), 2);
+    assert_ne!(info.versions[0].public_key, info.versions[1].public_key);
+
+    // Raising the floor retires v1 signatures outright.
+    svc.set_min_decryption_version("release", 2).await.unwrap();
+    assert!(matches!(
+        svc.verify("release", b"v1.0", &old, SignatureAlgorithm::default())
+            .await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn min_decryption_version_gates_decrypt_and_rewrap_moves_forward() {
+    let svc = service(boot_pool().await);
+    svc.create_key("pay", None).await.unwrap();
+    let ct_v1 = svc.encrypt("pay", b"card").await.unwrap();
+    svc.rotate("pay").await.unwrap();
+
+    let ct_v2 = svc.rewrap("pay", &ct_v1).await.unwrap();
+    assert!(ct_v2.starts_with("vault:v2:"));
+    assert_eq!(svc.decrypt("pay", &ct_v2).await.unwrap(), b"card");
+
+    let key = svc.set_min_decryption_version("pay", 2).await.unwrap();
+    assert_eq!(key.min_decryption_version, 2);
+    assert!(matches!(
+        svc.decrypt("pay", &ct_v1).await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+    assert!(matches!(
+        svc.rewrap("pay", &ct_v1).await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+    assert_eq!(svc.decrypt("pay", &ct_v2).await.unwrap(), b"card");
+
+    // The floor can't pass the latest version.
+    assert!(matches!(
+        svc.set_min_decryption_version("pay", 3).await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+    assert!(matches!(
+        svc.set_min_decryption_version("ghost", 1).await,
+        Err(assay_vault::VaultError::NotFound)
+    ));
+}
+
+#[tokio::test]
+async fn datakey_plaintext_matches_its_wrapped_copy() {
+    let svc = service(boot_pool().await);
+    svc.create_key("env", None).await.unwrap();
+    let dk = svc.datakey("env", None).await.unwrap();
+    assert_eq!(dk.plaintext.len(), 32);
+    assert_eq!(
+        svc.decrypt("env", &dk.ciphertext).await.unwrap(),
+        dk.plaintext
+    );
+    assert_eq!(
+        svc.datakey("env", Some(512)).await.unwrap().plaintext.len(),
+        64
+    );
+    assert!(matches!(
+        svc.datakey("env", Some(100)).await,
+        Err(assay_vault::VaultError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn hmac_is_versioned_and_verifiable() {
+    let svc = service(boot_pool().await);
+    svc.create_key("mac", None).await.unwrap();
+    let tag = svc.hmac("mac", b"payload", None).await.unwrap();
+    assert!(tag.starts_with("vault:v1:"));
+    assert_eq!(svc.hmac("mac", b"payload", None).await.unwrap(), tag);
+    assert!(
+        svc.verify_hmac("mac", b"payload", &tag, None)
+            .await
+            .unwrap()
+    );
+    assert!(!svc.verify_hmac("mac", b"other", &tag, None).await.unwrap());
+    assert!(
+        !svc.verify_hmac("mac", b"payload", &tag, Some("sha2-512"))
+            .await
+            .unwrap()
+    );
+
+    // A new version gets a new HMAC key; the old tag still checks out.
+    svc.rotate("mac").await.unwrap();
+    let tag_v2 = svc.hmac("mac", b"payload", None).await.unwrap();
+    assert!(tag_v2.starts_with("vault:v2:"));
+    assert!(
+        svc.verify_hmac("mac", b"payload", &tag, None)
+            .await
+            .unwrap()
+    );
+
+    // Signing keys HMAC too.
+    svc.create_key("sig", Some("ecdsa-p256")).await.unwrap();
+    let t = svc.hmac("sig", b"x", Some("sha2-512")).await.unwrap();
+    assert!(
+        svc.verify_hmac("sig", b"x", &t, Some("sha2-512"))
+            .await
+            .unwrap()
+    );
+}
@@ -224,12 +224,20 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
     ///
     /// If `attempt >= max_attempts`, the activity is permanently FAILED
     /// and an `ActivityFailed` event is appended so the workflow can react.
+    ///
+    /// An activity whose cancellation was requested is never retried: the
+    /// failure is the worker acknowledging the cancel, so it ends
+    /// CANCELLED with an `ActivityCancelled` event instead.
     pub async fn fail_activity(&self, id: i64, error: &str) -> Result<()> {
         let act = match self.store.get_activity(id).await? {
             Some(a) => a,
             None => return Ok(()),
         };
 
+        if self.store.acknowledge_activity_cancel(id).await? {
+            return self.record_activity_cancelled(&act).await;
+        }
+
         if act.attempt < act.max_attempts {
             // Compute exponential backoff: interval * coefficient^(attempt-1)
             let backoff = act.initial_interval_secs * act.backoff_coefficient.powi(act.attempt - 1);
@@ -271,10 +279,81 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         Ok(())
     }
 
-    pub async fn heartbeat_activity(&self, id: i64, details: Option<&str>) -> Result<()> {
+    /// Record a heartbeat. Returns true when the worker should stop the
+    /// activity (see [`WorkflowStore::heartbeat_activity`]).
+    pub async fn heartbeat_activity(&self, id: i64, details: Option<&str>) -> Result<bool> {
         self.store.heartbeat_activity(id, details).await
     }
 
+    /// Cancel activity `seq` of a workflow on behalf of its
+    /// `CancelActivity` command. A PENDING activity is cancelled outright
+    /// and the workflow woken to see `ActivityCancelled`; a RUNNING one
+    /// gets `ActivityCancelRequested` and ends when its worker reacts to
+    /// the heartbeat flag (or completes anyway).
+    pub async fn cancel_activity(
+        &self,
+        workflow_id: &str,
+        seq: i32,
+    ) -> Result<CancelActivityResult> {
+        let result = self.store.request_activity_cancel(workflow_id, seq).await?;
+        let Some(act) = self
+            .store
+            .get_activity_by_workflow_seq(workflow_id, seq)
+            .await?
+        else {
+            return Ok(result);
+        };
+        match result {
+            CancelActivityResult::Cancelled => self.record_activity_cancelled(&act).await?,
+            CancelActivityResult::CancelRequested => {
+                let event_seq = self.store.get_event_count(workflow_id).await? as i32 + 1;
+                self.store
+                    .append_event(&WorkflowEvent {
+                        id: None,
+                        workflow_id: workflow_id.to_string(),
+                        seq: event_seq,
+                        event_type: "ActivityCancelRequested".to_string(),
+                        payload: Some(
+                            serde_json::json!({
+                                "activity_id": act.id,
+                                "activity_seq": seq,
+                                "name": act.name,
+                            })
+                            .to_string(),
+                        ),
+                        timestamp: timestamp_now(),
+                    })
+                    .await?;
+            }
+            CancelActivityResult::Unchanged | CancelActivityResult::NotFound => {}
+        }
+        Ok(result)
+    }
+
+    /// Append `ActivityCancelled` for an activity that just became
+    /// CANCELLED and wake its workflow.
+    async fn record_activity_cancelled(&self, act: &WorkflowActivity) -> Result<()> {
+        let event_seq = self.store.get_event_count(&act.workflow_id).await? as i32 + 1;
+        self.store
+            .append_event(&WorkflowEvent {
+                id: None,
+                workflow_id: act.workflow_id.clone(),
+                seq: event_seq,
+                event_type: "ActivityCancelled".to_string(),
+                payload: Some(
+                    serde_json::json!({
+                        "activity_id": act.id,
+                        "activity_seq": act.seq,
+                        "name": act.name,
+                    })
+                    .to_string(),
+                ),
+                timestamp: timestamp_now(),
+            })
+            .await?;
+        self.mark_and_emit_needs_dispatch(&act.workflow_id).await
+    }
+
     pub async fn record_side_effect(&self, workflow_id: &str, value: &str) -> Result<()> {
         let now = timestamp_now();
         let seq = self.store.get_event_count(workflow_id).await? as i32 + 1;
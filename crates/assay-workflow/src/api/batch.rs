@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::api::workflows::AppError;
+use crate::batch::{BatchFilter, BatchOperation, StartBatchResult};
+use crate::ctx::WorkflowCtx;
+use crate::store::WorkflowStore;
+use crate::types::BatchJob;
+
+pub fn router<S: WorkflowStore + 'static>() -> Router<Arc<WorkflowCtx<S>>> {
+    Router::new()
+        .route("/batch", post(start_batch_job).get(list_batch_jobs))
+        .route("/batch/{id}", get(get_batch_job))
+        .route("/batch/{id}/cancel", post(cancel_batch_job))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct StartBatchRequest {
+    /// Namespace (default: "main")
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    /// Which workflows to apply the operation to
+    pub filter: BatchFilter,
+    /// What to do to each one, e.g. `{"type": "terminate", "reason": "..."}`
+    pub operation: BatchOperation,
+    /// Rate limit in workflows per second (default 50, 0.001 to 10000)
+    pub max_per_second: Option<f64>,
+}
+
+fn default_namespace() -> String {
+    "main".to_string()
+}
+
+#[derive(Deserialize)]
+pub struct ListBatchQuery {
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Start a batch job. Returns once the job is recorded; poll
+/// `GET /batch/{id}` for progress.
+#[utoipa::path(
+    post, path = "/api/v1/engine/workflow/batch",
+    tag = "batch",
+    request_body = StartBatchRequest,
+    responses(
+        (status = 201, description = "Batch job started", body = BatchJob),
+        (status = 400, description = "Invalid filter or operation"),
+    ),
+)]
+pub async fn start_batch_job<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Json(req): Json<StartBatchRequest>,
+) -> Result<(axum::http::StatusCode, Json<BatchJob>), AppError> {
+    match state
+        .start_batch_job(
+            &req.namespace,
+            req.filter,
+            req.operation,
+            req.max_per_second,
+        )
+        .await?
+    {
+        StartBatchResult::Started(job) => Ok((axum::http::StatusCode::CREATED, Json(*job))),
+        StartBatchResult::Invalid(msg) => Err(AppError::bad_request(msg)),
+    }
+}
+
+#[utoipa::path(
+    get, path = "/api/v1/engine/workflow/batch",
+    tag = "batch",
+    params(
+        ("namespace" = Option<String>, Query, description = "Namespace (default: main)"),
+        ("limit" = Option<i64>, Query, description = "Max results (default 50)"),
+        ("offset" = Option<i64>, Query, description = "Pagination offset"),
+    ),
+    responses((status = 200, description = "Batch jobs, newest first", body = Vec<BatchJob>)),
+)]
+pub async fn list_batch_jobs<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Query(q): Query<ListBatchQuery>,
+) -> Result<Json<Vec<BatchJob>>, AppError> {
+    let jobs = state
+        .list_batch_jobs(&q.namespace, q.limit, q.offset)
+        .await?;
+    Ok(Json(jobs))
+}
+
+#[utoipa::path(
+    get, path = "/api/v1/engine/workflow/batch/{id}",
+    tag = "batch",
+    params(("id" = String, Path, description = "Batch job ID")),
+    responses(
+        (status = 200, description = "Batch job with progress", body = BatchJob),
+        (status = 404, description = "Batch job not found"),
+    ),
+)]
+pub async fn get_batch_job<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path(id): Path<String>,
+) -> Result<Json<BatchJob>, AppError> {
+    let job = state
+        .get_batch_job(&id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("batch job {id}")))?;
+    Ok(Json(job))
+}
+
+/// Stop a running batch job. Workflows it already processed are not
+/// rolled back.
+#[utoipa::path(
+    post, path = "/api/v1/engine/workflow/batch/{id}/cancel",
+    tag = "batch",
+    params(("id" = String, Path, description = "Batch job ID")),
+    responses(
+        (status = 200, description = "Batch job cancelled", body = BatchJob),
+        (status = 404, description = "Batch job not found"),
+        (status = 409, description = "Batch job already finished"),
+    ),
+)]
+pub async fn cancel_batch_job<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path(id): Path<String>,
+) -> Result<Json<BatchJob>, AppError> {
+    let cancelled = state.cancel_batch_job(&id).await?;
+    let job = state
+        .get_batch_job(&id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("batch job {id}")))?;
+    if !cancelled {
+        return Err(AppError::conflict(format!(
+            "batch job {id} is already {}",
+            job.status
+        )));
+    }
+    Ok(Json(job))
+}
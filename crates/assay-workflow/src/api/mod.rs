@@ -1,4 +1,5 @@
 pub mod activities;
+pub mod batch;
 pub mod events;
 pub mod namespaces;
 pub mod openapi;
@@ -6,6 +7,7 @@ pub mod public;
 pub mod queues;
 pub mod schedules;
 pub mod tasks;
+pub mod visibility;
 pub mod workers;
 pub mod workflow_tasks;
 pub mod workflows;
@@ -54,4 +56,6 @@ fn api_v1_router<S: WorkflowStore>() -> Router<Arc<WorkflowCtx<S>>> {
         .merge(workers::router::<S>())
         .merge(namespaces::router::<S>())
         .merge(queues::router::<S>())
+        .merge(batch::router::<S>())
+        .merge(visibility::router::<S>())
 }
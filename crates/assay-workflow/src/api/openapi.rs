@@ -23,9 +23,12 @@ use crate::store::WorkflowStore;
         crate::api::workflows::describe_workflow,
         crate::api::workflows::get_events,
         crate::api::workflows::send_signal,
+        crate::api::workflows::update_workflow,
+        crate::api::workflows::get_update,
         crate::api::workflows::cancel_workflow,
         crate::api::workflows::terminate_workflow,
         crate::api::workflows::retry_failed_activity,
+        crate::api::workflows::reset_workflow,
         crate::api::tasks::register_worker,
         crate::api::tasks::poll_task,
         crate::api::tasks::complete_task,
@@ -39,11 +42,19 @@ use crate::store::WorkflowStore;
         crate::api::schedules::patch_schedule,
         crate::api::schedules::pause_schedule,
         crate::api::schedules::resume_schedule,
+        crate::api::schedules::backfill_schedule,
         crate::api::workflows::list_children,
         crate::api::workflows::continue_as_new,
         crate::api::workflows::get_workflow_state,
         crate::api::workflows::get_workflow_state_by_name,
         crate::api::workers::list_workers,
+        crate::api::batch::start_batch_job,
+        crate::api::batch::list_batch_jobs,
+        crate::api::batch::get_batch_job,
+        crate::api::batch::cancel_batch_job,
+        crate::api::visibility::count_workflows,
+        crate::api::visibility::list_search_attributes,
+        crate::api::visibility::register_search_attribute,
         crate::api::public::health_check,
         crate::api::public::version,
     ),
@@ -54,6 +65,8 @@ use crate::store::WorkflowStore;
         crate::types::WorkflowTimer,
         crate::types::WorkflowSignal,
         crate::types::WorkflowSchedule,
+        crate::types::ScheduleAction,
+        crate::types::ScheduleBackfillResult,
         crate::types::WorkflowWorker,
         crate::types::WorkflowStatus,
         crate::types::ActivityStatus,
@@ -64,11 +77,27 @@ use crate::store::WorkflowStore;
         crate::api::tasks::PollRequest,
         crate::api::tasks::CompleteTaskBody,
         crate::api::tasks::FailTaskBody,
+        crate::api::tasks::HeartbeatTaskBody,
+        crate::api::tasks::HeartbeatTaskResponse,
         crate::api::schedules::CreateScheduleRequest,
         crate::api::schedules::PatchScheduleRequest,
+        crate::api::schedules::BackfillScheduleRequest,
         crate::api::workflows::ContinueAsNewBody,
         crate::api::workflows::RetryFailedActivityBody,
         crate::api::workflows::RetryFailedActivityResponse,
+        crate::api::workflows::ResetWorkflowBody,
+        crate::api::workflows::ResetWorkflowResponse,
+        crate::api::workflows::UpdateBody,
+        crate::api::workflows::UpdateResponse,
+        crate::types::BatchJob,
+        crate::batch::BatchFilter,
+        crate::batch::BatchOperation,
+        crate::api::batch::StartBatchRequest,
+        crate::types::SearchAttributeDef,
+        crate::types::SearchAttributeType,
+        crate::types::WorkflowStatusCount,
+        crate::api::visibility::CountResponse,
+        crate::api::visibility::RegisterSearchAttributeRequest,
         crate::api::public::VersionInfo,
     )),
     tags(
@@ -76,6 +105,7 @@ use crate::store::WorkflowStore;
         (name = "tasks", description = "Task execution for worker apps"),
         (name = "schedules", description = "Cron schedule management"),
         (name = "workers", description = "Worker registry and health"),
+        (name = "batch", description = "Batch operations over filtered workflows"),
         (name = "events", description = "Real-time event streams (SSE)"),
         (name = "meta", description = "Engine metadata (version, build info)"),
     ),
@@ -8,8 +8,11 @@ use utoipa::ToSchema;
 
 use crate::api::workflows::AppError;
 use crate::ctx::WorkflowCtx;
+use crate::scheduler::{self, MAX_FIRES_PER_PASS};
 use crate::store::WorkflowStore;
-use crate::types::{SchedulePatch, WorkflowSchedule};
+use crate::types::{
+    CatchupPolicy, OverlapPolicy, ScheduleBackfillResult, SchedulePatch, WorkflowSchedule,
+};
 
 pub fn router<S: WorkflowStore + 'static>() -> Router<Arc<WorkflowCtx<S>>> {
     Router::new()
@@ -22,6 +25,7 @@ pub fn router<S: WorkflowStore + 'static>() -> Router<Arc<WorkflowCtx<S>>> {
         )
         .route("/schedules/{name}/pause", post(pause_schedule))
         .route("/schedules/{name}/resume", post(resume_schedule))
+        .route("/schedules/{name}/backfill", post(backfill_schedule))
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -47,6 +51,17 @@ pub struct CreateScheduleRequest {
     /// Overlap policy: skip, queue, cancel_old, allow_all (default: "skip")
     #[serde(default = "default_overlap")]
     pub overlap_policy: String,
+    /// Missed fires older than this many seconds are dropped instead of
+    /// caught up (default: 31536000, one year)
+    #[serde(default = "default_catchup_window")]
+    pub catchup_window_secs: i64,
+    /// What to do with missed fires inside the window: skip, one, all
+    /// (default: "one")
+    #[serde(default = "default_catchup_policy")]
+    pub catchup_policy: String,
+    /// Upper bound of a per-fire start delay in seconds (default: 0)
+    #[serde(default)]
+    pub jitter_secs: i64,
 }
 
 fn default_queue() -> String {
@@ -65,12 +80,48 @@ fn default_timezone() -> String {
     "UTC".to_string()
 }
 
+fn default_catchup_window() -> i64 {
+    365 * 24 * 3600
+}
+
+fn default_catchup_policy() -> String {
+    "one".to_string()
+}
+
+/// Reject policy names and durations the scheduler can't act on, so a
+/// typo surfaces at write time rather than as a silently defaulted policy.
+fn validate_schedule_options(
+    overlap_policy: Option<&str>,
+    catchup_policy: Option<&str>,
+    catchup_window_secs: Option<i64>,
+    jitter_secs: Option<i64>,
+) -> Result<(), AppError> {
+    if let Some(p) = overlap_policy {
+        p.parse::<OverlapPolicy>().map_err(AppError::bad_request)?;
+    }
+    if let Some(p) = catchup_policy {
+        p.parse::<CatchupPolicy>().map_err(AppError::bad_request)?;
+    }
+    if catchup_window_secs.is_some_and(|w| w < 0) {
+        return Err(AppError::bad_request(
+            "catchup_window_secs must not be negative".to_string(),
+        ));
+    }
+    if jitter_secs.is_some_and(|j| j < 0) {
+        return Err(AppError::bad_request(
+            "jitter_secs must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 #[utoipa::path(
     post, path = "/api/v1/engine/workflow/schedules",
     tag = "schedules",
     request_body = CreateScheduleRequest,
     responses(
         (status = 201, description = "Schedule created", body = WorkflowSchedule),
+        (status = 400, description = "Unknown policy or negative duration"),
         (status = 500, description = "Internal error"),
     ),
 )]
@@ -89,6 +140,13 @@ pub async fn create_schedule<S: WorkflowStore>(
         )));
     }
 
+    validate_schedule_options(
+        Some(&req.overlap_policy),
+        Some(&req.catchup_policy),
+        Some(req.catchup_window_secs),
+        Some(req.jitter_secs),
+    )?;
+
     let schedule = WorkflowSchedule {
         name: req.name.clone(),
         namespace: req.namespace.clone(),
@@ -102,7 +160,11 @@ pub async fn create_schedule<S: WorkflowStore>(
         last_run_at: None,
         next_run_at: None,
         last_workflow_id: None,
+        catchup_window_secs: req.catchup_window_secs,
+        catchup_policy: req.catchup_policy,
+        jitter_secs: req.jitter_secs,
         created_at: now,
+        recent_actions: Vec::new(),
     };
 
     state.create_schedule(&schedule).await?;
@@ -202,6 +264,12 @@ pub struct PatchScheduleRequest {
     pub task_queue: Option<String>,
     /// New overlap policy (skip, queue, cancel_old, allow_all).
     pub overlap_policy: Option<String>,
+    /// New catch-up window in seconds.
+    pub catchup_window_secs: Option<i64>,
+    /// New catch-up policy (skip, one, all).
+    pub catchup_policy: Option<String>,
+    /// New jitter bound in seconds.
+    pub jitter_secs: Option<i64>,
 }
 
 #[utoipa::path(
@@ -214,6 +282,7 @@ pub struct PatchScheduleRequest {
     request_body = PatchScheduleRequest,
     responses(
         (status = 200, description = "Schedule updated", body = WorkflowSchedule),
+        (status = 400, description = "Unknown policy or negative duration"),
         (status = 404, description = "Schedule not found"),
     ),
 )]
@@ -233,12 +302,22 @@ pub async fn patch_schedule<S: WorkflowStore>(
         )));
     }
 
+    validate_schedule_options(
+        req.overlap_policy.as_deref(),
+        req.catchup_policy.as_deref(),
+        req.catchup_window_secs,
+        req.jitter_secs,
+    )?;
+
     let patch = SchedulePatch {
         cron_expr: req.cron_expr,
         timezone: req.timezone,
         input: req.input,
         task_queue: req.task_queue,
         overlap_policy: req.overlap_policy,
+        catchup_window_secs: req.catchup_window_secs,
+        catchup_policy: req.catchup_policy,
+        jitter_secs: req.jitter_secs,
     };
 
     let updated = state
@@ -297,6 +376,68 @@ pub async fn resume_schedule<S: WorkflowStore>(
     Ok(Json(serde_json::to_value(updated)?))
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct BackfillScheduleRequest {
+    /// Start of the range, epoch seconds (inclusive)
+    pub start_at: f64,
+    /// End of the range, epoch seconds (exclusive)
+    pub end_at: f64,
+    /// Overlap policy for the backfilled runs (default: the schedule's own)
+    pub overlap_policy: Option<String>,
+}
+
+#[utoipa::path(
+    post, path = "/api/v1/engine/workflow/schedules/{name}/backfill",
+    tag = "schedules",
+    params(
+        ("name" = String, Path, description = "Schedule name"),
+        ("namespace" = Option<String>, Query, description = "Namespace (default: main)"),
+    ),
+    request_body = BackfillScheduleRequest,
+    responses(
+        (status = 200, description = "Backfill applied", body = ScheduleBackfillResult),
+        (status = 400, description = "Empty or over-large range, or unknown overlap policy"),
+        (status = 404, description = "Schedule not found"),
+    ),
+)]
+pub async fn backfill_schedule<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path(name): Path<String>,
+    Query(q): Query<NsQuery>,
+    Json(req): Json<BackfillScheduleRequest>,
+) -> Result<Json<ScheduleBackfillResult>, AppError> {
+    if req.end_at <= req.start_at {
+        return Err(AppError::bad_request(
+            "end_at must be after start_at".to_string(),
+        ));
+    }
+    let schedule = state
+        .get_schedule(&q.namespace, &name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("schedule {name}")))?;
+    let overlap = req
+        .overlap_policy
+        .as_deref()
+        .unwrap_or(&schedule.overlap_policy)
+        .parse::<OverlapPolicy>()
+        .map_err(AppError::bad_request)?;
+
+    let fires =
+        scheduler::backfill_fire_times(&schedule, req.start_at, req.end_at).ok_or_else(|| {
+            AppError::bad_request(format!(
+                "schedule {name} has an invalid cron expression or timezone"
+            ))
+        })?;
+    if fires.len() > MAX_FIRES_PER_PASS {
+        return Err(AppError::bad_request(format!(
+            "range covers more than {MAX_FIRES_PER_PASS} fires; split the backfill"
+        )));
+    }
+
+    let result = state.backfill_schedule(&schedule, &fires, overlap).await?;
+    Ok(Json(result))
+}
+
 fn timestamp_now() -> f64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
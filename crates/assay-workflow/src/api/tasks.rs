@@ -178,7 +178,7 @@ pub async fn fail_task<S: WorkflowStore>(
 ) -> Result<axum::http::StatusCode, AppError> {
     // fail_activity honors the activity's retry policy: re-queues with
     // backoff while attempts remain, otherwise marks FAILED + appends
-    // ActivityFailed event.
+    // ActivityFailed event. A cancel-requested activity ends CANCELLED.
     state.fail_activity(id, &body.error).await?;
     Ok(axum::http::StatusCode::OK)
 }
@@ -188,21 +188,29 @@ pub struct HeartbeatTaskBody {
     pub details: Option<String>,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct HeartbeatTaskResponse {
+    /// The activity or its workflow was cancelled; the worker should
+    /// stop and report the task failed.
+    pub cancel_requested: bool,
+}
+
 #[utoipa::path(
     post, path = "/api/v1/engine/workflow/tasks/{id}/heartbeat",
     tag = "tasks",
     params(("id" = i64, Path, description = "Activity task ID")),
-    responses((status = 200, description = "Heartbeat recorded")),
+    request_body = HeartbeatTaskBody,
+    responses((status = 200, description = "Heartbeat recorded", body = HeartbeatTaskResponse)),
 )]
 pub async fn heartbeat_task<S: WorkflowStore>(
     State(state): State<Arc<WorkflowCtx<S>>>,
     Path(id): Path<i64>,
     Json(body): Json<HeartbeatTaskBody>,
-) -> Result<axum::http::StatusCode, AppError> {
-    state
+) -> Result<Json<HeartbeatTaskResponse>, AppError> {
+    let cancel_requested = state
         .heartbeat_activity(id, body.details.as_deref())
         .await?;
-    Ok(axum::http::StatusCode::OK)
+    Ok(Json(HeartbeatTaskResponse { cancel_requested }))
 }
 
 fn timestamp_now() -> f64 {
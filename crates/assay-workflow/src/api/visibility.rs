@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::workflows::AppError;
+use crate::ctx::WorkflowCtx;
+use crate::store::WorkflowStore;
+use crate::types::{SearchAttributeDef, SearchAttributeType, WorkflowStatusCount};
+use crate::visibility::{ParsedQuery, RegisterSearchAttributeResult};
+
+pub fn router<S: WorkflowStore + 'static>() -> Router<Arc<WorkflowCtx<S>>> {
+    Router::new()
+        .route("/visibility/count", get(count_workflows))
+        .route(
+            "/namespaces/{name}/search-attributes",
+            get(list_search_attributes).post(register_search_attribute),
+        )
+}
+
+#[derive(Deserialize)]
+pub struct CountQuery {
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    pub query: Option<String>,
+    /// Only `status` is supported.
+    pub group_by: Option<String>,
+}
+
+fn default_namespace() -> String {
+    "main".to_string()
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CountResponse {
+    pub count: i64,
+    /// Present with `group_by=status`; statuses with no workflows are omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<WorkflowStatusCount>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegisterSearchAttributeRequest {
+    /// Identifier (`[A-Za-z_][A-Za-z0-9_]*`); queried as `search.<name>`
+    pub name: String,
+    /// keyword | int | double | bool | datetime
+    #[serde(rename = "type")]
+    pub attr_type: SearchAttributeType,
+}
+
+/// Count workflows matching a visibility query, optionally grouped by
+/// status.
+#[utoipa::path(
+    get, path = "/api/v1/engine/workflow/visibility/count",
+    tag = "workflows",
+    params(
+        ("namespace" = Option<String>, Query, description = "Namespace (default: main)"),
+        ("query" = Option<String>, Query, description = "Visibility query (default: every workflow)"),
+        ("group_by" = Option<String>, Query, description = "`status` to break the count down by status"),
+    ),
+    responses(
+        (status = 200, description = "Matching workflow count", body = CountResponse),
+        (status = 400, description = "Invalid query or group_by"),
+    ),
+)]
+pub async fn count_workflows<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Query(q): Query<CountQuery>,
+) -> Result<Json<CountResponse>, AppError> {
+    let query = match state
+        .parse_visibility_query(&q.namespace, q.query.as_deref().unwrap_or_default())
+        .await?
+    {
+        ParsedQuery::Valid(query) => query,
+        ParsedQuery::Invalid(msg) => return Err(AppError::bad_request(msg)),
+    };
+    let resp = match q.group_by.as_deref() {
+        None => CountResponse {
+            count: state.count_workflows(&q.namespace, query.as_ref()).await?,
+            groups: None,
+        },
+        Some("status") => {
+            let groups = state
+                .count_workflows_by_status(&q.namespace, query.as_ref())
+                .await?;
+            CountResponse {
+                count: groups.iter().map(|g| g.count).sum(),
+                groups: Some(groups),
+            }
+        }
+        Some(other) => {
+            return Err(AppError::bad_request(format!(
+                "unsupported group_by {other:?} (only \"status\")"
+            )));
+        }
+    };
+    Ok(Json(resp))
+}
+
+#[utoipa::path(
+    get, path = "/api/v1/engine/workflow/namespaces/{name}/search-attributes",
+    tag = "namespaces",
+    params(("name" = String, Path, description = "Namespace name")),
+    responses(
+        (status = 200, description = "Registered search attributes", body = Vec<SearchAttributeDef>),
+    ),
+)]
+pub async fn list_search_attributes<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<SearchAttributeDef>>, AppError> {
+    Ok(Json(state.list_search_attributes(&name).await?))
+}
+
+/// Register a typed search attribute so queries can use and index
+/// `search.<name>`. Re-registering with the same type is a no-op.
+#[utoipa::path(
+    post, path = "/api/v1/engine/workflow/namespaces/{name}/search-attributes",
+    tag = "namespaces",
+    params(("name" = String, Path, description = "Namespace name")),
+    request_body = RegisterSearchAttributeRequest,
+    responses(
+        (status = 201, description = "Search attribute registered", body = SearchAttributeDef),
+        (status = 200, description = "Already registered with this type", body = SearchAttributeDef),
+        (status = 400, description = "Invalid attribute name"),
+        (status = 409, description = "Already registered with a different type"),
+    ),
+)]
+pub async fn register_search_attribute<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path(name): Path<String>,
+    Json(req): Json<RegisterSearchAttributeRequest>,
+) -> Result<(StatusCode, Json<SearchAttributeDef>), AppError> {
+    match state
+        .register_search_attribute(&name, &req.name, req.attr_type)
+        .await?
+    {
+        RegisterSearchAttributeResult::Created(def) => Ok((StatusCode::CREATED, Json(def))),
+        RegisterSearchAttributeResult::Exists(def) => Ok((StatusCode::OK, Json(def))),
+        RegisterSearchAttributeResult::Conflict(def) => Err(AppError::conflict(format!(
+            "search attribute {} is already registered as {}",
+            def.name, def.attr_type
+        ))),
+        RegisterSearchAttributeResult::Invalid(msg) => Err(AppError::bad_request(msg)),
+    }
+}
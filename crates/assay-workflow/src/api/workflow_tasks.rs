@@ -9,7 +9,8 @@
 //!    signal arrived).
 //! 2. A worker calls `POST /workflow-tasks/poll` to claim the next
 //!    dispatchable workflow on its queue. Response carries the workflow
-//!    id, type, input, and full event history for replay.
+//!    id, type, input, full event history for replay, and any pending
+//!    workflow updates.
 //! 3. The worker invokes the handler in a coroutine that yields commands
 //!    (ScheduleActivity, CompleteWorkflow, FailWorkflow, etc.) instead of
 //!    making side effects directly.
@@ -59,19 +60,30 @@ pub async fn poll_workflow_task<S: WorkflowStore>(
         .claim_workflow_task(&req.queue, &req.worker_id)
         .await?
     {
-        Some((wf, history)) => Ok(Json(serde_json::json!({
-            "workflow_id": wf.id,
-            "namespace": wf.namespace,
-            "workflow_type": wf.workflow_type,
-            "task_queue": wf.task_queue,
-            "input": wf.input.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()),
-            "history": history.iter().map(|e| serde_json::json!({
-                "seq": e.seq,
-                "event_type": e.event_type,
-                "payload": e.payload.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()),
-                "timestamp": e.timestamp,
-            })).collect::<Vec<_>>(),
-        }))),
+        Some((wf, history)) => {
+            // Updates waiting for this workflow ride along with the
+            // history; the worker validates + applies them after
+            // replaying the handler (see `crate::updates`).
+            let updates = state.list_pending_updates(&wf.id).await?;
+            Ok(Json(serde_json::json!({
+                "workflow_id": wf.id,
+                "namespace": wf.namespace,
+                "workflow_type": wf.workflow_type,
+                "task_queue": wf.task_queue,
+                "input": wf.input.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()),
+                "history": history.iter().map(|e| serde_json::json!({
+                    "seq": e.seq,
+                    "event_type": e.event_type,
+                    "payload": e.payload.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()),
+                    "timestamp": e.timestamp,
+                })).collect::<Vec<_>>(),
+                "updates": updates.iter().map(|u| serde_json::json!({
+                    "update_id": u.id,
+                    "name": u.name,
+                    "args": u.args.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok()),
+                })).collect::<Vec<_>>(),
+            })))
+        }
         None => Ok(Json(serde_json::Value::Null)),
     }
 }
@@ -92,7 +104,10 @@ pub struct SubmitCommandsRequest {
     tag = "workflow-tasks",
     params(("id" = String, Path, description = "Workflow ID")),
     request_body = SubmitCommandsRequest,
-    responses((status = 200, description = "Commands processed; lease released")),
+    responses(
+        (status = 200, description = "Commands processed; lease released"),
+        (status = 409, description = "An update command named another workflow's or an unknown update"),
+    ),
 )]
 pub async fn submit_commands<S: WorkflowStore>(
     State(state): State<Arc<WorkflowCtx<S>>>,
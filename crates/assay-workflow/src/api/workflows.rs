@@ -8,8 +8,13 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::ctx::WorkflowCtx;
+use crate::reset::{ResetPoint, ResetResult};
 use crate::store::WorkflowStore;
-use crate::types::{RetryFailedActivityResult, WorkflowStatus};
+use crate::types::{
+    IdReusePolicy, RetryFailedActivityResult, StartWorkflowResult, WorkflowStatus, WorkflowUpdate,
+};
+use crate::updates::RequestUpdateResult;
+use crate::visibility::ParsedQuery;
 
 pub fn router<S: WorkflowStore + 'static>() -> Router<Arc<WorkflowCtx<S>>> {
     Router::new()
@@ -17,9 +22,12 @@ pub fn router<S: WorkflowStore + 'static>() -> Router<Arc<WorkflowCtx<S>>> {
         .route("/workflows/{id}", get(describe_workflow))
         .route("/workflows/{id}/events", get(get_events_route))
         .route("/workflows/{id}/signal/{name}", post(send_signal))
+        .route("/workflows/{id}/update/{name}", post(update_workflow))
+        .route("/workflows/{id}/updates/{update_id}", get(get_update))
         .route("/workflows/{id}/cancel", post(cancel_workflow))
         .route("/workflows/{id}/terminate", post(terminate_workflow))
         .route("/workflows/{id}/retry", post(retry_failed_activity))
+        .route("/workflows/{id}/reset", post(reset_workflow))
         .route("/workflows/{id}/children", get(list_children))
         .route("/workflows/{id}/continue-as-new", post(continue_as_new))
         .route("/workflows/{id}/state", get(get_workflow_state))
@@ -46,6 +54,14 @@ pub struct StartWorkflowRequest {
     /// workflows can also update it at runtime via
     /// `ctx:upsert_search_attributes(...)`.
     pub search_attributes: Option<serde_json::Value>,
+    /// What to do when `workflow_id` is already taken: `allow_duplicate`,
+    /// `allow_duplicate_failed_only`, `reject_duplicate` (default) or
+    /// `terminate_if_running`. Replaced runs stay readable under
+    /// `{workflow_id}@{run_id}`.
+    pub id_reuse_policy: Option<String>,
+    /// Idempotency key. Repeating a start with the same `request_id`
+    /// returns the run it created instead of starting another.
+    pub request_id: Option<String>,
 }
 
 fn default_queue() -> String {
@@ -65,6 +81,9 @@ pub struct WorkflowResponse {
     request_body = StartWorkflowRequest,
     responses(
         (status = 201, description = "Workflow started", body = WorkflowResponse),
+        (status = 200, description = "Run already started by this request_id", body = WorkflowResponse),
+        (status = 400, description = "Unknown id_reuse_policy"),
+        (status = 409, description = "Workflow id taken and the reuse policy forbids a new run"),
         (status = 500, description = "Internal error"),
     ),
 )]
@@ -72,22 +91,33 @@ pub async fn start_workflow<S: WorkflowStore>(
     State(state): State<Arc<WorkflowCtx<S>>>,
     Json(req): Json<StartWorkflowRequest>,
 ) -> Result<(axum::http::StatusCode, Json<WorkflowResponse>), AppError> {
+    let policy = match req.id_reuse_policy.as_deref() {
+        Some(p) => p.parse::<IdReusePolicy>().map_err(AppError::bad_request)?,
+        None => IdReusePolicy::default(),
+    };
     let input = req.input.map(|v| v.to_string());
     let namespace = req.namespace.as_deref().unwrap_or("main");
     let search_attributes = req.search_attributes.map(|v| v.to_string());
-    let wf = state
-        .start_workflow(
+    let (status, wf) = match state
+        .start_workflow_with(
             namespace,
             &req.workflow_type,
             &req.workflow_id,
             input.as_deref(),
             &req.task_queue,
             search_attributes.as_deref(),
+            policy,
+            req.request_id.as_deref(),
         )
-        .await?;
+        .await?
+    {
+        StartWorkflowResult::Started(wf) => (axum::http::StatusCode::CREATED, wf),
+        StartWorkflowResult::Existing(wf) => (axum::http::StatusCode::OK, wf),
+        StartWorkflowResult::Conflict(msg) => return Err(AppError::conflict(msg)),
+    };
 
     Ok((
-        axum::http::StatusCode::CREATED,
+        status,
         Json(WorkflowResponse {
             workflow_id: wf.id,
             run_id: wf.run_id,
@@ -107,6 +137,9 @@ pub struct ListQuery {
     /// contain every listed key at the given value. e.g.
     /// `?search_attrs=%7B%22env%22%3A%22prod%22%7D` for `{"env":"prod"}`.
     pub search_attrs: Option<String>,
+    /// Visibility query, e.g. `WorkflowType = 'deploy' AND search.progress > 0.5`.
+    /// Replaces the `status` / `type` / `search_attrs` filters.
+    pub query: Option<String>,
     #[serde(default = "default_limit")]
     pub limit: i64,
     #[serde(default)]
@@ -127,32 +160,48 @@ fn default_limit() -> i64 {
     params(
         ("status" = Option<String>, Query, description = "Filter by status"),
         ("type" = Option<String>, Query, description = "Filter by workflow type"),
+        ("query" = Option<String>, Query, description = "Visibility query (replaces status/type/search_attrs)"),
         ("limit" = Option<i64>, Query, description = "Max results (default 50)"),
         ("offset" = Option<i64>, Query, description = "Pagination offset"),
     ),
     responses(
         (status = 200, description = "List of workflows", body = Vec<WorkflowRecord>),
+        (status = 400, description = "Invalid query, or query combined with other filters"),
     ),
 )]
 pub async fn list_workflows<S: WorkflowStore>(
     State(state): State<Arc<WorkflowCtx<S>>>,
     Query(q): Query<ListQuery>,
 ) -> Result<Json<Vec<serde_json::Value>>, AppError> {
-    let status = q
-        .status
-        .as_deref()
-        .and_then(|s| s.parse::<WorkflowStatus>().ok());
-
-    let workflows = state
-        .list_workflows(
-            &q.namespace,
-            status,
-            q.workflow_type.as_deref(),
-            q.search_attrs.as_deref(),
-            q.limit,
-            q.offset,
-        )
-        .await?;
+    let workflows = if let Some(query) = q.query.as_deref() {
+        if q.status.is_some() || q.workflow_type.is_some() || q.search_attrs.is_some() {
+            return Err(AppError::bad_request(
+                "query cannot be combined with status, type or search_attrs".to_string(),
+            ));
+        }
+        let query = match state.parse_visibility_query(&q.namespace, query).await? {
+            ParsedQuery::Valid(query) => query,
+            ParsedQuery::Invalid(msg) => return Err(AppError::bad_request(msg)),
+        };
+        state
+            .query_workflows(&q.namespace, query.as_ref(), q.limit, q.offset)
+            .await?
+    } else {
+        let status = q
+            .status
+            .as_deref()
+            .and_then(|s| s.parse::<WorkflowStatus>().ok());
+        state
+            .list_workflows(
+                &q.namespace,
+                status,
+                q.workflow_type.as_deref(),
+                q.search_attrs.as_deref(),
+                q.limit,
+                q.offset,
+            )
+            .await?
+    };
 
     let json: Vec<serde_json::Value> = workflows
         .into_iter()
@@ -273,6 +322,168 @@ pub async fn send_signal<S: WorkflowStore>(
     Ok(axum::http::StatusCode::OK)
 }
 
+// ── Updates ─────────────────────────────────────────────────
+
+/// Default time `POST /workflows/{id}/update/{name}` blocks for a worker
+/// to resolve the update before answering 202 with the pending status.
+const DEFAULT_UPDATE_WAIT_SECS: f64 = 30.0;
+/// Upper bound on a caller-requested wait, so a typo can't pin a
+/// connection for hours.
+const MAX_UPDATE_WAIT_SECS: f64 = 300.0;
+
+#[derive(Deserialize, ToSchema, Default)]
+pub struct UpdateBody {
+    /// Arguments passed to the workflow's validator and update handler.
+    pub args: Option<serde_json::Value>,
+    /// Caller-chosen idempotency key. Retrying with the same id attaches
+    /// to the original update instead of applying it twice.
+    pub update_id: Option<String>,
+    /// How long to block for the outcome (default 30s, max 300s).
+    pub wait_secs: Option<f64>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct UpdateWaitQuery {
+    pub wait_secs: Option<f64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UpdateResponse {
+    pub update_id: String,
+    pub workflow_id: String,
+    pub name: String,
+    /// `REQUESTED`, `ACCEPTED`, `COMPLETED` or `REJECTED`.
+    pub status: String,
+    /// Value returned by the update handler.
+    pub result: Option<serde_json::Value>,
+    /// Rejection reason, or the error the handler raised after acceptance.
+    pub error: Option<String>,
+}
+
+impl UpdateResponse {
+    /// 200 once the handler ran, 422 when the update was rejected, 202
+    /// when the wait elapsed before a worker got to it.
+    fn into_reply(self) -> (axum::http::StatusCode, Json<UpdateResponse>) {
+        let status = match self.status.as_str() {
+            "COMPLETED" => axum::http::StatusCode::OK,
+            "REJECTED" => axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            _ => axum::http::StatusCode::ACCEPTED,
+        };
+        (status, Json(self))
+    }
+}
+
+impl From<WorkflowUpdate> for UpdateResponse {
+    fn from(u: WorkflowUpdate) -> Self {
+        Self {
+            update_id: u.id,
+            workflow_id: u.workflow_id,
+            name: u.name,
+            status: u.status,
+            result: u
+                .result
+                .as_deref()
+                .and_then(|s| serde_json::from_str(s).ok()),
+            error: u.error,
+        }
+    }
+}
+
+fn update_wait(wait_secs: Option<f64>, default: f64) -> std::time::Duration {
+    let secs = wait_secs
+        .filter(|s| s.is_finite())
+        .unwrap_or(default)
+        .clamp(0.0, MAX_UPDATE_WAIT_SECS);
+    std::time::Duration::from_secs_f64(secs)
+}
+
+/// Send an update to a running workflow and wait for its result.
+///
+/// Unlike a signal, an update is validated by the workflow
+/// (`ctx:register_update(name, validator, handler)`) and returns the
+/// handler's value. The request blocks until the workflow task that
+/// processes it completes, or `wait_secs` elapses.
+#[utoipa::path(
+    post, path = "/api/v1/engine/workflow/workflows/{id}/update/{name}",
+    tag = "workflows",
+    params(
+        ("id" = String, Path, description = "Workflow ID"),
+        ("name" = String, Path, description = "Update handler name"),
+    ),
+    request_body = UpdateBody,
+    responses(
+        (status = 200, description = "Update completed", body = UpdateResponse),
+        (status = 202, description = "Still pending when the wait elapsed", body = UpdateResponse),
+        (status = 404, description = "Workflow not found"),
+        (status = 409, description = "Workflow already finished"),
+        (status = 422, description = "Update rejected", body = UpdateResponse),
+    ),
+)]
+pub async fn update_workflow<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path((id, name)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<(axum::http::StatusCode, Json<UpdateResponse>), AppError> {
+    // Same leniency as cancel: an empty body (or the "[]" an empty Lua
+    // table encodes to) means "no args".
+    let body = if body.is_empty() {
+        UpdateBody::default()
+    } else {
+        serde_json::from_slice::<UpdateBody>(&body).unwrap_or_default()
+    };
+    let args = body.args.map(|v| v.to_string());
+    let update_id = body.update_id.as_deref().filter(|s| !s.trim().is_empty());
+    let update = match state
+        .request_update(&id, update_id, &name, args.as_deref())
+        .await?
+    {
+        RequestUpdateResult::Requested(update) => update,
+        RequestUpdateResult::NotFound => return Err(AppError::NotFound(format!("workflow {id}"))),
+        RequestUpdateResult::Terminal { status } => {
+            return Err(AppError::conflict(format!(
+                "workflow {id} is {status}; updates need a running workflow"
+            )));
+        }
+    };
+    let wait = update_wait(body.wait_secs, DEFAULT_UPDATE_WAIT_SECS);
+    let latest = state
+        .wait_for_update(&update.id, wait)
+        .await?
+        .unwrap_or(*update);
+    Ok(UpdateResponse::from(latest).into_reply())
+}
+
+/// Read an update's outcome. `?wait_secs=N` long-polls for up to N
+/// seconds — the follow-up for a `POST .../update/{name}` that
+/// answered 202.
+#[utoipa::path(
+    get, path = "/api/v1/engine/workflow/workflows/{id}/updates/{update_id}",
+    tag = "workflows",
+    params(
+        ("id" = String, Path, description = "Workflow ID"),
+        ("update_id" = String, Path, description = "Update ID"),
+        ("wait_secs" = Option<f64>, Query, description = "Long-poll for the outcome (default 0)"),
+    ),
+    responses(
+        (status = 200, description = "Update completed", body = UpdateResponse),
+        (status = 202, description = "Update still pending", body = UpdateResponse),
+        (status = 404, description = "Update not found"),
+        (status = 422, description = "Update rejected", body = UpdateResponse),
+    ),
+)]
+pub async fn get_update<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path((id, update_id)): Path<(String, String)>,
+    Query(q): Query<UpdateWaitQuery>,
+) -> Result<(axum::http::StatusCode, Json<UpdateResponse>), AppError> {
+    let update = state
+        .wait_for_update(&update_id, update_wait(q.wait_secs, 0.0))
+        .await?
+        .filter(|u| u.workflow_id == id)
+        .ok_or_else(|| AppError::NotFound(format!("update {update_id} on workflow {id}")))?;
+    Ok(UpdateResponse::from(update).into_reply())
+}
+
 #[derive(Deserialize, ToSchema, Default)]
 pub struct CancelBody {
     /// Why the workflow is being cancelled. Recorded in the
@@ -411,6 +622,111 @@ pub async fn retry_failed_activity<S: WorkflowStore>(
     }
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct ResetWorkflowBody {
+    /// Keep the source run's events up to and including this seq.
+    pub event_seq: Option<i32>,
+    /// Instead of `event_seq`: `last_workflow_task` or
+    /// `first_failed_activity`.
+    pub reset_type: Option<String>,
+    /// Why the run is being reset. Recorded on both runs.
+    pub reason: String,
+    /// Explicit id for the new run (default `<id>-reset-<unix millis>-<random hex>`).
+    pub workflow_id: Option<String>,
+    /// Carry signals the source received after the reset point over to
+    /// the new run (default true).
+    pub reapply_signals: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ResetWorkflowResponse {
+    /// The new run.
+    pub workflow_id: String,
+    pub run_id: String,
+    pub reset_from: String,
+    /// Last source event the new run kept.
+    pub reset_to_seq: i32,
+    pub events_copied: u64,
+    pub signals_reapplied: u64,
+    /// False when the source had already finished (e.g. FAILED).
+    pub source_terminated: bool,
+}
+
+/// Rewind a run to an earlier point in its history.
+///
+/// Starts a new run whose history is the source's events up to the reset
+/// point, terminates the source if it is still running, and records the
+/// reason on both. Give exactly one of `event_seq` or `reset_type`.
+#[utoipa::path(
+    post, path = "/api/v1/engine/workflow/workflows/{id}/reset",
+    tag = "workflows",
+    params(("id" = String, Path, description = "Workflow ID to reset")),
+    request_body = ResetWorkflowBody,
+    responses(
+        (status = 201, description = "New run started from the reset point", body = ResetWorkflowResponse),
+        (status = 400, description = "Invalid reset point"),
+        (status = 404, description = "Workflow not found"),
+        (status = 409, description = "Workflow cannot be reset"),
+    ),
+)]
+pub async fn reset_workflow<S: WorkflowStore>(
+    State(state): State<Arc<WorkflowCtx<S>>>,
+    Path(id): Path<String>,
+    Json(body): Json<ResetWorkflowBody>,
+) -> Result<(axum::http::StatusCode, Json<ResetWorkflowResponse>), AppError> {
+    let reason = body.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::bad_request("reason is required".to_string()));
+    }
+    let point = match (body.event_seq, body.reset_type.as_deref()) {
+        (Some(seq), None) => ResetPoint::EventSeq(seq),
+        (None, Some(kind)) => kind.parse().map_err(AppError::bad_request)?,
+        _ => {
+            return Err(AppError::bad_request(
+                "give exactly one of event_seq or reset_type".to_string(),
+            ));
+        }
+    };
+    let new_id = body.workflow_id.as_deref().filter(|s| !s.trim().is_empty());
+    match state
+        .reset_workflow(
+            &id,
+            point,
+            reason,
+            new_id,
+            body.reapply_signals.unwrap_or(true),
+        )
+        .await?
+    {
+        ResetResult::Reset {
+            reset,
+            reset_to_seq,
+            source_terminated,
+        } => Ok((
+            axum::http::StatusCode::CREATED,
+            Json(ResetWorkflowResponse {
+                workflow_id: reset.workflow.id,
+                run_id: reset.workflow.run_id,
+                reset_from: id,
+                reset_to_seq,
+                events_copied: reset.events_copied,
+                signals_reapplied: reset.signals_reapplied,
+                source_terminated,
+            }),
+        )),
+        ResetResult::NotFound => Err(AppError::NotFound(format!("workflow {id}"))),
+        ResetResult::Archived => Err(AppError::conflict(format!("workflow {id} is archived"))),
+        ResetResult::ChildWorkflow => Err(AppError::conflict(format!(
+            "child workflow {id} cannot be reset independently"
+        ))),
+        ResetResult::NoResetPoint(msg) => Err(AppError::bad_request(msg)),
+        ResetResult::AlreadyExists => Err(AppError::conflict(match new_id {
+            Some(new_id) => format!("workflow {new_id} already exists"),
+            None => format!("workflow {id} was already reset this second; retry"),
+        })),
+    }
+}
+
 #[utoipa::path(
     get, path = "/api/v1/engine/workflow/workflows/{id}/children",
     tag = "workflows",
@@ -569,11 +885,11 @@ impl std::fmt::Display for HttpError {
 impl std::error::Error for HttpError {}
 
 impl AppError {
-    fn bad_request(message: String) -> Self {
+    pub(crate) fn bad_request(message: String) -> Self {
         Self::http(axum::http::StatusCode::BAD_REQUEST, message)
     }
 
-    fn conflict(message: String) -> Self {
+    pub(crate) fn conflict(message: String) -> Self {
         Self::http(axum::http::StatusCode::CONFLICT, message)
     }
 
@@ -605,6 +921,13 @@ impl axum::response::IntoResponse for AppError {
                     )
                         .into_response();
                 }
+                if let Some(conflict) = e.downcast_ref::<crate::updates::UpdateConflict>() {
+                    return (
+                        axum::http::StatusCode::CONFLICT,
+                        Json(serde_json::json!({ "error": conflict.to_string() })),
+                    )
+                        .into_response();
+                }
                 tracing::error!("Internal error: {e}");
                 (
                     axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -0,0 +1,511 @@
+//! Batch jobs: apply one operation to every workflow matching a filter.
+//!
+//! `start_batch_job` records a `RUNNING` job row and spawns its runner on
+//! the accepting engine node. The runner first selects the targets (the
+//! full id list, so operations that change a workflow's status can't
+//! shift the pages under it), then applies the operation one workflow at
+//! a time at `max_per_second`, writing progress after each. Cancelling
+//! flips the row out of `RUNNING`; the runner notices on its next
+//! progress write and stops.
+//!
+//! Jobs are not resumed across engine restarts. A runner writes its row
+//! at least every `BATCH_HEARTBEAT`, even while waiting out its rate;
+//! [`run_orphan_sweep`], started with the engine, fails a `RUNNING` job
+//! whose row has gone `BATCH_JOB_STALE_SECS` without a write, since the
+//! node that ran it has stopped.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::ctx::{WorkflowCtx, timestamp_now};
+use crate::reset::{ResetPoint, ResetResult};
+use crate::store::WorkflowStore;
+use crate::types::*;
+use crate::visibility::ParsedQuery;
+
+/// Page size used while selecting a job's targets.
+const TARGET_PAGE_SIZE: i64 = 500;
+
+/// How often a runner writes its row while waiting for its next tick.
+const BATCH_HEARTBEAT: Duration = Duration::from_secs(30);
+
+/// A `RUNNING` job whose row hasn't been written for this long has lost
+/// its runner. Ten heartbeats, so a slow page of target selection or a
+/// busy database doesn't fail a live job.
+pub const BATCH_JOB_STALE_SECS: f64 = 300.0;
+
+/// How often [`run_orphan_sweep`] runs after its first pass at startup.
+const ORPHAN_SWEEP_SECS: u64 = 60;
+
+/// Default rate when the request doesn't set `max_per_second`.
+pub const DEFAULT_BATCH_RATE: f64 = 50.0;
+
+/// Accepted `max_per_second` range. Outside it the runner's tick
+/// interval (`1 / rate` seconds) would overflow or round to zero.
+pub const BATCH_RATE_RANGE: std::ops::RangeInclusive<f64> = 0.001..=10_000.0;
+
+/// Which workflows a batch job applies to. Same fields as the
+/// `GET /workflows` filters; at least one must be set, and `query`
+/// can't be combined with the others.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct BatchFilter {
+    pub status: Option<String>,
+    #[serde(rename = "type")]
+    pub workflow_type: Option<String>,
+    /// JSON object; matches workflows whose `search_attributes` contain
+    /// every listed key at the given value.
+    pub search_attrs: Option<serde_json::Value>,
+    /// Visibility query, e.g. `WorkflowType = 'deploy' AND search.progress < 1`.
+    pub query: Option<String>,
+}
+
+impl BatchFilter {
+    fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.workflow_type.is_none()
+            && self.search_attrs.is_none()
+            && self.query().is_none()
+    }
+
+    fn query(&self) -> Option<&str> {
+        self.query.as_deref().filter(|q| !q.trim().is_empty())
+    }
+}
+
+/// What a batch job does to each target.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Signal {
+        signal_name: String,
+        payload: Option<serde_json::Value>,
+    },
+    Cancel {
+        reason: Option<String>,
+    },
+    Terminate {
+        reason: Option<String>,
+    },
+    /// Reset each target; same points as `POST /workflows/{id}/reset`.
+    Reset {
+        event_seq: Option<i32>,
+        reset_type: Option<String>,
+        reason: String,
+        #[serde(default = "default_true")]
+        reapply_signals: bool,
+    },
+    UpsertSearchAttributes {
+        search_attributes: serde_json::Value,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl BatchOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Signal { .. } => "signal",
+            Self::Cancel { .. } => "cancel",
+            Self::Terminate { .. } => "terminate",
+            Self::Reset { .. } => "reset",
+            Self::UpsertSearchAttributes { .. } => "upsert_search_attributes",
+        }
+    }
+
+    /// Reject operations that would fail on every target.
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Signal { signal_name, .. } if signal_name.is_empty() => {
+                Err("signal_name is required".to_string())
+            }
+            Self::Reset {
+                event_seq,
+                reset_type,
+                reason,
+                ..
+            } => {
+                if reason.trim().is_empty() {
+                    return Err("reason is required".to_string());
+                }
+                match (event_seq, reset_type) {
+                    (Some(_), None) => Ok(()),
+                    (None, Some(kind)) => kind.parse::<ResetPoint>().map(|_| ()),
+                    _ => Err("give exactly one of event_seq or reset_type".to_string()),
+                }
+            }
+            Self::UpsertSearchAttributes { search_attributes }
+                if !search_attributes.is_object() =>
+            {
+                Err("search_attributes must be a JSON object".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Outcome of `start_batch_job`.
+#[derive(Clone, Debug)]
+pub enum StartBatchResult {
+    Started(Box<BatchJob>),
+    Invalid(String),
+}
+
+impl<S: WorkflowStore> WorkflowCtx<S> {
+    /// Record a batch job and start working through its targets in the
+    /// background. Returns as soon as the job row exists.
+    pub async fn start_batch_job(
+        self: &Arc<Self>,
+        namespace: &str,
+        filter: BatchFilter,
+        operation: BatchOperation,
+        max_per_second: Option<f64>,
+    ) -> Result<StartBatchResult> {
+        if filter.is_empty() {
+            return Ok(StartBatchResult::Invalid(
+                "filter must set at least one of status, type, search_attrs or query".to_string(),
+            ));
+        }
+        if let Some(query) = filter.query() {
+            if filter.status.is_some()
+                || filter.workflow_type.is_some()
+                || filter.search_attrs.is_some()
+            {
+                return Ok(StartBatchResult::Invalid(
+                    "query cannot be combined with status, type or search_attrs".to_string(),
+                ));
+            }
+            if let ParsedQuery::Invalid(msg) = self.parse_visibility_query(namespace, query).await?
+            {
+                return Ok(StartBatchResult::Invalid(msg));
+            }
+        }
+        if let Some(status) = &filter.status
+            && status.parse::<WorkflowStatus>().is_err()
+        {
+            return Ok(StartBatchResult::Invalid(format!(
+                "unknown workflow status {status:?}"
+            )));
+        }
+        if let Err(msg) = operation.validate() {
+            return Ok(StartBatchResult::Invalid(msg));
+        }
+        let rate = max_per_second.unwrap_or(DEFAULT_BATCH_RATE);
+        if !BATCH_RATE_RANGE.contains(&rate) {
+            return Ok(StartBatchResult::Invalid(format!(
+                "max_per_second must be between {} and {}",
+                BATCH_RATE_RANGE.start(),
+                BATCH_RATE_RANGE.end()
+            )));
+        }
+
+        let now = timestamp_now();
+        let job = BatchJob {
+            id: new_batch_id(),
+            namespace: namespace.to_string(),
+            operation: operation.name().to_string(),
+            filter: serde_json::to_string(&filter)?,
+            params: Some(serde_json::to_string(&operation)?),
+            max_per_second: rate,
+            status: "RUNNING".to_string(),
+            total: 0,
+            processed: 0,
+            succeeded: 0,
+            failed: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        };
+        self.store.create_batch_job(&job).await?;
+
+        let ctx = Arc::clone(self);
+        let id = job.id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ctx.run_batch_job(&id, &filter, &operation, rate).await {
+                warn!("Batch job {id} failed: {e}");
+                if let Err(e) = ctx
+                    .store
+                    .finish_batch_job(&id, "FAILED", Some(&e.to_string()))
+                    .await
+                {
+                    warn!("Batch job {id}: could not record failure: {e}");
+                }
+            }
+        });
+
+        Ok(StartBatchResult::Started(Box::new(job)))
+    }
+
+    pub async fn get_batch_job(&self, id: &str) -> Result<Option<BatchJob>> {
+        self.store.get_batch_job(id).await
+    }
+
+    pub async fn list_batch_jobs(
+        &self,
+        namespace: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BatchJob>> {
+        self.store.list_batch_jobs(namespace, limit, offset).await
+    }
+
+    /// Stop a running batch job. Workflows already processed stay
+    /// processed. Returns false if the job had already finished.
+    pub async fn cancel_batch_job(&self, id: &str) -> Result<bool> {
+        self.store.finish_batch_job(id, "CANCELLED", None).await
+    }
+
+    async fn run_batch_job(
+        &self,
+        id: &str,
+        filter: &BatchFilter,
+        operation: &BatchOperation,
+        max_per_second: f64,
+    ) -> Result<()> {
+        let Some(job) = self.store.get_batch_job(id).await? else {
+            return Ok(());
+        };
+        let targets = self.select_batch_targets(&job.namespace, filter).await?;
+        let total = targets.len() as i64;
+        if !self
+            .store
+            .update_batch_job_progress(id, total, 0, 0, 0, None)
+            .await?
+        {
+            return Ok(());
+        }
+        info!(
+            "Batch job {id}: {} on {total} workflow(s) at {max_per_second}/s",
+            operation.name()
+        );
+
+        let mut tick = tokio::time::interval(Duration::from_secs_f64(1.0 / max_per_second));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut heartbeat = tokio::time::interval(BATCH_HEARTBEAT);
+        heartbeat.reset();
+        let (mut succeeded, mut failed) = (0, 0);
+        for (i, workflow_id) in targets.iter().enumerate() {
+            // Slow rates wait minutes per item; keep the row fresh so the
+            // orphan sweep can tell this job from an abandoned one.
+            loop {
+                tokio::select! {
+                    _ = tick.tick() => break,
+                    _ = heartbeat.tick() => {
+                        if !self
+                            .store
+                            .update_batch_job_progress(id, total, i as i64, succeeded, failed, None)
+                            .await?
+                        {
+                            info!("Batch job {id} cancelled after {i} workflow(s)");
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+            let error = match self.apply_batch_operation(workflow_id, operation).await {
+                Ok(None) => {
+                    succeeded += 1;
+                    None
+                }
+                Ok(Some(msg)) => {
+                    failed += 1;
+                    Some(format!("{workflow_id}: {msg}"))
+                }
+                Err(e) => {
+                    failed += 1;
+                    Some(format!("{workflow_id}: {e}"))
+                }
+            };
+            if !self
+                .store
+                .update_batch_job_progress(
+                    id,
+                    total,
+                    i as i64 + 1,
+                    succeeded,
+                    failed,
+                    error.as_deref(),
+                )
+                .await?
+            {
+                info!("Batch job {id} cancelled after {} workflow(s)", i + 1);
+                return Ok(());
+            }
+        }
+
+        self.store.finish_batch_job(id, "COMPLETED", None).await?;
+        info!("Batch job {id} completed: {succeeded} succeeded, {failed} failed");
+        Ok(())
+    }
+
+    async fn select_batch_targets(
+        &self,
+        namespace: &str,
+        filter: &BatchFilter,
+    ) -> Result<Vec<String>> {
+        if let Some(query) = filter.query() {
+            let query = match self.parse_visibility_query(namespace, query).await? {
+                ParsedQuery::Valid(query) => query,
+                ParsedQuery::Invalid(msg) => anyhow::bail!("invalid query: {msg}"),
+            };
+            let mut ids = Vec::new();
+            loop {
+                let page = self
+                    .store
+                    .query_workflows(
+                        namespace,
+                        query.as_ref(),
+                        TARGET_PAGE_SIZE,
+                        ids.len() as i64,
+                    )
+                    .await?;
+                let done = (page.len() as i64) < TARGET_PAGE_SIZE;
+                ids.extend(page.into_iter().map(|w| w.id));
+                if done {
+                    return Ok(ids);
+                }
+            }
+        }
+        let status = filter
+            .status
+            .as_deref()
+            .and_then(|s| s.parse::<WorkflowStatus>().ok());
+        let search_attrs = filter.search_attrs.as_ref().map(|v| v.to_string());
+        let mut ids = Vec::new();
+        loop {
+            let page = self
+                .store
+                .list_workflows(
+                    namespace,
+                    status,
+                    filter.workflow_type.as_deref(),
+                    search_attrs.as_deref(),
+                    TARGET_PAGE_SIZE,
+                    ids.len() as i64,
+                )
+                .await?;
+            let done = (page.len() as i64) < TARGET_PAGE_SIZE;
+            ids.extend(page.into_iter().map(|w| w.id));
+            if done {
+                return Ok(ids);
+            }
+        }
+    }
+
+    /// Apply the operation to one workflow. `Ok(Some(msg))` is a
+    /// per-workflow refusal (already terminal, not found, ...), counted
+    /// as failed without stopping the job.
+    async fn apply_batch_operation(
+        &self,
+        workflow_id: &str,
+        operation: &BatchOperation,
+    ) -> Result<Option<String>> {
+        let terminal = || Some("workflow not found or already finished".to_string());
+        Ok(match operation {
+            BatchOperation::Signal {
+                signal_name,
+                payload,
+            } => {
+                let running = self
+                    .store
+                    .get_workflow(workflow_id)
+                    .await?
+                    .and_then(|wf| wf.status.parse::<WorkflowStatus>().ok())
+                    .is_some_and(|s| !s.is_terminal());
+                if !running {
+                    return Ok(terminal());
+                }
+                let payload = payload.as_ref().map(|p| p.to_string());
+                self.send_signal(workflow_id, signal_name, payload.as_deref())
+                    .await?;
+                None
+            }
+            BatchOperation::Cancel { reason } => {
+                if self.cancel_workflow(workflow_id, reason.as_deref()).await? {
+                    None
+                } else {
+                    terminal()
+                }
+            }
+            BatchOperation::Terminate { reason } => {
+                if self
+                    .terminate_workflow(workflow_id, reason.as_deref())
+                    .await?
+                {
+                    None
+                } else {
+                    terminal()
+                }
+            }
+            BatchOperation::Reset {
+                event_seq,
+                reset_type,
+                reason,
+                reapply_signals,
+            } => {
+                let point = match (event_seq, reset_type) {
+                    (Some(seq), _) => ResetPoint::EventSeq(*seq),
+                    (None, Some(kind)) => kind.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+                    (None, None) => anyhow::bail!("no reset point"),
+                };
+                match self
+                    .reset_workflow(workflow_id, point, reason, None, *reapply_signals)
+                    .await?
+                {
+                    ResetResult::Reset { .. } => None,
+                    ResetResult::NotFound => Some("workflow not found".to_string()),
+                    ResetResult::Archived => Some("workflow is archived".to_string()),
+                    ResetResult::ChildWorkflow => {
+                        Some("child workflows cannot be reset independently".to_string())
+                    }
+                    ResetResult::NoResetPoint(msg) => Some(msg),
+                    ResetResult::AlreadyExists => {
+                        Some("reset run id already exists; retry".to_string())
+                    }
+                }
+            }
+            BatchOperation::UpsertSearchAttributes { search_attributes } => {
+                if self.store.get_workflow(workflow_id).await?.is_none() {
+                    return Ok(Some("workflow not found".to_string()));
+                }
+                self.upsert_search_attributes(workflow_id, &search_attributes.to_string())
+                    .await?;
+                None
+            }
+        })
+    }
+}
+
+/// Fail `RUNNING` batch jobs whose runner is gone: once at startup (the
+/// first tick fires immediately), then every `ORPHAN_SWEEP_SECS` for
+/// jobs another node abandoned. Every node runs it; failing an already
+/// failed job is a no-op.
+pub async fn run_orphan_sweep<S: WorkflowStore>(store: Arc<S>) {
+    let mut tick = tokio::time::interval(Duration::from_secs(ORPHAN_SWEEP_SECS));
+    loop {
+        tick.tick().await;
+        match store
+            .fail_stale_batch_jobs(
+                timestamp_now() - BATCH_JOB_STALE_SECS,
+                "engine restarted: the node running this job stopped",
+            )
+            .await
+        {
+            Ok(0) => {}
+            Ok(n) => info!("Failed {n} orphaned batch job(s)"),
+            Err(e) => warn!("Batch orphan sweep error: {e}"),
+        }
+    }
+}
+
+/// Engine-assigned batch job id. Same shape as update ids: UUIDv7.
+fn new_batch_id() -> String {
+    format!("batch-{}", uuid::Uuid::now_v7())
+}
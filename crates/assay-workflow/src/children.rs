@@ -104,6 +104,8 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
             self.store
                 .update_workflow_status(workflow_id, WorkflowStatus::Completed, None, None)
                 .await?;
+            self.reject_pending_updates(workflow_id, "workflow continued as new")
+                .await?;
         }
 
         // Start a new run with the same type, namespace, and queue.
@@ -3,9 +3,11 @@ use std::sync::Arc;
 use tokio::task::JoinHandle;
 use tracing::info;
 
+use crate::batch;
 use crate::dispatch_recovery;
 use crate::events::{WorkflowBusEvent, WorkflowEventBus};
 use crate::health;
+use crate::metrics::WorkflowMetrics;
 use crate::scheduler;
 use crate::store::WorkflowStore;
 use crate::timers;
@@ -17,6 +19,7 @@ pub struct BackgroundTasks {
     _timer_poller: JoinHandle<()>,
     _health_monitor: JoinHandle<()>,
     _dispatch_recovery: JoinHandle<()>,
+    _batch_orphan_sweep: JoinHandle<()>,
     #[cfg(feature = "s3-archival")]
     _archival: Option<JoinHandle<()>>,
 }
@@ -35,6 +38,8 @@ pub struct WorkflowCtx<S: WorkflowStore> {
     /// `None` for tests / embedders without a dashboard — emit becomes
     /// a no-op.
     pub(crate) bus: Option<WorkflowEventBus>,
+    /// Hot-path instruments, rendered by the engine's `/metrics`.
+    pub(crate) metrics: Arc<WorkflowMetrics>,
     pub(crate) _bg: Arc<BackgroundTasks>,
     /// Version of the containing binary (e.g. the `assay-lua` CLI) — set
     /// by embedders so `/api/v1/engine/workflow/version` reflects the user-facing
@@ -50,6 +55,7 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         let _health_monitor = tokio::spawn(health::run_health_monitor(Arc::clone(&store)));
         let _dispatch_recovery =
             tokio::spawn(dispatch_recovery::run_dispatch_recovery(Arc::clone(&store)));
+        let _batch_orphan_sweep = tokio::spawn(batch::run_orphan_sweep(Arc::clone(&store)));
 
         #[cfg(feature = "s3-archival")]
         let _archival = crate::archival::ArchivalConfig::from_env()
@@ -60,11 +66,13 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         Self {
             store,
             bus: None,
+            metrics: Arc::default(),
             _bg: Arc::new(BackgroundTasks {
                 _scheduler,
                 _timer_poller,
                 _health_monitor,
                 _dispatch_recovery,
+                _batch_orphan_sweep,
                 #[cfg(feature = "s3-archival")]
                 _archival,
             }),
@@ -97,6 +105,11 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         self.bus.as_ref()
     }
 
+    /// Access the hot-path instruments (for the engine's `/metrics`).
+    pub fn metrics(&self) -> &WorkflowMetrics {
+        &self.metrics
+    }
+
     /// Emit a typed workflow event. No-op when no bus is wired (tests,
     /// embedders without a dashboard). Errors are logged, not returned —
     /// an emission failure must not fail the state-mutating method that
@@ -1,6 +1,7 @@
 pub mod activities;
 pub mod api;
 pub mod archival;
+pub mod batch;
 pub mod children;
 pub mod ctx;
 pub mod dispatch_recovery;
@@ -8,7 +9,9 @@ pub mod events;
 pub mod events_cleanup;
 pub mod health;
 pub mod lifecycle;
+pub mod metrics;
 pub mod namespaces;
+pub mod reset;
 pub mod scheduler;
 pub mod schedules;
 pub mod signals;
@@ -16,6 +19,8 @@ pub mod state;
 pub mod store;
 pub mod tasks;
 pub mod timers;
+pub mod updates;
+pub mod visibility;
 pub mod workers;
 
 // Types live in assay-domain; re-exported here so existing `crate::types::*`
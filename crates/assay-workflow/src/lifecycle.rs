@@ -20,8 +20,44 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         task_queue: &str,
         search_attributes: Option<&str>,
     ) -> Result<WorkflowRecord> {
+        match self
+            .start_workflow_with(
+                namespace,
+                workflow_type,
+                workflow_id,
+                input,
+                task_queue,
+                search_attributes,
+                IdReusePolicy::RejectDuplicate,
+                None,
+            )
+            .await?
+        {
+            StartWorkflowResult::Started(wf) | StartWorkflowResult::Existing(wf) => Ok(wf),
+            StartWorkflowResult::Conflict(msg) => Err(anyhow::anyhow!(msg)),
+        }
+    }
+
+    /// Start a workflow, applying `policy` when `workflow_id` is already
+    /// taken. A start whose `request_id` matches the one that created the
+    /// current run returns that run instead of starting another, so
+    /// retried requests are safe.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_workflow_with(
+        &self,
+        namespace: &str,
+        workflow_type: &str,
+        workflow_id: &str,
+        input: Option<&str>,
+        task_queue: &str,
+        search_attributes: Option<&str>,
+        policy: IdReusePolicy,
+        request_id: Option<&str>,
+    ) -> Result<StartWorkflowResult> {
         let now = timestamp_now();
-        let run_id = format!("run-{workflow_id}-{}", now as u64);
+        // Millisecond precision so a run that takes over the id of one
+        // started in the same second still gets a run id of its own.
+        let run_id = format!("run-{workflow_id}-{}", (now * 1000.0) as u64);
 
         // Auto-stamp the engine version that started this run into its
         // search attributes. Makes post-mortem triage concrete: "this
@@ -53,7 +89,41 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
             completed_at: None,
         };
 
-        self.store.create_workflow(&wf).await?;
+        let mut outcome = self
+            .store
+            .start_workflow_run(&wf, policy, request_id)
+            .await?;
+        if policy == IdReusePolicy::TerminateIfRunning
+            && let StartRunOutcome::Running(ref existing) = outcome
+        {
+            self.terminate_workflow(
+                &existing.id,
+                Some("terminated by a new start of the same workflow id"),
+            )
+            .await?;
+            outcome = self
+                .store
+                .start_workflow_run(&wf, IdReusePolicy::AllowDuplicate, request_id)
+                .await?;
+        }
+        match outcome {
+            StartRunOutcome::Started { .. } => {}
+            StartRunOutcome::Duplicate(existing) => {
+                return Ok(StartWorkflowResult::Existing(existing));
+            }
+            StartRunOutcome::Running(existing) => {
+                return Ok(StartWorkflowResult::Conflict(format!(
+                    "workflow {workflow_id} is already running (run {})",
+                    existing.run_id
+                )));
+            }
+            StartRunOutcome::Rejected(existing) => {
+                return Ok(StartWorkflowResult::Conflict(format!(
+                    "workflow {workflow_id} already exists with status {} and id_reuse_policy {policy} does not allow reusing it",
+                    existing.status
+                )));
+            }
+        }
 
         self.store
             .append_event(&WorkflowEvent {
@@ -81,7 +151,7 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         )
         .await;
 
-        Ok(wf)
+        Ok(StartWorkflowResult::Started(wf))
     }
 
     pub async fn get_workflow(&self, id: &str) -> Result<Option<WorkflowRecord>> {
@@ -205,6 +275,8 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
                         Some(reason.unwrap_or("terminated")),
                     )
                     .await?;
+                self.reject_pending_updates(id, "workflow was terminated")
+                    .await?;
 
                 // Live-refresh the dashboard — no more F5 after
                 // Terminate.
@@ -235,6 +307,8 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         self.store
             .update_workflow_status(workflow_id, WorkflowStatus::Cancelled, None, None)
             .await?;
+        self.reject_pending_updates(workflow_id, "workflow was cancelled")
+            .await?;
         let event_seq = self.store.get_event_count(workflow_id).await? as i32 + 1;
         self.store
             .append_event(&WorkflowEvent {
@@ -270,6 +344,8 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         self.store
             .update_workflow_status(workflow_id, WorkflowStatus::Completed, result, None)
             .await?;
+        self.reject_pending_updates(workflow_id, "workflow completed")
+            .await?;
         let event_seq = self.store.get_event_count(workflow_id).await? as i32 + 1;
         self.store
             .append_event(&WorkflowEvent {
@@ -312,6 +388,8 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         self.store
             .update_workflow_status(workflow_id, WorkflowStatus::Failed, None, Some(error))
             .await?;
+        self.reject_pending_updates(workflow_id, "workflow failed")
+            .await?;
         let event_seq = self.store.get_event_count(workflow_id).await? as i32 + 1;
         self.store
             .append_event(&WorkflowEvent {
@@ -0,0 +1,19 @@
+//! Instruments the workflow engine bumps on its hot paths. The engine
+//! renders them at `/metrics` next to the gauges it reads from the
+//! store at scrape time (queue depth, timer backlog, schedule lag).
+
+use assay_domain::metrics::{HistogramVec, LATENCY_BUCKETS};
+
+pub struct WorkflowMetrics {
+    /// Seconds from a workflow task becoming dispatchable — its latest
+    /// history event — to a worker claiming it, per task queue.
+    pub dispatch_latency: HistogramVec,
+}
+
+impl Default for WorkflowMetrics {
+    fn default() -> Self {
+        Self {
+            dispatch_latency: HistogramVec::new(&["queue"], LATENCY_BUCKETS),
+        }
+    }
+}
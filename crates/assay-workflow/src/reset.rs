@@ -0,0 +1,301 @@
+//! Workflow reset: fork a run from an earlier point of its history.
+//!
+//! `retry_failed_activity` re-runs one failed activity in place. Reset is
+//! the heavier tool for when the damage is further back: the engine
+//! starts a new run whose history is the source's events up to a chosen
+//! seq, terminates the source (if it is still running), and lets a worker
+//! replay the new run forward from there — typically with fixed code.
+
+use std::str::FromStr;
+
+use anyhow::Result;
+
+use crate::ctx::{WorkflowCtx, timestamp_now};
+use crate::events::WorkflowBusEvent;
+use crate::store::WorkflowStore;
+use crate::types::*;
+
+/// Event types written in response to a worker's commands. A workflow
+/// task's output is the run of these that follows the event which
+/// triggered the task; everything else (activity outcomes, timers,
+/// signals, child outcomes, cancel requests) is input.
+const WORKFLOW_TASK_OUTPUT_EVENTS: &[&str] = &[
+    "ActivityScheduled",
+    "TimerScheduled",
+    "SideEffectRecorded",
+    "ChildWorkflowStarted",
+    "WorkflowAwaitingSignal",
+    "VersionMarkerRecorded",
+    "UpdateAccepted",
+    "UpdateCompleted",
+    "UpdateRejected",
+    "WorkflowTaskFailed",
+    "WorkflowCompleted",
+    "WorkflowFailed",
+    "WorkflowCancelled",
+];
+
+/// Where to cut the source run's history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetPoint {
+    /// Keep events `1..=seq`.
+    EventSeq(i32),
+    /// Drop the output of the most recent workflow task so it runs again
+    /// against the same inputs.
+    LastWorkflowTask,
+    /// Cut just before the first activity that failed terminally was
+    /// scheduled, so the new run schedules it afresh.
+    FirstFailedActivity,
+}
+
+impl FromStr for ResetPoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "last_workflow_task" => Ok(Self::LastWorkflowTask),
+            "first_failed_activity" => Ok(Self::FirstFailedActivity),
+            other => Err(format!(
+                "unknown reset type {other:?} (expected last_workflow_task or first_failed_activity)"
+            )),
+        }
+    }
+}
+
+/// Outcome of `reset_workflow`.
+#[derive(Clone, Debug)]
+pub enum ResetResult {
+    Reset {
+        reset: Box<ResetWorkflow>,
+        reset_to_seq: i32,
+        /// Whether the source run was still running and got terminated.
+        source_terminated: bool,
+    },
+    NotFound,
+    Archived,
+    /// Children are driven by their parent's replay; resetting one on its
+    /// own would orphan the parent's wait.
+    ChildWorkflow,
+    /// The requested point doesn't exist in the source's history.
+    NoResetPoint(String),
+    AlreadyExists,
+}
+
+impl<S: WorkflowStore> WorkflowCtx<S> {
+    /// Reset `workflow_id` to `point`, recording `reason` on both runs.
+    /// The new run gets `new_workflow_id` if given, otherwise
+    /// `<source>-reset-<unix millis>-<random hex>`, so two resets in the
+    /// same instant still get distinct ids. With `reapply_signals`,
+    /// signals the source received after the reset point are carried
+    /// over.
+    pub async fn reset_workflow(
+        &self,
+        workflow_id: &str,
+        point: ResetPoint,
+        reason: &str,
+        new_workflow_id: Option<&str>,
+        reapply_signals: bool,
+    ) -> Result<ResetResult> {
+        let Some(source) = self.store.get_workflow(workflow_id).await? else {
+            return Ok(ResetResult::NotFound);
+        };
+        if source.archived_at.is_some() {
+            return Ok(ResetResult::Archived);
+        }
+        if source.parent_id.is_some() {
+            return Ok(ResetResult::ChildWorkflow);
+        }
+        let history = self.store.list_events(workflow_id).await?;
+        let reset_to_seq = match resolve_reset_point(&history, point) {
+            Ok(seq) => seq,
+            Err(msg) => return Ok(ResetResult::NoResetPoint(msg)),
+        };
+
+        let now = timestamp_now();
+        let new_id = match new_workflow_id {
+            Some(id) => id.to_string(),
+            None => format!(
+                "{}-reset-{}-{:08x}",
+                strip_reset_suffix(workflow_id),
+                (now * 1000.0) as u64,
+                rand::random::<u32>()
+            ),
+        };
+        let new_run = WorkflowRecord {
+            id: new_id.clone(),
+            namespace: source.namespace.clone(),
+            run_id: format!("run-{new_id}-{}", now as u64),
+            workflow_type: source.workflow_type.clone(),
+            task_queue: source.task_queue.clone(),
+            status: "PENDING".to_string(),
+            input: source.input.clone(),
+            result: None,
+            error: None,
+            parent_id: None,
+            claimed_by: None,
+            search_attributes: source.search_attributes.clone(),
+            archived_at: None,
+            archive_uri: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        };
+
+        let reset = match self
+            .store
+            .reset_workflow(workflow_id, &new_run, reset_to_seq, reapply_signals, reason)
+            .await?
+        {
+            ResetWorkflowResult::Reset(reset) => reset,
+            ResetWorkflowResult::NotFound => return Ok(ResetResult::NotFound),
+            ResetWorkflowResult::Archived => return Ok(ResetResult::Archived),
+            ResetWorkflowResult::AlreadyExists => return Ok(ResetResult::AlreadyExists),
+            ResetWorkflowResult::InvalidEventSeq { event_count } => {
+                return Ok(ResetResult::NoResetPoint(format!(
+                    "event seq {reset_to_seq} is outside the run's history (1..={event_count})"
+                )));
+            }
+        };
+
+        self.mark_and_emit_needs_dispatch(&new_id).await?;
+        self.emit(
+            &new_run.namespace,
+            WorkflowBusEvent::WorkflowStarted {
+                workflow_id: new_id.clone(),
+            },
+        )
+        .await;
+
+        let source_terminated = self
+            .terminate_workflow(workflow_id, Some(&format!("reset to {new_id}: {reason}")))
+            .await?;
+
+        Ok(ResetResult::Reset {
+            reset,
+            reset_to_seq,
+            source_terminated,
+        })
+    }
+}
+
+/// Map a `ResetPoint` to the last seq the new run keeps.
+fn resolve_reset_point(history: &[WorkflowEvent], point: ResetPoint) -> Result<i32, String> {
+    match point {
+        ResetPoint::EventSeq(seq) => Ok(seq),
+        ResetPoint::LastWorkflowTask => history
+            .iter()
+            .rev()
+            .find(|e| !WORKFLOW_TASK_OUTPUT_EVENTS.contains(&e.event_type.as_str()))
+            .map(|e| e.seq)
+            .ok_or_else(|| "run has no history to reset to".to_string()),
+        ResetPoint::FirstFailedActivity => {
+            let payload = |e: &WorkflowEvent| {
+                e.payload
+                    .as_deref()
+                    .and_then(|p| serde_json::from_str::<serde_json::Value>(p).ok())
+                    .unwrap_or_default()
+            };
+            let (failed_at, activity_seq) = history
+                .iter()
+                .find(|e| e.event_type == "ActivityFailed")
+                .map(|e| (e.seq, payload(e)["activity_seq"].clone()))
+                .ok_or_else(|| "run has no failed activity".to_string())?;
+            let scheduled_at = history
+                .iter()
+                .filter(|e| {
+                    e.seq < failed_at
+                        && e.event_type == "ActivityScheduled"
+                        && payload(e)["activity_seq"] == activity_seq
+                })
+                .map(|e| e.seq)
+                .next_back()
+                .unwrap_or(failed_at);
+            Ok((scheduled_at - 1).max(1))
+        }
+    }
+}
+
+/// Strip a trailing `-reset-<digits>` so resetting a reset run doesn't
+/// stack suffixes. Counterpart of `strip_continued_suffix`.
+fn strip_reset_suffix(id: &str) -> &str {
+    let Some(idx) = id.rfind("-reset-") else {
+        return id;
+    };
+    let rest = &id[idx + "-reset-".len()..];
+    let stamp = match rest.split_once('-') {
+        Some((stamp, nonce))
+            if nonce.len() == 8 && nonce.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            stamp
+        }
+        Some(_) => return id,
+        // Ids from before the random suffix: `-reset-<unix secs>`.
+        None => rest,
+    };
+    if !stamp.is_empty() && stamp.chars().all(|c| c.is_ascii_digit()) {
+        &id[..idx]
+    } else {
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(seq: i32, event_type: &str, payload: Option<serde_json::Value>) -> WorkflowEvent {
+        WorkflowEvent {
+            id: None,
+            workflow_id: "wf".to_string(),
+            seq,
+            event_type: event_type.to_string(),
+            payload: payload.map(|p| p.to_string()),
+            timestamp: 0.0,
+        }
+    }
+
+    fn history() -> Vec<WorkflowEvent> {
+        let act = |seq| Some(serde_json::json!({ "activity_seq": seq }));
+        vec![
+            event(1, "WorkflowStarted", None),
+            event(2, "ActivityScheduled", act(1)),
+            event(3, "ActivityCompleted", act(1)),
+            event(4, "ActivityScheduled", act(2)),
+            event(5, "ActivityFailed", act(2)),
+            event(6, "WorkflowFailed", None),
+        ]
+    }
+
+    #[test]
+    fn last_workflow_task_drops_the_trailing_task_output() {
+        let seq = resolve_reset_point(&history(), ResetPoint::LastWorkflowTask).unwrap();
+        assert_eq!(seq, 5);
+        let seq = resolve_reset_point(&history()[..4], ResetPoint::LastWorkflowTask).unwrap();
+        assert_eq!(seq, 3);
+    }
+
+    #[test]
+    fn first_failed_activity_cuts_before_its_schedule() {
+        let seq = resolve_reset_point(&history(), ResetPoint::FirstFailedActivity).unwrap();
+        assert_eq!(seq, 3);
+        assert!(resolve_reset_point(&history()[..3], ResetPoint::FirstFailedActivity).is_err());
+    }
+
+    #[test]
+    fn reset_suffix_does_not_stack() {
+        assert_eq!(
+            strip_reset_suffix("order-7-reset-1700000000123-0a1b2c3d"),
+            "order-7"
+        );
+        assert_eq!(strip_reset_suffix("order-7-reset-1700000000"), "order-7");
+        assert_eq!(
+            strip_reset_suffix("order-7-reset-1700000000-later"),
+            "order-7-reset-1700000000-later"
+        );
+        assert_eq!(
+            strip_reset_suffix("order-7-reset-later"),
+            "order-7-reset-later"
+        );
+        assert_eq!(strip_reset_suffix("order-7"), "order-7");
+    }
+}
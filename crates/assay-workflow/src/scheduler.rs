@@ -7,10 +7,23 @@ use tokio::time::{Duration, interval};
 use tracing::{debug, error, info, warn};
 
 use crate::store::WorkflowStore;
-use crate::types::{WorkflowEvent, WorkflowRecord, WorkflowSchedule};
+use crate::types::{
+    CatchupPolicy, OverlapPolicy, ScheduleAction, ScheduleBackfillResult, WorkflowEvent,
+    WorkflowRecord, WorkflowSchedule, WorkflowStatus,
+};
 
 const SCHEDULER_POLL_SECS: u64 = 15;
 
+/// How late a fire may start and still count as on time for the `skip`
+/// catch-up policy: a couple of poll ticks.
+const ON_TIME_SLACK_SECS: f64 = 2.0 * SCHEDULER_POLL_SECS as f64;
+
+/// Settled actions kept per schedule for `recent_actions`.
+pub(crate) const RECENT_ACTIONS_KEPT: i64 = 20;
+
+/// Most fires one catch-up pass or backfill acts on.
+pub const MAX_FIRES_PER_PASS: usize = 1000;
+
 /// Evaluates cron schedules and starts workflow runs when they're due.
 /// Runs as a background tokio task.
 pub async fn run_scheduler<S: WorkflowStore>(store: Arc<S>) {
@@ -64,82 +77,389 @@ async fn evaluate_namespace_schedules<S: WorkflowStore>(
     let schedules = store.list_schedules(namespace).await?;
 
     for sched in schedules {
-        if sched.paused {
-            continue;
+        if let Err(e) = evaluate_schedule(store, &sched, now).await {
+            error!("Schedule '{}': {e}", sched.name);
         }
+    }
 
-        // Creation seeds next_run_at (see `seed_next_run`), so a NULL here is
-        // either a row written before that or a cron that won't parse — the
-        // latter is rejected below. The seeding is not retroactive: a pre-seed
-        // row still fires once, and that fire gives it a cadence.
-        let is_due = match sched.next_run_at {
-            Some(next) => now >= next,
-            None => true,
-        };
-
-        if !is_due {
-            continue;
-        }
+    Ok(())
+}
 
-        // Parse cron to compute next run — interpreted in the schedule's
-        // configured timezone (defaults to UTC).
-        let next_run = match compute_next_run(&sched.cron_expr, &sched.timezone) {
-            Some(t) => t,
-            None => {
-                warn!(
-                    "Invalid cron expression or timezone for schedule '{}': expr={} tz={}",
-                    sched.name, sched.cron_expr, sched.timezone
-                );
-                continue;
-            }
-        };
-
-        // Check overlap policy — skip if previous run is still active
-        if sched.overlap_policy == "skip"
-            && let Some(ref last_wf_id) = sched.last_workflow_id
-            && let Some(wf) = store.get_workflow(last_wf_id).await?
-            && !crate::types::WorkflowStatus::from_str(&wf.status)
-                .map(|s| s.is_terminal())
-                .unwrap_or(true)
-        {
-            debug!(
-                "Schedule '{}': skipping — previous run {} still {}",
-                sched.name, last_wf_id, wf.status
-            );
-            // Still update next_run_at so we don't re-evaluate every tick
+async fn evaluate_schedule<S: WorkflowStore>(
+    store: &S,
+    sched: &WorkflowSchedule,
+    now: f64,
+) -> Result<()> {
+    // Buffered fires were already accepted, so they start once the run
+    // ahead of them finishes even while the schedule is paused.
+    let mut runs = start_buffered_runs(store, sched, now).await?;
+
+    // Creation seeds next_run_at (see `seed_next_run`), so a NULL here is
+    // either a row written before that or a cron that won't parse — the
+    // latter is rejected below. The seeding is not retroactive: a pre-seed
+    // row still fires once, and that fire gives it a cadence.
+    let is_due = match sched.next_run_at {
+        Some(next) => now >= next + jitter_for(sched, next),
+        None => true,
+    };
+
+    if sched.paused || !is_due {
+        if runs.last_workflow_id != sched.last_workflow_id {
             store
-                .update_schedule_last_run(namespace, &sched.name, now, next_run, last_wf_id)
+                .update_schedule_last_run(
+                    &sched.namespace,
+                    &sched.name,
+                    now,
+                    sched.next_run_at.unwrap_or(now),
+                    runs.last_workflow_id.as_deref(),
+                )
                 .await?;
-            continue;
         }
+        return Ok(());
+    }
 
-        let workflow_id = start_scheduled_run(store, namespace, &sched, now).await?;
+    // Parse cron to compute next run — interpreted in the schedule's
+    // configured timezone (defaults to UTC).
+    let Some(next_after_now) = next_fire_after(&sched.cron_expr, &sched.timezone, now) else {
+        warn!(
+            "Invalid cron expression or timezone for schedule '{}': expr={} tz={}",
+            sched.name, sched.cron_expr, sched.timezone
+        );
+        return Ok(());
+    };
 
+    let (due, next_run) = due_fires(sched, now, next_after_now);
+    let policy = CatchupPolicy::from_str(&sched.catchup_policy).unwrap_or(CatchupPolicy::One);
+    let run_from = match policy {
+        CatchupPolicy::All => 0,
+        CatchupPolicy::One => due.len().saturating_sub(1),
+        CatchupPolicy::Skip => match due.last() {
+            Some(&t) if now - (t + jitter_for(sched, t)) <= ON_TIME_SLACK_SECS => due.len() - 1,
+            _ => due.len(),
+        },
+    };
+
+    // Older misses would be pruned from the history straight away, so
+    // only the most recent ones are worth writing down.
+    let missed = &due[..run_from];
+    for &fire_time in &missed[missed.len().saturating_sub(RECENT_ACTIONS_KEPT as usize)..] {
+        let mut action = new_action(sched, fire_time, now, false);
+        action.action = "skipped".to_string();
+        action.reason = Some(format!("missed (catch-up policy {policy})"));
         store
-            .update_schedule_last_run(namespace, &sched.name, now, next_run, &workflow_id)
+            .record_schedule_action(&action, RECENT_ACTIONS_KEPT)
             .await?;
+    }
+
+    let overlap = OverlapPolicy::from_str(&sched.overlap_policy).unwrap_or(OverlapPolicy::Skip);
+    for &fire_time in &due[run_from..] {
+        let action = act_on_fire(store, sched, fire_time, overlap, false, now, &mut runs).await?;
+        match action.workflow_id {
+            Some(ref id) if action.action == "started" => info!(
+                "Schedule '{}': started workflow {id} (type: {})",
+                sched.name, sched.workflow_type
+            ),
+            _ => debug!(
+                "Schedule '{}': {} fire at {fire_time} ({})",
+                sched.name,
+                action.action,
+                action.reason.as_deref().unwrap_or("")
+            ),
+        }
+    }
 
+    store
+        .update_schedule_last_run(
+            &sched.namespace,
+            &sched.name,
+            now,
+            next_run,
+            runs.last_workflow_id.as_deref(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Fires owed at `now` — `next_run_at` plus every cron fire after it up to
+/// `now`, minus anything older than the catch-up window — and the
+/// `next_run_at` to store once they're handled.
+fn due_fires(sched: &WorkflowSchedule, now: f64, next_after_now: f64) -> (Vec<f64>, f64) {
+    let Some(first) = sched.next_run_at else {
+        return (vec![now], next_after_now);
+    };
+    let window_start = now - sched.catchup_window_secs as f64;
+    let mut fires = fires_between(
+        &sched.cron_expr,
+        &sched.timezone,
+        first.max(window_start),
+        now,
+        MAX_FIRES_PER_PASS,
+    )
+    .unwrap_or_default();
+    if first >= window_start {
+        fires.insert(0, first);
+    }
+    // A fire whose jittered start is still ahead becomes the next run.
+    let cut = fires
+        .iter()
+        .position(|&t| t + jitter_for(sched, t) > now)
+        .unwrap_or(fires.len());
+    let next_run = fires.get(cut).copied().unwrap_or(next_after_now);
+    fires.truncate(cut);
+    (fires, next_run)
+}
+
+/// Cron fires in `start_at..end_at` for a backfill, oldest first. Returns
+/// at most `MAX_FIRES_PER_PASS + 1` so callers can tell an over-large
+/// range apart; `None` if the cron expression or timezone won't parse.
+pub(crate) fn backfill_fire_times(
+    sched: &WorkflowSchedule,
+    start_at: f64,
+    end_at: f64,
+) -> Option<Vec<f64>> {
+    let schedule = Schedule::from_str(&sched.cron_expr).ok()?;
+    let tz = parse_timezone(&sched.timezone)?;
+    Some(
+        schedule
+            .after(&at(start_at - 1.0, tz)?)
+            .map(|t| t.timestamp() as f64)
+            .skip_while(|&t| t < start_at)
+            .take_while(|&t| t < end_at)
+            .take(MAX_FIRES_PER_PASS + 1)
+            .collect(),
+    )
+}
+
+/// Act on each backfill fire immediately, in order, under `overlap`.
+/// Backfill starts ignore jitter and the catch-up window.
+pub(crate) async fn backfill_schedule<S: WorkflowStore>(
+    store: &S,
+    sched: &WorkflowSchedule,
+    fires: &[f64],
+    overlap: OverlapPolicy,
+    now: f64,
+) -> Result<ScheduleBackfillResult> {
+    let mut runs = ScheduleRuns {
+        last_workflow_id: sched.last_workflow_id.clone(),
+        buffering: !store
+            .list_buffered_schedule_actions(&sched.namespace, &sched.name)
+            .await?
+            .is_empty(),
+    };
+    let mut result = ScheduleBackfillResult {
+        started: 0,
+        skipped: 0,
+        buffered: 0,
+        actions: Vec::with_capacity(fires.len()),
+    };
+    for &fire_time in fires {
+        let action = act_on_fire(store, sched, fire_time, overlap, true, now, &mut runs).await?;
+        match action.action.as_str() {
+            "started" => result.started += 1,
+            "buffered" => result.buffered += 1,
+            _ => result.skipped += 1,
+        }
+        result.actions.push(action);
+    }
+
+    if runs.last_workflow_id != sched.last_workflow_id
+        && let Some(next_run) = sched.next_run_at.or_else(|| seed_next_run(sched))
+    {
+        store
+            .update_schedule_last_run(
+                &sched.namespace,
+                &sched.name,
+                now,
+                next_run,
+                runs.last_workflow_id.as_deref(),
+            )
+            .await?;
+    }
+    Ok(result)
+}
+
+/// The schedule's run bookkeeping while a pass works through its fires.
+struct ScheduleRuns {
+    /// Latest run started for the schedule.
+    last_workflow_id: Option<String>,
+    /// Fires are waiting under the `queue` policy, so new ones must queue
+    /// behind them even if the latest run has finished.
+    buffering: bool,
+}
+
+/// Start buffered fires, oldest first, for as long as the run ahead of
+/// each has finished.
+async fn start_buffered_runs<S: WorkflowStore>(
+    store: &S,
+    sched: &WorkflowSchedule,
+    now: f64,
+) -> Result<ScheduleRuns> {
+    let buffered = store
+        .list_buffered_schedule_actions(&sched.namespace, &sched.name)
+        .await?;
+    let mut runs = ScheduleRuns {
+        last_workflow_id: sched.last_workflow_id.clone(),
+        buffering: !buffered.is_empty(),
+    };
+    for (i, action) in buffered.iter().enumerate() {
+        if run_is_active(store, runs.last_workflow_id.as_deref()).await? {
+            return Ok(runs);
+        }
+        let workflow_id = scheduled_workflow_id(sched, action.fire_time);
+        if store.get_workflow(&workflow_id).await?.is_none() {
+            start_scheduled_run(store, sched, &workflow_id, now).await?;
+        }
+        if let Some(id) = action.id {
+            store
+                .resolve_buffered_schedule_action(id, &workflow_id, now)
+                .await?;
+        }
         info!(
-            "Schedule '{}': started workflow {workflow_id} (type: {})",
-            sched.name, sched.workflow_type
+            "Schedule '{}': started buffered workflow {workflow_id}",
+            sched.name
         );
+        runs.last_workflow_id = Some(workflow_id);
+        runs.buffering = i + 1 < buffered.len();
     }
+    Ok(runs)
+}
 
+/// Decide what one fire does under `overlap`, do it, and record it.
+async fn act_on_fire<S: WorkflowStore>(
+    store: &S,
+    sched: &WorkflowSchedule,
+    fire_time: f64,
+    overlap: OverlapPolicy,
+    backfill: bool,
+    now: f64,
+    runs: &mut ScheduleRuns,
+) -> Result<ScheduleAction> {
+    let mut action = new_action(sched, fire_time, now, backfill);
+    let workflow_id = scheduled_workflow_id(sched, fire_time);
+
+    if store.get_workflow(&workflow_id).await?.is_some() {
+        action.action = "skipped".to_string();
+        action.reason = Some("a run for this fire already exists".to_string());
+        action.workflow_id = Some(workflow_id);
+    } else {
+        let previous = runs.last_workflow_id.clone();
+        let active = run_is_active(store, previous.as_deref()).await?;
+        match overlap {
+            OverlapPolicy::Skip if active => {
+                action.action = "skipped".to_string();
+                action.reason = Some(format!(
+                    "previous run {} still running",
+                    previous.unwrap_or_default()
+                ));
+            }
+            OverlapPolicy::Queue if active || runs.buffering => {
+                action.action = "buffered".to_string();
+                action.reason = previous.map(|id| format!("waiting for {id}"));
+                runs.buffering = true;
+            }
+            _ => {
+                if overlap == OverlapPolicy::CancelOld
+                    && active
+                    && let Some(ref previous) = previous
+                {
+                    request_cancel(store, previous, &workflow_id, now).await?;
+                }
+                start_scheduled_run(store, sched, &workflow_id, now).await?;
+                action.workflow_id = Some(workflow_id.clone());
+                runs.last_workflow_id = Some(workflow_id);
+            }
+        }
+    }
+
+    action.id = Some(
+        store
+            .record_schedule_action(&action, RECENT_ACTIONS_KEPT)
+            .await?,
+    );
+    Ok(action)
+}
+
+fn new_action(
+    sched: &WorkflowSchedule,
+    fire_time: f64,
+    now: f64,
+    backfill: bool,
+) -> ScheduleAction {
+    ScheduleAction {
+        id: None,
+        namespace: sched.namespace.clone(),
+        schedule_name: sched.name.clone(),
+        fire_time,
+        taken_at: now,
+        action: "started".to_string(),
+        workflow_id: None,
+        reason: None,
+        backfill,
+    }
+}
+
+async fn run_is_active<S: WorkflowStore>(store: &S, workflow_id: Option<&str>) -> Result<bool> {
+    let Some(id) = workflow_id else {
+        return Ok(false);
+    };
+    Ok(match store.get_workflow(id).await? {
+        Some(wf) => !WorkflowStatus::from_str(&wf.status)
+            .map(|s| s.is_terminal())
+            .unwrap_or(true),
+        None => false,
+    })
+}
+
+/// First phase of a cancel for `cancel_old`, as `WorkflowCtx::cancel_workflow`
+/// does it: the run's next replay sees the request and winds down. The
+/// scheduler only holds the store, so child workflows are left to the
+/// parent's own cancellation handling.
+async fn request_cancel<S: WorkflowStore>(
+    store: &S,
+    workflow_id: &str,
+    superseded_by: &str,
+    now: f64,
+) -> Result<()> {
+    store.cancel_pending_activities(workflow_id).await?;
+    store.cancel_pending_timers(workflow_id).await?;
+    let seq = store.get_event_count(workflow_id).await? as i32 + 1;
+    store
+        .append_event(&WorkflowEvent {
+            id: None,
+            workflow_id: workflow_id.to_string(),
+            seq,
+            event_type: "WorkflowCancelRequested".to_string(),
+            payload: Some(
+                serde_json::json!({
+                    "reason": format!("superseded by scheduled run {superseded_by}")
+                })
+                .to_string(),
+            ),
+            timestamp: now,
+        })
+        .await?;
+    store.mark_workflow_dispatchable(workflow_id).await?;
     Ok(())
 }
 
+/// Run ids derive from the nominal fire time, so a fire retried by a
+/// later pass (or backfilled twice) maps onto the same workflow.
+fn scheduled_workflow_id(sched: &WorkflowSchedule, fire_time: f64) -> String {
+    format!("{}-{}", sched.name, fire_time as u64)
+}
+
 async fn start_scheduled_run<S: WorkflowStore>(
     store: &S,
-    namespace: &str,
     sched: &WorkflowSchedule,
+    workflow_id: &str,
     now: f64,
-) -> Result<String> {
-    let workflow_id = format!("{}-{}", sched.name, now as u64);
+) -> Result<()> {
     let run_id = format!("run-{workflow_id}");
 
     let wf = WorkflowRecord {
-        id: workflow_id.clone(),
-        namespace: namespace.to_string(),
+        id: workflow_id.to_string(),
+        namespace: sched.namespace.clone(),
         run_id,
         workflow_type: sched.workflow_type.clone(),
         task_queue: sched.task_queue.clone(),
@@ -161,7 +481,7 @@ async fn start_scheduled_run<S: WorkflowStore>(
     store
         .append_event(&WorkflowEvent {
             id: None,
-            workflow_id: workflow_id.clone(),
+            workflow_id: workflow_id.to_string(),
             seq: 1,
             event_type: "WorkflowStarted".to_string(),
             payload: sched.input.clone(),
@@ -170,9 +490,32 @@ async fn start_scheduled_run<S: WorkflowStore>(
         .await?;
 
     // worker can pick them up. Without this they'd sit PENDING forever.
-    store.mark_workflow_dispatchable(&workflow_id).await?;
+    store.mark_workflow_dispatchable(workflow_id).await?;
+
+    Ok(())
+}
 
-    Ok(workflow_id)
+/// Deterministic start delay in `[0, jitter_secs]` for one fire. Derived
+/// from the schedule and fire time rather than drawn at random, so every
+/// pass (and every engine instance) agrees on when a fire is due.
+pub(crate) fn jitter_for(sched: &WorkflowSchedule, fire_time: f64) -> f64 {
+    if sched.jitter_secs <= 0 {
+        return 0.0;
+    }
+    // FNV-1a: stable across builds, unlike `DefaultHasher`.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in sched
+        .namespace
+        .bytes()
+        .chain([0])
+        .chain(sched.name.bytes())
+        .chain([0])
+        .chain((fire_time as i64).to_le_bytes())
+    {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    (hash % (sched.jitter_secs as u64 + 1)) as f64
 }
 
 /// `next_run_at` to persist for a schedule being created. The scheduler reads
@@ -185,16 +528,52 @@ pub(crate) fn seed_next_run(sched: &WorkflowSchedule) -> Option<f64> {
 }
 
 pub(crate) fn compute_next_run(cron_expr: &str, timezone: &str) -> Option<f64> {
+    next_fire_after(cron_expr, timezone, timestamp_now())
+}
+
+/// First cron fire strictly after `after`.
+pub(crate) fn next_fire_after(cron_expr: &str, timezone: &str, after: f64) -> Option<f64> {
     let schedule = Schedule::from_str(cron_expr).ok()?;
+    let tz = parse_timezone(timezone)?;
+    let next = schedule.after(&at(after, tz)?).next()?;
+    Some(next.timestamp() as f64)
+}
+
+/// Cron fires in `(after, until]`, oldest first. When there are more than
+/// `limit`, the most recent `limit` are kept.
+pub(crate) fn fires_between(
+    cron_expr: &str,
+    timezone: &str,
+    after: f64,
+    until: f64,
+    limit: usize,
+) -> Option<Vec<f64>> {
+    let schedule = Schedule::from_str(cron_expr).ok()?;
+    let tz = parse_timezone(timezone)?;
+    // Walk backwards from just past `until` so a capped walk keeps the
+    // newest fires.
+    let mut fires: Vec<f64> = schedule
+        .after(&at(until.floor() + 1.0, tz)?)
+        .rev()
+        .map(|t| t.timestamp() as f64)
+        .take_while(|&t| t > after)
+        .take(limit)
+        .collect();
+    fires.reverse();
+    Some(fires)
+}
+
+fn parse_timezone(timezone: &str) -> Option<chrono_tz::Tz> {
     // Empty string is treated as UTC so older schedules (pre-v0.11.3) keep
     // behaving identically even if they migrate in without the column set.
     if timezone.is_empty() || timezone.eq_ignore_ascii_case("UTC") {
-        let next = schedule.upcoming(chrono::Utc).next()?;
-        return Some(next.timestamp() as f64);
+        return Some(chrono_tz::UTC);
     }
-    let tz: chrono_tz::Tz = timezone.parse().ok()?;
-    let next = schedule.upcoming(tz).next()?;
-    Some(next.timestamp() as f64)
+    timezone.parse().ok()
+}
+
+fn at(timestamp: f64, tz: chrono_tz::Tz) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+    chrono::DateTime::from_timestamp(timestamp.floor() as i64, 0).map(|t| t.with_timezone(&tz))
 }
 
 fn timestamp_now() -> f64 {
@@ -222,7 +601,11 @@ mod tests {
             last_run_at: None,
             next_run_at: None,
             last_workflow_id: None,
+            catchup_window_secs: 365 * 24 * 3600,
+            catchup_policy: "one".to_string(),
+            jitter_secs: 0,
             created_at: 0.0,
+            recent_actions: Vec::new(),
         }
     }
 
@@ -276,4 +659,78 @@ mod tests {
     fn compute_next_run_invalid_cron_returns_none() {
         assert!(compute_next_run("not a cron", "UTC").is_none());
     }
+
+    /// 2026-01-01T00:00:00Z.
+    const JAN_1_2026: f64 = 1_767_225_600.0;
+
+    fn hourly() -> WorkflowSchedule {
+        let mut sched = schedule_in_timezone("UTC");
+        sched.cron_expr = "0 0 * * * *".to_string();
+        sched
+    }
+
+    #[test]
+    fn fires_between_is_exclusive_then_inclusive() {
+        let fires = fires_between("0 0 * * * *", "UTC", JAN_1_2026, JAN_1_2026 + 7200.0, 10)
+            .expect("valid cron");
+        assert_eq!(fires, vec![JAN_1_2026 + 3600.0, JAN_1_2026 + 7200.0]);
+    }
+
+    #[test]
+    fn fires_between_keeps_the_newest_when_capped() {
+        let fires = fires_between("0 0 * * * *", "UTC", JAN_1_2026, JAN_1_2026 + 36_000.0, 2)
+            .expect("valid cron");
+        assert_eq!(fires, vec![JAN_1_2026 + 32_400.0, JAN_1_2026 + 36_000.0]);
+    }
+
+    #[test]
+    fn backfill_fire_times_cover_a_half_open_range() {
+        let fires =
+            backfill_fire_times(&hourly(), JAN_1_2026, JAN_1_2026 + 7200.0).expect("valid cron");
+        assert_eq!(fires, vec![JAN_1_2026, JAN_1_2026 + 3600.0]);
+    }
+
+    #[test]
+    fn backfill_fire_times_stop_one_past_the_cap() {
+        let mut sched = hourly();
+        sched.cron_expr = "* * * * * *".to_string();
+        let fires =
+            backfill_fire_times(&sched, JAN_1_2026, JAN_1_2026 + 86_400.0).expect("valid cron");
+        assert_eq!(fires.len(), MAX_FIRES_PER_PASS + 1);
+    }
+
+    #[test]
+    fn jitter_is_deterministic_and_bounded() {
+        let mut sched = hourly();
+        assert_eq!(jitter_for(&sched, JAN_1_2026), 0.0, "no jitter by default");
+
+        sched.jitter_secs = 90;
+        let offsets: Vec<f64> = (0..50)
+            .map(|i| jitter_for(&sched, JAN_1_2026 + 3600.0 * i as f64))
+            .collect();
+        assert!(offsets.iter().all(|&j| (0.0..=90.0).contains(&j)));
+        assert!(
+            offsets.iter().any(|&j| j != offsets[0]),
+            "fires should not all share one offset"
+        );
+        assert_eq!(jitter_for(&sched, JAN_1_2026), offsets[0]);
+    }
+
+    #[test]
+    fn due_fires_defers_a_fire_whose_jitter_has_not_elapsed() {
+        let mut sched = hourly();
+        sched.jitter_secs = 3000;
+        sched.next_run_at = Some(JAN_1_2026);
+        let second = JAN_1_2026 + 3600.0;
+        assert!(
+            jitter_for(&sched, second) > 1.0,
+            "fixture needs a real delay"
+        );
+
+        // Both nominal fire times have passed, but only the first one's
+        // jittered start has (its delay is under an hour).
+        let (due, next_run) = due_fires(&sched, second + 1.0, JAN_1_2026 + 7200.0);
+        assert_eq!(due, vec![JAN_1_2026]);
+        assert_eq!(next_run, second, "the jittered fire stays next");
+    }
 }
@@ -2,7 +2,8 @@
 
 use anyhow::Result;
 
-use crate::ctx::WorkflowCtx;
+use crate::ctx::{WorkflowCtx, timestamp_now};
+use crate::scheduler;
 use crate::store::WorkflowStore;
 use crate::types::*;
 
@@ -12,7 +13,11 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
     }
 
     pub async fn list_schedules(&self, namespace: &str) -> Result<Vec<WorkflowSchedule>> {
-        self.store.list_schedules(namespace).await
+        let mut schedules = self.store.list_schedules(namespace).await?;
+        for sched in &mut schedules {
+            self.attach_recent_actions(sched).await?;
+        }
+        Ok(schedules)
     }
 
     pub async fn get_schedule(
@@ -20,7 +25,34 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
         namespace: &str,
         name: &str,
     ) -> Result<Option<WorkflowSchedule>> {
-        self.store.get_schedule(namespace, name).await
+        let Some(mut sched) = self.store.get_schedule(namespace, name).await? else {
+            return Ok(None);
+        };
+        self.attach_recent_actions(&mut sched).await?;
+        Ok(Some(sched))
+    }
+
+    /// Start (or skip, or buffer) one run per fire in `fires`, in order,
+    /// under `overlap`. `fires` come from `scheduler::backfill_fire_times`.
+    pub async fn backfill_schedule(
+        &self,
+        schedule: &WorkflowSchedule,
+        fires: &[f64],
+        overlap: OverlapPolicy,
+    ) -> Result<ScheduleBackfillResult> {
+        scheduler::backfill_schedule(&*self.store, schedule, fires, overlap, timestamp_now()).await
+    }
+
+    async fn attach_recent_actions(&self, sched: &mut WorkflowSchedule) -> Result<()> {
+        sched.recent_actions = self
+            .store
+            .list_schedule_actions(
+                &sched.namespace,
+                &sched.name,
+                scheduler::RECENT_ACTIONS_KEPT,
+            )
+            .await?;
+        Ok(())
     }
 
     pub async fn delete_schedule(&self, namespace: &str, name: &str) -> Result<bool> {
@@ -6,12 +6,28 @@
 
 pub mod postgres;
 pub mod sqlite;
+pub(crate) mod visibility;
 
 pub use assay_domain::store::WorkflowStore;
-pub use assay_domain::{NamespaceRecord, NamespaceStats, QueueStats};
+pub use assay_domain::{NamespaceRecord, NamespaceStats, QueueStats, TimerBacklog};
 
 use assay_domain::RetryFailedActivityResult;
 
+/// `queue`'s row in a per-queue stats map, zeroed on first use. Both
+/// backends fill it from separate per-queue counts.
+pub(crate) fn queue_stats_entry(
+    stats: &mut std::collections::BTreeMap<String, QueueStats>,
+    queue: String,
+) -> &mut QueueStats {
+    stats.entry(queue.clone()).or_insert(QueueStats {
+        queue,
+        pending_workflow_tasks: 0,
+        pending_activities: 0,
+        running_activities: 0,
+        workers: 0,
+    })
+}
+
 pub(crate) fn retry_denial(
     status: String,
     parent_id: Option<String>,
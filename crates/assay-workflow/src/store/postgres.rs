@@ -1,7 +1,8 @@
 use anyhow::Result;
 use sqlx::PgPool;
 
-use crate::store::{RetryEvent, WorkflowStore, retry_denial};
+use crate::store::visibility::{CompiledQuery, Dialect, compile, search_attribute_expr};
+use crate::store::{RetryEvent, WorkflowStore, queue_stats_entry, retry_denial};
 use crate::types::*;
 
 const RETRY_ACTIVITY_SELECT: &str = "SELECT id, workflow_id, seq, name, task_queue, input, status, result, error, attempt, max_attempts, initial_interval_secs, backoff_coefficient, start_to_close_secs, heartbeat_timeout_secs, claimed_by, scheduled_at, started_at, completed_at, last_heartbeat FROM workflow.activities WHERE workflow_id = $1 AND status = 'FAILED' ORDER BY seq DESC LIMIT 1 FOR UPDATE";
@@ -45,7 +46,9 @@ CREATE TABLE IF NOT EXISTS workflow.workflows (
     dispatch_last_heartbeat DOUBLE PRECISION,
     created_at      DOUBLE PRECISION NOT NULL,
     updated_at      DOUBLE PRECISION NOT NULL,
-    completed_at    DOUBLE PRECISION
+    completed_at    DOUBLE PRECISION,
+    -- Idempotency key of the start request that created this run.
+    request_id      TEXT
 );
 CREATE INDEX IF NOT EXISTS idx_wf_status_queue ON workflow.workflows(status, task_queue);
 CREATE INDEX IF NOT EXISTS idx_wf_namespace ON workflow.workflows(namespace);
@@ -82,6 +85,7 @@ CREATE TABLE IF NOT EXISTS workflow.activities (
     started_at      DOUBLE PRECISION,
     completed_at    DOUBLE PRECISION,
     last_heartbeat  DOUBLE PRECISION,
+    cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
     UNIQUE (workflow_id, seq)
 );
 CREATE INDEX IF NOT EXISTS idx_wf_act_pending ON workflow.activities(task_queue, status, scheduled_at);
@@ -106,6 +110,19 @@ CREATE TABLE IF NOT EXISTS workflow.signals (
 );
 CREATE INDEX IF NOT EXISTS idx_wf_signals_lookup ON workflow.signals(workflow_id, name, consumed);
 
+CREATE TABLE IF NOT EXISTS workflow.updates (
+    id              TEXT PRIMARY KEY,
+    workflow_id     TEXT NOT NULL REFERENCES workflow.workflows(id),
+    name            TEXT NOT NULL,
+    args            TEXT,
+    status          TEXT NOT NULL DEFAULT 'REQUESTED',
+    result          TEXT,
+    error           TEXT,
+    requested_at    DOUBLE PRECISION NOT NULL,
+    completed_at    DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS idx_wf_updates_pending ON workflow.updates(workflow_id, requested_at) WHERE status = 'REQUESTED';
+
 CREATE TABLE IF NOT EXISTS workflow.schedules (
     namespace       TEXT NOT NULL DEFAULT 'main',
     name            TEXT NOT NULL,
@@ -119,6 +136,49 @@ CREATE TABLE IF NOT EXISTS workflow.schedules (
     last_run_at     DOUBLE PRECISION,
     next_run_at     DOUBLE PRECISION,
     last_workflow_id TEXT,
+    catchup_window_secs BIGINT NOT NULL DEFAULT 31536000,
+    catchup_policy  TEXT NOT NULL DEFAULT 'one',
+    jitter_secs     BIGINT NOT NULL DEFAULT 0,
+    created_at      DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (namespace, name)
+);
+
+CREATE TABLE IF NOT EXISTS workflow.schedule_actions (
+    id              BIGSERIAL PRIMARY KEY,
+    namespace       TEXT NOT NULL,
+    schedule_name   TEXT NOT NULL,
+    fire_time       DOUBLE PRECISION NOT NULL,
+    taken_at        DOUBLE PRECISION NOT NULL,
+    action          TEXT NOT NULL,
+    workflow_id     TEXT,
+    reason          TEXT,
+    backfill        BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX IF NOT EXISTS idx_wf_schedule_actions ON workflow.schedule_actions(namespace, schedule_name, id);
+
+CREATE TABLE IF NOT EXISTS workflow.batch_jobs (
+    id              TEXT PRIMARY KEY,
+    namespace       TEXT NOT NULL DEFAULT 'main',
+    operation       TEXT NOT NULL,
+    filter          TEXT NOT NULL,
+    params          TEXT,
+    max_per_second  DOUBLE PRECISION NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'RUNNING',
+    total           BIGINT NOT NULL DEFAULT 0,
+    processed       BIGINT NOT NULL DEFAULT 0,
+    succeeded       BIGINT NOT NULL DEFAULT 0,
+    failed          BIGINT NOT NULL DEFAULT 0,
+    last_error      TEXT,
+    created_at      DOUBLE PRECISION NOT NULL,
+    updated_at      DOUBLE PRECISION NOT NULL,
+    completed_at    DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS idx_wf_batch_jobs_ns ON workflow.batch_jobs(namespace, created_at);
+
+CREATE TABLE IF NOT EXISTS workflow.search_attributes (
+    namespace       TEXT NOT NULL,
+    name            TEXT NOT NULL,
+    attr_type       TEXT NOT NULL,
     created_at      DOUBLE PRECISION NOT NULL,
     PRIMARY KEY (namespace, name)
 );
@@ -363,6 +423,25 @@ impl PostgresStore {
         )
         .execute(&self.pool)
         .await?;
+        // Columns added after the base schema, for databases created
+        // before them.
+        sqlx::query(
+            "ALTER TABLE workflow.activities
+             ADD COLUMN IF NOT EXISTS cancel_requested BOOLEAN NOT NULL DEFAULT FALSE",
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("ALTER TABLE workflow.workflows ADD COLUMN IF NOT EXISTS request_id TEXT")
+            .execute(&self.pool)
+            .await?;
+        sqlx::raw_sql(
+            "ALTER TABLE workflow.schedules
+                 ADD COLUMN IF NOT EXISTS catchup_window_secs BIGINT NOT NULL DEFAULT 31536000,
+                 ADD COLUMN IF NOT EXISTS catchup_policy TEXT NOT NULL DEFAULT 'one',
+                 ADD COLUMN IF NOT EXISTS jitter_secs BIGINT NOT NULL DEFAULT 0",
+        )
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
@@ -489,6 +568,137 @@ impl WorkflowStore for PostgresStore {
         Ok(())
     }
 
+    async fn start_workflow_run(
+        &self,
+        wf: &WorkflowRecord,
+        policy: IdReusePolicy,
+        request_id: Option<&str>,
+    ) -> Result<StartRunOutcome> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO workflow.workflows (id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at, request_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&wf.id)
+        .bind(&wf.namespace)
+        .bind(&wf.run_id)
+        .bind(&wf.workflow_type)
+        .bind(&wf.task_queue)
+        .bind(&wf.status)
+        .bind(&wf.input)
+        .bind(&wf.result)
+        .bind(&wf.error)
+        .bind(&wf.parent_id)
+        .bind(&wf.claimed_by)
+        .bind(&wf.search_attributes)
+        .bind(wf.archived_at)
+        .bind(&wf.archive_uri)
+        .bind(wf.created_at)
+        .bind(wf.updated_at)
+        .bind(wf.completed_at)
+        .bind(request_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 1 {
+            tx.commit().await?;
+            return Ok(StartRunOutcome::Started {
+                replaced_run_id: None,
+            });
+        }
+
+        let existing: WorkflowRecord = sqlx::query_as::<_, PgWorkflowRow>(
+            "SELECT id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at
+             FROM workflow.workflows WHERE id = $1 FOR UPDATE",
+        )
+        .bind(&wf.id)
+        .fetch_one(&mut *tx)
+        .await?
+        .into();
+        let (existing_request_id,): (Option<String>,) =
+            sqlx::query_as("SELECT request_id FROM workflow.workflows WHERE id = $1")
+                .bind(&wf.id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if request_id.is_some() && existing_request_id.as_deref() == request_id {
+            return Ok(StartRunOutcome::Duplicate(existing));
+        }
+        let status = existing
+            .status
+            .parse::<WorkflowStatus>()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if !status.is_terminal() {
+            return Ok(StartRunOutcome::Running(existing));
+        }
+        if !policy.allows_replacing(status) {
+            return Ok(StartRunOutcome::Rejected(existing));
+        }
+
+        // Keep the closed run, with everything that hangs off it, under
+        // its retired id; then hand the id to the new run.
+        let retired = retired_workflow_id(&wf.id, &existing.run_id);
+        sqlx::query(
+            "INSERT INTO workflow.workflows (id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, needs_dispatch, dispatch_claimed_by, dispatch_last_heartbeat, created_at, updated_at, completed_at, request_id)
+             SELECT $1, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, needs_dispatch, dispatch_claimed_by, dispatch_last_heartbeat, created_at, updated_at, completed_at, request_id
+             FROM workflow.workflows WHERE id = $2",
+        )
+        .bind(&retired)
+        .bind(&wf.id)
+        .execute(&mut *tx)
+        .await?;
+        for table in [
+            "events",
+            "activities",
+            "timers",
+            "signals",
+            "updates",
+            "snapshots",
+        ] {
+            sqlx::query(&format!(
+                "UPDATE workflow.{table} SET workflow_id = $1 WHERE workflow_id = $2"
+            ))
+            .bind(&retired)
+            .bind(&wf.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("UPDATE workflow.workflows SET parent_id = $1 WHERE parent_id = $2")
+            .bind(&retired)
+            .bind(&wf.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE workflow.workflows
+             SET run_id = $1, workflow_type = $2, task_queue = $3, status = $4, input = $5, result = $6, error = $7, parent_id = $8, claimed_by = $9, search_attributes = $10, archived_at = $11, archive_uri = $12, created_at = $13, updated_at = $14, completed_at = $15, request_id = $16,
+                 needs_dispatch = FALSE, dispatch_claimed_by = NULL, dispatch_last_heartbeat = NULL
+             WHERE id = $17",
+        )
+        .bind(&wf.run_id)
+        .bind(&wf.workflow_type)
+        .bind(&wf.task_queue)
+        .bind(&wf.status)
+        .bind(&wf.input)
+        .bind(&wf.result)
+        .bind(&wf.error)
+        .bind(&wf.parent_id)
+        .bind(&wf.claimed_by)
+        .bind(&wf.search_attributes)
+        .bind(wf.archived_at)
+        .bind(&wf.archive_uri)
+        .bind(wf.created_at)
+        .bind(wf.updated_at)
+        .bind(wf.completed_at)
+        .bind(request_id)
+        .bind(&wf.id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(StartRunOutcome::Started {
+            replaced_run_id: Some(existing.run_id),
+        })
+    }
+
     async fn get_workflow(&self, id: &str) -> Result<Option<WorkflowRecord>> {
         let row = sqlx::query_as::<_, PgWorkflowRow>(
             "SELECT id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at FROM workflow.workflows WHERE id = $1",
@@ -556,6 +766,105 @@ impl WorkflowStore for PostgresStore {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    async fn query_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WorkflowRecord>> {
+        let compiled = compile(query, Dialect::Postgres, 2);
+        let sql = format!(
+            "SELECT id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at
+             FROM workflow.workflows
+             WHERE namespace = $1{}
+             ORDER BY created_at DESC LIMIT ${} OFFSET ${}",
+            compiled.and_clause(),
+            compiled.next_param,
+            compiled.next_param + 1
+        );
+        let q = sqlx::query_as::<_, PgWorkflowRow>(&sql).bind(namespace);
+        let rows = bind_query_values(q, &compiled)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> Result<i64> {
+        let compiled = compile(query, Dialect::Postgres, 2);
+        let sql = format!(
+            "SELECT COUNT(*) FROM workflow.workflows WHERE namespace = $1{}",
+            compiled.and_clause()
+        );
+        let q = sqlx::query_as::<_, (i64,)>(&sql).bind(namespace);
+        let (count,) = bind_query_values(q, &compiled)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn count_workflows_by_status(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> Result<Vec<WorkflowStatusCount>> {
+        let compiled = compile(query, Dialect::Postgres, 2);
+        let sql = format!(
+            "SELECT status, COUNT(*) FROM workflow.workflows WHERE namespace = $1{} GROUP BY status ORDER BY status",
+            compiled.and_clause()
+        );
+        let q = sqlx::query_as::<_, (String, i64)>(&sql).bind(namespace);
+        let rows = bind_query_values(q, &compiled)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(status, count)| WorkflowStatusCount { status, count })
+            .collect())
+    }
+
+    async fn create_search_attribute(&self, def: &SearchAttributeDef) -> Result<bool> {
+        let res = sqlx::query(
+            "INSERT INTO workflow.search_attributes (namespace, name, attr_type, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        )
+        .bind(&def.namespace)
+        .bind(&def.name)
+        .bind(def.attr_type.to_string())
+        .bind(def.created_at)
+        .execute(&self.pool)
+        .await?;
+        if res.rows_affected() == 0 {
+            return Ok(false);
+        }
+        // One index per attribute name, shared across namespaces. Best
+        // effort: queries still work (unindexed) if this fails.
+        let index = format!(
+            "CREATE INDEX IF NOT EXISTS idx_wf_sa_{name} ON workflow.workflows ({expr})",
+            name = def.name,
+            expr = search_attribute_expr(Dialect::Postgres, &def.name),
+        );
+        if let Err(e) = sqlx::query(&index).execute(&self.pool).await {
+            tracing::warn!("failed to index search attribute {}: {e}", def.name);
+        }
+        Ok(true)
+    }
+
+    async fn list_search_attributes(&self, namespace: &str) -> Result<Vec<SearchAttributeDef>> {
+        let rows = sqlx::query_as::<_, PgSearchAttributeRow>(
+            "SELECT namespace, name, attr_type, created_at FROM workflow.search_attributes WHERE namespace = $1 ORDER BY name",
+        )
+        .bind(namespace)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     async fn update_workflow_status(
         &self,
         id: &str,
@@ -932,6 +1241,131 @@ impl WorkflowStore for PostgresStore {
         )))
     }
 
+    async fn reset_workflow(
+        &self,
+        source_id: &str,
+        new_run: &WorkflowRecord,
+        reset_to_seq: i32,
+        reapply_signals: bool,
+        reason: &str,
+    ) -> Result<ResetWorkflowResult> {
+        let mut tx = self.pool.begin().await?;
+        let source: Option<(String, Option<f64>)> = sqlx::query_as(
+            "SELECT run_id, archived_at FROM workflow.workflows WHERE id = $1 FOR UPDATE",
+        )
+        .bind(source_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((source_run_id, archived_at)) = source else {
+            return Ok(ResetWorkflowResult::NotFound);
+        };
+        if archived_at.is_some() {
+            return Ok(ResetWorkflowResult::Archived);
+        }
+        let event_count: (i32,) = sqlx::query_as(
+            "SELECT COALESCE(MAX(seq), 0) FROM workflow.events WHERE workflow_id = $1",
+        )
+        .bind(source_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if reset_to_seq < 1 || reset_to_seq > event_count.0 {
+            return Ok(ResetWorkflowResult::InvalidEventSeq {
+                event_count: event_count.0,
+            });
+        }
+        let inserted = sqlx::query(
+            "INSERT INTO workflow.workflows (id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&new_run.id)
+        .bind(&new_run.namespace)
+        .bind(&new_run.run_id)
+        .bind(&new_run.workflow_type)
+        .bind(&new_run.task_queue)
+        .bind(&new_run.status)
+        .bind(&new_run.input)
+        .bind(&new_run.result)
+        .bind(&new_run.error)
+        .bind(&new_run.parent_id)
+        .bind(&new_run.claimed_by)
+        .bind(&new_run.search_attributes)
+        .bind(new_run.archived_at)
+        .bind(&new_run.archive_uri)
+        .bind(new_run.created_at)
+        .bind(new_run.updated_at)
+        .bind(new_run.completed_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Ok(ResetWorkflowResult::AlreadyExists);
+        }
+
+        let events_copied = sqlx::query(
+            "INSERT INTO workflow.events (workflow_id, seq, event_type, payload, timestamp)
+             SELECT $1, seq, event_type, payload, timestamp FROM workflow.events
+             WHERE workflow_id = $2 AND seq <= $3",
+        )
+        .bind(&new_run.id)
+        .bind(source_id)
+        .bind(reset_to_seq)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let signals_reapplied = if reapply_signals {
+            sqlx::query(
+                "INSERT INTO workflow.events (workflow_id, seq, event_type, payload, timestamp)
+                 SELECT $1, $2 + ROW_NUMBER() OVER (ORDER BY seq), event_type, payload, timestamp
+                 FROM workflow.events
+                 WHERE workflow_id = $3 AND seq > $2 AND event_type = 'SignalReceived'",
+            )
+            .bind(&new_run.id)
+            .bind(reset_to_seq)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+        } else {
+            0
+        };
+
+        let payload = serde_json::json!({
+            "source_workflow_id": source_id,
+            "source_run_id": source_run_id,
+            "reset_workflow_id": new_run.id,
+            "reset_run_id": new_run.run_id,
+            "reset_to_seq": reset_to_seq,
+            "signals_reapplied": signals_reapplied,
+            "reason": reason,
+        })
+        .to_string();
+        for (workflow_id, seq) in [
+            (
+                new_run.id.as_str(),
+                reset_to_seq + signals_reapplied as i32 + 1,
+            ),
+            (source_id, event_count.0 + 1),
+        ] {
+            sqlx::query(
+                "INSERT INTO workflow.events (workflow_id, seq, event_type, payload, timestamp)
+                 VALUES ($1, $2, 'WorkflowReset', $3, $4)",
+            )
+            .bind(workflow_id)
+            .bind(seq)
+            .bind(&payload)
+            .bind(new_run.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(ResetWorkflowResult::Reset(Box::new(ResetWorkflow {
+            workflow: new_run.clone(),
+            events_copied,
+            signals_reapplied,
+        })))
+    }
+
     async fn complete_activity(
         &self,
         id: i64,
@@ -953,13 +1387,80 @@ impl WorkflowStore for PostgresStore {
         Ok(())
     }
 
-    async fn heartbeat_activity(&self, id: i64, _details: Option<&str>) -> Result<()> {
-        sqlx::query("UPDATE workflow.activities SET last_heartbeat = $1 WHERE id = $2")
-            .bind(timestamp_now())
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
-        Ok(())
+    async fn heartbeat_activity(&self, id: i64, _details: Option<&str>) -> Result<bool> {
+        let stop: Option<(bool,)> = sqlx::query_as(
+            "UPDATE workflow.activities a SET last_heartbeat = $1
+             FROM workflow.workflows w
+             WHERE a.id = $2 AND w.id = a.workflow_id
+             RETURNING a.cancel_requested
+                 OR w.status IN ('COMPLETED', 'FAILED', 'CANCELLED', 'TIMED_OUT')",
+        )
+        .bind(timestamp_now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(stop.is_some_and(|(stop,)| stop))
+    }
+
+    async fn request_activity_cancel(
+        &self,
+        workflow_id: &str,
+        seq: i32,
+    ) -> Result<CancelActivityResult> {
+        let mut tx = self.pool.begin().await?;
+        let status: Option<(String, bool)> = sqlx::query_as(
+            "SELECT status, cancel_requested FROM workflow.activities
+             WHERE workflow_id = $1 AND seq = $2
+             FOR UPDATE",
+        )
+        .bind(workflow_id)
+        .bind(seq)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let result = match status
+            .as_ref()
+            .map(|(s, requested)| (s.as_str(), *requested))
+        {
+            None => CancelActivityResult::NotFound,
+            Some(("PENDING", _)) => {
+                sqlx::query(
+                    "UPDATE workflow.activities SET status = 'CANCELLED', completed_at = $1
+                     WHERE workflow_id = $2 AND seq = $3",
+                )
+                .bind(timestamp_now())
+                .bind(workflow_id)
+                .bind(seq)
+                .execute(&mut *tx)
+                .await?;
+                CancelActivityResult::Cancelled
+            }
+            Some(("RUNNING", false)) => {
+                sqlx::query(
+                    "UPDATE workflow.activities SET cancel_requested = TRUE
+                     WHERE workflow_id = $1 AND seq = $2",
+                )
+                .bind(workflow_id)
+                .bind(seq)
+                .execute(&mut *tx)
+                .await?;
+                CancelActivityResult::CancelRequested
+            }
+            Some(_) => CancelActivityResult::Unchanged,
+        };
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    async fn acknowledge_activity_cancel(&self, id: i64) -> Result<bool> {
+        let res = sqlx::query(
+            "UPDATE workflow.activities SET status = 'CANCELLED', completed_at = $1
+             WHERE id = $2 AND status = 'RUNNING' AND cancel_requested",
+        )
+        .bind(timestamp_now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
     }
 
     async fn get_timed_out_activities(&self, now: f64) -> Result<Vec<WorkflowActivity>> {
@@ -1016,6 +1517,13 @@ impl WorkflowStore for PostgresStore {
         .bind(workflow_id)
         .execute(&self.pool)
         .await?;
+        sqlx::query(
+            "UPDATE workflow.activities SET cancel_requested = TRUE
+             WHERE workflow_id = $1 AND status = 'RUNNING'",
+        )
+        .bind(workflow_id)
+        .execute(&self.pool)
+        .await?;
         Ok(res.rows_affected())
     }
 
@@ -1058,6 +1566,17 @@ impl WorkflowStore for PostgresStore {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    async fn get_timer_backlog(&self, now: f64) -> Result<TimerBacklog> {
+        let (pending, overdue) = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT COUNT(*), COUNT(*) FILTER (WHERE fire_at <= $1)
+             FROM workflow.timers WHERE fired = FALSE",
+        )
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(TimerBacklog { pending, overdue })
+    }
+
     // ── Signals ─────────────────────────────────────────────
 
     async fn send_signal(&self, sig: &WorkflowSignal) -> Result<i64> {
@@ -1086,12 +1605,196 @@ impl WorkflowStore for PostgresStore {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    // ── Updates ─────────────────────────────────────────────
+
+    async fn create_update(&self, update: &WorkflowUpdate) -> Result<bool> {
+        let res = sqlx::query(
+            "INSERT INTO workflow.updates (id, workflow_id, name, args, status, result, error, requested_at, completed_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&update.id)
+        .bind(&update.workflow_id)
+        .bind(&update.name)
+        .bind(&update.args)
+        .bind(&update.status)
+        .bind(&update.result)
+        .bind(&update.error)
+        .bind(update.requested_at)
+        .bind(update.completed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn get_update(&self, update_id: &str) -> Result<Option<WorkflowUpdate>> {
+        let row = sqlx::query_as::<_, PgUpdateRow>(
+            "SELECT id, workflow_id, name, args, status, result, error, requested_at, completed_at FROM workflow.updates WHERE id = $1",
+        )
+        .bind(update_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn list_pending_updates(&self, workflow_id: &str) -> Result<Vec<WorkflowUpdate>> {
+        let rows = sqlx::query_as::<_, PgUpdateRow>(
+            "SELECT id, workflow_id, name, args, status, result, error, requested_at, completed_at FROM workflow.updates
+             WHERE workflow_id = $1 AND status = 'REQUESTED' ORDER BY requested_at ASC, id ASC",
+        )
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn transition_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        from_status: &str,
+        to_status: &str,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<bool> {
+        let completed_at = (to_status != "ACCEPTED").then(timestamp_now);
+        let res = sqlx::query(
+            "UPDATE workflow.updates SET status = $1, result = $2, error = $3, completed_at = $4
+             WHERE id = $5 AND workflow_id = $6 AND status = $7",
+        )
+        .bind(to_status)
+        .bind(result)
+        .bind(error)
+        .bind(completed_at)
+        .bind(update_id)
+        .bind(workflow_id)
+        .bind(from_status)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // ── Batch jobs ──────────────────────────────────────────
+
+    async fn create_batch_job(&self, job: &BatchJob) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO workflow.batch_jobs (id, namespace, operation, filter, params, max_per_second, status, total, processed, succeeded, failed, last_error, created_at, updated_at, completed_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+        )
+        .bind(&job.id)
+        .bind(&job.namespace)
+        .bind(&job.operation)
+        .bind(&job.filter)
+        .bind(&job.params)
+        .bind(job.max_per_second)
+        .bind(&job.status)
+        .bind(job.total)
+        .bind(job.processed)
+        .bind(job.succeeded)
+        .bind(job.failed)
+        .bind(&job.last_error)
+        .bind(job.created_at)
+        .bind(job.updated_at)
+        .bind(job.completed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_batch_job(&self, id: &str) -> Result<Option<BatchJob>> {
+        let row = sqlx::query_as::<_, PgBatchJobRow>(
+            "SELECT id, namespace, operation, filter, params, max_per_second, status, total, processed, succeeded, failed, last_error, created_at, updated_at, completed_at FROM workflow.batch_jobs WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn list_batch_jobs(
+        &self,
+        namespace: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BatchJob>> {
+        let rows = sqlx::query_as::<_, PgBatchJobRow>(
+            "SELECT id, namespace, operation, filter, params, max_per_second, status, total, processed, succeeded, failed, last_error, created_at, updated_at, completed_at FROM workflow.batch_jobs
+             WHERE namespace = $1 ORDER BY created_at DESC, id DESC LIMIT $2 OFFSET $3",
+        )
+        .bind(namespace)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn update_batch_job_progress(
+        &self,
+        id: &str,
+        total: i64,
+        processed: i64,
+        succeeded: i64,
+        failed: i64,
+        last_error: Option<&str>,
+    ) -> Result<bool> {
+        let status: Option<String> = sqlx::query_scalar(
+            "UPDATE workflow.batch_jobs
+             SET total = $1, processed = $2, succeeded = $3, failed = $4,
+                 last_error = COALESCE($5, last_error), updated_at = $6
+             WHERE id = $7
+             RETURNING status",
+        )
+        .bind(total)
+        .bind(processed)
+        .bind(succeeded)
+        .bind(failed)
+        .bind(last_error)
+        .bind(timestamp_now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(status.as_deref() == Some("RUNNING"))
+    }
+
+    async fn finish_batch_job(&self, id: &str, status: &str, error: Option<&str>) -> Result<bool> {
+        let now = timestamp_now();
+        let res = sqlx::query(
+            "UPDATE workflow.batch_jobs
+             SET status = $1, last_error = COALESCE($2, last_error), updated_at = $3, completed_at = $4
+             WHERE id = $5 AND status = 'RUNNING'",
+        )
+        .bind(status)
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn fail_stale_batch_jobs(&self, stale_before: f64, error: &str) -> Result<u64> {
+        let now = timestamp_now();
+        let res = sqlx::query(
+            "UPDATE workflow.batch_jobs
+             SET status = 'FAILED', last_error = $1, updated_at = $2, completed_at = $2
+             WHERE status = 'RUNNING' AND updated_at < $3",
+        )
+        .bind(error)
+        .bind(now)
+        .bind(stale_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
     // ── Schedules ───────────────────────────────────────────
 
     async fn create_schedule(&self, sched: &WorkflowSchedule) -> Result<()> {
         sqlx::query(
-            "INSERT INTO workflow.schedules (namespace, name, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+            "INSERT INTO workflow.schedules (namespace, name, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, catchup_window_secs, catchup_policy, jitter_secs, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
         )
         .bind(&sched.namespace)
         .bind(&sched.name)
@@ -1105,6 +1808,9 @@ impl WorkflowStore for PostgresStore {
         .bind(sched.last_run_at)
         .bind(crate::scheduler::seed_next_run(sched))
         .bind(&sched.last_workflow_id)
+        .bind(sched.catchup_window_secs)
+        .bind(&sched.catchup_policy)
+        .bind(sched.jitter_secs)
         .bind(sched.created_at)
         .execute(&self.pool)
         .await?;
@@ -1113,7 +1819,7 @@ impl WorkflowStore for PostgresStore {
 
     async fn get_schedule(&self, namespace: &str, name: &str) -> Result<Option<WorkflowSchedule>> {
         let row = sqlx::query_as::<_, PgScheduleRow>(
-            "SELECT namespace, name, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, created_at FROM workflow.schedules WHERE namespace = $1 AND name = $2",
+            "SELECT namespace, name, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, catchup_window_secs, catchup_policy, jitter_secs, created_at FROM workflow.schedules WHERE namespace = $1 AND name = $2",
         )
         .bind(namespace)
         .bind(name)
@@ -1124,7 +1830,7 @@ impl WorkflowStore for PostgresStore {
 
     async fn list_schedules(&self, namespace: &str) -> Result<Vec<WorkflowSchedule>> {
         let rows = sqlx::query_as::<_, PgScheduleRow>(
-            "SELECT namespace, name, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, created_at FROM workflow.schedules WHERE namespace = $1 ORDER BY name",
+            "SELECT namespace, name, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, catchup_window_secs, catchup_policy, jitter_secs, created_at FROM workflow.schedules WHERE namespace = $1 ORDER BY name",
         )
         .bind(namespace)
         .fetch_all(&self.pool)
@@ -1138,10 +1844,10 @@ impl WorkflowStore for PostgresStore {
         name: &str,
         last_run_at: f64,
         next_run_at: f64,
-        workflow_id: &str,
+        workflow_id: Option<&str>,
     ) -> Result<()> {
         sqlx::query(
-            "UPDATE workflow.schedules SET last_run_at = $1, next_run_at = $2, last_workflow_id = $3 WHERE namespace = $4 AND name = $5",
+            "UPDATE workflow.schedules SET last_run_at = $1, next_run_at = $2, last_workflow_id = COALESCE($3, last_workflow_id) WHERE namespace = $4 AND name = $5",
         )
         .bind(last_run_at)
         .bind(next_run_at)
@@ -1154,11 +1860,20 @@ impl WorkflowStore for PostgresStore {
     }
 
     async fn delete_schedule(&self, namespace: &str, name: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "DELETE FROM workflow.schedule_actions WHERE namespace = $1 AND schedule_name = $2",
+        )
+        .bind(namespace)
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
         let res = sqlx::query("DELETE FROM workflow.schedules WHERE namespace = $1 AND name = $2")
             .bind(namespace)
             .bind(name)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(res.rows_affected() > 0)
     }
 
@@ -1211,6 +1926,10 @@ impl WorkflowStore for PostgresStore {
             .bind(workflow_id)
             .execute(&mut *tx)
             .await?;
+        sqlx::query("DELETE FROM workflow.updates WHERE workflow_id = $1")
+            .bind(workflow_id)
+            .execute(&mut *tx)
+            .await?;
         sqlx::query(
             "UPDATE workflow.workflows SET archived_at = $1, archive_uri = $2 WHERE id = $3",
         )
@@ -1269,6 +1988,18 @@ impl WorkflowStore for PostgresStore {
             sets.push(format!("overlap_policy = ${idx}"));
             idx += 1;
         }
+        if patch.catchup_window_secs.is_some() {
+            sets.push(format!("catchup_window_secs = ${idx}"));
+            idx += 1;
+        }
+        if patch.catchup_policy.is_some() {
+            sets.push(format!("catchup_policy = ${idx}"));
+            idx += 1;
+        }
+        if patch.jitter_secs.is_some() {
+            sets.push(format!("jitter_secs = ${idx}"));
+            idx += 1;
+        }
         if sets.is_empty() {
             return self.get_schedule(namespace, name).await;
         }
@@ -1294,6 +2025,15 @@ impl WorkflowStore for PostgresStore {
         if let Some(ref v) = patch.overlap_policy {
             q = q.bind(v);
         }
+        if let Some(v) = patch.catchup_window_secs {
+            q = q.bind(v);
+        }
+        if let Some(ref v) = patch.catchup_policy {
+            q = q.bind(v);
+        }
+        if let Some(v) = patch.jitter_secs {
+            q = q.bind(v);
+        }
         let res = q.bind(namespace).bind(name).execute(&self.pool).await?;
         if res.rows_affected() == 0 {
             return Ok(None);
@@ -1321,6 +2061,100 @@ impl WorkflowStore for PostgresStore {
         self.get_schedule(namespace, name).await
     }
 
+    async fn record_schedule_action(&self, action: &ScheduleAction, keep: i64) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO workflow.schedule_actions (namespace, schedule_name, fire_time, taken_at, action, workflow_id, reason, backfill)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING id",
+        )
+        .bind(&action.namespace)
+        .bind(&action.schedule_name)
+        .bind(action.fire_time)
+        .bind(action.taken_at)
+        .bind(&action.action)
+        .bind(&action.workflow_id)
+        .bind(&action.reason)
+        .bind(action.backfill)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM workflow.schedule_actions
+             WHERE namespace = $1 AND schedule_name = $2 AND action <> 'buffered'
+               AND id NOT IN (
+                   SELECT id FROM workflow.schedule_actions
+                   WHERE namespace = $3 AND schedule_name = $4 AND action <> 'buffered'
+                   ORDER BY id DESC LIMIT $5
+               )",
+        )
+        .bind(&action.namespace)
+        .bind(&action.schedule_name)
+        .bind(&action.namespace)
+        .bind(&action.schedule_name)
+        .bind(keep)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    async fn list_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+        limit: i64,
+    ) -> Result<Vec<ScheduleAction>> {
+        let rows = sqlx::query_as::<_, PgScheduleActionRow>(
+            "SELECT id, namespace, schedule_name, fire_time, taken_at, action, workflow_id, reason, backfill
+             FROM workflow.schedule_actions
+             WHERE namespace = $1 AND schedule_name = $2
+             ORDER BY id DESC LIMIT $3",
+        )
+        .bind(namespace)
+        .bind(schedule_name)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn list_buffered_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+    ) -> Result<Vec<ScheduleAction>> {
+        let rows = sqlx::query_as::<_, PgScheduleActionRow>(
+            "SELECT id, namespace, schedule_name, fire_time, taken_at, action, workflow_id, reason, backfill
+             FROM workflow.schedule_actions
+             WHERE namespace = $1 AND schedule_name = $2 AND action = 'buffered'
+             ORDER BY fire_time ASC, id ASC",
+        )
+        .bind(namespace)
+        .bind(schedule_name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn resolve_buffered_schedule_action(
+        &self,
+        id: i64,
+        workflow_id: &str,
+        taken_at: f64,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE workflow.schedule_actions
+             SET action = 'started', workflow_id = $1, taken_at = $2, reason = NULL
+             WHERE id = $3 AND action = 'buffered'",
+        )
+        .bind(workflow_id)
+        .bind(taken_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     // ── Workers ─────────────────────────────────────────────
 
     async fn register_worker(&self, w: &WorkflowWorker) -> Result<()> {
@@ -1438,12 +2272,28 @@ impl WorkflowStore for PostgresStore {
     // ── Queue Stats ─────────────────────────────────────────
 
     async fn get_queue_stats(&self, namespace: &str) -> Result<Vec<crate::store::QueueStats>> {
-        let rows = sqlx::query_as::<_, (String, i64, i64, i64)>(
+        let mut stats = std::collections::BTreeMap::new();
+
+        let task_rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT task_queue, COUNT(*) FROM workflow.workflows
+             WHERE namespace = $1
+               AND needs_dispatch = TRUE
+               AND dispatch_claimed_by IS NULL
+               AND status NOT IN ('COMPLETED', 'FAILED', 'CANCELLED', 'TIMED_OUT')
+             GROUP BY task_queue",
+        )
+        .bind(namespace)
+        .fetch_all(&self.pool)
+        .await?;
+        for (queue, count) in task_rows {
+            queue_stats_entry(&mut stats, queue).pending_workflow_tasks = count;
+        }
+
+        let rows = sqlx::query_as::<_, (String, i64, i64)>(
             "SELECT
                 a.task_queue AS queue,
                 SUM(CASE WHEN a.status = 'PENDING' THEN 1 ELSE 0 END) AS pending,
-                SUM(CASE WHEN a.status = 'RUNNING' THEN 1 ELSE 0 END) AS running,
-                (SELECT COUNT(*) FROM workflow.workers w WHERE w.task_queue = a.task_queue AND w.namespace = $1) AS workers
+                SUM(CASE WHEN a.status = 'RUNNING' THEN 1 ELSE 0 END) AS running
              FROM workflow.activities a
              JOIN workflow.workflows wf ON a.workflow_id = wf.id AND wf.namespace = $1
              GROUP BY a.task_queue",
@@ -1451,18 +2301,23 @@ impl WorkflowStore for PostgresStore {
         .bind(namespace)
         .fetch_all(&self.pool)
         .await?;
+        for (queue, pending, running) in rows {
+            let s = queue_stats_entry(&mut stats, queue);
+            s.pending_activities = pending;
+            s.running_activities = running;
+        }
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(queue, pending, running, workers)| crate::store::QueueStats {
-                    queue,
-                    pending_activities: pending,
-                    running_activities: running,
-                    workers,
-                },
-            )
-            .collect())
+        let worker_rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT task_queue, COUNT(*) FROM workflow.workers WHERE namespace = $1 GROUP BY task_queue",
+        )
+        .bind(namespace)
+        .fetch_all(&self.pool)
+        .await?;
+        for (queue, count) in worker_rows {
+            queue_stats_entry(&mut stats, queue).workers = count;
+        }
+
+        Ok(stats.into_values().collect())
     }
 
     // ── Leader Election ─────────────────────────────────────
@@ -1650,6 +2505,111 @@ impl From<PgSignalRow> for WorkflowSignal {
     }
 }
 
+#[derive(sqlx::FromRow)]
+struct PgBatchJobRow {
+    id: String,
+    namespace: String,
+    operation: String,
+    filter: String,
+    params: Option<String>,
+    max_per_second: f64,
+    status: String,
+    total: i64,
+    processed: i64,
+    succeeded: i64,
+    failed: i64,
+    last_error: Option<String>,
+    created_at: f64,
+    updated_at: f64,
+    completed_at: Option<f64>,
+}
+
+impl From<PgBatchJobRow> for BatchJob {
+    fn from(r: PgBatchJobRow) -> Self {
+        Self {
+            id: r.id,
+            namespace: r.namespace,
+            operation: r.operation,
+            filter: r.filter,
+            params: r.params,
+            max_per_second: r.max_per_second,
+            status: r.status,
+            total: r.total,
+            processed: r.processed,
+            succeeded: r.succeeded,
+            failed: r.failed,
+            last_error: r.last_error,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+            completed_at: r.completed_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PgSearchAttributeRow {
+    namespace: String,
+    name: String,
+    attr_type: String,
+    created_at: f64,
+}
+
+impl From<PgSearchAttributeRow> for SearchAttributeDef {
+    fn from(r: PgSearchAttributeRow) -> Self {
+        Self {
+            namespace: r.namespace,
+            name: r.name,
+            attr_type: r.attr_type.parse().unwrap_or(SearchAttributeType::Keyword),
+            created_at: r.created_at,
+        }
+    }
+}
+
+/// Bind a compiled visibility query's values, in placeholder order.
+fn bind_query_values<'q, O>(
+    mut q: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
+    compiled: &'q CompiledQuery,
+) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
+    for value in &compiled.binds {
+        q = match value {
+            QueryValue::String(s) => q.bind(s.as_str()),
+            QueryValue::Int(i) => q.bind(*i),
+            QueryValue::Double(d) => q.bind(*d),
+            QueryValue::Bool(b) => q.bind(*b),
+        };
+    }
+    q
+}
+
+#[derive(sqlx::FromRow)]
+struct PgUpdateRow {
+    id: String,
+    workflow_id: String,
+    name: String,
+    args: Option<String>,
+    status: String,
+    result: Option<String>,
+    error: Option<String>,
+    requested_at: f64,
+    completed_at: Option<f64>,
+}
+
+impl From<PgUpdateRow> for WorkflowUpdate {
+    fn from(r: PgUpdateRow) -> Self {
+        Self {
+            id: r.id,
+            workflow_id: r.workflow_id,
+            name: r.name,
+            args: r.args,
+            status: r.status,
+            result: r.result,
+            error: r.error,
+            requested_at: r.requested_at,
+            completed_at: r.completed_at,
+        }
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct PgScheduleRow {
     namespace: String,
@@ -1664,6 +2624,9 @@ struct PgScheduleRow {
     last_run_at: Option<f64>,
     next_run_at: Option<f64>,
     last_workflow_id: Option<String>,
+    catchup_window_secs: i64,
+    catchup_policy: String,
+    jitter_secs: i64,
     created_at: f64,
 }
 
@@ -1682,7 +2645,40 @@ impl From<PgScheduleRow> for WorkflowSchedule {
             last_run_at: r.last_run_at,
             next_run_at: r.next_run_at,
             last_workflow_id: r.last_workflow_id,
+            catchup_window_secs: r.catchup_window_secs,
+            catchup_policy: r.catchup_policy,
+            jitter_secs: r.jitter_secs,
             created_at: r.created_at,
+            recent_actions: Vec::new(),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PgScheduleActionRow {
+    id: i64,
+    namespace: String,
+    schedule_name: String,
+    fire_time: f64,
+    taken_at: f64,
+    action: String,
+    workflow_id: Option<String>,
+    reason: Option<String>,
+    backfill: bool,
+}
+
+impl From<PgScheduleActionRow> for ScheduleAction {
+    fn from(r: PgScheduleActionRow) -> Self {
+        Self {
+            id: Some(r.id),
+            namespace: r.namespace,
+            schedule_name: r.schedule_name,
+            fire_time: r.fire_time,
+            taken_at: r.taken_at,
+            action: r.action,
+            workflow_id: r.workflow_id,
+            reason: r.reason,
+            backfill: r.backfill,
         }
     }
 }
@@ -2,8 +2,10 @@ use anyhow::Result;
 use sqlx::SqlitePool;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 
+use crate::store::visibility::{CompiledQuery, Dialect, compile, search_attribute_expr};
 use crate::store::{
-    NamespaceRecord, NamespaceStats, QueueStats, RetryEvent, WorkflowStore, retry_denial,
+    NamespaceRecord, NamespaceStats, QueueStats, RetryEvent, WorkflowStore, queue_stats_entry,
+    retry_denial,
 };
 use crate::types::*;
 
@@ -55,7 +57,9 @@ CREATE TABLE IF NOT EXISTS workflow.workflows (
     dispatch_last_heartbeat REAL,
     created_at      REAL NOT NULL,
     updated_at      REAL NOT NULL,
-    completed_at    REAL
+    completed_at    REAL,
+    -- Idempotency key of the start request that created this run.
+    request_id      TEXT
 );
 CREATE INDEX IF NOT EXISTS workflow.idx_wf_status_queue ON workflows(status, task_queue);
 CREATE INDEX IF NOT EXISTS workflow.idx_wf_namespace ON workflows(namespace);
@@ -92,6 +96,7 @@ CREATE TABLE IF NOT EXISTS workflow.activities (
     started_at      REAL,
     completed_at    REAL,
     last_heartbeat  REAL,
+    cancel_requested INTEGER NOT NULL DEFAULT 0,
     UNIQUE (workflow_id, seq)
 );
 CREATE INDEX IF NOT EXISTS workflow.idx_wf_act_pending ON activities(task_queue, status, scheduled_at);
@@ -116,6 +121,19 @@ CREATE TABLE IF NOT EXISTS workflow.signals (
 );
 CREATE INDEX IF NOT EXISTS workflow.idx_wf_signals_lookup ON signals(workflow_id, name, consumed);
 
+CREATE TABLE IF NOT EXISTS workflow.updates (
+    id              TEXT PRIMARY KEY,
+    workflow_id     TEXT NOT NULL REFERENCES workflows(id),
+    name            TEXT NOT NULL,
+    args            TEXT,
+    status          TEXT NOT NULL DEFAULT 'REQUESTED',
+    result          TEXT,
+    error           TEXT,
+    requested_at    REAL NOT NULL,
+    completed_at    REAL
+);
+CREATE INDEX IF NOT EXISTS workflow.idx_wf_updates_pending ON updates(workflow_id, status, requested_at);
+
 CREATE TABLE IF NOT EXISTS workflow.schedules (
     name            TEXT NOT NULL,
     namespace       TEXT NOT NULL DEFAULT 'main',
@@ -129,6 +147,49 @@ CREATE TABLE IF NOT EXISTS workflow.schedules (
     last_run_at     REAL,
     next_run_at     REAL,
     last_workflow_id TEXT,
+    catchup_window_secs INTEGER NOT NULL DEFAULT 31536000,
+    catchup_policy  TEXT NOT NULL DEFAULT 'one',
+    jitter_secs     INTEGER NOT NULL DEFAULT 0,
+    created_at      REAL NOT NULL,
+    PRIMARY KEY (namespace, name)
+);
+
+CREATE TABLE IF NOT EXISTS workflow.schedule_actions (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    namespace       TEXT NOT NULL,
+    schedule_name   TEXT NOT NULL,
+    fire_time       REAL NOT NULL,
+    taken_at        REAL NOT NULL,
+    action          TEXT NOT NULL,
+    workflow_id     TEXT,
+    reason          TEXT,
+    backfill        INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS workflow.idx_wf_schedule_actions ON schedule_actions(namespace, schedule_name, id);
+
+CREATE TABLE IF NOT EXISTS workflow.batch_jobs (
+    id              TEXT PRIMARY KEY,
+    namespace       TEXT NOT NULL DEFAULT 'main',
+    operation       TEXT NOT NULL,
+    filter          TEXT NOT NULL,
+    params          TEXT,
+    max_per_second  REAL NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'RUNNING',
+    total           INTEGER NOT NULL DEFAULT 0,
+    processed       INTEGER NOT NULL DEFAULT 0,
+    succeeded       INTEGER NOT NULL DEFAULT 0,
+    failed          INTEGER NOT NULL DEFAULT 0,
+    last_error      TEXT,
+    created_at      REAL NOT NULL,
+    updated_at      REAL NOT NULL,
+    completed_at    REAL
+);
+CREATE INDEX IF NOT EXISTS workflow.idx_wf_batch_jobs_ns ON batch_jobs(namespace, created_at);
+
+CREATE TABLE IF NOT EXISTS workflow.search_attributes (
+    namespace       TEXT NOT NULL,
+    name            TEXT NOT NULL,
+    attr_type       TEXT NOT NULL,
     created_at      REAL NOT NULL,
     PRIMARY KEY (namespace, name)
 );
@@ -379,10 +440,9 @@ impl SqliteStore {
     }
 
     /// Apply the baseline schema. SCHEMA's `CREATE TABLE IF NOT EXISTS`
-    /// statements are the source of truth — pre-1.0 we don't carry
-    /// `ALTER TABLE ADD COLUMN` history. For additive migrations later,
-    /// chain a `Self::add_column_if_missing(&self.pool, "<table>",
-    /// "<column>", "<type_def>")` call here before returning.
+    /// statements are the source of truth for fresh databases; columns
+    /// added since are also back-filled onto existing tables with
+    /// `Self::add_column_if_missing` below.
     async fn migrate(&self) -> Result<()> {
         for statement in SCHEMA.split(';') {
             let trimmed = statement.trim();
@@ -390,7 +450,35 @@ impl SqliteStore {
                 sqlx::query(trimmed).execute(&self.pool).await?;
             }
         }
-        // Future additive migrations go here; see doc-comment above.
+        Self::add_column_if_missing(
+            &self.pool,
+            "workflow.activities",
+            "cancel_requested",
+            "INTEGER NOT NULL DEFAULT 0",
+        )
+        .await?;
+        Self::add_column_if_missing(&self.pool, "workflow.workflows", "request_id", "TEXT").await?;
+        Self::add_column_if_missing(
+            &self.pool,
+            "workflow.schedules",
+            "catchup_window_secs",
+            "INTEGER NOT NULL DEFAULT 31536000",
+        )
+        .await?;
+        Self::add_column_if_missing(
+            &self.pool,
+            "workflow.schedules",
+            "catchup_policy",
+            "TEXT NOT NULL DEFAULT 'one'",
+        )
+        .await?;
+        Self::add_column_if_missing(
+            &self.pool,
+            "workflow.schedules",
+            "jitter_secs",
+            "INTEGER NOT NULL DEFAULT 0",
+        )
+        .await?;
         Ok(())
     }
 
@@ -398,21 +486,19 @@ impl SqliteStore {
     ///
     /// SQLite (unlike Postgres) doesn't support `ADD COLUMN IF NOT EXISTS`,
     /// so we check via `pragma_table_info` before issuing the ALTER. Each
-    /// call is idempotent across startups.
-    ///
-    /// Currently unused — kept as the documented pattern for the first
-    /// additive migration after v0.11.3. Remove `#[allow(dead_code)]` when
-    /// a caller is added.
-    #[allow(dead_code)]
+    /// call is idempotent across startups. `table` may be qualified with
+    /// its ATTACHed database (`workflow.activities`).
     async fn add_column_if_missing(
         pool: &SqlitePool,
         table: &str,
         column: &str,
         type_def: &str,
     ) -> Result<()> {
+        let (schema, name) = table.split_once('.').unwrap_or(("main", table));
         let exists: Option<(String,)> =
-            sqlx::query_as("SELECT name FROM pragma_table_info(?) WHERE name = ?")
-                .bind(table)
+            sqlx::query_as("SELECT name FROM pragma_table_info(?, ?) WHERE name = ?")
+                .bind(name)
+                .bind(schema)
                 .bind(column)
                 .fetch_optional(pool)
                 .await?;
@@ -539,6 +625,137 @@ impl WorkflowStore for SqliteStore {
         Ok(())
     }
 
+    async fn start_workflow_run(
+        &self,
+        wf: &WorkflowRecord,
+        policy: IdReusePolicy,
+        request_id: Option<&str>,
+    ) -> Result<StartRunOutcome> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO workflow.workflows (id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at, request_id)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&wf.id)
+        .bind(&wf.namespace)
+        .bind(&wf.run_id)
+        .bind(&wf.workflow_type)
+        .bind(&wf.task_queue)
+        .bind(&wf.status)
+        .bind(&wf.input)
+        .bind(&wf.result)
+        .bind(&wf.error)
+        .bind(&wf.parent_id)
+        .bind(&wf.claimed_by)
+        .bind(&wf.search_attributes)
+        .bind(wf.archived_at)
+        .bind(&wf.archive_uri)
+        .bind(wf.created_at)
+        .bind(wf.updated_at)
+        .bind(wf.completed_at)
+        .bind(request_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 1 {
+            tx.commit().await?;
+            return Ok(StartRunOutcome::Started {
+                replaced_run_id: None,
+            });
+        }
+
+        let existing: WorkflowRecord = sqlx::query_as::<_, SqliteWorkflowRow>(
+            "SELECT id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at
+             FROM workflow.workflows WHERE id = ?",
+        )
+        .bind(&wf.id)
+        .fetch_one(&mut *tx)
+        .await?
+        .into();
+        let (existing_request_id,): (Option<String>,) =
+            sqlx::query_as("SELECT request_id FROM workflow.workflows WHERE id = ?")
+                .bind(&wf.id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if request_id.is_some() && existing_request_id.as_deref() == request_id {
+            return Ok(StartRunOutcome::Duplicate(existing));
+        }
+        let status = existing
+            .status
+            .parse::<WorkflowStatus>()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if !status.is_terminal() {
+            return Ok(StartRunOutcome::Running(existing));
+        }
+        if !policy.allows_replacing(status) {
+            return Ok(StartRunOutcome::Rejected(existing));
+        }
+
+        // Keep the closed run, with everything that hangs off it, under
+        // its retired id; then hand the id to the new run.
+        let retired = retired_workflow_id(&wf.id, &existing.run_id);
+        sqlx::query(
+            "INSERT INTO workflow.workflows (id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, needs_dispatch, dispatch_claimed_by, dispatch_last_heartbeat, created_at, updated_at, completed_at, request_id)
+             SELECT ?, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, needs_dispatch, dispatch_claimed_by, dispatch_last_heartbeat, created_at, updated_at, completed_at, request_id
+             FROM workflow.workflows WHERE id = ?",
+        )
+        .bind(&retired)
+        .bind(&wf.id)
+        .execute(&mut *tx)
+        .await?;
+        for table in [
+            "events",
+            "activities",
+            "timers",
+            "signals",
+            "updates",
+            "snapshots",
+        ] {
+            sqlx::query(&format!(
+                "UPDATE workflow.{table} SET workflow_id = ? WHERE workflow_id = ?"
+            ))
+            .bind(&retired)
+            .bind(&wf.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("UPDATE workflow.workflows SET parent_id = ? WHERE parent_id = ?")
+            .bind(&retired)
+            .bind(&wf.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE workflow.workflows
+             SET run_id = ?, workflow_type = ?, task_queue = ?, status = ?, input = ?, result = ?, error = ?, parent_id = ?, claimed_by = ?, search_attributes = ?, archived_at = ?, archive_uri = ?, created_at = ?, updated_at = ?, completed_at = ?, request_id = ?,
+                 needs_dispatch = 0, dispatch_claimed_by = NULL, dispatch_last_heartbeat = NULL
+             WHERE id = ?",
+        )
+        .bind(&wf.run_id)
+        .bind(&wf.workflow_type)
+        .bind(&wf.task_queue)
+        .bind(&wf.status)
+        .bind(&wf.input)
+        .bind(&wf.result)
+        .bind(&wf.error)
+        .bind(&wf.parent_id)
+        .bind(&wf.claimed_by)
+        .bind(&wf.search_attributes)
+        .bind(wf.archived_at)
+        .bind(&wf.archive_uri)
+        .bind(wf.created_at)
+        .bind(wf.updated_at)
+        .bind(wf.completed_at)
+        .bind(request_id)
+        .bind(&wf.id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(StartRunOutcome::Started {
+            replaced_run_id: Some(existing.run_id),
+        })
+    }
+
     async fn get_workflow(&self, id: &str) -> Result<Option<WorkflowRecord>> {
         let row = sqlx::query_as::<_, SqliteWorkflowRow>(
             "SELECT id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at FROM workflow.workflows WHERE id = ?",
@@ -613,6 +830,103 @@ impl WorkflowStore for SqliteStore {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    async fn query_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WorkflowRecord>> {
+        let compiled = compile(query, Dialect::Sqlite, 1);
+        let sql = format!(
+            "SELECT id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at
+             FROM workflow.workflows
+             WHERE namespace = ?{}
+             ORDER BY created_at DESC LIMIT ? OFFSET ?",
+            compiled.and_clause()
+        );
+        let q = sqlx::query_as::<_, SqliteWorkflowRow>(&sql).bind(namespace);
+        let rows = bind_query_values(q, &compiled)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> Result<i64> {
+        let compiled = compile(query, Dialect::Sqlite, 1);
+        let sql = format!(
+            "SELECT COUNT(*) FROM workflow.workflows WHERE namespace = ?{}",
+            compiled.and_clause()
+        );
+        let q = sqlx::query_as::<_, (i64,)>(&sql).bind(namespace);
+        let (count,) = bind_query_values(q, &compiled)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn count_workflows_by_status(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> Result<Vec<WorkflowStatusCount>> {
+        let compiled = compile(query, Dialect::Sqlite, 1);
+        let sql = format!(
+            "SELECT status, COUNT(*) FROM workflow.workflows WHERE namespace = ?{} GROUP BY status ORDER BY status",
+            compiled.and_clause()
+        );
+        let q = sqlx::query_as::<_, (String, i64)>(&sql).bind(namespace);
+        let rows = bind_query_values(q, &compiled)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(status, count)| WorkflowStatusCount { status, count })
+            .collect())
+    }
+
+    async fn create_search_attribute(&self, def: &SearchAttributeDef) -> Result<bool> {
+        let res = sqlx::query(
+            "INSERT OR IGNORE INTO workflow.search_attributes (namespace, name, attr_type, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&def.namespace)
+        .bind(&def.name)
+        .bind(def.attr_type.to_string())
+        .bind(def.created_at)
+        .execute(&self.pool)
+        .await?;
+        if res.rows_affected() == 0 {
+            return Ok(false);
+        }
+        // One index per attribute name, shared across namespaces. Best
+        // effort: queries still work (unindexed) if this fails.
+        let index = format!(
+            "CREATE INDEX IF NOT EXISTS workflow.idx_wf_sa_{name} ON workflows({expr})",
+            name = def.name,
+            expr = search_attribute_expr(Dialect::Sqlite, &def.name),
+        );
+        if let Err(e) = sqlx::query(&index).execute(&self.pool).await {
+            tracing::warn!("failed to index search attribute {}: {e}", def.name);
+        }
+        Ok(true)
+    }
+
+    async fn list_search_attributes(&self, namespace: &str) -> Result<Vec<SearchAttributeDef>> {
+        let rows = sqlx::query_as::<_, SqliteSearchAttributeRow>(
+            "SELECT namespace, name, attr_type, created_at FROM workflow.search_attributes WHERE namespace = ? ORDER BY name",
+        )
+        .bind(namespace)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     async fn update_workflow_status(
         &self,
         id: &str,
@@ -989,6 +1303,130 @@ impl WorkflowStore for SqliteStore {
         )))
     }
 
+    async fn reset_workflow(
+        &self,
+        source_id: &str,
+        new_run: &WorkflowRecord,
+        reset_to_seq: i32,
+        reapply_signals: bool,
+        reason: &str,
+    ) -> Result<ResetWorkflowResult> {
+        let mut tx = self.pool.begin().await?;
+        let source: Option<(String, Option<f64>)> =
+            sqlx::query_as("SELECT run_id, archived_at FROM workflow.workflows WHERE id = ?")
+                .bind(source_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let Some((source_run_id, archived_at)) = source else {
+            return Ok(ResetWorkflowResult::NotFound);
+        };
+        if archived_at.is_some() {
+            return Ok(ResetWorkflowResult::Archived);
+        }
+        let event_count: (i32,) = sqlx::query_as(
+            "SELECT COALESCE(MAX(seq), 0) FROM workflow.events WHERE workflow_id = ?",
+        )
+        .bind(source_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if reset_to_seq < 1 || reset_to_seq > event_count.0 {
+            return Ok(ResetWorkflowResult::InvalidEventSeq {
+                event_count: event_count.0,
+            });
+        }
+        let inserted = sqlx::query(
+            "INSERT OR IGNORE INTO workflow.workflows (id, namespace, run_id, workflow_type, task_queue, status, input, result, error, parent_id, claimed_by, search_attributes, archived_at, archive_uri, created_at, updated_at, completed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&new_run.id)
+        .bind(&new_run.namespace)
+        .bind(&new_run.run_id)
+        .bind(&new_run.workflow_type)
+        .bind(&new_run.task_queue)
+        .bind(&new_run.status)
+        .bind(&new_run.input)
+        .bind(&new_run.result)
+        .bind(&new_run.error)
+        .bind(&new_run.parent_id)
+        .bind(&new_run.claimed_by)
+        .bind(&new_run.search_attributes)
+        .bind(new_run.archived_at)
+        .bind(&new_run.archive_uri)
+        .bind(new_run.created_at)
+        .bind(new_run.updated_at)
+        .bind(new_run.completed_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Ok(ResetWorkflowResult::AlreadyExists);
+        }
+
+        let events_copied = sqlx::query(
+            "INSERT INTO workflow.events (workflow_id, seq, event_type, payload, timestamp)
+             SELECT ?, seq, event_type, payload, timestamp FROM workflow.events
+             WHERE workflow_id = ? AND seq <= ?",
+        )
+        .bind(&new_run.id)
+        .bind(source_id)
+        .bind(reset_to_seq)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let signals_reapplied = if reapply_signals {
+            sqlx::query(
+                "INSERT INTO workflow.events (workflow_id, seq, event_type, payload, timestamp)
+                 SELECT ?, ? + ROW_NUMBER() OVER (ORDER BY seq), event_type, payload, timestamp
+                 FROM workflow.events
+                 WHERE workflow_id = ? AND seq > ? AND event_type = 'SignalReceived'",
+            )
+            .bind(&new_run.id)
+            .bind(reset_to_seq)
+            .bind(source_id)
+            .bind(reset_to_seq)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+        } else {
+            0
+        };
+
+        let payload = serde_json::json!({
+            "source_workflow_id": source_id,
+            "source_run_id": source_run_id,
+            "reset_workflow_id": new_run.id,
+            "reset_run_id": new_run.run_id,
+            "reset_to_seq": reset_to_seq,
+            "signals_reapplied": signals_reapplied,
+            "reason": reason,
+        })
+        .to_string();
+        for (workflow_id, seq) in [
+            (
+                new_run.id.as_str(),
+                reset_to_seq + signals_reapplied as i32 + 1,
+            ),
+            (source_id, event_count.0 + 1),
+        ] {
+            sqlx::query(
+                "INSERT INTO workflow.events (workflow_id, seq, event_type, payload, timestamp)
+                 VALUES (?, ?, 'WorkflowReset', ?, ?)",
+            )
+            .bind(workflow_id)
+            .bind(seq)
+            .bind(&payload)
+            .bind(new_run.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(ResetWorkflowResult::Reset(Box::new(ResetWorkflow {
+            workflow: new_run.clone(),
+            events_copied,
+            signals_reapplied,
+        })))
+    }
+
     async fn complete_activity(
         &self,
         id: i64,
@@ -1010,13 +1448,83 @@ impl WorkflowStore for SqliteStore {
         Ok(())
     }
 
-    async fn heartbeat_activity(&self, id: i64, _details: Option<&str>) -> Result<()> {
+    async fn heartbeat_activity(&self, id: i64, _details: Option<&str>) -> Result<bool> {
         sqlx::query("UPDATE workflow.activities SET last_heartbeat = ? WHERE id = ?")
             .bind(timestamp_now())
             .bind(id)
             .execute(&self.pool)
             .await?;
-        Ok(())
+        let stop: Option<(bool,)> = sqlx::query_as(
+            "SELECT a.cancel_requested <> 0
+                 OR w.status IN ('COMPLETED', 'FAILED', 'CANCELLED', 'TIMED_OUT')
+             FROM workflow.activities a
+             JOIN workflow.workflows w ON w.id = a.workflow_id
+             WHERE a.id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(stop.is_some_and(|(stop,)| stop))
+    }
+
+    async fn request_activity_cancel(
+        &self,
+        workflow_id: &str,
+        seq: i32,
+    ) -> Result<CancelActivityResult> {
+        let mut tx = self.pool.begin().await?;
+        let status: Option<(String, bool)> = sqlx::query_as(
+            "SELECT status, cancel_requested <> 0 FROM workflow.activities
+             WHERE workflow_id = ? AND seq = ?",
+        )
+        .bind(workflow_id)
+        .bind(seq)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let result = match status
+            .as_ref()
+            .map(|(s, requested)| (s.as_str(), *requested))
+        {
+            None => CancelActivityResult::NotFound,
+            Some(("PENDING", _)) => {
+                sqlx::query(
+                    "UPDATE workflow.activities SET status = 'CANCELLED', completed_at = ?
+                     WHERE workflow_id = ? AND seq = ?",
+                )
+                .bind(timestamp_now())
+                .bind(workflow_id)
+                .bind(seq)
+                .execute(&mut *tx)
+                .await?;
+                CancelActivityResult::Cancelled
+            }
+            Some(("RUNNING", false)) => {
+                sqlx::query(
+                    "UPDATE workflow.activities SET cancel_requested = 1
+                     WHERE workflow_id = ? AND seq = ?",
+                )
+                .bind(workflow_id)
+                .bind(seq)
+                .execute(&mut *tx)
+                .await?;
+                CancelActivityResult::CancelRequested
+            }
+            Some(_) => CancelActivityResult::Unchanged,
+        };
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    async fn acknowledge_activity_cancel(&self, id: i64) -> Result<bool> {
+        let res = sqlx::query(
+            "UPDATE workflow.activities SET status = 'CANCELLED', completed_at = ?
+             WHERE id = ? AND status = 'RUNNING' AND cancel_requested <> 0",
+        )
+        .bind(timestamp_now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
     }
 
     async fn get_timed_out_activities(&self, now: f64) -> Result<Vec<WorkflowActivity>> {
@@ -1071,6 +1579,13 @@ impl WorkflowStore for SqliteStore {
         .bind(workflow_id)
         .execute(&self.pool)
         .await?;
+        sqlx::query(
+            "UPDATE workflow.activities SET cancel_requested = 1
+             WHERE workflow_id = ? AND status = 'RUNNING'",
+        )
+        .bind(workflow_id)
+        .execute(&self.pool)
+        .await?;
         Ok(res.rows_affected())
     }
 
@@ -1113,6 +1628,17 @@ impl WorkflowStore for SqliteStore {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    async fn get_timer_backlog(&self, now: f64) -> Result<TimerBacklog> {
+        let (pending, overdue) = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT COUNT(*), COALESCE(SUM(CASE WHEN fire_at <= ? THEN 1 ELSE 0 END), 0)
+             FROM workflow.timers WHERE fired = 0",
+        )
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(TimerBacklog { pending, overdue })
+    }
+
     // ── Signals ─────────────────────────────────────────────
 
     async fn send_signal(&self, sig: &WorkflowSignal) -> Result<i64> {
@@ -1141,12 +1667,196 @@ impl WorkflowStore for SqliteStore {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    // ── Updates ─────────────────────────────────────────────
+
+    async fn create_update(&self, update: &WorkflowUpdate) -> Result<bool> {
+        let res = sqlx::query(
+            "INSERT OR IGNORE INTO workflow.updates (id, workflow_id, name, args, status, result, error, requested_at, completed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&update.id)
+        .bind(&update.workflow_id)
+        .bind(&update.name)
+        .bind(&update.args)
+        .bind(&update.status)
+        .bind(&update.result)
+        .bind(&update.error)
+        .bind(update.requested_at)
+        .bind(update.completed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn get_update(&self, update_id: &str) -> Result<Option<WorkflowUpdate>> {
+        let row = sqlx::query_as::<_, SqliteUpdateRow>(
+            "SELECT id, workflow_id, name, args, status, result, error, requested_at, completed_at FROM workflow.updates WHERE id = ?",
+        )
+        .bind(update_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn list_pending_updates(&self, workflow_id: &str) -> Result<Vec<WorkflowUpdate>> {
+        let rows = sqlx::query_as::<_, SqliteUpdateRow>(
+            "SELECT id, workflow_id, name, args, status, result, error, requested_at, completed_at FROM workflow.updates
+             WHERE workflow_id = ? AND status = 'REQUESTED' ORDER BY requested_at ASC, id ASC",
+        )
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn transition_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        from_status: &str,
+        to_status: &str,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<bool> {
+        let completed_at = (to_status != "ACCEPTED").then(timestamp_now);
+        let res = sqlx::query(
+            "UPDATE workflow.updates SET status = ?, result = ?, error = ?, completed_at = ?
+             WHERE id = ? AND workflow_id = ? AND status = ?",
+        )
+        .bind(to_status)
+        .bind(result)
+        .bind(error)
+        .bind(completed_at)
+        .bind(update_id)
+        .bind(workflow_id)
+        .bind(from_status)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // ── Batch jobs ──────────────────────────────────────────
+
+    async fn create_batch_job(&self, job: &BatchJob) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO workflow.batch_jobs (id, namespace, operation, filter, params, max_per_second, status, total, processed, succeeded, failed, last_error, created_at, updated_at, completed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&job.id)
+        .bind(&job.namespace)
+        .bind(&job.operation)
+        .bind(&job.filter)
+        .bind(&job.params)
+        .bind(job.max_per_second)
+        .bind(&job.status)
+        .bind(job.total)
+        .bind(job.processed)
+        .bind(job.succeeded)
+        .bind(job.failed)
+        .bind(&job.last_error)
+        .bind(job.created_at)
+        .bind(job.updated_at)
+        .bind(job.completed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_batch_job(&self, id: &str) -> Result<Option<BatchJob>> {
+        let row = sqlx::query_as::<_, SqliteBatchJobRow>(
+            "SELECT id, namespace, operation, filter, params, max_per_second, status, total, processed, succeeded, failed, last_error, created_at, updated_at, completed_at FROM workflow.batch_jobs WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn list_batch_jobs(
+        &self,
+        namespace: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BatchJob>> {
+        let rows = sqlx::query_as::<_, SqliteBatchJobRow>(
+            "SELECT id, namespace, operation, filter, params, max_per_second, status, total, processed, succeeded, failed, last_error, created_at, updated_at, completed_at FROM workflow.batch_jobs
+             WHERE namespace = ? ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(namespace)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn update_batch_job_progress(
+        &self,
+        id: &str,
+        total: i64,
+        processed: i64,
+        succeeded: i64,
+        failed: i64,
+        last_error: Option<&str>,
+    ) -> Result<bool> {
+        let status: Option<String> = sqlx::query_scalar(
+            "UPDATE workflow.batch_jobs
+             SET total = ?, processed = ?, succeeded = ?, failed = ?,
+                 last_error = COALESCE(?, last_error), updated_at = ?
+             WHERE id = ?
+             RETURNING status",
+        )
+        .bind(total)
+        .bind(processed)
+        .bind(succeeded)
+        .bind(failed)
+        .bind(last_error)
+        .bind(timestamp_now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(status.as_deref() == Some("RUNNING"))
+    }
+
+    async fn finish_batch_job(&self, id: &str, status: &str, error: Option<&str>) -> Result<bool> {
+        let now = timestamp_now();
+        let res = sqlx::query(
+            "UPDATE workflow.batch_jobs
+             SET status = ?, last_error = COALESCE(?, last_error), updated_at = ?, completed_at = ?
+             WHERE id = ? AND status = 'RUNNING'",
+        )
+        .bind(status)
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn fail_stale_batch_jobs(&self, stale_before: f64, error: &str) -> Result<u64> {
+        let now = timestamp_now();
+        let res = sqlx::query(
+            "UPDATE workflow.batch_jobs
+             SET status = 'FAILED', last_error = ?, updated_at = ?, completed_at = ?
+             WHERE status = 'RUNNING' AND updated_at < ?",
+        )
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .bind(stale_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
     // ── Schedules ───────────────────────────────────────────
 
     async fn create_schedule(&self, sched: &WorkflowSchedule) -> Result<()> {
         sqlx::query(
-            "INSERT INTO workflow.schedules (name, namespace, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, created_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO workflow.schedules (name, namespace, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, catchup_window_secs, catchup_policy, jitter_secs, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&sched.name)
         .bind(&sched.namespace)
@@ -1160,6 +1870,9 @@ impl WorkflowStore for SqliteStore {
         .bind(sched.last_run_at)
         .bind(crate::scheduler::seed_next_run(sched))
         .bind(&sched.last_workflow_id)
+        .bind(sched.catchup_window_secs)
+        .bind(&sched.catchup_policy)
+        .bind(sched.jitter_secs)
         .bind(sched.created_at)
         .execute(&self.pool)
         .await?;
@@ -1168,7 +1881,7 @@ impl WorkflowStore for SqliteStore {
 
     async fn get_schedule(&self, namespace: &str, name: &str) -> Result<Option<WorkflowSchedule>> {
         let row = sqlx::query_as::<_, SqliteScheduleRow>(
-            "SELECT name, namespace, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, created_at
+            "SELECT name, namespace, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, catchup_window_secs, catchup_policy, jitter_secs, created_at
              FROM workflow.schedules WHERE namespace = ? AND name = ?",
         )
         .bind(namespace)
@@ -1180,7 +1893,7 @@ impl WorkflowStore for SqliteStore {
 
     async fn list_schedules(&self, namespace: &str) -> Result<Vec<WorkflowSchedule>> {
         let rows = sqlx::query_as::<_, SqliteScheduleRow>(
-            "SELECT name, namespace, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, created_at
+            "SELECT name, namespace, workflow_type, cron_expr, timezone, input, task_queue, overlap_policy, paused, last_run_at, next_run_at, last_workflow_id, catchup_window_secs, catchup_policy, jitter_secs, created_at
              FROM workflow.schedules WHERE namespace = ? ORDER BY name",
         )
         .bind(namespace)
@@ -1195,10 +1908,10 @@ impl WorkflowStore for SqliteStore {
         name: &str,
         last_run_at: f64,
         next_run_at: f64,
-        workflow_id: &str,
+        workflow_id: Option<&str>,
     ) -> Result<()> {
         sqlx::query(
-            "UPDATE workflow.schedules SET last_run_at = ?, next_run_at = ?, last_workflow_id = ? WHERE namespace = ? AND name = ?",
+            "UPDATE workflow.schedules SET last_run_at = ?, next_run_at = ?, last_workflow_id = COALESCE(?, last_workflow_id) WHERE namespace = ? AND name = ?",
         )
         .bind(last_run_at)
         .bind(next_run_at)
@@ -1211,11 +1924,20 @@ impl WorkflowStore for SqliteStore {
     }
 
     async fn delete_schedule(&self, namespace: &str, name: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "DELETE FROM workflow.schedule_actions WHERE namespace = ? AND schedule_name = ?",
+        )
+        .bind(namespace)
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
         let res = sqlx::query("DELETE FROM workflow.schedules WHERE namespace = ? AND name = ?")
             .bind(namespace)
             .bind(name)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(res.rows_affected() > 0)
     }
 
@@ -1268,6 +1990,10 @@ impl WorkflowStore for SqliteStore {
             .bind(workflow_id)
             .execute(&mut *tx)
             .await?;
+        sqlx::query("DELETE FROM workflow.updates WHERE workflow_id = ?")
+            .bind(workflow_id)
+            .execute(&mut *tx)
+            .await?;
         sqlx::query("UPDATE workflow.workflows SET archived_at = ?, archive_uri = ? WHERE id = ?")
             .bind(archived_at)
             .bind(archive_uri)
@@ -1319,6 +2045,15 @@ impl WorkflowStore for SqliteStore {
         if patch.overlap_policy.is_some() {
             sets.push("overlap_policy = ?");
         }
+        if patch.catchup_window_secs.is_some() {
+            sets.push("catchup_window_secs = ?");
+        }
+        if patch.catchup_policy.is_some() {
+            sets.push("catchup_policy = ?");
+        }
+        if patch.jitter_secs.is_some() {
+            sets.push("jitter_secs = ?");
+        }
         // Updating last_run_at/next_run_at is internal only (update_schedule_last_run).
         if sets.is_empty() {
             return self.get_schedule(namespace, name).await;
@@ -1344,6 +2079,15 @@ impl WorkflowStore for SqliteStore {
         if let Some(ref v) = patch.overlap_policy {
             q = q.bind(v);
         }
+        if let Some(v) = patch.catchup_window_secs {
+            q = q.bind(v);
+        }
+        if let Some(ref v) = patch.catchup_policy {
+            q = q.bind(v);
+        }
+        if let Some(v) = patch.jitter_secs {
+            q = q.bind(v);
+        }
         let res = q.bind(namespace).bind(name).execute(&self.pool).await?;
         if res.rows_affected() == 0 {
             return Ok(None);
@@ -1371,6 +2115,100 @@ impl WorkflowStore for SqliteStore {
         self.get_schedule(namespace, name).await
     }
 
+    async fn record_schedule_action(&self, action: &ScheduleAction, keep: i64) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO workflow.schedule_actions (namespace, schedule_name, fire_time, taken_at, action, workflow_id, reason, backfill)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(&action.namespace)
+        .bind(&action.schedule_name)
+        .bind(action.fire_time)
+        .bind(action.taken_at)
+        .bind(&action.action)
+        .bind(&action.workflow_id)
+        .bind(&action.reason)
+        .bind(action.backfill)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM workflow.schedule_actions
+             WHERE namespace = ? AND schedule_name = ? AND action <> 'buffered'
+               AND id NOT IN (
+                   SELECT id FROM workflow.schedule_actions
+                   WHERE namespace = ? AND schedule_name = ? AND action <> 'buffered'
+                   ORDER BY id DESC LIMIT ?
+               )",
+        )
+        .bind(&action.namespace)
+        .bind(&action.schedule_name)
+        .bind(&action.namespace)
+        .bind(&action.schedule_name)
+        .bind(keep)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    async fn list_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+        limit: i64,
+    ) -> Result<Vec<ScheduleAction>> {
+        let rows = sqlx::query_as::<_, SqliteScheduleActionRow>(
+            "SELECT id, namespace, schedule_name, fire_time, taken_at, action, workflow_id, reason, backfill
+             FROM workflow.schedule_actions
+             WHERE namespace = ? AND schedule_name = ?
+             ORDER BY id DESC LIMIT ?",
+        )
+        .bind(namespace)
+        .bind(schedule_name)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn list_buffered_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+    ) -> Result<Vec<ScheduleAction>> {
+        let rows = sqlx::query_as::<_, SqliteScheduleActionRow>(
+            "SELECT id, namespace, schedule_name, fire_time, taken_at, action, workflow_id, reason, backfill
+             FROM workflow.schedule_actions
+             WHERE namespace = ? AND schedule_name = ? AND action = 'buffered'
+             ORDER BY fire_time ASC, id ASC",
+        )
+        .bind(namespace)
+        .bind(schedule_name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn resolve_buffered_schedule_action(
+        &self,
+        id: i64,
+        workflow_id: &str,
+        taken_at: f64,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE workflow.schedule_actions
+             SET action = 'started', workflow_id = ?, taken_at = ?, reason = NULL
+             WHERE id = ? AND action = 'buffered'",
+        )
+        .bind(workflow_id)
+        .bind(taken_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     // ── Workers ─────────────────────────────────────────────
 
     async fn register_worker(&self, w: &WorkflowWorker) -> Result<()> {
@@ -1487,6 +2325,24 @@ impl WorkflowStore for SqliteStore {
     // ── Queue Stats ─────────────────────────────────────────
 
     async fn get_queue_stats(&self, namespace: &str) -> Result<Vec<QueueStats>> {
+        let mut stats = std::collections::BTreeMap::new();
+
+        // Workflows waiting for a worker to claim their next task
+        let task_rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT task_queue, COUNT(*) FROM workflow.workflows
+             WHERE namespace = ?
+               AND needs_dispatch = 1
+               AND dispatch_claimed_by IS NULL
+               AND status NOT IN ('COMPLETED', 'FAILED', 'CANCELLED', 'TIMED_OUT')
+             GROUP BY task_queue",
+        )
+        .bind(namespace)
+        .fetch_all(&self.pool)
+        .await?;
+        for (queue, count) in task_rows {
+            queue_stats_entry(&mut stats, queue).pending_workflow_tasks = count;
+        }
+
         // Gather activity stats per queue for workflows in this namespace
         let rows = sqlx::query_as::<_, (String, i64, i64)>(
             "SELECT a.task_queue,
@@ -1500,16 +2356,11 @@ impl WorkflowStore for SqliteStore {
         .bind(namespace)
         .fetch_all(&self.pool)
         .await?;
-
-        let mut stats: Vec<QueueStats> = rows
-            .into_iter()
-            .map(|(queue, pending, running)| QueueStats {
-                queue,
-                pending_activities: pending,
-                running_activities: running,
-                workers: 0,
-            })
-            .collect();
+        for (queue, pending, running) in rows {
+            let s = queue_stats_entry(&mut stats, queue);
+            s.pending_activities = pending;
+            s.running_activities = running;
+        }
 
         // Gather worker counts per queue in this namespace
         let worker_rows = sqlx::query_as::<_, (String, i64)>(
@@ -1518,22 +2369,11 @@ impl WorkflowStore for SqliteStore {
         .bind(namespace)
         .fetch_all(&self.pool)
         .await?;
-
         for (queue, count) in worker_rows {
-            if let Some(s) = stats.iter_mut().find(|s| s.queue == queue) {
-                s.workers = count;
-            } else {
-                stats.push(QueueStats {
-                    queue,
-                    pending_activities: 0,
-                    running_activities: 0,
-                    workers: count,
-                });
-            }
+            queue_stats_entry(&mut stats, queue).workers = count;
         }
 
-        stats.sort_by(|a, b| a.queue.cmp(&b.queue));
-        Ok(stats)
+        Ok(stats.into_values().collect())
     }
 
     // ── Leader Election ─────────────────────────────────────
@@ -1736,6 +2576,111 @@ impl From<SqliteSignalRow> for WorkflowSignal {
     }
 }
 
+#[derive(sqlx::FromRow)]
+struct SqliteBatchJobRow {
+    id: String,
+    namespace: String,
+    operation: String,
+    filter: String,
+    params: Option<String>,
+    max_per_second: f64,
+    status: String,
+    total: i64,
+    processed: i64,
+    succeeded: i64,
+    failed: i64,
+    last_error: Option<String>,
+    created_at: f64,
+    updated_at: f64,
+    completed_at: Option<f64>,
+}
+
+impl From<SqliteBatchJobRow> for BatchJob {
+    fn from(r: SqliteBatchJobRow) -> Self {
+        Self {
+            id: r.id,
+            namespace: r.namespace,
+            operation: r.operation,
+            filter: r.filter,
+            params: r.params,
+            max_per_second: r.max_per_second,
+            status: r.status,
+            total: r.total,
+            processed: r.processed,
+            succeeded: r.succeeded,
+            failed: r.failed,
+            last_error: r.last_error,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+            completed_at: r.completed_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqliteSearchAttributeRow {
+    namespace: String,
+    name: String,
+    attr_type: String,
+    created_at: f64,
+}
+
+impl From<SqliteSearchAttributeRow> for SearchAttributeDef {
+    fn from(r: SqliteSearchAttributeRow) -> Self {
+        Self {
+            namespace: r.namespace,
+            name: r.name,
+            attr_type: r.attr_type.parse().unwrap_or(SearchAttributeType::Keyword),
+            created_at: r.created_at,
+        }
+    }
+}
+
+/// Bind a compiled visibility query's values, in placeholder order.
+fn bind_query_values<'q, O>(
+    mut q: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+    compiled: &'q CompiledQuery,
+) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+    for value in &compiled.binds {
+        q = match value {
+            QueryValue::String(s) => q.bind(s.as_str()),
+            QueryValue::Int(i) => q.bind(*i),
+            QueryValue::Double(d) => q.bind(*d),
+            QueryValue::Bool(b) => q.bind(*b),
+        };
+    }
+    q
+}
+
+#[derive(sqlx::FromRow)]
+struct SqliteUpdateRow {
+    id: String,
+    workflow_id: String,
+    name: String,
+    args: Option<String>,
+    status: String,
+    result: Option<String>,
+    error: Option<String>,
+    requested_at: f64,
+    completed_at: Option<f64>,
+}
+
+impl From<SqliteUpdateRow> for WorkflowUpdate {
+    fn from(r: SqliteUpdateRow) -> Self {
+        Self {
+            id: r.id,
+            workflow_id: r.workflow_id,
+            name: r.name,
+            args: r.args,
+            status: r.status,
+            result: r.result,
+            error: r.error,
+            requested_at: r.requested_at,
+            completed_at: r.completed_at,
+        }
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct SqliteScheduleRow {
     name: String,
@@ -1750,6 +2695,9 @@ struct SqliteScheduleRow {
     last_run_at: Option<f64>,
     next_run_at: Option<f64>,
     last_workflow_id: Option<String>,
+    catchup_window_secs: i64,
+    catchup_policy: String,
+    jitter_secs: i64,
     created_at: f64,
 }
 
@@ -1768,7 +2716,40 @@ impl From<SqliteScheduleRow> for WorkflowSchedule {
             last_run_at: r.last_run_at,
             next_run_at: r.next_run_at,
             last_workflow_id: r.last_workflow_id,
+            catchup_window_secs: r.catchup_window_secs,
+            catchup_policy: r.catchup_policy,
+            jitter_secs: r.jitter_secs,
             created_at: r.created_at,
+            recent_actions: Vec::new(),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqliteScheduleActionRow {
+    id: i64,
+    namespace: String,
+    schedule_name: String,
+    fire_time: f64,
+    taken_at: f64,
+    action: String,
+    workflow_id: Option<String>,
+    reason: Option<String>,
+    backfill: bool,
+}
+
+impl From<SqliteScheduleActionRow> for ScheduleAction {
+    fn from(r: SqliteScheduleActionRow) -> Self {
+        Self {
+            id: Some(r.id),
+            namespace: r.namespace,
+            schedule_name: r.schedule_name,
+            fire_time: r.fire_time,
+            taken_at: r.taken_at,
+            action: r.action,
+            workflow_id: r.workflow_id,
+            reason: r.reason,
+            backfill: r.backfill,
         }
     }
 }
@@ -0,0 +1,252 @@
+//! Compiles a [`VisibilityQuery`] to a SQL predicate over
+//! `workflow.workflows` for either backend.
+//!
+//! Search attributes live in the `search_attributes` JSON column. Every
+//! attribute predicate is guarded by a JSON type check so a workflow
+//! whose attribute holds a value of the wrong type simply doesn't match
+//! (instead of SQLite comparing across storage classes or Postgres
+//! failing a cast). Attribute names are validated identifiers at
+//! registration time, so they're inlined into the JSON path.
+
+use crate::types::{QueryField, QueryValue, SearchAttributeType, VisibilityQuery};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    Sqlite,
+    Postgres,
+}
+
+/// A compiled predicate plus its bind values, in placeholder order.
+pub(crate) struct CompiledQuery {
+    pub sql: Option<String>,
+    pub binds: Vec<QueryValue>,
+    /// First placeholder number after this query's binds (Postgres).
+    pub next_param: usize,
+}
+
+impl CompiledQuery {
+    /// ` AND (<predicate>)` to append to a `WHERE`, or empty when the
+    /// query matches everything.
+    pub fn and_clause(&self) -> String {
+        match &self.sql {
+            Some(sql) => format!(" AND ({sql})"),
+            None => String::new(),
+        }
+    }
+}
+
+/// Compile `query` (`None` matches everything). Postgres placeholders
+/// are numbered from `first_param`; SQLite uses positional `?`.
+pub(crate) fn compile(
+    query: Option<&VisibilityQuery>,
+    dialect: Dialect,
+    first_param: usize,
+) -> CompiledQuery {
+    let mut c = Compiler {
+        dialect,
+        next_param: first_param,
+        binds: Vec::new(),
+    };
+    let sql = query.map(|q| c.node(q));
+    CompiledQuery {
+        sql,
+        binds: c.binds,
+        next_param: c.next_param,
+    }
+}
+
+/// Expression indexed for a registered attribute. Queries compile to the
+/// same expression so the planner can use the index.
+pub(crate) fn search_attribute_expr(dialect: Dialect, name: &str) -> String {
+    match dialect {
+        Dialect::Sqlite => format!("json_extract(search_attributes, '$.{name}')"),
+        Dialect::Postgres => format!("((search_attributes::jsonb) -> '{name}')"),
+    }
+}
+
+struct Compiler {
+    dialect: Dialect,
+    next_param: usize,
+    binds: Vec<QueryValue>,
+}
+
+impl Compiler {
+    fn node(&mut self, query: &VisibilityQuery) -> String {
+        match query {
+            VisibilityQuery::And(parts) => self.join(parts, " AND "),
+            VisibilityQuery::Or(parts) => self.join(parts, " OR "),
+            VisibilityQuery::Not(inner) => format!("NOT ({})", self.node(inner)),
+            VisibilityQuery::Compare { field, op, value } => {
+                let lhs = self.field(field);
+                let rhs = self.param(field, value.clone());
+                self.guarded(field, format!("{lhs} {} {rhs}", op.sql()))
+            }
+            VisibilityQuery::In {
+                field,
+                values,
+                negated,
+            } => {
+                let lhs = self.field(field);
+                let params: Vec<String> = values
+                    .iter()
+                    .map(|v| self.param(field, v.clone()))
+                    .collect();
+                let not = if *negated { "NOT " } else { "" };
+                self.guarded(field, format!("{lhs} {not}IN ({})", params.join(", ")))
+            }
+            VisibilityQuery::IsNull { field, negated } => {
+                let op = if *negated { "<>" } else { "=" };
+                match field {
+                    QueryField::Workflow(f) => {
+                        let not = if *negated { "NOT " } else { "" };
+                        format!("{} IS {not}NULL", f.column())
+                    }
+                    QueryField::SearchAttribute { name, .. } => {
+                        format!("COALESCE({}, 'null') {op} 'null'", self.json_type(name))
+                    }
+                }
+            }
+        }
+    }
+
+    fn join(&mut self, parts: &[VisibilityQuery], sep: &str) -> String {
+        let compiled: Vec<String> = parts
+            .iter()
+            .map(|p| format!("({})", self.node(p)))
+            .collect();
+        compiled.join(sep)
+    }
+
+    fn field(&self, field: &QueryField) -> String {
+        match field {
+            QueryField::Workflow(f) => f.column().to_string(),
+            QueryField::SearchAttribute { name, .. } => search_attribute_expr(self.dialect, name),
+        }
+    }
+
+    /// Wrap an attribute predicate in its JSON type check; builtin
+    /// columns need none. The guard is never NULL, so a missing
+    /// attribute makes the predicate false and `NOT` behaves.
+    fn guarded(&self, field: &QueryField, predicate: String) -> String {
+        let QueryField::SearchAttribute { name, attr_type } = field else {
+            return predicate;
+        };
+        let allowed = match (self.dialect, attr_type) {
+            (Dialect::Sqlite, SearchAttributeType::Keyword | SearchAttributeType::Datetime) => {
+                "('text')"
+            }
+            (Dialect::Sqlite, SearchAttributeType::Int | SearchAttributeType::Double) => {
+                "('integer', 'real')"
+            }
+            (Dialect::Sqlite, SearchAttributeType::Bool) => "('true', 'false')",
+            (Dialect::Postgres, SearchAttributeType::Keyword | SearchAttributeType::Datetime) => {
+                "('string')"
+            }
+            (Dialect::Postgres, SearchAttributeType::Int | SearchAttributeType::Double) => {
+                "('number')"
+            }
+            (Dialect::Postgres, SearchAttributeType::Bool) => "('boolean')",
+        };
+        format!(
+            "COALESCE({}, 'null') IN {allowed} AND {predicate}",
+            self.json_type(name)
+        )
+    }
+
+    fn json_type(&self, name: &str) -> String {
+        match self.dialect {
+            Dialect::Sqlite => format!("json_type(search_attributes, '$.{name}')"),
+            Dialect::Postgres => format!("jsonb_typeof((search_attributes::jsonb) -> '{name}')"),
+        }
+    }
+
+    /// Emit a placeholder for `value`. Postgres attribute comparisons are
+    /// jsonb-to-jsonb, so the bind is wrapped in `to_jsonb`; SQLite's
+    /// `json_extract` already yields native values (booleans as 0/1).
+    fn param(&mut self, field: &QueryField, value: QueryValue) -> String {
+        let placeholder = match self.dialect {
+            Dialect::Sqlite => "?".to_string(),
+            Dialect::Postgres => {
+                let n = self.next_param;
+                self.next_param += 1;
+                let cast = match value {
+                    QueryValue::String(_) => "TEXT",
+                    QueryValue::Int(_) => "BIGINT",
+                    QueryValue::Double(_) => "DOUBLE PRECISION",
+                    QueryValue::Bool(_) => "BOOLEAN",
+                };
+                match field {
+                    QueryField::Workflow(_) => format!("${n}::{cast}"),
+                    QueryField::SearchAttribute { .. } => format!("to_jsonb(${n}::{cast})"),
+                }
+            }
+        };
+        self.binds.push(value);
+        placeholder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CompareOp, WorkflowField};
+
+    fn progress() -> QueryField {
+        QueryField::SearchAttribute {
+            name: "progress".into(),
+            attr_type: SearchAttributeType::Double,
+        }
+    }
+
+    #[test]
+    fn sqlite_guards_attribute_comparisons() {
+        let q = VisibilityQuery::And(vec![
+            VisibilityQuery::Compare {
+                field: QueryField::Workflow(WorkflowField::WorkflowType),
+                op: CompareOp::Eq,
+                value: QueryValue::String("deploy".into()),
+            },
+            VisibilityQuery::Compare {
+                field: progress(),
+                op: CompareOp::Gt,
+                value: QueryValue::Double(0.5),
+            },
+        ]);
+        let c = compile(Some(&q), Dialect::Sqlite, 1);
+        assert_eq!(
+            c.and_clause(),
+            " AND ((workflow_type = ?) AND (COALESCE(json_type(search_attributes, '$.progress'), 'null') IN ('integer', 'real') AND json_extract(search_attributes, '$.progress') > ?))"
+        );
+        assert_eq!(c.binds.len(), 2);
+    }
+
+    #[test]
+    fn postgres_numbers_placeholders_from_first_param() {
+        let q = VisibilityQuery::Or(vec![
+            VisibilityQuery::In {
+                field: QueryField::Workflow(WorkflowField::Status),
+                values: vec![
+                    QueryValue::String("RUNNING".into()),
+                    QueryValue::String("FAILED".into()),
+                ],
+                negated: true,
+            },
+            VisibilityQuery::IsNull {
+                field: progress(),
+                negated: false,
+            },
+            VisibilityQuery::Compare {
+                field: progress(),
+                op: CompareOp::Le,
+                value: QueryValue::Double(1.0),
+            },
+        ]);
+        let c = compile(Some(&q), Dialect::Postgres, 2);
+        assert_eq!(
+            c.sql.as_deref().unwrap(),
+            "(status NOT IN ($2::TEXT, $3::TEXT)) OR (COALESCE(jsonb_typeof((search_attributes::jsonb) -> 'progress'), 'null') = 'null') OR (COALESCE(jsonb_typeof((search_attributes::jsonb) -> 'progress'), 'null') IN ('number') AND ((search_attributes::jsonb) -> 'progress') <= to_jsonb($4::DOUBLE PRECISION))"
+        );
+        assert_eq!(c.binds.len(), 3);
+        assert_eq!(c.next_param, 5);
+    }
+}
@@ -45,23 +45,40 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
             .await;
         }
         let history = self.store.list_events(&wf.id).await?;
+        // The latest event is what made the task dispatchable (start,
+        // timer fired, activity finished, signal, …).
+        let ready_at = history.last().map_or(wf.created_at, |e| e.timestamp);
+        self.metrics.dispatch_latency.observe(
+            &[wf.task_queue.as_str()],
+            (timestamp_now() - ready_at).max(0.0),
+        );
         Ok(Some((wf, history)))
     }
 
     /// Submit a worker's batch of commands for a workflow it claimed.
     /// Each command produces durable events / rows transactionally and
-    /// the dispatch lease is released on return.
+    /// the dispatch lease is released on return — except when the batch
+    /// fails the workflow task (see `fail_workflow_task`).
     ///
     /// Supported command types:
     /// - `ScheduleActivity` { seq, name, task_queue, input?, max_attempts?, ... }
+    /// - `CancelActivity`   { seq }
     /// - `CompleteWorkflow` { result }
     /// - `FailWorkflow`     { error }
+    /// - `AcceptUpdate`     { update_id, block_point }
+    /// - `CompleteUpdate`   { update_id, result?, error? }
+    /// - `RejectUpdate`     { update_id, error }
+    /// - `RecordVersionMarker` { change_id, version }
+    /// - `FailWorkflowTask` { error }
     pub async fn submit_workflow_commands(
         &self,
         workflow_id: &str,
         worker_id: &str,
         commands: &[serde_json::Value],
     ) -> Result<()> {
+        if let Some(reason) = self.detect_nondeterminism(workflow_id, commands).await? {
+            return self.fail_workflow_task(workflow_id, &reason).await;
+        }
         for cmd in commands {
             let cmd_type = cmd.get("type").and_then(|v| v.as_str()).unwrap_or("");
             match cmd_type {
@@ -94,6 +111,10 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
                     self.schedule_activity(workflow_id, seq, name, input.as_deref(), queue, opts)
                         .await?;
                 }
+                "CancelActivity" => {
+                    let seq = cmd.get("seq").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                    self.cancel_activity(workflow_id, seq).await?;
+                }
                 "CancelWorkflow" => {
                     // Worker acknowledged a cancellation — finalise.
                     self.finalise_cancellation(workflow_id).await?;
@@ -244,6 +265,51 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
                         .create_snapshot(workflow_id, event_seq, &state.to_string())
                         .await?;
                 }
+                "WaitForCondition" => {
+                    // Handler parked in `ctx:wait_condition`. Nothing to
+                    // persist — whatever changes the condition (an update
+                    // completing, a signal, a timer) re-dispatches the
+                    // workflow, and replay re-evaluates the predicate.
+                }
+                "AcceptUpdate" => {
+                    let update_id = cmd.get("update_id").and_then(|v| v.as_str()).unwrap_or("");
+                    let block_point = cmd.get("block_point").and_then(|v| v.as_i64()).unwrap_or(0);
+                    self.accept_update(workflow_id, update_id, block_point)
+                        .await?;
+                }
+                "CompleteUpdate" => {
+                    let update_id = cmd.get("update_id").and_then(|v| v.as_str()).unwrap_or("");
+                    let result = cmd
+                        .get("result")
+                        .filter(|v| !v.is_null())
+                        .map(|v| v.to_string());
+                    let error = cmd.get("error").and_then(|v| v.as_str());
+                    self.complete_update(workflow_id, update_id, result.as_deref(), error)
+                        .await?;
+                }
+                "RejectUpdate" => {
+                    let update_id = cmd.get("update_id").and_then(|v| v.as_str()).unwrap_or("");
+                    let error = cmd
+                        .get("error")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("update rejected");
+                    self.reject_update(workflow_id, update_id, error).await?;
+                }
+                "RecordVersionMarker" => {
+                    let change_id = cmd.get("change_id").and_then(|v| v.as_str()).unwrap_or("");
+                    let version = cmd.get("version").and_then(|v| v.as_i64()).unwrap_or(-1);
+                    self.record_version_marker(workflow_id, change_id, version)
+                        .await?;
+                }
+                "FailWorkflowTask" => {
+                    // The worker detected non-determinism while replaying.
+                    // Nothing else in the batch is trustworthy.
+                    let error = cmd
+                        .get("error")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("workflow task failed");
+                    return self.fail_workflow_task(workflow_id, error).await;
+                }
                 "CompleteWorkflow" => {
                     let result = cmd.get("result").map(|v| v.to_string());
                     self.complete_workflow(workflow_id, result.as_deref())
@@ -326,4 +392,118 @@ impl<S: WorkflowStore> WorkflowCtx<S> {
 
         Ok(timer)
     }
+
+    /// Unfired timers, and how many of them are already due — a
+    /// growing `overdue` means the timer poller is falling behind.
+    pub async fn get_timer_backlog(&self) -> Result<TimerBacklog> {
+        self.store.get_timer_backlog(timestamp_now()).await
+    }
+
+    /// Record which branch of a `ctx:get_version(change_id, ...)` call
+    /// this run took. Idempotent on `change_id` — the first decision
+    /// sticks for the life of the run, so replay always agrees with it.
+    pub async fn record_version_marker(
+        &self,
+        workflow_id: &str,
+        change_id: &str,
+        version: i64,
+    ) -> Result<()> {
+        let history = self.store.list_events(workflow_id).await?;
+        let already_recorded = history.iter().any(|e| {
+            e.event_type == "VersionMarkerRecorded"
+                && e.payload
+                    .as_deref()
+                    .and_then(|p| serde_json::from_str::<serde_json::Value>(p).ok())
+                    .is_some_and(|p| p["change_id"] == change_id)
+        });
+        if already_recorded {
+            return Ok(());
+        }
+        self.store
+            .append_event(&WorkflowEvent {
+                id: None,
+                workflow_id: workflow_id.to_string(),
+                seq: history.len() as i32 + 1,
+                event_type: "VersionMarkerRecorded".to_string(),
+                payload: Some(
+                    serde_json::json!({
+                        "change_id": change_id,
+                        "version": version,
+                    })
+                    .to_string(),
+                ),
+                timestamp: timestamp_now(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Reject a workflow task whose commands no longer match history.
+    ///
+    /// The run stays RUNNING and nothing from the batch is applied. The
+    /// dispatch lease is deliberately left held: re-dispatching straight
+    /// away would hand the same history to the same code and fail again
+    /// in a tight loop. Dispatch recovery releases the stale lease later,
+    /// by which time a fixed worker (or one on the previous version) can
+    /// pick the task up and carry on.
+    ///
+    /// A `WorkflowTaskFailed` event records the reason; repeated failures
+    /// with the same reason don't grow history.
+    pub async fn fail_workflow_task(&self, workflow_id: &str, error: &str) -> Result<()> {
+        tracing::warn!(workflow_id, "workflow task failed: {error}");
+        let history = self.store.list_events(workflow_id).await?;
+        let repeated = history.last().is_some_and(|e| {
+            e.event_type == "WorkflowTaskFailed"
+                && e.payload
+                    .as_deref()
+                    .and_then(|p| serde_json::from_str::<serde_json::Value>(p).ok())
+                    .is_some_and(|p| p["error"] == error)
+        });
+        if repeated {
+            return Ok(());
+        }
+        self.store
+            .append_event(&WorkflowEvent {
+                id: None,
+                workflow_id: workflow_id.to_string(),
+                seq: history.len() as i32 + 1,
+                event_type: "WorkflowTaskFailed".to_string(),
+                payload: Some(serde_json::json!({ "error": error }).to_string()),
+                timestamp: timestamp_now(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Engine-side non-determinism check, independent of the worker's own
+    /// replay checks: an activity seq that already exists must be
+    /// re-scheduled under the same name. A mismatch means the handler
+    /// code changed under an in-flight run without `ctx:get_version`.
+    async fn detect_nondeterminism(
+        &self,
+        workflow_id: &str,
+        commands: &[serde_json::Value],
+    ) -> Result<Option<String>> {
+        for cmd in commands {
+            if cmd.get("type").and_then(|v| v.as_str()) != Some("ScheduleActivity") {
+                continue;
+            }
+            let seq = cmd.get("seq").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+            let name = cmd.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            if let Some(existing) = self
+                .store
+                .get_activity_by_workflow_seq(workflow_id, seq)
+                .await?
+                && existing.name != name
+            {
+                return Ok(Some(format!(
+                    "non-deterministic workflow: activity seq {seq} was scheduled as \
+                     '{}' but the handler now schedules '{name}'; guard code changes \
+                     with ctx:get_version",
+                    existing.name
+                )));
+            }
+        }
+        Ok(None)
+    }
 }
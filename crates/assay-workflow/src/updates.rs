@@ -0,0 +1,305 @@
+//! Workflow updates: synchronous, validated, result-returning signals.
+//!
+//! Lifecycle of one update:
+//!
+//! 1. `request_update` inserts a `REQUESTED` row and marks the workflow
+//!    dispatchable. The HTTP caller then blocks in `wait_for_update`.
+//! 2. The next workflow task delivers pending updates alongside the
+//!    history. The worker replays the handler, runs the registered
+//!    validator + handler, and submits `AcceptUpdate` + `CompleteUpdate`
+//!    (or `RejectUpdate`) commands.
+//! 3. Accepted updates land in history as `UpdateAccepted` with the
+//!    `block_point` the handler was parked at, so replay re-applies the
+//!    update handler at the same place. `UpdateCompleted` carries the
+//!    result returned to the caller.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::ctx::{WorkflowCtx, timestamp_now};
+use crate::store::WorkflowStore;
+use crate::types::*;
+
+/// How often `wait_for_update` re-reads the update row. The row lives
+/// in the store (not in-process state) so a caller attached to one
+/// engine node sees an update resolved by a worker talking to another.
+const UPDATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A worker's update command named an update that isn't one of the
+/// commanding workflow's. The API answers 409.
+#[derive(Debug)]
+pub struct UpdateConflict(pub String);
+
+impl std::fmt::Display for UpdateConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UpdateConflict {}
+
+/// Outcome of `request_update`.
+#[derive(Clone, Debug)]
+pub enum RequestUpdateResult {
+    /// The update is recorded (or already was, for a repeated
+    /// `update_id`) and will be delivered to the next workflow task.
+    Requested(Box<WorkflowUpdate>),
+    NotFound,
+    /// The workflow has already finished; nothing can apply the update.
+    Terminal {
+        status: String,
+    },
+}
+
+impl<S: WorkflowStore> WorkflowCtx<S> {
+    /// Record an update request against a running workflow and wake a
+    /// worker for it. Idempotent on `update_id`: a retried request with
+    /// the same id returns the original row instead of queueing a second
+    /// update.
+    pub async fn request_update(
+        &self,
+        workflow_id: &str,
+        update_id: Option<&str>,
+        name: &str,
+        args: Option<&str>,
+    ) -> Result<RequestUpdateResult> {
+        let Some(wf) = self.store.get_workflow(workflow_id).await? else {
+            return Ok(RequestUpdateResult::NotFound);
+        };
+        if let Some(id) = update_id
+            && let Some(existing) = self.store.get_update(id).await?
+            && existing.workflow_id == workflow_id
+        {
+            return Ok(RequestUpdateResult::Requested(Box::new(existing)));
+        }
+        let status = WorkflowStatus::from_str(&wf.status).map_err(|e| anyhow::anyhow!(e))?;
+        if status.is_terminal() {
+            return Ok(RequestUpdateResult::Terminal { status: wf.status });
+        }
+
+        let update = WorkflowUpdate {
+            id: update_id.map_or_else(new_update_id, String::from),
+            workflow_id: workflow_id.to_string(),
+            name: name.to_string(),
+            args: args.map(String::from),
+            status: "REQUESTED".to_string(),
+            result: None,
+            error: None,
+            requested_at: timestamp_now(),
+            completed_at: None,
+        };
+        if !self.store.create_update(&update).await? {
+            // Lost a race with a concurrent request carrying the same id
+            // (or the id belongs to another workflow). Report whatever
+            // is stored under it.
+            let existing = self
+                .store
+                .get_update(&update.id)
+                .await?
+                .filter(|u| u.workflow_id == workflow_id)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("update id {} belongs to another workflow", update.id)
+                })?;
+            return Ok(RequestUpdateResult::Requested(Box::new(existing)));
+        }
+
+        self.mark_and_emit_needs_dispatch(workflow_id).await?;
+        Ok(RequestUpdateResult::Requested(Box::new(update)))
+    }
+
+    pub async fn get_update(&self, update_id: &str) -> Result<Option<WorkflowUpdate>> {
+        self.store.get_update(update_id).await
+    }
+
+    pub async fn list_pending_updates(&self, workflow_id: &str) -> Result<Vec<WorkflowUpdate>> {
+        self.store.list_pending_updates(workflow_id).await
+    }
+
+    /// Block until the update reaches `COMPLETED` or `REJECTED`, or
+    /// `timeout` elapses. Returns the latest row either way; callers
+    /// check `status` to tell the two apart.
+    pub async fn wait_for_update(
+        &self,
+        update_id: &str,
+        timeout: Duration,
+    ) -> Result<Option<WorkflowUpdate>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let update = self.store.get_update(update_id).await?;
+            let done = update
+                .as_ref()
+                .is_none_or(|u| matches!(u.status.as_str(), "COMPLETED" | "REJECTED"));
+            if done || tokio::time::Instant::now() >= deadline {
+                return Ok(update);
+            }
+            tokio::time::sleep(UPDATE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// `AcceptUpdate` command: the validator passed. Appends
+    /// `UpdateAccepted` carrying the args and the block point the
+    /// handler was parked at, which replay uses to re-apply the update.
+    pub(crate) async fn accept_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        block_point: i64,
+    ) -> Result<()> {
+        if !self
+            .store
+            .transition_update(workflow_id, update_id, "REQUESTED", "ACCEPTED", None, None)
+            .await?
+        {
+            return self.check_update_owner(workflow_id, update_id).await;
+        }
+        let Some(update) = self.store.get_update(update_id).await? else {
+            return Ok(());
+        };
+        let args: serde_json::Value = update
+            .args
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or(serde_json::Value::Null);
+        self.append_update_event(
+            workflow_id,
+            "UpdateAccepted",
+            serde_json::json!({
+                "update_id": update_id,
+                "name": update.name,
+                "args": args,
+                "block_point": block_point,
+            }),
+        )
+        .await
+    }
+
+    /// `CompleteUpdate` command: the handler ran. `error` is set when the
+    /// handler raised after acceptance — the update still completes (its
+    /// side effects on workflow state replay either way).
+    ///
+    /// The workflow is re-dispatched so a handler parked in
+    /// `ctx:wait_condition` re-evaluates against the updated state.
+    pub(crate) async fn complete_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        if !self
+            .store
+            .transition_update(
+                workflow_id,
+                update_id,
+                "ACCEPTED",
+                "COMPLETED",
+                result,
+                error,
+            )
+            .await?
+        {
+            return self.check_update_owner(workflow_id, update_id).await;
+        }
+        let result_value: serde_json::Value = result
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or(serde_json::Value::Null);
+        self.append_update_event(
+            workflow_id,
+            "UpdateCompleted",
+            serde_json::json!({
+                "update_id": update_id,
+                "result": result_value,
+                "error": error,
+            }),
+        )
+        .await?;
+        self.mark_and_emit_needs_dispatch(workflow_id).await
+    }
+
+    /// `RejectUpdate` command: the validator refused, no handler was
+    /// registered, or the workflow finished before it could apply it.
+    /// Workflow state is untouched, so nothing is re-dispatched.
+    pub(crate) async fn reject_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        error: &str,
+    ) -> Result<()> {
+        if !self
+            .store
+            .transition_update(
+                workflow_id,
+                update_id,
+                "REQUESTED",
+                "REJECTED",
+                None,
+                Some(error),
+            )
+            .await?
+        {
+            return self.check_update_owner(workflow_id, update_id).await;
+        }
+        self.append_update_event(
+            workflow_id,
+            "UpdateRejected",
+            serde_json::json!({ "update_id": update_id, "error": error }),
+        )
+        .await
+    }
+
+    /// Reject every update still waiting on a workflow that just reached
+    /// a terminal state, so blocked callers return instead of timing out.
+    pub(crate) async fn reject_pending_updates(
+        &self,
+        workflow_id: &str,
+        reason: &str,
+    ) -> Result<()> {
+        for update in self.store.list_pending_updates(workflow_id).await? {
+            self.reject_update(workflow_id, &update.id, reason).await?;
+        }
+        Ok(())
+    }
+
+    /// A transition matched no row. Fine when the update is this
+    /// workflow's and already moved on (a worker replaying a batch after
+    /// a crash); an [`UpdateConflict`] when it's unknown or another
+    /// workflow's.
+    async fn check_update_owner(&self, workflow_id: &str, update_id: &str) -> Result<()> {
+        match self.store.get_update(update_id).await? {
+            Some(update) if update.workflow_id == workflow_id => Ok(()),
+            _ => Err(UpdateConflict(format!(
+                "update {update_id:?} does not belong to workflow {workflow_id}"
+            ))
+            .into()),
+        }
+    }
+
+    async fn append_update_event(
+        &self,
+        workflow_id: &str,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<()> {
+        let seq = self.store.get_event_count(workflow_id).await? as i32 + 1;
+        self.store
+            .append_event(&WorkflowEvent {
+                id: None,
+                workflow_id: workflow_id.to_string(),
+                seq,
+                event_type: event_type.to_string(),
+                payload: Some(payload.to_string()),
+                timestamp: timestamp_now(),
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Engine-assigned update id for callers that don't supply one. UUIDv7:
+/// unique across engine nodes, and time-ordered, which keeps the
+/// `(requested_at, id)` tie-break stable.
+fn new_update_id() -> String {
+    format!("upd-{}", uuid::Uuid::now_v7())
+}
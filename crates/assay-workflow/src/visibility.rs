@@ -0,0 +1,722 @@
+//! Visibility queries: a small SQL-like filter language for listing and
+//! counting workflows, plus typed search attribute registration.
+//!
+//! ```text
+//! WorkflowType = 'deploy' AND status IN ('RUNNING', 'FAILED')
+//!   AND search.progress > 0.5 AND created_at > '2026-10-01'
+//! ```
+//!
+//! Predicates compare a field with a literal (`= != <> < <= > >=`), test
+//! membership (`[NOT] IN (...)`), ranges (`BETWEEN a AND b`) or presence
+//! (`IS [NOT] NULL`), and combine with `AND`, `OR`, `NOT` and parentheses.
+//! Keywords are case-insensitive; strings are single-quoted with `''` as
+//! the escape. Fields are the workflow columns below or `search.<name>`
+//! for an attribute registered on the namespace — registration fixes the
+//! attribute's type, so literals are checked here and each store only
+//! has to compile a well-typed tree (see `store::visibility`).
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+
+use crate::ctx::{WorkflowCtx, timestamp_now};
+use crate::store::WorkflowStore;
+use crate::types::*;
+
+/// Deepest nesting of parentheses / `NOT` a query may use.
+const MAX_DEPTH: usize = 32;
+
+/// Longest search attribute name. Keeps `idx_wf_sa_<name>` within
+/// Postgres' 63-byte identifier limit.
+const MAX_ATTRIBUTE_NAME_LEN: usize = 48;
+
+/// Outcome of parsing a `?query=` string against a namespace.
+#[derive(Debug)]
+pub enum ParsedQuery {
+    /// `None` when the query is blank (matches everything).
+    Valid(Option<VisibilityQuery>),
+    Invalid(String),
+}
+
+#[derive(Debug)]
+pub enum RegisterSearchAttributeResult {
+    Created(SearchAttributeDef),
+    /// Already registered with the same type; registration is idempotent.
+    Exists(SearchAttributeDef),
+    /// Already registered with a different type.
+    Conflict(SearchAttributeDef),
+    Invalid(String),
+}
+
+impl<S: WorkflowStore> WorkflowCtx<S> {
+    /// Parse and type-check `query` against the namespace's registered
+    /// search attributes.
+    pub async fn parse_visibility_query(
+        &self,
+        namespace: &str,
+        query: &str,
+    ) -> Result<ParsedQuery> {
+        if query.trim().is_empty() {
+            return Ok(ParsedQuery::Valid(None));
+        }
+        let attributes = self.store.list_search_attributes(namespace).await?;
+        Ok(match parse_query(query, &attributes) {
+            Ok(q) => ParsedQuery::Valid(Some(q)),
+            Err(msg) => ParsedQuery::Invalid(msg),
+        })
+    }
+
+    pub async fn query_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WorkflowRecord>> {
+        self.store
+            .query_workflows(namespace, query, limit, offset)
+            .await
+    }
+
+    pub async fn count_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> Result<i64> {
+        self.store.count_workflows(namespace, query).await
+    }
+
+    pub async fn count_workflows_by_status(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> Result<Vec<WorkflowStatusCount>> {
+        self.store.count_workflows_by_status(namespace, query).await
+    }
+
+    pub async fn register_search_attribute(
+        &self,
+        namespace: &str,
+        name: &str,
+        attr_type: SearchAttributeType,
+    ) -> Result<RegisterSearchAttributeResult> {
+        if !is_identifier(name) || name.len() > MAX_ATTRIBUTE_NAME_LEN {
+            return Ok(RegisterSearchAttributeResult::Invalid(format!(
+                "search attribute name {name:?} must match [A-Za-z_][A-Za-z0-9_]* and be at most {MAX_ATTRIBUTE_NAME_LEN} characters"
+            )));
+        }
+        let def = SearchAttributeDef {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            attr_type,
+            created_at: timestamp_now(),
+        };
+        if self.store.create_search_attribute(&def).await? {
+            return Ok(RegisterSearchAttributeResult::Created(def));
+        }
+        let existing = self
+            .store
+            .list_search_attributes(namespace)
+            .await?
+            .into_iter()
+            .find(|d| d.name == name)
+            .ok_or_else(|| {
+                anyhow::anyhow!("search attribute {name} vanished during registration")
+            })?;
+        Ok(if existing.attr_type == attr_type {
+            RegisterSearchAttributeResult::Exists(existing)
+        } else {
+            RegisterSearchAttributeResult::Conflict(existing)
+        })
+    }
+
+    pub async fn list_search_attributes(&self, namespace: &str) -> Result<Vec<SearchAttributeDef>> {
+        self.store.list_search_attributes(namespace).await
+    }
+}
+
+/// Parse `input` into a type-checked query. `attributes` are the search
+/// attributes registered on the namespace being queried.
+pub fn parse_query(
+    input: &str,
+    attributes: &[SearchAttributeDef],
+) -> Result<VisibilityQuery, String> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: input.len(),
+        depth: 0,
+        attributes,
+    };
+    let query = parser.or()?;
+    if let Some((tok, at)) = parser.tokens.get(parser.pos) {
+        return Err(format!("unexpected {} at position {at}", tok.describe()));
+    }
+    Ok(query)
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn builtin_field(name: &str) -> Option<WorkflowField> {
+    Some(match name {
+        "WorkflowId" | "workflow_id" | "id" => WorkflowField::WorkflowId,
+        "RunId" | "run_id" => WorkflowField::RunId,
+        "WorkflowType" | "workflow_type" => WorkflowField::WorkflowType,
+        "ExecutionStatus" | "status" => WorkflowField::Status,
+        "TaskQueue" | "task_queue" => WorkflowField::TaskQueue,
+        "ParentWorkflowId" | "parent_id" => WorkflowField::ParentId,
+        "StartTime" | "created_at" => WorkflowField::CreatedAt,
+        "updated_at" => WorkflowField::UpdatedAt,
+        "CloseTime" | "completed_at" => WorkflowField::CompletedAt,
+        _ => return None,
+    })
+}
+
+// ── Lexer ───────────────────────────────────────────────────
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Self::Ident(s) => format!("'{s}'"),
+            Self::Str(s) => format!("string '{s}'"),
+            Self::Num(s) => format!("number {s}"),
+            Self::Op(op) => format!("'{}'", op.sql()),
+            Self::LParen => "'('".into(),
+            Self::RParen => "')'".into(),
+            Self::Comma => "','".into(),
+        }
+    }
+
+    fn is_keyword(&self, kw: &str) -> bool {
+        matches!(self, Self::Ident(s) if s.eq_ignore_ascii_case(kw))
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, String> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let tok = match c {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                i += 1;
+                continue;
+            }
+            b'(' => {
+                i += 1;
+                Token::LParen
+            }
+            b')' => {
+                i += 1;
+                Token::RParen
+            }
+            b',' => {
+                i += 1;
+                Token::Comma
+            }
+            b'=' => {
+                i += 1;
+                Token::Op(CompareOp::Eq)
+            }
+            b'!' if bytes.get(i + 1) == Some(&b'=') => {
+                i += 2;
+                Token::Op(CompareOp::Ne)
+            }
+            b'<' => match bytes.get(i + 1) {
+                Some(b'=') => {
+                    i += 2;
+                    Token::Op(CompareOp::Le)
+                }
+                Some(b'>') => {
+                    i += 2;
+                    Token::Op(CompareOp::Ne)
+                }
+                _ => {
+                    i += 1;
+                    Token::Op(CompareOp::Lt)
+                }
+            },
+            b'>' => {
+                if bytes.get(i + 1) == Some(&b'=') {
+                    i += 2;
+                    Token::Op(CompareOp::Ge)
+                } else {
+                    i += 1;
+                    Token::Op(CompareOp::Gt)
+                }
+            }
+            b'\'' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    let Some(rest) = input.get(i..) else {
+                        return Err(format!("unterminated string at position {start}"));
+                    };
+                    let Some(quote) = rest.find('\'') else {
+                        return Err(format!("unterminated string at position {start}"));
+                    };
+                    value.push_str(&rest[..quote]);
+                    i += quote + 1;
+                    if bytes.get(i) == Some(&b'\'') {
+                        value.push('\'');
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                Token::Str(value)
+            }
+            b'-' | b'0'..=b'9' => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                let text = &input[start..i];
+                if text.parse::<f64>().is_err() {
+                    return Err(format!("invalid number '{text}' at position {start}"));
+                }
+                Token::Num(text.to_string())
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'.')
+                {
+                    i += 1;
+                }
+                Token::Ident(input[start..i].to_string())
+            }
+            _ => {
+                let ch = input[start..].chars().next().unwrap_or_default();
+                return Err(format!("unexpected character '{ch}' at position {start}"));
+            }
+        };
+        tokens.push((tok, start));
+    }
+    Ok(tokens)
+}
+
+// ── Parser ──────────────────────────────────────────────────
+
+enum Literal {
+    Str(String),
+    Num(String),
+    Bool(bool),
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize,
+    depth: usize,
+    attributes: &'a [SearchAttributeDef],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, at)| *at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        tok
+    }
+
+    fn eat_keyword(&mut self, kw: &str) -> bool {
+        if self.peek().is_some_and(|t| t.is_keyword(kw)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, want: Token, what: &str) -> Result<(), String> {
+        let at = self.position();
+        match self.next() {
+            Some(t) if t == want => Ok(()),
+            Some(t) => Err(format!(
+                "expected {what} at position {at}, found {}",
+                t.describe()
+            )),
+            None => Err(format!("expected {what} at end of query")),
+        }
+    }
+
+    fn or(&mut self) -> Result<VisibilityQuery, String> {
+        let mut parts = vec![self.and()?];
+        while self.eat_keyword("OR") {
+            parts.push(self.and()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            VisibilityQuery::Or(parts)
+        })
+    }
+
+    fn and(&mut self) -> Result<VisibilityQuery, String> {
+        let mut parts = vec![self.unary()?];
+        while self.eat_keyword("AND") {
+            parts.push(self.unary()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            VisibilityQuery::And(parts)
+        })
+    }
+
+    fn unary(&mut self) -> Result<VisibilityQuery, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(format!("query nests deeper than {MAX_DEPTH} levels"));
+        }
+        let result = if self.eat_keyword("NOT") {
+            self.unary().map(|q| VisibilityQuery::Not(Box::new(q)))
+        } else if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            self.or()
+                .and_then(|q| self.expect(Token::RParen, "')'").map(|()| q))
+        } else {
+            self.predicate()
+        };
+        self.depth -= 1;
+        result
+    }
+
+    fn predicate(&mut self) -> Result<VisibilityQuery, String> {
+        let at = self.position();
+        let (field, name) = match self.next() {
+            Some(Token::Ident(name)) => (self.resolve_field(&name, at)?, name),
+            Some(t) => {
+                return Err(format!(
+                    "expected a field name at position {at}, found {}",
+                    t.describe()
+                ));
+            }
+            None => return Err("expected a field name at end of query".into()),
+        };
+
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            if !self.eat_keyword("NULL") {
+                return Err(format!("expected NULL at position {}", self.position()));
+            }
+            return Ok(VisibilityQuery::IsNull { field, negated });
+        }
+        let negated = self.eat_keyword("NOT");
+        if self.eat_keyword("IN") {
+            self.expect(Token::LParen, "'('")?;
+            let mut values = vec![self.value(&field, &name)?];
+            while self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+                values.push(self.value(&field, &name)?);
+            }
+            self.expect(Token::RParen, "')'")?;
+            return Ok(VisibilityQuery::In {
+                field,
+                values,
+                negated,
+            });
+        }
+        if self.eat_keyword("BETWEEN") {
+            let low = self.value(&field, &name)?;
+            if !self.eat_keyword("AND") {
+                return Err(format!("expected AND at position {}", self.position()));
+            }
+            let high = self.value(&field, &name)?;
+            self.check_ordered(&field, &name)?;
+            let range = VisibilityQuery::And(vec![
+                VisibilityQuery::Compare {
+                    field: field.clone(),
+                    op: CompareOp::Ge,
+                    value: low,
+                },
+                VisibilityQuery::Compare {
+                    field,
+                    op: CompareOp::Le,
+                    value: high,
+                },
+            ]);
+            return Ok(if negated {
+                VisibilityQuery::Not(Box::new(range))
+            } else {
+                range
+            });
+        }
+        if negated {
+            return Err(format!(
+                "expected IN or BETWEEN after NOT at position {}",
+                self.position()
+            ));
+        }
+
+        let at = self.position();
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            Some(t) => {
+                return Err(format!(
+                    "expected an operator after {name} at position {at}, found {}",
+                    t.describe()
+                ));
+            }
+            None => return Err(format!("expected an operator after {name} at end of query")),
+        };
+        if !matches!(op, CompareOp::Eq | CompareOp::Ne) {
+            self.check_ordered(&field, &name)?;
+        }
+        let value = self.value(&field, &name)?;
+        Ok(VisibilityQuery::Compare { field, op, value })
+    }
+
+    fn resolve_field(&self, name: &str, at: usize) -> Result<QueryField, String> {
+        if let Some(attr) = name.strip_prefix("search.") {
+            return self
+                .attributes
+                .iter()
+                .find(|d| d.name == attr)
+                .map(|d| QueryField::SearchAttribute {
+                    name: d.name.clone(),
+                    attr_type: d.attr_type,
+                })
+                .ok_or_else(|| {
+                    format!("search attribute '{attr}' is not registered (position {at})")
+                });
+        }
+        builtin_field(name)
+            .map(QueryField::Workflow)
+            .ok_or_else(|| format!("unknown field '{name}' at position {at}"))
+    }
+
+    /// Range operators make no sense on booleans.
+    fn check_ordered(&self, field: &QueryField, name: &str) -> Result<(), String> {
+        match field {
+            QueryField::SearchAttribute {
+                attr_type: SearchAttributeType::Bool,
+                ..
+            } => Err(format!("{name} is a bool attribute; only = and != apply")),
+            _ => Ok(()),
+        }
+    }
+
+    fn value(&mut self, field: &QueryField, name: &str) -> Result<QueryValue, String> {
+        let at = self.position();
+        let literal = match self.next() {
+            Some(Token::Str(s)) => Literal::Str(s),
+            Some(Token::Num(n)) => Literal::Num(n),
+            Some(t) if t.is_keyword("TRUE") => Literal::Bool(true),
+            Some(t) if t.is_keyword("FALSE") => Literal::Bool(false),
+            Some(t) => {
+                return Err(format!(
+                    "expected a value for {name} at position {at}, found {}",
+                    t.describe()
+                ));
+            }
+            None => return Err(format!("expected a value for {name} at end of query")),
+        };
+        coerce(field, literal).map_err(|e| format!("{name} at position {at}: {e}"))
+    }
+}
+
+/// Check a literal against the field's type and convert it to the
+/// representation the stores compare against.
+fn coerce(field: &QueryField, literal: Literal) -> Result<QueryValue, String> {
+    use SearchAttributeType as T;
+    match (field, literal) {
+        (QueryField::Workflow(WorkflowField::Status), Literal::Str(s)) => s
+            .to_ascii_uppercase()
+            .parse::<WorkflowStatus>()
+            .map(|st| QueryValue::String(st.to_string())),
+        (
+            QueryField::Workflow(
+                WorkflowField::CreatedAt | WorkflowField::UpdatedAt | WorkflowField::CompletedAt,
+            ),
+            literal,
+        ) => match literal {
+            Literal::Str(s) => parse_time(&s).map(|t| QueryValue::Double(epoch_secs(&t))),
+            Literal::Num(n) => Ok(QueryValue::Double(n.parse().unwrap_or_default())),
+            Literal::Bool(_) => Err("expected a timestamp".into()),
+        },
+        (QueryField::Workflow(_), Literal::Str(s)) => Ok(QueryValue::String(s)),
+        (QueryField::Workflow(_), _) => Err("expected a quoted string".into()),
+        (QueryField::SearchAttribute { attr_type, .. }, literal) => match (attr_type, literal) {
+            (T::Keyword, Literal::Str(s)) => Ok(QueryValue::String(s)),
+            (T::Int, Literal::Num(n)) => n
+                .parse::<i64>()
+                .map(QueryValue::Int)
+                .map_err(|_| format!("expected an integer, got {n}")),
+            (T::Double, Literal::Num(n)) => Ok(QueryValue::Double(n.parse().unwrap_or_default())),
+            (T::Bool, Literal::Bool(b)) => Ok(QueryValue::Bool(b)),
+            (T::Datetime, Literal::Str(s)) => parse_time(&s)
+                .map(|t| QueryValue::String(t.to_rfc3339_opts(SecondsFormat::Secs, true))),
+            (t, _) => Err(format!("expected a {t} value")),
+        },
+    }
+}
+
+/// Accepts RFC 3339, `YYYY-MM-DD HH:MM:SS` (UTC) or a bare date
+/// (midnight UTC).
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Ok(t.and_utc());
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Err(format!(
+        "invalid timestamp '{s}' (expected RFC 3339 or YYYY-MM-DD)"
+    ))
+}
+
+fn epoch_secs(t: &DateTime<Utc>) -> f64 {
+    t.timestamp() as f64 + f64::from(t.timestamp_subsec_nanos()) / 1e9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs() -> Vec<SearchAttributeDef> {
+        [
+            ("env", SearchAttributeType::Keyword),
+            ("progress", SearchAttributeType::Double),
+            ("attempts", SearchAttributeType::Int),
+            ("urgent", SearchAttributeType::Bool),
+            ("deadline", SearchAttributeType::Datetime),
+        ]
+        .into_iter()
+        .map(|(name, attr_type)| SearchAttributeDef {
+            namespace: "main".into(),
+            name: name.into(),
+            attr_type,
+            created_at: 0.0,
+        })
+        .collect()
+    }
+
+    fn parse(q: &str) -> Result<VisibilityQuery, String> {
+        parse_query(q, &attrs())
+    }
+
+    #[test]
+    fn parses_the_documented_example() {
+        let q = parse(
+            "WorkflowType = 'deploy' AND status IN ('RUNNING','failed') AND search.progress > 0.5 AND created_at > '2026-10-01'",
+        )
+        .unwrap();
+        let VisibilityQuery::And(parts) = q else {
+            panic!("expected AND, got {q:?}");
+        };
+        assert_eq!(parts.len(), 4);
+        assert_eq!(
+            parts[1],
+            VisibilityQuery::In {
+                field: QueryField::Workflow(WorkflowField::Status),
+                values: vec![
+                    QueryValue::String("RUNNING".into()),
+                    QueryValue::String("FAILED".into()),
+                ],
+                negated: false,
+            }
+        );
+        assert_eq!(
+            parts[3],
+            VisibilityQuery::Compare {
+                field: QueryField::Workflow(WorkflowField::CreatedAt),
+                op: CompareOp::Gt,
+                value: QueryValue::Double(1_790_812_800.0),
+            }
+        );
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let q =
+            parse("search.env = 'a' OR search.env = 'b' AND NOT (search.urgent = true)").unwrap();
+        let VisibilityQuery::Or(parts) = q else {
+            panic!("expected OR, got {q:?}");
+        };
+        assert!(
+            matches!(&parts[1], VisibilityQuery::And(and) if matches!(and[1], VisibilityQuery::Not(_)))
+        );
+    }
+
+    #[test]
+    fn keywords_are_case_insensitive_and_strings_escape_quotes() {
+        let q = parse("search.env not in ('it''s') and parent_id is null").unwrap();
+        let VisibilityQuery::And(parts) = q else {
+            panic!("expected AND, got {q:?}");
+        };
+        assert_eq!(
+            parts[0],
+            VisibilityQuery::In {
+                field: QueryField::SearchAttribute {
+                    name: "env".into(),
+                    attr_type: SearchAttributeType::Keyword,
+                },
+                values: vec![QueryValue::String("it's".into())],
+                negated: true,
+            }
+        );
+    }
+
+    #[test]
+    fn datetime_attributes_normalise_to_utc() {
+        let q =
+            parse("search.deadline BETWEEN '2026-10-01' AND '2026-10-02T12:00:00+02:00'").unwrap();
+        let VisibilityQuery::And(parts) = q else {
+            panic!("expected AND, got {q:?}");
+        };
+        assert!(matches!(
+            &parts[1],
+            VisibilityQuery::Compare { value: QueryValue::String(s), .. } if s == "2026-10-02T10:00:00Z"
+        ));
+    }
+
+    #[test]
+    fn rejects_ill_typed_or_malformed_queries() {
+        for (query, needle) in [
+            ("search.unknown = 'x'", "not registered"),
+            ("nope = 'x'", "unknown field"),
+            ("search.attempts = 1.5", "expected an integer"),
+            ("search.progress = 'high'", "expected a double value"),
+            ("search.urgent > true", "only = and != apply"),
+            ("status = 'SLEEPING'", "unknown workflow status"),
+            ("created_at > 'yesterday'", "invalid timestamp"),
+            ("WorkflowType = 'a' AND", "end of query"),
+            ("(WorkflowType = 'a'", "expected ')'"),
+            ("WorkflowType = 'a", "unterminated string"),
+            ("WorkflowType = 'a' 'b'", "unexpected string"),
+        ] {
+            let err = parse(query).unwrap_err();
+            assert!(err.contains(needle), "{query}: {err}");
+        }
+        let deep = format!("{}status = 'RUNNING'{}", "(".repeat(40), ")".repeat(40));
+        assert!(parse(&deep).unwrap_err().contains("nests deeper"));
+    }
+}
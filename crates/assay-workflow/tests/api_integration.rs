@@ -269,6 +269,81 @@ async fn start_and_list_workflows() {
     );
 }
 
+#[tokio::test]
+async fn start_honours_request_id_and_id_reuse_policy() {
+    let (url, _handle) = start_test_server().await;
+    let c = client();
+    let start = |body: serde_json::Value| {
+        c.post(format!("{url}/api/v1/engine/workflow/workflows"))
+            .json(&body)
+            .send()
+    };
+
+    let resp = start(serde_json::json!({
+        "workflow_type": "Webhook",
+        "workflow_id": "wf-reuse-1",
+        "request_id": "delivery-1",
+    }))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 201);
+    let first: serde_json::Value = resp.json().await.unwrap();
+
+    // Redelivery of the same webhook returns the existing run.
+    let resp = start(serde_json::json!({
+        "workflow_type": "Webhook",
+        "workflow_id": "wf-reuse-1",
+        "request_id": "delivery-1",
+    }))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 200);
+    let again: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(again["run_id"], first["run_id"]);
+
+    // Another start of the same id is a conflict, not a 500.
+    let resp = start(serde_json::json!({
+        "workflow_type": "Webhook",
+        "workflow_id": "wf-reuse-1",
+        "request_id": "delivery-2",
+    }))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 409);
+
+    let resp = start(serde_json::json!({
+        "workflow_type": "Webhook",
+        "workflow_id": "wf-reuse-1",
+        "id_reuse_policy": "sometimes",
+    }))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = start(serde_json::json!({
+        "workflow_type": "Webhook",
+        "workflow_id": "wf-reuse-1",
+        "request_id": "delivery-2",
+        "id_reuse_policy": "terminate_if_running",
+    }))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 201);
+    let second: serde_json::Value = resp.json().await.unwrap();
+    assert_ne!(second["run_id"], first["run_id"]);
+
+    // The terminated run is kept under its retired id.
+    let retired = format!("wf-reuse-1@{}", first["run_id"].as_str().unwrap());
+    let resp = c
+        .get(format!("{url}/api/v1/engine/workflow/workflows/{retired}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let old: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(old["status"], "FAILED");
+}
+
 #[tokio::test]
 async fn signal_and_cancel_workflow() {
     let (url, _handle) = start_test_server().await;
@@ -658,6 +733,202 @@ async fn schedule_patch_rejects_invalid_timezone() {
     assert_eq!(resp.status(), 500, "invalid timezone rejected");
 }
 
+/// 2026-01-01T00:00:00Z.
+const JAN_1_2026: f64 = 1_767_225_600.0;
+
+#[tokio::test]
+async fn schedule_backfill_starts_runs_and_records_history() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    let resp = c
+        .post(format!("{url}/api/v1/engine/workflow/schedules"))
+        .json(&serde_json::json!({
+            "name": "daily",
+            "workflow_type": "Rollup",
+            "cron_expr": "0 0 0 * * *",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(created["catchup_policy"], "one");
+    assert_eq!(created["jitter_secs"], 0);
+    assert_eq!(created["recent_actions"], serde_json::json!([]));
+
+    let backfill = |start: f64, end: f64, overlap: Option<&str>| {
+        let mut body = serde_json::json!({ "start_at": start, "end_at": end });
+        if let Some(o) = overlap {
+            body["overlap_policy"] = o.into();
+        }
+        c.post(format!(
+            "{url}/api/v1/engine/workflow/schedules/daily/backfill"
+        ))
+        .json(&body)
+        .send()
+    };
+
+    // Three midnights in [Jan 1, Jan 4): the end bound is exclusive.
+    let resp = backfill(JAN_1_2026, JAN_1_2026 + 3.0 * 86_400.0, Some("allow_all"))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["started"], 3);
+    assert_eq!(body["skipped"], 0);
+    let ids: Vec<&str> = body["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["workflow_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        ids,
+        vec!["daily-1767225600", "daily-1767312000", "daily-1767398400"]
+    );
+    let resp = c
+        .get(format!(
+            "{url}/api/v1/engine/workflow/workflows/daily-1767312000"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // The same range again maps onto the runs that already exist.
+    let body: serde_json::Value = backfill(JAN_1_2026, JAN_1_2026 + 3.0 * 86_400.0, None)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["started"], 0);
+    assert_eq!(body["skipped"], 3);
+
+    // Under the schedule's own `skip` policy, the last backfilled run is
+    // still pending, so both new fires are dropped.
+    let body: serde_json::Value = backfill(
+        JAN_1_2026 + 10.0 * 86_400.0,
+        JAN_1_2026 + 12.0 * 86_400.0,
+        None,
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(body["started"], 0);
+    assert_eq!(body["skipped"], 2);
+    assert!(
+        body["actions"][0]["reason"]
+            .as_str()
+            .unwrap()
+            .contains("still running")
+    );
+
+    let sched: serde_json::Value = c
+        .get(format!("{url}/api/v1/engine/workflow/schedules/daily"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let actions = sched["recent_actions"].as_array().unwrap();
+    assert_eq!(actions.len(), 8);
+    assert!(actions.iter().all(|a| a["backfill"] == true));
+    assert_eq!(
+        actions[0]["fire_time"], 1_768_176_000.0,
+        "newest action first"
+    );
+}
+
+#[tokio::test]
+async fn schedule_backfill_rejects_bad_requests() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    for (body, why) in [
+        (
+            serde_json::json!({ "catchup_policy": "sometimes" }),
+            "unknown catch-up policy",
+        ),
+        (
+            serde_json::json!({ "overlap_policy": "pile_up" }),
+            "unknown overlap policy",
+        ),
+        (serde_json::json!({ "jitter_secs": -5 }), "negative jitter"),
+    ] {
+        let mut req = serde_json::json!({
+            "name": "bad",
+            "workflow_type": "T",
+            "cron_expr": "0 0 * * * *",
+        });
+        req.as_object_mut()
+            .unwrap()
+            .extend(body.as_object().unwrap().clone());
+        let resp = c
+            .post(format!("{url}/api/v1/engine/workflow/schedules"))
+            .json(&req)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 400, "{why}");
+    }
+
+    c.post(format!("{url}/api/v1/engine/workflow/schedules"))
+        .json(&serde_json::json!({
+            "name": "every-second",
+            "workflow_type": "T",
+            "cron_expr": "* * * * * *",
+        }))
+        .send()
+        .await
+        .unwrap();
+    let backfill = |name: &str, body: serde_json::Value| {
+        c.post(format!(
+            "{url}/api/v1/engine/workflow/schedules/{name}/backfill"
+        ))
+        .json(&body)
+        .send()
+    };
+
+    let resp = backfill(
+        "every-second",
+        serde_json::json!({ "start_at": JAN_1_2026, "end_at": JAN_1_2026 }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 400, "empty range");
+
+    let resp = backfill(
+        "every-second",
+        serde_json::json!({ "start_at": JAN_1_2026, "end_at": JAN_1_2026 + 7_200.0 }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 400, "more fires than one backfill allows");
+
+    let resp = backfill(
+        "every-second",
+        serde_json::json!({
+            "start_at": JAN_1_2026,
+            "end_at": JAN_1_2026 + 10.0,
+            "overlap_policy": "pile_up",
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 400, "unknown overlap policy");
+
+    let resp = backfill(
+        "ghost",
+        serde_json::json!({ "start_at": JAN_1_2026, "end_at": JAN_1_2026 + 10.0 }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
 #[tokio::test]
 async fn version_endpoint_returns_shape() {
     let (url, _h) = start_test_server().await;
@@ -810,3 +1081,666 @@ async fn concurrent_retry_requests_schedule_the_failed_activity_once() {
     );
     handle.abort();
 }
+
+#[tokio::test]
+async fn reset_forks_a_new_run_before_the_first_failed_activity() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    prepare_failed_activity_workflow(&state, "wf-reset").await;
+    let app = assay_workflow::api::router(Arc::clone(&state), |router| router);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let reset_url =
+        format!("http://127.0.0.1:{port}/api/v1/engine/workflow/workflows/wf-reset/reset");
+
+    let response = client()
+        .post(&reset_url)
+        .json(&serde_json::json!({
+            "reset_type": "first_failed_activity",
+            "reason": "update_config bug fixed",
+            "workflow_id": "wf-reset-fixed",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["workflow_id"], "wf-reset-fixed");
+    assert_eq!(body["reset_from"], "wf-reset");
+    assert_eq!(body["reset_to_seq"], 1);
+    assert_eq!(body["events_copied"], 1);
+    // The source had already failed; there was nothing left to terminate.
+    assert_eq!(body["source_terminated"], false);
+
+    let new_run = state.get_workflow("wf-reset-fixed").await.unwrap().unwrap();
+    assert_eq!(new_run.status, "PENDING");
+    assert_eq!(new_run.workflow_type, "DeploymentWorkflow");
+    assert_eq!(new_run.task_queue, "deployments");
+    assert_ne!(new_run.run_id, "");
+    let new_events = state.get_events("wf-reset-fixed").await.unwrap();
+    let types: Vec<&str> = new_events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, ["WorkflowStarted", "WorkflowReset"]);
+    let reset_payload: serde_json::Value =
+        serde_json::from_str(new_events[1].payload.as_deref().unwrap()).unwrap();
+    assert_eq!(reset_payload["source_workflow_id"], "wf-reset");
+    assert_eq!(reset_payload["reason"], "update_config bug fixed");
+
+    let source_events = state.get_events("wf-reset").await.unwrap();
+    assert_eq!(source_events.last().unwrap().event_type, "WorkflowReset");
+
+    // Resetting into an id that's taken conflicts.
+    let response = client()
+        .post(&reset_url)
+        .json(&serde_json::json!({
+            "event_seq": 2,
+            "reason": "again",
+            "workflow_id": "wf-reset-fixed",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn reset_terminates_a_running_source() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    state
+        .start_workflow("main", "LongRun", "wf-reset-live", None, "q", None)
+        .await
+        .unwrap();
+    state
+        .schedule_activity("wf-reset-live", 1, "step", None, "q", Default::default())
+        .await
+        .unwrap();
+
+    let result = state
+        .reset_workflow(
+            "wf-reset-live",
+            assay_workflow::reset::ResetPoint::LastWorkflowTask,
+            "bad deploy",
+            None,
+            true,
+        )
+        .await
+        .unwrap();
+    let assay_workflow::reset::ResetResult::Reset {
+        reset,
+        reset_to_seq,
+        source_terminated,
+    } = result
+    else {
+        panic!("running workflow should reset")
+    };
+    assert!(source_terminated);
+    assert_eq!(reset_to_seq, 1, "the ActivityScheduled output is dropped");
+    assert!(reset.workflow.id.starts_with("wf-reset-live-reset-"));
+
+    let source = state.get_workflow("wf-reset-live").await.unwrap().unwrap();
+    assert_eq!(source.status, "FAILED");
+    let error = source.error.unwrap();
+    assert!(error.contains(&reset.workflow.id), "{error}");
+    assert!(error.contains("bad deploy"), "{error}");
+
+    // A second reset straight after gets its own run, not a collision.
+    let again = state
+        .reset_workflow(
+            "wf-reset-live",
+            assay_workflow::reset::ResetPoint::EventSeq(1),
+            "bad deploy, again",
+            None,
+            true,
+        )
+        .await
+        .unwrap();
+    let assay_workflow::reset::ResetResult::Reset { reset: again, .. } = again else {
+        panic!("a finished source can be reset again")
+    };
+    assert!(again.workflow.id.starts_with("wf-reset-live-reset-"));
+    assert_ne!(again.workflow.id, reset.workflow.id);
+}
+
+#[tokio::test]
+async fn reset_rejects_invalid_requests() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    state
+        .start_workflow("main", "LongRun", "wf-reset-live", None, "q", None)
+        .await
+        .unwrap();
+    let app = assay_workflow::api::router(Arc::clone(&state), |router| router);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let url =
+        |id: &str| format!("http://127.0.0.1:{port}/api/v1/engine/workflow/workflows/{id}/reset");
+    for (id, body, status) in [
+        (
+            "wf-reset-live",
+            serde_json::json!({ "event_seq": 1, "reason": " " }),
+            400,
+        ),
+        (
+            "wf-reset-live",
+            serde_json::json!({ "event_seq": 1, "reset_type": "last_workflow_task", "reason": "x" }),
+            400,
+        ),
+        (
+            "wf-reset-live",
+            serde_json::json!({ "reset_type": "nope", "reason": "x" }),
+            400,
+        ),
+        (
+            "wf-reset-live",
+            serde_json::json!({ "event_seq": 99, "reason": "x" }),
+            400,
+        ),
+        (
+            "wf-reset-live",
+            serde_json::json!({ "reset_type": "first_failed_activity", "reason": "x" }),
+            400,
+        ),
+        (
+            "ghost",
+            serde_json::json!({ "event_seq": 1, "reason": "x" }),
+            404,
+        ),
+    ] {
+        let response = client().post(url(id)).json(&body).send().await.unwrap();
+        assert_eq!(response.status(), status, "{id} {body}");
+    }
+    handle.abort();
+}
+
+/// Start the API over `state` and return its base URL.
+async fn serve_state(
+    state: &Arc<WorkflowCtx<SqliteStore>>,
+) -> (String, tokio::task::JoinHandle<()>) {
+    let app = assay_workflow::api::router(Arc::clone(state), |router| router);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://127.0.0.1:{port}"), handle)
+}
+
+fn with_params(url: &str, params: &[(&str, &str)]) -> reqwest::Url {
+    reqwest::Url::parse_with_params(url, params).unwrap()
+}
+
+/// Poll a batch job until it leaves RUNNING.
+async fn wait_for_batch(url: &str, id: &str) -> serde_json::Value {
+    for _ in 0..100 {
+        let job: serde_json::Value = client()
+            .get(format!("{url}/api/v1/engine/workflow/batch/{id}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if job["status"] != "RUNNING" {
+            return job;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("batch job {id} did not finish");
+}
+
+#[tokio::test]
+async fn batch_terminate_applies_to_every_matching_workflow() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    for i in 0..3 {
+        state
+            .start_workflow("main", "Stuck", &format!("wf-stuck-{i}"), None, "q", None)
+            .await
+            .unwrap();
+    }
+    state
+        .start_workflow("main", "Healthy", "wf-healthy", None, "q", None)
+        .await
+        .unwrap();
+    let (url, handle) = serve_state(&state).await;
+
+    let response = client()
+        .post(format!("{url}/api/v1/engine/workflow/batch"))
+        .json(&serde_json::json!({
+            "filter": { "type": "Stuck" },
+            "operation": { "type": "terminate", "reason": "incident 42 cleanup" },
+            "max_per_second": 100.0,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let job: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(job["operation"], "terminate");
+    assert_eq!(job["status"], "RUNNING");
+
+    let job = wait_for_batch(&url, job["id"].as_str().unwrap()).await;
+    assert_eq!(job["status"], "COMPLETED");
+    assert_eq!(job["total"], 3);
+    assert_eq!(job["processed"], 3);
+    assert_eq!(job["succeeded"], 3);
+    assert_eq!(job["failed"], 0);
+
+    for i in 0..3 {
+        let wf = state
+            .get_workflow(&format!("wf-stuck-{i}"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(wf.status, "FAILED");
+        assert_eq!(wf.error.as_deref(), Some("incident 42 cleanup"));
+    }
+    let healthy = state.get_workflow("wf-healthy").await.unwrap().unwrap();
+    assert_eq!(healthy.status, "PENDING");
+
+    let jobs: Vec<serde_json::Value> = client()
+        .get(format!("{url}/api/v1/engine/workflow/batch"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(jobs.len(), 1);
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn batch_signal_and_upsert_select_by_search_attributes() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    for (id, env) in [("wf-a", "prod"), ("wf-b", "prod"), ("wf-c", "staging")] {
+        state
+            .start_workflow(
+                "main",
+                "Deploy",
+                id,
+                None,
+                "q",
+                Some(&serde_json::json!({ "env": env }).to_string()),
+            )
+            .await
+            .unwrap();
+    }
+    state.terminate_workflow("wf-b", None).await.unwrap();
+    let (url, handle) = serve_state(&state).await;
+
+    let job: serde_json::Value = client()
+        .post(format!("{url}/api/v1/engine/workflow/batch"))
+        .json(&serde_json::json!({
+            "filter": { "search_attrs": { "env": "prod" } },
+            "operation": { "type": "signal", "signal_name": "pause", "payload": { "by": "ops" } },
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job = wait_for_batch(&url, job["id"].as_str().unwrap()).await;
+    assert_eq!(job["status"], "COMPLETED");
+    assert_eq!(job["total"], 2);
+    assert_eq!(job["succeeded"], 1);
+    assert_eq!(job["failed"], 1, "the terminated run can't take a signal");
+    assert!(
+        job["last_error"].as_str().unwrap().starts_with("wf-b: "),
+        "{job}"
+    );
+    let events = state.get_events("wf-a").await.unwrap();
+    assert!(events.iter().any(|e| e.event_type == "SignalReceived"));
+
+    let job: serde_json::Value = client()
+        .post(format!("{url}/api/v1/engine/workflow/batch"))
+        .json(&serde_json::json!({
+            "filter": { "status": "PENDING" },
+            "operation": {
+                "type": "upsert_search_attributes",
+                "search_attributes": { "incident": "42" },
+            },
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job = wait_for_batch(&url, job["id"].as_str().unwrap()).await;
+    assert_eq!(job["succeeded"], 2);
+    for id in ["wf-a", "wf-c"] {
+        let wf = state.get_workflow(id).await.unwrap().unwrap();
+        let attrs: serde_json::Value =
+            serde_json::from_str(wf.search_attributes.as_deref().unwrap()).unwrap();
+        assert_eq!(attrs["incident"], "42", "{id}");
+    }
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn batch_jobs_can_be_cancelled_and_reject_bad_requests() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    for i in 0..5 {
+        state
+            .start_workflow("main", "Slow", &format!("wf-slow-{i}"), None, "q", None)
+            .await
+            .unwrap();
+    }
+    let (url, handle) = serve_state(&state).await;
+    let batch_url = format!("{url}/api/v1/engine/workflow/batch");
+
+    let job: serde_json::Value = client()
+        .post(&batch_url)
+        .json(&serde_json::json!({
+            "filter": { "type": "Slow" },
+            "operation": { "type": "cancel" },
+            "max_per_second": 2.0,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let id = job["id"].as_str().unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let response = client()
+        .post(format!("{batch_url}/{id}/cancel"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let cancelled: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(cancelled["status"], "CANCELLED");
+
+    tokio::time::sleep(std::time::Duration::from_millis(1200)).await;
+    let job = wait_for_batch(&url, id).await;
+    assert_eq!(job["status"], "CANCELLED");
+    let processed = job["processed"].as_i64().unwrap();
+    assert!(processed < 5, "runner should stop after cancel: {job}");
+    let mut still_pending = 0;
+    for i in 0..5 {
+        let wf = state
+            .get_workflow(&format!("wf-slow-{i}"))
+            .await
+            .unwrap()
+            .unwrap();
+        if wf.status == "PENDING" {
+            still_pending += 1;
+        }
+    }
+    assert_eq!(still_pending, 5 - processed);
+
+    let response = client()
+        .post(format!("{batch_url}/{id}/cancel"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+    let response = client()
+        .post(format!("{batch_url}/ghost/cancel"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    for body in [
+        serde_json::json!({ "filter": {}, "operation": { "type": "cancel" } }),
+        serde_json::json!({ "filter": { "status": "STUCK" }, "operation": { "type": "cancel" } }),
+        serde_json::json!({
+            "filter": { "type": "Slow" },
+            "operation": { "type": "reset", "reason": "x" },
+        }),
+        serde_json::json!({
+            "filter": { "type": "Slow" },
+            "operation": { "type": "cancel" },
+            "max_per_second": 0.0,
+        }),
+        // A tick interval of 1/rate seconds would overflow...
+        serde_json::json!({
+            "filter": { "type": "Slow" },
+            "operation": { "type": "cancel" },
+            "max_per_second": 1e-300,
+        }),
+        // ...or round down to zero and panic the runner.
+        serde_json::json!({
+            "filter": { "type": "Slow" },
+            "operation": { "type": "cancel" },
+            "max_per_second": 1e300,
+        }),
+    ] {
+        let response = client().post(&batch_url).json(&body).send().await.unwrap();
+        assert_eq!(response.status(), 400, "{body}");
+    }
+
+    handle.abort();
+}
+
+/// A job left `RUNNING` by a node that stopped is failed when the
+/// engine starts, not left looking live forever.
+#[tokio::test]
+async fn startup_fails_batch_jobs_orphaned_by_a_stopped_node() {
+    use assay_workflow::WorkflowStore;
+
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    store
+        .create_batch_job(&assay_workflow::types::BatchJob {
+            id: "batch-orphan".to_string(),
+            namespace: "main".to_string(),
+            operation: "cancel".to_string(),
+            filter: r#"{"type":"Slow"}"#.to_string(),
+            params: Some(r#"{"type":"cancel"}"#.to_string()),
+            max_per_second: 1.0,
+            status: "RUNNING".to_string(),
+            total: 10,
+            processed: 3,
+            succeeded: 3,
+            failed: 0,
+            last_error: None,
+            created_at: 1000.0,
+            updated_at: 1000.0,
+            completed_at: None,
+        })
+        .await
+        .unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    let (url, handle) = serve_state(&state).await;
+
+    let job = wait_for_batch(&url, "batch-orphan").await;
+    assert_eq!(job["status"], "FAILED");
+    assert!(
+        job["last_error"]
+            .as_str()
+            .unwrap()
+            .contains("engine restarted"),
+        "{job}"
+    );
+    assert_eq!(job["processed"], 3, "progress is kept");
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn visibility_query_lists_counts_and_drives_batches() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    for (id, wf_type, progress) in [
+        ("wf-1", "deploy", 0.9),
+        ("wf-2", "deploy", 0.1),
+        ("wf-3", "ingest", 0.7),
+    ] {
+        state
+            .start_workflow(
+                "main",
+                wf_type,
+                id,
+                None,
+                "q",
+                Some(&serde_json::json!({ "progress": progress }).to_string()),
+            )
+            .await
+            .unwrap();
+    }
+    let (url, handle) = serve_state(&state).await;
+    let base = format!("{url}/api/v1/engine/workflow");
+
+    let register = |name: &str, ty: &str| {
+        client()
+            .post(format!("{base}/namespaces/main/search-attributes"))
+            .json(&serde_json::json!({ "name": name, "type": ty }))
+            .send()
+    };
+    let resp = register("progress", "double").await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let def: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(def["type"], "double");
+    assert_eq!(register("progress", "double").await.unwrap().status(), 200);
+    assert_eq!(register("progress", "int").await.unwrap().status(), 409);
+    assert_eq!(register("bad-name", "keyword").await.unwrap().status(), 400);
+    let attrs: serde_json::Value = client()
+        .get(format!("{base}/namespaces/main/search-attributes"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(attrs.as_array().unwrap().len(), 1);
+
+    let list: serde_json::Value = client()
+        .get(with_params(
+            &format!("{base}/workflows"),
+            &[(
+                "query",
+                "WorkflowType = 'deploy' AND status IN ('PENDING', 'RUNNING') AND search.progress > 0.5",
+            )],
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let ids: Vec<&str> = list
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["wf-1"]);
+
+    let job: serde_json::Value = client()
+        .post(format!("{base}/batch"))
+        .json(&serde_json::json!({
+            "filter": { "query": "search.progress < 0.5" },
+            "operation": { "type": "terminate", "reason": "stalled" },
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let job = wait_for_batch(&url, job["id"].as_str().unwrap()).await;
+    assert_eq!(job["status"], "COMPLETED");
+    assert_eq!(job["succeeded"], 1);
+
+    let count: serde_json::Value = client()
+        .get(format!("{base}/visibility/count"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(count, serde_json::json!({ "count": 3 }));
+    let count: serde_json::Value = client()
+        .get(with_params(
+            &format!("{base}/visibility/count"),
+            &[("query", "search.progress >= 0.1"), ("group_by", "status")],
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        count,
+        serde_json::json!({
+            "count": 3,
+            "groups": [
+                { "status": "FAILED", "count": 1 },
+                { "status": "PENDING", "count": 2 },
+            ],
+        })
+    );
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn visibility_query_rejects_bad_requests() {
+    let store = SqliteStore::new("sqlite::memory:").await.unwrap();
+    let state = Arc::new(WorkflowCtx::start(Arc::new(store)));
+    let (url, handle) = serve_state(&state).await;
+    let base = format!("{url}/api/v1/engine/workflow");
+
+    let workflows = format!("{base}/workflows");
+    let count = format!("{base}/visibility/count");
+    let cases = [
+        (
+            with_params(&workflows, &[("query", "search.env = 'prod'")]),
+            "not registered",
+        ),
+        (
+            with_params(
+                &workflows,
+                &[("query", "status = 'RUNNING'"), ("status", "RUNNING")],
+            ),
+            "cannot be combined",
+        ),
+        (
+            with_params(&workflows, &[("query", "created_at >")]),
+            "end of query",
+        ),
+        (
+            with_params(&count, &[("group_by", "workflow_type")]),
+            "group_by",
+        ),
+    ];
+    for (url, needle) in cases {
+        let resp = client().get(url.clone()).send().await.unwrap();
+        assert_eq!(resp.status(), 400, "{url}");
+        let body = resp.text().await.unwrap();
+        assert!(body.contains(needle), "{url}: {body}");
+    }
+
+    let resp = client()
+        .post(format!("{base}/batch"))
+        .json(&serde_json::json!({
+            "filter": { "query": "status = 'RUNNING'", "type": "deploy" },
+            "operation": { "type": "cancel" },
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    handle.abort();
+}
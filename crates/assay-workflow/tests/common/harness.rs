@@ -67,6 +67,15 @@ impl Harness {
         dispatch!(self, s => s.create_workflow(wf).await)
     }
 
+    pub async fn start_workflow_run(
+        &self,
+        wf: &WorkflowRecord,
+        policy: IdReusePolicy,
+        request_id: Option<&str>,
+    ) -> anyhow::Result<StartRunOutcome> {
+        dispatch!(self, s => s.start_workflow_run(wf, policy, request_id).await)
+    }
+
     pub async fn get_workflow(&self, id: &str) -> anyhow::Result<Option<WorkflowRecord>> {
         dispatch!(self, s => s.get_workflow(id).await)
     }
@@ -83,6 +92,43 @@ impl Harness {
         dispatch!(self, s => s.list_workflows(namespace, status, workflow_type, search_attrs_filter, limit, offset).await)
     }
 
+    pub async fn query_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<WorkflowRecord>> {
+        dispatch!(self, s => s.query_workflows(namespace, query, limit, offset).await)
+    }
+
+    pub async fn count_workflows(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> anyhow::Result<i64> {
+        dispatch!(self, s => s.count_workflows(namespace, query).await)
+    }
+
+    pub async fn count_workflows_by_status(
+        &self,
+        namespace: &str,
+        query: Option<&VisibilityQuery>,
+    ) -> anyhow::Result<Vec<WorkflowStatusCount>> {
+        dispatch!(self, s => s.count_workflows_by_status(namespace, query).await)
+    }
+
+    pub async fn create_search_attribute(&self, def: &SearchAttributeDef) -> anyhow::Result<bool> {
+        dispatch!(self, s => s.create_search_attribute(def).await)
+    }
+
+    pub async fn list_search_attributes(
+        &self,
+        namespace: &str,
+    ) -> anyhow::Result<Vec<SearchAttributeDef>> {
+        dispatch!(self, s => s.list_search_attributes(namespace).await)
+    }
+
     pub async fn update_workflow_status(
         &self,
         id: &str,
@@ -209,6 +255,63 @@ impl Harness {
         dispatch!(self, s => s.retry_failed_activity(workflow_id, requested_by, reason, requested_at).await)
     }
 
+    pub async fn reset_workflow(
+        &self,
+        source_id: &str,
+        new_run: &WorkflowRecord,
+        reset_to_seq: i32,
+        reapply_signals: bool,
+        reason: &str,
+    ) -> anyhow::Result<ResetWorkflowResult> {
+        dispatch!(self, s => s.reset_workflow(source_id, new_run, reset_to_seq, reapply_signals, reason).await)
+    }
+
+    pub async fn create_batch_job(&self, job: &BatchJob) -> anyhow::Result<()> {
+        dispatch!(self, s => s.create_batch_job(job).await)
+    }
+
+    pub async fn get_batch_job(&self, id: &str) -> anyhow::Result<Option<BatchJob>> {
+        dispatch!(self, s => s.get_batch_job(id).await)
+    }
+
+    pub async fn list_batch_jobs(
+        &self,
+        namespace: &str,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<BatchJob>> {
+        dispatch!(self, s => s.list_batch_jobs(namespace, limit, offset).await)
+    }
+
+    pub async fn update_batch_job_progress(
+        &self,
+        id: &str,
+        total: i64,
+        processed: i64,
+        succeeded: i64,
+        failed: i64,
+        last_error: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        dispatch!(self, s => s.update_batch_job_progress(id, total, processed, succeeded, failed, last_error).await)
+    }
+
+    pub async fn finish_batch_job(
+        &self,
+        id: &str,
+        status: &str,
+        error: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        dispatch!(self, s => s.finish_batch_job(id, status, error).await)
+    }
+
+    pub async fn fail_stale_batch_jobs(
+        &self,
+        stale_before: f64,
+        error: &str,
+    ) -> anyhow::Result<u64> {
+        dispatch!(self, s => s.fail_stale_batch_jobs(stale_before, error).await)
+    }
+
     pub async fn complete_activity(
         &self,
         id: i64,
@@ -219,10 +322,22 @@ impl Harness {
         dispatch!(self, s => s.complete_activity(id, result, error, failed).await)
     }
 
-    pub async fn heartbeat_activity(&self, id: i64, details: Option<&str>) -> anyhow::Result<()> {
+    pub async fn heartbeat_activity(&self, id: i64, details: Option<&str>) -> anyhow::Result<bool> {
         dispatch!(self, s => s.heartbeat_activity(id, details).await)
     }
 
+    pub async fn request_activity_cancel(
+        &self,
+        workflow_id: &str,
+        seq: i32,
+    ) -> anyhow::Result<CancelActivityResult> {
+        dispatch!(self, s => s.request_activity_cancel(workflow_id, seq).await)
+    }
+
+    pub async fn acknowledge_activity_cancel(&self, id: i64) -> anyhow::Result<bool> {
+        dispatch!(self, s => s.acknowledge_activity_cancel(id).await)
+    }
+
     pub async fn get_timed_out_activities(
         &self,
         now: f64,
@@ -257,6 +372,10 @@ impl Harness {
         dispatch!(self, s => s.fire_due_timers(now).await)
     }
 
+    pub async fn get_timer_backlog(&self, now: f64) -> anyhow::Result<TimerBacklog> {
+        dispatch!(self, s => s.get_timer_backlog(now).await)
+    }
+
     pub async fn cancel_pending_timers(&self, workflow_id: &str) -> anyhow::Result<u64> {
         dispatch!(self, s => s.cancel_pending_timers(workflow_id).await)
     }
@@ -275,6 +394,35 @@ impl Harness {
         dispatch!(self, s => s.consume_signals(workflow_id, name).await)
     }
 
+    // ── Updates ───────────────────────────────────────────────────────────────
+
+    pub async fn create_update(&self, update: &WorkflowUpdate) -> anyhow::Result<bool> {
+        dispatch!(self, s => s.create_update(update).await)
+    }
+
+    pub async fn get_update(&self, update_id: &str) -> anyhow::Result<Option<WorkflowUpdate>> {
+        dispatch!(self, s => s.get_update(update_id).await)
+    }
+
+    pub async fn list_pending_updates(
+        &self,
+        workflow_id: &str,
+    ) -> anyhow::Result<Vec<WorkflowUpdate>> {
+        dispatch!(self, s => s.list_pending_updates(workflow_id).await)
+    }
+
+    pub async fn transition_update(
+        &self,
+        workflow_id: &str,
+        update_id: &str,
+        from_status: &str,
+        to_status: &str,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        dispatch!(self, s => s.transition_update(workflow_id, update_id, from_status, to_status, result, error).await)
+    }
+
     // ── Schedules ─────────────────────────────────────────────────────────────
 
     pub async fn create_schedule(&self, sched: &WorkflowSchedule) -> anyhow::Result<()> {
@@ -304,7 +452,7 @@ impl Harness {
         name: &str,
         last_run_at: f64,
         next_run_at: f64,
-        workflow_id: &str,
+        workflow_id: Option<&str>,
     ) -> anyhow::Result<()> {
         dispatch!(self, s => s.update_schedule_last_run(namespace, name, last_run_at, next_run_at, workflow_id).await)
     }
@@ -331,6 +479,40 @@ impl Harness {
         dispatch!(self, s => s.set_schedule_paused(namespace, name, paused).await)
     }
 
+    pub async fn record_schedule_action(
+        &self,
+        action: &ScheduleAction,
+        keep: i64,
+    ) -> anyhow::Result<i64> {
+        dispatch!(self, s => s.record_schedule_action(action, keep).await)
+    }
+
+    pub async fn list_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<ScheduleAction>> {
+        dispatch!(self, s => s.list_schedule_actions(namespace, schedule_name, limit).await)
+    }
+
+    pub async fn list_buffered_schedule_actions(
+        &self,
+        namespace: &str,
+        schedule_name: &str,
+    ) -> anyhow::Result<Vec<ScheduleAction>> {
+        dispatch!(self, s => s.list_buffered_schedule_actions(namespace, schedule_name).await)
+    }
+
+    pub async fn resolve_buffered_schedule_action(
+        &self,
+        id: i64,
+        workflow_id: &str,
+        taken_at: f64,
+    ) -> anyhow::Result<()> {
+        dispatch!(self, s => s.resolve_buffered_schedule_action(id, workflow_id, taken_at).await)
+    }
+
     // ── Snapshots ─────────────────────────────────────────────────────────────
 
     pub async fn create_snapshot(
@@ -704,6 +704,544 @@ async fn complete_workflow_command_marks_terminal() {
     assert!(next.is_null(), "completed workflow must not poll");
 }
 
+/// Helper: start a workflow, claim its first task as `worker-A`, and
+/// park it with a `WaitForCondition` so it's idle but running.
+async fn start_parked_workflow(c: &reqwest::Client, url: &str, workflow_id: &str) {
+    c.post(format!("{url}/api/v1/engine/workflow/workflows"))
+        .json(&serde_json::json!({
+            "workflow_type": "TestWorkflow",
+            "workflow_id": workflow_id,
+            "task_queue": "default",
+        }))
+        .send()
+        .await
+        .unwrap();
+    poll_workflow_task(c, url, "default", "worker-A").await;
+    submit_commands(
+        c,
+        url,
+        workflow_id,
+        serde_json::json!([{"type": "WaitForCondition"}]),
+    )
+    .await;
+}
+
+async fn submit_commands(
+    c: &reqwest::Client,
+    url: &str,
+    workflow_id: &str,
+    commands: serde_json::Value,
+) {
+    let resp = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/workflow-tasks/{workflow_id}/commands"
+        ))
+        .json(&serde_json::json!({"worker_id": "worker-A", "commands": commands}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+}
+
+/// Workflow updates — the blocking POST is delivered to the next
+/// workflow task, and returns the handler's result once the worker
+/// submits `AcceptUpdate` + `CompleteUpdate`. History records both.
+#[tokio::test]
+async fn update_round_trips_through_workflow_task() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    start_parked_workflow(&c, &url, "wf-upd-1").await;
+
+    let caller = {
+        let c = c.clone();
+        let url = url.clone();
+        tokio::spawn(async move {
+            c.post(format!(
+                "{url}/api/v1/engine/workflow/workflows/wf-upd-1/update/approve"
+            ))
+            .json(&serde_json::json!({"args": {"by": "alice"}, "wait_secs": 10}))
+            .send()
+            .await
+            .unwrap()
+        })
+    };
+
+    // The update makes the parked workflow dispatchable again.
+    let mut task = serde_json::Value::Null;
+    for _ in 0..50 {
+        task = poll_workflow_task(&c, &url, "default", "worker-A").await;
+        if !task.is_null() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let updates = task["updates"].as_array().expect("updates on the task");
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0]["name"], "approve");
+    assert_eq!(updates[0]["args"]["by"], "alice");
+    let update_id = updates[0]["update_id"].as_str().unwrap().to_string();
+
+    submit_commands(
+        &c,
+        &url,
+        "wf-upd-1",
+        serde_json::json!([
+            {"type": "AcceptUpdate", "update_id": update_id, "block_point": 1},
+            {"type": "CompleteUpdate", "update_id": update_id, "result": {"approved": true}},
+            {"type": "WaitForCondition"},
+        ]),
+    )
+    .await;
+
+    let resp = caller.await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["status"], "COMPLETED");
+    assert_eq!(body["result"]["approved"], true);
+    assert!(body["error"].is_null());
+
+    let events: Vec<serde_json::Value> = c
+        .get(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-1/events"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let accepted = events
+        .iter()
+        .find(|e| e["event_type"] == "UpdateAccepted")
+        .expect("UpdateAccepted in history");
+    let payload: serde_json::Value =
+        serde_json::from_str(accepted["payload"].as_str().unwrap()).unwrap();
+    assert_eq!(payload["block_point"], 1);
+    assert_eq!(payload["name"], "approve");
+    assert!(events.iter().any(|e| e["event_type"] == "UpdateCompleted"));
+
+    // The outcome stays readable, and CompleteUpdate re-dispatched the
+    // workflow so a handler parked on a condition can re-check it.
+    let got = c
+        .get(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-1/updates/{update_id}"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(got.status(), 200);
+    let next = poll_workflow_task(&c, &url, "default", "worker-A").await;
+    assert_eq!(next["workflow_id"], "wf-upd-1");
+    assert!(next["updates"].as_array().unwrap().is_empty());
+}
+
+/// Workflow updates — a validator rejection answers 422, leaves
+/// `UpdateRejected` in history, and updates against unknown or
+/// finished workflows fail fast.
+#[tokio::test]
+async fn update_rejection_and_terminal_workflows() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    start_parked_workflow(&c, &url, "wf-upd-2").await;
+
+    // Short wait with no worker: 202 with the pending update.
+    let pending = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-2/update/approve"
+        ))
+        .json(&serde_json::json!({"update_id": "upd-fixed", "wait_secs": 0}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(pending.status(), 202);
+    let body: serde_json::Value = pending.json().await.unwrap();
+    assert_eq!(body["update_id"], "upd-fixed");
+    assert_eq!(body["status"], "REQUESTED");
+
+    poll_workflow_task(&c, &url, "default", "worker-A").await;
+    submit_commands(
+        &c,
+        &url,
+        "wf-upd-2",
+        serde_json::json!([
+            {"type": "RejectUpdate", "update_id": "upd-fixed", "error": "not allowed"},
+            {"type": "WaitForCondition"},
+        ]),
+    )
+    .await;
+
+    // Retrying with the same update_id re-attaches to the outcome.
+    let retried = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-2/update/approve"
+        ))
+        .json(&serde_json::json!({"update_id": "upd-fixed", "wait_secs": 1}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(retried.status(), 422);
+    let body: serde_json::Value = retried.json().await.unwrap();
+    assert_eq!(body["status"], "REJECTED");
+    assert_eq!(body["error"], "not allowed");
+
+    let missing = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-nope/update/approve"
+        ))
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 404);
+
+    c.post(format!(
+        "{url}/api/v1/engine/workflow/workflows/wf-upd-2/terminate"
+    ))
+    .json(&serde_json::json!({}))
+    .send()
+    .await
+    .unwrap();
+    let finished = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-2/update/approve"
+        ))
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(finished.status(), 409);
+}
+
+/// Workflow updates — update commands only move the commanding
+/// workflow's own updates. Another workflow's or an unknown update_id
+/// answers 409; replaying a command for one already applied is a no-op.
+#[tokio::test]
+async fn update_commands_are_scoped_to_their_workflow() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    start_parked_workflow(&c, &url, "wf-upd-3").await;
+    start_parked_workflow(&c, &url, "wf-upd-4").await;
+    let pending = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-3/update/approve"
+        ))
+        .json(&serde_json::json!({"update_id": "upd-owned", "wait_secs": 0}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(pending.status(), 202);
+
+    let commands_url = format!("{url}/api/v1/engine/workflow/workflow-tasks/wf-upd-4/commands");
+    for update_id in ["upd-owned", "upd-ghost"] {
+        let resp = c
+            .post(&commands_url)
+            .json(&serde_json::json!({
+                "worker_id": "worker-A",
+                "commands": [{"type": "AcceptUpdate", "update_id": update_id, "block_point": 1}],
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 409, "{update_id}");
+    }
+    let update: serde_json::Value = c
+        .get(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-upd-3/updates/upd-owned"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(update["status"], "REQUESTED");
+
+    let reject = serde_json::json!([
+        {"type": "RejectUpdate", "update_id": "upd-owned", "error": "not allowed"},
+        {"type": "WaitForCondition"},
+    ]);
+    submit_commands(&c, &url, "wf-upd-3", reject.clone()).await;
+    submit_commands(&c, &url, "wf-upd-3", reject).await;
+    let rejected = list_events(&c, &url, "wf-upd-3")
+        .await
+        .into_iter()
+        .filter(|e| e["event_type"] == "UpdateRejected")
+        .count();
+    assert_eq!(rejected, 1);
+}
+
+async fn list_events(c: &reqwest::Client, url: &str, workflow_id: &str) -> Vec<serde_json::Value> {
+    c.get(format!(
+        "{url}/api/v1/engine/workflow/workflows/{workflow_id}/events"
+    ))
+    .send()
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap()
+}
+
+/// Versioning — `RecordVersionMarker` lands in history once per
+/// change id, however many tasks re-submit it.
+#[tokio::test]
+async fn version_marker_is_recorded_once_per_change_id() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    start_parked_workflow(&c, &url, "wf-ver-1").await;
+
+    for version in [2, 3] {
+        c.post(format!(
+            "{url}/api/v1/engine/workflow/workflows/wf-ver-1/signal/poke"
+        ))
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+        poll_workflow_task(&c, &url, "default", "worker-A").await;
+        submit_commands(
+            &c,
+            &url,
+            "wf-ver-1",
+            serde_json::json!([
+                {"type": "RecordVersionMarker", "change_id": "fraud-check", "version": version},
+                {"type": "WaitForCondition"},
+            ]),
+        )
+        .await;
+    }
+
+    let markers: Vec<_> = list_events(&c, &url, "wf-ver-1")
+        .await
+        .into_iter()
+        .filter(|e| e["event_type"] == "VersionMarkerRecorded")
+        .collect();
+    assert_eq!(markers.len(), 1, "one marker per change id");
+    let payload: serde_json::Value =
+        serde_json::from_str(markers[0]["payload"].as_str().unwrap()).unwrap();
+    assert_eq!(payload["change_id"], "fraud-check");
+    assert_eq!(payload["version"], 2, "the first decision sticks");
+}
+
+/// Versioning — re-scheduling an existing activity seq under another
+/// name fails the workflow task: nothing in the batch is applied, the
+/// run stays RUNNING with a `WorkflowTaskFailed` event, and the lease
+/// is held so the same code doesn't spin on it.
+#[tokio::test]
+async fn diverging_commands_fail_the_workflow_task() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    c.post(format!("{url}/api/v1/engine/workflow/workflows"))
+        .json(&serde_json::json!({
+            "workflow_type": "TestWorkflow",
+            "workflow_id": "wf-ver-2",
+            "task_queue": "default",
+        }))
+        .send()
+        .await
+        .unwrap();
+    poll_workflow_task(&c, &url, "default", "worker-A").await;
+    submit_commands(
+        &c,
+        &url,
+        "wf-ver-2",
+        serde_json::json!([
+            {"type": "ScheduleActivity", "seq": 1, "name": "charge", "task_queue": "default"},
+        ]),
+    )
+    .await;
+
+    c.post(format!(
+        "{url}/api/v1/engine/workflow/workflows/wf-ver-2/signal/poke"
+    ))
+    .json(&serde_json::json!({}))
+    .send()
+    .await
+    .unwrap();
+    poll_workflow_task(&c, &url, "default", "worker-A").await;
+    submit_commands(
+        &c,
+        &url,
+        "wf-ver-2",
+        serde_json::json!([
+            {"type": "ScheduleActivity", "seq": 1, "name": "refund", "task_queue": "default"},
+            {"type": "CompleteWorkflow", "result": null},
+        ]),
+    )
+    .await;
+
+    let events = list_events(&c, &url, "wf-ver-2").await;
+    let failed = events
+        .iter()
+        .find(|e| e["event_type"] == "WorkflowTaskFailed")
+        .expect("WorkflowTaskFailed in history");
+    let payload: serde_json::Value =
+        serde_json::from_str(failed["payload"].as_str().unwrap()).unwrap();
+    let error = payload["error"].as_str().unwrap();
+    assert!(
+        error.contains("'charge'") && error.contains("'refund'"),
+        "error names both activities: {error}"
+    );
+
+    let wf: serde_json::Value = c
+        .get(format!("{url}/api/v1/engine/workflow/workflows/wf-ver-2"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(wf["status"], "RUNNING", "the run itself is untouched");
+    let next = poll_workflow_task(&c, &url, "default", "worker-B").await;
+    assert!(next.is_null(), "the failed task keeps its lease");
+}
+
+async fn heartbeat(c: &reqwest::Client, url: &str, activity_id: i64) -> bool {
+    let resp: serde_json::Value = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/tasks/{activity_id}/heartbeat"
+        ))
+        .json(&serde_json::json!({"details": "halfway"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    resp["cancel_requested"]
+        .as_bool()
+        .expect("cancel_requested flag")
+}
+
+async fn activity_status(c: &reqwest::Client, url: &str, activity_id: i64) -> String {
+    let activity: serde_json::Value = c
+        .get(format!(
+            "{url}/api/v1/engine/workflow/activities/{activity_id}"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    activity["status"].as_str().unwrap().to_string()
+}
+
+/// Cancellation — cancelling a workflow reaches the activity already
+/// running on a worker through its heartbeat, and the worker's failure
+/// report then ends the activity CANCELLED instead of retrying it.
+#[tokio::test]
+async fn workflow_cancel_reaches_running_activity_heartbeat() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    let activity_id = schedule_and_claim(&c, &url, "wf-act-cancel").await;
+    assert!(!heartbeat(&c, &url, activity_id).await);
+
+    c.post(format!(
+        "{url}/api/v1/engine/workflow/workflows/wf-act-cancel/cancel"
+    ))
+    .send()
+    .await
+    .unwrap();
+    assert!(heartbeat(&c, &url, activity_id).await);
+    assert_eq!(activity_status(&c, &url, activity_id).await, "RUNNING");
+
+    let resp = c
+        .post(format!(
+            "{url}/api/v1/engine/workflow/tasks/{activity_id}/fail"
+        ))
+        .json(&serde_json::json!({"error": "activity cancelled"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(activity_status(&c, &url, activity_id).await, "CANCELLED");
+    let types: Vec<_> = list_events(&c, &url, "wf-act-cancel")
+        .await
+        .into_iter()
+        .map(|e| e["event_type"].as_str().unwrap().to_string())
+        .collect();
+    assert!(
+        types.contains(&"ActivityCancelled".to_string()),
+        "{types:?}"
+    );
+    assert!(!types.contains(&"ActivityFailed".to_string()), "{types:?}");
+}
+
+/// Cancellation — `CancelActivity` cancels a PENDING activity outright
+/// and wakes the workflow; a RUNNING one gets `ActivityCancelRequested`
+/// and is flagged on its next heartbeat.
+#[tokio::test]
+async fn cancel_activity_command_cancels_pending_and_flags_running() {
+    let (url, _h) = start_test_server().await;
+    let c = client();
+    c.post(format!("{url}/api/v1/engine/workflow/workflows"))
+        .json(&serde_json::json!({
+            "workflow_type": "TestWorkflow",
+            "workflow_id": "wf-act-cmd",
+            "task_queue": "default",
+        }))
+        .send()
+        .await
+        .unwrap();
+    poll_workflow_task(&c, &url, "default", "worker-A").await;
+    submit_commands(
+        &c,
+        &url,
+        "wf-act-cmd",
+        serde_json::json!([
+            {"type": "ScheduleActivity", "seq": 1, "name": "backup", "task_queue": "backups"},
+            {"type": "ScheduleActivity", "seq": 2, "name": "backup", "task_queue": "idle"},
+        ]),
+    )
+    .await;
+    let running: serde_json::Value = c
+        .post(format!("{url}/api/v1/engine/workflow/tasks/poll"))
+        .json(&serde_json::json!({"queue": "backups", "worker_id": "worker-B"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let running_id = running["id"].as_i64().expect("claimed activity");
+
+    submit_commands(
+        &c,
+        &url,
+        "wf-act-cmd",
+        serde_json::json!([
+            {"type": "CancelActivity", "seq": 1},
+            {"type": "CancelActivity", "seq": 2},
+            {"type": "WaitForCondition"},
+        ]),
+    )
+    .await;
+
+    let events = list_events(&c, &url, "wf-act-cmd").await;
+    let seq_of = |event_type: &str| -> Vec<i64> {
+        events
+            .iter()
+            .filter(|e| e["event_type"] == event_type)
+            .map(|e| {
+                let p: serde_json::Value =
+                    serde_json::from_str(e["payload"].as_str().unwrap()).unwrap();
+                p["activity_seq"].as_i64().unwrap()
+            })
+            .collect()
+    };
+    assert_eq!(seq_of("ActivityCancelRequested"), vec![1]);
+    assert_eq!(seq_of("ActivityCancelled"), vec![2]);
+    assert_eq!(activity_status(&c, &url, running_id).await, "RUNNING");
+    assert!(heartbeat(&c, &url, running_id).await);
+
+    let task = poll_workflow_task(&c, &url, "default", "worker-A").await;
+    assert_eq!(
+        task["workflow_id"], "wf-act-cmd",
+        "the cancelled pending activity wakes the workflow"
+    );
+}
+
 // ─── 9.4 — Lua deterministic-replay runtime end-to-end ─────────────────────
 //
 // These tests boot the engine in-process AND spawn a real assay subprocess
@@ -369,7 +369,11 @@ async fn pg_schedules() {
             last_run_at: None,
             next_run_at: None,
             last_workflow_id: None,
+            catchup_window_secs: 365 * 24 * 3600,
+            catchup_policy: "one".to_string(),
+            jitter_secs: 0,
             created_at: now(),
+            recent_actions: Vec::new(),
         })
         .await
         .unwrap();
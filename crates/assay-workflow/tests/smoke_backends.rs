@@ -717,6 +717,242 @@ async fn terminal_activity_retry_is_atomic_across_backends(#[case] backend: Back
     assert!(workflow.completed_at.is_none());
 }
 
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn reset_workflow_forks_history(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let src_id = uid("wf-reset-src");
+    h.create_workflow(&make_workflow(&src_id, "main", "reset-q"))
+        .await
+        .unwrap();
+    for (seq, event_type) in [
+        (1, "WorkflowStarted"),
+        (2, "ActivityScheduled"),
+        (3, "ActivityFailed"),
+        (4, "SignalReceived"),
+        (5, "ActivityScheduled"),
+        (6, "SignalReceived"),
+    ] {
+        let mut evt = make_event(&src_id, seq);
+        evt.event_type = event_type.to_string();
+        h.append_event(&evt).await.unwrap();
+    }
+
+    let new_id = uid("wf-reset-new");
+    let new_run = make_workflow(&new_id, "main", "reset-q");
+
+    let out_of_range = h
+        .reset_workflow(&src_id, &new_run, 7, true, "bad seq")
+        .await
+        .unwrap();
+    assert!(matches!(
+        out_of_range,
+        assay_domain::types::ResetWorkflowResult::InvalidEventSeq { event_count: 6 }
+    ));
+    assert!(h.get_workflow(&new_id).await.unwrap().is_none());
+
+    let assay_domain::types::ResetWorkflowResult::Reset(reset) = h
+        .reset_workflow(&src_id, &new_run, 2, true, "activity bug fixed")
+        .await
+        .unwrap()
+    else {
+        panic!("reset should fork the run")
+    };
+    assert_eq!(reset.workflow.id, new_id);
+    assert_eq!(reset.events_copied, 2);
+    assert_eq!(reset.signals_reapplied, 2);
+
+    let types: Vec<(i32, String)> = h
+        .list_events(&new_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.seq, e.event_type))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            (1, "WorkflowStarted".to_string()),
+            (2, "ActivityScheduled".to_string()),
+            (3, "SignalReceived".to_string()),
+            (4, "SignalReceived".to_string()),
+            (5, "WorkflowReset".to_string()),
+        ]
+    );
+
+    // The source keeps its history and gets the reset recorded on top.
+    let source_events = h.list_events(&src_id).await.unwrap();
+    assert_eq!(source_events.len(), 7);
+    let marker = source_events.last().unwrap();
+    assert_eq!(marker.event_type, "WorkflowReset");
+    let payload: serde_json::Value =
+        serde_json::from_str(marker.payload.as_deref().unwrap()).unwrap();
+    assert_eq!(payload["reset_workflow_id"], new_id.as_str());
+    assert_eq!(payload["reset_to_seq"], 2);
+    assert_eq!(payload["reason"], "activity bug fixed");
+
+    // Resetting into an id that already exists is refused atomically.
+    let again = h
+        .reset_workflow(&src_id, &new_run, 1, false, "again")
+        .await
+        .unwrap();
+    assert!(matches!(
+        again,
+        assay_domain::types::ResetWorkflowResult::AlreadyExists
+    ));
+    assert_eq!(h.list_events(&src_id).await.unwrap().len(), 7);
+}
+
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn batch_job_progress_stops_once_finished(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let ns = uid("batch-ns");
+    let job = assay_domain::types::BatchJob {
+        id: uid("batch"),
+        namespace: ns.clone(),
+        operation: "terminate".to_string(),
+        filter: r#"{"status":"RUNNING"}"#.to_string(),
+        params: Some(r#"{"type":"terminate","reason":"incident"}"#.to_string()),
+        max_per_second: 10.0,
+        status: "RUNNING".to_string(),
+        total: 0,
+        processed: 0,
+        succeeded: 0,
+        failed: 0,
+        last_error: None,
+        created_at: 1000.0,
+        updated_at: 1000.0,
+        completed_at: None,
+    };
+    h.create_batch_job(&job).await.unwrap();
+
+    assert!(
+        h.update_batch_job_progress(&job.id, 3, 2, 1, 1, Some("wf-x: gone"))
+            .await
+            .unwrap()
+    );
+    // A later write without an error keeps the last one.
+    assert!(
+        h.update_batch_job_progress(&job.id, 3, 3, 2, 1, None)
+            .await
+            .unwrap()
+    );
+    let stored = h.get_batch_job(&job.id).await.unwrap().unwrap();
+    assert_eq!(
+        (
+            stored.total,
+            stored.processed,
+            stored.succeeded,
+            stored.failed
+        ),
+        (3, 3, 2, 1)
+    );
+    assert_eq!(stored.last_error.as_deref(), Some("wf-x: gone"));
+
+    assert!(
+        h.finish_batch_job(&job.id, "CANCELLED", None)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !h.finish_batch_job(&job.id, "COMPLETED", None)
+            .await
+            .unwrap(),
+        "a finished job can't be finished again"
+    );
+    // The item in flight when the job was cancelled still gets counted,
+    // but the runner is told to stop.
+    assert!(
+        !h.update_batch_job_progress(&job.id, 4, 4, 3, 1, None)
+            .await
+            .unwrap(),
+        "progress writes report that the job left RUNNING"
+    );
+    let stored = h.get_batch_job(&job.id).await.unwrap().unwrap();
+    assert_eq!(stored.status, "CANCELLED");
+    assert!(stored.completed_at.is_some());
+    assert_eq!(stored.processed, 4);
+
+    let listed = h.list_batch_jobs(&ns, 10, 0).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, job.id);
+    assert!(
+        h.list_batch_jobs("other-ns", 10, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn stale_batch_jobs_are_failed(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let ns = uid("batch-ns");
+    let job = |id: String, updated_at: f64| assay_domain::types::BatchJob {
+        id,
+        namespace: ns.clone(),
+        operation: "cancel".to_string(),
+        filter: r#"{"status":"RUNNING"}"#.to_string(),
+        params: Some(r#"{"type":"cancel"}"#.to_string()),
+        max_per_second: 10.0,
+        status: "RUNNING".to_string(),
+        total: 0,
+        processed: 0,
+        succeeded: 0,
+        failed: 0,
+        last_error: None,
+        created_at: updated_at,
+        updated_at,
+        completed_at: None,
+    };
+    let orphan = job(uid("batch-orphan"), 500.0);
+    let live = job(uid("batch-live"), 2000.0);
+    h.create_batch_job(&orphan).await.unwrap();
+    h.create_batch_job(&live).await.unwrap();
+
+    assert_eq!(
+        h.fail_stale_batch_jobs(600.0, "engine restarted")
+            .await
+            .unwrap(),
+        1
+    );
+    let failed = h.get_batch_job(&orphan.id).await.unwrap().unwrap();
+    assert_eq!(failed.status, "FAILED");
+    assert_eq!(failed.last_error.as_deref(), Some("engine restarted"));
+    assert!(failed.completed_at.is_some());
+    let running = h.get_batch_job(&live.id).await.unwrap().unwrap();
+    assert_eq!(running.status, "RUNNING");
+    assert_eq!(
+        h.fail_stale_batch_jobs(600.0, "engine restarted")
+            .await
+            .unwrap(),
+        0,
+        "a failed job stays failed"
+    );
+    assert!(
+        h.finish_batch_job(&live.id, "CANCELLED", None)
+            .await
+            .unwrap()
+    );
+}
+
 #[rstest]
 #[cfg_attr(
     all(feature = "backend-postgres", target_os = "linux"),
@@ -822,6 +1058,71 @@ async fn activity_cancel_pending(#[case] backend: Backend) {
         a3.status, "RUNNING",
         "RUNNING activity must not be cancelled"
     );
+    // ...but its worker is told to stop on the next heartbeat.
+    assert!(h.heartbeat_activity(id3, None).await.unwrap());
+}
+
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn activity_cancel_request_reaches_heartbeat(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let wf_id = uid("wf-act-cr");
+    h.create_workflow(&make_workflow(&wf_id, "main", "cr-q"))
+        .await
+        .unwrap();
+    let pending = h
+        .create_activity(&make_activity(&wf_id, 1, "cr-q"))
+        .await
+        .unwrap();
+    let running = h
+        .create_activity(&make_activity(&wf_id, 2, "cr-claim-q"))
+        .await
+        .unwrap();
+    h.claim_activity("cr-claim-q", "worker-c").await.unwrap();
+
+    assert!(!h.heartbeat_activity(running, None).await.unwrap());
+    assert!(!h.acknowledge_activity_cancel(running).await.unwrap());
+
+    assert_eq!(
+        h.request_activity_cancel(&wf_id, 1).await.unwrap(),
+        assay_domain::types::CancelActivityResult::Cancelled
+    );
+    assert_eq!(
+        h.get_activity(pending).await.unwrap().unwrap().status,
+        "CANCELLED"
+    );
+    assert_eq!(
+        h.request_activity_cancel(&wf_id, 2).await.unwrap(),
+        assay_domain::types::CancelActivityResult::CancelRequested
+    );
+    assert_eq!(
+        h.request_activity_cancel(&wf_id, 2).await.unwrap(),
+        assay_domain::types::CancelActivityResult::Unchanged
+    );
+    assert_eq!(
+        h.request_activity_cancel(&wf_id, 9).await.unwrap(),
+        assay_domain::types::CancelActivityResult::NotFound
+    );
+    assert_eq!(
+        h.get_activity(running).await.unwrap().unwrap().status,
+        "RUNNING"
+    );
+    assert!(h.heartbeat_activity(running, None).await.unwrap());
+
+    assert!(h.acknowledge_activity_cancel(running).await.unwrap());
+    assert_eq!(
+        h.get_activity(running).await.unwrap().unwrap().status,
+        "CANCELLED"
+    );
+    assert_eq!(
+        h.request_activity_cancel(&wf_id, 2).await.unwrap(),
+        assay_domain::types::CancelActivityResult::Unchanged
+    );
 }
 
 // ── Task 3.7 — Timers ─────────────────────────────────────────────────────────
@@ -859,6 +1160,10 @@ async fn timer_create_and_fire(#[case] backend: Backend) {
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs_f64();
+    let backlog = h.get_timer_backlog(now).await.unwrap();
+    assert_eq!((backlog.pending, backlog.overdue), (1, 1));
+    let backlog = h.get_timer_backlog(past - 1.0).await.unwrap();
+    assert_eq!((backlog.pending, backlog.overdue), (1, 0));
     let fired = h.fire_due_timers(now).await.unwrap();
     assert!(
         fired.iter().any(|t| t.id == Some(id)),
@@ -872,6 +1177,8 @@ async fn timer_create_and_fire(#[case] backend: Backend) {
         .unwrap()
         .unwrap();
     assert!(after.fired, "timer should be marked fired");
+    let backlog = h.get_timer_backlog(now).await.unwrap();
+    assert_eq!((backlog.pending, backlog.overdue), (0, 0));
 
     // Second call to fire_due_timers should NOT return already-fired timer.
     let second_fire = h.fire_due_timers(now + 1.0).await.unwrap();
@@ -955,6 +1262,106 @@ async fn signal_send_and_consume(#[case] backend: Backend) {
     assert_eq!(other_consumed.len(), 1);
 }
 
+// ── Updates ───────────────────────────────────────────────────────────────────
+
+fn make_update(
+    workflow_id: &str,
+    id: &str,
+    requested_at: f64,
+) -> assay_domain::types::WorkflowUpdate {
+    assay_domain::types::WorkflowUpdate {
+        id: id.to_string(),
+        workflow_id: workflow_id.to_string(),
+        name: "approve".to_string(),
+        args: Some(r#"{"by":"alice"}"#.to_string()),
+        status: "REQUESTED".to_string(),
+        result: None,
+        error: None,
+        requested_at,
+        completed_at: None,
+    }
+}
+
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn update_lifecycle(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let wf_id = uid("wf-upd");
+    h.create_workflow(&make_workflow(&wf_id, "main", "main"))
+        .await
+        .unwrap();
+
+    let u1 = format!("{wf_id}-u1");
+    let u2 = format!("{wf_id}-u2");
+    assert!(
+        h.create_update(&make_update(&wf_id, &u1, 1.0))
+            .await
+            .unwrap()
+    );
+    assert!(
+        h.create_update(&make_update(&wf_id, &u2, 2.0))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !h.create_update(&make_update(&wf_id, &u1, 3.0))
+            .await
+            .unwrap(),
+        "re-creating an existing update id must be a no-op"
+    );
+
+    let pending = h.list_pending_updates(&wf_id).await.unwrap();
+    let ids: Vec<_> = pending.iter().map(|u| u.id.as_str()).collect();
+    assert_eq!(ids, vec![u1.as_str(), u2.as_str()], "arrival order");
+
+    // Another workflow can't move this one's updates.
+    assert!(
+        !h.transition_update("wf-other", &u1, "REQUESTED", "ACCEPTED", None, None)
+            .await
+            .unwrap()
+    );
+
+    // REQUESTED → ACCEPTED → COMPLETED; wrong from-status is refused.
+    assert!(
+        !h.transition_update(&wf_id, &u1, "ACCEPTED", "COMPLETED", None, None)
+            .await
+            .unwrap()
+    );
+    assert!(
+        h.transition_update(&wf_id, &u1, "REQUESTED", "ACCEPTED", None, None)
+            .await
+            .unwrap()
+    );
+    let accepted = h.get_update(&u1).await.unwrap().unwrap();
+    assert_eq!(accepted.status, "ACCEPTED");
+    assert!(accepted.completed_at.is_none());
+    assert!(
+        h.transition_update(&wf_id, &u1, "ACCEPTED", "COMPLETED", Some("42"), None)
+            .await
+            .unwrap()
+    );
+    let done = h.get_update(&u1).await.unwrap().unwrap();
+    assert_eq!(done.status, "COMPLETED");
+    assert_eq!(done.result.as_deref(), Some("42"));
+    assert!(done.completed_at.is_some());
+
+    assert!(
+        h.transition_update(&wf_id, &u2, "REQUESTED", "REJECTED", None, Some("nope"))
+            .await
+            .unwrap()
+    );
+    let rejected = h.get_update(&u2).await.unwrap().unwrap();
+    assert_eq!(rejected.error.as_deref(), Some("nope"));
+
+    assert!(h.list_pending_updates(&wf_id).await.unwrap().is_empty());
+    assert!(h.get_update("missing").await.unwrap().is_none());
+}
+
 // ── Task 3.9 — Schedules ──────────────────────────────────────────────────────
 
 fn wall_clock_now() -> f64 {
@@ -979,7 +1386,11 @@ fn make_schedule(namespace: &str, name: &str) -> common::harness::WorkflowSchedu
         last_run_at: None,
         next_run_at: None,
         last_workflow_id: None,
+        catchup_window_secs: 365 * 24 * 3600,
+        catchup_policy: "one".to_string(),
+        jitter_secs: 0,
         created_at: now,
+        recent_actions: Vec::new(),
     }
 }
 
@@ -1022,6 +1433,9 @@ async fn schedule_crud(#[case] backend: Backend) {
         input: None,
         task_queue: None,
         overlap_policy: None,
+        catchup_window_secs: None,
+        catchup_policy: Some("all".to_string()),
+        jitter_secs: Some(30),
     };
     let updated = h
         .update_schedule("main", &sched_name, &patch)
@@ -1031,7 +1445,15 @@ async fn schedule_crud(#[case] backend: Backend) {
         updated.is_some(),
         "update_schedule should return updated record"
     );
-    assert_eq!(updated.unwrap().cron_expr, "0 0 * * *");
+    let updated = updated.unwrap();
+    assert_eq!(updated.cron_expr, "0 0 * * *");
+    assert_eq!(updated.catchup_policy, "all");
+    assert_eq!(updated.jitter_secs, 30);
+    assert_eq!(
+        updated.catchup_window_secs,
+        365 * 24 * 3600,
+        "fields left out of the patch keep their value"
+    );
 
     // set_paused = true
     let paused = h
@@ -1086,7 +1508,7 @@ async fn schedule_last_run_update(#[case] backend: Backend) {
     let next_run = 1_700_003_600.0_f64;
     let wf_id = uid("wf-last-run");
 
-    h.update_schedule_last_run("main", &sched_name, last_run, next_run, &wf_id)
+    h.update_schedule_last_run("main", &sched_name, last_run, next_run, Some(&wf_id))
         .await
         .unwrap();
 
@@ -1204,6 +1626,252 @@ async fn schedule_with_elapsed_next_run_fires_on_catch_up(#[case] backend: Backe
     );
 }
 
+fn schedule_action(
+    namespace: &str,
+    schedule_name: &str,
+    fire_time: f64,
+    action: &str,
+) -> assay_domain::types::ScheduleAction {
+    assay_domain::types::ScheduleAction {
+        id: None,
+        namespace: namespace.to_string(),
+        schedule_name: schedule_name.to_string(),
+        fire_time,
+        taken_at: fire_time,
+        action: action.to_string(),
+        workflow_id: None,
+        reason: None,
+        backfill: false,
+    }
+}
+
+/// Settled actions are trimmed to the newest `keep`; a buffered action is
+/// still owed a run and survives until it is resolved.
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn schedule_actions_prune_settled_and_keep_buffered(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let sched_name = uid("sched-actions");
+    h.create_schedule(&make_schedule("main", &sched_name))
+        .await
+        .unwrap();
+
+    let buffered_id = h
+        .record_schedule_action(&schedule_action("main", &sched_name, 100.0, "buffered"), 2)
+        .await
+        .unwrap();
+    for fire_time in [200.0, 300.0, 400.0] {
+        h.record_schedule_action(
+            &schedule_action("main", &sched_name, fire_time, "started"),
+            2,
+        )
+        .await
+        .unwrap();
+    }
+
+    let actions = h
+        .list_schedule_actions("main", &sched_name, 50)
+        .await
+        .unwrap();
+    let fires: Vec<f64> = actions.iter().map(|a| a.fire_time).collect();
+    assert_eq!(
+        fires,
+        vec![400.0, 300.0, 100.0],
+        "newest first, oldest settled pruned"
+    );
+
+    let buffered = h
+        .list_buffered_schedule_actions("main", &sched_name)
+        .await
+        .unwrap();
+    assert_eq!(buffered.len(), 1);
+    assert_eq!(buffered[0].id, Some(buffered_id));
+
+    h.resolve_buffered_schedule_action(buffered_id, "wf-buffered", 500.0)
+        .await
+        .unwrap();
+    assert!(
+        h.list_buffered_schedule_actions("main", &sched_name)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let resolved = h
+        .list_schedule_actions("main", &sched_name, 50)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|a| a.id == Some(buffered_id))
+        .unwrap();
+    assert_eq!(resolved.action, "started");
+    assert_eq!(resolved.workflow_id.as_deref(), Some("wf-buffered"));
+
+    h.delete_schedule("main", &sched_name).await.unwrap();
+    assert!(
+        h.list_schedule_actions("main", &sched_name, 50)
+            .await
+            .unwrap()
+            .is_empty(),
+        "deleting a schedule drops its history"
+    );
+}
+
+async fn scheduled_runs(h: &common::harness::Harness, sched_name: &str) -> Vec<String> {
+    let mut ids: Vec<String> = h
+        .list_workflows("main", None, None, None, 100, 0)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|wf| wf.id)
+        .filter(|id| id.starts_with(&format!("{sched_name}-")))
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// An hourly schedule last due three hours before the top of the hour,
+/// evaluated ten minutes past it: four fires are owed.
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn schedule_catch_up_policy_and_window(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let top = (wall_clock_now() / 3600.0).floor() * 3600.0;
+    let now = top + 600.0;
+
+    let create = |name: String, policy: &str, window: i64| {
+        let mut sched = make_schedule("main", &name);
+        sched.cron_expr = "0 0 * * * *".to_string();
+        sched.overlap_policy = "allow_all".to_string();
+        sched.catchup_policy = policy.to_string();
+        sched.catchup_window_secs = window;
+        sched.next_run_at = Some(top - 3.0 * 3600.0);
+        sched
+    };
+    let all = uid("sched-all");
+    let windowed = uid("sched-window");
+    let one = uid("sched-one");
+    let skip = uid("sched-skip");
+    h.create_schedule(&create(all.clone(), "all", 86_400))
+        .await
+        .unwrap();
+    h.create_schedule(&create(windowed.clone(), "all", 5_400))
+        .await
+        .unwrap();
+    h.create_schedule(&create(one.clone(), "one", 86_400))
+        .await
+        .unwrap();
+    h.create_schedule(&create(skip.clone(), "skip", 86_400))
+        .await
+        .unwrap();
+
+    h.evaluate_schedules_at(now).await.unwrap();
+
+    assert_eq!(
+        scheduled_runs(&h, &all).await.len(),
+        4,
+        "every missed fire runs"
+    );
+    assert_eq!(
+        scheduled_runs(&h, &windowed).await,
+        vec![
+            format!("{windowed}-{}", (top - 3600.0) as u64),
+            format!("{windowed}-{}", top as u64),
+        ],
+        "fires older than the window are dropped"
+    );
+    assert_eq!(
+        scheduled_runs(&h, &one).await,
+        vec![format!("{one}-{}", top as u64)],
+        "only the latest missed fire runs"
+    );
+    assert!(
+        scheduled_runs(&h, &skip).await.is_empty(),
+        "a fire ten minutes late is not on time"
+    );
+
+    let one_sched = h.get_schedule("main", &one).await.unwrap().unwrap();
+    assert_eq!(one_sched.next_run_at, Some(top + 3600.0));
+    let one_actions = h.list_schedule_actions("main", &one, 50).await.unwrap();
+    assert_eq!(
+        one_actions.iter().filter(|a| a.action == "skipped").count(),
+        3
+    );
+    assert_eq!(
+        one_actions.iter().filter(|a| a.action == "started").count(),
+        1
+    );
+}
+
+/// Under `queue`, a fire that lands while the previous run is active is
+/// buffered and starts on the first pass after that run finishes.
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn schedule_queue_overlap_buffers_until_previous_run_finishes(#[case] backend: Backend) {
+    let h = backend.setup().await.expect("setup");
+    let top = (wall_clock_now() / 3600.0).floor() * 3600.0;
+    let now = top + 60.0;
+    let sched_name = uid("sched-queue");
+    let mut sched = make_schedule("main", &sched_name);
+    sched.cron_expr = "0 0 * * * *".to_string();
+    sched.overlap_policy = "queue".to_string();
+    sched.catchup_policy = "all".to_string();
+    sched.next_run_at = Some(top - 3600.0);
+    h.create_schedule(&sched).await.unwrap();
+
+    h.evaluate_schedules_at(now).await.unwrap();
+    let first = format!("{sched_name}-{}", (top - 3600.0) as u64);
+    assert_eq!(scheduled_runs(&h, &sched_name).await, vec![first.clone()]);
+    let buffered = h
+        .list_buffered_schedule_actions("main", &sched_name)
+        .await
+        .unwrap();
+    assert_eq!(buffered.len(), 1);
+    assert_eq!(buffered[0].fire_time, top);
+
+    // Still running: the buffered fire keeps waiting.
+    h.evaluate_schedules_at(now + 15.0).await.unwrap();
+    assert_eq!(scheduled_runs(&h, &sched_name).await.len(), 1);
+
+    h.update_workflow_status(
+        &first,
+        assay_domain::types::WorkflowStatus::Completed,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    h.evaluate_schedules_at(now + 30.0).await.unwrap();
+
+    let second = format!("{sched_name}-{}", top as u64);
+    assert_eq!(
+        scheduled_runs(&h, &sched_name).await,
+        vec![first, second.clone()]
+    );
+    assert!(
+        h.list_buffered_schedule_actions("main", &sched_name)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let after = h.get_schedule("main", &sched_name).await.unwrap().unwrap();
+    assert_eq!(after.last_workflow_id.as_deref(), Some(second.as_str()));
+}
+
 // ── Task 3.10 — Snapshots ─────────────────────────────────────────────────────
 
 #[rstest]
@@ -1495,6 +2163,9 @@ async fn queue_stats(#[case] backend: Backend) {
     h.register_worker(&w2).await.unwrap();
     h.register_worker(&w3).await.unwrap();
 
+    // Only alpha's workflow is waiting for a workflow task
+    h.mark_workflow_dispatchable(&wf_id1).await.unwrap();
+
     // Get queue stats
     let stats = h.get_queue_stats(&ns).await.unwrap();
 
@@ -1527,6 +2198,9 @@ async fn queue_stats(#[case] backend: Backend) {
         "queue-beta should have 0 running activities"
     );
     assert_eq!(beta.workers, 1, "queue-beta should have 1 worker");
+
+    assert_eq!(alpha.pending_workflow_tasks, 1);
+    assert_eq!(beta.pending_workflow_tasks, 0);
 }
 
 // ── Task 3.14 — Child Workflows ───────────────────────────────────────────────
@@ -1775,3 +2449,280 @@ async fn reaper_fails_workflow_when_attempts_exhausted(#[case] backend: Backend)
         "exhausted timeout must terminate the workflow"
     );
 }
+
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn visibility_queries_filter_and_count(#[case] backend: Backend) {
+    use assay_domain::types::{SearchAttributeDef, SearchAttributeType, WorkflowStatus};
+
+    let h = backend.setup().await.expect("setup");
+    let ns = uid("vis-ns");
+    for (name, attr_type) in [
+        ("progress", SearchAttributeType::Double),
+        ("attempts", SearchAttributeType::Int),
+        ("env", SearchAttributeType::Keyword),
+        ("urgent", SearchAttributeType::Bool),
+        ("deadline", SearchAttributeType::Datetime),
+    ] {
+        let def = SearchAttributeDef {
+            namespace: ns.clone(),
+            name: name.to_string(),
+            attr_type,
+            created_at: 1000.0,
+        };
+        assert!(h.create_search_attribute(&def).await.unwrap());
+        assert!(
+            !h.create_search_attribute(&def).await.unwrap(),
+            "second registration of {name} must report a duplicate"
+        );
+    }
+    let attrs = h.list_search_attributes(&ns).await.unwrap();
+    assert_eq!(attrs.len(), 5);
+    assert_eq!(attrs[0].name, "attempts");
+
+    let rows = [
+        (
+            "deploy",
+            r#"{"progress":0.8,"attempts":3,"env":"prod","urgent":true,"deadline":"2026-10-05T00:00:00Z"}"#,
+            1_790_900_000.0,
+        ),
+        (
+            "deploy",
+            r#"{"progress":0.2,"attempts":1,"env":"staging","urgent":false}"#,
+            1_790_000_000.0,
+        ),
+        // Wrong JSON type for `progress`: must not match numeric predicates.
+        (
+            "deploy",
+            r#"{"progress":"0.9","env":"prod"}"#,
+            1_790_900_000.0,
+        ),
+        ("ingest", r#"{"progress":1}"#, 1_790_900_000.0),
+    ];
+    let mut ids = Vec::new();
+    for (i, (wf_type, attrs, created_at)) in rows.iter().enumerate() {
+        let id = format!("{ns}-{i}");
+        let mut wf = make_workflow(&id, &ns, "vis-q");
+        wf.workflow_type = wf_type.to_string();
+        wf.search_attributes = Some(attrs.to_string());
+        wf.created_at = *created_at;
+        h.create_workflow(&wf).await.unwrap();
+        ids.push(id);
+    }
+    h.update_workflow_status(&ids[0], WorkflowStatus::Running, None, None)
+        .await
+        .unwrap();
+    h.update_workflow_status(&ids[3], WorkflowStatus::Failed, None, Some("boom"))
+        .await
+        .unwrap();
+
+    let matching = |q: &str| {
+        let parsed = assay_workflow::visibility::parse_query(q, &attrs).unwrap();
+        let q = q.to_string();
+        let h = &h;
+        let ns = ns.clone();
+        async move {
+            let mut got: Vec<String> = h
+                .query_workflows(&ns, Some(&parsed), 100, 0)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|w| w.id)
+                .collect();
+            got.sort();
+            let count = h.count_workflows(&ns, Some(&parsed)).await.unwrap();
+            assert_eq!(count, got.len() as i64, "count disagrees with list for {q}");
+            got
+        }
+    };
+
+    assert_eq!(
+        matching(
+            "WorkflowType = 'deploy' AND status IN ('RUNNING','FAILED') AND search.progress > 0.5 AND created_at > '2026-10-01'"
+        )
+        .await,
+        vec![ids[0].clone()]
+    );
+    assert_eq!(
+        matching("search.progress >= 0.2").await,
+        vec![ids[0].clone(), ids[1].clone(), ids[3].clone()]
+    );
+    assert_eq!(
+        matching("search.env = 'prod' OR search.attempts = 1").await,
+        vec![ids[0].clone(), ids[1].clone(), ids[2].clone()]
+    );
+    assert_eq!(
+        matching("search.urgent = false").await,
+        vec![ids[1].clone()]
+    );
+    assert_eq!(
+        matching("search.deadline BETWEEN '2026-10-01' AND '2026-10-31'").await,
+        vec![ids[0].clone()]
+    );
+    assert_eq!(
+        matching("search.attempts IS NULL AND NOT WorkflowType = 'ingest'").await,
+        vec![ids[2].clone()]
+    );
+    assert_eq!(
+        matching("NOT search.env = 'prod'").await,
+        vec![ids[1].clone(), ids[3].clone()]
+    );
+    assert_eq!(
+        matching("completed_at IS NOT NULL").await,
+        vec![ids[3].clone()]
+    );
+
+    let mut page = h.query_workflows(&ns, None, 2, 0).await.unwrap();
+    page.extend(h.query_workflows(&ns, None, 2, 2).await.unwrap());
+    assert_eq!(page.len(), 4);
+
+    let groups = h.count_workflows_by_status(&ns, None).await.unwrap();
+    let groups: Vec<(String, i64)> = groups.into_iter().map(|g| (g.status, g.count)).collect();
+    assert_eq!(
+        groups,
+        vec![
+            ("FAILED".to_string(), 1),
+            ("PENDING".to_string(), 2),
+            ("RUNNING".to_string(), 1),
+        ]
+    );
+    let deploy =
+        assay_workflow::visibility::parse_query("WorkflowType = 'deploy'", &attrs).unwrap();
+    let groups = h
+        .count_workflows_by_status(&ns, Some(&deploy))
+        .await
+        .unwrap();
+    assert_eq!(groups.iter().map(|g| g.count).sum::<i64>(), 3);
+}
+
+#[rstest]
+#[cfg_attr(
+    all(feature = "backend-postgres", target_os = "linux"),
+    case::pg(Backend::Postgres)
+)]
+#[cfg_attr(feature = "backend-sqlite", case::sqlite(Backend::Sqlite))]
+#[tokio::test(flavor = "multi_thread")]
+async fn start_workflow_run_applies_id_reuse_policy(#[case] backend: Backend) {
+    use assay_domain::types::{
+        IdReusePolicy, StartRunOutcome, WorkflowStatus, retired_workflow_id,
+    };
+
+    let h = backend.setup().await.expect("setup");
+    let ns = uid("reuse-ns");
+    let id = uid("reuse-wf");
+    let run = |n: u32| {
+        let mut wf = make_workflow(&id, &ns, "reuse-q");
+        wf.run_id = format!("run-{id}-{n}");
+        wf
+    };
+
+    let first = h
+        .start_workflow_run(&run(1), IdReusePolicy::RejectDuplicate, Some("req-1"))
+        .await
+        .unwrap();
+    assert!(matches!(
+        first,
+        StartRunOutcome::Started {
+            replaced_run_id: None
+        }
+    ));
+    h.append_event(&make_event(&id, 1)).await.unwrap();
+    let mut child = make_workflow(&format!("{id}-child"), &ns, "reuse-q");
+    child.parent_id = Some(id.clone());
+    h.create_workflow(&child).await.unwrap();
+
+    // A retried request gets the run it created, whatever the policy.
+    match h
+        .start_workflow_run(&run(2), IdReusePolicy::AllowDuplicate, Some("req-1"))
+        .await
+        .unwrap()
+    {
+        StartRunOutcome::Duplicate(wf) => assert_eq!(wf.run_id, format!("run-{id}-1")),
+        other => panic!("expected Duplicate, got {other:?}"),
+    }
+    // A different request can't take over a run that hasn't closed.
+    match h
+        .start_workflow_run(&run(2), IdReusePolicy::AllowDuplicate, Some("req-2"))
+        .await
+        .unwrap()
+    {
+        StartRunOutcome::Running(wf) => assert_eq!(wf.run_id, format!("run-{id}-1")),
+        other => panic!("expected Running, got {other:?}"),
+    }
+
+    h.update_workflow_status(&id, WorkflowStatus::Completed, Some("{}"), None)
+        .await
+        .unwrap();
+    for policy in [
+        IdReusePolicy::RejectDuplicate,
+        IdReusePolicy::AllowDuplicateFailedOnly,
+    ] {
+        assert!(
+            matches!(
+                h.start_workflow_run(&run(2), policy, Some("req-2"))
+                    .await
+                    .unwrap(),
+                StartRunOutcome::Rejected(_)
+            ),
+            "{policy} must not replace a COMPLETED run"
+        );
+    }
+
+    let second = h
+        .start_workflow_run(&run(2), IdReusePolicy::AllowDuplicate, Some("req-2"))
+        .await
+        .unwrap();
+    let replaced = match second {
+        StartRunOutcome::Started {
+            replaced_run_id: Some(r),
+        } => r,
+        other => panic!("expected Started with a replaced run, got {other:?}"),
+    };
+    assert_eq!(replaced, format!("run-{id}-1"));
+
+    let current = h.get_workflow(&id).await.unwrap().unwrap();
+    assert_eq!(current.run_id, format!("run-{id}-2"));
+    assert_eq!(current.status, "PENDING");
+    assert!(current.result.is_none());
+    assert!(h.list_events(&id).await.unwrap().is_empty());
+
+    let retired = retired_workflow_id(&id, &replaced);
+    let old = h.get_workflow(&retired).await.unwrap().unwrap();
+    assert_eq!(old.run_id, replaced);
+    assert_eq!(old.status, "COMPLETED");
+    assert_eq!(h.list_events(&retired).await.unwrap().len(), 1);
+    let children = h.list_child_workflows(&retired).await.unwrap();
+    assert_eq!(children.len(), 1);
+    assert!(h.list_child_workflows(&id).await.unwrap().is_empty());
+
+    // The old run's request id no longer matches the current run.
+    match h
+        .start_workflow_run(&run(3), IdReusePolicy::RejectDuplicate, Some("req-1"))
+        .await
+        .unwrap()
+    {
+        StartRunOutcome::Running(wf) => assert_eq!(wf.run_id, format!("run-{id}-2")),
+        other => panic!("expected Running, got {other:?}"),
+    }
+
+    h.update_workflow_status(&id, WorkflowStatus::Failed, None, Some("boom"))
+        .await
+        .unwrap();
+    assert!(matches!(
+        h.start_workflow_run(&run(3), IdReusePolicy::AllowDuplicateFailedOnly, None)
+            .await
+            .unwrap(),
+        StartRunOutcome::Started {
+            replaced_run_id: Some(_)
+        }
+    ));
+    assert_eq!(
+        h.get_workflow(&id).await.unwrap().unwrap().run_id,
+        format!("run-{id}-3")
+    );
+}
@@ -231,6 +231,10 @@ pub(crate) enum WorkflowCommands {
         /// Filter by search attributes. Literal JSON, `@file.json`, or `-` for stdin.
         #[arg(long)]
         search_attrs: Option<String>,
+        /// Visibility query, e.g. "WorkflowType = 'deploy' AND search.progress > 0.5".
+        /// Can't be combined with --status / --type / --search-attrs.
+        #[arg(long, conflicts_with_all = ["status", "workflow_type", "search_attrs"])]
+        query: Option<String>,
         #[arg(long, default_value = "20")]
         limit: i64,
     },
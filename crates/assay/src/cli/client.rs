@@ -91,6 +91,7 @@ impl EngineClient {
         status: Option<&str>,
         workflow_type: Option<&str>,
         search_attrs: Option<&Value>,
+        query: Option<&str>,
         limit: Option<i64>,
     ) -> Result<Value> {
         let mut url = format!("{}/workflows?namespace={}", self.base, self.namespace);
@@ -107,6 +108,9 @@ impl EngineClient {
             let encoded = urlencoding_encode(&attrs.to_string());
             url.push_str(&format!("&search_attrs={encoded}"));
         }
+        if let Some(q) = query {
+            url.push_str(&format!("&query={}", urlencoding_encode(q)));
+        }
         self.send(self.http.get(&url), "workflow list").await
     }
 
@@ -94,6 +94,7 @@ pub async fn workflow_list(
     status: Option<String>,
     workflow_type: Option<String>,
     search_attrs: Option<String>,
+    query: Option<String>,
     limit: i64,
 ) -> ExitCode {
     let client = EngineClient::new(opts);
@@ -109,6 +110,7 @@ pub async fn workflow_list(
             status.as_deref(),
             workflow_type.as_deref(),
             resolved_attrs.as_ref(),
+            query.as_deref(),
             Some(limit),
         )
         .await;
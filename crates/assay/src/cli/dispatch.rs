@@ -31,8 +31,12 @@ pub(crate) async fn workflow(global: CliEngineOpts, command: WorkflowCommands) -
             status,
             workflow_type,
             search_attrs,
+            query,
             limit,
-        } => cli::commands::workflow_list(&opts, status, workflow_type, search_attrs, limit).await,
+        } => {
+            cli::commands::workflow_list(&opts, status, workflow_type, search_attrs, query, limit)
+                .await
+        }
         WorkflowCommands::Describe { id } => cli::commands::workflow_describe(&opts, &id).await,
         WorkflowCommands::State { id, name } => {
             cli::commands::workflow_state(&opts, &id, name.as_deref()).await
@@ -1,9 +1,10 @@
 --- @module assay.engine.auth
---- @description Lua client for assay-engine's auth module — login/whoami, passkey, OIDC client + provider, biscuit, zanzibar, and admin (users, sessions, OIDC clients/upstream, JWKS, audit).
+--- @description Lua client for assay-engine's auth module — login/whoami, passkey, OIDC client + provider, biscuit, zanzibar, and admin (users, sessions, organizations/groups, OIDC clients/upstream, JWKS, audit).
 --- @category identity
---- @keywords auth, login, session, passkey, oidc, biscuit, zanzibar, rebac, admin, users, sessions
+--- @keywords auth, login, session, mfa, totp, passkey, oidc, biscuit, zanzibar, rebac, admin, users, sessions, organizations, groups, tenants
 --- @quickref auth.client(opts) -> client | Build an auth client (engine_url + optional api_key)
---- @quickref c:login(email, password) -> {user_id, email, csrf_token} | Password login
+--- @quickref c:login(email, password) -> {user_id, email, csrf_token} | Password login ({mfa_required, mfa_token} when TOTP is enrolled)
+--- @quickref c:login_mfa(mfa_token, code) -> {user_id, email, csrf_token} | Finish a login with a TOTP or recovery code
 --- @quickref c:logout() -> nil | Revoke the current session cookie
 --- @quickref c:whoami() -> User|nil | Resolve the current session
 --- @quickref c.passkey:start_register(...) | Start a passkey registration
@@ -14,10 +15,11 @@
 --- @quickref c.oidc:complete(provider_slug, code, state) | Complete federated SSO
 --- @quickref c.biscuit:public_pem() -> string | Engine's biscuit root public key (PEM)
 --- @quickref c.biscuit:active_kid() -> string | Currently-active biscuit key id
---- @quickref c.zanzibar:check(rt, rid, perm, st, sid, srel?) -> bool, detail | Permission check
+--- @quickref c.zanzibar:check(rt, rid, perm, st, sid, srel?, opts?) -> bool, detail | Permission check (opts: at_least_as_fresh / at_exact_snapshot zookie, caveat context)
 --- @quickref c.zanzibar:expand(rt, rid, relation, depth?) -> tree | Userset expand
---- @quickref c.zanzibar:write(tuple) -> ok | Admin write a relation tuple
---- @quickref c.zanzibar:delete(tuple) -> nil | Admin remove a relation tuple
+--- @quickref c.zanzibar:write(tuple) -> {ok, zookie} | Admin write a relation tuple (optional caveat = {name, context}, expires_at)
+--- @quickref c.zanzibar:delete(tuple) -> {ok, zookie} | Admin remove a relation tuple
+--- @quickref c.zanzibar:watch(opts?, handler?) -> {changes, zookie} | Long-poll tuple TOUCH/DELETE changes since a zookie
 --- @quickref c.jwks:get() -> {keys} | Admin JWKS proxy
 --- @quickref c.oidc_provider:discovery() -> table | Public OIDC discovery
 --- @quickref c.oidc_provider:jwks() -> {keys} | Public JWKS
@@ -26,15 +28,26 @@
 --- @quickref c.oidc_provider:userinfo({access_token}) -> claims | OIDC userinfo
 --- @quickref c.oidc_provider:revoke(body) -> ok | RFC 7009 revoke
 --- @quickref c.oidc_provider:introspect(token) -> {active, ...} | RFC 7662 introspect
+--- @quickref c.oidc_provider:device_authorization({client_id, client_secret, scope}) -> {device_code, user_code, verification_uri, ...} | RFC 8628 start device login
+--- @quickref c.oidc_provider:device_token({client_id, client_secret, device_code, interval, expires_in}) -> {access_token, ...} | Poll /token until the user approves
 --- @quickref c.users:list({limit, offset, search}) -> {items, total, ...} | Admin list users
 --- @quickref c.users:create({email, display_name, password, email_verified}) -> User | Admin create
 --- @quickref c.users:get(id) -> {user, passkeys, sessions, upstream} | Admin get user detail
 --- @quickref c.users:update(id, body) -> User | Admin update user
 --- @quickref c.users:delete(id) -> nil | Admin hard-delete (cascades)
 --- @quickref c.users:reset_password(id, password) -> nil | Admin set password
+--- @quickref c.users:reset_mfa(id) -> nil | Admin clear a user's TOTP + recovery codes
 --- @quickref c.sessions:list({limit, offset, user_id}) -> {items, total, ...} | Admin list sessions
 --- @quickref c.sessions:revoke(session_id) -> nil | Admin revoke a single session
 --- @quickref c.sessions:revoke_all_for_user(user_id) -> {revoked} | Admin revoke every session
+--- @quickref c.orgs:list({limit, offset, search}) -> {items, total, ...} | Admin list organizations
+--- @quickref c.orgs:create({slug, display_name}) -> Organization | Admin create an organization
+--- @quickref c.orgs:get(id_or_slug) -> {organization, groups, member_count} | Admin organization detail
+--- @quickref c.orgs:set_member(org, user_id, role?) -> OrgMembership | Add a member or change their role (owner/admin/member)
+--- @quickref c.orgs:remove_member(org, user_id) -> nil | Drop a membership (and the user's group memberships in that org)
+--- @quickref c.orgs:create_group(org, {slug, display_name}) -> Group | Admin create a group
+--- @quickref c.orgs:add_group_member(group_id, member_type, member_id) -> nil | Add a user or nested group to a group
+--- @quickref c.orgs:for_user(user_id) -> {items} | Every organization a user belongs to
 --- @quickref c.oidc_clients:list() -> [client] | Admin list OIDC consumer apps
 --- @quickref c.oidc_clients:create(body) -> {client, client_secret} | Admin register (secret returned ONCE)
 --- @quickref c.oidc_clients:rotate_secret(id) -> {client_id, client_secret} | Rotate secret (returned ONCE)
@@ -122,6 +135,21 @@ function M.client(opts)
   -- OIDC spec paths kept under /auth/* (well-known, authorize, token, ...).
   local SPEC = "/auth"
 
+  -- The OAuth endpoints (/token, /device_authorization) read
+  -- form-encoded bodies. Returns the raw response so pollers can read
+  -- RFC 6749 error bodies off a 400.
+  local function post_form(path, fields)
+    local parts = {}
+    for k, v in pairs(fields) do
+      if v ~= nil and v ~= "" then
+        parts[#parts + 1] = url_encode(k) .. "=" .. url_encode(v)
+      end
+    end
+    local h = build_headers(false)
+    h["Content-Type"] = "application/x-www-form-urlencoded"
+    return http.post(engine_url .. SPEC .. path, table.concat(parts, "&"), { headers = h })
+  end
+
   local c = {}
 
   -- ===== Auth flow (sessions) =====
@@ -135,6 +163,13 @@ function M.client(opts)
     return result
   end
 
+  --- POST /api/v1/engine/auth/login/mfa — second step for accounts
+  --- with TOTP enrolled: the `mfa_token` from c:login plus a TOTP or
+  --- recovery code → session cookie.
+  function c:login_mfa(mfa_token, code)
+    return post(AUTH .. "/login/mfa", { mfa_token = mfa_token, code = code })
+  end
+
   --- DELETE /api/v1/engine/auth/session — revoke the current session.
   function c:logout() return del(AUTH .. "/session") end
 
@@ -246,7 +281,14 @@ function M.client(opts)
 
   c.zanzibar = {}
 
-  function c.zanzibar:check(resource_type, resource_id, permission, subject_type, subject_id, subject_rel)
+  --- `opts.at_least_as_fresh` (a zookie from `:write` / `:delete`) reads
+  --- your own writes; `opts.at_exact_snapshot` evaluates at exactly that
+  --- zookie. Omit both for the fastest read. `opts.context` supplies
+  --- caveat parameters (e.g. `{ client_ip = "10.1.2.3" }`); a caveated
+  --- grant lacking context comes back `result = "Conditional"` with
+  --- `missing_context` listing what to supply.
+  function c.zanzibar:check(resource_type, resource_id, permission, subject_type, subject_id, subject_rel, opts)
+    opts = opts or {}
     local r = post(AUTH .. "/admin/zanzibar/check", {
       resource_type = resource_type,
       resource_id = resource_id,
@@ -254,6 +296,9 @@ function M.client(opts)
       subject_type = subject_type,
       subject_id = subject_id,
       subject_rel = subject_rel,
+      at_least_as_fresh = opts.at_least_as_fresh,
+      at_exact_snapshot = opts.at_exact_snapshot,
+      context = opts.context,
     }, true)
     return r and r.allowed == true, r
   end
@@ -267,12 +312,48 @@ function M.client(opts)
     }, true)
   end
 
+  --- Write a relation tuple. `tuple.caveat = { name, context }` makes
+  --- the grant conditional on a caveat declared in the namespace;
+  --- `tuple.expires_at` (unix seconds, in the future) makes it lapse.
   function c.zanzibar:write(tuple) return post(AUTH .. "/admin/zanzibar/tuples", tuple, true) end
 
-  --- Remove a relation tuple. Body matches `:write` shape. Returns nil
-  --- on 204; raises on 404 / 5xx.
+  --- Remove a relation tuple. Body matches `:write` shape. Returns
+  --- `{ok, zookie}`; raises on 404 / 5xx.
   function c.zanzibar:delete(tuple) return del(AUTH .. "/admin/zanzibar/tuples", true, tuple) end
 
+  --- Long-poll the tuple change feed. `opts`: `since` (zookie; omit to
+  --- start at the current head), `object_type`, `object_id`, `relation`,
+  --- `subject_type`, `subject_id`, `limit`, `timeout_secs` (default 30).
+  --- Returns `{changes, zookie}`; pass `zookie` back as `since`.
+  ---
+  --- With `handler`, keeps watching and calls `handler(change)` for
+  --- every change (`{zookie, operation, tuple, changed_at}`) until it
+  --- returns `false`; returns the zookie to resume from.
+  function c.zanzibar:watch(wopts, handler)
+    wopts = wopts or {}
+    local timeout_secs = wopts.timeout_secs or 30
+    local function poll(since)
+      local q = "?timeout_secs=" .. timeout_secs
+      if since then q = q .. "&since=" .. url_encode(since) end
+      for _, k in ipairs({ "object_type", "object_id", "relation", "subject_type", "subject_id", "limit" }) do
+        if wopts[k] ~= nil then q = q .. "&" .. k .. "=" .. url_encode(wopts[k]) end
+      end
+      return decode(http.get(engine_url .. AUTH .. "/admin/zanzibar/watch" .. q, {
+        headers = build_headers(true),
+        timeout = timeout_secs + 10,
+      }))
+    end
+    if handler == nil then return poll(wopts.since) end
+    local since = wopts.since
+    while true do
+      local batch = poll(since)
+      for _, change in ipairs(batch.changes) do
+        if handler(change) == false then return change.zookie end
+      end
+      since = batch.zookie
+    end
+  end
+
   --- Persist (or replace) a namespace schema. Use this to seed the
   --- default `engine` / `auth` / `workflow` namespaces — see init.lua.
   function c.zanzibar:define_namespace(schema)
@@ -310,6 +391,10 @@ function M.client(opts)
     )
   end
 
+  function c.users:reset_mfa(id)
+    return del(AUTH .. "/admin/users/" .. url_encode(id) .. "/mfa", true)
+  end
+
   -- ===== Admin: sessions =====
 
   c.sessions = {}
@@ -335,6 +420,61 @@ function M.client(opts)
     return del(AUTH .. "/admin/sessions/by-user/" .. url_encode(user_id), true)
   end
 
+  -- ===== Admin: organizations + groups =====
+
+  c.orgs = {}
+
+  local function org_path(org) return AUTH .. "/admin/orgs/" .. url_encode(org) end
+  local function group_path(id) return AUTH .. "/admin/groups/" .. url_encode(id) end
+
+  function c.orgs:list(qopts)
+    qopts = qopts or {}
+    local q = "?limit=" .. (qopts.limit or 50) .. "&offset=" .. (qopts.offset or 0)
+    if qopts.search and qopts.search ~= "" then
+      q = q .. "&search=" .. url_encode(qopts.search)
+    end
+    return get(AUTH .. "/admin/orgs" .. q, true)
+  end
+
+  --- `org` is an organization id or slug everywhere below.
+  function c.orgs:get(org) return get(org_path(org), true) end
+  function c.orgs:create(body) return post(AUTH .. "/admin/orgs", body, true) end
+  function c.orgs:update(org, body) return put(org_path(org), body, true) end
+  function c.orgs:delete(org) return del(org_path(org), true) end
+
+  function c.orgs:members(org, qopts)
+    qopts = qopts or {}
+    local q = "?limit=" .. (qopts.limit or 50) .. "&offset=" .. (qopts.offset or 0)
+    return get(org_path(org) .. "/members" .. q, true)
+  end
+
+  function c.orgs:set_member(org, user_id, role)
+    return put(org_path(org) .. "/members/" .. url_encode(user_id), { role = role or "member" }, true)
+  end
+
+  function c.orgs:remove_member(org, user_id)
+    return del(org_path(org) .. "/members/" .. url_encode(user_id), true)
+  end
+
+  function c.orgs:for_user(user_id)
+    return get(AUTH .. "/admin/users/" .. url_encode(user_id) .. "/orgs", true)
+  end
+
+  function c.orgs:groups(org) return get(org_path(org) .. "/groups", true) end
+  function c.orgs:create_group(org, body) return post(org_path(org) .. "/groups", body, true) end
+  function c.orgs:get_group(id) return get(group_path(id), true) end
+  function c.orgs:delete_group(id) return del(group_path(id), true) end
+
+  --- `member_type` is "user" or "group"; a nested group must belong to
+  --- the same organization.
+  function c.orgs:add_group_member(group_id, member_type, member_id)
+    return put(group_path(group_id) .. "/members/" .. url_encode(member_type) .. "/" .. url_encode(member_id), nil, true)
+  end
+
+  function c.orgs:remove_group_member(group_id, member_type, member_id)
+    return del(group_path(group_id) .. "/members/" .. url_encode(member_type) .. "/" .. url_encode(member_id), true)
+  end
+
   -- ===== Admin: OIDC clients =====
 
   c.oidc_clients = {}
@@ -406,6 +546,50 @@ function M.client(opts)
     return post(SPEC .. "/introspect", { token = token }, false)
   end
 
+  --- POST /auth/device_authorization — start an RFC 8628 device login.
+  --- Show the returned `user_code` + `verification_uri` to the user,
+  --- then hand the whole result to `device_token`.
+  function c.oidc_provider:device_authorization(dopts)
+    dopts = dopts or {}
+    return decode(post_form("/device_authorization", {
+      client_id = dopts.client_id,
+      client_secret = dopts.client_secret,
+      scope = dopts.scope,
+    }))
+  end
+
+  --- Poll /auth/token with the device code until the user approves
+  --- (returns the token response) or denies / lets it expire (errors).
+  --- Honours `slow_down` by widening the poll interval by 5s.
+  function c.oidc_provider:device_token(dopts)
+    dopts = dopts or {}
+    local interval = dopts.interval or 5
+    local deadline = time() + (dopts.expires_in or 600)
+    while true do
+      local resp = post_form("/token", {
+        grant_type = "urn:ietf:params:oauth:grant-type:device_code",
+        device_code = dopts.device_code,
+        client_id = dopts.client_id,
+        client_secret = dopts.client_secret,
+      })
+      if resp.status >= 200 and resp.status < 300 then
+        return json.parse(resp.body)
+      end
+      local ok, body = pcall(json.parse, resp.body or "")
+      local code = ok and type(body) == "table" and body.error or nil
+      if code == "slow_down" then
+        interval = interval + 5
+      elseif code ~= "authorization_pending" then
+        error("assay.engine.auth: device login failed: "
+          .. tostring(code or ("HTTP " .. tostring(resp.status))))
+      end
+      if time() >= deadline then
+        error("assay.engine.auth: device login timed out")
+      end
+      sleep(interval)
+    end
+  end
+
   function c.oidc_provider:consent(body)
     return post(SPEC .. "/authorize/consent", body, false)
   end
@@ -1,12 +1,13 @@
 --- @module assay.engine.vault
---- @description Lua client for assay-engine's vault module (plan 17 / v0.3.0) mounted at `/api/v1/vault/*`. KV v2, transit, plus collections / share / dynamic-creds / sealing surfaces. For HashiCorp Vault / OpenBao use `assay.hashicorp.vault`.
+--- @description Lua client for assay-engine's vault module (plan 17 / v0.3.0) mounted at `/api/v1/vault/*`. KV v2, transit, PKI, SSH CA, plus collections / share / dynamic-creds / sealing surfaces. For HashiCorp Vault / OpenBao use `assay.hashicorp.vault`.
 --- @category secrets
---- @keywords vault, secrets, kv, transit, encrypt, decrypt, encryption, decryption, rotate, rotation, password, key, share, sealing, lease, credential, biscuit, kdf, assay-engine
+--- @keywords vault, secrets, kv, transit, encrypt, decrypt, encryption, decryption, rotate, rotation, password, key, share, sealing, lease, credential, biscuit, kdf, pki, certificate, x509, ca, crl, ocsp, mtls, ssh, vault-ssh, assay-engine
 --- @quickref vault.client(opts) -> client | Build a vault client (engine_url + optional api_key)
---- @quickref c.kv:put(path, data, custom_md?) -> {path, version} | Store new KV version
+--- @quickref c.kv:put(path, data, custom_md?, cas?) -> {path, version} | Store new KV version (cas: expected latest version, 0 = create only)
 --- @quickref c.kv:get(path, version?) -> {data, version, deleted_at, created_at} | Read latest or specific version
 --- @quickref c.kv:list(prefix?) -> {entries} | List paths under a prefix
 --- @quickref c.kv:meta(path) -> {latest_version, custom_md, ...} | Read path metadata
+--- @quickref c.kv:set_meta(path, meta) -> {latest_version, cas_required, ...} | Set cas_required / max_versions / delete_version_after / custom_md
 --- @quickref c.kv:delete(path, version) | Soft-delete a version
 --- @quickref c.kv:destroy(path, version) | Hard-destroy a version (irreversible)
 --- @quickref c.kv:undelete(path, version) | Reverse a soft-delete
@@ -15,6 +16,19 @@
 --- @quickref c.transit:decrypt(name, ciphertext) -> plaintext | Decrypt envelope back to raw bytes
 --- @quickref c.transit:rotate(name) -> {version} | Mint a new version; old versions still decrypt
 --- @quickref c.transit:list() -> {keys} | List every transit key
+--- @quickref c.pki:generate_root(common_name, opts?) -> {certificate} | Create a self-signed root CA (key sealed under the KEK)
+--- @quickref c.pki:set_role(name, role) | Create or replace a role (allowed_domains, allow_subdomains, ttl_secs, ...)
+--- @quickref c.pki:roles() -> {roles} | List every role
+--- @quickref c.pki:issue(role, common_name, opts?) -> {certificate, private_key, ca_chain, serial_number, ...} | Issue a cert + fresh key
+--- @quickref c.pki:sign(role, csr_pem, opts?) -> {certificate, ca_chain, serial_number, ...} | Sign a CSR under a role
+--- @quickref c.pki:revoke(serial_number) -> {revocation_time} | Revoke a cert; it shows up in the CRL and OCSP
+--- @quickref c.pki:ca_pem() -> pem | Fetch the CA certificate (public surface)
+--- @quickref c.pki:crl_pem() -> pem | Fetch the current CRL (public surface)
+--- @quickref c.ssh:generate_ca(key_type?) -> {public_key} | Create the SSH CA key (ed25519 unless asked otherwise)
+--- @quickref c.ssh:set_role(name, role) | Create or replace a role (cert_type, allowed_users, allowed_domains, ttl_secs, ...)
+--- @quickref c.ssh:sign(role, public_key, opts?) -> {signed_key, serial_number, valid_principals, ...} | Sign a user or host public key
+--- @quickref c.ssh:public_key() -> line | Fetch the CA public key for TrustedUserCAKeys (public surface)
+--- @quickref c.ssh:certs() -> {certs} | List issuance records (who got what, until when)
 --- @quickref c.share:mint(opts) -> {token, revocation_ids, expires_at} | Mint a biscuit share link
 --- @quickref c.share:redeem(token) -> grant | Redeem a share token (public surface)
 --- @quickref c.share:revoke(revocation_id, reason?) | Revoke a token by block id
@@ -101,10 +115,11 @@ function M.client(opts)
   -- ────────── KV v2 ──────────
   c.kv = {}
 
-  function c.kv:put(path_str, data, custom_md)
+  function c.kv:put(path_str, data, custom_md, cas)
     return api_put("/kv/" .. path_str, {
       data = data,
       custom_md = custom_md or {},
+      cas = cas,
     }, { 201 })
   end
 
@@ -125,6 +140,10 @@ function M.client(opts)
     return api_get("/kv-meta/" .. path_str)
   end
 
+  function c.kv:set_meta(path_str, meta)
+    return api_post("/kv-meta/" .. path_str, meta or {}, { 200 })
+  end
+
   function c.kv:delete(path_str, version)
     if not version then error("assay.engine.vault.kv:delete requires a version") end
     return api_delete("/kv/" .. path_str .. "?version=" .. tostring(version))
@@ -170,6 +189,131 @@ function M.client(opts)
     return base64.decode(resp.plaintext_b64)
   end
 
+  -- ────────── PKI ──────────
+  c.pki = {}
+
+  local function api_text(path_str)
+    local resp = http.get(url(path_str), { headers = headers() })
+    if resp.status == 404 then return nil end
+    check(resp, 200, "GET " .. path_str)
+    return resp.body
+  end
+
+  function c.pki:generate_root(common_name, pki_opts)
+    pki_opts = pki_opts or {}
+    return api_post("/pki/root/generate", {
+      common_name = common_name,
+      key_type    = pki_opts.key_type,
+      ttl_secs    = pki_opts.ttl_secs,
+    }, { 201 })
+  end
+
+  function c.pki:set_role(name, role)
+    return api_post("/pki/roles/" .. name, role or {}, { 204 })
+  end
+
+  function c.pki:role(name)
+    return api_get("/pki/roles/" .. name)
+  end
+
+  function c.pki:roles()
+    return api_get("/pki/roles")
+  end
+
+  function c.pki:delete_role(name)
+    return api_delete("/pki/roles/" .. name)
+  end
+
+  function c.pki:issue(role, common_name, pki_opts)
+    pki_opts = pki_opts or {}
+    return api_post("/pki/issue/" .. role, {
+      common_name = common_name,
+      alt_names   = pki_opts.alt_names,
+      ip_sans     = pki_opts.ip_sans,
+      ttl_secs    = pki_opts.ttl_secs,
+    })
+  end
+
+  function c.pki:sign(role, csr_pem, pki_opts)
+    pki_opts = pki_opts or {}
+    return api_post("/pki/sign/" .. role, {
+      csr         = csr_pem,
+      common_name = pki_opts.common_name,
+      alt_names   = pki_opts.alt_names,
+      ip_sans     = pki_opts.ip_sans,
+      ttl_secs    = pki_opts.ttl_secs,
+    })
+  end
+
+  function c.pki:revoke(serial_number)
+    return api_post("/pki/revoke", { serial_number = serial_number })
+  end
+
+  function c.pki:cert(serial_number)
+    return api_get("/pki/cert/" .. serial_number)
+  end
+
+  function c.pki:ca_pem()
+    return api_text("/pki/ca/pem")
+  end
+
+  function c.pki:crl_pem()
+    return api_text("/pki/crl/pem")
+  end
+
+  -- ────────── SSH CA ──────────
+  c.ssh = {}
+
+  function c.ssh:generate_ca(key_type)
+    return api_post("/ssh/ca/generate", { key_type = key_type }, { 201 })
+  end
+
+  function c.ssh:import_ca(private_key_pem)
+    return api_post("/ssh/ca/import", { private_key = private_key_pem }, { 201 })
+  end
+
+  function c.ssh:delete_ca()
+    return api_delete("/ssh/ca")
+  end
+
+  function c.ssh:public_key()
+    return api_text("/ssh/public_key")
+  end
+
+  function c.ssh:set_role(name, role)
+    return api_post("/ssh/roles/" .. name, role or {}, { 204 })
+  end
+
+  function c.ssh:role(name)
+    return api_get("/ssh/roles/" .. name)
+  end
+
+  function c.ssh:roles()
+    return api_get("/ssh/roles")
+  end
+
+  function c.ssh:delete_role(name)
+    return api_delete("/ssh/roles/" .. name)
+  end
+
+  -- opts: principals, ttl_secs, key_id, extensions, critical_options
+  -- (the last two are name -> value tables; flags take "").
+  function c.ssh:sign(role, public_key, ssh_opts)
+    ssh_opts = ssh_opts or {}
+    return api_post("/ssh/sign/" .. role, {
+      public_key       = public_key,
+      valid_principals = ssh_opts.principals,
+      ttl_secs         = ssh_opts.ttl_secs,
+      key_id           = ssh_opts.key_id,
+      extensions       = ssh_opts.extensions,
+      critical_options = ssh_opts.critical_options,
+    })
+  end
+
+  function c.ssh:certs()
+    return api_get("/ssh/certs")
+  end
+
   -- ────────── Biscuit-share (Phase 4) ──────────
   c.share = {}
 
@@ -3,21 +3,26 @@
 --- @category devtools
 --- @keywords workflow, engine, scheduler, signal, queue, namespace, worker
 --- @quickref workflow.client(opts) -> client | Build a workflow client
---- @quickref c:start({workflow_type, workflow_id, namespace?, input?, task_queue?}) -> {workflow_id, run_id, status} | Start a workflow
+--- @quickref c:start({workflow_type, workflow_id, namespace?, input?, task_queue?, id_reuse_policy?, request_id?}) -> {workflow_id, run_id, status} | Start a workflow
 --- @quickref c:signal(workflow_id, signal_name, payload?) -> nil | Send a signal
+--- @quickref c:update(workflow_id, update_name, args?, {update_id?, wait_secs?}?) -> any | Send an update and wait for the handler's result
 --- @quickref c:describe(workflow_id) -> WorkflowRecord | Query workflow state
 --- @quickref c:cancel(workflow_id) -> nil | Request cancellation
 --- @quickref c:terminate(workflow_id, reason?) -> nil | Hard-terminate (no graceful cleanup)
---- @quickref c:list({namespace?, status?, type?, search_attrs?, limit?, offset?}) -> [WorkflowRecord] | List workflows
+--- @quickref c:list({namespace?, status?, type?, search_attrs?, query?, limit?, offset?}) -> [WorkflowRecord] | List workflows
+--- @quickref c:count({namespace?, query?, group_by?}) -> {count, groups?} | Count matching workflows, optionally per status
 --- @quickref c:get_events(workflow_id) -> [Event] | Full event history
 --- @quickref c:get_state(workflow_id, name?) -> table|any | Latest snapshot
 --- @quickref c:list_children(workflow_id) -> [WorkflowRecord] | Child workflows
 --- @quickref c:continue_as_new(workflow_id, input?) -> WorkflowRecord | Client-side continue-as-new
 --- @quickref c:retry_failed_activity(workflow_id, requested_by, reason) -> RetryResult | Retry terminal failed activity with audit context
+--- @quickref c:reset(workflow_id, {event_seq?|reset_type?, reason, workflow_id?, reapply_signals?}) -> ResetResult | Fork a new run from an earlier point of history
 --- @quickref c.namespaces:create(name) | Create a namespace
 --- @quickref c.namespaces:list() -> [NamespaceRecord] | List namespaces
 --- @quickref c.namespaces:stats(name) -> NamespaceStats | Per-namespace counters
 --- @quickref c.namespaces:delete(name) | Delete a namespace
+--- @quickref c.namespaces:register_search_attribute(name, attr, type) -> SearchAttributeDef | Register a typed, queryable search attribute
+--- @quickref c.namespaces:search_attributes(name) -> [SearchAttributeDef] | List registered search attributes
 --- @quickref c.schedules:create(opts) -> ScheduleRecord | Create a cron schedule
 --- @quickref c.schedules:list({namespace?}) -> [ScheduleRecord] | List schedules
 --- @quickref c.schedules:describe(name, {namespace?}) -> ScheduleRecord|nil | Describe one
@@ -25,6 +30,11 @@
 --- @quickref c.schedules:pause(name, {namespace?}) | Pause schedule
 --- @quickref c.schedules:resume(name, {namespace?}) | Resume schedule
 --- @quickref c.schedules:delete(name, {namespace?}) | Delete schedule
+--- @quickref c.schedules:backfill(name, {start_at, end_at, overlap_policy?}, {namespace?}) -> {started, skipped, buffered, actions} | Start runs for a past time range
+--- @quickref c.batch:start({filter, operation, namespace?, max_per_second?}) -> BatchJob | Apply an operation to every matching workflow in the background
+--- @quickref c.batch:list({namespace?, limit?, offset?}) -> [BatchJob] | List batch jobs, newest first
+--- @quickref c.batch:describe(id) -> BatchJob|nil | Batch job progress
+--- @quickref c.batch:cancel(id) -> BatchJob | Stop a running batch job
 --- @quickref c.workers:list({namespace?}) -> [Worker] | List registered workers
 --- @quickref c.queues:stats({namespace?}) -> [QueueStats] | Pending/running counts per queue
 --- @quickref c:register_workflow(name, handler) | Register a workflow handler (worker mode)
@@ -110,6 +120,8 @@ function M.client(opts)
   -- ===== Workflow lifecycle =====
 
   --- Start a workflow. `opts.namespace` defaults to "main" engine-side.
+  --- A retry carrying the same `opts.request_id` returns the run the
+  --- first call started.
   function client:start(start_opts)
     local body = {
       workflow_type = start_opts.workflow_type,
@@ -118,9 +130,11 @@ function M.client(opts)
       input = start_opts.input,
       task_queue = start_opts.task_queue or "default",
       search_attributes = start_opts.search_attributes,
+      id_reuse_policy = start_opts.id_reuse_policy,
+      request_id = start_opts.request_id,
     }
     local resp = api_call("POST", "/workflows", body)
-    expect(resp, 201, "engine.workflow.start")
+    expect(resp, { 200, 201 }, "engine.workflow.start")
     return json.parse(resp.body)
   end
 
@@ -131,6 +145,31 @@ function M.client(opts)
     expect(resp, 200, "engine.workflow.signal")
   end
 
+  --- Send an update and block for the handler's return value. Raises
+  --- when the workflow rejects the update, the handler errors, or the
+  --- wait elapses before a worker picks it up (the message carries the
+  --- update_id; retry with the same `opts.update_id` to re-attach).
+  function client:update(workflow_id, update_name, args, update_opts)
+    update_opts = update_opts or {}
+    local body = {
+      args = args,
+      update_id = update_opts.update_id,
+      wait_secs = update_opts.wait_secs,
+    }
+    local resp = api_call("POST",
+      "/workflows/" .. url_encode(workflow_id) .. "/update/" .. url_encode(update_name), body)
+    expect(resp, { 200, 202, 422 }, "engine.workflow.update")
+    local outcome = json.parse(resp.body)
+    if resp.status == 202 then
+      error("engine.workflow.update: update " .. tostring(outcome.update_id) ..
+        " still " .. tostring(outcome.status) .. " after wait")
+    end
+    if outcome.error ~= nil then
+      error("engine.workflow.update: " .. tostring(outcome.error))
+    end
+    return outcome.result
+  end
+
   function client:describe(workflow_id)
     local resp = api_call("GET", "/workflows/" .. url_encode(workflow_id))
     expect(resp, 200, "engine.workflow.describe")
@@ -149,6 +188,9 @@ function M.client(opts)
     expect(resp, 200, "engine.workflow.terminate")
   end
 
+  --- `qopts.query` is a visibility query string, e.g.
+  --- "WorkflowType = 'deploy' AND search.progress > 0.5"; it can't be
+  --- combined with status / type / search_attrs.
   function client:list(qopts)
     qopts = qopts or {}
     local parts = {}
@@ -158,6 +200,7 @@ function M.client(opts)
     if qopts.search_attrs then
       parts[#parts + 1] = "search_attrs=" .. url_encode(json.encode(qopts.search_attrs))
     end
+    if qopts.query then parts[#parts + 1] = "query=" .. url_encode(qopts.query) end
     if qopts.limit then parts[#parts + 1] = "limit=" .. tostring(qopts.limit) end
     if qopts.offset then parts[#parts + 1] = "offset=" .. tostring(qopts.offset) end
     local path = "/workflows"
@@ -167,6 +210,19 @@ function M.client(opts)
     return json.parse(resp.body)
   end
 
+  function client:count(qopts)
+    qopts = qopts or {}
+    local parts = {}
+    if qopts.namespace then parts[#parts + 1] = "namespace=" .. url_encode(qopts.namespace) end
+    if qopts.query then parts[#parts + 1] = "query=" .. url_encode(qopts.query) end
+    if qopts.group_by then parts[#parts + 1] = "group_by=" .. url_encode(qopts.group_by) end
+    local path = "/visibility/count"
+    if #parts > 0 then path = path .. "?" .. table.concat(parts, "&") end
+    local resp = api_call("GET", path)
+    expect(resp, 200, "engine.workflow.count")
+    return json.parse(resp.body)
+  end
+
   function client:get_events(workflow_id)
     local resp = api_call("GET", "/workflows/" .. url_encode(workflow_id) .. "/events")
     expect(resp, 200, "engine.workflow.get_events")
@@ -205,6 +261,21 @@ function M.client(opts)
     return json.parse(resp.body)
   end
 
+  --- Start a new run from `workflow_id`'s history up to a reset point
+  --- and terminate the source. Give `opts.event_seq` or `opts.reset_type`
+  --- ("last_workflow_task" / "first_failed_activity"); `opts.reason` is
+  --- required and recorded on both runs.
+  function client:reset(workflow_id, ropts)
+    ropts = ropts or {}
+    if not ropts.reason or ropts.reason == "" then
+      error("engine.workflow.reset: reason required")
+    end
+    local resp = api_call("POST",
+      "/workflows/" .. url_encode(workflow_id) .. "/reset", ropts)
+    expect(resp, 201, "engine.workflow.reset")
+    return json.parse(resp.body)
+  end
+
   --- Close out `workflow_id` and start a fresh run with the same
   --- workflow type, namespace, and task queue. Client-side variant of
   --- continue-as-new; the worker-side `ctx:continue_as_new(input)` is
@@ -276,6 +347,17 @@ function M.client(opts)
     expect(resp, 200, "engine.workflow.schedules.delete")
   end
 
+  function client.schedules:backfill(name, bopts, sopts)
+    if not bopts or not bopts.start_at or not bopts.end_at then
+      error("engine.workflow.schedules.backfill: start_at, end_at required")
+    end
+    local ns = (sopts and sopts.namespace) or "main"
+    local resp = api_call("POST",
+      "/schedules/" .. url_encode(name) .. "/backfill?namespace=" .. url_encode(ns), bopts)
+    expect(resp, 200, "engine.workflow.schedules.backfill")
+    return json.parse(resp.body)
+  end
+
   -- ===== Namespaces =====
 
   client.namespaces = {}
@@ -305,6 +387,61 @@ function M.client(opts)
     expect(resp, 200, "engine.workflow.namespaces.delete")
   end
 
+  --- `attr_type` is one of keyword / int / double / bool / datetime.
+  --- Re-registering with the same type is a no-op.
+  function client.namespaces:register_search_attribute(name, attr, attr_type)
+    local resp = api_call("POST", "/namespaces/" .. url_encode(name) .. "/search-attributes",
+      { name = attr, type = attr_type })
+    expect(resp, { 200, 201 }, "engine.workflow.namespaces.register_search_attribute")
+    return json.parse(resp.body)
+  end
+
+  function client.namespaces:search_attributes(name)
+    local resp = api_call("GET", "/namespaces/" .. url_encode(name) .. "/search-attributes")
+    expect(resp, 200, "engine.workflow.namespaces.search_attributes")
+    return json.parse(resp.body)
+  end
+
+  -- ===== Batch jobs =====
+
+  client.batch = {}
+
+  --- `bopts.filter` takes the `list` filters ({status?, type?,
+  --- search_attrs?} or {query}); `bopts.operation` is a table with
+  --- `type` one of signal / cancel / terminate / reset /
+  --- upsert_search_attributes plus that operation's fields.
+  function client.batch:start(bopts)
+    if not bopts or not bopts.filter or not bopts.operation then
+      error("engine.workflow.batch.start: filter and operation required")
+    end
+    local resp = api_call("POST", "/batch", bopts)
+    expect(resp, 201, "engine.workflow.batch.start")
+    return json.parse(resp.body)
+  end
+
+  function client.batch:list(bopts)
+    bopts = bopts or {}
+    local path = "/batch?namespace=" .. url_encode(bopts.namespace or "main")
+    if bopts.limit then path = path .. "&limit=" .. tostring(bopts.limit) end
+    if bopts.offset then path = path .. "&offset=" .. tostring(bopts.offset) end
+    local resp = api_call("GET", path)
+    expect(resp, 200, "engine.workflow.batch.list")
+    return json.parse(resp.body)
+  end
+
+  function client.batch:describe(id)
+    local resp = api_call("GET", "/batch/" .. url_encode(id))
+    if resp.status == 404 then return nil end
+    expect(resp, 200, "engine.workflow.batch.describe")
+    return json.parse(resp.body)
+  end
+
+  function client.batch:cancel(id)
+    local resp = api_call("POST", "/batch/" .. url_encode(id) .. "/cancel")
+    expect(resp, 200, "engine.workflow.batch.cancel")
+    return json.parse(resp.body)
+  end
+
   -- ===== Workers =====
 
   client.workers = {}
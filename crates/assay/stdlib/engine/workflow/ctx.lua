@@ -14,9 +14,40 @@
 --- every ctx call, the first wait_for_signal on replay would raise
 --- before any state had been rebuilt, leading to a stale snapshot
 --- (all steps back to initial).
+---
+--- Updates:
+--- every ctx call that can park the workflow is a numbered "block
+--- point". The worker applies an incoming update while the handler is
+--- parked at the latest block point, and the engine records that
+--- number on `UpdateAccepted`. Replay re-runs the update handler on
+--- entry to the same block point, so state mutations from updates
+--- land in the same place relative to the workflow body every time.
+---
+--- Versioning:
+--- replay matches history by per-type seq, so a handler edited under
+--- in-flight runs must agree with what those runs already recorded.
+--- `ctx:get_version` picks a branch once per run and records it as a
+--- `VersionMarkerRecorded` event. Replayed calls whose activity or side
+--- effect name no longer matches the one at the same seq in history
+--- raise a non-determinism error, which the worker turns into
+--- `FailWorkflowTask` instead of failing the run.
+---
+--- Activity cancellation:
+--- `ctx:start_activity` / `ctx:start_parallel` schedule without
+--- blocking and return handles whose `cancel()` queues a
+--- `CancelActivity` command. Like version markers, commands queued
+--- between yields ride along with the next batch the worker submits.
+--- A cancelled activity's `result()` raises "activity '<name>' cancelled".
 
 local M = {}
 
+--- Version returned by `ctx:get_version` for runs that passed the
+--- change point before the call existed.
+M.DEFAULT_VERSION = -1
+
+local NONDETERMINISM = "__ASSAY_NONDETERMINISM__"
+M.NONDETERMINISM = NONDETERMINISM
+
 --- Build the workflow ctx object used during replay.
 --- @param workflow_id string
 --- @param history table  Workflow event history (already fetched by the worker).
@@ -31,17 +62,39 @@ function M.make(workflow_id, history)
   local signals_by_name = {}
   local signal_seqs_by_name = {}
   local timer_fired_seqs = {}
+  local cancel_issued = {}
+  local updates_by_block_point = {}
+  local scheduled_activities, recorded_side_effects, version_markers = {}, {}, {}
+  local max_activity_seq, max_timer_seq, max_side_effect_seq = 0, 0, 0
   local cancel_requested = false
   for _, event in ipairs(history) do
     local p = event.payload
-    if event.event_type == "ActivityCompleted" and p and p.activity_seq then
+    if event.event_type == "ActivityScheduled" and p and p.activity_seq then
+      scheduled_activities[p.activity_seq] = p.name
+      max_activity_seq = math.max(max_activity_seq, p.activity_seq)
+    elseif event.event_type == "ActivityCompleted" and p and p.activity_seq then
       activity_results[p.activity_seq] = { ok = true, value = p.result }
     elseif event.event_type == "ActivityFailed" and p and p.activity_seq then
       activity_results[p.activity_seq] = { ok = false, err = p.error }
+    elseif event.event_type == "ActivityCancelled" and p and p.activity_seq then
+      activity_results[p.activity_seq] = { ok = false, cancelled = true }
+    elseif event.event_type == "ActivityCancelRequested" and p and p.activity_seq then
+      cancel_issued[p.activity_seq] = true
     elseif event.event_type == "ActivityRetryRequested" and p and p.activity_seq then
       for seq in pairs(activity_results) do
         if seq >= p.activity_seq then activity_results[seq] = nil end
       end
+      for seq in pairs(cancel_issued) do
+        if seq >= p.activity_seq then cancel_issued[seq] = nil end
+      end
+      -- The retried path may schedule different activities after the
+      -- boundary; the engine dropped the old rows, so forget their names.
+      for seq in pairs(scheduled_activities) do
+        if seq > p.activity_seq then scheduled_activities[seq] = nil end
+      end
+      max_activity_seq = p.activity_seq
+    elseif event.event_type == "TimerScheduled" and p and p.timer_seq then
+      max_timer_seq = math.max(max_timer_seq, p.timer_seq)
     elseif event.event_type == "TimerFired" and p and p.timer_seq then
       fired_timers[p.timer_seq] = true
       timer_fired_seqs[p.timer_seq] = event.seq
@@ -52,10 +105,17 @@ function M.make(workflow_id, history)
       table.insert(signal_seqs_by_name[p.signal], event.seq)
     elseif event.event_type == "SideEffectRecorded" and p and p.side_effect_seq then
       side_effects[p.side_effect_seq] = p.value
+      recorded_side_effects[p.side_effect_seq] = p.name
+      max_side_effect_seq = math.max(max_side_effect_seq, p.side_effect_seq)
     elseif event.event_type == "ChildWorkflowCompleted" and p and p.child_workflow_id then
       child_results[p.child_workflow_id] = { ok = true, value = p.result }
     elseif event.event_type == "ChildWorkflowFailed" and p and p.child_workflow_id then
       child_results[p.child_workflow_id] = { ok = false, err = p.error }
+    elseif event.event_type == "UpdateAccepted" and p and p.block_point then
+      updates_by_block_point[p.block_point] = updates_by_block_point[p.block_point] or {}
+      table.insert(updates_by_block_point[p.block_point], { name = p.name, args = p.args })
+    elseif event.event_type == "VersionMarkerRecorded" and p and p.change_id then
+      version_markers[p.change_id] = p.version
     elseif event.event_type == "WorkflowCancelRequested" then
       cancel_requested = true
     end
@@ -63,34 +123,76 @@ function M.make(workflow_id, history)
 
   local signal_cursor = {}
   local activity_seq, timer_seq, side_effect_seq = 0, 0, 0
-  local ctx = { workflow_id = workflow_id }
+  local ctx = {
+    workflow_id = workflow_id,
+    DEFAULT_VERSION = M.DEFAULT_VERSION,
+    _block_point = 0,
+    _version_markers = {},
+    _pending_commands = {},
+  }
 
   local function check_cancel()
     if cancel_requested then error("__ASSAY_WORKFLOW_CANCELLED__") end
   end
 
+  local function nondeterminism(msg)
+    error(NONDETERMINISM .. "non-deterministic workflow: " .. msg ..
+      "; guard code changes with ctx:get_version", 0)
+  end
+
+  local function check_activity_name(seq, name)
+    local recorded = scheduled_activities[seq]
+    if recorded ~= nil and recorded ~= name then
+      nondeterminism("activity seq " .. seq .. " was scheduled as '" .. tostring(recorded) ..
+        "' but the handler now schedules '" .. tostring(name) .. "'")
+    end
+  end
+
+  local function activity_error(name, r)
+    if r.cancelled then return "activity '" .. tostring(name) .. "' cancelled" end
+    return "activity '" .. tostring(name) .. "' failed: " .. tostring(r.err)
+  end
+
+  local function schedule_command(seq, name, input, opts)
+    opts = opts or {}
+    return {
+      type = "ScheduleActivity",
+      seq = seq,
+      name = name,
+      task_queue = opts.task_queue or "default",
+      input = input,
+      max_attempts = opts.max_attempts,
+      initial_interval_secs = opts.initial_interval_secs,
+      backoff_coefficient = opts.backoff_coefficient,
+      start_to_close_secs = opts.start_to_close_secs,
+      heartbeat_timeout_secs = opts.heartbeat_timeout_secs,
+    }
+  end
+
+  -- Enter the next block point and re-apply any updates history says
+  -- were accepted here. Handler errors are swallowed: the original run
+  -- already reported them to the caller via CompleteUpdate.
+  local function reach_block_point()
+    ctx._block_point = ctx._block_point + 1
+    for _, u in ipairs(updates_by_block_point[ctx._block_point] or {}) do
+      local registered = ctx._updates and ctx._updates[u.name]
+      if registered then pcall(registered.handler, u.args) end
+    end
+  end
+
   --- Schedule an activity and (synchronously, for the workflow author)
   --- return its result.
   function ctx:execute_activity(name, input, opts)
+    reach_block_point()
     activity_seq = activity_seq + 1
+    check_activity_name(activity_seq, name)
     local r = activity_results[activity_seq]
     if r then
       if r.ok then return r.value end
-      error("activity '" .. name .. "' failed: " .. tostring(r.err))
+      error(activity_error(name, r))
     end
     check_cancel()
-    coroutine.yield({
-      type = "ScheduleActivity",
-      seq = activity_seq,
-      name = name,
-      task_queue = (opts and opts.task_queue) or "default",
-      input = input,
-      max_attempts = opts and opts.max_attempts,
-      initial_interval_secs = opts and opts.initial_interval_secs,
-      backoff_coefficient = opts and opts.backoff_coefficient,
-      start_to_close_secs = opts and opts.start_to_close_secs,
-      heartbeat_timeout_secs = opts and opts.heartbeat_timeout_secs,
-    })
+    coroutine.yield(schedule_command(activity_seq, name, input, opts))
     error("workflow ctx: yielded but resumed unexpectedly")
   end
 
@@ -101,34 +203,23 @@ function M.make(workflow_id, history)
     if type(activities) ~= "table" or #activities == 0 then
       error("ctx:execute_parallel: activities must be a non-empty list")
     end
+    reach_block_point()
     local seqs, results, all_done, first_error = {}, {}, true, nil
     local pending_cmds = {}
     for i, a in ipairs(activities) do
       activity_seq = activity_seq + 1
       seqs[i] = activity_seq
+      check_activity_name(activity_seq, a.name)
       local r = activity_results[activity_seq]
       if r then
         if r.ok then
           results[i] = r.value
         else
-          first_error = first_error
-            or ("activity '" .. (a.name or "?") .. "' failed: " .. tostring(r.err))
+          first_error = first_error or activity_error(a.name or "?", r)
         end
       else
         all_done = false
-        local opts = a.opts or {}
-        pending_cmds[#pending_cmds + 1] = {
-          type = "ScheduleActivity",
-          seq = activity_seq,
-          name = a.name,
-          task_queue = opts.task_queue or "default",
-          input = a.input,
-          max_attempts = opts.max_attempts,
-          initial_interval_secs = opts.initial_interval_secs,
-          backoff_coefficient = opts.backoff_coefficient,
-          start_to_close_secs = opts.start_to_close_secs,
-          heartbeat_timeout_secs = opts.heartbeat_timeout_secs,
-        }
+        pending_cmds[#pending_cmds + 1] = schedule_command(activity_seq, a.name, a.input, a.opts)
       end
     end
     if all_done then
@@ -140,8 +231,108 @@ function M.make(workflow_id, history)
     error("workflow ctx: yielded but resumed unexpectedly")
   end
 
+  -- Handle for an activity scheduled by start_activity / start_parallel.
+  local function activity_handle(seq, name)
+    local handle = { seq = seq, name = name }
+
+    --- True once the activity completed, failed or was cancelled.
+    function handle:done()
+      return activity_results[seq] ~= nil
+    end
+
+    --- Block until the activity finishes; return its result or raise.
+    function handle:result()
+      reach_block_point()
+      local r = activity_results[seq]
+      if r then
+        if r.ok then return r.value end
+        error(activity_error(name, r))
+      end
+      check_cancel()
+      coroutine.yield({ _batch = true, commands = {} })
+      error("workflow ctx: yielded but resumed unexpectedly")
+    end
+
+    --- Ask the engine to cancel the activity. Doesn't block; a running
+    --- activity stops when its worker next heartbeats. No-op once the
+    --- activity has finished.
+    function handle:cancel()
+      if activity_results[seq] or cancel_issued[seq] then return end
+      cancel_issued[seq] = true
+      table.insert(ctx._pending_commands, { type = "CancelActivity", seq = seq })
+    end
+
+    return handle
+  end
+
+  -- Queue a ScheduleActivity for the next yield unless history shows
+  -- it was already scheduled. Returns the activity's seq.
+  local function start(name, input, opts)
+    activity_seq = activity_seq + 1
+    check_activity_name(activity_seq, name)
+    if scheduled_activities[activity_seq] == nil and not activity_results[activity_seq] then
+      check_cancel()
+      table.insert(ctx._pending_commands, schedule_command(activity_seq, name, input, opts))
+    end
+    return activity_seq
+  end
+
+  --- Schedule an activity without waiting for it. Returns a handle with
+  --- `result()` (blocks), `done()` and `cancel()`.
+  function ctx:start_activity(name, input, opts)
+    return activity_handle(start(name, input, opts), name)
+  end
+
+  --- Schedule a group of activities (same shape as `execute_parallel`)
+  --- without waiting. The returned group has `handles`, `results()`
+  --- (blocks until all finish; results in input order, raising the
+  --- first error), `done()` and `cancel()` for every member.
+  function ctx:start_parallel(activities)
+    if type(activities) ~= "table" or #activities == 0 then
+      error("ctx:start_parallel: activities must be a non-empty list")
+    end
+    local group = { handles = {} }
+    for i, a in ipairs(activities) do
+      group.handles[i] = activity_handle(start(a.name, a.input, a.opts), a.name)
+    end
+
+    function group:done()
+      for _, h in ipairs(self.handles) do
+        if not h:done() then return false end
+      end
+      return true
+    end
+
+    function group:results()
+      reach_block_point()
+      if not self:done() then
+        check_cancel()
+        coroutine.yield({ _batch = true, commands = {} })
+        error("workflow ctx: yielded but resumed unexpectedly")
+      end
+      local results, first_error = {}, nil
+      for i, h in ipairs(self.handles) do
+        local r = activity_results[h.seq]
+        if r.ok then
+          results[i] = r.value
+        else
+          first_error = first_error or activity_error(h.name, r)
+        end
+      end
+      if first_error then error(first_error) end
+      return results
+    end
+
+    function group:cancel()
+      for _, h in ipairs(self.handles) do h:cancel() end
+    end
+
+    return group
+  end
+
   --- Pause the workflow durably for `seconds`.
   function ctx:sleep(seconds)
+    reach_block_point()
     timer_seq = timer_seq + 1
     if fired_timers[timer_seq] then return end
     check_cancel()
@@ -152,7 +343,13 @@ function M.make(workflow_id, history)
   --- Run a non-deterministic operation exactly once, recording the
   --- result so all subsequent replays return it from cache.
   function ctx:side_effect(name, fn)
+    reach_block_point()
     side_effect_seq = side_effect_seq + 1
+    local recorded = recorded_side_effects[side_effect_seq]
+    if recorded ~= nil and recorded ~= name then
+      nondeterminism("side effect seq " .. side_effect_seq .. " was recorded as '" ..
+        tostring(recorded) .. "' but the handler now records '" .. tostring(name) .. "'")
+    end
     local cached = side_effects[side_effect_seq]
     if cached ~= nil then return cached end
     check_cancel()
@@ -171,6 +368,7 @@ function M.make(workflow_id, history)
     if not opts or not opts.workflow_id then
       error("ctx:start_child_workflow: opts.workflow_id is required")
     end
+    reach_block_point()
     local cached = child_results[opts.workflow_id]
     if cached then
       if cached.ok then return cached.value end
@@ -189,6 +387,7 @@ function M.make(workflow_id, history)
 
   --- Merge a JSON object into the workflow's stored search_attributes.
   function ctx:upsert_search_attributes(patch)
+    reach_block_point()
     check_cancel()
     if type(patch) ~= "table" then
       error("ctx:upsert_search_attributes: patch must be a table")
@@ -200,11 +399,57 @@ function M.make(workflow_id, history)
   --- End this run and start a fresh one. Use for unbounded-loop
   --- workflows whose event log would otherwise grow forever.
   function ctx:continue_as_new(input)
+    reach_block_point()
     check_cancel()
     coroutine.yield({ type = "ContinueAsNew", input = input })
     error("workflow ctx: yielded but resumed unexpectedly")
   end
 
+  --- Branch on a code change without breaking in-flight runs:
+  ---
+  ---   local v = ctx:get_version("add-fraud-check", ctx.DEFAULT_VERSION, 1)
+  ---   if v == 1 then ctx:execute_activity("fraud_check", order) end
+  ---
+  --- A run reaching this call for the first time gets `max_supported`
+  --- and records it. A run whose history already holds commands issued
+  --- after this point ran the code from before the call existed and
+  --- gets `DEFAULT_VERSION` (-1). Either way the answer is fixed for the
+  --- rest of the run. Raise `min_supported` once no run on an older
+  --- version is left; a run recorded below it fails its task.
+  function ctx:get_version(change_id, min_supported, max_supported)
+    if type(change_id) ~= "string" or change_id == "" then
+      error("ctx:get_version: change_id must be a non-empty string")
+    end
+    if math.type(min_supported) ~= "integer" or math.type(max_supported) ~= "integer" then
+      error("ctx:get_version: min_supported and max_supported must be integers")
+    end
+    if min_supported > max_supported then
+      error("ctx:get_version: min_supported must not exceed max_supported")
+    end
+    local version = version_markers[change_id]
+    if version == nil then
+      local replaying_past_here = activity_seq < max_activity_seq
+        or timer_seq < max_timer_seq
+        or side_effect_seq < max_side_effect_seq
+      if replaying_past_here then
+        version = M.DEFAULT_VERSION
+      else
+        version = max_supported
+        table.insert(self._version_markers, {
+          type = "RecordVersionMarker",
+          change_id = change_id,
+          version = version,
+        })
+      end
+      version_markers[change_id] = version
+    end
+    if version < min_supported or version > max_supported then
+      nondeterminism("change '" .. change_id .. "' is at version " .. version ..
+        " in this run, outside the supported range " .. min_supported .. ".." .. max_supported)
+    end
+    return version
+  end
+
   --- Register a named query handler that exposes live workflow state
   --- via GET /api/v1/engine/workflow/workflows/{id}/state.
   function ctx:register_query(name, fn)
@@ -218,6 +463,41 @@ function M.make(workflow_id, history)
     self._queries[name] = fn
   end
 
+  --- Register a named update handler, invoked via
+  --- POST /api/v1/engine/workflow/workflows/{id}/update/{name}.
+  --- `validator(args)` (optional) runs first and rejects the update by
+  --- raising or returning false; it must not mutate state. `handler(args)`
+  --- may mutate workflow state and its return value is sent back to
+  --- the caller. Neither may call yielding ctx methods.
+  function ctx:register_update(name, validator, handler)
+    if type(name) ~= "string" or name == "" then
+      error("ctx:register_update: name must be a non-empty string")
+    end
+    if handler == nil then validator, handler = nil, validator end
+    if validator ~= nil and type(validator) ~= "function" then
+      error("ctx:register_update: validator must be a function if provided")
+    end
+    if type(handler) ~= "function" then
+      error("ctx:register_update: handler must be a function")
+    end
+    self._updates = self._updates or {}
+    self._updates[name] = { validator = validator, handler = handler }
+  end
+
+  --- Park until `predicate()` returns truthy. The predicate is
+  --- re-evaluated on each workflow task, typically after an update or
+  --- signal has changed the state it reads.
+  function ctx:wait_condition(predicate)
+    if type(predicate) ~= "function" then
+      error("ctx:wait_condition: predicate must be a function")
+    end
+    reach_block_point()
+    if predicate() then return end
+    check_cancel()
+    coroutine.yield({ type = "WaitForCondition" })
+    error("workflow ctx: yielded but resumed unexpectedly")
+  end
+
   --- Self-cancel: workflow decides itself it should stop early. Lands
   --- in the same terminal state as an externally-requested cancel.
   function ctx:cancel(reason)
@@ -241,6 +521,7 @@ function M.make(workflow_id, history)
     if timeout ~= nil and (type(timeout) ~= "number" or timeout <= 0) then
       error("ctx:wait_for_signal: opts.timeout must be a positive number")
     end
+    reach_block_point()
 
     if not timeout then
       local consumed = signal_cursor[name] or 0
@@ -29,6 +29,51 @@ local function collect_snapshot(ctx)
   return { type = "RecordSnapshot", state = state }
 end
 
+--- Apply updates delivered with this task to a handler parked at
+--- `ctx._block_point`. Returns the Accept/Complete/Reject commands.
+--- An update is rejected when the workflow is no longer parked (it
+--- finished this task), has no handler registered under that name, or
+--- its validator raises or returns false.
+local function apply_updates(ctx, updates, parked)
+  local cmds = {}
+  for _, u in ipairs(updates or {}) do
+    local registered = ctx._updates and ctx._updates[u.name]
+    local reject
+    if not parked then
+      reject = "workflow is not accepting updates"
+    elseif not registered then
+      reject = "no update handler registered for: " .. tostring(u.name)
+    elseif registered.validator then
+      local ok, valid = pcall(registered.validator, u.args)
+      if not ok then
+        reject = tostring(valid)
+      elseif valid == false then
+        reject = "update rejected by validator"
+      end
+    end
+    if reject then
+      cmds[#cmds + 1] = { type = "RejectUpdate", update_id = u.update_id, error = reject }
+    else
+      cmds[#cmds + 1] = {
+        type = "AcceptUpdate",
+        update_id = u.update_id,
+        block_point = ctx._block_point,
+      }
+      local ok, result = pcall(registered.handler, u.args)
+      if ok then
+        cmds[#cmds + 1] = { type = "CompleteUpdate", update_id = u.update_id, result = result }
+      else
+        cmds[#cmds + 1] = {
+          type = "CompleteUpdate",
+          update_id = u.update_id,
+          error = tostring(result),
+        }
+      end
+    end
+  end
+  return cmds
+end
+
 --- Run the workflow handler against the current event history and
 --- return the next batch of commands. See ctx.lua for the replay model.
 function M.handle_workflow_task(client, task)
@@ -45,8 +90,36 @@ function M.handle_workflow_task(client, task)
 
   local ok, yielded_or_returned = coroutine.resume(co)
 
+  -- Replay diverged from history: fail the task, not the run, and apply
+  -- nothing from it. A worker with compatible code can retry later.
+  if not ok then
+    local err = tostring(yielded_or_returned)
+    local at = err:find(ctx_mod.NONDETERMINISM, 1, true)
+    if at then
+      return {{
+        type = "FailWorkflowTask",
+        error = err:sub(at + #ctx_mod.NONDETERMINISM),
+      }}
+    end
+  end
+
+  -- Updates only apply while the handler is parked on a ctx call that
+  -- keeps the run alive; a run that is ending rejects them instead.
+  local parked = ok and coroutine.status(co) == "suspended"
+    and not (type(yielded_or_returned) == "table"
+      and yielded_or_returned.type == "ContinueAsNew")
+  local update_cmds = apply_updates(ctx, task.updates, parked)
+
+  -- Snapshot after updates so queries see the state they produced.
   local snapshot_cmd = collect_snapshot(ctx)
   local function with_snapshot(cmds)
+    -- Commands queued without yielding (start_activity, handle:cancel)
+    -- were issued before whatever the handler yielded last.
+    for i = #ctx._pending_commands, 1, -1 do table.insert(cmds, 1, ctx._pending_commands[i]) end
+    for i = #update_cmds, 1, -1 do table.insert(cmds, 1, update_cmds[i]) end
+    -- Version markers chosen this task precede the commands that depend
+    -- on them.
+    for i = #ctx._version_markers, 1, -1 do table.insert(cmds, 1, ctx._version_markers[i]) end
     if snapshot_cmd then table.insert(cmds, 1, snapshot_cmd) end
     return cmds
   end
@@ -68,11 +141,22 @@ function M.handle_workflow_task(client, task)
   return with_snapshot({ yielded_or_returned })
 end
 
+local ACTIVITY_CANCELLED = "__ASSAY_ACTIVITY_CANCELLED__"
+M.ACTIVITY_CANCELLED = ACTIVITY_CANCELLED
+
 --- Activity ctx — minimal, exposes a heartbeat for long-running work.
+--- `heartbeat` raises when the engine reports the activity (or its
+--- workflow) was cancelled; activities that need cleanup can pcall it
+--- and re-raise.
 local function make_activity_ctx(client, task)
   local ctx = {}
   function ctx:heartbeat(details)
-    client._api("POST", "/tasks/" .. task.id .. "/heartbeat", { details = details })
+    local resp = client._api("POST", "/tasks/" .. task.id .. "/heartbeat", { details = details })
+    if resp.status ~= 200 or not resp.body or resp.body == "" then return end
+    local body = json.parse(resp.body)
+    if type(body) == "table" and body.cancel_requested then
+      error(ACTIVITY_CANCELLED)
+    end
   end
   return ctx
 end
@@ -124,6 +208,10 @@ function M.poll_activity_task(client, queue)
   end)
   if ok then
     client._api("POST", "/tasks/" .. task.id .. "/complete", { result = result_or_err })
+  elseif tostring(result_or_err):find(ACTIVITY_CANCELLED, 1, true) then
+    -- Failing a cancel-requested activity acknowledges the cancel; the
+    -- engine records it as cancelled rather than retrying.
+    client._api("POST", "/tasks/" .. task.id .. "/fail", { error = "activity cancelled" })
   else
     client._api("POST", "/tasks/" .. task.id .. "/fail", { error = tostring(result_or_err) })
   end
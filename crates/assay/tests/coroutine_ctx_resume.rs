@@ -127,3 +127,285 @@ async fn activity_retry_boundary_discards_failure_path_activity_results() {
     "#;
     run_lua(script).await.unwrap();
 }
+
+#[tokio::test]
+async fn worker_applies_update_at_block_point_and_replay_reapplies_it() {
+    let script = r#"
+        local worker = require("assay.engine.workflow.worker")
+        local client = { _workflows = {} }
+        client._workflows.Approval = function(ctx, input)
+            local state = { approved_by = nil }
+            ctx:register_query("state", function() return state end)
+            ctx:register_update("approve", function(args)
+                if not args or not args.by then error("missing approver") end
+            end, function(args)
+                state.approved_by = args.by
+                return { ok = true }
+            end)
+            ctx:wait_condition(function() return state.approved_by ~= nil end)
+            return { approved_by = state.approved_by }
+        end
+
+        -- First task: handler parks on wait_condition (block point 1)
+        -- and the worker applies both updates against it.
+        local cmds = worker.handle_workflow_task(client, {
+            workflow_id = "wf-upd",
+            workflow_type = "Approval",
+            history = {},
+            updates = {
+                { update_id = "u1", name = "approve", args = {} },
+                { update_id = "u2", name = "approve", args = { by = "alice" } },
+                { update_id = "u3", name = "unknown" },
+            },
+        })
+        assert.eq(cmds[1].type, "RecordSnapshot")
+        assert.eq(cmds[1].state.state.approved_by, "alice")
+        assert.eq(cmds[2].type, "RejectUpdate")
+        assert.eq(cmds[2].update_id, "u1")
+        assert.eq(cmds[3].type, "AcceptUpdate")
+        assert.eq(cmds[3].block_point, 1)
+        assert.eq(cmds[4].type, "CompleteUpdate")
+        assert.eq(cmds[4].result.ok, true)
+        assert.eq(cmds[5].type, "RejectUpdate")
+        assert.eq(cmds[6].type, "WaitForCondition")
+
+        -- Replay: UpdateAccepted re-applies the handler on entry to block
+        -- point 1, so the condition holds and the workflow completes.
+        local replay = worker.handle_workflow_task(client, {
+            workflow_id = "wf-upd",
+            workflow_type = "Approval",
+            history = {
+                {
+                    seq = 2,
+                    event_type = "UpdateAccepted",
+                    payload = { update_id = "u2", name = "approve", args = { by = "alice" }, block_point = 1 },
+                },
+            },
+        })
+        local last = replay[#replay]
+        assert.eq(last.type, "CompleteWorkflow")
+        assert.eq(last.result.approved_by, "alice")
+    "#;
+    run_lua(script).await.unwrap();
+}
+
+#[tokio::test]
+async fn worker_rejects_updates_when_workflow_finishes() {
+    let script = r#"
+        local worker = require("assay.engine.workflow.worker")
+        local client = { _workflows = {} }
+        client._workflows.Quick = function(ctx)
+            ctx:register_update("noop", function() return 1 end)
+            return "done"
+        end
+        local cmds = worker.handle_workflow_task(client, {
+            workflow_id = "wf-quick",
+            workflow_type = "Quick",
+            history = {},
+            updates = { { update_id = "u1", name = "noop" } },
+        })
+        assert.eq(cmds[1].type, "RejectUpdate")
+        assert.eq(cmds[2].type, "CompleteWorkflow")
+    "#;
+    run_lua(script).await.unwrap();
+}
+
+#[tokio::test]
+async fn get_version_records_new_runs_and_defaults_old_ones() {
+    let script = r#"
+        local worker = require("assay.engine.workflow.worker")
+        local client = { _workflows = {} }
+        client._workflows.Order = function(ctx, input)
+            ctx:execute_activity("reserve", {})
+            if ctx:get_version("fraud-check", ctx.DEFAULT_VERSION, 1) == 1 then
+                ctx:execute_activity("fraud_check", {})
+            end
+            return ctx:execute_activity("charge", {})
+        end
+
+        local reserved = {
+            { seq = 2, event_type = "ActivityScheduled", payload = { activity_seq = 1, name = "reserve" } },
+            { seq = 3, event_type = "ActivityCompleted", payload = { activity_seq = 1, result = {} } },
+        }
+
+        -- A run reaching the change point fresh takes the new branch
+        -- and records the marker ahead of the command it gates.
+        local fresh = worker.handle_workflow_task(client, {
+            workflow_id = "wf-new", workflow_type = "Order", history = reserved,
+        })
+        assert.eq(fresh[1].type, "RecordVersionMarker")
+        assert.eq(fresh[1].change_id, "fraud-check")
+        assert.eq(fresh[1].version, 1)
+        assert.eq(fresh[2].type, "ScheduleActivity")
+        assert.eq(fresh[2].name, "fraud_check")
+
+        -- A run that already scheduled `charge` at seq 2 ran the old
+        -- code: it gets DEFAULT_VERSION and replays cleanly.
+        local old_history = {
+            reserved[1], reserved[2],
+            { seq = 4, event_type = "ActivityScheduled", payload = { activity_seq = 2, name = "charge" } },
+        }
+        local old = worker.handle_workflow_task(client, {
+            workflow_id = "wf-old", workflow_type = "Order", history = old_history,
+        })
+        assert.eq(#old, 1)
+        assert.eq(old[1].type, "ScheduleActivity")
+        assert.eq(old[1].name, "charge")
+
+        -- Once recorded, the marker decides on every replay.
+        local recorded = {
+            reserved[1], reserved[2],
+            { seq = 4, event_type = "VersionMarkerRecorded", payload = { change_id = "fraud-check", version = 1 } },
+        }
+        local replay = worker.handle_workflow_task(client, {
+            workflow_id = "wf-new", workflow_type = "Order", history = recorded,
+        })
+        assert.eq(#replay, 1)
+        assert.eq(replay[1].name, "fraud_check")
+    "#;
+    run_lua(script).await.unwrap();
+}
+
+#[tokio::test]
+async fn diverging_replay_fails_the_workflow_task() {
+    let script = r#"
+        local worker = require("assay.engine.workflow.worker")
+        local client = { _workflows = {} }
+        client._workflows.Changed = function(ctx)
+            return ctx:execute_activity("refund", {})
+        end
+        client._workflows.Retired = function(ctx)
+            ctx:get_version("fraud-check", 1, 1)
+            return ctx:execute_activity("charge", {})
+        end
+
+        local cmds = worker.handle_workflow_task(client, {
+            workflow_id = "wf-1", workflow_type = "Changed",
+            history = {
+                { seq = 2, event_type = "ActivityScheduled", payload = { activity_seq = 1, name = "charge" } },
+            },
+        })
+        assert.eq(#cmds, 1)
+        assert.eq(cmds[1].type, "FailWorkflowTask")
+        assert.contains(cmds[1].error, "activity seq 1 was scheduled as 'charge'")
+        assert.contains(cmds[1].error, "now schedules 'refund'")
+
+        -- A run on a version below the supported minimum can't replay.
+        local retired = worker.handle_workflow_task(client, {
+            workflow_id = "wf-2", workflow_type = "Retired",
+            history = {
+                { seq = 2, event_type = "ActivityScheduled", payload = { activity_seq = 1, name = "charge" } },
+            },
+        })
+        assert.eq(retired[1].type, "FailWorkflowTask")
+        assert.contains(retired[1].error, "change 'fraud-check' is at version -1")
+    "#;
+    run_lua(script).await.unwrap();
+}
+
+#[tokio::test]
+async fn started_activities_can_be_cancelled_and_replay_as_cancelled() {
+    let script = r#"
+        local worker = require("assay.engine.workflow.worker")
+        local client = { _workflows = {} }
+        client._workflows.Backup = function(ctx)
+            local backup = ctx:start_activity("backup", { volume = "data" })
+            local group = ctx:start_parallel({
+                { name = "snapshot", input = { disk = 1 } },
+                { name = "snapshot", input = { disk = 2 } },
+            })
+            local stop = ctx:wait_for_signal("stop")
+            if stop then
+                backup:cancel()
+                group:cancel()
+            end
+            local ok, err = pcall(function() return backup:result() end)
+            return { ok = ok, err = err, snapshots = group:done() }
+        end
+
+        -- First task: both starts ride along with the signal wait.
+        local first = worker.handle_workflow_task(client, {
+            workflow_id = "wf-bk", workflow_type = "Backup", history = {},
+        })
+        assert.eq(#first, 4)
+        assert.eq(first[1].type, "ScheduleActivity")
+        assert.eq(first[1].seq, 1)
+        assert.eq(first[1].name, "backup")
+        assert.eq(first[3].seq, 3)
+        assert.eq(first[4].type, "WaitForSignal")
+
+        local scheduled = {
+            { seq = 2, event_type = "ActivityScheduled", payload = { activity_seq = 1, name = "backup" } },
+            { seq = 3, event_type = "ActivityScheduled", payload = { activity_seq = 2, name = "snapshot" } },
+            { seq = 4, event_type = "ActivityScheduled", payload = { activity_seq = 3, name = "snapshot" } },
+            { seq = 5, event_type = "ActivityCompleted", payload = { activity_seq = 2, result = {} } },
+            { seq = 6, event_type = "SignalReceived", payload = { signal = "stop", payload = true } },
+        }
+
+        -- Signal arrives: cancels go out for every unfinished activity,
+        -- then the handler parks on backup:result().
+        local second = worker.handle_workflow_task(client, {
+            workflow_id = "wf-bk", workflow_type = "Backup", history = scheduled,
+        })
+        assert.eq(#second, 2)
+        assert.eq(second[1].type, "CancelActivity")
+        assert.eq(second[1].seq, 1)
+        assert.eq(second[2].type, "CancelActivity")
+        assert.eq(second[2].seq, 3)
+
+        -- Replay after the engine recorded the outcomes: requested
+        -- cancels aren't re-issued and result() raises for the cancelled
+        -- activity.
+        local history = {}
+        for i, e in ipairs(scheduled) do history[i] = e end
+        table.insert(history, { seq = 7, event_type = "ActivityCancelRequested", payload = { activity_seq = 1 } })
+        table.insert(history, { seq = 8, event_type = "ActivityCancelled", payload = { activity_seq = 3 } })
+        local parked = worker.handle_workflow_task(client, {
+            workflow_id = "wf-bk", workflow_type = "Backup", history = history,
+        })
+        assert.eq(#parked, 0)
+
+        table.insert(history, { seq = 9, event_type = "ActivityCancelled", payload = { activity_seq = 1 } })
+        local done = worker.handle_workflow_task(client, {
+            workflow_id = "wf-bk", workflow_type = "Backup", history = history,
+        })
+        assert.eq(#done, 1)
+        assert.eq(done[1].type, "CompleteWorkflow")
+        assert.eq(done[1].result.ok, false)
+        assert.contains(done[1].result.err, "activity 'backup' cancelled")
+        assert.eq(done[1].result.snapshots, true)
+    "#;
+    run_lua(script).await.unwrap();
+}
+
+#[tokio::test]
+async fn activity_heartbeat_raises_when_cancel_requested() {
+    let script = r#"
+        local worker = require("assay.engine.workflow.worker")
+        local calls = {}
+        local client = { _activities = {} }
+        client._api = function(method, path, body)
+            calls[#calls + 1] = { path = path, body = body }
+            if path == "/tasks/poll" then
+                return { status = 200, body = '{"id": 7, "name": "backup", "input": "{}"}' }
+            elseif path == "/tasks/7/heartbeat" then
+                return { status = 200, body = '{"cancel_requested": true}' }
+            end
+            return { status = 200, body = "" }
+        end
+        local cleaned_up = false
+        client._activities.backup = function(ctx)
+            local ok, err = pcall(function() ctx:heartbeat("50%") end)
+            cleaned_up = true
+            if not ok then error(err) end
+            return "finished"
+        end
+
+        assert.eq(worker.poll_activity_task(client, "default"), true)
+        assert.eq(cleaned_up, true)
+        local last = calls[#calls]
+        assert.eq(last.path, "/tasks/7/fail")
+        assert.eq(last.body.error, "activity cancelled")
+    "#;
+    run_lua(script).await.unwrap();
+}
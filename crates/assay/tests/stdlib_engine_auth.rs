@@ -0,0 +1,207 @@
+//! Tests for the assay-engine auth Lua client (`assay.engine.auth`).
+//!
+//! Mocks the engine's auth surface with wiremock and drives the RFC 8628
+//! device login and two-step MFA login helpers end-to-end. The
+//! server-side state machines are covered by `assay-auth`'s own tests;
+//! here we check the wrapper's request shapes and that it polls through
+//! `authorization_pending` / `slow_down`.
+
+mod common;
+
+use common::run_lua;
+use serde_json::json;
+use wiremock::matchers::{body_json, body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const FORM: &str = "application/x-www-form-urlencoded";
+
+#[tokio::test]
+async fn device_login_polls_until_approved() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/auth/device_authorization"))
+        .and(header("Content-Type", FORM))
+        .and(body_string_contains("client_id=ops-cli"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "device_code": "odc_abc",
+            "user_code": "BCDF-GHJK",
+            "verification_uri": "https://idp.example.com/auth/device",
+            "verification_uri_complete": "https://idp.example.com/auth/device?user_code=BCDF-GHJK",
+            "expires_in": 600,
+            "interval": 0,
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/auth/token"))
+        .and(body_string_contains("device_code=odc_abc"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": "authorization_pending",
+        })))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/auth/token"))
+        .and(header("Content-Type", FORM))
+        .and(body_string_contains(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "at_xyz",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+            "scope": "openid",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let script = format!(
+        r#"
+        local auth = require("assay.engine.auth")
+        local c = auth.client({{ engine_url = "{base}" }})
+        local start = c.oidc_provider:device_authorization({{ client_id = "ops-cli", scope = "openid" }})
+        assert.eq(start.user_code, "BCDF-GHJK")
+        start.client_id = "ops-cli"
+        local tokens = c.oidc_provider:device_token(start)
+        assert.eq(tokens.access_token, "at_xyz")
+        "#,
+        base = server.uri(),
+    );
+    run_lua(&script).await.unwrap();
+}
+
+#[tokio::test]
+async fn device_login_errors_when_denied() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/auth/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": "access_denied",
+        })))
+        .mount(&server)
+        .await;
+
+    let script = format!(
+        r#"
+        local auth = require("assay.engine.auth")
+        local c = auth.client({{ engine_url = "{base}" }})
+        local ok, err = pcall(function()
+          return c.oidc_provider:device_token({{ client_id = "ops-cli", device_code = "odc_abc", interval = 0 }})
+        end)
+        assert.eq(ok, false)
+        assert.contains(tostring(err), "access_denied")
+        "#,
+        base = server.uri(),
+    );
+    run_lua(&script).await.unwrap();
+}
+
+#[tokio::test]
+async fn mfa_login_redeems_the_challenge_token() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/v1/engine/auth/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "mfa_required": true,
+            "mfa_token": "mfa_abc",
+            "methods": ["totp", "recovery_code"],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/engine/auth/login/mfa"))
+        .and(body_json(json!({"mfa_token": "mfa_abc", "code": "123456"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "user_id": "usr_ops",
+            "email": "ops@example.com",
+            "csrf_token": "csrf",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/v1/engine/auth/admin/users/usr_ops/mfa"))
+        .and(header("Authorization", "Bearer admin-key"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let script = format!(
+        r#"
+        local auth = require("assay.engine.auth")
+        local c = auth.client({{ engine_url = "{base}", api_key = "admin-key" }})
+        local first = c:login("ops@example.com", "pw")
+        assert.eq(first.mfa_required, true)
+        local session = c:login_mfa(first.mfa_token, "123456")
+        assert.eq(session.user_id, "usr_ops")
+        assert.eq(c.users:reset_mfa("usr_ops"), nil)
+        "#,
+        base = server.uri(),
+    );
+    run_lua(&script).await.unwrap();
+}
+
+#[tokio::test]
+async fn orgs_helpers_hit_the_admin_routes() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/v1/engine/auth/admin/orgs"))
+        .and(header("Authorization", "Bearer admin-key"))
+        .and(body_json(json!({"slug": "acme", "display_name": "Acme"})))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "id": "org_1", "slug": "acme", "display_name": "Acme", "created_at": 1,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v1/engine/auth/admin/orgs/acme/members/usr_ops"))
+        .and(body_json(json!({"role": "admin"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "org_id": "org_1", "user_id": "usr_ops", "role": "admin", "created_at": 1,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path(
+            "/api/v1/engine/auth/admin/groups/grp_1/members/group/grp_2",
+        ))
+        .respond_with(ResponseTemplate::new(201))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/engine/auth/admin/users/usr_ops/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"org_id": "org_1", "user_id": "usr_ops", "role": "admin", "created_at": 1}],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let script = format!(
+        r#"
+        local auth = require("assay.engine.auth")
+        local c = auth.client({{ engine_url = "{base}", api_key = "admin-key" }})
+        local org = c.orgs:create({{ slug = "acme", display_name = "Acme" }})
+        assert.eq(org.id, "org_1")
+        local m = c.orgs:set_member("acme", "usr_ops", "admin")
+        assert.eq(m.role, "admin")
+        assert.eq(c.orgs:add_group_member("grp_1", "group", "grp_2"), nil)
+        local mine = c.orgs:for_user("usr_ops")
+        assert.eq(mine.items[1].org_id, "org_1")
+        "#,
+        base = server.uri(),
+    );
+    run_lua(&script).await.unwrap();
+}
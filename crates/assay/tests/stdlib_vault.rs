@@ -11,7 +11,7 @@ mod common;
 
 use common::run_lua;
 use serde_json::json;
-use wiremock::matchers::{header, method, path, query_param};
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 const ADMIN_KEY: &str = "test-admin-key";
@@ -129,6 +129,56 @@ async fn kv_lifecycle_paths() {
     run_lua(&script).await.unwrap();
 }
 
+#[tokio::test]
+async fn kv_cas_put_and_metadata_write() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/vault/kv-meta/k"))
+        .and(body_partial_json(
+            json!({"cas_required": true, "max_versions": 5}),
+        ))
+        .and(auth_header())
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "path": "k", "latest_version": 2, "custom_md": {}, "cas_required": true,
+            "max_versions": 5, "delete_version_after": 0, "created_at": 0.0, "updated_at": 0.0,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v1/vault/kv/k"))
+        .and(body_partial_json(json!({"data": "v3", "cas": 2})))
+        .and(auth_header())
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({"path": "k", "version": 3})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v1/vault/kv/k"))
+        .and(body_partial_json(json!({"cas": 1})))
+        .respond_with(ResponseTemplate::new(409).set_body_json(json!({
+            "error": "conflict",
+            "error_description": "check-and-set parameter did not match the current version (3)",
+        })))
+        .mount(&server)
+        .await;
+    let script = format!(
+        r#"
+        local vault = require("assay.engine.vault")
+        local c = vault.client({{ engine_url = "{base}", api_key = "{key}" }})
+        local meta = c.kv:set_meta("k", {{ cas_required = true, max_versions = 5 }})
+        assert.eq(meta.cas_required, true)
+        assert.eq(c.kv:put("k", "v3", nil, 2).version, 3)
+        local ok, err = pcall(function() return c.kv:put("k", "stale", nil, 1) end)
+        assert.eq(ok, false)
+        assert.contains(tostring(err), "409")
+        "#,
+        base = server.uri(),
+        key = ADMIN_KEY,
+    );
+    run_lua(&script).await.unwrap();
+}
+
 #[tokio::test]
 async fn kv_list_with_and_without_prefix() {
     let server = MockServer::start().await;
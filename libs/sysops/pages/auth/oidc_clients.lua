@@ -55,14 +55,19 @@ end
 function M.create(req)
   local f   = form.parse(req)
   local sdk = auth.new(ctx.engine).oidc
-  if not nz(f.redirect_uris) then
+  local grants = split_csv(f.grant_types)
+  local machine_only = grants ~= nil
+  for _, g in ipairs(grants or {}) do
+    if g ~= "client_credentials" then machine_only = false end
+  end
+  if not nz(f.redirect_uris) and not machine_only then
     return { status = 303, headers = { Location = "/auth/oidc-clients?error=400:redirect_uris+required" } }
   end
   local fields = {
     client_id                  = nz(f.client_id),
     name                       = nz(f.name),
     redirect_uris              = split_csv(f.redirect_uris),
-    grant_types                = split_csv(f.grant_types),
+    grant_types                = grants,
     token_endpoint_auth_method = nz(f.token_endpoint_auth_method) or "none",
     is_public                  = (f.is_public == "on"),
   }
@@ -26,6 +26,23 @@ local function tuple_body(f)
   }
 end
 
+-- Remaining lifetime of an expiring tuple, e.g. "in 2h 5m". Tuples
+-- past `expires_at` are already ignored by checks; they still list
+-- until the engine's sweeper deletes them.
+local function lifetime_label(expires_at, now)
+  if type(expires_at) ~= "number" then return nil end
+  local left = math.floor(expires_at - now)
+  if left <= 0 then return "expired" end
+  local d = math.floor(left / 86400)
+  local h = math.floor((left % 86400) / 3600)
+  local m = math.floor((left % 3600) / 60)
+  local s = left % 60
+  if d > 0 then return string.format("in %dd %dh", d, h) end
+  if h > 0 then return string.format("in %dh %dm", h, m) end
+  if m > 0 then return string.format("in %dm %ds", m, s) end
+  return string.format("in %ds", s)
+end
+
 local function relation_names(ns)
   -- NamespaceSchema rows from the engine carry a `definitions` map
   -- whose keys are the relation / permission names available on the
@@ -120,12 +137,14 @@ function M.page(req)
   end
   table.sort(group_ids)
 
+  local now = os.time()
   for _, t in ipairs(tuples) do
     if t.subject_type == "user" then
       t.subject_label = user_email_by_id[t.subject_id] or t.subject_id
     else
       t.subject_label = t.subject_id
     end
+    t.expires_label = lifetime_label(t.expires_at, now)
   end
 
   local zb_data_json = json.encode({
@@ -166,7 +185,12 @@ end
 function M.write(req)
   local f   = form.parse(req)
   local sdk = auth.new(ctx.engine).zanzibar
-  local _, err = sdk.write_tuple(tuple_body(f))
+  local body = tuple_body(f)
+  local ttl = tonumber(f.ttl_seconds or "")
+  if ttl and ttl > 0 then
+    body.expires_at = os.time() + math.floor(ttl)
+  end
+  local _, err = sdk.write_tuple(body)
   if err then
     return {
       status  = 303,